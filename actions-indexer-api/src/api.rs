@@ -0,0 +1,226 @@
+//! Typed read methods over the actions repository.
+//!
+//! [`ActionsApi`] answers the questions consumers used to ask the actions tables
+//! with ad-hoc SQL: who voted on an object, what a user voted on and which objects
+//! of a space score best. Every method returns a [`Paginated`] page of results.
+use std::sync::Arc;
+
+use actions_indexer_repository::ActionsRepository;
+use actions_indexer_shared::types::{ObjectId, ObjectType, SpaceId, UserAddress, UserVote, VotesCount};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ApiError;
+
+/// Items returned by a page without an explicit limit.
+pub const DEFAULT_PAGE_LIMIT: i64 = 20;
+
+/// Most items returned by a single page.
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+/// Selects a page of results by limit and offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Default for Page {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_PAGE_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+impl Page {
+    /// Creates a page of `limit` items starting after `offset` items.
+    pub fn new(limit: i64, offset: i64) -> Self {
+        Self { limit, offset }
+    }
+
+    /// Checks the limit is between 1 and [`MAX_PAGE_LIMIT`] and the offset isn't negative.
+    fn validate(&self) -> Result<(), ApiError> {
+        if !(1..=MAX_PAGE_LIMIT).contains(&self.limit) {
+            return Err(ApiError::InvalidPage(format!(
+                "limit must be between 1 and {MAX_PAGE_LIMIT}, got {}",
+                self.limit
+            )));
+        }
+        if self.offset < 0 {
+            return Err(ApiError::InvalidPage(format!(
+                "offset must not be negative, got {}",
+                self.offset
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A page of results.
+///
+/// `next_offset` is the offset of the next page, or `None` if this is the last one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub next_offset: Option<i64>,
+}
+
+impl<T> Paginated<T> {
+    /// Builds the page from items fetched with one more than the page's limit, the
+    /// extra item telling whether a next page exists.
+    fn from_lookahead(mut items: Vec<T>, page: Page) -> Self {
+        let has_more = items.len() as i64 > page.limit;
+        items.truncate(page.limit as usize);
+        Self {
+            next_offset: has_more.then(|| page.offset + page.limit),
+            items,
+        }
+    }
+}
+
+/// The vote counts of an object along with its score, upvotes minus downvotes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectScore {
+    pub object_id: ObjectId,
+    pub space_id: SpaceId,
+    pub object_type: ObjectType,
+    pub upvotes: i64,
+    pub downvotes: i64,
+    pub score: i64,
+}
+
+impl From<VotesCount> for ObjectScore {
+    fn from(count: VotesCount) -> Self {
+        Self {
+            score: count.score(),
+            object_id: count.object_id,
+            space_id: count.space_id,
+            object_type: count.object_type,
+            upvotes: count.upvotes,
+            downvotes: count.downvotes,
+        }
+    }
+}
+
+/// Read access to the actions data.
+///
+/// Wraps an `ActionsRepository`, validating pages before querying it.
+pub struct ActionsApi {
+    actions_repository: Arc<dyn ActionsRepository>,
+}
+
+impl ActionsApi {
+    /// Creates a new `ActionsApi` reading from `actions_repository`.
+    pub fn new(actions_repository: Arc<dyn ActionsRepository>) -> Self {
+        Self { actions_repository }
+    }
+
+    /// Returns a page of the current upvotes and downvotes on an object within a
+    /// space, latest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The UUID of the voted object
+    /// * `space_id` - The UUID of the space the votes were cast in
+    /// * `object_type` - Whether the object is an entity or a relation
+    /// * `page` - The page of votes to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Paginated<UserVote>)` - The page of votes
+    /// * `Err(ApiError)` - If the page is invalid or the repository query fails
+    pub async fn votes_for_object(
+        &self,
+        object_id: ObjectId,
+        space_id: SpaceId,
+        object_type: ObjectType,
+        page: Page,
+    ) -> Result<Paginated<UserVote>, ApiError> {
+        page.validate()?;
+        let votes = self
+            .actions_repository
+            .get_object_votes(&object_id, &space_id, object_type, page.limit + 1, page.offset)
+            .await?;
+        Ok(Paginated::from_lookahead(votes, page))
+    }
+
+    /// Returns a page of a user's vote history, latest first.
+    ///
+    /// The history holds the user's latest vote on each object, including removals.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The blockchain address of the voter
+    /// * `page` - The page of votes to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Paginated<UserVote>)` - The page of votes
+    /// * `Err(ApiError)` - If the page is invalid or the repository query fails
+    pub async fn user_vote_history(
+        &self,
+        user_id: UserAddress,
+        page: Page,
+    ) -> Result<Paginated<UserVote>, ApiError> {
+        page.validate()?;
+        let votes = self
+            .actions_repository
+            .get_votes_by_user(&user_id, page.limit + 1, page.offset)
+            .await?;
+        Ok(Paginated::from_lookahead(votes, page))
+    }
+
+    /// Returns a page of the objects of a space ranked by score, highest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - The UUID of the space to rank the objects of
+    /// * `page` - The page of objects to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Paginated<ObjectScore>)` - The page of objects and their scores
+    /// * `Err(ApiError)` - If the page is invalid or the repository query fails
+    pub async fn top_objects(
+        &self,
+        space_id: SpaceId,
+        page: Page,
+    ) -> Result<Paginated<ObjectScore>, ApiError> {
+        page.validate()?;
+        let counts = self
+            .actions_repository
+            .get_top_objects(&space_id, page.limit + 1, page.offset)
+            .await?;
+        let page_of_counts = Paginated::from_lookahead(counts, page);
+        Ok(Paginated {
+            items: page_of_counts.items.into_iter().map(ObjectScore::from).collect(),
+            next_offset: page_of_counts.next_offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_validation() {
+        assert!(Page::default().validate().is_ok());
+        assert!(Page::new(MAX_PAGE_LIMIT, 0).validate().is_ok());
+        assert!(matches!(Page::new(0, 0).validate(), Err(ApiError::InvalidPage(_))));
+        assert!(matches!(Page::new(MAX_PAGE_LIMIT + 1, 0).validate(), Err(ApiError::InvalidPage(_))));
+        assert!(matches!(Page::new(10, -1).validate(), Err(ApiError::InvalidPage(_))));
+    }
+
+    #[test]
+    fn test_from_lookahead_sets_next_offset() {
+        let page = Page::new(2, 4);
+
+        let full = Paginated::from_lookahead(vec![1, 2, 3], page);
+        assert_eq!(full, Paginated { items: vec![1, 2], next_offset: Some(6) });
+
+        let last = Paginated::from_lookahead(vec![1, 2], page);
+        assert_eq!(last, Paginated { items: vec![1, 2], next_offset: None });
+    }
+}
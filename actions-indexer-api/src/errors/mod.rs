@@ -0,0 +1,10 @@
+//! Error types for the Actions Indexer API.
+//! Defines the errors returned by the read methods, either invalid requests or
+//! failures of the underlying repository.
+#[derive(Debug, thiserror::Error)]
+pub enum ApiError {
+    #[error("Repository error: {0}")]
+    Repository(#[from] actions_indexer_repository::ActionsRepositoryError),
+    #[error("Invalid page: {0}")]
+    InvalidPage(String),
+}
@@ -0,0 +1,11 @@
+//! # Actions Indexer API
+//! This crate provides typed, paginated reads over the actions data stored by the
+//! actions indexer, so consumers don't have to query the actions tables directly.
+//! With the `server` feature, the same reads are served over HTTP, see [`server`].
+pub mod api;
+pub mod errors;
+#[cfg(feature = "server")]
+pub mod server;
+
+pub use api::{ActionsApi, ObjectScore, Page, Paginated};
+pub use errors::ApiError;
@@ -0,0 +1,35 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use actions_indexer_api::{ActionsApi, server};
+use actions_indexer_repository::PostgresActionsRepository;
+use dotenv::dotenv;
+
+/// Address the API listens on when `API_ADDR` isn't set.
+const DEFAULT_API_ADDR: &str = "0.0.0.0:8080";
+
+/// Main entry point for the Actions Indexer API server.
+///
+/// Initializes dotenv, connects to the database at `DATABASE_URL` and serves the
+/// read API on `API_ADDR` until the process exits.
+///
+/// # Returns
+///
+/// A `Result` indicating success or an error if the configuration is invalid,
+/// the database is unreachable or the server fails.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let database_url = std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL not set")?;
+    let addr: SocketAddr = std::env::var("API_ADDR")
+        .unwrap_or_else(|_| DEFAULT_API_ADDR.to_string())
+        .parse()?;
+
+    let pool = sqlx::PgPool::connect(&database_url).await?;
+    let repository = PostgresActionsRepository::new(pool).await?;
+    let api = Arc::new(ActionsApi::new(Arc::new(repository)));
+
+    server::serve(api, addr).await?;
+    Ok(())
+}
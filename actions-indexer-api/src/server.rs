@@ -0,0 +1,129 @@
+//! HTTP server exposing [`ActionsApi`], enabled by the `server` feature.
+//!
+//! - `GET /objects/{object_id}/votes?space_id=S&object_type=T`: the current votes
+//!   on an object within space `S`, latest first. `object_type` is `Entity` or
+//!   `Relation` and defaults to `Entity`.
+//! - `GET /users/{user_id}/votes`: the latest vote of a user on each object,
+//!   latest first.
+//! - `GET /spaces/{space_id}/top`: the objects of a space with their
+//!   [`ObjectScore`](crate::ObjectScore), highest score first.
+//!
+//! Every route takes `limit` and `offset` query parameters, defaulting to
+//! [`DEFAULT_PAGE_LIMIT`](crate::api::DEFAULT_PAGE_LIMIT) and `0`, and returns a
+//! JSON [`Paginated`](crate::Paginated) page. Invalid pages are answered with
+//! `400`, repository failures with `500`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let repository = PostgresActionsRepository::new(pool).await?;
+//! let api = Arc::new(ActionsApi::new(Arc::new(repository)));
+//! server::serve(api, "0.0.0.0:8080".parse()?).await?;
+//! ```
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use actions_indexer_shared::types::{ObjectId, ObjectType, SpaceId, UserAddress};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+
+use crate::api::{ActionsApi, Page};
+use crate::errors::ApiError;
+
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObjectVotesParams {
+    space_id: SpaceId,
+    object_type: Option<ObjectType>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+/// Builds the requested page, defaulting to the first page of [`Page::default`] size.
+fn page(limit: Option<i64>, offset: Option<i64>) -> Page {
+    let default = Page::default();
+    Page::new(limit.unwrap_or(default.limit), offset.unwrap_or(default.offset))
+}
+
+/// Build the API routes over `api`.
+pub fn router(api: Arc<ActionsApi>) -> Router {
+    Router::new()
+        .route("/objects/:object_id/votes", get(get_object_votes))
+        .route("/users/:user_id/votes", get(get_user_votes))
+        .route("/spaces/:space_id/top", get(get_top_objects))
+        .with_state(api)
+}
+
+/// Serve the API on `addr` until the process exits.
+pub async fn serve(api: Arc<ActionsApi>, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Serving actions API on {addr}");
+    axum::serve(listener, router(api)).await
+}
+
+async fn get_object_votes(
+    State(api): State<Arc<ActionsApi>>,
+    Path(object_id): Path<ObjectId>,
+    Query(params): Query<ObjectVotesParams>,
+) -> Result<Response, ServerError> {
+    let votes = api
+        .votes_for_object(
+            object_id,
+            params.space_id,
+            params.object_type.unwrap_or(ObjectType::Entity),
+            page(params.limit, params.offset),
+        )
+        .await?;
+    Ok(Json(votes).into_response())
+}
+
+async fn get_user_votes(
+    State(api): State<Arc<ActionsApi>>,
+    Path(user_id): Path<UserAddress>,
+    Query(params): Query<PageParams>,
+) -> Result<Response, ServerError> {
+    let votes = api.user_vote_history(user_id, page(params.limit, params.offset)).await?;
+    Ok(Json(votes).into_response())
+}
+
+async fn get_top_objects(
+    State(api): State<Arc<ActionsApi>>,
+    Path(space_id): Path<SpaceId>,
+    Query(params): Query<PageParams>,
+) -> Result<Response, ServerError> {
+    let objects = api.top_objects(space_id, page(params.limit, params.offset)).await?;
+    Ok(Json(objects).into_response())
+}
+
+/// An API error, reported to the client as a `400` for invalid pages or a `500`
+/// otherwise.
+struct ServerError(ApiError);
+
+impl From<ApiError> for ServerError {
+    fn from(error: ApiError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let status = match self.0 {
+            ApiError::InvalidPage(_) => StatusCode::BAD_REQUEST,
+            ApiError::Repository(_) => {
+                eprintln!("Failed to read actions data: {}", self.0);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+        (status, self.0.to_string()).into_response()
+    }
+}
@@ -0,0 +1,153 @@
+//! Integration tests for the actions API over the PostgreSQL repository.
+//!
+//! These tests require a real PostgreSQL database and use SQLx test macros
+//! to ensure proper test isolation and cleanup. The HTTP server tests need the
+//! `server` feature.
+//!
+//! Run with: `cargo test --test postgres_api --features server`
+
+use std::sync::Arc;
+
+use actions_indexer_api::{ActionsApi, ApiError, ObjectScore, Page, Paginated};
+use actions_indexer_repository::{ActionsRepository, PostgresActionsRepository};
+use actions_indexer_shared::types::{ObjectType, UserVote, VoteValue, VotesCount};
+use alloy::primitives::Address;
+use uuid::{Uuid, uuid};
+
+const SPACE_ID: Uuid = uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19");
+
+/// Creates an upvote on `object_id` cast by the address made of the `user` byte.
+fn make_user_vote(user: u8, object_id: Uuid, voted_at: u64) -> UserVote {
+    UserVote {
+        user_id: Address::repeat_byte(user),
+        object_id,
+        object_type: ObjectType::Entity,
+        space_id: SPACE_ID,
+        vote_type: VoteValue::Up,
+        voted_at,
+        weight: 1,
+    }
+}
+
+/// Creates a votes count for a new object of the test space.
+fn make_votes_count(upvotes: i64, downvotes: i64) -> VotesCount {
+    VotesCount {
+        object_id: Uuid::new_v4(),
+        object_type: ObjectType::Entity,
+        space_id: SPACE_ID,
+        upvotes,
+        downvotes,
+    }
+}
+
+async fn make_api(pool: sqlx::PgPool) -> (ActionsApi, Arc<dyn ActionsRepository>) {
+    let repository: Arc<dyn ActionsRepository> = Arc::new(PostgresActionsRepository::new(pool).await.unwrap());
+    (ActionsApi::new(repository.clone()), repository)
+}
+
+#[sqlx::test(migrations = "../actions-indexer-repository/src/postgres/migrations")]
+async fn test_votes_for_object_paginates(pool: sqlx::PgPool) {
+    let (api, repository) = make_api(pool).await;
+
+    let object_id = Uuid::new_v4();
+    let votes: Vec<UserVote> = (1..=3).map(|user| make_user_vote(user, object_id, 1755182910 + user as u64)).collect();
+    repository.update_user_votes(&votes).await.unwrap();
+
+    let first = api.votes_for_object(object_id, SPACE_ID, ObjectType::Entity, Page::new(2, 0)).await.unwrap();
+    assert_eq!(first, Paginated { items: vec![votes[2].clone(), votes[1].clone()], next_offset: Some(2) });
+
+    let last = api.votes_for_object(object_id, SPACE_ID, ObjectType::Entity, Page::new(2, 2)).await.unwrap();
+    assert_eq!(last, Paginated { items: vec![votes[0].clone()], next_offset: None });
+}
+
+#[sqlx::test(migrations = "../actions-indexer-repository/src/postgres/migrations")]
+async fn test_user_vote_history(pool: sqlx::PgPool) {
+    let (api, repository) = make_api(pool).await;
+
+    let older = make_user_vote(1, Uuid::new_v4(), 1755182913);
+    let newer = UserVote { vote_type: VoteValue::Remove, ..make_user_vote(1, Uuid::new_v4(), 1755182914) };
+    let other_user = make_user_vote(2, older.object_id, 1755182915);
+    repository.update_user_votes(&[older.clone(), newer.clone(), other_user]).await.unwrap();
+
+    let history = api.user_vote_history(older.user_id, Page::default()).await.unwrap();
+    assert_eq!(history, Paginated { items: vec![newer, older], next_offset: None });
+}
+
+#[sqlx::test(migrations = "../actions-indexer-repository/src/postgres/migrations")]
+async fn test_top_objects_ranks_by_score(pool: sqlx::PgPool) {
+    let (api, repository) = make_api(pool).await;
+
+    let low = make_votes_count(1, 2);
+    let high = make_votes_count(4, 0);
+    repository.update_votes_counts(&[low.clone(), high.clone()]).await.unwrap();
+
+    let top = api.top_objects(SPACE_ID, Page::default()).await.unwrap();
+    assert_eq!(top.items, vec![ObjectScore::from(high), ObjectScore::from(low)]);
+    assert_eq!(top.items[1].score, -1);
+}
+
+#[sqlx::test(migrations = "../actions-indexer-repository/src/postgres/migrations")]
+async fn test_invalid_page_is_rejected(pool: sqlx::PgPool) {
+    let (api, _) = make_api(pool).await;
+
+    let result = api.top_objects(SPACE_ID, Page::new(0, 0)).await;
+    assert!(matches!(result, Err(ApiError::InvalidPage(_))));
+}
+
+#[cfg(feature = "server")]
+mod server {
+    use super::*;
+    use actions_indexer_api::server::router;
+    use axum::body::{Body, to_bytes};
+    use axum::http::{Request, StatusCode};
+    use axum::response::Response;
+    use tower::ServiceExt;
+
+    async fn body<T: serde::de::DeserializeOwned>(response: Response) -> T {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn get(path: &str) -> Request<Body> {
+        Request::get(path).body(Body::empty()).unwrap()
+    }
+
+    #[sqlx::test(migrations = "../actions-indexer-repository/src/postgres/migrations")]
+    async fn test_routes_return_pages(pool: sqlx::PgPool) {
+        let (api, repository) = make_api(pool).await;
+        let app = router(Arc::new(api));
+
+        let vote = make_user_vote(1, Uuid::new_v4(), 1755182913);
+        let count = make_votes_count(1, 0);
+        repository.update_user_votes(std::slice::from_ref(&vote)).await.unwrap();
+        repository.update_votes_counts(std::slice::from_ref(&count)).await.unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(get(&format!("/objects/{}/votes?space_id={SPACE_ID}&object_type=Entity&limit=10", vote.object_id)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let votes: Paginated<UserVote> = body(response).await;
+        assert_eq!(votes.items, vec![vote.clone()]);
+
+        let response = app.clone().oneshot(get(&format!("/users/{}/votes", vote.user_id))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let history: Paginated<UserVote> = body(response).await;
+        assert_eq!(history.items, vec![vote]);
+
+        let response = app.oneshot(get(&format!("/spaces/{SPACE_ID}/top?offset=0"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let top: Paginated<ObjectScore> = body(response).await;
+        assert_eq!(top.items, vec![ObjectScore::from(count)]);
+    }
+
+    #[sqlx::test(migrations = "../actions-indexer-repository/src/postgres/migrations")]
+    async fn test_invalid_page_is_bad_request(pool: sqlx::PgPool) {
+        let (api, _) = make_api(pool).await;
+        let app = router(Arc::new(api));
+
+        let response = app.oneshot(get(&format!("/spaces/{SPACE_ID}/top?limit=1000"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,248 @@
+//! # Actions Indexer E2E
+//! End-to-end test harness of the actions indexer.
+//!
+//! Runs the full pipeline, the `Orchestrator` with the processor handlers of
+//! `actions-indexer`, against a [`ScriptedStream`] and an in-memory SQLite
+//! repository, so scenarios can assert the user votes and vote counts that end up
+//! stored without a database server or a Substreams endpoint.
+use actions_indexer::config::actions_processor;
+use actions_indexer_pipeline::consumer::stream::pb::sf::substreams::rpc::v2::BlockUndoSignal;
+use actions_indexer_pipeline::consumer::stream::pb::sf::substreams::v1::BlockRef;
+use actions_indexer_pipeline::consumer::{ActionsConsumer, BlockDataMessage, ConsumeActionsStream, StartMode, StreamMessage};
+use actions_indexer_pipeline::errors::{ConsumerError, OrchestratorError};
+use actions_indexer_pipeline::loader::ActionsLoader;
+use actions_indexer_pipeline::orchestrator::{BatchConfig, Orchestrator, RunSummary};
+use actions_indexer_repository::{ActionsRepository, SqliteActionsRepository, SqliteCursorRepository};
+use actions_indexer_shared::types::{ActionRaw, ActionType, ObjectId, ObjectType, SpaceId, UserAddress, VoteValue};
+use alloy::primitives::{Address, Bytes, TxHash};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Space every scripted vote is cast in.
+pub const SPACE: SpaceId = Uuid::from_u128(0x5ace);
+/// Timestamp of block 0, blocks being 12 seconds apart.
+const GENESIS_TIMESTAMP: u64 = 1_755_180_000;
+
+/// A voter, identified by the last byte of its address.
+pub fn user(id: u8) -> UserAddress {
+    Address::with_last_byte(id)
+}
+
+/// A voted entity, identified by a small number.
+pub fn object(id: u8) -> ObjectId {
+    Uuid::from_u128(id as u128)
+}
+
+/// Starts scripting the block `block_number`.
+pub fn block(block_number: u64) -> BlockBuilder {
+    BlockBuilder {
+        block_number,
+        actions: Vec::new(),
+    }
+}
+
+/// An undo signal rolling back the blocks after `last_valid_block`, like a reorg.
+pub fn undo(last_valid_block: u64) -> StreamMessage {
+    StreamMessage::UndoSignal(BlockUndoSignal {
+        last_valid_block: Some(BlockRef {
+            id: format!("block_{last_valid_block}"),
+            number: last_valid_block,
+        }),
+        last_valid_cursor: cursor(last_valid_block),
+    })
+}
+
+/// The cursor of a scripted block.
+pub fn cursor(block_number: u64) -> String {
+    format!("cursor_{block_number}")
+}
+
+/// `BlockBuilder` scripts the actions of a block, in the order they were emitted.
+///
+/// Every action gets its own log index, and the transaction hash of its block, so
+/// delivering the same block twice delivers the same actions.
+#[derive(Debug, Clone)]
+pub struct BlockBuilder {
+    block_number: u64,
+    actions: Vec<ActionRaw>,
+}
+
+impl BlockBuilder {
+    /// Adds a vote of `user` on `object` in [`SPACE`].
+    pub fn vote(mut self, user: UserAddress, object: ObjectId, vote: VoteValue) -> Self {
+        let metadata = match vote {
+            VoteValue::Up => 0u8,
+            VoteValue::Down => 1,
+            VoteValue::Remove => 2,
+        };
+        let mut tx_hash = [0u8; 32];
+        tx_hash[24..].copy_from_slice(&self.block_number.to_be_bytes());
+
+        self.actions.push(ActionRaw {
+            action_type: ActionType::Vote,
+            action_version: 1,
+            sender: user,
+            object_id: object,
+            group_id: None,
+            space_pov: SPACE,
+            metadata: Some(Bytes::from(vec![metadata])),
+            block_number: self.block_number,
+            block_timestamp: GENESIS_TIMESTAMP + self.block_number * 12,
+            tx_hash: TxHash::from(tx_hash),
+            object_type: ObjectType::Entity,
+            log_index: Some(self.actions.len() as u32),
+            output_position: None,
+        });
+        self
+    }
+}
+
+impl From<BlockBuilder> for StreamMessage {
+    fn from(block: BlockBuilder) -> Self {
+        StreamMessage::BlockData(BlockDataMessage {
+            actions: block.actions,
+            dead_letters: Vec::new(),
+            cursor: cursor(block.block_number),
+            block_number: block.block_number as i64,
+            final_block_number: 0,
+        })
+    }
+}
+
+/// `ScriptedStream` sends a fixed list of messages, then ends like a stream
+/// reaching its stop block.
+///
+/// The messages are sent once, and the cursor the stream starts from is recorded,
+/// see [`Harness::cursors`].
+pub struct ScriptedStream {
+    messages: Mutex<Vec<StreamMessage>>,
+    cursors: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl ScriptedStream {
+    /// Creates a stream sending `messages`, recording its cursors into `cursors`.
+    pub fn new(messages: Vec<StreamMessage>, cursors: Arc<Mutex<Vec<Option<String>>>>) -> Self {
+        Self {
+            messages: Mutex::new(messages),
+            cursors,
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumeActionsStream for ScriptedStream {
+    async fn stream_events(&self, sender: mpsc::Sender<StreamMessage>, cursor: Option<String>, _start_mode: &StartMode) -> Result<(), ConsumerError> {
+        self.cursors.lock().unwrap().push(cursor);
+        let messages = std::mem::take(&mut *self.messages.lock().unwrap());
+        for message in messages {
+            sender.send(message).await.map_err(|e| ConsumerError::ChannelSend(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// `Harness` runs scripts through the pipeline against an in-memory SQLite database.
+///
+/// The database is kept across runs, so a second run resumes from the cursor the
+/// first one saved, like an indexer restart.
+pub struct Harness {
+    pub actions_repository: Arc<SqliteActionsRepository>,
+    pub cursor_repository: Arc<SqliteCursorRepository>,
+    pub batch_config: BatchConfig,
+    pub namespace: Option<String>,
+    cursors: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl Harness {
+    /// Creates a harness over an empty database, persisting blocks one by one.
+    pub async fn new() -> Self {
+        let pool = actions_indexer_repository::sqlite::connect("sqlite::memory:")
+            .await
+            .expect("Failed to open the in-memory database");
+
+        Self {
+            actions_repository: Arc::new(SqliteActionsRepository::new(pool.clone()).await.unwrap()),
+            cursor_repository: Arc::new(SqliteCursorRepository::new(pool).await.unwrap()),
+            batch_config: BatchConfig::default(),
+            namespace: None,
+            cursors: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Creates a harness over the database of this one, running in `namespace`.
+    ///
+    /// The new harness records its own cursors, so each tenant's restarts can be
+    /// checked separately.
+    pub fn tenant(&self, namespace: &str) -> Self {
+        Self {
+            actions_repository: self.actions_repository.clone(),
+            cursor_repository: self.cursor_repository.clone(),
+            batch_config: self.batch_config,
+            namespace: Some(namespace.to_string()),
+            cursors: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Persists up to `max_blocks` blocks per transaction. Batches are only flushed
+    /// early by an undo signal or the end of the script.
+    pub fn with_batches(mut self, max_blocks: usize) -> Self {
+        self.batch_config = BatchConfig {
+            max_blocks,
+            max_actions: usize::MAX,
+            flush_interval: std::time::Duration::from_secs(3600),
+        };
+        self
+    }
+
+    /// Runs the pipeline until the scripted stream ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The messages the consumer delivers, in order
+    ///
+    /// # Returns
+    ///
+    /// The `RunSummary` of the run, or the `OrchestratorError` it stopped with.
+    pub async fn run(&self, messages: Vec<StreamMessage>) -> Result<RunSummary, OrchestratorError> {
+        let stream = ScriptedStream::new(messages, self.cursors.clone());
+        let loader = ActionsLoader::new(self.actions_repository.clone(), self.cursor_repository.clone());
+
+        let mut orchestrator = Orchestrator::new(
+            Box::new(ActionsConsumer::new(Box::new(stream))),
+            Box::new(actions_processor()),
+            Box::new(loader),
+        )
+        .with_batch_config(self.batch_config);
+        if let Some(namespace) = &self.namespace {
+            orchestrator = orchestrator.with_namespace(namespace);
+        }
+        orchestrator.run_until(std::future::pending()).await
+    }
+
+    /// The cursor each run started streaming from, in run order.
+    pub fn cursors(&self) -> Vec<Option<String>> {
+        self.cursors.lock().unwrap().clone()
+    }
+
+    /// The stored upvotes and downvotes of `object`, `(0, 0)` if it was never voted.
+    pub async fn votes(&self, object: ObjectId) -> (i64, i64) {
+        self.actions_repository
+            .get_vote_counts(&[(object, SPACE, ObjectType::Entity)])
+            .await
+            .unwrap()
+            .first()
+            .map_or((0, 0), |votes_count| (votes_count.upvotes, votes_count.downvotes))
+    }
+
+    /// The stored vote of `user` on `object`, if any.
+    pub async fn user_vote(&self, user: UserAddress, object: ObjectId) -> Option<VoteValue> {
+        self.actions_repository
+            .get_user_votes(&[(user, object, SPACE, ObjectType::Entity)])
+            .await
+            .unwrap()
+            .first()
+            .map(|user_vote| user_vote.vote_type.clone())
+    }
+}
@@ -0,0 +1,222 @@
+use actions_indexer_e2e::{block, cursor, object, undo, user, Harness};
+use actions_indexer_shared::types::VoteValue;
+
+// ============================================================================
+// Votes
+// ============================================================================
+
+#[tokio::test]
+async fn test_vote_flips() {
+    let harness = Harness::new().await;
+
+    harness
+        .run(vec![
+            block(1).vote(user(1), object(1), VoteValue::Up).into(),
+            block(2).vote(user(1), object(1), VoteValue::Down).into(),
+            block(3).vote(user(2), object(1), VoteValue::Down).vote(user(2), object(1), VoteValue::Up).into(),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(harness.votes(object(1)).await, (1, 1));
+    assert_eq!(harness.user_vote(user(1), object(1)).await, Some(VoteValue::Down));
+    // The latest vote of a block wins
+    assert_eq!(harness.user_vote(user(2), object(1)).await, Some(VoteValue::Up));
+}
+
+#[tokio::test]
+async fn test_vote_removes() {
+    let harness = Harness::new().await;
+
+    harness
+        .run(vec![
+            block(1)
+                .vote(user(1), object(1), VoteValue::Up)
+                .vote(user(2), object(1), VoteValue::Up)
+                .vote(user(3), object(2), VoteValue::Down)
+                .into(),
+            block(2).vote(user(1), object(1), VoteValue::Remove).vote(user(3), object(2), VoteValue::Remove).into(),
+            // Removing a vote that was never cast doesn't change the counts
+            block(3).vote(user(4), object(1), VoteValue::Remove).into(),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(harness.votes(object(1)).await, (1, 0));
+    assert_eq!(harness.votes(object(2)).await, (0, 0));
+    assert_eq!(harness.user_vote(user(1), object(1)).await, Some(VoteValue::Remove));
+    assert_eq!(harness.user_vote(user(2), object(1)).await, Some(VoteValue::Up));
+}
+
+// ============================================================================
+// Reorgs
+// ============================================================================
+
+#[tokio::test]
+async fn test_reorg_restores_votes_of_last_valid_block() {
+    let harness = Harness::new().await;
+
+    let summary = harness
+        .run(vec![
+            block(1).vote(user(1), object(1), VoteValue::Up).into(),
+            block(2).vote(user(2), object(1), VoteValue::Up).into(),
+            block(3).vote(user(1), object(1), VoteValue::Down).vote(user(3), object(2), VoteValue::Up).into(),
+            undo(1),
+            block(2).vote(user(2), object(1), VoteValue::Down).into(),
+        ])
+        .await
+        .unwrap();
+
+    // User 1 flipped and user 3 voted on the undone fork only
+    assert_eq!(harness.votes(object(1)).await, (1, 1));
+    assert_eq!(harness.votes(object(2)).await, (0, 0));
+    assert_eq!(harness.user_vote(user(1), object(1)).await, Some(VoteValue::Up));
+    assert_eq!(harness.user_vote(user(2), object(1)).await, Some(VoteValue::Down));
+    assert_eq!(harness.user_vote(user(3), object(2)).await, None);
+    assert_eq!(summary.last_block_number, Some(2));
+}
+
+#[tokio::test]
+async fn test_reorg_replays_same_actions_on_new_fork() {
+    let harness = Harness::new().await;
+
+    harness
+        .run(vec![
+            block(1).vote(user(1), object(1), VoteValue::Up).into(),
+            block(2).vote(user(2), object(1), VoteValue::Up).into(),
+            undo(1),
+            // The undone actions are removed, so the same ones aren't skipped on the new fork
+            block(2).vote(user(2), object(1), VoteValue::Up).into(),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(harness.votes(object(1)).await, (2, 0));
+}
+
+#[tokio::test]
+async fn test_reorg_within_batch() {
+    let harness = Harness::new().await.with_batches(10);
+
+    harness
+        .run(vec![
+            block(1).vote(user(1), object(1), VoteValue::Up).into(),
+            block(2).vote(user(1), object(1), VoteValue::Down).into(),
+            block(3).vote(user(2), object(1), VoteValue::Up).into(),
+            // The pending batch is persisted before it is rolled back
+            undo(2),
+            block(3).vote(user(2), object(1), VoteValue::Down).into(),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(harness.votes(object(1)).await, (0, 2));
+    assert_eq!(harness.user_vote(user(1), object(1)).await, Some(VoteValue::Down));
+}
+
+// ============================================================================
+// Duplicate Deliveries and Restarts
+// ============================================================================
+
+#[tokio::test]
+async fn test_duplicate_delivery_counts_votes_once() {
+    let harness = Harness::new().await;
+
+    let summary = harness
+        .run(vec![
+            block(1).vote(user(1), object(1), VoteValue::Up).into(),
+            block(1).vote(user(1), object(1), VoteValue::Up).into(),
+            block(2).vote(user(2), object(1), VoteValue::Up).vote(user(3), object(1), VoteValue::Down).into(),
+            block(2).vote(user(2), object(1), VoteValue::Up).vote(user(3), object(1), VoteValue::Down).into(),
+        ])
+        .await
+        .unwrap();
+
+    assert_eq!(harness.votes(object(1)).await, (2, 1));
+    assert_eq!(summary.blocks, 4);
+}
+
+#[tokio::test]
+async fn test_duplicate_delivery_after_restart() {
+    let harness = Harness::new().await;
+    let first_run = vec![
+        block(1).vote(user(1), object(1), VoteValue::Up).into(),
+        block(2).vote(user(2), object(1), VoteValue::Down).into(),
+    ];
+    // The stream redelivers the last block it sent before the restart
+    let second_run = vec![
+        block(2).vote(user(2), object(1), VoteValue::Down).into(),
+        block(3).vote(user(1), object(1), VoteValue::Remove).into(),
+    ];
+
+    harness.run(first_run).await.unwrap();
+    harness.run(second_run).await.unwrap();
+
+    assert_eq!(harness.cursors(), vec![None, Some(cursor(2))]);
+    assert_eq!(harness.votes(object(1)).await, (0, 1));
+    assert_eq!(harness.user_vote(user(1), object(1)).await, Some(VoteValue::Remove));
+}
+
+#[tokio::test]
+async fn test_restart_after_reorg_resumes_from_last_valid_block() {
+    let harness = Harness::new().await;
+
+    harness
+        .run(vec![
+            block(1).vote(user(1), object(1), VoteValue::Up).into(),
+            block(2).vote(user(2), object(1), VoteValue::Up).into(),
+            undo(1),
+        ])
+        .await
+        .unwrap();
+    harness.run(vec![block(2).vote(user(2), object(1), VoteValue::Down).into()]).await.unwrap();
+
+    // The cursor of the undone block 2 isn't resumed from
+    assert_eq!(harness.cursors(), vec![None, Some(cursor(1))]);
+    assert_eq!(harness.votes(object(1)).await, (1, 1));
+}
+
+#[tokio::test]
+async fn test_batches_match_single_blocks() {
+    let script = || {
+        vec![
+            block(1).vote(user(1), object(1), VoteValue::Up).vote(user(2), object(2), VoteValue::Up).into(),
+            block(2).vote(user(1), object(1), VoteValue::Down).into(),
+            block(2).vote(user(1), object(1), VoteValue::Down).into(),
+            block(3).vote(user(2), object(2), VoteValue::Remove).vote(user(3), object(1), VoteValue::Up).into(),
+            block(4).vote(user(1), object(2), VoteValue::Down).into(),
+        ]
+    };
+    let single_blocks = Harness::new().await;
+    let batches = Harness::new().await.with_batches(2);
+
+    single_blocks.run(script()).await.unwrap();
+    let summary = batches.run(script()).await.unwrap();
+
+    for object in [object(1), object(2)] {
+        assert_eq!(batches.votes(object).await, single_blocks.votes(object).await);
+    }
+    assert_eq!(batches.votes(object(1)).await, (1, 1));
+    assert_eq!(batches.votes(object(2)).await, (0, 1));
+    assert_eq!(summary.last_block_number, Some(4));
+}
+
+// ============================================================================
+// Namespaces
+// ============================================================================
+
+#[tokio::test]
+async fn test_namespaces_resume_from_their_own_cursor() {
+    let mainnet = Harness::new().await;
+    let testnet = mainnet.tenant("testnet");
+
+    mainnet.run(vec![block(5).vote(user(1), object(1), VoteValue::Up).into()]).await.unwrap();
+    testnet.run(vec![block(2).vote(user(2), object(2), VoteValue::Down).into()]).await.unwrap();
+    mainnet.run(vec![block(6).vote(user(1), object(1), VoteValue::Down).into()]).await.unwrap();
+    testnet.run(vec![block(3).vote(user(2), object(2), VoteValue::Remove).into()]).await.unwrap();
+
+    assert_eq!(mainnet.cursors(), vec![None, Some(cursor(5))]);
+    assert_eq!(testnet.cursors(), vec![None, Some(cursor(2))]);
+    assert_eq!(mainnet.votes(object(1)).await, (0, 1));
+    assert_eq!(testnet.votes(object(2)).await, (0, 0));
+}
@@ -0,0 +1,218 @@
+//! Rolling activity counters of the persisted actions.
+//!
+//! [`activity_deltas`] counts the actions of each block per hourly bucket, space, sender
+//! and action type. The deltas are persisted with the block's changeset, so the counters
+//! are rolled back with it. Summing the recent buckets ranks the most active spaces and
+//! senders, such as over the last 24 hours. The [`ActivityAggregator`] prunes the buckets
+//! older than the retention as newer ones are persisted, so the counters stay bounded.
+use actions_indexer_repository::ActivityRepository;
+use actions_indexer_shared::types::{activity_bucket_start, Action, ActionType, ActivityDelta, Changeset, SpaceId, UserAddress};
+use crate::errors::AggregationError;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// `ActivityAggregator` maintains the rolling activity counters of the persisted actions.
+///
+/// It utilizes an `ActivityRepository` to prune the buckets that fell out of the
+/// retention once newer changesets are persisted.
+pub struct ActivityAggregator {
+    pub activity_repository: Arc<dyn ActivityRepository>,
+    pub retention: Duration,
+    /// Newest bucket the counters were pruned for, so they are pruned once per bucket.
+    pruned_for: AtomicU64,
+}
+
+impl ActivityAggregator {
+    /// Creates a new `ActivityAggregator` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_repository` - An `Arc` trait object that implements `ActivityRepository`,
+    ///   providing the interface for counter pruning.
+    /// * `retention` - How long buckets are kept, counted back from the newest persisted bucket.
+    ///
+    /// # Returns
+    ///
+    /// A new `ActivityAggregator` instance.
+    pub fn new(activity_repository: Arc<dyn ActivityRepository>, retention: Duration) -> Self {
+        Self {
+            activity_repository,
+            retention,
+            pruned_for: AtomicU64::new(0),
+        }
+    }
+
+    /// Prunes the activity counters after changesets were persisted.
+    ///
+    /// Once a newer bucket is persisted, the buckets that started more than `retention`
+    /// before it are pruned.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - The changesets that were persisted, with their activity deltas.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or an `AggregationError` if the counters couldn't be
+    /// pruned.
+    pub async fn prune(&self, changesets: &[Changeset<'_>]) -> Result<(), AggregationError> {
+        let newest_bucket = changesets
+            .iter()
+            .flat_map(|changeset| changeset.activity_deltas)
+            .map(|delta| delta.bucket_start)
+            .max();
+        let Some(newest_bucket) = newest_bucket else {
+            return Ok(());
+        };
+        if self.pruned_for.fetch_max(newest_bucket, Ordering::Relaxed) < newest_bucket {
+            let before = newest_bucket.saturating_sub(self.retention.as_secs());
+            let pruned = self.activity_repository.prune_activity(before).await?;
+            if pruned > 0 {
+                println!("Pruned {} activity counters before {}", pruned, before);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts the actions of a block per bucket, space, sender and action type.
+///
+/// # Arguments
+///
+/// * `actions` - The actions to count.
+///
+/// # Returns
+///
+/// One `ActivityDelta` per counter the actions touch.
+pub fn activity_deltas(actions: &[Action]) -> Vec<ActivityDelta> {
+    let mut counts: HashMap<(u64, SpaceId, UserAddress, ActionType), i64> = HashMap::new();
+
+    for action in actions {
+        let raw = action.raw();
+        let key = (activity_bucket_start(raw.block_timestamp), raw.space_pov, raw.sender, raw.action_type);
+        *counts.entry(key).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|((bucket_start, space_id, sender, action_type), actions)| ActivityDelta {
+            bucket_start,
+            space_id,
+            sender,
+            action_type,
+            actions,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions_indexer_repository::ActivityRepositoryError;
+    use actions_indexer_shared::types::{Action, ActionRaw, Flag, ObjectType, SenderActivity, SpaceActivity, ACTIVITY_BUCKET_SECONDS};
+    use alloy::primitives::{Address, TxHash};
+    use std::sync::Mutex;
+    use uuid::{uuid, Uuid};
+
+    const SPACE_ID: Uuid = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+    /// Start of an hourly bucket.
+    const NOW: u64 = 1755180000;
+
+    #[derive(Default)]
+    struct MockActivityRepository {
+        pruned: Mutex<Vec<u64>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ActivityRepository for MockActivityRepository {
+        async fn get_most_active_spaces(&self, _since: u64, _action_type: Option<ActionType>, _limit: i64) -> Result<Vec<SpaceActivity>, ActivityRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_most_active_senders(&self, _since: u64, _space_id: Option<SpaceId>, _action_type: Option<ActionType>, _limit: i64) -> Result<Vec<SenderActivity>, ActivityRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn prune_activity(&self, before: u64) -> Result<u64, ActivityRepositoryError> {
+            self.pruned.lock().unwrap().push(before);
+            Ok(0)
+        }
+    }
+
+    fn flag(sender: u8, block_timestamp: u64) -> Action {
+        Action::Flag(Flag {
+            raw: ActionRaw {
+                action_type: ActionType::Flag,
+                action_version: 1,
+                sender: Address::with_last_byte(sender),
+                object_id: Uuid::new_v4(),
+                group_id: None,
+                space_pov: SPACE_ID,
+                metadata: None,
+                block_number: 1,
+                block_timestamp,
+                tx_hash: TxHash::ZERO,
+                object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
+            },
+            reason: None,
+        })
+    }
+
+    fn changeset(block_number: u64, activity_deltas: &[ActivityDelta]) -> Changeset<'_> {
+        Changeset {
+            block_number,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: &[],
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas,
+        }
+    }
+
+    #[test]
+    fn test_activity_deltas_count_per_bucket_and_sender() {
+        let actions = [flag(1, NOW + 10), flag(1, NOW + 20), flag(2, NOW + 30), flag(1, NOW + ACTIVITY_BUCKET_SECONDS)];
+
+        let mut deltas = activity_deltas(&actions);
+        deltas.sort_by_key(|delta| (delta.bucket_start, delta.sender));
+
+        let delta = |bucket_start, sender, actions| ActivityDelta {
+            bucket_start,
+            space_id: SPACE_ID,
+            sender: Address::with_last_byte(sender),
+            action_type: ActionType::Flag,
+            actions,
+        };
+        assert_eq!(
+            deltas,
+            vec![
+                delta(NOW, 1, 2),
+                delta(NOW, 2, 1),
+                delta(NOW + ACTIVITY_BUCKET_SECONDS, 1, 1),
+            ]
+        );
+        assert!(activity_deltas(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prune_once_per_bucket() {
+        let repository = Arc::new(MockActivityRepository::default());
+        let aggregator = ActivityAggregator::new(repository.clone(), Duration::from_secs(24 * ACTIVITY_BUCKET_SECONDS));
+
+        aggregator.prune(&[changeset(1, &activity_deltas(&[flag(1, NOW)]))]).await.unwrap();
+        aggregator.prune(&[changeset(2, &activity_deltas(&[flag(1, NOW + 60)]))]).await.unwrap();
+        aggregator.prune(&[changeset(3, &[])]).await.unwrap();
+        aggregator.prune(&[changeset(4, &activity_deltas(&[flag(1, NOW + ACTIVITY_BUCKET_SECONDS)]))]).await.unwrap();
+
+        assert_eq!(
+            *repository.pruned.lock().unwrap(),
+            vec![NOW - 24 * ACTIVITY_BUCKET_SECONDS, NOW - 23 * ACTIVITY_BUCKET_SECONDS]
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Allow and deny lists applied by the consumer before processing.
+//!
+//! Spam contracts can flood the pipeline, so the consumer can drop the actions of
+//! given senders, spaces or groups before they reach the processor. The rules are
+//! held behind a lock and can be swapped while the indexer runs, e.g. when the file
+//! they were read from changes.
+use actions_indexer_shared::types::{ActionRaw, GroupId, SpaceId, UserAddress};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use crate::consumer::BlockDataMessage;
+use crate::metrics::{FilterReason, PipelineMetrics};
+
+/// Senders, spaces and groups an allow or deny list matches.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct FilterList {
+    pub senders: HashSet<UserAddress>,
+    pub spaces: HashSet<SpaceId>,
+    pub groups: HashSet<GroupId>,
+}
+
+/// Allow and deny lists of the consumer.
+///
+/// An action is dropped if its sender, space or group is denied. For each of them
+/// whose allow list isn't empty, it is also dropped unless it is allowed. Actions
+/// without a group never match a group list.
+///
+/// ```json
+/// {
+///   "allow": { "spaces": ["e50fe85c-108a-4d4a-97b9-376a1e5d318b"] },
+///   "deny": { "senders": ["0x000000000000000000000000000000000000dead"] }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct FilterRules {
+    pub allow: FilterList,
+    pub deny: FilterList,
+}
+
+impl FilterRules {
+    /// Why `action` is filtered out, or `None` if it passes the lists.
+    pub fn check(&self, action: &ActionRaw) -> Option<FilterReason> {
+        if !passes(&self.allow.senders, &self.deny.senders, Some(&action.sender)) {
+            return Some(FilterReason::Sender);
+        }
+        if !passes(&self.allow.spaces, &self.deny.spaces, Some(&action.space_pov)) {
+            return Some(FilterReason::Space);
+        }
+        if !passes(&self.allow.groups, &self.deny.groups, action.group_id.as_ref()) {
+            return Some(FilterReason::Group);
+        }
+        None
+    }
+
+    /// Whether the rules can't filter anything out.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Whether a value passes an allow list, ignored when empty, and a deny list.
+fn passes<T: Eq + std::hash::Hash>(allow: &HashSet<T>, deny: &HashSet<T>, value: Option<&T>) -> bool {
+    match value {
+        Some(value) => !deny.contains(value) && (allow.is_empty() || allow.contains(value)),
+        None => allow.is_empty(),
+    }
+}
+
+/// `ActionFilter` applies the current `FilterRules` to the blocks of the consumer.
+///
+/// Filtered actions are counted by reason in the `PipelineMetrics`. The rules can be
+/// replaced at any time with [`Self::reload`], and apply from the next block on.
+pub struct ActionFilter {
+    rules: RwLock<Arc<FilterRules>>,
+    metrics: Arc<PipelineMetrics>,
+}
+
+impl ActionFilter {
+    /// Creates a new `ActionFilter` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The allow and deny lists to apply
+    /// * `metrics` - The metrics counting the filtered actions
+    ///
+    /// # Returns
+    ///
+    /// A new `ActionFilter` instance.
+    pub fn new(rules: FilterRules, metrics: Arc<PipelineMetrics>) -> Self {
+        Self {
+            rules: RwLock::new(Arc::new(rules)),
+            metrics,
+        }
+    }
+
+    /// The rules currently applied.
+    pub fn rules(&self) -> Arc<FilterRules> {
+        self.rules.read().unwrap().clone()
+    }
+
+    /// Replaces the rules applied to the next blocks.
+    pub fn reload(&self, rules: FilterRules) {
+        *self.rules.write().unwrap() = Arc::new(rules);
+    }
+
+    /// Drops the actions of a block the rules filter out.
+    ///
+    /// Dead letters are kept, since they couldn't be decoded to be checked.
+    ///
+    /// # Returns
+    ///
+    /// The number of actions dropped.
+    pub fn apply(&self, block: &mut BlockDataMessage) -> usize {
+        let rules = self.rules();
+        if rules.is_empty() {
+            return 0;
+        }
+
+        let before = block.actions.len();
+        block.actions.retain(|action| match rules.check(action) {
+            Some(reason) => {
+                self.metrics.record_filtered(reason, 1);
+                false
+            }
+            None => true,
+        });
+        before - block.actions.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions_indexer_shared::types::{ActionType, ObjectType};
+    use alloy::primitives::{Address, TxHash};
+    use uuid::{uuid, Uuid};
+
+    const SPACE_A: Uuid = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+    const SPACE_B: Uuid = uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19");
+    const GROUP: Uuid = uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5");
+
+    fn action(sender: u8, space_pov: Uuid, group_id: Option<Uuid>) -> ActionRaw {
+        ActionRaw {
+            action_type: ActionType::Vote,
+            action_version: 1,
+            sender: Address::with_last_byte(sender),
+            object_id: Uuid::new_v4(),
+            group_id,
+            space_pov,
+            metadata: None,
+            block_number: 1,
+            block_timestamp: 1755182913,
+            tx_hash: TxHash::ZERO,
+            object_type: ObjectType::Entity,
+            log_index: None,
+            output_position: None,
+        }
+    }
+
+    #[test]
+    fn test_check_deny_and_allow_lists() {
+        let rules = FilterRules {
+            allow: FilterList {
+                spaces: HashSet::from([SPACE_A]),
+                ..Default::default()
+            },
+            deny: FilterList {
+                senders: HashSet::from([Address::with_last_byte(1)]),
+                groups: HashSet::from([GROUP]),
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(rules.check(&action(1, SPACE_A, None)), Some(FilterReason::Sender));
+        assert_eq!(rules.check(&action(2, SPACE_B, None)), Some(FilterReason::Space));
+        assert_eq!(rules.check(&action(2, SPACE_A, Some(GROUP))), Some(FilterReason::Group));
+        assert_eq!(rules.check(&action(2, SPACE_A, Some(Uuid::new_v4()))), None);
+        assert_eq!(rules.check(&action(2, SPACE_A, None)), None);
+        assert_eq!(FilterRules::default().check(&action(1, SPACE_B, Some(GROUP))), None);
+    }
+
+    #[test]
+    fn test_check_group_allow_list_requires_a_group() {
+        let rules = FilterRules {
+            allow: FilterList {
+                groups: HashSet::from([GROUP]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(rules.check(&action(1, SPACE_A, None)), Some(FilterReason::Group));
+        assert_eq!(rules.check(&action(1, SPACE_A, Some(GROUP))), None);
+    }
+
+    #[test]
+    fn test_apply_counts_and_reloads() {
+        let metrics = Arc::new(PipelineMetrics::default());
+        let filter = ActionFilter::new(FilterRules::default(), metrics.clone());
+        let block = || BlockDataMessage {
+            actions: vec![action(1, SPACE_A, None), action(2, SPACE_A, None), action(1, SPACE_B, None)],
+            dead_letters: vec![],
+            cursor: "cursor".to_string(),
+            block_number: 1,
+            final_block_number: 0,
+        };
+
+        let mut unfiltered = block();
+        assert_eq!(filter.apply(&mut unfiltered), 0);
+        assert_eq!(unfiltered.actions.len(), 3);
+
+        filter.reload(FilterRules {
+            deny: FilterList {
+                senders: HashSet::from([Address::with_last_byte(1)]),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let mut filtered = block();
+        assert_eq!(filter.apply(&mut filtered), 2);
+        assert_eq!(filtered.actions.len(), 1);
+        assert_eq!(filtered.actions[0].sender, Address::with_last_byte(2));
+        assert_eq!(metrics.filtered(FilterReason::Sender), 2);
+        assert_eq!(metrics.filtered(FilterReason::Space), 0);
+    }
+}
@@ -2,13 +2,18 @@
 //!
 //! Provides the `ConsumeActions` trait for consuming blockchain action events
 //! from data sources like substreams. Acts as the entry point for the pipeline,
-//! feeding data to processing and loading components.
+//! feeding data to processing and loading components, after dropping the actions
+//! its allow and deny lists filter out.
 use crate::errors::ConsumerError;
 
+pub mod filter;
 pub mod stream;
 
-use actions_indexer_shared::types::ActionRaw;
+use actions_indexer_shared::types::{ActionRaw, DeadLetter};
 use async_trait::async_trait;
+use filter::ActionFilter;
+use std::ops::Range;
+use std::sync::Arc;
 use stream::pb::sf::substreams::rpc::v2::BlockUndoSignal;
 use tokio::sync::mpsc;
 
@@ -24,19 +29,55 @@ pub enum StreamMessage {
     StreamEnd,
 }
 
+/// Where the consumer starts streaming from.
+///
+/// Only `FromCursor` resumes from the stored cursor. The other modes ignore it, so
+/// operators can reindex a range or skip ahead without editing the database.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StartMode {
+    /// Resume from the stored cursor, or from the configured start block without one.
+    #[default]
+    FromCursor,
+    /// Start at the given block, up to the configured stop block.
+    FromBlock(u64),
+    /// Start at the chain head, up to the configured stop block.
+    FromTail,
+    /// Stream the given block range, stop block excluded, then stop. The stored
+    /// cursor is left untouched.
+    Replay(Range<u64>),
+}
+
+impl StartMode {
+    /// Whether the cursor of the streamed blocks should be saved.
+    ///
+    /// A replay doesn't move the stored cursor, so the indexer resumes where it was
+    /// once the replay is over.
+    pub fn saves_cursor(&self) -> bool {
+        !matches!(self, StartMode::Replay(_))
+    }
+}
+
+/// The actions of a block, along with the events of the block that couldn't be decoded.
+///
+/// `final_block_number` is the last irreversible block when the block was streamed, or
+/// 0 for blocks read outside of the stream.
 #[derive(Debug)]
 pub struct BlockDataMessage {
     pub actions: Vec<ActionRaw>,
+    pub dead_letters: Vec<DeadLetter>,
     pub cursor: String,
     pub block_number: i64,
+    pub final_block_number: u64,
 }
 /// Consumer component responsible for orchestrating blockchain action streaming.
 ///
 /// Acts as a coordinator between stream providers and the processing pipeline,
 /// managing the flow of blockchain action data through channels. Provides a
-/// clean abstraction over different streaming implementations.
+/// clean abstraction over different streaming implementations, and drops the
+/// actions its `ActionFilter` filters out, if any.
 pub struct ActionsConsumer {
     stream_provider: Box<dyn ConsumeActionsStream>,
+    filter: Option<Arc<ActionFilter>>,
 }
 
 impl ActionsConsumer {
@@ -51,18 +92,35 @@ impl ActionsConsumer {
     ///
     /// A new `ActionsConsumer` instance ready to start streaming.
     pub fn new(stream_provider: Box<dyn ConsumeActionsStream>) -> Self {
-        Self { stream_provider }
+        Self { stream_provider, filter: None }
+    }
+
+    /// Sets the filter applied to every block before it is sent to the orchestrator.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The `ActionFilter` to apply, shared with whatever reloads its rules
+    ///
+    /// # Returns
+    ///
+    /// The `ActionsConsumer` with the new filter.
+    pub fn with_filter(mut self, filter: Arc<ActionFilter>) -> Self {
+        self.filter = Some(filter);
+        self
     }
 
     /// Starts the consumer and begins streaming blockchain action events.
     ///
     /// This method delegates to the underlying stream provider to initiate the
     /// streaming process. It will continue until the stream ends or an error occurs.
+    /// With a filter, the blocks go through it on their way to the orchestrator.
     /// 
     /// # Arguments
     ///
     /// * `sender` - Channel sender for streaming messages to the orchestrator
-    /// * `cursor` - The cursor to start streaming from (optional)
+    /// * `cursor` - The cursor to start streaming from (optional), only used with
+    ///   `StartMode::FromCursor`
+    /// * `start_mode` - Where to start streaming from
     ///
     /// # Returns
     ///
@@ -74,9 +132,26 @@ impl ActionsConsumer {
     /// - The stream provider fails to initialize or connect
     /// - Network connectivity issues occur during streaming
     /// - Data parsing or validation errors happen
-    pub async fn run(&self, sender: mpsc::Sender<StreamMessage>, cursor: Option<String>) -> Result<(), ConsumerError> {
-        self.stream_provider.stream_events(sender, cursor).await?;
-        Ok(())
+    pub async fn run(&self, sender: mpsc::Sender<StreamMessage>, cursor: Option<String>, start_mode: &StartMode) -> Result<(), ConsumerError> {
+        let Some(filter) = &self.filter else {
+            self.stream_provider.stream_events(sender, cursor, start_mode).await?;
+            return Ok(());
+        };
+
+        let (filter_tx, mut filter_rx) = mpsc::channel(sender.max_capacity());
+        let forward = async move {
+            while let Some(mut message) = filter_rx.recv().await {
+                if let StreamMessage::BlockData(block) = &mut message {
+                    filter.apply(block);
+                }
+                if sender.send(message).await.is_err() {
+                    break;
+                }
+            }
+        };
+
+        let (result, _) = tokio::join!(self.stream_provider.stream_events(filter_tx, cursor, start_mode), forward);
+        result
     }
 }
 
@@ -93,10 +168,12 @@ pub trait ConsumeActionsStream: Send + Sync {
     /// # Arguments
     ///
     /// * `sender` - Channel sender for streaming messages to the orchestrator
-    /// * `cursor` - The cursor to start streaming from (optional)
+    /// * `cursor` - The cursor to start streaming from (optional), only used with
+    ///   `StartMode::FromCursor`
+    /// * `start_mode` - Where to start streaming from, and where to stop for a replay
     ///
     /// # Returns
     ///
     /// A `Result` indicating success or a `ConsumerError` if streaming fails.
-    async fn stream_events(&self, sender: mpsc::Sender<StreamMessage>, cursor: Option<String>) -> Result<(), ConsumerError>;
+    async fn stream_events(&self, sender: mpsc::Sender<StreamMessage>, cursor: Option<String>, start_mode: &StartMode) -> Result<(), ConsumerError>;
 }
@@ -0,0 +1,530 @@
+//! Decoding of `map_actions` output into `ActionRaw`s.
+//!
+//! Each block's map output is a protobuf-encoded `Actions` message produced by
+//! actions-substream. Metadata bytes are passed through as-is, interpreting
+//! them is up to the action handlers in the processor.
+//!
+//! Events that can't be decoded are turned into `DeadLetter`s holding their raw
+//! bytes, instead of failing the whole block, so they can be decoded again once
+//! the decoder is fixed.
+//!
+//! Packages built before `log_index` was added don't emit it, so actions without
+//! one get their position in the block's output as `output_position` instead.
+//! The substream emits actions in log order, so the position identifies an
+//! action across replays as long as the same package is used.
+use actions_indexer_shared::types::{ActionRaw, ActionType, DeadLetter, ObjectType};
+use prost::Message;
+
+use super::pb::sf::actions::v1::{Action, Actions};
+use super::pb::sf::substreams::rpc::v2::BlockScopedData;
+use crate::errors::ConsumerError;
+
+/// The actions decoded from a block's map output, and the events that couldn't be.
+#[derive(Debug, Default, PartialEq)]
+pub struct DecodedActions {
+    pub actions: Vec<ActionRaw>,
+    pub dead_letters: Vec<DeadLetter>,
+}
+
+/// Decodes the actions in a block's map output, dead-lettering the invalid ones.
+///
+/// # Errors
+///
+/// Returns a `ConsumerError` if the block has no map output.
+pub fn decode_block_scoped_data(data: &BlockScopedData) -> Result<DecodedActions, ConsumerError> {
+    let output = data
+        .output
+        .as_ref()
+        .ok_or_else(|| ConsumerError::MissingField("output".to_string()))?
+        .map_output
+        .as_ref()
+        .ok_or_else(|| ConsumerError::MissingField("map_output".to_string()))?;
+    let block_number = data.clock.as_ref().map_or(0, |clock| clock.number);
+
+    Ok(decode_actions_or_dead_letter(output.value.as_slice(), block_number))
+}
+
+/// Decodes a protobuf-encoded `Actions` message read from `block_number`.
+///
+/// Each action with an invalid field becomes a dead letter holding an `Actions`
+/// message with only that action. If the message itself can't be decoded, it
+/// becomes a single dead letter holding all of its bytes. Actions without a log
+/// index get their position in the message as `output_position`, dead-lettered
+/// ones included.
+pub fn decode_actions_or_dead_letter(bytes: &[u8], block_number: u64) -> DecodedActions {
+    let actions = match Actions::decode(bytes) {
+        Ok(actions) => actions,
+        Err(e) => {
+            return DecodedActions {
+                actions: Vec::new(),
+                dead_letters: vec![DeadLetter {
+                    block_number,
+                    raw: bytes.to_vec(),
+                    error: ConsumerError::DecodingActions(e.to_string()).to_string(),
+                }],
+            };
+        }
+    };
+
+    let mut decoded = DecodedActions::default();
+    for (position, mut action) in actions.actions.into_iter().enumerate() {
+        if action.log_index.is_none() {
+            action.output_position.get_or_insert(position as u32);
+        }
+        match ActionRaw::try_from(&action) {
+            Ok(action) => decoded.actions.push(action),
+            Err(e) => decoded.dead_letters.push(DeadLetter {
+                block_number,
+                raw: Actions { actions: vec![action] }.encode_to_vec(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    decoded
+}
+
+/// Encodes an action back into an `Actions` message holding only that action.
+///
+/// Used to dead-letter actions that were decoded but couldn't be processed. The
+/// fields `ActionRaw` doesn't keep, like the emitting contract, are left empty.
+pub fn encode_action(action: &ActionRaw) -> Vec<u8> {
+    Actions { actions: vec![Action::from(action)] }.encode_to_vec()
+}
+
+/// Decodes a protobuf-encoded `Actions` message.
+///
+/// An empty buffer decodes to no actions, which is what the substream emits
+/// for blocks without action events.
+pub fn decode_actions(bytes: &[u8]) -> Result<Vec<ActionRaw>, ConsumerError> {
+    let actions =
+        Actions::decode(bytes).map_err(|e| ConsumerError::DecodingActions(e.to_string()))?;
+
+    actions.actions.iter().map(ActionRaw::try_from).collect()
+}
+
+impl From<&ActionRaw> for Action {
+    fn from(action: &ActionRaw) -> Self {
+        Action {
+            action_type: action.action_type as u64,
+            action_version: action.action_version,
+            sender: action.sender.to_string(),
+            object_id: action.object_id.to_string(),
+            group_id: action.group_id.map(|group_id| group_id.to_string()),
+            space_pov: action.space_pov.to_string(),
+            metadata: action.metadata.as_ref().map(|metadata| metadata.to_vec()),
+            block_number: action.block_number,
+            block_timestamp: action.block_timestamp,
+            tx_hash: action.tx_hash.to_string(),
+            object_type: action.object_type as u64,
+            log_index: action.log_index,
+            output_position: action.output_position,
+            ..Default::default()
+        }
+    }
+}
+
+impl TryFrom<&Action> for ActionRaw {
+    type Error = ConsumerError;
+
+    fn try_from(action: &Action) -> Result<Self, Self::Error> {
+        Ok(ActionRaw {
+            sender: action
+                .sender
+                .parse()
+                .map_err(|e| ConsumerError::InvalidAddress(format!("sender: {}", e)))?,
+            action_type: match action.action_type {
+                0 => ActionType::Vote,
+                1 => ActionType::Flag,
+                2 => ActionType::EditPublished,
+                3 => ActionType::Membership,
+                _ => {
+                    return Err(ConsumerError::InvalidActionType(format!(
+                        "action_type: {}",
+                        action.action_type
+                    )));
+                }
+            },
+            action_version: action.action_version,
+            space_pov: action
+                .space_pov
+                .parse()
+                .map_err(|e| ConsumerError::InvalidAddress(format!("space_pov: {}", e)))?,
+            object_id: action
+                .object_id
+                .parse()
+                .map_err(|e| ConsumerError::InvalidUuid(format!("entity: {}", e)))?,
+            group_id: action
+                .group_id
+                .as_ref()
+                .map(|group_id| group_id.parse())
+                .transpose()
+                .map_err(|e| ConsumerError::InvalidUuid(format!("group_id: {}", e)))?,
+            metadata: action
+                .metadata
+                .as_ref()
+                .map(|metadata| metadata.to_vec().into()),
+            block_number: action.block_number,
+            block_timestamp: action.block_timestamp,
+            tx_hash: action
+                .tx_hash
+                .parse()
+                .map_err(|e| ConsumerError::InvalidTxHash(format!("tx_hash: {}", e)))?,
+            object_type: match action.object_type {
+                0 => ObjectType::Entity,
+                1 => ObjectType::Relation,
+                _ => {
+                    return Err(ConsumerError::InvalidObjectType(format!(
+                        "object_type: {}",
+                        action.object_type
+                    )));
+                }
+            },
+            log_index: action.log_index,
+            output_position: action.output_position,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consumer::stream::pb::sf::substreams::rpc::v2::MapModuleOutput;
+    use alloy::primitives::{Address, TxHash};
+    use uuid::uuid;
+
+    // Golden `Actions` messages as emitted by actions-substream's `map_actions`
+
+    /// Upvote on an entity
+    const UPVOTE_ENTITY: &str = "
+        0aca0110011a2a30783439383934646266613964373862626534383037646132
+        3865666163613964313035653532343466222461386630333636302d39323162
+        2d346632612d623363362d653363623935343237343864322466386439373434
+        642d663534362d343566312d613930642d6362643531316339643630303a0100
+        40e8074880e2cfaa065242307835643166326164356236613164306136633165
+        6536636435643363326633623163383765306636623961336530633164326233
+        61346635653664376338623961";
+
+    /// Downvote on a relation with a group, then remove on an entity
+    const DOWNVOTE_RELATION_THEN_REMOVE: &str = "
+        0af20110011a2a30783439383934646266613964373862626534383037646132
+        3865666163613964313035653532343466222461386630333636302d39323162
+        2d346632612d623363362d6533636239353432373439612a2430623361356239
+        652d366631642d346332612d396538662d376436633562346133393238322466
+        386439373434642d663534362d343566312d613930642d636264353131633964
+        3630303a010140e8074880e2cfaa065242307835643166326164356236613164
+        3061366331656536636435643363326633623163383765306636623961336530
+        6331643262336134663565366437633862396158010aca0110011a2a30783439
+        3839346462666139643738626265343830376461323865666163613964313035
+        653532343466222461386630333636302d393231622d346632612d623363362d
+        653363623935343237343864322466386439373434642d663534362d34356631
+        2d613930642d6362643531316339643630303a010240e8074880e2cfaa065242
+        3078356431663261643562366131643061366331656536636435643363326633
+        6231633837653066366239613365306331643262336134663565366437633862
+        3961";
+
+    /// Upvote with metadata that isn't a vote value
+    const MALFORMED_METADATA: &str = "
+        0acc0110011a2a30783439383934646266613964373862626534383037646132
+        3865666163613964313035653532343466222461386630333636302d39323162
+        2d346632612d623363362d653363623935343237343864322466386439373434
+        642d663534362d343566312d613930642d6362643531316339643630303a03ff
+        007f40e8074880e2cfaa06524230783564316632616435623661316430613663
+        3165653663643564336332663362316338376530663662396133653063316432
+        623361346635653664376338623961";
+
+    /// Upvote without metadata
+    const NO_METADATA: &str = "
+        0ac70110011a2a30783439383934646266613964373862626534383037646132
+        3865666163613964313035653532343466222461386630333636302d39323162
+        2d346632612d623363362d653363623935343237343864322466386439373434
+        642d663534362d343566312d613930642d63626435313163396436303040e807
+        4880e2cfaa065242307835643166326164356236613164306136633165653663
+        6435643363326633623163383765306636623961336530633164326233613466
+        35653664376338623961";
+
+    /// Unknown action type 7
+    const UNKNOWN_ACTION_TYPE: &str = "
+        0acc01080710011a2a3078343938393464626661396437386262653438303764
+        61323865666163613964313035653532343466222461386630333636302d3932
+        31622d346632612d623363362d65336362393534323734386432246638643937
+        3434642d663534362d343566312d613930642d6362643531316339643630303a
+        010040e8074880e2cfaa06524230783564316632616435623661316430613663
+        3165653663643564336332663362316338376530663662396133653063316432
+        623361346635653664376338623961";
+
+    /// Unknown object type 9
+    const UNKNOWN_OBJECT_TYPE: &str = "
+        0acc0110011a2a30783439383934646266613964373862626534383037646132
+        3865666163613964313035653532343466222461386630333636302d39323162
+        2d346632612d623363362d653363623935343237343864322466386439373434
+        642d663534362d343566312d613930642d6362643531316339643630303a0100
+        40e8074880e2cfaa065242307835643166326164356236613164306136633165
+        6536636435643363326633623163383765306636623961336530633164326233
+        613466356536643763386239615809";
+
+    /// Sender that isn't an address
+    const INVALID_SENDER: &str = "
+        0aa60110011a06307831323334222461386630333636302d393231622d346632
+        612d623363362d653363623935343237343864322466386439373434642d6635
+        34362d343566312d613930642d6362643531316339643630303a010040e80748
+        80e2cfaa06524230783564316632616435623661316430613663316565366364
+        3564336332663362316338376530663662396133653063316432623361346635
+        653664376338623961";
+
+    /// Group ID that isn't a UUID
+    const INVALID_GROUP: &str = "
+        0ad60110011a2a30783439383934646266613964373862626534383037646132
+        3865666163613964313035653532343466222461386630333636302d39323162
+        2d346632612d623363362d6533636239353432373438642a0a6e6f742d612d75
+        756964322466386439373434642d663534362d343566312d613930642d636264
+        3531316339643630303a010040e8074880e2cfaa065242307835643166326164
+        3562366131643061366331656536636435643363326633623163383765306636
+        62396133653063316432623361346635653664376338623961";
+
+    fn fixture(hex_string: &str) -> Vec<u8> {
+        let hex_string = hex_string
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        alloy::hex::decode(hex_string).unwrap()
+    }
+
+    fn block_with_output(value: Vec<u8>) -> BlockScopedData {
+        BlockScopedData {
+            output: Some(MapModuleOutput {
+                name: "map_actions".to_string(),
+                map_output: Some(prost_types::Any {
+                    type_url: "type.googleapis.com/actions.v1.Actions".to_string(),
+                    value,
+                }),
+                debug_info: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_upvote_on_entity() {
+        let actions = decode_actions(&fixture(UPVOTE_ENTITY)).unwrap();
+
+        assert_eq!(actions.len(), 1);
+        let action = &actions[0];
+        assert_eq!(action.action_type, ActionType::Vote);
+        assert_eq!(action.action_version, 1);
+        assert_eq!(
+            action.sender,
+            "0x49894dbfa9d78bbe4807da28efaca9d105e5244f"
+                .parse::<Address>()
+                .unwrap()
+        );
+        assert_eq!(
+            action.object_id,
+            uuid!("a8f03660-921b-4f2a-b3c6-e3cb9542748d")
+        );
+        assert_eq!(action.group_id, None);
+        assert_eq!(
+            action.space_pov,
+            uuid!("f8d9744d-f546-45f1-a90d-cbd511c9d600")
+        );
+        assert_eq!(action.metadata, Some(vec![0].into()));
+        assert_eq!(action.block_number, 1_000);
+        assert_eq!(action.block_timestamp, 1_700_000_000);
+        assert_eq!(
+            action.tx_hash,
+            "0x5d1f2ad5b6a1d0a6c1ee6cd5d3c2f3b1c87e0f6b9a3e0c1d2b3a4f5e6d7c8b9a"
+                .parse::<TxHash>()
+                .unwrap()
+        );
+        assert_eq!(action.object_type, ObjectType::Entity);
+    }
+
+    #[test]
+    fn test_decode_multiple_actions_in_order() {
+        let actions = decode_actions(&fixture(DOWNVOTE_RELATION_THEN_REMOVE)).unwrap();
+
+        assert_eq!(actions.len(), 2);
+
+        assert_eq!(actions[0].object_type, ObjectType::Relation);
+        assert_eq!(
+            actions[0].object_id,
+            uuid!("a8f03660-921b-4f2a-b3c6-e3cb9542749a")
+        );
+        assert_eq!(
+            actions[0].group_id,
+            Some(uuid!("0b3a5b9e-6f1d-4c2a-9e8f-7d6c5b4a3928"))
+        );
+        assert_eq!(actions[0].metadata, Some(vec![1].into()));
+
+        assert_eq!(actions[1].object_type, ObjectType::Entity);
+        assert_eq!(actions[1].group_id, None);
+        assert_eq!(actions[1].metadata, Some(vec![2].into()));
+    }
+
+    #[test]
+    fn test_decode_malformed_metadata_is_passed_through() {
+        let actions = decode_actions(&fixture(MALFORMED_METADATA)).unwrap();
+
+        assert_eq!(actions[0].metadata, Some(vec![0xff, 0x00, 0x7f].into()));
+    }
+
+    #[test]
+    fn test_decode_missing_metadata() {
+        let actions = decode_actions(&fixture(NO_METADATA)).unwrap();
+
+        assert_eq!(actions[0].metadata, None);
+    }
+
+    #[test]
+    fn test_decode_empty_block() {
+        assert!(decode_actions(&[]).unwrap().is_empty());
+        assert!(
+            decode_block_scoped_data(&block_with_output(vec![]))
+                .unwrap()
+                == DecodedActions::default()
+        );
+    }
+
+    #[test]
+    fn test_decode_block_scoped_data() {
+        let actions = decode_block_scoped_data(&block_with_output(fixture(UPVOTE_ENTITY))).unwrap();
+
+        let mut expected = decode_actions(&fixture(UPVOTE_ENTITY)).unwrap();
+        expected[0].output_position = Some(0);
+        assert_eq!(actions.actions, expected);
+        assert!(actions.dead_letters.is_empty());
+    }
+
+    #[test]
+    fn test_decode_block_scoped_data_missing_output() {
+        let result = decode_block_scoped_data(&BlockScopedData::default());
+        assert!(matches!(result, Err(ConsumerError::MissingField(field)) if field == "output"));
+
+        let mut data = block_with_output(vec![]);
+        data.output.as_mut().unwrap().map_output = None;
+        let result = decode_block_scoped_data(&data);
+        assert!(matches!(result, Err(ConsumerError::MissingField(field)) if field == "map_output"));
+    }
+
+    #[test]
+    fn test_decode_invalid_protobuf() {
+        let result = decode_actions(&[0xff, 0xff, 0xff]);
+        assert!(matches!(result, Err(ConsumerError::DecodingActions(_))));
+
+        let mut truncated = fixture(UPVOTE_ENTITY);
+        truncated.truncate(truncated.len() - 10);
+        let result = decode_actions(&truncated);
+        assert!(matches!(result, Err(ConsumerError::DecodingActions(_))));
+    }
+
+    #[test]
+    fn test_decode_action_types() {
+        let mut actions = Actions::decode(fixture(UPVOTE_ENTITY).as_slice()).unwrap();
+
+        for (code, expected) in [
+            (0, ActionType::Vote),
+            (1, ActionType::Flag),
+            (2, ActionType::EditPublished),
+            (3, ActionType::Membership),
+        ] {
+            actions.actions[0].action_type = code;
+            let decoded = decode_actions(&actions.encode_to_vec()).unwrap();
+            assert_eq!(decoded[0].action_type, expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_log_index() {
+        // The golden messages predate log_index, like the output of older packages
+        let mut actions = Actions::decode(fixture(UPVOTE_ENTITY).as_slice()).unwrap();
+        assert_eq!(decode_actions(&actions.encode_to_vec()).unwrap()[0].log_index, None);
+
+        actions.actions[0].log_index = Some(0);
+        assert_eq!(decode_actions(&actions.encode_to_vec()).unwrap()[0].log_index, Some(0));
+    }
+
+    #[test]
+    fn test_dead_letter_decoding_falls_back_to_position() {
+        let mut actions = Actions::decode(fixture(UPVOTE_ENTITY).as_slice()).unwrap();
+        actions.actions.push(actions.actions[0].clone());
+        actions.actions.push(actions.actions[0].clone());
+        actions.actions[2].log_index = Some(7);
+
+        let decoded = decode_actions_or_dead_letter(&actions.encode_to_vec(), 1_000);
+
+        // Actions from older packages are identified by their position in the block
+        let log_indexes: Vec<_> = decoded.actions.iter().map(|action| action.log_index).collect();
+        assert_eq!(log_indexes, vec![None, None, Some(7)]);
+        let positions: Vec<_> = decoded.actions.iter().map(|action| action.output_position).collect();
+        assert_eq!(positions, vec![Some(0), Some(1), None]);
+    }
+
+    #[test]
+    fn test_decode_unknown_action_type() {
+        let result = decode_actions(&fixture(UNKNOWN_ACTION_TYPE));
+        assert!(matches!(result, Err(ConsumerError::InvalidActionType(_))));
+    }
+
+    #[test]
+    fn test_decode_unknown_object_type() {
+        let result = decode_actions(&fixture(UNKNOWN_OBJECT_TYPE));
+        assert!(matches!(result, Err(ConsumerError::InvalidObjectType(_))));
+    }
+
+    #[test]
+    fn test_decode_invalid_sender() {
+        let result = decode_actions(&fixture(INVALID_SENDER));
+        assert!(matches!(result, Err(ConsumerError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_decode_invalid_group_id() {
+        let result = decode_actions(&fixture(INVALID_GROUP));
+        assert!(matches!(result, Err(ConsumerError::InvalidUuid(_))));
+    }
+
+    #[test]
+    fn test_dead_letter_invalid_actions() {
+        let mut actions = Actions::decode(fixture(UPVOTE_ENTITY).as_slice()).unwrap();
+        let invalid = Actions::decode(fixture(UNKNOWN_ACTION_TYPE).as_slice()).unwrap();
+        actions.actions.extend(invalid.actions.clone());
+
+        let decoded = decode_actions_or_dead_letter(&actions.encode_to_vec(), 1_000);
+
+        let mut expected = decode_actions(&fixture(UPVOTE_ENTITY)).unwrap();
+        expected[0].output_position = Some(0);
+        assert_eq!(decoded.actions, expected);
+        assert_eq!(decoded.dead_letters.len(), 1);
+        let dead_letter = &decoded.dead_letters[0];
+        assert_eq!(dead_letter.block_number, 1_000);
+        let mut invalid = invalid;
+        invalid.actions[0].output_position = Some(1);
+        assert_eq!(dead_letter.raw, invalid.encode_to_vec());
+        assert!(dead_letter.error.contains("action_type: 7"));
+    }
+
+    #[test]
+    fn test_dead_letter_invalid_protobuf() {
+        let decoded = decode_actions_or_dead_letter(&[0xff, 0xff, 0xff], 1_000);
+
+        assert!(decoded.actions.is_empty());
+        assert_eq!(
+            decoded.dead_letters,
+            vec![DeadLetter {
+                block_number: 1_000,
+                raw: vec![0xff, 0xff, 0xff],
+                error: decode_actions(&[0xff, 0xff, 0xff]).unwrap_err().to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_encode_action_round_trips() {
+        let mut actions = decode_actions(&fixture(DOWNVOTE_RELATION_THEN_REMOVE)).unwrap();
+        actions[0].log_index = Some(3);
+        actions[1].output_position = Some(4);
+
+        for action in actions {
+            assert_eq!(decode_actions(&encode_action(&action)).unwrap(), vec![action]);
+        }
+    }
+}
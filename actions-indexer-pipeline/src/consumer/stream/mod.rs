@@ -1,3 +1,4 @@
+pub mod decoder;
 pub mod pb;
 pub mod sink;
 pub mod substreams;
@@ -42,5 +42,153 @@ pub struct Action {
     /// Object type identifier
     #[prost(uint64, tag="11")]
     pub object_type: u64,
+    /// Emitting contract (hex-encoded address)
+    #[prost(string, tag="12")]
+    pub contract_address: ::prost::alloc::string::String,
+    /// Position of the log in the block, with tx_hash the action's unique key
+    #[prost(uint32, optional, tag="14")]
+    pub log_index: ::core::option::Option<u32>,
+    /// Position of the action in the block's output. Never emitted by the substream: the
+    /// indexer sets it on the actions of packages without log_index it dead-letters, so
+    /// they keep their key when reprocessed.
+    #[prost(uint32, optional, tag="15")]
+    pub output_position: ::core::option::Option<u32>,
+    /// Metadata decoded per action_type. Unset when the type has no payload schema or
+    /// the metadata doesn't match it, `metadata` keeps the raw bytes either way.
+    #[prost(oneof="action::Payload", tags="13")]
+    pub payload: ::core::option::Option<action::Payload>,
+}
+/// Nested message and enum types in `Action`.
+pub mod action {
+    /// Metadata decoded per action_type. Unset when the type has no payload schema or
+    /// the metadata doesn't match it, `metadata` keeps the raw bytes either way.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Payload {
+        #[prost(message, tag="13")]
+        Vote(super::VoteMetadata),
+    }
+}
+/// Metadata of a vote, action_type 0
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VoteMetadata {
+    #[prost(enumeration="VoteDirection", tag="1")]
+    pub direction: i32,
+}
+/// Logs of the tracked contracts that couldn't be decoded as actions
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecodeFailures {
+    #[prost(message, repeated, tag="1")]
+    pub failures: ::prost::alloc::vec::Vec<DecodeFailure>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecodeFailure {
+    /// Emitting contract (hex-encoded address)
+    #[prost(string, tag="1")]
+    pub contract_address: ::prost::alloc::string::String,
+    /// TxHash (hex-encoded hash)
+    #[prost(string, tag="2")]
+    pub tx_hash: ::prost::alloc::string::String,
+    /// BlockNumber
+    #[prost(uint64, tag="3")]
+    pub block_number: u64,
+    /// Position of the log in the block
+    #[prost(uint32, tag="4")]
+    pub log_index: u32,
+    /// Raw log topics
+    #[prost(bytes="vec", repeated, tag="5")]
+    pub topics: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// Raw log data
+    #[prost(bytes="vec", tag="6")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    /// Why decoding failed
+    #[prost(string, tag="7")]
+    pub reason: ::prost::alloc::string::String,
+}
+/// Action volume of a block, for monitoring
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ActionStats {
+    /// BlockNumber
+    #[prost(uint64, tag="1")]
+    pub block_number: u64,
+    /// BlockTimestamp
+    #[prost(uint64, tag="2")]
+    pub block_timestamp: u64,
+    /// Actions decoded in the block
+    #[prost(uint64, tag="3")]
+    pub total_actions: u64,
+    /// Distinct senders of those actions
+    #[prost(uint64, tag="4")]
+    pub unique_senders: u64,
+    #[prost(message, repeated, tag="5")]
+    pub counts: ::prost::alloc::vec::Vec<ActionTypeCount>,
+}
+/// Actions of one action type on one object type, ordered by action_type then object_type
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ActionTypeCount {
+    #[prost(uint64, tag="1")]
+    pub action_type: u64,
+    #[prost(uint64, tag="2")]
+    pub object_type: u64,
+    #[prost(uint64, tag="3")]
+    pub count: u64,
+}
+/// Actions of a block bucketed by group
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupedActions {
+    /// In the order the groups first appear in the block
+    #[prost(message, repeated, tag="1")]
+    pub groups: ::prost::alloc::vec::Vec<GroupActions>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupActions {
+    /// GroupId (UUID string)
+    #[prost(string, tag="1")]
+    pub group_id: ::prost::alloc::string::String,
+    /// In block order
+    #[prost(message, repeated, tag="2")]
+    pub actions: ::prost::alloc::vec::Vec<Action>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum VoteDirection {
+    Unspecified = 0,
+    /// Metadata byte 0
+    Up = 1,
+    /// Metadata byte 1
+    Down = 2,
+    /// Metadata byte 2
+    Remove = 3,
+}
+impl VoteDirection {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            VoteDirection::Unspecified => "VOTE_DIRECTION_UNSPECIFIED",
+            VoteDirection::Up => "VOTE_DIRECTION_UP",
+            VoteDirection::Down => "VOTE_DIRECTION_DOWN",
+            VoteDirection::Remove => "VOTE_DIRECTION_REMOVE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "VOTE_DIRECTION_UNSPECIFIED" => Some(Self::Unspecified),
+            "VOTE_DIRECTION_UP" => Some(Self::Up),
+            "VOTE_DIRECTION_DOWN" => Some(Self::Down),
+            "VOTE_DIRECTION_REMOVE" => Some(Self::Remove),
+            _ => None,
+        }
+    }
 }
 // @@protoc_insertion_point(module)
@@ -4,18 +4,16 @@ use regex::Regex;
 use semver::Version;
 use lazy_static::lazy_static;
 
-use actions_indexer_shared::types::{ActionRaw, ActionType, ObjectType};
-
 use super::pb::sf::substreams::rpc::v2::{BlockScopedData, BlockUndoSignal};
 use super::pb::sf::substreams::v1::Package;
 use super::pb::sf::substreams::v1::module::input::{Input, Params};
-use super::pb::sf::actions::v1::{Action, Actions};
+use super::decoder::{decode_block_scoped_data, DecodedActions};
 use super::substreams::SubstreamsEndpoint;
 use super::substreams_stream::{BlockResponse, SubstreamsStream};
 use prost::Message;
 use std::sync::Arc;
 use crate::errors::ConsumerError;
-use crate::consumer::{ConsumeActionsStream, StreamMessage, BlockDataMessage};
+use crate::consumer::{ConsumeActionsStream, StreamMessage, BlockDataMessage, StartMode};
 
 lazy_static! {
     static ref MODULE_NAME_REGEXP: Regex = Regex::new(r"^([a-zA-Z][a-zA-Z0-9_-]{0,63})$").unwrap();
@@ -57,26 +55,27 @@ impl SubstreamsStreamProvider {
         }
     }
 
-    pub fn process_block_scoped_data(&self, data: &BlockScopedData) -> Result<Vec<ActionRaw>, Error> {
+    /// Reads the package and applies the params and block range to it, without streaming.
+    ///
+    /// Meant to be called at startup, so a package that can't stream the configured
+    /// params fails before the indexer starts instead of on its first stream.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `ConsumerError` if the package can't be read,
+    /// doesn't take the params or has no valid block range.
+    pub async fn check_package(&self) -> Result<(), ConsumerError> {
+        let package = read_package(&self.package_file, self.params.clone()).await.map_err(|e| ConsumerError::ReadingPackage(e.to_string()))?;
+        read_block_range(&package, &self.module_name, self.block_range.clone()).map_err(|e| ConsumerError::ReadingBlockRange(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn process_block_scoped_data(&self, data: &BlockScopedData) -> Result<DecodedActions, Error> {
         let now = chrono::Utc::now();
         let block_number = data.clock.as_ref().unwrap().number;
         println!("{} - Processing block {}", now.to_rfc3339(), block_number);
 
-        let output = data.output
-            .as_ref()
-            .ok_or_else(|| ConsumerError::MissingField("output".to_string()))?
-            .map_output
-            .as_ref()
-            .ok_or_else(|| ConsumerError::MissingField("map_output".to_string()))?;
-        let actions = Actions::decode(output.value.as_slice())
-            .map_err(|e| ConsumerError::DecodingActions(e.to_string()))?;
-        let raw_actions = actions
-            .actions
-            .iter()
-            .map(|action| ActionRaw::try_from(action))
-            .collect::<Result<Vec<ActionRaw>, ConsumerError>>()?;
-
-        Ok(raw_actions)
+        Ok(decode_block_scoped_data(data)?)
     }
     
     pub fn process_block_undo_signal(&self, _undo_signal: &BlockUndoSignal) -> Result<(), anyhow::Error> {
@@ -114,9 +113,10 @@ impl SubstreamsStreamProvider {
 
 #[async_trait::async_trait]
 impl ConsumeActionsStream for SubstreamsStreamProvider {
-    async fn stream_events(&self, sender: tokio::sync::mpsc::Sender<StreamMessage>, cursor: Option<String>) -> Result<(), ConsumerError> {
+    async fn stream_events(&self, sender: tokio::sync::mpsc::Sender<StreamMessage>, cursor: Option<String>, start_mode: &StartMode) -> Result<(), ConsumerError> {
         let package = read_package(&self.package_file, self.params.clone()).await.map_err(|e| ConsumerError::ReadingPackage(e.to_string()))?;
         let block_range = read_block_range(&package, &self.module_name, self.block_range.clone()).map_err(|e| ConsumerError::ReadingBlockRange(e.to_string()))?;
+        let (cursor, block_range) = apply_start_mode(start_mode, cursor, block_range);
 
         let endpoint =
             Arc::new(SubstreamsEndpoint::new(&self.endpoint_url, self.token.clone()).await.map_err(|e| ConsumerError::ReadingEndpoint(e.to_string()))?);
@@ -137,11 +137,13 @@ impl ConsumeActionsStream for SubstreamsStreamProvider {
                     break;
                 }
                 Some(Ok(BlockResponse::New(data))) => {
-                    let actions = self.process_block_scoped_data(&data).map_err(|e| ConsumerError::ProcessingBlockScopedData(e.to_string()))?;
+                    let decoded = self.process_block_scoped_data(&data).map_err(|e| ConsumerError::ProcessingBlockScopedData(e.to_string()))?;
                     sender.send(StreamMessage::BlockData(BlockDataMessage {
-                        actions,
+                        actions: decoded.actions,
+                        dead_letters: decoded.dead_letters,
                         cursor: data.cursor,
                         block_number: data.clock.unwrap().number as i64,
+                        final_block_number: data.final_block_height,
                     })).await.map_err(|e| ConsumerError::ChannelSend(e.to_string()))?;
                 }
                 Some(Ok(BlockResponse::Undo(undo_signal))) => {
@@ -162,6 +164,21 @@ impl ConsumeActionsStream for SubstreamsStreamProvider {
 }
 
 
+/// Applies a start mode to the configured block range.
+///
+/// Returns the cursor to resume from, if any, and the start and stop blocks to stream.
+/// Only `StartMode::FromCursor` keeps the cursor. `StartMode::FromTail` starts at block
+/// `-1`, which Substreams resolves relative to the chain head.
+fn apply_start_mode(start_mode: &StartMode, cursor: Option<String>, block_range: (i64, u64)) -> (Option<String>, (i64, u64)) {
+    let (_, stop_block) = block_range;
+    match start_mode {
+        StartMode::FromCursor => (cursor, block_range),
+        StartMode::FromBlock(start_block) => (None, (*start_block as i64, stop_block)),
+        StartMode::FromTail => (None, (-1, stop_block)),
+        StartMode::Replay(range) => (None, (range.start as i64, range.end)),
+    }
+}
+
 fn read_block_range(
     pkg: &Package,
     module_name: &str,
@@ -250,9 +267,30 @@ async fn read_package(input: &str, params: Vec<Param>) -> Result<Package, Error>
                     .iter_mut()
                     .find(|m| m.name == param.module_name)
                 {
-                    module.inputs[0].input = Some(Input::Params(Params {
-                        value: param.expression,
-                    }));
+                    // Params are always the first input. Overwriting another input would
+                    // silently feed the module the wrong data, e.g. with a stale package.
+                    // A package built before the module took params only runs when its
+                    // built-in settings are the ones asked for.
+                    match module.inputs.first_mut() {
+                        Some(input) if matches!(input.input, Some(Input::Params(_))) => {
+                            input.input = Some(Input::Params(Params {
+                                value: param.expression,
+                            }));
+                        }
+                        _ if param.built_in.as_ref() == Some(&param.expression) => {
+                            println!(
+                                "Module '{}' doesn't take params, its built-in '{}' applies",
+                                param.module_name, param.expression
+                            );
+                        }
+                        _ => {
+                            return Err(format_err!(
+                                "module '{}' doesn't take params, so '{}' can't be applied",
+                                param.module_name,
+                                param.expression
+                            ));
+                        }
+                    }
                 }
             }
         }
@@ -285,6 +323,7 @@ fn read_params_flag(input: &str) -> anyhow::Result<Vec<Param>> {
             Some((module_name, expression)) => params.push(Param {
                 module_name: module_name.trim().to_string(),
                 expression: expression.trim().to_string(),
+                built_in: None,
             }),
             None => {
                 return Err(anyhow!(
@@ -349,40 +388,23 @@ fn is_valid_version(version: &str) -> bool {
 pub struct Param {
     pub module_name: String,
     pub expression: String,
+    /// Params a package built before the module took them applies, if known.
+    /// Such a package is only streamed when they match `expression`.
+    pub built_in: Option<String>,
 }
 
-impl TryFrom<&Action> for ActionRaw {
-    type Error = ConsumerError;
-
-    fn try_from(action: &Action) -> Result<Self, Self::Error> {
-        Ok(ActionRaw {
-            sender: action.sender.parse()
-                .map_err(|e| ConsumerError::InvalidAddress(format!("sender: {}", e)))?,
-            action_type: match action.action_type {
-                0 => ActionType::Vote,
-                _ => return Err(ConsumerError::InvalidActionType(format!("action_type: {}", action.action_type))),
-            },
-            action_version: action.action_version,
-            space_pov: action.space_pov.parse()
-                .map_err(|e| ConsumerError::InvalidAddress(format!("space_pov: {}", e)))?,
-            object_id: action.object_id.parse()
-                .map_err(|e| ConsumerError::InvalidUuid(format!("entity: {}", e)))?,
-            group_id: if action.group_id.is_some() {
-                Some(action.group_id.as_ref().unwrap().parse()
-                    .map_err(|e| ConsumerError::InvalidUuid(format!("group_id: {}", e)))?)
-            } else {
-                None
-            },
-            metadata: action.metadata.as_ref().map(|metadata| metadata.to_vec().into()),
-            block_number: action.block_number.into(),
-            block_timestamp: action.block_timestamp.into(),
-            tx_hash: action.tx_hash.parse()
-                .map_err(|e| ConsumerError::InvalidTxHash(format!("tx_hash: {}", e)))?,
-            object_type: match action.object_type {
-                0 => ObjectType::Entity,
-                1 => ObjectType::Relation,
-                _ => return Err(ConsumerError::InvalidObjectType(format!("object_type: {}", action.object_type))),
-            },
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_start_mode() {
+        let cursor = Some("cursor".to_string());
+        let block_range = (100, 500);
+
+        assert_eq!(apply_start_mode(&StartMode::FromCursor, cursor.clone(), block_range), (cursor.clone(), (100, 500)));
+        assert_eq!(apply_start_mode(&StartMode::FromBlock(300), cursor.clone(), block_range), (None, (300, 500)));
+        assert_eq!(apply_start_mode(&StartMode::FromTail, cursor.clone(), block_range), (None, (-1, 500)));
+        assert_eq!(apply_start_mode(&StartMode::Replay(200..250), cursor, block_range), (None, (200, 250)));
     }
-}
\ No newline at end of file
+}
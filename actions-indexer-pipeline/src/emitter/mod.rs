@@ -0,0 +1,107 @@
+//! Publication of the vote count changes of the persisted actions.
+//!
+//! Once a batch is persisted, the orchestrator hands the new totals of every vote count
+//! its changesets changed to a [`VotesCountEmitter`], as `VotesCountChanged` messages of
+//! `hermes-schema`. An emitter publishing them to a Kafka topic lets search indexes
+//! update their ranking incrementally instead of polling the vote counts.
+use actions_indexer_shared::types::{Changeset, ObjectType};
+use async_trait::async_trait;
+use hermes_schema::pb::actions::{ObjectType as ProtoObjectType, VotesCountChanged};
+use crate::errors::EmitterError;
+
+/// `VotesCountEmitter` publishes the vote count changes of the persisted changesets.
+///
+/// Changes are given in the order their blocks were persisted, so an emitter keeping
+/// that order per object lets consumers apply them as they come.
+#[async_trait]
+pub trait VotesCountEmitter: Send + Sync {
+    /// Publishes the vote count changes of a persisted batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes` - The new totals of the changed vote counts, oldest block first.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or an `EmitterError` if a change couldn't be
+    /// published.
+    async fn emit(&self, changes: &[VotesCountChanged]) -> Result<(), EmitterError>;
+}
+
+/// Turns the vote counts of changesets into `VotesCountChanged` messages.
+///
+/// # Arguments
+///
+/// * `changesets` - The persisted changesets, in block order.
+///
+/// # Returns
+///
+/// One `VotesCountChanged` per vote count of each changeset, carrying the block of
+/// its changeset.
+pub fn votes_count_changes(changesets: &[Changeset<'_>]) -> Vec<VotesCountChanged> {
+    changesets
+        .iter()
+        .flat_map(|changeset| {
+            changeset.votes_count.iter().map(|votes_count| VotesCountChanged {
+                object_id: votes_count.object_id.as_bytes().to_vec(),
+                space_id: votes_count.space_id.as_bytes().to_vec(),
+                object_type: match votes_count.object_type {
+                    ObjectType::Entity => ProtoObjectType::Entity,
+                    ObjectType::Relation => ProtoObjectType::Relation,
+                } as i32,
+                upvotes: votes_count.upvotes,
+                downvotes: votes_count.downvotes,
+                block_number: changeset.block_number,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions_indexer_shared::types::VotesCount;
+    use uuid::uuid;
+
+    fn changeset(block_number: u64, votes_count: &[VotesCount]) -> Changeset<'_> {
+        Changeset {
+            block_number,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: &[],
+            votes_count,
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        }
+    }
+
+    #[test]
+    fn test_votes_count_changes() {
+        let object_id = uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5");
+        let space_id = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+        let votes_count = |object_type, upvotes, downvotes| VotesCount {
+            object_id,
+            space_id,
+            object_type,
+            upvotes,
+            downvotes,
+        };
+        let first = [votes_count(ObjectType::Entity, 1, 0), votes_count(ObjectType::Relation, 0, 1)];
+        let second = [votes_count(ObjectType::Entity, 2, 0)];
+
+        let changes = votes_count_changes(&[changeset(10, &first), changeset(11, &[]), changeset(12, &second)]);
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0], VotesCountChanged {
+            object_id: object_id.as_bytes().to_vec(),
+            space_id: space_id.as_bytes().to_vec(),
+            object_type: ProtoObjectType::Entity as i32,
+            upvotes: 1,
+            downvotes: 0,
+            block_number: 10,
+        });
+        assert_eq!(changes[1].object_type(), ProtoObjectType::Relation);
+        assert_eq!(changes[1].block_number, 10);
+        assert_eq!((changes[2].upvotes, changes[2].block_number), (2, 12));
+    }
+}
@@ -0,0 +1,24 @@
+//! Error types for the aggregation module of the Actions Indexer Pipeline.
+//! Defines specific errors that can occur while maintaining the activity
+//! counters of persisted actions.
+use thiserror::Error;
+use crate::errors::class::{Classify, ErrorClass};
+use actions_indexer_repository::ActivityRepositoryError;
+
+/// Represents errors that can occur within the activity aggregator.
+///
+/// This enum consolidates various error conditions specific to the aggregation
+/// process, including errors propagated from the activity repository.
+#[derive(Debug, Error)]
+pub enum AggregationError {
+    #[error("Activity repository error: {0}")]
+    ActivityRepository(#[from] ActivityRepositoryError),
+}
+
+impl Classify for AggregationError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            AggregationError::ActivityRepository(e) => e.class(),
+        }
+    }
+}
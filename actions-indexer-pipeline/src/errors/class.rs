@@ -0,0 +1,129 @@
+//! Classification of the errors of the Actions Indexer Pipeline.
+//! Tells the transient failures worth retrying, such as a database timeout or a
+//! dropped connection, from the fatal ones that would fail again, such as a schema
+//! mismatch or an invalid package.
+use actions_indexer_repository::{ActionsRepositoryError, ActivityRepositoryError, BackfillRepositoryError, CursorRepositoryError};
+
+/// Whether an error is worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The operation may succeed if retried, e.g. after a timeout or a lost connection.
+    Retryable,
+    /// The operation would fail again, e.g. because of a schema mismatch or invalid data.
+    Fatal,
+}
+
+/// Classifies an error as retryable or fatal.
+pub trait Classify {
+    /// The class of the error.
+    fn class(&self) -> ErrorClass;
+
+    /// Whether the operation that failed with this error may succeed if retried.
+    fn is_retryable(&self) -> bool {
+        self.class() == ErrorClass::Retryable
+    }
+}
+
+/// PostgreSQL SQLSTATE classes and codes of transient failures: connection exceptions,
+/// insufficient resources, serialization failures, deadlocks, lock and statement
+/// timeouts, and a server shutting down or starting up.
+const RETRYABLE_SQLSTATE_CLASSES: [&str; 2] = ["08", "53"];
+const RETRYABLE_SQLSTATES: [&str; 6] = ["40001", "40P01", "55P03", "57014", "57P01", "57P03"];
+/// SQLite primary result codes of transient failures: `SQLITE_BUSY` and `SQLITE_LOCKED`.
+const RETRYABLE_SQLITE_CODES: [i32; 2] = [5, 6];
+
+impl Classify for sqlx::Error {
+    fn class(&self) -> ErrorClass {
+        match self {
+            sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut => ErrorClass::Retryable,
+            sqlx::Error::Database(e) => match e.code() {
+                Some(code) if is_retryable_code(&code) => ErrorClass::Retryable,
+                _ => ErrorClass::Fatal,
+            },
+            _ => ErrorClass::Fatal,
+        }
+    }
+}
+
+/// Whether a database error code, a SQLSTATE or an extended SQLite result code,
+/// is a transient failure.
+fn is_retryable_code(code: &str) -> bool {
+    match code.parse::<i32>() {
+        Ok(sqlite_code) if code.len() != 5 => RETRYABLE_SQLITE_CODES.contains(&(sqlite_code & 0xff)),
+        _ => RETRYABLE_SQLSTATE_CLASSES.contains(&&code[..code.len().min(2)]) || RETRYABLE_SQLSTATES.contains(&code),
+    }
+}
+
+impl Classify for ActionsRepositoryError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            ActionsRepositoryError::DatabaseError(e) => e.class(),
+            _ => ErrorClass::Fatal,
+        }
+    }
+}
+
+impl Classify for CursorRepositoryError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            CursorRepositoryError::DatabaseError(e) => e.class(),
+        }
+    }
+}
+
+impl Classify for ActivityRepositoryError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            ActivityRepositoryError::DatabaseError(e) => e.class(),
+            _ => ErrorClass::Fatal,
+        }
+    }
+}
+
+impl Classify for BackfillRepositoryError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            BackfillRepositoryError::DatabaseError(e) => e.class(),
+            _ => ErrorClass::Fatal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlx_error_class() {
+        let io = sqlx::Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+
+        assert_eq!(io.class(), ErrorClass::Retryable);
+        assert_eq!(sqlx::Error::PoolTimedOut.class(), ErrorClass::Retryable);
+        assert_eq!(sqlx::Error::RowNotFound.class(), ErrorClass::Fatal);
+        assert_eq!(sqlx::Error::ColumnNotFound("upvotes".to_string()).class(), ErrorClass::Fatal);
+    }
+
+    #[test]
+    fn test_is_retryable_code() {
+        // Connection failure, deadlock and statement timeout
+        assert!(is_retryable_code("08006"));
+        assert!(is_retryable_code("40P01"));
+        assert!(is_retryable_code("57014"));
+        // Undefined table and column, unique violation
+        assert!(!is_retryable_code("42P01"));
+        assert!(!is_retryable_code("42703"));
+        assert!(!is_retryable_code("23505"));
+        // SQLITE_BUSY, SQLITE_BUSY_SNAPSHOT and SQLITE_CONSTRAINT_UNIQUE
+        assert!(is_retryable_code("5"));
+        assert!(is_retryable_code("517"));
+        assert!(!is_retryable_code("2067"));
+    }
+
+    #[test]
+    fn test_repository_error_class() {
+        assert!(ActionsRepositoryError::DatabaseError(sqlx::Error::PoolTimedOut).is_retryable());
+        assert!(!ActionsRepositoryError::InvalidVoteType(7).is_retryable());
+        assert!(CursorRepositoryError::DatabaseError(sqlx::Error::PoolTimedOut).is_retryable());
+        assert!(!ActivityRepositoryError::InvalidTimestamp(0).is_retryable());
+    }
+}
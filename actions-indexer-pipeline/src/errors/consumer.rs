@@ -1,6 +1,7 @@
 //! Error types for the consumer module of the Actions Indexer Pipeline.
 //! Defines specific errors that can occur during the consumption of action events.
 use thiserror::Error;
+use crate::errors::class::{Classify, ErrorClass};
 
 /// Represents errors that can occur within the action consumer.
 ///
@@ -41,3 +42,15 @@ pub enum ConsumerError {
     #[error("Invalid object type: {0}")]
     InvalidObjectType(String),
 }
+
+impl Classify for ConsumerError {
+    /// Stream failures, such as a dropped connection, and a cursor that couldn't be
+    /// loaded are retryable. A package, block range or endpoint that can't be read,
+    /// a closed channel and data that can't be decoded would fail the same way again.
+    fn class(&self) -> ErrorClass {
+        match self {
+            ConsumerError::StreamError(_) | ConsumerError::StreamingError(_) | ConsumerError::LoadingCursor(_) => ErrorClass::Retryable,
+            _ => ErrorClass::Fatal,
+        }
+    }
+}
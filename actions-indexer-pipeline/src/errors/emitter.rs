@@ -0,0 +1,20 @@
+//! Error types for the emitter module of the Actions Indexer Pipeline.
+//! Defines specific errors that can occur while publishing the vote count
+//! changes of persisted actions.
+use thiserror::Error;
+use crate::errors::class::{Classify, ErrorClass};
+
+/// Represents errors that can occur within a vote count emitter.
+#[derive(Debug, Error)]
+pub enum EmitterError {
+    #[error("Failed to publish vote count changes: {0}")]
+    Publish(String),
+}
+
+impl Classify for EmitterError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            EmitterError::Publish(_) => ErrorClass::Retryable,
+        }
+    }
+}
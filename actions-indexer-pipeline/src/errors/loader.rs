@@ -2,6 +2,7 @@
 //! Defines specific errors that can occur during the loading and persistence
 //! of processed action data.
 use thiserror::Error;
+use crate::errors::class::{Classify, ErrorClass};
 use actions_indexer_repository::ActionsRepositoryError;
 use actions_indexer_repository::CursorRepositoryError;
 
@@ -16,3 +17,12 @@ pub enum LoaderError {
     #[error("Cursor repository error: {0}")]
     CursorRepository(#[from] CursorRepositoryError),
 }
+
+impl Classify for LoaderError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            LoaderError::ActionsRepository(e) => e.class(),
+            LoaderError::CursorRepository(e) => e.class(),
+        }
+    }
+}
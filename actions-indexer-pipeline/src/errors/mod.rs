@@ -1,12 +1,19 @@
 //! Error types for the Actions Indexer Pipeline.
 //! Consolidates and re-exports error types from various pipeline components
-//! such as the processor, orchestrator, loader, and consumer.
+//! such as the processor, orchestrator, loader, consumer, aggregator and emitter, along
+//! with their classification as retryable or fatal.
 mod processor;
 mod orchestrator;
 mod loader;
 mod consumer;
+mod aggregation;
+mod emitter;
+mod class;
 
 pub use processor::ProcessorError;
 pub use orchestrator::OrchestratorError;
 pub use loader::LoaderError;
-pub use consumer::ConsumerError;
\ No newline at end of file
+pub use consumer::ConsumerError;
+pub use aggregation::AggregationError;
+pub use emitter::EmitterError;
+pub use class::{Classify, ErrorClass};
@@ -1,6 +1,7 @@
 //! Error types for the orchestrator module of the Actions Indexer Pipeline.
 //! Defines specific errors that can occur during the orchestration process.
 use thiserror::Error;
+use crate::errors::class::{Classify, ErrorClass};
 use crate::errors::consumer::ConsumerError;
 use actions_indexer_repository::errors::ActionsRepositoryError;
 use actions_indexer_repository::errors::CursorRepositoryError;
@@ -20,4 +21,15 @@ pub enum OrchestratorError {
     CursorRepository(#[from] CursorRepositoryError),
     #[error("Loader error: {0}")]
     Loader(#[from] LoaderError),
-}
\ No newline at end of file
+}
+
+impl Classify for OrchestratorError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            OrchestratorError::Consumer(e) => e.class(),
+            OrchestratorError::ActionsRepository(e) => e.class(),
+            OrchestratorError::CursorRepository(e) => e.class(),
+            OrchestratorError::Loader(e) => e.class(),
+        }
+    }
+}
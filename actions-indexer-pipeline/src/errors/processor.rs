@@ -1,6 +1,8 @@
 //! Error types for the processor module of the Actions Indexer Pipeline.
 //! Defines specific errors that can occur during the processing of action events.
 use thiserror::Error;
+use crate::errors::class::{Classify, ErrorClass};
+use actions_indexer_shared::types::{ActionType, ActionVersion, ObjectType};
 
 /// Represents errors that can occur within the action processor.
 ///
@@ -10,4 +12,19 @@ use thiserror::Error;
 pub enum ProcessorError {
     #[error("Invalid vote")]
     InvalidVote,
+    #[error("Invalid flag")]
+    InvalidFlag,
+    #[error("Invalid edit published")]
+    InvalidEditPublished,
+    #[error("Invalid membership")]
+    InvalidMembership,
+    #[error("No handler for version {0} of {1:?} actions on {2:?} objects")]
+    MissingHandler(ActionVersion, ActionType, ObjectType),
+}
+
+impl Classify for ProcessorError {
+    /// Processing is deterministic, so every processor error is fatal.
+    fn class(&self) -> ErrorClass {
+        ErrorClass::Fatal
+    }
 }
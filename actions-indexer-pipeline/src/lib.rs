@@ -2,10 +2,15 @@
 //! This crate defines the core traits and modules for processing actions within
 //! the indexer.
 //! It includes modules for consuming, loading, processing, and orchestrating
-//! actions, along with error handling.
+//! actions, along with error handling, retries, metrics, activity aggregation and the
+//! publication of vote count changes.
+pub mod aggregation;
 pub mod consumer;
+pub mod emitter;
 pub mod loader;
+pub mod metrics;
 pub mod processor;
 pub mod orchestrator;
+pub mod retry;
 
 pub mod errors;
\ No newline at end of file
@@ -3,7 +3,7 @@
 //! It acts as an interface between the processing pipeline and the data storage.
 pub use actions_indexer_repository::{ActionsRepository, CursorRepository};
 pub use actions_indexer_repository::PostgresActionsRepository;
-pub use actions_indexer_shared::types::Changeset;
+pub use actions_indexer_shared::types::{Changeset, DeadLetter};
 pub use crate::errors::LoaderError;
 use std::sync::Arc;
 
@@ -50,6 +50,94 @@ impl ActionsLoader {
         Ok(())
     }
 
+    /// Persists a batch of `Changeset`s to the actions repository.
+    ///
+    /// The changesets are written in a single transaction, so either the whole
+    /// batch is persisted or none of it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - The changesets to be persisted, ordered by block number.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `LoaderError` if the persistence fails.
+    pub async fn persist_changesets(&self, changesets: &[Changeset<'_>]) -> Result<(), LoaderError> {
+        self.actions_repository.persist_changesets(changesets).await?;
+        Ok(())
+    }
+
+    /// Persists a batch of `Changeset`s along with the cursor of its last block.
+    ///
+    /// The changesets and the cursor are written in a single transaction, so the
+    /// saved cursor always matches the persisted data.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - The changesets to be persisted, ordered by block number.
+    /// * `id` - The id of the indexer.
+    /// * `cursor` - The cursor of the last block of the batch.
+    /// * `block_number` - The block number of the cursor.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `LoaderError` if the persistence fails.
+    pub async fn persist_changesets_with_cursor(&self, changesets: &[Changeset<'_>], id: &str, cursor: &str, block_number: i64) -> Result<(), LoaderError> {
+        self.actions_repository.persist_changesets_with_cursor(changesets, id, cursor, block_number).await?;
+        Ok(())
+    }
+
+    /// Rolls back the changesets of the blocks after `block_number`.
+    ///
+    /// The user votes, vote counts, leaderboards and activity counters are restored
+    /// from the changeset journal and the actions of the undone blocks are removed, so the blocks of the new fork
+    /// can be persisted in their place.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - The last valid block, kept along with the blocks before it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `LoaderError` if the rollback fails.
+    pub async fn rollback_to(&self, block_number: u64) -> Result<(), LoaderError> {
+        self.actions_repository.rollback_to(block_number).await?;
+        Ok(())
+    }
+
+    /// Rolls back the changesets of the blocks after `block_number` and saves its cursor.
+    ///
+    /// The rollback and the cursor are written in a single transaction, so a restart
+    /// resumes from the last valid block instead of the undone fork.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - The last valid block, kept along with the blocks before it.
+    /// * `id` - The id of the indexer.
+    /// * `cursor` - The cursor of the last valid block.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `LoaderError` if the rollback fails.
+    pub async fn rollback_to_with_cursor(&self, block_number: u64, id: &str, cursor: &str) -> Result<(), LoaderError> {
+        self.actions_repository.rollback_to_with_cursor(block_number, id, cursor).await?;
+        Ok(())
+    }
+
+    /// Persists the events that couldn't be decoded or processed to the actions repository.
+    ///
+    /// # Arguments
+    ///
+    /// * `dead_letters` - The failed events, with their raw bytes, error and block.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or a `LoaderError` if the persistence fails.
+    pub async fn persist_dead_letters(&self, dead_letters: &[DeadLetter]) -> Result<(), LoaderError> {
+        self.actions_repository.insert_dead_letters(dead_letters).await?;
+        Ok(())
+    }
+
     /// Persists the latest processed block number to the block repository.
     ///
     /// This asynchronous method takes a reference to a `block_number` and delegates
@@ -0,0 +1,472 @@
+//! Pipeline metrics: throughput, stage latencies, indexing lag and errors.
+//!
+//! The orchestrator records into a shared [`PipelineMetrics`] as it runs, and
+//! [`render`] writes them in the Prometheus text format:
+//!
+//! - `actions_indexer_actions_processed_total`, `actions_indexer_blocks_processed_total`:
+//!   actions and blocks persisted since startup. Their `rate()` is the throughput.
+//! - `actions_indexer_stage_duration_seconds` per stage: how long the orchestrator
+//!   waited for the consumer to deliver each block (`consume`), how long each batch
+//!   took to be processed into changesets (`process`) and to be written (`load`).
+//! - `actions_indexer_db_transaction_duration_seconds`: the transaction writing the
+//!   changesets and cursor of a batch.
+//! - `actions_indexer_head_block`, `actions_indexer_cursor_block`,
+//!   `actions_indexer_cursor_lag_blocks`: how far the persisted cursor trails the stream.
+//! - `actions_indexer_errors_total` per kind: stream errors, batches that failed to
+//!   persist, dead-lettered events and batches whose activity counters failed to update.
+//! - `actions_indexer_retries_total` per stage: operations retried after a retryable
+//!   error, such as consumer restarts and batches persisted again.
+//! - `actions_indexer_filtered_events_total` per reason: events the consumer's allow and
+//!   deny lists dropped before processing.
+
+use std::fmt::{Display, Write};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds of the latency histogram buckets, in milliseconds.
+/// Latencies longer than the last bound land in an overflow bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 12] = [1, 5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+
+/// Content type of the Prometheus text format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// A stage of the pipeline whose latency is recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Consume,
+    Process,
+    Load,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 3] = [Stage::Consume, Stage::Process, Stage::Load];
+
+    /// The `stage` label of the stage.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Consume => "consume",
+            Stage::Process => "process",
+            Stage::Load => "load",
+        }
+    }
+}
+
+/// A kind of error counted by the pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The stream reported an error.
+    Stream,
+    /// A batch couldn't be persisted.
+    Persist,
+    /// An event couldn't be decoded or processed and was dead-lettered.
+    DeadLetter,
+    /// The activity counters of a persisted batch couldn't be updated.
+    Activity,
+    /// The vote count changes of a persisted batch couldn't be published.
+    Emit,
+}
+
+impl ErrorKind {
+    pub const ALL: [ErrorKind; 5] = [ErrorKind::Stream, ErrorKind::Persist, ErrorKind::DeadLetter, ErrorKind::Activity, ErrorKind::Emit];
+
+    /// The `kind` label of the error.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Stream => "stream",
+            ErrorKind::Persist => "persist",
+            ErrorKind::DeadLetter => "dead_letter",
+            ErrorKind::Activity => "activity",
+            ErrorKind::Emit => "emit",
+        }
+    }
+}
+
+/// Why the consumer filtered out an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// Its sender is denied or not allowed.
+    Sender,
+    /// Its space is denied or not allowed.
+    Space,
+    /// Its group is denied or not allowed.
+    Group,
+}
+
+impl FilterReason {
+    pub const ALL: [FilterReason; 3] = [FilterReason::Sender, FilterReason::Space, FilterReason::Group];
+
+    /// The `reason` label of the filtered event.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterReason::Sender => "sender",
+            FilterReason::Space => "space",
+            FilterReason::Group => "group",
+        }
+    }
+}
+
+/// Latency histogram using [`LATENCY_BUCKETS_MS`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Count per bucket, with the overflow bucket last.
+    pub buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    pub count: u64,
+    pub sum_ms: u64,
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        let ms = latency.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += ms;
+    }
+}
+
+/// Metrics shared by the orchestrator and the metrics exporter.
+#[derive(Debug, Default)]
+pub struct PipelineMetrics {
+    actions_processed: AtomicU64,
+    blocks_processed: AtomicU64,
+    stages: Mutex<[LatencyHistogram; Stage::ALL.len()]>,
+    db_transactions: Mutex<LatencyHistogram>,
+    head_block: Mutex<Option<i64>>,
+    cursor_block: Mutex<Option<i64>>,
+    errors: [AtomicU64; ErrorKind::ALL.len()],
+    filtered: [AtomicU64; FilterReason::ALL.len()],
+    retries: [AtomicU64; Stage::ALL.len()],
+}
+
+impl PipelineMetrics {
+    /// Record how long a stage took.
+    pub fn record_stage(&self, stage: Stage, latency: Duration) {
+        self.stages.lock().unwrap()[stage as usize].record(latency);
+    }
+
+    /// Snapshot of the latency histogram of a stage.
+    pub fn stage(&self, stage: Stage) -> LatencyHistogram {
+        self.stages.lock().unwrap()[stage as usize].clone()
+    }
+
+    /// Record how long the transaction persisting a batch took.
+    pub fn record_db_transaction(&self, latency: Duration) {
+        self.db_transactions.lock().unwrap().record(latency);
+    }
+
+    /// Snapshot of the database transaction latency histogram.
+    pub fn db_transactions(&self) -> LatencyHistogram {
+        self.db_transactions.lock().unwrap().clone()
+    }
+
+    /// Record a block delivered by the stream.
+    pub fn record_head_block(&self, block_number: i64) {
+        *self.head_block.lock().unwrap() = Some(block_number);
+    }
+
+    /// Last block delivered by the stream.
+    pub fn head_block(&self) -> Option<i64> {
+        *self.head_block.lock().unwrap()
+    }
+
+    /// Record a persisted batch, along with the block its cursor was saved at, if it was.
+    pub fn record_persisted(&self, blocks: usize, actions: usize, cursor_block: Option<i64>) {
+        self.blocks_processed.fetch_add(blocks as u64, Ordering::Relaxed);
+        self.actions_processed.fetch_add(actions as u64, Ordering::Relaxed);
+        if cursor_block.is_some() {
+            *self.cursor_block.lock().unwrap() = cursor_block;
+        }
+    }
+
+    /// Block the cursor was last persisted at.
+    pub fn cursor_block(&self) -> Option<i64> {
+        *self.cursor_block.lock().unwrap()
+    }
+
+    /// Blocks between the persisted cursor and the head, or 0 before both are known.
+    pub fn cursor_lag_blocks(&self) -> i64 {
+        match (self.head_block(), self.cursor_block()) {
+            (Some(head_block), Some(cursor_block)) => (head_block - cursor_block).max(0),
+            _ => 0,
+        }
+    }
+
+    /// Number of actions persisted since startup.
+    pub fn actions_processed(&self) -> u64 {
+        self.actions_processed.load(Ordering::Relaxed)
+    }
+
+    /// Number of blocks persisted since startup.
+    pub fn blocks_processed(&self) -> u64 {
+        self.blocks_processed.load(Ordering::Relaxed)
+    }
+
+    /// Record `count` errors of a kind.
+    pub fn record_errors(&self, kind: ErrorKind, count: usize) {
+        self.errors[kind as usize].fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Number of errors of a kind since startup.
+    pub fn errors(&self, kind: ErrorKind) -> u64 {
+        self.errors[kind as usize].load(Ordering::Relaxed)
+    }
+
+    /// Record `count` events filtered out for a reason.
+    pub fn record_filtered(&self, reason: FilterReason, count: usize) {
+        self.filtered[reason as usize].fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Number of events filtered out for a reason since startup.
+    pub fn filtered(&self, reason: FilterReason) -> u64 {
+        self.filtered[reason as usize].load(Ordering::Relaxed)
+    }
+
+    /// Record a retry of an operation of a stage.
+    pub fn record_retry(&self, stage: Stage) {
+        self.retries[stage as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of retries of a stage since startup.
+    pub fn retries(&self, stage: Stage) -> u64 {
+        self.retries[stage as usize].load(Ordering::Relaxed)
+    }
+}
+
+/// Render `metrics` in the Prometheus text format.
+pub fn render(metrics: &PipelineMetrics) -> String {
+    let mut out = Exposition::default();
+
+    out.counter(
+        "actions_indexer_actions_processed_total",
+        "Actions persisted since startup.",
+        metrics.actions_processed(),
+    );
+    out.counter(
+        "actions_indexer_blocks_processed_total",
+        "Blocks persisted since startup.",
+        metrics.blocks_processed(),
+    );
+
+    out.header(
+        "actions_indexer_stage_duration_seconds",
+        "histogram",
+        "Time spent in each stage of the pipeline.",
+    );
+    for stage in Stage::ALL {
+        out.histogram(
+            "actions_indexer_stage_duration_seconds",
+            &[("stage", stage.as_str())],
+            &metrics.stage(stage),
+        );
+    }
+
+    out.header(
+        "actions_indexer_db_transaction_duration_seconds",
+        "histogram",
+        "Duration of the transactions persisting a batch and its cursor.",
+    );
+    out.histogram(
+        "actions_indexer_db_transaction_duration_seconds",
+        &[],
+        &metrics.db_transactions(),
+    );
+
+    if let Some(head_block) = metrics.head_block() {
+        out.gauge(
+            "actions_indexer_head_block",
+            "Last block delivered by the stream.",
+            head_block,
+        );
+    }
+    if let Some(cursor_block) = metrics.cursor_block() {
+        out.gauge(
+            "actions_indexer_cursor_block",
+            "Block the cursor was last persisted at.",
+            cursor_block,
+        );
+    }
+    out.gauge(
+        "actions_indexer_cursor_lag_blocks",
+        "Blocks between the persisted cursor and the head.",
+        metrics.cursor_lag_blocks(),
+    );
+
+    out.header(
+        "actions_indexer_errors_total",
+        "counter",
+        "Errors since startup by kind.",
+    );
+    for kind in ErrorKind::ALL {
+        out.sample(
+            "actions_indexer_errors_total",
+            &[("kind", kind.as_str())],
+            metrics.errors(kind),
+        );
+    }
+
+    out.header(
+        "actions_indexer_retries_total",
+        "counter",
+        "Retries after a retryable error since startup by stage.",
+    );
+    for stage in Stage::ALL {
+        out.sample(
+            "actions_indexer_retries_total",
+            &[("stage", stage.as_str())],
+            metrics.retries(stage),
+        );
+    }
+
+    out.header(
+        "actions_indexer_filtered_events_total",
+        "counter",
+        "Events filtered out by the consumer since startup by reason.",
+    );
+    for reason in FilterReason::ALL {
+        out.sample(
+            "actions_indexer_filtered_events_total",
+            &[("reason", reason.as_str())],
+            metrics.filtered(reason),
+        );
+    }
+
+    out.0
+}
+
+fn seconds(ms: u64) -> f64 {
+    ms as f64 / 1_000.0
+}
+
+/// Metric families in the Prometheus text format.
+#[derive(Default)]
+struct Exposition(String);
+
+impl Exposition {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        // Writing to a String can't fail
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{label}=\"{value}\""))
+                .collect();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: impl Display) {
+        self.header(name, "gauge", help);
+        self.sample(name, &[], value);
+    }
+
+    fn counter(&mut self, name: &str, help: &str, value: impl Display) {
+        self.header(name, "counter", help);
+        self.sample(name, &[], value);
+    }
+
+    /// Write the cumulative buckets, sum and count of a histogram.
+    fn histogram(&mut self, name: &str, labels: &[(&str, &str)], histogram: &LatencyHistogram) {
+        let bucket_name = format!("{name}_bucket");
+        let mut cumulative = 0;
+
+        for (bound_ms, count) in LATENCY_BUCKETS_MS.iter().zip(histogram.buckets) {
+            cumulative += count;
+            let le = seconds(*bound_ms).to_string();
+            let mut bucket_labels = labels.to_vec();
+            bucket_labels.push(("le", &le));
+            self.sample(&bucket_name, &bucket_labels, cumulative);
+        }
+        let mut bucket_labels = labels.to_vec();
+        bucket_labels.push(("le", "+Inf"));
+        self.sample(&bucket_name, &bucket_labels, histogram.count);
+        self.sample(&format!("{name}_sum"), labels, seconds(histogram.sum_ms));
+        self.sample(&format!("{name}_count"), labels, histogram.count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram_buckets() {
+        let metrics = PipelineMetrics::default();
+
+        metrics.record_stage(Stage::Process, Duration::from_millis(3));
+        metrics.record_stage(Stage::Process, Duration::from_millis(40));
+        metrics.record_stage(Stage::Process, Duration::from_secs(60));
+
+        let process = metrics.stage(Stage::Process);
+        assert_eq!(process.count, 3);
+        assert_eq!(process.sum_ms, 60_043);
+        assert_eq!(process.buckets[1], 1);
+        assert_eq!(process.buckets[4], 1);
+        assert_eq!(process.buckets[LATENCY_BUCKETS_MS.len()], 1);
+        assert_eq!(metrics.stage(Stage::Load).count, 0);
+    }
+
+    #[test]
+    fn test_cursor_lag_blocks() {
+        let metrics = PipelineMetrics::default();
+        assert_eq!(metrics.cursor_lag_blocks(), 0);
+
+        metrics.record_head_block(105);
+        assert_eq!(metrics.cursor_lag_blocks(), 0);
+
+        metrics.record_persisted(3, 10, Some(99));
+        assert_eq!(metrics.cursor_lag_blocks(), 6);
+        assert_eq!(metrics.blocks_processed(), 3);
+        assert_eq!(metrics.actions_processed(), 10);
+    }
+
+    #[test]
+    fn test_render_exports_every_family() {
+        let metrics = PipelineMetrics::default();
+        metrics.record_head_block(105);
+        metrics.record_persisted(2, 7, Some(100));
+        metrics.record_stage(Stage::Load, Duration::from_millis(20));
+        metrics.record_db_transaction(Duration::from_millis(15));
+        metrics.record_errors(ErrorKind::DeadLetter, 2);
+        metrics.record_filtered(FilterReason::Space, 3);
+        metrics.record_retry(Stage::Consume);
+
+        let output = render(&metrics);
+
+        assert!(output.contains("# TYPE actions_indexer_actions_processed_total counter\n"));
+        assert!(output.contains("\nactions_indexer_actions_processed_total 7\n"));
+        assert!(output.contains("\nactions_indexer_blocks_processed_total 2\n"));
+        assert!(output.contains("\nactions_indexer_stage_duration_seconds_bucket{stage=\"load\",le=\"0.01\"} 0\n"));
+        assert!(output.contains("\nactions_indexer_stage_duration_seconds_bucket{stage=\"load\",le=\"0.025\"} 1\n"));
+        assert!(output.contains("\nactions_indexer_stage_duration_seconds_bucket{stage=\"load\",le=\"+Inf\"} 1\n"));
+        assert!(output.contains("\nactions_indexer_stage_duration_seconds_sum{stage=\"load\"} 0.02\n"));
+        assert!(output.contains("\nactions_indexer_stage_duration_seconds_count{stage=\"consume\"} 0\n"));
+        assert!(output.contains("\nactions_indexer_db_transaction_duration_seconds_count 1\n"));
+        assert!(output.contains("\nactions_indexer_head_block 105\n"));
+        assert!(output.contains("\nactions_indexer_cursor_block 100\n"));
+        assert!(output.contains("\nactions_indexer_cursor_lag_blocks 5\n"));
+        assert!(output.contains("\nactions_indexer_errors_total{kind=\"dead_letter\"} 2\n"));
+        assert!(output.contains("\nactions_indexer_errors_total{kind=\"stream\"} 0\n"));
+        assert!(output.contains("\nactions_indexer_filtered_events_total{reason=\"space\"} 3\n"));
+        assert!(output.contains("\nactions_indexer_retries_total{stage=\"consume\"} 1\n"));
+    }
+
+    #[test]
+    fn test_render_omits_unknown_blocks() {
+        let output = render(&PipelineMetrics::default());
+
+        assert!(!output.contains("actions_indexer_head_block"));
+        assert!(!output.contains("actions_indexer_cursor_block "));
+        assert!(output.contains("\nactions_indexer_cursor_lag_blocks 0\n"));
+    }
+}
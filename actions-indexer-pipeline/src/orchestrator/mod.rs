@@ -2,29 +2,91 @@
 //! action processing pipeline.
 //! It integrates the consumer, processor, and loader components to manage the
 //! flow of action events from ingestion to persistence.
+use crate::aggregation::{activity_deltas, ActivityAggregator};
+use crate::emitter::{votes_count_changes, VotesCountEmitter};
 use crate::errors::OrchestratorError;
-use crate::consumer::{ActionsConsumer, StreamMessage};
-use crate::processor::{ActionsProcessor, ProcessActions};
+use crate::consumer::{ActionsConsumer, BlockDataMessage, StartMode, StreamMessage};
+use crate::processor::{ActionsProcessor, ProcessActions, ProcessedActions};
+use crate::consumer::stream::decoder::encode_action;
 use crate::loader::ActionsLoader;
-use actions_indexer_shared::types::{Action, Changeset, UserVote, Vote, VoteCriteria, VoteCountCriteria, VoteValue, VotesCount};
+use crate::metrics::{ErrorKind, PipelineMetrics, Stage};
+use crate::retry::{RetryConfig, RetryPolicy};
+use actions_indexer_shared::types::{Action, ActionIndex, ActionKey, ActionRaw, ActivityDelta, Changeset, DeadLetter, LeaderboardDelta, LeaderboardWindow, ObjectId, ObjectType, SpaceId, UserVote, Vote, VoteCriteria, VoteCountCriteria, VoteValue, VotesCount};
 use tokio::sync::mpsc;
-use std::collections::HashMap;
-use actions_indexer_repository::{ActionsRepository, CursorRepository};
+use tokio::time::Instant;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+use actions_indexer_repository::{cursor_id, ActionsRepository};
+
+mod vote_weight;
+
+pub use vote_weight::{ConstantVoteWeight, VoteWeightProvider};
+
+/// `BatchConfig` controls how many blocks the orchestrator accumulates before
+/// persisting them.
+///
+/// A batch is flushed in a single transaction as soon as it holds `max_blocks`
+/// blocks or `max_actions` actions, or once `flush_interval` has elapsed since its
+/// first block was received, which bounds the indexing latency at the chain head.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchConfig {
+    pub max_blocks: usize,
+    pub max_actions: usize,
+    pub flush_interval: Duration,
+}
+
+impl Default for BatchConfig {
+    /// Persists every block on its own as soon as it is received.
+    fn default() -> Self {
+        Self {
+            max_blocks: 1,
+            max_actions: 1000,
+            flush_interval: Duration::from_secs(1),
+        }
+    }
+}
 
 /// `Orchestrator` is responsible for coordinating the consumption, processing,
 /// and loading of actions.
 ///
 /// It holds references to the `ConsumeActions`, `ProcessActions`, and
-/// `ActionsLoader` traits, enabling a flexible and extensible pipeline.
+/// `ActionsLoader` traits, enabling a flexible and extensible pipeline, the
+/// `VoteWeightProvider` votes are counted with, the `PipelineMetrics` it
+/// records into, the `StartMode` the consumer starts streaming with, the
+/// `ActivityAggregator` counting the persisted actions, if any, the
+/// `VotesCountEmitter` publishing the changed vote counts, if any, the
+/// `RetryConfig` transient failures of the consumer and loader are retried with,
+/// and the ID its cursor is stored under.
 pub struct Orchestrator {
     pub actions_consumer: Box<ActionsConsumer>,
     pub actions_processor: Box<ActionsProcessor>,
     pub actions_loader: Box<ActionsLoader>,
+    pub batch_config: BatchConfig,
+    pub vote_weight_provider: Box<dyn VoteWeightProvider>,
+    pub metrics: Arc<PipelineMetrics>,
+    pub start_mode: StartMode,
+    pub activity_aggregator: Option<ActivityAggregator>,
+    pub votes_count_emitter: Option<Box<dyn VotesCountEmitter>>,
+    pub retry_config: RetryConfig,
+    pub cursor_id: String,
 }
 
 impl Orchestrator {
     /// Creates a new `Orchestrator` instance.
     ///
+    /// Blocks are persisted one by one and every vote has a weight of 1, see
+    /// [`Self::with_batch_config`] and [`Self::with_vote_weight_provider`] to change it.
+    /// Metrics are recorded into a registry of its own, see [`Self::with_metrics`] to
+    /// export them, the consumer resumes from the stored cursor, see
+    /// [`Self::with_start_mode`], no activity counters are maintained, see
+    /// [`Self::with_activity_aggregator`], vote count changes aren't published, see
+    /// [`Self::with_votes_count_emitter`], and transient failures are retried with
+    /// the default `RetryPolicy`, see [`Self::with_retry_config`], and the cursor is
+    /// stored under the default ID, see [`Self::with_namespace`].
+    ///
     /// # Arguments
     ///
     /// * `actions_consumer` - A boxed `ActionsConsumer` instance
@@ -43,29 +105,197 @@ impl Orchestrator {
             actions_consumer,
             actions_processor,
             actions_loader,
+            batch_config: BatchConfig::default(),
+            vote_weight_provider: Box::new(ConstantVoteWeight::default()),
+            metrics: Arc::new(PipelineMetrics::default()),
+            start_mode: StartMode::default(),
+            activity_aggregator: None,
+            votes_count_emitter: None,
+            retry_config: RetryConfig::default(),
+            cursor_id: cursor_id(None),
         }
     }
 
+    /// Sets how blocks are batched before being persisted.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_config` - The batch limits and flush interval to use
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` with the new batch configuration.
+    pub fn with_batch_config(mut self, batch_config: BatchConfig) -> Self {
+        self.batch_config = batch_config;
+        self
+    }
+
+    /// Sets how much each vote counts towards vote counts and leaderboards.
+    ///
+    /// # Arguments
+    ///
+    /// * `vote_weight_provider` - A boxed `VoteWeightProvider` instance
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` with the new vote weighting.
+    pub fn with_vote_weight_provider(mut self, vote_weight_provider: Box<dyn VoteWeightProvider>) -> Self {
+        self.vote_weight_provider = vote_weight_provider;
+        self
+    }
+
+    /// Sets the metrics the orchestrator records its progress into.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - The shared `PipelineMetrics`, typically also served by an exporter
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` recording into the given metrics.
+    pub fn with_metrics(mut self, metrics: Arc<PipelineMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Sets where the consumer starts streaming from.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_mode` - The `StartMode` to start the consumer with
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` with the new start mode.
+    pub fn with_start_mode(mut self, start_mode: StartMode) -> Self {
+        self.start_mode = start_mode;
+        self
+    }
+
+    /// Sets the aggregator maintaining the activity counters of the persisted actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_aggregator` - The `ActivityAggregator` to count the persisted actions with
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` with the new activity aggregator.
+    pub fn with_activity_aggregator(mut self, activity_aggregator: ActivityAggregator) -> Self {
+        self.activity_aggregator = Some(activity_aggregator);
+        self
+    }
+
+    /// Sets the emitter publishing the vote counts changed by the persisted actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `votes_count_emitter` - A boxed `VotesCountEmitter` instance
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` with the new vote count emitter.
+    pub fn with_votes_count_emitter(mut self, votes_count_emitter: Box<dyn VotesCountEmitter>) -> Self {
+        self.votes_count_emitter = Some(votes_count_emitter);
+        self
+    }
+
+    /// Sets how the consumer and loader retry transient failures.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_config` - The `RetryPolicy` of each stage
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` with the new retry policies.
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Sets the namespace the cursor is stored under.
+    ///
+    /// Indexers sharing a database, e.g. a mainnet and a testnet one, each resume
+    /// from their own cursor by running in their own namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace of the indexer, see [`cursor_id`]
+    ///
+    /// # Returns
+    ///
+    /// The `Orchestrator` with the new cursor ID.
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.cursor_id = cursor_id(Some(namespace));
+        self
+    }
+
     /// Runs the orchestrator, initiating the action processing pipeline.
     ///
     /// This method is the main entry point for starting the continuous flow of
-    /// action consumption, processing, and loading.
+    /// action consumption, processing, and loading. Blocks are accumulated
+    /// according to the `BatchConfig` and each batch is persisted in a single
+    /// transaction along with the cursor of its last block. Pending blocks are
+    /// also flushed before handling an undo signal or the end of the stream.
+    ///
+    /// An undo signal rolls the persisted changesets back to its last valid block,
+    /// so the blocks of the new fork are persisted in place of the undone ones.
+    /// The activity counters are journaled with the changesets and rolled back too.
+    ///
+    /// The orchestrator stops gracefully on Ctrl-C or SIGTERM, see [`Self::run_until`].
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the `RunSummary` of the run, or an `OrchestratorError` if an
+    /// error occurs during the orchestration process.
+    pub async fn run(self) -> Result<RunSummary, OrchestratorError> {
+        self.run_until(shutdown_signal()).await
+    }
+
+    /// Runs the orchestrator until `shutdown` resolves or the consumer stops.
+    ///
+    /// On shutdown the consumer is stopped and the blocks it already sent are
+    /// drained from the channel and persisted. A batch being persisted when the
+    /// shutdown is requested is finished first, so the cursor never gets ahead of
+    /// or behind the persisted data. A summary of the run is logged before returning.
+    ///
+    /// The stored cursor is only read with `StartMode::FromCursor`, and isn't saved
+    /// during a `StartMode::Replay`, which stops at the end of its range.
+    ///
+    /// Retryable errors, see [`Classify`](crate::errors::Classify), are retried with
+    /// the `RetryConfig`: the consumer is restarted from the last block it delivered,
+    /// and the loader reads and persists a batch again. A batch still failing once its
+    /// retries are exhausted aborts the run, like a fatal error, so the cursor is never
+    /// saved past blocks that weren't persisted.
+    ///
+    /// # Arguments
+    ///
+    /// * `shutdown` - A future resolving once the orchestrator should stop
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or an `OrchestratorError` if an error occurs
-    /// during the orchestration process.
-    pub async fn run(self) -> Result<(), OrchestratorError> {
+    /// A `Result` with the `RunSummary` of the run, or an `OrchestratorError` if an
+    /// error occurs during the orchestration process.
+    pub async fn run_until(self, shutdown: impl Future<Output = ()>) -> Result<RunSummary, OrchestratorError> {
         let (tx, mut rx) = mpsc::channel(1000); 
         
-        let consumer_tx = tx.clone();
         let consumer = self.actions_consumer;
         let processor = self.actions_processor;
         let loader = self.actions_loader;
+        let batch_config = self.batch_config;
+        let weights = self.vote_weight_provider;
+        let metrics = self.metrics;
+        let start_mode = self.start_mode;
+        let activity = self.activity_aggregator;
+        let emitter = self.votes_count_emitter;
+        let retry_config = self.retry_config;
+        let cursor_id = self.cursor_id;
+        let save_cursor = start_mode.saves_cursor().then_some(cursor_id.as_str());
 
         // Wait until the tables are created
         loop {
-            if loader.actions_repository.check_tables_created().await? {
+            if retry_config.loader.retry(Stage::Load, &metrics, || loader.actions_repository.check_tables_created()).await? {
                 break;
             }
             println!("Waiting for tables to be created...");
@@ -73,90 +303,523 @@ impl Orchestrator {
         }
 
         // Get the cursor from the database
-        let cursor = loader.cursor_repository.get_cursor("actions_indexer").await.map_err(OrchestratorError::from)?;
-        
-        tokio::spawn(async move {
-            if let Err(e) = consumer.run(consumer_tx, cursor).await {
-                eprintln!("Consumer error: {:?}", e);
-            }
-        });
-        
-        while let Some(message) = rx.recv().await {
+        let cursor = match start_mode {
+            StartMode::FromCursor => retry_config.loader.retry(Stage::Load, &metrics, || loader.cursor_repository.get_cursor(&cursor_id)).await?,
+            _ => None,
+        };
+
+        let consumer_task = tokio::spawn(run_consumer(consumer, tx, cursor, start_mode, retry_config.consumer, metrics.clone()));
+
+        let mut shutdown = std::pin::pin!(shutdown);
+        let mut draining = false;
+        let mut batch = PendingBatch::default();
+        let mut summary = RunSummary::default();
+
+        loop {
+            let waiting_since = Instant::now();
+            let next = tokio::select! {
+                _ = &mut shutdown, if !draining => {
+                    println!("Shutdown requested, draining pending blocks...");
+                    consumer_task.abort();
+                    rx.close();
+                    draining = true;
+                    continue;
+                }
+                next = next_message(&mut rx, batch.deadline) => next,
+            };
+
+            let message = match next {
+                NextMessage::Message(message) => message,
+                NextMessage::Deadline => {
+                    flush_batch(batch.take(), &processor, weights.as_ref(), &loader, activity.as_ref(), emitter.as_deref(), &metrics, &retry_config.loader, save_cursor, &mut summary).await?;
+                    continue;
+                }
+                NextMessage::Closed => break,
+            };
+
             match message {
                 StreamMessage::BlockData(block_data) => {
-                    let actions = block_data.actions;
-                    let cursor = block_data.cursor;
-                    let block_number = block_data.block_number;
-
-                    if actions.len() > 0 {
-                        let now = chrono::Utc::now();
-                        println!("{} - Processing {} actions", now.to_rfc3339(), actions.len());
-                        
-                        let actions = processor.process(&actions);
-                        
-                        let mut votes: Vec<Vote> = Vec::new();
-                        for action in actions.clone() {
-                            match action {
-                                Action::Vote(vote) => votes.push(vote),
-                            }
-                        }
-                        
-                        let user_votes = get_latest_user_votes(&votes);
-                        let votes_count = update_vote_counts(&user_votes, loader.actions_repository.as_ref()).await?;
-
-                        let changeset = Changeset { 
-                            actions: &actions,  
-                            user_votes: &user_votes,
-                            votes_count: &votes_count,
-                        };
-
-                        if let Err(e) = loader.persist_changeset(&changeset).await {
-                            eprintln!("Failed to persist changeset: {:?}", e);
-                        } else {
-                            save_cursor(&cursor, &block_number, loader.cursor_repository.as_ref()).await?;
-                        }
-                    } else {
-                        if !cursor.is_empty() {
-                            save_cursor(&cursor, &block_number, loader.cursor_repository.as_ref()).await?;
-                        }
+                    metrics.record_stage(Stage::Consume, waiting_since.elapsed());
+                    metrics.record_head_block(block_data.block_number);
+                    batch.push(block_data, batch_config.flush_interval);
+                    if batch.is_full(&batch_config) {
+                        flush_batch(batch.take(), &processor, weights.as_ref(), &loader, activity.as_ref(), emitter.as_deref(), &metrics, &retry_config.loader, save_cursor, &mut summary).await?;
                     }
-
                 }
                 StreamMessage::UndoSignal(undo_signal) => {
-                    println!("UndoSignal: {:?}", undo_signal);
+                    flush_batch(batch.take(), &processor, weights.as_ref(), &loader, activity.as_ref(), emitter.as_deref(), &metrics, &retry_config.loader, save_cursor, &mut summary).await?;
+                    match undo_signal.last_valid_block {
+                        Some(last_valid_block) => {
+                            println!("UndoSignal: rolling back the blocks after {}", last_valid_block.number);
+                            // The cursor moves back with the data, so a restart resumes from the last valid block
+                            match save_cursor {
+                                Some(cursor_id) => retry_config.loader.retry(Stage::Load, &metrics, || loader.rollback_to_with_cursor(last_valid_block.number, cursor_id, &undo_signal.last_valid_cursor)).await?,
+                                None => retry_config.loader.retry(Stage::Load, &metrics, || loader.rollback_to(last_valid_block.number)).await?,
+                            }
+                        }
+                        None => println!("UndoSignal without a last valid block: {:?}", undo_signal),
+                    }
                 }
                 StreamMessage::Error(error) => {
+                    metrics.record_errors(ErrorKind::Stream, 1);
                     println!("Error: {:?}", error);
                 }
                 StreamMessage::StreamEnd => {
+                    flush_batch(batch.take(), &processor, weights.as_ref(), &loader, activity.as_ref(), emitter.as_deref(), &metrics, &retry_config.loader, save_cursor, &mut summary).await?;
                     println!("StreamEnd");
                 }
             }   
         }
-        Ok(())
+
+        flush_batch(batch.take(), &processor, weights.as_ref(), &loader, activity.as_ref(), emitter.as_deref(), &metrics, &retry_config.loader, save_cursor, &mut summary).await?;
+        summary.log();
+        Ok(summary)
+    }
+}
+
+/// `RunSummary` reports what an orchestrator run persisted before it stopped.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RunSummary {
+    pub blocks: usize,
+    pub actions: usize,
+    pub last_block_number: Option<i64>,
+}
+
+impl RunSummary {
+    /// Logs the outcome of the run.
+    pub fn log(&self) {
+        let last_block = self
+            .last_block_number
+            .map_or("none".to_string(), |block_number| block_number.to_string());
+        println!(
+            "Orchestrator stopped: persisted {} blocks with {} actions, last block {}",
+            self.blocks,
+            self.actions,
+            last_block,
+        );
+    }
+}
+
+/// Votes per shard below which `update_vote_counts` doesn't split a batch further.
+const MIN_VOTES_PER_SHARD: usize = 500;
+/// Maximum number of shards `update_vote_counts` splits a batch into.
+const MAX_VOTE_SHARDS: usize = 8;
+
+/// What the orchestrator got while waiting on the channel.
+enum NextMessage {
+    Message(StreamMessage),
+    /// The flush interval of the pending batch elapsed first.
+    Deadline,
+    /// The channel is closed and fully drained.
+    Closed,
+}
+
+/// Waits for the next message of the consumer, up to the given deadline if any.
+async fn next_message(rx: &mut mpsc::Receiver<StreamMessage>, deadline: Option<Instant>) -> NextMessage {
+    let message = match deadline {
+        Some(deadline) => match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(message) => message,
+            Err(_) => return NextMessage::Deadline,
+        },
+        None => rx.recv().await,
+    };
+    message.map_or(NextMessage::Closed, NextMessage::Message)
+}
+
+/// Runs the consumer until its stream ends, restarting it when it fails with a
+/// retryable error.
+///
+/// The consumer resumes after the last block it delivered: from its cursor, or for
+/// a `StartMode::Replay`, from the next block of the range. Its retries are reset
+/// once a restarted stream delivers a block. A fatal error, or a retryable one once
+/// the retries are exhausted, is logged and stops the consumer, which closes the
+/// channel.
+async fn run_consumer(consumer: Box<ActionsConsumer>, tx: mpsc::Sender<StreamMessage>, mut cursor: Option<String>, mut start_mode: StartMode, retry_policy: RetryPolicy, metrics: Arc<PipelineMetrics>) {
+    let mut retries = 0;
+    loop {
+        let (stream_tx, mut stream_rx) = mpsc::channel(tx.max_capacity());
+        let mut last_block: Option<(String, i64)> = None;
+        let mut stream_error = None;
+        let forward = async {
+            while let Some(message) = stream_rx.recv().await {
+                match &message {
+                    StreamMessage::BlockData(block) => last_block = Some((block.cursor.clone(), block.block_number)),
+                    StreamMessage::Error(e) => stream_error = Some(e.clone()),
+                    _ => {}
+                }
+                if tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+        };
+
+        // The stream reports its failures as a message before stopping
+        let (result, _) = tokio::join!(consumer.run(stream_tx, cursor.clone(), &start_mode), forward);
+        let error = match (result, stream_error) {
+            (Err(e), _) | (Ok(()), Some(e)) => e,
+            (Ok(()), None) => return,
+        };
+
+        if let Some((last_cursor, block_number)) = last_block {
+            retries = 0;
+            match &start_mode {
+                StartMode::Replay(range) => start_mode = StartMode::Replay(block_number as u64 + 1..range.end),
+                _ if !last_cursor.is_empty() => {
+                    cursor = Some(last_cursor);
+                    start_mode = StartMode::FromCursor;
+                }
+                _ => {}
+            }
+        }
+        if !retry_policy.should_retry(&error, retries) {
+            eprintln!("Consumer error: {:?}", error);
+            return;
+        }
+
+        retries += 1;
+        let backoff = retry_policy.backoff(retries);
+        eprintln!(
+            "Retryable consume error, restarting the consumer in {:?} ({}/{}): {:?}",
+            backoff, retries, retry_policy.max_retries, error,
+        );
+        metrics.record_retry(Stage::Consume);
+        tokio::time::sleep(backoff).await;
+    }
+}
+
+/// Resolves once the process receives Ctrl-C or, on Unix, SIGTERM.
+///
+/// If a signal handler can't be installed, the error is logged and that signal
+/// is ignored.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            eprintln!("Failed to listen for Ctrl-C: {:?}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                eprintln!("Failed to listen for SIGTERM: {:?}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Blocks received from the consumer that are waiting to be persisted together.
+#[derive(Default)]
+struct PendingBatch {
+    blocks: Vec<BlockDataMessage>,
+    actions: usize,
+    deadline: Option<Instant>,
+}
+
+impl PendingBatch {
+    /// Adds a block to the batch, starting the flush timer if it is the first one.
+    fn push(&mut self, block: BlockDataMessage, flush_interval: Duration) {
+        self.deadline.get_or_insert_with(|| Instant::now() + flush_interval);
+        self.actions += block.actions.len();
+        self.blocks.push(block);
+    }
+
+    fn is_full(&self, batch_config: &BatchConfig) -> bool {
+        self.blocks.len() >= batch_config.max_blocks || self.actions >= batch_config.max_actions
+    }
+
+    /// Empties the batch, returning its blocks in the order they were received.
+    fn take(&mut self) -> Vec<BlockDataMessage> {
+        self.actions = 0;
+        self.deadline = None;
+        std::mem::take(&mut self.blocks)
+    }
+}
+
+/// Persists a batch of blocks along with the cursor of its last block.
+///
+/// # Arguments
+///
+/// * `batch` - The blocks to persist, in the order they were received
+/// * `processor` - The processor turning raw actions into typed actions
+/// * `weights` - The provider weighting each vote
+/// * `loader` - The loader persisting the changesets and the cursor
+/// * `activity` - The aggregator counting the persisted actions, if any
+/// * `emitter` - The emitter publishing the changed vote counts, if any
+/// * `metrics` - The metrics recording the stages and outcome of the batch
+/// * `retry_policy` - The policy retrying the batch after a retryable error
+/// * `save_cursor` - The ID to save the cursor of the last block under, if any
+/// * `summary` - The summary of the run, updated with the outcome of the batch
+///
+/// # Returns
+///
+/// A `Result` indicating success or an `OrchestratorError` if the batch failed with
+/// a fatal error, or with a retryable error once its retries are exhausted. A failed
+/// batch leaves the changesets and cursor untouched, so the run must stop rather than
+/// persist the next batch and save its cursor past the failed blocks.
+///
+#[allow(clippy::too_many_arguments)]
+async fn flush_batch(batch: Vec<BlockDataMessage>, processor: &ActionsProcessor, weights: &dyn VoteWeightProvider, loader: &ActionsLoader, activity: Option<&ActivityAggregator>, emitter: Option<&dyn VotesCountEmitter>, metrics: &PipelineMetrics, retry_policy: &RetryPolicy, save_cursor: Option<&str>, summary: &mut RunSummary) -> Result<(), OrchestratorError> {
+    let Some(last_block) = batch.last() else {
+        return Ok(());
+    };
+    let cursor = last_block.cursor.clone();
+    let block_number = last_block.block_number;
+    let blocks = batch.len();
+    let actions: usize = batch.iter().map(|block| block.actions.len()).sum();
+    let cursor = save_cursor.filter(|_| !cursor.is_empty()).map(|cursor_id| (cursor_id, cursor.as_str(), block_number));
+
+    let result = retry_policy
+        .retry(Stage::Load, metrics, || persist_blocks(&batch, processor, weights, loader, activity, emitter, cursor, metrics))
+        .await;
+    if let Err(e) = result {
+        eprintln!("Failed to persist changesets: {:?}", e);
+        metrics.record_errors(ErrorKind::Persist, 1);
+        return Err(e);
+    }
+    metrics.record_persisted(blocks, actions, cursor.map(|(_, _, block_number)| block_number));
+    summary.blocks += blocks;
+    summary.actions += actions;
+    summary.last_block_number = Some(block_number);
+    Ok(())
+}
+
+/// Persists blocks read outside of the stream, such as reprocessed dead letters.
+///
+/// The blocks are processed and persisted like a batch, but the cursor is left
+/// untouched. Actions that were already persisted are skipped, so replaying the
+/// same blocks twice doesn't count their votes twice.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to persist
+/// * `processor` - The processor turning raw actions into typed actions
+/// * `weights` - The provider weighting each vote
+/// * `loader` - The loader persisting the changesets and dead letters
+///
+/// # Returns
+///
+/// A `Result` indicating success or an `OrchestratorError` if the blocks couldn't
+/// be prepared or persisted.
+///
+pub async fn replay_blocks(blocks: Vec<BlockDataMessage>, processor: &ActionsProcessor, weights: &dyn VoteWeightProvider, loader: &ActionsLoader) -> Result<(), OrchestratorError> {
+    persist_blocks(&blocks, processor, weights, loader, None, None, None, &PipelineMetrics::default()).await
+}
+
+/// Processes blocks and persists their changesets and dead letters.
+///
+/// The actions of every block are processed together and turned into one changeset
+/// per block, so the journal used for rollbacks stays per block, and all changesets
+/// are persisted in a single transaction, along with the cursor if one is given. The
+/// journal of the blocks the stream reports as final is pruned in that transaction.
+/// The events the consumer couldn't decode and the actions the processor rejected
+/// are persisted as dead letters first. The time spent preparing and writing the
+/// changesets is recorded as the `process` and `load` stages.
+///
+/// With an activity aggregator, the actions of each block are also counted into
+/// its changeset, so the activity counters are updated and rolled back with it.
+/// Once persisted, the old activity counters are pruned and the changed vote counts
+/// are published. Both are derived data, so a failure to prune the counters or
+/// publish the changes is logged and counted rather than failing the batch.
+///
+/// # Arguments
+///
+/// * `blocks` - The blocks to persist, in the order they were received
+/// * `processor` - The processor turning raw actions into typed actions
+/// * `weights` - The provider weighting each vote
+/// * `loader` - The loader persisting the changesets and dead letters
+/// * `activity` - The aggregator counting the persisted actions, if any
+/// * `emitter` - The emitter publishing the changed vote counts, if any
+/// * `cursor` - The cursor ID, cursor and block number to save with the changesets, if any
+/// * `metrics` - The metrics recording the stages, transaction and dead letters
+///
+/// # Returns
+///
+/// A `Result` indicating success or an `OrchestratorError` if the blocks couldn't be
+/// prepared, or an `OrchestratorError::Loader` if they couldn't be persisted.
+///
+#[allow(clippy::too_many_arguments)]
+async fn persist_blocks(blocks: &[BlockDataMessage], processor: &ActionsProcessor, weights: &dyn VoteWeightProvider, loader: &ActionsLoader, activity: Option<&ActivityAggregator>, emitter: Option<&dyn VotesCountEmitter>, cursor: Option<(&str, &str, i64)>, metrics: &PipelineMetrics) -> Result<(), OrchestratorError> {
+    let started = Instant::now();
+    let mut actions: Vec<ActionRaw> = Vec::new();
+    let mut dead_letters: Vec<DeadLetter> = Vec::new();
+    for block in blocks {
+        actions.extend(block.actions.iter().cloned());
+        dead_letters.extend(block.dead_letters.iter().cloned());
+    }
+
+    let mut processed = ProcessedActions::default();
+    if !actions.is_empty() {
+        let now = chrono::Utc::now();
+        println!("{} - Processing {} actions", now.to_rfc3339(), actions.len());
+
+        let actions = skip_persisted_actions(actions, loader.actions_repository.as_ref()).await?;
+        processed = processor.try_process(&actions);
+    }
+
+    dead_letters.extend(processed.rejected.iter().map(|(action, error)| DeadLetter {
+        block_number: action.block_number,
+        raw: encode_action(action),
+        error: error.to_string(),
+    }));
+
+    let mut block_actions: BTreeMap<u64, Vec<Action>> = BTreeMap::new();
+    for action in processed.actions {
+        block_actions.entry(action.raw().block_number).or_default().push(action);
+    }
+
+    let block_user_votes: Vec<Vec<UserVote>> = block_actions
+        .values()
+        .map(|actions| {
+            let votes: Vec<Vote> = actions
+                .iter()
+                .filter_map(|action| match action {
+                    Action::Vote(vote) => Some(vote.clone()),
+                    _ => None,
+                })
+                .collect();
+            get_latest_user_votes(&votes, weights)
+        })
+        .collect();
+    let block_vote_counts = update_vote_counts(&block_user_votes, loader.actions_repository.as_ref()).await?;
+
+    let block_activity: Vec<Vec<ActivityDelta>> = block_actions
+        .values()
+        .map(|actions| if activity.is_some() { activity_deltas(actions) } else { Vec::new() })
+        .collect();
+
+    let final_block_number = blocks.iter().map(|block| block.final_block_number).max().unwrap_or_default();
+    let changesets: Vec<Changeset> = block_actions
+        .iter()
+        .zip(&block_user_votes)
+        .zip(&block_vote_counts)
+        .zip(&block_activity)
+        .map(|((((block_number, actions), user_votes), (votes_count, leaderboard_deltas)), activity_deltas)| Changeset {
+            block_number: *block_number,
+            final_block_number,
+            actions,
+            user_votes,
+            votes_count,
+            leaderboard_deltas,
+            activity_deltas,
+        })
+        .collect();
+    metrics.record_stage(Stage::Process, started.elapsed());
+
+    let started = Instant::now();
+    if !dead_letters.is_empty() {
+        let now = chrono::Utc::now();
+        println!("{} - Dead-lettering {} events", now.to_rfc3339(), dead_letters.len());
+        loader.persist_dead_letters(&dead_letters).await?;
+        metrics.record_errors(ErrorKind::DeadLetter, dead_letters.len());
+    }
+
+    let transaction_started = Instant::now();
+    match cursor {
+        Some((cursor_id, cursor, block_number)) => loader.persist_changesets_with_cursor(&changesets, cursor_id, cursor, block_number).await?,
+        None if !changesets.is_empty() => loader.persist_changesets(&changesets).await?,
+        None => return Ok(()),
+    }
+    metrics.record_db_transaction(transaction_started.elapsed());
+    metrics.record_stage(Stage::Load, started.elapsed());
+
+    if let Some(activity) = activity
+        && let Err(e) = activity.prune(&changesets).await
+    {
+        eprintln!("Failed to prune activity counters: {:?}", e);
+        metrics.record_errors(ErrorKind::Activity, 1);
+    }
+
+    if let Some(emitter) = emitter {
+        let changes = votes_count_changes(&changesets);
+        if !changes.is_empty()
+            && let Err(e) = emitter.emit(&changes).await
+        {
+            eprintln!("Failed to publish {} vote count changes: {:?}", changes.len(), e);
+            metrics.record_errors(ErrorKind::Emit, 1);
+        }
     }
+    Ok(())
 }
 
 #[derive(Debug)]
 struct VotesDelta {
-    upvotes: i32,
-    downvotes: i32,
+    upvotes: i64,
+    downvotes: i64,
+}
+
+type LeaderboardKey = (ObjectId, SpaceId, ObjectType, LeaderboardWindow, u64);
+
+/// Drops the actions that were already persisted, so replaying a cursor range doesn't
+/// count their votes twice.
+///
+/// Actions with neither a log index nor an output position can't be identified and
+/// are always kept.
+///
+/// # Arguments
+///
+/// * `actions` - The raw actions of a block
+/// * `actions_repository` - The repository to look up persisted actions in
+///
+/// # Returns
+///
+/// The actions that weren't persisted yet, in their original order.
+///
+async fn skip_persisted_actions(actions: Vec<ActionRaw>, actions_repository: &dyn ActionsRepository) -> Result<Vec<ActionRaw>, OrchestratorError> {
+    let action_keys: Vec<ActionKey> = actions.iter().filter_map(action_key).collect();
+    if action_keys.is_empty() {
+        return Ok(actions);
+    }
+
+    let persisted: HashSet<ActionKey> = actions_repository
+        .get_persisted_action_keys(&action_keys)
+        .await?
+        .into_iter()
+        .collect();
+
+    Ok(actions
+        .into_iter()
+        .filter(|action| !action_key(action).is_some_and(|key| persisted.contains(&key)))
+        .collect())
+}
+
+fn action_key(action: &ActionRaw) -> Option<ActionKey> {
+    let index = match (action.log_index, action.output_position) {
+        (Some(log_index), _) => ActionIndex::Log(log_index),
+        (None, Some(position)) => ActionIndex::OutputPosition(position),
+        (None, None) => return None,
+    };
+    Some((action.tx_hash, index))
 }
 
 /// This method returns the latest vote for each user/entity/space combination
 /// 
 /// It assumes that the votes are sorted by block_timestamp so it simply returns the last occurrence
-/// of each user/entity/space combination.
+/// of each user/entity/space combination, weighted with the given provider.
 ///
 /// # Arguments
 ///
 /// * `votes` - A slice of `Vote`s to process
+/// * `weights` - The provider weighting each vote
 ///
 /// # Returns
 ///
 /// A vector of `UserVote`s with the latest vote for each user/entity/space combination.
 ///
-fn get_latest_user_votes(votes: &[Vote]) -> Vec<UserVote> {
+fn get_latest_user_votes(votes: &[Vote], weights: &dyn VoteWeightProvider) -> Vec<UserVote> {
     let mut latest_votes: HashMap<VoteCriteria, &Vote> = HashMap::new();
     
     for vote in votes {
@@ -174,36 +837,98 @@ fn get_latest_user_votes(votes: &[Vote]) -> Vec<UserVote> {
             space_id,
             vote_type: vote.vote.clone(),
             voted_at: vote.raw.block_timestamp,
+            weight: weights.weight(vote),
         });
     }
     
     user_votes
 }
 
-/// This method updates the vote counts for each entity/space combination
+/// This method updates the vote counts and leaderboards for each entity/space combination
 ///
-/// It uses the user votes to calculate the vote changes and then updates the vote counts
-/// for each entity/space combination.
+/// It uses the user votes of each block to calculate the vote changes and then updates the
+/// vote counts for each entity/space combination. The same changes are turned into leaderboard
+/// deltas for every leaderboard window. Blocks are applied in order, so a vote is compared
+/// against the latest vote of the user in the previous blocks of the batch, if any, rather
+/// than the stored one. A vote older than the one it is compared against, e.g. from a
+/// reprocessed dead letter, is ignored, as the stored vote is kept.
+///
+/// A vote only changes the counts and leaderboards of its own object, so large batches are
+/// split into shards by object and space. The stored state of every shard is fetched
+/// concurrently and its changes are computed on a blocking task of its own, then the shards
+/// are merged back per block.
 ///
 /// # Arguments
 ///
-/// * `user_votes` - A slice of `UserVote`s to process
+/// * `block_user_votes` - The `UserVote`s of each block to process, ordered by block number
 /// * `actions_repository` - A reference to the `ActionsRepository` to use
 ///
 /// # Returns
 ///
-/// A vector of `VotesCount`s with the updated vote counts for each entity/space combination.
+/// For each block, a vector of `VotesCount`s with the updated vote counts for the entity/space
+/// combinations it touches, and a vector of the non-empty `LeaderboardDelta`s to apply.
+///
+async fn update_vote_counts(block_user_votes: &[Vec<UserVote>], actions_repository: &dyn ActionsRepository) -> Result<Vec<(Vec<VotesCount>, Vec<LeaderboardDelta>)>, OrchestratorError> {
+    let vote_total: usize = block_user_votes.iter().map(Vec::len).sum();
+    if vote_total == 0 {
+        return Ok(block_user_votes.iter().map(|_| (Vec::new(), Vec::new())).collect());
+    }
+
+    let shard_count = vote_total.div_ceil(MIN_VOTES_PER_SHARD).min(MAX_VOTE_SHARDS);
+    let shards = shard_user_votes(block_user_votes, shard_count);
+
+    let stored_states = futures03::future::try_join_all(
+        shards.iter().map(|shard| get_stored_vote_state(shard, actions_repository)),
+    )
+    .await?;
+
+    let tasks: Vec<_> = shards
+        .into_iter()
+        .zip(stored_states)
+        .map(|(shard, (stored_user_votes, stored_vote_counts))| {
+            tokio::task::spawn_blocking(move || compute_vote_counts(&shard, stored_user_votes, stored_vote_counts))
+        })
+        .collect();
+
+    let mut block_updates: Vec<(Vec<VotesCount>, Vec<LeaderboardDelta>)> = block_user_votes.iter().map(|_| (Vec::new(), Vec::new())).collect();
+    for task in tasks {
+        let shard_updates = task.await.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()));
+        for ((votes_count, leaderboard_deltas), (shard_votes_count, shard_leaderboard_deltas)) in block_updates.iter_mut().zip(shard_updates) {
+            votes_count.extend(shard_votes_count);
+            leaderboard_deltas.extend(shard_leaderboard_deltas);
+        }
+    }
+
+    Ok(block_updates)
+}
+
+/// Splits the user votes of each block into `shard_count` shards by object and space.
 ///
-async fn update_vote_counts(user_votes: &[UserVote], actions_repository: &dyn ActionsRepository) -> Result<Vec<VotesCount>, OrchestratorError> {
-    if user_votes.is_empty() {
-        return Ok(Vec::new());
+/// Every shard keeps one, possibly empty, vector of votes per block, in the same order.
+fn shard_user_votes(block_user_votes: &[Vec<UserVote>], shard_count: usize) -> Vec<Vec<Vec<UserVote>>> {
+    let mut shards = vec![vec![Vec::new(); block_user_votes.len()]; shard_count];
+
+    for (block, user_votes) in block_user_votes.iter().enumerate() {
+        for vote in user_votes {
+            let mut hasher = DefaultHasher::new();
+            (vote.object_id, vote.space_id).hash(&mut hasher);
+            let shard = (hasher.finish() % shard_count as u64) as usize;
+            shards[shard][block].push(vote.clone());
+        }
     }
 
-    let vote_criteria: Vec<VoteCriteria> = user_votes.iter()
+    shards
+}
+
+/// Fetches the stored user votes and vote counts the votes of a shard build on.
+async fn get_stored_vote_state(block_user_votes: &[Vec<UserVote>], actions_repository: &dyn ActionsRepository) -> Result<(HashMap<VoteCriteria, UserVote>, HashMap<VoteCountCriteria, VotesCount>), OrchestratorError> {
+    let vote_criteria: Vec<VoteCriteria> = block_user_votes.iter()
+        .flatten()
         .map(|vote| (vote.user_id, vote.object_id, vote.space_id, vote.object_type))
         .collect();
         
-    let vote_count_criteria: Vec<VoteCountCriteria> = user_votes.iter()
+    let vote_count_criteria: Vec<VoteCountCriteria> = block_user_votes.iter()
+        .flatten()
         .map(|vote| (vote.object_id, vote.space_id, vote.object_type))
         .collect();
 
@@ -212,63 +937,136 @@ async fn update_vote_counts(user_votes: &[UserVote], actions_repository: &dyn Ac
         actions_repository.get_vote_counts(&vote_count_criteria)
     )?;
 
-    let stored_user_votes_map: HashMap<VoteCriteria, UserVote> = stored_user_votes
+    let stored_user_votes_map = stored_user_votes
         .into_iter()
         .map(|vote| ((vote.user_id, vote.object_id, vote.space_id, vote.object_type), vote))
         .collect();
 
-    let mut vote_counts_map: HashMap<VoteCountCriteria, VotesCount> = stored_vote_counts
+    let vote_counts_map = stored_vote_counts
         .into_iter()
         .map(|count| ((count.object_id, count.space_id, count.object_type), count))
         .collect();
 
-    for new_vote in user_votes {
-        let vote_criteria = (new_vote.user_id, new_vote.object_id, new_vote.space_id, new_vote.object_type);
-        let count_criteria = (new_vote.object_id, new_vote.space_id, new_vote.object_type);
-        
-        let stored_user_vote = stored_user_votes_map.get(&vote_criteria);
-        let vote_delta = compute_vote_delta(&stored_user_vote, new_vote);
-        
-        let vote_count = vote_counts_map.entry(count_criteria).or_insert_with(|| VotesCount {
-            object_id: new_vote.object_id,
-            object_type: new_vote.object_type,
-            space_id: new_vote.space_id,
-            upvotes: 0,
-            downvotes: 0,
-        });
-        
-        vote_count.upvotes += vote_delta.upvotes as i64;
-        vote_count.downvotes += vote_delta.downvotes as i64;
-    }
-
-    Ok(vote_counts_map.into_values().collect())
+    Ok((stored_user_votes_map, vote_counts_map))
 }
 
-fn compute_vote_delta(saved_vote: &Option<&UserVote>, new_vote: &UserVote) -> VotesDelta {
-    let saved_vote_value = saved_vote.map(|vote| vote.vote_type.clone());
-    let new_vote_value = new_vote.vote_type.clone();
-
-    let (upvotes, downvotes) = match (saved_vote_value, new_vote_value) {
-        (Some(VoteValue::Up), VoteValue::Down)          => (-1, 1),
-        (Some(VoteValue::Up), VoteValue::Remove)        => (-1, 0),
-        (Some(VoteValue::Down), VoteValue::Up)          => (1, -1),
-        (Some(VoteValue::Down), VoteValue::Remove)      => (0, -1),
-        (Some(VoteValue::Remove), VoteValue::Up)        => (1, 0),
-        (Some(VoteValue::Remove), VoteValue::Down)      => (0, 1),
-        (None, VoteValue::Up)                          => (1, 0),
-        (None, VoteValue::Down)                        => (0, 1),
-        (_, _) => (0, 0)
-    };
+/// Applies the user votes of each block on top of the stored state, see [`update_vote_counts`].
+fn compute_vote_counts(
+    block_user_votes: &[Vec<UserVote>],
+    mut stored_user_votes_map: HashMap<VoteCriteria, UserVote>,
+    mut vote_counts_map: HashMap<VoteCountCriteria, VotesCount>,
+) -> Vec<(Vec<VotesCount>, Vec<LeaderboardDelta>)> {
+    let mut block_updates = Vec::with_capacity(block_user_votes.len());
+
+    for user_votes in block_user_votes {
+        let mut block_vote_counts: HashMap<VoteCountCriteria, VotesCount> = HashMap::new();
+        let mut leaderboard_deltas: HashMap<LeaderboardKey, LeaderboardDelta> = HashMap::new();
+
+        for new_vote in user_votes {
+            let vote_criteria = (new_vote.user_id, new_vote.object_id, new_vote.space_id, new_vote.object_type);
+            let count_criteria = (new_vote.object_id, new_vote.space_id, new_vote.object_type);
+            
+            let stored_user_vote = stored_user_votes_map.get(&vote_criteria);
+            // The stored vote is kept over an older one, see `ActionsRepository::update_user_votes`,
+            // so it mustn't change the counts either
+            if stored_user_vote.is_some_and(|stored_vote| stored_vote.voted_at > new_vote.voted_at) {
+                continue;
+            }
+            let vote_delta = compute_vote_delta(&stored_user_vote, new_vote);
+            
+            let vote_count = vote_counts_map.entry(count_criteria).or_insert_with(|| VotesCount {
+                object_id: new_vote.object_id,
+                object_type: new_vote.object_type,
+                space_id: new_vote.space_id,
+                upvotes: 0,
+                downvotes: 0,
+            });
+            
+            vote_count.upvotes += vote_delta.upvotes;
+            vote_count.downvotes += vote_delta.downvotes;
+            block_vote_counts.insert(count_criteria, vote_count.clone());
+
+            apply_leaderboard_deltas(&mut leaderboard_deltas, stored_user_vote, new_vote);
+
+            // Later blocks of the batch build on top of this vote
+            stored_user_votes_map.insert(vote_criteria, new_vote.clone());
+        }
+
+        let leaderboard_deltas = leaderboard_deltas
+            .into_values()
+            .filter(|delta| delta.upvotes != 0 || delta.downvotes != 0)
+            .collect();
 
-    VotesDelta { upvotes, downvotes }
+        block_updates.push((block_vote_counts.into_values().collect(), leaderboard_deltas));
+    }
+
+    block_updates
 }
 
-async fn save_cursor(cursor: &str, block_number: &i64, cursor_repository: &dyn CursorRepository) -> Result<(), OrchestratorError> {
-    if let Err(e) = cursor_repository.save_cursor("actions_indexer", cursor, block_number).await {
-        eprintln!("Failed to save cursor to database: {:?}", e);
-        return Err(OrchestratorError::from(e));
+/// This method accumulates the leaderboard changes caused by a single user vote
+///
+/// Leaderboards count the current votes by the window containing their `voted_at`
+/// timestamp, so the saved vote is taken out of its window and the new vote is added
+/// to its own, each with its own weight. When both fall in the same window the changes
+/// cancel out except for the vote value and weight change.
+///
+/// # Arguments
+///
+/// * `deltas` - The deltas accumulated so far, keyed by object, space and window
+/// * `saved_vote` - The previously stored vote of the user, if any
+/// * `new_vote` - The latest vote of the user
+///
+fn apply_leaderboard_deltas(deltas: &mut HashMap<LeaderboardKey, LeaderboardDelta>, saved_vote: Option<&UserVote>, new_vote: &UserVote) {
+    let changes = saved_vote
+        .map(|vote| (vote, -1))
+        .into_iter()
+        .chain(std::iter::once((new_vote, 1)));
+
+    for (vote, sign) in changes {
+        let (upvotes, downvotes) = vote_contribution(vote);
+        if upvotes == 0 && downvotes == 0 {
+            continue;
+        }
+
+        for window in LeaderboardWindow::ALL {
+            let window_start = window.window_start(vote.voted_at);
+            let key = (vote.object_id, vote.space_id, vote.object_type, window, window_start);
+
+            let delta = deltas.entry(key).or_insert_with(|| LeaderboardDelta {
+                object_id: vote.object_id,
+                space_id: vote.space_id,
+                object_type: vote.object_type,
+                window,
+                window_start,
+                upvotes: 0,
+                downvotes: 0,
+            });
+
+            delta.upvotes += sign * upvotes;
+            delta.downvotes += sign * downvotes;
+        }
+    }
+}
+
+fn compute_vote_delta(saved_vote: &Option<&UserVote>, new_vote: &UserVote) -> VotesDelta {
+    let (saved_upvotes, saved_downvotes) = saved_vote.map_or((0, 0), vote_contribution);
+    let (new_upvotes, new_downvotes) = vote_contribution(new_vote);
+
+    VotesDelta {
+        upvotes: new_upvotes - saved_upvotes,
+        downvotes: new_downvotes - saved_downvotes,
+    }
+}
+
+/// The upvotes and downvotes a user vote adds to the counts of its object.
+fn vote_contribution(vote: &UserVote) -> (i64, i64) {
+    let weight = vote.weight as i64;
+
+    match vote.vote_type {
+        VoteValue::Up => (weight, 0),
+        VoteValue::Down => (0, weight),
+        VoteValue::Remove => (0, 0),
     }
-    Ok(())
 }
 
 #[cfg(test)]    
@@ -292,6 +1090,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Up,
             voted_at: 1713859200,
+            weight: 1,
         };
         
         let new_vote = UserVote {
@@ -301,6 +1100,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Down,
             voted_at: 1713859200,
+            weight: 1,
         };
         
         let votes_changes = compute_vote_delta(&Some(&prev_vote), &new_vote);
@@ -317,6 +1117,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Up,
             voted_at: 1713859200,
+            weight: 1,
         };
         
         let new_vote = UserVote {
@@ -326,6 +1127,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Remove,
             voted_at: 1713859200,
+            weight: 1,
         };
         
         let votes_changes = compute_vote_delta(&Some(&prev_vote), &new_vote);
@@ -342,6 +1144,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Down,
             voted_at: 1713859200,
+            weight: 1,
         };
         
         let new_vote = UserVote {
@@ -351,6 +1154,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Up,
             voted_at: 1713859200,
+            weight: 1,
         };
         
         let votes_changes = compute_vote_delta(&Some(&prev_vote), &new_vote);
@@ -367,6 +1171,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Down,
             voted_at: 1713859200,
+            weight: 1,
         };
 
         let new_vote = UserVote {
@@ -376,6 +1181,7 @@ mod tests {
             space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
             vote_type: VoteValue::Remove,
             voted_at: 1713859200,
+            weight: 1,
         };
 
         let votes_changes = compute_vote_delta(&Some(&prev_vote), &new_vote);
@@ -404,6 +1210,8 @@ mod tests {
             block_timestamp: 1713859200,
             tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
             object_type: ObjectType::Entity,
+            log_index: None,
+            output_position: None,
         };
 
         let vote = Vote {
@@ -412,7 +1220,7 @@ mod tests {
         };
 
         let votes = vec![vote];
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
 
         assert_eq!(user_votes.len(), 1);
         assert_eq!(user_votes[0].user_id, dead_address());
@@ -425,7 +1233,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_latest_user_votes_empty_input() {
         let votes: Vec<Vote> = Vec::new();
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
         assert!(user_votes.is_empty());
     }
 
@@ -446,6 +1254,8 @@ mod tests {
             block_timestamp: 1713859200,
             tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
             object_type: ObjectType::Entity,
+            log_index: None,
+            output_position: None,
         };
 
         // First vote (older)
@@ -468,7 +1278,7 @@ mod tests {
         };
 
         let votes = vec![vote1, vote2.clone()];
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
 
         // Should only return one vote (the latest one)
         assert_eq!(user_votes.len(), 1);
@@ -501,6 +1311,8 @@ mod tests {
                 block_timestamp: 1713859200,
                 tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Up,
         };
@@ -518,12 +1330,14 @@ mod tests {
                 block_timestamp: 1713859300,
                 tx_hash: TxHash::from_hex("0x6538dbff9d04388e9ac36264cf493b8c96e05421e59ead18b6e6547bc3d72fc5").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Down,
         };
 
         let votes = vec![vote1, vote2];
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
 
         // Should return both votes since they are from different users
         assert_eq!(user_votes.len(), 2);
@@ -561,6 +1375,8 @@ mod tests {
                 block_timestamp: 1713859200,
                 tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Up,
         };
@@ -578,12 +1394,14 @@ mod tests {
                 block_timestamp: 1713859300,
                 tx_hash: TxHash::from_hex("0x6538dbff9d04388e9ac36264cf493b8c96e05421e59ead18b6e6547bc3d72fc5").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Remove,
         };
 
         let votes = vec![vote1, vote2];
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
 
         // Should return both votes since they are for different entities
         assert_eq!(user_votes.len(), 2);
@@ -622,6 +1440,8 @@ mod tests {
                 block_timestamp: 1713859200,
                 tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Up,
         };
@@ -639,6 +1459,8 @@ mod tests {
                 block_timestamp: 1713859300,
                 tx_hash: TxHash::from_hex("0x6538dbff9d04388e9ac36264cf493b8c96e05421e59ead18b6e6547bc3d72fc5").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Down,
         };
@@ -656,12 +1478,14 @@ mod tests {
                 block_timestamp: 1713859400,
                 tx_hash: TxHash::from_hex("0x7649ec009e05499f9bd47274ef4e73a6f7b24126f79ead19c6e6648cd4e83af6").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Remove,
         };
 
         let votes = vec![upvote, downvote, remove_vote];
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
 
         // Should return all three votes since they are from different users
         assert_eq!(user_votes.len(), 3);
@@ -699,6 +1523,8 @@ mod tests {
                 block_timestamp: 1713859200,
                 tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Up,
         };
@@ -716,12 +1542,14 @@ mod tests {
                 block_timestamp: 1713859300,
                 tx_hash: TxHash::from_hex("0x6538dbff9d04388e9ac36264cf493b8c96e05421e59ead18b6e6547bc3d72fc5").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Down,
         };
 
         let votes = vec![vote1, vote2];
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
 
         // Should return both votes since they are for different spaces
         assert_eq!(user_votes.len(), 2);
@@ -758,6 +1586,8 @@ mod tests {
                 block_timestamp: 1713859200,
                 tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
                 object_type: ObjectType::Entity,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Up,
         };
@@ -774,13 +1604,15 @@ mod tests {
                 block_number: 1,
                 block_timestamp: 1713859200,
                 tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
-                object_type: ObjectType::Relation, // Different object type
+                object_type: ObjectType::Relation, // Different object type,
+                log_index: None,
+                output_position: None,
             },
             vote: VoteValue::Up,
         };
 
         let votes = vec![vote1, vote2];
-        let user_votes = get_latest_user_votes(&votes);
+        let user_votes = get_latest_user_votes(&votes, &ConstantVoteWeight::default());
 
         // Should return both votes since they are for different object types
         assert_eq!(user_votes.len(), 2);
@@ -822,6 +1654,33 @@ mod tests {
             unimplemented!()
         }
 
+        async fn persist_changesets(&self, _changesets: &[Changeset<'_>]) -> Result<(), actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn persist_changesets_with_cursor(&self, _changesets: &[Changeset<'_>], _id: &str, _cursor: &str, _block_number: i64) -> Result<(), actions_indexer_repository::errors::ActionsRepositoryError> {
+            Ok(())
+        }
+
+        async fn rollback_to(&self, _block_number: u64) -> Result<(), actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn rollback_to_with_cursor(&self, _block_number: u64, _id: &str, _cursor: &str) -> Result<(), actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_persisted_action_keys(&self, action_keys: &[ActionKey]) -> Result<Vec<ActionKey>, actions_indexer_repository::errors::ActionsRepositoryError> {
+            // Actions at even indexes are already persisted
+            Ok(action_keys
+                .iter()
+                .filter(|(_, index)| match index {
+                    ActionIndex::Log(index) | ActionIndex::OutputPosition(index) => index % 2 == 0,
+                })
+                .cloned()
+                .collect())
+        }
+
         async fn get_user_votes(&self, _vote_criteria: &[VoteCriteria]) -> Result<Vec<UserVote>, actions_indexer_repository::errors::ActionsRepositoryError> {
             Ok(self.stored_user_votes.clone())
         }
@@ -830,9 +1689,50 @@ mod tests {
             Ok(self.stored_vote_counts.clone())
         }
 
-        async fn check_tables_created(&self) -> Result<bool, actions_indexer_repository::errors::ActionsRepositoryError> {
+        async fn update_leaderboards(&self, _leaderboard_deltas: &[LeaderboardDelta]) -> Result<(), actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_leaderboard(&self, _space_id: &SpaceId, _window: LeaderboardWindow, _timestamp: u64, _limit: i64) -> Result<Vec<actions_indexer_shared::types::LeaderboardEntry>, actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_object_votes(&self, _object_id: &ObjectId, _space_id: &SpaceId, _object_type: ObjectType, _limit: i64, _offset: i64) -> Result<Vec<UserVote>, actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_votes_by_user(&self, _user_id: &Address, _limit: i64, _offset: i64) -> Result<Vec<UserVote>, actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_top_objects(&self, _space_id: &SpaceId, _limit: i64, _offset: i64) -> Result<Vec<VotesCount>, actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn insert_dead_letters(&self, _dead_letters: &[DeadLetter]) -> Result<(), actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn get_pending_dead_letters(&self, _after: Option<i64>, _limit: i64) -> Result<Vec<(i64, DeadLetter)>, actions_indexer_repository::errors::ActionsRepositoryError> {
             unimplemented!()
         }
+
+        async fn mark_dead_letters_reprocessed(&self, _ids: &[i64]) -> Result<(), actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn reconcile_vote_counts(&self, _after: Option<VoteCountCriteria>, _limit: i64) -> Result<actions_indexer_shared::types::VotesCountReconciliation, actions_indexer_repository::errors::ActionsRepositoryError> {
+            unimplemented!()
+        }
+
+        async fn check_tables_created(&self) -> Result<bool, actions_indexer_repository::errors::ActionsRepositoryError> {
+            Ok(true)
+        }
+    }
+
+    async fn update_block_vote_counts(user_votes: &[UserVote], repo: &dyn ActionsRepository) -> Result<(Vec<VotesCount>, Vec<LeaderboardDelta>), OrchestratorError> {
+        let mut block_updates = update_vote_counts(&[user_votes.to_vec()], repo).await?;
+        Ok(block_updates.remove(0))
     }
 
     #[tokio::test]
@@ -843,10 +1743,10 @@ mod tests {
         };
 
         let user_votes: Vec<UserVote> = vec![];
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 0);
     }
 
@@ -868,12 +1768,13 @@ mod tests {
             space_id,
             vote_type: VoteValue::Up,
             voted_at: 1713859200,
+            weight: 1,
         }];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 1);
         assert_eq!(vote_counts[0].object_id, object_id);
         assert_eq!(vote_counts[0].space_id, space_id);
@@ -896,6 +1797,7 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Up,
                 voted_at: 1713859100,
+                weight: 1,
             }],
             stored_vote_counts: vec![VotesCount {
                 object_id,
@@ -913,12 +1815,13 @@ mod tests {
             space_id,
             vote_type: VoteValue::Down,
             voted_at: 1713859200,
+            weight: 1,
         }];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 1);
         assert_eq!(vote_counts[0].upvotes, 4); // 5 - 1
         assert_eq!(vote_counts[0].downvotes, 3); // 2 + 1
@@ -938,6 +1841,7 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Down,
                 voted_at: 1713859100,
+                weight: 1,
             }],
             stored_vote_counts: vec![VotesCount {
                 object_id,
@@ -955,12 +1859,13 @@ mod tests {
             space_id,
             vote_type: VoteValue::Up,
             voted_at: 1713859200,
+            weight: 1,
         }];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 1);
         assert_eq!(vote_counts[0].upvotes, 4); // 3 + 1
         assert_eq!(vote_counts[0].downvotes, 6); // 7 - 1
@@ -980,6 +1885,7 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Up,
                 voted_at: 1713859100,
+                weight: 1,
             }],
             stored_vote_counts: vec![VotesCount {
                 object_id,
@@ -997,12 +1903,13 @@ mod tests {
             space_id,
             vote_type: VoteValue::Remove,
             voted_at: 1713859200,
+            weight: 1,
         }];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 1);
         assert_eq!(vote_counts[0].upvotes, 9); // 10 - 1
         assert_eq!(vote_counts[0].downvotes, 5); // unchanged
@@ -1028,6 +1935,7 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Up,
                 voted_at: 1713859200,
+                weight: 1,
             },
             UserVote {
                 user_id: user2,
@@ -1036,13 +1944,14 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Down,
                 voted_at: 1713859200,
+                weight: 1,
             },
         ];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 1);
         assert_eq!(vote_counts[0].object_id, object_id);
         assert_eq!(vote_counts[0].upvotes, 1);
@@ -1069,6 +1978,7 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Up,
                 voted_at: 1713859200,
+                weight: 1,
             },
             UserVote {
                 user_id: user,
@@ -1077,13 +1987,14 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Up,
                 voted_at: 1713859200,
+                weight: 1,
             },
         ];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 2);
         
         let object1_count = vote_counts.iter().find(|v| v.object_id == object1).unwrap();
@@ -1109,6 +2020,7 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Up,
                 voted_at: 1713859100,
+                weight: 1,
             }],
             stored_vote_counts: vec![VotesCount {
                 object_id,
@@ -1126,17 +2038,60 @@ mod tests {
             space_id,
             vote_type: VoteValue::Up, // Same vote type
             voted_at: 1713859200,
+            weight: 1,
         }];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 1);
         assert_eq!(vote_counts[0].upvotes, 5); // No change
         assert_eq!(vote_counts[0].downvotes, 2); // No change
     }
 
+    #[tokio::test]
+    async fn test_update_vote_counts_ignores_vote_older_than_stored() {
+        let user = dead_address();
+        let object_id = uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5");
+        let space_id = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![UserVote {
+                user_id: user,
+                object_id,
+                object_type: ObjectType::Entity,
+                space_id,
+                vote_type: VoteValue::Up,
+                voted_at: 1713859200,
+                weight: 1,
+            }],
+            stored_vote_counts: vec![VotesCount {
+                object_id,
+                object_type: ObjectType::Entity,
+                space_id,
+                upvotes: 5,
+                downvotes: 2,
+            }],
+        };
+
+        // Replayed from a dead letter, after the stored vote was cast
+        let user_votes = vec![UserVote {
+            user_id: user,
+            object_id,
+            object_type: ObjectType::Entity,
+            space_id,
+            vote_type: VoteValue::Down,
+            voted_at: 1713859100,
+            weight: 1,
+        }];
+
+        let (vote_counts, leaderboard_deltas) = update_block_vote_counts(&user_votes, &mock_repo).await.unwrap();
+
+        assert!(vote_counts.is_empty());
+        assert!(leaderboard_deltas.is_empty());
+    }
+
     #[tokio::test]
     async fn test_update_vote_counts_different_object_types() {
         let user = dead_address();
@@ -1156,6 +2111,7 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Up,
                 voted_at: 1713859200,
+                weight: 1,
             },
             UserVote {
                 user_id: user,
@@ -1164,13 +2120,14 @@ mod tests {
                 space_id,
                 vote_type: VoteValue::Down,
                 voted_at: 1713859200,
+                weight: 1,
             },
         ];
 
-        let result = update_vote_counts(&user_votes, &mock_repo).await;
+        let result = update_block_vote_counts(&user_votes, &mock_repo).await;
 
         assert!(result.is_ok());
-        let vote_counts = result.unwrap();
+        let (vote_counts, _) = result.unwrap();
         assert_eq!(vote_counts.len(), 2);
         
         let entity_count = vote_counts.iter().find(|v| v.object_type == ObjectType::Entity).unwrap();
@@ -1181,4 +2138,754 @@ mod tests {
         assert_eq!(relation_count.upvotes, 0);
         assert_eq!(relation_count.downvotes, 1);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_update_vote_counts_batch_applies_blocks_in_order() {
+        let user = dead_address();
+        let object_id = uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5");
+        let space_id = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![],
+            stored_vote_counts: vec![],
+        };
+
+        let vote = |vote_type: VoteValue, voted_at: u64| UserVote {
+            user_id: user,
+            object_id,
+            object_type: ObjectType::Entity,
+            space_id,
+            vote_type,
+            voted_at,
+            weight: 1,
+        };
+        let block_user_votes = vec![
+            vec![vote(VoteValue::Up, 1713859200)],
+            vec![],
+            vec![vote(VoteValue::Down, 1713859260)],
+        ];
+
+        let block_updates = update_vote_counts(&block_user_votes, &mock_repo).await.unwrap();
+        assert_eq!(block_updates.len(), 3);
+
+        let (first_counts, first_deltas) = &block_updates[0];
+        assert_eq!(first_counts.len(), 1);
+        assert_eq!(first_counts[0].upvotes, 1);
+        assert_eq!(first_counts[0].downvotes, 0);
+        assert!(first_deltas.iter().all(|d| d.upvotes == 1 && d.downvotes == 0));
+
+        let (second_counts, second_deltas) = &block_updates[1];
+        assert!(second_counts.is_empty());
+        assert!(second_deltas.is_empty());
+
+        // The downvote replaces the upvote of the first block, not a stored vote
+        let (third_counts, third_deltas) = &block_updates[2];
+        assert_eq!(third_counts.len(), 1);
+        assert_eq!(third_counts[0].upvotes, 0);
+        assert_eq!(third_counts[0].downvotes, 1);
+        assert_eq!(third_deltas.len(), LeaderboardWindow::ALL.len());
+        assert!(third_deltas.iter().all(|d| d.upvotes == -1 && d.downvotes == 1));
+    }
+
+    #[tokio::test]
+    async fn test_update_vote_counts_merges_shards() {
+        let space_id = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![],
+            stored_vote_counts: vec![],
+        };
+
+        let votes = |vote_type: VoteValue| {
+            let mut votes = Vec::new();
+            for object in 0..20u128 {
+                for user in 0..100u8 {
+                    votes.push(UserVote {
+                        user_id: Address::with_last_byte(user),
+                        object_id: uuid::Uuid::from_u128(object),
+                        object_type: ObjectType::Entity,
+                        space_id,
+                        vote_type: vote_type.clone(),
+                        voted_at: 1713859200,
+                        weight: 1,
+                    });
+                }
+            }
+            votes
+        };
+        let block_user_votes = vec![votes(VoteValue::Up), votes(VoteValue::Down)];
+
+        // 4000 votes are split into 8 shards
+        let shards = shard_user_votes(&block_user_votes, MAX_VOTE_SHARDS);
+        assert_eq!(shards.iter().flatten().flatten().count(), 4000);
+        for object in 0..20u128 {
+            let object_id = uuid::Uuid::from_u128(object);
+            let holding = shards.iter().filter(|shard| shard.iter().flatten().any(|vote| vote.object_id == object_id)).count();
+            assert_eq!(holding, 1);
+        }
+
+        let block_updates = update_vote_counts(&block_user_votes, &mock_repo).await.unwrap();
+        assert_eq!(block_updates.len(), 2);
+
+        let (first_counts, _) = &block_updates[0];
+        assert_eq!(first_counts.len(), 20);
+        assert!(first_counts.iter().all(|count| count.upvotes == 100 && count.downvotes == 0));
+
+        // Each shard carries its own votes over to the next block
+        let (second_counts, second_deltas) = &block_updates[1];
+        assert_eq!(second_counts.len(), 20);
+        assert!(second_counts.iter().all(|count| count.upvotes == 0 && count.downvotes == 100));
+        assert_eq!(second_deltas.len(), 20 * LeaderboardWindow::ALL.len());
+    }
+
+    #[tokio::test]
+    async fn test_update_vote_counts_weighted_votes() {
+        let user = dead_address();
+        let object_id = uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5");
+        let space_id = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![UserVote {
+                user_id: user,
+                object_id,
+                object_type: ObjectType::Entity,
+                space_id,
+                vote_type: VoteValue::Up,
+                voted_at: 1713859200,
+                weight: 2,
+            }],
+            stored_vote_counts: vec![VotesCount {
+                object_id,
+                object_type: ObjectType::Entity,
+                space_id,
+                upvotes: 2,
+                downvotes: 0,
+            }],
+        };
+
+        // The stored upvote is taken back with its own weight, not the new one
+        let user_votes = vec![UserVote {
+            user_id: user,
+            object_id,
+            object_type: ObjectType::Entity,
+            space_id,
+            vote_type: VoteValue::Down,
+            voted_at: 1713859260,
+            weight: 3,
+        }];
+
+        let (vote_counts, leaderboard_deltas) = update_block_vote_counts(&user_votes, &mock_repo).await.unwrap();
+
+        assert_eq!(vote_counts.len(), 1);
+        assert_eq!(vote_counts[0].upvotes, 0);
+        assert_eq!(vote_counts[0].downvotes, 3);
+        assert_eq!(leaderboard_deltas.len(), LeaderboardWindow::ALL.len());
+        assert!(leaderboard_deltas.iter().all(|d| d.upvotes == -2 && d.downvotes == 3));
+    }
+
+    #[test]
+    fn test_get_latest_user_votes_records_weight() {
+        struct SenderWeight;
+
+        impl VoteWeightProvider for SenderWeight {
+            fn weight(&self, vote: &Vote) -> u64 {
+                if vote.raw.sender == dead_address() { 10 } else { 1 }
+            }
+        }
+
+        let raw_action = ActionRaw {
+            action_type: ActionType::Vote,
+            action_version: 1,
+            sender: dead_address(),
+            object_id: uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5"),
+            group_id: None,
+            space_pov: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
+            metadata: None,
+            block_number: 1,
+            block_timestamp: 1713859200,
+            tx_hash: Default::default(),
+            object_type: ObjectType::Entity,
+            log_index: None,
+            output_position: None,
+        };
+        let votes = vec![
+            Vote { raw: raw_action.clone(), vote: VoteValue::Up },
+            Vote { raw: ActionRaw { sender: Address::ZERO, ..raw_action }, vote: VoteValue::Up },
+        ];
+
+        let user_votes = get_latest_user_votes(&votes, &SenderWeight);
+
+        assert_eq!(user_votes.len(), 2);
+        assert!(user_votes.iter().all(|v| v.weight == if v.user_id == dead_address() { 10 } else { 1 }));
+        assert!(get_latest_user_votes(&votes, &ConstantVoteWeight::default()).iter().all(|v| v.weight == 1));
+    }
+
+    #[test]
+    fn test_pending_batch_flush_limits() {
+        let batch_config = BatchConfig {
+            max_blocks: 3,
+            max_actions: 2,
+            flush_interval: Duration::from_secs(1),
+        };
+        let block = |actions: Vec<ActionRaw>| BlockDataMessage {
+            actions,
+            dead_letters: Vec::new(),
+            cursor: "cursor".to_string(),
+            block_number: 1,
+            final_block_number: 0,
+        };
+        let action = ActionRaw {
+            action_type: ActionType::Vote,
+            action_version: 1,
+            sender: dead_address(),
+            object_id: uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5"),
+            group_id: None,
+            space_pov: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
+            metadata: None,
+            block_number: 1,
+            block_timestamp: 1713859200,
+            tx_hash: Default::default(),
+            object_type: ObjectType::Entity,
+            log_index: None,
+            output_position: None,
+        };
+
+        let mut batch = PendingBatch::default();
+        batch.push(block(vec![]), batch_config.flush_interval);
+        batch.push(block(vec![]), batch_config.flush_interval);
+        assert!(batch.deadline.is_some());
+        assert!(!batch.is_full(&batch_config));
+        batch.push(block(vec![]), batch_config.flush_interval);
+        assert!(batch.is_full(&batch_config));
+
+        assert_eq!(batch.take().len(), 3);
+        assert!(batch.deadline.is_none());
+
+        batch.push(block(vec![action.clone(), action]), batch_config.flush_interval);
+        assert!(batch.is_full(&batch_config));
+    }
+
+    // ============================================================================
+    // skip_persisted_actions Tests
+    // ============================================================================
+
+    #[tokio::test]
+    async fn test_skip_persisted_actions() {
+        use alloy::primitives::TxHash;
+
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![],
+            stored_vote_counts: vec![],
+        };
+        let raw_action = |log_index, output_position| ActionRaw {
+            action_type: ActionType::Vote,
+            action_version: 1,
+            sender: dead_address(),
+            object_id: uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5"),
+            group_id: None,
+            space_pov: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
+            metadata: None,
+            block_number: 1,
+            block_timestamp: 1713859200,
+            tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
+            object_type: ObjectType::Entity,
+            log_index,
+            output_position,
+        };
+
+        let actions = vec![
+            raw_action(Some(0), None),
+            raw_action(Some(1), None),
+            raw_action(None, None),
+            raw_action(Some(2), None),
+            raw_action(None, Some(4)),
+            raw_action(None, Some(5)),
+        ];
+        let result = skip_persisted_actions(actions, &mock_repo).await.unwrap();
+
+        // The mock stores even indexes, and actions without any are always kept
+        assert_eq!(
+            result.iter().map(|action| (action.log_index, action.output_position)).collect::<Vec<_>>(),
+            vec![(Some(1), None), (None, None), (None, Some(5))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_skip_persisted_actions_empty_input() {
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![],
+            stored_vote_counts: vec![],
+        };
+
+        let result = skip_persisted_actions(Vec::new(), &mock_repo).await.unwrap();
+
+        assert!(result.is_empty());
+    }
+
+    // ============================================================================
+    // Leaderboard Tests
+    // ============================================================================
+
+    #[test]
+    fn test_leaderboard_window_start() {
+        // Tuesday 2024-04-23 08:00:00 UTC
+        let timestamp = 1713859200;
+
+        assert_eq!(LeaderboardWindow::Day.window_start(timestamp), 1713830400);
+        assert_eq!(LeaderboardWindow::Week.window_start(timestamp), 1713744000);
+        assert_eq!(LeaderboardWindow::Month.window_start(timestamp), 1711929600);
+        assert_eq!(LeaderboardWindow::AllTime.window_start(timestamp), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_vote_counts_new_upvote_leaderboard_deltas() {
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![],
+            stored_vote_counts: vec![],
+        };
+
+        let user_votes = vec![UserVote {
+            user_id: dead_address(),
+            object_id: uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5"),
+            object_type: ObjectType::Entity,
+            space_id: uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b"),
+            vote_type: VoteValue::Up,
+            voted_at: 1713859200,
+            weight: 1,
+        }];
+
+        let (_, leaderboard_deltas) = update_block_vote_counts(&user_votes, &mock_repo).await.unwrap();
+
+        // One upvote in each window
+        assert_eq!(leaderboard_deltas.len(), LeaderboardWindow::ALL.len());
+        for window in LeaderboardWindow::ALL {
+            let delta = leaderboard_deltas.iter().find(|d| d.window == window).unwrap();
+            assert_eq!(delta.window_start, window.window_start(1713859200));
+            assert_eq!(delta.upvotes, 1);
+            assert_eq!(delta.downvotes, 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_vote_counts_revote_moves_leaderboard_window() {
+        let user = dead_address();
+        let object_id = uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5");
+        let space_id = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+        // Thursday 2024-03-21, a month before the new vote
+        let previous_voted_at = 1711000000;
+
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![UserVote {
+                user_id: user,
+                object_id,
+                object_type: ObjectType::Entity,
+                space_id,
+                vote_type: VoteValue::Up,
+                voted_at: previous_voted_at,
+                weight: 1,
+            }],
+            stored_vote_counts: vec![],
+        };
+
+        let user_votes = vec![UserVote {
+            user_id: user,
+            object_id,
+            object_type: ObjectType::Entity,
+            space_id,
+            vote_type: VoteValue::Up,
+            voted_at: 1713859200,
+            weight: 1,
+        }];
+
+        let (_, leaderboard_deltas) = update_block_vote_counts(&user_votes, &mock_repo).await.unwrap();
+
+        // The all-time window cancels out, the others move the upvote to the new window
+        assert_eq!(leaderboard_deltas.len(), 6);
+        assert!(leaderboard_deltas.iter().all(|d| d.window != LeaderboardWindow::AllTime));
+
+        for window in [LeaderboardWindow::Day, LeaderboardWindow::Week, LeaderboardWindow::Month] {
+            let removed = leaderboard_deltas.iter()
+                .find(|d| d.window == window && d.window_start == window.window_start(previous_voted_at))
+                .unwrap();
+            let added = leaderboard_deltas.iter()
+                .find(|d| d.window == window && d.window_start == window.window_start(1713859200))
+                .unwrap();
+
+            assert_eq!(removed.upvotes, -1);
+            assert_eq!(added.upvotes, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_vote_counts_upvote_to_downvote_leaderboard_deltas() {
+        let user = dead_address();
+        let object_id = uuid!("a7ef0016-a2f4-44fb-82ca-a4f5c61d2cf5");
+        let space_id = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+
+        let mock_repo = MockActionsRepository {
+            stored_user_votes: vec![UserVote {
+                user_id: user,
+                object_id,
+                object_type: ObjectType::Entity,
+                space_id,
+                vote_type: VoteValue::Up,
+                voted_at: 1713859200,
+                weight: 1,
+            }],
+            stored_vote_counts: vec![],
+        };
+
+        let user_votes = vec![UserVote {
+            user_id: user,
+            object_id,
+            object_type: ObjectType::Entity,
+            space_id,
+            vote_type: VoteValue::Down,
+            voted_at: 1713859260,
+            weight: 1,
+        }];
+
+        let (_, leaderboard_deltas) = update_block_vote_counts(&user_votes, &mock_repo).await.unwrap();
+
+        assert_eq!(leaderboard_deltas.len(), LeaderboardWindow::ALL.len());
+        assert!(leaderboard_deltas.iter().all(|d| d.upvotes == -1 && d.downvotes == 1));
+    }
+
+    // ============================================================================
+    // Shutdown Tests
+    // ============================================================================
+
+    struct MockCursorRepository;
+
+    #[async_trait::async_trait]
+    impl actions_indexer_repository::CursorRepository for MockCursorRepository {
+        async fn get_cursor(&self, _id: &str) -> Result<Option<String>, actions_indexer_repository::errors::CursorRepositoryError> {
+            Ok(None)
+        }
+
+        async fn save_cursor(&self, _id: &str, _cursor: &str, _block_number: &i64) -> Result<(), actions_indexer_repository::errors::CursorRepositoryError> {
+            unimplemented!()
+        }
+    }
+
+    /// Sends empty blocks, then either ends or stays open like a stream at the chain head.
+    struct MockStream {
+        blocks: i64,
+        keep_open: bool,
+        sent: std::sync::Arc<tokio::sync::Notify>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::consumer::ConsumeActionsStream for MockStream {
+        async fn stream_events(&self, sender: mpsc::Sender<StreamMessage>, _cursor: Option<String>, _start_mode: &StartMode) -> Result<(), crate::errors::ConsumerError> {
+            for block_number in 1..=self.blocks {
+                let block = BlockDataMessage {
+                    actions: Vec::new(),
+                    dead_letters: Vec::new(),
+                    cursor: format!("cursor_{block_number}"),
+                    block_number,
+                    final_block_number: 0,
+                };
+                sender.send(StreamMessage::BlockData(block)).await.unwrap();
+            }
+            self.sent.notify_one();
+            if self.keep_open {
+                std::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+    }
+
+    fn mock_orchestrator(blocks: i64, keep_open: bool, sent: std::sync::Arc<tokio::sync::Notify>) -> Orchestrator {
+        let actions_repository = MockActionsRepository {
+            stored_user_votes: vec![],
+            stored_vote_counts: vec![],
+        };
+        let loader = ActionsLoader::new(std::sync::Arc::new(actions_repository), std::sync::Arc::new(MockCursorRepository));
+        let consumer = ActionsConsumer::new(Box::new(MockStream { blocks, keep_open, sent }));
+
+        // Blocks are only flushed by the shutdown or the end of the stream
+        Orchestrator::new(Box::new(consumer), Box::new(ActionsProcessor::new()), Box::new(loader))
+            .with_batch_config(BatchConfig {
+                max_blocks: 100,
+                max_actions: 100,
+                flush_interval: Duration::from_secs(3600),
+            })
+    }
+
+    #[tokio::test]
+    async fn test_run_until_drains_pending_blocks_on_shutdown() {
+        let sent = std::sync::Arc::new(tokio::sync::Notify::new());
+        let metrics = std::sync::Arc::new(PipelineMetrics::default());
+        let orchestrator = mock_orchestrator(3, true, sent.clone()).with_metrics(metrics.clone());
+
+        let summary = orchestrator.run_until(async move { sent.notified().await }).await.unwrap();
+
+        assert_eq!(metrics.blocks_processed(), 3);
+        assert_eq!(metrics.head_block(), Some(3));
+        assert_eq!(metrics.cursor_block(), Some(3));
+        assert_eq!(metrics.stage(Stage::Consume).count, 3);
+        assert_eq!(metrics.db_transactions().count, 1);
+
+        assert_eq!(summary, RunSummary {
+            blocks: 3,
+            actions: 0,
+            last_block_number: Some(3),
+        });
+    }
+
+    #[tokio::test]
+    async fn test_run_until_stops_when_consumer_stops() {
+        let orchestrator = mock_orchestrator(2, false, std::sync::Arc::new(tokio::sync::Notify::new()));
+
+        let summary = orchestrator.run_until(std::future::pending()).await.unwrap();
+
+        assert_eq!(summary.blocks, 2);
+        assert_eq!(summary.last_block_number, Some(2));
+    }
+
+    // ============================================================================
+    // Retry Tests
+    // ============================================================================
+
+    type RepositoryResult<T> = Result<T, actions_indexer_repository::errors::ActionsRepositoryError>;
+
+    /// Fails to persist changesets with the given errors, in order, then delegates to
+    /// a `MockActionsRepository` without stored votes.
+    struct FlakyActionsRepository {
+        inner: MockActionsRepository,
+        persist_failures: std::sync::Mutex<Vec<actions_indexer_repository::errors::ActionsRepositoryError>>,
+    }
+
+    impl FlakyActionsRepository {
+        fn new(mut persist_failures: Vec<actions_indexer_repository::errors::ActionsRepositoryError>) -> Self {
+            persist_failures.reverse();
+            Self {
+                inner: MockActionsRepository {
+                    stored_user_votes: vec![],
+                    stored_vote_counts: vec![],
+                },
+                persist_failures: std::sync::Mutex::new(persist_failures),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ActionsRepository for FlakyActionsRepository {
+        async fn insert_actions(&self, actions: &[Action]) -> RepositoryResult<()> {
+            self.inner.insert_actions(actions).await
+        }
+
+        async fn update_user_votes(&self, user_votes: &[UserVote]) -> RepositoryResult<()> {
+            self.inner.update_user_votes(user_votes).await
+        }
+
+        async fn update_votes_counts(&self, votes_counts: &[VotesCount]) -> RepositoryResult<()> {
+            self.inner.update_votes_counts(votes_counts).await
+        }
+
+        async fn persist_changeset(&self, changeset: &Changeset<'_>) -> RepositoryResult<()> {
+            self.inner.persist_changeset(changeset).await
+        }
+
+        async fn persist_changesets(&self, changesets: &[Changeset<'_>]) -> RepositoryResult<()> {
+            self.inner.persist_changesets(changesets).await
+        }
+
+        async fn persist_changesets_with_cursor(&self, changesets: &[Changeset<'_>], id: &str, cursor: &str, block_number: i64) -> RepositoryResult<()> {
+            if let Some(e) = self.persist_failures.lock().unwrap().pop() {
+                return Err(e);
+            }
+            self.inner.persist_changesets_with_cursor(changesets, id, cursor, block_number).await
+        }
+
+        async fn rollback_to(&self, block_number: u64) -> RepositoryResult<()> {
+            self.inner.rollback_to(block_number).await
+        }
+
+        async fn rollback_to_with_cursor(&self, block_number: u64, id: &str, cursor: &str) -> RepositoryResult<()> {
+            self.inner.rollback_to_with_cursor(block_number, id, cursor).await
+        }
+
+        async fn get_persisted_action_keys(&self, action_keys: &[ActionKey]) -> RepositoryResult<Vec<ActionKey>> {
+            self.inner.get_persisted_action_keys(action_keys).await
+        }
+
+        async fn get_user_votes(&self, vote_criteria: &[VoteCriteria]) -> RepositoryResult<Vec<UserVote>> {
+            self.inner.get_user_votes(vote_criteria).await
+        }
+
+        async fn get_vote_counts(&self, vote_criteria: &[VoteCountCriteria]) -> RepositoryResult<Vec<VotesCount>> {
+            self.inner.get_vote_counts(vote_criteria).await
+        }
+
+        async fn update_leaderboards(&self, leaderboard_deltas: &[LeaderboardDelta]) -> RepositoryResult<()> {
+            self.inner.update_leaderboards(leaderboard_deltas).await
+        }
+
+        async fn get_leaderboard(&self, space_id: &SpaceId, window: LeaderboardWindow, timestamp: u64, limit: i64) -> RepositoryResult<Vec<actions_indexer_shared::types::LeaderboardEntry>> {
+            self.inner.get_leaderboard(space_id, window, timestamp, limit).await
+        }
+
+        async fn get_object_votes(&self, object_id: &ObjectId, space_id: &SpaceId, object_type: ObjectType, limit: i64, offset: i64) -> RepositoryResult<Vec<UserVote>> {
+            self.inner.get_object_votes(object_id, space_id, object_type, limit, offset).await
+        }
+
+        async fn get_votes_by_user(&self, user_id: &Address, limit: i64, offset: i64) -> RepositoryResult<Vec<UserVote>> {
+            self.inner.get_votes_by_user(user_id, limit, offset).await
+        }
+
+        async fn get_top_objects(&self, space_id: &SpaceId, limit: i64, offset: i64) -> RepositoryResult<Vec<VotesCount>> {
+            self.inner.get_top_objects(space_id, limit, offset).await
+        }
+
+        async fn insert_dead_letters(&self, dead_letters: &[DeadLetter]) -> RepositoryResult<()> {
+            self.inner.insert_dead_letters(dead_letters).await
+        }
+
+        async fn get_pending_dead_letters(&self, after: Option<i64>, limit: i64) -> RepositoryResult<Vec<(i64, DeadLetter)>> {
+            self.inner.get_pending_dead_letters(after, limit).await
+        }
+
+        async fn mark_dead_letters_reprocessed(&self, ids: &[i64]) -> RepositoryResult<()> {
+            self.inner.mark_dead_letters_reprocessed(ids).await
+        }
+
+        async fn reconcile_vote_counts(&self, after: Option<VoteCountCriteria>, limit: i64) -> RepositoryResult<actions_indexer_shared::types::VotesCountReconciliation> {
+            self.inner.reconcile_vote_counts(after, limit).await
+        }
+
+        async fn check_tables_created(&self) -> RepositoryResult<bool> {
+            self.inner.check_tables_created().await
+        }
+    }
+
+    /// Sends one block per call, reporting a stream error after the first `failures`
+    /// blocks like a dropped connection, and records the cursor of every call.
+    struct FlakyStream {
+        blocks: i64,
+        failures: i64,
+        cursors: std::sync::Arc<std::sync::Mutex<Vec<Option<String>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::consumer::ConsumeActionsStream for FlakyStream {
+        async fn stream_events(&self, sender: mpsc::Sender<StreamMessage>, cursor: Option<String>, _start_mode: &StartMode) -> Result<(), crate::errors::ConsumerError> {
+            let block_number = {
+                let mut cursors = self.cursors.lock().unwrap();
+                cursors.push(cursor);
+                cursors.len() as i64
+            };
+            sender.send(StreamMessage::BlockData(BlockDataMessage {
+                actions: Vec::new(),
+                dead_letters: Vec::new(),
+                cursor: format!("cursor_{block_number}"),
+                block_number,
+                final_block_number: 0,
+            })).await.unwrap();
+
+            if block_number <= self.failures {
+                sender.send(StreamMessage::Error(crate::errors::ConsumerError::StreamingError("connection reset".to_string()))).await.unwrap();
+            } else if block_number < self.blocks {
+                return Err(crate::errors::ConsumerError::ReadingPackage("invalid package".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    fn retry_orchestrator(actions_repository: impl ActionsRepository + 'static, stream: impl crate::consumer::ConsumeActionsStream + 'static) -> Orchestrator {
+        let loader = ActionsLoader::new(std::sync::Arc::new(actions_repository), std::sync::Arc::new(MockCursorRepository));
+        let consumer = ActionsConsumer::new(Box::new(stream));
+        let policy = RetryPolicy {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        Orchestrator::new(Box::new(consumer), Box::new(ActionsProcessor::new()), Box::new(loader))
+            .with_retry_config(RetryConfig { consumer: policy, loader: policy })
+    }
+
+    fn stream(blocks: i64) -> MockStream {
+        MockStream { blocks, keep_open: false, sent: std::sync::Arc::new(tokio::sync::Notify::new()) }
+    }
+
+    #[tokio::test]
+    async fn test_run_until_retries_transient_persist_failures() {
+        use actions_indexer_repository::errors::ActionsRepositoryError;
+
+        let metrics = std::sync::Arc::new(PipelineMetrics::default());
+        let repository = FlakyActionsRepository::new(vec![
+            ActionsRepositoryError::DatabaseError(sqlx::Error::PoolTimedOut),
+            ActionsRepositoryError::DatabaseError(sqlx::Error::PoolTimedOut),
+        ]);
+        let orchestrator = retry_orchestrator(repository, stream(2)).with_metrics(metrics.clone());
+
+        let summary = orchestrator.run_until(std::future::pending()).await.unwrap();
+
+        assert_eq!(summary.blocks, 2);
+        assert_eq!(metrics.retries(Stage::Load), 2);
+        assert_eq!(metrics.errors(ErrorKind::Persist), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_until_aborts_on_batches_exhausting_retries() {
+        use actions_indexer_repository::errors::ActionsRepositoryError;
+
+        let metrics = std::sync::Arc::new(PipelineMetrics::default());
+        let timeouts = (0..3).map(|_| ActionsRepositoryError::DatabaseError(sqlx::Error::PoolTimedOut)).collect();
+        let orchestrator = retry_orchestrator(FlakyActionsRepository::new(timeouts), stream(2)).with_metrics(metrics.clone());
+
+        let result = orchestrator.run_until(std::future::pending()).await;
+
+        // The second block isn't persisted, so the cursor can't skip the first one
+        assert!(matches!(result, Err(OrchestratorError::Loader(_))));
+        assert_eq!(metrics.retries(Stage::Load), 2);
+        assert_eq!(metrics.errors(ErrorKind::Persist), 1);
+        assert_eq!(metrics.blocks_processed(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_until_aborts_on_fatal_persist_error() {
+        use actions_indexer_repository::errors::ActionsRepositoryError;
+
+        let metrics = std::sync::Arc::new(PipelineMetrics::default());
+        let repository = FlakyActionsRepository::new(vec![ActionsRepositoryError::InvalidVoteType(7)]);
+        let orchestrator = retry_orchestrator(repository, stream(2)).with_metrics(metrics.clone());
+
+        let result = orchestrator.run_until(std::future::pending()).await;
+
+        assert!(matches!(result, Err(OrchestratorError::Loader(_))));
+        assert_eq!(metrics.retries(Stage::Load), 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_until_restarts_failed_consumer() {
+        let metrics = std::sync::Arc::new(PipelineMetrics::default());
+        let cursors = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let flaky_stream = FlakyStream { blocks: 3, failures: 2, cursors: cursors.clone() };
+        let orchestrator = retry_orchestrator(FlakyActionsRepository::new(vec![]), flaky_stream).with_metrics(metrics.clone());
+
+        let summary = orchestrator.run_until(std::future::pending()).await.unwrap();
+
+        // Each restart resumes from the last block delivered, and resets the retries
+        assert_eq!(*cursors.lock().unwrap(), vec![None, Some("cursor_1".to_string()), Some("cursor_2".to_string())]);
+        assert_eq!(summary.blocks, 3);
+        assert_eq!(metrics.retries(Stage::Consume), 2);
+        assert_eq!(metrics.errors(ErrorKind::Stream), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_until_stops_consumer_on_fatal_error() {
+        let metrics = std::sync::Arc::new(PipelineMetrics::default());
+        let cursors = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let flaky_stream = FlakyStream { blocks: 3, failures: 0, cursors: cursors.clone() };
+        let orchestrator = retry_orchestrator(FlakyActionsRepository::new(vec![]), flaky_stream).with_metrics(metrics.clone());
+
+        let summary = orchestrator.run_until(std::future::pending()).await.unwrap();
+
+        assert_eq!(cursors.lock().unwrap().len(), 1);
+        assert_eq!(summary.blocks, 1);
+        assert_eq!(metrics.retries(Stage::Consume), 0);
+    }
+}
@@ -0,0 +1,40 @@
+//! This module defines the `VoteWeightProvider` trait used by the orchestrator to
+//! decide how much each vote counts towards vote counts and leaderboards.
+use actions_indexer_shared::types::Vote;
+
+/// Defines the interface for weighting votes.
+///
+/// The weight of a vote is resolved once, when the vote is read from the chain, and
+/// recorded on the resulting `UserVote`. Changing or removing the vote later takes
+/// back exactly the weight it was counted with, even if the provider would weigh it
+/// differently by then.
+pub trait VoteWeightProvider: Send + Sync {
+    /// Returns the weight of a vote.
+    ///
+    /// # Arguments
+    ///
+    /// * `vote` - The vote to weigh, along with the raw action it was read from.
+    ///
+    /// # Returns
+    ///
+    /// The number of upvotes or downvotes the vote is worth.
+    fn weight(&self, vote: &Vote) -> u64;
+}
+
+/// Gives every vote the same weight.
+///
+/// The default weight of 1 counts one vote per address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantVoteWeight(pub u64);
+
+impl Default for ConstantVoteWeight {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl VoteWeightProvider for ConstantVoteWeight {
+    fn weight(&self, _vote: &Vote) -> u64 {
+        self.0
+    }
+}
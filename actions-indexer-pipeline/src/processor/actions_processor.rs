@@ -1,7 +1,8 @@
 use std::{collections::HashMap, sync::Arc};
 
-use crate::processor::{HandleAction, ProcessActions};
-use actions_indexer_shared::types::{Action, ActionRaw, ActionType, ActionVersion, ObjectType};
+use crate::errors::ProcessorError;
+use crate::processor::{HandleAction, ProcessActions, ProcessedActions};
+use actions_indexer_shared::types::{ActionRaw, ActionType, ActionVersion, ObjectType};
 
 /// `ActionsProcessor` is responsible for processing raw `ActionEvent` data into structured `Action` data.
 /// It manages a registry of handlers for different action versions and kinds.
@@ -32,7 +33,7 @@ impl ActionsProcessor {
 }
 
 impl ProcessActions for ActionsProcessor {
-    /// Processes a slice of `ActionRaw`s and returns a vector of `Action`s.
+    /// Processes a slice of `ActionRaw`s with the handlers registered for them.
     ///
     /// This method takes an array of raw `ActionRaw`s, applies necessary processing rules,
     /// and converts them into a structured `Action` format.
@@ -43,22 +44,25 @@ impl ProcessActions for ActionsProcessor {
     ///
     /// # Returns
     ///
-    /// A `Vec<Action>` on successful processing.
-    fn process(&self, actions: &[ActionRaw]) -> Vec<Action> {
-        let mut results = Vec::new();
+    /// The processed `Action`s, and the `ActionRaw`s that failed their handler or
+    /// have no handler registered.
+    fn try_process(&self, actions: &[ActionRaw]) -> ProcessedActions {
+        let mut processed = ProcessedActions::default();
         for action in actions {
             let handler = self.handler_registry.get(&(action.action_version, action.action_type, action.object_type));
-            if let Some(handler) = handler {
-                if let Ok(result) = handler.handle(action) {
-                    results.push(result);
-                } else {
-                    println!("Error processing action: {:?}", action);
+            let result = match handler {
+                Some(handler) => handler.handle(action),
+                None => Err(ProcessorError::MissingHandler(action.action_version, action.action_type, action.object_type)),
+            };
+            match result {
+                Ok(result) => processed.actions.push(result),
+                Err(e) => {
+                    println!("Error processing action: {:?}: {}", action, e);
+                    processed.rejected.push((action.clone(), e));
                 }
-            } else {
-                println!("No handler found for action: {:?}", action);
             }
         }
-        results
+        processed
     }
 }
 
@@ -105,6 +109,8 @@ mod tests {
             )
             .unwrap(),
             object_type: ObjectType::Entity,
+            log_index: None,
+            output_position: None,
         }
     }
 
@@ -205,8 +211,27 @@ mod tests {
             )
             .unwrap(),
             object_type: ObjectType::Relation, // no handler defined for this object type
+            log_index: None,
+            output_position: None,
         };
         let result = processor.process(&[action_event.clone()]);
         assert!(result.len() == 0); // no actions were processed
     }
+
+    #[test]
+    fn test_try_process_keeps_rejected_actions() {
+        let processor = mocked_processor();
+        let invalid_vote = make_action_event(3);
+        let unhandled = ActionRaw { action_version: 2, ..make_action_event(0) };
+        let action_events = vec![make_action_event(0), invalid_vote.clone(), unhandled.clone()];
+
+        let result = processor.try_process(&action_events);
+
+        assert_eq!(result.actions.len(), 1);
+        assert_eq!(result.rejected.len(), 2);
+        assert_eq!(result.rejected[0].0, invalid_vote);
+        assert!(matches!(result.rejected[0].1, ProcessorError::InvalidVote));
+        assert_eq!(result.rejected[1].0, unhandled);
+        assert!(matches!(result.rejected[1].1, ProcessorError::MissingHandler(2, ActionType::Vote, ObjectType::Entity)));
+    }
 }
@@ -7,6 +7,14 @@ mod actions_processor;
 
 pub use actions_processor::ActionsProcessor;
 
+/// The actions a processor turned into `Action`s, and the ones it couldn't along
+/// with the reason, in their original order.
+#[derive(Debug, Default)]
+pub struct ProcessedActions {
+    pub actions: Vec<Action>,
+    pub rejected: Vec<(ActionRaw, ProcessorError)>,
+}
+
 /// Defines the interface for processing raw `ActionEvent` data into structured `Action` data.
 ///
 /// Implementors of this trait are responsible for applying business logic and transformations
@@ -23,8 +31,23 @@ pub trait ProcessActions {
     ///
     /// # Returns
     ///
-    /// A `Vec<Action>` on successful processing.
-    fn process(&self, actions: &[ActionRaw]) -> Vec<Action>;
+    /// A `Vec<Action>` on successful processing. Actions that couldn't be processed
+    /// are left out, see [`Self::try_process`] to keep them.
+    fn process(&self, actions: &[ActionRaw]) -> Vec<Action> {
+        self.try_process(actions).actions
+    }
+
+    /// Processes a slice of `ActionRaw`s, keeping the ones that couldn't be processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - A slice of `ActionRaw`s to be processed.
+    ///
+    /// # Returns
+    ///
+    /// The processed `Action`s and the rejected `ActionRaw`s with the `ProcessorError`
+    /// each of them failed with.
+    fn try_process(&self, actions: &[ActionRaw]) -> ProcessedActions;
 }
 
 /// Defines the interface for handling a single `ActionRaw` and converting it into a structured `Action` data.
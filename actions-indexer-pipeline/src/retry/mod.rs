@@ -0,0 +1,176 @@
+//! Retry policies of the pipeline stages.
+//!
+//! Operations failing with a retryable error, see [`Classify`], are retried with an
+//! exponential backoff instead of aborting the run. Fatal errors are returned right
+//! away, since retrying them would fail the same way.
+use crate::errors::Classify;
+use crate::metrics::{PipelineMetrics, Stage};
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// `RetryPolicy` controls how often, and how far apart, a failing operation is retried.
+///
+/// The backoff starts at `initial_backoff` and doubles with every retry, up to
+/// `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// Retries 5 times, waiting from 500ms up to 30s.
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// The time to wait before the given retry, starting at 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// Whether an error is worth one more retry after `retries` retries.
+    pub fn should_retry(&self, error: &impl Classify, retries: u32) -> bool {
+        error.is_retryable() && retries < self.max_retries
+    }
+
+    /// Runs an operation, retrying it while it fails with a retryable error.
+    ///
+    /// Every retry is logged and counted in the `retries` of `stage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `stage` - The stage the operation belongs to
+    /// * `metrics` - The metrics counting the retries
+    /// * `operation` - Builds the future of each attempt
+    ///
+    /// # Returns
+    ///
+    /// The result of the first attempt that succeeds, fails with a fatal error or
+    /// exhausts the retries.
+    pub async fn retry<T, E, F, Fut>(&self, stage: Stage, metrics: &PipelineMetrics, mut operation: F) -> Result<T, E>
+    where
+        E: Classify + Debug,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retries = 0;
+        loop {
+            match operation().await {
+                Err(e) if self.should_retry(&e, retries) => {
+                    retries += 1;
+                    let backoff = self.backoff(retries);
+                    eprintln!(
+                        "Retryable {} error, retrying in {:?} ({}/{}): {:?}",
+                        stage.as_str(), backoff, retries, self.max_retries, e,
+                    );
+                    metrics.record_retry(stage);
+                    tokio::time::sleep(backoff).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// `RetryConfig` holds the retry policy of each stage that talks to the outside world.
+///
+/// The consumer is restarted from the last block it delivered when its stream fails,
+/// and the loader retries reading the stored state and persisting a batch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetryConfig {
+    pub consumer: RetryPolicy,
+    pub loader: RetryPolicy,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorClass;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug)]
+    struct TestError(ErrorClass);
+
+    impl Classify for TestError {
+        fn class(&self) -> ErrorClass {
+            self.0
+        }
+    }
+
+    fn policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = policy(5);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_retryable_errors() {
+        let metrics = PipelineMetrics::default();
+        let attempts = AtomicU32::new(0);
+
+        let result = policy(5)
+            .retry(Stage::Load, &metrics, || async {
+                match attempts.fetch_add(1, Ordering::Relaxed) {
+                    0 | 1 => Err(TestError(ErrorClass::Retryable)),
+                    attempt => Ok(attempt),
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(metrics.retries(Stage::Load), 2);
+        assert_eq!(metrics.retries(Stage::Consume), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_gives_up() {
+        let metrics = PipelineMetrics::default();
+        let attempts = AtomicU32::new(0);
+        let fail = |class| {
+            attempts.store(0, Ordering::Relaxed);
+            let attempts = &attempts;
+            move || async move {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err::<(), _>(TestError(class))
+            }
+        };
+
+        assert!(policy(3).retry(Stage::Load, &metrics, fail(ErrorClass::Retryable)).await.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 4);
+
+        // Fatal errors aren't retried
+        assert!(policy(3).retry(Stage::Load, &metrics, fail(ErrorClass::Fatal)).await.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+        assert!(RetryPolicy::none().retry(Stage::Load, &metrics, fail(ErrorClass::Retryable)).await.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+    }
+}
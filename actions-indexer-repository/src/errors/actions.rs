@@ -14,6 +14,9 @@ pub enum ActionsRepositoryError {
     #[error("Invalid address: {0}")]
     InvalidAddress(String),
 
+    #[error("Invalid tx hash: {0}")]
+    InvalidTxHash(String),
+
     #[error("Invalid timestamp: {0}")]
     InvalidTimestamp(u64),
 
@@ -22,4 +25,7 @@ pub enum ActionsRepositoryError {
 
     #[error("Invalid object type: {0}")]
     InvalidObjectType(i16),
+
+    #[error("Invalid leaderboard window: {0}")]
+    InvalidLeaderboardWindow(i16),
 }
\ No newline at end of file
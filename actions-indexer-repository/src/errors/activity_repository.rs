@@ -0,0 +1,19 @@
+//! Error types for the activity repository.
+//! Defines specific errors that can occur during database operations related to the activity counters.
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+/// Represents errors that can occur within the activity repository.
+///
+/// This enum consolidates various error conditions specific to database interactions,
+/// such as SQLx errors during database operations and invalid stored values.
+pub enum ActivityRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+
+    #[error("Invalid timestamp: {0}")]
+    InvalidTimestamp(u64),
+}
@@ -0,0 +1,20 @@
+//! Error types for the backfill repository.
+//! Defines specific errors that can occur while staging, comparing and swapping the actions tables.
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+/// Represents errors that can occur within the backfill repository.
+///
+/// This enum consolidates various error conditions specific to database interactions,
+/// such as SQLx errors during database operations, migrations of a staging schema that
+/// failed and actions tables missing from a schema being swapped.
+pub enum BackfillRepositoryError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+
+    #[error("Migration error: {0}")]
+    MigrationError(#[from] sqlx::migrate::MigrateError),
+
+    #[error("Missing table: {0}")]
+    MissingTable(String),
+}
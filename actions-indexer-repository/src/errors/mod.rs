@@ -1,7 +1,11 @@
 //! Error types for the actions indexer repository.
 //! Consolidates and re-exports error types related to actions repository operations.
 mod actions;
+mod activity_repository;
+mod backfill_repository;
 mod cursor_repository;
 
 pub use actions::ActionsRepositoryError;
+pub use activity_repository::ActivityRepositoryError;
+pub use backfill_repository::BackfillRepositoryError;
 pub use cursor_repository::CursorRepositoryError;
\ No newline at end of file
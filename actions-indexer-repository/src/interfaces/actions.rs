@@ -6,13 +6,19 @@
 //!
 //! - **Actions**: Processed blockchain actions (e.g., voting actions)
 //! - **User Votes**: Individual user voting records with timestamps
-//! - **Vote Counts**: Aggregated vote tallies per entity and space
-//! - **Changesets**: Atomic batches of related data modifications
+//! - **Vote Counts**: Aggregated vote tallies per entity and space, periodically
+//!   reconciled with the user votes
+//! - **Leaderboards**: Top voted objects per space and time window
+//! - **Changesets**: Atomic batches of related data modifications, journaled per block
+//!   so they can be rolled back
+//! - **Dead Letters**: Action events that couldn't be decoded or processed, kept to be
+//!   reprocessed
 //!
 //! The trait is designed to support transactional operations and efficient batch processing,
 //! making it suitable for high-throughput blockchain data indexing scenarios.
-use actions_indexer_shared::types::{Action, UserVote, VotesCount, Changeset, VoteCriteria, VoteCountCriteria};
+use actions_indexer_shared::types::{Action, ActionKey, UserVote, VotesCount, VotesCountReconciliation, Changeset, VoteCriteria, VoteCountCriteria, LeaderboardDelta, LeaderboardEntry, LeaderboardWindow, DeadLetter, DeadLetterId, ObjectId, ObjectType, SpaceId, UserAddress};
 use crate::errors::ActionsRepositoryError;
+use alloy::primitives::BlockNumber;
 
 /// Repository interface for managing actions indexer data storage operations.
 ///
@@ -45,6 +51,11 @@ pub trait ActionsRepository: Send + Sync {
     /// * `Err(ActionsRepositoryError)` - If the insertion fails due to database errors,
     ///   constraint violations, or connection issues
     ///
+    /// # Idempotency
+    ///
+    /// Actions with the tx hash and log index of a stored action are skipped, so
+    /// inserting the actions of a replayed block doesn't duplicate them.
+    ///
     /// # Performance
     ///
     /// This method is optimized for batch operations and should be preferred over
@@ -75,7 +86,8 @@ pub trait ActionsRepository: Send + Sync {
     /// # Behavior
     ///
     /// The implementation should handle conflicts gracefully, typically by updating
-    /// existing votes with new data rather than creating duplicates.
+    /// existing votes with new data rather than creating duplicates. A stored vote cast
+    /// after the new one must be kept, so replaying older actions doesn't undo it.
     async fn update_user_votes(
         &self,
         user_votes: &[UserVote],
@@ -108,6 +120,28 @@ pub trait ActionsRepository: Send + Sync {
         votes_counts: &[VotesCount],
     ) -> Result<(), ActionsRepositoryError>;
 
+    /// Applies incremental changes to the vote leaderboards.
+    ///
+    /// Each delta is added to the stored totals of the leaderboard entry identified
+    /// by its space, window, window start and object. Entries that don't exist yet
+    /// are created from the delta.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaderboard_deltas` - A slice of `LeaderboardDelta` objects to apply. Each
+    ///   delta contains the object and space, the window it applies to, and the
+    ///   upvote and downvote changes.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If all deltas were successfully applied
+    /// * `Err(ActionsRepositoryError)` - If the operation fails due to database errors
+    ///   or connection issues
+    async fn update_leaderboards(
+        &self,
+        leaderboard_deltas: &[LeaderboardDelta],
+    ) -> Result<(), ActionsRepositoryError>;
+
     /// Atomically persists a complete changeset to the repository.
     ///
     /// This method handles the transactional persistence of related data modifications
@@ -118,9 +152,11 @@ pub trait ActionsRepository: Send + Sync {
     /// # Arguments
     ///
     /// * `changeset` - A reference to the `Changeset` object containing:
+    ///   - `block_number`: The block the changes were read from
     ///   - `actions`: New actions to be inserted
     ///   - `user_votes`: User vote records to be updated/inserted
     ///   - `votes_count`: Aggregated vote counts to be updated
+    ///   - `leaderboard_deltas`: Leaderboard changes to be applied
     ///
     /// # Returns
     ///
@@ -132,11 +168,152 @@ pub trait ActionsRepository: Send + Sync {
     ///
     /// This method should implement proper transaction boundaries to ensure that
     /// either all changes succeed or all are rolled back on failure.
+    ///
+    /// # Journal
+    ///
+    /// The values overwritten by the changeset are recorded under its block number
+    /// in the same transaction, so `rollback_to` can undo it later.
     async fn persist_changeset(
         &self,
         changeset: &Changeset<'_>,
     ) -> Result<(), ActionsRepositoryError>;
 
+    /// Atomically persists several changesets to the repository.
+    ///
+    /// This method persists a batch of consecutive blocks as a single atomic
+    /// operation, so the indexer can commit many blocks at once while keeping the
+    /// per-block journal that `rollback_to` relies on.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - The changesets to persist, ordered by block number. Every
+    ///   changeset must be computed on top of the ones before it.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every changeset was successfully persisted
+    /// * `Err(ActionsRepositoryError)` - If any changeset fails, in which case none
+    ///   of them are persisted
+    ///
+    /// # Transaction Safety
+    ///
+    /// All changesets must be applied in the same transaction and in order, as if
+    /// `persist_changeset` had been called for each of them.
+    async fn persist_changesets(
+        &self,
+        changesets: &[Changeset<'_>],
+    ) -> Result<(), ActionsRepositoryError>;
+
+    /// Atomically persists several changesets together with the indexer cursor.
+    ///
+    /// This method persists a batch like `persist_changesets` and saves the cursor
+    /// of its last block in the same atomic operation, so the stored cursor never
+    /// gets ahead of, or falls behind, the persisted data.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - The changesets to persist, ordered by block number
+    /// * `id` - The id of the indexer the cursor belongs to
+    /// * `cursor` - The stream cursor of the last block of the batch
+    /// * `block_number` - The block number of the cursor
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every changeset and the cursor were successfully persisted
+    /// * `Err(ActionsRepositoryError)` - If anything fails, in which case neither
+    ///   the changesets nor the cursor are persisted
+    ///
+    /// # Transaction Safety
+    ///
+    /// The cursor must be readable through `CursorRepository::get_cursor` once
+    /// the transaction commits.
+    async fn persist_changesets_with_cursor(
+        &self,
+        changesets: &[Changeset<'_>],
+        id: &str,
+        cursor: &str,
+        block_number: i64,
+    ) -> Result<(), ActionsRepositoryError>;
+
+    /// Rolls the repository back to the state it had after persisting a block.
+    ///
+    /// This admin operation undoes every changeset persisted for a block after
+    /// `block_number`, using the journal recorded by `persist_changeset`. It is meant
+    /// for chain reorgs and for replaying blocks after a bugfix.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - The last block to keep. Actions, user votes, vote counts,
+    ///   leaderboard changes and dead letters of later blocks are reverted.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every later changeset was rolled back
+    /// * `Err(ActionsRepositoryError)` - If the operation fails due to database errors
+    ///   or connection issues, leaving the repository unchanged
+    ///
+    /// # Behavior
+    ///
+    /// The stream cursor is not touched: callers must move it back to `block_number`
+    /// so the rolled back blocks are consumed again, see `rollback_to_with_cursor`.
+    /// Rolling back to a block at or after the latest persisted one is a no-op. The
+    /// journal of the blocks at or below the `final_block_number` of a persisted
+    /// changeset is pruned, so they can't be rolled back anymore.
+    async fn rollback_to(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<(), ActionsRepositoryError>;
+
+    /// Atomically rolls the repository back to a block and saves its cursor.
+    ///
+    /// This method rolls back like `rollback_to` and saves the cursor of
+    /// `block_number` in the same atomic operation, so a restart resumes from the
+    /// last kept block rather than from a block that was rolled back.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - The last block to keep
+    /// * `id` - The id of the indexer the cursor belongs to
+    /// * `cursor` - The stream cursor of `block_number`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every later changeset was rolled back and the cursor saved
+    /// * `Err(ActionsRepositoryError)` - If anything fails, in which case neither
+    ///   the rollback nor the cursor are persisted
+    async fn rollback_to_with_cursor(
+        &self,
+        block_number: BlockNumber,
+        id: &str,
+        cursor: &str,
+    ) -> Result<(), ActionsRepositoryError>;
+
+    /// Retrieves the keys of the given actions that are already stored.
+    ///
+    /// An action is identified by the hash of its transaction and the position of its
+    /// log in the block, or of the action in the block's output for substream packages
+    /// that don't emit log indexes. The pipeline uses this method to skip the actions of
+    /// a replayed block range, so they aren't counted twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_keys` - A slice of `ActionKey` tuples to look up. Each key is a tuple
+    ///   containing:
+    ///   - `TxHash` - The hash of the transaction that emitted the action
+    ///   - `ActionIndex` - The position of the action's log, or of the action in the
+    ///     output, in its block
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ActionKey>)` - The keys of the actions already stored. Returns an
+    ///   empty vector if none of the actions were stored.
+    /// * `Err(ActionsRepositoryError)` - If the query fails due to database errors
+    ///   or connection issues
+    async fn get_persisted_action_keys(
+        &self,
+        action_keys: &[ActionKey],
+    ) -> Result<Vec<ActionKey>, ActionsRepositoryError>;
+
     /// Retrieves user votes matching the specified criteria.
     ///
     /// This method queries for user vote records based on combinations of user address,
@@ -196,6 +373,200 @@ pub trait ActionsRepository: Send + Sync {
         vote_criteria: &[VoteCountCriteria],
     ) -> Result<Vec<VotesCount>, ActionsRepositoryError>;
 
+    /// Retrieves the most upvoted objects of a space for a time window.
+    ///
+    /// Reads the leaderboard maintained by the loader, so the cost does not depend
+    /// on the number of votes in the space.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - The UUID of the space to get the leaderboard for
+    /// * `window` - The time window to rank votes over
+    /// * `timestamp` - A unix timestamp within the window to read (e.g. now for the
+    ///   current day, week or month)
+    /// * `limit` - The maximum number of entries to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<LeaderboardEntry>)` - Entries ordered by upvotes, highest first.
+    ///   Returns an empty vector if nothing was voted on in the window.
+    /// * `Err(ActionsRepositoryError)` - If the query fails due to database errors
+    ///   or connection issues
+    async fn get_leaderboard(
+        &self,
+        space_id: &SpaceId,
+        window: LeaderboardWindow,
+        timestamp: u64,
+        limit: i64,
+    ) -> Result<Vec<LeaderboardEntry>, ActionsRepositoryError>;
+
+    /// Retrieves a page of the current votes on an object within a space.
+    ///
+    /// Removed votes are left out, so the page only holds the users currently
+    /// upvoting or downvoting the object.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The UUID of the voted object
+    /// * `space_id` - The UUID of the space the votes were cast in
+    /// * `object_type` - Whether the object is an entity or a relation
+    /// * `limit` - The maximum number of votes to return
+    /// * `offset` - The number of votes to skip
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<UserVote>)` - Votes ordered by vote time, latest first. Returns an
+    ///   empty vector if the page is past the last vote.
+    /// * `Err(ActionsRepositoryError)` - If the query fails due to database errors
+    ///   or connection issues
+    async fn get_object_votes(
+        &self,
+        object_id: &ObjectId,
+        space_id: &SpaceId,
+        object_type: ObjectType,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserVote>, ActionsRepositoryError>;
+
+    /// Retrieves a page of the votes cast by a user, across all spaces.
+    ///
+    /// Each object holds only the user's latest vote on it, removals included, so the
+    /// history tells which objects the user voted on and where they stand now.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - The blockchain address of the voter
+    /// * `limit` - The maximum number of votes to return
+    /// * `offset` - The number of votes to skip
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<UserVote>)` - Votes ordered by vote time, latest first. Returns an
+    ///   empty vector if the page is past the last vote.
+    /// * `Err(ActionsRepositoryError)` - If the query fails due to database errors
+    ///   or connection issues
+    async fn get_votes_by_user(
+        &self,
+        user_id: &UserAddress,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserVote>, ActionsRepositoryError>;
+
+    /// Retrieves a page of the vote counts of a space, ranked by score.
+    ///
+    /// The score of an object is its upvotes minus its downvotes, see
+    /// [`VotesCount::score`].
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - The UUID of the space to rank the objects of
+    /// * `limit` - The maximum number of vote counts to return
+    /// * `offset` - The number of vote counts to skip
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<VotesCount>)` - Vote counts ordered by score, highest first, ties
+    ///   broken by upvotes. Returns an empty vector if the page is past the last object.
+    /// * `Err(ActionsRepositoryError)` - If the query fails due to database errors
+    ///   or connection issues
+    async fn get_top_objects(
+        &self,
+        space_id: &SpaceId,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<VotesCount>, ActionsRepositoryError>;
+
+    /// Recomputes a page of vote counts from the user votes and repairs the ones that drifted.
+    ///
+    /// Vote counts are maintained incrementally by the loader, so a crash or a partial
+    /// failure can leave them out of sync with `user_votes`, which is the ground truth.
+    /// This method walks the vote counts in pages ordered by object, space and object type,
+    /// replacing the counts that don't match the sum of the weighted user votes.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The last key of the previous page, or `None` to start from the beginning
+    /// * `limit` - The maximum number of vote counts to check
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VotesCountReconciliation)` - The number of vote counts checked, the last
+    ///   key checked and the drifted counts that were repaired
+    /// * `Err(ActionsRepositoryError)` - If the operation fails due to database errors
+    ///   or connection issues, leaving the vote counts unchanged
+    ///
+    /// # Behavior
+    ///
+    /// A count is only repaired if it still holds the value it was checked with, so a
+    /// changeset persisted concurrently is never overwritten with stale counts. Such a
+    /// count is left for the next pass. Repairs aren't journaled, so rolling back a
+    /// block restores the counts it overwrote, drift included.
+    async fn reconcile_vote_counts(
+        &self,
+        after: Option<VoteCountCriteria>,
+        limit: i64,
+    ) -> Result<VotesCountReconciliation, ActionsRepositoryError>;
+
+    /// Stores action events that couldn't be decoded or processed.
+    ///
+    /// The pipeline calls this method instead of failing the whole block or dropping
+    /// the events, so they can be reprocessed once the cause is fixed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dead_letters` - The failed events, with their raw bytes, error and block
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every dead letter was stored
+    /// * `Err(ActionsRepositoryError)` - If the operation fails due to database errors
+    ///   or connection issues
+    ///
+    /// # Behavior
+    ///
+    /// A dead letter already stored for the same block and raw bytes is skipped, so
+    /// replaying a block range doesn't store its dead letters twice.
+    async fn insert_dead_letters(
+        &self,
+        dead_letters: &[DeadLetter],
+    ) -> Result<(), ActionsRepositoryError>;
+
+    /// Retrieves a page of the dead letters that weren't reprocessed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - The id of the last dead letter of the previous page, or `None` to
+    ///   start from the oldest one
+    /// * `limit` - The maximum number of dead letters to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(DeadLetterId, DeadLetter)>)` - Pending dead letters ordered by id.
+    ///   Returns an empty vector if there are none left.
+    /// * `Err(ActionsRepositoryError)` - If the query fails due to database errors
+    ///   or connection issues
+    async fn get_pending_dead_letters(
+        &self,
+        after: Option<DeadLetterId>,
+        limit: i64,
+    ) -> Result<Vec<(DeadLetterId, DeadLetter)>, ActionsRepositoryError>;
+
+    /// Marks dead letters as reprocessed, so they are no longer pending.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The ids of the dead letters whose events were persisted
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the dead letters were marked
+    /// * `Err(ActionsRepositoryError)` - If the operation fails due to database errors
+    ///   or connection issues
+    async fn mark_dead_letters_reprocessed(
+        &self,
+        ids: &[DeadLetterId],
+    ) -> Result<(), ActionsRepositoryError>;
+
     /// Checks if the tables are created in the database.
     ///
     /// This method checks if the tables are created in the database.
@@ -0,0 +1,51 @@
+use actions_indexer_shared::types::{ActionType, SenderActivity, SpaceActivity, SpaceId};
+
+use crate::errors::ActivityRepositoryError;
+
+/// Trait for interacting with the activity repository.
+///
+/// This trait provides a clean abstraction over the underlying data store for the rolling
+/// activity counters of the actions indexer system. It ranks the most active spaces and senders
+/// over a recent period from the action counters per space, sender and action type, and prunes
+/// the old counters. The counters themselves are written by the `ActionsRepository`, with the
+/// changeset of each block, so they are rolled back with it.
+#[async_trait::async_trait]
+pub trait ActivityRepository: Send + Sync {
+    /// Retrieves the spaces with the most actions since a given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - Unix timestamp to count actions from, rounded down to its bucket.
+    /// * `action_type` - The action type to count, or `None` to count every action.
+    /// * `limit` - The maximum number of spaces to return.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the spaces with their number of actions, most active first.
+    async fn get_most_active_spaces(&self, since: u64, action_type: Option<ActionType>, limit: i64) -> Result<Vec<SpaceActivity>, ActivityRepositoryError>;
+
+    /// Retrieves the senders with the most actions since a given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - Unix timestamp to count actions from, rounded down to its bucket.
+    /// * `space_id` - The space to count actions in, or `None` to count them in every space.
+    /// * `action_type` - The action type to count, or `None` to count every action.
+    /// * `limit` - The maximum number of senders to return.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the senders with their number of actions, most active first.
+    async fn get_most_active_senders(&self, since: u64, space_id: Option<SpaceId>, action_type: Option<ActionType>, limit: i64) -> Result<Vec<SenderActivity>, ActivityRepositoryError>;
+
+    /// Deletes the counters of the buckets that started before a given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - Unix timestamp before which buckets are deleted.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of deleted counters.
+    async fn prune_activity(&self, before: u64) -> Result<u64, ActivityRepositoryError>;
+}
\ No newline at end of file
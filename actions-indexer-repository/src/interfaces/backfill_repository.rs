@@ -0,0 +1,76 @@
+use actions_indexer_shared::types::ActionsTableCounts;
+
+use crate::errors::BackfillRepositoryError;
+
+/// Trait for staging, comparing and swapping the actions tables of a backfill.
+///
+/// A backfill reprocesses a block range into the actions tables of a staging schema,
+/// compares them with the production ones, then swaps the two sets of tables in a
+/// single transaction, so readers never see a half-reprocessed state. Schemas are
+/// given by name, and are expected to be plain lowercase identifiers.
+#[async_trait::async_trait]
+pub trait BackfillRepository: Send + Sync {
+    /// Creates a schema holding empty actions tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema to create, which must not exist yet.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure, e.g. if the schema already exists.
+    async fn create_schema(&self, schema: &str) -> Result<(), BackfillRepositoryError>;
+
+    /// Counts the rows of the actions tables of a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema to count the rows of.
+    /// * `start_block` - The first block actions and dead letters are counted from.
+    /// * `stop_block` - The last block actions and dead letters are counted to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `ActionsTableCounts` of the schema.
+    async fn count_rows(&self, schema: &str, start_block: u64, stop_block: u64) -> Result<ActionsTableCounts, BackfillRepositoryError>;
+
+    /// Retrieves the block of a cursor stored in a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the `meta` table holding the cursor.
+    /// * `id` - The ID the cursor is stored under.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the block number of the cursor, or `None` if there is none.
+    async fn get_cursor_block(&self, schema: &str, id: &str) -> Result<Option<i64>, BackfillRepositoryError>;
+
+    /// Retrieves the first block holding actions in a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the `raw_actions` table.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the lowest block number of the actions, or `None` if there are none.
+    async fn get_first_block(&self, schema: &str) -> Result<Option<i64>, BackfillRepositoryError>;
+
+    /// Swaps the actions tables of two schemas, archiving the replaced ones.
+    ///
+    /// The actions tables of `production` are moved to `archive`, then the ones of
+    /// `staging` to `production`, in a single transaction. The `meta` table holding the
+    /// cursors stays in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `staging` - The schema holding the new actions tables.
+    /// * `production` - The schema the new actions tables replace the tables of.
+    /// * `archive` - The schema the replaced tables are kept in, which must not exist yet.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure, in which case no table was moved.
+    async fn swap_schemas(&self, staging: &str, production: &str, archive: &str) -> Result<(), BackfillRepositoryError>;
+}
@@ -1,5 +1,28 @@
 use crate::errors::CursorRepositoryError;
 
+/// ID the cursor of the actions indexer is stored under when it runs without a namespace.
+pub const DEFAULT_CURSOR_ID: &str = "actions_indexer";
+
+/// Returns the ID the cursor of an indexer namespace is stored under.
+///
+/// Pipelines sharing a database, such as mainnet, testnet and replay ones, each keep
+/// their own cursor by running in their own namespace. Without a namespace the ID is
+/// [`DEFAULT_CURSOR_ID`], so the cursor of an existing deployment keeps being used.
+///
+/// # Arguments
+///
+/// * `namespace` - The namespace of the indexer, if any.
+///
+/// # Returns
+///
+/// The cursor ID of the namespace, e.g. `actions_indexer:testnet`.
+pub fn cursor_id(namespace: Option<&str>) -> String {
+    match namespace {
+        Some(namespace) => format!("{DEFAULT_CURSOR_ID}:{namespace}"),
+        None => DEFAULT_CURSOR_ID.to_string(),
+    }
+}
+
 /// Trait for interacting with the cursor repository.
 ///
 /// This trait provides a clean abstraction over the underlying data store for the actions indexer system. It handles the retrieval and persistence of the cursor.
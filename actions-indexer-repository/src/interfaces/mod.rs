@@ -1,6 +1,10 @@
 //! This module defines and re-exports the interfaces for the actions repository.
 //! It serves as a central point for accessing traits related to data interaction.
 mod actions;
+mod activity_repository;
+mod backfill_repository;
 mod cursor_repository;
 pub use actions::ActionsRepository;
-pub use cursor_repository::CursorRepository;
\ No newline at end of file
+pub use activity_repository::ActivityRepository;
+pub use backfill_repository::BackfillRepository;
+pub use cursor_repository::{cursor_id, CursorRepository, DEFAULT_CURSOR_ID};
\ No newline at end of file
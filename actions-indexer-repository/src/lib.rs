@@ -1,16 +1,31 @@
 //! # Actions Indexer Repository
 //! This crate provides traits and implementations for interacting with the
 //! actions data repository. It includes definitions for errors, interfaces,
-//! and concrete implementations for PostgreSQL.
+//! and concrete implementations for PostgreSQL, and for SQLite behind the `sqlite`
+//! feature.
 pub mod errors;
 pub mod interfaces;
 pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 pub use errors::ActionsRepositoryError;
 pub use errors::CursorRepositoryError;
+pub use errors::ActivityRepositoryError;
+pub use errors::BackfillRepositoryError;
 
 pub use interfaces::ActionsRepository;
 pub use interfaces::CursorRepository;
+pub use interfaces::{cursor_id, DEFAULT_CURSOR_ID};
+pub use interfaces::ActivityRepository;
+pub use interfaces::BackfillRepository;
 
 pub use postgres::PostgresActionsRepository;
 pub use postgres::PostgresCursorRepository;
+pub use postgres::PostgresActivityRepository;
+pub use postgres::PostgresBackfillRepository;
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteActionsRepository;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteCursorRepository;
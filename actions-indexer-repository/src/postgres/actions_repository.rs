@@ -14,14 +14,21 @@
 //! ## Database Tables
 //!
 //! - `raw_actions`: Processed blockchain actions
-//! - `user_votes`: Individual voting records with upsert support
+//! - `user_votes`: Individual voting records and their weights, with upsert support
 //! - `votes_count`: Aggregated vote tallies per entity/space
+//! - `vote_leaderboards`: Vote tallies per entity/space and time window
+//! - `flags`, `published_edits`, `membership_changes`: Actions of the other kinds
+//! - `action_activity`: Hourly action counters per space, sender and action type
+//! - `changeset_user_votes`, `changeset_votes_count`, `changeset_leaderboards`,
+//!   `changeset_activity`: Per-block journal of persisted changesets, used to roll them back
+//! - `action_dead_letters`: Action events that couldn't be decoded or processed
 use async_trait::async_trait;
-use actions_indexer_shared::types::{Action, Changeset, UserVote, VotesCount, ObjectId, VoteCriteria, VoteCountCriteria, VoteValue, ObjectType};
+use actions_indexer_shared::types::{Action, ActionIndex, ActionKey, Changeset, EditPublished, Flag, Membership, MembershipChange, UserVote, VotesCount, ActivityDelta, VotesCountDrift, VotesCountReconciliation, ObjectId, VoteCriteria, VoteCountCriteria, VoteValue, ObjectType, LeaderboardDelta, LeaderboardEntry, LeaderboardWindow, SpaceId, UserAddress, DeadLetter, DeadLetterId};
+use sqlx::{postgres::PgRow, Row};
 use crate::{ActionsRepository, ActionsRepositoryError};
 use hex;
 use time::OffsetDateTime;
-use alloy::{primitives::Address, hex::FromHex};
+use alloy::{primitives::{Address, BlockNumber, TxHash}, hex::FromHex};
 use uuid::Uuid;
 
 /// PostgreSQL implementation of the actions indexer repository.
@@ -59,6 +66,7 @@ impl PostgresActionsRepository {
     ///
     /// Uses `QueryBuilder` for efficient multi-row INSERT into `raw_actions` table.
     /// Handles blockchain addresses as hex-encoded strings and timestamps as PostgreSQL timestamps.
+    /// Actions already stored with the same tx hash and log index are skipped.
     ///
     /// # Arguments
     ///
@@ -75,30 +83,156 @@ impl PostgresActionsRepository {
         }
 
         let mut query_builder = sqlx::QueryBuilder::new(
-            "INSERT INTO raw_actions (action_type, action_version, sender, object_id, group_id, space_pov, metadata, block_number, block_timestamp, tx_hash, object_type)"
+            "INSERT INTO raw_actions (action_type, action_version, sender, object_id, group_id, space_pov, metadata, block_number, block_timestamp, tx_hash, object_type, log_index, output_position)"
         );
 
         query_builder.push_values(actions, |mut b, action| {
-            match action {
-                Action::Vote(vote_action) => {
-                    // TODO: extract to a helper function
-                    let voted_at = OffsetDateTime::from_unix_timestamp(vote_action.raw.block_timestamp as i64)
-                        .unwrap_or(OffsetDateTime::now_utc());
-                    b.push_bind(vote_action.raw.action_type as i64)
-                     .push_bind(vote_action.raw.action_version as i64)
-                     .push_bind(format!("0x{}", hex::encode(vote_action.raw.sender.as_slice())))
-                     .push_bind(vote_action.raw.object_id.clone())
-                     .push_bind(vote_action.raw.group_id.clone())
-                     .push_bind(vote_action.raw.space_pov.clone())
-                     .push_bind(vote_action.raw.metadata.as_ref().map(|b| b.as_ref().to_vec()))
-                     .push_bind(vote_action.raw.block_number as i64)
-                     .push_bind(voted_at)
-                     .push_bind(format!("0x{}", hex::encode(vote_action.raw.tx_hash.as_slice())))
-                     .push_bind(vote_action.raw.object_type as i16);
-                }
-            }
+            let raw = action.raw();
+            // TODO: extract to a helper function
+            let acted_at = OffsetDateTime::from_unix_timestamp(raw.block_timestamp as i64)
+                .unwrap_or(OffsetDateTime::now_utc());
+            b.push_bind(raw.action_type as i64)
+             .push_bind(raw.action_version as i64)
+             .push_bind(format!("0x{}", hex::encode(raw.sender.as_slice())))
+             .push_bind(raw.object_id)
+             .push_bind(raw.group_id)
+             .push_bind(raw.space_pov)
+             .push_bind(raw.metadata.as_ref().map(|b| b.as_ref().to_vec()))
+             .push_bind(raw.block_number as i64)
+             .push_bind(acted_at)
+             .push_bind(format!("0x{}", hex::encode(raw.tx_hash.as_slice())))
+             .push_bind(raw.object_type as i16)
+             .push_bind(raw.log_index.map(i64::from))
+             .push_bind(raw.output_position.map(i64::from));
+        });
+        // Skips actions already stored under either (tx_hash, log_index) or (tx_hash, output_position)
+        query_builder.push(" ON CONFLICT DO NOTHING");
+
+        query_builder.build().execute(&mut **tx).await?;
+
+        self.insert_flags_tx(actions, tx).await?;
+        self.insert_published_edits_tx(actions, tx).await?;
+        self.insert_membership_changes_tx(actions, tx).await?;
+        Ok(())
+    }
+
+    /// Inserts the flag actions of a batch within an active transaction.
+    ///
+    /// Uses `QueryBuilder` for a multi-row INSERT into the `flags` table. Other actions
+    /// of the batch are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - Actions to insert the flags of
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All flags inserted successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn insert_flags_tx(&self, actions: &[Action], tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        let flags: Vec<&Flag> = actions.iter().filter_map(|action| match action {
+            Action::Flag(flag) => Some(flag),
+            _ => None,
+        }).collect();
+        if flags.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO flags (user_id, object_id, object_type, space_id, reason, flagged_at, block_number, tx_hash)"
+        );
+        query_builder.push_values(flags, |mut b, flag| {
+            b.push_bind(format!("0x{}", hex::encode(flag.raw.sender.as_slice())))
+             .push_bind(flag.raw.object_id)
+             .push_bind(flag.raw.object_type as i16)
+             .push_bind(flag.raw.space_pov)
+             .push_bind(flag.reason.clone())
+             .push_bind(OffsetDateTime::from_unix_timestamp(flag.raw.block_timestamp as i64).unwrap_or(OffsetDateTime::now_utc()))
+             .push_bind(flag.raw.block_number as i64)
+             .push_bind(format!("0x{}", hex::encode(flag.raw.tx_hash.as_slice())));
+        });
+        query_builder.build().execute(&mut **tx).await?;
+        Ok(())
+    }
+
+    /// Inserts the edit published actions of a batch within an active transaction.
+    ///
+    /// Uses `QueryBuilder` for a multi-row INSERT into the `published_edits` table, with
+    /// the object of the action as the edit ID. Other actions of the batch are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - Actions to insert the published edits of
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All published edits inserted successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn insert_published_edits_tx(&self, actions: &[Action], tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        let edits: Vec<&EditPublished> = actions.iter().filter_map(|action| match action {
+            Action::EditPublished(edit) => Some(edit),
+            _ => None,
+        }).collect();
+        if edits.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO published_edits (edit_id, space_id, author, content_uri, published_at, block_number, tx_hash)"
+        );
+        query_builder.push_values(edits, |mut b, edit| {
+            b.push_bind(edit.raw.object_id)
+             .push_bind(edit.raw.space_pov)
+             .push_bind(format!("0x{}", hex::encode(edit.raw.sender.as_slice())))
+             .push_bind(edit.content_uri.clone())
+             .push_bind(OffsetDateTime::from_unix_timestamp(edit.raw.block_timestamp as i64).unwrap_or(OffsetDateTime::now_utc()))
+             .push_bind(edit.raw.block_number as i64)
+             .push_bind(format!("0x{}", hex::encode(edit.raw.tx_hash.as_slice())));
         });
+        query_builder.build().execute(&mut **tx).await?;
+        Ok(())
+    }
 
+    /// Inserts the membership actions of a batch within an active transaction.
+    ///
+    /// Uses `QueryBuilder` for a multi-row INSERT into the `membership_changes` table.
+    /// Changes are appended rather than upserted, so the membership of a user is its
+    /// latest change. Other actions of the batch are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - Actions to insert the membership changes of
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All membership changes inserted successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn insert_membership_changes_tx(&self, actions: &[Action], tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        let memberships: Vec<&Membership> = actions.iter().filter_map(|action| match action {
+            Action::Membership(membership) => Some(membership),
+            _ => None,
+        }).collect();
+        if memberships.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO membership_changes (user_id, space_id, change, changed_at, block_number, tx_hash)"
+        );
+        query_builder.push_values(memberships, |mut b, membership| {
+            b.push_bind(format!("0x{}", hex::encode(membership.raw.sender.as_slice())))
+             .push_bind(membership.raw.space_pov)
+             .push_bind(match membership.change {
+                 MembershipChange::Join => 0,
+                 MembershipChange::Leave => 1,
+             } as i16)
+             .push_bind(OffsetDateTime::from_unix_timestamp(membership.raw.block_timestamp as i64).unwrap_or(OffsetDateTime::now_utc()))
+             .push_bind(membership.raw.block_number as i64)
+             .push_bind(format!("0x{}", hex::encode(membership.raw.tx_hash.as_slice())));
+        });
         query_builder.build().execute(&mut **tx).await?;
         Ok(())
     }
@@ -107,6 +241,8 @@ impl PostgresActionsRepository {
     ///
     /// Uses `ON CONFLICT DO UPDATE` for each vote record targeting the `user_votes` table
     /// with composite key (user_id, object_id, object_type, space_id). Addresses are hex-encoded.
+    /// A stored vote cast after the new one is kept, so replaying older actions, such as
+    /// reprocessed dead letters, never overwrites a later vote.
     ///
     /// # Arguments
     ///
@@ -123,27 +259,29 @@ impl PostgresActionsRepository {
         }
 
         for vote in user_votes {
-            sqlx::query!(
+            sqlx::query(
                 r#"
-                INSERT INTO user_votes (user_id, object_id, object_type, space_id, vote_type, voted_at)
-                VALUES ($1, $2, $3, $4, $5, $6)
+                INSERT INTO user_votes (user_id, object_id, object_type, space_id, vote_type, voted_at, weight)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
                 ON CONFLICT (user_id, object_id, object_type, space_id)
                 DO UPDATE SET
                     vote_type = EXCLUDED.vote_type,
-                    voted_at = EXCLUDED.voted_at
+                    voted_at = EXCLUDED.voted_at,
+                    weight = EXCLUDED.weight
+                WHERE user_votes.voted_at <= EXCLUDED.voted_at
                 "#,
-                format!("0x{}", hex::encode(vote.user_id.as_slice())),
-                vote.object_id.clone(),
-                vote.object_type as i16,
-                vote.space_id.clone(),
-                match vote.vote_type {
-                    VoteValue::Up => 0,
-                    VoteValue::Down => 1,
-                    VoteValue::Remove => 2,
-                } as i16,
-                OffsetDateTime::from_unix_timestamp(vote.voted_at as i64)
-                    .unwrap_or(OffsetDateTime::now_utc())
             )
+            .bind(format!("0x{}", hex::encode(vote.user_id.as_slice())))
+            .bind(vote.object_id)
+            .bind(vote.object_type as i16)
+            .bind(vote.space_id)
+            .bind(match vote.vote_type {
+                VoteValue::Up => 0,
+                VoteValue::Down => 1,
+                VoteValue::Remove => 2,
+            } as i16)
+            .bind(OffsetDateTime::from_unix_timestamp(vote.voted_at as i64).unwrap_or(OffsetDateTime::now_utc()))
+            .bind(vote.weight as i64)
             .execute(&mut **tx)
             .await?;
         }
@@ -190,6 +328,512 @@ impl PostgresActionsRepository {
         }
         Ok(())
     }
+
+    /// Applies leaderboard deltas within an active transaction.
+    ///
+    /// Uses upsert operations on `vote_leaderboards` with composite key
+    /// (space_id, time_window, window_start, object_id, object_type). Unlike vote counts,
+    /// deltas are added to the stored totals instead of replacing them.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaderboard_deltas` - Deltas to apply (empty slices are no-ops)
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All deltas applied successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn update_leaderboards_tx(&self, leaderboard_deltas: &[LeaderboardDelta], tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        if leaderboard_deltas.is_empty() {
+            return Ok(());
+        }
+
+        for delta in leaderboard_deltas {
+            sqlx::query(
+                r#"
+                INSERT INTO vote_leaderboards (space_id, time_window, window_start, object_id, object_type, upvotes, downvotes)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (space_id, time_window, window_start, object_id, object_type)
+                DO UPDATE SET
+                    upvotes = vote_leaderboards.upvotes + EXCLUDED.upvotes,
+                    downvotes = vote_leaderboards.downvotes + EXCLUDED.downvotes
+                "#,
+            )
+            .bind(delta.space_id)
+            .bind(window_to_i16(delta.window))
+            .bind(timestamp_to_datetime(delta.window_start)?)
+            .bind(delta.object_id)
+            .bind(delta.object_type as i16)
+            .bind(delta.upvotes)
+            .bind(delta.downvotes)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Adds activity deltas to the stored counters within an active transaction.
+    ///
+    /// Deltas are unnested and summed per counter first, so the same counter can appear
+    /// several times in a changeset.
+    ///
+    /// # Arguments
+    ///
+    /// * `activity_deltas` - Deltas to add (empty slices are no-ops)
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All deltas added successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn update_activity_tx(&self, activity_deltas: &[ActivityDelta], tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        if activity_deltas.is_empty() {
+            return Ok(());
+        }
+
+        let mut bucket_starts = Vec::with_capacity(activity_deltas.len());
+        let mut space_ids = Vec::with_capacity(activity_deltas.len());
+        let mut senders = Vec::with_capacity(activity_deltas.len());
+        let mut action_types = Vec::with_capacity(activity_deltas.len());
+        let mut actions = Vec::with_capacity(activity_deltas.len());
+        for delta in activity_deltas {
+            bucket_starts.push(timestamp_to_datetime(delta.bucket_start)?);
+            space_ids.push(delta.space_id);
+            senders.push(format!("0x{}", hex::encode(delta.sender.as_slice())));
+            action_types.push(delta.action_type as i16);
+            actions.push(delta.actions);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO action_activity (bucket_start, space_id, sender, action_type, actions)
+            SELECT bucket_start, space_id, sender, action_type, SUM(actions)::BIGINT
+            FROM UNNEST($1::TIMESTAMPTZ[], $2::UUID[], $3::VARCHAR[], $4::SMALLINT[], $5::BIGINT[])
+                AS deltas(bucket_start, space_id, sender, action_type, actions)
+            GROUP BY bucket_start, space_id, sender, action_type
+            ON CONFLICT (bucket_start, space_id, sender, action_type) DO UPDATE SET
+                actions = action_activity.actions + EXCLUDED.actions
+            "#,
+        )
+        .bind(bucket_starts)
+        .bind(space_ids)
+        .bind(senders)
+        .bind(action_types)
+        .bind(actions)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records the values a changeset overwrites within an active transaction.
+    ///
+    /// Must run before the changeset is applied. Prior `user_votes` and `votes_count` rows
+    /// are copied into the journal under the changeset's block number, with NULL values for
+    /// rows that don't exist yet, and leaderboard and activity deltas are accumulated as
+    /// applied. If a block is persisted twice, the values recorded the first time are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `changeset` - Changeset about to be persisted
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Journal entries recorded successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn record_changeset_tx(&self, changeset: &Changeset<'_>, tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        let block_number = changeset.block_number as i64;
+
+        for vote in changeset.user_votes {
+            sqlx::query(
+                r#"
+                INSERT INTO changeset_user_votes (block_number, user_id, object_id, object_type, space_id, vote_type, voted_at, weight)
+                SELECT $1, $2, $3, $4, $5, user_votes.vote_type, user_votes.voted_at, user_votes.weight
+                FROM (SELECT 1) AS journaled
+                LEFT JOIN user_votes ON user_votes.user_id = $2
+                    AND user_votes.object_id = $3
+                    AND user_votes.object_type = $4
+                    AND user_votes.space_id = $5
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(block_number)
+            .bind(format!("0x{}", hex::encode(vote.user_id.as_slice())))
+            .bind(vote.object_id)
+            .bind(vote.object_type as i16)
+            .bind(vote.space_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for count in changeset.votes_count {
+            sqlx::query(
+                r#"
+                INSERT INTO changeset_votes_count (block_number, object_id, object_type, space_id, upvotes, downvotes)
+                SELECT $1, $2, $3, $4, votes_count.upvotes, votes_count.downvotes
+                FROM (SELECT 1) AS journaled
+                LEFT JOIN votes_count ON votes_count.object_id = $2
+                    AND votes_count.object_type = $3
+                    AND votes_count.space_id = $4
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(block_number)
+            .bind(count.object_id)
+            .bind(count.object_type as i16)
+            .bind(count.space_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for delta in changeset.leaderboard_deltas {
+            sqlx::query(
+                r#"
+                INSERT INTO changeset_leaderboards (block_number, space_id, time_window, window_start, object_id, object_type, upvotes, downvotes)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (block_number, space_id, time_window, window_start, object_id, object_type)
+                DO UPDATE SET
+                    upvotes = changeset_leaderboards.upvotes + EXCLUDED.upvotes,
+                    downvotes = changeset_leaderboards.downvotes + EXCLUDED.downvotes
+                "#,
+            )
+            .bind(block_number)
+            .bind(delta.space_id)
+            .bind(window_to_i16(delta.window))
+            .bind(timestamp_to_datetime(delta.window_start)?)
+            .bind(delta.object_id)
+            .bind(delta.object_type as i16)
+            .bind(delta.upvotes)
+            .bind(delta.downvotes)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for delta in changeset.activity_deltas {
+            sqlx::query(
+                r#"
+                INSERT INTO changeset_activity (block_number, bucket_start, space_id, sender, action_type, actions)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (block_number, bucket_start, space_id, sender, action_type)
+                DO UPDATE SET actions = changeset_activity.actions + EXCLUDED.actions
+                "#,
+            )
+            .bind(block_number)
+            .bind(timestamp_to_datetime(delta.bucket_start)?)
+            .bind(delta.space_id)
+            .bind(format!("0x{}", hex::encode(delta.sender.as_slice())))
+            .bind(delta.action_type as i16)
+            .bind(delta.actions)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Reverts every changeset persisted after a block within an active transaction.
+    ///
+    /// Each `user_votes` and `votes_count` row changed after `block_number` is restored to
+    /// the value journaled by the earliest later block, or deleted if it didn't exist then.
+    /// Journaled leaderboard and activity deltas are subtracted, actions of later blocks are
+    /// deleted and the journal entries of the reverted blocks are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - Last block to keep
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All later changesets reverted successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn rollback_to_tx(&self, block_number: BlockNumber, tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        let block_number = block_number as i64;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_votes (user_id, object_id, object_type, space_id, vote_type, voted_at, weight)
+            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight
+            FROM (
+                SELECT DISTINCT ON (user_id, object_id, object_type, space_id) *
+                FROM changeset_user_votes
+                WHERE block_number > $1
+                ORDER BY user_id, object_id, object_type, space_id, block_number
+            ) AS prior
+            WHERE vote_type IS NOT NULL
+            ON CONFLICT (user_id, object_id, object_type, space_id)
+            DO UPDATE SET
+                vote_type = EXCLUDED.vote_type,
+                voted_at = EXCLUDED.voted_at,
+                weight = EXCLUDED.weight
+            "#,
+        )
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM user_votes
+            USING (
+                SELECT DISTINCT ON (user_id, object_id, object_type, space_id) *
+                FROM changeset_user_votes
+                WHERE block_number > $1
+                ORDER BY user_id, object_id, object_type, space_id, block_number
+            ) AS prior
+            WHERE prior.vote_type IS NULL
+                AND user_votes.user_id = prior.user_id
+                AND user_votes.object_id = prior.object_id
+                AND user_votes.object_type = prior.object_type
+                AND user_votes.space_id = prior.space_id
+            "#,
+        )
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO votes_count (object_id, object_type, space_id, upvotes, downvotes)
+            SELECT object_id, object_type, space_id, upvotes, downvotes
+            FROM (
+                SELECT DISTINCT ON (object_id, object_type, space_id) *
+                FROM changeset_votes_count
+                WHERE block_number > $1
+                ORDER BY object_id, object_type, space_id, block_number
+            ) AS prior
+            WHERE upvotes IS NOT NULL
+            ON CONFLICT (object_id, object_type, space_id)
+            DO UPDATE SET
+                upvotes = EXCLUDED.upvotes,
+                downvotes = EXCLUDED.downvotes
+            "#,
+        )
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM votes_count
+            USING (
+                SELECT DISTINCT ON (object_id, object_type, space_id) *
+                FROM changeset_votes_count
+                WHERE block_number > $1
+                ORDER BY object_id, object_type, space_id, block_number
+            ) AS prior
+            WHERE prior.upvotes IS NULL
+                AND votes_count.object_id = prior.object_id
+                AND votes_count.object_type = prior.object_type
+                AND votes_count.space_id = prior.space_id
+            "#,
+        )
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE vote_leaderboards
+            SET
+                upvotes = vote_leaderboards.upvotes - applied.upvotes,
+                downvotes = vote_leaderboards.downvotes - applied.downvotes
+            FROM (
+                SELECT space_id, time_window, window_start, object_id, object_type,
+                    SUM(upvotes)::BIGINT AS upvotes, SUM(downvotes)::BIGINT AS downvotes
+                FROM changeset_leaderboards
+                WHERE block_number > $1
+                GROUP BY space_id, time_window, window_start, object_id, object_type
+            ) AS applied
+            WHERE vote_leaderboards.space_id = applied.space_id
+                AND vote_leaderboards.time_window = applied.time_window
+                AND vote_leaderboards.window_start = applied.window_start
+                AND vote_leaderboards.object_id = applied.object_id
+                AND vote_leaderboards.object_type = applied.object_type
+            "#,
+        )
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE action_activity
+            SET actions = action_activity.actions - applied.actions
+            FROM (
+                SELECT bucket_start, space_id, sender, action_type, SUM(actions)::BIGINT AS actions
+                FROM changeset_activity
+                WHERE block_number > $1
+                GROUP BY bucket_start, space_id, sender, action_type
+            ) AS applied
+            WHERE action_activity.bucket_start = applied.bucket_start
+                AND action_activity.space_id = applied.space_id
+                AND action_activity.sender = applied.sender
+                AND action_activity.action_type = applied.action_type
+            "#,
+        )
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        let tables = [
+            "raw_actions",
+            "flags",
+            "published_edits",
+            "membership_changes",
+            "changeset_user_votes",
+            "changeset_votes_count",
+            "changeset_leaderboards",
+            "changeset_activity",
+            "action_dead_letters",
+        ];
+        for table in tables {
+            sqlx::query(&format!("DELETE FROM {table} WHERE block_number > $1"))
+                .bind(block_number)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Drops the journal entries of final blocks within an active transaction.
+    ///
+    /// Final blocks can't be undone by a reorg, so their journal would only grow.
+    /// Rolling back to a block at or below `final_block_number` isn't possible anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `final_block_number` - Last irreversible block
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Journal entries pruned successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn prune_changesets_tx(&self, final_block_number: BlockNumber, tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        let tables = [
+            "changeset_user_votes",
+            "changeset_votes_count",
+            "changeset_leaderboards",
+            "changeset_activity",
+        ];
+        for table in tables {
+            sqlx::query(&format!("DELETE FROM {table} WHERE block_number <= $1"))
+                .bind(final_block_number as i64)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Persists changesets in order within an active transaction.
+    ///
+    /// The journal of the blocks that became final is pruned afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - Changesets of consecutive blocks, ordered by block number
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every changeset applied successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn persist_changesets_tx(&self, changesets: &[Changeset<'_>], tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        for changeset in changesets {
+            self.record_changeset_tx(changeset, tx).await?;
+            self.insert_actions_tx(changeset.actions, tx).await?;
+            self.update_user_votes_tx(changeset.user_votes, tx).await?;
+            self.update_votes_counts_tx(changeset.votes_count, tx).await?;
+            self.update_leaderboards_tx(changeset.leaderboard_deltas, tx).await?;
+            self.update_activity_tx(changeset.activity_deltas, tx).await?;
+        }
+        if let Some(final_block_number) = changesets.iter().map(|changeset| changeset.final_block_number).max() {
+            self.prune_changesets_tx(final_block_number, tx).await?;
+        }
+        Ok(())
+    }
+
+    /// Saves an indexer cursor in the `meta` table within an active transaction.
+    ///
+    /// Uses the same upsert as `PostgresCursorRepository::save_cursor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Indexer identifier
+    /// * `cursor` - Stream cursor to resume from
+    /// * `block_number` - Block number of the cursor
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Cursor saved successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn save_cursor_tx(&self, id: &str, cursor: &str, block_number: i64, tx: &mut sqlx::Transaction<'_, sqlx::Postgres>) -> Result<(), ActionsRepositoryError> {
+        sqlx::query(
+            "INSERT INTO meta (id, cursor, block_number) VALUES ($1, $2, $3) ON CONFLICT (id) DO UPDATE SET cursor = $2, block_number = $3",
+        )
+        .bind(id)
+        .bind(cursor)
+        .bind(block_number.to_string())
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Maps a leaderboard window to its `time_window` column value.
+fn window_to_i16(window: LeaderboardWindow) -> i16 {
+    match window {
+        LeaderboardWindow::Day => 0,
+        LeaderboardWindow::Week => 1,
+        LeaderboardWindow::Month => 2,
+        LeaderboardWindow::AllTime => 3,
+    }
+}
+
+/// Maps a `time_window` column value back to a leaderboard window.
+fn window_from_i16(value: i16) -> Result<LeaderboardWindow, ActionsRepositoryError> {
+    match value {
+        0 => Ok(LeaderboardWindow::Day),
+        1 => Ok(LeaderboardWindow::Week),
+        2 => Ok(LeaderboardWindow::Month),
+        3 => Ok(LeaderboardWindow::AllTime),
+        _ => Err(ActionsRepositoryError::InvalidLeaderboardWindow(value)),
+    }
+}
+
+fn timestamp_to_datetime(timestamp: u64) -> Result<OffsetDateTime, ActionsRepositoryError> {
+    OffsetDateTime::from_unix_timestamp(timestamp as i64)
+        .map_err(|_| ActionsRepositoryError::InvalidTimestamp(timestamp))
+}
+
+/// Maps a `user_votes` row to a user vote.
+fn user_vote_from_row(row: &PgRow) -> Result<UserVote, ActionsRepositoryError> {
+    let user_id: String = row.get("user_id");
+    let object_type: i16 = row.get("object_type");
+    let vote_type: i16 = row.get("vote_type");
+    let voted_at: OffsetDateTime = row.get("voted_at");
+    let weight: i64 = row.get("weight");
+    Ok(UserVote {
+        user_id: Address::from_hex(&user_id).map_err(|_| ActionsRepositoryError::InvalidAddress(user_id))?,
+        object_id: row.get("object_id"),
+        space_id: row.get("space_id"),
+        object_type: match object_type {
+            0 => ObjectType::Entity,
+            1 => ObjectType::Relation,
+            _ => return Err(ActionsRepositoryError::InvalidObjectType(object_type)),
+        },
+        vote_type: match vote_type {
+            0 => VoteValue::Up,
+            1 => VoteValue::Down,
+            2 => VoteValue::Remove,
+            _ => return Err(ActionsRepositoryError::InvalidVoteType(vote_type)),
+        },
+        voted_at: voted_at.unix_timestamp() as u64,
+        weight: weight as u64,
+    })
 }
 
 #[async_trait]
@@ -263,10 +907,34 @@ impl ActionsRepository for PostgresActionsRepository {
         Ok(())
     }
 
+    /// Applies leaderboard deltas in a new transaction.
+    ///
+    /// Adds each delta to the stored totals of its leaderboard entry.
+    /// Empty slices are handled efficiently as no-ops.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaderboard_deltas` - Leaderboard deltas to apply
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All deltas applied successfully
+    /// * `Err(ActionsRepositoryError)` - Transaction or update failure
+    async fn update_leaderboards(
+        &self,
+        leaderboard_deltas: &[LeaderboardDelta],
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        self.update_leaderboards_tx(leaderboard_deltas, &mut tx).await?;
+        tx.commit().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        Ok(())
+    }
+
     /// Atomically persists a complete changeset in a single transaction.
     ///
-    /// Bundles actions, user votes, and vote counts together for atomic persistence.
-    /// Either all changes succeed or all are rolled back on failure.
+    /// Bundles actions, user votes, vote counts, and leaderboard deltas together for atomic persistence.
+    /// Either all changes succeed or all are rolled back on failure. The values the changeset
+    /// overwrites are journaled under its block number first, so it can be rolled back later.
     ///
     /// # Arguments
     ///
@@ -279,15 +947,183 @@ impl ActionsRepository for PostgresActionsRepository {
     async fn persist_changeset(
         &self,
         changeset: &Changeset<'_>,
+    ) -> Result<(), ActionsRepositoryError> {
+        self.persist_changesets(std::slice::from_ref(changeset)).await
+    }
+
+    /// Atomically persists several changesets in a single transaction.
+    ///
+    /// Applies the changesets in order, journaling each one under its own block number
+    /// before writing it, so a batch can still be rolled back block by block.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - Changesets of consecutive blocks, ordered by block number
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every changeset persisted successfully
+    /// * `Err(ActionsRepositoryError)` - Transaction failure with automatic rollback
+    async fn persist_changesets(
+        &self,
+        changesets: &[Changeset<'_>],
     ) -> Result<(), ActionsRepositoryError> {
         let mut tx = self.pool.begin().await.map_err(|e| ActionsRepositoryError::DatabaseError(e))?;
-        self.insert_actions_tx(changeset.actions, &mut tx).await?;
-        self.update_user_votes_tx(changeset.user_votes, &mut tx).await?;
-        self.update_votes_counts_tx(changeset.votes_count, &mut tx).await?;
+        self.persist_changesets_tx(changesets, &mut tx).await?;
         tx.commit().await.map_err(|e| ActionsRepositoryError::DatabaseError(e))?;
         Ok(())
     }
 
+    /// Atomically persists several changesets and the cursor of their last block.
+    ///
+    /// Applies the changesets like `persist_changesets` and upserts the cursor into
+    /// the `meta` table in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - Changesets of consecutive blocks, ordered by block number
+    /// * `id` - Indexer identifier the cursor is saved under
+    /// * `cursor` - Stream cursor of the last block
+    /// * `block_number` - Block number of the cursor
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Changesets and cursor persisted successfully
+    /// * `Err(ActionsRepositoryError)` - Transaction failure with automatic rollback
+    async fn persist_changesets_with_cursor(
+        &self,
+        changesets: &[Changeset<'_>],
+        id: &str,
+        cursor: &str,
+        block_number: i64,
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        self.persist_changesets_tx(changesets, &mut tx).await?;
+        self.save_cursor_tx(id, cursor, block_number, &mut tx).await?;
+        tx.commit().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        Ok(())
+    }
+
+    /// Rolls back every changeset persisted after a block in a new transaction.
+    ///
+    /// Restores user votes and vote counts from the changeset journal, subtracts
+    /// journaled leaderboard deltas and deletes later actions atomically.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - Last block to keep
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All later changesets rolled back successfully
+    /// * `Err(ActionsRepositoryError)` - Transaction failure with automatic rollback
+    async fn rollback_to(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        self.rollback_to_tx(block_number, &mut tx).await?;
+        tx.commit().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        Ok(())
+    }
+
+    /// Rolls back every changeset persisted after a block and saves the cursor of
+    /// that block in a new transaction.
+    ///
+    /// Reverts the changesets like `rollback_to` and upserts the cursor into the
+    /// `meta` table in the same transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - Last block to keep
+    /// * `id` - Indexer identifier the cursor is saved under
+    /// * `cursor` - Stream cursor of the last block to keep
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Changesets rolled back and cursor saved successfully
+    /// * `Err(ActionsRepositoryError)` - Transaction failure with automatic rollback
+    async fn rollback_to_with_cursor(
+        &self,
+        block_number: BlockNumber,
+        id: &str,
+        cursor: &str,
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        self.rollback_to_tx(block_number, &mut tx).await?;
+        self.save_cursor_tx(id, cursor, block_number as i64, &mut tx).await?;
+        tx.commit().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        Ok(())
+    }
+
+    /// Retrieves the keys of the given actions that are already stored.
+    ///
+    /// Uses PostgreSQL's UNNEST function to look up all (tx_hash, log_index) and
+    /// (tx_hash, output_position) pairs in a single query against the unique indexes
+    /// of `raw_actions`.
+    ///
+    /// # Arguments
+    ///
+    /// * `action_keys` - Tuples of (tx_hash, action index) to look up
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ActionKey>)` - Keys already stored (empty if none found)
+    /// * `Err(ActionsRepositoryError)` - Database query failure
+    async fn get_persisted_action_keys(&self, action_keys: &[ActionKey]) -> Result<Vec<ActionKey>, ActionsRepositoryError> {
+        if action_keys.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (mut log_tx_hashes, mut log_indexes) = (Vec::new(), Vec::new());
+        let (mut position_tx_hashes, mut positions) = (Vec::new(), Vec::new());
+        for (tx_hash, index) in action_keys {
+            let tx_hash = format!("0x{}", hex::encode(tx_hash.as_slice()));
+            match index {
+                ActionIndex::Log(log_index) => {
+                    log_tx_hashes.push(tx_hash);
+                    log_indexes.push(i64::from(*log_index));
+                }
+                ActionIndex::OutputPosition(position) => {
+                    position_tx_hashes.push(tx_hash);
+                    positions.push(i64::from(*position));
+                }
+            }
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT tx_hash, log_index, output_position
+            FROM raw_actions
+            WHERE (tx_hash, log_index) IN (SELECT * FROM UNNEST($1::text[], $2::bigint[]))
+               OR (tx_hash, output_position) IN (SELECT * FROM UNNEST($3::text[], $4::bigint[]))
+            "#,
+        )
+        .bind(&log_tx_hashes)
+        .bind(&log_indexes)
+        .bind(&position_tx_hashes)
+        .bind(&positions)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut keys = Vec::with_capacity(rows.len());
+        for row in rows {
+            let tx_hash: String = row.get("tx_hash");
+            let log_index: Option<i64> = row.get("log_index");
+            let output_position: Option<i64> = row.get("output_position");
+            let index = match log_index {
+                Some(log_index) => ActionIndex::Log(log_index as u32),
+                None => ActionIndex::OutputPosition(output_position.unwrap_or_default() as u32),
+            };
+            keys.push((
+                TxHash::from_hex(&tx_hash).map_err(|_| ActionsRepositoryError::InvalidTxHash(tx_hash))?,
+                index,
+            ));
+        }
+
+        Ok(keys)
+    }
+
     /// Retrieves user votes matching the specified criteria.
     ///
     /// Uses PostgreSQL's UNNEST function for efficient batch queries of multiple
@@ -313,7 +1149,7 @@ impl ActionsRepository for PostgresActionsRepository {
 
         let votes = sqlx::query!(
             r#"
-            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at
+            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight
             FROM user_votes
             WHERE (user_id, object_id, space_id, object_type) IN (SELECT * FROM UNNEST($1::text[], $2::uuid[], $3::uuid[], $4::smallint[]))
             "#,
@@ -343,6 +1179,7 @@ impl ActionsRepository for PostgresActionsRepository {
                     _ => return Err(ActionsRepositoryError::InvalidVoteType(v.vote_type)),
                 },
                 voted_at: v.voted_at.unix_timestamp() as u64,
+                weight: v.weight as u64,
             });
         }
 
@@ -402,25 +1239,461 @@ impl ActionsRepository for PostgresActionsRepository {
         Ok(result_counts)
     }
 
+    /// Retrieves the most upvoted objects of a space for a time window.
+    ///
+    /// Reads the window containing `timestamp` from `vote_leaderboards`, using the
+    /// rank index to avoid sorting `votes_count`. Ties are broken by fewer downvotes.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - Space to rank objects in
+    /// * `window` - Time window to rank votes over
+    /// * `timestamp` - Unix timestamp within the window to read
+    /// * `limit` - Maximum number of entries to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<LeaderboardEntry>)` - Entries with upvotes, highest first
+    /// * `Err(ActionsRepositoryError)` - Database query failure
+    async fn get_leaderboard(
+        &self,
+        space_id: &SpaceId,
+        window: LeaderboardWindow,
+        timestamp: u64,
+        limit: i64,
+    ) -> Result<Vec<LeaderboardEntry>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT object_id, object_type, space_id, time_window, window_start, upvotes, downvotes
+            FROM vote_leaderboards
+            WHERE space_id = $1 AND time_window = $2 AND window_start = $3 AND upvotes > 0
+            ORDER BY upvotes DESC, downvotes ASC, object_id
+            LIMIT $4
+            "#,
+        )
+        .bind(space_id)
+        .bind(window_to_i16(window))
+        .bind(timestamp_to_datetime(window.window_start(timestamp))?)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let object_type: i16 = row.get("object_type");
+            let window_start: OffsetDateTime = row.get("window_start");
+            entries.push(LeaderboardEntry {
+                object_id: row.get("object_id"),
+                space_id: row.get("space_id"),
+                object_type: match object_type {
+                    0 => ObjectType::Entity,
+                    1 => ObjectType::Relation,
+                    _ => return Err(ActionsRepositoryError::InvalidObjectType(object_type)),
+                },
+                window: window_from_i16(row.get("time_window"))?,
+                window_start: window_start.unix_timestamp() as u64,
+                upvotes: row.get("upvotes"),
+                downvotes: row.get("downvotes"),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Retrieves a page of the current votes on an object, latest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - UUID of the voted object
+    /// * `space_id` - UUID of the space the votes were cast in
+    /// * `object_type` - Type of the voted object
+    /// * `limit` - Maximum number of votes to return
+    /// * `offset` - Number of votes to skip
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<UserVote>)` - Upvotes and downvotes on the object (empty if none found)
+    /// * `Err(ActionsRepositoryError)` - Database query failure
+    async fn get_object_votes(
+        &self,
+        object_id: &ObjectId,
+        space_id: &SpaceId,
+        object_type: ObjectType,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserVote>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight
+            FROM user_votes
+            WHERE object_id = $1 AND space_id = $2 AND object_type = $3 AND vote_type <> 2
+            ORDER BY voted_at DESC, user_id
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(object_id)
+        .bind(space_id)
+        .bind(object_type as i16)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(user_vote_from_row).collect()
+    }
+
+    /// Retrieves a page of the votes cast by a user, latest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - Address of the voter
+    /// * `limit` - Maximum number of votes to return
+    /// * `offset` - Number of votes to skip
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<UserVote>)` - The user's latest vote on each object (empty if none found)
+    /// * `Err(ActionsRepositoryError)` - Database query failure
+    async fn get_votes_by_user(
+        &self,
+        user_id: &UserAddress,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserVote>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight
+            FROM user_votes
+            WHERE user_id = $1
+            ORDER BY voted_at DESC, object_id, space_id, object_type
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(format!("0x{}", hex::encode(user_id.as_slice())))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(user_vote_from_row).collect()
+    }
+
+    /// Retrieves a page of the vote counts of a space, highest score first.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - UUID of the space to rank
+    /// * `limit` - Maximum number of vote counts to return
+    /// * `offset` - Number of vote counts to skip
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<VotesCount>)` - Vote counts ranked by score (empty if none found)
+    /// * `Err(ActionsRepositoryError)` - Database query failure
+    async fn get_top_objects(
+        &self,
+        space_id: &SpaceId,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<VotesCount>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT object_id, object_type, space_id, upvotes, downvotes
+            FROM votes_count
+            WHERE space_id = $1
+            ORDER BY upvotes - downvotes DESC, upvotes DESC, object_id, object_type
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(space_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut counts = Vec::with_capacity(rows.len());
+        for row in rows {
+            let object_type: i16 = row.get("object_type");
+            counts.push(VotesCount {
+                object_id: row.get("object_id"),
+                space_id: row.get("space_id"),
+                object_type: match object_type {
+                    0 => ObjectType::Entity,
+                    1 => ObjectType::Relation,
+                    _ => return Err(ActionsRepositoryError::InvalidObjectType(object_type)),
+                },
+                upvotes: row.get("upvotes"),
+                downvotes: row.get("downvotes"),
+            });
+        }
+
+        Ok(counts)
+    }
+
+    /// Reconciles a page of vote counts with the weighted user votes in a new transaction.
+    ///
+    /// Pages over the keys of both `user_votes` and `votes_count`, so counts without any
+    /// user vote left are zeroed and missing counts are created. Repairs compare and set
+    /// the stored counts, skipping the ones changed since they were read.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - Last (object_id, space_id, object_type) of the previous page, if any
+    /// * `limit` - Maximum number of vote counts to check
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VotesCountReconciliation)` - Checked count, last key and repaired drifts
+    /// * `Err(ActionsRepositoryError)` - Transaction failure with automatic rollback
+    async fn reconcile_vote_counts(
+        &self,
+        after: Option<VoteCountCriteria>,
+        limit: i64,
+    ) -> Result<VotesCountReconciliation, ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await.map_err(ActionsRepositoryError::DatabaseError)?;
+
+        let rows = sqlx::query(
+            r#"
+            WITH page AS (
+                SELECT object_id, space_id, object_type
+                FROM (
+                    SELECT object_id, space_id, object_type FROM user_votes
+                    UNION
+                    SELECT object_id, space_id, object_type FROM votes_count
+                ) AS counted
+                WHERE $1::uuid IS NULL OR (object_id, space_id, object_type) > ($1::uuid, $2::uuid, $3::smallint)
+                ORDER BY object_id, space_id, object_type
+                LIMIT $4
+            )
+            SELECT
+                page.object_id,
+                page.space_id,
+                page.object_type,
+                COALESCE(SUM(user_votes.weight) FILTER (WHERE user_votes.vote_type = 0), 0)::BIGINT AS upvotes,
+                COALESCE(SUM(user_votes.weight) FILTER (WHERE user_votes.vote_type = 1), 0)::BIGINT AS downvotes,
+                votes_count.upvotes AS stored_upvotes,
+                votes_count.downvotes AS stored_downvotes
+            FROM page
+            LEFT JOIN user_votes ON user_votes.object_id = page.object_id
+                AND user_votes.space_id = page.space_id
+                AND user_votes.object_type = page.object_type
+            LEFT JOIN votes_count ON votes_count.object_id = page.object_id
+                AND votes_count.space_id = page.space_id
+                AND votes_count.object_type = page.object_type
+            GROUP BY page.object_id, page.space_id, page.object_type, votes_count.upvotes, votes_count.downvotes
+            ORDER BY page.object_id, page.space_id, page.object_type
+            "#,
+        )
+        .bind(after.map(|(object_id, _, _)| object_id))
+        .bind(after.map(|(_, space_id, _)| space_id))
+        .bind(after.map(|(_, _, object_type)| object_type as i16))
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut reconciliation = VotesCountReconciliation {
+            checked: rows.len(),
+            ..Default::default()
+        };
+
+        for row in rows {
+            let object_type: i16 = row.get("object_type");
+            let expected = VotesCount {
+                object_id: row.get("object_id"),
+                space_id: row.get("space_id"),
+                object_type: match object_type {
+                    0 => ObjectType::Entity,
+                    1 => ObjectType::Relation,
+                    _ => return Err(ActionsRepositoryError::InvalidObjectType(object_type)),
+                },
+                upvotes: row.get("upvotes"),
+                downvotes: row.get("downvotes"),
+            };
+            let stored: Option<(i64, i64)> = row
+                .get::<Option<i64>, _>("stored_upvotes")
+                .zip(row.get::<Option<i64>, _>("stored_downvotes"));
+            reconciliation.last_checked = Some((expected.object_id, expected.space_id, expected.object_type));
+
+            let repaired = match stored {
+                Some(stored) if stored == (expected.upvotes, expected.downvotes) => false,
+                Some((stored_upvotes, stored_downvotes)) => sqlx::query(
+                    r#"
+                    UPDATE votes_count
+                    SET upvotes = $4, downvotes = $5
+                    WHERE object_id = $1 AND space_id = $2 AND object_type = $3
+                        AND upvotes = $6 AND downvotes = $7
+                    "#,
+                )
+                .bind(expected.object_id)
+                .bind(expected.space_id)
+                .bind(object_type)
+                .bind(expected.upvotes)
+                .bind(expected.downvotes)
+                .bind(stored_upvotes)
+                .bind(stored_downvotes)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected() > 0,
+                None if expected.upvotes == 0 && expected.downvotes == 0 => false,
+                None => sqlx::query(
+                    r#"
+                    INSERT INTO votes_count (object_id, space_id, object_type, upvotes, downvotes)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT (object_id, object_type, space_id) DO NOTHING
+                    "#,
+                )
+                .bind(expected.object_id)
+                .bind(expected.space_id)
+                .bind(object_type)
+                .bind(expected.upvotes)
+                .bind(expected.downvotes)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected() > 0,
+            };
+
+            if repaired {
+                let (stored_upvotes, stored_downvotes) = stored.unwrap_or_default();
+                reconciliation.repaired.push(VotesCountDrift {
+                    expected,
+                    stored_upvotes,
+                    stored_downvotes,
+                });
+            }
+        }
+
+        tx.commit().await.map_err(ActionsRepositoryError::DatabaseError)?;
+        Ok(reconciliation)
+    }
+
+    /// Stores dead letters using PostgreSQL's UNNEST function for a single bulk insert.
+    ///
+    /// Conflicts on the unique (block_number, md5(raw)) index are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `dead_letters` - Failed events to store
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Successful insertion (including empty input)
+    /// * `Err(ActionsRepositoryError)` - Database operation failure
+    async fn insert_dead_letters(&self, dead_letters: &[DeadLetter]) -> Result<(), ActionsRepositoryError> {
+        if dead_letters.is_empty() {
+            return Ok(());
+        }
+
+        let block_numbers: Vec<i64> = dead_letters.iter().map(|d| d.block_number as i64).collect();
+        let raws: Vec<Vec<u8>> = dead_letters.iter().map(|d| d.raw.clone()).collect();
+        let errors: Vec<String> = dead_letters.iter().map(|d| d.error.clone()).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO action_dead_letters (block_number, raw, error)
+            SELECT * FROM UNNEST($1::bigint[], $2::bytea[], $3::text[])
+            ON CONFLICT (block_number, md5(raw)) DO NOTHING
+            "#,
+        )
+        .bind(&block_numbers)
+        .bind(&raws)
+        .bind(&errors)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Retrieves a page of pending dead letters, oldest first.
+    ///
+    /// # Arguments
+    ///
+    /// * `after` - Id of the last dead letter of the previous page, if any
+    /// * `limit` - Maximum number of dead letters to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(DeadLetterId, DeadLetter)>)` - Pending dead letters (empty if none found)
+    /// * `Err(ActionsRepositoryError)` - Database query failure
+    async fn get_pending_dead_letters(
+        &self,
+        after: Option<DeadLetterId>,
+        limit: i64,
+    ) -> Result<Vec<(DeadLetterId, DeadLetter)>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, block_number, raw, error
+            FROM action_dead_letters
+            WHERE reprocessed_at IS NULL AND id > $1
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(after.unwrap_or(0))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let block_number: i64 = row.get("block_number");
+                (
+                    row.get("id"),
+                    DeadLetter {
+                        block_number: block_number as u64,
+                        raw: row.get("raw"),
+                        error: row.get("error"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Marks dead letters as reprocessed now.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - Ids of the reprocessed dead letters
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Successful update (including empty input)
+    /// * `Err(ActionsRepositoryError)` - Database operation failure
+    async fn mark_dead_letters_reprocessed(&self, ids: &[DeadLetterId]) -> Result<(), ActionsRepositoryError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query("UPDATE action_dead_letters SET reprocessed_at = NOW() WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Checks if the tables are created in the database.
     ///
-    /// This method checks if the tables are created in the database.
+    /// This method checks if the tables are created in the schemas of the
+    /// `search_path`, so tables of another schema don't count.
     ///
     /// # Returns
     ///
     /// * `Ok(true)` - If the tables are created
     async fn check_tables_created(&self) -> Result<bool, ActionsRepositoryError> {
-        let tables = vec!["raw_actions", "user_votes", "votes_count"];
-        for table in tables {
-            let table_exists: bool = sqlx::query_scalar!(
+        for table in super::ACTIONS_TABLES {
+            // Only the schemas of the search path count, see `super::connect`
+            let table_exists: bool = sqlx::query_scalar(
                 r#"
-                SELECT EXISTS(SELECT 1 FROM information_schema.tables WHERE table_name = $1)
+                SELECT EXISTS(
+                    SELECT 1 FROM information_schema.tables
+                    WHERE table_name = $1 AND table_schema = ANY(current_schemas(false))
+                )
                 "#,
-                table
             )
+            .bind(table)
             .fetch_one(&self.pool)
-            .await?
-            .unwrap_or(false);
+            .await?;
             if !table_exists {
                 return Ok(false);
             }
@@ -0,0 +1,155 @@
+//! PostgreSQL implementation of the activity repository.
+//!
+//! Reads the hourly action counters of the `action_activity` table, keyed by bucket, space,
+//! sender and action type, and ranks spaces and senders by summing the recent buckets.
+
+use crate::ActivityRepository;
+use crate::errors::ActivityRepositoryError;
+use actions_indexer_shared::types::{activity_bucket_start, ActionType, SenderActivity, SpaceActivity, SpaceId};
+use alloy::hex::FromHex;
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use sqlx::Row;
+use time::OffsetDateTime;
+
+/// PostgreSQL-backed activity repository.
+///
+/// Reads and prunes the counters that `PostgresActionsRepository` maintains incrementally
+/// with each changeset, so counters are never recomputed from `raw_actions`.
+pub struct PostgresActivityRepository {
+    /// PostgreSQL connection pool
+    pool: sqlx::PgPool,
+}
+
+impl PostgresActivityRepository {
+    /// Creates a new PostgreSQL activity repository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Configured PostgreSQL connection pool with required schema (action_activity table)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PostgresActivityRepository)` - Ready-to-use repository instance
+    /// * `Err(ActivityRepositoryError)` - Future validation errors (currently always succeeds)
+    pub async fn new(pool: sqlx::PgPool) -> Result<Self, ActivityRepositoryError> {
+        Ok(Self { pool })
+    }
+}
+
+/// Converts the start of a bucket to a `TIMESTAMPTZ` value.
+fn bucket_to_datetime(timestamp: u64) -> Result<OffsetDateTime, ActivityRepositoryError> {
+    OffsetDateTime::from_unix_timestamp(activity_bucket_start(timestamp) as i64)
+        .map_err(|_| ActivityRepositoryError::InvalidTimestamp(timestamp))
+}
+
+#[async_trait]
+impl ActivityRepository for PostgresActivityRepository {
+    /// Ranks spaces by the number of actions in the buckets since `since`.
+    ///
+    /// Ties are broken by space id so pages are stable.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - Unix timestamp to count actions from
+    /// * `action_type` - Action type to count, or `None` for every action
+    /// * `limit` - Maximum number of spaces to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SpaceActivity>)` - Spaces with actions, most active first
+    /// * `Err(ActivityRepositoryError)` - Database query failure
+    async fn get_most_active_spaces(&self, since: u64, action_type: Option<ActionType>, limit: i64) -> Result<Vec<SpaceActivity>, ActivityRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT space_id, SUM(actions)::BIGINT AS actions
+            FROM action_activity
+            WHERE bucket_start >= $1 AND ($2::SMALLINT IS NULL OR action_type = $2)
+            GROUP BY space_id
+            HAVING SUM(actions) > 0
+            ORDER BY actions DESC, space_id
+            LIMIT $3
+            "#,
+        )
+        .bind(bucket_to_datetime(since)?)
+        .bind(action_type.map(|action_type| action_type as i16))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SpaceActivity {
+                space_id: row.get("space_id"),
+                actions: row.get("actions"),
+            })
+            .collect())
+    }
+
+    /// Ranks senders by the number of actions in the buckets since `since`.
+    ///
+    /// Ties are broken by sender address so pages are stable.
+    ///
+    /// # Arguments
+    ///
+    /// * `since` - Unix timestamp to count actions from
+    /// * `space_id` - Space to count actions in, or `None` for every space
+    /// * `action_type` - Action type to count, or `None` for every action
+    /// * `limit` - Maximum number of senders to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SenderActivity>)` - Senders with actions, most active first
+    /// * `Err(ActivityRepositoryError)` - Database query failure or malformed address
+    async fn get_most_active_senders(&self, since: u64, space_id: Option<SpaceId>, action_type: Option<ActionType>, limit: i64) -> Result<Vec<SenderActivity>, ActivityRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT sender, SUM(actions)::BIGINT AS actions
+            FROM action_activity
+            WHERE bucket_start >= $1
+                AND ($2::UUID IS NULL OR space_id = $2)
+                AND ($3::SMALLINT IS NULL OR action_type = $3)
+            GROUP BY sender
+            HAVING SUM(actions) > 0
+            ORDER BY actions DESC, sender
+            LIMIT $4
+            "#,
+        )
+        .bind(bucket_to_datetime(since)?)
+        .bind(space_id)
+        .bind(action_type.map(|action_type| action_type as i16))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut senders = Vec::with_capacity(rows.len());
+        for row in rows {
+            let sender: String = row.get("sender");
+            senders.push(SenderActivity {
+                sender: Address::from_hex(&sender).map_err(|_| ActivityRepositoryError::InvalidAddress(sender))?,
+                actions: row.get("actions"),
+            });
+        }
+
+        Ok(senders)
+    }
+
+    /// Deletes the counters of the buckets that started before `before`.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - Unix timestamp before which buckets are deleted
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - Number of deleted counters
+    /// * `Err(ActivityRepositoryError)` - Database query failure
+    async fn prune_activity(&self, before: u64) -> Result<u64, ActivityRepositoryError> {
+        let result = sqlx::query("DELETE FROM action_activity WHERE bucket_start < $1")
+            .bind(bucket_to_datetime(before)?)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,199 @@
+//! PostgreSQL implementation of the backfill repository.
+//!
+//! Stages backfills in schemas of their own, created with the migrations of the actions
+//! tables, and swaps them with the production tables by moving tables between schemas,
+//! which only touches the catalog and commits in a single transaction.
+
+use crate::BackfillRepository;
+use crate::errors::BackfillRepositoryError;
+use actions_indexer_shared::types::ActionsTableCounts;
+use async_trait::async_trait;
+use sqlx::Row;
+
+/// PostgreSQL-backed backfill repository.
+///
+/// Schema names are quoted before being interpolated in statements, as identifiers
+/// can't be bound as parameters.
+pub struct PostgresBackfillRepository {
+    /// PostgreSQL connection pool
+    pool: sqlx::PgPool,
+}
+
+impl PostgresBackfillRepository {
+    /// Creates a new PostgreSQL backfill repository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - Configured PostgreSQL connection pool, its `search_path` is irrelevant
+    ///   as every statement names its schema
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PostgresBackfillRepository)` - Ready-to-use repository instance
+    /// * `Err(BackfillRepositoryError)` - Future validation errors (currently always succeeds)
+    pub async fn new(pool: sqlx::PgPool) -> Result<Self, BackfillRepositoryError> {
+        Ok(Self { pool })
+    }
+}
+
+/// Quotes an identifier, doubling its quotes.
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[async_trait]
+impl BackfillRepository for PostgresBackfillRepository {
+    /// Creates a schema and runs the migrations in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema to create
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The schema holds empty, migrated actions tables
+    /// * `Err(BackfillRepositoryError)` - The schema exists, or a migration failed
+    async fn create_schema(&self, schema: &str) -> Result<(), BackfillRepositoryError> {
+        sqlx::query(&format!("CREATE SCHEMA {}", quote(schema)))
+            .execute(&self.pool)
+            .await?;
+
+        let options = (*self.pool.connect_options()).clone();
+        let pool = super::connect_with(options, Some(schema)).await?;
+        let result = super::migrate(&pool).await;
+        pool.close().await;
+        Ok(result?)
+    }
+
+    /// Counts the actions and dead letters of a block range, and the vote tables in full.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema to count the rows of
+    /// * `start_block` - The first block of the range
+    /// * `stop_block` - The last block of the range
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ActionsTableCounts)` - The counts of the schema
+    /// * `Err(BackfillRepositoryError)` - Database error, e.g. a missing table
+    async fn count_rows(&self, schema: &str, start_block: u64, stop_block: u64) -> Result<ActionsTableCounts, BackfillRepositoryError> {
+        let schema = quote(schema);
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM {schema}.raw_actions WHERE block_number BETWEEN $1 AND $2) AS raw_actions,
+                (SELECT COUNT(*) FROM {schema}.action_dead_letters WHERE block_number BETWEEN $1 AND $2) AS dead_letters,
+                (SELECT COUNT(*) FROM {schema}.user_votes) AS user_votes,
+                (SELECT COUNT(*) FROM {schema}.votes_count) AS votes_count,
+                (SELECT COALESCE(SUM(upvotes), 0)::BIGINT FROM {schema}.votes_count) AS upvotes,
+                (SELECT COALESCE(SUM(downvotes), 0)::BIGINT FROM {schema}.votes_count) AS downvotes
+            "#
+        ))
+        .bind(start_block as i64)
+        .bind(stop_block as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ActionsTableCounts {
+            raw_actions: row.get("raw_actions"),
+            dead_letters: row.get("dead_letters"),
+            user_votes: row.get("user_votes"),
+            votes_count: row.get("votes_count"),
+            upvotes: row.get("upvotes"),
+            downvotes: row.get("downvotes"),
+        })
+    }
+
+    /// Retrieves the block of a cursor from the `meta` table of a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the `meta` table
+    /// * `id` - The ID the cursor is stored under
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(block_number))` - The block of the cursor
+    /// * `Ok(None)` - No cursor is stored under `id`
+    /// * `Err(BackfillRepositoryError)` - Database error
+    async fn get_cursor_block(&self, schema: &str, id: &str) -> Result<Option<i64>, BackfillRepositoryError> {
+        // Block numbers of cursors are stored as text
+        let block_number = sqlx::query_scalar(&format!("SELECT block_number::BIGINT FROM {}.meta WHERE id = $1", quote(schema)))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(block_number)
+    }
+
+    /// Retrieves the first block holding actions from the `raw_actions` table of a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The schema of the `raw_actions` table
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(block_number))` - The lowest block number of the actions
+    /// * `Ok(None)` - The table holds no actions
+    /// * `Err(BackfillRepositoryError)` - Database error
+    async fn get_first_block(&self, schema: &str) -> Result<Option<i64>, BackfillRepositoryError> {
+        let block_number = sqlx::query_scalar(&format!("SELECT MIN(block_number) FROM {}.raw_actions", quote(schema)))
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(block_number)
+    }
+
+    /// Moves the production tables to the archive schema and the staging tables to the
+    /// production schema in a single transaction.
+    ///
+    /// Moving a table takes an exclusive lock on it, so readers wait for the swap to
+    /// commit, then read the new tables. Indexes and sequences move along with their
+    /// tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `staging` - The schema holding the new actions tables
+    /// * `production` - The schema the new actions tables replace the tables of
+    /// * `archive` - The schema the replaced tables are moved to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The tables were swapped
+    /// * `Err(BackfillRepositoryError)` - A table is missing from `staging` or
+    ///   `production`, the archive exists, or a database error, leaving every table
+    ///   in place
+    async fn swap_schemas(&self, staging: &str, production: &str, archive: &str) -> Result<(), BackfillRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        for schema in [staging, production] {
+            let tables: Vec<String> = sqlx::query_scalar(
+                "SELECT table_name::TEXT FROM information_schema.tables WHERE table_schema = $1 AND table_name = ANY($2)",
+            )
+            .bind(schema)
+            .bind(&super::ACTIONS_TABLES[..])
+            .fetch_all(&mut *tx)
+            .await?;
+            if let Some(missing) = super::ACTIONS_TABLES.iter().find(|table| !tables.iter().any(|t| t == *table)) {
+                return Err(BackfillRepositoryError::MissingTable(format!("{schema}.{missing}")));
+            }
+        }
+
+        sqlx::query(&format!("CREATE SCHEMA {}", quote(archive)))
+            .execute(&mut *tx)
+            .await?;
+        for table in super::ACTIONS_TABLES {
+            sqlx::query(&format!("ALTER TABLE {}.{table} SET SCHEMA {}", quote(production), quote(archive)))
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(&format!("ALTER TABLE {}.{table} SET SCHEMA {}", quote(staging), quote(production)))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+CREATE TABLE vote_leaderboards (
+    space_id        UUID NOT NULL,
+    time_window     SMALLINT NOT NULL,
+    window_start    TIMESTAMPTZ NOT NULL,
+    object_id       UUID NOT NULL,
+    object_type     SMALLINT NOT NULL,
+    upvotes         BIGINT NOT NULL DEFAULT 0,
+    downvotes       BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (space_id, time_window, window_start, object_id, object_type)
+);
+
+CREATE INDEX idx_vote_leaderboards_rank ON vote_leaderboards(space_id, time_window, window_start, upvotes DESC);
+
+-- Backfill every window from the current user votes. time_window values:
+-- 0 = day, 1 = week, 2 = month, 3 = all time
+INSERT INTO vote_leaderboards (space_id, time_window, window_start, object_id, object_type, upvotes, downvotes)
+SELECT
+    space_id,
+    windows.time_window,
+    CASE windows.time_window
+        WHEN 0 THEN date_trunc('day', voted_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+        WHEN 1 THEN date_trunc('week', voted_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+        WHEN 2 THEN date_trunc('month', voted_at AT TIME ZONE 'UTC') AT TIME ZONE 'UTC'
+        ELSE to_timestamp(0)
+    END AS window_start,
+    object_id,
+    object_type,
+    COUNT(*) FILTER (WHERE vote_type = 0),
+    COUNT(*) FILTER (WHERE vote_type = 1)
+FROM user_votes
+CROSS JOIN (VALUES (0::smallint), (1::smallint), (2::smallint), (3::smallint)) AS windows(time_window)
+WHERE vote_type IN (0, 1)
+GROUP BY space_id, windows.time_window, window_start, object_id, object_type;
@@ -0,0 +1,43 @@
+-- Per-block journal of the changesets persisted by the loader, read by rollback_to.
+-- Inserted actions are identified by raw_actions.block_number, so only the values
+-- overwritten by a changeset are recorded here.
+
+-- Rows of user_votes before the block changed them. vote_type and voted_at are
+-- NULL when the row didn't exist yet.
+CREATE TABLE changeset_user_votes (
+    block_number    BIGINT NOT NULL,
+    user_id         VARCHAR(42) NOT NULL,
+    object_id       UUID NOT NULL,
+    object_type     SMALLINT NOT NULL,
+    space_id        UUID NOT NULL,
+    vote_type       SMALLINT,
+    voted_at        TIMESTAMPTZ,
+    PRIMARY KEY (block_number, user_id, object_id, object_type, space_id)
+);
+
+-- Rows of votes_count before the block changed them. upvotes and downvotes are
+-- NULL when the row didn't exist yet.
+CREATE TABLE changeset_votes_count (
+    block_number    BIGINT NOT NULL,
+    object_id       UUID NOT NULL,
+    object_type     SMALLINT NOT NULL,
+    space_id        UUID NOT NULL,
+    upvotes         BIGINT,
+    downvotes       BIGINT,
+    PRIMARY KEY (block_number, object_id, object_type, space_id)
+);
+
+-- Leaderboard deltas applied by the block, subtracted on rollback.
+CREATE TABLE changeset_leaderboards (
+    block_number    BIGINT NOT NULL,
+    space_id        UUID NOT NULL,
+    time_window     SMALLINT NOT NULL,
+    window_start    TIMESTAMPTZ NOT NULL,
+    object_id       UUID NOT NULL,
+    object_type     SMALLINT NOT NULL,
+    upvotes         BIGINT NOT NULL DEFAULT 0,
+    downvotes       BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (block_number, space_id, time_window, window_start, object_id, object_type)
+);
+
+CREATE INDEX idx_raw_actions_block_number ON raw_actions(block_number);
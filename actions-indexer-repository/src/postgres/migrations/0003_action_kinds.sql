@@ -0,0 +1,43 @@
+-- Tables of the action kinds other than votes. Rows are append-only and keep the
+-- block they were read from, so rollback_to can delete them like raw_actions.
+
+CREATE TABLE flags (
+    id              SERIAL PRIMARY KEY,
+    user_id         VARCHAR(42) NOT NULL,
+    object_id       UUID NOT NULL,
+    object_type     SMALLINT NOT NULL,
+    space_id        UUID NOT NULL,
+    reason          TEXT,
+    flagged_at      TIMESTAMPTZ NOT NULL,
+    block_number    BIGINT NOT NULL,
+    tx_hash         VARCHAR(66) NOT NULL
+);
+
+CREATE TABLE published_edits (
+    id              SERIAL PRIMARY KEY,
+    edit_id         UUID NOT NULL,
+    space_id        UUID NOT NULL,
+    author          VARCHAR(42) NOT NULL,
+    content_uri     TEXT NOT NULL,
+    published_at    TIMESTAMPTZ NOT NULL,
+    block_number    BIGINT NOT NULL,
+    tx_hash         VARCHAR(66) NOT NULL
+);
+
+-- change values: 0 = join, 1 = leave
+CREATE TABLE membership_changes (
+    id              SERIAL PRIMARY KEY,
+    user_id         VARCHAR(42) NOT NULL,
+    space_id        UUID NOT NULL,
+    change          SMALLINT NOT NULL,
+    changed_at      TIMESTAMPTZ NOT NULL,
+    block_number    BIGINT NOT NULL,
+    tx_hash         VARCHAR(66) NOT NULL
+);
+
+CREATE INDEX idx_flags_object_space ON flags(object_id, object_type, space_id);
+CREATE INDEX idx_flags_block_number ON flags(block_number);
+CREATE INDEX idx_published_edits_space ON published_edits(space_id);
+CREATE INDEX idx_published_edits_block_number ON published_edits(block_number);
+CREATE INDEX idx_membership_changes_space_user ON membership_changes(space_id, user_id);
+CREATE INDEX idx_membership_changes_block_number ON membership_changes(block_number);
@@ -0,0 +1,7 @@
+-- An action is identified by its transaction and the position of its log in the block,
+-- so replaying a block range can't insert it twice. Actions indexed before this
+-- migration, or read from substream packages that don't emit log_index, have a NULL
+-- log_index and are never deduplicated.
+ALTER TABLE raw_actions ADD COLUMN log_index BIGINT;
+
+CREATE UNIQUE INDEX idx_raw_actions_tx_hash_log_index ON raw_actions(tx_hash, log_index);
@@ -0,0 +1,6 @@
+-- Weight each user vote was counted with, so changing or removing it takes back
+-- exactly what it added. Votes indexed before this migration were counted once.
+ALTER TABLE user_votes ADD COLUMN weight BIGINT NOT NULL DEFAULT 1;
+
+-- Prior weight of the journaled row, NULL when the row didn't exist yet.
+ALTER TABLE changeset_user_votes ADD COLUMN weight BIGINT;
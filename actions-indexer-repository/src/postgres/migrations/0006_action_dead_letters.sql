@@ -0,0 +1,16 @@
+-- Action events that couldn't be decoded or processed, kept with the block they were
+-- read from so rollback_to can delete them like raw_actions. reprocessed_at is set
+-- once a dead letter was reprocessed successfully.
+CREATE TABLE action_dead_letters (
+    id              BIGSERIAL PRIMARY KEY,
+    block_number    BIGINT NOT NULL,
+    raw             BYTEA NOT NULL,
+    error           TEXT NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reprocessed_at  TIMESTAMPTZ
+);
+
+-- Replaying a block range dead-letters its events again, this keeps a single row each.
+CREATE UNIQUE INDEX idx_action_dead_letters_block_raw ON action_dead_letters(block_number, md5(raw));
+
+CREATE INDEX idx_action_dead_letters_pending ON action_dead_letters(id) WHERE reprocessed_at IS NULL;
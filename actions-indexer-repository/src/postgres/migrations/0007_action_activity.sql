@@ -0,0 +1,24 @@
+-- Hourly action counters per space, sender and action type, summed over the recent
+-- buckets to rank the most active spaces and senders. The pipeline adds the actions
+-- of each changeset to their bucket and prunes the buckets past its retention.
+CREATE TABLE action_activity (
+    bucket_start    TIMESTAMPTZ NOT NULL,
+    space_id        UUID NOT NULL,
+    sender          VARCHAR(42) NOT NULL,
+    action_type     SMALLINT NOT NULL,
+    actions         BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (bucket_start, space_id, sender, action_type)
+);
+
+CREATE INDEX idx_action_activity_space ON action_activity(space_id, bucket_start);
+
+-- Backfill the counters from the actions indexed so far.
+INSERT INTO action_activity (bucket_start, space_id, sender, action_type, actions)
+SELECT
+    date_trunc('hour', block_timestamp AT TIME ZONE 'UTC') AT TIME ZONE 'UTC' AS bucket_start,
+    space_pov,
+    sender,
+    action_type::SMALLINT,
+    COUNT(*)
+FROM raw_actions
+GROUP BY bucket_start, space_pov, sender, action_type;
@@ -0,0 +1,10 @@
+-- Activity deltas applied by the block, subtracted from action_activity on rollback.
+CREATE TABLE changeset_activity (
+    block_number    BIGINT NOT NULL,
+    bucket_start    TIMESTAMPTZ NOT NULL,
+    space_id        UUID NOT NULL,
+    sender          VARCHAR(42) NOT NULL,
+    action_type     SMALLINT NOT NULL,
+    actions         BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (block_number, bucket_start, space_id, sender, action_type)
+);
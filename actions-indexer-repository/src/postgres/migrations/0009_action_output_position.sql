@@ -0,0 +1,6 @@
+-- Substream packages that don't emit log_index are deduplicated by the position of the
+-- action in its block's output instead. It's only set when log_index is NULL, so each
+-- action is identified by one of the two unique indexes.
+ALTER TABLE raw_actions ADD COLUMN output_position BIGINT;
+
+CREATE UNIQUE INDEX idx_raw_actions_tx_hash_output_position ON raw_actions(tx_hash, output_position);
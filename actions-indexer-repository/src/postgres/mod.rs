@@ -14,9 +14,150 @@
 //! ## Database Tables
 //!
 //! - `raw_actions`: Processed blockchain actions
-//! - `user_votes`: Individual voting records with upsert support
+//! - `user_votes`: Individual voting records and their weights, with upsert support
 //! - `votes_count`: Aggregated vote tallies per entity/space
+//! - `vote_leaderboards`: Vote tallies per entity/space and time window
+//! - `flags`, `published_edits`, `membership_changes`: Actions of the other kinds
+//! - `changeset_user_votes`, `changeset_votes_count`, `changeset_leaderboards`,
+//!   `changeset_activity`: Per-block journal of persisted changesets, used to roll them back
+//! - `action_dead_letters`: Action events that couldn't be decoded or processed
+//! - `action_activity`: Hourly action counters per space, sender and action type
+//!
+//! ## Schemas
+//!
+//! Tables are looked up through the `search_path` of the connection, so indexers
+//! sharing a database can keep their tables apart by running in their own schema,
+//! see [`connect`].
+//!
+//! ## Writers Lock
+//!
+//! Vote counts are computed from the stored votes before being written, so two
+//! processes writing the same tables can lose each other's updates. Indexers hold
+//! the writers lock of their schema shared while they run, and the dead letter
+//! reprocessor holds it exclusively, see [`lock_writers`] and [`try_lock_writers_exclusive`].
 mod actions_repository;
+mod activity_repository;
+mod backfill_repository;
 mod cursor_repository;
 pub use actions_repository::PostgresActionsRepository;
-pub use cursor_repository::PostgresCursorRepository;
\ No newline at end of file
+pub use activity_repository::PostgresActivityRepository;
+pub use backfill_repository::PostgresBackfillRepository;
+pub use cursor_repository::PostgresCursorRepository;
+
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgPool};
+use std::str::FromStr;
+
+/// Advisory lock class of the writers lock, the schema of the connection being its key.
+const WRITERS_LOCK_CLASS: i32 = 0x6163_7473;
+
+/// Tables holding the indexed actions and the state derived from them, every table
+/// of the migrations but `meta`, which holds the cursors.
+pub(crate) const ACTIONS_TABLES: [&str; 13] = [
+    "raw_actions",
+    "user_votes",
+    "votes_count",
+    "vote_leaderboards",
+    "changeset_user_votes",
+    "changeset_votes_count",
+    "changeset_leaderboards",
+    "changeset_activity",
+    "flags",
+    "published_edits",
+    "membership_changes",
+    "action_dead_letters",
+    "action_activity",
+];
+
+/// Connects to a PostgreSQL database, optionally within a schema.
+///
+/// # Arguments
+///
+/// * `url` - PostgreSQL connection URL
+/// * `schema` - The schema the tables are created and looked up in, `None` to use
+///   the default `search_path` of the database
+///
+/// # Returns
+///
+/// * `Ok(PgPool)` - Pool whose connections resolve tables in `schema`
+/// * `Err(sqlx::Error)` - Invalid URL or connection failure
+pub async fn connect(url: &str, schema: Option<&str>) -> Result<PgPool, sqlx::Error> {
+    connect_with(PgConnectOptions::from_str(url)?, schema).await
+}
+
+/// Connects to a PostgreSQL database with the given options, optionally within a schema.
+///
+/// The schema must exist, running the migrations on the returned pool creates the
+/// tables in it.
+///
+/// # Arguments
+///
+/// * `options` - PostgreSQL connection options
+/// * `schema` - The schema the tables are created and looked up in, `None` to use
+///   the default `search_path` of the database
+///
+/// # Returns
+///
+/// * `Ok(PgPool)` - Pool whose connections resolve tables in `schema`
+/// * `Err(sqlx::Error)` - Connection failure
+pub async fn connect_with(options: PgConnectOptions, schema: Option<&str>) -> Result<PgPool, sqlx::Error> {
+    let options = match schema {
+        Some(schema) => options.options([("search_path", schema)]),
+        None => options,
+    };
+    PgPool::connect_with(options).await
+}
+
+/// Runs the migrations on a database, creating its tables in the first schema of the
+/// `search_path` of `pool`.
+///
+/// # Arguments
+///
+/// * `pool` - Pool over the database to migrate, see [`connect_with`] to pick a schema
+///
+/// # Returns
+///
+/// * `Ok(())` - The tables are up to date
+/// * `Err(sqlx::migrate::MigrateError)` - A migration failed
+pub async fn migrate(pool: &PgPool) -> Result<(), sqlx::migrate::MigrateError> {
+    sqlx::migrate!("src/postgres/migrations").run(pool).await
+}
+
+/// Takes the writers lock of the schema of `pool` shared, waiting while it is held
+/// exclusively.
+///
+/// # Arguments
+///
+/// * `pool` - Pool whose `search_path` picks the schema, see [`connect`]
+///
+/// # Returns
+///
+/// * `Ok(PgConnection)` - Connection detached from the pool, holding the lock until it is dropped
+/// * `Err(sqlx::Error)` - Connection failure
+pub async fn lock_writers(pool: &PgPool) -> Result<PgConnection, sqlx::Error> {
+    let mut connection = pool.acquire().await?.detach();
+    sqlx::query("SELECT pg_advisory_lock_shared($1, hashtext(current_schema()))")
+        .bind(WRITERS_LOCK_CLASS)
+        .execute(&mut connection)
+        .await?;
+    Ok(connection)
+}
+
+/// Takes the writers lock of the schema of `pool` exclusively, unless it is held.
+///
+/// # Arguments
+///
+/// * `pool` - Pool whose `search_path` picks the schema, see [`connect`]
+///
+/// # Returns
+///
+/// * `Ok(Some(PgConnection))` - Connection detached from the pool, holding the lock until it is dropped
+/// * `Ok(None)` - Another process, such as an indexer, holds the lock
+/// * `Err(sqlx::Error)` - Connection failure
+pub async fn try_lock_writers_exclusive(pool: &PgPool) -> Result<Option<PgConnection>, sqlx::Error> {
+    let mut connection = pool.acquire().await?.detach();
+    let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1, hashtext(current_schema()))")
+        .bind(WRITERS_LOCK_CLASS)
+        .fetch_one(&mut connection)
+        .await?;
+    Ok(locked.then_some(connection))
+}
@@ -0,0 +1,1227 @@
+//! SQLite implementation of the actions indexer repository.
+//!
+//! Mirrors `PostgresActionsRepository` table for table, so the pipeline behaves the
+//! same on both backends: changesets are journaled per block and can be rolled back,
+//! replayed actions and dead letters are deduplicated and vote counts can be
+//! reconciled with the user votes.
+use async_trait::async_trait;
+use actions_indexer_shared::types::{Action, ActionIndex, ActionKey, Changeset, EditPublished, Flag, Membership, MembershipChange, UserVote, VotesCount, VotesCountDrift, VotesCountReconciliation, ObjectId, VoteCriteria, VoteCountCriteria, VoteValue, ObjectType, LeaderboardDelta, LeaderboardEntry, LeaderboardWindow, SpaceId, UserAddress, DeadLetter, DeadLetterId};
+use sqlx::{sqlite::SqliteRow, Row};
+use crate::{ActionsRepository, ActionsRepositoryError};
+use alloy::{primitives::{Address, BlockNumber, TxHash}, hex::FromHex};
+
+/// Maximum number of keys looked up per query, well under SQLite's bind parameter limit.
+const LOOKUP_CHUNK_SIZE: usize = 500;
+
+/// SQLite implementation of the actions indexer repository.
+///
+/// Provides the same operations as `PostgresActionsRepository` on a local SQLite
+/// database, with every write wrapped in a transaction.
+pub struct SqliteActionsRepository {
+    pool: sqlx::SqlitePool,
+}
+
+impl SqliteActionsRepository {
+    /// Creates a new SQLite repository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - SQLite connection pool with the schema applied, see [`super::connect`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SqliteActionsRepository)` - Ready-to-use repository instance
+    /// * `Err(ActionsRepositoryError)` - Future validation errors (currently always succeeds)
+    pub async fn new(pool: sqlx::SqlitePool) -> Result<Self, ActionsRepositoryError> {
+        Ok(Self { pool })
+    }
+
+    /// Inserts actions within an active transaction using a multi-row INSERT.
+    ///
+    /// Actions already stored with the same tx hash and log index are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - Actions to insert (empty slices are no-ops)
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All actions inserted successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn insert_actions_tx(&self, actions: &[Action], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        if actions.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO raw_actions (action_type, action_version, sender, object_id, group_id, space_pov, metadata, block_number, block_timestamp, tx_hash, object_type, log_index, output_position)"
+        );
+        query_builder.push_values(actions, |mut b, action| {
+            let raw = action.raw();
+            b.push_bind(raw.action_type as i64)
+             .push_bind(raw.action_version as i64)
+             .push_bind(address_to_string(&raw.sender))
+             .push_bind(raw.object_id)
+             .push_bind(raw.group_id)
+             .push_bind(raw.space_pov)
+             .push_bind(raw.metadata.as_ref().map(|b| b.as_ref().to_vec()))
+             .push_bind(raw.block_number as i64)
+             .push_bind(raw.block_timestamp as i64)
+             .push_bind(tx_hash_to_string(&raw.tx_hash))
+             .push_bind(raw.object_type as i16)
+             .push_bind(raw.log_index.map(i64::from))
+             .push_bind(raw.output_position.map(i64::from));
+        });
+        query_builder.push(" ON CONFLICT DO NOTHING");
+        query_builder.build().execute(&mut **tx).await?;
+
+        self.insert_flags_tx(actions, tx).await?;
+        self.insert_published_edits_tx(actions, tx).await?;
+        self.insert_membership_changes_tx(actions, tx).await?;
+        Ok(())
+    }
+
+    /// Inserts the flag actions of a batch within an active transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - Actions to insert the flags of
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All flags inserted successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn insert_flags_tx(&self, actions: &[Action], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        let flags: Vec<&Flag> = actions.iter().filter_map(|action| match action {
+            Action::Flag(flag) => Some(flag),
+            _ => None,
+        }).collect();
+        if flags.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO flags (user_id, object_id, object_type, space_id, reason, flagged_at, block_number, tx_hash)"
+        );
+        query_builder.push_values(flags, |mut b, flag| {
+            b.push_bind(address_to_string(&flag.raw.sender))
+             .push_bind(flag.raw.object_id)
+             .push_bind(flag.raw.object_type as i16)
+             .push_bind(flag.raw.space_pov)
+             .push_bind(flag.reason.clone())
+             .push_bind(flag.raw.block_timestamp as i64)
+             .push_bind(flag.raw.block_number as i64)
+             .push_bind(tx_hash_to_string(&flag.raw.tx_hash));
+        });
+        query_builder.build().execute(&mut **tx).await?;
+        Ok(())
+    }
+
+    /// Inserts the edit published actions of a batch within an active transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - Actions to insert the published edits of
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All published edits inserted successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn insert_published_edits_tx(&self, actions: &[Action], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        let edits: Vec<&EditPublished> = actions.iter().filter_map(|action| match action {
+            Action::EditPublished(edit) => Some(edit),
+            _ => None,
+        }).collect();
+        if edits.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO published_edits (edit_id, space_id, author, content_uri, published_at, block_number, tx_hash)"
+        );
+        query_builder.push_values(edits, |mut b, edit| {
+            b.push_bind(edit.raw.object_id)
+             .push_bind(edit.raw.space_pov)
+             .push_bind(address_to_string(&edit.raw.sender))
+             .push_bind(edit.content_uri.clone())
+             .push_bind(edit.raw.block_timestamp as i64)
+             .push_bind(edit.raw.block_number as i64)
+             .push_bind(tx_hash_to_string(&edit.raw.tx_hash));
+        });
+        query_builder.build().execute(&mut **tx).await?;
+        Ok(())
+    }
+
+    /// Inserts the membership actions of a batch within an active transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - Actions to insert the membership changes of
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All membership changes inserted successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn insert_membership_changes_tx(&self, actions: &[Action], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        let memberships: Vec<&Membership> = actions.iter().filter_map(|action| match action {
+            Action::Membership(membership) => Some(membership),
+            _ => None,
+        }).collect();
+        if memberships.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "INSERT INTO membership_changes (user_id, space_id, change, changed_at, block_number, tx_hash)"
+        );
+        query_builder.push_values(memberships, |mut b, membership| {
+            b.push_bind(address_to_string(&membership.raw.sender))
+             .push_bind(membership.raw.space_pov)
+             .push_bind(match membership.change {
+                 MembershipChange::Join => 0,
+                 MembershipChange::Leave => 1,
+             } as i16)
+             .push_bind(membership.raw.block_timestamp as i64)
+             .push_bind(membership.raw.block_number as i64)
+             .push_bind(tx_hash_to_string(&membership.raw.tx_hash));
+        });
+        query_builder.build().execute(&mut **tx).await?;
+        Ok(())
+    }
+
+    /// Upserts user votes within an active transaction.
+    ///
+    /// A stored vote cast after the new one is kept, like on PostgreSQL.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_votes` - Vote records to upsert (empty slices are no-ops)
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All votes processed successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn update_user_votes_tx(&self, user_votes: &[UserVote], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        for vote in user_votes {
+            sqlx::query(
+                r#"
+                INSERT INTO user_votes (user_id, object_id, object_type, space_id, vote_type, voted_at, weight)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT (user_id, object_id, object_type, space_id)
+                DO UPDATE SET
+                    vote_type = excluded.vote_type,
+                    voted_at = excluded.voted_at,
+                    weight = excluded.weight
+                WHERE user_votes.voted_at <= excluded.voted_at
+                "#,
+            )
+            .bind(address_to_string(&vote.user_id))
+            .bind(vote.object_id)
+            .bind(vote.object_type as i16)
+            .bind(vote.space_id)
+            .bind(vote_to_i16(&vote.vote_type))
+            .bind(vote.voted_at as i64)
+            .bind(vote.weight as i64)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Upserts vote counts within an active transaction, replacing the stored totals.
+    ///
+    /// # Arguments
+    ///
+    /// * `votes_counts` - Count records to upsert (empty slices are no-ops)
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All counts updated successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn update_votes_counts_tx(&self, votes_counts: &[VotesCount], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        for count in votes_counts {
+            sqlx::query(
+                r#"
+                INSERT INTO votes_count (object_id, object_type, space_id, upvotes, downvotes)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                ON CONFLICT (object_id, object_type, space_id)
+                DO UPDATE SET
+                    upvotes = excluded.upvotes,
+                    downvotes = excluded.downvotes
+                "#,
+            )
+            .bind(count.object_id)
+            .bind(count.object_type as i16)
+            .bind(count.space_id)
+            .bind(count.upvotes)
+            .bind(count.downvotes)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Adds leaderboard deltas to the stored totals within an active transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `leaderboard_deltas` - Deltas to apply (empty slices are no-ops)
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All deltas applied successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn update_leaderboards_tx(&self, leaderboard_deltas: &[LeaderboardDelta], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        for delta in leaderboard_deltas {
+            sqlx::query(
+                r#"
+                INSERT INTO vote_leaderboards (space_id, time_window, window_start, object_id, object_type, upvotes, downvotes)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT (space_id, time_window, window_start, object_id, object_type)
+                DO UPDATE SET
+                    upvotes = vote_leaderboards.upvotes + excluded.upvotes,
+                    downvotes = vote_leaderboards.downvotes + excluded.downvotes
+                "#,
+            )
+            .bind(delta.space_id)
+            .bind(window_to_i16(delta.window))
+            .bind(timestamp_to_i64(delta.window_start)?)
+            .bind(delta.object_id)
+            .bind(delta.object_type as i16)
+            .bind(delta.upvotes)
+            .bind(delta.downvotes)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Records the values a changeset overwrites within an active transaction.
+    ///
+    /// Must run before the changeset is applied, see
+    /// `PostgresActionsRepository` for the journal layout. If a block is persisted
+    /// twice, the values recorded the first time are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `changeset` - Changeset about to be persisted
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Journal entries recorded successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn record_changeset_tx(&self, changeset: &Changeset<'_>, tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        let block_number = changeset.block_number as i64;
+
+        for vote in changeset.user_votes {
+            sqlx::query(
+                r#"
+                INSERT INTO changeset_user_votes (block_number, user_id, object_id, object_type, space_id, vote_type, voted_at, weight)
+                SELECT ?1, ?2, ?3, ?4, ?5, user_votes.vote_type, user_votes.voted_at, user_votes.weight
+                FROM (SELECT 1) AS journaled
+                LEFT JOIN user_votes ON user_votes.user_id = ?2
+                    AND user_votes.object_id = ?3
+                    AND user_votes.object_type = ?4
+                    AND user_votes.space_id = ?5
+                WHERE true
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(block_number)
+            .bind(address_to_string(&vote.user_id))
+            .bind(vote.object_id)
+            .bind(vote.object_type as i16)
+            .bind(vote.space_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for count in changeset.votes_count {
+            sqlx::query(
+                r#"
+                INSERT INTO changeset_votes_count (block_number, object_id, object_type, space_id, upvotes, downvotes)
+                SELECT ?1, ?2, ?3, ?4, votes_count.upvotes, votes_count.downvotes
+                FROM (SELECT 1) AS journaled
+                LEFT JOIN votes_count ON votes_count.object_id = ?2
+                    AND votes_count.object_type = ?3
+                    AND votes_count.space_id = ?4
+                WHERE true
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(block_number)
+            .bind(count.object_id)
+            .bind(count.object_type as i16)
+            .bind(count.space_id)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        for delta in changeset.leaderboard_deltas {
+            sqlx::query(
+                r#"
+                INSERT INTO changeset_leaderboards (block_number, space_id, time_window, window_start, object_id, object_type, upvotes, downvotes)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT (block_number, space_id, time_window, window_start, object_id, object_type)
+                DO UPDATE SET
+                    upvotes = changeset_leaderboards.upvotes + excluded.upvotes,
+                    downvotes = changeset_leaderboards.downvotes + excluded.downvotes
+                "#,
+            )
+            .bind(block_number)
+            .bind(delta.space_id)
+            .bind(window_to_i16(delta.window))
+            .bind(timestamp_to_i64(delta.window_start)?)
+            .bind(delta.object_id)
+            .bind(delta.object_type as i16)
+            .bind(delta.upvotes)
+            .bind(delta.downvotes)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Reverts every changeset persisted after a block within an active transaction.
+    ///
+    /// Rows are restored to the value journaled by the earliest later block, picked
+    /// with `ROW_NUMBER()` where PostgreSQL uses `DISTINCT ON`.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_number` - Last block to keep
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - All later changesets reverted successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn rollback_to_tx(&self, block_number: BlockNumber, tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        let block_number = block_number as i64;
+
+        const PRIOR_USER_VOTES: &str = r#"
+            SELECT * FROM (
+                SELECT *, ROW_NUMBER() OVER (
+                    PARTITION BY user_id, object_id, object_type, space_id ORDER BY block_number
+                ) AS journal_rank
+                FROM changeset_user_votes
+                WHERE block_number > ?1
+            ) WHERE journal_rank = 1
+        "#;
+        const PRIOR_VOTES_COUNT: &str = r#"
+            SELECT * FROM (
+                SELECT *, ROW_NUMBER() OVER (
+                    PARTITION BY object_id, object_type, space_id ORDER BY block_number
+                ) AS journal_rank
+                FROM changeset_votes_count
+                WHERE block_number > ?1
+            ) WHERE journal_rank = 1
+        "#;
+
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO user_votes (user_id, object_id, object_type, space_id, vote_type, voted_at, weight)
+            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight
+            FROM ({PRIOR_USER_VOTES})
+            WHERE vote_type IS NOT NULL
+            ON CONFLICT (user_id, object_id, object_type, space_id)
+            DO UPDATE SET
+                vote_type = excluded.vote_type,
+                voted_at = excluded.voted_at,
+                weight = excluded.weight
+            "#
+        ))
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"
+            DELETE FROM user_votes
+            WHERE (user_id, object_id, object_type, space_id) IN (
+                SELECT user_id, object_id, object_type, space_id
+                FROM ({PRIOR_USER_VOTES})
+                WHERE vote_type IS NULL
+            )
+            "#
+        ))
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO votes_count (object_id, object_type, space_id, upvotes, downvotes)
+            SELECT object_id, object_type, space_id, upvotes, downvotes
+            FROM ({PRIOR_VOTES_COUNT})
+            WHERE upvotes IS NOT NULL
+            ON CONFLICT (object_id, object_type, space_id)
+            DO UPDATE SET
+                upvotes = excluded.upvotes,
+                downvotes = excluded.downvotes
+            "#
+        ))
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"
+            DELETE FROM votes_count
+            WHERE (object_id, object_type, space_id) IN (
+                SELECT object_id, object_type, space_id
+                FROM ({PRIOR_VOTES_COUNT})
+                WHERE upvotes IS NULL
+            )
+            "#
+        ))
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE vote_leaderboards
+            SET
+                upvotes = vote_leaderboards.upvotes - applied.upvotes,
+                downvotes = vote_leaderboards.downvotes - applied.downvotes
+            FROM (
+                SELECT space_id, time_window, window_start, object_id, object_type,
+                    SUM(upvotes) AS upvotes, SUM(downvotes) AS downvotes
+                FROM changeset_leaderboards
+                WHERE block_number > ?1
+                GROUP BY space_id, time_window, window_start, object_id, object_type
+            ) AS applied
+            WHERE vote_leaderboards.space_id = applied.space_id
+                AND vote_leaderboards.time_window = applied.time_window
+                AND vote_leaderboards.window_start = applied.window_start
+                AND vote_leaderboards.object_id = applied.object_id
+                AND vote_leaderboards.object_type = applied.object_type
+            "#,
+        )
+        .bind(block_number)
+        .execute(&mut **tx)
+        .await?;
+
+        let tables = [
+            "raw_actions",
+            "flags",
+            "published_edits",
+            "membership_changes",
+            "changeset_user_votes",
+            "changeset_votes_count",
+            "changeset_leaderboards",
+            "action_dead_letters",
+        ];
+        for table in tables {
+            sqlx::query(&format!("DELETE FROM {table} WHERE block_number > ?1"))
+                .bind(block_number)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Drops the journal entries of final blocks within an active transaction, like
+    /// `PostgresActionsRepository`.
+    ///
+    /// # Arguments
+    ///
+    /// * `final_block_number` - Last irreversible block
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Journal entries pruned successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn prune_changesets_tx(&self, final_block_number: BlockNumber, tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        let tables = [
+            "changeset_user_votes",
+            "changeset_votes_count",
+            "changeset_leaderboards",
+        ];
+        for table in tables {
+            sqlx::query(&format!("DELETE FROM {table} WHERE block_number <= ?1"))
+                .bind(final_block_number as i64)
+                .execute(&mut **tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Persists changesets in order within an active transaction.
+    ///
+    /// The journal of the blocks that became final is pruned afterwards. The activity
+    /// counters are not implemented on SQLite, so activity deltas are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `changesets` - Changesets of consecutive blocks, ordered by block number
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Every changeset applied successfully
+    /// * `Err(ActionsRepositoryError)` - Database or encoding error
+    async fn persist_changesets_tx(&self, changesets: &[Changeset<'_>], tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        for changeset in changesets {
+            self.record_changeset_tx(changeset, tx).await?;
+            self.insert_actions_tx(changeset.actions, tx).await?;
+            self.update_user_votes_tx(changeset.user_votes, tx).await?;
+            self.update_votes_counts_tx(changeset.votes_count, tx).await?;
+            self.update_leaderboards_tx(changeset.leaderboard_deltas, tx).await?;
+        }
+        if let Some(final_block_number) = changesets.iter().map(|changeset| changeset.final_block_number).max() {
+            self.prune_changesets_tx(final_block_number, tx).await?;
+        }
+        Ok(())
+    }
+
+    /// Saves an indexer cursor in the `meta` table within an active transaction.
+    ///
+    /// Uses the same upsert as `SqliteCursorRepository::save_cursor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Indexer identifier
+    /// * `cursor` - Stream cursor to resume from
+    /// * `block_number` - Block number of the cursor
+    /// * `tx` - Active transaction context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - Cursor saved successfully
+    /// * `Err(ActionsRepositoryError)` - Database error
+    async fn save_cursor_tx(&self, id: &str, cursor: &str, block_number: i64, tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>) -> Result<(), ActionsRepositoryError> {
+        sqlx::query(
+            "INSERT INTO meta (id, cursor, block_number) VALUES (?1, ?2, ?3) ON CONFLICT (id) DO UPDATE SET cursor = ?2, block_number = ?3",
+        )
+        .bind(id)
+        .bind(cursor)
+        .bind(block_number.to_string())
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+}
+
+/// Formats an address like the `user_id` and `sender` columns store it.
+fn address_to_string(address: &Address) -> String {
+    format!("0x{}", hex::encode(address.as_slice()))
+}
+
+/// Formats a transaction hash like the `tx_hash` columns store it.
+fn tx_hash_to_string(tx_hash: &TxHash) -> String {
+    format!("0x{}", hex::encode(tx_hash.as_slice()))
+}
+
+/// Converts a unix timestamp to the `INTEGER` value of the timestamp columns.
+fn timestamp_to_i64(timestamp: u64) -> Result<i64, ActionsRepositoryError> {
+    i64::try_from(timestamp).map_err(|_| ActionsRepositoryError::InvalidTimestamp(timestamp))
+}
+
+/// Maps a vote to its `vote_type` column value.
+fn vote_to_i16(vote: &VoteValue) -> i16 {
+    match vote {
+        VoteValue::Up => 0,
+        VoteValue::Down => 1,
+        VoteValue::Remove => 2,
+    }
+}
+
+/// Maps an `object_type` column value back to an object type.
+fn object_type_from_i16(value: i16) -> Result<ObjectType, ActionsRepositoryError> {
+    match value {
+        0 => Ok(ObjectType::Entity),
+        1 => Ok(ObjectType::Relation),
+        _ => Err(ActionsRepositoryError::InvalidObjectType(value)),
+    }
+}
+
+/// Maps a leaderboard window to its `time_window` column value.
+fn window_to_i16(window: LeaderboardWindow) -> i16 {
+    match window {
+        LeaderboardWindow::Day => 0,
+        LeaderboardWindow::Week => 1,
+        LeaderboardWindow::Month => 2,
+        LeaderboardWindow::AllTime => 3,
+    }
+}
+
+/// Maps a `time_window` column value back to a leaderboard window.
+fn window_from_i16(value: i16) -> Result<LeaderboardWindow, ActionsRepositoryError> {
+    match value {
+        0 => Ok(LeaderboardWindow::Day),
+        1 => Ok(LeaderboardWindow::Week),
+        2 => Ok(LeaderboardWindow::Month),
+        3 => Ok(LeaderboardWindow::AllTime),
+        _ => Err(ActionsRepositoryError::InvalidLeaderboardWindow(value)),
+    }
+}
+
+/// Maps a `user_votes` row to a user vote.
+fn user_vote_from_row(row: &SqliteRow) -> Result<UserVote, ActionsRepositoryError> {
+    let user_id: String = row.get("user_id");
+    let vote_type: i16 = row.get("vote_type");
+    let voted_at: i64 = row.get("voted_at");
+    let weight: i64 = row.get("weight");
+    Ok(UserVote {
+        user_id: Address::from_hex(&user_id).map_err(|_| ActionsRepositoryError::InvalidAddress(user_id))?,
+        object_id: row.get("object_id"),
+        space_id: row.get("space_id"),
+        object_type: object_type_from_i16(row.get("object_type"))?,
+        vote_type: match vote_type {
+            0 => VoteValue::Up,
+            1 => VoteValue::Down,
+            2 => VoteValue::Remove,
+            _ => return Err(ActionsRepositoryError::InvalidVoteType(vote_type)),
+        },
+        voted_at: voted_at as u64,
+        weight: weight as u64,
+    })
+}
+
+/// Maps a `votes_count` row to a vote count.
+fn votes_count_from_row(row: &SqliteRow) -> Result<VotesCount, ActionsRepositoryError> {
+    Ok(VotesCount {
+        object_id: row.get("object_id"),
+        space_id: row.get("space_id"),
+        object_type: object_type_from_i16(row.get("object_type"))?,
+        upvotes: row.get("upvotes"),
+        downvotes: row.get("downvotes"),
+    })
+}
+
+#[async_trait]
+impl ActionsRepository for SqliteActionsRepository {
+    async fn insert_actions(
+        &self,
+        actions: &[Action],
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.insert_actions_tx(actions, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn update_user_votes(
+        &self,
+        user_votes: &[UserVote],
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.update_user_votes_tx(user_votes, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn update_votes_counts(
+        &self,
+        votes_counts: &[VotesCount],
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.update_votes_counts_tx(votes_counts, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn update_leaderboards(
+        &self,
+        leaderboard_deltas: &[LeaderboardDelta],
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.update_leaderboards_tx(leaderboard_deltas, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn persist_changeset(
+        &self,
+        changeset: &Changeset<'_>,
+    ) -> Result<(), ActionsRepositoryError> {
+        self.persist_changesets(std::slice::from_ref(changeset)).await
+    }
+
+    async fn persist_changesets(
+        &self,
+        changesets: &[Changeset<'_>],
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.persist_changesets_tx(changesets, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn persist_changesets_with_cursor(
+        &self,
+        changesets: &[Changeset<'_>],
+        id: &str,
+        cursor: &str,
+        block_number: i64,
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.persist_changesets_tx(changesets, &mut tx).await?;
+        self.save_cursor_tx(id, cursor, block_number, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn rollback_to(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.rollback_to_tx(block_number, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn rollback_to_with_cursor(
+        &self,
+        block_number: BlockNumber,
+        id: &str,
+        cursor: &str,
+    ) -> Result<(), ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+        self.rollback_to_tx(block_number, &mut tx).await?;
+        self.save_cursor_tx(id, cursor, block_number as i64, &mut tx).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Looks up the keys in chunks of `(tx_hash, log_index) IN (VALUES ...)` lists, and
+    /// those of actions without a log index in `(tx_hash, output_position)` ones.
+    async fn get_persisted_action_keys(&self, action_keys: &[ActionKey]) -> Result<Vec<ActionKey>, ActionsRepositoryError> {
+        let (log_keys, position_keys): (Vec<_>, Vec<_>) = action_keys
+            .iter()
+            .partition(|(_, index)| matches!(index, ActionIndex::Log(_)));
+
+        let mut keys = Vec::new();
+        for (column, column_keys) in [("log_index", log_keys), ("output_position", position_keys)] {
+            for chunk in column_keys.chunks(LOOKUP_CHUNK_SIZE) {
+                let mut query_builder = sqlx::QueryBuilder::new(format!(
+                    "SELECT DISTINCT tx_hash, {column} FROM raw_actions WHERE (tx_hash, {column}) IN (VALUES "
+                ));
+                let mut values = query_builder.separated(", ");
+                for (tx_hash, index) in chunk {
+                    let (ActionIndex::Log(index) | ActionIndex::OutputPosition(index)) = index;
+                    values.push("(");
+                    values.push_bind_unseparated(tx_hash_to_string(tx_hash));
+                    values.push_unseparated(", ");
+                    values.push_bind_unseparated(i64::from(*index));
+                    values.push_unseparated(")");
+                }
+                query_builder.push(")");
+
+                for row in query_builder.build().fetch_all(&self.pool).await? {
+                    let tx_hash: String = row.get("tx_hash");
+                    let index = row.get::<i64, _>(column) as u32;
+                    keys.push((
+                        TxHash::from_hex(&tx_hash).map_err(|_| ActionsRepositoryError::InvalidTxHash(tx_hash))?,
+                        if column == "log_index" { ActionIndex::Log(index) } else { ActionIndex::OutputPosition(index) },
+                    ));
+                }
+            }
+        }
+
+        Ok(keys)
+    }
+
+    /// Looks up the votes in chunks of row-value `IN (VALUES ...)` lists.
+    async fn get_user_votes(&self, vote_criteria: &[VoteCriteria]) -> Result<Vec<UserVote>, ActionsRepositoryError> {
+        let mut votes = Vec::new();
+        for chunk in vote_criteria.chunks(LOOKUP_CHUNK_SIZE) {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight FROM user_votes WHERE (user_id, object_id, space_id, object_type) IN (VALUES "
+            );
+            let mut values = query_builder.separated(", ");
+            for (user_id, object_id, space_id, object_type) in chunk {
+                values.push("(");
+                values.push_bind_unseparated(address_to_string(user_id));
+                values.push_unseparated(", ");
+                values.push_bind_unseparated(*object_id);
+                values.push_unseparated(", ");
+                values.push_bind_unseparated(*space_id);
+                values.push_unseparated(", ");
+                values.push_bind_unseparated(*object_type as i16);
+                values.push_unseparated(")");
+            }
+            query_builder.push(")");
+
+            for row in query_builder.build().fetch_all(&self.pool).await? {
+                votes.push(user_vote_from_row(&row)?);
+            }
+        }
+
+        Ok(votes)
+    }
+
+    /// Looks up the counts in chunks of row-value `IN (VALUES ...)` lists.
+    async fn get_vote_counts(&self, vote_criteria: &[VoteCountCriteria]) -> Result<Vec<VotesCount>, ActionsRepositoryError> {
+        let mut counts = Vec::new();
+        for chunk in vote_criteria.chunks(LOOKUP_CHUNK_SIZE) {
+            let mut query_builder = sqlx::QueryBuilder::new(
+                "SELECT object_id, object_type, space_id, upvotes, downvotes FROM votes_count WHERE (object_id, object_type, space_id) IN (VALUES "
+            );
+            let mut values = query_builder.separated(", ");
+            for (object_id, space_id, object_type) in chunk {
+                values.push("(");
+                values.push_bind_unseparated(*object_id);
+                values.push_unseparated(", ");
+                values.push_bind_unseparated(*object_type as i16);
+                values.push_unseparated(", ");
+                values.push_bind_unseparated(*space_id);
+                values.push_unseparated(")");
+            }
+            query_builder.push(")");
+
+            for row in query_builder.build().fetch_all(&self.pool).await? {
+                counts.push(votes_count_from_row(&row)?);
+            }
+        }
+
+        Ok(counts)
+    }
+
+    async fn get_leaderboard(
+        &self,
+        space_id: &SpaceId,
+        window: LeaderboardWindow,
+        timestamp: u64,
+        limit: i64,
+    ) -> Result<Vec<LeaderboardEntry>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT object_id, object_type, space_id, time_window, window_start, upvotes, downvotes
+            FROM vote_leaderboards
+            WHERE space_id = ?1 AND time_window = ?2 AND window_start = ?3 AND upvotes > 0
+            ORDER BY upvotes DESC, downvotes ASC, object_id
+            LIMIT ?4
+            "#,
+        )
+        .bind(space_id)
+        .bind(window_to_i16(window))
+        .bind(timestamp_to_i64(window.window_start(timestamp))?)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let window_start: i64 = row.get("window_start");
+            entries.push(LeaderboardEntry {
+                object_id: row.get("object_id"),
+                space_id: row.get("space_id"),
+                object_type: object_type_from_i16(row.get("object_type"))?,
+                window: window_from_i16(row.get("time_window"))?,
+                window_start: window_start as u64,
+                upvotes: row.get("upvotes"),
+                downvotes: row.get("downvotes"),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    async fn get_object_votes(
+        &self,
+        object_id: &ObjectId,
+        space_id: &SpaceId,
+        object_type: ObjectType,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserVote>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight
+            FROM user_votes
+            WHERE object_id = ?1 AND space_id = ?2 AND object_type = ?3 AND vote_type <> 2
+            ORDER BY voted_at DESC, user_id
+            LIMIT ?4 OFFSET ?5
+            "#,
+        )
+        .bind(object_id)
+        .bind(space_id)
+        .bind(object_type as i16)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(user_vote_from_row).collect()
+    }
+
+    async fn get_votes_by_user(
+        &self,
+        user_id: &UserAddress,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<UserVote>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, object_id, object_type, space_id, vote_type, voted_at, weight
+            FROM user_votes
+            WHERE user_id = ?1
+            ORDER BY voted_at DESC, object_id, space_id, object_type
+            LIMIT ?2 OFFSET ?3
+            "#,
+        )
+        .bind(address_to_string(user_id))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(user_vote_from_row).collect()
+    }
+
+    async fn get_top_objects(
+        &self,
+        space_id: &SpaceId,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<VotesCount>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT object_id, object_type, space_id, upvotes, downvotes
+            FROM votes_count
+            WHERE space_id = ?1
+            ORDER BY upvotes - downvotes DESC, upvotes DESC, object_id, object_type
+            LIMIT ?2 OFFSET ?3
+            "#,
+        )
+        .bind(space_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(votes_count_from_row).collect()
+    }
+
+    /// Pages over the keys of both `user_votes` and `votes_count` like the PostgreSQL
+    /// implementation, comparing and setting each drifted count.
+    async fn reconcile_vote_counts(
+        &self,
+        after: Option<VoteCountCriteria>,
+        limit: i64,
+    ) -> Result<VotesCountReconciliation, ActionsRepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        let rows = sqlx::query(
+            r#"
+            WITH page AS (
+                SELECT object_id, space_id, object_type
+                FROM (
+                    SELECT object_id, space_id, object_type FROM user_votes
+                    UNION
+                    SELECT object_id, space_id, object_type FROM votes_count
+                )
+                WHERE ?1 IS NULL OR (object_id, space_id, object_type) > (?1, ?2, ?3)
+                ORDER BY object_id, space_id, object_type
+                LIMIT ?4
+            )
+            SELECT
+                page.object_id,
+                page.space_id,
+                page.object_type,
+                COALESCE(SUM(user_votes.weight) FILTER (WHERE user_votes.vote_type = 0), 0) AS upvotes,
+                COALESCE(SUM(user_votes.weight) FILTER (WHERE user_votes.vote_type = 1), 0) AS downvotes,
+                votes_count.upvotes AS stored_upvotes,
+                votes_count.downvotes AS stored_downvotes
+            FROM page
+            LEFT JOIN user_votes ON user_votes.object_id = page.object_id
+                AND user_votes.space_id = page.space_id
+                AND user_votes.object_type = page.object_type
+            LEFT JOIN votes_count ON votes_count.object_id = page.object_id
+                AND votes_count.space_id = page.space_id
+                AND votes_count.object_type = page.object_type
+            GROUP BY page.object_id, page.space_id, page.object_type, votes_count.upvotes, votes_count.downvotes
+            ORDER BY page.object_id, page.space_id, page.object_type
+            "#,
+        )
+        .bind(after.map(|(object_id, _, _)| object_id))
+        .bind(after.map(|(_, space_id, _)| space_id))
+        .bind(after.map(|(_, _, object_type)| object_type as i16))
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut reconciliation = VotesCountReconciliation {
+            checked: rows.len(),
+            ..Default::default()
+        };
+
+        for row in rows {
+            let object_type: i16 = row.get("object_type");
+            let expected = VotesCount {
+                object_id: row.get("object_id"),
+                space_id: row.get("space_id"),
+                object_type: object_type_from_i16(object_type)?,
+                upvotes: row.get("upvotes"),
+                downvotes: row.get("downvotes"),
+            };
+            let stored: Option<(i64, i64)> = row
+                .get::<Option<i64>, _>("stored_upvotes")
+                .zip(row.get::<Option<i64>, _>("stored_downvotes"));
+            reconciliation.last_checked = Some((expected.object_id, expected.space_id, expected.object_type));
+
+            let repaired = match stored {
+                Some(stored) if stored == (expected.upvotes, expected.downvotes) => false,
+                Some((stored_upvotes, stored_downvotes)) => sqlx::query(
+                    r#"
+                    UPDATE votes_count
+                    SET upvotes = ?4, downvotes = ?5
+                    WHERE object_id = ?1 AND space_id = ?2 AND object_type = ?3
+                        AND upvotes = ?6 AND downvotes = ?7
+                    "#,
+                )
+                .bind(expected.object_id)
+                .bind(expected.space_id)
+                .bind(object_type)
+                .bind(expected.upvotes)
+                .bind(expected.downvotes)
+                .bind(stored_upvotes)
+                .bind(stored_downvotes)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected() > 0,
+                None if expected.upvotes == 0 && expected.downvotes == 0 => false,
+                None => sqlx::query(
+                    r#"
+                    INSERT INTO votes_count (object_id, space_id, object_type, upvotes, downvotes)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                    ON CONFLICT (object_id, object_type, space_id) DO NOTHING
+                    "#,
+                )
+                .bind(expected.object_id)
+                .bind(expected.space_id)
+                .bind(object_type)
+                .bind(expected.upvotes)
+                .bind(expected.downvotes)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected() > 0,
+            };
+
+            if repaired {
+                let (stored_upvotes, stored_downvotes) = stored.unwrap_or_default();
+                reconciliation.repaired.push(VotesCountDrift {
+                    expected,
+                    stored_upvotes,
+                    stored_downvotes,
+                });
+            }
+        }
+
+        tx.commit().await?;
+        Ok(reconciliation)
+    }
+
+    /// Stores dead letters in a multi-row INSERT, skipping the ones already stored for
+    /// the same block and raw bytes.
+    async fn insert_dead_letters(&self, dead_letters: &[DeadLetter]) -> Result<(), ActionsRepositoryError> {
+        if dead_letters.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new("INSERT INTO action_dead_letters (block_number, raw, error)");
+        query_builder.push_values(dead_letters, |mut b, dead_letter| {
+            b.push_bind(dead_letter.block_number as i64)
+             .push_bind(dead_letter.raw.clone())
+             .push_bind(dead_letter.error.clone());
+        });
+        query_builder.push(" ON CONFLICT (block_number, raw) DO NOTHING");
+        query_builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn get_pending_dead_letters(
+        &self,
+        after: Option<DeadLetterId>,
+        limit: i64,
+    ) -> Result<Vec<(DeadLetterId, DeadLetter)>, ActionsRepositoryError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, block_number, raw, error
+            FROM action_dead_letters
+            WHERE reprocessed_at IS NULL AND id > ?1
+            ORDER BY id
+            LIMIT ?2
+            "#,
+        )
+        .bind(after.unwrap_or(0))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let block_number: i64 = row.get("block_number");
+                (
+                    row.get("id"),
+                    DeadLetter {
+                        block_number: block_number as u64,
+                        raw: row.get("raw"),
+                        error: row.get("error"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn mark_dead_letters_reprocessed(&self, ids: &[DeadLetterId]) -> Result<(), ActionsRepositoryError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut query_builder = sqlx::QueryBuilder::new(
+            "UPDATE action_dead_letters SET reprocessed_at = CAST(strftime('%s', 'now') AS INTEGER) WHERE id IN ("
+        );
+        let mut values = query_builder.separated(", ");
+        for id in ids {
+            values.push_bind(*id);
+        }
+        query_builder.push(")");
+        query_builder.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn check_tables_created(&self) -> Result<bool, ActionsRepositoryError> {
+        let tables = [
+            "raw_actions",
+            "user_votes",
+            "votes_count",
+            "vote_leaderboards",
+            "changeset_user_votes",
+            "changeset_votes_count",
+            "changeset_leaderboards",
+            "flags",
+            "published_edits",
+            "membership_changes",
+            "action_dead_letters",
+        ];
+        for table in tables {
+            let table_exists: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            )
+            .bind(table)
+            .fetch_one(&self.pool)
+            .await?;
+            if !table_exists {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
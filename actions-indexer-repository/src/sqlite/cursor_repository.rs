@@ -0,0 +1,62 @@
+//! SQLite implementation of the cursor repository.
+//!
+//! Stores cursor state in a `meta` table, like the PostgreSQL implementation.
+
+use crate::CursorRepository;
+use crate::errors::CursorRepositoryError;
+use async_trait::async_trait;
+use sqlx::Row;
+
+/// SQLite-backed cursor repository.
+///
+/// Persists indexer cursors in a `meta` table with upsert operations for atomic updates.
+pub struct SqliteCursorRepository {
+    /// SQLite connection pool
+    pool: sqlx::SqlitePool,
+}
+
+impl SqliteCursorRepository {
+    /// Creates a new SQLite cursor repository instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - SQLite connection pool with the schema applied, see [`super::connect`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SqliteCursorRepository)` - Ready-to-use repository instance
+    /// * `Err(CursorRepositoryError)` - Future validation errors (currently always succeeds)
+    pub async fn new(pool: sqlx::SqlitePool) -> Result<Self, CursorRepositoryError> {
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl CursorRepository for SqliteCursorRepository {
+    async fn get_cursor(&self, id: &str) -> Result<Option<String>, CursorRepositoryError> {
+        let row = sqlx::query("SELECT cursor FROM meta WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get("cursor")))
+    }
+
+    async fn save_cursor(
+        &self,
+        id: &str,
+        cursor: &str,
+        block_number: &i64,
+    ) -> Result<(), CursorRepositoryError> {
+        sqlx::query(
+            "INSERT INTO meta (id, cursor, block_number) VALUES (?1, ?2, ?3) ON CONFLICT (id) DO UPDATE SET cursor = ?2, block_number = ?3",
+        )
+        .bind(id)
+        .bind(cursor)
+        .bind(block_number.to_string())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
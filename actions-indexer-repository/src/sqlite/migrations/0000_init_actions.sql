@@ -0,0 +1,150 @@
+-- Schema of the SQLite repository, equivalent to the PostgreSQL migrations up to
+-- 0006_action_dead_letters, plus 0009_action_output_position. UUIDs are stored as 16-byte blobs and timestamps as unix
+-- seconds, so keys compare and sort like their PostgreSQL counterparts.
+
+CREATE TABLE raw_actions (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    action_type     INTEGER NOT NULL,
+    action_version  INTEGER NOT NULL,
+    sender          TEXT NOT NULL,
+    object_id       BLOB NOT NULL,
+    group_id        BLOB,
+    space_pov       BLOB NOT NULL,
+    metadata        BLOB,
+    block_number    INTEGER NOT NULL,
+    block_timestamp INTEGER NOT NULL,
+    tx_hash         TEXT NOT NULL,
+    object_type     INTEGER NOT NULL,
+    log_index       INTEGER,
+    output_position INTEGER
+);
+
+CREATE TABLE user_votes (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id         TEXT NOT NULL,
+    object_id       BLOB NOT NULL,
+    space_id        BLOB NOT NULL,
+    vote_type       INTEGER NOT NULL,
+    voted_at        INTEGER NOT NULL,
+    object_type     INTEGER NOT NULL,
+    weight          INTEGER NOT NULL DEFAULT 1,
+    UNIQUE(user_id, object_id, object_type, space_id)
+);
+
+CREATE TABLE votes_count (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    object_id       BLOB NOT NULL,
+    space_id        BLOB NOT NULL,
+    upvotes         INTEGER NOT NULL DEFAULT 0,
+    downvotes       INTEGER NOT NULL DEFAULT 0,
+    object_type     INTEGER NOT NULL,
+    UNIQUE(object_id, object_type, space_id)
+);
+
+CREATE TABLE meta (
+    id              TEXT PRIMARY KEY,
+    cursor          TEXT NOT NULL,
+    block_number    TEXT NOT NULL
+);
+
+-- time_window values: 0 = day, 1 = week, 2 = month, 3 = all time
+CREATE TABLE vote_leaderboards (
+    space_id        BLOB NOT NULL,
+    time_window     INTEGER NOT NULL,
+    window_start    INTEGER NOT NULL,
+    object_id       BLOB NOT NULL,
+    object_type     INTEGER NOT NULL,
+    upvotes         INTEGER NOT NULL DEFAULT 0,
+    downvotes       INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (space_id, time_window, window_start, object_id, object_type)
+);
+
+-- Per-block journal of the changesets persisted by the loader, read by rollback_to.
+-- Prior values are NULL when the row didn't exist yet.
+CREATE TABLE changeset_user_votes (
+    block_number    INTEGER NOT NULL,
+    user_id         TEXT NOT NULL,
+    object_id       BLOB NOT NULL,
+    object_type     INTEGER NOT NULL,
+    space_id        BLOB NOT NULL,
+    vote_type       INTEGER,
+    voted_at        INTEGER,
+    weight          INTEGER,
+    PRIMARY KEY (block_number, user_id, object_id, object_type, space_id)
+);
+
+CREATE TABLE changeset_votes_count (
+    block_number    INTEGER NOT NULL,
+    object_id       BLOB NOT NULL,
+    object_type     INTEGER NOT NULL,
+    space_id        BLOB NOT NULL,
+    upvotes         INTEGER,
+    downvotes       INTEGER,
+    PRIMARY KEY (block_number, object_id, object_type, space_id)
+);
+
+CREATE TABLE changeset_leaderboards (
+    block_number    INTEGER NOT NULL,
+    space_id        BLOB NOT NULL,
+    time_window     INTEGER NOT NULL,
+    window_start    INTEGER NOT NULL,
+    object_id       BLOB NOT NULL,
+    object_type     INTEGER NOT NULL,
+    upvotes         INTEGER NOT NULL DEFAULT 0,
+    downvotes       INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (block_number, space_id, time_window, window_start, object_id, object_type)
+);
+
+CREATE TABLE flags (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id         TEXT NOT NULL,
+    object_id       BLOB NOT NULL,
+    object_type     INTEGER NOT NULL,
+    space_id        BLOB NOT NULL,
+    reason          TEXT,
+    flagged_at      INTEGER NOT NULL,
+    block_number    INTEGER NOT NULL,
+    tx_hash         TEXT NOT NULL
+);
+
+CREATE TABLE published_edits (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    edit_id         BLOB NOT NULL,
+    space_id        BLOB NOT NULL,
+    author          TEXT NOT NULL,
+    content_uri     TEXT NOT NULL,
+    published_at    INTEGER NOT NULL,
+    block_number    INTEGER NOT NULL,
+    tx_hash         TEXT NOT NULL
+);
+
+-- change values: 0 = join, 1 = leave
+CREATE TABLE membership_changes (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id         TEXT NOT NULL,
+    space_id        BLOB NOT NULL,
+    change          INTEGER NOT NULL,
+    changed_at      INTEGER NOT NULL,
+    block_number    INTEGER NOT NULL,
+    tx_hash         TEXT NOT NULL
+);
+
+CREATE TABLE action_dead_letters (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    block_number    INTEGER NOT NULL,
+    raw             BLOB NOT NULL,
+    error           TEXT NOT NULL,
+    created_at      INTEGER NOT NULL DEFAULT (CAST(strftime('%s', 'now') AS INTEGER)),
+    reprocessed_at  INTEGER,
+    UNIQUE(block_number, raw)
+);
+
+CREATE UNIQUE INDEX idx_raw_actions_tx_hash_log_index ON raw_actions(tx_hash, log_index);
+CREATE UNIQUE INDEX idx_raw_actions_tx_hash_output_position ON raw_actions(tx_hash, output_position);
+CREATE INDEX idx_raw_actions_block_number ON raw_actions(block_number);
+CREATE INDEX idx_votes_count_space ON votes_count(space_id);
+CREATE INDEX idx_vote_leaderboards_rank ON vote_leaderboards(space_id, time_window, window_start, upvotes DESC);
+CREATE INDEX idx_flags_block_number ON flags(block_number);
+CREATE INDEX idx_published_edits_block_number ON published_edits(block_number);
+CREATE INDEX idx_membership_changes_block_number ON membership_changes(block_number);
+CREATE INDEX idx_action_dead_letters_pending ON action_dead_letters(id) WHERE reprocessed_at IS NULL;
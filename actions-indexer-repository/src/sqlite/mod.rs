@@ -0,0 +1,51 @@
+//! SQLite implementation of the actions indexer repository.
+//!
+//! Provides a zero-infrastructure backend for the `ActionsRepository` and
+//! `CursorRepository` traits, meant for running and integration-testing the pipeline
+//! locally. It is enabled by the `sqlite` feature.
+//!
+//! ## Differences with PostgreSQL
+//!
+//! - The schema is embedded and applied by [`connect`], no migration step is needed
+//! - UUIDs are stored as blobs and timestamps as unix seconds
+//! - Batch lookups use row-value `IN (VALUES ...)` lists instead of `UNNEST`
+//! - Activity counters aren't supported
+mod actions_repository;
+mod cursor_repository;
+pub use actions_repository::SqliteActionsRepository;
+pub use cursor_repository::SqliteCursorRepository;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::str::FromStr;
+
+/// Opens a SQLite database and applies the embedded schema.
+///
+/// The database file is created if it doesn't exist. In-memory databases
+/// (`sqlite::memory:`) live as long as their single connection, so the pool is
+/// limited to one connection for them that is never closed for being idle or
+/// old.
+///
+/// # Arguments
+///
+/// * `url` - SQLite connection URL, e.g. `sqlite://actions.db` or `sqlite::memory:`
+///
+/// # Returns
+///
+/// * `Ok(SqlitePool)` - Pool over a database with the schema up to date
+/// * `Err(sqlx::Error)` - Invalid URL, connection or migration failure
+pub async fn connect(url: &str) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+    let pool_options = if options.get_filename().as_os_str() == ":memory:" {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+    } else {
+        SqlitePoolOptions::new().max_connections(4)
+    };
+    let pool = pool_options.connect_with(options).await?;
+
+    sqlx::migrate!("src/sqlite/migrations").run(&pool).await?;
+    Ok(pool)
+}
@@ -5,8 +5,8 @@
 //!
 //! Run with: `cargo test --test postgres_actions`
 
-use actions_indexer_repository::{ActionsRepository, PostgresActionsRepository};
-use actions_indexer_shared::types::{Action, ActionRaw, Vote, UserVote, VotesCount, VoteCriteria, VoteValue, ObjectType, ActionType};
+use actions_indexer_repository::{ActionsRepository, CursorRepository, PostgresActionsRepository, PostgresCursorRepository};
+use actions_indexer_shared::types::{Action, ActionIndex, ActionRaw, Changeset, EditPublished, Flag, Membership, MembershipChange, Vote, UserVote, VotesCount, VoteCriteria, VoteValue, ObjectType, ActionType, LeaderboardDelta, LeaderboardWindow, DeadLetter};
 use alloy::primitives::{Address, TxHash};
 use alloy::hex::FromHex;
 use uuid::{Uuid, uuid};
@@ -27,6 +27,8 @@ fn make_raw_action() -> ActionRaw {
         block_timestamp: 1755182913,
         tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
         object_type: ObjectType::Entity,
+        log_index: None,
+        output_position: None,
     }
 }
 
@@ -39,6 +41,7 @@ fn make_user_vote() -> UserVote {
         space_id: uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19"),
         vote_type: VoteValue::Up,
         voted_at: 1755182913,
+        weight: 1,
     }
 }
 
@@ -53,6 +56,19 @@ fn make_votes_count() -> VotesCount {
     }
 }
 
+/// Creates a test leaderboard delta for the current day with default values.
+fn make_leaderboard_delta(upvotes: i64) -> LeaderboardDelta {
+    LeaderboardDelta {
+        object_id: Uuid::new_v4(),
+        space_id: uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19"),
+        object_type: ObjectType::Entity,
+        window: LeaderboardWindow::Day,
+        window_start: LeaderboardWindow::Day.window_start(1755182913),
+        upvotes,
+        downvotes: 0,
+    }
+}
+
 // ============================================================================
 // Raw Actions Tests
 // ============================================================================
@@ -141,6 +157,93 @@ async fn test_insert_raw_action_with_metadata(pool: sqlx::PgPool) {
     assert_eq!(metadata.as_ref().unwrap(), &test_metadata);
 }
 
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_insert_action_kinds(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let actions = vec![
+        Action::Flag(Flag {
+            raw: ActionRaw { action_type: ActionType::Flag, ..make_raw_action() },
+            reason: Some("spam".to_string()),
+        }),
+        Action::EditPublished(EditPublished {
+            raw: ActionRaw { action_type: ActionType::EditPublished, ..make_raw_action() },
+            content_uri: "ipfs://QmEdit".to_string(),
+        }),
+        Action::Membership(Membership {
+            raw: ActionRaw { action_type: ActionType::Membership, ..make_raw_action() },
+            change: MembershipChange::Leave,
+        }),
+    ];
+
+    repository.insert_actions(&actions).await.unwrap();
+
+    let raw_actions = sqlx::query("SELECT action_type FROM raw_actions ORDER BY action_type")
+        .fetch_all(&pool).await.unwrap();
+    let flag = sqlx::query("SELECT object_id, reason FROM flags").fetch_one(&pool).await.unwrap();
+    let edit = sqlx::query("SELECT edit_id, content_uri FROM published_edits").fetch_one(&pool).await.unwrap();
+    let membership = sqlx::query("SELECT user_id, change FROM membership_changes").fetch_one(&pool).await.unwrap();
+
+    assert_eq!(raw_actions.iter().map(|row| row.get::<i64, _>("action_type")).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(flag.get::<Uuid, _>("object_id"), actions[0].raw().object_id);
+    assert_eq!(flag.get::<Option<String>, _>("reason").as_deref(), Some("spam"));
+    assert_eq!(edit.get::<Uuid, _>("edit_id"), actions[1].raw().object_id);
+    assert_eq!(edit.get::<String, _>("content_uri"), "ipfs://QmEdit");
+    assert_eq!(membership.get::<String, _>("user_id"), format!("0x{}", hex::encode(actions[2].raw().sender.as_slice())));
+    assert_eq!(membership.get::<i16, _>("change"), 1);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_insert_actions_skips_replayed_actions(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let action = |log_index, output_position| Action::Vote(Vote {
+        raw: ActionRaw { log_index, output_position, ..make_raw_action() },
+        vote: VoteValue::Up,
+    });
+
+    repository.insert_actions(&[action(Some(0), None), action(Some(1), None), action(None, Some(1)), action(None, None)]).await.unwrap();
+    repository.insert_actions(&[action(Some(1), None), action(Some(2), None), action(None, Some(1)), action(None, None)]).await.unwrap();
+
+    let actions = sqlx::query("SELECT log_index, output_position FROM raw_actions ORDER BY log_index, output_position")
+        .fetch_all(&pool).await.unwrap();
+
+    // Actions without a log index are deduplicated by their output position, if they have one
+    assert_eq!(
+        actions
+            .iter()
+            .map(|row| (row.get::<Option<i64>, _>("log_index"), row.get::<Option<i64>, _>("output_position")))
+            .collect::<Vec<_>>(),
+        vec![(Some(0), None), (Some(1), None), (Some(2), None), (None, Some(1)), (None, None), (None, None)]
+    );
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_persisted_action_keys(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let raw_action = ActionRaw { log_index: Some(3), ..make_raw_action() };
+    let positioned_action = ActionRaw { output_position: Some(5), ..make_raw_action() };
+
+    repository.insert_actions(&[
+        Action::Vote(Vote { raw: raw_action.clone(), vote: VoteValue::Up }),
+        Action::Vote(Vote { raw: positioned_action, vote: VoteValue::Up }),
+    ]).await.unwrap();
+
+    let tx_hash = raw_action.tx_hash;
+    let mut keys = repository
+        .get_persisted_action_keys(&[
+            (tx_hash, ActionIndex::Log(3)),
+            (tx_hash, ActionIndex::Log(5)),
+            (tx_hash, ActionIndex::OutputPosition(3)),
+            (tx_hash, ActionIndex::OutputPosition(5)),
+        ])
+        .await
+        .unwrap();
+    keys.sort_by_key(|(_, index)| matches!(index, ActionIndex::OutputPosition(_)));
+    let empty = repository.get_persisted_action_keys(&[]).await.unwrap();
+
+    assert_eq!(keys, vec![(tx_hash, ActionIndex::Log(3)), (tx_hash, ActionIndex::OutputPosition(5))]);
+    assert!(empty.is_empty());
+}
+
 // ============================================================================
 // User Votes Tests
 // ============================================================================
@@ -192,6 +295,28 @@ async fn test_update_user_vote(pool: sqlx::PgPool) {
     assert_eq!(updated_votes_in_db.get::<OffsetDateTime, _>("voted_at").unix_timestamp() as u64, updated_user_vote.voted_at);
 }
 
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_update_user_vote_keeps_later_vote(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    let user_vote = make_user_vote();
+    repository.update_user_votes(std::slice::from_ref(&user_vote)).await.unwrap();
+
+    // A replayed vote cast before the stored one doesn't overwrite it
+    let older_vote = UserVote {
+        vote_type: VoteValue::Down,
+        voted_at: user_vote.voted_at - 1,
+        ..user_vote.clone()
+    };
+    repository.update_user_votes(&[older_vote]).await.unwrap();
+
+    let votes = repository
+        .get_user_votes(&[(user_vote.user_id, user_vote.object_id, user_vote.space_id, user_vote.object_type)])
+        .await
+        .unwrap();
+    assert_eq!(votes, vec![user_vote]);
+}
+
 #[sqlx::test(migrations = "src/postgres/migrations")]
 async fn test_update_multiple_user_votes(pool: sqlx::PgPool) {
     let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
@@ -336,6 +461,7 @@ async fn test_get_user_votes(pool: sqlx::PgPool) {
         object_type: ObjectType::Entity,
         vote_type: VoteValue::Down,
         voted_at: 1755182913,
+        weight: 1,
     };
     let user_vote3 = UserVote {
         user_id: Address::from_hex("0x1234567890123456789012345678901234567890").unwrap(),
@@ -344,6 +470,7 @@ async fn test_get_user_votes(pool: sqlx::PgPool) {
         object_type: ObjectType::Entity,
         vote_type: VoteValue::Remove,
         voted_at: 1755182914,
+        weight: 1,
     };
 
     repository.update_user_votes(&[user_vote1.clone(), user_vote2.clone(), user_vote3.clone()]).await.unwrap();
@@ -377,6 +504,7 @@ async fn test_get_user_votes_partial_matches(pool: sqlx::PgPool) {
         object_type: ObjectType::Entity,
         vote_type: VoteValue::Down,
         voted_at: 1755182913,
+        weight: 1,
     };
 
     repository.update_user_votes(&[user_vote1.clone()]).await.unwrap();
@@ -421,4 +549,622 @@ async fn test_get_user_votes_nonexistent_data(pool: sqlx::PgPool) {
     
     let found_votes = repository.get_user_votes(&vote_criteria).await.unwrap();
     assert!(found_votes.is_empty());
-}
\ No newline at end of file
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_object_votes_pages_current_votes(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    let upvote = make_user_vote();
+    let downvote = UserVote {
+        user_id: Address::from_hex("0x1234567890123456789012345678901234567890").unwrap(),
+        vote_type: VoteValue::Down,
+        voted_at: upvote.voted_at + 1,
+        ..upvote.clone()
+    };
+    let removed = UserVote {
+        user_id: Address::from_hex("0x1111111111111111111111111111111111111111").unwrap(),
+        vote_type: VoteValue::Remove,
+        voted_at: upvote.voted_at + 2,
+        ..upvote.clone()
+    };
+    let other_object = make_user_vote();
+    repository.update_user_votes(&[upvote.clone(), downvote.clone(), removed, other_object]).await.unwrap();
+
+    let first_page = repository.get_object_votes(&upvote.object_id, &upvote.space_id, upvote.object_type, 1, 0).await.unwrap();
+    let second_page = repository.get_object_votes(&upvote.object_id, &upvote.space_id, upvote.object_type, 1, 1).await.unwrap();
+    let last_page = repository.get_object_votes(&upvote.object_id, &upvote.space_id, upvote.object_type, 1, 2).await.unwrap();
+    assert_eq!(first_page, vec![downvote]);
+    assert_eq!(second_page, vec![upvote]);
+    assert!(last_page.is_empty());
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_votes_by_user(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    let older = make_user_vote();
+    let newer = UserVote {
+        vote_type: VoteValue::Remove,
+        voted_at: older.voted_at + 1,
+        ..make_user_vote()
+    };
+    let other_user = UserVote {
+        user_id: Address::from_hex("0x1234567890123456789012345678901234567890").unwrap(),
+        ..make_user_vote()
+    };
+    repository.update_user_votes(&[older.clone(), newer.clone(), other_user]).await.unwrap();
+
+    let votes = repository.get_votes_by_user(&older.user_id, 10, 0).await.unwrap();
+    assert_eq!(votes, vec![newer, older]);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_top_objects_ranks_by_score(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    let popular = VotesCount { upvotes: 5, downvotes: 1, ..make_votes_count() };
+    let contested = VotesCount { upvotes: 6, downvotes: 4, ..make_votes_count() };
+    let disliked = VotesCount { upvotes: 0, downvotes: 3, ..make_votes_count() };
+    let other_space = VotesCount { space_id: Uuid::new_v4(), upvotes: 10, ..make_votes_count() };
+    repository.update_votes_counts(&[disliked.clone(), contested.clone(), popular.clone(), other_space]).await.unwrap();
+
+    let top = repository.get_top_objects(&popular.space_id, 2, 0).await.unwrap();
+    let rest = repository.get_top_objects(&popular.space_id, 2, 2).await.unwrap();
+    let ranked: Vec<_> = top.iter().chain(rest.iter()).map(|count| (count.object_id, count.score())).collect();
+    assert_eq!(ranked, vec![(popular.object_id, 4), (contested.object_id, 2), (disliked.object_id, -3)]);
+}
+
+// ============================================================================
+// Leaderboard Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_update_leaderboards_accumulates_deltas(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let delta = make_leaderboard_delta(2);
+
+    repository.update_leaderboards(std::slice::from_ref(&delta)).await.unwrap();
+    repository.update_leaderboards(&[LeaderboardDelta { upvotes: -1, downvotes: 1, ..delta.clone() }]).await.unwrap();
+
+    let entries = repository
+        .get_leaderboard(&delta.space_id, LeaderboardWindow::Day, 1755182913, 10)
+        .await
+        .unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].object_id, delta.object_id);
+    assert_eq!(entries[0].window_start, delta.window_start);
+    assert_eq!(entries[0].upvotes, 1);
+    assert_eq!(entries[0].downvotes, 1);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_leaderboard_orders_and_limits(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let deltas = vec![
+        make_leaderboard_delta(1),
+        make_leaderboard_delta(5),
+        make_leaderboard_delta(3),
+        make_leaderboard_delta(0),
+    ];
+
+    repository.update_leaderboards(&deltas).await.unwrap();
+
+    let entries = repository
+        .get_leaderboard(&deltas[0].space_id, LeaderboardWindow::Day, 1755182913, 2)
+        .await
+        .unwrap();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].object_id, deltas[1].object_id);
+    assert_eq!(entries[1].object_id, deltas[2].object_id);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_leaderboard_other_window_is_empty(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let delta = make_leaderboard_delta(1);
+
+    repository.update_leaderboards(std::slice::from_ref(&delta)).await.unwrap();
+
+    let next_day = repository
+        .get_leaderboard(&delta.space_id, LeaderboardWindow::Day, 1755182913 + 86400, 10)
+        .await
+        .unwrap();
+    let all_time = repository
+        .get_leaderboard(&delta.space_id, LeaderboardWindow::AllTime, 1755182913, 10)
+        .await
+        .unwrap();
+
+    assert!(next_day.is_empty());
+    assert!(all_time.is_empty());
+}
+
+// ============================================================================
+// Reconciliation Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_reconcile_vote_counts_repairs_drift(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+    let downvote = UserVote {
+        user_id: Address::from_hex("0x0000000000000000000000000000000000000001").unwrap(),
+        vote_type: VoteValue::Down,
+        weight: 2,
+        ..user_vote.clone()
+    };
+    let unvoted_count = make_votes_count();
+    let key = (user_vote.object_id, user_vote.space_id, user_vote.object_type);
+
+    repository.update_user_votes(&[user_vote.clone(), downvote]).await.unwrap();
+    repository.update_votes_counts(&[
+        VotesCount { object_id: user_vote.object_id, upvotes: 5, downvotes: 0, ..make_votes_count() },
+        unvoted_count.clone(),
+    ]).await.unwrap();
+
+    let reconciliation = repository.reconcile_vote_counts(None, 10).await.unwrap();
+
+    assert_eq!(reconciliation.checked, 2);
+    assert_eq!(reconciliation.repaired.len(), 2);
+
+    let counts = repository
+        .get_vote_counts(&[key, (unvoted_count.object_id, unvoted_count.space_id, unvoted_count.object_type)])
+        .await
+        .unwrap();
+    let voted = counts.iter().find(|count| count.object_id == user_vote.object_id).unwrap();
+    let unvoted = counts.iter().find(|count| count.object_id == unvoted_count.object_id).unwrap();
+
+    assert_eq!((voted.upvotes, voted.downvotes), (1, 2));
+    assert_eq!((unvoted.upvotes, unvoted.downvotes), (0, 0));
+
+    // A second pass finds nothing left to repair
+    let reconciliation = repository.reconcile_vote_counts(None, 10).await.unwrap();
+    assert_eq!(reconciliation.checked, 2);
+    assert!(reconciliation.repaired.is_empty());
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_reconcile_vote_counts_pages(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_votes = [make_user_vote(), make_user_vote(), make_user_vote()];
+
+    // The user votes were persisted without their counts
+    repository.update_user_votes(&user_votes).await.unwrap();
+
+    let first = repository.reconcile_vote_counts(None, 2).await.unwrap();
+    let second = repository.reconcile_vote_counts(first.last_checked, 2).await.unwrap();
+    let third = repository.reconcile_vote_counts(second.last_checked, 2).await.unwrap();
+
+    assert_eq!((first.checked, first.repaired.len()), (2, 2));
+    assert_eq!((second.checked, second.repaired.len()), (1, 1));
+    assert_eq!(third.checked, 0);
+    assert!(third.last_checked.is_none());
+    assert_ne!(first.last_checked, second.last_checked);
+
+    let counts = sqlx::query("SELECT * FROM votes_count WHERE upvotes = 1").fetch_all(&pool).await.unwrap();
+    assert_eq!(counts.len(), 3);
+}
+
+// ============================================================================
+// Dead Letter Tests
+// ============================================================================
+
+/// Creates a dead letter read from `block_number` with default values.
+fn make_dead_letter(block_number: u64, raw: &[u8]) -> DeadLetter {
+    DeadLetter {
+        block_number,
+        raw: raw.to_vec(),
+        error: "Invalid action type: action_type: 7".to_string(),
+    }
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_insert_dead_letters_skips_duplicates(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    let dead_letter = make_dead_letter(1, &[1, 2, 3]);
+    let other_block = make_dead_letter(2, &[1, 2, 3]);
+    repository.insert_dead_letters(&[dead_letter.clone(), other_block.clone()]).await.unwrap();
+    repository.insert_dead_letters(std::slice::from_ref(&dead_letter)).await.unwrap();
+    repository.insert_dead_letters(&[]).await.unwrap();
+
+    let pending = repository.get_pending_dead_letters(None, 10).await.unwrap();
+    let dead_letters: Vec<DeadLetter> = pending.into_iter().map(|(_, dead_letter)| dead_letter).collect();
+    assert_eq!(dead_letters, vec![dead_letter, other_block]);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_pending_dead_letters_pages_and_skips_reprocessed(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    let dead_letters: Vec<DeadLetter> = (1..=3).map(|block_number| make_dead_letter(block_number, &[block_number as u8])).collect();
+    repository.insert_dead_letters(&dead_letters).await.unwrap();
+
+    let first_page = repository.get_pending_dead_letters(None, 2).await.unwrap();
+    assert_eq!(first_page.len(), 2);
+    let last_page = repository.get_pending_dead_letters(Some(first_page[1].0), 2).await.unwrap();
+    assert_eq!(last_page.len(), 1);
+    assert_eq!(last_page[0].1, dead_letters[2]);
+
+    repository.mark_dead_letters_reprocessed(&[first_page[0].0, last_page[0].0]).await.unwrap();
+
+    let pending = repository.get_pending_dead_letters(None, 10).await.unwrap();
+    assert_eq!(pending, vec![first_page[1].clone()]);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_rollback_to_deletes_later_dead_letters(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    repository.insert_dead_letters(&[make_dead_letter(1, &[1]), make_dead_letter(2, &[2])]).await.unwrap();
+    repository.rollback_to(1).await.unwrap();
+
+    let pending = repository.get_pending_dead_letters(None, 10).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].1.block_number, 1);
+}
+
+// ============================================================================
+// Changeset Batch Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_persist_changesets_journals_each_block(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+    let votes_count = VotesCount { object_id: user_vote.object_id, ..make_votes_count() };
+    let action = |block_number, vote| Action::Vote(Vote {
+        raw: ActionRaw { object_id: user_vote.object_id, block_number, ..make_raw_action() },
+        vote,
+    });
+    let actions = [action(1, VoteValue::Up), action(2, VoteValue::Down)];
+    let user_votes = [user_vote.clone(), UserVote { vote_type: VoteValue::Down, ..user_vote.clone() }];
+    let votes_counts = [votes_count.clone(), VotesCount { upvotes: 0, downvotes: 1, ..votes_count.clone() }];
+
+    repository.persist_changesets(&[
+        Changeset {
+            block_number: 1,
+            final_block_number: 0,
+            actions: &actions[..1],
+            user_votes: &user_votes[..1],
+            votes_count: &votes_counts[..1],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        },
+        Changeset {
+            block_number: 2,
+            final_block_number: 0,
+            actions: &actions[1..],
+            user_votes: &user_votes[1..],
+            votes_count: &votes_counts[1..],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        },
+    ]).await.unwrap();
+
+    let persisted = sqlx::query("SELECT * FROM raw_actions").fetch_all(&pool).await.unwrap();
+    assert_eq!(persisted.len(), 2);
+
+    // The batch is journaled per block, so it can be rolled back to its middle
+    repository.rollback_to(1).await.unwrap();
+
+    let votes = repository
+        .get_user_votes(&[(user_vote.user_id, user_vote.object_id, user_vote.space_id, user_vote.object_type)])
+        .await
+        .unwrap();
+    let counts = repository
+        .get_vote_counts(&[(votes_count.object_id, votes_count.space_id, votes_count.object_type)])
+        .await
+        .unwrap();
+    let persisted = sqlx::query("SELECT * FROM raw_actions").fetch_all(&pool).await.unwrap();
+
+    assert_eq!(votes, vec![user_vote]);
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].upvotes, 1);
+    assert_eq!(counts[0].downvotes, 0);
+    assert_eq!(persisted.len(), 1);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_persist_changesets_is_atomic(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+
+    // Only the second changeset touches the leaderboards, so it is the one that fails
+    sqlx::query("DROP TABLE vote_leaderboards").execute(&pool).await.unwrap();
+
+    let result = repository.persist_changesets(&[
+        Changeset {
+            block_number: 1,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: std::slice::from_ref(&user_vote),
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        },
+        Changeset {
+            block_number: 2,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: &[],
+            votes_count: &[],
+            leaderboard_deltas: &[make_leaderboard_delta(1)],
+            activity_deltas: &[],
+        },
+    ]).await;
+
+    let votes = sqlx::query("SELECT * FROM user_votes").fetch_all(&pool).await.unwrap();
+
+    assert!(result.is_err());
+    assert!(votes.is_empty());
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_persist_changesets_with_cursor(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let cursor_repository = PostgresCursorRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+
+    repository.persist_changesets_with_cursor(
+        &[Changeset {
+            block_number: 1,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: std::slice::from_ref(&user_vote),
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        }],
+        "actions_indexer",
+        "cursor_1",
+        1,
+    ).await.unwrap();
+
+    let votes = sqlx::query("SELECT * FROM user_votes").fetch_all(&pool).await.unwrap();
+    let cursor = cursor_repository.get_cursor("actions_indexer").await.unwrap();
+
+    assert_eq!(votes.len(), 1);
+    assert_eq!(cursor, Some("cursor_1".to_string()));
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_persist_changesets_with_cursor_is_atomic(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let cursor_repository = PostgresCursorRepository::new(pool.clone()).await.unwrap();
+    cursor_repository.save_cursor("actions_indexer", "cursor_1", &1).await.unwrap();
+
+    sqlx::query("DROP TABLE vote_leaderboards").execute(&pool).await.unwrap();
+
+    let result = repository.persist_changesets_with_cursor(
+        &[Changeset {
+            block_number: 2,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: &[],
+            votes_count: &[],
+            leaderboard_deltas: &[make_leaderboard_delta(1)],
+            activity_deltas: &[],
+        }],
+        "actions_indexer",
+        "cursor_2",
+        2,
+    ).await;
+
+    let cursor = cursor_repository.get_cursor("actions_indexer").await.unwrap();
+
+    assert!(result.is_err());
+    assert_eq!(cursor, Some("cursor_1".to_string()));
+}
+
+// ============================================================================
+// Rollback Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_rollback_to_restores_prior_block(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+    let votes_count = VotesCount { object_id: user_vote.object_id, ..make_votes_count() };
+    let delta = LeaderboardDelta { object_id: user_vote.object_id, ..make_leaderboard_delta(1) };
+    let action = |block_number| Action::Vote(Vote {
+        raw: ActionRaw { object_id: user_vote.object_id, block_number, ..make_raw_action() },
+        vote: VoteValue::Up,
+    });
+
+    repository.persist_changeset(&Changeset {
+        block_number: 1,
+        final_block_number: 0,
+        actions: &[action(1)],
+        user_votes: std::slice::from_ref(&user_vote),
+        votes_count: std::slice::from_ref(&votes_count),
+        leaderboard_deltas: std::slice::from_ref(&delta),
+        activity_deltas: &[],
+    }).await.unwrap();
+
+    // Block 2 flips the vote, block 3 removes it
+    for (block_number, vote_type, count, change) in [(2, VoteValue::Down, (0, 1), (-1, 1)), (3, VoteValue::Remove, (0, 0), (0, -1))] {
+        repository.persist_changeset(&Changeset {
+            block_number,
+            final_block_number: 0,
+            actions: &[action(block_number)],
+            user_votes: &[UserVote { vote_type, ..user_vote.clone() }],
+            votes_count: &[VotesCount { upvotes: count.0, downvotes: count.1, ..votes_count.clone() }],
+            leaderboard_deltas: &[LeaderboardDelta { upvotes: change.0, downvotes: change.1, ..delta.clone() }],
+            activity_deltas: &[],
+        }).await.unwrap();
+    }
+
+    repository.rollback_to(1).await.unwrap();
+
+    let votes = repository
+        .get_user_votes(&[(user_vote.user_id, user_vote.object_id, user_vote.space_id, user_vote.object_type)])
+        .await
+        .unwrap();
+    let counts = repository
+        .get_vote_counts(&[(votes_count.object_id, votes_count.space_id, votes_count.object_type)])
+        .await
+        .unwrap();
+    let entries = repository
+        .get_leaderboard(&delta.space_id, LeaderboardWindow::Day, 1755182913, 10)
+        .await
+        .unwrap();
+    let actions = sqlx::query("SELECT * FROM raw_actions").fetch_all(&pool).await.unwrap();
+    let journal = sqlx::query("SELECT * FROM changeset_user_votes WHERE block_number > 1")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(votes, vec![user_vote]);
+    assert_eq!(counts.len(), 1);
+    assert_eq!(counts[0].upvotes, votes_count.upvotes);
+    assert_eq!(counts[0].downvotes, votes_count.downvotes);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].upvotes, 1);
+    assert_eq!(entries[0].downvotes, 0);
+    assert_eq!(actions.len(), 1);
+    assert!(journal.is_empty());
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_rollback_to_restores_vote_weight(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+    let criteria = [(user_vote.user_id, user_vote.object_id, user_vote.space_id, user_vote.object_type)];
+
+    for (block_number, weight) in [(1, 3), (2, 5)] {
+        repository.persist_changeset(&Changeset {
+            block_number,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: &[UserVote { weight, ..user_vote.clone() }],
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        }).await.unwrap();
+    }
+
+    let votes = repository.get_user_votes(&criteria).await.unwrap();
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].weight, 5);
+
+    repository.rollback_to(1).await.unwrap();
+
+    let votes = repository.get_user_votes(&criteria).await.unwrap();
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].weight, 3);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_rollback_to_with_cursor(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let cursor_repository = PostgresCursorRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+    let criteria = [(user_vote.user_id, user_vote.object_id, user_vote.space_id, user_vote.object_type)];
+
+    for (block_number, vote_type) in [(1, VoteValue::Up), (2, VoteValue::Down)] {
+        repository.persist_changesets_with_cursor(
+            &[Changeset {
+                block_number,
+                final_block_number: 0,
+                actions: &[],
+                user_votes: &[UserVote { vote_type, ..user_vote.clone() }],
+                votes_count: &[],
+                leaderboard_deltas: &[],
+                activity_deltas: &[],
+            }],
+            "actions_indexer",
+            &format!("cursor_{block_number}"),
+            block_number as i64,
+        ).await.unwrap();
+    }
+
+    repository.rollback_to_with_cursor(1, "actions_indexer", "cursor_1").await.unwrap();
+
+    let votes = repository.get_user_votes(&criteria).await.unwrap();
+    let cursor = cursor_repository.get_cursor("actions_indexer").await.unwrap();
+
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].vote_type, VoteValue::Up);
+    assert_eq!(cursor, Some("cursor_1".to_string()));
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_persist_changeset_prunes_journal_of_final_blocks(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+
+    for (block_number, final_block_number) in [(1, 0), (2, 0), (3, 2)] {
+        repository.persist_changeset(&Changeset {
+            block_number,
+            final_block_number,
+            actions: &[],
+            user_votes: std::slice::from_ref(&user_vote),
+            votes_count: &[],
+            leaderboard_deltas: &[make_leaderboard_delta(1)],
+            activity_deltas: &[],
+        }).await.unwrap();
+    }
+
+    let user_votes = sqlx::query("SELECT block_number FROM changeset_user_votes").fetch_all(&pool).await.unwrap();
+    let leaderboards = sqlx::query("SELECT block_number FROM changeset_leaderboards").fetch_all(&pool).await.unwrap();
+
+    // Blocks 1 and 2 are final, so they can't be rolled back anymore
+    assert_eq!(user_votes.iter().map(|row| row.get::<i64, _>("block_number")).collect::<Vec<_>>(), vec![3]);
+    assert_eq!(leaderboards.iter().map(|row| row.get::<i64, _>("block_number")).collect::<Vec<_>>(), vec![3]);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_rollback_to_deletes_rows_created_later(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+    let votes_count = make_votes_count();
+
+    repository.persist_changeset(&Changeset {
+        block_number: 5,
+        final_block_number: 0,
+        actions: &[],
+        user_votes: std::slice::from_ref(&user_vote),
+        votes_count: std::slice::from_ref(&votes_count),
+        leaderboard_deltas: &[],
+        activity_deltas: &[],
+    }).await.unwrap();
+
+    repository.rollback_to(4).await.unwrap();
+
+    let votes = sqlx::query("SELECT * FROM user_votes").fetch_all(&pool).await.unwrap();
+    let counts = sqlx::query("SELECT * FROM votes_count").fetch_all(&pool).await.unwrap();
+
+    assert!(votes.is_empty());
+    assert!(counts.is_empty());
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_rollback_to_deletes_later_action_kinds(pool: sqlx::PgPool) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let membership = |block_number, change| Action::Membership(Membership {
+        raw: ActionRaw { action_type: ActionType::Membership, block_number, ..make_raw_action() },
+        change,
+    });
+
+    for (block_number, change) in [(1, MembershipChange::Join), (2, MembershipChange::Leave)] {
+        repository.persist_changeset(&Changeset {
+            block_number,
+            final_block_number: 0,
+            actions: &[membership(block_number, change)],
+            user_votes: &[],
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        }).await.unwrap();
+    }
+
+    repository.rollback_to(1).await.unwrap();
+
+    let changes = sqlx::query("SELECT change FROM membership_changes").fetch_all(&pool).await.unwrap();
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].get::<i16, _>("change"), 0);
+}
@@ -0,0 +1,177 @@
+//! Integration tests for PostgreSQL activity repository implementation.
+//!
+//! These tests require a real PostgreSQL database and use SQLx test macros
+//! to ensure proper test isolation and cleanup.
+//!
+//! Run with: `cargo test --test postgres_activity`
+
+use actions_indexer_repository::{ActionsRepository, ActivityRepository, PostgresActionsRepository, PostgresActivityRepository};
+use actions_indexer_shared::types::{ActionType, ActivityDelta, Changeset, SenderActivity, SpaceActivity, ACTIVITY_BUCKET_SECONDS};
+use alloy::primitives::Address;
+use uuid::{Uuid, uuid};
+
+const SPACE_A: Uuid = uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19");
+const SPACE_B: Uuid = uuid!("e50fe85c-108a-4d4a-97b9-376a1e5d318b");
+/// Start of an hourly bucket.
+const NOW: u64 = 1755180000;
+
+/// Creates a test activity delta in the current bucket.
+fn make_delta(space_id: Uuid, sender: u8, action_type: ActionType, actions: i64) -> ActivityDelta {
+    ActivityDelta {
+        bucket_start: NOW,
+        space_id,
+        sender: Address::with_last_byte(sender),
+        action_type,
+        actions,
+    }
+}
+
+/// Persists the activity deltas of a block through its changeset.
+async fn record_activity(pool: &sqlx::PgPool, block_number: u64, activity_deltas: &[ActivityDelta]) {
+    let repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    repository
+        .persist_changeset(&Changeset {
+            block_number,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: &[],
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas,
+        })
+        .await
+        .unwrap();
+}
+
+// ============================================================================
+// Activity Counter Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_most_active_spaces_ranks_spaces(pool: sqlx::PgPool) {
+    record_activity(
+        &pool,
+        1,
+        &[
+            make_delta(SPACE_A, 1, ActionType::Vote, 2),
+            make_delta(SPACE_B, 1, ActionType::Vote, 3),
+            make_delta(SPACE_A, 2, ActionType::Flag, 4),
+        ],
+    )
+    .await;
+    let repository = PostgresActivityRepository::new(pool).await.unwrap();
+
+    let spaces = repository.get_most_active_spaces(NOW, None, 10).await.unwrap();
+    assert_eq!(
+        spaces,
+        vec![
+            SpaceActivity { space_id: SPACE_A, actions: 6 },
+            SpaceActivity { space_id: SPACE_B, actions: 3 },
+        ]
+    );
+
+    let vote_spaces = repository.get_most_active_spaces(NOW, Some(ActionType::Vote), 10).await.unwrap();
+    assert_eq!(
+        vote_spaces,
+        vec![
+            SpaceActivity { space_id: SPACE_B, actions: 3 },
+            SpaceActivity { space_id: SPACE_A, actions: 2 },
+        ]
+    );
+
+    let top_space = repository.get_most_active_spaces(NOW, None, 1).await.unwrap();
+    assert_eq!(top_space.len(), 1);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_persist_changeset_adds_to_activity_counters(pool: sqlx::PgPool) {
+    // The same counter twice in a block, then again in a later block
+    record_activity(
+        &pool,
+        1,
+        &[
+            make_delta(SPACE_A, 1, ActionType::Vote, 1),
+            make_delta(SPACE_A, 1, ActionType::Vote, 2),
+        ],
+    )
+    .await;
+    record_activity(&pool, 2, &[make_delta(SPACE_A, 1, ActionType::Vote, 4)]).await;
+    record_activity(&pool, 3, &[]).await;
+    let repository = PostgresActivityRepository::new(pool).await.unwrap();
+
+    let senders = repository.get_most_active_senders(NOW, None, None, 10).await.unwrap();
+    assert_eq!(senders, vec![SenderActivity { sender: Address::with_last_byte(1), actions: 7 }]);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_most_active_senders_filters(pool: sqlx::PgPool) {
+    let mut old = make_delta(SPACE_A, 3, ActionType::Vote, 100);
+    old.bucket_start = NOW - 24 * ACTIVITY_BUCKET_SECONDS;
+    record_activity(
+        &pool,
+        1,
+        &[
+            old,
+            make_delta(SPACE_A, 1, ActionType::Vote, 2),
+            make_delta(SPACE_A, 2, ActionType::Membership, 1),
+            make_delta(SPACE_B, 2, ActionType::Vote, 5),
+        ],
+    )
+    .await;
+    let repository = PostgresActivityRepository::new(pool).await.unwrap();
+
+    // The bucket of the old delta is outside of the last 24 hours
+    let since = NOW + ACTIVITY_BUCKET_SECONDS - 24 * ACTIVITY_BUCKET_SECONDS;
+    let senders = repository.get_most_active_senders(since, None, None, 10).await.unwrap();
+    assert_eq!(
+        senders,
+        vec![
+            SenderActivity { sender: Address::with_last_byte(2), actions: 6 },
+            SenderActivity { sender: Address::with_last_byte(1), actions: 2 },
+        ]
+    );
+
+    let in_space = repository.get_most_active_senders(since, Some(SPACE_A), None, 10).await.unwrap();
+    assert_eq!(
+        in_space,
+        vec![
+            SenderActivity { sender: Address::with_last_byte(1), actions: 2 },
+            SenderActivity { sender: Address::with_last_byte(2), actions: 1 },
+        ]
+    );
+
+    let memberships = repository.get_most_active_senders(since, Some(SPACE_A), Some(ActionType::Membership), 10).await.unwrap();
+    assert_eq!(memberships, vec![SenderActivity { sender: Address::with_last_byte(2), actions: 1 }]);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_prune_activity(pool: sqlx::PgPool) {
+    let mut old = make_delta(SPACE_A, 1, ActionType::Vote, 1);
+    old.bucket_start = NOW - ACTIVITY_BUCKET_SECONDS;
+    record_activity(&pool, 1, &[old, make_delta(SPACE_B, 1, ActionType::Vote, 1)]).await;
+    let repository = PostgresActivityRepository::new(pool).await.unwrap();
+
+    let pruned = repository.prune_activity(NOW).await.unwrap();
+    assert_eq!(pruned, 1);
+
+    let spaces = repository.get_most_active_spaces(0, None, 10).await.unwrap();
+    assert_eq!(spaces, vec![SpaceActivity { space_id: SPACE_B, actions: 1 }]);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_rollback_to_reverts_activity_counters(pool: sqlx::PgPool) {
+    record_activity(&pool, 1, &[make_delta(SPACE_A, 1, ActionType::Vote, 2)]).await;
+    record_activity(&pool, 2, &[make_delta(SPACE_A, 1, ActionType::Vote, 3), make_delta(SPACE_B, 2, ActionType::Flag, 1)]).await;
+
+    let actions_repository = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    actions_repository.rollback_to(1).await.unwrap();
+
+    let repository = PostgresActivityRepository::new(pool.clone()).await.unwrap();
+    let spaces = repository.get_most_active_spaces(NOW, None, 10).await.unwrap();
+    assert_eq!(spaces, vec![SpaceActivity { space_id: SPACE_A, actions: 2 }]);
+
+    // Persisting the block again after the rollback counts it once
+    record_activity(&pool, 2, &[make_delta(SPACE_A, 1, ActionType::Vote, 3)]).await;
+    let senders = repository.get_most_active_senders(NOW, None, None, 10).await.unwrap();
+    assert_eq!(senders, vec![SenderActivity { sender: Address::with_last_byte(1), actions: 5 }]);
+}
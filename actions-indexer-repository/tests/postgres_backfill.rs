@@ -0,0 +1,167 @@
+//! Integration tests for PostgreSQL backfill repository implementation.
+//!
+//! These tests require a real PostgreSQL database and use SQLx test macros
+//! to ensure proper test isolation and cleanup.
+//!
+//! Run with: `cargo test --test postgres_backfill`
+
+use actions_indexer_repository::{ActionsRepository, BackfillRepository, BackfillRepositoryError, CursorRepository, PostgresActionsRepository, PostgresBackfillRepository, PostgresCursorRepository};
+use actions_indexer_shared::types::{Action, ActionRaw, ActionType, ActionsTableCounts, DeadLetter, ObjectType, Vote, VoteValue, VotesCount};
+use alloy::primitives::{Address, TxHash};
+use uuid::{Uuid, uuid};
+
+const SPACE: Uuid = uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19");
+
+/// Creates a test vote in `block_number`, its log index telling it apart.
+fn make_vote(block_number: u64, log_index: u32) -> Action {
+    Action::Vote(Vote {
+        raw: ActionRaw {
+            action_type: ActionType::Vote,
+            action_version: 1,
+            sender: Address::with_last_byte(1),
+            object_id: Uuid::new_v4(),
+            group_id: None,
+            space_pov: SPACE,
+            metadata: None,
+            block_number,
+            block_timestamp: 1755182913,
+            tx_hash: TxHash::with_last_byte(block_number as u8),
+            object_type: ObjectType::Entity,
+            log_index: Some(log_index),
+            output_position: None,
+        },
+        vote: VoteValue::Up,
+    })
+}
+
+/// Creates a test votes count of a new object.
+fn make_votes_count(upvotes: i64, downvotes: i64) -> VotesCount {
+    VotesCount {
+        object_id: Uuid::new_v4(),
+        object_type: ObjectType::Entity,
+        space_id: SPACE,
+        upvotes,
+        downvotes,
+    }
+}
+
+/// Creates the `staging` schema and returns a repository over its actions tables.
+async fn create_staging(backfill: &PostgresBackfillRepository, pool: &sqlx::PgPool) -> PostgresActionsRepository {
+    backfill.create_schema("staging").await.unwrap();
+    let options = (*pool.connect_options()).clone();
+    let staging_pool = actions_indexer_repository::postgres::connect_with(options, Some("staging")).await.unwrap();
+    PostgresActionsRepository::new(staging_pool).await.unwrap()
+}
+
+// ============================================================================
+// Staging Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_create_schema_migrates_actions_tables(pool: sqlx::PgPool) {
+    let backfill = PostgresBackfillRepository::new(pool.clone()).await.unwrap();
+
+    let staging = create_staging(&backfill, &pool).await;
+
+    assert!(staging.check_tables_created().await.unwrap());
+    assert_eq!(backfill.count_rows("staging", 0, u64::MAX >> 1).await.unwrap(), ActionsTableCounts::default());
+    // The schema can't be created twice, which would mix two backfills
+    assert!(matches!(backfill.create_schema("staging").await, Err(BackfillRepositoryError::DatabaseError(_))));
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_count_rows(pool: sqlx::PgPool) {
+    let backfill = PostgresBackfillRepository::new(pool.clone()).await.unwrap();
+    let production = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+
+    production.insert_actions(&[make_vote(1, 0), make_vote(5, 0), make_vote(5, 1), make_vote(9, 0)]).await.unwrap();
+    production.update_votes_counts(&[make_votes_count(3, 1), make_votes_count(2, 0)]).await.unwrap();
+    production
+        .insert_dead_letters(&[DeadLetter {
+            block_number: 5,
+            raw: vec![1, 2, 3],
+            error: "Invalid action type: action_type: 7".to_string(),
+        }])
+        .await
+        .unwrap();
+
+    let counts = backfill.count_rows("public", 2, 8).await.unwrap();
+
+    assert_eq!(counts, ActionsTableCounts {
+        raw_actions: 2,
+        dead_letters: 1,
+        user_votes: 0,
+        votes_count: 2,
+        upvotes: 5,
+        downvotes: 1,
+    });
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_cursor_block(pool: sqlx::PgPool) {
+    let backfill = PostgresBackfillRepository::new(pool.clone()).await.unwrap();
+    let cursors = PostgresCursorRepository::new(pool).await.unwrap();
+
+    cursors.save_cursor("actions_indexer", "cursor_42", &42).await.unwrap();
+
+    assert_eq!(backfill.get_cursor_block("public", "actions_indexer").await.unwrap(), Some(42));
+    assert_eq!(backfill.get_cursor_block("public", "actions_indexer:testnet").await.unwrap(), None);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_get_first_block(pool: sqlx::PgPool) {
+    let backfill = PostgresBackfillRepository::new(pool.clone()).await.unwrap();
+    let production = PostgresActionsRepository::new(pool).await.unwrap();
+
+    assert_eq!(backfill.get_first_block("public").await.unwrap(), None);
+
+    production.insert_actions(&[make_vote(9, 0), make_vote(5, 0)]).await.unwrap();
+    assert_eq!(backfill.get_first_block("public").await.unwrap(), Some(5));
+}
+
+// ============================================================================
+// Swap Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_swap_schemas(pool: sqlx::PgPool) {
+    let backfill = PostgresBackfillRepository::new(pool.clone()).await.unwrap();
+    let production = PostgresActionsRepository::new(pool.clone()).await.unwrap();
+    let cursors = PostgresCursorRepository::new(pool.clone()).await.unwrap();
+    let staging = create_staging(&backfill, &pool).await;
+
+    production.insert_actions(&[make_vote(1, 0)]).await.unwrap();
+    production.update_votes_counts(&[make_votes_count(1, 0)]).await.unwrap();
+    cursors.save_cursor("actions_indexer", "cursor_1", &1).await.unwrap();
+    staging.insert_actions(&[make_vote(1, 0), make_vote(1, 1)]).await.unwrap();
+    staging.update_votes_counts(&[make_votes_count(2, 0)]).await.unwrap();
+
+    backfill.swap_schemas("staging", "public", "previous").await.unwrap();
+
+    // The repositories of the production pool read the new tables, and the cursor stays
+    let counts = backfill.count_rows("public", 1, 1).await.unwrap();
+    assert_eq!((counts.raw_actions, counts.upvotes), (2, 2));
+    assert!(production.check_tables_created().await.unwrap());
+    assert_eq!(cursors.get_cursor("actions_indexer").await.unwrap().as_deref(), Some("cursor_1"));
+
+    let archived = backfill.count_rows("previous", 1, 1).await.unwrap();
+    assert_eq!((archived.raw_actions, archived.upvotes), (1, 1));
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_swap_schemas_requires_every_table(pool: sqlx::PgPool) {
+    let backfill = PostgresBackfillRepository::new(pool.clone()).await.unwrap();
+    create_staging(&backfill, &pool).await;
+    sqlx::query("DROP TABLE staging.action_activity").execute(&pool).await.unwrap();
+
+    let result = backfill.swap_schemas("staging", "public", "previous").await;
+
+    assert!(matches!(result, Err(BackfillRepositoryError::MissingTable(table)) if table == "staging.action_activity"));
+    // Nothing was moved, the production tables are still in place
+    let archive: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM information_schema.schemata WHERE schema_name = 'previous')")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!archive);
+    assert!(PostgresActionsRepository::new(pool).await.unwrap().check_tables_created().await.unwrap());
+}
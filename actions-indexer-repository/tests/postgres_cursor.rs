@@ -5,7 +5,7 @@
 //!
 //! Run with: `cargo test --test postgres_cursor`
 
-use actions_indexer_repository::{CursorRepository, PostgresCursorRepository};
+use actions_indexer_repository::{cursor_id, ActionsRepository, CursorRepository, PostgresActionsRepository, PostgresCursorRepository};
 use sqlx::Row;
 
 /// Creates test cursor data for testing.
@@ -162,3 +162,56 @@ async fn test_multiple_repository_instances(pool: sqlx::PgPool) {
     assert!(result.is_some());
     assert_eq!(result.unwrap(), "cursor_from_repo1");
 }
+
+// ============================================================================
+// Namespace Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_namespaces_keep_their_own_cursor(pool: sqlx::PgPool) {
+    let repository = PostgresCursorRepository::new(pool).await.unwrap();
+
+    repository.save_cursor(&cursor_id(None), "cursor_mainnet", &100).await.unwrap();
+    repository.save_cursor(&cursor_id(Some("testnet")), "cursor_testnet", &10).await.unwrap();
+
+    assert_eq!(repository.get_cursor("actions_indexer").await.unwrap().as_deref(), Some("cursor_mainnet"));
+    assert_eq!(repository.get_cursor("actions_indexer:testnet").await.unwrap().as_deref(), Some("cursor_testnet"));
+    assert_eq!(repository.get_cursor(&cursor_id(Some("replay"))).await.unwrap(), None);
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_schema_isolates_tables(pool: sqlx::PgPool) {
+    sqlx::query("CREATE SCHEMA testnet").execute(&pool).await.unwrap();
+    let options = (*pool.connect_options()).clone();
+    let testnet_pool = actions_indexer_repository::postgres::connect_with(options, Some("testnet")).await.unwrap();
+    let testnet_actions = PostgresActionsRepository::new(testnet_pool.clone()).await.unwrap();
+
+    // The tables of the public schema don't count
+    assert!(!testnet_actions.check_tables_created().await.unwrap());
+
+    sqlx::migrate!("src/postgres/migrations").run(&testnet_pool).await.unwrap();
+    assert!(testnet_actions.check_tables_created().await.unwrap());
+
+    let public_cursors = PostgresCursorRepository::new(pool).await.unwrap();
+    let testnet_cursors = PostgresCursorRepository::new(testnet_pool).await.unwrap();
+    testnet_cursors.save_cursor(&cursor_id(None), "cursor_testnet", &10).await.unwrap();
+
+    assert_eq!(public_cursors.get_cursor(&cursor_id(None)).await.unwrap(), None);
+    assert_eq!(testnet_cursors.get_cursor(&cursor_id(None)).await.unwrap().as_deref(), Some("cursor_testnet"));
+}
+
+#[sqlx::test(migrations = "src/postgres/migrations")]
+async fn test_writers_lock_excludes_reprocessing(pool: sqlx::PgPool) {
+    use actions_indexer_repository::postgres::{lock_writers, try_lock_writers_exclusive};
+
+    // Indexers share the lock, the reprocessor can't take it meanwhile
+    let first_indexer = lock_writers(&pool).await.unwrap();
+    let second_indexer = lock_writers(&pool).await.unwrap();
+    assert!(try_lock_writers_exclusive(&pool).await.unwrap().is_none());
+
+    drop(first_indexer);
+    drop(second_indexer);
+    let reprocessor = try_lock_writers_exclusive(&pool).await.unwrap();
+    assert!(reprocessor.is_some());
+    assert!(try_lock_writers_exclusive(&pool).await.unwrap().is_none());
+}
@@ -0,0 +1,569 @@
+//! Integration tests for SQLite actions and cursor repository implementations.
+//!
+//! These tests need no database server: each one runs against a fresh SQLite
+//! database created by the SQLx test macros.
+//!
+//! Run with: `cargo test --features sqlite --test sqlite_actions`
+#![cfg(feature = "sqlite")]
+
+use actions_indexer_repository::sqlite;
+use actions_indexer_repository::{ActionsRepository, CursorRepository, SqliteActionsRepository, SqliteCursorRepository};
+use actions_indexer_shared::types::{Action, ActionIndex, ActionRaw, Changeset, EditPublished, Flag, Membership, MembershipChange, Vote, UserVote, VotesCount, VoteValue, ObjectType, ActionType, LeaderboardDelta, LeaderboardWindow, DeadLetter};
+use alloy::primitives::{Address, TxHash};
+use alloy::hex::FromHex;
+use uuid::{Uuid, uuid};
+use sqlx::Row;
+
+/// Creates a test action raw data with default values.
+fn make_raw_action() -> ActionRaw {
+    ActionRaw {
+        action_type: ActionType::Vote,
+        action_version: 1,
+        sender: Address::from_hex("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045").unwrap(),
+        object_id: Uuid::new_v4(),
+        group_id: None,
+        space_pov: uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19"),
+        metadata: None,
+        block_number: 1,
+        block_timestamp: 1755182913,
+        tx_hash: TxHash::from_hex("0x5427daee8d03277f8a30ea881692c04861e692ce5f305b7a689b76248cae63c4").unwrap(),
+        object_type: ObjectType::Entity,
+        log_index: None,
+        output_position: None,
+    }
+}
+
+/// Creates a test user vote with default values.
+fn make_user_vote() -> UserVote {
+    UserVote {
+        user_id: Address::from_hex("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045").unwrap(),
+        object_id: Uuid::new_v4(),
+        object_type: ObjectType::Entity,
+        space_id: uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19"),
+        vote_type: VoteValue::Up,
+        voted_at: 1755182913,
+        weight: 1,
+    }
+}
+
+/// Creates a test votes count with default values.
+fn make_votes_count() -> VotesCount {
+    VotesCount {
+        object_id: Uuid::new_v4(),
+        object_type: ObjectType::Entity,
+        space_id: uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19"),
+        upvotes: 1,
+        downvotes: 0,
+    }
+}
+
+/// Creates a test leaderboard delta for the current day with default values.
+fn make_leaderboard_delta(upvotes: i64) -> LeaderboardDelta {
+    LeaderboardDelta {
+        object_id: Uuid::new_v4(),
+        space_id: uuid!("f5d2fe0c-fb9d-4027-b227-54f59af20f19"),
+        object_type: ObjectType::Entity,
+        window: LeaderboardWindow::Day,
+        window_start: LeaderboardWindow::Day.window_start(1755182913),
+        upvotes,
+        downvotes: 0,
+    }
+}
+
+/// Creates a dead letter read from `block_number` with default values.
+fn make_dead_letter(block_number: u64, raw: &[u8]) -> DeadLetter {
+    DeadLetter {
+        block_number,
+        raw: raw.to_vec(),
+        error: "Invalid action type: action_type: 7".to_string(),
+    }
+}
+
+// ============================================================================
+// Connection Tests
+// ============================================================================
+
+#[sqlx::test]
+async fn test_connect_applies_schema() {
+    let pool = sqlite::connect("sqlite::memory:").await.unwrap();
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+
+    assert!(repository.check_tables_created().await.unwrap());
+}
+
+// ============================================================================
+// Raw Actions Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_insert_action_kinds(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let raw = |action_type, log_index| ActionRaw { action_type, log_index: Some(log_index), ..make_raw_action() };
+
+    repository.insert_actions(&[
+        Action::Vote(Vote { raw: raw(ActionType::Vote, 0), vote: VoteValue::Up }),
+        Action::Flag(Flag { raw: raw(ActionType::Flag, 1), reason: Some("spam".to_string()) }),
+        Action::EditPublished(EditPublished { raw: raw(ActionType::EditPublished, 2), content_uri: "ipfs://edit".to_string() }),
+        Action::Membership(Membership { raw: raw(ActionType::Membership, 3), change: MembershipChange::Join }),
+    ]).await.unwrap();
+    repository.insert_actions(&[]).await.unwrap();
+
+    let actions = sqlx::query("SELECT * FROM raw_actions").fetch_all(&pool).await.unwrap();
+    let flag = sqlx::query("SELECT reason FROM flags").fetch_one(&pool).await.unwrap();
+    let edit = sqlx::query("SELECT content_uri FROM published_edits").fetch_one(&pool).await.unwrap();
+    let membership = sqlx::query("SELECT change FROM membership_changes").fetch_one(&pool).await.unwrap();
+
+    assert_eq!(actions.len(), 4);
+    assert_eq!(flag.get::<Option<String>, _>("reason"), Some("spam".to_string()));
+    assert_eq!(edit.get::<String, _>("content_uri"), "ipfs://edit");
+    assert_eq!(membership.get::<i16, _>("change"), 0);
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_insert_actions_skips_replayed_actions(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let action = |log_index, output_position| Action::Vote(Vote {
+        raw: ActionRaw { log_index, output_position, ..make_raw_action() },
+        vote: VoteValue::Up,
+    });
+
+    repository.insert_actions(&[action(Some(0), None), action(Some(1), None), action(None, Some(1)), action(None, None)]).await.unwrap();
+    repository.insert_actions(&[action(Some(1), None), action(Some(2), None), action(None, Some(1)), action(None, None)]).await.unwrap();
+
+    let actions = sqlx::query("SELECT log_index, output_position FROM raw_actions ORDER BY log_index IS NULL, log_index, output_position IS NULL, output_position")
+        .fetch_all(&pool).await.unwrap();
+
+    // Actions without a log index are deduplicated by their output position, if they have one
+    assert_eq!(
+        actions
+            .iter()
+            .map(|row| (row.get::<Option<i64>, _>("log_index"), row.get::<Option<i64>, _>("output_position")))
+            .collect::<Vec<_>>(),
+        vec![(Some(0), None), (Some(1), None), (Some(2), None), (None, Some(1)), (None, None), (None, None)]
+    );
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_get_persisted_action_keys(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+    let raw_action = ActionRaw { log_index: Some(3), ..make_raw_action() };
+    let positioned_action = ActionRaw { output_position: Some(5), ..make_raw_action() };
+
+    repository.insert_actions(&[
+        Action::Vote(Vote { raw: raw_action.clone(), vote: VoteValue::Up }),
+        Action::Vote(Vote { raw: positioned_action, vote: VoteValue::Up }),
+    ]).await.unwrap();
+
+    let tx_hash = raw_action.tx_hash;
+    let mut keys = repository
+        .get_persisted_action_keys(&[
+            (tx_hash, ActionIndex::Log(3)),
+            (tx_hash, ActionIndex::Log(5)),
+            (tx_hash, ActionIndex::OutputPosition(3)),
+            (tx_hash, ActionIndex::OutputPosition(5)),
+        ])
+        .await
+        .unwrap();
+    keys.sort_by_key(|(_, index)| matches!(index, ActionIndex::OutputPosition(_)));
+    let empty = repository.get_persisted_action_keys(&[]).await.unwrap();
+
+    assert_eq!(keys, vec![(tx_hash, ActionIndex::Log(3)), (tx_hash, ActionIndex::OutputPosition(5))]);
+    assert!(empty.is_empty());
+}
+
+// ============================================================================
+// Query Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_update_and_get_user_votes(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+    let user_vote = make_user_vote();
+    let other_vote = make_user_vote();
+    let criteria = |vote: &UserVote| (vote.user_id, vote.object_id, vote.space_id, vote.object_type);
+
+    repository.update_user_votes(&[user_vote.clone(), other_vote.clone()]).await.unwrap();
+    let changed = UserVote { vote_type: VoteValue::Down, voted_at: 1755182914, weight: 2, ..user_vote.clone() };
+    repository.update_user_votes(std::slice::from_ref(&changed)).await.unwrap();
+    // A replayed vote cast before the stored one doesn't overwrite it
+    repository.update_user_votes(std::slice::from_ref(&user_vote)).await.unwrap();
+
+    let votes = repository.get_user_votes(&[criteria(&user_vote), criteria(&make_user_vote())]).await.unwrap();
+    let empty = repository.get_user_votes(&[]).await.unwrap();
+
+    assert_eq!(votes, vec![changed]);
+    assert!(empty.is_empty());
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_update_and_get_vote_counts(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+    let votes_count = make_votes_count();
+
+    repository.update_votes_counts(std::slice::from_ref(&votes_count)).await.unwrap();
+    let changed = VotesCount { upvotes: 3, downvotes: 2, ..votes_count.clone() };
+    repository.update_votes_counts(std::slice::from_ref(&changed)).await.unwrap();
+
+    let counts = repository
+        .get_vote_counts(&[(votes_count.object_id, votes_count.space_id, votes_count.object_type)])
+        .await
+        .unwrap();
+
+    assert_eq!(counts.len(), 1);
+    assert_eq!((counts[0].upvotes, counts[0].downvotes), (changed.upvotes, changed.downvotes));
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_get_object_votes_and_votes_by_user(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+    let user_vote = make_user_vote();
+    let later = UserVote {
+        user_id: Address::from_hex("0x0000000000000000000000000000000000000001").unwrap(),
+        voted_at: user_vote.voted_at + 1,
+        ..user_vote.clone()
+    };
+    let removed = UserVote {
+        user_id: Address::from_hex("0x0000000000000000000000000000000000000002").unwrap(),
+        vote_type: VoteValue::Remove,
+        ..user_vote.clone()
+    };
+    let elsewhere = UserVote { object_id: Uuid::new_v4(), voted_at: user_vote.voted_at + 2, ..user_vote.clone() };
+
+    repository.update_user_votes(&[user_vote.clone(), later.clone(), removed, elsewhere.clone()]).await.unwrap();
+
+    let object_votes = repository
+        .get_object_votes(&user_vote.object_id, &user_vote.space_id, user_vote.object_type, 10, 0)
+        .await
+        .unwrap();
+    let second_page = repository
+        .get_object_votes(&user_vote.object_id, &user_vote.space_id, user_vote.object_type, 1, 1)
+        .await
+        .unwrap();
+    let by_user = repository.get_votes_by_user(&user_vote.user_id, 10, 0).await.unwrap();
+
+    assert_eq!(object_votes, vec![later, user_vote.clone()]);
+    assert_eq!(second_page, vec![user_vote.clone()]);
+    assert_eq!(by_user, vec![elsewhere, user_vote]);
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_get_top_objects_ranks_by_score(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+    let low = VotesCount { upvotes: 5, downvotes: 5, ..make_votes_count() };
+    let high = VotesCount { upvotes: 3, downvotes: 0, ..make_votes_count() };
+
+    repository.update_votes_counts(&[low.clone(), high.clone()]).await.unwrap();
+
+    let top = repository.get_top_objects(&high.space_id, 10, 0).await.unwrap();
+
+    assert_eq!(top.iter().map(|count| count.object_id).collect::<Vec<_>>(), vec![high.object_id, low.object_id]);
+}
+
+// ============================================================================
+// Leaderboard Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_get_leaderboard_accumulates_and_orders(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+    let first = make_leaderboard_delta(2);
+    let second = make_leaderboard_delta(1);
+
+    repository.update_leaderboards(&[first.clone(), second.clone()]).await.unwrap();
+    repository.update_leaderboards(&[LeaderboardDelta { upvotes: 3, ..second.clone() }]).await.unwrap();
+
+    let entries = repository
+        .get_leaderboard(&first.space_id, LeaderboardWindow::Day, 1755182913, 10)
+        .await
+        .unwrap();
+    let other_window = repository
+        .get_leaderboard(&first.space_id, LeaderboardWindow::Week, 1755182913, 10)
+        .await
+        .unwrap();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!((entries[0].object_id, entries[0].upvotes), (second.object_id, 4));
+    assert_eq!((entries[1].object_id, entries[1].upvotes), (first.object_id, 2));
+    assert_eq!(entries[0].window_start, first.window_start);
+    assert!(other_window.is_empty());
+}
+
+// ============================================================================
+// Reconciliation Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_reconcile_vote_counts_repairs_drift(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+    let user_vote = make_user_vote();
+    let downvote = UserVote {
+        user_id: Address::from_hex("0x0000000000000000000000000000000000000001").unwrap(),
+        vote_type: VoteValue::Down,
+        weight: 2,
+        ..user_vote.clone()
+    };
+    let unvoted_count = make_votes_count();
+    let key = (user_vote.object_id, user_vote.space_id, user_vote.object_type);
+
+    repository.update_user_votes(&[user_vote.clone(), downvote]).await.unwrap();
+    repository.update_votes_counts(&[
+        VotesCount { object_id: user_vote.object_id, upvotes: 5, downvotes: 0, ..make_votes_count() },
+        unvoted_count.clone(),
+    ]).await.unwrap();
+
+    let reconciliation = repository.reconcile_vote_counts(None, 10).await.unwrap();
+
+    assert_eq!(reconciliation.checked, 2);
+    assert_eq!(reconciliation.repaired.len(), 2);
+
+    let counts = repository
+        .get_vote_counts(&[key, (unvoted_count.object_id, unvoted_count.space_id, unvoted_count.object_type)])
+        .await
+        .unwrap();
+    let voted = counts.iter().find(|count| count.object_id == user_vote.object_id).unwrap();
+    let unvoted = counts.iter().find(|count| count.object_id == unvoted_count.object_id).unwrap();
+
+    assert_eq!((voted.upvotes, voted.downvotes), (1, 2));
+    assert_eq!((unvoted.upvotes, unvoted.downvotes), (0, 0));
+
+    // A second pass finds nothing left to repair
+    let reconciliation = repository.reconcile_vote_counts(None, 10).await.unwrap();
+    assert_eq!(reconciliation.checked, 2);
+    assert!(reconciliation.repaired.is_empty());
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_reconcile_vote_counts_pages(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let user_votes = [make_user_vote(), make_user_vote(), make_user_vote()];
+
+    // The user votes were persisted without their counts
+    repository.update_user_votes(&user_votes).await.unwrap();
+
+    let first = repository.reconcile_vote_counts(None, 2).await.unwrap();
+    let second = repository.reconcile_vote_counts(first.last_checked, 2).await.unwrap();
+    let third = repository.reconcile_vote_counts(second.last_checked, 2).await.unwrap();
+
+    assert_eq!((first.checked, first.repaired.len()), (2, 2));
+    assert_eq!((second.checked, second.repaired.len()), (1, 1));
+    assert_eq!(third.checked, 0);
+    assert!(third.last_checked.is_none());
+
+    let counts = sqlx::query("SELECT * FROM votes_count WHERE upvotes = 1").fetch_all(&pool).await.unwrap();
+    assert_eq!(counts.len(), 3);
+}
+
+// ============================================================================
+// Dead Letter Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_dead_letters_skip_duplicates_and_page(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool).await.unwrap();
+
+    let dead_letters: Vec<DeadLetter> = (1..=3).map(|block_number| make_dead_letter(block_number, &[1, 2, 3])).collect();
+    repository.insert_dead_letters(&dead_letters).await.unwrap();
+    repository.insert_dead_letters(&dead_letters[..1]).await.unwrap();
+    repository.insert_dead_letters(&[]).await.unwrap();
+
+    let first_page = repository.get_pending_dead_letters(None, 2).await.unwrap();
+    assert_eq!(first_page.len(), 2);
+    let last_page = repository.get_pending_dead_letters(Some(first_page[1].0), 2).await.unwrap();
+    assert_eq!(last_page.len(), 1);
+    assert_eq!(last_page[0].1, dead_letters[2]);
+
+    repository.mark_dead_letters_reprocessed(&[first_page[0].0, last_page[0].0]).await.unwrap();
+
+    let pending = repository.get_pending_dead_letters(None, 10).await.unwrap();
+    assert_eq!(pending, vec![first_page[1].clone()]);
+}
+
+// ============================================================================
+// Changeset and Rollback Tests
+// ============================================================================
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_persist_changesets_with_cursor(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let cursor_repository = SqliteCursorRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+
+    assert_eq!(cursor_repository.get_cursor("actions_indexer").await.unwrap(), None);
+
+    repository.persist_changesets_with_cursor(
+        &[Changeset {
+            block_number: 1,
+            final_block_number: 0,
+            actions: &[],
+            user_votes: std::slice::from_ref(&user_vote),
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        }],
+        "actions_indexer",
+        "cursor_1",
+        1,
+    ).await.unwrap();
+    cursor_repository.save_cursor("other_indexer", "cursor_9", &9).await.unwrap();
+
+    let votes = sqlx::query("SELECT * FROM user_votes").fetch_all(&pool).await.unwrap();
+
+    assert_eq!(votes.len(), 1);
+    assert_eq!(cursor_repository.get_cursor("actions_indexer").await.unwrap(), Some("cursor_1".to_string()));
+    assert_eq!(cursor_repository.get_cursor("other_indexer").await.unwrap(), Some("cursor_9".to_string()));
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_persist_changesets_with_cursor_is_atomic(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let cursor_repository = SqliteCursorRepository::new(pool.clone()).await.unwrap();
+    cursor_repository.save_cursor("actions_indexer", "cursor_1", &1).await.unwrap();
+
+    // Only the second changeset touches the leaderboards, so it is the one that fails
+    sqlx::query("DROP TABLE vote_leaderboards").execute(&pool).await.unwrap();
+
+    let result = repository.persist_changesets_with_cursor(
+        &[
+            Changeset {
+                block_number: 2,
+                final_block_number: 0,
+                actions: &[],
+                user_votes: &[make_user_vote()],
+                votes_count: &[],
+                leaderboard_deltas: &[],
+                activity_deltas: &[],
+            },
+            Changeset {
+                block_number: 3,
+                final_block_number: 0,
+                actions: &[],
+                user_votes: &[],
+                votes_count: &[],
+                leaderboard_deltas: &[make_leaderboard_delta(1)],
+                activity_deltas: &[],
+            },
+        ],
+        "actions_indexer",
+        "cursor_3",
+        3,
+    ).await;
+
+    let votes = sqlx::query("SELECT * FROM user_votes").fetch_all(&pool).await.unwrap();
+    let cursor = cursor_repository.get_cursor("actions_indexer").await.unwrap();
+
+    assert!(result.is_err());
+    assert!(votes.is_empty());
+    assert_eq!(cursor, Some("cursor_1".to_string()));
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_rollback_to_restores_prior_block(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+    let votes_count = VotesCount { object_id: user_vote.object_id, ..make_votes_count() };
+    let delta = LeaderboardDelta { object_id: user_vote.object_id, ..make_leaderboard_delta(1) };
+    let action = |block_number| Action::Vote(Vote {
+        raw: ActionRaw { object_id: user_vote.object_id, block_number, ..make_raw_action() },
+        vote: VoteValue::Up,
+    });
+
+    repository.persist_changeset(&Changeset {
+        block_number: 1,
+        final_block_number: 0,
+        actions: &[action(1)],
+        user_votes: std::slice::from_ref(&user_vote),
+        votes_count: std::slice::from_ref(&votes_count),
+        leaderboard_deltas: std::slice::from_ref(&delta),
+        activity_deltas: &[],
+    }).await.unwrap();
+
+    // Block 2 flips the vote, block 3 removes it
+    for (block_number, vote_type, count, change) in [(2, VoteValue::Down, (0, 1), (-1, 1)), (3, VoteValue::Remove, (0, 0), (0, -1))] {
+        repository.persist_changeset(&Changeset {
+            block_number,
+            final_block_number: 0,
+            actions: &[action(block_number)],
+            user_votes: &[UserVote { vote_type, weight: 2, ..user_vote.clone() }],
+            votes_count: &[VotesCount { upvotes: count.0, downvotes: count.1, ..votes_count.clone() }],
+            leaderboard_deltas: &[LeaderboardDelta { upvotes: change.0, downvotes: change.1, ..delta.clone() }],
+            activity_deltas: &[],
+        }).await.unwrap();
+    }
+
+    repository.rollback_to(1).await.unwrap();
+
+    let votes = repository
+        .get_user_votes(&[(user_vote.user_id, user_vote.object_id, user_vote.space_id, user_vote.object_type)])
+        .await
+        .unwrap();
+    let counts = repository
+        .get_vote_counts(&[(votes_count.object_id, votes_count.space_id, votes_count.object_type)])
+        .await
+        .unwrap();
+    let entries = repository
+        .get_leaderboard(&delta.space_id, LeaderboardWindow::Day, 1755182913, 10)
+        .await
+        .unwrap();
+    let actions = sqlx::query("SELECT * FROM raw_actions").fetch_all(&pool).await.unwrap();
+    let journal = sqlx::query("SELECT * FROM changeset_user_votes WHERE block_number > 1")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(votes, vec![user_vote]);
+    assert_eq!(counts.len(), 1);
+    assert_eq!((counts[0].upvotes, counts[0].downvotes), (votes_count.upvotes, votes_count.downvotes));
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].upvotes, entries[0].downvotes), (1, 0));
+    assert_eq!(actions.len(), 1);
+    assert!(journal.is_empty());
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_persist_changeset_prunes_journal_of_final_blocks(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let user_vote = make_user_vote();
+
+    for (block_number, final_block_number) in [(1, 0), (2, 0), (3, 2)] {
+        repository.persist_changeset(&Changeset {
+            block_number,
+            final_block_number,
+            actions: &[],
+            user_votes: std::slice::from_ref(&user_vote),
+            votes_count: &[],
+            leaderboard_deltas: &[],
+            activity_deltas: &[],
+        }).await.unwrap();
+    }
+
+    let journal = sqlx::query("SELECT block_number FROM changeset_user_votes").fetch_all(&pool).await.unwrap();
+
+    assert_eq!(journal.iter().map(|row| row.get::<i64, _>("block_number")).collect::<Vec<_>>(), vec![3]);
+}
+
+#[sqlx::test(migrations = "src/sqlite/migrations")]
+async fn test_rollback_to_deletes_rows_created_later(pool: sqlx::SqlitePool) {
+    let repository = SqliteActionsRepository::new(pool.clone()).await.unwrap();
+    let membership = Action::Membership(Membership {
+        raw: ActionRaw { action_type: ActionType::Membership, block_number: 5, ..make_raw_action() },
+        change: MembershipChange::Join,
+    });
+
+    repository.persist_changeset(&Changeset {
+        block_number: 5,
+        final_block_number: 0,
+        actions: std::slice::from_ref(&membership),
+        user_votes: &[make_user_vote()],
+        votes_count: &[make_votes_count()],
+        leaderboard_deltas: &[],
+        activity_deltas: &[],
+    }).await.unwrap();
+    repository.insert_dead_letters(&[make_dead_letter(5, &[5])]).await.unwrap();
+
+    repository.rollback_to(4).await.unwrap();
+
+    for table in ["user_votes", "votes_count", "raw_actions", "membership_changes", "action_dead_letters"] {
+        let rows = sqlx::query(&format!("SELECT * FROM {table}")).fetch_all(&pool).await.unwrap();
+        assert!(rows.is_empty(), "{table} wasn't rolled back");
+    }
+}
@@ -1,3 +1,7 @@
+use super::action_edit::EditPublished;
+use super::action_flag::Flag;
+use super::action_membership::Membership;
+use super::action_raw::ActionRaw;
 use super::action_vote::Vote;
 
 /// Represents a processed action with its associated data.
@@ -8,4 +12,22 @@ use super::action_vote::Vote;
 pub enum Action {
     /// Represents a vote action, containing details about the vote.
     Vote(Vote),
-}
\ No newline at end of file
+    /// Represents an object flagged for moderation.
+    Flag(Flag),
+    /// Represents an edit published to a space.
+    EditPublished(EditPublished),
+    /// Represents a user joining or leaving a space.
+    Membership(Membership),
+}
+
+impl Action {
+    /// Returns the raw data the action was processed from.
+    pub fn raw(&self) -> &ActionRaw {
+        match self {
+            Action::Vote(vote) => &vote.raw,
+            Action::Flag(flag) => &flag.raw,
+            Action::EditPublished(edit) => &edit.raw,
+            Action::Membership(membership) => &membership.raw,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use crate::types::ActionRaw;
+use serde::{Deserialize, Serialize};
+
+/// Represents a processed edit published action.
+///
+/// The object of the action is the published edit, and its content URI
+/// (e.g. `ipfs://...`) is the UTF-8 text carried in the action metadata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+pub struct EditPublished {
+    pub raw: ActionRaw,
+    pub content_uri: String,
+}
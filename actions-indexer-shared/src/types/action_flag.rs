@@ -0,0 +1,12 @@
+use crate::types::ActionRaw;
+use serde::{Deserialize, Serialize};
+
+/// Represents a processed flag action.
+///
+/// A flag reports an object to the moderators of a space. The reason is the
+/// optional UTF-8 text carried in the action metadata.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+pub struct Flag {
+    pub raw: ActionRaw,
+    pub reason: Option<String>,
+}
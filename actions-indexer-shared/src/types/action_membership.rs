@@ -0,0 +1,21 @@
+use crate::types::ActionRaw;
+use serde::{Deserialize, Serialize};
+
+/// Represents the membership change requested by a user.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MembershipChange {
+    /// Indicates the sender joined the space.
+    Join,
+    /// Indicates the sender left the space.
+    Leave,
+}
+
+/// Represents a processed membership action.
+///
+/// This struct combines the raw action data with the membership change of the
+/// sender in the space of the action.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Eq)]
+pub struct Membership {
+    pub raw: ActionRaw,
+    pub change: MembershipChange,
+}
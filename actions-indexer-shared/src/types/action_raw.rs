@@ -19,4 +19,11 @@ pub struct ActionRaw {
     pub block_timestamp: BlockTimestamp,
     pub tx_hash: TxHash,
     pub object_type: ObjectType,
+    /// Position of the log in its block. With `tx_hash`, it identifies the action across
+    /// replays. `None` for substream packages that don't emit it.
+    pub log_index: Option<u32>,
+    /// Position of the action in its block's output, only set when `log_index` isn't.
+    /// With `tx_hash`, it identifies the action across replays of the same package and
+    /// tracked contracts, which emit the same actions in the same order.
+    pub output_position: Option<u32>,
 }
\ No newline at end of file
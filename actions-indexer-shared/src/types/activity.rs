@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use crate::types::{ActionType, SpaceId, UserAddress};
+
+/// Length of the buckets actions are counted in, in seconds.
+pub const ACTIVITY_BUCKET_SECONDS: u64 = 3_600;
+
+/// Returns the unix timestamp at which the activity bucket containing `timestamp` starts.
+///
+/// Buckets are hours aligned in UTC, so "the last 24 hours" is read as the 24 buckets
+/// ending with the current one.
+pub fn activity_bucket_start(timestamp: u64) -> u64 {
+    timestamp - timestamp % ACTIVITY_BUCKET_SECONDS
+}
+
+/// Represents an incremental change to the action counter of a sender in a space.
+///
+/// Deltas are computed by the pipeline from the actions of each changeset and added
+/// to the stored counter of the bucket containing their block timestamp.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityDelta {
+    pub bucket_start: u64,
+    pub space_id: SpaceId,
+    pub sender: UserAddress,
+    pub action_type: ActionType,
+    pub actions: i64,
+}
+
+/// Represents the number of actions taken in a space over a period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpaceActivity {
+    pub space_id: SpaceId,
+    pub actions: i64,
+}
+
+/// Represents the number of actions taken by a sender over a period.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SenderActivity {
+    pub sender: UserAddress,
+    pub actions: i64,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents the row counts of the actions tables of a schema.
+///
+/// Backfills compare the counts of their staging schema with the production ones
+/// before swapping them. `raw_actions` and `dead_letters` are counted over a block
+/// range, while the vote tables hold the state at the last indexed block and are
+/// counted in full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionsTableCounts {
+    pub raw_actions: i64,
+    pub dead_letters: i64,
+    pub user_votes: i64,
+    pub votes_count: i64,
+    pub upvotes: i64,
+    pub downvotes: i64,
+}
@@ -1,11 +1,19 @@
-use crate::types::{Action, UserVote, VotesCount};
+use crate::types::{Action, ActivityDelta, LeaderboardDelta, UserVote, VotesCount};
+use alloy::primitives::BlockNumber;
 
 /// Represents a collection of changes to be persisted in the actions repository.
 ///
-/// A `Changeset` bundles new actions, updated user votes, and updated vote counts
-/// together for atomic persistence operations.
+/// A `Changeset` bundles new actions, updated user votes, updated vote counts,
+/// leaderboard deltas and activity deltas together for atomic persistence operations.
+/// Each changeset holds the changes of a single block, so it can be rolled back on a
+/// reorg. `final_block_number` is the last irreversible block when the block was read:
+/// the blocks up to it can't be undone anymore, so their journal is pruned.
 pub struct Changeset<'a> {
+	pub block_number: BlockNumber,
+	pub final_block_number: BlockNumber,
 	pub actions: &'a [Action],
 	pub user_votes: &'a [UserVote],
-	pub votes_count: &'a [VotesCount]
+	pub votes_count: &'a [VotesCount],
+	pub leaderboard_deltas: &'a [LeaderboardDelta],
+	pub activity_deltas: &'a [ActivityDelta]
 }
\ No newline at end of file
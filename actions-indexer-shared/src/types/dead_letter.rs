@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents an action event that couldn't be decoded or processed.
+///
+/// `raw` holds a protobuf-encoded `Actions` message with the failed event, or the
+/// whole output of the block when it couldn't be decoded at all, so the event can
+/// be decoded again once the decoder or its handler is fixed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub block_number: u64,
+    pub raw: Vec<u8>,
+    pub error: String,
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use crate::types::{ObjectId, SpaceId, ObjectType};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Time window over which a vote leaderboard is aggregated.
+///
+/// Windows are calendar aligned in UTC: days start at midnight, weeks start on
+/// Monday and months start on the first day of the month. A vote belongs to the
+/// window that contains its `voted_at` timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LeaderboardWindow {
+    Day,
+    Week,
+    Month,
+    AllTime,
+}
+
+impl LeaderboardWindow {
+    /// All windows maintained by the loader.
+    pub const ALL: [LeaderboardWindow; 4] = [
+        LeaderboardWindow::Day,
+        LeaderboardWindow::Week,
+        LeaderboardWindow::Month,
+        LeaderboardWindow::AllTime,
+    ];
+
+    /// Returns the unix timestamp at which the window containing `timestamp` starts.
+    ///
+    /// The all-time window always starts at the unix epoch.
+    pub fn window_start(&self, timestamp: u64) -> u64 {
+        match self {
+            LeaderboardWindow::Day => timestamp - timestamp % SECONDS_PER_DAY,
+            LeaderboardWindow::Week => {
+                // The unix epoch is a Thursday, so shift by three days to align weeks on Monday
+                let days = timestamp / SECONDS_PER_DAY;
+                (days - (days + 3) % 7) * SECONDS_PER_DAY
+            }
+            LeaderboardWindow::Month => {
+                let date = DateTime::<Utc>::from_timestamp(timestamp as i64, 0)
+                    .unwrap_or_default()
+                    .date_naive();
+                NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+                    .and_then(|first| first.and_hms_opt(0, 0, 0))
+                    .map(|start| start.and_utc().timestamp() as u64)
+                    .unwrap_or(0)
+            }
+            LeaderboardWindow::AllTime => 0,
+        }
+    }
+}
+
+/// Represents an incremental change to a leaderboard entry.
+///
+/// Deltas are computed by the pipeline from user vote changes and added to the
+/// stored totals of the matching window, so leaderboards never have to be
+/// recomputed from `user_votes`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardDelta {
+    pub object_id: ObjectId,
+    pub space_id: SpaceId,
+    pub object_type: ObjectType,
+    pub window: LeaderboardWindow,
+    pub window_start: u64,
+    pub upvotes: i64,
+    pub downvotes: i64,
+}
+
+/// Represents a ranked object in a space's leaderboard for a time window.
+///
+/// `upvotes` and `downvotes` count the current votes that were cast within the window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub object_id: ObjectId,
+    pub space_id: SpaceId,
+    pub object_type: ObjectType,
+    pub window: LeaderboardWindow,
+    pub window_start: u64,
+    pub upvotes: i64,
+    pub downvotes: i64,
+}
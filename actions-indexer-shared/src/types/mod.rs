@@ -1,6 +1,6 @@
 //! This module defines the core data structures and types used across the actions indexer.
-//! It re-exports specific types like `Action`, `UserVote`, `VotesCount`, `Changeset`, `ActionRaw`, `Vote`, and `VoteValue`.
-use alloy::primitives::Address;
+//! It re-exports specific types like `Action`, `UserVote`, `VotesCount`, `Changeset`, `ActionRaw`, `Vote`, `VoteValue`, the other action payloads, the leaderboard types, the vote count reconciliation types, the activity counter types, the `ActionsTableCounts` compared by backfills and the `DeadLetter` of events that couldn't be processed.
+use alloy::primitives::{Address, TxHash};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -10,13 +10,27 @@ mod votes_count;
 mod changeset;
 mod action_raw;
 mod action_vote;
+mod action_flag;
+mod action_edit;
+mod action_membership;
+mod leaderboard;
+mod dead_letter;
+mod activity;
+mod backfill;
 
 pub use action::Action;
 pub use user_vote::UserVote;
-pub use votes_count::VotesCount;
+pub use votes_count::{VotesCount, VotesCountDrift, VotesCountReconciliation};
 pub use changeset::Changeset;
 pub use action_raw::ActionRaw;
 pub use action_vote::{Vote, VoteValue};
+pub use action_flag::Flag;
+pub use action_edit::EditPublished;
+pub use action_membership::{Membership, MembershipChange};
+pub use leaderboard::{LeaderboardWindow, LeaderboardDelta, LeaderboardEntry};
+pub use dead_letter::DeadLetter;
+pub use activity::{ACTIVITY_BUCKET_SECONDS, activity_bucket_start, ActivityDelta, SpaceActivity, SenderActivity};
+pub use backfill::ActionsTableCounts;
 
 pub type ObjectId = Uuid;
 pub type GroupId = Uuid;
@@ -25,6 +39,8 @@ pub type UserAddress = Address;
 pub type VoteCriteria = (UserAddress, ObjectId, SpaceId, ObjectType);
 pub type VoteCountCriteria = (ObjectId, SpaceId, ObjectType);
 pub type ActionVersion = u64;
+pub type ActionKey = (TxHash, ActionIndex);
+pub type DeadLetterId = i64;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, Copy)]
 pub enum ObjectType {
@@ -32,7 +48,21 @@ pub enum ObjectType {
     Relation,
 }
 
+/// Where an action sits in its block. With the transaction hash, it identifies the
+/// action across replays, see `ActionKey`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, Copy)]
+pub enum ActionIndex {
+    /// Position of the action's log in the block.
+    Log(u32),
+    /// Position of the action in the block's output, for substream packages that don't
+    /// emit log indexes.
+    OutputPosition(u32),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, Copy)]
 pub enum ActionType {
-    Vote,
+    Vote = 0,
+    Flag = 1,
+    EditPublished = 2,
+    Membership = 3,
 }
\ No newline at end of file
@@ -4,7 +4,7 @@ use crate::types::{ObjectId, SpaceId, UserAddress, VoteValue, ObjectType};
 /// Represents a user's vote on an entity and space.
 ///
 /// This struct is intended to store information about a user's vote
-/// on a specific entity and space.
+/// on a specific entity and space, along with the weight it was counted with.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct UserVote {
     pub user_id: UserAddress,
@@ -13,4 +13,5 @@ pub struct UserVote {
     pub object_type: ObjectType,
     pub vote_type: VoteValue,
     pub voted_at: u64,
+    pub weight: u64,
 }
\ No newline at end of file
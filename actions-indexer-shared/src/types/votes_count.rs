@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use crate::types::{ObjectId, SpaceId, ObjectType};
+use crate::types::{ObjectId, SpaceId, ObjectType, VoteCountCriteria};
 
 /// Represents the aggregated vote counts for an entity and space.
 ///
@@ -13,3 +13,32 @@ pub struct VotesCount {
     pub upvotes: i64,
     pub downvotes: i64,
 }
+
+impl VotesCount {
+    /// Returns the net score of the object, its upvotes minus its downvotes.
+    pub fn score(&self) -> i64 {
+        self.upvotes - self.downvotes
+    }
+}
+
+/// Represents a vote count that didn't match the user votes it aggregates.
+///
+/// `expected` holds the counts recomputed from the weighted user votes, which
+/// replaced the stored ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VotesCountDrift {
+    pub expected: VotesCount,
+    pub stored_upvotes: i64,
+    pub stored_downvotes: i64,
+}
+
+/// Represents the outcome of reconciling a page of vote counts with the user votes.
+///
+/// Pages are ordered by `VoteCountCriteria`, so `last_checked` is where the next
+/// page starts. It is `None` when there was nothing left to check.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VotesCountReconciliation {
+    pub checked: usize,
+    pub last_checked: Option<VoteCountCriteria>,
+    pub repaired: Vec<VotesCountDrift>,
+}
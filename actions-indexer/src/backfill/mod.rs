@@ -0,0 +1,401 @@
+//! Backfills of the Actions Indexer.
+//!
+//! A backfill reprocesses a block range with the processor and loader of the indexer
+//! into the actions tables of a staging schema, leaving the production tables untouched
+//! while it runs. The staging tables are then compared with the production ones and,
+//! once they check out, swapped with them in a single transaction. The replaced tables
+//! are kept in an archive schema, so a backfill can be rolled back by swapping them in
+//! again.
+use std::sync::Arc;
+
+use actions_indexer_repository::{cursor_id, BackfillRepository};
+use actions_indexer_shared::types::ActionsTableCounts;
+
+use crate::config::{is_identifier, IndexerConfig, StartModeConfig};
+use crate::errors::IndexingError;
+
+/// Schema the tables of a backfill are staged in when none is configured.
+pub const DEFAULT_STAGING_SCHEMA: &str = "backfill";
+/// Schema of the production tables when the indexer doesn't configure one.
+const DEFAULT_PRODUCTION_SCHEMA: &str = "public";
+
+/// Comparison of the staging tables of a backfill with the production ones.
+///
+/// Actions and dead letters are compared over the blocks both have indexed, from the
+/// start of the backfill to its last block or the production cursor, whichever comes
+/// first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillReport {
+    pub first_block: u64,
+    pub last_block: u64,
+    pub cursor_block: Option<i64>,
+    pub staging: ActionsTableCounts,
+    pub production: ActionsTableCounts,
+}
+
+impl BackfillReport {
+    /// Whether the staging tables hold actions, as many as the production ones over
+    /// the compared blocks.
+    ///
+    /// Reprocessing the same blocks reads the same actions, so only their count has
+    /// to match. Dead letters and vote counts are expected to change when a backfill
+    /// follows a fix of the decoder or a handler.
+    pub fn is_valid(&self) -> bool {
+        self.staging.raw_actions > 0 && self.staging.raw_actions == self.production.raw_actions
+    }
+
+    /// Logs the counts of both schemas.
+    pub fn log(&self) {
+        println!("Compared blocks {} to {}, production cursor at {:?}", self.first_block, self.last_block, self.cursor_block);
+        let rows = [
+            ("actions", self.staging.raw_actions, self.production.raw_actions),
+            ("dead letters", self.staging.dead_letters, self.production.dead_letters),
+            ("user votes", self.staging.user_votes, self.production.user_votes),
+            ("vote counts", self.staging.votes_count, self.production.votes_count),
+            ("upvotes", self.staging.upvotes, self.production.upvotes),
+            ("downvotes", self.staging.downvotes, self.production.downvotes),
+        ];
+        for (name, staging, production) in rows {
+            println!("  {}: {} staged, {} in production ({:+})", name, staging, production, staging - production);
+        }
+        match self.is_valid() {
+            true => println!("The staged actions match the production ones"),
+            false => println!("The staged actions don't match the production ones"),
+        }
+    }
+}
+
+/// `Backfill` stages, validates and swaps the reprocessed tables of a block range.
+///
+/// The block range is the `start_block` and `stop_block` of the indexer's
+/// configuration, the stop block being excluded like in the `replay` start mode.
+pub struct Backfill {
+    repository: Arc<dyn BackfillRepository>,
+    staging_schema: String,
+    production_schema: String,
+    start_block: u64,
+    stop_block: u64,
+    cursor_id: String,
+}
+
+impl Backfill {
+    /// Creates a new `Backfill` of the block range of `config`.
+    ///
+    /// # Arguments
+    ///
+    /// * `repository` - The repository staging and swapping the tables
+    /// * `config` - The configuration of the indexer whose tables are backfilled
+    /// * `staging_schema` - The schema the reprocessed tables are staged in
+    ///
+    /// # Returns
+    ///
+    /// A new `Backfill`, or an `IndexingError::Config` if the block range isn't set,
+    /// or the staging schema isn't a lowercase identifier or is the production one.
+    pub fn new(repository: Arc<dyn BackfillRepository>, config: &IndexerConfig, staging_schema: &str) -> Result<Self, IndexingError> {
+        let (Some(start_block), Some(stop_block)) = (config.start_block, config.stop_block) else {
+            return Err(IndexingError::Config("a backfill requires a start and stop block".to_string()));
+        };
+        let production_schema = config.database_schema.as_deref().unwrap_or(DEFAULT_PRODUCTION_SCHEMA);
+        if !is_identifier(staging_schema) || staging_schema == production_schema {
+            return Err(IndexingError::Config(format!(
+                "staging schema '{}' must be a lowercase identifier other than the production schema",
+                staging_schema
+            )));
+        }
+
+        Ok(Self {
+            repository,
+            staging_schema: staging_schema.to_string(),
+            production_schema: production_schema.to_string(),
+            start_block: start_block.max(0) as u64,
+            stop_block,
+            cursor_id: cursor_id(config.namespace.as_deref()),
+        })
+    }
+
+    /// The schema the replaced production tables are kept in after a swap.
+    pub fn archive_schema(&self) -> String {
+        format!("{}_previous", self.staging_schema)
+    }
+
+    /// The configuration replaying the block range into the staging schema.
+    ///
+    /// The replay leaves the stored cursor untouched and doesn't publish vote count
+    /// changes, serve metrics or reconcile vote counts.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration of the indexer whose tables are backfilled
+    ///
+    /// # Returns
+    ///
+    /// The `IndexerConfig` the backfill pipeline runs with.
+    pub fn staging_config(&self, config: &IndexerConfig) -> IndexerConfig {
+        IndexerConfig {
+            start_mode: StartModeConfig::Replay,
+            database_schema: Some(self.staging_schema.clone()),
+            votes_count_topic: None,
+            metrics_addr: None,
+            reconcile_interval_secs: 0,
+            ..config.clone()
+        }
+    }
+
+    /// Creates the staging schema with empty actions tables.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or an `IndexingError` if the schema already
+    /// exists, e.g. left over by a previous backfill, or couldn't be migrated.
+    pub async fn prepare(&self) -> Result<(), IndexingError> {
+        self.repository.create_schema(&self.staging_schema).await?;
+        println!("Created the staging schema {}", self.staging_schema);
+        Ok(())
+    }
+
+    /// Compares the staging tables with the production ones.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the `BackfillReport` of the comparison, or an `IndexingError`
+    /// if either schema can't be read.
+    pub async fn validate(&self) -> Result<BackfillReport, IndexingError> {
+        let cursor_block = self.repository.get_cursor_block(&self.production_schema, &self.cursor_id).await?;
+        let last_staged_block = self.stop_block.saturating_sub(1);
+        let last_block = match cursor_block {
+            Some(cursor_block) => last_staged_block.min(cursor_block.max(0) as u64),
+            None => last_staged_block,
+        };
+
+        Ok(BackfillReport {
+            first_block: self.start_block,
+            last_block,
+            cursor_block,
+            staging: self.repository.count_rows(&self.staging_schema, self.start_block, last_block).await?,
+            production: self.repository.count_rows(&self.production_schema, self.start_block, last_block).await?,
+        })
+    }
+
+    /// Validates the staging tables, then swaps them with the production ones.
+    ///
+    /// The staging tables replace the production ones in full, so the backfill must
+    /// cover every block production holds actions of, from its first action to the
+    /// production cursor. The indexer must be stopped during the swap, with its cursor
+    /// at the last backfilled block, which the caller ensures by holding the writers
+    /// lock of the production schema exclusively until the swap returns. Once restarted, it resumes from the block after
+    /// it on top of the backfilled tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - Whether to swap tables whose actions don't match the production ones
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the `BackfillReport` the swap was made on, or an
+    /// `IndexingError::Config` if the tables don't match and `force` isn't set, if
+    /// production holds actions before the start block, which would be lost, or if
+    /// the production cursor isn't at the last backfilled block. Even forced, the
+    /// swap is refused in the latter two cases.
+    pub async fn swap(&self, force: bool) -> Result<BackfillReport, IndexingError> {
+        let report = self.validate().await?;
+        if !report.is_valid() && !force {
+            return Err(IndexingError::Config(format!(
+                "{} staged actions don't match the {} production ones over blocks {} to {}, force the swap to swap them anyway",
+                report.staging.raw_actions, report.production.raw_actions, report.first_block, report.last_block
+            )));
+        }
+        if let Some(first_block) = self.repository.get_first_block(&self.production_schema).await?.filter(|first_block| (*first_block as u64) < self.start_block) {
+            return Err(IndexingError::Config(format!(
+                "production holds actions from block {}, before the start block {}, backfill from the first action first",
+                first_block, self.start_block
+            )));
+        }
+        let last_backfilled_block = self.stop_block.saturating_sub(1);
+        if report.cursor_block != Some(last_backfilled_block as i64) {
+            return Err(IndexingError::Config(format!(
+                "the production cursor is at block {:?}, not at the last backfilled block {}, stop the indexer and backfill up to its cursor first",
+                report.cursor_block, last_backfilled_block
+            )));
+        }
+
+        let archive_schema = self.archive_schema();
+        self.repository.swap_schemas(&self.staging_schema, &self.production_schema, &archive_schema).await?;
+        println!(
+            "Swapped the tables of {} into {}, the previous ones are kept in {}",
+            self.staging_schema, self.production_schema, archive_schema
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions_indexer_repository::BackfillRepositoryError;
+    use std::sync::Mutex;
+
+    /// Returns fixed counts per schema and records the swaps.
+    struct MockBackfillRepository {
+        staging: ActionsTableCounts,
+        production: ActionsTableCounts,
+        cursor_block: Option<i64>,
+        first_block: Option<i64>,
+        counted: Mutex<Vec<(String, u64, u64)>>,
+        swaps: Mutex<Vec<(String, String, String)>>,
+    }
+
+    impl MockBackfillRepository {
+        fn new(staged_actions: i64, production_actions: i64, cursor_block: Option<i64>) -> Self {
+            Self {
+                staging: ActionsTableCounts { raw_actions: staged_actions, ..Default::default() },
+                production: ActionsTableCounts { raw_actions: production_actions, ..Default::default() },
+                cursor_block,
+                first_block: Some(100),
+                counted: Mutex::new(Vec::new()),
+                swaps: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl BackfillRepository for MockBackfillRepository {
+        async fn create_schema(&self, _schema: &str) -> Result<(), BackfillRepositoryError> {
+            Ok(())
+        }
+
+        async fn count_rows(&self, schema: &str, start_block: u64, stop_block: u64) -> Result<ActionsTableCounts, BackfillRepositoryError> {
+            self.counted.lock().unwrap().push((schema.to_string(), start_block, stop_block));
+            Ok(if schema == "public" { self.production } else { self.staging })
+        }
+
+        async fn get_cursor_block(&self, _schema: &str, _id: &str) -> Result<Option<i64>, BackfillRepositoryError> {
+            Ok(self.cursor_block)
+        }
+
+        async fn get_first_block(&self, _schema: &str) -> Result<Option<i64>, BackfillRepositoryError> {
+            Ok(self.first_block)
+        }
+
+        async fn swap_schemas(&self, staging: &str, production: &str, archive: &str) -> Result<(), BackfillRepositoryError> {
+            self.swaps.lock().unwrap().push((staging.to_string(), production.to_string(), archive.to_string()));
+            Ok(())
+        }
+    }
+
+    fn config() -> IndexerConfig {
+        IndexerConfig {
+            start_block: Some(100),
+            stop_block: Some(200),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_backfills() {
+        let repository = Arc::new(MockBackfillRepository::new(0, 0, None));
+        let open_range = IndexerConfig {
+            stop_block: None,
+            ..config()
+        };
+
+        assert!(matches!(Backfill::new(repository.clone(), &open_range, "backfill"), Err(IndexingError::Config(_))));
+        assert!(matches!(Backfill::new(repository.clone(), &config(), "public"), Err(IndexingError::Config(_))));
+        assert!(matches!(Backfill::new(repository.clone(), &config(), "back fill"), Err(IndexingError::Config(_))));
+        assert!(Backfill::new(repository, &config(), DEFAULT_STAGING_SCHEMA).is_ok());
+    }
+
+    #[test]
+    fn test_staging_config() {
+        let config = IndexerConfig {
+            votes_count_topic: Some("actions.votes_count".to_string()),
+            metrics_addr: Some("127.0.0.1:9090".parse().unwrap()),
+            ..config()
+        };
+        let backfill = Backfill::new(Arc::new(MockBackfillRepository::new(0, 0, None)), &config, "backfill").unwrap();
+
+        let staging_config = backfill.staging_config(&config);
+
+        assert_eq!(staging_config.start_mode, StartModeConfig::Replay);
+        assert_eq!(staging_config.database_schema.as_deref(), Some("backfill"));
+        assert_eq!(staging_config.votes_count_topic, None);
+        assert_eq!(staging_config.metrics_addr, None);
+        assert_eq!(staging_config.block_range(), config.block_range());
+        assert!(staging_config.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_compares_blocks_indexed_by_both() {
+        let repository = Arc::new(MockBackfillRepository::new(10, 10, Some(150)));
+        let backfill = Backfill::new(repository.clone(), &config(), "backfill").unwrap();
+
+        let report = backfill.validate().await.unwrap();
+
+        assert!(report.is_valid());
+        assert_eq!((report.first_block, report.last_block), (100, 150));
+        assert_eq!(*repository.counted.lock().unwrap(), vec![
+            ("backfill".to_string(), 100, 150),
+            ("public".to_string(), 100, 150),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_swap() {
+        let repository = Arc::new(MockBackfillRepository::new(10, 10, Some(199)));
+        let backfill = Backfill::new(repository.clone(), &config(), "backfill").unwrap();
+
+        backfill.swap(false).await.unwrap();
+
+        assert_eq!(*repository.swaps.lock().unwrap(), vec![(
+            "backfill".to_string(),
+            "public".to_string(),
+            "backfill_previous".to_string(),
+        )]);
+    }
+
+    #[tokio::test]
+    async fn test_swap_requires_matching_actions_unless_forced() {
+        let repository = Arc::new(MockBackfillRepository::new(9, 10, Some(199)));
+        let backfill = Backfill::new(repository.clone(), &config(), "backfill").unwrap();
+
+        assert!(matches!(backfill.swap(false).await, Err(IndexingError::Config(_))));
+        assert!(repository.swaps.lock().unwrap().is_empty());
+
+        backfill.swap(true).await.unwrap();
+        assert_eq!(repository.swaps.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_swap_refuses_cursor_past_backfill() {
+        let repository = Arc::new(MockBackfillRepository::new(10, 10, Some(200)));
+        let backfill = Backfill::new(repository.clone(), &config(), "backfill").unwrap();
+
+        // Even forced, blocks 200 and later would be lost
+        assert!(matches!(backfill.swap(true).await, Err(IndexingError::Config(_))));
+        assert!(repository.swaps.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_swap_refuses_cursor_behind_backfill() {
+        for cursor_block in [Some(150), None] {
+            let repository = Arc::new(MockBackfillRepository::new(10, 10, cursor_block));
+            let backfill = Backfill::new(repository.clone(), &config(), "backfill").unwrap();
+
+            // The indexer would replay blocks the staging tables already hold
+            assert!(matches!(backfill.swap(true).await, Err(IndexingError::Config(_))));
+            assert!(repository.swaps.lock().unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_refuses_actions_before_backfill() {
+        let repository = Arc::new(MockBackfillRepository {
+            first_block: Some(99),
+            ..MockBackfillRepository::new(10, 10, Some(199))
+        });
+        let backfill = Backfill::new(repository.clone(), &config(), "backfill").unwrap();
+
+        // Even forced, the actions of block 99 would be lost
+        assert!(matches!(backfill.swap(true).await, Err(IndexingError::Config(_))));
+        assert!(repository.swaps.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use actions_indexer::backfill::DEFAULT_STAGING_SCHEMA;
+use actions_indexer::{Backfill, Dependencies, IndexerConfig, IndexingError};
+use actions_indexer_pipeline::orchestrator::Orchestrator;
+use actions_indexer_repository::postgres::{self, try_lock_writers_exclusive};
+use actions_indexer_repository::PostgresBackfillRepository;
+use dotenv::dotenv;
+
+/// Environment variable for the schema the backfilled tables are staged in.
+const BACKFILL_SCHEMA_ENV: &str = "BACKFILL_SCHEMA";
+const USAGE: &str = "Usage: actions-backfill <run|validate|swap> [--force]";
+
+/// Backfills a block range of the Actions Indexer.
+///
+/// Reads the indexer's configuration, where `START_BLOCK` and `STOP_BLOCK` set the
+/// block range, and `BACKFILL_SCHEMA` the schema it is staged in, `backfill` by
+/// default. The step to take is the first argument:
+///
+/// - `run` - Creates the staging schema, reprocesses the block range into it, then
+///   compares it with the production tables
+/// - `validate` - Compares the staging tables with the production ones, failing if
+///   their actions don't match
+/// - `swap` - Validates the staging tables, then swaps them with the production ones
+///   in a single transaction. `--force` swaps tables whose actions don't match
+///
+/// `run` and `validate` can run while the indexer is running. `swap` holds the writers
+/// lock of the production schema exclusively while it validates and swaps the tables,
+/// so it refuses to run next to an indexer.
+///
+/// # Returns
+///
+/// A `Result` indicating success or an `IndexingError` if the configuration is
+/// invalid, the database can't be reached, an indexer is running during a swap, a
+/// step fails or the staged tables don't validate.
+#[tokio::main]
+async fn main() -> Result<(), IndexingError> {
+    dotenv().ok();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let force = args.iter().any(|arg| arg == "--force");
+    let Some(step) = args.iter().find(|arg| !arg.starts_with("--")) else {
+        return Err(IndexingError::Config(USAGE.to_string()));
+    };
+
+    let config = IndexerConfig::from_env()?;
+    let staging_schema = std::env::var(BACKFILL_SCHEMA_ENV).unwrap_or_else(|_| DEFAULT_STAGING_SCHEMA.to_string());
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = postgres::connect(&database_url, config.database_schema.as_deref()).await?;
+    let backfill = Backfill::new(Arc::new(PostgresBackfillRepository::new(pool.clone()).await?), &config, &staging_schema)?;
+
+    match step.as_str() {
+        "run" => {
+            backfill.prepare().await?;
+            let dependencies = Dependencies::with_config(backfill.staging_config(&config)).await?;
+            let mut orchestrator = Orchestrator::new(
+                dependencies.consumer,
+                dependencies.processor,
+                dependencies.loader,
+            )
+            .with_batch_config(dependencies.batch_config)
+            .with_metrics(dependencies.metrics)
+            .with_start_mode(dependencies.start_mode)
+            .with_retry_config(dependencies.retry_config);
+            if let Some(activity_aggregator) = dependencies.activity_aggregator {
+                orchestrator = orchestrator.with_activity_aggregator(activity_aggregator);
+            }
+            orchestrator.run().await?;
+            backfill.validate().await?.log();
+        }
+        "validate" => {
+            let report = backfill.validate().await?;
+            report.log();
+            if !report.is_valid() {
+                return Err(IndexingError::Config("the staged tables don't validate".to_string()));
+            }
+        }
+        "swap" => {
+            // Held until the swap commits, so an indexer can't move its cursor or write
+            // to the tables being archived between the checks and the swap
+            let Some(_writers_lock) = try_lock_writers_exclusive(&pool).await? else {
+                return Err(IndexingError::Config("an indexer is writing the actions tables, stop it before swapping them".to_string()));
+            };
+            backfill.swap(force).await?.log();
+        }
+        _ => return Err(IndexingError::Config(USAGE.to_string())),
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use actions_indexer::config::actions_processor;
+use actions_indexer::{DeadLetterReprocessor, IndexerConfig, IndexingError};
+use actions_indexer_pipeline::loader::ActionsLoader;
+use actions_indexer_repository::{PostgresActionsRepository, PostgresCursorRepository};
+use actions_indexer_repository::postgres::{self, try_lock_writers_exclusive};
+use dotenv::dotenv;
+
+/// How many dead letters are replayed together.
+const PAGE_SIZE: usize = 100;
+
+/// Reprocesses the pending dead letters of the Actions Indexer.
+///
+/// Meant to be run once the decoder or a handler has been fixed. Dead letters that
+/// now succeed are persisted and marked as reprocessed, the others are left pending.
+/// The stream cursor isn't touched, but vote counts are computed from the stored votes,
+/// so it holds the writers lock exclusively and refuses to run next to an indexer.
+/// The tables are those of the indexer's `DATABASE_SCHEMA`, if one is configured.
+///
+/// # Returns
+///
+/// A `Result` indicating success or an `IndexingError` if the database can't be
+/// reached, the configuration is invalid, an indexer is running or the dead letters
+/// can't be persisted.
+#[tokio::main]
+async fn main() -> Result<(), IndexingError> {
+    dotenv().ok();
+
+    let config = IndexerConfig::from_env()?;
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let pool = postgres::connect(&database_url, config.database_schema.as_deref()).await?;
+    let Some(_writers_lock) = try_lock_writers_exclusive(&pool).await? else {
+        return Err(IndexingError::Config("an indexer is writing the actions tables, stop it before reprocessing dead letters".to_string()));
+    };
+
+    let loader = ActionsLoader::new(
+        Arc::new(PostgresActionsRepository::new(pool.clone()).await?),
+        Arc::new(PostgresCursorRepository::new(pool).await?),
+    );
+    let reprocessor = DeadLetterReprocessor::new(loader, actions_processor(), PAGE_SIZE);
+
+    reprocessor.reprocess_all().await?.log();
+    Ok(())
+}
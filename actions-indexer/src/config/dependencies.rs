@@ -1,46 +1,101 @@
-use actions_indexer_pipeline::consumer::ActionsConsumer;
+use actions_indexer_pipeline::aggregation::ActivityAggregator;
+use actions_indexer_pipeline::consumer::{ActionsConsumer, StartMode};
+use actions_indexer_pipeline::consumer::filter::ActionFilter;
+use actions_indexer_pipeline::emitter::VotesCountEmitter;
 use actions_indexer_pipeline::loader::ActionsLoader;
+use actions_indexer_pipeline::metrics::PipelineMetrics;
+use actions_indexer_pipeline::orchestrator::BatchConfig;
 use actions_indexer_pipeline::processor::ActionsProcessor;
-use actions_indexer_pipeline::consumer::stream::sink::SubstreamsStreamProvider;
-use actions_indexer_repository::{PostgresActionsRepository, PostgresCursorRepository};
+use actions_indexer_pipeline::retry::RetryConfig;
+use actions_indexer_pipeline::consumer::stream::sink::{Param, SubstreamsStreamProvider};
+use actions_indexer_repository::{ActionsRepository, ActivityRepository, CursorRepository, PostgresActionsRepository, PostgresActivityRepository, PostgresCursorRepository};
 use actions_indexer_shared::types::{ActionType, ObjectType};
+use std::net::SocketAddr;
 use std::sync::Arc;
-use crate::config::handlers::VoteHandler;
+use crate::config::handlers::{EditPublishedHandler, FlagHandler, MembershipHandler, VoteHandler};
+use crate::config::IndexerConfig;
 use crate::errors::IndexingError;
+use crate::filter::{load_filter_rules, FilterReloader};
+use crate::reconciliation::VoteCountReconciler;
 
 // Use CARGO_MANIFEST_DIR to get path relative to the crate
-const PKG_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/geo-actions-v0.1.0.spkg");
+pub(crate) const PKG_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/geo-actions-v0.1.0.spkg");
 const MODULE_NAME: &str = "map_actions";
     
 /// `Dependencies` struct holds the necessary components for the action indexer.
 ///
 /// It includes a consumer for ingesting actions, a processor for handling
-/// business logic, and a loader for persisting processed data.
+/// business logic, a loader for persisting processed data, the batch
+/// limits the loader persists blocks with, the vote count reconciler
+/// unless reconciliation is disabled, the pipeline metrics along with
+/// the address to serve them on, if any, where the consumer starts
+/// streaming from, the activity aggregator unless activity counters
+/// are disabled, the reloader of the consumer's filter when it is
+/// read from a file that is checked for changes, the policies
+/// transient failures of the consumer and loader are retried with,
+/// the emitter publishing vote count changes when a topic is set,
+/// the namespace the cursor is stored under, if any, and the connection
+/// holding the writers lock of a PostgreSQL database while the indexer runs.
 pub struct Dependencies {
     pub consumer: Box<ActionsConsumer>,
     pub processor: Box<ActionsProcessor>,
     pub loader: Box<ActionsLoader>,
+    pub batch_config: BatchConfig,
+    pub reconciler: Option<VoteCountReconciler>,
+    pub metrics: Arc<PipelineMetrics>,
+    pub metrics_addr: Option<SocketAddr>,
+    pub start_mode: StartMode,
+    pub activity_aggregator: Option<ActivityAggregator>,
+    pub filter_reloader: Option<FilterReloader>,
+    pub retry_config: RetryConfig,
+    pub votes_count_emitter: Option<Box<dyn VotesCountEmitter>>,
+    pub namespace: Option<String>,
+    pub writers_lock: Option<sqlx::PgConnection>,
 }
 
 impl Dependencies {
     /// Creates a new `Dependencies` instance.
     ///
     /// This asynchronous function is responsible for initializing and wiring up
-    /// all the external services and components required by the indexer.
+    /// all the external services and components required by the indexer. The
+    /// block range and tracked contracts are read with `IndexerConfig::from_env`.
     ///
     /// # Returns
     ///
     /// A `Result` which is `Ok(Self)` on successful initialization or an
     /// `IndexingError` if any dependency fails to initialize.
     pub async fn new() -> Result<Self, IndexingError> {
+        let config = IndexerConfig::from_env()?;
+        Self::with_config(config).await
+    }
+
+    /// Creates a new `Dependencies` instance streaming the block range and
+    /// contracts of `config`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The validated indexer configuration
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok(Self)` on successful initialization or an
+    /// `IndexingError` if any dependency fails to initialize, or the package
+    /// can't track the configured contracts.
+    pub async fn with_config(config: IndexerConfig) -> Result<Self, IndexingError> {
         let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
         let substreams_endpoint = std::env::var("SUBSTREAMS_ENDPOINT").expect("SUBSTREAMS_ENDPOINT must be set");
         let substreams_api_token = std::env::var("SUBSTREAMS_API_TOKEN").expect("SUBSTREAMS_API_TOKEN must be set");
 
-        let package_file = PKG_FILE.to_string();
+        config.log();
+
+        let package_file = config.package_file.clone();
         let module_name = MODULE_NAME.to_string();
-        let block_range = None;
-        let params = vec![];
+        let block_range = config.block_range();
+        let params = vec![Param {
+            module_name: MODULE_NAME.to_string(),
+            expression: config.module_params(),
+            built_in: config.package_built_in_params(),
+        }];
 
         let substreams_stream_provider = SubstreamsStreamProvider::new(
             substreams_endpoint,
@@ -50,31 +105,164 @@ impl Dependencies {
             params,
             Some(substreams_api_token),
         );
+        // A package that can't track the configured contracts must fail here, rather
+        // than silently index the contracts it was built for
+        substreams_stream_provider
+            .check_package()
+            .await
+            .map_err(|e| IndexingError::Config(e.to_string()))?;
 
-        let actions_consumer = ActionsConsumer::new(Box::new(substreams_stream_provider));
-        let mut actions_processor = ActionsProcessor::new();
-        actions_processor.register_handler(1, ActionType::Vote, ObjectType::Entity, Arc::new(VoteHandler));
-        actions_processor.register_handler(1, ActionType::Vote, ObjectType::Relation, Arc::new(VoteHandler));
-
-        let pool = sqlx::PgPool::connect(&database_url).await.map_err(|e| IndexingError::Database(e.into()))?;
+        let metrics = Arc::new(PipelineMetrics::default());
+        let mut actions_consumer = ActionsConsumer::new(Box::new(substreams_stream_provider));
+        let mut filter_reloader = None;
+        if let Some(filter_file) = &config.filter_file {
+            let filter = Arc::new(ActionFilter::new(load_filter_rules(filter_file)?, metrics.clone()));
+            if let Some(interval) = config.filter_reload_interval() {
+                filter_reloader = Some(FilterReloader::new(filter.clone(), filter_file, interval));
+            }
+            actions_consumer = actions_consumer.with_filter(filter);
+        }
+        let actions_processor = actions_processor();
 
-        let actions_loader = ActionsLoader::new(
-            Arc::new(PostgresActionsRepository::new(pool.clone()).await.map_err(|e| IndexingError::ActionsRepository(e))?), 
-            Arc::new(PostgresCursorRepository::new(pool).await.map_err(|e| IndexingError::CursorRepository(e))?));
+        let (actions_repository, cursor_repository, activity_repository, writers_lock) = connect_repositories(&database_url, config.database_schema.as_deref()).await?;
+        let actions_loader = ActionsLoader::new(actions_repository.clone(), cursor_repository);
+        let activity_aggregator = match (config.activity_retention(), activity_repository) {
+            (Some(retention), Some(activity_repository)) => Some(ActivityAggregator::new(activity_repository, retention)),
+            (Some(_), None) => {
+                println!("Activity counters are not supported by this database, disabling them");
+                None
+            }
+            (None, _) => None,
+        };
+        let votes_count_emitter = votes_count_emitter(&config)?;
+        let reconciler = config
+            .reconciliation_config()
+            .map(|reconciliation_config| VoteCountReconciler::new(actions_repository, reconciliation_config));
 
         Ok(Dependencies {
             consumer: Box::new(actions_consumer),
             processor: Box::new(actions_processor),
             loader: Box::new(actions_loader),
+            batch_config: config.batch_config(),
+            reconciler,
+            metrics,
+            metrics_addr: config.metrics_addr,
+            start_mode: config.start_mode(),
+            activity_aggregator,
+            filter_reloader,
+            retry_config: config.retry_config(),
+            votes_count_emitter,
+            namespace: config.namespace.clone(),
+            writers_lock,
         })
     }
 }
 
+/// Repositories the pipeline persists to, the activity one being `None` when
+/// the database doesn't support activity counters, and the connection holding
+/// the writers lock, `None` when the database has none.
+type Repositories = (Arc<dyn ActionsRepository>, Arc<dyn CursorRepository>, Option<Arc<dyn ActivityRepository>>, Option<sqlx::PgConnection>);
+
+/// Connects to the database of `database_url` and creates its repositories.
+///
+/// `sqlite:` URLs are served by the SQLite repositories when the `sqlite` feature
+/// is enabled, their schema being created on connection. Any other URL is
+/// expected to point to a migrated PostgreSQL database, its tables being looked up
+/// in `database_schema` when one is set. The writers lock of the schema is taken
+/// shared, so the dead letter reprocessor can't write the same tables meanwhile.
+///
+/// # Arguments
+///
+/// * `database_url` - The `DATABASE_URL` of the indexer
+/// * `database_schema` - The PostgreSQL schema the tables are in, if any
+///
+/// # Returns
+///
+/// A `Result` with the actions, cursor and activity repositories and the writers lock, or an
+/// `IndexingError` if the connection or any repository fails to initialize, or a
+/// schema is set for a SQLite database.
+async fn connect_repositories(database_url: &str, database_schema: Option<&str>) -> Result<Repositories, IndexingError> {
+    #[cfg(feature = "sqlite")]
+    if database_url.starts_with("sqlite:") {
+        use actions_indexer_repository::{SqliteActionsRepository, SqliteCursorRepository};
+
+        if let Some(schema) = database_schema {
+            return Err(IndexingError::Config(format!(
+                "database schema '{}' isn't supported by SQLite, use a database file per schema instead",
+                schema
+            )));
+        }
+
+        let pool = actions_indexer_repository::sqlite::connect(database_url).await.map_err(IndexingError::Database)?;
+        return Ok((
+            Arc::new(SqliteActionsRepository::new(pool.clone()).await.map_err(IndexingError::ActionsRepository)?),
+            Arc::new(SqliteCursorRepository::new(pool).await.map_err(IndexingError::CursorRepository)?),
+            None,
+            None,
+        ));
+    }
+
+    let pool = actions_indexer_repository::postgres::connect(database_url, database_schema)
+        .await
+        .map_err(IndexingError::Database)?;
+    let writers_lock = actions_indexer_repository::postgres::lock_writers(&pool).await.map_err(IndexingError::Database)?;
+    Ok((
+        Arc::new(PostgresActionsRepository::new(pool.clone()).await.map_err(IndexingError::ActionsRepository)?),
+        Arc::new(PostgresCursorRepository::new(pool.clone()).await.map_err(IndexingError::CursorRepository)?),
+        Some(Arc::new(PostgresActivityRepository::new(pool).await.map_err(IndexingError::ActivityRepository)?)),
+        Some(writers_lock),
+    ))
+}
+
+/// Creates the emitter publishing vote count changes to the configured topic.
+///
+/// Publishing requires the `kafka` feature. Without it, a configured topic is
+/// ignored.
+///
+/// # Arguments
+///
+/// * `config` - The validated indexer configuration
+///
+/// # Returns
+///
+/// A `Result` with the emitter, `None` if no topic is configured, or an
+/// `IndexingError` if the Kafka producer fails to initialize.
+fn votes_count_emitter(config: &IndexerConfig) -> Result<Option<Box<dyn VotesCountEmitter>>, IndexingError> {
+    #[cfg(feature = "kafka")]
+    if let Some(topic) = &config.votes_count_topic {
+        return Ok(Some(Box::new(crate::emitter::KafkaVotesCountEmitter::from_env(topic)?)));
+    }
+
+    #[cfg(not(feature = "kafka"))]
+    if config.votes_count_topic.is_some() {
+        println!("Publishing vote count changes requires the kafka feature, disabling it");
+    }
+    Ok(None)
+}
+
+/// Creates the `ActionsProcessor` with the handlers of every supported action.
+///
+/// # Returns
+///
+/// An `ActionsProcessor` handling version 1 of votes, flags, published edits and
+/// memberships.
+pub fn actions_processor() -> ActionsProcessor {
+    let mut actions_processor = ActionsProcessor::new();
+    actions_processor.register_handler(1, ActionType::Vote, ObjectType::Entity, Arc::new(VoteHandler));
+    actions_processor.register_handler(1, ActionType::Vote, ObjectType::Relation, Arc::new(VoteHandler));
+    actions_processor.register_handler(1, ActionType::Flag, ObjectType::Entity, Arc::new(FlagHandler));
+    actions_processor.register_handler(1, ActionType::Flag, ObjectType::Relation, Arc::new(FlagHandler));
+    actions_processor.register_handler(1, ActionType::EditPublished, ObjectType::Entity, Arc::new(EditPublishedHandler));
+    actions_processor.register_handler(1, ActionType::Membership, ObjectType::Entity, Arc::new(MembershipHandler));
+    actions_processor
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::env;
     use serial_test::serial;
+    use actions_indexer_pipeline::errors::ConsumerError;
     use tempfile::NamedTempFile;
     use std::io::Write;
 
@@ -165,6 +353,42 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    #[serial]
+    async fn test_dependencies_new_sqlite_database_url() {
+        clear_env_vars();
+        unsafe {
+            env::set_var("DATABASE_URL", "sqlite::memory:");
+            env::set_var("SUBSTREAMS_ENDPOINT", "https://test-endpoint.com");
+            env::set_var("SUBSTREAMS_API_TOKEN", "test-token");
+        }
+
+        let dependencies = Dependencies::new().await.unwrap();
+
+        assert!(dependencies.activity_aggregator.is_none());
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_connect_repositories_rejects_sqlite_schema() {
+        let result = connect_repositories("sqlite::memory:", Some("testnet")).await;
+
+        assert!(matches!(result, Err(IndexingError::Config(_))));
+    }
+
+    #[cfg(not(feature = "kafka"))]
+    #[test]
+    fn test_votes_count_emitter_requires_kafka_feature() {
+        let config = IndexerConfig {
+            votes_count_topic: Some("actions.votes_count".to_string()),
+            ..IndexerConfig::default()
+        };
+
+        assert!(votes_count_emitter(&config).unwrap().is_none());
+        assert!(votes_count_emitter(&IndexerConfig::default()).unwrap().is_none());
+    }
+
     #[test]
     fn test_dependencies_struct_creation() {
         // Test that we can create individual components that make up Dependencies
@@ -225,6 +449,79 @@ mod tests {
         assert!(true); // If we get here, registration worked
     }
 
+    #[test]
+    fn test_action_kind_handlers() {
+        use actions_indexer_pipeline::processor::ProcessActions;
+        use actions_indexer_shared::types::{Action, ActionRaw, MembershipChange};
+        use alloy::primitives::{Address, Bytes, TxHash};
+
+        let mut processor = ActionsProcessor::new();
+        processor.register_handler(1, ActionType::Flag, ObjectType::Entity, Arc::new(FlagHandler));
+        processor.register_handler(1, ActionType::EditPublished, ObjectType::Entity, Arc::new(EditPublishedHandler));
+        processor.register_handler(1, ActionType::Membership, ObjectType::Entity, Arc::new(MembershipHandler));
+
+        let raw = |action_type, metadata: &[u8]| ActionRaw {
+            action_type,
+            action_version: 1,
+            sender: Address::ZERO,
+            object_id: uuid::Uuid::new_v4(),
+            group_id: None,
+            space_pov: uuid::Uuid::new_v4(),
+            metadata: Some(Bytes::copy_from_slice(metadata)),
+            block_number: 1,
+            block_timestamp: 1,
+            tx_hash: TxHash::ZERO,
+            object_type: ObjectType::Entity,
+            log_index: None,
+            output_position: None,
+        };
+
+        let actions = processor.process(&[
+            raw(ActionType::Flag, b""),
+            raw(ActionType::EditPublished, b"ipfs://QmEdit"),
+            raw(ActionType::Membership, &[1]),
+            // Invalid payloads are dropped
+            raw(ActionType::EditPublished, b""),
+            raw(ActionType::Membership, &[2]),
+        ]);
+
+        assert_eq!(actions.len(), 3);
+        assert!(matches!(&actions[0], Action::Flag(flag) if flag.reason.is_none()));
+        assert!(matches!(&actions[1], Action::EditPublished(edit) if edit.content_uri == "ipfs://QmEdit"));
+        assert!(matches!(&actions[2], Action::Membership(membership) if membership.change == MembershipChange::Leave));
+    }
+
+    /// Provider of the bundled package, tracking the contracts of `config`.
+    fn bundled_package_provider(config: &IndexerConfig) -> SubstreamsStreamProvider {
+        SubstreamsStreamProvider::new(
+            "https://test-endpoint.com".to_string(),
+            config.package_file.clone(),
+            MODULE_NAME.to_string(),
+            None,
+            vec![Param {
+                module_name: MODULE_NAME.to_string(),
+                expression: config.module_params(),
+                built_in: config.package_built_in_params(),
+            }],
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_bundled_package_only_tracks_its_built_in_contract() {
+        let config = IndexerConfig::default();
+        let redeployed = IndexerConfig {
+            tracked_contracts: vec!["0x0000000000000000000000000000000000000001".to_string()],
+            ..IndexerConfig::default()
+        };
+
+        assert!(bundled_package_provider(&config).check_package().await.is_ok());
+        assert!(matches!(
+            bundled_package_provider(&redeployed).check_package().await,
+            Err(ConsumerError::ReadingPackage(_))
+        ));
+    }
+
     #[test]
     fn test_substreams_provider_creation() {
         // Test SubstreamsStreamProvider creation with various parameters
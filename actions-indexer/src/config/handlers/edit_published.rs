@@ -0,0 +1,38 @@
+use actions_indexer_pipeline::processor::HandleAction;
+use actions_indexer_pipeline::errors::ProcessorError;
+use actions_indexer_shared::types::{Action, ActionRaw, EditPublished};
+
+pub struct EditPublishedHandler;
+
+impl HandleAction for EditPublishedHandler {
+    /// Handles an edit published action.
+    ///
+    /// This method converts the `ActionRaw` into an `EditPublished` enum variant.
+    /// The metadata is the UTF-8 content URI of the edit.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - A reference to the `ActionRaw` to handle
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Action` enum variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ProcessorError` if the content URI is missing or isn't valid UTF-8.
+    ///
+    fn handle(&self, action: &ActionRaw) -> Result<Action, ProcessorError> {
+        let content_uri = action
+            .metadata
+            .as_ref()
+            .filter(|m| !m.is_empty())
+            .and_then(|m| String::from_utf8(m.to_vec()).ok())
+            .ok_or(ProcessorError::InvalidEditPublished)?;
+
+        Ok(Action::EditPublished(EditPublished {
+            raw: action.clone(),
+            content_uri,
+        }))
+    }
+}
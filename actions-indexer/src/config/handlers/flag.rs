@@ -0,0 +1,38 @@
+use actions_indexer_pipeline::processor::HandleAction;
+use actions_indexer_pipeline::errors::ProcessorError;
+use actions_indexer_shared::types::{Action, ActionRaw, Flag};
+
+pub struct FlagHandler;
+
+impl HandleAction for FlagHandler {
+    /// Handles a flag action.
+    ///
+    /// This method converts the `ActionRaw` into a `Flag` enum variant. The
+    /// metadata is the optional UTF-8 reason of the flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - A reference to the `ActionRaw` to handle
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Action` enum variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ProcessorError` if the reason isn't valid UTF-8.
+    ///
+    fn handle(&self, action: &ActionRaw) -> Result<Action, ProcessorError> {
+        let reason = match action.metadata.as_ref().filter(|m| !m.is_empty()) {
+            Some(metadata) => Some(
+                String::from_utf8(metadata.to_vec()).map_err(|_| ProcessorError::InvalidFlag)?,
+            ),
+            None => None,
+        };
+
+        Ok(Action::Flag(Flag {
+            raw: action.clone(),
+            reason,
+        }))
+    }
+}
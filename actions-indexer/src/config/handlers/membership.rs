@@ -0,0 +1,34 @@
+use actions_indexer_pipeline::processor::HandleAction;
+use actions_indexer_pipeline::errors::ProcessorError;
+use actions_indexer_shared::types::{Action, ActionRaw, Membership, MembershipChange};
+
+pub struct MembershipHandler;
+
+impl HandleAction for MembershipHandler {
+    /// Handles a membership action.
+    ///
+    /// This method converts the `ActionRaw` into a `Membership` enum variant.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - A reference to the `ActionRaw` to handle
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the `Action` enum variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ProcessorError` if the membership change is invalid.
+    ///
+    fn handle(&self, action: &ActionRaw) -> Result<Action, ProcessorError> {
+        Ok(Action::Membership(Membership {
+            raw: action.clone(),
+            change: match action.metadata.as_ref().and_then(|m| m.first()) {
+                Some(&0) => MembershipChange::Join,
+                Some(&1) => MembershipChange::Leave,
+                _ => return Err(ProcessorError::InvalidMembership),
+            },
+        }))
+    }
+}
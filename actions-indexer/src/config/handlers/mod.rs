@@ -1,3 +1,9 @@
+mod edit_published;
+mod flag;
+mod membership;
 mod vote;
 
-pub use vote::VoteHandler;
\ No newline at end of file
+pub use edit_published::EditPublishedHandler;
+pub use flag::FlagHandler;
+pub use membership::MembershipHandler;
+pub use vote::VoteHandler;
@@ -0,0 +1,886 @@
+//! Startup configuration for the Actions Indexer.
+//!
+//! Holds the block range to index and the actions contracts to track, which used to be
+//! baked into the Substreams package, how many blocks are persisted per transaction, how
+//! often vote counts are reconciled, where metrics are served, where the consumer
+//! starts streaming from, how long activity counters are kept, where the consumer's
+//! allow and deny lists are read from and how transient failures are retried. Values are read from an optional JSON file and
+//! overridden by environment variables, then validated before any dependency is built.
+use actions_indexer_pipeline::consumer::StartMode;
+use actions_indexer_pipeline::orchestrator::BatchConfig;
+use actions_indexer_pipeline::retry::{RetryConfig, RetryPolicy};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::errors::IndexingError;
+use crate::reconciliation::ReconciliationConfig;
+
+/// Environment variable for a JSON file with the configuration, see [`IndexerConfig::from_file`].
+const CONFIG_FILE_ENV: &str = "ACTIONS_INDEXER_CONFIG";
+/// Environment variable for the first block to index.
+const START_BLOCK_ENV: &str = "START_BLOCK";
+/// Environment variable for the block to stop indexing at (exclusive).
+const STOP_BLOCK_ENV: &str = "STOP_BLOCK";
+/// Environment variable for comma-separated actions contract addresses.
+const TRACKED_CONTRACTS_ENV: &str = "TRACKED_CONTRACTS";
+/// Environment variable for the path of the Substreams package.
+const PACKAGE_FILE_ENV: &str = "SUBSTREAMS_PACKAGE";
+/// Environment variable for the maximum number of blocks persisted in one transaction.
+const BATCH_MAX_BLOCKS_ENV: &str = "BATCH_MAX_BLOCKS";
+/// Environment variable for the number of actions that flushes a batch early.
+const BATCH_MAX_ACTIONS_ENV: &str = "BATCH_MAX_ACTIONS";
+/// Environment variable for the maximum time a block waits in a batch, in milliseconds.
+const BATCH_FLUSH_INTERVAL_MS_ENV: &str = "BATCH_FLUSH_INTERVAL_MS";
+/// Environment variable for the seconds between vote count reconciliations, `0` disables them.
+const RECONCILE_INTERVAL_SECS_ENV: &str = "RECONCILE_INTERVAL_SECS";
+/// Environment variable for the number of vote counts checked per reconciliation.
+const RECONCILE_PAGE_SIZE_ENV: &str = "RECONCILE_PAGE_SIZE";
+/// Environment variable for the address to serve Prometheus metrics on.
+const METRICS_ADDR_ENV: &str = "METRICS_ADDR";
+/// Environment variable for where the consumer starts streaming from, see [`StartModeConfig`].
+const START_MODE_ENV: &str = "START_MODE";
+/// Environment variable for the hours of activity counters kept, `0` disables them.
+const ACTIVITY_RETENTION_HOURS_ENV: &str = "ACTIVITY_RETENTION_HOURS";
+/// Environment variable for the JSON file with the consumer's allow and deny lists.
+const FILTER_FILE_ENV: &str = "FILTER_FILE";
+/// Environment variable for the seconds between checks of the filter file, `0` disables them.
+const FILTER_RELOAD_INTERVAL_SECS_ENV: &str = "FILTER_RELOAD_INTERVAL_SECS";
+/// Environment variable for the number of consumer restarts after a retryable error.
+const CONSUMER_MAX_RETRIES_ENV: &str = "CONSUMER_MAX_RETRIES";
+/// Environment variable for the number of loader retries after a retryable error.
+const LOADER_MAX_RETRIES_ENV: &str = "LOADER_MAX_RETRIES";
+/// Environment variable for the wait before the first retry, in milliseconds.
+const RETRY_INITIAL_BACKOFF_MS_ENV: &str = "RETRY_INITIAL_BACKOFF_MS";
+/// Environment variable for the maximum wait between retries, in milliseconds.
+const RETRY_MAX_BACKOFF_MS_ENV: &str = "RETRY_MAX_BACKOFF_MS";
+/// Environment variable for the Kafka topic vote count changes are published to.
+const VOTES_COUNT_TOPIC_ENV: &str = "VOTES_COUNT_TOPIC";
+/// Environment variable for the namespace the cursor is stored under.
+const INDEXER_NAMESPACE_ENV: &str = "INDEXER_NAMESPACE";
+/// Environment variable for the PostgreSQL schema the tables are in.
+const DATABASE_SCHEMA_ENV: &str = "DATABASE_SCHEMA";
+
+/// Actions contract tracked when none is configured.
+const DEFAULT_TRACKED_CONTRACT: &str = "0x80ef8d87fafcb65f5399c6d28c72a27577616339";
+/// Prefix of each contract in the `map_actions` params, which double as its block filter query.
+const EVT_ADDR_PREFIX: &str = "evt_addr:";
+
+/// Where the consumer starts streaming from, resolved to a [`StartMode`] with the
+/// configured block range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartModeConfig {
+    /// Resume from the stored cursor, or from `start_block` without one.
+    #[default]
+    Cursor,
+    /// Start at `start_block`, ignoring the stored cursor.
+    Block,
+    /// Start at the chain head, ignoring the stored cursor.
+    Tail,
+    /// Reindex `start_block` to `stop_block` without moving the stored cursor, then stop.
+    Replay,
+}
+
+impl StartModeConfig {
+    /// Parses the value of `START_MODE`: `cursor`, `block`, `tail` or `replay`.
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "cursor" => Some(StartModeConfig::Cursor),
+            "block" => Some(StartModeConfig::Block),
+            "tail" => Some(StartModeConfig::Tail),
+            "replay" => Some(StartModeConfig::Replay),
+            _ => None,
+        }
+    }
+}
+
+/// Block range and contract set the indexer streams.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct IndexerConfig {
+    /// First block to index. `None` starts at the package's initial block.
+    ///
+    /// With the `cursor` start mode, only used when there is no persisted cursor,
+    /// since the cursor always wins.
+    pub start_block: Option<i64>,
+    /// Block to stop indexing at (exclusive). `None` streams forever.
+    pub stop_block: Option<u64>,
+    /// Addresses of the actions contracts to track.
+    pub tracked_contracts: Vec<String>,
+    /// Path of the Substreams package.
+    pub package_file: String,
+    /// Maximum number of blocks persisted in a single transaction.
+    pub batch_max_blocks: usize,
+    /// Number of actions that flushes a batch before it reaches `batch_max_blocks`.
+    pub batch_max_actions: usize,
+    /// Maximum time, in milliseconds, a block waits in a batch before it is persisted.
+    pub batch_flush_interval_ms: u64,
+    /// Seconds between vote count reconciliations. `0` disables them.
+    pub reconcile_interval_secs: u64,
+    /// Number of vote counts checked per reconciliation.
+    pub reconcile_page_size: usize,
+    /// Address to serve Prometheus metrics on. `None` doesn't serve them.
+    pub metrics_addr: Option<SocketAddr>,
+    /// Where the consumer starts streaming from.
+    pub start_mode: StartModeConfig,
+    /// Hours of activity counters kept. `0` disables them.
+    pub activity_retention_hours: u64,
+    /// JSON file with the consumer's allow and deny lists. `None` doesn't filter actions.
+    pub filter_file: Option<String>,
+    /// Seconds between checks of the filter file for changes. `0` never reloads it.
+    pub filter_reload_interval_secs: u64,
+    /// Times the consumer is restarted after a retryable error. `0` never restarts it.
+    pub consumer_max_retries: u32,
+    /// Times the loader retries a batch after a retryable error. `0` never retries it.
+    pub loader_max_retries: u32,
+    /// Wait before the first retry, in milliseconds, doubled with every retry.
+    pub retry_initial_backoff_ms: u64,
+    /// Maximum wait between retries, in milliseconds.
+    pub retry_max_backoff_ms: u64,
+    /// Kafka topic vote count changes are published to, with the `kafka` feature.
+    /// `None` doesn't publish them.
+    pub votes_count_topic: Option<String>,
+    /// Namespace the cursor is stored under, so indexers sharing a database resume
+    /// from their own cursor. `None` uses the default cursor.
+    pub namespace: Option<String>,
+    /// PostgreSQL schema the tables are created and looked up in. `None` uses the
+    /// `search_path` of the database.
+    pub database_schema: Option<String>,
+}
+
+impl Default for IndexerConfig {
+    fn default() -> Self {
+        Self {
+            start_block: None,
+            stop_block: None,
+            tracked_contracts: vec![DEFAULT_TRACKED_CONTRACT.to_string()],
+            package_file: super::dependencies::PKG_FILE.to_string(),
+            batch_max_blocks: 100,
+            batch_max_actions: 1000,
+            batch_flush_interval_ms: 1000,
+            reconcile_interval_secs: 60,
+            reconcile_page_size: 1000,
+            metrics_addr: None,
+            start_mode: StartModeConfig::default(),
+            activity_retention_hours: 168,
+            filter_file: None,
+            filter_reload_interval_secs: 30,
+            consumer_max_retries: 5,
+            loader_max_retries: 5,
+            retry_initial_backoff_ms: 500,
+            retry_max_backoff_ms: 30_000,
+            votes_count_topic: None,
+            namespace: None,
+            database_schema: None,
+        }
+    }
+}
+
+impl IndexerConfig {
+    /// Reads the configuration from the environment and validates it.
+    ///
+    /// - `ACTIONS_INDEXER_CONFIG`: JSON file with the configuration, see [`Self::from_file`]
+    /// - `START_BLOCK` / `STOP_BLOCK`: block range to index
+    /// - `TRACKED_CONTRACTS`: comma-separated actions contract addresses
+    /// - `SUBSTREAMS_PACKAGE`: path of the Substreams package
+    /// - `BATCH_MAX_BLOCKS` / `BATCH_MAX_ACTIONS` / `BATCH_FLUSH_INTERVAL_MS`: batch limits
+    /// - `RECONCILE_INTERVAL_SECS` / `RECONCILE_PAGE_SIZE`: vote count reconciliation
+    /// - `METRICS_ADDR`: address to serve Prometheus metrics on
+    /// - `START_MODE`: where the consumer starts streaming from, `cursor`, `block`, `tail` or `replay`
+    /// - `ACTIVITY_RETENTION_HOURS`: hours of activity counters kept
+    /// - `FILTER_FILE` / `FILTER_RELOAD_INTERVAL_SECS`: consumer allow and deny lists
+    /// - `CONSUMER_MAX_RETRIES` / `LOADER_MAX_RETRIES` / `RETRY_INITIAL_BACKOFF_MS` /
+    ///   `RETRY_MAX_BACKOFF_MS`: retries of transient failures
+    /// - `VOTES_COUNT_TOPIC`: Kafka topic vote count changes are published to
+    /// - `INDEXER_NAMESPACE`: namespace the cursor is stored under
+    /// - `DATABASE_SCHEMA`: PostgreSQL schema the tables are in
+    ///
+    /// Environment variables override the values from the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok(Self)` if the configuration is valid or an
+    /// `IndexingError::Config` describing the first invalid value.
+    pub fn from_env() -> Result<Self, IndexingError> {
+        let mut config = match std::env::var(CONFIG_FILE_ENV) {
+            Ok(path) if !path.is_empty() => Self::from_file(path)?,
+            _ => Self::default(),
+        };
+
+        if let Some(start_block) = env_var(START_BLOCK_ENV) {
+            config.start_block = Some(start_block.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a block number: {}", START_BLOCK_ENV, start_block, e))
+            })?);
+        }
+        if let Some(stop_block) = env_var(STOP_BLOCK_ENV) {
+            config.stop_block = Some(stop_block.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a block number: {}", STOP_BLOCK_ENV, stop_block, e))
+            })?);
+        }
+        if let Some(contracts) = env_var(TRACKED_CONTRACTS_ENV) {
+            config.tracked_contracts = contracts
+                .split(',')
+                .map(str::trim)
+                .filter(|contract| !contract.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        if let Some(package_file) = env_var(PACKAGE_FILE_ENV) {
+            config.package_file = package_file;
+        }
+        if let Some(max_blocks) = env_var(BATCH_MAX_BLOCKS_ENV) {
+            config.batch_max_blocks = max_blocks.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", BATCH_MAX_BLOCKS_ENV, max_blocks, e))
+            })?;
+        }
+        if let Some(max_actions) = env_var(BATCH_MAX_ACTIONS_ENV) {
+            config.batch_max_actions = max_actions.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", BATCH_MAX_ACTIONS_ENV, max_actions, e))
+            })?;
+        }
+        if let Some(flush_interval) = env_var(BATCH_FLUSH_INTERVAL_MS_ENV) {
+            config.batch_flush_interval_ms = flush_interval.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", BATCH_FLUSH_INTERVAL_MS_ENV, flush_interval, e))
+            })?;
+        }
+        if let Some(interval) = env_var(RECONCILE_INTERVAL_SECS_ENV) {
+            config.reconcile_interval_secs = interval.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", RECONCILE_INTERVAL_SECS_ENV, interval, e))
+            })?;
+        }
+        if let Some(page_size) = env_var(RECONCILE_PAGE_SIZE_ENV) {
+            config.reconcile_page_size = page_size.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", RECONCILE_PAGE_SIZE_ENV, page_size, e))
+            })?;
+        }
+        if let Some(metrics_addr) = env_var(METRICS_ADDR_ENV) {
+            config.metrics_addr = Some(metrics_addr.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a socket address: {}", METRICS_ADDR_ENV, metrics_addr, e))
+            })?);
+        }
+        if let Some(start_mode) = env_var(START_MODE_ENV) {
+            config.start_mode = StartModeConfig::parse(&start_mode).ok_or_else(|| {
+                IndexingError::Config(format!(
+                    "{} '{}' is not one of cursor, block, tail or replay",
+                    START_MODE_ENV, start_mode
+                ))
+            })?;
+        }
+        if let Some(retention) = env_var(ACTIVITY_RETENTION_HOURS_ENV) {
+            config.activity_retention_hours = retention.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", ACTIVITY_RETENTION_HOURS_ENV, retention, e))
+            })?;
+        }
+        if let Some(filter_file) = env_var(FILTER_FILE_ENV) {
+            config.filter_file = Some(filter_file);
+        }
+        if let Some(interval) = env_var(FILTER_RELOAD_INTERVAL_SECS_ENV) {
+            config.filter_reload_interval_secs = interval.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", FILTER_RELOAD_INTERVAL_SECS_ENV, interval, e))
+            })?;
+        }
+        if let Some(retries) = env_var(CONSUMER_MAX_RETRIES_ENV) {
+            config.consumer_max_retries = retries.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", CONSUMER_MAX_RETRIES_ENV, retries, e))
+            })?;
+        }
+        if let Some(retries) = env_var(LOADER_MAX_RETRIES_ENV) {
+            config.loader_max_retries = retries.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", LOADER_MAX_RETRIES_ENV, retries, e))
+            })?;
+        }
+        if let Some(backoff) = env_var(RETRY_INITIAL_BACKOFF_MS_ENV) {
+            config.retry_initial_backoff_ms = backoff.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", RETRY_INITIAL_BACKOFF_MS_ENV, backoff, e))
+            })?;
+        }
+        if let Some(backoff) = env_var(RETRY_MAX_BACKOFF_MS_ENV) {
+            config.retry_max_backoff_ms = backoff.parse().map_err(|e| {
+                IndexingError::Config(format!("{} '{}' is not a number: {}", RETRY_MAX_BACKOFF_MS_ENV, backoff, e))
+            })?;
+        }
+        if let Some(topic) = env_var(VOTES_COUNT_TOPIC_ENV) {
+            config.votes_count_topic = Some(topic);
+        }
+        if let Some(namespace) = env_var(INDEXER_NAMESPACE_ENV) {
+            config.namespace = Some(namespace);
+        }
+        if let Some(schema) = env_var(DATABASE_SCHEMA_ENV) {
+            config.database_schema = Some(schema);
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reads the configuration from a JSON file. Every field is optional:
+    ///
+    /// ```json
+    /// {
+    ///   "start_block": 64213,
+    ///   "stop_block": null,
+    ///   "tracked_contracts": ["0x80ef8d87fafcb65f5399c6d28c72a27577616339"],
+    ///   "package_file": "./geo-actions-v0.1.0.spkg",
+    ///   "batch_max_blocks": 100,
+    ///   "batch_max_actions": 1000,
+    ///   "batch_flush_interval_ms": 1000,
+    ///   "reconcile_interval_secs": 60,
+    ///   "reconcile_page_size": 1000,
+    ///   "metrics_addr": "0.0.0.0:9090",
+    ///   "start_mode": "cursor",
+    ///   "activity_retention_hours": 168,
+    ///   "filter_file": "./filter.json",
+    ///   "filter_reload_interval_secs": 30,
+    ///   "consumer_max_retries": 5,
+    ///   "loader_max_retries": 5,
+    ///   "retry_initial_backoff_ms": 500,
+    ///   "retry_max_backoff_ms": 30000,
+    ///   "votes_count_topic": "actions.votes_count",
+    ///   "namespace": "testnet",
+    ///   "database_schema": "testnet"
+    /// }
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, IndexingError> {
+        let path = path.as_ref();
+        let content = std::fs::read(path)
+            .map_err(|e| IndexingError::Config(format!("reading {}: {}", path.display(), e)))?;
+
+        serde_json::from_slice(&content)
+            .map_err(|e| IndexingError::Config(format!("parsing {}: {}", path.display(), e)))
+    }
+
+    /// Checks the block range, start mode, contract addresses, batch limits,
+    /// reconciliation page size, retry backoff, namespace and database schema.
+    ///
+    /// # Returns
+    ///
+    /// An `IndexingError::Config` if the range is empty, the start mode is missing
+    /// the blocks it starts or stops at, no contract is tracked, a contract isn't a
+    /// `0x`-prefixed 20 byte hex address, a batch limit or the reconciliation page
+    /// size is zero, the retry backoff is zero or exceeds its maximum, or the
+    /// namespace or database schema isn't made of lowercase letters, digits and
+    /// underscores.
+    pub fn validate(&self) -> Result<(), IndexingError> {
+        if let Some(start_block) = self.start_block {
+            if start_block < 0 {
+                return Err(IndexingError::Config(format!("start block {} is negative", start_block)));
+            }
+            if self.stop_block.is_some_and(|stop_block| stop_block <= start_block as u64) {
+                return Err(IndexingError::Config(format!(
+                    "stop block {} must be after start block {}",
+                    self.stop_block.unwrap_or_default(),
+                    start_block
+                )));
+            }
+        }
+
+        match self.start_mode {
+            StartModeConfig::Block if self.start_block.is_none() => {
+                return Err(IndexingError::Config("the block start mode requires a start block".to_string()));
+            }
+            StartModeConfig::Replay if self.start_block.is_none() || self.stop_block.is_none() => {
+                return Err(IndexingError::Config("the replay start mode requires a start and stop block".to_string()));
+            }
+            _ => {}
+        }
+
+        if self.tracked_contracts.is_empty() {
+            return Err(IndexingError::Config("at least one tracked contract is required".to_string()));
+        }
+
+        for contract in &self.tracked_contracts {
+            let is_address = contract
+                .strip_prefix("0x")
+                .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+            if !is_address {
+                return Err(IndexingError::Config(format!("tracked contract '{}' is not an address", contract)));
+            }
+        }
+
+        if self.batch_max_blocks == 0 || self.batch_max_actions == 0 || self.batch_flush_interval_ms == 0 {
+            return Err(IndexingError::Config("batch limits and flush interval must be positive".to_string()));
+        }
+
+        if self.reconcile_page_size == 0 {
+            return Err(IndexingError::Config("reconciliation page size must be positive".to_string()));
+        }
+
+        if self.retry_initial_backoff_ms == 0 || self.retry_max_backoff_ms < self.retry_initial_backoff_ms {
+            return Err(IndexingError::Config(format!(
+                "retry backoff must be positive and at most {}ms, got {}ms",
+                self.retry_max_backoff_ms, self.retry_initial_backoff_ms
+            )));
+        }
+
+        for (name, value) in [("namespace", &self.namespace), ("database schema", &self.database_schema)] {
+            if let Some(value) = value.as_ref().filter(|value| !is_identifier(value)) {
+                return Err(IndexingError::Config(format!(
+                    "{} '{}' must only contain lowercase letters, digits and underscores",
+                    name, value
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The block range in the Substreams `start:stop` format, or `None` for the package defaults.
+    pub fn block_range(&self) -> Option<String> {
+        if self.start_block.is_none() && self.stop_block.is_none() {
+            return None;
+        }
+
+        Some(format!(
+            "{}:{}",
+            self.start_block.map(|block| block.to_string()).unwrap_or_default(),
+            self.stop_block.map(|block| block.to_string()).unwrap_or_default(),
+        ))
+    }
+
+    /// The `map_actions` params, in block filter query format: `evt_addr:0x... || evt_addr:0x...`.
+    pub fn module_params(&self) -> String {
+        self.tracked_contracts
+            .iter()
+            .map(|contract| format!("{}{}", EVT_ADDR_PREFIX, contract.to_lowercase()))
+            .collect::<Vec<_>>()
+            .join(" || ")
+    }
+
+    /// The `map_actions` params applied by the package when it doesn't take any, i.e.
+    /// by the bundled package, built before `map_actions` took params and tracking the
+    /// default contract only. `None` for other packages, whose built-in params are unknown.
+    pub fn package_built_in_params(&self) -> Option<String> {
+        (self.package_file == super::dependencies::PKG_FILE).then(|| IndexerConfig::default().module_params())
+    }
+
+    /// The batch limits the orchestrator persists blocks with.
+    pub fn batch_config(&self) -> BatchConfig {
+        BatchConfig {
+            max_blocks: self.batch_max_blocks,
+            max_actions: self.batch_max_actions,
+            flush_interval: Duration::from_millis(self.batch_flush_interval_ms),
+        }
+    }
+
+    /// Where the consumer starts streaming from. Assumes the configuration is valid,
+    /// see [`Self::validate`].
+    pub fn start_mode(&self) -> StartMode {
+        let start_block = self.start_block.unwrap_or_default() as u64;
+        match self.start_mode {
+            StartModeConfig::Cursor => StartMode::FromCursor,
+            StartModeConfig::Block => StartMode::FromBlock(start_block),
+            StartModeConfig::Tail => StartMode::FromTail,
+            StartModeConfig::Replay => StartMode::Replay(start_block..self.stop_block.unwrap_or_default()),
+        }
+    }
+
+    /// The vote count reconciliation settings, or `None` if reconciliation is disabled.
+    pub fn reconciliation_config(&self) -> Option<ReconciliationConfig> {
+        if self.reconcile_interval_secs == 0 {
+            return None;
+        }
+
+        Some(ReconciliationConfig {
+            interval: Duration::from_secs(self.reconcile_interval_secs),
+            page_size: self.reconcile_page_size,
+        })
+    }
+
+    /// How long activity counters are kept, or `None` if they are disabled.
+    pub fn activity_retention(&self) -> Option<Duration> {
+        if self.activity_retention_hours == 0 {
+            return None;
+        }
+
+        Some(Duration::from_secs(self.activity_retention_hours * 3600))
+    }
+
+    /// How often the filter file is checked for changes, or `None` if it is never reloaded.
+    pub fn filter_reload_interval(&self) -> Option<Duration> {
+        if self.filter_file.is_none() || self.filter_reload_interval_secs == 0 {
+            return None;
+        }
+
+        Some(Duration::from_secs(self.filter_reload_interval_secs))
+    }
+
+    /// The retry policies of the consumer and loader, sharing the same backoff.
+    pub fn retry_config(&self) -> RetryConfig {
+        let policy = |max_retries| RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(self.retry_initial_backoff_ms),
+            max_backoff: Duration::from_millis(self.retry_max_backoff_ms),
+        };
+
+        RetryConfig {
+            consumer: policy(self.consumer_max_retries),
+            loader: policy(self.loader_max_retries),
+        }
+    }
+
+    /// Logs the configuration the indexer starts with.
+    pub fn log(&self) {
+        println!(
+            "Indexing actions from package {} (start block {}, stop block {}, tracked contracts {})",
+            self.package_file,
+            self.start_block.map_or("package default".to_string(), |block| block.to_string()),
+            self.stop_block.map_or("none".to_string(), |block| block.to_string()),
+            self.tracked_contracts.join(", "),
+        );
+        println!("Starting the consumer with {:?}", self.start_mode());
+        println!(
+            "Persisting up to {} blocks or {} actions per transaction, flushing every {}ms",
+            self.batch_max_blocks, self.batch_max_actions, self.batch_flush_interval_ms,
+        );
+        match self.reconciliation_config() {
+            Some(config) => println!(
+                "Reconciling {} vote counts every {}s",
+                config.page_size,
+                config.interval.as_secs(),
+            ),
+            None => println!("Vote count reconciliation is disabled"),
+        }
+        match self.activity_retention() {
+            Some(_) => println!("Keeping {}h of activity counters", self.activity_retention_hours),
+            None => println!("Activity counters are disabled"),
+        }
+        match (&self.filter_file, self.filter_reload_interval()) {
+            (Some(file), Some(interval)) => {
+                println!("Filtering actions with {}, reloaded every {}s", file, interval.as_secs())
+            }
+            (Some(file), None) => println!("Filtering actions with {}", file),
+            (None, _) => println!("Actions aren't filtered"),
+        }
+        println!(
+            "Retrying transient failures up to {} times in the consumer and {} times in the loader, backing off from {}ms to {}ms",
+            self.consumer_max_retries, self.loader_max_retries, self.retry_initial_backoff_ms, self.retry_max_backoff_ms,
+        );
+        match &self.votes_count_topic {
+            Some(topic) => println!("Publishing vote count changes to {}", topic),
+            None => println!("Vote count changes aren't published"),
+        }
+        match (&self.namespace, &self.database_schema) {
+            (Some(namespace), Some(schema)) => println!("Running in namespace {} with the tables of schema {}", namespace, schema),
+            (Some(namespace), None) => println!("Running in namespace {}", namespace),
+            (None, Some(schema)) => println!("Running with the tables of schema {}", schema),
+            (None, None) => println!("Running without a namespace"),
+        }
+        match self.metrics_addr {
+            Some(addr) => println!("Serving metrics on {}", addr),
+            None => println!("Metrics aren't served"),
+        }
+    }
+}
+
+/// Whether `value` is a non-empty lowercase identifier, safe to use as a namespace or schema.
+pub(crate) fn is_identifier(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Non-empty value of an environment variable.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let config = IndexerConfig::default();
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.block_range(), None);
+        assert_eq!(
+            config.module_params(),
+            "evt_addr:0x80ef8d87fafcb65f5399c6d28c72a27577616339"
+        );
+    }
+
+    #[test]
+    fn test_package_built_in_params() {
+        let config = IndexerConfig::default();
+        let custom_package = IndexerConfig {
+            package_file: "./geo-actions-v0.2.0.spkg".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(config.package_built_in_params(), Some(config.module_params()));
+        assert_eq!(custom_package.package_built_in_params(), None);
+    }
+
+    #[test]
+    fn test_block_range_and_params() {
+        let config = IndexerConfig {
+            start_block: Some(100),
+            tracked_contracts: vec![
+                "0x80eF8d87fafCB65F5399c6d28c72A27577616339".to_string(),
+                "0x0000000000000000000000000000000000000001".to_string(),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(config.block_range(), Some("100:".to_string()));
+        assert_eq!(
+            config.module_params(),
+            "evt_addr:0x80ef8d87fafcb65f5399c6d28c72a27577616339 || evt_addr:0x0000000000000000000000000000000000000001"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_values() {
+        let empty_range = IndexerConfig {
+            start_block: Some(100),
+            stop_block: Some(100),
+            ..Default::default()
+        };
+        assert!(matches!(empty_range.validate(), Err(IndexingError::Config(_))));
+
+        let no_contracts = IndexerConfig {
+            tracked_contracts: vec![],
+            ..Default::default()
+        };
+        assert!(matches!(no_contracts.validate(), Err(IndexingError::Config(_))));
+
+        let bad_contract = IndexerConfig {
+            tracked_contracts: vec!["0x80ef".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(bad_contract.validate(), Err(IndexingError::Config(_))));
+
+        let empty_batch = IndexerConfig {
+            batch_max_blocks: 0,
+            ..Default::default()
+        };
+        assert!(matches!(empty_batch.validate(), Err(IndexingError::Config(_))));
+    }
+
+    #[test]
+    fn test_from_file_fields_are_optional() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "start_block": 64213 }"#).expect("Failed to write to temp file");
+
+        let config = IndexerConfig::from_file(file.path()).unwrap();
+
+        assert_eq!(config.start_block, Some(64213));
+        assert_eq!(config.tracked_contracts, IndexerConfig::default().tracked_contracts);
+        assert!(IndexerConfig::from_file("missing-actions-indexer-config.json").is_err());
+    }
+
+    #[test]
+    fn test_reconciliation_config() {
+        let config = IndexerConfig {
+            reconcile_interval_secs: 30,
+            reconcile_page_size: 200,
+            ..Default::default()
+        };
+        let disabled = IndexerConfig {
+            reconcile_interval_secs: 0,
+            ..Default::default()
+        };
+        let empty_page = IndexerConfig {
+            reconcile_page_size: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.reconciliation_config(),
+            Some(ReconciliationConfig {
+                interval: Duration::from_secs(30),
+                page_size: 200,
+            })
+        );
+        assert_eq!(disabled.reconciliation_config(), None);
+        assert!(disabled.validate().is_ok());
+        assert!(matches!(empty_page.validate(), Err(IndexingError::Config(_))));
+    }
+
+    #[test]
+    fn test_from_file_metrics_addr() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "metrics_addr": "127.0.0.1:9090" }"#).expect("Failed to write to temp file");
+
+        let config = IndexerConfig::from_file(file.path()).unwrap();
+
+        assert_eq!(config.metrics_addr, Some("127.0.0.1:9090".parse().unwrap()));
+        assert_eq!(IndexerConfig::default().metrics_addr, None);
+    }
+
+    #[test]
+    fn test_from_file_votes_count_topic() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "votes_count_topic": "actions.votes_count" }"#).expect("Failed to write to temp file");
+
+        let config = IndexerConfig::from_file(file.path()).unwrap();
+
+        assert_eq!(config.votes_count_topic.as_deref(), Some("actions.votes_count"));
+        assert_eq!(IndexerConfig::default().votes_count_topic, None);
+    }
+
+    #[test]
+    fn test_from_file_namespace() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "namespace": "testnet", "database_schema": "testnet_v2" }"#).expect("Failed to write to temp file");
+
+        let config = IndexerConfig::from_file(file.path()).unwrap();
+
+        assert_eq!(config.namespace.as_deref(), Some("testnet"));
+        assert_eq!(config.database_schema.as_deref(), Some("testnet_v2"));
+        assert!(config.validate().is_ok());
+        assert_eq!(IndexerConfig::default().namespace, None);
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_namespace() {
+        let namespace = IndexerConfig {
+            namespace: Some("test net".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(namespace.validate(), Err(IndexingError::Config(_))));
+
+        let schema = IndexerConfig {
+            database_schema: Some("Testnet;".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(schema.validate(), Err(IndexingError::Config(_))));
+
+        let empty = IndexerConfig {
+            namespace: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(matches!(empty.validate(), Err(IndexingError::Config(_))));
+    }
+
+    #[test]
+    fn test_start_mode() {
+        let config = IndexerConfig {
+            start_block: Some(100),
+            stop_block: Some(200),
+            ..Default::default()
+        };
+        let mode = |start_mode| IndexerConfig { start_mode, ..config.clone() };
+
+        assert_eq!(config.start_mode(), StartMode::FromCursor);
+        assert_eq!(mode(StartModeConfig::Block).start_mode(), StartMode::FromBlock(100));
+        assert_eq!(mode(StartModeConfig::Tail).start_mode(), StartMode::FromTail);
+        assert_eq!(mode(StartModeConfig::Replay).start_mode(), StartMode::Replay(100..200));
+        assert_eq!(StartModeConfig::parse(" Replay "), Some(StartModeConfig::Replay));
+        assert_eq!(StartModeConfig::parse("head"), None);
+    }
+
+    #[test]
+    fn test_validate_rejects_start_mode_without_blocks() {
+        let block = IndexerConfig {
+            start_mode: StartModeConfig::Block,
+            ..Default::default()
+        };
+        let replay = IndexerConfig {
+            start_mode: StartModeConfig::Replay,
+            start_block: Some(100),
+            ..Default::default()
+        };
+        let tail = IndexerConfig {
+            start_mode: StartModeConfig::Tail,
+            ..Default::default()
+        };
+
+        assert!(matches!(block.validate(), Err(IndexingError::Config(_))));
+        assert!(matches!(replay.validate(), Err(IndexingError::Config(_))));
+        assert!(tail.validate().is_ok());
+    }
+
+    #[test]
+    fn test_from_file_start_mode() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "start_mode": "tail" }"#).expect("Failed to write to temp file");
+
+        let config = IndexerConfig::from_file(file.path()).unwrap();
+
+        assert_eq!(config.start_mode, StartModeConfig::Tail);
+    }
+
+    #[test]
+    fn test_activity_retention() {
+        let config = IndexerConfig {
+            activity_retention_hours: 24,
+            ..Default::default()
+        };
+        let disabled = IndexerConfig {
+            activity_retention_hours: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(config.activity_retention(), Some(Duration::from_secs(24 * 3600)));
+        assert_eq!(disabled.activity_retention(), None);
+        assert!(disabled.validate().is_ok());
+    }
+
+    #[test]
+    fn test_filter_reload_interval() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "filter_file": "./filter.json", "filter_reload_interval_secs": 10 }"#)
+            .expect("Failed to write to temp file");
+
+        let config = IndexerConfig::from_file(file.path()).unwrap();
+        let never = IndexerConfig {
+            filter_reload_interval_secs: 0,
+            ..config.clone()
+        };
+
+        assert_eq!(config.filter_file.as_deref(), Some("./filter.json"));
+        assert_eq!(config.filter_reload_interval(), Some(Duration::from_secs(10)));
+        assert_eq!(never.filter_reload_interval(), None);
+        assert_eq!(IndexerConfig::default().filter_reload_interval(), None);
+    }
+
+    #[test]
+    fn test_retry_config() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "loader_max_retries": 0, "retry_initial_backoff_ms": 100, "retry_max_backoff_ms": 1000 }"#)
+            .expect("Failed to write to temp file");
+
+        let config = IndexerConfig::from_file(file.path()).unwrap();
+        let retry_config = config.retry_config();
+        let no_backoff = IndexerConfig {
+            retry_initial_backoff_ms: 0,
+            ..Default::default()
+        };
+        let inverted_backoff = IndexerConfig {
+            retry_initial_backoff_ms: 1000,
+            retry_max_backoff_ms: 100,
+            ..Default::default()
+        };
+
+        assert_eq!(retry_config.consumer.max_retries, 5);
+        assert_eq!(retry_config.loader.max_retries, 0);
+        assert_eq!(retry_config.loader.initial_backoff, Duration::from_millis(100));
+        assert_eq!(retry_config.consumer.max_backoff, Duration::from_secs(1));
+        assert!(config.validate().is_ok());
+        assert!(matches!(no_backoff.validate(), Err(IndexingError::Config(_))));
+        assert!(matches!(inverted_backoff.validate(), Err(IndexingError::Config(_))));
+    }
+
+    #[test]
+    fn test_batch_config() {
+        let config = IndexerConfig {
+            batch_max_blocks: 10,
+            batch_max_actions: 500,
+            batch_flush_interval_ms: 250,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.batch_config(),
+            BatchConfig {
+                max_blocks: 10,
+                max_actions: 500,
+                flush_interval: Duration::from_millis(250),
+            }
+        );
+    }
+}
@@ -2,5 +2,8 @@
 //! Defines and manages application-wide settings and dependencies.
 mod dependencies;
 mod handlers;
+mod indexer_config;
 
-pub use dependencies::Dependencies;
+pub use dependencies::{actions_processor, Dependencies};
+pub use indexer_config::{IndexerConfig, StartModeConfig};
+pub(crate) use indexer_config::is_identifier;
@@ -0,0 +1,81 @@
+//! Kafka emitter of the vote count changes, enabled by the `kafka` feature.
+//!
+//! Publishes the `VotesCountChanged` messages of every persisted batch to a Kafka
+//! topic, keyed by object so the changes of an object stay ordered within its
+//! partition. The producer is configured with `hermes-kafka`, reading
+//! `KAFKA_BROKER`, `KAFKA_USERNAME`, `KAFKA_PASSWORD` and `KAFKA_SSL_CA_PEM`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let emitter = KafkaVotesCountEmitter::from_env("actions.votes_count")?;
+//! let orchestrator = orchestrator.with_votes_count_emitter(Box::new(emitter));
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use actions_indexer_pipeline::emitter::VotesCountEmitter;
+use actions_indexer_pipeline::errors::EmitterError;
+use async_trait::async_trait;
+use hermes_kafka::{create_producer_with_config, BaseProducer, BaseRecord, Producer, ProducerConfig};
+use hermes_schema::pb::actions::VotesCountChanged;
+use prost::Message;
+
+use crate::errors::IndexingError;
+
+/// Broker used when `KAFKA_BROKER` isn't set.
+const DEFAULT_KAFKA_BROKER: &str = "localhost:9092";
+/// Client ID of the producer.
+const CLIENT_ID: &str = "actions-indexer";
+/// Maximum wait for the messages of a batch to be delivered.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Publishes vote count changes to a Kafka topic.
+pub struct KafkaVotesCountEmitter {
+    producer: Arc<BaseProducer>,
+    topic: String,
+}
+
+impl KafkaVotesCountEmitter {
+    /// Create an emitter publishing to `topic` with the given producer.
+    pub fn new(producer: BaseProducer, topic: impl Into<String>) -> Self {
+        Self {
+            producer: Arc::new(producer),
+            topic: topic.into(),
+        }
+    }
+
+    /// Create an emitter publishing to `topic` with a producer configured from the
+    /// `KAFKA_*` environment variables.
+    pub fn from_env(topic: impl Into<String>) -> Result<Self, IndexingError> {
+        let config = ProducerConfig::from_env(DEFAULT_KAFKA_BROKER, CLIENT_ID);
+        let producer = create_producer_with_config(&config)
+            .map_err(|e| IndexingError::Config(format!("creating the Kafka producer for {}: {}", config.broker, e)))?;
+        Ok(Self::new(producer, topic))
+    }
+}
+
+#[async_trait]
+impl VotesCountEmitter for KafkaVotesCountEmitter {
+    /// Sends every change, then waits for the batch to be delivered.
+    async fn emit(&self, changes: &[VotesCountChanged]) -> Result<(), EmitterError> {
+        let records: Vec<(Vec<u8>, Vec<u8>)> = changes
+            .iter()
+            .map(|change| (change.object_id.clone(), change.encode_to_vec()))
+            .collect();
+        let producer = self.producer.clone();
+        let topic = self.topic.clone();
+
+        // Flushing blocks until the messages are delivered
+        tokio::task::spawn_blocking(move || {
+            for (key, payload) in &records {
+                let record = BaseRecord::to(&topic).key(key).payload(payload);
+                producer.send(record).map_err(|(e, _)| EmitterError::Publish(e.to_string()))?;
+            }
+            producer.flush(FLUSH_TIMEOUT).map_err(|e| EmitterError::Publish(e.to_string()))
+        })
+        .await
+        .map_err(|e| EmitterError::Publish(e.to_string()))?
+    }
+}
@@ -1,6 +1,9 @@
 //! Error types for the Actions Indexer application.
 //! Defines a comprehensive set of errors that can occur during the indexing process,
-//! consolidating errors from various modules like the orchestrator.
+//! consolidating errors from various modules like the orchestrator, and classifies
+//! them as retryable or fatal like the pipeline errors they wrap.
+use actions_indexer_pipeline::errors::{Classify, ErrorClass};
+
 #[derive(Debug, thiserror::Error)]
 pub enum IndexingError {
     #[error("Orchestrator error: {0}")]
@@ -11,4 +14,24 @@ pub enum IndexingError {
     ActionsRepository(#[from] actions_indexer_repository::ActionsRepositoryError),
     #[error("Cursor repository error: {0}")]
     CursorRepository(#[from] actions_indexer_repository::CursorRepositoryError),
+    #[error("Activity repository error: {0}")]
+    ActivityRepository(#[from] actions_indexer_repository::ActivityRepositoryError),
+    #[error("Backfill repository error: {0}")]
+    BackfillRepository(#[from] actions_indexer_repository::BackfillRepositoryError),
+    #[error("Configuration error: {0}")]
+    Config(String),
+}
+
+impl Classify for IndexingError {
+    fn class(&self) -> ErrorClass {
+        match self {
+            IndexingError::Orchestrator(e) => e.class(),
+            IndexingError::Database(e) => e.class(),
+            IndexingError::ActionsRepository(e) => e.class(),
+            IndexingError::CursorRepository(e) => e.class(),
+            IndexingError::ActivityRepository(e) => e.class(),
+            IndexingError::BackfillRepository(e) => e.class(),
+            IndexingError::Config(_) => ErrorClass::Fatal,
+        }
+    }
 }
@@ -0,0 +1,65 @@
+//! Prometheus exporter for the indexer, enabled by the `exporter` feature.
+//!
+//! Serves the [`PipelineMetrics`] the orchestrator records into on `GET /metrics`,
+//! in the Prometheus text format written by [`metrics::render`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! tokio::spawn(exporter::serve(dependencies.metrics.clone(), "0.0.0.0:9090".parse()?));
+//! ```
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use actions_indexer_pipeline::metrics::{self, PipelineMetrics, CONTENT_TYPE};
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+
+/// Build the `/metrics` route over the pipeline metrics.
+pub fn router(metrics: Arc<PipelineMetrics>) -> Router {
+    Router::new()
+        .route("/metrics", get(get_metrics))
+        .with_state(metrics)
+}
+
+/// Serve `/metrics` on `addr` until the process exits.
+pub async fn serve(metrics: Arc<PipelineMetrics>, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Serving metrics on {addr}");
+    axum::serve(listener, router(metrics)).await
+}
+
+async fn get_metrics(State(metrics): State<Arc<PipelineMetrics>>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics::render(&metrics))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_get_metrics_serves_the_text_format() {
+        let metrics = Arc::new(PipelineMetrics::default());
+        metrics.record_head_block(100);
+        metrics.record_persisted(1, 4, Some(100));
+
+        let response = router(metrics)
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("\nactions_indexer_actions_processed_total 4\n"));
+        assert!(body.contains("\nactions_indexer_cursor_lag_blocks 0\n"));
+    }
+}
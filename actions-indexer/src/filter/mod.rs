@@ -0,0 +1,169 @@
+//! Hot reload of the consumer's allow and deny lists.
+//!
+//! The lists are read from a JSON file when the indexer starts. The `FilterReloader`
+//! then checks the file for changes on an interval and swaps the rules of the
+//! `ActionFilter` in place, so a spammer can be blocked without restarting the indexer.
+use actions_indexer_pipeline::consumer::filter::{ActionFilter, FilterRules};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use crate::errors::IndexingError;
+
+/// Reads the allow and deny lists from a JSON file, see [`FilterRules`].
+///
+/// # Arguments
+///
+/// * `path` - The path of the JSON file
+///
+/// # Returns
+///
+/// A `Result` with the `FilterRules` of the file, or an `IndexingError::Config` if it
+/// can't be read or parsed.
+pub fn load_filter_rules(path: impl AsRef<Path>) -> Result<FilterRules, IndexingError> {
+    let path = path.as_ref();
+    let content = std::fs::read(path)
+        .map_err(|e| IndexingError::Config(format!("reading {}: {}", path.display(), e)))?;
+
+    serde_json::from_slice(&content)
+        .map_err(|e| IndexingError::Config(format!("parsing {}: {}", path.display(), e)))
+}
+
+/// `FilterReloader` reloads the rules of an `ActionFilter` when their file changes.
+///
+/// Changes are detected by the modification time of the file. If the new file can't
+/// be read or parsed, the current rules are kept until it is fixed.
+pub struct FilterReloader {
+    filter: Arc<ActionFilter>,
+    path: PathBuf,
+    interval: Duration,
+    modified: Option<SystemTime>,
+}
+
+impl FilterReloader {
+    /// Creates a new `FilterReloader` for a filter whose rules were just read from `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - The `ActionFilter` of the consumer
+    /// * `path` - The JSON file the rules are read from
+    /// * `interval` - How often the file is checked for changes
+    ///
+    /// # Returns
+    ///
+    /// A new `FilterReloader` instance.
+    pub fn new(filter: Arc<ActionFilter>, path: impl Into<PathBuf>, interval: Duration) -> Self {
+        let path = path.into();
+        let modified = modified_at(&path);
+        Self {
+            filter,
+            path,
+            interval,
+            modified,
+        }
+    }
+
+    /// Reloads the rules if the file was modified since they were last read.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok(true)` if the rules were reloaded, `Ok(false)` if the
+    /// file didn't change, or an `IndexingError::Config` if it can't be read or parsed.
+    pub fn reload_if_changed(&mut self) -> Result<bool, IndexingError> {
+        let modified = modified_at(&self.path);
+        if modified == self.modified {
+            return Ok(false);
+        }
+
+        let rules = load_filter_rules(&self.path)?;
+        self.filter.reload(rules);
+        self.modified = modified;
+        Ok(true)
+    }
+
+    /// Checks the file for changes on every interval, forever.
+    ///
+    /// Failures are logged and don't stop the reloader.
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            match self.reload_if_changed() {
+                Ok(true) => println!("Reloaded the action filter from {}", self.path.display()),
+                Ok(false) => {}
+                Err(e) => eprintln!("Failed to reload the action filter: {:?}", e),
+            }
+        }
+    }
+}
+
+/// Modification time of a file, or `None` if it can't be read.
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions_indexer_pipeline::metrics::PipelineMetrics;
+    use alloy::primitives::Address;
+    use std::io::{Seek, Write};
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_load_filter_rules() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "deny": { "senders": ["0x000000000000000000000000000000000000dead"] } }"#)
+            .expect("Failed to write to temp file");
+
+        let rules = load_filter_rules(file.path()).unwrap();
+
+        let dead: Address = "0x000000000000000000000000000000000000dead".parse().unwrap();
+        assert_eq!(rules.deny.senders, [dead].into());
+        assert!(rules.allow.senders.is_empty());
+        assert!(rules.deny.spaces.is_empty());
+    }
+
+    #[test]
+    fn test_load_filter_rules_invalid() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(br#"{ "deny": { "senders": ["not an address"] } }"#).expect("Failed to write to temp file");
+
+        assert!(matches!(load_filter_rules(file.path()), Err(IndexingError::Config(_))));
+        assert!(matches!(load_filter_rules("./missing-filter.json"), Err(IndexingError::Config(_))));
+    }
+
+    #[test]
+    fn test_reload_if_changed() {
+        let mut file = NamedTempFile::new().expect("Failed to create temp file");
+        file.write_all(b"{}").expect("Failed to write to temp file");
+        let filter = Arc::new(ActionFilter::new(
+            load_filter_rules(file.path()).unwrap(),
+            Arc::new(PipelineMetrics::default()),
+        ));
+        let mut reloader = FilterReloader::new(filter.clone(), file.path(), Duration::from_secs(30));
+
+        assert!(!reloader.reload_if_changed().unwrap());
+
+        let rewrite = |file: &mut NamedTempFile, content: &[u8], modified: SystemTime| {
+            file.as_file().set_len(0).unwrap();
+            file.rewind().unwrap();
+            file.write_all(content).unwrap();
+            file.as_file().set_modified(modified).unwrap();
+        };
+
+        // A broken file keeps the current rules
+        rewrite(&mut file, b"{ not json", SystemTime::now() + Duration::from_secs(10));
+        assert!(matches!(reloader.reload_if_changed(), Err(IndexingError::Config(_))));
+        assert!(filter.rules().is_empty());
+
+        rewrite(
+            &mut file,
+            br#"{ "deny": { "senders": ["0x0000000000000000000000000000000000000001"] } }"#,
+            SystemTime::now() + Duration::from_secs(20),
+        );
+        assert!(reloader.reload_if_changed().unwrap());
+        assert!(filter.rules().deny.senders.contains(&Address::with_last_byte(1)));
+        assert!(!reloader.reload_if_changed().unwrap());
+    }
+}
@@ -1,10 +1,25 @@
 //! Actions Indexer Library
 //! 
 //! This library provides the core functionality for indexing blockchain actions,
-//! including configuration management, error handling, and dependency injection.
+//! including configuration management, error handling, dependency injection, vote
+//! count reconciliation, dead letter reprocessing, backfills staged in a schema of
+//! their own, the metrics exporter, the Kafka emitter of vote count changes and the
+//! hot reload of the consumer's allow and deny lists.
 
+pub mod backfill;
 pub mod config;
+pub mod dead_letters;
+#[cfg(feature = "kafka")]
+pub mod emitter;
 pub mod errors;
+#[cfg(feature = "exporter")]
+pub mod exporter;
+pub mod filter;
+pub mod reconciliation;
 
-pub use config::Dependencies;
+pub use backfill::{Backfill, BackfillReport};
+pub use config::{Dependencies, IndexerConfig};
+pub use dead_letters::{DeadLetterReprocessor, ReprocessReport};
 pub use errors::IndexingError;
+pub use filter::FilterReloader;
+pub use reconciliation::{ReconciliationConfig, VoteCountReconciler};
@@ -1,11 +1,14 @@
 use actions_indexer::{Dependencies, IndexingError};
 use dotenv::dotenv;
+use actions_indexer_pipeline::errors::Classify;
 use actions_indexer_pipeline::orchestrator::Orchestrator;
 
 /// Main entry point for the Actions Indexer application.
 ///
-/// Initializes dotenv, sets up application dependencies, and starts the
-/// orchestrator to process actions.
+/// Initializes dotenv, sets up application dependencies, starts the vote count
+/// reconciler, the filter reloader and the metrics exporter in the background and the orchestrator to
+/// process actions until Ctrl-C or SIGTERM, which drains and persists the pending
+/// blocks before exiting, or until a fatal error, which is logged with its class.
 ///
 /// # Returns
 ///
@@ -16,12 +19,49 @@ async fn main() -> Result<(), IndexingError> {
     dotenv().ok();
 
     let dependencies = Dependencies::new().await?;
+    // Held until the indexer exits, so dead letters aren't reprocessed meanwhile
+    let _writers_lock = dependencies.writers_lock;
 
-    let orchestrator = Orchestrator::new(
+    if let Some(reconciler) = dependencies.reconciler {
+        tokio::spawn(reconciler.run());
+    }
+
+    if let Some(filter_reloader) = dependencies.filter_reloader {
+        tokio::spawn(filter_reloader.run());
+    }
+
+    // Export the pipeline metrics to Prometheus when an address is configured
+    #[cfg(feature = "exporter")]
+    if let Some(addr) = dependencies.metrics_addr {
+        let metrics = dependencies.metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = actions_indexer::exporter::serve(metrics, addr).await {
+                eprintln!("Metrics exporter error: {:?}", e);
+            }
+        });
+    }
+
+    let mut orchestrator = Orchestrator::new(
         dependencies.consumer,
         dependencies.processor,
         dependencies.loader,
-    );
-    orchestrator.run().await?;
+    )
+    .with_batch_config(dependencies.batch_config)
+    .with_metrics(dependencies.metrics)
+    .with_start_mode(dependencies.start_mode)
+    .with_retry_config(dependencies.retry_config);
+    if let Some(activity_aggregator) = dependencies.activity_aggregator {
+        orchestrator = orchestrator.with_activity_aggregator(activity_aggregator);
+    }
+    if let Some(votes_count_emitter) = dependencies.votes_count_emitter {
+        orchestrator = orchestrator.with_votes_count_emitter(votes_count_emitter);
+    }
+    if let Some(namespace) = &dependencies.namespace {
+        orchestrator = orchestrator.with_namespace(namespace);
+    }
+    if let Err(e) = orchestrator.run().await {
+        eprintln!("Indexer stopped on a {:?} error: {}", e.class(), e);
+        return Err(e.into());
+    }
     Ok(())
 }
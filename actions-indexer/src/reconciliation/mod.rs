@@ -0,0 +1,190 @@
+//! Vote count reconciliation for the Actions Indexer.
+//!
+//! The loader keeps vote counts up to date incrementally, so they can drift from the
+//! user votes after a crash or a partial failure. The `VoteCountReconciler` periodically
+//! recomputes a sliding window of vote counts from the user votes, repairs the ones that
+//! drifted and logs how far off they were.
+use actions_indexer_repository::ActionsRepository;
+use actions_indexer_shared::types::{VoteCountCriteria, VotesCountReconciliation};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::errors::IndexingError;
+
+/// How often vote counts are reconciled and how many are checked each time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconciliationConfig {
+    pub interval: Duration,
+    pub page_size: usize,
+}
+
+/// Drift metrics of a reconciliation pass.
+///
+/// `upvotes_drift` and `downvotes_drift` add up how far each repaired count was from
+/// the user votes, in either direction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriftReport {
+    pub checked: usize,
+    pub repaired: usize,
+    pub upvotes_drift: i64,
+    pub downvotes_drift: i64,
+}
+
+impl DriftReport {
+    /// Summarizes the vote counts checked and repaired by the repository.
+    pub fn new(reconciliation: &VotesCountReconciliation) -> Self {
+        reconciliation.repaired.iter().fold(
+            Self {
+                checked: reconciliation.checked,
+                ..Default::default()
+            },
+            |mut report, drift| {
+                report.repaired += 1;
+                report.upvotes_drift += (drift.expected.upvotes - drift.stored_upvotes).abs();
+                report.downvotes_drift += (drift.expected.downvotes - drift.stored_downvotes).abs();
+                report
+            },
+        )
+    }
+
+    /// Logs the drift found by the pass.
+    pub fn log(&self) {
+        println!(
+            "Reconciled {} vote counts: {} repaired, upvotes drift {}, downvotes drift {}",
+            self.checked,
+            self.repaired,
+            self.upvotes_drift,
+            self.downvotes_drift,
+        );
+    }
+}
+
+/// `VoteCountReconciler` repairs vote counts that drifted from the user votes.
+///
+/// Each pass checks the next page of vote counts, so the whole table is covered a
+/// page at a time and the cost of a pass doesn't grow with the number of objects.
+/// After the last page it starts over from the first one.
+pub struct VoteCountReconciler {
+    actions_repository: Arc<dyn ActionsRepository>,
+    config: ReconciliationConfig,
+    next_after: Option<VoteCountCriteria>,
+}
+
+impl VoteCountReconciler {
+    /// Creates a new `VoteCountReconciler` starting from the first page.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions_repository` - The repository holding the vote counts and user votes
+    /// * `config` - The reconciliation interval and page size
+    ///
+    /// # Returns
+    ///
+    /// A new `VoteCountReconciler` instance.
+    pub fn new(actions_repository: Arc<dyn ActionsRepository>, config: ReconciliationConfig) -> Self {
+        Self {
+            actions_repository,
+            config,
+            next_after: None,
+        }
+    }
+
+    /// Reconciles the next page of vote counts.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the `DriftReport` of the page, or an `IndexingError` if the
+    /// repository fails, in which case the same page is retried on the next pass.
+    pub async fn reconcile_next_page(&mut self) -> Result<DriftReport, IndexingError> {
+        let reconciliation = self
+            .actions_repository
+            .reconcile_vote_counts(self.next_after, self.config.page_size as i64)
+            .await?;
+
+        self.next_after = next_after(&reconciliation, self.config.page_size);
+        Ok(DriftReport::new(&reconciliation))
+    }
+
+    /// Reconciles a page of vote counts on every interval, forever.
+    ///
+    /// Failures are logged and don't stop the reconciler.
+    pub async fn run(mut self) {
+        let mut interval = tokio::time::interval(self.config.interval);
+        loop {
+            interval.tick().await;
+            match self.reconcile_next_page().await {
+                Ok(report) => report.log(),
+                Err(e) => eprintln!("Failed to reconcile vote counts: {:?}", e),
+            }
+        }
+    }
+}
+
+/// Where the page after `reconciliation` starts. A short page is the last one, so
+/// the next pass wraps around to the first page.
+fn next_after(reconciliation: &VotesCountReconciliation, page_size: usize) -> Option<VoteCountCriteria> {
+    if reconciliation.checked < page_size {
+        return None;
+    }
+    reconciliation.last_checked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actions_indexer_shared::types::{ObjectType, VotesCount, VotesCountDrift};
+    use uuid::Uuid;
+
+    fn make_drift(upvotes: i64, downvotes: i64, stored_upvotes: i64, stored_downvotes: i64) -> VotesCountDrift {
+        VotesCountDrift {
+            expected: VotesCount {
+                object_id: Uuid::new_v4(),
+                space_id: Uuid::new_v4(),
+                object_type: ObjectType::Entity,
+                upvotes,
+                downvotes,
+            },
+            stored_upvotes,
+            stored_downvotes,
+        }
+    }
+
+    #[test]
+    fn test_drift_report_adds_up_drift_in_both_directions() {
+        let reconciliation = VotesCountReconciliation {
+            checked: 10,
+            last_checked: None,
+            repaired: vec![make_drift(3, 1, 5, 0), make_drift(0, 2, 0, 4)],
+        };
+
+        let report = DriftReport::new(&reconciliation);
+
+        assert_eq!(
+            report,
+            DriftReport {
+                checked: 10,
+                repaired: 2,
+                upvotes_drift: 2,
+                downvotes_drift: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_next_after_wraps_around_after_last_page() {
+        let last_checked = Some((Uuid::new_v4(), Uuid::new_v4(), ObjectType::Relation));
+        let full_page = VotesCountReconciliation {
+            checked: 2,
+            last_checked,
+            repaired: vec![],
+        };
+        let short_page = VotesCountReconciliation {
+            checked: 1,
+            ..full_page.clone()
+        };
+
+        assert_eq!(next_after(&full_page, 2), last_checked);
+        assert_eq!(next_after(&short_page, 2), None);
+        assert_eq!(next_after(&VotesCountReconciliation::default(), 2), None);
+    }
+}
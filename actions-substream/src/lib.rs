@@ -1,54 +1,211 @@
+// The wasm entrypoints generated for handlers with params read them from a raw pointer
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
 mod pb;
-use hex_literal::hex;
-use pb::actions::v1::{Action, Actions};
+use pb::actions::v1::action::Payload;
+use pb::actions::v1::{
+    Action, ActionStats, ActionTypeCount, Actions, DecodeFailure, DecodeFailures, GroupActions, GroupedActions,
+    VoteDirection, VoteMetadata,
+};
 use pb::sf::ethereum::r#type::v2::{Block, Log};
+use substreams::errors::Error;
+use substreams::pb::substreams::Clock;
 
 substreams_ethereum::init!();
 
-const FACTORY_TRACKED_CONTRACTS: &[[u8; 20]] = &[hex!("80eF8d87fafCB65F5399c6d28c72A27577616339")];
+/// Prefix of each contract in the `map_actions` params, which double as the block filter query.
+const EVT_ADDR_PREFIX: &str = "evt_addr:";
+
+/// Tracks the contracts in `params`, e.g. `evt_addr:0x80ef... || evt_addr:0x1234...`.
+///
+/// The params are set in `substreams.yaml` and can be overridden by the sink, so moving to a new
+/// actions contract doesn't require a new package.
+#[substreams::handlers::map]
+fn map_actions(params: String, blk: Block) -> Result<Actions, Error> {
+    let tracked_contracts = parse_tracked_contracts(&params)?;
+
+    Ok(decode_block(&tracked_contracts, &blk).0)
+}
+
+/// The logs of the contracts in `params` that couldn't be decoded as actions, which
+/// `map_actions` skips. Takes the same params as `map_actions`.
+#[substreams::handlers::map]
+fn map_decode_failures(params: String, blk: Block) -> Result<DecodeFailures, Error> {
+    let tracked_contracts = parse_tracked_contracts(&params)?;
+
+    Ok(decode_block(&tracked_contracts, &blk).1)
+}
+
+/// Counts the actions of `map_actions` per action and object type, so monitoring can chart
+/// action volume without running the actions indexer.
+#[substreams::handlers::map]
+fn map_action_stats(clock: Clock, actions: Actions) -> Result<ActionStats, Error> {
+    let block_timestamp = clock.timestamp.map(|t| t.seconds as u64).unwrap_or(0);
+
+    Ok(action_stats(clock.number, block_timestamp, &actions.actions))
+}
+
+fn action_stats(block_number: u64, block_timestamp: u64, actions: &[Action]) -> ActionStats {
+    let mut counts: BTreeMap<(u64, u64), u64> = BTreeMap::new();
+    for action in actions {
+        *counts.entry((action.action_type, action.object_type)).or_default() += 1;
+    }
+    let senders: HashSet<&str> = actions.iter().map(|action| action.sender.as_str()).collect();
+
+    ActionStats {
+        block_number,
+        block_timestamp,
+        total_actions: actions.len() as u64,
+        unique_senders: senders.len() as u64,
+        counts: counts
+            .into_iter()
+            .map(|((action_type, object_type), count)| ActionTypeCount { action_type, object_type, count })
+            .collect(),
+    }
+}
 
+/// Buckets the actions of `map_actions` by `group_id`, so group consumers don't have to
+/// filter every action. Actions without a group are left out.
 #[substreams::handlers::map]
-fn map_actions(blk: Block) -> Result<Actions, substreams::errors::Error> {
+fn map_group_actions(actions: Actions) -> Result<GroupedActions, Error> {
+    Ok(GroupedActions { groups: group_actions(actions.actions) })
+}
+
+fn group_actions(actions: Vec<Action>) -> Vec<GroupActions> {
+    let mut groups: Vec<GroupActions> = Vec::new();
+
+    for action in actions {
+        let Some(group_id) = action.group_id.clone() else {
+            continue;
+        };
+
+        match groups.iter_mut().find(|group| group.group_id == group_id) {
+            Some(group) => group.actions.push(action),
+            None => groups.push(GroupActions { group_id, actions: vec![action] }),
+        }
+    }
+
+    groups
+}
+
+/// Decodes the logs emitted in `blk` by any of the tracked contracts, into actions and the
+/// logs that failed to decode.
+fn decode_block(tracked_contracts: &[[u8; 20]], blk: &Block) -> (Actions, DecodeFailures) {
     let mut actions = Actions::default();
+    let mut failures = DecodeFailures::default();
 
     for transaction in &blk.transaction_traces {
         if let Some(receipt) = &transaction.receipt {
             let tx_hash = format!("0x{}", hex::encode(&transaction.hash));
             let block_number = blk.number;
-            let block_timestamp = blk.header.as_ref().map(|h| h.timestamp.as_ref().map(|t| t.seconds as u64)).flatten().unwrap_or(0);
+            let block_timestamp = blk.header.as_ref().and_then(|h| h.timestamp.as_ref()).map(|t| t.seconds as u64).unwrap_or(0);
 
             for log in &receipt.logs {
-                if is_address_in_contracts(&log.address) {
-                    if let Some(action) = decode_action_log(&log)? {
-                        let action = Action {
-                            action_type: action.action_type,
-                            action_version: action.action_version,
-                            sender: format!("0x{}", hex::encode(&transaction.from)),
-                            object_id: action.object_id,
-                            group_id: action.group_id,
-                            space_pov: action.space_pov,
-                            metadata: action.metadata,
-                            block_number,
-                            block_timestamp,
-                            tx_hash: tx_hash.clone(),
-                            object_type: action.object_type,
-                        };
-                        actions.actions.push(action);
-                    }
+                if !is_address_in_contracts(tracked_contracts, &log.address) || !is_action_log(log) {
+                    continue;
+                }
+
+                match decode_action_log(log) {
+                    Ok(action) => actions.actions.push(Action {
+                        payload: decode_payload(action.action_type, action.metadata.as_deref()),
+                        action_type: action.action_type,
+                        action_version: action.action_version,
+                        sender: format!("0x{}", hex::encode(&transaction.from)),
+                        object_id: action.object_id,
+                        group_id: action.group_id,
+                        space_pov: action.space_pov,
+                        metadata: action.metadata,
+                        block_number,
+                        block_timestamp,
+                        tx_hash: tx_hash.clone(),
+                        object_type: action.object_type,
+                        contract_address: format!("0x{}", hex::encode(&log.address)),
+                        log_index: Some(log.block_index),
+                        output_position: None,
+                    }),
+                    Err(e) => failures.failures.push(DecodeFailure {
+                        contract_address: format!("0x{}", hex::encode(&log.address)),
+                        tx_hash: tx_hash.clone(),
+                        block_number,
+                        log_index: log.block_index,
+                        topics: log.topics.clone(),
+                        data: log.data.clone(),
+                        reason: e.to_string(),
+                    }),
                 }
             }
         }
     }
 
-    Ok(actions)
+    (actions, failures)
 }
 
-fn is_address_in_contracts(address: &Vec<u8>) -> bool {
+fn is_address_in_contracts(tracked_contracts: &[[u8; 20]], address: &Vec<u8>) -> bool {
     if address.len() != 20 {
         return false;
     }
 
-    FACTORY_TRACKED_CONTRACTS.contains(&address.as_slice().try_into().unwrap())
+    tracked_contracts.contains(&address.as_slice().try_into().unwrap())
+}
+
+/// Parse the contract addresses from the module params, in block filter query format.
+fn parse_tracked_contracts(params: &str) -> Result<Vec<[u8; 20]>, Error> {
+    let contracts = params
+        .split("||")
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .map(|term| {
+            let address = term.strip_prefix(EVT_ADDR_PREFIX).ok_or_else(|| {
+                Error::msg(format!("invalid contract filter '{}', expected '{}0x...'", term, EVT_ADDR_PREFIX))
+            })?;
+            let bytes = hex::decode(address.trim_start_matches("0x"))
+                .map_err(|e| Error::msg(format!("invalid contract address '{}': {}", address, e)))?;
+
+            <[u8; 20]>::try_from(bytes.as_slice())
+                .map_err(|_| Error::msg(format!("invalid contract address '{}': expected 20 bytes", address)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if contracts.is_empty() {
+        return Err(Error::msg("no tracked contracts in params"));
+    }
+
+    Ok(contracts)
+}
+
+/// Version of the action encoding read from the first word of the log data.
+const ACTION_VERSION_V1: u64 = 1;
+/// Size of an ABI word.
+const WORD_SIZE: usize = 32;
+/// Size of the head of a v1 action: the packed IDs, the sender and the metadata offset.
+const V1_HEAD_SIZE: usize = 4 * WORD_SIZE;
+/// Size of a v1 action with empty metadata: the head and the metadata length.
+const V1_MIN_SIZE: usize = V1_HEAD_SIZE + WORD_SIZE;
+
+/// Why a log of a tracked contract couldn't be decoded as an action.
+#[derive(Debug, PartialEq)]
+enum DecodeError {
+    /// The log data is shorter than its version requires.
+    TooShort { len: usize, expected: usize },
+    /// There's no decoder for the action version.
+    UnsupportedVersion(u64),
+    /// The log data doesn't have the ABI layout of its version.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::TooShort { len, expected } => {
+                write!(f, "log data has {} bytes, expected at least {}", len, expected)
+            }
+            DecodeError::UnsupportedVersion(version) => write!(f, "unsupported action version {}", version),
+            DecodeError::Malformed(reason) => write!(f, "malformed action: {}", reason),
+        }
+    }
 }
 
 /// Decoded event data from the Action event log
@@ -62,17 +219,29 @@ struct EventData {
     metadata: Option<Vec<u8>>,
 }
 
-/// Decode an Action event log into an Action message
-/// Based on the Action event signature from the smart contract
-fn decode_action_log(log: &Log) -> Result<Option<EventData>, substreams::errors::Error> {
+/// Decode an Action event log, dispatching on the action version in its first word.
+fn decode_action_log(log: &Log) -> Result<EventData, DecodeError> {
     let data = &log.data;
-    if data.len() < 128 {
-        return Ok(None);
+    if data.len() < WORD_SIZE {
+        return Err(DecodeError::TooShort { len: data.len(), expected: WORD_SIZE });
+    }
+
+    match u16::from_be_bytes([data[2], data[3]]) as u64 {
+        ACTION_VERSION_V1 => decode_v1(data),
+        // New encodings get their own decoder, keyed on their version
+        version => Err(DecodeError::UnsupportedVersion(version)),
+    }
+}
+
+/// Decode a v1 action, based on the Action event signature from the smart contract
+fn decode_v1(data: &[u8]) -> Result<EventData, DecodeError> {
+    if data.len() < V1_MIN_SIZE {
+        return Err(DecodeError::TooShort { len: data.len(), expected: V1_MIN_SIZE });
     }
+    let metadata = decode_v1_metadata(data)?;
 
     let word0 = &data[0..32];
     let action_type = u16::from_be_bytes([word0[0], word0[1]]) as u64;
-    let action_version = u16::from_be_bytes([word0[2], word0[3]]) as u64;
     let object_type = (word0[15] & 0x0F) as u64;
     let space_uuid_bytes = &word0[16..32];
     
@@ -108,44 +277,86 @@ fn decode_action_log(log: &Log) -> Result<Option<EventData>, substreams::errors:
         ))
     };
 
-    let metadata = if data.len() < 128 {
-        None
-    } else {
-        let payload_offset = u64::from_be_bytes([
-            data[120], data[121], data[122], data[123], data[124], data[125], data[126], data[127],
-        ]) as usize;
-        
-        if payload_offset + 32 <= data.len() {
-            let payload_length = u64::from_be_bytes([
-                data[payload_offset + 24], data[payload_offset + 25], data[payload_offset + 26], data[payload_offset + 27],
-                data[payload_offset + 28], data[payload_offset + 29], data[payload_offset + 30], data[payload_offset + 31],
-            ]) as usize;
-            
-            if payload_length > 0 && data.len() >= payload_offset + 32 + payload_length {
-                Some(data[payload_offset + 32..payload_offset + 32 + payload_length].to_vec())
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    };
-
-    Ok(Some(EventData {
+    Ok(EventData {
         action_type,
-        action_version,
+        action_version: ACTION_VERSION_V1,
         object_type,
         object_id,
         group_id,
         space_pov,
         metadata,
-    }))
+    })
+}
+
+/// Action type of votes.
+const ACTION_TYPE_VOTE: u64 = 0;
+
+/// Decodes the metadata of an action with the payload schema of its type. The raw metadata
+/// is emitted too, so types and payloads without a schema yet still reach consumers.
+fn decode_payload(action_type: u64, metadata: Option<&[u8]>) -> Option<Payload> {
+    match action_type {
+        // The vote direction is the first metadata byte
+        ACTION_TYPE_VOTE => {
+            let direction = match metadata?.first()? {
+                0 => VoteDirection::Up,
+                1 => VoteDirection::Down,
+                2 => VoteDirection::Remove,
+                _ => return None,
+            };
+            Some(Payload::Vote(VoteMetadata { direction: direction as i32 }))
+        }
+        _ => None,
+    }
+}
+
+/// Checks the ABI layout of the tail of a v1 action, the padded sender and the `bytes`
+/// metadata right after the head, and returns the metadata. It's missing when empty.
+fn decode_v1_metadata(data: &[u8]) -> Result<Option<Vec<u8>>, DecodeError> {
+    if !is_padded(&data[2 * WORD_SIZE..3 * WORD_SIZE], 20) {
+        return Err(DecodeError::Malformed("sender isn't a padded address"));
+    }
+    if read_word(&data[3 * WORD_SIZE..V1_HEAD_SIZE]) != Some(V1_HEAD_SIZE) {
+        return Err(DecodeError::Malformed("metadata doesn't start after the head"));
+    }
+
+    let length = read_word(&data[V1_HEAD_SIZE..V1_MIN_SIZE])
+        .ok_or(DecodeError::Malformed("metadata length overflows"))?;
+    let padded_length = length
+        .checked_next_multiple_of(WORD_SIZE)
+        .and_then(|padded_length| padded_length.checked_add(V1_MIN_SIZE));
+    if padded_length != Some(data.len()) {
+        return Err(DecodeError::Malformed("metadata length doesn't match the log data"));
+    }
+
+    Ok((length > 0).then(|| data[V1_MIN_SIZE..V1_MIN_SIZE + length].to_vec()))
+}
+
+/// Reads an ABI word as an offset or length, if it fits in a `usize`.
+fn read_word(word: &[u8]) -> Option<usize> {
+    if !is_padded(word, 8) {
+        return None;
+    }
+
+    usize::try_from(u64::from_be_bytes(word[WORD_SIZE - 8..].try_into().ok()?)).ok()
+}
+
+/// Whether only the low `size` bytes of a word are set, as for an address or an integer.
+fn is_padded(word: &[u8], size: usize) -> bool {
+    word[..WORD_SIZE - size].iter().all(|byte| *byte == 0)
+}
+
+/// Whether a log of a tracked contract can be an Action event. The event is anonymous and
+/// has no indexed fields, so its logs have no topics: a log with a topic0 is another event
+/// of the contract, like an upgrade or an ownership transfer, and isn't decoded.
+fn is_action_log(log: &Log) -> bool {
+    log.topics.is_empty()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pb::sf::ethereum::r#type::v2::Log;
+    use hex_literal::hex;
+    use pb::sf::ethereum::r#type::v2::{TransactionReceipt, TransactionTrace};
 
     pub fn log_with_data(data: Vec<u8>) -> Log {
         Log {
@@ -193,7 +404,7 @@ mod tests {
         let log = log_with_data(data);
         let result = decode_action_log(&log);
         assert!(result.is_ok());
-        let event_data = result.unwrap().unwrap();
+        let event_data = result.unwrap();
         assert_eq!(event_data.action_version, 1);
         assert_eq!(event_data.action_type, 0);
         assert_eq!(event_data.object_type, 0);
@@ -210,7 +421,7 @@ mod tests {
         let log = log_with_data(data);
         let result = decode_action_log(&log);
         assert!(result.is_ok());
-        let event_data = result.unwrap().unwrap();
+        let event_data = result.unwrap();
         assert_eq!(event_data.action_version, 1);
         assert_eq!(event_data.action_type, 0);
         assert_eq!(event_data.object_type, 1);
@@ -227,7 +438,7 @@ mod tests {
         let log = log_with_data(data);
         let result = decode_action_log(&log);
         assert!(result.is_ok());
-        let event_data = result.unwrap().unwrap();
+        let event_data = result.unwrap();
         assert_eq!(event_data.action_version, 1);
         assert_eq!(event_data.action_type, 0);
         assert_eq!(event_data.object_type, 0);
@@ -244,7 +455,7 @@ mod tests {
         let log = log_with_data(data);
         let result = decode_action_log(&log);
         assert!(result.is_ok());
-        let event_data = result.unwrap().unwrap();
+        let event_data = result.unwrap();
         assert_eq!(event_data.action_version, 1);
         assert_eq!(event_data.action_type, 0);
         assert_eq!(event_data.object_type, 1);
@@ -253,4 +464,244 @@ mod tests {
         assert_eq!(event_data.object_id, "a8f03660-921b-4f2a-b3c6-e3cb9542749a");
         assert_eq!(event_data.metadata, Some(vec![2]));
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_parse_tracked_contracts() {
+        let contracts = parse_tracked_contracts(
+            "evt_addr:0x80ef8d87fafcb65f5399c6d28c72a27577616339 || evt_addr:0x0000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        assert_eq!(
+            contracts,
+            vec![
+                hex!("80eF8d87fafCB65F5399c6d28c72A27577616339"),
+                hex!("0000000000000000000000000000000000000001"),
+            ]
+        );
+        assert!(is_address_in_contracts(&contracts, &contracts[0].to_vec()));
+        assert!(!is_address_in_contracts(&contracts, &vec![0; 20]));
+    }
+
+    #[test]
+    fn test_parse_tracked_contracts_invalid() {
+        assert!(parse_tracked_contracts("").is_err());
+        assert!(parse_tracked_contracts("0x80ef8d87fafcb65f5399c6d28c72a27577616339").is_err());
+        assert!(parse_tracked_contracts("evt_addr:0x80ef").is_err());
+    }
+
+    #[test]
+    fn test_decode_block_records_log_origin() {
+        let tracked = hex!("80eF8d87fafCB65F5399c6d28c72A27577616339");
+        let data = hex::decode(TEST_CASES[0].chars().filter(|c| !c.is_whitespace()).collect::<String>()).unwrap();
+        let log = |address: [u8; 20]| Log {
+            address: address.to_vec(),
+            block_index: 7,
+            ..log_with_data(data.clone())
+        };
+        let blk = Block {
+            number: 100,
+            transaction_traces: vec![TransactionTrace {
+                receipt: Some(TransactionReceipt {
+                    logs: vec![log(tracked), log([1; 20])],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let actions = decode_block(&[tracked], &blk).0.actions;
+
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].contract_address, "0x80ef8d87fafcb65f5399c6d28c72a27577616339");
+        assert_eq!(actions[0].block_number, 100);
+        assert_eq!(actions[0].log_index, Some(7));
+    }
+
+    #[test]
+    fn test_decode_action_log_rejects_malformed_data() {
+        assert_eq!(
+            decode_action_log(&log_with_data(vec![0; 16])).err(),
+            Some(DecodeError::TooShort { len: 16, expected: 32 })
+        );
+
+        let mut data = vec![0; 128];
+        data[3] = 1;
+        assert_eq!(
+            decode_action_log(&log_with_data(data)).err(),
+            Some(DecodeError::TooShort { len: 128, expected: 160 })
+        );
+
+        let mut data = vec![0; 128];
+        data[3] = 2;
+        assert_eq!(
+            decode_action_log(&log_with_data(data)).err(),
+            Some(DecodeError::UnsupportedVersion(2))
+        );
+    }
+
+    fn v1_data() -> Vec<u8> {
+        hex::decode(TEST_CASES[0].chars().filter(|c| !c.is_whitespace()).collect::<String>()).unwrap()
+    }
+
+    fn malformed(data: Vec<u8>) -> Option<DecodeError> {
+        decode_action_log(&log_with_data(data)).err()
+    }
+
+    #[test]
+    fn test_decode_action_log_rejects_adversarial_payloads() {
+        let mut data = v1_data();
+        data[64] = 1;
+        assert_eq!(malformed(data), Some(DecodeError::Malformed("sender isn't a padded address")));
+
+        // Metadata pointing back into the head
+        let mut data = v1_data();
+        data[127] = 0x20;
+        assert_eq!(malformed(data), Some(DecodeError::Malformed("metadata doesn't start after the head")));
+
+        let mut data = v1_data();
+        data[96] = 1;
+        assert_eq!(malformed(data), Some(DecodeError::Malformed("metadata doesn't start after the head")));
+
+        let mut data = v1_data();
+        data[128..160].copy_from_slice(&[0xff; 32]);
+        assert_eq!(malformed(data), Some(DecodeError::Malformed("metadata length overflows")));
+
+        let mut data = v1_data();
+        data[152..160].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(matches!(malformed(data), Some(DecodeError::Malformed(_))));
+
+        // Metadata running past the data, and trailing bytes after it
+        let mut data = v1_data();
+        data[159] = 33;
+        assert_eq!(malformed(data), Some(DecodeError::Malformed("metadata length doesn't match the log data")));
+
+        let mut data = v1_data();
+        data.extend_from_slice(&[0; 32]);
+        assert_eq!(malformed(data), Some(DecodeError::Malformed("metadata length doesn't match the log data")));
+    }
+
+    #[test]
+    fn test_decode_action_log_empty_metadata() {
+        let mut data = v1_data();
+        data[159] = 0;
+        data.truncate(160);
+
+        assert_eq!(decode_action_log(&log_with_data(data)).unwrap().metadata, None);
+    }
+
+    #[test]
+    fn test_decode_block_reports_failures() {
+        let tracked = hex!("80eF8d87fafCB65F5399c6d28c72A27577616339");
+        let mut unknown_version = vec![0; 128];
+        unknown_version[3] = 2;
+        let blk = Block {
+            number: 100,
+            transaction_traces: vec![TransactionTrace {
+                hash: vec![0xee; 32],
+                receipt: Some(TransactionReceipt {
+                    logs: vec![
+                        Log {
+                            address: tracked.to_vec(),
+                            block_index: 3,
+                            ..log_with_data(unknown_version.clone())
+                        },
+                        Log {
+                            address: [1; 20].to_vec(),
+                            ..log_with_data(vec![])
+                        },
+                        // Another event of the tracked contract
+                        Log {
+                            address: tracked.to_vec(),
+                            topics: vec![vec![0xab; 32]],
+                            ..log_with_data(v1_data())
+                        },
+                    ],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let (actions, failures) = decode_block(&[tracked], &blk);
+
+        assert!(actions.actions.is_empty());
+        assert_eq!(failures.failures.len(), 1);
+        let failure = &failures.failures[0];
+        assert_eq!(failure.contract_address, "0x80ef8d87fafcb65f5399c6d28c72a27577616339");
+        assert_eq!(failure.tx_hash, format!("0x{}", "ee".repeat(32)));
+        assert_eq!(failure.block_number, 100);
+        assert_eq!(failure.log_index, 3);
+        assert_eq!(failure.data, unknown_version);
+        assert_eq!(failure.reason, "unsupported action version 2");
+    }
+
+    #[test]
+    fn test_action_stats() {
+        let action = |action_type, object_type, sender: &str| Action {
+            action_type,
+            object_type,
+            sender: sender.to_string(),
+            ..Default::default()
+        };
+        let actions = vec![
+            action(0, 1, "0x01"),
+            action(0, 0, "0x02"),
+            action(0, 1, "0x01"),
+            action(1, 0, "0x03"),
+        ];
+
+        let stats = action_stats(100, 1_700_000_000, &actions);
+
+        assert_eq!(stats.block_number, 100);
+        assert_eq!(stats.block_timestamp, 1_700_000_000);
+        assert_eq!(stats.total_actions, 4);
+        assert_eq!(stats.unique_senders, 3);
+        assert_eq!(
+            stats.counts,
+            vec![
+                ActionTypeCount { action_type: 0, object_type: 0, count: 1 },
+                ActionTypeCount { action_type: 0, object_type: 1, count: 2 },
+                ActionTypeCount { action_type: 1, object_type: 0, count: 1 },
+            ]
+        );
+        assert_eq!(action_stats(101, 0, &[]).counts, vec![]);
+    }
+
+    #[test]
+    fn test_group_actions() {
+        let action = |object_id: &str, group_id: Option<&str>| Action {
+            object_id: object_id.to_string(),
+            group_id: group_id.map(str::to_string),
+            ..Default::default()
+        };
+        let group_a = "a8f03660-921b-4f2a-b3c6-e3cb9542748d";
+        let group_b = "f8d9744d-f546-45f1-a90d-cbd511c9d600";
+
+        let groups = group_actions(vec![
+            action("1", Some(group_b)),
+            action("2", None),
+            action("3", Some(group_a)),
+            action("4", Some(group_b)),
+        ]);
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].group_id, group_b);
+        assert_eq!(groups[0].actions, vec![action("1", Some(group_b)), action("4", Some(group_b))]);
+        assert_eq!(groups[1].group_id, group_a);
+        assert_eq!(groups[1].actions, vec![action("3", Some(group_a))]);
+    }
+
+    #[test]
+    fn test_decode_payload() {
+        let vote = |direction: VoteDirection| Some(Payload::Vote(VoteMetadata { direction: direction as i32 }));
+
+        assert_eq!(decode_payload(ACTION_TYPE_VOTE, Some(&[0])), vote(VoteDirection::Up));
+        assert_eq!(decode_payload(ACTION_TYPE_VOTE, Some(&[1])), vote(VoteDirection::Down));
+        assert_eq!(decode_payload(ACTION_TYPE_VOTE, Some(&[2, 7])), vote(VoteDirection::Remove));
+        assert_eq!(decode_payload(ACTION_TYPE_VOTE, Some(&[3])), None);
+        assert_eq!(decode_payload(ACTION_TYPE_VOTE, Some(&[])), None);
+        assert_eq!(decode_payload(ACTION_TYPE_VOTE, None), None);
+        assert_eq!(decode_payload(1, Some(&[0])), None);
+    }
+}
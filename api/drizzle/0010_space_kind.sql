@@ -0,0 +1,4 @@
+CREATE TYPE "public"."spaceKinds" AS ENUM('Personal', 'Dao', 'Successor');--> statement-breakpoint
+ALTER TABLE "spaces" ADD COLUMN "space_kind" "spaceKinds";--> statement-breakpoint
+UPDATE "spaces" SET "space_kind" = CASE WHEN "type" = 'Personal' THEN 'Personal'::"spaceKinds" ELSE 'Dao'::"spaceKinds" END;--> statement-breakpoint
+ALTER TABLE "spaces" ALTER COLUMN "space_kind" SET NOT NULL;
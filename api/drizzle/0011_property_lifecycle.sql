@@ -0,0 +1,2 @@
+ALTER TABLE "properties" ADD COLUMN "deprecated" boolean DEFAULT false NOT NULL;--> statement-breakpoint
+ALTER TABLE "properties" ADD COLUMN "replaced_by" uuid;
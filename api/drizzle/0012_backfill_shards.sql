@@ -0,0 +1,21 @@
+CREATE TABLE "backfill_blocks" (
+	"backfill_id" text NOT NULL,
+	"block_number" bigint NOT NULL,
+	"cursor" text NOT NULL,
+	"timestamp" bigint NOT NULL,
+	"output" "bytea" NOT NULL,
+	CONSTRAINT "backfill_blocks_backfill_id_block_number_pk" PRIMARY KEY("backfill_id","block_number")
+);
+--> statement-breakpoint
+CREATE TABLE "backfill_shards" (
+	"backfill_id" text NOT NULL,
+	"start_block" bigint NOT NULL,
+	"end_block" bigint NOT NULL,
+	"status" text DEFAULT 'pending' NOT NULL,
+	"owner" text,
+	"lease_expires_at" timestamp with time zone,
+	"cursor" text,
+	"block_number" bigint,
+	"merged_block" bigint,
+	CONSTRAINT "backfill_shards_backfill_id_start_block_pk" PRIMARY KEY("backfill_id","start_block")
+);
@@ -0,0 +1,2 @@
+ALTER TABLE "backfill_blocks" ADD COLUMN "block_hash" text DEFAULT '' NOT NULL;--> statement-breakpoint
+ALTER TABLE "backfill_blocks" ADD COLUMN "final_block_height" bigint DEFAULT 0 NOT NULL;
@@ -0,0 +1,3 @@
+ALTER TABLE "ipfs_cache" ADD COLUMN "block_number" bigint;--> statement-breakpoint
+ALTER TABLE "ipfs_cache" ADD COLUMN "orphaned" boolean DEFAULT false NOT NULL;--> statement-breakpoint
+CREATE INDEX "ipfs_cache_block_number_idx" ON "ipfs_cache" USING btree ("block_number");
\ No newline at end of file
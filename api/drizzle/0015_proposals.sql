@@ -0,0 +1,12 @@
+CREATE TABLE "proposals" (
+	"plugin_address" text NOT NULL,
+	"proposal_id" text NOT NULL,
+	"creator" text NOT NULL,
+	"start_time" bigint NOT NULL,
+	"end_time" bigint NOT NULL,
+	"metadata_uri" text NOT NULL,
+	"created_at_block" text NOT NULL,
+	CONSTRAINT "proposals_plugin_address_proposal_id_pk" PRIMARY KEY("plugin_address","proposal_id")
+);
+--> statement-breakpoint
+CREATE INDEX "proposals_creator_idx" ON "proposals" USING btree ("creator");
\ No newline at end of file
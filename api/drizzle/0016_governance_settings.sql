@@ -0,0 +1,10 @@
+CREATE TABLE "governance_settings" (
+	"plugin_address" text NOT NULL,
+	"block_number" bigint NOT NULL,
+	"log_index" bigint NOT NULL,
+	"tx_hash" text NOT NULL,
+	"voting_mode" integer,
+	"support_threshold" bigint,
+	"duration" bigint NOT NULL,
+	CONSTRAINT "governance_settings_plugin_address_block_number_log_index_pk" PRIMARY KEY("plugin_address","block_number","log_index")
+);
\ No newline at end of file
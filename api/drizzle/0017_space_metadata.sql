@@ -0,0 +1,3 @@
+ALTER TABLE "spaces" ADD COLUMN "metadata_uri" text;--> statement-breakpoint
+ALTER TABLE "spaces" ADD COLUMN "name" text;--> statement-breakpoint
+ALTER TABLE "spaces" ADD COLUMN "description" text;
\ No newline at end of file
@@ -0,0 +1,3 @@
+CREATE TYPE "public"."proposalStatus" AS ENUM('Proposed', 'Executed', 'Canceled', 'Expired');--> statement-breakpoint
+ALTER TABLE "proposals" ADD COLUMN "status" "proposalStatus" DEFAULT 'Proposed' NOT NULL;--> statement-breakpoint
+CREATE INDEX "proposals_status_end_time_idx" ON "proposals" USING btree ("status","end_time");
\ No newline at end of file
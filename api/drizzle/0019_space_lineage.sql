@@ -0,0 +1,12 @@
+CREATE TABLE "space_lineage" (
+	"space_id" uuid PRIMARY KEY NOT NULL,
+	"predecessor_space_id" uuid,
+	"predecessor_space_address" text NOT NULL,
+	"forked_at_block" text NOT NULL,
+	"forked_in_tx" text NOT NULL
+);
+--> statement-breakpoint
+ALTER TABLE "space_lineage" ADD CONSTRAINT "space_lineage_space_id_spaces_id_fk" FOREIGN KEY ("space_id") REFERENCES "public"."spaces"("id") ON DELETE no action ON UPDATE no action;--> statement-breakpoint
+ALTER TABLE "space_lineage" ADD CONSTRAINT "space_lineage_predecessor_space_id_spaces_id_fk" FOREIGN KEY ("predecessor_space_id") REFERENCES "public"."spaces"("id") ON DELETE no action ON UPDATE no action;--> statement-breakpoint
+CREATE INDEX "space_lineage_predecessor_space_id_idx" ON "space_lineage" USING btree ("predecessor_space_id");--> statement-breakpoint
+CREATE INDEX "space_lineage_predecessor_space_address_idx" ON "space_lineage" USING btree ("predecessor_space_address");
\ No newline at end of file
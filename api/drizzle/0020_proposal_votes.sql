@@ -0,0 +1,11 @@
+CREATE TABLE "proposal_votes" (
+	"plugin_address" text NOT NULL,
+	"proposal_id" text NOT NULL,
+	"voter" text NOT NULL,
+	"vote_option" integer NOT NULL,
+	"voting_power" numeric NOT NULL,
+	"block_number" bigint NOT NULL,
+	"log_index" bigint NOT NULL,
+	"tx_hash" text NOT NULL,
+	CONSTRAINT "proposal_votes_plugin_address_proposal_id_voter_pk" PRIMARY KEY("plugin_address","proposal_id","voter")
+);
\ No newline at end of file
@@ -44,6 +44,7 @@ describe("property helper functions", () => {
 		await db.insert(spaces).values({
 			id: testSpaceId,
 			type: 'Public',
+			spaceKind: 'Dao',
 			daoAddress: '0x123',
 			spaceAddress: '0x456'
 		}).onConflictDoNothing()
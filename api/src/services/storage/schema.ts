@@ -9,6 +9,7 @@ import {
 	customType,
 	decimal,
 	index,
+	integer,
 	jsonb,
 	pgEnum,
 	pgTable,
@@ -26,20 +27,31 @@ import {
 // This comment signals that we want the trigram extension available
 // The actual extension creation is handled in migrations
 
-export const ipfsCache = pgTable("ipfs_cache", {
-	id: serial(),
-	json: jsonb(),
-	uri: text().notNull().unique(),
-	/**
-	 * Sometimes an IPFS fetch can fail for multiple reasons. Primarily
-	 * we care about cases where we fetched it correctly but it was in
-	 * an incorrect format. We need to signal to consumers that the cache
-	 * has the IPFS CID, but was unable to parse it.
-	 */
-	isErrored: boolean().notNull().default(false),
-	block: text().notNull(),
-	space: uuid().notNull(),
-});
+export const ipfsCache = pgTable(
+	"ipfs_cache",
+	{
+		id: serial(),
+		json: jsonb(),
+		uri: text().notNull().unique(),
+		/**
+		 * Sometimes an IPFS fetch can fail for multiple reasons. Primarily
+		 * we care about cases where we fetched it correctly but it was in
+		 * an incorrect format. We need to signal to consumers that the cache
+		 * has the IPFS CID, but was unable to parse it.
+		 */
+		isErrored: boolean().notNull().default(false),
+		block: text().notNull(),
+		space: uuid().notNull(),
+		// Number of the block the edit was published in, null for older rows
+		blockNumber: bigint("block_number", { mode: "number" }),
+		/**
+		 * Set when the block was undone by a reorg after the content was
+		 * cached, and cleared if a canonical block publishes the same URI.
+		 */
+		orphaned: boolean().notNull().default(false),
+	},
+	(table) => [index("ipfs_cache_block_number_idx").on(table.blockNumber)],
+);
 
 /**
  * Cursors store the latest indexed block log. Indexers store their latest
@@ -62,15 +74,28 @@ export const meta = pgTable("meta", {
 });
 
 export const spaceTypesEnum = pgEnum("spaceTypes", ["Personal", "Public"]);
+export const spaceKindsEnum = pgEnum("spaceKinds", ["Personal", "Dao", "Successor"]);
+export const proposalStatusEnum = pgEnum("proposalStatus", [
+	"Proposed",
+	"Executed",
+	"Canceled",
+	"Expired",
+]);
 
 export const spaces = pgTable("spaces", {
 	id: uuid().primaryKey(),
 	type: spaceTypesEnum().notNull(),
+	spaceKind: spaceKindsEnum().notNull(),
 	daoAddress: text().notNull(),
 	spaceAddress: text().notNull(),
 	mainVotingAddress: text(),
 	membershipAddress: text(),
 	personalAddress: text(),
+	// Read from the DAO's metadata document, null until the DAO sets one or
+	// if the document couldn't be fetched
+	metadataUri: text(),
+	name: text(),
+	description: text(),
 });
 
 export const entities = pgTable(
@@ -104,6 +129,9 @@ export const properties = pgTable(
 	{
 		id: uuid().primaryKey(),
 		type: dataTypesEnum().notNull(),
+		// Set by the indexer from the property's lifecycle markers
+		deprecated: boolean().notNull().default(false),
+		replacedBy: uuid("replaced_by"),
 	},
 	(table) => [
 		// Index for filtering by data type
@@ -274,6 +302,94 @@ export const subspaces = pgTable(
 	],
 );
 
+// Links a successor space to the space it was forked from. Successors reference
+// the predecessor's content as of the fork block instead of copying it. The
+// predecessor is recorded by its space plugin address, and its id is null when
+// the predecessor isn't indexed, like legacy spaces.
+export const spaceLineage = pgTable(
+	"space_lineage",
+	{
+		spaceId: uuid()
+			.primaryKey()
+			.references(() => spaces.id),
+		predecessorSpaceId: uuid().references(() => spaces.id),
+		predecessorSpaceAddress: text().notNull(),
+		forkedAtBlock: text().notNull(),
+		forkedInTx: text().notNull(),
+	},
+	(table) => [
+		index("space_lineage_predecessor_space_id_idx").on(table.predecessorSpaceId),
+		index("space_lineage_predecessor_space_address_idx").on(
+			table.predecessorSpaceAddress,
+		),
+	],
+);
+
+// Proposals created in a space's voting or admin plugins. Onchain proposal ids
+// are only unique within the plugin that created them.
+export const proposals = pgTable(
+	"proposals",
+	{
+		pluginAddress: text().notNull(),
+		proposalId: text().notNull(),
+		creator: text().notNull(),
+		startTime: bigint("start_time", { mode: "number" }).notNull(),
+		endTime: bigint("end_time", { mode: "number" }).notNull(),
+		metadataUri: text().notNull(),
+		createdAtBlock: text().notNull(),
+		// Expired is set by the indexer once a block passes the end time
+		status: proposalStatusEnum().notNull().default("Proposed"),
+	},
+	(table) => [
+		primaryKey({ columns: [table.pluginAddress, table.proposalId] }),
+		index("proposals_creator_idx").on(table.creator),
+		index("proposals_status_end_time_idx").on(table.status, table.endTime),
+	],
+);
+
+// Latest vote of each voter on a proposal. Voting power is the voter's power at
+// the proposal's snapshot block, so quorum and support can be computed without
+// reading the chain.
+export const proposalVotes = pgTable(
+	"proposal_votes",
+	{
+		pluginAddress: text().notNull(),
+		proposalId: text().notNull(),
+		voter: text().notNull(),
+		voteOption: integer().notNull(),
+		votingPower: decimal().notNull(),
+		blockNumber: bigint("block_number", { mode: "number" }).notNull(),
+		logIndex: bigint("log_index", { mode: "number" }).notNull(),
+		txHash: text().notNull(),
+	},
+	(table) => [
+		primaryKey({
+			columns: [table.pluginAddress, table.proposalId, table.voter],
+		}),
+	],
+);
+
+// History of the settings of a space's voting and member access plugins, one
+// row per settings event. Member access plugins only have a proposal duration,
+// so their voting mode and support threshold are null.
+export const governanceSettings = pgTable(
+	"governance_settings",
+	{
+		pluginAddress: text().notNull(),
+		blockNumber: bigint("block_number", { mode: "number" }).notNull(),
+		logIndex: bigint("log_index", { mode: "number" }).notNull(),
+		txHash: text().notNull(),
+		votingMode: integer(),
+		supportThreshold: bigint("support_threshold", { mode: "number" }),
+		duration: bigint("duration", { mode: "number" }).notNull(),
+	},
+	(table) => [
+		primaryKey({
+			columns: [table.pluginAddress, table.blockNumber, table.logIndex],
+		}),
+	],
+);
+
 export const entityForeignValues = drizzleRelations(
 	entities,
 	({ many, one }) => ({
@@ -390,6 +506,10 @@ export type DbProperty = InferSelectModel<typeof values>;
 export type DbRelations = InferSelectModel<typeof relations>;
 export type DbMember = InferSelectModel<typeof members>;
 export type DbEditor = InferSelectModel<typeof editors>;
+export type DbProposal = InferSelectModel<typeof proposals>;
+export type DbProposalVote = InferSelectModel<typeof proposalVotes>;
+export type DbGovernanceSettings = InferSelectModel<typeof governanceSettings>;
+export type DbSpaceLineage = InferSelectModel<typeof spaceLineage>;
 
 /** Actions Schema definitions */
 
@@ -502,3 +622,44 @@ export const votesCount = pgTable(
 		};
 	},
 );
+
+/**
+ * Backfills split a full resync of the indexer into block ranges (shards)
+ * that several indexer instances stream at the same time. Each instance
+ * leases a shard until `leaseExpiresAt`, renewing the lease as the shard's
+ * cursor advances, so shards of crashed instances are picked up by others.
+ *
+ * Streamed blocks are staged in `backfill_blocks` until every shard is done,
+ * then merged into the knowledge graph in block order.
+ */
+export const backfillShards = pgTable(
+	"backfill_shards",
+	{
+		backfillId: text().notNull(),
+		startBlock: bigint("start_block", { mode: "number" }).notNull(),
+		endBlock: bigint("end_block", { mode: "number" }).notNull(),
+		// pending, claimed, done or merged
+		status: text().notNull().default("pending"),
+		owner: text(),
+		leaseExpiresAt: timestamp("lease_expires_at", { withTimezone: true }),
+		cursor: text(),
+		blockNumber: bigint("block_number", { mode: "number" }),
+		mergedBlock: bigint("merged_block", { mode: "number" }),
+	},
+	(table) => [primaryKey({ columns: [table.backfillId, table.startBlock] })],
+);
+
+export const backfillBlocks = pgTable(
+	"backfill_blocks",
+	{
+		backfillId: text().notNull(),
+		blockNumber: bigint("block_number", { mode: "number" }).notNull(),
+		cursor: text().notNull(),
+		timestamp: bigint("timestamp", { mode: "number" }).notNull(),
+		output: bytea("output").notNull(),
+		blockHash: text("block_hash").notNull().default(""),
+		// Final block height of the stream when the block was delivered
+		finalBlockHeight: bigint("final_block_height", { mode: "number" }).notNull().default(0),
+	},
+	(table) => [primaryKey({ columns: [table.backfillId, table.blockNumber] })],
+);
@@ -36,6 +36,8 @@ fn make_block_meta() -> BlockMetadata {
         block_timestamp: 12,
         tx_hash: "0x1".to_string(),
         cursor: "cursor_1".to_string(),
+        block_hash: String::new(),
+        finalized: true,
     }
 }
 
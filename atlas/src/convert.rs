@@ -209,6 +209,8 @@ mod tests {
             block_timestamp: 1200,
             tx_hash: "0xabc".to_string(),
             cursor: "cursor_1".to_string(),
+            block_hash: String::new(),
+            finalized: true,
         }
     }
 
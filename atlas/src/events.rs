@@ -3,6 +3,8 @@
 //! These events represent changes to the space topology graph,
 //! consumed from the blockchain via substreams.
 
+use hermes_schema::SpaceKind;
+
 /// Unique identifier for a space
 pub type SpaceId = [u8; 16];
 
@@ -19,6 +21,11 @@ pub struct BlockMetadata {
     pub block_timestamp: u64,
     pub tx_hash: String,
     pub cursor: String,
+    /// Hash of the block (the clock ID)
+    pub block_hash: String,
+    /// Whether the block was final when streamed; events of other blocks
+    /// may still be undone by a reorg
+    pub finalized: bool,
 }
 
 /// A space topology event from the blockchain
@@ -56,6 +63,15 @@ pub enum SpaceType {
     },
 }
 
+impl SpaceType {
+    pub fn kind(&self) -> SpaceKind {
+        match self {
+            SpaceType::Personal { .. } => SpaceKind::Personal,
+            SpaceType::Dao { .. } => SpaceKind::Dao,
+        }
+    }
+}
+
 /// A space extended trust to another space or topic
 #[derive(Debug, Clone)]
 pub struct TrustExtended {
@@ -219,6 +219,8 @@ mod tests {
             block_timestamp: 12,
             tx_hash: "0x1".to_string(),
             cursor: "cursor_1".to_string(),
+            block_hash: String::new(),
+            finalized: true,
         }
     }
 
@@ -168,6 +168,8 @@ mod tests {
             block_timestamp: block * 12,
             tx_hash: format!("0x{:064x}", block),
             cursor: format!("cursor_{}", block),
+            block_hash: String::new(),
+            finalized: true,
         }
     }
 
@@ -57,6 +57,9 @@ impl CanonicalGraphEmitter {
                 created_by: Vec::new(),
                 block_number: meta.block_number,
                 cursor: meta.cursor.clone(),
+                block_hash: meta.block_hash.clone(),
+                finalized: meta.finalized,
+                ..Default::default()
             }),
         };
 
@@ -8,4 +8,5 @@
 pub mod convert;
 pub mod events;
 pub mod graph;
+#[cfg(feature = "kafka")]
 pub mod kafka;
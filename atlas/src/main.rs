@@ -107,6 +107,8 @@ impl Sink for AtlasSink {
             block_timestamp,
             tx_hash: String::new(),
             cursor: data.cursor.clone(),
+            block_hash: clock.map(|c| c.id.clone()).unwrap_or_default(),
+            finalized: block_number <= data.final_block_height,
         };
 
         // Decode actions from the block output
@@ -193,7 +195,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("├──────────────────────────────────────────────────────────────────────────────┤");
 
     // Run with mock data source (all events in a single block)
-    // In production, this would be StreamSource::live(endpoint_url, module, start_block, end_block)
+    // In production, this would be StreamSource::live(endpoint_url, module, network, end_block)
     sink.run(StreamSource::mock()).await?;
 
     println!("└──────────────────────────────────────────────────────────────────────────────┘");
@@ -1,236 +1,190 @@
-use indexer_utils::get_blocklist;
-use indexer_utils::id::derive_space_id;
-use indexer_utils::network_ids::GEO;
+//! IPFS cache for geo_substream
+//!
+//! Pre-fetches IPFS content for the edits published on geo_substream into
+//! the KG indexer's `ipfs_cache` table. The pre-fetching itself lives in the
+//! `ipfs-prefetch` crate, shared with hermes-ipfs-cache; this binary only
+//! decodes `GeoOutput` and streams it from the substream.
+//!
+//! The metadata documents DAOs set are cached in the same table, so the
+//! indexer can name their spaces.
+
+use std::env;
 use std::sync::Arc;
-use std::{env, io::Error};
-use stream::utils::BlockMetadata;
-use thiserror::Error;
-use tokio::task;
-use wire::pb::chain::{EditPublished, GeoOutput};
 
 use dotenv::dotenv;
+use indexer_utils::get_blocklist;
+use indexer_utils::id::derive_space_id;
+use indexer_utils::network_ids::GEO;
+use ipfs::{IpfsFetcher, IpfsFetcherExt, IpfsSource, ThrottleConfig};
+use ipfs_prefetch::cache::Cache;
+use ipfs_prefetch::decoder::map_output;
+use ipfs_prefetch::{EventDecoder, PrefetchError, PrefetchEvent, PrefetchFilter, Prefetcher};
 use prost::Message;
+use stream::pb::sf::substreams::rpc::v2::{BlockScopedData, BlockUndoSignal};
 use stream::Sink;
-use tokio::sync::{Mutex, Semaphore};
+use wire::pb::chain::GeoOutput;
 
 const PKG_FILE: &str = "geo_substream.spkg";
 const MODULE_NAME: &str = "geo_out";
 const START_BLOCK: i64 = 74964;
 
-mod cache;
-use cache::{Cache, CacheItem};
-use ipfs::{IpfsClient, IpfsFetcher};
+/// Indexer ID for cursor persistence.
+const INDEXER_ID: &str = "ipfs_indexer";
 
-type CacheIndexerError = Error;
+mod storage;
+use storage::IndexerStorage;
 
-pub struct EventData {
-    pub block: BlockMetadata,
-    pub edits_published: Vec<(EditPublished, Vec<EditPublished>)>,
-}
+/// Decodes `GeoOutput` blocks.
+///
+/// The space ID is the UUID derived from the edit's DAO address, as the
+/// indexer stores it. Blocklisted DAOs are skipped by the prefetcher's
+/// filter, see `main`.
+struct GeoDecoder;
 
-struct CacheIndexer {
-    semaphore: Arc<Semaphore>,
-    cache: Arc<Mutex<Cache>>,
-    ipfs: Arc<IpfsClient>,
-}
+impl EventDecoder for GeoDecoder {
+    fn decode(&self, data: &BlockScopedData) -> Result<Vec<PrefetchEvent>, PrefetchError> {
+        let geo = GeoOutput::decode(map_output(data)?)?;
 
-impl CacheIndexer {
-    pub fn new(cache: Cache, ipfs: IpfsClient) -> Self {
-        CacheIndexer {
-            cache: Arc::new(Mutex::new(cache)),
-            ipfs: Arc::new(ipfs),
-            semaphore: Arc::new(Semaphore::new(20)),
-        }
+        Ok(geo
+            .edits_published
+            .into_iter()
+            .map(|edit| PrefetchEvent {
+                space_id: derive_space_id(GEO, &edit.dao_address).to_string(),
+                uri: edit.content_uri,
+                dao_address: Some(edit.dao_address),
+            })
+            .collect())
     }
 }
 
-#[derive(Error, Debug)]
-enum IndexerError {
-    #[error("Cache indexer error: {0}")]
-    Error(#[from] cache::CacheError),
+/// Fetches the metadata DAOs set into the cache.
+///
+/// Metadata is a JSON document rather than a GRC-20 edit, so it's fetched
+/// here instead of by the prefetcher, before the block is handed to it. The
+/// prefetcher persists the block's cursor once its edits are written, by
+/// which point its metadata is written too.
+struct MetadataFetcher {
+    ipfs: Box<dyn IpfsFetcher>,
+    storage: IndexerStorage,
 }
 
-impl Sink<EventData> for CacheIndexer {
-    type Error = CacheIndexerError;
-
-    async fn load_persisted_cursor(&self) -> Result<Option<String>, Self::Error> {
-        self.cache
-            .lock()
-            .await
-            .load_cursor("ipfs_indexer")
-            .await
-            .map_err(|e| Error::new(std::io::ErrorKind::Other, e))
-    }
-
-    async fn persist_cursor(&self, cursor: String, block: u64) -> Result<(), Self::Error> {
-        self.cache
-            .lock()
-            .await
-            .persist_cursor("ipfs_indexer", &cursor, &block)
-            .await
-            .map_err(|e| Error::new(std::io::ErrorKind::Other, e))
-    }
-
-    async fn process_block_scoped_data(
-        &self,
-        block_data: &stream::pb::sf::substreams::rpc::v2::BlockScopedData,
-    ) -> Result<(), Self::Error> {
-        let output = stream::utils::output(block_data);
-
-        // @TODO: Parsing and decoding of event data should happen in a separate module.
-        // This makes it so we can generate test data using these decoders and pass them
-        // to any arbitrary handler. This gives us testing and prototyping by mocking the
-        // events coming via the stream.
-
-        // We should take the code to get the output and decode it into
-        // a "GeoOutput" into it's own module that any Sink trait impl
-        // can consume to get the decoded data from the substream.
-
-        // We want to enable extensible governance actions. This means we should probably
-        // distinguish between KG messages and governance messages.
-        let geo = GeoOutput::decode(output.value.as_slice())?;
-
-        let block_metadata = stream::utils::block_metadata(block_data);
-
-        let block_timestamp_seconds: i64 = block_metadata.timestamp.parse().unwrap_or(0);
-        let block_datetime = chrono::DateTime::from_timestamp(block_timestamp_seconds, 0)
-            .unwrap_or_else(|| chrono::Utc::now())
-            .with_timezone(&chrono::Local);
-        let drift_str = stream::utils::format_drift(&block_metadata);
-
-        println!(
-            "Processing Block #{} [{}] - Payload {} ({} bytes) - Drift {} – Edits Published {}",
-            block_metadata.block_number,
-            block_datetime.format("%Y-%m-%d %H:%M:%S"),
-            output.type_url.replace("type.googleapis.com/", ""),
-            output.value.len(),
-            drift_str,
-            geo.edits_published.len()
-        );
-
-        for edit in geo.edits_published {
+impl MetadataFetcher {
+    async fn process_block(&self, data: &BlockScopedData) -> Result<(), PrefetchError> {
+        let geo = GeoOutput::decode(map_output(data)?)?;
+        let block_number = data.clock.as_ref().map(|clock| clock.number);
+        let block = data
+            .clock
+            .as_ref()
+            .and_then(|clock| clock.timestamp.as_ref())
+            .map(|timestamp| timestamp.seconds.to_string())
+            .unwrap_or_default();
+
+        for metadata in geo.dao_metadata_set {
             if get_blocklist()
                 .dao_addresses
-                .contains(&edit.dao_address.as_str())
+                .contains(&metadata.dao_address.as_str())
             {
                 continue;
             }
 
-            let permit = self.semaphore.clone().acquire_owned().await.unwrap();
-            let cache = self.cache.clone();
-            let ipfs = self.ipfs.clone();
-
-            println!(
-                "Processing cache entry for uri {} in block {}",
-                edit.content_uri, block_metadata.block_number
-            );
-
-            let block_metadata = stream::utils::block_metadata(block_data);
+            let json = match self
+                .ipfs
+                .get_json::<serde_json::Value>(&metadata.metadata_uri)
+                .await
+            {
+                Ok(json) => Some(json),
+                Err(error) => {
+                    tracing::warn!(
+                        uri = %metadata.metadata_uri,
+                        dao_address = %metadata.dao_address,
+                        error = %error,
+                        "Failed to fetch DAO metadata"
+                    );
+                    None
+                }
+            };
 
-            task::spawn(async move {
-                process_edit_event(edit, &cache, &ipfs, &block_metadata).await?;
-                drop(permit);
-                Ok::<(), IndexerError>(())
-            });
+            self.storage
+                .insert_metadata(
+                    &metadata.metadata_uri,
+                    json.as_ref(),
+                    &block,
+                    derive_space_id(GEO, &metadata.dao_address),
+                    block_number,
+                )
+                .await?;
         }
 
         Ok(())
     }
 }
 
-async fn process_edit_event(
-    edit: EditPublished,
-    cache: &Arc<Mutex<Cache>>,
-    ipfs: &Arc<IpfsClient>,
-    block: &BlockMetadata,
-) -> Result<(), IndexerError> {
-    {
-        let mut cache_instance = cache.lock().await;
-
-        if cache_instance.has(&edit.content_uri).await? {
-            return Ok(());
-        }
-    }
-
-    let data = ipfs.get(&edit.content_uri).await;
+struct CacheIndexer {
+    prefetcher: Arc<Prefetcher<GeoDecoder>>,
+    metadata: MetadataFetcher,
+}
 
-    match data {
-        Ok(result) => {
-            let item = CacheItem {
-                uri: edit.content_uri.clone(),
-                block: block.timestamp.clone(),
-                json: Some(result),
-                space: derive_space_id(GEO, &edit.dao_address),
-                is_errored: false,
-            };
+impl Sink<()> for CacheIndexer {
+    type Error = PrefetchError;
 
-            let mut cache_instance = cache.lock().await;
-            let res = cache_instance.put(&item).await;
+    async fn load_persisted_cursor(&self) -> Result<Option<String>, Self::Error> {
+        self.prefetcher.load_cursor().await
+    }
 
-            match res {
-                Ok(_) => {
-                    println!(
-                        "Successfully wrote cid to cache {} for block {}",
-                        edit.content_uri.clone(),
-                        block.block_number,
-                    );
-                }
-                Err(err) => {
-                    println!("Err {:?}", err)
-                }
-            }
-        }
-        Err(error) => {
-            println!(
-                "Error writing decoded edit event to cache for uri {} in block {} {}",
-                edit.content_uri, block.block_number, error
-            );
-
-            // We may receive events where the format of the ipfs contents is
-            // invalid. We still write a cache item with an is_errored status
-            // so that cache consumers can always read the cache to get either
-            // the decoded state, or be notified that the event exists, but the
-            // contents are invalid.
-            let item = CacheItem {
-                uri: edit.content_uri,
-                block: block.timestamp.clone(),
-                json: None,
-                space: derive_space_id(GEO, &edit.dao_address),
-                is_errored: true,
-            };
+    async fn persist_cursor(&self, _cursor: String, _block: u64) -> Result<(), Self::Error> {
+        // No-op: the prefetcher persists the cursor once a block's items are written.
+        Ok(())
+    }
 
-            let mut cache_instance = cache.lock().await;
-            cache_instance.put(&item).await?;
-        }
+    async fn process_block_scoped_data(&self, data: &BlockScopedData) -> Result<(), Self::Error> {
+        self.metadata.process_block(data).await?;
+        self.prefetcher.process_block(data).await
     }
 
-    Ok(())
+    fn process_block_undo_signal(&self, undo_signal: &BlockUndoSignal) -> Result<(), Self::Error> {
+        // Applied before the next block: the undone blocks' items are marked orphaned
+        let last_valid_block = undo_signal
+            .last_valid_block
+            .as_ref()
+            .map_or(0, |block| block.number);
+        self.prefetcher
+            .record_undo(last_valid_block, undo_signal.last_valid_cursor.clone());
+        Ok(())
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Error> {
+async fn main() -> anyhow::Result<()> {
     dotenv().ok();
+    tracing_subscriber::fmt::init();
 
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set");
     let ipfs_gateway = env::var("IPFS_GATEWAY").expect("IPFS_GATEWAY not set");
-    let ipfs = IpfsClient::new(&ipfs_gateway);
-    let storage = cache::Storage::new().await;
-
-    match storage {
-        Ok(result) => {
-            let kv = cache::Cache::new(result);
-            let indexer = CacheIndexer::new(kv, ipfs);
-
-            let endpoint_url =
-                env::var("SUBSTREAMS_ENDPOINT").expect("SUBSTREAMS_ENDPOINT not set");
-
-            let _result = indexer
-                .run(&endpoint_url, PKG_FILE, MODULE_NAME, START_BLOCK, 0)
-                .await;
-        }
-        Err(err) => {
-            println!("Error initializing stream {}", err);
-        }
-    }
-
-    Ok(())
+    let endpoint_url = env::var("SUBSTREAMS_ENDPOINT").expect("SUBSTREAMS_ENDPOINT not set");
+
+    // The built-in blocklist always applies, on top of the configured lists
+    let filter = PrefetchFilter::from_env()?.block_daos(get_blocklist().dao_addresses);
+
+    let storage = IndexerStorage::new(&database_url).await?;
+    let metadata = MetadataFetcher {
+        ipfs: IpfsSource::live(ipfs_gateway.clone()).into_fetcher(),
+        storage: storage.clone(),
+    };
+    let prefetcher = Prefetcher::with_throttle(
+        GeoDecoder,
+        INDEXER_ID,
+        Cache::new(storage),
+        IpfsSource::live(ipfs_gateway),
+        ThrottleConfig::from_env(),
+    )
+    .with_filter(filter);
+
+    let indexer = CacheIndexer {
+        prefetcher: Arc::new(prefetcher),
+        metadata,
+    };
+
+    indexer
+        .run(&endpoint_url, PKG_FILE, MODULE_NAME, START_BLOCK, 0)
+        .await
 }
@@ -0,0 +1,183 @@
+//! Cache storage in the KG indexer's `ipfs_cache` table.
+//!
+//! The indexer reads pre-fetched content from
+//! `ipfs_cache (uri, json, block, space, is_errored)`, with the space as a
+//! UUID. The table stores edits as inline JSON and has no retry schedule,
+//! so errored items are never retried. Items are tagged with the number of
+//! their block and marked `orphaned` when a reorg undoes the block; the table
+//! has no fetched or journaled blocks to forget.
+
+use sqlx::{postgres::PgPoolOptions, Postgres, Row};
+use uuid::Uuid;
+
+use ipfs_prefetch::cache::{CacheError, CacheItem, CacheStorage, RetryCandidate};
+use wire::pb::grc20::Edit;
+
+#[derive(Clone)]
+pub struct IndexerStorage {
+    connection: sqlx::Pool<Postgres>,
+}
+
+impl IndexerStorage {
+    pub async fn new(database_url: &str) -> Result<Self, CacheError> {
+        let connection = PgPoolOptions::new()
+            .max_connections(20)
+            .connect(database_url)
+            .await?;
+
+        Ok(IndexerStorage { connection })
+    }
+
+    /// Cache a DAO's metadata document. Unlike edits, the document is stored
+    /// as the JSON it was published as, or errored if it couldn't be fetched.
+    pub async fn insert_metadata(
+        &self,
+        uri: &str,
+        json: Option<&serde_json::Value>,
+        block: &str,
+        space_id: Uuid,
+        block_number: Option<u64>,
+    ) -> Result<(), CacheError> {
+        // Same conflict handling as edits
+        sqlx::query(
+            "INSERT INTO ipfs_cache (uri, json, block, space, is_errored, block_number, orphaned) \
+             VALUES ($1, $2, $3, $4, $5, $6, false) \
+             ON CONFLICT (uri) DO UPDATE SET \
+             block = EXCLUDED.block, block_number = EXCLUDED.block_number, orphaned = false \
+             WHERE ipfs_cache.orphaned",
+        )
+        .bind(uri)
+        .bind(json)
+        .bind(block)
+        .bind(space_id)
+        .bind(json.is_none())
+        .bind(block_number.map(|number| number as i64))
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheStorage for IndexerStorage {
+    async fn insert(&self, item: &CacheItem) -> Result<(), CacheError> {
+        let json = serde_json::to_value(&item.json)?;
+        let space = Uuid::parse_str(&item.space_id)
+            .map_err(|e| CacheError::Database(sqlx::Error::Encode(Box::new(e))))?;
+
+        // Orphaned items are claimed by the block publishing them again
+        sqlx::query(
+            "INSERT INTO ipfs_cache (uri, json, block, space, is_errored, block_number, orphaned) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) \
+             ON CONFLICT (uri) DO UPDATE SET \
+             block = EXCLUDED.block, block_number = EXCLUDED.block_number, orphaned = false \
+             WHERE ipfs_cache.orphaned",
+        )
+        .bind(&item.uri)
+        .bind(&json)
+        .bind(&item.block)
+        .bind(space)
+        .bind(item.is_errored)
+        .bind(item.block_number.map(|number| number as i64))
+        .bind(item.orphaned)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, uri: &str) -> Result<Option<CacheItem>, CacheError> {
+        let row = sqlx::query(
+            "SELECT json, block, space, is_errored, block_number, orphaned FROM ipfs_cache \
+             WHERE uri = $1",
+        )
+        .bind(uri)
+        .fetch_optional(&self.connection)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let json: Option<serde_json::Value> = row.get("json");
+        let space: Uuid = row.get("space");
+        let block_number: Option<i64> = row.get("block_number");
+
+        Ok(Some(CacheItem {
+            uri: uri.to_string(),
+            json: json
+                .map(serde_json::from_value::<Option<Edit>>)
+                .transpose()?
+                .flatten(),
+            block: row.get("block"),
+            space_id: space.to_string(),
+            is_errored: row.get("is_errored"),
+            block_number: block_number.map(|number| number as u64),
+            orphaned: row.get("orphaned"),
+        }))
+    }
+
+    async fn errored_due(
+        &self,
+        _now: i64,
+        _max_attempts: u32,
+        _limit: usize,
+    ) -> Result<Vec<RetryCandidate>, CacheError> {
+        Ok(vec![])
+    }
+
+    async fn resolve(&self, uri: &str, json: &Edit) -> Result<(), CacheError> {
+        sqlx::query("UPDATE ipfs_cache SET json = $2, is_errored = false WHERE uri = $1")
+            .bind(uri)
+            .bind(serde_json::to_value(json)?)
+            .execute(&self.connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn schedule_retry(
+        &self,
+        _uri: &str,
+        _attempts: u32,
+        _next_retry_at: i64,
+    ) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    async fn load_cursor(&self, id: &str) -> Result<Option<String>, CacheError> {
+        let row = sqlx::query("SELECT cursor FROM meta WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.connection)
+            .await?;
+
+        Ok(row.map(|row| row.get("cursor")))
+    }
+
+    async fn persist_cursor(&self, id: &str, cursor: &str, block: u64) -> Result<(), CacheError> {
+        sqlx::query(
+            "INSERT INTO meta (id, cursor, block_number) VALUES ($1, $2, $3) \
+             ON CONFLICT (id) DO UPDATE SET cursor = $2, block_number = $3",
+        )
+        .bind(id)
+        .bind(cursor)
+        .bind(block.to_string())
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn invalidate_from(&self, _id: &str, block: u64) -> Result<u64, CacheError> {
+        let orphaned = sqlx::query(
+            "UPDATE ipfs_cache SET orphaned = true WHERE block_number >= $1 AND NOT orphaned",
+        )
+        .bind(block as i64)
+        .execute(&self.connection)
+        .await?
+        .rows_affected();
+
+        Ok(orphaned)
+    }
+}
@@ -4,7 +4,10 @@
 
 use std::collections::HashMap;
 
-use hermes_ipfs_cache::{cache::CacheSource, IpfsCacheSink};
+use hermes_ipfs_cache::{
+    cache::CacheSource, compaction::CompactionConfig, filter::PrefetchFilter, retry::RetryConfig,
+    IpfsCacheSink,
+};
 use hermes_relay::{Sink, StreamSource};
 use ipfs::IpfsSource;
 use wire::pb::grc20::Edit;
@@ -68,7 +71,31 @@ async fn main() -> anyhow::Result<()> {
     let ipfs_source = IpfsSource::mock(test_topology_edits());
 
     // Create and run the sink with mock data
-    let sink = IpfsCacheSink::new(cache, ipfs_source);
+    let sink = IpfsCacheSink::new(cache, ipfs_source).with_filter(PrefetchFilter::from_env()?);
+    tokio::spawn(sink.retry_worker(RetryConfig::from_env()).run());
+    tokio::spawn(sink.compaction_worker(CompactionConfig::from_env()).run());
+
+    // Export the fetch pipeline to Prometheus when an address is configured
+    #[cfg(feature = "exporter")]
+    if let Ok(addr) = std::env::var("IPFS_CACHE_METRICS_ADDR") {
+        let addr = addr.parse()?;
+        tokio::spawn(hermes_ipfs_cache::exporter::serve(
+            sink.status_handle(),
+            addr,
+        ));
+    }
+
+    // Serve cache reads, the sink's status and metrics over HTTP when an address is configured
+    #[cfg(feature = "sidecar")]
+    if let Ok(addr) = std::env::var("IPFS_CACHE_HTTP_ADDR") {
+        let addr = addr.parse()?;
+        tokio::spawn(hermes_ipfs_cache::sidecar::serve_with_status(
+            sink.cache(),
+            sink.status_handle(),
+            addr,
+        ));
+    }
+
     sink.run(StreamSource::mock()).await?;
 
     tracing::info!("Hermes IPFS Cache finished");
@@ -16,11 +16,12 @@ use hermes_schema::pb::space::{
     DefaultDaoSpacePayload, HermesCreateSpace, HermesSpaceTrustExtension, PersonalSpacePayload,
     RelatedExtension, SubtopicExtension, VerifiedExtension,
 };
+use hermes_schema::{AuthorVerifier, SpaceMembership};
 use wire::pb::grc20::{DataType as WireDataType, Entity, Op, Property, Relation, Value};
 
 use mock_substream::{
-    test_topology, BlockMetadata, EditPublished, MockEvent, SpaceCreated, SpaceType,
-    TrustExtended, TrustExtension,
+    test_topology, BlockMetadata, EditPublished, MockEvent, SpaceCreated, SpaceType, TrustExtended,
+    TrustExtension,
 };
 
 // =============================================================================
@@ -33,29 +34,32 @@ fn convert_block_metadata(meta: &BlockMetadata) -> BlockchainMetadata {
         created_by: vec![], // Not available in mock metadata
         block_number: meta.block_number,
         cursor: meta.cursor.clone(),
+        // Mock blocks are never undone
+        finalized: true,
+        ..Default::default()
     }
 }
 
 fn convert_space_created(event: &SpaceCreated) -> HermesCreateSpace {
     let payload = match &event.space_type {
-        SpaceType::Personal { owner } => {
-            Some(hermes_schema::pb::space::hermes_create_space::Payload::PersonalSpace(
+        SpaceType::Personal { owner } => Some(
+            hermes_schema::pb::space::hermes_create_space::Payload::PersonalSpace(
                 PersonalSpacePayload {
                     owner: owner.to_vec(),
                 },
-            ))
-        }
+            ),
+        ),
         SpaceType::Dao {
             initial_editors,
             initial_members,
-        } => {
-            Some(hermes_schema::pb::space::hermes_create_space::Payload::DefaultDaoSpace(
+        } => Some(
+            hermes_schema::pb::space::hermes_create_space::Payload::DefaultDaoSpace(
                 DefaultDaoSpacePayload {
                     initial_editors: initial_editors.iter().map(|id| id.to_vec()).collect(),
                     initial_members: initial_members.iter().map(|id| id.to_vec()).collect(),
                 },
-            ))
-        }
+            ),
+        ),
     };
 
     HermesCreateSpace {
@@ -68,27 +72,27 @@ fn convert_space_created(event: &SpaceCreated) -> HermesCreateSpace {
 
 fn convert_trust_extended(event: &TrustExtended) -> HermesSpaceTrustExtension {
     let extension = match &event.extension {
-        TrustExtension::Verified { target_space_id } => {
-            Some(hermes_schema::pb::space::hermes_space_trust_extension::Extension::Verified(
+        TrustExtension::Verified { target_space_id } => Some(
+            hermes_schema::pb::space::hermes_space_trust_extension::Extension::Verified(
                 VerifiedExtension {
                     target_space_id: target_space_id.to_vec(),
                 },
-            ))
-        }
-        TrustExtension::Related { target_space_id } => {
-            Some(hermes_schema::pb::space::hermes_space_trust_extension::Extension::Related(
+            ),
+        ),
+        TrustExtension::Related { target_space_id } => Some(
+            hermes_schema::pb::space::hermes_space_trust_extension::Extension::Related(
                 RelatedExtension {
                     target_space_id: target_space_id.to_vec(),
                 },
-            ))
-        }
-        TrustExtension::Subtopic { target_topic_id } => {
-            Some(hermes_schema::pb::space::hermes_space_trust_extension::Extension::Subtopic(
+            ),
+        ),
+        TrustExtension::Subtopic { target_topic_id } => Some(
+            hermes_schema::pb::space::hermes_space_trust_extension::Extension::Subtopic(
                 SubtopicExtension {
                     target_topic_id: target_topic_id.to_vec(),
                 },
-            ))
-        }
+            ),
+        ),
     };
 
     HermesSpaceTrustExtension {
@@ -192,6 +196,7 @@ fn convert_edit_published(event: &EditPublished) -> HermesEdit {
         space_id: hex::encode(event.space_id),
         is_canonical: true, // Canonicality is determined by Atlas, default to true
         meta: Some(convert_block_metadata(&event.meta)),
+        verified_authors: false, // Set by the caller when author verification is enabled
     }
 }
 
@@ -213,10 +218,7 @@ fn create_producer(broker: &str) -> Result<BaseProducer, Box<dyn std::error::Err
 
     // If SASL credentials are provided, enable SASL/SSL (for managed Kafka)
     // Otherwise, use plaintext (for local development)
-    if let (Ok(username), Ok(password)) = (
-        env::var("KAFKA_USERNAME"),
-        env::var("KAFKA_PASSWORD"),
-    ) {
+    if let (Ok(username), Ok(password)) = (env::var("KAFKA_USERNAME"), env::var("KAFKA_PASSWORD")) {
         config
             .set("security.protocol", "SASL_SSL")
             .set("sasl.mechanisms", "PLAIN")
@@ -239,15 +241,7 @@ fn send_space(
     let mut payload = Vec::new();
     space.encode(&mut payload)?;
 
-    let space_type = match &space.payload {
-        Some(hermes_schema::pb::space::hermes_create_space::Payload::PersonalSpace(_)) => {
-            "PERSONAL"
-        }
-        Some(hermes_schema::pb::space::hermes_create_space::Payload::DefaultDaoSpace(_)) => {
-            "DEFAULT_DAO"
-        }
-        None => "UNKNOWN",
-    };
+    let space_type = space.space_type_header();
 
     let record = BaseRecord::to("space.creations")
         .key(&space.space_id)
@@ -293,10 +287,7 @@ fn send_trust_extension(
     Ok(())
 }
 
-fn send_edit(
-    producer: &BaseProducer,
-    edit: &HermesEdit,
-) -> Result<(), Box<dyn std::error::Error>> {
+fn send_edit(producer: &BaseProducer, edit: &HermesEdit) -> Result<(), Box<dyn std::error::Error>> {
     let mut payload = Vec::new();
     edit.encode(&mut payload)?;
 
@@ -326,6 +317,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("Connected to Kafka broker");
 
+    // Check edit authors against the membership of their space when enabled
+    let verify_authors = env::var("VERIFY_AUTHORS").is_ok_and(|value| value == "true");
+    let mut membership = SpaceMembership::new();
+
     // Generate deterministic topology from mock-substream
     println!("\n=== Processing mock-substream topology ===\n");
     let blocks = test_topology::generate();
@@ -341,6 +336,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 MockEvent::SpaceCreated(space) => {
                     let hermes_space = convert_space_created(space);
                     let space_id_hex = hex::encode(&space.space_id);
+                    membership.record_space(&hermes_space);
                     match send_space(&producer, &hermes_space) {
                         Ok(_) => {
                             space_count += 1;
@@ -361,23 +357,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     match send_trust_extension(&producer, &hermes_trust) {
                         Ok(_) => {
                             trust_count += 1;
-                            println!("Trust extended: {} -> {} ({})", source_hex, ext_type, ext_type);
+                            println!(
+                                "Trust extended: {} -> {} ({})",
+                                source_hex, ext_type, ext_type
+                            );
                             Ok(())
                         }
                         Err(e) => Err(e),
                     }
                 }
                 MockEvent::EditPublished(edit) => {
-                    let hermes_edit = convert_edit_published(edit);
+                    let mut hermes_edit = convert_edit_published(edit);
+                    if verify_authors {
+                        hermes_edit.verified_authors = membership.verify_authors(&hermes_edit);
+                    }
                     let space_id_hex = hex::encode(&edit.space_id);
                     match send_edit(&producer, &hermes_edit) {
                         Ok(_) => {
                             edit_count += 1;
                             println!(
-                                "Edit published: {} in space {} ({} ops)",
+                                "Edit published: {} in space {} ({} ops, verified authors: {})",
                                 edit.name,
                                 space_id_hex,
-                                edit.ops.len()
+                                edit.ops.len(),
+                                hermes_edit.verified_authors
                             );
                             Ok(())
                         }
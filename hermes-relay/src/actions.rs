@@ -7,9 +7,26 @@
 //! contract. The same values are defined in `hermes-substream/src/lib.rs` for
 //! filtering on the substream side - keep them in sync if adding new actions.
 //!
+//! Prefer [`ActionKind`] over comparing against the raw constants: matching on
+//! it without a wildcard arm makes consumers fail to compile when a new action
+//! type is added here.
+//!
 //! ## Example
 //!
 //! ```ignore
+//! use hermes_relay::actions::{self, ActionKind};
+//!
+//! match ActionKind::from_bytes(&action.action) {
+//!     Some(ActionKind::SpaceRegistered) => handle_space(action),
+//!     Some(ActionKind::SubspaceAdded | ActionKind::SubspaceRemoved) => handle_subspace(action),
+//!     Some(_) => {}
+//!     None => tracing::warn!("Unknown action type"),
+//! }
+//! ```
+//!
+//! Or, comparing raw bytes:
+//!
+//! ```ignore
 //! use hermes_relay::actions;
 //!
 //! fn filter_space_events(action: &Action) -> bool {
@@ -140,7 +157,166 @@ pub const OBJECT_UNVOTED: [u8; 32] = [
     0x3b, 0xc0, 0xc7, 0xdc, 0xfd, 0xcd, 0x27, 0x5f, 0xb4, 0x31, 0x6e, 0x8b, 0xf2, 0x25, 0xc1, 0x83,
 ];
 
+/// Typed action kind, one variant per action constant in this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionKind {
+    SpaceRegistered,
+    SpaceMigrated,
+    ProposalCreated,
+    ProposalVoted,
+    ProposalExecuted,
+    EditorAdded,
+    EditorRemoved,
+    MemberAdded,
+    MemberRemoved,
+    EditorFlagged,
+    EditorUnflagged,
+    SpaceLeft,
+    TopicDeclared,
+    EditsPublished,
+    ContentFlagged,
+    SubspaceAdded,
+    SubspaceRemoved,
+    ObjectUpvoted,
+    ObjectDownvoted,
+    ObjectUnvoted,
+}
+
+impl ActionKind {
+    /// Every action kind, in the order the constants are declared.
+    pub const ALL: [ActionKind; 20] = [
+        ActionKind::SpaceRegistered,
+        ActionKind::SpaceMigrated,
+        ActionKind::ProposalCreated,
+        ActionKind::ProposalVoted,
+        ActionKind::ProposalExecuted,
+        ActionKind::EditorAdded,
+        ActionKind::EditorRemoved,
+        ActionKind::MemberAdded,
+        ActionKind::MemberRemoved,
+        ActionKind::EditorFlagged,
+        ActionKind::EditorUnflagged,
+        ActionKind::SpaceLeft,
+        ActionKind::TopicDeclared,
+        ActionKind::EditsPublished,
+        ActionKind::ContentFlagged,
+        ActionKind::SubspaceAdded,
+        ActionKind::SubspaceRemoved,
+        ActionKind::ObjectUpvoted,
+        ActionKind::ObjectDownvoted,
+        ActionKind::ObjectUnvoted,
+    ];
+
+    /// Look up the action kind for the 32-byte `action` field of an action.
+    ///
+    /// Returns `None` for unknown action types.
+    pub fn from_bytes(action_bytes: &[u8]) -> Option<ActionKind> {
+        let action_type: [u8; 32] = action_bytes.try_into().ok()?;
+
+        match action_type {
+            SPACE_REGISTERED => Some(ActionKind::SpaceRegistered),
+            SPACE_MIGRATED => Some(ActionKind::SpaceMigrated),
+            PROPOSAL_CREATED => Some(ActionKind::ProposalCreated),
+            PROPOSAL_VOTED => Some(ActionKind::ProposalVoted),
+            PROPOSAL_EXECUTED => Some(ActionKind::ProposalExecuted),
+            EDITOR_ADDED => Some(ActionKind::EditorAdded),
+            EDITOR_REMOVED => Some(ActionKind::EditorRemoved),
+            MEMBER_ADDED => Some(ActionKind::MemberAdded),
+            MEMBER_REMOVED => Some(ActionKind::MemberRemoved),
+            EDITOR_FLAGGED => Some(ActionKind::EditorFlagged),
+            EDITOR_UNFLAGGED => Some(ActionKind::EditorUnflagged),
+            SPACE_LEFT => Some(ActionKind::SpaceLeft),
+            TOPIC_DECLARED => Some(ActionKind::TopicDeclared),
+            EDITS_PUBLISHED => Some(ActionKind::EditsPublished),
+            CONTENT_FLAGGED => Some(ActionKind::ContentFlagged),
+            SUBSPACE_ADDED => Some(ActionKind::SubspaceAdded),
+            SUBSPACE_REMOVED => Some(ActionKind::SubspaceRemoved),
+            OBJECT_UPVOTED => Some(ActionKind::ObjectUpvoted),
+            OBJECT_DOWNVOTED => Some(ActionKind::ObjectDownvoted),
+            OBJECT_UNVOTED => Some(ActionKind::ObjectUnvoted),
+            _ => None,
+        }
+    }
+
+    /// The keccak256 hash identifying this action type.
+    pub fn bytes(&self) -> &'static [u8; 32] {
+        match self {
+            ActionKind::SpaceRegistered => &SPACE_REGISTERED,
+            ActionKind::SpaceMigrated => &SPACE_MIGRATED,
+            ActionKind::ProposalCreated => &PROPOSAL_CREATED,
+            ActionKind::ProposalVoted => &PROPOSAL_VOTED,
+            ActionKind::ProposalExecuted => &PROPOSAL_EXECUTED,
+            ActionKind::EditorAdded => &EDITOR_ADDED,
+            ActionKind::EditorRemoved => &EDITOR_REMOVED,
+            ActionKind::MemberAdded => &MEMBER_ADDED,
+            ActionKind::MemberRemoved => &MEMBER_REMOVED,
+            ActionKind::EditorFlagged => &EDITOR_FLAGGED,
+            ActionKind::EditorUnflagged => &EDITOR_UNFLAGGED,
+            ActionKind::SpaceLeft => &SPACE_LEFT,
+            ActionKind::TopicDeclared => &TOPIC_DECLARED,
+            ActionKind::EditsPublished => &EDITS_PUBLISHED,
+            ActionKind::ContentFlagged => &CONTENT_FLAGGED,
+            ActionKind::SubspaceAdded => &SUBSPACE_ADDED,
+            ActionKind::SubspaceRemoved => &SUBSPACE_REMOVED,
+            ActionKind::ObjectUpvoted => &OBJECT_UPVOTED,
+            ActionKind::ObjectDownvoted => &OBJECT_DOWNVOTED,
+            ActionKind::ObjectUnvoted => &OBJECT_UNVOTED,
+        }
+    }
+
+    /// The action name string from the Space Registry contract.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActionKind::SpaceRegistered => "SPACE_ID_REGISTERED",
+            ActionKind::SpaceMigrated => "SPACE_ID_MIGRATED",
+            ActionKind::ProposalCreated => "PROPOSAL_CREATED",
+            ActionKind::ProposalVoted => "PROPOSAL_VOTED",
+            ActionKind::ProposalExecuted => "PROPOSAL_EXECUTED",
+            ActionKind::EditorAdded => "EDITOR_ADDED",
+            ActionKind::EditorRemoved => "EDITOR_REMOVED",
+            ActionKind::MemberAdded => "MEMBER_ADDED",
+            ActionKind::MemberRemoved => "MEMBER_REMOVED",
+            ActionKind::EditorFlagged => "EDITOR_FLAGGED",
+            ActionKind::EditorUnflagged => "EDITOR_UNFLAGGED",
+            ActionKind::SpaceLeft => "SPACE_LEFT",
+            ActionKind::TopicDeclared => "TOPIC_DECLARED",
+            ActionKind::EditsPublished => "EDITS_PUBLISHED",
+            ActionKind::ContentFlagged => "FLAGGED",
+            ActionKind::SubspaceAdded => "SUBSPACE_ADDED",
+            ActionKind::SubspaceRemoved => "SUBSPACE_REMOVED",
+            ActionKind::ObjectUpvoted => "OBJECT_UPVOTED",
+            ActionKind::ObjectDownvoted => "OBJECT_DOWNVOTED",
+            ActionKind::ObjectUnvoted => "OBJECT_UNVOTED",
+        }
+    }
+}
+
 /// Check if an action matches a specific action type.
 pub fn matches(action_bytes: &[u8], action_type: &[u8; 32]) -> bool {
     action_bytes == action_type
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn action_kind_round_trips_through_bytes() {
+        for kind in ActionKind::ALL {
+            assert_eq!(ActionKind::from_bytes(kind.bytes()), Some(kind));
+        }
+    }
+
+    #[test]
+    fn action_kind_rejects_unknown_bytes() {
+        assert_eq!(ActionKind::from_bytes(&[0u8; 32]), None);
+        assert_eq!(ActionKind::from_bytes(&SPACE_REGISTERED[..31]), None);
+    }
+
+    #[test]
+    fn action_kind_bytes_are_unique() {
+        let unique: std::collections::HashSet<_> =
+            ActionKind::ALL.iter().map(|kind| kind.bytes()).collect();
+        assert_eq!(unique.len(), ActionKind::ALL.len());
+    }
+}
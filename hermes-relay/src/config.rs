@@ -1,7 +1,12 @@
 //! Hermes-specific configuration for connecting to hermes-substream.
 //!
 //! This module provides the module names and package file paths that transformers
-//! use to subscribe to specific event types from hermes-substream.
+//! use to subscribe to specific event types from hermes-substream, and the
+//! registry of block numbers each module should start streaming from.
+
+use std::collections::HashMap;
+use std::env;
+use std::str::FromStr;
 
 /// Path to the hermes-substream package file
 pub const HERMES_SPKG: &str = "hermes-substream.spkg";
@@ -15,15 +20,19 @@ pub const HERMES_SPKG: &str = "hermes-substream.spkg";
 ///
 /// The substreams protocol only supports consuming a single output module per stream
 /// in production mode. If your transformer needs events from multiple modules, use
-/// [`HermesModule::Actions`] to receive all raw actions and filter client-side.
+/// [`HermesModule::HermesOut`] to receive every typed list of a block in one message,
+/// or [`HermesModule::Actions`] to receive all raw actions and filter client-side.
 ///
 /// See `docs/decisions/0001-multiple-substreams-modules-consumers.md` for more details.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum HermesModule {
     /// Raw actions - use this when you need multiple event types.
     /// Filter client-side based on the action type.
     Actions,
 
+    /// Every typed event list of a block in one `HermesOutput` message.
+    HermesOut,
+
     // Governance events - space lifecycle
     SpacesRegistered,
     SpacesMigrated,
@@ -32,6 +41,7 @@ pub enum HermesModule {
     ProposalsCreated,
     ProposalsVoted,
     ProposalsExecuted,
+    ProposalLifecycle,
 
     // Governance events - membership
     EditorsAdded,
@@ -41,6 +51,7 @@ pub enum HermesModule {
     EditorsFlagged,
     EditorsUnflagged,
     SpacesLeft,
+    MembershipDeltas,
 
     // Governance events - topics
     TopicsDeclared,
@@ -49,6 +60,9 @@ pub enum HermesModule {
     EditsPublished,
     ContentFlagged,
 
+    // Moderation - per-object flag counts
+    FlagCountDeltas,
+
     // Subspace events
     SubspacesAdded,
     SubspacesRemoved,
@@ -57,13 +71,45 @@ pub enum HermesModule {
     ObjectsUpvoted,
     ObjectsDownvoted,
     ObjectsUnvoted,
+    ObjectVoteTotals,
 }
 
 impl HermesModule {
+    /// All available modules.
+    pub const ALL: [HermesModule; 26] = [
+        HermesModule::Actions,
+        HermesModule::HermesOut,
+        HermesModule::SpacesRegistered,
+        HermesModule::SpacesMigrated,
+        HermesModule::ProposalsCreated,
+        HermesModule::ProposalsVoted,
+        HermesModule::ProposalsExecuted,
+        HermesModule::ProposalLifecycle,
+        HermesModule::EditorsAdded,
+        HermesModule::EditorsRemoved,
+        HermesModule::MembersAdded,
+        HermesModule::MembersRemoved,
+        HermesModule::EditorsFlagged,
+        HermesModule::EditorsUnflagged,
+        HermesModule::SpacesLeft,
+        HermesModule::MembershipDeltas,
+        HermesModule::TopicsDeclared,
+        HermesModule::EditsPublished,
+        HermesModule::ContentFlagged,
+        HermesModule::FlagCountDeltas,
+        HermesModule::SubspacesAdded,
+        HermesModule::SubspacesRemoved,
+        HermesModule::ObjectsUpvoted,
+        HermesModule::ObjectsDownvoted,
+        HermesModule::ObjectsUnvoted,
+        HermesModule::ObjectVoteTotals,
+    ];
+
     /// Returns the module name as expected by the substreams API.
     pub fn as_str(&self) -> &'static str {
         match self {
             HermesModule::Actions => "map_actions",
+            HermesModule::HermesOut => "hermes_out",
 
             HermesModule::SpacesRegistered => "map_spaces_registered",
             HermesModule::SpacesMigrated => "map_spaces_migrated",
@@ -71,6 +117,7 @@ impl HermesModule {
             HermesModule::ProposalsCreated => "map_proposals_created",
             HermesModule::ProposalsVoted => "map_proposals_voted",
             HermesModule::ProposalsExecuted => "map_proposals_executed",
+            HermesModule::ProposalLifecycle => "map_proposal_lifecycle",
 
             HermesModule::EditorsAdded => "map_editors_added",
             HermesModule::EditorsRemoved => "map_editors_removed",
@@ -79,18 +126,22 @@ impl HermesModule {
             HermesModule::EditorsFlagged => "map_editors_flagged",
             HermesModule::EditorsUnflagged => "map_editors_unflagged",
             HermesModule::SpacesLeft => "map_spaces_left",
+            HermesModule::MembershipDeltas => "map_membership_deltas",
 
             HermesModule::TopicsDeclared => "map_topics_declared",
 
             HermesModule::EditsPublished => "map_edits_published",
             HermesModule::ContentFlagged => "map_content_flagged",
 
+            HermesModule::FlagCountDeltas => "map_flag_count_deltas",
+
             HermesModule::SubspacesAdded => "map_subspaces_added",
             HermesModule::SubspacesRemoved => "map_subspaces_removed",
 
             HermesModule::ObjectsUpvoted => "map_objects_upvoted",
             HermesModule::ObjectsDownvoted => "map_objects_downvoted",
             HermesModule::ObjectsUnvoted => "map_objects_unvoted",
+            HermesModule::ObjectVoteTotals => "map_object_vote_totals",
         }
     }
 }
@@ -100,3 +151,185 @@ impl std::fmt::Display for HermesModule {
         write!(f, "{}", self.as_str())
     }
 }
+
+/// Networks hermes-substream is deployed against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Network {
+    Testnet,
+    Mainnet,
+}
+
+impl Network {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Testnet => "testnet",
+            Network::Mainnet => "mainnet",
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "testnet" => Ok(Network::Testnet),
+            "mainnet" => Ok(Network::Mainnet),
+            other => Err(format!("unknown network: {}", other)),
+        }
+    }
+}
+
+impl std::fmt::Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Block to start from when neither the network nor the module has an entry.
+///
+/// Matches the `initialBlock` of every module in `substreams.yaml`.
+pub const DEFAULT_START_BLOCK: i64 = 0;
+
+/// Registry of the block each module should start streaming from, per network.
+///
+/// Modules become meaningful at different heights: registry events only exist
+/// after the Space Registry is deployed, while plugin events start at each
+/// plugin's deployment. Lookups check for a module-specific entry first, then
+/// the network default, then fall back to [`DEFAULT_START_BLOCK`].
+///
+/// # Example
+///
+/// ```ignore
+/// use hermes_relay::config::{HermesModule, Network, StartBlockRegistry};
+///
+/// let registry = StartBlockRegistry::new()
+///     .with_network_default(Network::Testnet, 1_000)
+///     .with_module(Network::Testnet, HermesModule::ObjectsUpvoted, 2_500);
+///
+/// assert_eq!(registry.start_block(HermesModule::EditsPublished, Network::Testnet), 1_000);
+/// assert_eq!(registry.start_block(HermesModule::ObjectsUpvoted, Network::Testnet), 2_500);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StartBlockRegistry {
+    network_defaults: HashMap<Network, i64>,
+    modules: HashMap<(Network, HermesModule), i64>,
+}
+
+impl StartBlockRegistry {
+    /// Create an empty registry where every module starts at [`DEFAULT_START_BLOCK`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a registry from the environment.
+    ///
+    /// - `HERMES_START_BLOCK`: start block for every module on `network`
+    /// - `HERMES_START_BLOCK_<MODULE>`: start block for a single module, where
+    ///   `<MODULE>` is the upper-cased module name (e.g. `MAP_EDITS_PUBLISHED`)
+    ///
+    /// Unparseable values are ignored.
+    pub fn from_env(network: Network) -> Self {
+        let mut registry = Self::new();
+
+        if let Some(block) = parse_env_block("HERMES_START_BLOCK") {
+            registry.set_network_default(network, block);
+        }
+
+        for module in HermesModule::ALL {
+            let key = format!("HERMES_START_BLOCK_{}", module.as_str().to_uppercase());
+
+            if let Some(block) = parse_env_block(&key) {
+                registry.set_module(network, module, block);
+            }
+        }
+
+        registry
+    }
+
+    /// Set the start block for every module on a network without its own entry.
+    pub fn with_network_default(mut self, network: Network, block: i64) -> Self {
+        self.set_network_default(network, block);
+        self
+    }
+
+    /// Set the start block for a single module on a network.
+    pub fn with_module(mut self, network: Network, module: HermesModule, block: i64) -> Self {
+        self.set_module(network, module, block);
+        self
+    }
+
+    pub fn set_network_default(&mut self, network: Network, block: i64) {
+        self.network_defaults.insert(network, block);
+    }
+
+    pub fn set_module(&mut self, network: Network, module: HermesModule, block: i64) {
+        self.modules.insert((network, module), block);
+    }
+
+    /// Look up the start block for a module on a network.
+    pub fn start_block(&self, module: HermesModule, network: Network) -> i64 {
+        self.modules
+            .get(&(network, module))
+            .or_else(|| self.network_defaults.get(&network))
+            .copied()
+            .unwrap_or(DEFAULT_START_BLOCK)
+    }
+}
+
+fn parse_env_block(key: &str) -> Option<i64> {
+    env::var(key).ok().and_then(|value| value.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_block_falls_back_to_default() {
+        let registry = StartBlockRegistry::new();
+
+        assert_eq!(
+            registry.start_block(HermesModule::EditsPublished, Network::Testnet),
+            DEFAULT_START_BLOCK
+        );
+    }
+
+    #[test]
+    fn test_module_entry_overrides_network_default() {
+        let registry = StartBlockRegistry::new()
+            .with_network_default(Network::Testnet, 1_000)
+            .with_module(Network::Testnet, HermesModule::ObjectsUpvoted, 2_500);
+
+        assert_eq!(
+            registry.start_block(HermesModule::EditsPublished, Network::Testnet),
+            1_000
+        );
+        assert_eq!(
+            registry.start_block(HermesModule::ObjectsUpvoted, Network::Testnet),
+            2_500
+        );
+        assert_eq!(
+            registry.start_block(HermesModule::ObjectsUpvoted, Network::Mainnet),
+            DEFAULT_START_BLOCK
+        );
+    }
+
+    #[test]
+    fn test_module_names_are_unique() {
+        let names: std::collections::HashSet<_> = HermesModule::ALL
+            .iter()
+            .map(|module| module.as_str())
+            .collect();
+
+        assert_eq!(names.len(), HermesModule::ALL.len());
+        assert_eq!(HermesModule::HermesOut.as_str(), "hermes_out");
+    }
+
+    #[test]
+    fn test_network_from_str() {
+        assert_eq!("testnet".parse::<Network>(), Ok(Network::Testnet));
+        assert_eq!("Mainnet".parse::<Network>(), Ok(Network::Mainnet));
+        assert!("devnet".parse::<Network>().is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Detection of blocks the stream delivers more than once.
+//!
+//! After a reconnect the substreams endpoint occasionally sends the last
+//! block again, either the one matching the cursor the stream resumed from or
+//! one the sink already handled in the current run. [`CursorGuard`] tracks
+//! the last cursor and block so [`Sink::run`](crate::Sink::run) can skip
+//! those blocks instead of handing them to sinks that aren't idempotent.
+
+/// Tracks the last processed cursor and block of a stream.
+#[derive(Debug, Clone, Default)]
+pub struct CursorGuard {
+    last_cursor: Option<String>,
+    last_block: Option<u64>,
+    skipped: u64,
+}
+
+impl CursorGuard {
+    /// Create a guard for a stream resuming from `persisted_cursor`.
+    ///
+    /// Cursors are opaque, so only a block with this exact cursor counts as
+    /// processed until the first block of the run is seen.
+    pub fn new(persisted_cursor: Option<String>) -> Self {
+        Self {
+            last_cursor: persisted_cursor,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the block at `cursor` and `block` was already processed. A
+    /// duplicate is counted in [`CursorGuard::skipped`].
+    pub fn is_duplicate(&mut self, cursor: &str, block: u64) -> bool {
+        let duplicate = self.last_cursor.as_deref() == Some(cursor)
+            || self.last_block.is_some_and(|last| block <= last);
+
+        if duplicate {
+            self.skipped += 1;
+        }
+
+        duplicate
+    }
+
+    /// Record that the block at `cursor` and `block` was processed.
+    pub fn advance(&mut self, cursor: &str, block: u64) {
+        self.last_cursor = Some(cursor.to_string());
+        self.last_block = Some(block);
+    }
+
+    /// Record an undo back to the last valid `cursor` and `block`, so the
+    /// blocks replacing the undone ones are processed again.
+    pub fn rewind(&mut self, cursor: &str, block: u64) {
+        self.advance(cursor, block);
+    }
+
+    /// Number of duplicate blocks seen so far.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persisted_cursor_is_a_duplicate() {
+        let mut guard = CursorGuard::new(Some("cursor-10".to_string()));
+
+        assert!(guard.is_duplicate("cursor-10", 10));
+        assert!(!guard.is_duplicate("cursor-11", 11));
+        assert_eq!(guard.skipped(), 1);
+    }
+
+    #[test]
+    fn test_blocks_at_or_below_the_last_block_are_duplicates() {
+        let mut guard = CursorGuard::new(None);
+        assert!(!guard.is_duplicate("cursor-10", 10));
+        guard.advance("cursor-10", 10);
+        guard.advance("cursor-11", 11);
+
+        assert!(guard.is_duplicate("cursor-11", 11));
+        assert!(guard.is_duplicate("cursor-10-other", 10));
+        assert!(!guard.is_duplicate("cursor-12", 12));
+        assert_eq!(guard.skipped(), 2);
+    }
+
+    #[test]
+    fn test_rewind_allows_replacement_blocks() {
+        let mut guard = CursorGuard::new(None);
+        guard.advance("cursor-12", 12);
+        guard.rewind("cursor-10", 10);
+
+        assert!(!guard.is_duplicate("cursor-11-fork", 11));
+        assert!(guard.is_duplicate("cursor-10", 10));
+    }
+}
@@ -5,8 +5,9 @@
 //! This crate provides:
 //! - [`Sink`] and [`PreprocessedSink`] traits for consuming hermes-substream events
 //! - [`StreamSource`] config for explicitly choosing mock or live data sources
+//! - [`dedup::CursorGuard`] for skipping blocks the stream delivers twice
 //! - [`source::MockSource`] and [`source::mock_events`] for custom mock data
-//! - Hermes-specific configuration (module names, package paths)
+//! - Hermes-specific configuration (module names, package paths, start blocks)
 //! - Action type constants for filtering raw actions
 //!
 //! ## Usage
@@ -14,7 +15,7 @@
 //! Transformers implement the [`Sink`] trait and call `run` with a [`StreamSource`]:
 //!
 //! ```ignore
-//! use hermes_relay::{Sink, StreamSource, HermesModule};
+//! use hermes_relay::{Sink, StreamSource, HermesModule, Network};
 //! use stream::pb::sf::substreams::rpc::v2::BlockScopedData;
 //!
 //! struct EditsTransformer { /* ... */ }
@@ -31,12 +32,13 @@
 //! // Development/testing: use mock data (all events in a single block)
 //! transformer.run(StreamSource::mock()).await?;
 //!
-//! // Production: use live substream
+//! // Production: use live substream, starting at the module's registered
+//! // start block for the network
 //! let source = StreamSource::live(
 //!     "https://substreams.example.com",
 //!     HermesModule::EditsPublished,
+//!     Network::Testnet,
 //!     0,
-//!     1000,
 //! );
 //! transformer.run(source).await?;
 //! ```
@@ -75,31 +77,38 @@
 //! For transformers that need a **single event type**, use the specific module
 //! (e.g., `HermesModule::EditsPublished`, `HermesModule::SpacesRegistered`).
 //!
-//! For transformers that need **multiple event types**, use `HermesModule::Actions`
-//! and filter client-side using the constants in the [`actions`] module:
+//! For transformers that need **multiple event types**, use `HermesModule::HermesOut`
+//! to receive every typed list of a block in one `HermesOutput`, or use
+//! `HermesModule::Actions` and filter client-side using [`ActionKind`] from the
+//! [`actions`] module:
 //!
 //! ```ignore
-//! use hermes_relay::{actions, HermesModule};
+//! use hermes_relay::ActionKind;
 //!
 //! fn is_space_event(action_bytes: &[u8]) -> bool {
-//!     actions::matches(action_bytes, &actions::SPACE_REGISTERED)
-//!         || actions::matches(action_bytes, &actions::SUBSPACE_ADDED)
-//!         || actions::matches(action_bytes, &actions::SUBSPACE_REMOVED)
+//!     matches!(
+//!         ActionKind::from_bytes(action_bytes),
+//!         Some(ActionKind::SpaceRegistered | ActionKind::SubspaceAdded | ActionKind::SubspaceRemoved)
+//!     )
 //! }
 //! ```
 
 pub mod actions;
 pub mod config;
+pub mod dedup;
 pub mod sink;
 pub mod source;
 
+pub use actions::ActionKind;
+
 // Re-export config types at crate root for convenience
-pub use config::{HermesModule, HERMES_SPKG};
+pub use config::{HermesModule, Network, StartBlockRegistry, HERMES_SPKG};
 
 // Re-export sink traits and stream source config
 pub use sink::{PreprocessedSink, Sink, StreamSource};
 
 // Re-export hermes-substream types for consumers
+pub use hermes_substream::decode::ACTION_VERSION_V1;
 pub use hermes_substream::pb::hermes::{Action, Actions};
 
 // Re-export stream crate for consumers who need substreams types
@@ -3,7 +3,7 @@
 //! # Example
 //!
 //! ```ignore
-//! use hermes_relay::{Sink, StreamSource, HermesModule};
+//! use hermes_relay::{Sink, StreamSource, HermesModule, Network};
 //!
 //! struct MyTransformer { /* ... */ }
 //!
@@ -19,12 +19,13 @@
 //! // Run with mock data (for development/testing)
 //! transformer.run(StreamSource::mock()).await?;
 //!
-//! // Run with live substream (for production)
+//! // Run with live substream (for production), starting at the module's
+//! // registered start block
 //! let source = StreamSource::live(
 //!     "https://substreams.example.com",
 //!     HermesModule::Actions,
+//!     Network::Testnet,
 //!     0,
-//!     1000,
 //! );
 //! transformer.run(source).await?;
 //! ```
@@ -33,7 +34,12 @@ use std::{env, process::exit, sync::Arc};
 
 use futures03::StreamExt;
 
-use crate::{source::MockSource, HermesModule, HERMES_SPKG};
+use crate::{
+    config::{Network, StartBlockRegistry},
+    dedup::CursorGuard,
+    source::MockSource,
+    HermesModule, HERMES_SPKG,
+};
 use stream::{
     pb::sf::substreams::rpc::v2::{BlockScopedData, BlockUndoSignal},
     substreams::SubstreamsEndpoint,
@@ -74,8 +80,44 @@ impl StreamSource {
         Self::Mock
     }
 
-    /// Create a live source with the given endpoint, module, and block range.
+    /// Create a live source that starts at the module's block in the default
+    /// [`StartBlockRegistry`], read from the environment.
     pub fn live(
+        endpoint_url: impl Into<String>,
+        module: HermesModule,
+        network: Network,
+        end_block: u64,
+    ) -> Self {
+        Self::live_with_registry(
+            endpoint_url,
+            module,
+            network,
+            &StartBlockRegistry::from_env(network),
+            end_block,
+        )
+    }
+
+    /// Create a live source that starts at the module's block in `registry`.
+    pub fn live_with_registry(
+        endpoint_url: impl Into<String>,
+        module: HermesModule,
+        network: Network,
+        registry: &StartBlockRegistry,
+        end_block: u64,
+    ) -> Self {
+        Self::live_from_block(
+            endpoint_url,
+            module,
+            registry.start_block(module, network),
+            end_block,
+        )
+    }
+
+    /// Create a live source with an explicit block range.
+    ///
+    /// Prefer [`StreamSource::live`] unless the start block is intentionally
+    /// different from the registered one (e.g. relative positioning).
+    pub fn live_from_block(
         endpoint_url: impl Into<String>,
         module: HermesModule,
         start_block: i64,
@@ -117,6 +159,11 @@ pub trait Sink: Send + Sync {
         async { Ok(None) }
     }
 
+    /// Called when a live stream delivers a block that was already processed,
+    /// e.g. the last block again after a reconnect. The block is skipped;
+    /// override this to count it in the sink's metrics.
+    fn record_skipped_block(&self, _block: u64) {}
+
     /// Run the sink with the specified source.
     ///
     /// # Example
@@ -129,8 +176,8 @@ pub trait Sink: Send + Sync {
     /// let source = StreamSource::live(
     ///     "https://substreams.example.com",
     ///     HermesModule::Actions,
+    ///     Network::Testnet,
     ///     0,
-    ///     1000,
     /// );
     /// sink.run(source).await?;
     /// ```
@@ -195,6 +242,7 @@ pub trait Sink: Send + Sync {
         async move {
             let token = env::var("SUBSTREAMS_API_TOKEN").ok();
             let cursor = self.load_persisted_cursor().await?;
+            let mut guard = CursorGuard::new(cursor.clone());
 
             let package = stream::read_package(HERMES_SPKG).await?;
             let endpoint = Arc::new(SubstreamsEndpoint::new(endpoint_url, token).await?);
@@ -211,21 +259,30 @@ pub trait Sink: Send + Sync {
             loop {
                 match stream.next().await {
                     None => {
-                        println!("Stream consumed");
+                        println!(
+                            "Stream consumed ({} duplicate blocks skipped)",
+                            guard.skipped()
+                        );
                         break;
                     }
                     Some(Ok(BlockResponse::New(data))) => {
+                        let block_num = data.clock.as_ref().unwrap().number;
+                        if guard.is_duplicate(&data.cursor, block_num) {
+                            println!("Skipping already processed block {}", block_num);
+                            self.record_skipped_block(block_num);
+                            continue;
+                        }
+
                         self.process_block_scoped_data(&data).await?;
-                        self.persist_cursor(data.cursor, data.clock.unwrap().number)
-                            .await?;
+                        guard.advance(&data.cursor, block_num);
+                        self.persist_cursor(data.cursor, block_num).await?;
                     }
                     Some(Ok(BlockResponse::Undo(undo_signal))) => {
                         self.process_block_undo_signal(&undo_signal)?;
-                        self.persist_cursor(
-                            undo_signal.last_valid_cursor,
-                            undo_signal.last_valid_block.unwrap().number,
-                        )
-                        .await?;
+                        let block_num = undo_signal.last_valid_block.unwrap().number;
+                        guard.rewind(&undo_signal.last_valid_cursor, block_num);
+                        self.persist_cursor(undo_signal.last_valid_cursor, block_num)
+                            .await?;
                     }
                     Some(Err(err)) => {
                         println!("Stream terminated with error: {:?}", err);
@@ -272,6 +329,11 @@ pub trait PreprocessedSink<P: Send>: Send + Sync {
         async { Ok(None) }
     }
 
+    /// Called when a live stream delivers a block that was already processed,
+    /// e.g. the last block again after a reconnect. The block is skipped;
+    /// override this to count it in the sink's metrics.
+    fn record_skipped_block(&self, _block: u64) {}
+
     /// Run the sink with the specified source.
     fn run(
         &self,
@@ -338,6 +400,7 @@ pub trait PreprocessedSink<P: Send>: Send + Sync {
         async move {
             let token = env::var("SUBSTREAMS_API_TOKEN").ok();
             let cursor = self.load_persisted_cursor().await?;
+            let mut guard = CursorGuard::new(cursor.clone());
 
             let package = stream::read_package(HERMES_SPKG).await?;
             let endpoint = Arc::new(SubstreamsEndpoint::new(endpoint_url, token).await?);
@@ -354,22 +417,31 @@ pub trait PreprocessedSink<P: Send>: Send + Sync {
             loop {
                 match stream.next().await {
                     None => {
-                        println!("Stream consumed");
+                        println!(
+                            "Stream consumed ({} duplicate blocks skipped)",
+                            guard.skipped()
+                        );
                         break;
                     }
                     Some(Ok(BlockResponse::New(data))) => {
+                        let block_num = data.clock.as_ref().unwrap().number;
+                        if guard.is_duplicate(&data.cursor, block_num) {
+                            println!("Skipping already processed block {}", block_num);
+                            self.record_skipped_block(block_num);
+                            continue;
+                        }
+
                         let preprocessed = self.preprocess_block_scoped_data(&data).await?;
                         self.process_block_scoped_data(&data, preprocessed).await?;
-                        self.persist_cursor(data.cursor, data.clock.unwrap().number)
-                            .await?;
+                        guard.advance(&data.cursor, block_num);
+                        self.persist_cursor(data.cursor, block_num).await?;
                     }
                     Some(Ok(BlockResponse::Undo(undo_signal))) => {
                         self.process_block_undo_signal(&undo_signal)?;
-                        self.persist_cursor(
-                            undo_signal.last_valid_cursor,
-                            undo_signal.last_valid_block.unwrap().number,
-                        )
-                        .await?;
+                        let block_num = undo_signal.last_valid_block.unwrap().number;
+                        guard.rewind(&undo_signal.last_valid_cursor, block_num);
+                        self.persist_cursor(undo_signal.last_valid_cursor, block_num)
+                            .await?;
                     }
                     Some(Err(err)) => {
                         println!("Stream terminated with error: {:?}", err);
@@ -23,6 +23,7 @@
 //! ```
 
 use crate::actions;
+use hermes_substream::decode::ACTION_VERSION_V1;
 use hermes_substream::pb::hermes::Action;
 
 // Trust extension type bytes (first 2 bytes of data field)
@@ -55,6 +56,8 @@ pub fn space_created(space_id: SpaceId, owner: Address) -> Action {
         action: actions::SPACE_REGISTERED.to_vec(),
         topic: owner.to_vec(),
         data: vec![],
+        decoded_version: ACTION_VERSION_V1,
+        ..Default::default()
     }
 }
 
@@ -91,6 +94,8 @@ pub fn space_created_dao(
         action: actions::SPACE_REGISTERED.to_vec(),
         topic: vec![0u8; 32], // No owner for DAO
         data,
+        decoded_version: ACTION_VERSION_V1,
+        ..Default::default()
     }
 }
 
@@ -114,6 +119,8 @@ pub fn trust_extended_verified(source_space_id: SpaceId, target_space_id: SpaceI
         action: actions::SUBSPACE_ADDED.to_vec(),
         topic,
         data: TRUST_TYPE_VERIFIED.to_vec(),
+        decoded_version: ACTION_VERSION_V1,
+        ..Default::default()
     }
 }
 
@@ -133,6 +140,8 @@ pub fn trust_extended_related(source_space_id: SpaceId, target_space_id: SpaceId
         action: actions::SUBSPACE_ADDED.to_vec(),
         topic,
         data: TRUST_TYPE_RELATED.to_vec(),
+        decoded_version: ACTION_VERSION_V1,
+        ..Default::default()
     }
 }
 
@@ -152,6 +161,8 @@ pub fn trust_extended_subtopic(source_space_id: SpaceId, target_topic_id: TopicI
         action: actions::SUBSPACE_ADDED.to_vec(),
         topic,
         data: TRUST_TYPE_SUBTOPIC.to_vec(),
+        decoded_version: ACTION_VERSION_V1,
+        ..Default::default()
     }
 }
 
@@ -172,6 +183,8 @@ pub fn edit_published(space_id: SpaceId, ipfs_hash: &str) -> Action {
         action: actions::EDITS_PUBLISHED.to_vec(),
         topic: vec![0u8; 32],
         data: ipfs_hash.as_bytes().to_vec(),
+        decoded_version: ACTION_VERSION_V1,
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,237 @@
+//! Verification of edit authors against space membership.
+//!
+//! Authors are addresses. The owner of a personal space is its only editor.
+//! Editors and members of a DAO space are personal space IDs, so an author
+//! counts for a DAO space when a personal space they own is an editor or
+//! member of it.
+//!
+//! [`SpaceMembership`] builds this view from `space.creations` messages and
+//! membership changes. It keeps the history of every membership, so each
+//! edit is checked against the membership at its own block.
+
+use std::collections::HashMap;
+
+use crate::pb::knowledge::HermesEdit;
+use crate::pb::space::{hermes_create_space::Payload, HermesCreateSpace};
+
+/// Decides whether the authors of an edit were allowed to publish it.
+///
+/// Implemented by [`SpaceMembership`] from the event stream; a lookup
+/// service can implement it as well.
+pub trait AuthorVerifier {
+    /// Whether every author of `edit` was a member or editor of its space at
+    /// the block it was published in. Edits without authors, a valid space
+    /// ID or block metadata are never verified.
+    fn verify_authors(&self, edit: &HermesEdit) -> bool;
+}
+
+/// A space and a personal space that may be one of its editors or members.
+type MembershipKey = (Vec<u8>, Vec<u8>);
+
+/// Space membership over time, built from the event stream.
+#[derive(Debug, Default)]
+pub struct SpaceMembership {
+    /// Owner address -> personal spaces they own, with their creation block.
+    owned_spaces: HashMap<Vec<u8>, Vec<(Vec<u8>, u64)>>,
+    /// (space, member personal space) -> membership changes as
+    /// `(block, is_member)`, in block order.
+    changes: HashMap<MembershipKey, Vec<(u64, bool)>>,
+}
+
+impl SpaceMembership {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a space creation: the owner of a personal space, or the
+    /// initial editors and members of a DAO space.
+    pub fn record_space(&mut self, space: &HermesCreateSpace) {
+        let block = space.meta.as_ref().map_or(0, |meta| meta.block_number);
+
+        match &space.payload {
+            Some(Payload::PersonalSpace(personal)) => {
+                self.owned_spaces
+                    .entry(personal.owner.clone())
+                    .or_default()
+                    .push((space.space_id.clone(), block));
+                self.record_change(&space.space_id, &space.space_id, block, true);
+            }
+            Some(Payload::DefaultDaoSpace(dao)) => {
+                for member in dao.initial_editors.iter().chain(&dao.initial_members) {
+                    self.record_change(&space.space_id, member, block, true);
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Record a personal space becoming an editor or member of a space.
+    pub fn add_member(&mut self, space_id: &[u8], member_space_id: &[u8], block: u64) {
+        self.record_change(space_id, member_space_id, block, true);
+    }
+
+    /// Record a personal space no longer being an editor or member of a space.
+    pub fn remove_member(&mut self, space_id: &[u8], member_space_id: &[u8], block: u64) {
+        self.record_change(space_id, member_space_id, block, false);
+    }
+
+    /// Whether `author` owned a personal space that was an editor or member
+    /// of `space_id` at `block`.
+    pub fn is_member(&self, space_id: &[u8], author: &[u8], block: u64) -> bool {
+        self.owned_spaces
+            .get(author)
+            .into_iter()
+            .flatten()
+            .filter(|(_, created_at)| *created_at <= block)
+            .any(|(personal_space, _)| self.was_member(space_id, personal_space, block))
+    }
+
+    fn was_member(&self, space_id: &[u8], member_space_id: &[u8], block: u64) -> bool {
+        let Some(changes) = self
+            .changes
+            .get(&(space_id.to_vec(), member_space_id.to_vec()))
+        else {
+            return false;
+        };
+
+        let applied = changes.partition_point(|(at, _)| *at <= block);
+        applied > 0 && changes[applied - 1].1
+    }
+
+    fn record_change(
+        &mut self,
+        space_id: &[u8],
+        member_space_id: &[u8],
+        block: u64,
+        is_member: bool,
+    ) {
+        let changes = self
+            .changes
+            .entry((space_id.to_vec(), member_space_id.to_vec()))
+            .or_default();
+
+        // Changes within the same block apply in the order they were recorded
+        let index = changes.partition_point(|(at, _)| *at <= block);
+        changes.insert(index, (block, is_member));
+    }
+}
+
+impl AuthorVerifier for SpaceMembership {
+    fn verify_authors(&self, edit: &HermesEdit) -> bool {
+        let Ok(space_id) = hex::decode(&edit.space_id) else {
+            return false;
+        };
+        let Some(block) = edit.meta.as_ref().map(|meta| meta.block_number) else {
+            return false;
+        };
+
+        !edit.authors.is_empty()
+            && edit
+                .authors
+                .iter()
+                .all(|author| self.is_member(&space_id, author, block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::blockchain_metadata::BlockchainMetadata;
+    use crate::pb::space::{DefaultDaoSpacePayload, PersonalSpacePayload};
+
+    const ALICE: [u8; 20] = [0xaa; 20];
+    const BOB: [u8; 20] = [0xbb; 20];
+    const ALICE_SPACE: [u8; 16] = [0x01; 16];
+    const BOB_SPACE: [u8; 16] = [0x02; 16];
+    const DAO_SPACE: [u8; 16] = [0x03; 16];
+
+    fn meta(block_number: u64) -> Option<BlockchainMetadata> {
+        Some(BlockchainMetadata {
+            created_at: 0,
+            created_by: vec![],
+            block_number,
+            cursor: String::new(),
+            ..Default::default()
+        })
+    }
+
+    fn personal_space(space_id: [u8; 16], owner: [u8; 20], block: u64) -> HermesCreateSpace {
+        HermesCreateSpace {
+            space_id: space_id.to_vec(),
+            topic_id: vec![],
+            payload: Some(Payload::PersonalSpace(PersonalSpacePayload {
+                owner: owner.to_vec(),
+            })),
+            meta: meta(block),
+        }
+    }
+
+    fn edit(space_id: [u8; 16], authors: &[[u8; 20]], block: u64) -> HermesEdit {
+        HermesEdit {
+            id: vec![],
+            name: "Edit".to_string(),
+            ops: vec![],
+            authors: authors.iter().map(|author| author.to_vec()).collect(),
+            language: None,
+            space_id: hex::encode(space_id),
+            is_canonical: true,
+            meta: meta(block),
+            verified_authors: false,
+        }
+    }
+
+    fn membership() -> SpaceMembership {
+        let mut membership = SpaceMembership::new();
+        membership.record_space(&personal_space(ALICE_SPACE, ALICE, 10));
+        membership.record_space(&personal_space(BOB_SPACE, BOB, 10));
+        membership.record_space(&HermesCreateSpace {
+            space_id: DAO_SPACE.to_vec(),
+            topic_id: vec![],
+            payload: Some(Payload::DefaultDaoSpace(DefaultDaoSpacePayload {
+                initial_editors: vec![ALICE_SPACE.to_vec()],
+                initial_members: vec![],
+            })),
+            meta: meta(20),
+        });
+        membership
+    }
+
+    #[test]
+    fn test_personal_space_owner_is_verified() {
+        let membership = membership();
+
+        assert!(membership.verify_authors(&edit(ALICE_SPACE, &[ALICE], 15)));
+        assert!(!membership.verify_authors(&edit(ALICE_SPACE, &[BOB], 15)));
+        assert!(!membership.verify_authors(&edit(ALICE_SPACE, &[ALICE, BOB], 15)));
+        // Before the space existed
+        assert!(!membership.verify_authors(&edit(ALICE_SPACE, &[ALICE], 5)));
+    }
+
+    #[test]
+    fn test_dao_membership_is_checked_at_the_edit_block() {
+        let mut membership = membership();
+        membership.add_member(&DAO_SPACE, &BOB_SPACE, 30);
+        membership.remove_member(&DAO_SPACE, &ALICE_SPACE, 40);
+
+        assert!(membership.verify_authors(&edit(DAO_SPACE, &[ALICE], 20)));
+        assert!(!membership.verify_authors(&edit(DAO_SPACE, &[BOB], 29)));
+        assert!(membership.verify_authors(&edit(DAO_SPACE, &[ALICE, BOB], 35)));
+        assert!(!membership.verify_authors(&edit(DAO_SPACE, &[ALICE], 40)));
+        assert!(membership.verify_authors(&edit(DAO_SPACE, &[BOB], 40)));
+    }
+
+    #[test]
+    fn test_incomplete_edits_are_not_verified() {
+        let membership = membership();
+
+        assert!(!membership.verify_authors(&edit(ALICE_SPACE, &[], 15)));
+
+        let mut no_meta = edit(ALICE_SPACE, &[ALICE], 15);
+        no_meta.meta = None;
+        assert!(!membership.verify_authors(&no_meta));
+
+        let mut bad_space = edit(ALICE_SPACE, &[ALICE], 15);
+        bad_space.space_id = "not hex".to_string();
+        assert!(!membership.verify_authors(&bad_space));
+    }
+}
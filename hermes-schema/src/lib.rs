@@ -1 +1,6 @@
+pub mod authors;
 pub mod pb;
+pub mod space_kind;
+
+pub use authors::{AuthorVerifier, SpaceMembership};
+pub use space_kind::SpaceKind;
@@ -0,0 +1,50 @@
+// This file is @generated by prost-build.
+/// Emitted by the actions indexer when the vote counts of an object change.
+/// Carries the new totals rather than the votes that changed them, so a
+/// consumer can overwrite its copy without tracking the previous counts.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VotesCountChanged {
+    /// uuid of the voted entity or relation
+    #[prost(bytes = "vec", tag = "1")]
+    pub object_id: ::prost::alloc::vec::Vec<u8>,
+    /// uuid of the space the votes were cast in
+    #[prost(bytes = "vec", tag = "2")]
+    pub space_id: ::prost::alloc::vec::Vec<u8>,
+    #[prost(enumeration = "ObjectType", tag = "3")]
+    pub object_type: i32,
+    /// Totals once the block was persisted
+    #[prost(int64, tag = "4")]
+    pub upvotes: i64,
+    #[prost(int64, tag = "5")]
+    pub downvotes: i64,
+    /// Block whose votes changed the totals. Counts of an older block than the
+    /// last one seen for the same object and space are stale.
+    #[prost(uint64, tag = "6")]
+    pub block_number: u64,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ObjectType {
+    Entity = 0,
+    Relation = 1,
+}
+impl ObjectType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Entity => "OBJECT_TYPE_ENTITY",
+            Self::Relation => "OBJECT_TYPE_RELATION",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OBJECT_TYPE_ENTITY" => Some(Self::Entity),
+            "OBJECT_TYPE_RELATION" => Some(Self::Relation),
+            _ => None,
+        }
+    }
+}
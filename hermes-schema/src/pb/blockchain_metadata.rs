@@ -11,4 +11,15 @@ pub struct BlockchainMetadata {
     pub block_number: u64,
     #[prost(string, tag = "4")]
     pub cursor: ::prost::alloc::string::String,
+    /// network the block was streamed from, empty if unknown
+    #[prost(string, tag = "5")]
+    pub chain_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub block_hash: ::prost::alloc::string::String,
+    /// empty if the stream doesn't report it
+    #[prost(string, tag = "7")]
+    pub parent_hash: ::prost::alloc::string::String,
+    /// false if data from this block may still be undone by a reorg
+    #[prost(bool, tag = "8")]
+    pub finalized: bool,
 }
@@ -27,4 +27,8 @@ pub struct HermesEdit {
     pub is_canonical: bool,
     #[prost(message, optional, tag = "8")]
     pub meta: ::core::option::Option<super::blockchain_metadata::BlockchainMetadata>,
+    /// Whether every author was a member or editor of the space at the block
+    /// the edit was published in. Only set when author verification is enabled.
+    #[prost(bool, tag = "9")]
+    pub verified_authors: bool,
 }
@@ -1,3 +1,4 @@
+pub mod actions;
 pub mod blockchain_metadata;
 pub mod knowledge;
 pub mod space;
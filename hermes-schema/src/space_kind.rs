@@ -0,0 +1,58 @@
+//! Space classification for hermes space creation events.
+
+pub use indexer_utils::space_kind::SpaceKind;
+
+use crate::pb::space::{hermes_create_space::Payload, HermesCreateSpace};
+
+impl HermesCreateSpace {
+    /// Kind of space created, or `None` if the payload is missing.
+    pub fn space_kind(&self) -> Option<SpaceKind> {
+        match self.payload.as_ref()? {
+            Payload::PersonalSpace(_) => Some(SpaceKind::Personal),
+            Payload::DefaultDaoSpace(_) => Some(SpaceKind::Dao),
+        }
+    }
+
+    /// Value of the `space-type` header on `space.creations` messages.
+    pub fn space_type_header(&self) -> &'static str {
+        match self.space_kind() {
+            Some(SpaceKind::Personal) => "PERSONAL",
+            Some(SpaceKind::Dao | SpaceKind::Successor) => "DEFAULT_DAO",
+            None => "UNKNOWN",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::space::{DefaultDaoSpacePayload, PersonalSpacePayload};
+
+    fn create_space(payload: Option<Payload>) -> HermesCreateSpace {
+        HermesCreateSpace {
+            space_id: vec![0x01; 16],
+            topic_id: vec![0x02; 16],
+            meta: None,
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_space_kind_from_payload() {
+        let personal = create_space(Some(Payload::PersonalSpace(PersonalSpacePayload {
+            owner: vec![0x03; 32],
+        })));
+        let dao = create_space(Some(Payload::DefaultDaoSpace(DefaultDaoSpacePayload {
+            initial_editors: vec![],
+            initial_members: vec![],
+        })));
+        let unknown = create_space(None);
+
+        assert_eq!(personal.space_kind(), Some(SpaceKind::Personal));
+        assert_eq!(personal.space_type_header(), "PERSONAL");
+        assert_eq!(dao.space_kind(), Some(SpaceKind::Dao));
+        assert_eq!(dao.space_type_header(), "DEFAULT_DAO");
+        assert_eq!(unknown.space_kind(), None);
+        assert_eq!(unknown.space_type_header(), "UNKNOWN");
+    }
+}
@@ -25,6 +25,10 @@ fn convert_block_metadata(meta: &BlockMetadata) -> BlockchainMetadata {
         created_by: vec![], // Not available in block metadata
         block_number: meta.block_number,
         cursor: meta.cursor.clone(),
+        chain_id: meta.chain_id.clone().unwrap_or_default(),
+        block_hash: meta.block_hash.clone(),
+        parent_hash: meta.parent_hash.clone().unwrap_or_default(),
+        finalized: meta.finalized,
     }
 }
 
@@ -128,12 +132,17 @@ pub fn convert_subspace_removed(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hermes_relay::ACTION_VERSION_V1;
 
     fn test_block_metadata() -> BlockMetadata {
         BlockMetadata {
             cursor: "test_cursor".to_string(),
             block_number: 12345,
             timestamp: "1234567890".to_string(),
+            chain_id: Some("testnet".to_string()),
+            block_hash: "0xabc".to_string(),
+            parent_hash: None,
+            finalized: true,
         }
     }
 
@@ -145,6 +154,8 @@ mod tests {
             action: vec![0; 32],
             topic: vec![2; 32],
             data: vec![],
+            decoded_version: ACTION_VERSION_V1,
+            ..Default::default()
         };
 
         let result = convert_space_registered(&action, &test_block_metadata()).unwrap();
@@ -161,6 +172,8 @@ mod tests {
             action: vec![0; 32],
             topic: vec![2; 32],
             data: vec![],
+            decoded_version: ACTION_VERSION_V1,
+            ..Default::default()
         };
 
         let result = convert_subspace_added(&action, &test_block_metadata()).unwrap();
@@ -26,15 +26,7 @@ pub fn send_space_creation(producer: &BaseProducer, space: &HermesCreateSpace) -
     let mut payload = Vec::new();
     space.encode(&mut payload)?;
 
-    let space_type = match &space.payload {
-        Some(hermes_schema::pb::space::hermes_create_space::Payload::PersonalSpace(_)) => {
-            "PERSONAL"
-        }
-        Some(hermes_schema::pb::space::hermes_create_space::Payload::DefaultDaoSpace(_)) => {
-            "DEFAULT_DAO"
-        }
-        None => "UNKNOWN",
-    };
+    let space_type = space.space_type_header();
 
     let record = BaseRecord::to(TOPIC_SPACE_CREATIONS)
         .key(&space.space_id)
@@ -16,6 +16,7 @@
 //! - `KAFKA_USERNAME` - SASL username for managed Kafka (optional)
 //! - `KAFKA_PASSWORD` - SASL password for managed Kafka (optional)
 //! - `KAFKA_SSL_CA_PEM` - Custom CA cert for SSL (optional)
+//! - `HERMES_NETWORK` - Network stamped on the outputs' metadata (default: testnet)
 
 mod conversion;
 mod kafka;
@@ -25,7 +26,7 @@ use std::env;
 
 use anyhow::Result;
 
-use hermes_relay::{HermesModule, Sink, StreamSource};
+use hermes_relay::{HermesModule, Network, Sink, StreamSource};
 
 use kafka::create_producer;
 use transformer::SpacesTransformer;
@@ -35,9 +36,14 @@ async fn main() -> Result<()> {
     println!("Hermes Spaces Transformer starting...");
 
     let broker = env::var("KAFKA_BROKER").unwrap_or_else(|_| "localhost:9092".to_string());
+    let network: Network = env::var("HERMES_NETWORK")
+        .unwrap_or_else(|_| Network::Testnet.to_string())
+        .parse()
+        .map_err(anyhow::Error::msg)?;
 
     println!("Configuration:");
     println!("  Kafka broker: {}", broker);
+    println!("  Network: {}", network);
 
     // Create Kafka producer
     println!("\nConnecting to Kafka broker...");
@@ -45,7 +51,7 @@ async fn main() -> Result<()> {
     println!("Connected to Kafka broker");
 
     // Create the transformer
-    let transformer = SpacesTransformer::new(producer);
+    let transformer = SpacesTransformer::new(producer, network);
 
     println!("\nStarting spaces transformer with mock data...");
     println!("Subscribing to module: {}", HermesModule::Actions);
@@ -7,7 +7,7 @@ use hermes_kafka::BaseProducer;
 
 use hermes_relay::stream::pb::sf::substreams::rpc::v2::BlockScopedData;
 use hermes_relay::stream::utils;
-use hermes_relay::{actions, Actions, Sink};
+use hermes_relay::{actions, Actions, Network, Sink};
 use hermes_schema::pb::space::HermesSpaceTrustExtension;
 
 use crate::conversion::{
@@ -51,11 +51,13 @@ impl From<prost::DecodeError> for TransformerError {
 /// - `SUBSPACE_REMOVED` - trust revocations
 pub struct SpacesTransformer {
     producer: BaseProducer,
+    /// Network the actions are streamed from, stamped on every output.
+    network: Network,
 }
 
 impl SpacesTransformer {
-    pub fn new(producer: BaseProducer) -> Self {
-        Self { producer }
+    pub fn new(producer: BaseProducer, network: Network) -> Self {
+        Self { producer, network }
     }
 }
 
@@ -64,7 +66,7 @@ impl Sink for SpacesTransformer {
 
     async fn process_block_scoped_data(&self, data: &BlockScopedData) -> Result<(), Self::Error> {
         let output = utils::output(data);
-        let block_meta = utils::block_metadata(data);
+        let block_meta = utils::block_metadata(data).with_chain_id(self.network.as_str());
 
         // Decode the Actions message from the block output
         let actions_msg = Actions::decode(output.value.as_slice())?;
@@ -0,0 +1,173 @@
+//! Decoding of the Space Registry `Action` event.
+//!
+//! The version of an action is read from the shape of its log, and every field is sliced
+//! with bounds checks, so a registry upgrade emitting a new encoding is reported as a
+//! [`DecodeError`] instead of panicking the module.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::pb::hermes::Action;
+
+/// The anonymous `Action(bytes16 indexed fromId, bytes16 indexed toId, bytes32 indexed action,
+/// bytes32 indexed topic, bytes data)` event.
+pub const ACTION_VERSION_V1: u32 = 1;
+
+/// Topics of a v1 action, one per indexed field.
+const V1_TOPICS: usize = 4;
+/// Size of each topic.
+const TOPIC_SIZE: usize = 32;
+/// Position of the 16 byte space IDs in their topics.
+const SPACE_ID_RANGE: Range<usize> = 16..32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The log doesn't match any known encoding of the Action event.
+    UnknownEncoding { topics: usize },
+    /// The action was decoded with a version the caller doesn't handle.
+    UnsupportedVersion(u32),
+    /// A field is shorter than the range read from it.
+    OutOfBounds {
+        field: &'static str,
+        range: Range<usize>,
+        len: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownEncoding { topics } => {
+                write!(f, "unknown action encoding with {} topics", topics)
+            }
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported action version {}", version)
+            }
+            DecodeError::OutOfBounds { field, range, len } => write!(
+                f,
+                "{} has {} bytes, can't read {}..{}",
+                field, len, range.start, range.end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decodes an Action event from the topics and data of a Space Registry log.
+pub fn decode_action(topics: &[Vec<u8>], data: &[u8]) -> Result<Action, DecodeError> {
+    match action_version(topics)? {
+        ACTION_VERSION_V1 => decode_v1(topics, data),
+        version => Err(DecodeError::UnsupportedVersion(version)),
+    }
+}
+
+/// The version discriminator: the encoding an action log was emitted with.
+///
+/// v1 is the only anonymous 4 topic event of the registry. New encodings get their own
+/// branch here, keyed on what sets them apart from v1.
+fn action_version(topics: &[Vec<u8>]) -> Result<u32, DecodeError> {
+    match topics.len() {
+        V1_TOPICS => Ok(ACTION_VERSION_V1),
+        topics => Err(DecodeError::UnknownEncoding { topics }),
+    }
+}
+
+fn decode_v1(topics: &[Vec<u8>], data: &[u8]) -> Result<Action, DecodeError> {
+    for topic in topics {
+        field("topic", topic, 0..TOPIC_SIZE)?;
+    }
+
+    Ok(Action {
+        from_id: field("from_id", &topics[0], SPACE_ID_RANGE)?.to_vec(),
+        to_id: field("to_id", &topics[1], SPACE_ID_RANGE)?.to_vec(),
+        action: topics[2].to_vec(),
+        topic: topics[3].to_vec(),
+        data: data.to_vec(),
+        decoded_version: ACTION_VERSION_V1,
+        ..Default::default()
+    })
+}
+
+/// Fails unless `action` was decoded with `version`.
+pub fn expect_version(action: &Action, version: u32) -> Result<(), DecodeError> {
+    if action.decoded_version != version {
+        return Err(DecodeError::UnsupportedVersion(action.decoded_version));
+    }
+
+    Ok(())
+}
+
+/// Reads `range` of a field, failing if the field is too short.
+pub fn field<'a>(
+    name: &'static str,
+    bytes: &'a [u8],
+    range: Range<usize>,
+) -> Result<&'a [u8], DecodeError> {
+    bytes.get(range.clone()).ok_or(DecodeError::OutOfBounds {
+        field: name,
+        range,
+        len: bytes.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topics() -> Vec<Vec<u8>> {
+        let mut from_id = vec![0; 32];
+        from_id[16..].copy_from_slice(&[1; 16]);
+        let mut to_id = vec![0; 32];
+        to_id[16..].copy_from_slice(&[2; 16]);
+
+        vec![from_id, to_id, vec![3; 32], vec![4; 32]]
+    }
+
+    #[test]
+    fn test_decode_action_v1() {
+        let action = decode_action(&topics(), &[5, 6]).unwrap();
+
+        assert_eq!(action.decoded_version, ACTION_VERSION_V1);
+        assert_eq!(action.from_id, vec![1; 16]);
+        assert_eq!(action.to_id, vec![2; 16]);
+        assert_eq!(action.action, vec![3; 32]);
+        assert_eq!(action.topic, vec![4; 32]);
+        assert_eq!(action.data, vec![5, 6]);
+        assert!(expect_version(&action, ACTION_VERSION_V1).is_ok());
+        assert_eq!(
+            expect_version(&action, 2),
+            Err(DecodeError::UnsupportedVersion(ACTION_VERSION_V1))
+        );
+    }
+
+    #[test]
+    fn test_decode_action_rejects_unknown_encodings() {
+        let mut topics = topics();
+        topics.pop();
+        assert_eq!(
+            decode_action(&topics, &[]),
+            Err(DecodeError::UnknownEncoding { topics: 3 })
+        );
+
+        let mut topics = self::topics();
+        topics[3] = vec![4; 20];
+        assert_eq!(
+            decode_action(&topics, &[]),
+            Err(DecodeError::OutOfBounds {
+                field: "topic",
+                range: 0..32,
+                len: 20
+            })
+        );
+    }
+
+    #[test]
+    fn test_field_is_bounds_checked() {
+        assert_eq!(field("topic", &[1, 2, 3], 1..3), Ok(&[2, 3][..]));
+        assert!(matches!(
+            field("topic", &[1, 2, 3], 0..4),
+            Err(DecodeError::OutOfBounds { len: 3, .. })
+        ));
+    }
+}
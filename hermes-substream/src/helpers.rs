@@ -1,6 +1,608 @@
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::store::{DeltaInt64, DeltaString};
 use substreams::Hex;
 
+use crate::pb::hermes::{
+    FlagCountDelta, MembershipChange, MembershipDelta, MembershipRole, ProposalCreated,
+    ProposalExecuted, ProposalStatus, ProposalUpdate, ProposalVoted, TopicDeclared,
+};
+
+/// Prefix of the keys in `store_flag_counts`.
+const FLAG_COUNT_PREFIX: &str = "flags";
+/// Prefix of the keys in `store_editors`.
+const EDITOR_PREFIX: &str = "editor";
+/// Prefix of the keys in `store_members`.
+const MEMBER_PREFIX: &str = "member";
+/// Prefix of the keys in `store_object_votes`.
+const VOTE_PREFIX: &str = "vote";
+/// Prefix of the keys in `store_open_proposals`.
+const PROPOSAL_PREFIX: &str = "proposal";
+
+/// Value of an editor or member in `store_editors` and `store_members`.
+pub const MEMBERSHIP_ACTIVE: &str = "active";
+/// Value of a flagged editor in `store_editors`.
+pub const MEMBERSHIP_FLAGGED: &str = "flagged";
+
+/// Value of an upvote in `store_object_votes`, and prefix of upvote totals in
+/// `store_object_vote_totals`.
+pub const VOTE_UP: &str = "up";
+/// Value of a downvote in `store_object_votes`, and prefix of downvote totals in
+/// `store_object_vote_totals`.
+pub const VOTE_DOWN: &str = "down";
+
 /// Returns the hex representation of the address in lowercase with 0x prefix
 pub fn format_hex(address: &[u8]) -> String {
     format!("0x{}", Hex(address).to_string())
 }
+
+/// Parses the Space Registry addresses in a module's params, separated by commas.
+///
+/// At least one registry is required: a package without one would silently match nothing.
+pub fn parse_registry_addresses(params: &str) -> Result<Vec<[u8; 20]>, substreams::errors::Error> {
+    let registries = params
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            parse_address(address).ok_or_else(|| {
+                substreams::errors::Error::msg(format!(
+                    "invalid Space Registry address '{}' in params",
+                    address
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if registries.is_empty() {
+        return Err(substreams::errors::Error::msg(
+            "no Space Registry address in params",
+        ));
+    }
+
+    Ok(registries)
+}
+
+/// Decodes a `0x`-prefixed 20 byte hex address.
+fn parse_address(address: &str) -> Option<[u8; 20]> {
+    let hex = address.strip_prefix("0x")?;
+    if hex.len() != 40 || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut bytes = [0u8; 20];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+
+    Some(bytes)
+}
+
+/// Orders topics so a topic declared under a parent from the same batch comes after it,
+/// keeping the log order otherwise.
+///
+/// Topics in a parent cycle are left at the end in log order.
+pub fn order_parents_first(topics: Vec<TopicDeclared>) -> Vec<TopicDeclared> {
+    let mut ordered = Vec::with_capacity(topics.len());
+    let mut remaining = topics;
+
+    while !remaining.is_empty() {
+        let remaining_ids: Vec<Vec<u8>> = remaining
+            .iter()
+            .map(|topic| topic.topic_id.clone())
+            .collect();
+        let (ready, waiting): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|topic| {
+            topic.parent_topic_id == topic.topic_id
+                || !remaining_ids.contains(&topic.parent_topic_id)
+        });
+
+        if ready.is_empty() {
+            ordered.extend(waiting);
+            break;
+        }
+
+        ordered.extend(ready);
+        remaining = waiting;
+    }
+
+    ordered
+}
+
+/// A store key of an ID within a scope, like an account within a space:
+/// `<prefix>:<scope>:<id>` in hex.
+fn store_key(prefix: &str, scope: &[u8], id: &[u8]) -> String {
+    format!("{}:{}:{}", prefix, Hex(scope), Hex(id))
+}
+
+/// The scope and ID of a key built by [`store_key`].
+fn parse_store_key(prefix: &str, key: &str) -> Option<(Vec<u8>, Vec<u8>)> {
+    let mut segments = key.split(':');
+    if segments.next()? != prefix {
+        return None;
+    }
+
+    let scope = Hex::decode(segments.next()?).ok()?;
+    let id = Hex::decode(segments.next()?).ok()?;
+    if segments.next().is_some() {
+        return None;
+    }
+
+    Some((scope, id))
+}
+
+/// The `store_flag_counts` key of a flagged object: `flags:<space_id>:<object_id>` in hex.
+pub fn flag_count_key(space_id: &[u8], object_id: &[u8]) -> String {
+    store_key(FLAG_COUNT_PREFIX, space_id, object_id)
+}
+
+/// The `store_editors` key of an editor: `editor:<space_id>:<address>` in hex.
+pub fn editor_key(space_id: &[u8], address: &[u8]) -> String {
+    store_key(EDITOR_PREFIX, space_id, address)
+}
+
+/// The `store_members` key of a member: `member:<space_id>:<address>` in hex.
+pub fn member_key(space_id: &[u8], address: &[u8]) -> String {
+    store_key(MEMBER_PREFIX, space_id, address)
+}
+
+/// Collapses the `store_flag_counts` deltas of a block into one delta per key, from the
+/// count before the first flag to the count after the last, in the order keys were
+/// first flagged.
+pub fn flag_count_deltas(deltas: Vec<DeltaInt64>) -> Vec<FlagCountDelta> {
+    let mut collapsed: Vec<(String, FlagCountDelta)> = Vec::new();
+
+    for delta in deltas {
+        if let Some((_, existing)) = collapsed.iter_mut().find(|(key, _)| *key == delta.key) {
+            existing.new_count = delta.new_value;
+            continue;
+        }
+
+        let Some((space_id, object_id)) = parse_store_key(FLAG_COUNT_PREFIX, &delta.key) else {
+            continue;
+        };
+        collapsed.push((
+            delta.key,
+            FlagCountDelta {
+                space_id,
+                object_id,
+                old_count: delta.old_value,
+                new_count: delta.new_value,
+            },
+        ));
+    }
+
+    collapsed.into_iter().map(|(_, delta)| delta).collect()
+}
+
+/// Turns the deltas of `store_editors` or `store_members` into membership changes, in
+/// the order they were applied.
+///
+/// Setting an entry that already had the same value, like adding an existing member,
+/// isn't a change and is skipped.
+pub fn membership_deltas(role: MembershipRole, deltas: Vec<DeltaString>) -> Vec<MembershipDelta> {
+    let prefix = match role {
+        MembershipRole::Editor => EDITOR_PREFIX,
+        MembershipRole::Member => MEMBER_PREFIX,
+        MembershipRole::Unspecified => return vec![],
+    };
+
+    deltas
+        .into_iter()
+        .filter_map(|delta| {
+            let change = match delta.operation {
+                Operation::Create if delta.new_value == MEMBERSHIP_FLAGGED => {
+                    MembershipChange::Flagged
+                }
+                Operation::Create => MembershipChange::Added,
+                Operation::Update if delta.old_value == delta.new_value => return None,
+                Operation::Update if delta.new_value == MEMBERSHIP_FLAGGED => {
+                    MembershipChange::Flagged
+                }
+                Operation::Update => MembershipChange::Unflagged,
+                Operation::Delete => MembershipChange::Removed,
+                Operation::Unset => return None,
+            };
+            let (space_id, account) = parse_store_key(prefix, &delta.key)?;
+
+            Some(MembershipDelta {
+                space_id,
+                account,
+                role: role as i32,
+                change: change as i32,
+                ordinal: delta.ordinal,
+            })
+        })
+        .collect()
+}
+
+/// The `store_object_votes` key of a vote: `vote:<object_type><object_id>:<voter_id>` in hex.
+pub fn object_vote_key(object_type: &[u8], object_id: &[u8], voter_id: &[u8]) -> String {
+    store_key(VOTE_PREFIX, &[object_type, object_id].concat(), voter_id)
+}
+
+/// The `store_object_vote_totals` key of an object's upvotes or downvotes:
+/// `<up|down>:<object_type><object_id>` in hex.
+pub fn vote_total_key(direction: &str, object: &[u8]) -> String {
+    format!("{}:{}", direction, Hex(object))
+}
+
+/// Sums how the vote changes of a block, the deltas of `store_object_votes`, move the
+/// upvotes and downvotes of each object. Objects are listed in the order they were first
+/// voted on, as `(object_type ++ object_id, upvotes, downvotes)`.
+///
+/// A vote that didn't change, like upvoting twice, doesn't move the tallies.
+pub fn object_vote_changes(deltas: Vec<DeltaString>) -> Vec<(Vec<u8>, i64, i64)> {
+    let mut changes: Vec<(Vec<u8>, i64, i64)> = Vec::new();
+
+    for delta in deltas {
+        let Some((object, _voter_id)) = parse_store_key(VOTE_PREFIX, &delta.key) else {
+            continue;
+        };
+        let old_vote = match delta.operation {
+            Operation::Create => "",
+            _ => delta.old_value.as_str(),
+        };
+        let new_vote = match delta.operation {
+            Operation::Delete => "",
+            _ => delta.new_value.as_str(),
+        };
+        let count = |vote: &str, direction: &str| i64::from(vote == direction);
+        let upvotes = count(new_vote, VOTE_UP) - count(old_vote, VOTE_UP);
+        let downvotes = count(new_vote, VOTE_DOWN) - count(old_vote, VOTE_DOWN);
+
+        match changes
+            .iter_mut()
+            .find(|(existing, _, _)| *existing == object)
+        {
+            Some((_, total_upvotes, total_downvotes)) => {
+                *total_upvotes += upvotes;
+                *total_downvotes += downvotes;
+            }
+            None => changes.push((object, upvotes, downvotes)),
+        }
+    }
+
+    changes
+}
+
+/// The `store_open_proposals` key of a proposal: `proposal:<space_id>:<proposal_id>` in hex.
+pub fn proposal_key(space_id: &[u8], proposal_id: &[u8]) -> String {
+    store_key(PROPOSAL_PREFIX, space_id, proposal_id)
+}
+
+/// A proposal action of any of the three typed lists.
+enum ProposalAction {
+    Created(ProposalCreated),
+    Voted(ProposalVoted),
+    Executed(ProposalExecuted),
+}
+
+impl ProposalAction {
+    fn log_index(&self) -> u64 {
+        match self {
+            ProposalAction::Created(proposal) => proposal.log_index,
+            ProposalAction::Voted(vote) => vote.log_index,
+            ProposalAction::Executed(proposal) => proposal.log_index,
+        }
+    }
+
+    fn proposal(&self) -> (&[u8], &[u8]) {
+        match self {
+            ProposalAction::Created(proposal) => (&proposal.space_id, &proposal.proposal_id),
+            ProposalAction::Voted(vote) => (&vote.space_id, &vote.proposal_id),
+            ProposalAction::Executed(proposal) => (&proposal.space_id, &proposal.proposal_id),
+        }
+    }
+}
+
+/// Joins the proposal actions of a block into one update per proposal, in the order
+/// proposals were first acted on.
+///
+/// `opened_at` returns the block a proposal was created in, for proposals that were
+/// already open before this block. Proposals created in the block get `block_number`.
+pub fn proposal_updates(
+    block_number: u64,
+    created: Vec<ProposalCreated>,
+    voted: Vec<ProposalVoted>,
+    executed: Vec<ProposalExecuted>,
+    opened_at: impl Fn(&[u8], &[u8]) -> Option<u64>,
+) -> Vec<ProposalUpdate> {
+    let mut actions: Vec<ProposalAction> = Vec::new();
+    actions.extend(created.into_iter().map(ProposalAction::Created));
+    actions.extend(voted.into_iter().map(ProposalAction::Voted));
+    actions.extend(executed.into_iter().map(ProposalAction::Executed));
+    actions.sort_by_key(ProposalAction::log_index);
+
+    let mut updates: Vec<ProposalUpdate> = Vec::new();
+    for action in actions {
+        let (space_id, proposal_id) = action.proposal();
+        let index = match updates
+            .iter()
+            .position(|update| update.space_id == space_id && update.proposal_id == proposal_id)
+        {
+            Some(index) => index,
+            None => {
+                updates.push(ProposalUpdate {
+                    space_id: space_id.to_vec(),
+                    proposal_id: proposal_id.to_vec(),
+                    status: ProposalStatus::Open as i32,
+                    created_block: opened_at(space_id, proposal_id).unwrap_or_default(),
+                    ..Default::default()
+                });
+                updates.len() - 1
+            }
+        };
+
+        let update = &mut updates[index];
+        match action {
+            ProposalAction::Created(proposal) => {
+                update.status = ProposalStatus::Open as i32;
+                update.created_block = block_number;
+                update.created = Some(proposal);
+            }
+            ProposalAction::Voted(vote) => update.votes.push(vote),
+            ProposalAction::Executed(proposal) => {
+                update.status = ProposalStatus::Executed as i32;
+                update.executed = Some(proposal);
+            }
+        }
+    }
+
+    updates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_registry_addresses() {
+        let registries = parse_registry_addresses(
+            "0x80eF8d87fafCB65F5399c6d28c72A27577616339, 0x0000000000000000000000000000000000000001",
+        )
+        .unwrap();
+
+        assert_eq!(registries.len(), 2);
+        assert_eq!(
+            format_hex(&registries[0]),
+            "0x80ef8d87fafcb65f5399c6d28c72a27577616339"
+        );
+        assert_eq!(registries[1][19], 1);
+    }
+
+    #[test]
+    fn test_parse_registry_addresses_invalid() {
+        assert!(parse_registry_addresses("").is_err());
+        assert!(parse_registry_addresses(" , ").is_err());
+        assert!(parse_registry_addresses("80ef8d87fafcb65f5399c6d28c72a27577616339").is_err());
+        assert!(parse_registry_addresses("0x80ef").is_err());
+        assert!(parse_registry_addresses("0x80ef8d87fafcb65f5399c6d28c72a2757761633z").is_err());
+    }
+
+    fn topic(topic_id: u8, parent_topic_id: Option<u8>) -> TopicDeclared {
+        TopicDeclared {
+            topic_id: vec![topic_id; 32],
+            parent_topic_id: parent_topic_id.map(|id| vec![id; 32]).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    fn topic_ids(topics: &[TopicDeclared]) -> Vec<u8> {
+        topics.iter().map(|topic| topic.topic_id[0]).collect()
+    }
+
+    #[test]
+    fn test_order_parents_first() {
+        let topics = vec![
+            topic(3, Some(2)),
+            topic(2, Some(1)),
+            topic(1, None),
+            topic(4, Some(9)),
+        ];
+
+        let ordered = order_parents_first(topics);
+
+        // 4's parent was declared in an earlier block, so it keeps its place among the roots
+        assert_eq!(topic_ids(&ordered), vec![1, 4, 2, 3]);
+    }
+
+    #[test]
+    fn test_order_parents_first_keeps_cycles() {
+        let topics = vec![topic(1, Some(2)), topic(2, Some(1)), topic(3, None)];
+
+        let ordered = order_parents_first(topics);
+
+        assert_eq!(topic_ids(&ordered), vec![3, 1, 2]);
+    }
+
+    fn delta(key: String, old_value: i64, new_value: i64) -> DeltaInt64 {
+        DeltaInt64 {
+            operation: substreams::pb::substreams::store_delta::Operation::Update,
+            ordinal: 0,
+            key,
+            old_value,
+            new_value,
+        }
+    }
+
+    #[test]
+    fn test_flag_count_key_roundtrip() {
+        let key = flag_count_key(&[0xab; 16], &[0xcd; 32]);
+
+        assert!(key.starts_with("flags:abab"));
+        assert_eq!(
+            parse_store_key(FLAG_COUNT_PREFIX, &key),
+            Some((vec![0xab; 16], vec![0xcd; 32]))
+        );
+        assert_eq!(parse_store_key(FLAG_COUNT_PREFIX, "flags:zz:00"), None);
+        assert_eq!(parse_store_key(FLAG_COUNT_PREFIX, "votes:ab:cd"), None);
+    }
+
+    #[test]
+    fn test_flag_count_deltas_collapse_per_key() {
+        let first = flag_count_key(&[1; 16], &[1; 32]);
+        let second = flag_count_key(&[2; 16], &[2; 32]);
+
+        let deltas = flag_count_deltas(vec![
+            delta(first.clone(), 3, 4),
+            delta(second, 0, 1),
+            delta(first, 4, 5),
+        ]);
+
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(deltas[0].space_id, vec![1; 16]);
+        assert_eq!(deltas[0].object_id, vec![1; 32]);
+        assert_eq!((deltas[0].old_count, deltas[0].new_count), (3, 5));
+        assert_eq!((deltas[1].old_count, deltas[1].new_count), (0, 1));
+    }
+
+    fn string_delta(
+        operation: Operation,
+        key: String,
+        old_value: &str,
+        new_value: &str,
+    ) -> DeltaString {
+        DeltaString {
+            operation,
+            ordinal: 7,
+            key,
+            old_value: old_value.to_string(),
+            new_value: new_value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_membership_deltas_for_editors() {
+        let key = editor_key(&[1; 16], &[2; 20]);
+
+        let deltas = membership_deltas(
+            MembershipRole::Editor,
+            vec![
+                string_delta(Operation::Create, key.clone(), "", MEMBERSHIP_ACTIVE),
+                string_delta(
+                    Operation::Update,
+                    key.clone(),
+                    MEMBERSHIP_ACTIVE,
+                    MEMBERSHIP_ACTIVE,
+                ),
+                string_delta(
+                    Operation::Update,
+                    key.clone(),
+                    MEMBERSHIP_ACTIVE,
+                    MEMBERSHIP_FLAGGED,
+                ),
+                string_delta(
+                    Operation::Update,
+                    key.clone(),
+                    MEMBERSHIP_FLAGGED,
+                    MEMBERSHIP_ACTIVE,
+                ),
+                string_delta(Operation::Delete, key, MEMBERSHIP_ACTIVE, ""),
+            ],
+        );
+
+        let changes: Vec<i32> = deltas.iter().map(|delta| delta.change).collect();
+        assert_eq!(
+            changes,
+            vec![
+                MembershipChange::Added as i32,
+                MembershipChange::Flagged as i32,
+                MembershipChange::Unflagged as i32,
+                MembershipChange::Removed as i32,
+            ]
+        );
+        assert_eq!(deltas[0].space_id, vec![1; 16]);
+        assert_eq!(deltas[0].account, vec![2; 20]);
+        assert_eq!(deltas[0].role, MembershipRole::Editor as i32);
+        assert_eq!(deltas[0].ordinal, 7);
+    }
+
+    #[test]
+    fn test_membership_deltas_skip_other_stores() {
+        let deltas = membership_deltas(
+            MembershipRole::Member,
+            vec![string_delta(
+                Operation::Create,
+                editor_key(&[1; 16], &[2; 20]),
+                "",
+                MEMBERSHIP_ACTIVE,
+            )],
+        );
+
+        assert!(deltas.is_empty());
+    }
+
+    #[test]
+    fn test_object_vote_changes() {
+        let first = object_vote_key(&[1; 4], &[1; 16], &[9; 16]);
+        let second = object_vote_key(&[2; 4], &[2; 16], &[9; 16]);
+
+        let changes = object_vote_changes(vec![
+            string_delta(Operation::Create, first.clone(), "", VOTE_UP),
+            string_delta(Operation::Create, second.clone(), "", VOTE_DOWN),
+            string_delta(Operation::Update, first.clone(), VOTE_UP, VOTE_DOWN),
+            string_delta(Operation::Update, second.clone(), VOTE_DOWN, VOTE_DOWN),
+            string_delta(Operation::Delete, second, VOTE_DOWN, ""),
+        ]);
+
+        let first_object = [vec![1; 4], vec![1; 16]].concat();
+        let second_object = [vec![2; 4], vec![2; 16]].concat();
+        assert_eq!(
+            changes,
+            vec![(first_object, 0, 1), (second_object.clone(), 0, 0)]
+        );
+        assert_eq!(
+            vote_total_key(VOTE_UP, &second_object),
+            format!("up:{}", Hex(&second_object))
+        );
+    }
+
+    #[test]
+    fn test_proposal_updates_join_actions_per_proposal() {
+        let created = ProposalCreated {
+            space_id: vec![1; 16],
+            proposal_id: vec![1; 32],
+            log_index: 1,
+            ..Default::default()
+        };
+        let vote = |proposal: u8, log_index| ProposalVoted {
+            voter_id: vec![9; 16],
+            space_id: vec![proposal; 16],
+            proposal_id: vec![proposal; 32],
+            log_index,
+            ..Default::default()
+        };
+        let executed = ProposalExecuted {
+            space_id: vec![2; 16],
+            proposal_id: vec![2; 32],
+            log_index: 4,
+            ..Default::default()
+        };
+
+        // Proposal 2 opened in block 7, proposal 3 before the module's initial block
+        let updates = proposal_updates(
+            10,
+            vec![created.clone()],
+            vec![vote(1, 3), vote(2, 2), vote(3, 5)],
+            vec![executed.clone()],
+            |space_id, proposal_id| {
+                (proposal_key(space_id, proposal_id) == proposal_key(&[2; 16], &[2; 32]))
+                    .then_some(7)
+            },
+        );
+
+        assert_eq!(updates.len(), 3);
+        assert_eq!(updates[0].proposal_id, vec![1; 32]);
+        assert_eq!(updates[0].status, ProposalStatus::Open as i32);
+        assert_eq!(updates[0].created_block, 10);
+        assert_eq!(updates[0].created, Some(created));
+        assert_eq!(updates[0].votes, vec![vote(1, 3)]);
+        assert_eq!(updates[1].proposal_id, vec![2; 32]);
+        assert_eq!(updates[1].status, ProposalStatus::Executed as i32);
+        assert_eq!(updates[1].created_block, 7);
+        assert_eq!(updates[1].votes, vec![vote(2, 2)]);
+        assert_eq!(updates[1].executed, Some(executed));
+        assert_eq!(updates[2].proposal_id, vec![3; 32]);
+        assert_eq!(updates[2].created_block, 0);
+        assert_eq!(updates[2].created, None);
+    }
+}
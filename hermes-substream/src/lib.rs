@@ -3,15 +3,31 @@
 //! Filters and emits Action events from the Space Registry contract.
 //! Provides both raw actions and pre-filtered typed events.
 
+// The wasm entrypoints generated for handlers with params read them from a raw pointer
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub mod decode;
 pub mod helpers;
 pub mod pb;
-
+// The handlers decoding actions are only wasm entrypoints outside of tests, so the tests
+// can call them on blocks built with `test_support`
+#[cfg(test)]
+mod test_support;
+
+use decode::{decode_action, expect_version, field, DecodeError, ACTION_VERSION_V1};
+use helpers::{
+    editor_key, flag_count_deltas, flag_count_key, member_key, membership_deltas, object_vote_changes,
+    object_vote_key, order_parents_first, parse_registry_addresses, proposal_key, proposal_updates,
+    vote_total_key, MEMBERSHIP_ACTIVE, MEMBERSHIP_FLAGGED, VOTE_DOWN, VOTE_UP,
+};
 use pb::hermes::*;
+use substreams::pb::substreams::Clock;
+use substreams::store::{
+    DeltaInt64, DeltaString, Deltas, StoreAdd, StoreAddInt64, StoreDelete, StoreGet, StoreGetInt64, StoreNew,
+    StoreSet, StoreSetInt64, StoreSetString,
+};
 use substreams_ethereum::{block_view::LogView, pb::eth};
 
-// TODO: Replace with actual Space Registry contract address
-const SPACE_REGISTRY_ADDRESS: [u8; 20] = [0u8; 20];
-
 // Action type hashes - keccak256 of action names.
 // These same values are defined in `hermes-relay/src/actions.rs` for consumer-side
 // filtering - keep them in sync if adding new actions.
@@ -37,36 +53,56 @@ const ACTION_OBJECT_DOWNVOTED: [u8; 32] = [0xb0, 0x6b, 0x60, 0xe1, 0x1f, 0x65, 0
 const ACTION_OBJECT_UNVOTED: [u8; 32] = [0xab, 0xa4, 0x9c, 0x6d, 0xa7, 0x70, 0x58, 0x8e, 0xd6, 0x02, 0x5f, 0x73, 0x6d, 0xa8, 0x76, 0xb7, 0x3b, 0xc0, 0xc7, 0xdc, 0xfd, 0xcd, 0x27, 0x5f, 0xb4, 0x31, 0x6e, 0x8b, 0xf2, 0x25, 0xc1, 0x83];
 
 /// Parse Action event from log.
-/// Returns None if not a valid Action event from Space Registry.
-fn parse_action(log: LogView) -> Option<Action> {
-    if log.address() != SPACE_REGISTRY_ADDRESS {
+/// Returns None if the log isn't from one of the Space Registries, or isn't an Action event
+/// in an encoding this package can decode.
+fn parse_action(log: LogView, registries: &[[u8; 20]]) -> Option<Action> {
+    if !registries.iter().any(|registry| log.address() == registry) {
         return None;
     }
 
-    // The Action event is anonymous with 4 indexed fields
-    let topics = log.topics();
-    if topics.len() != 4 {
-        return None;
+    let transaction = log.receipt.transaction;
+    match decode_action(log.topics(), log.data()) {
+        Ok(action) => Some(Action {
+            tx_from: transaction.from.clone(),
+            tx_hash: transaction.hash.clone(),
+            log_index: log.log.block_index as u64,
+            ..action
+        }),
+        Err(e) => {
+            let tx_hash = helpers::format_hex(&transaction.hash);
+            substreams::log::info!("Skipping log {} of tx {}: {}", log.index(), tx_hash, e);
+            None
+        }
     }
+}
 
-    Some(Action {
-        from_id: topics[0][16..32].to_vec(),
-        to_id: topics[1][16..32].to_vec(),
-        action: topics[2].to_vec(),
-        topic: topics[3].to_vec(),
-        data: log.data().to_vec(),
-    })
+/// Decode a typed event from a v1 action, skipping actions decoded with another version or
+/// whose fields are too short for the event.
+fn decode_v1<T>(action: Action, decode: impl FnOnce(Action) -> Result<T, DecodeError>) -> Option<T> {
+    match expect_version(&action, ACTION_VERSION_V1).and_then(|_| decode(action)) {
+        Ok(event) => Some(event),
+        Err(e) => {
+            substreams::log::info!("Skipping action: {}", e);
+            None
+        }
+    }
 }
 
 // =============================================================================
 // Raw Actions
 // =============================================================================
 
-#[substreams::handlers::map]
-fn map_actions(block: eth::v2::Block) -> Result<Actions, substreams::errors::Error> {
+/// Emits the Action events of the Space Registries in `params`, comma-separated.
+///
+/// The params are set in `substreams.yaml` and can be overridden by the sink. Several
+/// registries can be tracked at once, so events keep flowing across registry upgrades.
+/// The typed modules below read this module's output, so they share its registries.
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_actions(params: String, block: eth::v2::Block) -> Result<Actions, substreams::errors::Error> {
+    let registries = parse_registry_addresses(&params)?;
     let actions: Vec<Action> = block
         .logs()
-        .filter_map(|log| parse_action(log))
+        .filter_map(|log| parse_action(log, &registries))
         .collect();
 
     Ok(Actions { actions })
@@ -76,273 +112,335 @@ fn map_actions(block: eth::v2::Block) -> Result<Actions, substreams::errors::Err
 // Governance Events
 // =============================================================================
 
-#[substreams::handlers::map]
-fn map_spaces_registered(block: eth::v2::Block) -> Result<SpaceRegisteredList, substreams::errors::Error> {
-    let spaces: Vec<SpaceRegistered> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_spaces_registered(actions: Actions) -> Result<SpaceRegisteredList, substreams::errors::Error> {
+    let spaces: Vec<SpaceRegistered> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_SPACE_ID_REGISTERED)
-        .map(|action| SpaceRegistered {
+        .filter_map(|action| decode_v1(action, |action| Ok(SpaceRegistered {
             space_id: action.from_id,
-            space_address: action.topic[12..32].to_vec(),
+            space_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(SpaceRegisteredList { spaces })
 }
 
-#[substreams::handlers::map]
-fn map_spaces_migrated(block: eth::v2::Block) -> Result<SpaceMigratedList, substreams::errors::Error> {
-    let spaces: Vec<SpaceMigrated> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_spaces_migrated(actions: Actions) -> Result<SpaceMigratedList, substreams::errors::Error> {
+    let spaces: Vec<SpaceMigrated> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_SPACE_ID_MIGRATED)
-        .map(|action| SpaceMigrated {
+        .filter_map(|action| decode_v1(action, |action| Ok(SpaceMigrated {
             space_id: action.from_id,
-            new_space_address: action.topic[12..32].to_vec(),
+            new_space_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(SpaceMigratedList { spaces })
 }
 
-#[substreams::handlers::map]
-fn map_proposals_created(block: eth::v2::Block) -> Result<ProposalCreatedList, substreams::errors::Error> {
-    let proposals: Vec<ProposalCreated> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_proposals_created(actions: Actions) -> Result<ProposalCreatedList, substreams::errors::Error> {
+    let proposals: Vec<ProposalCreated> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_PROPOSAL_CREATED)
-        .map(|action| ProposalCreated {
+        .filter_map(|action| decode_v1(action, |action| Ok(ProposalCreated {
             space_id: action.from_id,
             proposal_id: action.topic,
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(ProposalCreatedList { proposals })
 }
 
-#[substreams::handlers::map]
-fn map_proposals_voted(block: eth::v2::Block) -> Result<ProposalVotedList, substreams::errors::Error> {
-    let votes: Vec<ProposalVoted> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_proposals_voted(actions: Actions) -> Result<ProposalVotedList, substreams::errors::Error> {
+    let votes: Vec<ProposalVoted> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_PROPOSAL_VOTED)
-        .map(|action| ProposalVoted {
+        .filter_map(|action| decode_v1(action, |action| Ok(ProposalVoted {
             voter_id: action.from_id,
             space_id: action.to_id,
             proposal_id: action.topic,
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(ProposalVotedList { votes })
 }
 
-#[substreams::handlers::map]
-fn map_proposals_executed(block: eth::v2::Block) -> Result<ProposalExecutedList, substreams::errors::Error> {
-    let proposals: Vec<ProposalExecuted> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_proposals_executed(actions: Actions) -> Result<ProposalExecutedList, substreams::errors::Error> {
+    let proposals: Vec<ProposalExecuted> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_PROPOSAL_EXECUTED)
-        .map(|action| ProposalExecuted {
+        .filter_map(|action| decode_v1(action, |action| Ok(ProposalExecuted {
             space_id: action.from_id,
             proposal_id: action.topic,
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(ProposalExecutedList { proposals })
 }
 
-#[substreams::handlers::map]
-fn map_editors_added(block: eth::v2::Block) -> Result<EditorAddedList, substreams::errors::Error> {
-    let editors: Vec<EditorAdded> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_editors_added(actions: Actions) -> Result<EditorAddedList, substreams::errors::Error> {
+    let editors: Vec<EditorAdded> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_EDITOR_ADDED)
-        .map(|action| EditorAdded {
+        .filter_map(|action| decode_v1(action, |action| Ok(EditorAdded {
             space_id: action.from_id,
-            editor_address: action.topic[12..32].to_vec(),
+            editor_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(EditorAddedList { editors })
 }
 
-#[substreams::handlers::map]
-fn map_editors_removed(block: eth::v2::Block) -> Result<EditorRemovedList, substreams::errors::Error> {
-    let editors: Vec<EditorRemoved> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_editors_removed(actions: Actions) -> Result<EditorRemovedList, substreams::errors::Error> {
+    let editors: Vec<EditorRemoved> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_EDITOR_REMOVED)
-        .map(|action| EditorRemoved {
+        .filter_map(|action| decode_v1(action, |action| Ok(EditorRemoved {
             space_id: action.from_id,
-            editor_address: action.topic[12..32].to_vec(),
+            editor_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(EditorRemovedList { editors })
 }
 
-#[substreams::handlers::map]
-fn map_members_added(block: eth::v2::Block) -> Result<MemberAddedList, substreams::errors::Error> {
-    let members: Vec<MemberAdded> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_members_added(actions: Actions) -> Result<MemberAddedList, substreams::errors::Error> {
+    let members: Vec<MemberAdded> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_MEMBER_ADDED)
-        .map(|action| MemberAdded {
+        .filter_map(|action| decode_v1(action, |action| Ok(MemberAdded {
             space_id: action.from_id,
-            member_address: action.topic[12..32].to_vec(),
+            member_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(MemberAddedList { members })
 }
 
-#[substreams::handlers::map]
-fn map_members_removed(block: eth::v2::Block) -> Result<MemberRemovedList, substreams::errors::Error> {
-    let members: Vec<MemberRemoved> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_members_removed(actions: Actions) -> Result<MemberRemovedList, substreams::errors::Error> {
+    let members: Vec<MemberRemoved> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_MEMBER_REMOVED)
-        .map(|action| MemberRemoved {
+        .filter_map(|action| decode_v1(action, |action| Ok(MemberRemoved {
             space_id: action.from_id,
-            member_address: action.topic[12..32].to_vec(),
+            member_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(MemberRemovedList { members })
 }
 
-#[substreams::handlers::map]
-fn map_editors_flagged(block: eth::v2::Block) -> Result<EditorFlaggedList, substreams::errors::Error> {
-    let editors: Vec<EditorFlagged> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_editors_flagged(actions: Actions) -> Result<EditorFlaggedList, substreams::errors::Error> {
+    let editors: Vec<EditorFlagged> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_EDITOR_FLAGGED)
-        .map(|action| EditorFlagged {
+        .filter_map(|action| decode_v1(action, |action| Ok(EditorFlagged {
             space_id: action.from_id,
-            editor_address: action.topic[12..32].to_vec(),
+            editor_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(EditorFlaggedList { editors })
 }
 
-#[substreams::handlers::map]
-fn map_editors_unflagged(block: eth::v2::Block) -> Result<EditorUnflaggedList, substreams::errors::Error> {
-    let editors: Vec<EditorUnflagged> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_editors_unflagged(actions: Actions) -> Result<EditorUnflaggedList, substreams::errors::Error> {
+    let editors: Vec<EditorUnflagged> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_EDITOR_UNFLAGGED)
-        .map(|action| EditorUnflagged {
+        .filter_map(|action| decode_v1(action, |action| Ok(EditorUnflagged {
             space_id: action.from_id,
-            editor_address: action.topic[12..32].to_vec(),
+            editor_address: field("topic", &action.topic, 12..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(EditorUnflaggedList { editors })
 }
 
-#[substreams::handlers::map]
-fn map_spaces_left(block: eth::v2::Block) -> Result<SpaceLeftList, substreams::errors::Error> {
-    let spaces: Vec<SpaceLeft> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_spaces_left(actions: Actions) -> Result<SpaceLeftList, substreams::errors::Error> {
+    let spaces: Vec<SpaceLeft> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_SPACE_LEFT)
-        .map(|action| SpaceLeft {
+        .filter_map(|action| decode_v1(action, |action| Ok(SpaceLeft {
             member_id: action.from_id,
             space_id: action.to_id,
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(SpaceLeftList { spaces })
 }
 
-#[substreams::handlers::map]
-fn map_topics_declared(block: eth::v2::Block) -> Result<TopicDeclaredList, substreams::errors::Error> {
-    let topics: Vec<TopicDeclared> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+/// Decodes the parent topic and label hash of each declared topic. A zeroed parent marks
+/// a root topic.
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_topics_declared(actions: Actions) -> Result<TopicsDeclaredList, substreams::errors::Error> {
+    let topics: Vec<TopicDeclared> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_TOPIC_DECLARED)
-        .map(|action| TopicDeclared {
-            space_id: action.from_id,
-            topic_id: action.topic,
-            data: action.data,
-        })
+        .filter_map(|action| decode_v1(action, |action| {
+            let parent_topic_id = field("data", &action.data, 0..32)?;
+            let label_hash = field("data", &action.data, 32..64)?.to_vec();
+            let is_root = parent_topic_id.iter().all(|byte| *byte == 0);
+
+            Ok(TopicDeclared {
+                space_id: action.from_id,
+                topic_id: action.topic,
+                parent_topic_id: if is_root { vec![] } else { parent_topic_id.to_vec() },
+                label_hash,
+                data: action.data,
+                tx_from: action.tx_from,
+                tx_hash: action.tx_hash,
+                log_index: action.log_index,
+            })
+        }))
         .collect();
 
-    Ok(TopicDeclaredList { topics })
+    Ok(TopicsDeclaredList { topics: order_parents_first(topics) })
 }
 
-#[substreams::handlers::map]
-fn map_edits_published(block: eth::v2::Block) -> Result<EditsPublishedList, substreams::errors::Error> {
-    let edits: Vec<EditsPublished> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_edits_published(actions: Actions) -> Result<EditsPublishedList, substreams::errors::Error> {
+    let edits: Vec<EditsPublished> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_EDITS_PUBLISHED)
-        .map(|action| EditsPublished {
+        .filter_map(|action| decode_v1(action, |action| Ok(EditsPublished {
             space_id: action.from_id,
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(EditsPublishedList { edits })
 }
 
-#[substreams::handlers::map]
-fn map_content_flagged(block: eth::v2::Block) -> Result<ContentFlaggedList, substreams::errors::Error> {
-    let flags: Vec<ContentFlagged> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_content_flagged(actions: Actions) -> Result<ContentFlaggedList, substreams::errors::Error> {
+    let flags: Vec<ContentFlagged> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_FLAGGED)
-        .map(|action| ContentFlagged {
+        .filter_map(|action| decode_v1(action, |action| Ok(ContentFlagged {
             flagger_id: action.from_id,
             space_id: action.to_id,
+            object_id: action.topic,
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(ContentFlaggedList { flags })
 }
 
-#[substreams::handlers::map]
-fn map_subspaces_added(block: eth::v2::Block) -> Result<SubspaceAddedList, substreams::errors::Error> {
-    let subspaces: Vec<SubspaceAdded> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_subspaces_added(actions: Actions) -> Result<SubspaceAddedList, substreams::errors::Error> {
+    let subspaces: Vec<SubspaceAdded> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_SUBSPACE_ADDED)
-        .map(|action| SubspaceAdded {
+        .filter_map(|action| decode_v1(action, |action| Ok(SubspaceAdded {
             parent_space_id: action.from_id,
-            subspace_id: action.topic[16..32].to_vec(),
+            subspace_id: field("topic", &action.topic, 16..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(SubspaceAddedList { subspaces })
 }
 
-#[substreams::handlers::map]
-fn map_subspaces_removed(block: eth::v2::Block) -> Result<SubspaceRemovedList, substreams::errors::Error> {
-    let subspaces: Vec<SubspaceRemoved> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_subspaces_removed(actions: Actions) -> Result<SubspaceRemovedList, substreams::errors::Error> {
+    let subspaces: Vec<SubspaceRemoved> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_SUBSPACE_REMOVED)
-        .map(|action| SubspaceRemoved {
+        .filter_map(|action| decode_v1(action, |action| Ok(SubspaceRemoved {
             parent_space_id: action.from_id,
-            subspace_id: action.topic[16..32].to_vec(),
+            subspace_id: field("topic", &action.topic, 16..32)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(SubspaceRemovedList { subspaces })
@@ -352,53 +450,565 @@ fn map_subspaces_removed(block: eth::v2::Block) -> Result<SubspaceRemovedList, s
 // Permissionless Events
 // =============================================================================
 
-#[substreams::handlers::map]
-fn map_objects_upvoted(block: eth::v2::Block) -> Result<ObjectUpvotedList, substreams::errors::Error> {
-    let votes: Vec<ObjectVoted> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_objects_upvoted(actions: Actions) -> Result<ObjectUpvotedList, substreams::errors::Error> {
+    let votes: Vec<ObjectVoted> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_OBJECT_UPVOTED)
-        .map(|action| ObjectVoted {
+        .filter_map(|action| decode_v1(action, |action| Ok(ObjectVoted {
             voter_id: action.from_id,
-            object_type: action.topic[0..4].to_vec(),
-            object_id: action.topic[4..20].to_vec(),
+            object_type: field("topic", &action.topic, 0..4)?.to_vec(),
+            object_id: field("topic", &action.topic, 4..20)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(ObjectUpvotedList { votes })
 }
 
-#[substreams::handlers::map]
-fn map_objects_downvoted(block: eth::v2::Block) -> Result<ObjectDownvotedList, substreams::errors::Error> {
-    let votes: Vec<ObjectVoted> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_objects_downvoted(actions: Actions) -> Result<ObjectDownvotedList, substreams::errors::Error> {
+    let votes: Vec<ObjectVoted> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_OBJECT_DOWNVOTED)
-        .map(|action| ObjectVoted {
+        .filter_map(|action| decode_v1(action, |action| Ok(ObjectVoted {
             voter_id: action.from_id,
-            object_type: action.topic[0..4].to_vec(),
-            object_id: action.topic[4..20].to_vec(),
+            object_type: field("topic", &action.topic, 0..4)?.to_vec(),
+            object_id: field("topic", &action.topic, 4..20)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(ObjectDownvotedList { votes })
 }
 
-#[substreams::handlers::map]
-fn map_objects_unvoted(block: eth::v2::Block) -> Result<ObjectUnvotedList, substreams::errors::Error> {
-    let votes: Vec<ObjectVoted> = block
-        .logs()
-        .filter_map(|log| parse_action(log))
+#[cfg_attr(not(test), substreams::handlers::map)]
+fn map_objects_unvoted(actions: Actions) -> Result<ObjectUnvotedList, substreams::errors::Error> {
+    let votes: Vec<ObjectVoted> = actions
+        .actions
+        .into_iter()
         .filter(|action| action.action.as_slice() == ACTION_OBJECT_UNVOTED)
-        .map(|action| ObjectVoted {
+        .filter_map(|action| decode_v1(action, |action| Ok(ObjectVoted {
             voter_id: action.from_id,
-            object_type: action.topic[0..4].to_vec(),
-            object_id: action.topic[4..20].to_vec(),
+            object_type: field("topic", &action.topic, 0..4)?.to_vec(),
+            object_id: field("topic", &action.topic, 4..20)?.to_vec(),
             data: action.data,
-        })
+            tx_from: action.tx_from,
+            tx_hash: action.tx_hash,
+            log_index: action.log_index,
+        })))
         .collect();
 
     Ok(ObjectUnvotedList { votes })
 }
+
+// =============================================================================
+// Membership
+// =============================================================================
+
+/// Applies editor and member changes to a store in log order, since the same account can
+/// change several times in a block across the typed lists.
+fn apply_membership_changes(store: &StoreSetString, mut changes: Vec<(u64, String, Option<&str>)>) {
+    changes.sort_by_key(|(ordinal, _, _)| *ordinal);
+
+    for (ordinal, key, value) in changes {
+        match value {
+            Some(value) => store.set(ordinal, &key, &value.to_string()),
+            None => store.delete_prefix(ordinal as i64, &key),
+        }
+    }
+}
+
+/// Current editors per space, keyed `editor:<space_id>:<address>` in hex. Each value is
+/// `active`, or `flagged` while the editor is flagged.
+#[substreams::handlers::store]
+fn store_editors(
+    added: EditorAddedList,
+    removed: EditorRemovedList,
+    flagged: EditorFlaggedList,
+    unflagged: EditorUnflaggedList,
+    store: StoreSetString,
+) {
+    let mut changes = Vec::new();
+    for editor in added.editors {
+        changes.push((editor.log_index, editor_key(&editor.space_id, &editor.editor_address), Some(MEMBERSHIP_ACTIVE)));
+    }
+    for editor in removed.editors {
+        changes.push((editor.log_index, editor_key(&editor.space_id, &editor.editor_address), None));
+    }
+    for editor in flagged.editors {
+        changes.push((editor.log_index, editor_key(&editor.space_id, &editor.editor_address), Some(MEMBERSHIP_FLAGGED)));
+    }
+    for editor in unflagged.editors {
+        changes.push((editor.log_index, editor_key(&editor.space_id, &editor.editor_address), Some(MEMBERSHIP_ACTIVE)));
+    }
+
+    apply_membership_changes(&store, changes);
+}
+
+/// Current members per space, keyed `member:<space_id>:<address>` in hex.
+#[substreams::handlers::store]
+fn store_members(added: MemberAddedList, removed: MemberRemovedList, store: StoreSetString) {
+    let mut changes = Vec::new();
+    for member in added.members {
+        changes.push((member.log_index, member_key(&member.space_id, &member.member_address), Some(MEMBERSHIP_ACTIVE)));
+    }
+    for member in removed.members {
+        changes.push((member.log_index, member_key(&member.space_id, &member.member_address), None));
+    }
+
+    apply_membership_changes(&store, changes);
+}
+
+/// Changes to the editor and member sets in the block. Sinks resolve whether an account
+/// was an editor or member at block N by applying the deltas up to N.
+#[substreams::handlers::map]
+fn map_membership_deltas(
+    clock: Clock,
+    editors: Deltas<DeltaString>,
+    members: Deltas<DeltaString>,
+) -> Result<MembershipDeltas, substreams::errors::Error> {
+    let mut deltas = membership_deltas(MembershipRole::Editor, editors.deltas);
+    deltas.extend(membership_deltas(MembershipRole::Member, members.deltas));
+    deltas.sort_by_key(|delta| delta.ordinal);
+
+    Ok(MembershipDeltas { block_number: clock.number, deltas })
+}
+
+// =============================================================================
+// Moderation
+// =============================================================================
+
+/// Cumulative flag count per flagged (space, object).
+#[substreams::handlers::store]
+fn store_flag_counts(flags: ContentFlaggedList, store: StoreAddInt64) {
+    for flag in flags.flags {
+        store.add(flag.log_index, flag_count_key(&flag.space_id, &flag.object_id), 1);
+    }
+}
+
+/// The flag counts that changed in the block, one delta per (space, object).
+#[substreams::handlers::map]
+fn map_flag_count_deltas(deltas: Deltas<DeltaInt64>) -> Result<FlagCountDeltas, substreams::errors::Error> {
+    Ok(FlagCountDeltas { deltas: flag_count_deltas(deltas.deltas) })
+}
+
+// =============================================================================
+// Vote Tallies
+// =============================================================================
+
+/// The current vote of each voter on each object, keyed `vote:<object_type><object_id>:<voter_id>`
+/// in hex. Each value is `up` or `down`, and unvoting deletes the vote.
+#[substreams::handlers::store]
+fn store_object_votes(
+    upvoted: ObjectUpvotedList,
+    downvoted: ObjectDownvotedList,
+    unvoted: ObjectUnvotedList,
+    store: StoreSetString,
+) {
+    let mut votes: Vec<(ObjectVoted, Option<&str>)> = Vec::new();
+    votes.extend(upvoted.votes.into_iter().map(|vote| (vote, Some(VOTE_UP))));
+    votes.extend(downvoted.votes.into_iter().map(|vote| (vote, Some(VOTE_DOWN))));
+    votes.extend(unvoted.votes.into_iter().map(|vote| (vote, None)));
+    votes.sort_by_key(|(vote, _)| vote.log_index);
+
+    for (vote, direction) in votes {
+        let key = object_vote_key(&vote.object_type, &vote.object_id, &vote.voter_id);
+        match direction {
+            Some(direction) => store.set(vote.log_index, &key, &direction.to_string()),
+            None => store.delete_prefix(vote.log_index as i64, &key),
+        }
+    }
+}
+
+/// Upvotes and downvotes per object, keyed `up:<object_type><object_id>` and
+/// `down:<object_type><object_id>` in hex.
+#[substreams::handlers::store]
+fn store_object_vote_totals(votes: Deltas<DeltaString>, store: StoreAddInt64) {
+    for (object, upvotes, downvotes) in object_vote_changes(votes.deltas) {
+        if upvotes != 0 {
+            store.add(0, vote_total_key(VOTE_UP, &object), upvotes);
+        }
+        if downvotes != 0 {
+            store.add(0, vote_total_key(VOTE_DOWN, &object), downvotes);
+        }
+    }
+}
+
+/// The objects whose tallies changed in the block, with their totals after the block.
+#[substreams::handlers::map]
+fn map_object_vote_totals(
+    votes: Deltas<DeltaString>,
+    totals: StoreGetInt64,
+) -> Result<ObjectVoteTotals, substreams::errors::Error> {
+    let totals: Vec<ObjectVoteTotal> = object_vote_changes(votes.deltas)
+        .into_iter()
+        .filter(|(_, upvotes, downvotes)| *upvotes != 0 || *downvotes != 0)
+        .map(|(object, upvotes, downvotes)| {
+            let (object_type, object_id) = object.split_at(object.len().min(4));
+            ObjectVoteTotal {
+                object_type: object_type.to_vec(),
+                object_id: object_id.to_vec(),
+                upvotes: totals.get_last(vote_total_key(VOTE_UP, &object)).unwrap_or_default(),
+                downvotes: totals.get_last(vote_total_key(VOTE_DOWN, &object)).unwrap_or_default(),
+                upvotes_delta: upvotes,
+                downvotes_delta: downvotes,
+            }
+        })
+        .collect();
+
+    Ok(ObjectVoteTotals { totals })
+}
+
+// =============================================================================
+// Proposal Lifecycle
+// =============================================================================
+
+/// Open proposals, keyed `proposal:<space_id>:<proposal_id>` in hex. Each value is the
+/// block the proposal was created in, and executing the proposal deletes it.
+#[substreams::handlers::store]
+fn store_open_proposals(
+    clock: Clock,
+    created: ProposalCreatedList,
+    executed: ProposalExecutedList,
+    store: StoreSetInt64,
+) {
+    let mut changes: Vec<(u64, String, bool)> = Vec::new();
+    for proposal in created.proposals {
+        changes.push((proposal.log_index, proposal_key(&proposal.space_id, &proposal.proposal_id), true));
+    }
+    for proposal in executed.proposals {
+        changes.push((proposal.log_index, proposal_key(&proposal.space_id, &proposal.proposal_id), false));
+    }
+    changes.sort_by_key(|(ordinal, _, _)| *ordinal);
+
+    for (ordinal, key, open) in changes {
+        if open {
+            store.set(ordinal, &key, &(clock.number as i64));
+        } else {
+            store.delete_prefix(ordinal as i64, &key);
+        }
+    }
+}
+
+/// The created, voted and executed actions of a block joined into one update per
+/// proposal, with its status after the block and the block it was created in.
+#[substreams::handlers::map]
+fn map_proposal_lifecycle(
+    clock: Clock,
+    created: ProposalCreatedList,
+    voted: ProposalVotedList,
+    executed: ProposalExecutedList,
+    open: StoreGetInt64,
+) -> Result<ProposalLifecycle, substreams::errors::Error> {
+    // Read the store as of the start of the block, since executing a proposal deletes it
+    let opened_at = |space_id: &[u8], proposal_id: &[u8]| {
+        open.get_first(proposal_key(space_id, proposal_id)).map(|block| block as u64)
+    };
+    let updates = proposal_updates(clock.number, created.proposals, voted.votes, executed.proposals, opened_at);
+
+    Ok(ProposalLifecycle { block_number: clock.number, updates })
+}
+
+// =============================================================================
+// Combined Output
+// =============================================================================
+
+/// Every typed list of a block in one message, for consumers that need several event
+/// types without opening a stream per module.
+#[substreams::handlers::map]
+fn hermes_out(
+    spaces_registered: SpaceRegisteredList,
+    spaces_migrated: SpaceMigratedList,
+    proposals_created: ProposalCreatedList,
+    proposals_voted: ProposalVotedList,
+    proposals_executed: ProposalExecutedList,
+    editors_added: EditorAddedList,
+    editors_removed: EditorRemovedList,
+    members_added: MemberAddedList,
+    members_removed: MemberRemovedList,
+    editors_flagged: EditorFlaggedList,
+    editors_unflagged: EditorUnflaggedList,
+    spaces_left: SpaceLeftList,
+    topics_declared: TopicsDeclaredList,
+    edits_published: EditsPublishedList,
+    content_flagged: ContentFlaggedList,
+    subspaces_added: SubspaceAddedList,
+    subspaces_removed: SubspaceRemovedList,
+    objects_upvoted: ObjectUpvotedList,
+    objects_downvoted: ObjectDownvotedList,
+    objects_unvoted: ObjectUnvotedList,
+) -> Result<HermesOutput, substreams::errors::Error> {
+    Ok(HermesOutput {
+        spaces_registered: spaces_registered.spaces,
+        spaces_migrated: spaces_migrated.spaces,
+        proposals_created: proposals_created.proposals,
+        proposals_voted: proposals_voted.votes,
+        proposals_executed: proposals_executed.proposals,
+        editors_added: editors_added.editors,
+        editors_removed: editors_removed.editors,
+        members_added: members_added.members,
+        members_removed: members_removed.members,
+        editors_flagged: editors_flagged.editors,
+        editors_unflagged: editors_unflagged.editors,
+        spaces_left: spaces_left.spaces,
+        topics_declared: topics_declared.topics,
+        edits_published: edits_published.edits,
+        content_flagged: content_flagged.flags,
+        subspaces_added: subspaces_added.subspaces,
+        subspaces_removed: subspaces_removed.subspaces,
+        objects_upvoted: objects_upvoted.votes,
+        objects_downvoted: objects_downvoted.votes,
+        objects_unvoted: objects_unvoted.votes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_support::*;
+
+    const SPACE: [u8; 16] = [1; 16];
+    const OTHER_SPACE: [u8; 16] = [2; 16];
+    const ADDRESS: [u8; 20] = [3; 20];
+    const DATA: &[u8] = b"metadata";
+
+    fn actions(logs: Vec<ActionLog>) -> Actions {
+        map_actions(registry_params(), block(logs)).unwrap()
+    }
+
+    /// The actions of a block emitting `log` with `DATA`, after an unrelated action the typed
+    /// handlers have to filter out.
+    fn typed_actions(log: ActionLog) -> Actions {
+        let unrelated = action_log().from(&SPACE).action(ACTION_EDITS_PUBLISHED);
+        actions(vec![unrelated, log.data(DATA)])
+    }
+
+    #[test]
+    fn test_map_actions_decodes_registry_logs() {
+        let actions = actions(vec![
+            action_log().from(&SPACE).to(&OTHER_SPACE).action(ACTION_EDITOR_ADDED).topic(&ADDRESS).data(DATA),
+            action_log().address(&[0xbb; 20]).from(&SPACE).action(ACTION_EDITOR_ADDED),
+            action_log().topics(vec![vec![0; 32]; 3]),
+            action_log().from(&OTHER_SPACE).action(ACTION_MEMBER_ADDED),
+        ])
+        .actions;
+
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0].from_id, SPACE.to_vec());
+        assert_eq!(actions[0].to_id, OTHER_SPACE.to_vec());
+        assert_eq!(actions[0].action, ACTION_EDITOR_ADDED.to_vec());
+        assert_eq!(actions[0].topic[12..], ADDRESS);
+        assert_eq!(actions[0].data, DATA);
+        assert_eq!(actions[0].decoded_version, ACTION_VERSION_V1);
+        assert_eq!(actions[0].tx_from, TX_FROM.to_vec());
+        assert_eq!(actions[0].tx_hash, TX_HASH.to_vec());
+        assert_eq!(actions[0].log_index, 0);
+        // Logs of other contracts and events are skipped, and keep their index
+        assert_eq!(actions[1].from_id, OTHER_SPACE.to_vec());
+        assert_eq!(actions[1].log_index, 3);
+    }
+
+    #[test]
+    fn test_map_actions_rejects_invalid_params() {
+        assert!(map_actions(String::new(), block(vec![])).is_err());
+    }
+
+    #[test]
+    fn test_map_spaces_registered() {
+        let actions = typed_actions(action_log().from(&SPACE).action(ACTION_SPACE_ID_REGISTERED).topic(&ADDRESS));
+
+        let spaces = map_spaces_registered(actions).unwrap().spaces;
+
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0].space_id, SPACE.to_vec());
+        assert_eq!(spaces[0].space_address, ADDRESS.to_vec());
+        assert_eq!(spaces[0].data, DATA);
+        assert_eq!(spaces[0].tx_from, TX_FROM.to_vec());
+        assert_eq!(spaces[0].tx_hash, TX_HASH.to_vec());
+        assert_eq!(spaces[0].log_index, 1);
+    }
+
+    #[test]
+    fn test_map_spaces_migrated() {
+        let actions = typed_actions(action_log().from(&SPACE).action(ACTION_SPACE_ID_MIGRATED).topic(&ADDRESS));
+
+        let spaces = map_spaces_migrated(actions).unwrap().spaces;
+
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0].space_id, SPACE.to_vec());
+        assert_eq!(spaces[0].new_space_address, ADDRESS.to_vec());
+        assert_eq!(spaces[0].data, DATA);
+    }
+
+    #[test]
+    fn test_map_proposals() {
+        let proposal_id = [7; 32];
+        let actions = actions(vec![
+            action_log().from(&SPACE).action(ACTION_PROPOSAL_CREATED).topic(&proposal_id).data(DATA),
+            action_log().from(&OTHER_SPACE).to(&SPACE).action(ACTION_PROPOSAL_VOTED).topic(&proposal_id).data(&[1]),
+            action_log().from(&SPACE).action(ACTION_PROPOSAL_EXECUTED).topic(&proposal_id),
+        ]);
+
+        let created = map_proposals_created(actions.clone()).unwrap().proposals;
+        let voted = map_proposals_voted(actions.clone()).unwrap().votes;
+        let executed = map_proposals_executed(actions).unwrap().proposals;
+
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].space_id, SPACE.to_vec());
+        assert_eq!(created[0].proposal_id, proposal_id.to_vec());
+        assert_eq!(created[0].data, DATA);
+        assert_eq!(voted.len(), 1);
+        assert_eq!(voted[0].voter_id, OTHER_SPACE.to_vec());
+        assert_eq!(voted[0].space_id, SPACE.to_vec());
+        assert_eq!(voted[0].proposal_id, proposal_id.to_vec());
+        assert_eq!(voted[0].data, vec![1]);
+        assert_eq!(executed.len(), 1);
+        assert_eq!(executed[0].space_id, SPACE.to_vec());
+        assert_eq!(executed[0].proposal_id, proposal_id.to_vec());
+        assert_eq!(executed[0].log_index, 2);
+    }
+
+    #[test]
+    fn test_map_editors() {
+        let log = |action| action_log().from(&SPACE).action(action).topic(&ADDRESS);
+
+        let added = map_editors_added(typed_actions(log(ACTION_EDITOR_ADDED))).unwrap().editors;
+        let removed = map_editors_removed(typed_actions(log(ACTION_EDITOR_REMOVED))).unwrap().editors;
+        let flagged = map_editors_flagged(typed_actions(log(ACTION_EDITOR_FLAGGED))).unwrap().editors;
+        let unflagged = map_editors_unflagged(typed_actions(log(ACTION_EDITOR_UNFLAGGED))).unwrap().editors;
+
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].space_id, SPACE.to_vec());
+        assert_eq!(added[0].editor_address, ADDRESS.to_vec());
+        assert_eq!(added[0].data, DATA);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].space_id, SPACE.to_vec());
+        assert_eq!(removed[0].editor_address, ADDRESS.to_vec());
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].space_id, SPACE.to_vec());
+        assert_eq!(flagged[0].editor_address, ADDRESS.to_vec());
+        assert_eq!(unflagged.len(), 1);
+        assert_eq!(unflagged[0].space_id, SPACE.to_vec());
+        assert_eq!(unflagged[0].editor_address, ADDRESS.to_vec());
+    }
+
+    #[test]
+    fn test_map_members() {
+        let log = |action| action_log().from(&SPACE).action(action).topic(&ADDRESS);
+
+        let added = map_members_added(typed_actions(log(ACTION_MEMBER_ADDED))).unwrap().members;
+        let removed = map_members_removed(typed_actions(log(ACTION_MEMBER_REMOVED))).unwrap().members;
+
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].space_id, SPACE.to_vec());
+        assert_eq!(added[0].member_address, ADDRESS.to_vec());
+        assert_eq!(added[0].data, DATA);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].space_id, SPACE.to_vec());
+        assert_eq!(removed[0].member_address, ADDRESS.to_vec());
+    }
+
+    #[test]
+    fn test_map_spaces_left() {
+        let actions = typed_actions(action_log().from(&OTHER_SPACE).to(&SPACE).action(ACTION_SPACE_LEFT));
+
+        let spaces = map_spaces_left(actions).unwrap().spaces;
+
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0].member_id, OTHER_SPACE.to_vec());
+        assert_eq!(spaces[0].space_id, SPACE.to_vec());
+        assert_eq!(spaces[0].data, DATA);
+    }
+
+    #[test]
+    fn test_map_topics_declared() {
+        let root = [4; 32];
+        let child = [5; 32];
+        let label_hash = [6; 32];
+        let actions = actions(vec![
+            action_log().from(&SPACE).action(ACTION_TOPIC_DECLARED).topic(&child).data(&[root, label_hash].concat()),
+            action_log().from(&SPACE).action(ACTION_TOPIC_DECLARED).topic(&root).data(&[[0; 32], label_hash].concat()),
+            // Too short for a parent and a label hash
+            action_log().from(&SPACE).action(ACTION_TOPIC_DECLARED).topic(&[8; 32]).data(&[0; 32]),
+        ]);
+
+        let topics = map_topics_declared(actions).unwrap().topics;
+
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].topic_id, root.to_vec());
+        assert!(topics[0].parent_topic_id.is_empty());
+        assert_eq!(topics[1].topic_id, child.to_vec());
+        assert_eq!(topics[1].parent_topic_id, root.to_vec());
+        assert_eq!(topics[1].label_hash, label_hash.to_vec());
+        assert_eq!(topics[1].space_id, SPACE.to_vec());
+    }
+
+    #[test]
+    fn test_map_edits_published() {
+        let actions = actions(vec![action_log().from(&SPACE).action(ACTION_EDITS_PUBLISHED).data(DATA)]);
+
+        let edits = map_edits_published(actions).unwrap().edits;
+
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].space_id, SPACE.to_vec());
+        assert_eq!(edits[0].data, DATA);
+    }
+
+    #[test]
+    fn test_map_content_flagged() {
+        let object_id = [9; 32];
+        let log = action_log().from(&OTHER_SPACE).to(&SPACE).action(ACTION_FLAGGED).topic(&object_id);
+
+        let flags = map_content_flagged(typed_actions(log)).unwrap().flags;
+
+        assert_eq!(flags.len(), 1);
+        assert_eq!(flags[0].flagger_id, OTHER_SPACE.to_vec());
+        assert_eq!(flags[0].space_id, SPACE.to_vec());
+        assert_eq!(flags[0].object_id, object_id.to_vec());
+        assert_eq!(flags[0].data, DATA);
+    }
+
+    #[test]
+    fn test_map_subspaces() {
+        let log = |action| action_log().from(&SPACE).action(action).topic(&OTHER_SPACE);
+
+        let added = map_subspaces_added(typed_actions(log(ACTION_SUBSPACE_ADDED))).unwrap().subspaces;
+        let removed = map_subspaces_removed(typed_actions(log(ACTION_SUBSPACE_REMOVED))).unwrap().subspaces;
+
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].parent_space_id, SPACE.to_vec());
+        assert_eq!(added[0].subspace_id, OTHER_SPACE.to_vec());
+        assert_eq!(added[0].data, DATA);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].parent_space_id, SPACE.to_vec());
+        assert_eq!(removed[0].subspace_id, OTHER_SPACE.to_vec());
+    }
+
+    #[test]
+    fn test_map_object_votes() {
+        let object_type = [1, 2, 3, 4];
+        let object_id = [5; 16];
+        let log = |action| action_log().from(&SPACE).action(action).topic(&object_topic(&object_type, &object_id));
+
+        let upvoted = map_objects_upvoted(typed_actions(log(ACTION_OBJECT_UPVOTED))).unwrap().votes;
+        let downvoted = map_objects_downvoted(typed_actions(log(ACTION_OBJECT_DOWNVOTED))).unwrap().votes;
+        let unvoted = map_objects_unvoted(typed_actions(log(ACTION_OBJECT_UNVOTED))).unwrap().votes;
+
+        for votes in [upvoted, downvoted, unvoted] {
+            assert_eq!(votes.len(), 1);
+            assert_eq!(votes[0].voter_id, SPACE.to_vec());
+            assert_eq!(votes[0].object_type, object_type.to_vec());
+            assert_eq!(votes[0].object_id, object_id.to_vec());
+            assert_eq!(votes[0].data, DATA);
+        }
+    }
+}
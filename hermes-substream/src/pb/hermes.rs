@@ -22,6 +22,18 @@ pub struct Action {
     /// variable - action-specific payload
     #[prost(bytes="vec", tag="5")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// encoding of the Action event the fields were decoded from
+    #[prost(uint32, tag="6")]
+    pub decoded_version: u32,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="7")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="8")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="9")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -44,6 +56,15 @@ pub struct SpaceRegistered {
     pub space_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -62,6 +83,15 @@ pub struct SpaceMigrated {
     pub new_space_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -81,6 +111,15 @@ pub struct ProposalCreated {
     /// Proposal metadata
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -103,6 +142,15 @@ pub struct ProposalVoted {
     /// Vote choice
     #[prost(bytes="vec", tag="4")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="5")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="6")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="7")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -121,6 +169,15 @@ pub struct ProposalExecuted {
     pub proposal_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -139,6 +196,15 @@ pub struct EditorAdded {
     pub editor_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -157,6 +223,15 @@ pub struct EditorRemoved {
     pub editor_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -175,6 +250,15 @@ pub struct MemberAdded {
     pub member_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -193,6 +277,15 @@ pub struct MemberRemoved {
     pub member_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -211,6 +304,15 @@ pub struct EditorFlagged {
     pub editor_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -229,6 +331,15 @@ pub struct EditorUnflagged {
     pub editor_address: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -247,6 +358,15 @@ pub struct SpaceLeft {
     pub space_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -257,18 +377,36 @@ pub struct SpaceLeftList {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TopicDeclared {
-    /// 16 bytes
+    /// 16 bytes - declaring space
     #[prost(bytes="vec", tag="1")]
     pub space_id: ::prost::alloc::vec::Vec<u8>,
     /// 32 bytes - keccak256 of topic name
     #[prost(bytes="vec", tag="2")]
     pub topic_id: ::prost::alloc::vec::Vec<u8>,
+    /// parent_topic_id (32 bytes) ++ label_hash (32 bytes)
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
-}
-#[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct TopicDeclaredList {
+    /// 32 bytes - empty for root topics
+    #[prost(bytes="vec", tag="4")]
+    pub parent_topic_id: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes - keccak256 of topic label
+    #[prost(bytes="vec", tag="5")]
+    pub label_hash: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="6")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="7")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="8")]
+    pub log_index: u64,
+}
+/// Topics are ordered parents first, so a topic declared under a parent from the
+/// same block comes after it.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TopicsDeclaredList {
     #[prost(message, repeated, tag="1")]
     pub topics: ::prost::alloc::vec::Vec<TopicDeclared>,
 }
@@ -281,6 +419,15 @@ pub struct EditsPublished {
     /// IPFS CID or edit content
     #[prost(bytes="vec", tag="2")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="3")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="4")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="5")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -300,6 +447,18 @@ pub struct ContentFlagged {
     /// Flag details
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
+    /// 32 bytes - flagged content, from topic field
+    #[prost(bytes="vec", tag="7")]
+    pub object_id: ::prost::alloc::vec::Vec<u8>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -318,6 +477,15 @@ pub struct SubspaceAdded {
     pub subspace_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -336,6 +504,15 @@ pub struct SubspaceRemoved {
     pub subspace_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="3")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="4")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="5")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="6")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -361,6 +538,15 @@ pub struct ObjectVoted {
     pub object_id: ::prost::alloc::vec::Vec<u8>,
     #[prost(bytes="vec", tag="4")]
     pub data: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - account that sent the transaction
+    #[prost(bytes="vec", tag="5")]
+    pub tx_from: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="6")]
+    pub tx_hash: ::prost::alloc::vec::Vec<u8>,
+    /// position of the log in the block
+    #[prost(uint64, tag="7")]
+    pub log_index: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -380,4 +566,252 @@ pub struct ObjectUnvotedList {
     #[prost(message, repeated, tag="1")]
     pub votes: ::prost::alloc::vec::Vec<ObjectVoted>,
 }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlagCountDelta {
+    /// 16 bytes - space being flagged
+    #[prost(bytes="vec", tag="1")]
+    pub space_id: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes - flagged content
+    #[prost(bytes="vec", tag="2")]
+    pub object_id: ::prost::alloc::vec::Vec<u8>,
+    /// flags before the block
+    #[prost(int64, tag="3")]
+    pub old_count: i64,
+    /// flags after the block
+    #[prost(int64, tag="4")]
+    pub new_count: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FlagCountDeltas {
+    #[prost(message, repeated, tag="1")]
+    pub deltas: ::prost::alloc::vec::Vec<FlagCountDelta>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MembershipDelta {
+    /// 16 bytes
+    #[prost(bytes="vec", tag="1")]
+    pub space_id: ::prost::alloc::vec::Vec<u8>,
+    /// 20 bytes - editor or member address
+    #[prost(bytes="vec", tag="2")]
+    pub account: ::prost::alloc::vec::Vec<u8>,
+    #[prost(enumeration="MembershipRole", tag="3")]
+    pub role: i32,
+    #[prost(enumeration="MembershipChange", tag="4")]
+    pub change: i32,
+    /// position of the log in the block
+    #[prost(uint64, tag="5")]
+    pub ordinal: u64,
+}
+/// Changes to the editor and member sets of a block, in log order. Applying the
+/// deltas of every block up to N gives the sets at block N.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MembershipDeltas {
+    #[prost(uint64, tag="1")]
+    pub block_number: u64,
+    #[prost(message, repeated, tag="2")]
+    pub deltas: ::prost::alloc::vec::Vec<MembershipDelta>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ObjectVoteTotal {
+    /// 4 bytes
+    #[prost(bytes="vec", tag="1")]
+    pub object_type: ::prost::alloc::vec::Vec<u8>,
+    /// 16 bytes
+    #[prost(bytes="vec", tag="2")]
+    pub object_id: ::prost::alloc::vec::Vec<u8>,
+    /// total after the block
+    #[prost(int64, tag="3")]
+    pub upvotes: i64,
+    /// total after the block
+    #[prost(int64, tag="4")]
+    pub downvotes: i64,
+    /// change in the block
+    #[prost(int64, tag="5")]
+    pub upvotes_delta: i64,
+    /// change in the block
+    #[prost(int64, tag="6")]
+    pub downvotes_delta: i64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ObjectVoteTotals {
+    #[prost(message, repeated, tag="1")]
+    pub totals: ::prost::alloc::vec::Vec<ObjectVoteTotal>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProposalUpdate {
+    /// 16 bytes
+    #[prost(bytes="vec", tag="1")]
+    pub space_id: ::prost::alloc::vec::Vec<u8>,
+    /// 32 bytes
+    #[prost(bytes="vec", tag="2")]
+    pub proposal_id: ::prost::alloc::vec::Vec<u8>,
+    /// status after the block
+    #[prost(enumeration="ProposalStatus", tag="3")]
+    pub status: i32,
+    /// 0 if created before the module's initial block
+    #[prost(uint64, tag="4")]
+    pub created_block: u64,
+    /// set if created in the block
+    #[prost(message, optional, tag="5")]
+    pub created: ::core::option::Option<ProposalCreated>,
+    /// votes cast in the block, in log order
+    #[prost(message, repeated, tag="6")]
+    pub votes: ::prost::alloc::vec::Vec<ProposalVoted>,
+    /// set if executed in the block
+    #[prost(message, optional, tag="7")]
+    pub executed: ::core::option::Option<ProposalExecuted>,
+}
+/// The proposals acted on in a block, in the order they were first acted on.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProposalLifecycle {
+    #[prost(uint64, tag="1")]
+    pub block_number: u64,
+    #[prost(message, repeated, tag="2")]
+    pub updates: ::prost::alloc::vec::Vec<ProposalUpdate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HermesOutput {
+    #[prost(message, repeated, tag="1")]
+    pub spaces_registered: ::prost::alloc::vec::Vec<SpaceRegistered>,
+    #[prost(message, repeated, tag="2")]
+    pub spaces_migrated: ::prost::alloc::vec::Vec<SpaceMigrated>,
+    #[prost(message, repeated, tag="3")]
+    pub proposals_created: ::prost::alloc::vec::Vec<ProposalCreated>,
+    #[prost(message, repeated, tag="4")]
+    pub proposals_voted: ::prost::alloc::vec::Vec<ProposalVoted>,
+    #[prost(message, repeated, tag="5")]
+    pub proposals_executed: ::prost::alloc::vec::Vec<ProposalExecuted>,
+    #[prost(message, repeated, tag="6")]
+    pub editors_added: ::prost::alloc::vec::Vec<EditorAdded>,
+    #[prost(message, repeated, tag="7")]
+    pub editors_removed: ::prost::alloc::vec::Vec<EditorRemoved>,
+    #[prost(message, repeated, tag="8")]
+    pub members_added: ::prost::alloc::vec::Vec<MemberAdded>,
+    #[prost(message, repeated, tag="9")]
+    pub members_removed: ::prost::alloc::vec::Vec<MemberRemoved>,
+    #[prost(message, repeated, tag="10")]
+    pub editors_flagged: ::prost::alloc::vec::Vec<EditorFlagged>,
+    #[prost(message, repeated, tag="11")]
+    pub editors_unflagged: ::prost::alloc::vec::Vec<EditorUnflagged>,
+    #[prost(message, repeated, tag="12")]
+    pub spaces_left: ::prost::alloc::vec::Vec<SpaceLeft>,
+    #[prost(message, repeated, tag="13")]
+    pub topics_declared: ::prost::alloc::vec::Vec<TopicDeclared>,
+    #[prost(message, repeated, tag="14")]
+    pub edits_published: ::prost::alloc::vec::Vec<EditsPublished>,
+    #[prost(message, repeated, tag="15")]
+    pub content_flagged: ::prost::alloc::vec::Vec<ContentFlagged>,
+    #[prost(message, repeated, tag="16")]
+    pub subspaces_added: ::prost::alloc::vec::Vec<SubspaceAdded>,
+    #[prost(message, repeated, tag="17")]
+    pub subspaces_removed: ::prost::alloc::vec::Vec<SubspaceRemoved>,
+    #[prost(message, repeated, tag="18")]
+    pub objects_upvoted: ::prost::alloc::vec::Vec<ObjectVoted>,
+    #[prost(message, repeated, tag="19")]
+    pub objects_downvoted: ::prost::alloc::vec::Vec<ObjectVoted>,
+    #[prost(message, repeated, tag="20")]
+    pub objects_unvoted: ::prost::alloc::vec::Vec<ObjectVoted>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MembershipRole {
+    Unspecified = 0,
+    Editor = 1,
+    Member = 2,
+}
+impl MembershipRole {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            MembershipRole::Unspecified => "MEMBERSHIP_ROLE_UNSPECIFIED",
+            MembershipRole::Editor => "MEMBERSHIP_ROLE_EDITOR",
+            MembershipRole::Member => "MEMBERSHIP_ROLE_MEMBER",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "MEMBERSHIP_ROLE_UNSPECIFIED" => Some(Self::Unspecified),
+            "MEMBERSHIP_ROLE_EDITOR" => Some(Self::Editor),
+            "MEMBERSHIP_ROLE_MEMBER" => Some(Self::Member),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MembershipChange {
+    Unspecified = 0,
+    Added = 1,
+    Removed = 2,
+    Flagged = 3,
+    Unflagged = 4,
+}
+impl MembershipChange {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            MembershipChange::Unspecified => "MEMBERSHIP_CHANGE_UNSPECIFIED",
+            MembershipChange::Added => "MEMBERSHIP_CHANGE_ADDED",
+            MembershipChange::Removed => "MEMBERSHIP_CHANGE_REMOVED",
+            MembershipChange::Flagged => "MEMBERSHIP_CHANGE_FLAGGED",
+            MembershipChange::Unflagged => "MEMBERSHIP_CHANGE_UNFLAGGED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "MEMBERSHIP_CHANGE_UNSPECIFIED" => Some(Self::Unspecified),
+            "MEMBERSHIP_CHANGE_ADDED" => Some(Self::Added),
+            "MEMBERSHIP_CHANGE_REMOVED" => Some(Self::Removed),
+            "MEMBERSHIP_CHANGE_FLAGGED" => Some(Self::Flagged),
+            "MEMBERSHIP_CHANGE_UNFLAGGED" => Some(Self::Unflagged),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ProposalStatus {
+    Unspecified = 0,
+    Open = 1,
+    Executed = 2,
+}
+impl ProposalStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ProposalStatus::Unspecified => "PROPOSAL_STATUS_UNSPECIFIED",
+            ProposalStatus::Open => "PROPOSAL_STATUS_OPEN",
+            ProposalStatus::Executed => "PROPOSAL_STATUS_EXECUTED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PROPOSAL_STATUS_UNSPECIFIED" => Some(Self::Unspecified),
+            "PROPOSAL_STATUS_OPEN" => Some(Self::Open),
+            "PROPOSAL_STATUS_EXECUTED" => Some(Self::Executed),
+            _ => None,
+        }
+    }
+}
 // @@protoc_insertion_point(module)
@@ -0,0 +1,136 @@
+//! Builders of Space Registry logs for unit tests.
+//!
+//! ```ignore
+//! let block = block(vec![action_log()
+//!     .from(&space_id)
+//!     .action(ACTION_EDITOR_ADDED)
+//!     .topic(&editor_address)
+//!     .data(b"metadata")]);
+//! ```
+
+use substreams_ethereum::pb::eth::v2::{Block, Log, TransactionReceipt, TransactionTrace};
+
+use crate::helpers::format_hex;
+
+/// The Space Registry that emits the logs of [`action_log`].
+pub const REGISTRY: [u8; 20] = [0xaa; 20];
+/// Sender of the transaction of [`block`].
+pub const TX_FROM: [u8; 20] = [0xf0; 20];
+/// Hash of the transaction of [`block`].
+pub const TX_HASH: [u8; 32] = [0xee; 32];
+
+/// `map_actions` params tracking [`REGISTRY`].
+pub fn registry_params() -> String {
+    format_hex(&REGISTRY)
+}
+
+/// An `Action` log of [`REGISTRY`] with zeroed topics and no data.
+pub fn action_log() -> ActionLog {
+    ActionLog {
+        address: REGISTRY.to_vec(),
+        topics: vec![vec![0; 32]; 4],
+        data: vec![],
+    }
+}
+
+/// A topic holding an object type and ID, left-aligned like the vote actions encode them.
+pub fn object_topic(object_type: &[u8], object_id: &[u8]) -> Vec<u8> {
+    let mut topic = [object_type, object_id].concat();
+    topic.resize(32, 0);
+    topic
+}
+
+/// Builder of an anonymous `Action(bytes16 indexed fromId, bytes16 indexed toId,
+/// bytes32 indexed action, bytes32 indexed topic, bytes data)` log.
+#[derive(Clone, Debug)]
+pub struct ActionLog {
+    address: Vec<u8>,
+    topics: Vec<Vec<u8>>,
+    data: Vec<u8>,
+}
+
+impl ActionLog {
+    /// Emits the log from another contract.
+    pub fn address(mut self, address: &[u8]) -> Self {
+        self.address = address.to_vec();
+        self
+    }
+
+    /// The acting space, 16 bytes.
+    pub fn from(mut self, space_id: &[u8]) -> Self {
+        self.topics[0] = left_pad(space_id);
+        self
+    }
+
+    /// The space acted on, 16 bytes.
+    pub fn to(mut self, space_id: &[u8]) -> Self {
+        self.topics[1] = left_pad(space_id);
+        self
+    }
+
+    pub fn action(mut self, action: [u8; 32]) -> Self {
+        self.topics[2] = action.to_vec();
+        self
+    }
+
+    /// The topic field, left-padded to 32 bytes like an address or a space ID.
+    pub fn topic(mut self, topic: &[u8]) -> Self {
+        self.topics[3] = left_pad(topic);
+        self
+    }
+
+    /// Replaces the topics as is, to build logs of other events or malformed ones.
+    pub fn topics(mut self, topics: Vec<Vec<u8>>) -> Self {
+        self.topics = topics;
+        self
+    }
+
+    pub fn data(mut self, data: &[u8]) -> Self {
+        self.data = data.to_vec();
+        self
+    }
+
+    pub fn build(self) -> Log {
+        Log {
+            address: self.address,
+            topics: self.topics,
+            data: self.data,
+            ..Default::default()
+        }
+    }
+}
+
+/// A block with one successful transaction emitting `logs`, indexed in order.
+pub fn block(logs: Vec<ActionLog>) -> Block {
+    let logs: Vec<Log> = logs
+        .into_iter()
+        .enumerate()
+        .map(|(index, log)| Log {
+            index: index as u32,
+            block_index: index as u32,
+            ordinal: index as u64,
+            ..log.build()
+        })
+        .collect();
+
+    Block {
+        number: 1,
+        transaction_traces: vec![TransactionTrace {
+            from: TX_FROM.to_vec(),
+            hash: TX_HASH.to_vec(),
+            status: 1,
+            receipt: Some(TransactionReceipt {
+                logs,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+fn left_pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = vec![0; 32usize.saturating_sub(bytes.len())];
+    padded.extend_from_slice(bytes);
+    padded
+}
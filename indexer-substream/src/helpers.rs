@@ -1,6 +1,104 @@
 use substreams::Hex;
+use substreams_ethereum::{block_view::LogView, pb::eth};
+
+use crate::pb::schema::EventContext;
 
 /// This function will return the hex representation of the address in lowercase
 pub fn format_hex(address: &[u8]) -> String {
     format!("0x{}", Hex(address).to_string())
 }
+
+/// Where `log` was emitted onchain, attached to every event so sinks can dedupe
+/// and audit them.
+pub fn event_context(block: &eth::v2::Block, log: &LogView) -> EventContext {
+    EventContext {
+        tx_hash: format_hex(&log.receipt.transaction.hash),
+        log_index: log.log.block_index as u64,
+        block_number: block.number,
+        block_timestamp: block.timestamp_seconds(),
+    }
+}
+
+/// Parses the contract addresses in a module's params, separated by commas.
+///
+/// The params are set in `substreams.yaml` and can be overridden by the sink, so the same
+/// package can follow the mainnet, testnet or local deployments of the factories.
+pub fn parse_addresses(params: &str) -> Result<Vec<String>, substreams::errors::Error> {
+    params
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            let is_address = address
+                .strip_prefix("0x")
+                .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+            if !is_address {
+                return Err(substreams::errors::Error::msg(format!(
+                    "invalid contract address '{}' in params",
+                    address
+                )));
+            }
+
+            Ok(address.to_lowercase())
+        })
+        .collect()
+}
+
+/// Whether `address` is one of `addresses`. An empty list accepts every contract, which
+/// keeps packages built without params matching events from any emitter.
+pub fn is_tracked_address(addresses: &[String], address: &[u8]) -> bool {
+    addresses.is_empty() || addresses.contains(&format_hex(address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_addresses() {
+        let addresses = parse_addresses(
+            "0x80eF8d87fafCB65F5399c6d28c72A27577616339, 0x0000000000000000000000000000000000000001,",
+        )
+        .unwrap();
+
+        assert_eq!(
+            addresses,
+            vec![
+                "0x80ef8d87fafcb65f5399c6d28c72a27577616339".to_string(),
+                "0x0000000000000000000000000000000000000001".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_addresses_empty() {
+        assert!(parse_addresses("").unwrap().is_empty());
+        assert!(parse_addresses(" , ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_addresses_invalid() {
+        assert!(parse_addresses("80ef8d87fafcb65f5399c6d28c72a27577616339").is_err());
+        assert!(parse_addresses("0x80ef").is_err());
+        assert!(parse_addresses("0x80ef8d87fafcb65f5399c6d28c72a2757761633z").is_err());
+        assert!(parse_addresses(
+            "0x80ef8d87fafcb65f5399c6d28c72a27577616339, 0x80ef8d87fafcb65f5399c6d28c72a2757761633"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_is_tracked_address() {
+        let addresses = parse_addresses("0x80eF8d87fafCB65F5399c6d28c72A27577616339").unwrap();
+        let tracked = Hex::decode("80ef8d87fafcb65f5399c6d28c72a27577616339").unwrap();
+
+        assert!(is_tracked_address(&addresses, &tracked));
+        assert!(!is_tracked_address(&addresses, &[1u8; 20]));
+    }
+
+    #[test]
+    fn test_is_tracked_address_empty_tracks_every_address() {
+        assert!(is_tracked_address(&[], &[1u8; 20]));
+        assert!(is_tracked_address(&parse_addresses("").unwrap(), &[0u8; 20]));
+    }
+}
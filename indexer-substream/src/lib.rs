@@ -1,26 +1,39 @@
+// The wasm entrypoints generated for handlers with params read them from a raw pointer
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
 pub mod helpers;
 
 mod pb;
 
-use member_access_plugin::events::AddMemberProposalCreated as AddMemberProposalCreatedEvent;
+use member_access_plugin::events::{
+    AddMemberProposalCreated as AddMemberProposalCreatedEvent,
+    MultisigSettingsUpdated as MultisigSettingsUpdatedEvent,
+};
 use pb::schema::{
-    AddEditorProposalCreated, AddEditorProposalsCreated, AddMemberProposalCreated,
+    ordered_event, AddEditorProposalCreated, AddEditorProposalsCreated, AddMemberProposalCreated,
     AddMemberProposalsCreated, AddSubspaceProposalCreated, AddSubspaceProposalsCreated,
-    EditPublished, EditorAdded, EditorRemoved, EditorsAdded, EditorsRemoved, EditsPublished,
-    GeoGovernancePluginCreated, GeoGovernancePluginsCreated, GeoOutput,
-    GeoPersonalSpaceAdminPluginCreated, GeoPersonalSpaceAdminPluginsCreated, GeoSpaceCreated,
-    GeoSpacesCreated, InitialEditorAdded, InitialEditorsAdded, MemberAdded, MemberRemoved,
-    MembersAdded, MembersRemoved, ProposalExecuted, ProposalsExecuted, PublishEditProposalCreated,
-    PublishEditsProposalsCreated, RemoveEditorProposalCreated, RemoveEditorProposalsCreated,
-    RemoveMemberProposalCreated, RemoveMemberProposalsCreated, RemoveSubspaceProposalCreated,
-    RemoveSubspaceProposalsCreated, SubspaceAdded, SubspaceRemoved, SubspacesAdded,
-    SubspacesRemoved, SuccessorSpaceCreated, SuccessorSpacesCreated, VoteCast, VotesCast,
+    DaoMetadataSet, DaoMetadataSets, EditPublished, EditorAdded, EditorRemoved, EditorsAdded,
+    EditorsRemoved, EditsPublished, GeoGovernancePluginCreated, GeoGovernancePluginsCreated,
+    GeoOutput, GeoPersonalSpaceAdminPluginCreated, GeoPersonalSpaceAdminPluginsCreated,
+    GeoSpaceCreated, GeoSpacesCreated, InitialEditorAdded, InitialEditorsAdded,
+    MemberAccessSettingsUpdated, MemberAccessSettingsUpdates, MemberAdded, MemberRemoved,
+    MembersAdded, MembersRemoved, OrderedEvent, ProposalCanceled, ProposalCreated,
+    ProposalExecuted, ProposalsCanceled, ProposalsCreated, ProposalsExecuted,
+    PublishEditProposalCreated, PublishEditsProposalsCreated, RemoveEditorProposalCreated,
+    RemoveEditorProposalsCreated, RemoveMemberProposalCreated, RemoveMemberProposalsCreated,
+    RemoveSubspaceProposalCreated, RemoveSubspaceProposalsCreated, SubspaceAdded, SubspaceRemoved,
+    SubspacesAdded, SubspacesRemoved, SuccessorSpaceCreated, SuccessorSpacesCreated, VoteCast,
+    VotesCast, VotingSettingsUpdated, VotingSettingsUpdates,
 };
 
-use substreams_ethereum::{pb::eth, use_contract, Event};
+use substreams::store::{
+    StoreGet, StoreGetString, StoreNew, StoreSetIfNotExists, StoreSetIfNotExistsString,
+};
+use substreams_ethereum::{block_view::LogView, pb::eth, use_contract, Event};
 
 use helpers::*;
 
+use_contract!(dao, "abis/dao.json");
 use_contract!(space, "abis/space.json");
 use_contract!(space_setup, "abis/space-setup.json");
 use_contract!(governance_setup, "abis/governance-setup.json");
@@ -33,17 +46,20 @@ use_contract!(
     "abis/majority-voting-base.json"
 );
 
+use dao::events::MetadataSet as MetadataSetEvent;
 use governance_setup::events::GeoGovernancePluginsCreated as GovernancePluginCreatedEvent;
 use main_voting_plugin::events::{
     AcceptSubspaceProposalCreated as AcceptSubspaceProposalCreatedEvent,
     AddEditorProposalCreated as AddEditorProposalCreatedEvent, EditorAdded as EditorAddedEvent,
     EditorRemoved as EditorRemovedEvent, EditorsAdded as EditorsAddedEvent,
     MemberAdded as MemberAddedEvent, MemberRemoved as MemberRemovedEvent,
+    ProposalCanceled as ProposalCanceledEvent, ProposalCreated as ProposalCreatedEvent,
     ProposalExecuted as ProposalExecutedEvent,
     PublishEditsProposalCreated as PublishEditsProposalCreatedEvent,
     RemoveEditorProposalCreated as RemoveEditorProposalCreatedEvent,
     RemoveMemberProposalCreated as RemoveMemberProposalCreatedEvent,
     RemoveSubspaceProposalCreated as RemoveSubspaceProposalCreatedEvent,
+    VotingSettingsUpdated as VotingSettingsUpdatedEvent,
 };
 use majority_voting_base_plugin::events::VoteCast as VoteCastEvent;
 use personal_admin_setup::events::GeoPersonalAdminPluginCreated as GeoPersonalAdminPluginCreatedEvent;
@@ -75,6 +91,7 @@ fn map_successor_spaces_created(
                     plugin_address: address,
                     predecessor_space: format_hex(&successor_space_created.predecessor_space),
                     dao_address: format_hex(&successor_space_created.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -94,18 +111,25 @@ fn map_successor_spaces_created(
  *
  * This handler maps creation of the Space plugin and associates the Space plugin contract
  * address with the address of the DAO contract.
+ *
+ * Only plugins created by the space setup contracts in `params` are mapped. Empty
+ * `params` map plugins created by any contract.
  */
 #[substreams::handlers::map]
 fn map_spaces_created(
+    params: String,
     block: eth::v2::Block,
 ) -> Result<GeoSpacesCreated, substreams::errors::Error> {
+    let factories = parse_addresses(&params)?;
     let spaces: Vec<GeoSpaceCreated> = block
         .logs()
+        .filter(|log| is_tracked_address(&factories, &log.address()))
         .filter_map(|log| {
             if let Some(space_created) = SpacePluginCreatedEvent::match_and_decode(log) {
                 return Some(GeoSpaceCreated {
                     dao_address: format_hex(&space_created.dao),
                     space_address: format_hex(&space_created.plugin),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -127,6 +151,7 @@ fn map_subspaces_added(block: eth::v2::Block) -> Result<SubspacesAdded, substrea
                     subspace: format_hex(&space_created.subspace_dao),
                     plugin_address: format_hex(&log.address()),
                     dao_address: format_hex(&space_created.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -150,6 +175,7 @@ fn map_subspaces_removed(
                     subspace: format_hex(&space_created.subspace_dao),
                     plugin_address: format_hex(&log.address()),
                     dao_address: format_hex(&space_created.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -171,23 +197,35 @@ fn map_subspaces_removed(
  * As of January 23, 2024 there are two governance plugins:
  * 1. Voting plugin – This defines the voting and proposal rules and behaviors for a DAO
  * 2. Member access plugin – This defines the membership rules and behaviors for a DAO
+ *
+ * Only plugins created by the governance setup contracts in `params` are mapped. Empty
+ * `params` map plugins created by any contract.
  */
 #[substreams::handlers::map]
 fn map_governance_plugins_created(
+    params: String,
     block: eth::v2::Block,
 ) -> Result<GeoGovernancePluginsCreated, substreams::errors::Error> {
+    let factories = parse_addresses(&params)?;
     let plugins: Vec<GeoGovernancePluginCreated> = block
         .logs()
+        .filter(|log| is_tracked_address(&factories, &log.address()))
         .filter_map(|log| {
             if let Some(space_governance_created) =
                 GovernancePluginCreatedEvent::match_and_decode(log)
             {
+                let (initial_editors, initial_members) =
+                    initial_editors_and_members(&log, &space_governance_created.main_voting_plugin);
+
                 return Some(GeoGovernancePluginCreated {
                     dao_address: format_hex(&space_governance_created.dao),
                     main_voting_address: format_hex(&space_governance_created.main_voting_plugin),
                     member_access_address: format_hex(
                         &space_governance_created.member_access_plugin,
                     ),
+                    context: Some(event_context(&block, &log)),
+                    initial_editors,
+                    initial_members,
                 });
             }
 
@@ -198,22 +236,41 @@ fn map_governance_plugins_created(
     Ok(GeoGovernancePluginsCreated { plugins })
 }
 
+/**
+ * Only plugins created by the personal admin setup contracts in `params` are mapped. Empty
+ * `params` map plugins created by any contract.
+ */
 #[substreams::handlers::map]
 fn map_personal_admin_plugins_created(
+    params: String,
     block: eth::v2::Block,
 ) -> Result<GeoPersonalSpaceAdminPluginsCreated, substreams::errors::Error> {
+    let factories = parse_addresses(&params)?;
     let plugins: Vec<GeoPersonalSpaceAdminPluginCreated> = block
         .logs()
+        .filter(|log| is_tracked_address(&factories, &log.address()))
         .filter_map(|log| {
             if let Some(personal_space_created) =
                 GeoPersonalAdminPluginCreatedEvent::match_and_decode(log)
             {
+                let (mut initial_editors, initial_members) = initial_editors_and_members(
+                    &log,
+                    &personal_space_created.personal_admin_plugin,
+                );
+                let initial_editor = format_hex(&personal_space_created.initial_editor);
+                if !initial_editors.contains(&initial_editor) {
+                    initial_editors.insert(0, initial_editor);
+                }
+
                 return Some(GeoPersonalSpaceAdminPluginCreated {
                     initial_editor: format_hex(&personal_space_created.initial_editor),
                     dao_address: format_hex(&personal_space_created.dao),
                     personal_admin_address: (format_hex(
                         &personal_space_created.personal_admin_plugin,
                     )),
+                    context: Some(event_context(&block, &log)),
+                    initial_editors,
+                    initial_members,
                 });
             }
 
@@ -224,6 +281,73 @@ fn map_personal_admin_plugins_created(
     Ok(GeoPersonalSpaceAdminPluginsCreated { plugins })
 }
 
+/**
+ * Tracks the DAOs of Geo spaces so that DAO-level events, which any Aragon DAO can emit, are
+ * only emitted for spaces. Each DAO address maps to the address of its space plugin.
+ */
+#[substreams::handlers::store]
+fn store_dao_addresses(spaces_created: GeoSpacesCreated, store: StoreSetIfNotExistsString) {
+    for space in spaces_created.spaces {
+        store.set_if_not_exists(0, &space.dao_address, &space.space_address);
+    }
+}
+
+/**
+ * Aragon DAOs store the URI of their metadata, a JSON document with the DAO's name and
+ * description. The metadata is set when the DAO is created, in the same transaction as its
+ * space plugin, and again whenever an executed proposal changes it.
+ *
+ * The URI is stored as raw bytes, so metadata that isn't valid UTF-8 is skipped.
+ */
+#[substreams::handlers::map]
+fn map_dao_metadata_set(
+    block: eth::v2::Block,
+    daos: StoreGetString,
+) -> Result<DaoMetadataSets, substreams::errors::Error> {
+    let metadata: Vec<DaoMetadataSet> = block
+        .logs()
+        .filter(|log| daos.has_last(format_hex(&log.address())))
+        .filter_map(|log| {
+            if let Some(metadata_set) = MetadataSetEvent::match_and_decode(log) {
+                return Some(DaoMetadataSet {
+                    dao_address: format_hex(&log.address()),
+                    metadata_uri: String::from_utf8(metadata_set.metadata).ok()?,
+                    context: Some(event_context(&block, &log)),
+                });
+            }
+
+            return None;
+        })
+        .collect();
+
+    Ok(DaoMetadataSets { metadata })
+}
+
+/**
+ * Votes, proposals and membership changes are decoded by event signature alone, so any
+ * contract emitting an event with the same signature would leak into the stream. This store
+ * tracks the plugins deployed for Geo DAOs so those handlers only emit events from them.
+ *
+ * Each plugin address maps to the address of the DAO it was installed on. This covers the
+ * main voting and member access plugins of public spaces and the admin plugin of personal
+ * spaces, which emits the same proposal and editor events.
+ */
+#[substreams::handlers::store]
+fn store_plugin_addresses(
+    governance_plugins_created: GeoGovernancePluginsCreated,
+    personal_admin_plugins_created: GeoPersonalSpaceAdminPluginsCreated,
+    store: StoreSetIfNotExistsString,
+) {
+    for plugin in governance_plugins_created.plugins {
+        store.set_if_not_exists(0, &plugin.main_voting_address, &plugin.dao_address);
+        store.set_if_not_exists(0, &plugin.member_access_address, &plugin.dao_address);
+    }
+
+    for plugin in personal_admin_plugins_created.plugins {
+        store.set_if_not_exists(0, &plugin.personal_admin_address, &plugin.dao_address);
+    }
+}
+
 /**
  * An editor has editing and voting permissions in a DAO-based space. Editors join a space
  * one of two ways:
@@ -244,9 +368,11 @@ fn map_personal_admin_plugins_created(
 #[substreams::handlers::map]
 fn map_initial_editors_added(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<InitialEditorsAdded, substreams::errors::Error> {
     let editors: Vec<InitialEditorAdded> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(editors_added) = EditorsAddedEvent::match_and_decode(log) {
                 return Some(InitialEditorAdded {
@@ -257,6 +383,7 @@ fn map_initial_editors_added(
                         .collect(),
                     plugin_address: format_hex(&log.address()),
                     dao_address: format_hex(&editors_added.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -268,9 +395,13 @@ fn map_initial_editors_added(
 }
 
 #[substreams::handlers::map]
-fn map_members_added(block: eth::v2::Block) -> Result<MembersAdded, substreams::errors::Error> {
+fn map_members_added(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<MembersAdded, substreams::errors::Error> {
     let members: Vec<MemberAdded> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(members_approved) = MemberAddedEvent::match_and_decode(log) {
                 return Some(MemberAdded {
@@ -278,6 +409,7 @@ fn map_members_added(block: eth::v2::Block) -> Result<MembersAdded, substreams::
                     main_voting_plugin_address: format_hex(&log.address()),
                     member_address: format_hex(&members_approved.member),
                     dao_address: format_hex(&members_approved.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -288,10 +420,20 @@ fn map_members_added(block: eth::v2::Block) -> Result<MembersAdded, substreams::
     Ok(MembersAdded { members })
 }
 
+/**
+ * Unlike editors, which can be added in a batch with `EditorsAdded`, the plugins have no batch
+ * removal events: each removed member or editor, including those removed by a single executed
+ * proposal, is its own `MemberRemoved` or `EditorRemoved` event. Decoding the singular events
+ * is enough to pick up every removal.
+ */
 #[substreams::handlers::map]
-fn map_members_removed(block: eth::v2::Block) -> Result<MembersRemoved, substreams::errors::Error> {
+fn map_members_removed(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<MembersRemoved, substreams::errors::Error> {
     let members: Vec<MemberRemoved> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(members_approved) = MemberRemovedEvent::match_and_decode(log) {
                 return Some(MemberRemoved {
@@ -299,6 +441,7 @@ fn map_members_removed(block: eth::v2::Block) -> Result<MembersRemoved, substrea
                     dao_address: format_hex(&members_approved.dao),
                     plugin_address: format_hex(&log.address()),
                     member_address: format_hex(&members_approved.member),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -310,9 +453,13 @@ fn map_members_removed(block: eth::v2::Block) -> Result<MembersRemoved, substrea
 }
 
 #[substreams::handlers::map]
-fn map_editors_added(block: eth::v2::Block) -> Result<EditorsAdded, substreams::errors::Error> {
+fn map_editors_added(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<EditorsAdded, substreams::errors::Error> {
     let editors: Vec<EditorAdded> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(members_approved) = EditorAddedEvent::match_and_decode(log) {
                 return Some(EditorAdded {
@@ -320,6 +467,7 @@ fn map_editors_added(block: eth::v2::Block) -> Result<EditorsAdded, substreams::
                     main_voting_plugin_address: format_hex(&log.address()),
                     editor_address: format_hex(&members_approved.editor),
                     dao_address: format_hex(&members_approved.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -331,9 +479,13 @@ fn map_editors_added(block: eth::v2::Block) -> Result<EditorsAdded, substreams::
 }
 
 #[substreams::handlers::map]
-fn map_editors_removed(block: eth::v2::Block) -> Result<EditorsRemoved, substreams::errors::Error> {
+fn map_editors_removed(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<EditorsRemoved, substreams::errors::Error> {
     let editors: Vec<EditorRemoved> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(members_approved) = EditorRemovedEvent::match_and_decode(log) {
                 return Some(EditorRemoved {
@@ -341,6 +493,7 @@ fn map_editors_removed(block: eth::v2::Block) -> Result<EditorsRemoved, substrea
                     plugin_address: format_hex(&log.address()),
                     editor_address: format_hex(&members_approved.editor),
                     dao_address: format_hex(&members_approved.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -382,43 +535,52 @@ fn map_editors_removed(block: eth::v2::Block) -> Result<EditorsRemoved, substrea
  * }
  * ```
  */
-// #[substreams::handlers::map]
-// fn map_proposals_created(
-//     block: eth::v2::Block,
-// ) -> Result<ProposalsCreated, substreams::errors::Error> {
-//     let proposals: Vec<ProposalCreated> = block
-//         .logs()
-//         .filter_map(|log| {
-//             if let Some(proposal_created) = ProposalCreatedEvent::match_and_decode(log) {
-//                 // @TODO: Should we return none if actions is empty?
-//                 return Some(ProposalCreated {
-//                     proposal_id: proposal_created.proposal_id.to_string(),
-//                     creator: format_hex(&proposal_created.creator),
-//                     start_time: proposal_created.start_date.to_string(),
-//                     end_time: proposal_created.end_date.to_string(),
-//                     metadata_uri: String::from_utf8(proposal_created.metadata).unwrap(),
-//                     plugin_address: format_hex(&log.address()),
-//                 });
-//             }
-
-//             return None;
-//         })
-//         .collect();
-
-//     Ok(ProposalsCreated { proposals })
-// }
+#[substreams::handlers::map]
+fn map_proposals_created(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<ProposalsCreated, substreams::errors::Error> {
+    let proposals: Vec<ProposalCreated> = block
+        .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
+        .filter_map(|log| {
+            // Every voting and admin plugin emits the same ProposalCreated event, so
+            // decoding with the main voting plugin ABI covers all of them.
+            if let Some(proposal_created) = ProposalCreatedEvent::match_and_decode(log) {
+                return Some(ProposalCreated {
+                    proposal_id: proposal_created.proposal_id.to_string(),
+                    creator: format_hex(&proposal_created.creator),
+                    start_time: proposal_created.start_date.to_string(),
+                    end_time: proposal_created.end_date.to_string(),
+                    // The metadata is the proposal's metadata URI. Proposals with
+                    // metadata that isn't valid UTF-8 are still emitted without it.
+                    metadata_uri: String::from_utf8(proposal_created.metadata).unwrap_or_default(),
+                    plugin_address: format_hex(&log.address()),
+                    context: Some(event_context(&block, &log)),
+                });
+            }
+
+            return None;
+        })
+        .collect();
+
+    Ok(ProposalsCreated { proposals })
+}
 
 #[substreams::handlers::map]
 fn map_proposals_executed(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<ProposalsExecuted, substreams::errors::Error> {
     let executed_proposals: Vec<ProposalExecuted> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(proposal_created) = ProposalExecutedEvent::match_and_decode(log) {
                 return Some(ProposalExecuted {
                     plugin_address: format_hex(&log.address()),
                     proposal_id: proposal_created.proposal_id.to_string(),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -429,6 +591,30 @@ fn map_proposals_executed(
     Ok(ProposalsExecuted { executed_proposals })
 }
 
+#[substreams::handlers::map]
+fn map_proposals_canceled(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<ProposalsCanceled, substreams::errors::Error> {
+    let canceled_proposals: Vec<ProposalCanceled> = block
+        .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
+        .filter_map(|log| {
+            if let Some(proposal_canceled) = ProposalCanceledEvent::match_and_decode(log) {
+                return Some(ProposalCanceled {
+                    plugin_address: format_hex(&log.address()),
+                    proposal_id: proposal_canceled.proposal_id.to_string(),
+                    context: Some(event_context(&block, &log)),
+                });
+            }
+
+            return None;
+        })
+        .collect();
+
+    Ok(ProposalsCanceled { canceled_proposals })
+}
+
 /**
  * Processed Proposals represent content that has been approved by a DAO
  * and executed onchain.
@@ -447,6 +633,7 @@ fn map_edits_published(block: eth::v2::Block) -> Result<EditsPublished, substrea
                     content_uri: edit_published.edits_content_uri,
                     dao_address: format_hex(&edit_published.dao),
                     plugin_address: format_hex(&log.address()),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -465,11 +652,14 @@ fn map_edits_published(block: eth::v2::Block) -> Result<EditsPublished, substrea
  * _and_ members can create them.
  */
 #[substreams::handlers::map]
-fn map_votes_cast(block: eth::v2::Block) -> Result<VotesCast, substreams::errors::Error> {
+fn map_votes_cast(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<VotesCast, substreams::errors::Error> {
     let votes: Vec<VoteCast> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
-            // @TODO: Should we track our plugins/daos and only emit if the address is one of them?
             if let Some(vote_cast) = VoteCastEvent::match_and_decode(log) {
                 return Some(VoteCast {
                     // The onchain proposal id is an incrementing integer. We represent
@@ -479,6 +669,8 @@ fn map_votes_cast(block: eth::v2::Block) -> Result<VotesCast, substreams::errors
                     voter: format_hex(&vote_cast.voter),
                     plugin_address: format_hex(&log.address()),
                     vote_option: vote_cast.vote_option.to_u64(),
+                    voting_power: vote_cast.voting_power.to_string(),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -489,14 +681,76 @@ fn map_votes_cast(block: eth::v2::Block) -> Result<VotesCast, substreams::errors
     Ok(VotesCast { votes })
 }
 
+/**
+ * Governance settings decide how proposals pass, so the sink needs their history to explain
+ * the outcome of a proposal created under older settings. Both governance plugins emit their
+ * settings when they're initialized and again whenever an executed proposal changes them.
+ *
+ * The main voting plugin's settings are its voting mode, the support threshold a proposal
+ * needs to pass and how long proposals stay open.
+ */
+#[substreams::handlers::map]
+fn map_voting_settings_updated(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<VotingSettingsUpdates, substreams::errors::Error> {
+    let settings: Vec<VotingSettingsUpdated> = block
+        .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
+        .filter_map(|log| {
+            if let Some(settings_updated) = VotingSettingsUpdatedEvent::match_and_decode(log) {
+                return Some(VotingSettingsUpdated {
+                    plugin_address: format_hex(&log.address()),
+                    voting_mode: settings_updated.voting_mode.to_u64(),
+                    support_threshold: settings_updated.support_threshold.to_u64(),
+                    duration: settings_updated.duration.to_u64(),
+                    context: Some(event_context(&block, &log)),
+                });
+            }
+
+            return None;
+        })
+        .collect();
+
+    Ok(VotingSettingsUpdates { settings })
+}
+
+/**
+ * The member access plugin's only setting is how long membership proposals stay open.
+ */
+#[substreams::handlers::map]
+fn map_member_access_settings_updated(
+    block: eth::v2::Block,
+    plugins: StoreGetString,
+) -> Result<MemberAccessSettingsUpdates, substreams::errors::Error> {
+    let settings: Vec<MemberAccessSettingsUpdated> = block
+        .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
+        .filter_map(|log| {
+            if let Some(settings_updated) = MultisigSettingsUpdatedEvent::match_and_decode(log) {
+                return Some(MemberAccessSettingsUpdated {
+                    plugin_address: format_hex(&log.address()),
+                    proposal_duration: settings_updated.proposal_duration.to_u64(),
+                    context: Some(event_context(&block, &log)),
+                });
+            }
+
+            return None;
+        })
+        .collect();
+
+    Ok(MemberAccessSettingsUpdates { settings })
+}
+
 #[substreams::handlers::map]
 fn map_publish_edits_proposals_created(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<PublishEditsProposalsCreated, substreams::errors::Error> {
     let edits: Vec<PublishEditProposalCreated> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
-            // @TODO: Should we track our plugins/daos and only emit if the address is one of them?
             if let Some(proposed_edit) = PublishEditsProposalCreatedEvent::match_and_decode(log) {
                 return Some(PublishEditProposalCreated {
                     // The onchain proposal id is an incrementing integer. We represent
@@ -509,6 +763,7 @@ fn map_publish_edits_proposals_created(
                     content_uri: proposed_edit.edits_content_uri,
                     plugin_address: format_hex(&log.address()),
                     dao_address: format_hex(&proposed_edit.dao),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -522,9 +777,11 @@ fn map_publish_edits_proposals_created(
 #[substreams::handlers::map]
 fn map_add_member_proposals_created(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<AddMemberProposalsCreated, substreams::errors::Error> {
     let proposed_members: Vec<AddMemberProposalCreated> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(proposed_edit) = AddMemberProposalCreatedEvent::match_and_decode(log) {
                 return Some(AddMemberProposalCreated {
@@ -536,6 +793,7 @@ fn map_add_member_proposals_created(
                     dao_address: format_hex(&proposed_edit.dao),
                     change_type: "added".to_string(),
                     member: format_hex(&proposed_edit.member),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -549,9 +807,11 @@ fn map_add_member_proposals_created(
 #[substreams::handlers::map]
 fn map_remove_member_proposals_created(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<RemoveMemberProposalsCreated, substreams::errors::Error> {
     let proposed_members: Vec<RemoveMemberProposalCreated> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(proposed_edit) = RemoveMemberProposalCreatedEvent::match_and_decode(log) {
                 return Some(RemoveMemberProposalCreated {
@@ -563,6 +823,7 @@ fn map_remove_member_proposals_created(
                     dao_address: format_hex(&proposed_edit.dao),
                     change_type: "removed".to_string(),
                     member: format_hex(&proposed_edit.member),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -576,9 +837,11 @@ fn map_remove_member_proposals_created(
 #[substreams::handlers::map]
 fn map_add_editor_proposals_created(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<AddEditorProposalsCreated, substreams::errors::Error> {
     let proposed_editors: Vec<AddEditorProposalCreated> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(proposed_edit) = AddEditorProposalCreatedEvent::match_and_decode(log) {
                 return Some(AddEditorProposalCreated {
@@ -590,6 +853,7 @@ fn map_add_editor_proposals_created(
                     dao_address: format_hex(&proposed_edit.dao),
                     change_type: "added".to_string(),
                     editor: format_hex(&proposed_edit.editor),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -603,9 +867,11 @@ fn map_add_editor_proposals_created(
 #[substreams::handlers::map]
 fn map_remove_editor_proposals_created(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<RemoveEditorProposalsCreated, substreams::errors::Error> {
     let proposed_editors: Vec<RemoveEditorProposalCreated> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(proposed_edit) = RemoveEditorProposalCreatedEvent::match_and_decode(log) {
                 return Some(RemoveEditorProposalCreated {
@@ -617,6 +883,7 @@ fn map_remove_editor_proposals_created(
                     dao_address: format_hex(&proposed_edit.dao),
                     change_type: "removed".to_string(),
                     editor: format_hex(&proposed_edit.editor),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -630,9 +897,11 @@ fn map_remove_editor_proposals_created(
 #[substreams::handlers::map]
 fn map_add_subspace_proposals_created(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<AddSubspaceProposalsCreated, substreams::errors::Error> {
     let proposed_subspaces: Vec<AddSubspaceProposalCreated> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(proposed_edit) = AcceptSubspaceProposalCreatedEvent::match_and_decode(log) {
                 return Some(AddSubspaceProposalCreated {
@@ -644,6 +913,7 @@ fn map_add_subspace_proposals_created(
                     dao_address: format_hex(&proposed_edit.dao),
                     change_type: "added".to_string(),
                     subspace: format_hex(&proposed_edit.subspace),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -657,9 +927,11 @@ fn map_add_subspace_proposals_created(
 #[substreams::handlers::map]
 fn map_remove_subspace_proposals_created(
     block: eth::v2::Block,
+    plugins: StoreGetString,
 ) -> Result<RemoveSubspaceProposalsCreated, substreams::errors::Error> {
     let proposed_subspaces: Vec<RemoveSubspaceProposalCreated> = block
         .logs()
+        .filter(|log| is_known_plugin(&plugins, &log.address()))
         .filter_map(|log| {
             if let Some(proposed_edit) = RemoveSubspaceProposalCreatedEvent::match_and_decode(log) {
                 return Some(RemoveSubspaceProposalCreated {
@@ -671,6 +943,7 @@ fn map_remove_subspace_proposals_created(
                     dao_address: format_hex(&proposed_edit.dao),
                     change_type: "added".to_string(),
                     subspace: format_hex(&proposed_edit.subspace),
+                    context: Some(event_context(&block, &log)),
                 });
             }
 
@@ -704,6 +977,11 @@ fn geo_out(
     proposed_removed_editors: RemoveEditorProposalsCreated,
     proposed_added_subspaces: AddSubspaceProposalsCreated,
     proposed_removed_subspaces: RemoveSubspaceProposalsCreated,
+    proposals_created: ProposalsCreated,
+    voting_settings_updated: VotingSettingsUpdates,
+    member_access_settings_updated: MemberAccessSettingsUpdates,
+    dao_metadata_set: DaoMetadataSets,
+    proposals_canceled: ProposalsCanceled,
 ) -> Result<GeoOutput, substreams::errors::Error> {
     let spaces_created = spaces_created.spaces;
     let governance_plugins_created = governance_plugins_created.plugins;
@@ -720,6 +998,63 @@ fn geo_out(
     let editors_removed = editors_removed.editors;
     let personal_admin_plugins_created = personal_admin_plugins_created.plugins;
     let edit_proposals_created = edit_proposals.edits;
+    let proposed_added_members = proposed_added_members.proposed_members;
+    let proposed_removed_members = proposed_removed_members.proposed_members;
+    let proposed_added_editors = proposed_added_editors.proposed_editors;
+    let proposed_removed_editors = proposed_removed_editors.proposed_editors;
+    let proposed_added_subspaces = proposed_added_subspaces.proposed_subspaces;
+    let proposed_removed_subspaces = proposed_removed_subspaces.proposed_subspaces;
+    let proposals_created = proposals_created.proposals;
+    let voting_settings_updated = voting_settings_updated.settings;
+    let member_access_settings_updated = member_access_settings_updated.settings;
+    let dao_metadata_set = dao_metadata_set.metadata;
+    let canceled_proposals = proposals_canceled.canceled_proposals;
+
+    // The fields above group events by type. Ordered events repeat all of them
+    // in a single list so sinks can apply them in onchain order when events of
+    // different types depend on each other.
+    let mut ordered_events = Vec::new();
+    macro_rules! push_ordered {
+        ($events:expr, $variant:ident) => {
+            ordered_events.extend($events.iter().map(|event| {
+                OrderedEvent {
+                    log_index: event
+                        .context
+                        .as_ref()
+                        .map_or(0, |context| context.log_index),
+                    event: Some(ordered_event::Event::$variant(event.clone())),
+                }
+            }));
+        };
+    }
+    push_ordered!(spaces_created, SpaceCreated);
+    push_ordered!(governance_plugins_created, GovernancePluginCreated);
+    push_ordered!(initial_editors_added, InitialEditorAdded);
+    push_ordered!(votes_cast, VoteCast);
+    push_ordered!(edits_published, EditPublished);
+    push_ordered!(successor_spaces_created, SuccessorSpaceCreated);
+    push_ordered!(added_subspaces, SubspaceAdded);
+    push_ordered!(removed_subspaces, SubspaceRemoved);
+    push_ordered!(executed_proposals, ProposalExecuted);
+    push_ordered!(members_added, MemberAdded);
+    push_ordered!(editors_added, EditorAdded);
+    push_ordered!(personal_admin_plugins_created, PersonalPluginCreated);
+    push_ordered!(members_removed, MemberRemoved);
+    push_ordered!(editors_removed, EditorRemoved);
+    push_ordered!(edit_proposals_created, PublishEditProposalCreated);
+    push_ordered!(proposed_added_members, AddMemberProposalCreated);
+    push_ordered!(proposed_removed_members, RemoveMemberProposalCreated);
+    push_ordered!(proposed_added_editors, AddEditorProposalCreated);
+    push_ordered!(proposed_removed_editors, RemoveEditorProposalCreated);
+    push_ordered!(proposed_added_subspaces, AddSubspaceProposalCreated);
+    push_ordered!(proposed_removed_subspaces, RemoveSubspaceProposalCreated);
+    push_ordered!(proposals_created, ProposalCreated);
+    push_ordered!(voting_settings_updated, VotingSettingsUpdated);
+    push_ordered!(member_access_settings_updated, MemberAccessSettingsUpdated);
+    push_ordered!(dao_metadata_set, DaoMetadataSet);
+    push_ordered!(canceled_proposals, ProposalCanceled);
+    // Stable, so events without a context keep their grouped order
+    ordered_events.sort_by_key(|event| event.log_index);
 
     Ok(GeoOutput {
         spaces_created,
@@ -737,11 +1072,57 @@ fn geo_out(
         members_removed,
         editors_removed,
         edits: edit_proposals_created,
-        proposed_added_members: proposed_added_members.proposed_members,
-        proposed_removed_members: proposed_removed_members.proposed_members,
-        proposed_added_editors: proposed_added_editors.proposed_editors,
-        proposed_removed_editors: proposed_removed_editors.proposed_editors,
-        proposed_added_subspaces: proposed_added_subspaces.proposed_subspaces,
-        proposed_removed_subspaces: proposed_removed_subspaces.proposed_subspaces,
+        proposed_added_members,
+        proposed_removed_members,
+        proposed_added_editors,
+        proposed_removed_editors,
+        proposed_added_subspaces,
+        proposed_removed_subspaces,
+        proposals_created,
+        voting_settings_updated,
+        member_access_settings_updated,
+        dao_metadata_set,
+        canceled_proposals,
+        ordered_events,
     })
 }
+
+/// Editors and members added by `plugin` in the transaction that emitted `log`. Plugins add
+/// their initial editors and members while they're installed, so the setup event's transaction
+/// has all of them.
+fn initial_editors_and_members(log: &LogView, plugin: &[u8]) -> (Vec<String>, Vec<String>) {
+    let mut editors = Vec::new();
+    let mut members = Vec::new();
+
+    for tx_log in log
+        .receipt
+        .logs()
+        .filter(|tx_log| tx_log.address() == plugin)
+    {
+        if let Some(editors_added) = EditorsAddedEvent::match_and_decode(&tx_log) {
+            editors.extend(
+                editors_added
+                    .editors
+                    .iter()
+                    .map(|editor| format_hex(editor)),
+            );
+        } else if let Some(editor_added) = EditorAddedEvent::match_and_decode(&tx_log) {
+            editors.push(format_hex(&editor_added.editor));
+        } else if let Some(member_added) = MemberAddedEvent::match_and_decode(&tx_log) {
+            members.push(format_hex(&member_added.member));
+        }
+    }
+
+    // An editor can be added by both the batch and the singular event
+    editors.sort();
+    editors.dedup();
+    members.sort();
+    members.dedup();
+
+    (editors, members)
+}
+
+/// Whether `address` is one of the plugins recorded by `store_plugin_addresses`.
+fn is_known_plugin(plugins: &StoreGetString, address: &[u8]) -> bool {
+    plugins.has_last(format_hex(address))
+}
@@ -1,4 +1,20 @@
 // @generated
+/// Where an event was emitted onchain. Every event carries its context so
+/// sinks can dedupe events and audit them against the chain.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EventContext {
+    #[prost(string, tag="1")]
+    pub tx_hash: ::prost::alloc::string::String,
+    /// Index of the log in the block, not in the transaction.
+    #[prost(uint64, tag="2")]
+    pub log_index: u64,
+    #[prost(uint64, tag="3")]
+    pub block_number: u64,
+    /// Block timestamp in seconds.
+    #[prost(uint64, tag="4")]
+    pub block_timestamp: u64,
+}
 /// *
 /// The new DAO-based contracts allow forking of spaces into successor spaces. This is so
 /// users can create new spaces whose data is derived from another space.
@@ -14,6 +30,8 @@ pub struct SuccessorSpaceCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="3")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="4")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -35,6 +53,8 @@ pub struct GeoSpaceCreated {
     pub dao_address: ::prost::alloc::string::String,
     #[prost(string, tag="2")]
     pub space_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="3")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -62,6 +82,15 @@ pub struct GeoGovernancePluginCreated {
     pub main_voting_address: ::prost::alloc::string::String,
     #[prost(string, tag="3")]
     pub member_access_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="4")]
+    pub context: ::core::option::Option<EventContext>,
+    /// Editors and members the main voting plugin was installed with, read from
+    /// the events it emitted in the setup transaction. Sinks can create the space
+    /// with its initial editors without relying on the separate editor events.
+    #[prost(string, repeated, tag="5")]
+    pub initial_editors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag="6")]
+    pub initial_members: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -78,6 +107,15 @@ pub struct GeoPersonalSpaceAdminPluginCreated {
     pub personal_admin_address: ::prost::alloc::string::String,
     #[prost(string, tag="3")]
     pub initial_editor: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="4")]
+    pub context: ::core::option::Option<EventContext>,
+    /// Editors and members the personal admin plugin was installed with, read
+    /// from the events it emitted in the setup transaction. Always includes the
+    /// initial editor.
+    #[prost(string, repeated, tag="5")]
+    pub initial_editors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag="6")]
+    pub initial_members: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -113,6 +151,8 @@ pub struct InitialEditorAdded {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="3")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="4")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -120,6 +160,33 @@ pub struct InitialEditorsAdded {
     #[prost(message, repeated, tag="1")]
     pub editors: ::prost::alloc::vec::Vec<InitialEditorAdded>,
 }
+/// Proposals created by any of our voting or admin plugins. Every plugin
+/// emits the same `ProposalCreated` event, regardless of the proposal's
+/// actions, so this is the generic record of a proposal and its metadata.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProposalCreated {
+    #[prost(string, tag="1")]
+    pub proposal_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub start_time: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub end_time: ::prost::alloc::string::String,
+    #[prost(string, tag="5")]
+    pub metadata_uri: ::prost::alloc::string::String,
+    #[prost(string, tag="6")]
+    pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="7")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProposalsCreated {
+    #[prost(message, repeated, tag="1")]
+    pub proposals: ::prost::alloc::vec::Vec<ProposalCreated>,
+}
 /// Executed proposals have been approved and executed onchain in a DAO-based
 /// space's main voting plugin. The DAO itself also emits the executed event,
 /// but the ABI/interface is different. We really only care about the one
@@ -131,6 +198,8 @@ pub struct ProposalExecuted {
     pub proposal_id: ::prost::alloc::string::String,
     #[prost(string, tag="2")]
     pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="3")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -138,6 +207,26 @@ pub struct ProposalsExecuted {
     #[prost(message, repeated, tag="1")]
     pub executed_proposals: ::prost::alloc::vec::Vec<ProposalExecuted>,
 }
+/// Canceled proposals were withdrawn before they could be executed. Only the
+/// main voting plugin can cancel proposals.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProposalCanceled {
+    #[prost(string, tag="1")]
+    pub proposal_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="3")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProposalsCanceled {
+    #[prost(message, repeated, tag="1")]
+    pub canceled_proposals: ::prost::alloc::vec::Vec<ProposalCanceled>,
+    #[prost(message, repeated, tag="27")]
+    pub ordered_events: ::prost::alloc::vec::Vec<OrderedEvent>,
+}
 /// *
 /// Processed Proposals represent content that has been approved by a DAO
 /// and executed onchain.
@@ -154,6 +243,8 @@ pub struct EditPublished {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="3")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="4")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -177,6 +268,8 @@ pub struct SubspaceAdded {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag="4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -195,6 +288,8 @@ pub struct SubspaceRemoved {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag="4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -219,6 +314,13 @@ pub struct VoteCast {
     pub vote_option: u64,
     #[prost(string, tag="4")]
     pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
+    /// Voting power of the voter at the proposal's snapshot block, as a decimal
+    /// uint256. The event doesn't carry the plugin's total voting power, which
+    /// is the number of editors at the snapshot block.
+    #[prost(string, tag="6")]
+    pub voting_power: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -237,6 +339,8 @@ pub struct MemberAdded {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag="4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -255,6 +359,8 @@ pub struct MemberRemoved {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag="4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -273,6 +379,8 @@ pub struct EditorAdded {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag="4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -291,6 +399,8 @@ pub struct EditorRemoved {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag="4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -315,6 +425,8 @@ pub struct PublishEditProposalCreated {
     pub dao_address: ::prost::alloc::string::String,
     #[prost(string, tag="7")]
     pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="8")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -341,6 +453,8 @@ pub struct AddMemberProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="9")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -367,6 +481,8 @@ pub struct RemoveMemberProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="9")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -393,6 +509,8 @@ pub struct AddEditorProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="9")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -419,6 +537,8 @@ pub struct RemoveEditorProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="9")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -445,6 +565,8 @@ pub struct AddSubspaceProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="9")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -471,6 +593,8 @@ pub struct RemoveSubspaceProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag="8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="9")]
+    pub context: ::core::option::Option<EventContext>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -478,6 +602,142 @@ pub struct RemoveSubspaceProposalsCreated {
     #[prost(message, repeated, tag="1")]
     pub proposed_subspaces: ::prost::alloc::vec::Vec<RemoveSubspaceProposalCreated>,
 }
+/// Voting settings of a main voting plugin. Emitted when the plugin is
+/// initialized and whenever an executed proposal changes them, so proposals
+/// can be checked against the settings in effect when they were created.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VotingSettingsUpdated {
+    #[prost(string, tag="1")]
+    pub plugin_address: ::prost::alloc::string::String,
+    #[prost(uint64, tag="2")]
+    pub voting_mode: u64,
+    /// Share of votes required to pass, in parts per million.
+    #[prost(uint64, tag="3")]
+    pub support_threshold: u64,
+    /// Voting duration of new proposals in seconds.
+    #[prost(uint64, tag="4")]
+    pub duration: u64,
+    #[prost(message, optional, tag="5")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VotingSettingsUpdates {
+    #[prost(message, repeated, tag="1")]
+    pub settings: ::prost::alloc::vec::Vec<VotingSettingsUpdated>,
+}
+/// Settings of a member access plugin, which only configure how long
+/// membership proposals stay open.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MemberAccessSettingsUpdated {
+    #[prost(string, tag="1")]
+    pub plugin_address: ::prost::alloc::string::String,
+    /// Duration of new membership proposals in seconds.
+    #[prost(uint64, tag="2")]
+    pub proposal_duration: u64,
+    #[prost(message, optional, tag="3")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MemberAccessSettingsUpdates {
+    #[prost(message, repeated, tag="1")]
+    pub settings: ::prost::alloc::vec::Vec<MemberAccessSettingsUpdated>,
+}
+/// Metadata set on a Geo DAO. Aragon DAOs store the URI of a JSON document
+/// with the DAO's name and description, set on creation and whenever an
+/// executed proposal changes it.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DaoMetadataSet {
+    #[prost(string, tag="1")]
+    pub dao_address: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub metadata_uri: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="3")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DaoMetadataSets {
+    #[prost(message, repeated, tag="1")]
+    pub metadata: ::prost::alloc::vec::Vec<DaoMetadataSet>,
+}
+/// One of the block's events, tagged with its position in the block.
+/// GeoOutput groups events by type, which loses the order between events of
+/// different types, like an editor being added and then voting in the same
+/// block. Ordered events repeat every event of the other fields, sorted by
+/// log index.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderedEvent {
+    /// Index of the event's log in the block.
+    #[prost(uint64, tag="1")]
+    pub log_index: u64,
+    #[prost(oneof="ordered_event::Event", tags="2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27")]
+    pub event: ::core::option::Option<ordered_event::Event>,
+}
+/// Nested message and enum types in `OrderedEvent`.
+pub mod ordered_event {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag="2")]
+        SpaceCreated(super::GeoSpaceCreated),
+        #[prost(message, tag="3")]
+        GovernancePluginCreated(super::GeoGovernancePluginCreated),
+        #[prost(message, tag="4")]
+        InitialEditorAdded(super::InitialEditorAdded),
+        #[prost(message, tag="5")]
+        VoteCast(super::VoteCast),
+        #[prost(message, tag="6")]
+        EditPublished(super::EditPublished),
+        #[prost(message, tag="7")]
+        SuccessorSpaceCreated(super::SuccessorSpaceCreated),
+        #[prost(message, tag="8")]
+        SubspaceAdded(super::SubspaceAdded),
+        #[prost(message, tag="9")]
+        SubspaceRemoved(super::SubspaceRemoved),
+        #[prost(message, tag="10")]
+        ProposalExecuted(super::ProposalExecuted),
+        #[prost(message, tag="11")]
+        MemberAdded(super::MemberAdded),
+        #[prost(message, tag="12")]
+        EditorAdded(super::EditorAdded),
+        #[prost(message, tag="13")]
+        PersonalPluginCreated(super::GeoPersonalSpaceAdminPluginCreated),
+        #[prost(message, tag="14")]
+        MemberRemoved(super::MemberRemoved),
+        #[prost(message, tag="15")]
+        EditorRemoved(super::EditorRemoved),
+        #[prost(message, tag="16")]
+        PublishEditProposalCreated(super::PublishEditProposalCreated),
+        #[prost(message, tag="17")]
+        AddMemberProposalCreated(super::AddMemberProposalCreated),
+        #[prost(message, tag="18")]
+        RemoveMemberProposalCreated(super::RemoveMemberProposalCreated),
+        #[prost(message, tag="19")]
+        AddEditorProposalCreated(super::AddEditorProposalCreated),
+        #[prost(message, tag="20")]
+        RemoveEditorProposalCreated(super::RemoveEditorProposalCreated),
+        #[prost(message, tag="21")]
+        AddSubspaceProposalCreated(super::AddSubspaceProposalCreated),
+        #[prost(message, tag="22")]
+        RemoveSubspaceProposalCreated(super::RemoveSubspaceProposalCreated),
+        #[prost(message, tag="23")]
+        ProposalCreated(super::ProposalCreated),
+        #[prost(message, tag="24")]
+        VotingSettingsUpdated(super::VotingSettingsUpdated),
+        #[prost(message, tag="25")]
+        MemberAccessSettingsUpdated(super::MemberAccessSettingsUpdated),
+        #[prost(message, tag="26")]
+        DaoMetadataSet(super::DaoMetadataSet),
+        #[prost(message, tag="27")]
+        ProposalCanceled(super::ProposalCanceled),
+    }
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GeoOutput {
@@ -523,5 +783,15 @@ pub struct GeoOutput {
     pub proposed_added_subspaces: ::prost::alloc::vec::Vec<AddSubspaceProposalCreated>,
     #[prost(message, repeated, tag="21")]
     pub proposed_removed_subspaces: ::prost::alloc::vec::Vec<RemoveSubspaceProposalCreated>,
+    #[prost(message, repeated, tag="22")]
+    pub proposals_created: ::prost::alloc::vec::Vec<ProposalCreated>,
+    #[prost(message, repeated, tag="23")]
+    pub voting_settings_updated: ::prost::alloc::vec::Vec<VotingSettingsUpdated>,
+    #[prost(message, repeated, tag="24")]
+    pub member_access_settings_updated: ::prost::alloc::vec::Vec<MemberAccessSettingsUpdated>,
+    #[prost(message, repeated, tag="25")]
+    pub dao_metadata_set: ::prost::alloc::vec::Vec<DaoMetadataSet>,
+    #[prost(message, repeated, tag="26")]
+    pub canceled_proposals: ::prost::alloc::vec::Vec<ProposalCanceled>,
 }
 // @@protoc_insertion_point(module)
@@ -0,0 +1,163 @@
+//! Benchmarks for applying a block's edits through the block handler.
+//!
+//! Writes go to [`ShadowStorage`], the in-memory storage used for dry runs,
+//! so the numbers cover op mapping, validation and the properties cache
+//! rather than database round trips.
+//!
+//! Run with: cargo bench -p indexer --bench block_handler
+
+use std::hint::black_box;
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use indexer::block_handler::root_handler;
+use indexer::cache::properties_cache::PropertiesCache;
+use indexer::cache::PreprocessedEdit;
+use indexer::storage::shadow::ShadowStorage;
+use indexer::validators::EditAuthorValidator;
+use indexer::{AddedMember, KgData};
+use stream::utils::BlockMetadata;
+use uuid::Uuid;
+use wire::pb::grc20::op::Payload;
+use wire::pb::grc20::{DataType, Edit, Entity, Op, Property, Relation, Value};
+
+const DAO_ADDRESS: &str = "0x1234567890123456789012345678901234567890";
+const EDITOR_ADDRESS: &str = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+
+fn id() -> Vec<u8> {
+    Uuid::new_v4().as_bytes().to_vec()
+}
+
+/// An edit creating a text and a relation property, then `entity_count`
+/// entities with a value and a relation each.
+fn make_edit(entity_count: usize) -> Edit {
+    let name_property = id();
+    let relation_type = id();
+
+    let mut ops = vec![
+        Op {
+            payload: Some(Payload::CreateProperty(Property {
+                id: name_property.clone(),
+                data_type: DataType::String as i32,
+            })),
+        },
+        Op {
+            payload: Some(Payload::CreateProperty(Property {
+                id: relation_type.clone(),
+                data_type: DataType::Relation as i32,
+            })),
+        },
+    ];
+
+    for n in 0..entity_count {
+        let entity = id();
+
+        ops.push(Op {
+            payload: Some(Payload::UpdateEntity(Entity {
+                id: entity.clone(),
+                values: vec![Value {
+                    property: name_property.clone(),
+                    value: format!("Entity {n}"),
+                    options: None,
+                }],
+            })),
+        });
+        ops.push(Op {
+            payload: Some(Payload::CreateRelation(Relation {
+                id: id(),
+                r#type: relation_type.clone(),
+                from_entity: entity,
+                to_entity: id(),
+                entity: id(),
+                ..Default::default()
+            })),
+        });
+    }
+
+    Edit {
+        id: id(),
+        name: "Benchmark edit".to_string(),
+        ops,
+        authors: vec![EDITOR_ADDRESS.as_bytes().to_vec()],
+        language: None,
+    }
+}
+
+/// A block with `edit_count` edits of `entity_count` entities each, whose
+/// author is added as an editor in the same block.
+fn make_block(edit_count: usize, entity_count: usize) -> KgData {
+    KgData {
+        block: BlockMetadata {
+            cursor: "cursor_1".to_string(),
+            block_number: 1,
+            timestamp: "1700000000".to_string(),
+            ..Default::default()
+        },
+        edits: (0..edit_count)
+            .map(|n| PreprocessedEdit {
+                cid: format!("ipfs://bafkreibench{n:06}"),
+                edit: Some(make_edit(entity_count)),
+                is_errored: false,
+                space_id: Uuid::new_v4(),
+            })
+            .collect(),
+        added_editors: vec![AddedMember {
+            dao_address: DAO_ADDRESS.to_string(),
+            editor_address: EDITOR_ADDRESS.to_string(),
+            context: Default::default(),
+        }],
+        removed_editors: vec![],
+        added_members: vec![],
+        removed_members: vec![],
+        added_subspaces: vec![],
+        removed_subspaces: vec![],
+        proposals: vec![],
+        proposal_status_changes: vec![],
+        votes: vec![],
+        governance_settings: vec![],
+        dao_metadata: vec![],
+        successor_spaces: vec![],
+        spaces: vec![],
+    }
+}
+
+fn bench_apply_block(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let storage = Arc::new(ShadowStorage::new());
+    let properties_cache = Arc::new(PropertiesCache::new());
+    let author_validator = Arc::new(EditAuthorValidator::default());
+
+    let mut group = c.benchmark_group("apply_block");
+
+    for (edit_count, entity_count) in [(1, 10), (10, 10), (10, 100)] {
+        let block = make_block(edit_count, entity_count);
+        let ops = edit_count * (2 + 2 * entity_count);
+
+        group.throughput(Throughput::Elements(ops as u64));
+        group.bench_with_input(
+            BenchmarkId::new(
+                format!("{edit_count}_edits"),
+                format!("{entity_count}_entities"),
+            ),
+            &block,
+            |b, block| {
+                b.to_async(&runtime).iter(|| async {
+                    root_handler::run(
+                        black_box(block),
+                        &block.block,
+                        &storage,
+                        &properties_cache,
+                        &author_validator,
+                    )
+                    .await
+                    .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_apply_block);
+criterion_main!(benches);
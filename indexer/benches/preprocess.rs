@@ -0,0 +1,167 @@
+//! Benchmarks for block preprocessing: decoding `GeoOutput` and reading each
+//! published edit from the IPFS cache.
+//!
+//! The cache is an in-memory stub, so the numbers cover decoding, task
+//! spawning and bookkeeping rather than database round trips.
+//!
+//! Run with: cargo bench -p indexer --bench preprocess
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use indexer::cache::{CacheBackend, CacheError, DaoMetadata, PreprocessedEdit};
+use indexer::preprocess::preprocess_block_scoped_data;
+use prost::Message;
+use stream::pb::sf::substreams::rpc::v2::{BlockScopedData, MapModuleOutput};
+use stream::pb::sf::substreams::v1::Clock;
+use uuid::Uuid;
+use wire::pb::chain::{EditPublished, EditorAdded, GeoOutput, GeoSpaceCreated};
+use wire::pb::grc20::Edit;
+
+/// Cache stub that serves every edit from memory.
+struct InMemoryCache {
+    edits: HashMap<String, PreprocessedEdit>,
+}
+
+#[async_trait]
+impl CacheBackend for InMemoryCache {
+    async fn get(&self, uri: &String) -> Result<PreprocessedEdit, CacheError> {
+        self.edits.get(uri).cloned().ok_or(CacheError::NotFound)
+    }
+
+    async fn get_dao_metadata(&self, _uri: &String) -> Result<Option<DaoMetadata>, CacheError> {
+        Err(CacheError::NotFound)
+    }
+}
+
+fn content_uri(n: usize) -> String {
+    format!("ipfs://bafkreibench{n:06}")
+}
+
+fn dao_address(n: usize) -> String {
+    format!("0x{n:040x}")
+}
+
+/// A block publishing `edit_count` edits, spread over 10 spaces that are
+/// created in the same block.
+fn make_block(edit_count: usize) -> BlockScopedData {
+    let geo = GeoOutput {
+        edits_published: (0..edit_count)
+            .map(|n| EditPublished {
+                content_uri: content_uri(n),
+                plugin_address: dao_address(1000),
+                dao_address: dao_address(n % 10),
+                context: None,
+            })
+            .collect(),
+        spaces_created: (0..10)
+            .map(|n| GeoSpaceCreated {
+                dao_address: dao_address(n),
+                space_address: dao_address(100 + n),
+                context: None,
+            })
+            .collect(),
+        editors_added: (0..10)
+            .map(|n| EditorAdded {
+                dao_address: dao_address(n),
+                editor_address: dao_address(200 + n),
+                main_voting_plugin_address: dao_address(1000),
+                change_type: "0".to_string(),
+                context: None,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    BlockScopedData {
+        output: Some(MapModuleOutput {
+            name: "geo_out".to_string(),
+            map_output: Some(prost_types::Any {
+                type_url: "type.googleapis.com/chain.GeoOutput".to_string(),
+                value: geo.encode_to_vec(),
+            }),
+            debug_info: None,
+        }),
+        clock: Some(Clock {
+            id: "block_1".to_string(),
+            number: 1,
+            timestamp: Some(prost_types::Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 0,
+            }),
+        }),
+        cursor: "cursor_1".to_string(),
+        ..Default::default()
+    }
+}
+
+fn make_cache(edit_count: usize) -> Arc<InMemoryCache> {
+    let edits = (0..edit_count)
+        .map(|n| {
+            let edit = PreprocessedEdit {
+                cid: content_uri(n),
+                edit: Some(Edit {
+                    id: Uuid::new_v4().as_bytes().to_vec(),
+                    name: format!("Edit {n}"),
+                    ops: vec![],
+                    authors: vec![],
+                    language: None,
+                }),
+                is_errored: false,
+                space_id: Uuid::new_v4(),
+            };
+            (content_uri(n), edit)
+        })
+        .collect();
+
+    Arc::new(InMemoryCache { edits })
+}
+
+fn bench_decode_geo_output(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_geo_output");
+
+    for edit_count in [1, 10, 100] {
+        let block = make_block(edit_count);
+        let bytes = block.output.as_ref().unwrap().map_output.as_ref().unwrap();
+
+        group.throughput(Throughput::Bytes(bytes.value.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(edit_count),
+            &bytes.value,
+            |b, value| b.iter(|| GeoOutput::decode(black_box(value.as_slice())).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_preprocess_block(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("preprocess_block");
+
+    for edit_count in [1, 10, 100] {
+        let block = make_block(edit_count);
+        let cache = make_cache(edit_count);
+
+        group.throughput(Throughput::Elements(edit_count as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(edit_count),
+            &block,
+            |b, block| {
+                b.to_async(&runtime).iter(|| async {
+                    preprocess_block_scoped_data(black_box(block), &cache)
+                        .await
+                        .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode_geo_output, bench_preprocess_block);
+criterion_main!(benches);
@@ -0,0 +1,136 @@
+//! Log ingestion into Axiom, enabled by the `axiom` feature.
+//!
+//! Events are buffered in memory and sent in batches to the dataset named by
+//! `AXIOM_DATASET` when `AXIOM_TOKEN` is set.
+
+use std::env;
+use std::sync::Mutex;
+
+use axiom_rs::Client as AxiomClient;
+use serde_json::{json, Value};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Dataset logs are sent to, from `AXIOM_DATASET`.
+pub fn dataset() -> String {
+    env::var("AXIOM_DATASET").unwrap_or_else(|_| "gaia.indexer".to_string())
+}
+
+// Simple in-memory buffer for Axiom logs to batch them
+static AXIOM_LOG_BUFFER: Mutex<Vec<Value>> = Mutex::new(Vec::new());
+
+pub struct AxiomLayer {
+    dataset: String,
+}
+
+impl AxiomLayer {
+    pub fn new(dataset: String) -> Self {
+        Self { dataset }
+    }
+}
+
+impl<S> Layer<S> for AxiomLayer
+where
+    S: tracing::Subscriber + for<'lookup> tracing_subscriber::registry::LookupSpan<'lookup>,
+{
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = JsonVisitor::new();
+        event.record(&mut visitor);
+
+        // Check if current event has block_number in its fields
+        let mut block_number = None;
+        if let Some(Value::Number(bn)) = visitor.fields.get("block_number") {
+            block_number = bn.as_u64();
+        } else if let Some(Value::Number(bn)) = visitor.fields.get("block") {
+            block_number = bn.as_u64();
+        }
+
+        let mut log_entry = json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "level": event.metadata().level().to_string(),
+            "target": event.metadata().target(),
+            "service": "gaia.indexer",
+            "fields": visitor.fields
+        });
+
+        // Add block_number to top level if found
+        if let Some(bn) = block_number {
+            log_entry["block_number"] = json!(bn);
+        }
+
+        if let Ok(mut buffer) = AXIOM_LOG_BUFFER.lock() {
+            buffer.push(log_entry);
+
+            // Flush buffer when it gets large (simple batching)
+            if buffer.len() >= 10 {
+                let logs = buffer.drain(..).collect::<Vec<_>>();
+                let dataset = self.dataset.clone();
+
+                tokio::spawn(async move {
+                    if let Ok(client) = AxiomClient::new() {
+                        if let Err(e) = client.ingest(&dataset, logs).await {
+                            eprintln!("Failed to send logs to Axiom: {}", e);
+                        }
+                    }
+                });
+            }
+        }
+    }
+}
+
+struct JsonVisitor {
+    fields: serde_json::Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn new() -> Self {
+        Self {
+            fields: serde_json::Map::new(),
+        }
+    }
+}
+
+impl tracing::field::Visit for JsonVisitor {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.fields.insert(
+            field.name().to_string(),
+            Value::String(format!("{:?}", value)),
+        );
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.fields
+            .insert(field.name().to_string(), Value::String(value.to_string()));
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.fields
+            .insert(field.name().to_string(), Value::Number(value.into()));
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.fields
+            .insert(field.name().to_string(), Value::Number(value.into()));
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.fields
+            .insert(field.name().to_string(), Value::Bool(value));
+    }
+}
+
+/// Send any buffered logs that haven't reached the batch size yet.
+pub async fn flush_logs() {
+    let axiom_dataset = dataset();
+
+    if let Ok(mut buffer) = AXIOM_LOG_BUFFER.lock() {
+        if !buffer.is_empty() {
+            let logs = buffer.drain(..).collect::<Vec<_>>();
+            if let Ok(client) = AxiomClient::new() {
+                if let Err(e) = client.ingest(&axiom_dataset, logs).await {
+                    eprintln!("Failed to flush logs to Axiom: {}", e);
+                }
+            }
+        }
+    }
+}
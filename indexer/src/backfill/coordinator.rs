@@ -0,0 +1,620 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use stream::{pb::sf::substreams::rpc::v2::BlockScopedData, PreprocessedSink, Sink};
+use tracing::{info, warn};
+
+use crate::{error::IndexingError, KgData};
+
+use super::{
+    plan_shards, BackfillConfig, BackfillError, BackfillStore, Lease, ShardProgress, ShardState,
+    ShardStatus, StagedBlock, MERGE_PAGE_SIZE,
+};
+
+/// How often to check on shards streamed by other instances.
+const SHARD_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Sink streaming a single leased shard into the staging table.
+pub struct ShardWorker<S> {
+    store: Arc<S>,
+    lease: Lease,
+    lease_duration: Duration,
+    progress: Mutex<Option<ShardProgress>>,
+    last_renewal: Mutex<Instant>,
+}
+
+impl<S> ShardWorker<S>
+where
+    S: BackfillStore + 'static,
+{
+    pub fn new(store: Arc<S>, lease: Lease, lease_duration: Duration) -> Self {
+        ShardWorker {
+            store,
+            progress: Mutex::new(lease.progress.clone()),
+            lease,
+            lease_duration,
+            last_renewal: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn lease(&self) -> &Lease {
+        &self.lease
+    }
+
+    /// The last block streamed for the shard so far.
+    pub fn progress(&self) -> Option<ShardProgress> {
+        self.progress.lock().unwrap().clone()
+    }
+
+    /// Stages the block if it has any output.
+    pub async fn stage(&self, block_data: &BlockScopedData) -> Result<(), BackfillError> {
+        match StagedBlock::from_block_scoped_data(block_data) {
+            Some(block) => {
+                self.store
+                    .stage_block(&self.lease.backfill_id, &block)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Records that the shard was streamed up to `block`, renewing the lease
+    /// a few times per lease duration rather than on every block.
+    pub async fn advance(&self, cursor: String, block: u64) -> Result<(), BackfillError> {
+        let progress = ShardProgress {
+            cursor,
+            block_number: block,
+        };
+        *self.progress.lock().unwrap() = Some(progress.clone());
+
+        let renewal_due = self.last_renewal.lock().unwrap().elapsed() >= self.lease_duration / 3;
+
+        if renewal_due {
+            self.store
+                .renew(&self.lease, &progress, self.lease_duration)
+                .await?;
+            *self.last_renewal.lock().unwrap() = Instant::now();
+        }
+
+        Ok(())
+    }
+
+    /// Marks the shard as done once its stream is consumed.
+    pub async fn complete(&self) -> Result<(), BackfillError> {
+        self.store
+            .complete(&self.lease, self.progress().as_ref())
+            .await
+    }
+}
+
+impl<S> Sink<()> for ShardWorker<S>
+where
+    S: BackfillStore + 'static,
+{
+    type Error = BackfillError;
+
+    async fn process_block_scoped_data(
+        &self,
+        block_data: &BlockScopedData,
+    ) -> Result<(), Self::Error> {
+        self.stage(block_data).await
+    }
+
+    async fn persist_cursor(&self, cursor: String, block: u64) -> Result<(), Self::Error> {
+        self.advance(cursor, block).await
+    }
+
+    /// Resumes from where the shard's previous owner stopped, if anywhere.
+    async fn load_persisted_cursor(&self) -> Result<Option<String>, Self::Error> {
+        Ok(self.progress().map(|progress| progress.cursor))
+    }
+}
+
+/// Runs this instance's part of a backfill, see the [module docs](super).
+pub struct BackfillCoordinator<S> {
+    store: Arc<S>,
+    config: BackfillConfig,
+}
+
+impl<S> BackfillCoordinator<S>
+where
+    S: BackfillStore + 'static,
+{
+    pub fn new(store: S, config: BackfillConfig) -> Self {
+        BackfillCoordinator {
+            store: Arc::new(store),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &BackfillConfig {
+        &self.config
+    }
+
+    /// Records the backfill's shards. Safe to repeat on restarts.
+    pub async fn plan(&self) -> Result<(), BackfillError> {
+        let shards = plan_shards(
+            self.config.start_block,
+            self.config.end_block,
+            self.config.shard_size,
+        );
+
+        info!(
+            backfill_id = %self.config.backfill_id,
+            start_block = self.config.start_block,
+            end_block = self.config.end_block,
+            shard_count = shards.len(),
+            "Planning backfill"
+        );
+
+        self.store.plan(&self.config.backfill_id, &shards).await
+    }
+
+    /// Leases the next shard to stream, if any is left.
+    pub async fn claim(&self) -> Result<Option<ShardWorker<S>>, BackfillError> {
+        let lease = self
+            .store
+            .claim(
+                &self.config.backfill_id,
+                &self.config.instance_id,
+                self.config.lease_duration,
+            )
+            .await?;
+
+        Ok(lease
+            .map(|lease| ShardWorker::new(self.store.clone(), lease, self.config.lease_duration)))
+    }
+
+    /// Planned shards that aren't fully streamed yet.
+    pub async fn remaining_shards(&self) -> Result<usize, BackfillError> {
+        let shards = self.store.shards(&self.config.backfill_id).await?;
+
+        Ok(shards
+            .iter()
+            .filter(|state| matches!(state.status, ShardStatus::Pending | ShardStatus::Claimed))
+            .count())
+    }
+
+    /// Claims and streams shards until none are left to claim. Returns the
+    /// number of shards this instance completed.
+    pub async fn stream_shards(
+        &self,
+        endpoint_url: &str,
+        spkg_file: &str,
+        module_name: &str,
+    ) -> Result<usize, BackfillError> {
+        let mut completed = 0;
+
+        while let Some(worker) = self.claim().await? {
+            let shard = worker.lease().shard;
+
+            info!(
+                backfill_id = %self.config.backfill_id,
+                start_block = shard.start_block,
+                end_block = shard.end_block,
+                resume_block = worker.progress().map(|progress| progress.block_number),
+                "Streaming backfill shard"
+            );
+
+            if let Err(error) = worker
+                .run(
+                    endpoint_url,
+                    spkg_file,
+                    module_name,
+                    shard.start_block as i64,
+                    shard.end_block,
+                )
+                .await
+            {
+                // Another instance took the shard over after our lease
+                // expired, so move on to the next one.
+                if let Some(BackfillError::LeaseLost { .. }) = error.downcast_ref::<BackfillError>()
+                {
+                    warn!(start_block = shard.start_block, "Lost backfill shard lease");
+                    continue;
+                }
+
+                return Err(BackfillError::Stream(error.to_string()));
+            }
+
+            match worker.complete().await {
+                Ok(()) => completed += 1,
+                Err(BackfillError::LeaseLost { .. }) => {
+                    warn!(start_block = shard.start_block, "Lost backfill shard lease");
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(completed)
+    }
+
+    /// Streams shards until every shard is done, picking up the shards of
+    /// instances whose leases expired.
+    pub async fn stream_until_done(
+        &self,
+        endpoint_url: &str,
+        spkg_file: &str,
+        module_name: &str,
+    ) -> Result<(), BackfillError> {
+        loop {
+            self.stream_shards(endpoint_url, spkg_file, module_name)
+                .await?;
+
+            let remaining = self.remaining_shards().await?;
+            if remaining == 0 {
+                return Ok(());
+            }
+
+            info!(
+                backfill_id = %self.config.backfill_id,
+                remaining_shards = remaining,
+                "Waiting for other instances to finish their backfill shards"
+            );
+            tokio::time::sleep(SHARD_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Waits for the coordinator to plan the backfill, then streams shards
+    /// until none are left to claim.
+    pub async fn run_worker(
+        &self,
+        endpoint_url: &str,
+        spkg_file: &str,
+        module_name: &str,
+    ) -> Result<usize, BackfillError> {
+        while self
+            .store
+            .shards(&self.config.backfill_id)
+            .await?
+            .is_empty()
+        {
+            info!(
+                backfill_id = %self.config.backfill_id,
+                "Waiting for the backfill to be planned"
+            );
+            tokio::time::sleep(SHARD_POLL_INTERVAL).await;
+        }
+
+        self.stream_shards(endpoint_url, spkg_file, module_name)
+            .await
+    }
+
+    /// Applies the staged blocks of every shard in block order through
+    /// `sink`, then persists the sink's cursor at the end of the backfill.
+    ///
+    /// Progress is recorded after every block, so an interrupted merge
+    /// resumes after the last block it applied.
+    pub async fn merge<K>(&self, sink: &K) -> Result<(), IndexingError>
+    where
+        K: PreprocessedSink<KgData, Error = IndexingError>,
+    {
+        let shards = self.store.shards(&self.config.backfill_id).await?;
+
+        let remaining = shards
+            .iter()
+            .filter(|state| matches!(state.status, ShardStatus::Pending | ShardStatus::Claimed))
+            .count();
+        if remaining > 0 {
+            return Err(BackfillError::IncompleteShards { remaining }.into());
+        }
+
+        // The shards must cover the backfilled range exactly once before
+        // anything is written.
+        let mut next_block = self.config.start_block;
+        for state in &shards {
+            let start_block = state.shard.start_block;
+
+            if start_block > next_block {
+                return Err(BackfillError::Gap {
+                    from: next_block,
+                    to: start_block,
+                }
+                .into());
+            }
+            if start_block < next_block {
+                return Err(BackfillError::Overlap { start_block }.into());
+            }
+
+            next_block = state.shard.end_block;
+        }
+        if next_block < self.config.end_block {
+            return Err(BackfillError::Gap {
+                from: next_block,
+                to: self.config.end_block,
+            }
+            .into());
+        }
+
+        for state in shards
+            .iter()
+            .filter(|state| state.status != ShardStatus::Merged)
+        {
+            self.merge_shard(state, sink).await?;
+        }
+
+        self.store
+            .clear_staged_blocks(&self.config.backfill_id)
+            .await?;
+
+        info!(
+            backfill_id = %self.config.backfill_id,
+            end_block = self.config.end_block,
+            "Backfill merged"
+        );
+
+        Ok(())
+    }
+
+    async fn merge_shard<K>(&self, state: &ShardState, sink: &K) -> Result<(), IndexingError>
+    where
+        K: PreprocessedSink<KgData, Error = IndexingError>,
+    {
+        let backfill_id = &self.config.backfill_id;
+        let shard = state.shard;
+        let mut from_block = state
+            .merged_block
+            .map_or(shard.start_block, |block| block + 1);
+
+        info!(
+            backfill_id = %backfill_id,
+            start_block = shard.start_block,
+            end_block = shard.end_block,
+            resume_block = from_block,
+            "Merging backfill shard"
+        );
+
+        loop {
+            let blocks = self
+                .store
+                .staged_blocks(backfill_id, from_block, shard.end_block, MERGE_PAGE_SIZE)
+                .await?;
+
+            if blocks.is_empty() {
+                break;
+            }
+
+            for block in &blocks {
+                let block_data = block.to_block_scoped_data();
+                let kg_data = sink.preprocess_block_scoped_data(&block_data).await?;
+
+                sink.process_block_scoped_data(&block_data, kg_data).await?;
+                sink.persist_cursor(block.cursor.clone(), block.block_number)
+                    .await?;
+                self.store
+                    .record_merged_block(backfill_id, &shard, block.block_number)
+                    .await?;
+
+                from_block = block.block_number + 1;
+            }
+        }
+
+        // Empty blocks aren't staged, so the shard's last cursor is usually
+        // past its last staged block.
+        if let Some(progress) = &state.progress {
+            sink.persist_cursor(progress.cursor.clone(), progress.block_number)
+                .await?;
+        }
+
+        self.store.finish_merge(backfill_id, &shard).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use wire::pb::chain::{EditorAdded, GeoOutput};
+
+    use super::*;
+    use crate::{
+        backfill::{memory::MemoryBackfillStore, BackfillRole, Shard},
+        cache::{
+            properties_cache::PropertiesCache, CacheBackend, CacheError, DaoMetadata,
+            PreprocessedEdit,
+        },
+        dry_run::DryRunIndexer,
+        validators::EditAuthorValidator,
+    };
+
+    struct EmptyCache;
+
+    #[async_trait::async_trait]
+    impl CacheBackend for EmptyCache {
+        async fn get(&self, _uri: &String) -> Result<PreprocessedEdit, CacheError> {
+            Err(CacheError::NotFound)
+        }
+
+        async fn get_dao_metadata(&self, _uri: &String) -> Result<Option<DaoMetadata>, CacheError> {
+            Err(CacheError::NotFound)
+        }
+    }
+
+    fn config(lease_duration: Duration) -> BackfillConfig {
+        BackfillConfig {
+            backfill_id: "resync".to_string(),
+            start_block: 0,
+            end_block: 20,
+            shard_size: 10,
+            lease_duration,
+            instance_id: "instance".to_string(),
+            role: BackfillRole::Coordinator,
+        }
+    }
+
+    fn dry_run() -> DryRunIndexer<EmptyCache> {
+        DryRunIndexer::new(
+            EmptyCache,
+            PropertiesCache::new(),
+            EditAuthorValidator::default(),
+        )
+    }
+
+    /// A block adding one editor to a space.
+    fn staged_block(block_number: u64) -> StagedBlock {
+        let geo = GeoOutput {
+            editors_added: vec![EditorAdded {
+                dao_address: "0x1234567890123456789012345678901234567890".to_string(),
+                editor_address: format!("0x{:040x}", block_number),
+                main_voting_plugin_address: "0x0000000000000000000000000000000000000001"
+                    .to_string(),
+                change_type: "0".to_string(),
+                context: None,
+            }],
+            ..Default::default()
+        };
+
+        StagedBlock {
+            block_number,
+            cursor: format!("cursor_{}", block_number),
+            timestamp: 1_700_000_000,
+            output: geo.encode_to_vec(),
+            block_hash: format!("0xhash{}", block_number),
+            final_block_height: block_number,
+        }
+    }
+
+    /// Streams every shard as a worker would, staging `blocks`.
+    async fn stream(coordinator: &BackfillCoordinator<MemoryBackfillStore>, blocks: &[u64]) {
+        while let Some(worker) = coordinator.claim().await.unwrap() {
+            let shard = worker.lease().shard;
+
+            for block_number in (shard.start_block..shard.end_block).filter(|b| blocks.contains(b))
+            {
+                worker
+                    .stage(&staged_block(block_number).to_block_scoped_data())
+                    .await
+                    .unwrap();
+            }
+
+            let last_block = shard.end_block - 1;
+            worker
+                .advance(format!("cursor_{}", last_block), last_block)
+                .await
+                .unwrap();
+            worker.complete().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_merge_applies_staged_blocks_in_order() {
+        let coordinator =
+            BackfillCoordinator::new(MemoryBackfillStore::new(), config(Duration::from_secs(60)));
+        coordinator.plan().await.unwrap();
+        stream(&coordinator, &[3, 12, 15]).await;
+
+        let indexer = dry_run();
+        coordinator.merge(&indexer).await.unwrap();
+
+        let report = indexer.report();
+        assert_eq!(report.first_block, Some(3));
+        assert_eq!(report.last_block, Some(15));
+        assert_eq!(report.blocks_processed, 3);
+        assert_eq!(report.changes.editors_added, 3);
+
+        let shards = coordinator.store.shards("resync").await.unwrap();
+        assert!(shards
+            .iter()
+            .all(|state| state.status == ShardStatus::Merged));
+        assert!(coordinator
+            .store
+            .staged_blocks("resync", 0, 20, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_resumes_after_merged_block() {
+        let coordinator =
+            BackfillCoordinator::new(MemoryBackfillStore::new(), config(Duration::from_secs(60)));
+        coordinator.plan().await.unwrap();
+        stream(&coordinator, &[3, 5, 12]).await;
+
+        let first_shard = Shard {
+            start_block: 0,
+            end_block: 10,
+        };
+        coordinator
+            .store
+            .record_merged_block("resync", &first_shard, 3)
+            .await
+            .unwrap();
+
+        let indexer = dry_run();
+        coordinator.merge(&indexer).await.unwrap();
+
+        let report = indexer.report();
+        assert_eq!(report.first_block, Some(5));
+        assert_eq!(report.blocks_processed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_merge_waits_for_every_shard() {
+        let coordinator =
+            BackfillCoordinator::new(MemoryBackfillStore::new(), config(Duration::from_secs(60)));
+        coordinator.plan().await.unwrap();
+
+        let worker = coordinator.claim().await.unwrap().unwrap();
+        worker.complete().await.unwrap();
+
+        let indexer = dry_run();
+        let result = coordinator.merge(&indexer).await;
+
+        assert!(matches!(
+            result,
+            Err(IndexingError::BackfillError(
+                BackfillError::IncompleteShards { remaining: 1 }
+            ))
+        ));
+        assert_eq!(indexer.report().blocks_processed, 0);
+    }
+
+    #[tokio::test]
+    async fn test_merge_rejects_gaps() {
+        let coordinator =
+            BackfillCoordinator::new(MemoryBackfillStore::new(), config(Duration::from_secs(60)));
+        coordinator
+            .store
+            .plan(
+                "resync",
+                &[Shard {
+                    start_block: 0,
+                    end_block: 10,
+                }],
+            )
+            .await
+            .unwrap();
+        stream(&coordinator, &[3]).await;
+
+        let result = coordinator.merge(&dry_run()).await;
+
+        assert!(matches!(
+            result,
+            Err(IndexingError::BackfillError(BackfillError::Gap {
+                from: 10,
+                to: 20
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_worker_renews_lease_with_progress() {
+        let coordinator =
+            BackfillCoordinator::new(MemoryBackfillStore::new(), config(Duration::ZERO));
+        coordinator.plan().await.unwrap();
+
+        let worker = coordinator.claim().await.unwrap().unwrap();
+        worker.advance("cursor_4".to_string(), 4).await.unwrap();
+
+        // The lease expired immediately, so another instance takes the shard
+        // over from the renewed progress.
+        let takeover = coordinator.claim().await.unwrap().unwrap();
+        assert_eq!(takeover.lease().shard, worker.lease().shard);
+        assert_eq!(
+            takeover.load_persisted_cursor().await.unwrap(),
+            Some("cursor_4".to_string())
+        );
+    }
+}
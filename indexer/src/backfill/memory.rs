@@ -0,0 +1,344 @@
+//! In-memory backfill store used in tests.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use super::{
+    BackfillError, BackfillStore, Lease, Shard, ShardProgress, ShardState, ShardStatus, StagedBlock,
+};
+
+#[derive(Debug)]
+struct MemoryShard {
+    state: ShardState,
+    owner: Option<String>,
+    lease_expires_at: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryBackfill {
+    /// Shards by start block.
+    shards: BTreeMap<u64, MemoryShard>,
+    staged_blocks: BTreeMap<u64, StagedBlock>,
+}
+
+/// [`BackfillStore`] keeping shards and staged blocks in memory.
+#[derive(Debug, Default)]
+pub struct MemoryBackfillStore {
+    backfills: Mutex<HashMap<String, MemoryBackfill>>,
+}
+
+impl MemoryBackfillStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn leased_shard<'a>(
+        backfills: &'a mut HashMap<String, MemoryBackfill>,
+        lease: &Lease,
+    ) -> Result<&'a mut MemoryShard, BackfillError> {
+        backfills
+            .get_mut(&lease.backfill_id)
+            .and_then(|backfill| backfill.shards.get_mut(&lease.shard.start_block))
+            .filter(|shard| {
+                shard.state.status == ShardStatus::Claimed
+                    && shard.owner.as_deref() == Some(lease.owner.as_str())
+            })
+            .ok_or(BackfillError::LeaseLost {
+                start_block: lease.shard.start_block,
+            })
+    }
+}
+
+#[async_trait]
+impl BackfillStore for MemoryBackfillStore {
+    async fn plan(&self, backfill_id: &str, shards: &[Shard]) -> Result<(), BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+        let backfill = backfills.entry(backfill_id.to_string()).or_default();
+
+        for shard in shards {
+            backfill
+                .shards
+                .entry(shard.start_block)
+                .or_insert_with(|| MemoryShard {
+                    state: ShardState {
+                        shard: *shard,
+                        status: ShardStatus::Pending,
+                        progress: None,
+                        merged_block: None,
+                    },
+                    owner: None,
+                    lease_expires_at: None,
+                });
+        }
+
+        Ok(())
+    }
+
+    async fn claim(
+        &self,
+        backfill_id: &str,
+        owner: &str,
+        lease_duration: Duration,
+    ) -> Result<Option<Lease>, BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+        let Some(backfill) = backfills.get_mut(backfill_id) else {
+            return Ok(None);
+        };
+
+        let now = Instant::now();
+        let claimable = backfill
+            .shards
+            .values_mut()
+            .find(|shard| match shard.state.status {
+                ShardStatus::Pending => true,
+                ShardStatus::Claimed => shard
+                    .lease_expires_at
+                    .is_some_and(|expires_at| expires_at <= now),
+                ShardStatus::Done | ShardStatus::Merged => false,
+            });
+
+        Ok(claimable.map(|shard| {
+            shard.state.status = ShardStatus::Claimed;
+            shard.owner = Some(owner.to_string());
+            shard.lease_expires_at = Some(now + lease_duration);
+
+            Lease {
+                backfill_id: backfill_id.to_string(),
+                shard: shard.state.shard,
+                owner: owner.to_string(),
+                progress: shard.state.progress.clone(),
+            }
+        }))
+    }
+
+    async fn renew(
+        &self,
+        lease: &Lease,
+        progress: &ShardProgress,
+        lease_duration: Duration,
+    ) -> Result<(), BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+        let shard = Self::leased_shard(&mut backfills, lease)?;
+
+        shard.state.progress = Some(progress.clone());
+        shard.lease_expires_at = Some(Instant::now() + lease_duration);
+
+        Ok(())
+    }
+
+    async fn complete(
+        &self,
+        lease: &Lease,
+        progress: Option<&ShardProgress>,
+    ) -> Result<(), BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+        let shard = Self::leased_shard(&mut backfills, lease)?;
+
+        shard.state.status = ShardStatus::Done;
+        if let Some(progress) = progress {
+            shard.state.progress = Some(progress.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn shards(&self, backfill_id: &str) -> Result<Vec<ShardState>, BackfillError> {
+        let backfills = self.backfills.lock().unwrap();
+
+        Ok(backfills
+            .get(backfill_id)
+            .map(|backfill| {
+                backfill
+                    .shards
+                    .values()
+                    .map(|shard| shard.state.clone())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn stage_block(
+        &self,
+        backfill_id: &str,
+        block: &StagedBlock,
+    ) -> Result<(), BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+
+        backfills
+            .entry(backfill_id.to_string())
+            .or_default()
+            .staged_blocks
+            .insert(block.block_number, block.clone());
+
+        Ok(())
+    }
+
+    async fn staged_blocks(
+        &self,
+        backfill_id: &str,
+        from_block: u64,
+        to_block: u64,
+        limit: usize,
+    ) -> Result<Vec<StagedBlock>, BackfillError> {
+        let backfills = self.backfills.lock().unwrap();
+
+        Ok(backfills
+            .get(backfill_id)
+            .map(|backfill| {
+                backfill
+                    .staged_blocks
+                    .range(from_block..to_block)
+                    .take(limit)
+                    .map(|(_, block)| block.clone())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn record_merged_block(
+        &self,
+        backfill_id: &str,
+        shard: &Shard,
+        block_number: u64,
+    ) -> Result<(), BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+
+        if let Some(shard) = backfills
+            .get_mut(backfill_id)
+            .and_then(|backfill| backfill.shards.get_mut(&shard.start_block))
+        {
+            shard.state.merged_block = Some(block_number);
+        }
+
+        Ok(())
+    }
+
+    async fn finish_merge(&self, backfill_id: &str, shard: &Shard) -> Result<(), BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+
+        if let Some(shard) = backfills
+            .get_mut(backfill_id)
+            .and_then(|backfill| backfill.shards.get_mut(&shard.start_block))
+        {
+            shard.state.status = ShardStatus::Merged;
+        }
+
+        Ok(())
+    }
+
+    async fn clear_staged_blocks(&self, backfill_id: &str) -> Result<(), BackfillError> {
+        let mut backfills = self.backfills.lock().unwrap();
+
+        if let Some(backfill) = backfills.get_mut(backfill_id) {
+            backfill.staged_blocks.clear();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backfill::plan_shards;
+
+    const LEASE: Duration = Duration::from_secs(60);
+
+    fn progress(block_number: u64) -> ShardProgress {
+        ShardProgress {
+            cursor: format!("cursor_{}", block_number),
+            block_number,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_claims_disjoint_shards() {
+        let store = MemoryBackfillStore::new();
+        store.plan("resync", &plan_shards(0, 20, 10)).await.unwrap();
+
+        let first = store.claim("resync", "a", LEASE).await.unwrap().unwrap();
+        let second = store.claim("resync", "b", LEASE).await.unwrap().unwrap();
+
+        assert_eq!(first.shard.start_block, 0);
+        assert_eq!(second.shard.start_block, 10);
+        assert_eq!(store.claim("resync", "c", LEASE).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_replanning_keeps_shard_state() {
+        let store = MemoryBackfillStore::new();
+        let shards = plan_shards(0, 20, 10);
+        store.plan("resync", &shards).await.unwrap();
+
+        let lease = store.claim("resync", "a", LEASE).await.unwrap().unwrap();
+        store.complete(&lease, Some(&progress(9))).await.unwrap();
+        store.plan("resync", &shards).await.unwrap();
+
+        let states = store.shards("resync").await.unwrap();
+        assert_eq!(states[0].status, ShardStatus::Done);
+        assert_eq!(states[1].status, ShardStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_resumes_from_progress() {
+        let store = MemoryBackfillStore::new();
+        store.plan("resync", &plan_shards(0, 10, 10)).await.unwrap();
+
+        let lease = store
+            .claim("resync", "a", Duration::ZERO)
+            .await
+            .unwrap()
+            .unwrap();
+        store
+            .renew(&lease, &progress(4), Duration::ZERO)
+            .await
+            .unwrap();
+
+        let reclaimed = store.claim("resync", "b", LEASE).await.unwrap().unwrap();
+
+        assert_eq!(reclaimed.shard, lease.shard);
+        assert_eq!(reclaimed.progress, Some(progress(4)));
+        assert!(matches!(
+            store.renew(&lease, &progress(5), LEASE).await,
+            Err(BackfillError::LeaseLost { start_block: 0 })
+        ));
+        assert!(matches!(
+            store.complete(&lease, None).await,
+            Err(BackfillError::LeaseLost { start_block: 0 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_staged_blocks_are_ordered_and_bounded() {
+        let store = MemoryBackfillStore::new();
+
+        for block_number in [12, 3, 7] {
+            store
+                .stage_block(
+                    "resync",
+                    &StagedBlock {
+                        block_number,
+                        cursor: format!("cursor_{}", block_number),
+                        timestamp: 0,
+                        output: vec![1],
+                        block_hash: String::new(),
+                        final_block_height: 0,
+                    },
+                )
+                .await
+                .unwrap();
+        }
+
+        let blocks = store.staged_blocks("resync", 0, 10, 10).await.unwrap();
+        let numbers: Vec<u64> = blocks.iter().map(|block| block.block_number).collect();
+
+        assert_eq!(numbers, vec![3, 7]);
+        assert_eq!(
+            store.staged_blocks("resync", 0, 20, 1).await.unwrap().len(),
+            1
+        );
+    }
+}
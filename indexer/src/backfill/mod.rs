@@ -0,0 +1,467 @@
+//! Sharded backfills of the knowledge graph's history.
+//!
+//! A full resync streams every block since the indexer's start block through
+//! a single substreams connection. A backfill splits that history into [`Shard`]s that
+//! several indexer instances stream at the same time:
+//!
+//! 1. The coordinator plans the shards of `[start_block, end_block)` into the
+//!    `backfill_shards` table (see [`plan_shards`])
+//! 2. Every instance claims one shard at a time with a lease stored next to
+//!    it, streams the shard and stages the map output of each non-empty block
+//!    in `backfill_blocks`. Leases are renewed as the shard's cursor advances,
+//!    so a crashed instance's shard is picked up by another one once its lease
+//!    expires, resuming from the shard's last cursor
+//! 3. Once every shard is done, the coordinator merges the staged blocks in
+//!    block order through the live indexer's sink and persists its cursor at
+//!    `end_block`, so the indexer switches to live mode where the backfill
+//!    stopped
+//!
+//! Handlers are replayed in order during the merge rather than run inside
+//! each shard, since their writes depend on earlier blocks (property data
+//! types, space editors, deleted values). What runs in parallel is streaming
+//! the history from substreams, which dominates a resync because most
+//! historical blocks are empty.
+
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use stream::pb::sf::substreams::{
+    rpc::v2::{BlockScopedData, MapModuleOutput},
+    v1::Clock,
+};
+use thiserror::Error;
+
+use crate::storage::StorageError;
+
+mod coordinator;
+pub mod memory;
+pub mod postgres;
+
+pub use coordinator::{BackfillCoordinator, ShardWorker};
+
+/// Blocks per shard when BACKFILL_SHARD_SIZE isn't set.
+pub const DEFAULT_SHARD_SIZE: u64 = 250_000;
+
+/// How long a claimed shard stays leased without progress when
+/// BACKFILL_LEASE_SECONDS isn't set.
+pub const DEFAULT_LEASE_DURATION: Duration = Duration::from_secs(300);
+
+/// Staged blocks read per query while merging.
+pub const MERGE_PAGE_SIZE: usize = 500;
+
+#[derive(Error, Debug)]
+pub enum BackfillError {
+    #[error("Backfill storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    #[error("Lease on shard {start_block} was lost to another instance")]
+    LeaseLost { start_block: u64 },
+
+    #[error("Backfill has {remaining} shards left to stream")]
+    IncompleteShards { remaining: usize },
+
+    #[error("Backfill shards don't cover blocks {from} to {to}")]
+    Gap { from: u64, to: u64 },
+
+    #[error("Backfill shard {start_block} overlaps the previous shard")]
+    Overlap { start_block: u64 },
+
+    #[error("Backfill stream error: {0}")]
+    Stream(String),
+}
+
+impl From<sqlx::Error> for BackfillError {
+    fn from(error: sqlx::Error) -> Self {
+        BackfillError::Storage(error.into())
+    }
+}
+
+/// Whether this instance plans and merges the backfill or only streams shards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackfillRole {
+    /// Plans the shards, streams them, merges them and then runs live.
+    Coordinator,
+    /// Streams shards until none are left and exits.
+    Worker,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackfillConfig {
+    /// Identifies the backfill's shards and staged blocks, so a new resync
+    /// doesn't pick up the leftovers of a previous one.
+    pub backfill_id: String,
+    pub start_block: u64,
+    /// Exclusive end of the backfilled range.
+    pub end_block: u64,
+    pub shard_size: u64,
+    pub lease_duration: Duration,
+    /// Owner recorded on this instance's leases.
+    pub instance_id: String,
+    pub role: BackfillRole,
+}
+
+impl BackfillConfig {
+    /// Reads the backfill configuration, or `None` if BACKFILL_ID isn't set.
+    ///
+    /// - BACKFILL_ID: enables the backfill, shared by all of its instances
+    /// - BACKFILL_END_BLOCK: exclusive end of the range, required
+    /// - BACKFILL_START_BLOCK: defaults to `default_start_block`
+    /// - BACKFILL_SHARD_SIZE: blocks per shard, defaults to [`DEFAULT_SHARD_SIZE`]
+    /// - BACKFILL_LEASE_SECONDS: defaults to [`DEFAULT_LEASE_DURATION`]
+    /// - BACKFILL_WORKER: set to `true` on every instance but the coordinator
+    /// - BACKFILL_INSTANCE_ID: defaults to HOSTNAME, or a random ID
+    pub fn from_env(default_start_block: u64) -> Option<Self> {
+        let backfill_id = env::var("BACKFILL_ID").ok()?;
+
+        let end_block = env::var("BACKFILL_END_BLOCK")
+            .expect("BACKFILL_END_BLOCK must be set for a backfill")
+            .parse::<u64>()
+            .expect("BACKFILL_END_BLOCK must be a block number");
+
+        let start_block = env::var("BACKFILL_START_BLOCK")
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .expect("BACKFILL_START_BLOCK must be a block number")
+            })
+            .unwrap_or(default_start_block);
+
+        let shard_size = env::var("BACKFILL_SHARD_SIZE")
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .expect("BACKFILL_SHARD_SIZE must be a number of blocks")
+            })
+            .unwrap_or(DEFAULT_SHARD_SIZE);
+
+        let lease_duration = env::var("BACKFILL_LEASE_SECONDS")
+            .map(|value| {
+                Duration::from_secs(
+                    value
+                        .parse::<u64>()
+                        .expect("BACKFILL_LEASE_SECONDS must be a number of seconds"),
+                )
+            })
+            .unwrap_or(DEFAULT_LEASE_DURATION);
+
+        let role = match env::var("BACKFILL_WORKER").as_deref() {
+            Ok("true") => BackfillRole::Worker,
+            _ => BackfillRole::Coordinator,
+        };
+
+        let instance_id = env::var("BACKFILL_INSTANCE_ID")
+            .or_else(|_| env::var("HOSTNAME"))
+            .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string());
+
+        Some(BackfillConfig {
+            backfill_id,
+            start_block,
+            end_block,
+            shard_size,
+            lease_duration,
+            instance_id,
+            role,
+        })
+    }
+}
+
+/// A block range streamed by a single instance, `end_block` exclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shard {
+    pub start_block: u64,
+    pub end_block: u64,
+}
+
+/// Splits `[start_block, end_block)` into consecutive shards of at most
+/// `shard_size` blocks.
+pub fn plan_shards(start_block: u64, end_block: u64, shard_size: u64) -> Vec<Shard> {
+    let shard_size = shard_size.max(1);
+
+    (start_block..end_block)
+        .step_by(shard_size as usize)
+        .map(|shard_start| Shard {
+            start_block: shard_start,
+            end_block: shard_start.saturating_add(shard_size).min(end_block),
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShardStatus {
+    /// Not claimed yet.
+    Pending,
+    /// Leased to an instance. Another instance can claim it once the lease
+    /// expires.
+    Claimed,
+    /// Every block of the shard is staged.
+    Done,
+    /// Every staged block of the shard was applied by the merge.
+    Merged,
+}
+
+impl ShardStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShardStatus::Pending => "pending",
+            ShardStatus::Claimed => "claimed",
+            ShardStatus::Done => "done",
+            ShardStatus::Merged => "merged",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "claimed" => ShardStatus::Claimed,
+            "done" => ShardStatus::Done,
+            "merged" => ShardStatus::Merged,
+            _ => ShardStatus::Pending,
+        }
+    }
+}
+
+/// The last block streamed for a shard and the cursor to resume after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShardProgress {
+    pub cursor: String,
+    pub block_number: u64,
+}
+
+/// A shard claimed by this instance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lease {
+    pub backfill_id: String,
+    pub shard: Shard,
+    pub owner: String,
+    /// Where a previous owner of the shard stopped, if any.
+    pub progress: Option<ShardProgress>,
+}
+
+/// A planned shard as stored in the lease table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShardState {
+    pub shard: Shard,
+    pub status: ShardStatus,
+    pub progress: Option<ShardProgress>,
+    /// Last staged block applied by the merge.
+    pub merged_block: Option<u64>,
+}
+
+/// The map output of a block, staged until the merge applies it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StagedBlock {
+    pub block_number: u64,
+    pub cursor: String,
+    /// Block timestamp in seconds.
+    pub timestamp: i64,
+    pub output: Vec<u8>,
+    /// Hash of the block (the clock ID).
+    pub block_hash: String,
+    /// Final block height of the stream when the block was delivered, so
+    /// merged blocks keep their finality.
+    pub final_block_height: u64,
+}
+
+impl StagedBlock {
+    /// The block to stage, or `None` if its module output is empty.
+    pub fn from_block_scoped_data(block_data: &BlockScopedData) -> Option<Self> {
+        let output = block_data.output.as_ref()?.map_output.as_ref()?;
+        let clock = block_data.clock.as_ref()?;
+
+        if output.value.is_empty() {
+            return None;
+        }
+
+        Some(StagedBlock {
+            block_number: clock.number,
+            cursor: block_data.cursor.clone(),
+            timestamp: clock
+                .timestamp
+                .as_ref()
+                .map_or(0, |timestamp| timestamp.seconds),
+            output: output.value.clone(),
+            block_hash: clock.id.clone(),
+            final_block_height: block_data.final_block_height,
+        })
+    }
+
+    /// Rebuilds the block as streamed, for the live indexer's sink.
+    pub fn to_block_scoped_data(&self) -> BlockScopedData {
+        BlockScopedData {
+            output: Some(MapModuleOutput {
+                map_output: Some(prost_types::Any {
+                    type_url: String::new(),
+                    value: self.output.clone(),
+                }),
+                ..Default::default()
+            }),
+            clock: Some(Clock {
+                id: self.block_hash.clone(),
+                number: self.block_number,
+                timestamp: Some(prost_types::Timestamp {
+                    seconds: self.timestamp,
+                    nanos: 0,
+                }),
+            }),
+            cursor: self.cursor.clone(),
+            final_block_height: self.final_block_height,
+            ..Default::default()
+        }
+    }
+}
+
+/// Storage for a backfill's shard leases and staged blocks.
+#[async_trait]
+pub trait BackfillStore: Send + Sync {
+    /// Records the shards of a backfill. Shards that were already planned
+    /// keep their state, so every restart of the coordinator can plan again.
+    async fn plan(&self, backfill_id: &str, shards: &[Shard]) -> Result<(), BackfillError>;
+
+    /// Leases the first pending shard, or the first shard whose lease
+    /// expired, to `owner`.
+    async fn claim(
+        &self,
+        backfill_id: &str,
+        owner: &str,
+        lease_duration: Duration,
+    ) -> Result<Option<Lease>, BackfillError>;
+
+    /// Extends a lease and records the shard's progress. Fails with
+    /// [`BackfillError::LeaseLost`] if the shard was claimed by another owner.
+    async fn renew(
+        &self,
+        lease: &Lease,
+        progress: &ShardProgress,
+        lease_duration: Duration,
+    ) -> Result<(), BackfillError>;
+
+    /// Marks a leased shard as done.
+    async fn complete(
+        &self,
+        lease: &Lease,
+        progress: Option<&ShardProgress>,
+    ) -> Result<(), BackfillError>;
+
+    /// Every shard of a backfill, by start block.
+    async fn shards(&self, backfill_id: &str) -> Result<Vec<ShardState>, BackfillError>;
+
+    /// Stages a block, replacing it if a previous owner of its shard already
+    /// staged it.
+    async fn stage_block(
+        &self,
+        backfill_id: &str,
+        block: &StagedBlock,
+    ) -> Result<(), BackfillError>;
+
+    /// Up to `limit` staged blocks in `[from_block, to_block)`, by block number.
+    async fn staged_blocks(
+        &self,
+        backfill_id: &str,
+        from_block: u64,
+        to_block: u64,
+        limit: usize,
+    ) -> Result<Vec<StagedBlock>, BackfillError>;
+
+    /// Records the last staged block of a shard applied by the merge.
+    async fn record_merged_block(
+        &self,
+        backfill_id: &str,
+        shard: &Shard,
+        block_number: u64,
+    ) -> Result<(), BackfillError>;
+
+    /// Marks a shard as merged.
+    async fn finish_merge(&self, backfill_id: &str, shard: &Shard) -> Result<(), BackfillError>;
+
+    /// Drops the staged blocks of a merged backfill.
+    async fn clear_staged_blocks(&self, backfill_id: &str) -> Result<(), BackfillError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_shards_covers_range() {
+        let shards = plan_shards(100, 350, 100);
+
+        assert_eq!(
+            shards,
+            vec![
+                Shard {
+                    start_block: 100,
+                    end_block: 200
+                },
+                Shard {
+                    start_block: 200,
+                    end_block: 300
+                },
+                Shard {
+                    start_block: 300,
+                    end_block: 350
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_shards_empty_range() {
+        assert!(plan_shards(100, 100, 10).is_empty());
+        assert!(plan_shards(100, 50, 10).is_empty());
+    }
+
+    #[test]
+    fn test_plan_shards_zero_size() {
+        assert_eq!(plan_shards(0, 3, 0).len(), 3);
+    }
+
+    #[test]
+    fn test_staged_block_round_trips() {
+        let block = StagedBlock {
+            block_number: 42,
+            cursor: "cursor_42".to_string(),
+            timestamp: 1_700_000_000,
+            output: vec![1, 2, 3],
+            block_hash: "0xhash42".to_string(),
+            final_block_height: 42,
+        };
+
+        assert_eq!(
+            StagedBlock::from_block_scoped_data(&block.to_block_scoped_data()),
+            Some(block)
+        );
+    }
+
+    #[test]
+    fn test_empty_blocks_are_not_staged() {
+        let block = StagedBlock {
+            block_number: 42,
+            cursor: "cursor_42".to_string(),
+            timestamp: 0,
+            output: vec![],
+            block_hash: String::new(),
+            final_block_height: 0,
+        };
+
+        assert_eq!(
+            StagedBlock::from_block_scoped_data(&block.to_block_scoped_data()),
+            None
+        );
+        assert_eq!(
+            StagedBlock::from_block_scoped_data(&BlockScopedData::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shard_status_round_trips() {
+        for status in [
+            ShardStatus::Pending,
+            ShardStatus::Claimed,
+            ShardStatus::Done,
+            ShardStatus::Merged,
+        ] {
+            assert_eq!(ShardStatus::parse(status.as_str()), status);
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Backfill leases and staged blocks in the `backfill_shards` and
+//! `backfill_blocks` tables.
+//!
+//! Shards are claimed with `FOR UPDATE SKIP LOCKED`, so instances claiming at
+//! the same time never lease the same shard, and a lease only moves to another
+//! owner once its `lease_expires_at` has passed.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use sqlx::{Postgres, Row};
+
+use super::{
+    BackfillError, BackfillStore, Lease, Shard, ShardProgress, ShardState, ShardStatus, StagedBlock,
+};
+
+pub struct PostgresBackfillStore {
+    pool: sqlx::Pool<Postgres>,
+}
+
+impl PostgresBackfillStore {
+    /// Uses the indexer's pool, see `PostgresStorage::pool`.
+    pub fn new(pool: sqlx::Pool<Postgres>) -> Self {
+        PostgresBackfillStore { pool }
+    }
+}
+
+fn progress_from_row(row: &sqlx::postgres::PgRow) -> Result<Option<ShardProgress>, BackfillError> {
+    let cursor: Option<String> = row.try_get("cursor")?;
+    let block_number: Option<i64> = row.try_get("block_number")?;
+
+    Ok(cursor
+        .zip(block_number)
+        .map(|(cursor, block_number)| ShardProgress {
+            cursor,
+            block_number: block_number as u64,
+        }))
+}
+
+#[async_trait]
+impl BackfillStore for PostgresBackfillStore {
+    async fn plan(&self, backfill_id: &str, shards: &[Shard]) -> Result<(), BackfillError> {
+        let start_blocks: Vec<i64> = shards.iter().map(|s| s.start_block as i64).collect();
+        let end_blocks: Vec<i64> = shards.iter().map(|s| s.end_block as i64).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO backfill_shards (backfill_id, start_block, end_block)
+            SELECT $1, start_block, end_block
+            FROM UNNEST($2::bigint[], $3::bigint[]) AS shard(start_block, end_block)
+            ON CONFLICT (backfill_id, start_block) DO NOTHING
+            "#,
+        )
+        .bind(backfill_id)
+        .bind(&start_blocks)
+        .bind(&end_blocks)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn claim(
+        &self,
+        backfill_id: &str,
+        owner: &str,
+        lease_duration: Duration,
+    ) -> Result<Option<Lease>, BackfillError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE backfill_shards
+            SET status = 'claimed',
+                owner = $2,
+                lease_expires_at = now() + make_interval(secs => $3)
+            WHERE (backfill_id, start_block) = (
+                SELECT backfill_id, start_block
+                FROM backfill_shards
+                WHERE backfill_id = $1
+                  AND (status = 'pending' OR (status = 'claimed' AND lease_expires_at < now()))
+                ORDER BY start_block
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING start_block, end_block, cursor, block_number
+            "#,
+        )
+        .bind(backfill_id)
+        .bind(owner)
+        .bind(lease_duration.as_secs_f64())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|row| {
+            let start_block: i64 = row.try_get("start_block")?;
+            let end_block: i64 = row.try_get("end_block")?;
+
+            Ok(Lease {
+                backfill_id: backfill_id.to_string(),
+                shard: Shard {
+                    start_block: start_block as u64,
+                    end_block: end_block as u64,
+                },
+                owner: owner.to_string(),
+                progress: progress_from_row(&row)?,
+            })
+        })
+        .transpose()
+    }
+
+    async fn renew(
+        &self,
+        lease: &Lease,
+        progress: &ShardProgress,
+        lease_duration: Duration,
+    ) -> Result<(), BackfillError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE backfill_shards
+            SET lease_expires_at = now() + make_interval(secs => $4),
+                cursor = $5,
+                block_number = $6
+            WHERE backfill_id = $1 AND start_block = $2 AND owner = $3 AND status = 'claimed'
+            "#,
+        )
+        .bind(&lease.backfill_id)
+        .bind(lease.shard.start_block as i64)
+        .bind(&lease.owner)
+        .bind(lease_duration.as_secs_f64())
+        .bind(&progress.cursor)
+        .bind(progress.block_number as i64)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackfillError::LeaseLost {
+                start_block: lease.shard.start_block,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn complete(
+        &self,
+        lease: &Lease,
+        progress: Option<&ShardProgress>,
+    ) -> Result<(), BackfillError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE backfill_shards
+            SET status = 'done',
+                lease_expires_at = NULL,
+                cursor = COALESCE($4, cursor),
+                block_number = COALESCE($5, block_number)
+            WHERE backfill_id = $1 AND start_block = $2 AND owner = $3 AND status = 'claimed'
+            "#,
+        )
+        .bind(&lease.backfill_id)
+        .bind(lease.shard.start_block as i64)
+        .bind(&lease.owner)
+        .bind(progress.map(|progress| progress.cursor.as_str()))
+        .bind(progress.map(|progress| progress.block_number as i64))
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(BackfillError::LeaseLost {
+                start_block: lease.shard.start_block,
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn shards(&self, backfill_id: &str) -> Result<Vec<ShardState>, BackfillError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT start_block, end_block, status, cursor, block_number, merged_block
+            FROM backfill_shards
+            WHERE backfill_id = $1
+            ORDER BY start_block
+            "#,
+        )
+        .bind(backfill_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let start_block: i64 = row.try_get("start_block")?;
+                let end_block: i64 = row.try_get("end_block")?;
+                let status: String = row.try_get("status")?;
+                let merged_block: Option<i64> = row.try_get("merged_block")?;
+
+                Ok(ShardState {
+                    shard: Shard {
+                        start_block: start_block as u64,
+                        end_block: end_block as u64,
+                    },
+                    status: ShardStatus::parse(&status),
+                    progress: progress_from_row(row)?,
+                    merged_block: merged_block.map(|block| block as u64),
+                })
+            })
+            .collect()
+    }
+
+    async fn stage_block(
+        &self,
+        backfill_id: &str,
+        block: &StagedBlock,
+    ) -> Result<(), BackfillError> {
+        sqlx::query(
+            r#"
+            INSERT INTO backfill_blocks
+                (backfill_id, block_number, cursor, timestamp, output, block_hash, final_block_height)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (backfill_id, block_number) DO UPDATE
+            SET cursor = EXCLUDED.cursor,
+                timestamp = EXCLUDED.timestamp,
+                output = EXCLUDED.output,
+                block_hash = EXCLUDED.block_hash,
+                final_block_height = EXCLUDED.final_block_height
+            "#,
+        )
+        .bind(backfill_id)
+        .bind(block.block_number as i64)
+        .bind(&block.cursor)
+        .bind(block.timestamp)
+        .bind(&block.output)
+        .bind(&block.block_hash)
+        .bind(block.final_block_height as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn staged_blocks(
+        &self,
+        backfill_id: &str,
+        from_block: u64,
+        to_block: u64,
+        limit: usize,
+    ) -> Result<Vec<StagedBlock>, BackfillError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT block_number, cursor, timestamp, output, block_hash, final_block_height
+            FROM backfill_blocks
+            WHERE backfill_id = $1 AND block_number >= $2 AND block_number < $3
+            ORDER BY block_number
+            LIMIT $4
+            "#,
+        )
+        .bind(backfill_id)
+        .bind(from_block as i64)
+        .bind(to_block as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let block_number: i64 = row.try_get("block_number")?;
+                let final_block_height: i64 = row.try_get("final_block_height")?;
+
+                Ok(StagedBlock {
+                    block_number: block_number as u64,
+                    cursor: row.try_get("cursor")?,
+                    timestamp: row.try_get("timestamp")?,
+                    output: row.try_get("output")?,
+                    block_hash: row.try_get("block_hash")?,
+                    final_block_height: final_block_height as u64,
+                })
+            })
+            .collect()
+    }
+
+    async fn record_merged_block(
+        &self,
+        backfill_id: &str,
+        shard: &Shard,
+        block_number: u64,
+    ) -> Result<(), BackfillError> {
+        sqlx::query(
+            "UPDATE backfill_shards SET merged_block = $3 WHERE backfill_id = $1 AND start_block = $2",
+        )
+        .bind(backfill_id)
+        .bind(shard.start_block as i64)
+        .bind(block_number as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn finish_merge(&self, backfill_id: &str, shard: &Shard) -> Result<(), BackfillError> {
+        sqlx::query(
+            "UPDATE backfill_shards SET status = 'merged' WHERE backfill_id = $1 AND start_block = $2",
+        )
+        .bind(backfill_id)
+        .bind(shard.start_block as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn clear_staged_blocks(&self, backfill_id: &str) -> Result<(), BackfillError> {
+        sqlx::query("DELETE FROM backfill_blocks WHERE backfill_id = $1")
+            .bind(backfill_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -2,8 +2,10 @@ use std::sync::Arc;
 
 use stream::utils::BlockMetadata;
 use tracing::{debug, error, instrument, warn, Instrument};
+use uuid::Uuid;
+use wire::pb::grc20::Edit;
 
-use crate::cache::properties_cache::ImmutableCache;
+use crate::cache::properties_cache::{ImmutableCache, PropertyLifecycleCache};
 use crate::models::properties::PropertiesModel;
 use crate::models::relations::RelationsModel;
 use crate::models::{
@@ -11,6 +13,7 @@ use crate::models::{
     values::{ValueOp, ValuesModel},
 };
 use crate::storage::StorageBackend;
+use crate::validators::{AuthorCheck, EditAuthorValidator, EditorChanges};
 
 use crate::{cache::PreprocessedEdit, error::IndexingError};
 
@@ -71,19 +74,95 @@ where
     validated
 }
 
+/// Checks the edit's authors against the space's editors as of this block.
+///
+/// Returns whether the edit should be applied. Unauthorized edits are always
+/// logged and counted, but only skipped when the validator is in reject mode.
+/// If the editor set can't be read we apply the edit rather than dropping
+/// data on a transient storage error.
+#[instrument(skip_all, fields(space_id = %space_id))]
+async fn should_apply_edit<S>(
+    edit: &Edit,
+    space_id: &Uuid,
+    editor_changes: &EditorChanges,
+    storage: &Arc<S>,
+    tx: &mut S::Transaction,
+    author_validator: &EditAuthorValidator,
+) -> bool
+where
+    S: StorageBackend + Send + Sync + 'static,
+{
+    let stored_editors = match storage.get_space_editors(space_id, tx).await {
+        Ok(editors) => editors,
+        Err(error) => {
+            error!(
+                error = %error,
+                "Error reading space editors, skipping authorship check"
+            );
+            return true;
+        }
+    };
+
+    let editors = editor_changes.apply(space_id, stored_editors);
+
+    match author_validator.check(edit, &editors) {
+        AuthorCheck::Authorized => true,
+        AuthorCheck::Unauthorized { authors } => {
+            let rejected = author_validator.should_reject();
+            let metrics = author_validator.metrics();
+
+            warn!(
+                authors = ?authors,
+                rejected,
+                unauthorized_total = metrics.unauthorized(),
+                rejected_total = metrics.rejected(),
+                "Edit authors are not editors of the space"
+            );
+
+            !rejected
+        }
+    }
+}
+
+/// Warns about values written to deprecated properties.
+///
+/// Values are still written to the property they were set on; the warning
+/// points at the property that replaces it, if any, so editors can migrate.
+async fn warn_deprecated_values<C>(values: &[ValueOp], cache: &Arc<C>)
+where
+    C: PropertyLifecycleCache + Send + Sync + 'static,
+{
+    for value in values {
+        if !cache.lifecycle(&value.property_id).await.deprecated {
+            continue;
+        }
+
+        let replacement = cache.resolve_replacement(&value.property_id).await;
+
+        warn!(
+            entity_id = %value.entity_id,
+            property_id = %value.property_id,
+            replaced_by = ?(replacement != value.property_id).then_some(replacement),
+            "Value written to deprecated property"
+        );
+    }
+}
+
 #[instrument(skip_all, fields(
     edit_count = output.len(),
     block_number = block_metadata.block_number
 ))]
 pub async fn run<S, C>(
     output: &Vec<PreprocessedEdit>,
+    editor_changes: &EditorChanges,
     block_metadata: &BlockMetadata,
     storage: &Arc<S>,
     properties_cache: &Arc<C>,
+    author_validator: &Arc<EditAuthorValidator>,
 ) -> Result<(), IndexingError>
 where
     S: StorageBackend + Send + Sync + 'static,
-    C: ImmutableCache + Send + Sync + 'static,
+    C: ImmutableCache + PropertyLifecycleCache + Send + Sync + 'static,
 {
     // Ensure block context is available to all child operations
     let current_span = tracing::Span::current();
@@ -101,6 +180,8 @@ where
             let storage = storage.clone();
             let cache = properties_cache.clone();
             let block = block.clone();
+            let editor_changes = editor_changes.clone();
+            let author_validator = author_validator.clone();
 
             // Create a span for this specific edit processing with block context
             let edit_span = tracing::info_span!(
@@ -112,7 +193,7 @@ where
             );
 
             async move {
-                let tx_result = storage.get_pool().begin().await;
+                let tx_result = storage.begin().await;
                 let mut tx = match tx_result {
                     Ok(transaction) => transaction,
                     Err(error) => {
@@ -132,6 +213,21 @@ where
                     let edit = preprocessed_edit.edit.unwrap();
                     let space_id = preprocessed_edit.space_id;
 
+                    // Dropping the transaction without committing rolls it back,
+                    // so rejected edits leave no trace in storage.
+                    if !should_apply_edit(
+                        &edit,
+                        &space_id,
+                        &editor_changes,
+                        &storage,
+                        &mut tx,
+                        &author_validator,
+                    )
+                    .await
+                    {
+                        return;
+                    }
+
                     // We write properties first to update the cache with any properties
                     // created within the edit. This makes it simpler to do validation
                     // later in the edit handler as the properties cache will already
@@ -157,6 +253,22 @@ where
                         tracing::error!("Error writing properties: {}", error);
                     }
 
+                    // Lifecycle changes may target properties created earlier in
+                    // this edit, so they're applied after the properties are cached.
+                    let mut lifecycles = Vec::new();
+                    for change in PropertiesModel::map_edit_to_lifecycle_changes(&edit) {
+                        if let Some(lifecycle) = cache.apply_lifecycle(&change).await {
+                            lifecycles.push(lifecycle);
+                        }
+                    }
+
+                    if let Err(error) = storage
+                        .update_property_lifecycles(&lifecycles, &mut tx)
+                        .await
+                    {
+                        tracing::error!("Error writing property lifecycles: {}", error);
+                    }
+
                     let edit = edit.clone();
                     let block = block.clone();
                     let storage = storage.clone();
@@ -174,6 +286,8 @@ where
                     let validated_created_values =
                         validate_created_values(created_values, &cache).await;
 
+                    warn_deprecated_values(&validated_created_values, &cache).await;
+
                     let write_values_result = storage
                         .insert_values(&validated_created_values, &mut tx)
                         .await;
@@ -234,7 +348,7 @@ where
                     )
                 }
 
-                if let Err(error) = tx.commit().await {
+                if let Err(error) = storage.commit(tx).await {
                     error!(
                         cid = %preprocessed_edit.cid,
                         space_id = %preprocessed_edit.space_id,
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use stream::utils::BlockMetadata;
+
+use crate::{
+    error::IndexingError, models::governance_settings::GovernanceSettingsModel,
+    storage::StorageBackend, UpdatedGovernanceSettings,
+};
+
+pub async fn run<S>(
+    settings: &[UpdatedGovernanceSettings],
+    block_metadata: &BlockMetadata,
+    storage: &Arc<S>,
+) -> Result<(), IndexingError>
+where
+    S: StorageBackend + Send + Sync + 'static,
+{
+    if settings.is_empty() {
+        return Ok(());
+    }
+
+    let governance_settings =
+        GovernanceSettingsModel::map_governance_settings(settings, block_metadata);
+    let mut tx = storage.begin().await?;
+    storage
+        .insert_governance_settings(&governance_settings, &mut tx)
+        .await?;
+    storage.commit(tx).await?;
+
+    Ok(())
+}
@@ -24,7 +24,7 @@ where
         let added_members = added_members.clone();
         let removed_members = removed_members.clone();
         tokio::spawn(async move {
-            let mut tx = storage.get_pool().begin().await?;
+            let mut tx = storage.begin().await?;
 
             // Process added members
             if !added_members.is_empty() {
@@ -38,7 +38,7 @@ where
                 storage.remove_members(&members_to_remove, &mut tx).await?;
             }
 
-            tx.commit().await?;
+            storage.commit(tx).await?;
             Ok(())
         })
     };
@@ -48,7 +48,7 @@ where
         let added_editors = added_editors.clone();
         let removed_editors = removed_editors.clone();
         tokio::spawn(async move {
-            let mut tx = storage.get_pool().begin().await?;
+            let mut tx = storage.begin().await?;
 
             // Process added editors
             if !added_editors.is_empty() {
@@ -62,7 +62,7 @@ where
                 storage.remove_editors(&editors_to_remove, &mut tx).await?;
             }
 
-            tx.commit().await?;
+            storage.commit(tx).await?;
             Ok(())
         })
     };
@@ -1,6 +1,9 @@
 pub mod edit_handler;
+pub mod governance_settings_handler;
 pub mod membership_handler;
+pub mod proposal_handler;
 pub mod root_handler;
+pub mod space_fork_handler;
 pub mod space_handler;
 pub mod subspace_handler;
 pub mod utils;
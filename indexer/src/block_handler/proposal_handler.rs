@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use stream::utils::BlockMetadata;
+
+use crate::{
+    error::IndexingError, models::proposals::ProposalModel, storage::StorageBackend, CastVote,
+    CreatedProposal, ProposalStatusChange,
+};
+
+/// Indexes the block's created proposals, their status changes and votes,
+/// then expires proposals the block's timestamp is past the end time of. Expiry
+/// depends on time rather than events, so it runs for every block.
+pub async fn run<S>(
+    proposals: &[CreatedProposal],
+    status_changes: &[ProposalStatusChange],
+    votes: &[CastVote],
+    block_metadata: &BlockMetadata,
+    storage: &Arc<S>,
+) -> Result<(), IndexingError>
+where
+    S: StorageBackend + Send + Sync + 'static,
+{
+    let created_proposals = ProposalModel::map_created_proposals(proposals, block_metadata);
+    let statuses = ProposalModel::map_status_changes(status_changes);
+    let votes = ProposalModel::map_votes(votes, block_metadata);
+    let block_timestamp: i64 = block_metadata.timestamp.parse().unwrap_or_default();
+
+    let mut tx = storage.begin().await?;
+    storage
+        .insert_proposals(&created_proposals, &mut tx)
+        .await?;
+    storage.update_proposal_statuses(&statuses, &mut tx).await?;
+    storage.insert_proposal_votes(&votes, &mut tx).await?;
+    storage.expire_proposals(block_timestamp, &mut tx).await?;
+    storage.commit(tx).await?;
+
+    Ok(())
+}
@@ -5,12 +5,14 @@ use stream::utils::{self, BlockMetadata};
 use tracing::{info, instrument, Instrument};
 
 use crate::block_handler::{
-    edit_handler, membership_handler, space_handler, subspace_handler, utils::handle_task_result,
+    edit_handler, governance_settings_handler, membership_handler, proposal_handler,
+    space_fork_handler, space_handler, subspace_handler, utils::handle_task_result,
 };
-use crate::cache::properties_cache::ImmutableCache;
+use crate::cache::properties_cache::{ImmutableCache, PropertyLifecycleCache};
 
 use crate::error::IndexingError;
 use crate::storage::StorageBackend;
+use crate::validators::{EditAuthorValidator, EditorChanges};
 use crate::KgData;
 
 #[instrument(skip_all, fields(
@@ -20,17 +22,21 @@ use crate::KgData;
     space_count = output.spaces.len(),
     member_count = output.added_members.len(),
     editor_count = output.added_editors.len(),
-    subspace_count = output.added_subspaces.len()
+    subspace_count = output.added_subspaces.len(),
+    proposal_count = output.proposals.len(),
+    vote_count = output.votes.len(),
+    governance_settings_count = output.governance_settings.len()
 ))]
 pub async fn run<S, C>(
     output: &KgData,
     block_metadata: &BlockMetadata,
     storage: &Arc<S>,
     properties_cache: &Arc<C>,
+    author_validator: &Arc<EditAuthorValidator>,
 ) -> Result<(), IndexingError>
 where
     S: StorageBackend + Send + Sync + 'static,
-    C: ImmutableCache + Send + Sync + 'static,
+    C: ImmutableCache + PropertyLifecycleCache + Send + Sync + 'static,
 {
     // Set up block context that child spans can inherit
     let current_span = tracing::Span::current();
@@ -55,10 +61,15 @@ where
         let storage = Arc::clone(storage);
         let block_metadata = block_metadata.clone();
         let spaces = output.spaces.clone();
+        let dao_metadata = output.dao_metadata.clone();
+        let successor_spaces = output.successor_spaces.clone();
         let block_number = block_metadata.block_number;
 
         tokio::spawn(
-            async move { space_handler::run(&spaces, &block_metadata, &storage).await }
+            async move {
+                space_handler::run(&spaces, &dao_metadata, &block_metadata, &storage).await?;
+                space_fork_handler::run(&successor_spaces, &block_metadata, &storage).await
+            }
                 .instrument(tracing::info_span!("space_task", block_number = block_number))
         )
     };
@@ -67,13 +78,27 @@ where
         let storage = Arc::clone(storage);
         let properties_cache = Arc::clone(properties_cache);
         let block_metadata = block_metadata.clone();
+        let author_validator = Arc::clone(author_validator);
         let edits = output.edits.clone();
+        let editor_changes = EditorChanges::new(
+            &output.spaces,
+            &output.added_editors,
+            &output.removed_editors,
+        );
         let block_number = block_metadata.block_number;
         let edit_count = edits.len();
         
         tokio::spawn(
             async move {
-                edit_handler::run(&edits, &block_metadata, &storage, &properties_cache).await
+                edit_handler::run(
+                    &edits,
+                    &editor_changes,
+                    &block_metadata,
+                    &storage,
+                    &properties_cache,
+                    &author_validator,
+                )
+                .await
             }
             .instrument(tracing::info_span!("edit_task", block_number = block_number, edit_count = edit_count))
         )
@@ -135,13 +160,75 @@ where
         )
     };
 
-    let (space_result, edit_result, membership_result, subspace_result) =
-        tokio::join!(space_task, edit_task, membership_task, subspace_task);
+    let proposal_task = {
+        let storage = Arc::clone(storage);
+        let block_metadata = block_metadata.clone();
+        let proposals = output.proposals.clone();
+        let status_changes = output.proposal_status_changes.clone();
+        let votes = output.votes.clone();
+        let block_number = block_metadata.block_number;
+        let proposal_count = proposals.len();
+        let vote_count = votes.len();
+
+        tokio::spawn(
+            async move {
+                proposal_handler::run(
+                    &proposals,
+                    &status_changes,
+                    &votes,
+                    &block_metadata,
+                    &storage,
+                )
+                .await
+            }
+            .instrument(tracing::info_span!("proposal_task",
+                block_number = block_number,
+                proposal_count = proposal_count,
+                vote_count = vote_count
+            ))
+        )
+    };
+
+    let governance_settings_task = {
+        let storage = Arc::clone(storage);
+        let block_metadata = block_metadata.clone();
+        let settings = output.governance_settings.clone();
+        let block_number = block_metadata.block_number;
+        let settings_count = settings.len();
+
+        tokio::spawn(
+            async move {
+                governance_settings_handler::run(&settings, &block_metadata, &storage).await
+            }
+            .instrument(tracing::info_span!("governance_settings_task",
+                block_number = block_number,
+                settings_count = settings_count
+            ))
+        )
+    };
+
+    let (
+        space_result,
+        edit_result,
+        membership_result,
+        subspace_result,
+        proposal_result,
+        governance_settings_result,
+    ) = tokio::join!(
+        space_task,
+        edit_task,
+        membership_task,
+        subspace_task,
+        proposal_task,
+        governance_settings_task
+    );
 
     handle_task_result(space_result)?;
     handle_task_result(edit_result)?;
     handle_task_result(membership_result)?;
     handle_task_result(subspace_result)?;
+    handle_task_result(proposal_result)?;
+    handle_task_result(governance_settings_result)?;
 
     info!(
         block_number = block_metadata.block_number,
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use stream::utils::BlockMetadata;
+
+use crate::{
+    error::IndexingError, models::spaces::SpacesModel, storage::StorageBackend, SuccessorSpace,
+};
+
+/// Records the lineage of successor spaces. Successors are created in the
+/// same transaction as their space plugin, so this has to run after the
+/// block's spaces are inserted.
+pub async fn run<S>(
+    successor_spaces: &[SuccessorSpace],
+    block_metadata: &BlockMetadata,
+    storage: &Arc<S>,
+) -> Result<(), IndexingError>
+where
+    S: StorageBackend + Send + Sync + 'static,
+{
+    if successor_spaces.is_empty() {
+        return Ok(());
+    }
+
+    let lineage = SpacesModel::map_successor_spaces(successor_spaces, block_metadata);
+    let mut tx = storage.begin().await?;
+    storage.insert_space_lineage(&lineage, &mut tx).await?;
+    storage.commit(tx).await?;
+
+    Ok(())
+}
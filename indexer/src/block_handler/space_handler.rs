@@ -3,11 +3,15 @@ use std::sync::Arc;
 use stream::utils::BlockMetadata;
 
 use crate::{
-    error::IndexingError, models::spaces::SpacesModel, storage::StorageBackend, CreatedSpace,
+    error::IndexingError,
+    models::{membership::MembershipModel, spaces::SpacesModel},
+    storage::StorageBackend,
+    CreatedSpace, UpdatedDaoMetadata,
 };
 
 pub async fn run<S>(
     output: &Vec<CreatedSpace>,
+    dao_metadata: &[UpdatedDaoMetadata],
     _block_metadata: &BlockMetadata,
     storage: &Arc<S>,
 ) -> Result<(), IndexingError>
@@ -15,12 +19,24 @@ where
     S: StorageBackend + Send + Sync + 'static,
 {
     let created_spaces = SpacesModel::map_created_spaces(output);
-    let mut tx = storage.get_pool().begin().await?;
+    let mut tx = storage.begin().await?;
     storage
         .clone()
         .insert_spaces(&created_spaces, &mut tx)
         .await?;
-    tx.commit().await?;
+    // Spaces are created with the editors and members their plugins were
+    // installed with, so a space is never indexed without its editors
+    let initial_editors = MembershipModel::map_initial_editors(output);
+    storage.insert_editors(&initial_editors, &mut tx).await?;
+    let initial_members = MembershipModel::map_initial_members(output);
+    storage.insert_members(&initial_members, &mut tx).await?;
+    // DAOs set their metadata on creation, so the update has to follow the
+    // insert of spaces created in the same block
+    let space_metadata = SpacesModel::map_dao_metadata(dao_metadata);
+    storage
+        .update_space_metadata(&space_metadata, &mut tx)
+        .await?;
+    storage.commit(tx).await?;
 
     Ok(())
 }
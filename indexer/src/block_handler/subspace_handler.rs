@@ -22,7 +22,7 @@ where
         let added_subspaces = added_subspaces.clone();
         let removed_subspaces = removed_subspaces.clone();
         tokio::spawn(async move {
-            let mut tx = storage.get_pool().begin().await?;
+            let mut tx = storage.begin().await?;
 
             // Process added subspaces
             if !added_subspaces.is_empty() {
@@ -36,7 +36,7 @@ where
                 storage.remove_subspaces(&subspaces_to_remove, &mut tx).await?;
             }
 
-            tx.commit().await?;
+            storage.commit(tx).await?;
             Ok(())
         })
     };
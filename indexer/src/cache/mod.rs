@@ -2,6 +2,7 @@ use async_trait::async_trait;
 pub mod postgres;
 pub mod properties_cache;
 
+use serde::Deserialize;
 use thiserror::Error;
 use uuid::Uuid;
 use wire::pb::grc20::Edit;
@@ -26,7 +27,21 @@ pub struct PreprocessedEdit {
     pub space_id: Uuid,
 }
 
+/// The parts of an Aragon DAO's metadata document the indexer keeps. The
+/// document also has an avatar and links, which are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct DaoMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
 #[async_trait]
 pub trait CacheBackend: Send + Sync {
     async fn get(&self, uri: &String) -> Result<PreprocessedEdit, CacheError>;
+
+    /// Reads a DAO's metadata document. Returns `None` if the cache couldn't
+    /// fetch or parse it.
+    async fn get_dao_metadata(&self, uri: &String) -> Result<Option<DaoMetadata>, CacheError>;
 }
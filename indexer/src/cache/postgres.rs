@@ -4,7 +4,7 @@ use sqlx::{postgres::PgPoolOptions, Postgres, Row};
 use uuid::Uuid;
 use wire::pb::grc20::Edit;
 
-use super::{CacheBackend, CacheError, PreprocessedEdit};
+use super::{CacheBackend, CacheError, DaoMetadata, PreprocessedEdit};
 
 pub struct PostgresCache {
     pool: sqlx::Pool<Postgres>,
@@ -60,4 +60,19 @@ impl CacheBackend for PostgresCache {
             cid: uri.clone(),
         })
     }
+
+    async fn get_dao_metadata(&self, uri: &String) -> Result<Option<DaoMetadata>, CacheError> {
+        let row = sqlx::query("SELECT json, is_errored FROM ipfs_cache WHERE uri = $1")
+            .bind(uri)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let is_errored: bool = row.get("is_errored");
+        let json: Option<serde_json::Value> = row.get("json");
+
+        match json {
+            Some(json) if !is_errored => Ok(serde_json::from_value(json).ok()),
+            _ => Ok(None),
+        }
+    }
 }
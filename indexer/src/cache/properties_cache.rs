@@ -3,7 +3,7 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::models::properties::DataType;
+use crate::models::properties::{DataType, PropertyLifecycle, PropertyLifecycleChange};
 use crate::storage::{postgres::PostgresStorage, StorageError};
 
 pub struct PropertiesCache {
@@ -23,12 +23,17 @@ pub struct PropertiesCache {
     /// represent the data type, so it's safe to store the DataType enum
     /// directly.
     inner: Arc<RwLock<HashMap<Uuid, DataType>>>,
+    /// Lifecycle state of properties that have been deprecated or replaced.
+    /// Unlike data types this can change over time, and most properties
+    /// never have an entry.
+    lifecycles: Arc<RwLock<HashMap<Uuid, PropertyLifecycle>>>,
 }
 
 impl PropertiesCache {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(RwLock::new(HashMap::new())),
+            lifecycles: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -40,13 +45,22 @@ impl PropertiesCache {
             cache_map.insert(property.id, property.data_type);
         }
 
+        let lifecycles: HashMap<_, _> = storage
+            .get_property_lifecycles()
+            .await?
+            .into_iter()
+            .map(|lifecycle| (lifecycle.id, lifecycle))
+            .collect();
+
         tracing::info!(
-            "Initialized PropertiesCache from database with {} properties",
-            cache_map.len()
+            "Initialized PropertiesCache from database with {} properties ({} deprecated or replaced)",
+            cache_map.len(),
+            lifecycles.len()
         );
 
         Ok(Self {
             inner: Arc::new(RwLock::new(cache_map)),
+            lifecycles: Arc::new(RwLock::new(lifecycles)),
         })
     }
 }
@@ -95,10 +109,86 @@ impl ImmutableCache for PropertiesCache {
     }
 }
 
+/// Maximum number of replacement links followed when resolving a property.
+/// Guards against replacement cycles.
+const MAX_REPLACEMENT_DEPTH: usize = 16;
+
+/// Mutable lifecycle state of properties (deprecation and replacement),
+/// tracked alongside their immutable data types.
+#[async_trait::async_trait]
+pub trait PropertyLifecycleCache {
+    /// Applies a lifecycle change and returns the resulting lifecycle, or
+    /// `None` if the property doesn't exist.
+    async fn apply_lifecycle(&self, change: &PropertyLifecycleChange) -> Option<PropertyLifecycle>;
+    async fn lifecycle(&self, key: &Uuid) -> PropertyLifecycle;
+    /// Follows replacement links from `key` to the property that currently
+    /// replaces it, or `key` itself if it hasn't been replaced.
+    async fn resolve_replacement(&self, key: &Uuid) -> Uuid;
+}
+
+#[async_trait::async_trait]
+impl PropertyLifecycleCache for PropertiesCache {
+    async fn apply_lifecycle(&self, change: &PropertyLifecycleChange) -> Option<PropertyLifecycle> {
+        if !self.inner.read().await.contains_key(&change.id) {
+            tracing::warn!(
+                property_id = %change.id,
+                "[PropertiesCache][Lifecycle] Ignoring lifecycle change for unknown property"
+            );
+            return None;
+        }
+
+        let mut write = self.lifecycles.write().await;
+        let mut lifecycle = write.get(&change.id).cloned().unwrap_or(PropertyLifecycle {
+            id: change.id,
+            ..Default::default()
+        });
+        change.apply(&mut lifecycle);
+
+        if lifecycle.deprecated || lifecycle.replaced_by.is_some() {
+            write.insert(change.id, lifecycle.clone());
+        } else {
+            write.remove(&change.id);
+        }
+
+        Some(lifecycle)
+    }
+
+    async fn lifecycle(&self, key: &Uuid) -> PropertyLifecycle {
+        let read = self.lifecycles.read().await;
+
+        read.get(key).cloned().unwrap_or(PropertyLifecycle {
+            id: *key,
+            ..Default::default()
+        })
+    }
+
+    async fn resolve_replacement(&self, key: &Uuid) -> Uuid {
+        let read = self.lifecycles.read().await;
+        let mut current = *key;
+
+        for _ in 0..MAX_REPLACEMENT_DEPTH {
+            match read
+                .get(&current)
+                .and_then(|lifecycle| lifecycle.replaced_by)
+            {
+                Some(next) if next != *key => current = next,
+                _ => return current,
+            }
+        }
+
+        tracing::warn!(
+            property_id = %key,
+            "[PropertiesCache][Lifecycle] Replacement chain too long, stopping at {}",
+            current
+        );
+        current
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::properties::DataType;
+    use crate::models::properties::{DataType, PropertyLifecycle, PropertyLifecycleChange};
 
     #[tokio::test]
     async fn test_insert_and_get_property() {
@@ -269,4 +359,81 @@ mod tests {
         let prop4 = Uuid::new_v4();
         assert!(cache.get(&prop4).await.is_err());
     }
+
+    #[tokio::test]
+    async fn test_lifecycle_changes_apply_to_known_properties() {
+        let cache = PropertiesCache::new();
+        let key = Uuid::new_v4();
+
+        let deprecate = PropertyLifecycleChange {
+            id: key,
+            deprecated: Some(true),
+            replaced_by: None,
+        };
+
+        // Unknown properties can't be deprecated
+        assert!(cache.apply_lifecycle(&deprecate).await.is_none());
+        assert!(!cache.lifecycle(&key).await.deprecated);
+
+        cache.insert(&key, DataType::String).await;
+        let lifecycle = cache.apply_lifecycle(&deprecate).await.unwrap();
+        assert!(lifecycle.deprecated);
+        assert!(cache.lifecycle(&key).await.deprecated);
+
+        let undeprecate = PropertyLifecycleChange {
+            id: key,
+            deprecated: Some(false),
+            replaced_by: None,
+        };
+        cache.apply_lifecycle(&undeprecate).await;
+        assert_eq!(
+            cache.lifecycle(&key).await,
+            PropertyLifecycle {
+                id: key,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_replacement_follows_chain() {
+        let cache = PropertiesCache::new();
+        let (old, middle, new) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        for key in [old, middle, new] {
+            cache.insert(&key, DataType::String).await;
+        }
+        for (key, replacement) in [(old, middle), (middle, new)] {
+            cache
+                .apply_lifecycle(&PropertyLifecycleChange {
+                    id: key,
+                    deprecated: Some(true),
+                    replaced_by: Some(Some(replacement)),
+                })
+                .await;
+        }
+
+        assert_eq!(cache.resolve_replacement(&old).await, new);
+        assert_eq!(cache.resolve_replacement(&middle).await, new);
+        assert_eq!(cache.resolve_replacement(&new).await, new);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_replacement_stops_on_cycle() {
+        let cache = PropertiesCache::new();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        for (key, replacement) in [(a, b), (b, a)] {
+            cache.insert(&key, DataType::String).await;
+            cache
+                .apply_lifecycle(&PropertyLifecycleChange {
+                    id: key,
+                    deprecated: None,
+                    replaced_by: Some(Some(replacement)),
+                })
+                .await;
+        }
+
+        assert_eq!(cache.resolve_replacement(&a).await, b);
+    }
 }
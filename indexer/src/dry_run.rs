@@ -0,0 +1,275 @@
+//! Dry runs of the indexer over a block range.
+//!
+//! [`DryRunIndexer`] streams and preprocesses blocks exactly like the real
+//! indexer, but runs the handlers against a [`ShadowStorage`] and never loads
+//! or persists a cursor. It's meant for validating handler changes against
+//! production history before deploying them. Edits are still read from the
+//! IPFS cache and properties are seeded from the current database, but
+//! nothing is written to Postgres.
+
+use std::sync::{Arc, Mutex};
+
+use stream::{pb::sf::substreams::rpc::v2::BlockScopedData, PreprocessedSink};
+use tracing::info;
+
+use crate::{
+    block_handler::root_handler,
+    cache::{properties_cache::PropertiesCache, CacheBackend},
+    error::IndexingError,
+    preprocess,
+    storage::shadow::{ShadowChanges, ShadowStorage},
+    validators::EditAuthorValidator,
+    KgData,
+};
+
+/// Summary of what a dry run would have written.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DryRunReport {
+    pub first_block: Option<u64>,
+    pub last_block: Option<u64>,
+    pub blocks_processed: u64,
+    pub edits_processed: u64,
+    pub unauthorized_edits: u64,
+    pub rejected_edits: u64,
+    pub changes: ShadowChanges,
+}
+
+impl DryRunReport {
+    /// Logs the report as a single structured event.
+    pub fn log(&self) {
+        let changes = &self.changes;
+
+        info!(
+            first_block = self.first_block,
+            last_block = self.last_block,
+            blocks_processed = self.blocks_processed,
+            edits_processed = self.edits_processed,
+            unauthorized_edits = self.unauthorized_edits,
+            rejected_edits = self.rejected_edits,
+            entities = changes.entities,
+            values_set = changes.values_set,
+            values_deleted = changes.values_deleted,
+            relations_created = changes.relations_created,
+            relations_updated = changes.relations_updated,
+            relations_unset = changes.relations_unset,
+            relations_deleted = changes.relations_deleted,
+            properties = changes.properties,
+            spaces = changes.spaces,
+            space_metadata = changes.space_metadata,
+            space_lineage = changes.space_lineage,
+            members_added = changes.members_added,
+            members_removed = changes.members_removed,
+            editors_added = changes.editors_added,
+            editors_removed = changes.editors_removed,
+            subspaces_added = changes.subspaces_added,
+            subspaces_removed = changes.subspaces_removed,
+            proposals = changes.proposals,
+            proposal_statuses = changes.proposal_statuses,
+            proposal_votes = changes.proposal_votes,
+            governance_settings = changes.governance_settings,
+            "Dry run report"
+        );
+    }
+}
+
+#[derive(Debug, Default)]
+struct DryRunProgress {
+    first_block: Option<u64>,
+    last_block: Option<u64>,
+    blocks_processed: u64,
+    edits_processed: u64,
+}
+
+/// Sink that runs the block handlers against in-memory shadow storage.
+pub struct DryRunIndexer<C> {
+    storage: Arc<ShadowStorage>,
+    ipfs_cache: Arc<C>,
+    properties_cache: Arc<PropertiesCache>,
+    author_validator: Arc<EditAuthorValidator>,
+    progress: Mutex<DryRunProgress>,
+}
+
+impl<C> DryRunIndexer<C>
+where
+    C: CacheBackend + 'static,
+{
+    pub fn new(
+        ipfs_cache: C,
+        properties_cache: PropertiesCache,
+        author_validator: EditAuthorValidator,
+    ) -> Self {
+        DryRunIndexer {
+            storage: Arc::new(ShadowStorage::new()),
+            ipfs_cache: Arc::new(ipfs_cache),
+            properties_cache: Arc::new(properties_cache),
+            author_validator: Arc::new(author_validator),
+            progress: Mutex::new(DryRunProgress::default()),
+        }
+    }
+
+    pub fn storage(&self) -> &ShadowStorage {
+        &self.storage
+    }
+
+    /// Returns what the blocks processed so far would have written.
+    pub fn report(&self) -> DryRunReport {
+        let progress = self.progress.lock().unwrap();
+        let metrics = self.author_validator.metrics();
+
+        DryRunReport {
+            first_block: progress.first_block,
+            last_block: progress.last_block,
+            blocks_processed: progress.blocks_processed,
+            edits_processed: progress.edits_processed,
+            unauthorized_edits: metrics.unauthorized(),
+            rejected_edits: metrics.rejected(),
+            changes: self.storage.changes(),
+        }
+    }
+
+    /// Runs the handlers for a preprocessed block against the shadow storage.
+    pub async fn process_block(&self, decoded_data: &KgData) -> Result<(), IndexingError> {
+        root_handler::run(
+            decoded_data,
+            &decoded_data.block,
+            &self.storage,
+            &self.properties_cache,
+            &self.author_validator,
+        )
+        .await?;
+
+        let mut progress = self.progress.lock().unwrap();
+        let block_number = decoded_data.block.block_number;
+
+        progress.first_block.get_or_insert(block_number);
+        progress.last_block = Some(block_number);
+        progress.blocks_processed += 1;
+        progress.edits_processed += decoded_data.edits.len() as u64;
+
+        Ok(())
+    }
+}
+
+impl<C> PreprocessedSink<KgData> for DryRunIndexer<C>
+where
+    C: CacheBackend + 'static,
+{
+    type Error = IndexingError;
+
+    /// Dry runs always start from the requested start block.
+    async fn load_persisted_cursor(&self) -> Result<Option<String>, Self::Error> {
+        Ok(None)
+    }
+
+    /// Dry runs never persist a cursor so they can't move the real indexer.
+    async fn persist_cursor(&self, _cursor: String, _block: u64) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn preprocess_block_scoped_data(
+        &self,
+        block_data: &BlockScopedData,
+    ) -> Result<KgData, Self::Error> {
+        preprocess::preprocess_block_scoped_data(block_data, &self.ipfs_cache).await
+    }
+
+    async fn process_block_scoped_data(
+        &self,
+        _block_data: &BlockScopedData,
+        decoded_data: KgData,
+    ) -> Result<(), Self::Error> {
+        self.process_block(&decoded_data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stream::utils::BlockMetadata;
+
+    use super::*;
+    use crate::{
+        cache::{CacheError, DaoMetadata, PreprocessedEdit},
+        AddedMember, CreatedSpace, PersonalSpace,
+    };
+
+    struct EmptyCache;
+
+    #[async_trait::async_trait]
+    impl CacheBackend for EmptyCache {
+        async fn get(&self, _uri: &String) -> Result<PreprocessedEdit, CacheError> {
+            Err(CacheError::NotFound)
+        }
+
+        async fn get_dao_metadata(&self, _uri: &String) -> Result<Option<DaoMetadata>, CacheError> {
+            Err(CacheError::NotFound)
+        }
+    }
+
+    fn block(block_number: u64) -> KgData {
+        KgData {
+            block: BlockMetadata {
+                cursor: String::new(),
+                block_number,
+                timestamp: "1700000000".to_string(),
+                ..Default::default()
+            },
+            edits: vec![],
+            added_editors: vec![AddedMember {
+                dao_address: "0x1234567890123456789012345678901234567890".to_string(),
+                editor_address: "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".to_string(),
+                context: Default::default(),
+            }],
+            removed_editors: vec![],
+            added_members: vec![],
+            removed_members: vec![],
+            added_subspaces: vec![],
+            removed_subspaces: vec![],
+            proposals: vec![],
+            proposal_status_changes: vec![],
+            votes: vec![],
+            governance_settings: vec![],
+            dao_metadata: vec![],
+            successor_spaces: vec![],
+            spaces: vec![CreatedSpace::Personal(PersonalSpace {
+                dao_address: "0x1234567890123456789012345678901234567890".to_string(),
+                space_address: "0x0000000000000000000000000000000000000001".to_string(),
+                personal_plugin: "0x0000000000000000000000000000000000000002".to_string(),
+                initial_editors: vec![],
+                initial_members: vec![],
+                context: Default::default(),
+            })],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_reports_block_changes() {
+        let indexer = DryRunIndexer::new(
+            EmptyCache,
+            PropertiesCache::new(),
+            EditAuthorValidator::default(),
+        );
+
+        indexer.process_block(&block(10)).await.unwrap();
+        indexer.process_block(&block(11)).await.unwrap();
+
+        let report = indexer.report();
+
+        assert_eq!(report.first_block, Some(10));
+        assert_eq!(report.last_block, Some(11));
+        assert_eq!(report.blocks_processed, 2);
+        assert_eq!(report.changes.spaces, 2);
+        assert_eq!(report.changes.editors_added, 2);
+        assert_eq!(report.changes.entities, 0);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_never_loads_cursor() {
+        let indexer = DryRunIndexer::new(
+            EmptyCache,
+            PropertiesCache::new(),
+            EditAuthorValidator::default(),
+        );
+
+        assert_eq!(indexer.load_persisted_cursor().await.unwrap(), None);
+    }
+}
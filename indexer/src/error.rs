@@ -2,7 +2,9 @@ use prost::DecodeError;
 use thiserror::Error;
 use tokio::task::JoinError;
 
-use crate::{cache::CacheError, storage::StorageError};
+use crate::{
+    backfill::BackfillError, cache::CacheError, replay::ReplayError, storage::StorageError,
+};
 
 #[derive(Error, Debug)]
 pub enum IndexingError {
@@ -20,4 +22,10 @@ pub enum IndexingError {
 
     #[error("Indexing error: {0}")]
     SqlxError(#[from] sqlx::Error),
+
+    #[error("Indexing error: {0}")]
+    BackfillError(#[from] BackfillError),
+
+    #[error("Indexing error: {0}")]
+    ReplayError(#[from] ReplayError),
 }
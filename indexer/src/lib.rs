@@ -1,21 +1,43 @@
 use cache::PreprocessedEdit;
+use indexer_utils::space_kind::SpaceKind;
 use stream::utils::BlockMetadata;
 
+pub mod backfill;
 pub mod block_handler;
 pub mod cache;
+pub mod dry_run;
 pub mod error;
 pub mod models;
 pub mod preprocess;
+pub mod replay;
 pub mod storage;
 pub mod validators;
 
 pub mod test_utils;
 
+/// Where an event was emitted onchain: the transaction, the log's index in
+/// the block and the block it was included in. Together the transaction hash
+/// and log index identify an event, so replayed events can be deduplicated.
+///
+/// Events from substream packages that don't emit the context have the
+/// default, empty context.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventContext {
+    pub tx_hash: String,
+    pub log_index: u64,
+    pub block_number: u64,
+    pub block_timestamp: u64,
+}
+
 #[derive(Clone, Debug)]
 pub struct PersonalSpace {
     pub dao_address: String,
     pub space_address: String,
     pub personal_plugin: String,
+    /// Editors and members the plugin was installed with
+    pub initial_editors: Vec<String>,
+    pub initial_members: Vec<String>,
+    pub context: EventContext,
 }
 
 #[derive(Clone, Debug)]
@@ -24,6 +46,10 @@ pub struct PublicSpace {
     pub space_address: String,
     pub membership_plugin: String,
     pub governance_plugin: String,
+    /// Editors and members the plugins were installed with
+    pub initial_editors: Vec<String>,
+    pub initial_members: Vec<String>,
+    pub context: EventContext,
 }
 
 #[derive(Clone, Debug)]
@@ -32,28 +58,166 @@ pub enum CreatedSpace {
     Public(PublicSpace),
 }
 
+impl CreatedSpace {
+    pub fn kind(&self) -> SpaceKind {
+        match self {
+            CreatedSpace::Personal(_) => SpaceKind::Personal,
+            CreatedSpace::Public(_) => SpaceKind::Dao,
+        }
+    }
+
+    pub fn dao_address(&self) -> &str {
+        match self {
+            CreatedSpace::Personal(personal) => &personal.dao_address,
+            CreatedSpace::Public(public) => &public.dao_address,
+        }
+    }
+
+    pub fn context(&self) -> &EventContext {
+        match self {
+            CreatedSpace::Personal(personal) => &personal.context,
+            CreatedSpace::Public(public) => &public.context,
+        }
+    }
+
+    pub fn initial_editors(&self) -> &[String] {
+        match self {
+            CreatedSpace::Personal(personal) => &personal.initial_editors,
+            CreatedSpace::Public(public) => &public.initial_editors,
+        }
+    }
+
+    pub fn initial_members(&self) -> &[String] {
+        match self {
+            CreatedSpace::Personal(personal) => &personal.initial_members,
+            CreatedSpace::Public(public) => &public.initial_members,
+        }
+    }
+}
+
+/// A space created as the successor of another space. The predecessor is the
+/// address of its space plugin, which may be a legacy space that was never
+/// indexed.
+#[derive(Clone, Debug)]
+pub struct SuccessorSpace {
+    pub dao_address: String,
+    pub space_address: String,
+    pub predecessor_space: String,
+    pub context: EventContext,
+}
+
 #[derive(Clone, Debug)]
 pub struct AddedMember {
     pub dao_address: String,
     pub editor_address: String,
+    pub context: EventContext,
 }
 
 #[derive(Clone, Debug)]
 pub struct RemovedMember {
     pub dao_address: String,
     pub editor_address: String,
+    pub context: EventContext,
 }
 
 #[derive(Clone, Debug)]
 pub struct AddedSubspace {
     pub dao_address: String,
     pub subspace_address: String,
+    pub context: EventContext,
 }
 
 #[derive(Clone, Debug)]
 pub struct RemovedSubspace {
     pub dao_address: String,
     pub subspace_address: String,
+    pub context: EventContext,
+}
+
+/// A proposal created in any of a space's voting or admin plugins, along
+/// with the URI of its metadata.
+#[derive(Clone, Debug)]
+pub struct CreatedProposal {
+    pub plugin_address: String,
+    pub proposal_id: String,
+    pub creator: String,
+    pub start_time: String,
+    pub end_time: String,
+    pub metadata_uri: String,
+    pub context: EventContext,
+}
+
+/// Lifecycle of a proposal. Proposals are created `Proposed` and are
+/// `Executed` or `Canceled` by onchain events. `Expired` isn't an event: the
+/// sink marks proposals expired once a block passes their end time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalStatus {
+    Proposed,
+    Executed,
+    Canceled,
+    Expired,
+}
+
+impl ProposalStatus {
+    /// Value of the `proposalStatus` enum in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalStatus::Proposed => "Proposed",
+            ProposalStatus::Executed => "Executed",
+            ProposalStatus::Canceled => "Canceled",
+            ProposalStatus::Expired => "Expired",
+        }
+    }
+}
+
+/// A proposal executed or canceled in any of a space's voting or admin
+/// plugins.
+#[derive(Clone, Debug)]
+pub struct ProposalStatusChange {
+    pub plugin_address: String,
+    pub proposal_id: String,
+    pub status: ProposalStatus,
+    pub context: EventContext,
+}
+
+/// A vote cast on a proposal in a space's voting plugin. A voter can replace
+/// their vote while the proposal is open, so only the latest one counts.
+#[derive(Clone, Debug)]
+pub struct CastVote {
+    pub plugin_address: String,
+    pub proposal_id: String,
+    pub voter: String,
+    pub vote_option: u64,
+    /// Voting power of the voter at the proposal's snapshot block, as a
+    /// decimal uint256
+    pub voting_power: String,
+    pub context: EventContext,
+}
+
+/// New settings of a space's voting or member access plugin. The member
+/// access plugin only has a proposal duration, so it leaves the voting
+/// mode and support threshold empty.
+#[derive(Clone, Debug)]
+pub struct UpdatedGovernanceSettings {
+    pub plugin_address: String,
+    pub voting_mode: Option<u64>,
+    /// Support threshold in parts per million
+    pub support_threshold: Option<u64>,
+    /// Proposal duration in seconds
+    pub duration: u64,
+    pub context: EventContext,
+}
+
+/// Metadata set on a space's DAO, with the name and description read from
+/// its metadata document. Both are empty if the document couldn't be
+/// fetched or didn't have them.
+#[derive(Clone, Debug)]
+pub struct UpdatedDaoMetadata {
+    pub dao_address: String,
+    pub metadata_uri: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub context: EventContext,
 }
 
 #[derive(Clone, Debug)]
@@ -66,7 +230,13 @@ pub struct KgData {
     pub removed_members: Vec<RemovedMember>,
     pub added_subspaces: Vec<AddedSubspace>,
     pub removed_subspaces: Vec<RemovedSubspace>,
+    pub proposals: Vec<CreatedProposal>,
+    pub proposal_status_changes: Vec<ProposalStatusChange>,
+    pub votes: Vec<CastVote>,
+    pub governance_settings: Vec<UpdatedGovernanceSettings>,
+    pub dao_metadata: Vec<UpdatedDaoMetadata>,
     // Note for now that we only need the dao address. Eventually we'll
     // index the plugin addresses as well.
     pub spaces: Vec<CreatedSpace>,
+    pub successor_spaces: Vec<SuccessorSpace>,
 }
@@ -1,19 +1,27 @@
 use indexer::{
+    backfill::{
+        postgres::PostgresBackfillStore, BackfillConfig, BackfillCoordinator, BackfillRole,
+    },
     block_handler::root_handler,
     cache::{postgres::PostgresCache, properties_cache::PropertiesCache},
+    dry_run::DryRunIndexer,
     error::IndexingError,
     preprocess,
+    replay::{self, ReplayConfig, ReplayError, ReplayRecorder},
     storage::postgres::PostgresStorage,
+    validators::{AuthorizationMode, EditAuthorValidator},
     KgData,
 };
 use std::{env, sync::Arc};
 
-use axiom_rs::Client as AxiomClient;
 use dotenv::dotenv;
 use stream::{pb::sf::substreams::rpc::v2::BlockScopedData, PreprocessedSink};
 use tracing::{error, info, instrument};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+#[cfg(feature = "axiom")]
+mod axiom;
+
 const PKG_FILE: &str = "geo_substream.spkg";
 const MODULE_NAME: &str = "geo_out";
 const START_BLOCK: i64 = 74964;
@@ -22,115 +30,7 @@ struct KgIndexer {
     storage: Arc<PostgresStorage>,
     ipfs_cache: Arc<PostgresCache>,
     properties_cache: Arc<PropertiesCache>,
-}
-
-use serde_json::{json, Value};
-use std::sync::Mutex;
-
-// Simple in-memory buffer for Axiom logs to batch them
-static AXIOM_LOG_BUFFER: Mutex<Vec<Value>> = Mutex::new(Vec::new());
-
-struct AxiomLayer {
-    dataset: String,
-}
-
-impl AxiomLayer {
-    fn new(dataset: String) -> Self {
-        Self { dataset }
-    }
-}
-
-use tracing_subscriber::layer::Context;
-use tracing_subscriber::Layer;
-
-impl<S> Layer<S> for AxiomLayer
-where
-    S: tracing::Subscriber + for<'lookup> tracing_subscriber::registry::LookupSpan<'lookup>,
-{
-    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
-        let mut visitor = JsonVisitor::new();
-        event.record(&mut visitor);
-
-        // Check if current event has block_number in its fields
-        let mut block_number = None;
-        if let Some(Value::Number(bn)) = visitor.fields.get("block_number") {
-            block_number = bn.as_u64();
-        } else if let Some(Value::Number(bn)) = visitor.fields.get("block") {
-            block_number = bn.as_u64();
-        }
-
-        let mut log_entry = json!({
-            "timestamp": chrono::Utc::now().to_rfc3339(),
-            "level": event.metadata().level().to_string(),
-            "target": event.metadata().target(),
-            "service": "gaia.indexer",
-            "fields": visitor.fields
-        });
-
-        // Add block_number to top level if found
-        if let Some(bn) = block_number {
-            log_entry["block_number"] = json!(bn);
-        }
-
-        if let Ok(mut buffer) = AXIOM_LOG_BUFFER.lock() {
-            buffer.push(log_entry);
-
-            // Flush buffer when it gets large (simple batching)
-            if buffer.len() >= 10 {
-                let logs = buffer.drain(..).collect::<Vec<_>>();
-                let dataset = self.dataset.clone();
-
-                tokio::spawn(async move {
-                    if let Ok(client) = AxiomClient::new() {
-                        if let Err(e) = client.ingest(&dataset, logs).await {
-                            eprintln!("Failed to send logs to Axiom: {}", e);
-                        }
-                    }
-                });
-            }
-        }
-    }
-}
-
-struct JsonVisitor {
-    fields: serde_json::Map<String, Value>,
-}
-
-impl JsonVisitor {
-    fn new() -> Self {
-        Self {
-            fields: serde_json::Map::new(),
-        }
-    }
-}
-
-impl tracing::field::Visit for JsonVisitor {
-    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
-        self.fields.insert(
-            field.name().to_string(),
-            Value::String(format!("{:?}", value)),
-        );
-    }
-
-    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
-        self.fields
-            .insert(field.name().to_string(), Value::String(value.to_string()));
-    }
-
-    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
-        self.fields
-            .insert(field.name().to_string(), Value::Number(value.into()));
-    }
-
-    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
-        self.fields
-            .insert(field.name().to_string(), Value::Number(value.into()));
-    }
-
-    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
-        self.fields
-            .insert(field.name().to_string(), Value::Bool(value));
-    }
+    author_validator: Arc<EditAuthorValidator>,
 }
 
 impl KgIndexer {
@@ -138,11 +38,13 @@ impl KgIndexer {
         storage: PostgresStorage,
         ipfs_cache: PostgresCache,
         properties_cache: PropertiesCache,
+        author_validator: EditAuthorValidator,
     ) -> Self {
         KgIndexer {
             storage: Arc::new(storage),
             ipfs_cache: Arc::new(ipfs_cache),
             properties_cache: Arc::new(properties_cache),
+            author_validator: Arc::new(author_validator),
         }
     }
 }
@@ -215,6 +117,7 @@ impl PreprocessedSink<KgData> for KgIndexer {
             &decoded_data.block,
             &self.storage,
             &self.properties_cache,
+            &self.author_validator,
         )
         .await?;
 
@@ -237,16 +140,112 @@ async fn main() -> Result<(), IndexingError> {
             let cache = PostgresCache::new().await?;
             let properties_cache = PropertiesCache::from_storage(&result).await?;
 
-            let indexer = KgIndexer::new(result, cache, properties_cache);
+            // Unauthorized edits are flagged by default. Set
+            // EDIT_AUTHORIZATION_MODE=reject to skip them instead.
+            let authorization_mode = env::var("EDIT_AUTHORIZATION_MODE")
+                .map(|value| AuthorizationMode::from_config_value(&value))
+                .unwrap_or_default();
+            let author_validator = EditAuthorValidator::new(authorization_mode);
 
             let endpoint_url =
                 env::var("SUBSTREAMS_ENDPOINT").expect("SUBSTREAMS_ENDPOINT not set");
 
+            // Setting DRY_RUN_START_BLOCK runs the handlers over
+            // [DRY_RUN_START_BLOCK, DRY_RUN_END_BLOCK) against in-memory storage
+            // and reports the changes instead of writing them.
+            if let Some((start_block, end_block)) = dry_run_range() {
+                let indexer = DryRunIndexer::new(cache, properties_cache, author_validator);
+
+                info!(
+                    endpoint = %endpoint_url,
+                    package = PKG_FILE,
+                    module = MODULE_NAME,
+                    start_block = start_block,
+                    end_block = end_block,
+                    authorization_mode = ?authorization_mode,
+                    "Starting indexer dry run"
+                );
+
+                if let Err(error) = indexer
+                    .run(&endpoint_url, PKG_FILE, MODULE_NAME, start_block, end_block)
+                    .await
+                {
+                    error!("Dry run failed: {}", error);
+                }
+
+                indexer.report().log();
+                flush_axiom_logs().await;
+
+                return Ok(());
+            }
+
+            // Setting REPLAY_DATABASE_URLS streams
+            // [REPLAY_START_BLOCK, REPLAY_END_BLOCK) once, replays it into two
+            // fresh databases and fails if their tables differ (see
+            // indexer::replay).
+            if let Some(config) = ReplayConfig::from_env() {
+                let result =
+                    run_replay_check(&config, &endpoint_url, cache, authorization_mode).await;
+
+                if let Err(error) = &result {
+                    error!("Replay check failed: {}", error);
+                }
+
+                flush_axiom_logs().await;
+
+                return result;
+            }
+
+            // Setting BACKFILL_ID streams the history in shards across every
+            // instance started with the same ID (see indexer::backfill).
+            // Workers exit once no shards are left to claim, while the
+            // coordinator merges the shards and then keeps indexing live.
+            let backfill = BackfillConfig::from_env(START_BLOCK as u64).map(|config| {
+                BackfillCoordinator::new(PostgresBackfillStore::new(result.pool.clone()), config)
+            });
+
+            if let Some(backfill) = backfill
+                .as_ref()
+                .filter(|backfill| backfill.config().role == BackfillRole::Worker)
+            {
+                info!(
+                    backfill_id = %backfill.config().backfill_id,
+                    instance_id = %backfill.config().instance_id,
+                    "Starting backfill worker"
+                );
+
+                match backfill
+                    .run_worker(&endpoint_url, PKG_FILE, MODULE_NAME)
+                    .await
+                {
+                    Ok(completed) => {
+                        info!(completed_shards = completed, "Backfill worker finished")
+                    }
+                    Err(error) => error!("Backfill worker failed: {}", error),
+                }
+
+                flush_axiom_logs().await;
+
+                return Ok(());
+            }
+
+            let indexer = KgIndexer::new(result, cache, properties_cache, author_validator);
+
+            if let Some(backfill) = &backfill {
+                if let Err(error) = run_backfill(backfill, &indexer, &endpoint_url).await {
+                    error!("Backfill failed: {}", error);
+                    flush_axiom_logs().await;
+
+                    return Err(error);
+                }
+            }
+
             info!(
                 endpoint = %endpoint_url,
                 package = PKG_FILE,
                 module = MODULE_NAME,
                 start_block = START_BLOCK,
+                authorization_mode = ?authorization_mode,
                 "Starting indexer"
             );
 
@@ -266,34 +265,118 @@ async fn main() -> Result<(), IndexingError> {
     Ok(())
 }
 
-async fn flush_axiom_logs() {
-    let axiom_dataset = env::var("AXIOM_DATASET").unwrap_or_else(|_| "gaia.indexer".to_string());
-
-    if let Ok(mut buffer) = AXIOM_LOG_BUFFER.lock() {
-        if !buffer.is_empty() {
-            let logs = buffer.drain(..).collect::<Vec<_>>();
-            if let Ok(client) = AxiomClient::new() {
-                if let Err(e) = client.ingest(&axiom_dataset, logs).await {
-                    eprintln!("Failed to flush logs to Axiom: {}", e);
-                }
-            }
+/// Streams the backfill's shards alongside the worker instances, then merges
+/// them through the live indexer so it resumes at the end of the backfill.
+async fn run_backfill(
+    backfill: &BackfillCoordinator<PostgresBackfillStore>,
+    indexer: &KgIndexer,
+    endpoint_url: &str,
+) -> Result<(), IndexingError> {
+    let config = backfill.config();
+
+    info!(
+        backfill_id = %config.backfill_id,
+        instance_id = %config.instance_id,
+        start_block = config.start_block,
+        end_block = config.end_block,
+        shard_size = config.shard_size,
+        "Starting backfill"
+    );
+
+    backfill.plan().await?;
+    backfill
+        .stream_until_done(endpoint_url, PKG_FILE, MODULE_NAME)
+        .await?;
+    backfill.merge(indexer).await?;
+
+    Ok(())
+}
+
+/// Records the replay range once and replays it into both databases, so
+/// both replays process exactly the same blocks.
+async fn run_replay_check(
+    config: &ReplayConfig,
+    endpoint_url: &str,
+    cache: PostgresCache,
+    authorization_mode: AuthorizationMode,
+) -> Result<(), IndexingError> {
+    info!(
+        endpoint = %endpoint_url,
+        package = PKG_FILE,
+        module = MODULE_NAME,
+        start_block = config.start_block,
+        end_block = config.end_block,
+        authorization_mode = ?authorization_mode,
+        "Starting replay determinism check"
+    );
+
+    let recorder = ReplayRecorder::new();
+    recorder
+        .run(
+            endpoint_url,
+            PKG_FILE,
+            MODULE_NAME,
+            config.start_block,
+            config.end_block,
+        )
+        .await
+        .map_err(|error| ReplayError::Stream(error.to_string()))?;
+
+    let blocks = recorder.blocks();
+    let report = replay::check(
+        &blocks,
+        &config.database_urls,
+        &Arc::new(cache),
+        authorization_mode,
+    )
+    .await?;
+
+    report.log();
+
+    if !report.is_deterministic() {
+        return Err(ReplayError::Nondeterministic {
+            tables: report.diffs.len(),
         }
+        .into());
     }
+
+    Ok(())
 }
 
-fn init_tracing() -> Result<(), IndexingError> {
-    // Check if Axiom token is available
-    let axiom_token = env::var("AXIOM_TOKEN").ok();
-    let axiom_dataset = env::var("AXIOM_DATASET").unwrap_or_else(|_| "gaia.indexer".to_string());
+/// Reads the dry run block range from DRY_RUN_START_BLOCK and DRY_RUN_END_BLOCK.
+/// The end block is exclusive and required so a dry run always terminates.
+fn dry_run_range() -> Option<(i64, u64)> {
+    let start_block = env::var("DRY_RUN_START_BLOCK").ok()?;
+    let start_block = start_block
+        .parse::<i64>()
+        .expect("DRY_RUN_START_BLOCK must be a block number");
+
+    let end_block = env::var("DRY_RUN_END_BLOCK")
+        .expect("DRY_RUN_END_BLOCK must be set for a dry run")
+        .parse::<u64>()
+        .expect("DRY_RUN_END_BLOCK must be a block number");
+
+    Some((start_block, end_block))
+}
 
+async fn flush_axiom_logs() {
+    #[cfg(feature = "axiom")]
+    axiom::flush_logs().await;
+}
+
+fn init_tracing() -> Result<(), IndexingError> {
     let registry = tracing_subscriber::registry().with(
         tracing_subscriber::EnvFilter::try_from_default_env()
             .unwrap_or_else(|_| "indexer=info,stream=info".into()),
     );
 
-    if axiom_token.is_some() {
+    // Check if Axiom token is available
+    #[cfg(feature = "axiom")]
+    if env::var("AXIOM_TOKEN").is_ok() {
+        let axiom_dataset = axiom::dataset();
+
         // Set up tracing with Axiom layer
-        let layers = registry.with(AxiomLayer::new(axiom_dataset.clone()));
+        let layers = registry.with(axiom::AxiomLayer::new(axiom_dataset.clone()));
 
         // Only add console logging in debug builds
         #[cfg(debug_assertions)]
@@ -322,38 +405,40 @@ fn init_tracing() -> Result<(), IndexingError> {
             axiom_dataset = axiom_dataset,
             "Tracing initialized with Axiom ingestion only"
         );
-    } else {
-        // Only set up console tracing in debug builds
-        #[cfg(debug_assertions)]
-        {
-            registry
-                .with(
-                    tracing_subscriber::fmt::layer()
-                        .with_target(true)
-                        .with_thread_ids(true)
-                        .with_thread_names(true)
-                        .json(), // Use JSON format for structured logging
-                )
-                .init();
 
-            info!(
-                service_name = "gaia.indexer",
-                service_version = env!("CARGO_PKG_VERSION"),
-                "Tracing initialized with console logging (AXIOM_TOKEN not set)"
-            );
-        }
+        return Ok(());
+    }
 
-        #[cfg(not(debug_assertions))]
-        {
-            // In release mode without Axiom, just use a minimal registry
-            registry.init();
+    // Only set up console tracing in debug builds
+    #[cfg(debug_assertions)]
+    {
+        registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_target(true)
+                    .with_thread_ids(true)
+                    .with_thread_names(true)
+                    .json(), // Use JSON format for structured logging
+            )
+            .init();
 
-            info!(
-                service_name = "gaia.indexer",
-                service_version = env!("CARGO_PKG_VERSION"),
-                "Tracing initialized without console logging (release mode, AXIOM_TOKEN not set)"
-            );
-        }
+        info!(
+            service_name = "gaia.indexer",
+            service_version = env!("CARGO_PKG_VERSION"),
+            "Tracing initialized with console logging (AXIOM_TOKEN not set)"
+        );
+    }
+
+    #[cfg(not(debug_assertions))]
+    {
+        // In release mode without Axiom, just use a minimal registry
+        registry.init();
+
+        info!(
+            service_name = "gaia.indexer",
+            service_version = env!("CARGO_PKG_VERSION"),
+            "Tracing initialized without console logging (release mode, AXIOM_TOKEN not set)"
+        );
     }
 
     Ok(())
@@ -0,0 +1,99 @@
+use indexer_utils::checksum_address;
+use stream::utils::BlockMetadata;
+
+use crate::UpdatedGovernanceSettings;
+
+#[derive(Clone, Debug)]
+pub struct GovernanceSettingsItem {
+    pub plugin_address: String,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub tx_hash: String,
+    pub voting_mode: Option<i32>,
+    pub support_threshold: Option<i64>,
+    pub duration: i64,
+}
+
+pub struct GovernanceSettingsModel;
+
+impl GovernanceSettingsModel {
+    /// Maps governance settings updates from KgData to database-ready
+    /// GovernanceSettingsItem structs.
+    ///
+    /// Settings are a history rather than a current value, so every update is
+    /// kept and identified by its plugin and position in the chain.
+    pub fn map_governance_settings(
+        settings: &[UpdatedGovernanceSettings],
+        block: &BlockMetadata,
+    ) -> Vec<GovernanceSettingsItem> {
+        settings
+            .iter()
+            .map(|update| GovernanceSettingsItem {
+                plugin_address: checksum_address(update.plugin_address.clone()),
+                block_number: block.block_number as i64,
+                log_index: update.context.log_index as i64,
+                tx_hash: update.context.tx_hash.clone(),
+                voting_mode: update.voting_mode.map(|mode| mode as i32),
+                support_threshold: update.support_threshold.map(|threshold| threshold as i64),
+                duration: update.duration as i64,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventContext;
+
+    fn block() -> BlockMetadata {
+        BlockMetadata {
+            block_number: 42,
+            ..Default::default()
+        }
+    }
+
+    fn settings_update(voting_mode: Option<u64>, log_index: u64) -> UpdatedGovernanceSettings {
+        UpdatedGovernanceSettings {
+            plugin_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
+            voting_mode,
+            support_threshold: voting_mode.map(|_| 500_000),
+            duration: 86400,
+            context: EventContext {
+                tx_hash: "0xabc".to_string(),
+                log_index,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_map_governance_settings_empty() {
+        let result = GovernanceSettingsModel::map_governance_settings(&[], &block());
+        assert_eq!(result.len(), 0);
+    }
+
+    #[test]
+    fn test_map_governance_settings() {
+        let block = block();
+        let settings = vec![settings_update(Some(1), 3), settings_update(None, 5)];
+
+        let result = GovernanceSettingsModel::map_governance_settings(&settings, &block);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0].plugin_address,
+            checksum_address("0x1234567890abcdef1234567890abcdef12345678")
+        );
+        assert_eq!(result[0].block_number, 42);
+        assert_eq!(result[0].log_index, 3);
+        assert_eq!(result[0].tx_hash, "0xabc");
+        assert_eq!(result[0].voting_mode, Some(1));
+        assert_eq!(result[0].support_threshold, Some(500_000));
+        assert_eq!(result[0].duration, 86400);
+        // Member access plugins only have a proposal duration
+        assert_eq!(result[1].voting_mode, None);
+        assert_eq!(result[1].support_threshold, None);
+        assert_eq!(result[1].log_index, 5);
+    }
+}
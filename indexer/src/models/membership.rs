@@ -1,7 +1,7 @@
 use indexer_utils::{checksum_address, id::derive_space_id, network_ids::GEO};
 use uuid::Uuid;
 
-use crate::{AddedMember, RemovedMember};
+use crate::{AddedMember, CreatedSpace, RemovedMember};
 
 #[derive(Clone, Debug)]
 pub struct MemberItem {
@@ -81,4 +81,46 @@ impl MembershipModel {
 
         editors
     }
+    /// Maps the editors that created spaces were installed with to
+    /// database-ready EditorItem structs
+    pub fn map_initial_editors(spaces: &[CreatedSpace]) -> Vec<EditorItem> {
+        let mut editors = Vec::new();
+
+        for space in spaces {
+            let space_id = derive_space_id(GEO, &checksum_address(space.dao_address()));
+
+            for editor in space.initial_editors() {
+                editors.push(EditorItem {
+                    address: checksum_address(editor),
+                    space_id,
+                });
+            }
+        }
+
+        editors
+    }
+
+    /// Maps the members that created spaces were installed with to
+    /// database-ready MemberItem structs. Initial editors are members too.
+    pub fn map_initial_members(spaces: &[CreatedSpace]) -> Vec<MemberItem> {
+        let mut members = Vec::new();
+
+        for space in spaces {
+            let space_id = derive_space_id(GEO, &checksum_address(space.dao_address()));
+            let mut addresses: Vec<String> = space
+                .initial_editors()
+                .iter()
+                .chain(space.initial_members())
+                .map(checksum_address)
+                .collect();
+            addresses.sort();
+            addresses.dedup();
+
+            for address in addresses {
+                members.push(MemberItem { address, space_id });
+            }
+        }
+
+        members
+    }
 }
\ No newline at end of file
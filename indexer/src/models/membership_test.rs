@@ -1,13 +1,14 @@
 #[cfg(test)]
 mod tests {
     use crate::models::membership::MembershipModel;
-    use crate::{AddedMember, RemovedMember};
+    use crate::{AddedMember, CreatedSpace, PublicSpace, RemovedMember};
     use indexer_utils::{checksum_address, id::derive_space_id, network_ids::GEO};
 
     fn create_added_member(dao_address: &str, editor_address: &str) -> AddedMember {
         AddedMember {
             dao_address: dao_address.to_string(),
             editor_address: editor_address.to_string(),
+            context: Default::default(),
         }
     }
 
@@ -15,6 +16,7 @@ mod tests {
         RemovedMember {
             dao_address: dao_address.to_string(),
             editor_address: editor_address.to_string(),
+            context: Default::default(),
         }
     }
 
@@ -264,4 +266,36 @@ mod tests {
         assert_eq!(member_result[0].space_id, expected_space_id);
         assert_eq!(editor_result[0].space_id, expected_space_id);
     }
+
+    #[test]
+    fn test_map_initial_editors_and_members() {
+        let dao_addr = "0x1234567890123456789012345678901234567890";
+        let editor_addr = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+        let member_addr = "0xfedcbafedcbafedcbafedcbafedcbafedcbafedc";
+        let spaces = vec![CreatedSpace::Public(PublicSpace {
+            dao_address: dao_addr.to_string(),
+            space_address: "0x0000000000000000000000000000000000000001".to_string(),
+            membership_plugin: "0x0000000000000000000000000000000000000002".to_string(),
+            governance_plugin: "0x0000000000000000000000000000000000000003".to_string(),
+            initial_editors: vec![editor_addr.to_string()],
+            initial_members: vec![member_addr.to_string(), editor_addr.to_string()],
+            context: Default::default(),
+        })];
+        let expected_space_id = derive_space_id(GEO, &checksum_address(dao_addr));
+
+        let editors = MembershipModel::map_initial_editors(&spaces);
+        let members = MembershipModel::map_initial_members(&spaces);
+
+        assert_eq!(editors.len(), 1);
+        assert_eq!(editors[0].address, checksum_address(editor_addr));
+        assert_eq!(editors[0].space_id, expected_space_id);
+        // Initial editors are members too, but only once
+        assert_eq!(members.len(), 2);
+        assert!(members
+            .iter()
+            .any(|member| member.address == checksum_address(editor_addr)));
+        assert!(members
+            .iter()
+            .any(|member| member.address == checksum_address(member_addr)));
+    }
 }
\ No newline at end of file
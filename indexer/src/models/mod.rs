@@ -1,6 +1,8 @@
 pub mod entities;
+pub mod governance_settings;
 pub mod membership;
 pub mod properties;
+pub mod proposals;
 pub mod relations;
 pub mod spaces;
 pub mod subspaces;
@@ -9,6 +11,9 @@ pub mod values;
 #[cfg(test)]
 mod membership_test;
 
+#[cfg(test)]
+mod properties_test;
+
 #[cfg(test)]
 mod relations_test;
 
@@ -4,6 +4,8 @@ use std::fmt;
 use uuid::Uuid;
 use wire::pb::grc20::{op::Payload, DataType as PbDataType, Edit};
 
+use crate::validators::validate_checkbox;
+
 // Constants for PostgreSQL enum values - must match the data type enum in the db
 pub const DATA_TYPE_STRING: &str = "String";
 pub const DATA_TYPE_NUMBER: &str = "Number";
@@ -111,13 +113,60 @@ impl DataType {
     }
 }
 
-/// Represents a property with its ID and type information
+/// Checkbox property set on a property's entity to mark the property as
+/// deprecated ("1") or no longer deprecated ("0").
+pub const DEPRECATED_PROPERTY: Uuid = Uuid::from_u128(0x5b4c_0f3e_8a2d_4e61_9c07_3d1f_a6b8_e942);
+
+/// Text property set on a property's entity to the ID of the property that
+/// replaces it.
+pub const REPLACED_BY_PROPERTY: Uuid = Uuid::from_u128(0x2e9a_71c4_0d5b_4f38_b6e1_84c2_7a3f_d015);
 #[derive(Clone, Debug)]
 pub struct PropertyItem {
     pub id: Uuid,
     pub data_type: DataType,
 }
 
+/// Lifecycle state of a property. Properties are immutable once created,
+/// so renames are modelled as a deprecated property replaced by a new one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PropertyLifecycle {
+    pub id: Uuid,
+    pub deprecated: bool,
+    pub replaced_by: Option<Uuid>,
+}
+
+/// A change to a property's lifecycle made by an edit. Fields that are
+/// `None` are left unchanged; `replaced_by: Some(None)` clears the
+/// replacement.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PropertyLifecycleChange {
+    pub id: Uuid,
+    pub deprecated: Option<bool>,
+    pub replaced_by: Option<Option<Uuid>>,
+}
+
+impl PropertyLifecycleChange {
+    pub fn apply(&self, lifecycle: &mut PropertyLifecycle) {
+        if let Some(deprecated) = self.deprecated {
+            lifecycle.deprecated = deprecated;
+        }
+
+        if let Some(replaced_by) = self.replaced_by {
+            lifecycle.replaced_by = replaced_by;
+        }
+    }
+
+    fn merge(&mut self, other: &PropertyLifecycleChange) {
+        if other.deprecated.is_some() {
+            self.deprecated = other.deprecated;
+        }
+
+        if other.replaced_by.is_some() {
+            self.replaced_by = other.replaced_by;
+        }
+    }
+}
+
 pub struct PropertiesModel;
 
 impl PropertiesModel {
@@ -159,6 +208,113 @@ impl PropertiesModel {
     }
 }
 
+impl PropertiesModel {
+    /// Maps the lifecycle markers set on property entities in an edit to
+    /// lifecycle changes, one per property.
+    ///
+    /// Setting [`DEPRECATED_PROPERTY`] deprecates or un-deprecates the
+    /// property and setting [`REPLACED_BY_PROPERTY`] links it to its
+    /// replacement. Unsetting either marker reverts it. Whether the entity
+    /// is actually a property is checked against the properties cache when
+    /// the changes are applied.
+    pub fn map_edit_to_lifecycle_changes(edit: &Edit) -> Vec<PropertyLifecycleChange> {
+        let mut changes: Vec<PropertyLifecycleChange> = Vec::new();
+
+        for op in &edit.ops {
+            match &op.payload {
+                Some(Payload::UpdateEntity(entity)) => {
+                    let Ok(entity_id_bytes) = id::transform_id_bytes(entity.id.clone()) else {
+                        continue;
+                    };
+                    let property_id = Uuid::from_bytes(entity_id_bytes);
+
+                    for value in &entity.values {
+                        let Ok(marker_bytes) = id::transform_id_bytes(value.property.clone())
+                        else {
+                            continue;
+                        };
+
+                        match Uuid::from_bytes(marker_bytes) {
+                            DEPRECATED_PROPERTY => match validate_checkbox(&value.value) {
+                                Ok(deprecated) => changes.push(PropertyLifecycleChange {
+                                    id: property_id,
+                                    deprecated: Some(deprecated),
+                                    replaced_by: None,
+                                }),
+                                Err(_) => tracing::warn!(
+                                    property_id = %property_id,
+                                    value = %value.value,
+                                    "[Properties][Lifecycle] Invalid deprecated marker"
+                                ),
+                            },
+                            REPLACED_BY_PROPERTY => match Uuid::parse_str(&value.value) {
+                                Ok(replaced_by) if replaced_by != property_id => {
+                                    changes.push(PropertyLifecycleChange {
+                                        id: property_id,
+                                        deprecated: None,
+                                        replaced_by: Some(Some(replaced_by)),
+                                    })
+                                }
+                                _ => tracing::warn!(
+                                    property_id = %property_id,
+                                    value = %value.value,
+                                    "[Properties][Lifecycle] Invalid replacement property id"
+                                ),
+                            },
+                            _ => {}
+                        }
+                    }
+                }
+                Some(Payload::UnsetEntityValues(entity)) => {
+                    let Ok(entity_id_bytes) = id::transform_id_bytes(entity.id.clone()) else {
+                        continue;
+                    };
+                    let property_id = Uuid::from_bytes(entity_id_bytes);
+
+                    for marker in &entity.properties {
+                        let Ok(marker_bytes) = id::transform_id_bytes(marker.clone()) else {
+                            continue;
+                        };
+
+                        match Uuid::from_bytes(marker_bytes) {
+                            DEPRECATED_PROPERTY => changes.push(PropertyLifecycleChange {
+                                id: property_id,
+                                deprecated: Some(false),
+                                replaced_by: None,
+                            }),
+                            REPLACED_BY_PROPERTY => changes.push(PropertyLifecycleChange {
+                                id: property_id,
+                                deprecated: None,
+                                replaced_by: Some(None),
+                            }),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Later ops in the edit override earlier ones for the same marker,
+        // while changes to different markers of a property are combined.
+        squash_lifecycle_changes(&changes)
+    }
+}
+
+fn squash_lifecycle_changes(
+    changes: &Vec<PropertyLifecycleChange>,
+) -> Vec<PropertyLifecycleChange> {
+    let mut hash: HashMap<Uuid, PropertyLifecycleChange> = HashMap::new();
+
+    for change in changes {
+        hash.entry(change.id)
+            .and_modify(|existing| existing.merge(change))
+            .or_insert_with(|| change.clone());
+    }
+
+    hash.into_values().collect()
+}
+
 fn squash_properties(properties: &Vec<PropertyItem>) -> Vec<PropertyItem> {
     let mut hash = HashMap::new();
 
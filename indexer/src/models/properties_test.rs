@@ -0,0 +1,140 @@
+use uuid::Uuid;
+
+use wire::pb::grc20::op::Payload;
+use wire::pb::grc20::{Edit, Entity, Op, UnsetEntityValues, Value};
+
+use super::properties::{
+    PropertiesModel, PropertyLifecycle, PropertyLifecycleChange, DEPRECATED_PROPERTY,
+    REPLACED_BY_PROPERTY,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROPERTY_ID: &str = "12345678-1234-4012-8def-123456789012";
+    const REPLACEMENT_ID: &str = "23456789-1234-4012-8def-123456789012";
+
+    // Helper function to create a byte vector from a UUID string
+    fn bytes(s: &str) -> Vec<u8> {
+        Uuid::parse_str(s).unwrap().as_bytes().to_vec()
+    }
+
+    fn update_entity(entity_id: &str, values: Vec<(Uuid, &str)>) -> Op {
+        Op {
+            payload: Some(Payload::UpdateEntity(Entity {
+                id: bytes(entity_id),
+                values: values
+                    .into_iter()
+                    .map(|(property, value)| Value {
+                        property: property.as_bytes().to_vec(),
+                        value: value.to_string(),
+                        options: None,
+                    })
+                    .collect(),
+            })),
+        }
+    }
+
+    fn unset_entity_values(entity_id: &str, properties: Vec<Uuid>) -> Op {
+        Op {
+            payload: Some(Payload::UnsetEntityValues(UnsetEntityValues {
+                id: bytes(entity_id),
+                properties: properties
+                    .into_iter()
+                    .map(|property| property.as_bytes().to_vec())
+                    .collect(),
+            })),
+        }
+    }
+
+    fn edit(ops: Vec<Op>) -> Edit {
+        Edit {
+            id: bytes("78901234-1234-4012-8def-123456789012"),
+            name: "test edit".to_string(),
+            ops,
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_map_edit_to_lifecycle_changes_deprecate_and_replace() {
+        let edit = edit(vec![update_entity(
+            PROPERTY_ID,
+            vec![
+                (DEPRECATED_PROPERTY, "1"),
+                (REPLACED_BY_PROPERTY, REPLACEMENT_ID),
+            ],
+        )]);
+
+        let changes = PropertiesModel::map_edit_to_lifecycle_changes(&edit);
+
+        assert_eq!(
+            changes,
+            vec![PropertyLifecycleChange {
+                id: Uuid::parse_str(PROPERTY_ID).unwrap(),
+                deprecated: Some(true),
+                replaced_by: Some(Some(Uuid::parse_str(REPLACEMENT_ID).unwrap())),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_map_edit_to_lifecycle_changes_later_ops_win() {
+        let edit = edit(vec![
+            update_entity(
+                PROPERTY_ID,
+                vec![
+                    (DEPRECATED_PROPERTY, "1"),
+                    (REPLACED_BY_PROPERTY, REPLACEMENT_ID),
+                ],
+            ),
+            unset_entity_values(PROPERTY_ID, vec![REPLACED_BY_PROPERTY]),
+        ]);
+
+        let changes = PropertiesModel::map_edit_to_lifecycle_changes(&edit);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].deprecated, Some(true));
+        assert_eq!(changes[0].replaced_by, Some(None));
+    }
+
+    #[test]
+    fn test_map_edit_to_lifecycle_changes_ignores_invalid_markers() {
+        let edit = edit(vec![update_entity(
+            PROPERTY_ID,
+            vec![
+                (DEPRECATED_PROPERTY, "yes"),
+                (REPLACED_BY_PROPERTY, "not a uuid"),
+                // A property can't replace itself
+                (REPLACED_BY_PROPERTY, PROPERTY_ID),
+                (Uuid::parse_str(REPLACEMENT_ID).unwrap(), "1"),
+            ],
+        )]);
+
+        let changes = PropertiesModel::map_edit_to_lifecycle_changes(&edit);
+
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_lifecycle_change_only_updates_set_fields() {
+        let replacement = Uuid::parse_str(REPLACEMENT_ID).unwrap();
+        let mut lifecycle = PropertyLifecycle {
+            id: Uuid::parse_str(PROPERTY_ID).unwrap(),
+            deprecated: true,
+            replaced_by: Some(replacement),
+        };
+
+        PropertyLifecycleChange {
+            id: lifecycle.id,
+            deprecated: Some(false),
+            replaced_by: None,
+        }
+        .apply(&mut lifecycle);
+
+        assert!(!lifecycle.deprecated);
+        assert_eq!(lifecycle.replaced_by, Some(replacement));
+    }
+}
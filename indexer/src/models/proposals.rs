@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+
+use indexer_utils::checksum_address;
+use stream::utils::BlockMetadata;
+
+use crate::{CastVote, CreatedProposal, ProposalStatus, ProposalStatusChange};
+
+#[derive(Clone, Debug)]
+pub struct ProposalItem {
+    pub plugin_address: String,
+    pub proposal_id: String,
+    pub creator: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub metadata_uri: String,
+    pub created_at_block: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProposalStatusItem {
+    pub plugin_address: String,
+    pub proposal_id: String,
+    pub status: ProposalStatus,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProposalVoteItem {
+    pub plugin_address: String,
+    pub proposal_id: String,
+    pub voter: String,
+    pub vote_option: i32,
+    /// Decimal uint256, stored as numeric
+    pub voting_power: String,
+    pub block_number: i64,
+    pub log_index: i64,
+    pub tx_hash: String,
+}
+
+pub struct ProposalModel;
+
+impl ProposalModel {
+    /// Maps created proposals from KgData to database-ready ProposalItem structs.
+    ///
+    /// Proposal IDs are only unique within a plugin, so a proposal is identified
+    /// by its plugin address and onchain ID.
+    pub fn map_created_proposals(
+        proposals: &[CreatedProposal],
+        block: &BlockMetadata,
+    ) -> Vec<ProposalItem> {
+        proposals
+            .iter()
+            .map(|proposal| ProposalItem {
+                plugin_address: checksum_address(proposal.plugin_address.clone()),
+                proposal_id: proposal.proposal_id.clone(),
+                creator: checksum_address(proposal.creator.clone()),
+                start_time: proposal.start_time.parse().unwrap_or_default(),
+                end_time: proposal.end_time.parse().unwrap_or_default(),
+                metadata_uri: proposal.metadata_uri.clone(),
+                created_at_block: block.block_number.to_string(),
+            })
+            .collect()
+    }
+
+    /// Maps proposal status changes from KgData to database-ready
+    /// ProposalStatusItem structs.
+    pub fn map_status_changes(changes: &[ProposalStatusChange]) -> Vec<ProposalStatusItem> {
+        changes
+            .iter()
+            .map(|change| ProposalStatusItem {
+                plugin_address: checksum_address(change.plugin_address.clone()),
+                proposal_id: change.proposal_id.clone(),
+                status: change.status,
+            })
+            .collect()
+    }
+
+    /// Maps cast votes from KgData to database-ready ProposalVoteItem structs.
+    ///
+    /// A voter only has one vote per proposal. When they replace it within
+    /// the block, the last vote is kept, in place of the first.
+    pub fn map_votes(votes: &[CastVote], block: &BlockMetadata) -> Vec<ProposalVoteItem> {
+        let mut items: Vec<ProposalVoteItem> = Vec::new();
+        let mut positions: HashMap<(String, String, String), usize> = HashMap::new();
+
+        for vote in votes {
+            let item = ProposalVoteItem {
+                plugin_address: checksum_address(vote.plugin_address.clone()),
+                proposal_id: vote.proposal_id.clone(),
+                voter: checksum_address(vote.voter.clone()),
+                vote_option: vote.vote_option as i32,
+                voting_power: vote.voting_power.clone(),
+                block_number: block.block_number as i64,
+                log_index: vote.context.log_index as i64,
+                tx_hash: vote.context.tx_hash.clone(),
+            };
+            let key = (
+                item.plugin_address.clone(),
+                item.proposal_id.clone(),
+                item.voter.clone(),
+            );
+
+            match positions.get(&key) {
+                Some(&position) => items[position] = item,
+                None => {
+                    positions.insert(key, items.len());
+                    items.push(item);
+                }
+            }
+        }
+
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventContext;
+
+    fn block() -> BlockMetadata {
+        BlockMetadata {
+            block_number: 42,
+            ..Default::default()
+        }
+    }
+
+    fn created_proposal(proposal_id: &str, start_time: &str) -> CreatedProposal {
+        CreatedProposal {
+            plugin_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
+            proposal_id: proposal_id.to_string(),
+            creator: "0xfedcba0987654321fedcba0987654321fedcba09".to_string(),
+            start_time: start_time.to_string(),
+            end_time: "1700086400".to_string(),
+            metadata_uri: "ipfs://QmProposal".to_string(),
+            context: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_map_created_proposals_empty() {
+        let result = ProposalModel::map_created_proposals(&[], &block());
+        assert_eq!(result.len(), 0);
+    }
+
+    #[test]
+    fn test_map_created_proposals() {
+        let block = block();
+        let proposals = vec![
+            created_proposal("1", "1700000000"),
+            created_proposal("2", "not a timestamp"),
+        ];
+
+        let result = ProposalModel::map_created_proposals(&proposals, &block);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(
+            result[0].plugin_address,
+            checksum_address("0x1234567890abcdef1234567890abcdef12345678")
+        );
+        assert_eq!(
+            result[0].creator,
+            checksum_address("0xfedcba0987654321fedcba0987654321fedcba09")
+        );
+        assert_eq!(result[0].proposal_id, "1");
+        assert_eq!(result[0].start_time, 1700000000);
+        assert_eq!(result[0].end_time, 1700086400);
+        assert_eq!(result[0].metadata_uri, "ipfs://QmProposal");
+        assert_eq!(result[0].created_at_block, "42");
+        // Invalid timestamps fall back to 0 rather than dropping the proposal
+        assert_eq!(result[1].start_time, 0);
+    }
+
+    #[test]
+    fn test_map_status_changes() {
+        let changes = vec![ProposalStatusChange {
+            plugin_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
+            proposal_id: "1".to_string(),
+            status: ProposalStatus::Canceled,
+            context: Default::default(),
+        }];
+
+        let result = ProposalModel::map_status_changes(&changes);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].plugin_address,
+            checksum_address("0x1234567890abcdef1234567890abcdef12345678")
+        );
+        assert_eq!(result[0].proposal_id, "1");
+        assert_eq!(result[0].status, ProposalStatus::Canceled);
+    }
+
+    fn cast_vote(voter: &str, vote_option: u64, voting_power: &str, log_index: u64) -> CastVote {
+        CastVote {
+            plugin_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
+            proposal_id: "1".to_string(),
+            voter: voter.to_string(),
+            vote_option,
+            voting_power: voting_power.to_string(),
+            context: EventContext {
+                tx_hash: "0xabc".to_string(),
+                log_index,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_map_votes() {
+        let votes = vec![cast_vote(
+            "0xfedcba0987654321fedcba0987654321fedcba09",
+            2,
+            "1000000000000000000000000",
+            3,
+        )];
+
+        let result = ProposalModel::map_votes(&votes, &block());
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].plugin_address,
+            checksum_address("0x1234567890abcdef1234567890abcdef12345678")
+        );
+        assert_eq!(
+            result[0].voter,
+            checksum_address("0xfedcba0987654321fedcba0987654321fedcba09")
+        );
+        assert_eq!(result[0].proposal_id, "1");
+        assert_eq!(result[0].vote_option, 2);
+        assert_eq!(result[0].voting_power, "1000000000000000000000000");
+        assert_eq!(result[0].block_number, 42);
+        assert_eq!(result[0].log_index, 3);
+        assert_eq!(result[0].tx_hash, "0xabc");
+    }
+
+    #[test]
+    fn test_map_votes_keeps_last_vote_of_voter() {
+        let votes = vec![
+            cast_vote("0xfedcba0987654321fedcba0987654321fedcba09", 2, "5", 1),
+            cast_vote("0x1111111111111111111111111111111111111111", 3, "7", 2),
+            cast_vote("0xfedcba0987654321fedcba0987654321fedcba09", 3, "5", 3),
+        ];
+
+        let result = ProposalModel::map_votes(&votes, &block());
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].vote_option, 3);
+        assert_eq!(result[0].log_index, 3);
+        assert_eq!(result[1].voting_power, "7");
+    }
+}
@@ -1,18 +1,17 @@
-use indexer_utils::{checksum_address, id::derive_space_id, network_ids::GEO};
-use uuid::Uuid;
+use indexer_utils::{
+    checksum_address, id::derive_space_id, network_ids::GEO, space_kind::SpaceKind,
+};
+use std::collections::HashMap;
+use stream::utils::BlockMetadata;
 
-use crate::CreatedSpace;
+use uuid::Uuid;
 
-#[derive(Clone, Debug)]
-pub enum SpaceType {
-    Personal,
-    Public,
-}
+use crate::{CreatedSpace, SuccessorSpace, UpdatedDaoMetadata};
 
 #[derive(Clone, Debug)]
 pub struct SpaceItem {
     pub id: Uuid,
-    pub space_type: SpaceType,
+    pub space_kind: SpaceKind,
     pub dao_address: String,
     pub space_address: String,
     pub voting_address: Option<String>,
@@ -20,6 +19,25 @@ pub struct SpaceItem {
     pub personal_address: Option<String>,
 }
 
+#[derive(Clone, Debug)]
+pub struct SpaceMetadataItem {
+    pub id: Uuid,
+    pub metadata_uri: String,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Link from a successor space to the space it was forked from. The
+/// predecessor is stored by its space plugin address and resolved to a space
+/// id when it's indexed, so successors of legacy spaces keep their lineage.
+#[derive(Clone, Debug)]
+pub struct SpaceLineageItem {
+    pub space_id: Uuid,
+    pub predecessor_space_address: String,
+    pub forked_at_block: String,
+    pub forked_in_tx: String,
+}
+
 pub struct SpacesModel;
 
 impl SpacesModel {
@@ -34,7 +52,7 @@ impl SpacesModel {
 
                     SpaceItem {
                         id: space_id,
-                        space_type: SpaceType::Personal,
+                        space_kind: space.kind(),
                         dao_address: checksum_address(personal.dao_address.clone()),
                         space_address: checksum_address(personal.space_address.clone()),
                         voting_address: None,
@@ -48,7 +66,7 @@ impl SpacesModel {
 
                     SpaceItem {
                         id: space_id,
-                        space_type: SpaceType::Public,
+                        space_kind: space.kind(),
                         dao_address: checksum_address(public.dao_address.clone()),
                         space_address: checksum_address(public.space_address.clone()),
                         voting_address: Some(checksum_address(public.governance_plugin.clone())),
@@ -65,4 +83,146 @@ impl SpacesModel {
 
         return created_spaces;
     }
+
+    /// Maps DAO metadata updates to the metadata of their spaces. A DAO can
+    /// set its metadata more than once in a block, so only the last update
+    /// per space is kept.
+    pub fn map_dao_metadata(metadata: &[UpdatedDaoMetadata]) -> Vec<SpaceMetadataItem> {
+        let mut latest: HashMap<Uuid, SpaceMetadataItem> = HashMap::new();
+        let mut order: Vec<Uuid> = Vec::new();
+
+        let mut updates: Vec<&UpdatedDaoMetadata> = metadata.iter().collect();
+        updates.sort_by_key(|update| update.context.log_index);
+
+        for update in updates {
+            let id = derive_space_id(GEO, &checksum_address(update.dao_address.clone()));
+            let item = SpaceMetadataItem {
+                id,
+                metadata_uri: update.metadata_uri.clone(),
+                name: update.name.clone(),
+                description: update.description.clone(),
+            };
+
+            if latest.insert(id, item).is_none() {
+                order.push(id);
+            }
+        }
+
+        order
+            .into_iter()
+            .filter_map(|id| latest.remove(&id))
+            .collect()
+    }
+
+    /// Maps successor spaces to the lineage of their spaces. Successors
+    /// reference the predecessor's content as of the fork block rather than
+    /// copying it, since relation ids are global and can't be duplicated
+    /// into another space.
+    pub fn map_successor_spaces(
+        spaces: &[SuccessorSpace],
+        block: &BlockMetadata,
+    ) -> Vec<SpaceLineageItem> {
+        spaces
+            .iter()
+            .map(|space| SpaceLineageItem {
+                space_id: derive_space_id(GEO, &checksum_address(space.dao_address.clone())),
+                predecessor_space_address: checksum_address(space.predecessor_space.clone()),
+                forked_at_block: block.block_number.to_string(),
+                forked_in_tx: space.context.tx_hash.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Value of the legacy `type` column, which predates successor spaces and
+/// only distinguishes personal from public (DAO-governed) spaces.
+pub fn legacy_space_type(kind: SpaceKind) -> &'static str {
+    if kind.is_governed() {
+        "Public"
+    } else {
+        "Personal"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventContext;
+
+    fn metadata_update(dao_address: &str, name: &str, log_index: u64) -> UpdatedDaoMetadata {
+        UpdatedDaoMetadata {
+            dao_address: dao_address.to_string(),
+            metadata_uri: format!("ipfs://Qm{name}"),
+            name: Some(name.to_string()),
+            description: None,
+            context: EventContext {
+                log_index,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_map_dao_metadata() {
+        let dao = "0x1234567890abcdef1234567890abcdef12345678";
+        let metadata = vec![metadata_update(dao, "Geo", 1)];
+
+        let result = SpacesModel::map_dao_metadata(&metadata);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id, derive_space_id(GEO, &checksum_address(dao)));
+        assert_eq!(result[0].metadata_uri, "ipfs://QmGeo");
+        assert_eq!(result[0].name, Some("Geo".to_string()));
+        assert_eq!(result[0].description, None);
+    }
+
+    #[test]
+    fn test_map_dao_metadata_keeps_last_update_per_space() {
+        let dao = "0x1234567890abcdef1234567890abcdef12345678";
+        let other_dao = "0xfedcba0987654321fedcba0987654321fedcba09";
+        let metadata = vec![
+            metadata_update(dao, "Renamed", 7),
+            metadata_update(other_dao, "Other", 4),
+            metadata_update(dao, "Original", 2),
+        ];
+
+        let result = SpacesModel::map_dao_metadata(&metadata);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].name, Some("Renamed".to_string()));
+        assert_eq!(result[1].name, Some("Other".to_string()));
+    }
+
+    #[test]
+    fn test_map_successor_spaces() {
+        let dao = "0x1234567890abcdef1234567890abcdef12345678";
+        let predecessor = "0xfedcba0987654321fedcba0987654321fedcba09";
+        let spaces = vec![SuccessorSpace {
+            dao_address: dao.to_string(),
+            space_address: "0xaabbccddee112233aabbccddee112233aabbccdd".to_string(),
+            predecessor_space: predecessor.to_string(),
+            context: EventContext {
+                tx_hash: "0xabc".to_string(),
+                ..Default::default()
+            },
+        }];
+        let block = BlockMetadata {
+            block_number: 42,
+            ..Default::default()
+        };
+
+        let result = SpacesModel::map_successor_spaces(&spaces, &block);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].space_id,
+            derive_space_id(GEO, &checksum_address(dao))
+        );
+        assert_eq!(
+            result[0].predecessor_space_address,
+            checksum_address(predecessor)
+        );
+        assert_eq!(result[0].forked_at_block, "42");
+        assert_eq!(result[0].forked_in_tx, "0xabc");
+    }
 }
@@ -68,10 +68,12 @@ mod tests {
             AddedSubspace {
                 dao_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
                 subspace_address: "0xfedcba0987654321fedcba0987654321fedcba09".to_string(),
+                context: Default::default(),
             },
             AddedSubspace {
                 dao_address: "0xaabbccddee112233aabbccddee112233aabbccdd".to_string(),
                 subspace_address: "0x9988776655443322998877665544332299887766".to_string(),
+                context: Default::default(),
             },
         ];
 
@@ -91,6 +93,7 @@ mod tests {
         let removed_subspaces = vec![RemovedSubspace {
             dao_address: "0x1234567890abcdef1234567890abcdef12345678".to_string(),
             subspace_address: "0xfedcba0987654321fedcba0987654321fedcba09".to_string(),
+            context: Default::default(),
         }];
 
         let result = SubspaceModel::map_removed_subspaces(&removed_subspaces);
@@ -1,5 +1,5 @@
 use futures::future::join_all;
-use indexer_utils::get_blocklist;
+use indexer_utils::{get_blocklist, network_ids::GEO};
 use prost::Message;
 use std::{collections::HashSet, sync::Arc};
 use stream::pb::sf::substreams::rpc::v2::BlockScopedData;
@@ -12,11 +12,26 @@ use tracing::{debug, info, instrument, warn};
 use wire::pb::chain::GeoOutput;
 
 use crate::{
-    cache::{postgres::PostgresCache, CacheBackend, PreprocessedEdit},
+    cache::{CacheBackend, PreprocessedEdit},
     error::IndexingError,
-    AddedMember, AddedSubspace, CreatedSpace, KgData, PersonalSpace, PublicSpace, RemovedSubspace,
+    AddedMember, AddedSubspace, CastVote, CreatedProposal, CreatedSpace, EventContext, KgData,
+    PersonalSpace, ProposalStatus, ProposalStatusChange, PublicSpace, RemovedSubspace,
+    SuccessorSpace, UpdatedDaoMetadata, UpdatedGovernanceSettings,
 };
 
+/// Maps an event's block and transaction context. Events without a context
+/// get the default, empty one.
+pub fn map_event_context(context: Option<&wire::pb::chain::EventContext>) -> EventContext {
+    context
+        .map(|context| EventContext {
+            tx_hash: context.tx_hash.clone(),
+            log_index: context.log_index,
+            block_number: context.block_number,
+            block_timestamp: context.block_timestamp,
+        })
+        .unwrap_or_default()
+}
+
 /// Matches spaces with their corresponding plugins based on DAO address
 /// Returns a vector of CreatedSpace variants (Public or Personal)
 #[instrument(skip_all, fields(space_count = spaces.len(), governance_plugin_count = governance_plugins.len(), personal_plugin_count = personal_plugins.len()))]
@@ -39,6 +54,9 @@ pub fn match_spaces_with_plugins(
                 space_address: space.space_address.clone(),
                 membership_plugin: governance_plugin.member_access_address.clone(),
                 governance_plugin: governance_plugin.main_voting_address.clone(),
+                initial_editors: governance_plugin.initial_editors.clone(),
+                initial_members: governance_plugin.initial_members.clone(),
+                context: map_event_context(space.context.as_ref()),
             }));
         }
         // Otherwise, try to find a matching personal plugin (for personal spaces)
@@ -50,6 +68,9 @@ pub fn match_spaces_with_plugins(
                 dao_address: space.dao_address.clone(),
                 space_address: space.space_address.clone(),
                 personal_plugin: personal_plugin.personal_admin_address.clone(),
+                initial_editors: personal_plugin.initial_editors.clone(),
+                initial_members: personal_plugin.initial_members.clone(),
+                context: map_event_context(space.context.as_ref()),
             }));
         }
         // If no matching plugin is found, we skip this space
@@ -82,6 +103,7 @@ pub fn map_editors_added(editors: &[wire::pb::chain::EditorAdded]) -> Vec<AddedM
         .map(|e| AddedMember {
             dao_address: e.dao_address.clone(),
             editor_address: e.editor_address.clone(),
+            context: map_event_context(e.context.as_ref()),
         })
         .collect()
 }
@@ -96,6 +118,7 @@ pub fn map_initial_editors_added(
             e.addresses.iter().map(|address| AddedMember {
                 dao_address: e.dao_address.clone(),
                 editor_address: address.clone(),
+                context: map_event_context(e.context.as_ref()),
             })
         })
         .collect()
@@ -108,6 +131,22 @@ pub fn map_members_added(members: &[wire::pb::chain::MemberAdded]) -> Vec<AddedM
         .map(|e| AddedMember {
             dao_address: e.dao_address.clone(),
             editor_address: e.member_address.clone(),
+            context: map_event_context(e.context.as_ref()),
+        })
+        .collect()
+}
+
+/// Maps successor space created events to SuccessorSpace structs
+pub fn map_successor_spaces_created(
+    spaces: &[wire::pb::chain::SuccessorSpaceCreated],
+) -> Vec<SuccessorSpace> {
+    spaces
+        .iter()
+        .map(|s| SuccessorSpace {
+            dao_address: s.dao_address.clone(),
+            space_address: s.plugin_address.clone(),
+            predecessor_space: s.predecessor_space.clone(),
+            context: map_event_context(s.context.as_ref()),
         })
         .collect()
 }
@@ -119,6 +158,7 @@ pub fn map_subspaces_added(subspaces: &[wire::pb::chain::SubspaceAdded]) -> Vec<
         .map(|s| AddedSubspace {
             dao_address: s.dao_address.clone(),
             subspace_address: s.subspace.clone(),
+            context: map_event_context(s.context.as_ref()),
         })
         .collect()
 }
@@ -132,21 +172,177 @@ pub fn map_subspaces_removed(
         .map(|s| RemovedSubspace {
             dao_address: s.dao_address.clone(),
             subspace_address: s.subspace.clone(),
+            context: map_event_context(s.context.as_ref()),
+        })
+        .collect()
+}
+
+/// Maps proposal created events to CreatedProposal structs
+pub fn map_proposals_created(
+    proposals: &[wire::pb::chain::ProposalCreated],
+) -> Vec<CreatedProposal> {
+    proposals
+        .iter()
+        .map(|p| CreatedProposal {
+            plugin_address: p.plugin_address.clone(),
+            proposal_id: p.proposal_id.clone(),
+            creator: p.creator.clone(),
+            start_time: p.start_time.clone(),
+            end_time: p.end_time.clone(),
+            metadata_uri: p.metadata_uri.clone(),
+            context: map_event_context(p.context.as_ref()),
+        })
+        .collect()
+}
+
+/// Maps proposal executed and canceled events to ProposalStatusChange
+/// structs, in that order
+pub fn map_proposal_status_changes(
+    executed: &[wire::pb::chain::ProposalExecuted],
+    canceled: &[wire::pb::chain::ProposalCanceled],
+) -> Vec<ProposalStatusChange> {
+    let executed = executed.iter().map(|p| ProposalStatusChange {
+        plugin_address: p.plugin_address.clone(),
+        proposal_id: p.proposal_id.clone(),
+        status: ProposalStatus::Executed,
+        context: map_event_context(p.context.as_ref()),
+    });
+    let canceled = canceled.iter().map(|p| ProposalStatusChange {
+        plugin_address: p.plugin_address.clone(),
+        proposal_id: p.proposal_id.clone(),
+        status: ProposalStatus::Canceled,
+        context: map_event_context(p.context.as_ref()),
+    });
+
+    executed.chain(canceled).collect()
+}
+
+/// Maps vote cast events to CastVote structs
+pub fn map_votes_cast(votes: &[wire::pb::chain::VoteCast]) -> Vec<CastVote> {
+    votes
+        .iter()
+        .map(|v| CastVote {
+            plugin_address: v.plugin_address.clone(),
+            proposal_id: v.onchain_proposal_id.clone(),
+            voter: v.voter.clone(),
+            vote_option: v.vote_option,
+            voting_power: v.voting_power.clone(),
+            context: map_event_context(v.context.as_ref()),
+        })
+        .collect()
+}
+
+/// Maps voting and member access settings events to UpdatedGovernanceSettings
+/// structs, in that order
+pub fn map_governance_settings_updated(
+    voting_settings: &[wire::pb::chain::VotingSettingsUpdated],
+    member_access_settings: &[wire::pb::chain::MemberAccessSettingsUpdated],
+) -> Vec<UpdatedGovernanceSettings> {
+    let voting = voting_settings
+        .iter()
+        .map(|s| UpdatedGovernanceSettings {
+            plugin_address: s.plugin_address.clone(),
+            voting_mode: Some(s.voting_mode),
+            support_threshold: Some(s.support_threshold),
+            duration: s.duration,
+            context: map_event_context(s.context.as_ref()),
+        });
+    let member_access = member_access_settings
+        .iter()
+        .map(|s| UpdatedGovernanceSettings {
+            plugin_address: s.plugin_address.clone(),
+            voting_mode: None,
+            support_threshold: None,
+            duration: s.proposal_duration,
+            context: map_event_context(s.context.as_ref()),
+        });
+
+    voting.chain(member_access).collect()
+}
+
+/// Maps DAO metadata events to UpdatedDaoMetadata structs. The name and
+/// description are filled in from the cache by [`fetch_dao_metadata`].
+pub fn map_dao_metadata_set(metadata: &[wire::pb::chain::DaoMetadataSet]) -> Vec<UpdatedDaoMetadata> {
+    metadata
+        .iter()
+        .map(|m| UpdatedDaoMetadata {
+            dao_address: m.dao_address.clone(),
+            metadata_uri: m.metadata_uri.clone(),
+            name: None,
+            description: None,
+            context: map_event_context(m.context.as_ref()),
         })
         .collect()
 }
 
+/// Reads the metadata documents of DAO metadata updates from the cache,
+/// which fetches them from IPFS alongside edits. Updates from blocklisted
+/// DAOs are dropped.
+pub async fn fetch_dao_metadata<C>(
+    updates: Vec<UpdatedDaoMetadata>,
+    cache: &Arc<C>,
+) -> Vec<UpdatedDaoMetadata>
+where
+    C: CacheBackend + 'static,
+{
+    let fetches = updates
+        .into_iter()
+        .filter(|update| {
+            !get_blocklist()
+                .dao_addresses
+                .contains(&update.dao_address.as_str())
+        })
+        .map(|mut update| async move {
+            // Retried like edits, in case the cache is still populating
+            let retry = ExponentialBackoff::from_millis(10)
+                .factor(2)
+                .max_delay(std::time::Duration::from_secs(5))
+                .map(jitter);
+
+            match Retry::spawn(retry, async || cache.get_dao_metadata(&update.metadata_uri).await)
+                .await
+            {
+                Ok(Some(metadata)) => {
+                    update.name = metadata.name;
+                    update.description = metadata.description;
+                }
+                Ok(None) => {
+                    warn!(
+                        dao_address = %update.dao_address,
+                        metadata_uri = %update.metadata_uri,
+                        "Cached DAO metadata entry is errored"
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        dao_address = %update.dao_address,
+                        metadata_uri = %update.metadata_uri,
+                        error = %e,
+                        "Failed to fetch DAO metadata from cache after retries"
+                    );
+                }
+            }
+
+            update
+        });
+
+    join_all(fetches).await
+}
+
 /// Preprocesses block scoped data from the substream
 #[instrument(skip_all, fields(
     block_number = block_data.clock.as_ref().map(|c| c.number).unwrap_or(0),
     block_timestamp = block_data.clock.as_ref().and_then(|c| c.timestamp.as_ref()).map(|t| t.seconds).unwrap_or(0)
 ))]
-pub async fn preprocess_block_scoped_data(
+pub async fn preprocess_block_scoped_data<C>(
     block_data: &BlockScopedData,
-    ipfs_cache: &Arc<PostgresCache>,
-) -> Result<KgData, IndexingError> {
+    ipfs_cache: &Arc<C>,
+) -> Result<KgData, IndexingError>
+where
+    C: CacheBackend + 'static,
+{
     let output = stream::utils::output(block_data);
-    let block_metadata = stream::utils::block_metadata(block_data);
+    let block_metadata = stream::utils::block_metadata(block_data).with_chain_id(GEO);
     let geo = GeoOutput::decode(output.value.as_slice())?;
     let cache = ipfs_cache;
     let edits = Arc::new(Mutex::new(Vec::<PreprocessedEdit>::new()));
@@ -259,6 +455,24 @@ pub async fn preprocess_block_scoped_data(
     let initial_editors = map_initial_editors_added(&geo.initial_editors_added);
     added_editors.extend(initial_editors.clone());
 
+    // Created spaces carry the editors their plugins were installed with, so
+    // edits in the same block are authorized even if the separate editor
+    // events were missed
+    for space in &created_spaces {
+        for editor in space.initial_editors() {
+            let is_known = added_editors.iter().any(|added| {
+                added.dao_address == space.dao_address() && &added.editor_address == editor
+            });
+            if !is_known {
+                added_editors.push(AddedMember {
+                    dao_address: space.dao_address().to_string(),
+                    editor_address: editor.clone(),
+                    context: space.context().clone(),
+                });
+            }
+        }
+    }
+
     let mut added_members = map_members_added(&geo.members_added);
 
     // If any added editors come from a space created at the same time, add
@@ -276,32 +490,56 @@ pub async fn preprocess_block_scoped_data(
             added_members.push(AddedMember {
                 dao_address: editor.dao_address.clone(),
                 editor_address: editor.editor_address.clone(),
+                context: editor.context.clone(),
             });
         }
     }
 
+    let successor_spaces = map_successor_spaces_created(&geo.successor_spaces_created);
     let added_subspaces = map_subspaces_added(&geo.subspaces_added);
     let removed_subspaces = map_subspaces_removed(&geo.subspaces_removed);
+    let proposals = map_proposals_created(&geo.proposals_created);
+    let proposal_status_changes =
+        map_proposal_status_changes(&geo.executed_proposals, &geo.canceled_proposals);
+    let votes = map_votes_cast(&geo.votes_cast);
+    let governance_settings = map_governance_settings_updated(
+        &geo.voting_settings_updated,
+        &geo.member_access_settings_updated,
+    );
+    let dao_metadata =
+        fetch_dao_metadata(map_dao_metadata_set(&geo.dao_metadata_set), cache).await;
 
     let kg_data = KgData {
         edits: final_edits.clone(),
         spaces: created_spaces.clone(),
+        successor_spaces: successor_spaces.clone(),
         added_editors: added_editors.clone(),
         added_members: added_members.clone(),
         removed_editors: vec![],
         removed_members: vec![],
         added_subspaces: added_subspaces.clone(),
         removed_subspaces: removed_subspaces.clone(),
+        proposals,
+        proposal_status_changes,
+        votes,
+        governance_settings,
+        dao_metadata,
         block: block_metadata,
     };
 
     info!(
         edit_count = kg_data.edits.len(),
         space_count = kg_data.spaces.len(),
+        successor_space_count = kg_data.successor_spaces.len(),
         editor_count = kg_data.added_editors.len(),
         member_count = kg_data.added_members.len(),
         subspace_added_count = kg_data.added_subspaces.len(),
         subspace_removed_count = kg_data.removed_subspaces.len(),
+        proposal_count = kg_data.proposals.len(),
+        proposal_status_change_count = kg_data.proposal_status_changes.len(),
+        vote_count = kg_data.votes.len(),
+        governance_settings_count = kg_data.governance_settings.len(),
+        dao_metadata_count = kg_data.dao_metadata.len(),
         "Preprocessed block data"
     );
 
@@ -319,6 +557,7 @@ mod tests {
         GeoSpaceCreated {
             dao_address: dao_address.to_string(),
             space_address: space_address.to_string(),
+            context: None,
         }
     }
 
@@ -331,6 +570,9 @@ mod tests {
             dao_address: dao_address.to_string(),
             main_voting_address: main_voting_address.to_string(),
             member_access_address: member_access_address.to_string(),
+            context: None,
+            initial_editors: vec![],
+            initial_members: vec![],
         }
     }
 
@@ -343,6 +585,9 @@ mod tests {
             dao_address: dao_address.to_string(),
             personal_admin_address: personal_admin_address.to_string(),
             initial_editor: initial_editor.to_string(),
+            context: None,
+            initial_editors: vec![initial_editor.to_string()],
+            initial_members: vec![],
         }
     }
 
@@ -355,6 +600,7 @@ mod tests {
             editor_address: editor_address.to_string(),
             main_voting_plugin_address: "voting_plugin".to_string(),
             change_type: "0".to_string(),
+            context: None,
         }
     }
 
@@ -366,6 +612,7 @@ mod tests {
             dao_address: dao_address.to_string(),
             addresses: addresses.into_iter().map(|s| s.to_string()).collect(),
             plugin_address: "plugin".to_string(),
+            context: None,
         }
     }
 
@@ -378,6 +625,7 @@ mod tests {
             member_address: member_address.to_string(),
             main_voting_plugin_address: "voting_plugin".to_string(),
             change_type: "0".to_string(),
+            context: None,
         }
     }
 
@@ -390,6 +638,7 @@ mod tests {
             subspace: subspace.to_string(),
             plugin_address: "plugin".to_string(),
             change_type: "0".to_string(),
+            context: None,
         }
     }
 
@@ -402,6 +651,7 @@ mod tests {
             subspace: subspace.to_string(),
             plugin_address: "plugin".to_string(),
             change_type: "0".to_string(),
+            context: None,
         }
     }
 
@@ -439,11 +689,26 @@ mod tests {
                 assert_eq!(personal_space.dao_address, "dao2");
                 assert_eq!(personal_space.space_address, "space2");
                 assert_eq!(personal_space.personal_plugin, "admin2");
+                assert_eq!(personal_space.initial_editors, vec!["editor2".to_string()]);
             }
             CreatedSpace::Public(_) => panic!("Expected personal space, got public space"),
         }
     }
 
+    #[test]
+    fn test_match_public_space_with_initial_editors() {
+        let spaces = vec![create_test_space("dao1", "space1")];
+        let mut governance_plugin = create_test_governance_plugin("dao1", "voting1", "member1");
+        governance_plugin.initial_editors = vec!["editor1".to_string()];
+        governance_plugin.initial_members = vec!["member2".to_string()];
+
+        let result = match_spaces_with_plugins(&spaces, &[governance_plugin], &[]);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].initial_editors(), ["editor1".to_string()]);
+        assert_eq!(result[0].initial_members(), ["member2".to_string()]);
+    }
+
     #[test]
     fn test_space_with_no_matching_plugin() {
         let spaces = vec![create_test_space("dao3", "space3")];
@@ -547,6 +812,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_map_event_context() {
+        let context = wire::pb::chain::EventContext {
+            tx_hash: "0xabc".to_string(),
+            log_index: 7,
+            block_number: 100,
+            block_timestamp: 1700000000,
+        };
+        let editors = vec![wire::pb::chain::EditorAdded {
+            context: Some(context),
+            ..create_test_editor_added("dao1", "editor1")
+        }];
+
+        let result = map_editors_added(&editors);
+
+        assert_eq!(
+            result[0].context,
+            EventContext {
+                tx_hash: "0xabc".to_string(),
+                log_index: 7,
+                block_number: 100,
+                block_timestamp: 1700000000,
+            }
+        );
+        // Events from packages that don't emit a context get an empty one
+        assert_eq!(map_event_context(None), EventContext::default());
+    }
+
     #[test]
     fn test_map_editors_added_empty() {
         let editors = vec![];
@@ -763,6 +1056,7 @@ mod tests {
                 added_members.push(AddedMember {
                     dao_address: editor.dao_address.clone(),
                     editor_address: editor.editor_address.clone(),
+                    context: Default::default(),
                 });
             }
         }
@@ -868,4 +1162,168 @@ mod tests {
         assert_eq!(result[2].dao_address, "dao1");
         assert_eq!(result[2].subspace_address, "subspace3");
     }
+
+    #[test]
+    fn test_map_proposals_created() {
+        let proposals = vec![wire::pb::chain::ProposalCreated {
+            proposal_id: "7".to_string(),
+            creator: "creator1".to_string(),
+            start_time: "1700000000".to_string(),
+            end_time: "1700086400".to_string(),
+            metadata_uri: "ipfs://QmProposal".to_string(),
+            plugin_address: "plugin1".to_string(),
+            context: None,
+        }];
+        let result = map_proposals_created(&proposals);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].plugin_address, "plugin1");
+        assert_eq!(result[0].proposal_id, "7");
+        assert_eq!(result[0].creator, "creator1");
+        assert_eq!(result[0].start_time, "1700000000");
+        assert_eq!(result[0].end_time, "1700086400");
+        assert_eq!(result[0].metadata_uri, "ipfs://QmProposal");
+    }
+
+    #[test]
+    fn test_map_governance_settings_updated() {
+        let voting_settings = vec![wire::pb::chain::VotingSettingsUpdated {
+            plugin_address: "voting_plugin".to_string(),
+            voting_mode: 1,
+            support_threshold: 500_000,
+            duration: 86400,
+            context: None,
+        }];
+        let member_access_settings = vec![wire::pb::chain::MemberAccessSettingsUpdated {
+            plugin_address: "member_access_plugin".to_string(),
+            proposal_duration: 3600,
+            context: Some(wire::pb::chain::EventContext {
+                tx_hash: "0xabc".to_string(),
+                log_index: 2,
+                block_number: 100,
+                block_timestamp: 1700000000,
+            }),
+        }];
+        let result = map_governance_settings_updated(&voting_settings, &member_access_settings);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].plugin_address, "voting_plugin");
+        assert_eq!(result[0].voting_mode, Some(1));
+        assert_eq!(result[0].support_threshold, Some(500_000));
+        assert_eq!(result[0].duration, 86400);
+        assert_eq!(result[1].plugin_address, "member_access_plugin");
+        assert_eq!(result[1].voting_mode, None);
+        assert_eq!(result[1].support_threshold, None);
+        assert_eq!(result[1].duration, 3600);
+        assert_eq!(result[1].context.log_index, 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_dao_metadata() {
+        use crate::cache::{CacheError, DaoMetadata};
+
+        struct MetadataCache;
+
+        #[async_trait::async_trait]
+        impl CacheBackend for MetadataCache {
+            async fn get(&self, _uri: &String) -> Result<PreprocessedEdit, CacheError> {
+                Err(CacheError::NotFound)
+            }
+
+            async fn get_dao_metadata(
+                &self,
+                uri: &String,
+            ) -> Result<Option<DaoMetadata>, CacheError> {
+                Ok((uri == "ipfs://QmNamed").then(|| DaoMetadata {
+                    name: Some("Geo".to_string()),
+                    description: Some("The Geo space".to_string()),
+                }))
+            }
+        }
+
+        let metadata = vec![
+            wire::pb::chain::DaoMetadataSet {
+                dao_address: "dao1".to_string(),
+                metadata_uri: "ipfs://QmNamed".to_string(),
+                context: None,
+            },
+            wire::pb::chain::DaoMetadataSet {
+                dao_address: "dao2".to_string(),
+                metadata_uri: "ipfs://QmErrored".to_string(),
+                context: None,
+            },
+        ];
+        let result =
+            fetch_dao_metadata(map_dao_metadata_set(&metadata), &Arc::new(MetadataCache)).await;
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].dao_address, "dao1");
+        assert_eq!(result[0].name, Some("Geo".to_string()));
+        assert_eq!(result[0].description, Some("The Geo space".to_string()));
+        // Errored documents still update the metadata URI, without a name
+        assert_eq!(result[1].metadata_uri, "ipfs://QmErrored");
+        assert_eq!(result[1].name, None);
+    }
+
+    #[test]
+    fn test_map_proposal_status_changes() {
+        let executed = vec![wire::pb::chain::ProposalExecuted {
+            proposal_id: "7".to_string(),
+            plugin_address: "plugin1".to_string(),
+            context: None,
+        }];
+        let canceled = vec![wire::pb::chain::ProposalCanceled {
+            proposal_id: "8".to_string(),
+            plugin_address: "plugin1".to_string(),
+            context: None,
+        }];
+        let result = map_proposal_status_changes(&executed, &canceled);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].proposal_id, "7");
+        assert_eq!(result[0].status, ProposalStatus::Executed);
+        assert_eq!(result[1].proposal_id, "8");
+        assert_eq!(result[1].status, ProposalStatus::Canceled);
+    }
+
+    #[test]
+    fn test_map_votes_cast() {
+        let votes = vec![wire::pb::chain::VoteCast {
+            onchain_proposal_id: "7".to_string(),
+            voter: "voter1".to_string(),
+            vote_option: 2,
+            plugin_address: "plugin1".to_string(),
+            context: None,
+            voting_power: "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+                .to_string(),
+        }];
+        let result = map_votes_cast(&votes);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].plugin_address, "plugin1");
+        assert_eq!(result[0].proposal_id, "7");
+        assert_eq!(result[0].voter, "voter1");
+        assert_eq!(result[0].vote_option, 2);
+        // Voting power is a uint256, so it's kept as a decimal string
+        assert_eq!(
+            result[0].voting_power,
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+    }
+
+    #[test]
+    fn test_map_successor_spaces_created() {
+        let spaces = vec![wire::pb::chain::SuccessorSpaceCreated {
+            predecessor_space: "predecessor1".to_string(),
+            plugin_address: "space1".to_string(),
+            dao_address: "dao1".to_string(),
+            context: None,
+        }];
+        let result = map_successor_spaces_created(&spaces);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].dao_address, "dao1");
+        assert_eq!(result[0].space_address, "space1");
+        assert_eq!(result[0].predecessor_space, "predecessor1");
+    }
 }
@@ -0,0 +1,551 @@
+//! Replay determinism checks.
+//!
+//! Reorg handling and sharded backfills both assume that processing the same
+//! blocks always writes the same rows. A determinism check streams a block
+//! range once with a [`ReplayRecorder`], replays the recorded blocks into two
+//! fresh databases with [`replay`] and diffs their knowledge graph tables with
+//! [`compare`]. Any difference means a handler depends on something other than
+//! the blocks it's given, e.g. iteration order, random UUIDs or the wall clock.
+//!
+//! Both databases must be migrated and empty, since their tables are compared
+//! as a whole. Edits are read from the IPFS cache as in the live indexer.
+
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use sqlx::{Pool, Postgres};
+use stream::{pb::sf::substreams::rpc::v2::BlockScopedData, PreprocessedSink};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::{
+    backfill::StagedBlock,
+    block_handler::root_handler,
+    cache::{properties_cache::PropertiesCache, CacheBackend},
+    error::IndexingError,
+    preprocess,
+    storage::{postgres::PostgresStorage, StorageBackend},
+    validators::{AuthorizationMode, EditAuthorValidator},
+};
+
+/// Tables written by the block handlers, in the order they're compared.
+pub const REPLAY_TABLES: &[&str] = &[
+    "spaces",
+    "entities",
+    "properties",
+    "values",
+    "relations",
+    "members",
+    "editors",
+    "subspaces",
+    "space_lineage",
+    "proposals",
+    "proposal_votes",
+    "governance_settings",
+];
+
+/// Rows reported per table and database when tables differ.
+pub const MAX_REPORTED_ROWS: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("Replay database isn't empty, table {table} has rows")]
+    NotEmpty { table: String },
+
+    #[error("Replays differ in {tables} tables")]
+    Nondeterministic { tables: usize },
+
+    #[error("Replay stream error: {0}")]
+    Stream(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayConfig {
+    pub start_block: i64,
+    /// Exclusive end of the replayed range.
+    pub end_block: u64,
+    /// The two databases the range is replayed into.
+    pub database_urls: [String; 2],
+}
+
+impl ReplayConfig {
+    /// Reads the replay configuration, or `None` if REPLAY_DATABASE_URLS
+    /// isn't set.
+    ///
+    /// - REPLAY_DATABASE_URLS: two comma-separated database URLs
+    /// - REPLAY_START_BLOCK: first block of the range, required
+    /// - REPLAY_END_BLOCK: exclusive end of the range, required
+    pub fn from_env() -> Option<Self> {
+        let database_urls = env::var("REPLAY_DATABASE_URLS").ok()?;
+        let database_urls: Vec<String> = database_urls
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let database_urls: [String; 2] = database_urls
+            .try_into()
+            .expect("REPLAY_DATABASE_URLS must be two comma-separated database URLs");
+
+        let start_block = env::var("REPLAY_START_BLOCK")
+            .expect("REPLAY_START_BLOCK must be set for a replay check")
+            .parse::<i64>()
+            .expect("REPLAY_START_BLOCK must be a block number");
+
+        let end_block = env::var("REPLAY_END_BLOCK")
+            .expect("REPLAY_END_BLOCK must be set for a replay check")
+            .parse::<u64>()
+            .expect("REPLAY_END_BLOCK must be a block number");
+
+        Some(ReplayConfig {
+            start_block,
+            end_block,
+            database_urls,
+        })
+    }
+}
+
+/// Sink that records the map output of every non-empty block it streams.
+#[derive(Debug, Default)]
+pub struct ReplayRecorder {
+    blocks: Mutex<Vec<StagedBlock>>,
+}
+
+impl ReplayRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The blocks recorded so far, in stream order.
+    pub fn blocks(&self) -> Vec<StagedBlock> {
+        self.blocks.lock().unwrap().clone()
+    }
+}
+
+impl PreprocessedSink<Option<StagedBlock>> for ReplayRecorder {
+    type Error = IndexingError;
+
+    /// Recordings always start from the requested start block.
+    async fn load_persisted_cursor(&self) -> Result<Option<String>, Self::Error> {
+        Ok(None)
+    }
+
+    async fn persist_cursor(&self, _cursor: String, _block: u64) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn preprocess_block_scoped_data(
+        &self,
+        block_data: &BlockScopedData,
+    ) -> Result<Option<StagedBlock>, Self::Error> {
+        Ok(StagedBlock::from_block_scoped_data(block_data))
+    }
+
+    async fn process_block_scoped_data(
+        &self,
+        _block_data: &BlockScopedData,
+        block: Option<StagedBlock>,
+    ) -> Result<(), Self::Error> {
+        if let Some(block) = block {
+            self.blocks.lock().unwrap().push(block);
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs recorded blocks through preprocessing and the block handlers, in
+/// order, against `storage`. The properties cache starts empty, as it would
+/// for a fresh database.
+pub async fn replay<S, C>(
+    blocks: &[StagedBlock],
+    storage: &Arc<S>,
+    ipfs_cache: &Arc<C>,
+    authorization_mode: AuthorizationMode,
+) -> Result<(), IndexingError>
+where
+    S: StorageBackend + Send + Sync + 'static,
+    C: CacheBackend + 'static,
+{
+    let properties_cache = Arc::new(PropertiesCache::new());
+    let author_validator = Arc::new(EditAuthorValidator::new(authorization_mode));
+
+    for block in blocks {
+        let block_data = block.to_block_scoped_data();
+        let kg_data = preprocess::preprocess_block_scoped_data(&block_data, ipfs_cache).await?;
+
+        root_handler::run(
+            &kg_data,
+            &kg_data.block,
+            storage,
+            &properties_cache,
+            &author_validator,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Fails if any of the replayed tables already has rows.
+pub async fn ensure_empty(pool: &Pool<Postgres>) -> Result<(), IndexingError> {
+    for table in REPLAY_TABLES {
+        let has_rows: bool =
+            sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM \"{}\")", table))
+                .fetch_one(pool)
+                .await?;
+
+        if has_rows {
+            return Err(ReplayError::NotEmpty {
+                table: table.to_string(),
+            }
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Every row of a table as JSON, sorted so equal tables have equal
+/// snapshots regardless of how their rows are stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableSnapshot {
+    pub table: String,
+    pub rows: Vec<String>,
+}
+
+impl TableSnapshot {
+    pub fn new(table: &str, mut rows: Vec<String>) -> Self {
+        rows.sort();
+
+        TableSnapshot {
+            table: table.to_string(),
+            rows,
+        }
+    }
+}
+
+/// Reads a snapshot of every replayed table.
+pub async fn snapshot(pool: &Pool<Postgres>) -> Result<Vec<TableSnapshot>, IndexingError> {
+    let mut snapshots = Vec::with_capacity(REPLAY_TABLES.len());
+
+    for table in REPLAY_TABLES {
+        let rows: Vec<String> =
+            sqlx::query_scalar(&format!("SELECT row_to_json(t)::text FROM \"{}\" t", table))
+                .fetch_all(pool)
+                .await?;
+
+        snapshots.push(TableSnapshot::new(table, rows));
+    }
+
+    Ok(snapshots)
+}
+
+/// How a table differs between two replays.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableDiff {
+    pub table: String,
+    pub left_rows: usize,
+    pub right_rows: usize,
+    /// Rows only written by the first replay, up to [`MAX_REPORTED_ROWS`].
+    pub only_left: Vec<String>,
+    /// Rows only written by the second replay, up to [`MAX_REPORTED_ROWS`].
+    pub only_right: Vec<String>,
+    /// Rows written by one replay but not the other, including unreported ones.
+    pub differing_rows: usize,
+}
+
+/// Diffs the snapshots of two replays table by table. Tables missing from
+/// one side are compared against an empty table.
+pub fn compare(left: &[TableSnapshot], right: &[TableSnapshot]) -> Vec<TableDiff> {
+    let mut tables: Vec<&str> = left.iter().map(|s| s.table.as_str()).collect();
+    for snapshot in right {
+        if !tables.contains(&snapshot.table.as_str()) {
+            tables.push(&snapshot.table);
+        }
+    }
+
+    let rows = |snapshots: &[TableSnapshot], table: &str| -> Vec<String> {
+        snapshots
+            .iter()
+            .find(|snapshot| snapshot.table == table)
+            .map(|snapshot| snapshot.rows.clone())
+            .unwrap_or_default()
+    };
+
+    tables
+        .into_iter()
+        .filter_map(|table| {
+            let left_rows = rows(left, table);
+            let right_rows = rows(right, table);
+
+            if left_rows == right_rows {
+                return None;
+            }
+
+            let (only_left, only_right) = difference(&left_rows, &right_rows);
+
+            Some(TableDiff {
+                table: table.to_string(),
+                left_rows: left_rows.len(),
+                right_rows: right_rows.len(),
+                differing_rows: only_left.len() + only_right.len(),
+                only_left: only_left.into_iter().take(MAX_REPORTED_ROWS).collect(),
+                only_right: only_right.into_iter().take(MAX_REPORTED_ROWS).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Rows of two sorted lists missing from the other one, counting duplicates.
+fn difference(left: &[String], right: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut only_left = Vec::new();
+    let mut only_right = Vec::new();
+    let (mut l, mut r) = (0, 0);
+
+    while l < left.len() && r < right.len() {
+        match left[l].cmp(&right[r]) {
+            std::cmp::Ordering::Less => {
+                only_left.push(left[l].clone());
+                l += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                only_right.push(right[r].clone());
+                r += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                l += 1;
+                r += 1;
+            }
+        }
+    }
+
+    only_left.extend_from_slice(&left[l..]);
+    only_right.extend_from_slice(&right[r..]);
+
+    (only_left, only_right)
+}
+
+/// Outcome of replaying a block range into two databases.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub blocks_replayed: usize,
+    /// Tables that differ between the replays. Empty if processing is
+    /// deterministic.
+    pub diffs: Vec<TableDiff>,
+}
+
+impl ReplayReport {
+    pub fn is_deterministic(&self) -> bool {
+        self.diffs.is_empty()
+    }
+
+    /// Logs the report, with one event per differing table.
+    pub fn log(&self) {
+        for diff in &self.diffs {
+            warn!(
+                table = %diff.table,
+                left_rows = diff.left_rows,
+                right_rows = diff.right_rows,
+                differing_rows = diff.differing_rows,
+                only_left = ?diff.only_left,
+                only_right = ?diff.only_right,
+                "Replayed table differs"
+            );
+        }
+
+        info!(
+            blocks_replayed = self.blocks_replayed,
+            tables_compared = REPLAY_TABLES.len(),
+            tables_differing = self.diffs.len(),
+            deterministic = self.is_deterministic(),
+            "Replay determinism report"
+        );
+    }
+}
+
+/// Replays recorded blocks into both databases and diffs their tables.
+/// Fails if either database isn't empty.
+pub async fn check<C>(
+    blocks: &[StagedBlock],
+    database_urls: &[String; 2],
+    ipfs_cache: &Arc<C>,
+    authorization_mode: AuthorizationMode,
+) -> Result<ReplayReport, IndexingError>
+where
+    C: CacheBackend + 'static,
+{
+    let mut snapshots = Vec::with_capacity(database_urls.len());
+
+    for database_url in database_urls {
+        let storage = Arc::new(PostgresStorage::new(database_url).await?);
+
+        ensure_empty(&storage.pool).await?;
+        replay(blocks, &storage, ipfs_cache, authorization_mode).await?;
+        snapshots.push(snapshot(&storage.pool).await?);
+    }
+
+    Ok(ReplayReport {
+        blocks_replayed: blocks.len(),
+        diffs: compare(&snapshots[0], &snapshots[1]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use wire::pb::chain::{EditorAdded, GeoOutput};
+
+    use super::*;
+    use crate::{
+        cache::{CacheError, DaoMetadata, PreprocessedEdit},
+        storage::shadow::ShadowStorage,
+    };
+
+    struct EmptyCache;
+
+    #[async_trait::async_trait]
+    impl CacheBackend for EmptyCache {
+        async fn get(&self, _uri: &String) -> Result<PreprocessedEdit, CacheError> {
+            Err(CacheError::NotFound)
+        }
+
+        async fn get_dao_metadata(&self, _uri: &String) -> Result<Option<DaoMetadata>, CacheError> {
+            Err(CacheError::NotFound)
+        }
+    }
+
+    fn snapshot(table: &str, rows: &[&str]) -> TableSnapshot {
+        TableSnapshot::new(table, rows.iter().map(|row| row.to_string()).collect())
+    }
+
+    /// A block adding one editor to a space.
+    fn staged_block(block_number: u64) -> StagedBlock {
+        let geo = GeoOutput {
+            editors_added: vec![EditorAdded {
+                dao_address: "0x1234567890123456789012345678901234567890".to_string(),
+                editor_address: format!("0x{:040x}", block_number),
+                main_voting_plugin_address: "0x0000000000000000000000000000000000000001"
+                    .to_string(),
+                change_type: "0".to_string(),
+                context: None,
+            }],
+            ..Default::default()
+        };
+
+        StagedBlock {
+            block_number,
+            cursor: format!("cursor_{}", block_number),
+            timestamp: 1_700_000_000,
+            output: geo.encode_to_vec(),
+            block_hash: format!("0xhash{}", block_number),
+            final_block_height: block_number,
+        }
+    }
+
+    #[test]
+    fn test_compare_ignores_row_order() {
+        let left = vec![snapshot("spaces", &["a", "b"])];
+        let right = vec![snapshot("spaces", &["b", "a"])];
+
+        assert!(compare(&left, &right).is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_differing_rows() {
+        let left = vec![
+            snapshot("spaces", &["a"]),
+            snapshot("entities", &["x", "y", "y"]),
+        ];
+        let right = vec![
+            snapshot("spaces", &["a"]),
+            snapshot("entities", &["x", "y", "z"]),
+        ];
+
+        let diffs = compare(&left, &right);
+
+        assert_eq!(
+            diffs,
+            vec![TableDiff {
+                table: "entities".to_string(),
+                left_rows: 3,
+                right_rows: 3,
+                only_left: vec!["y".to_string()],
+                only_right: vec!["z".to_string()],
+                differing_rows: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_compare_treats_missing_tables_as_empty() {
+        let left = vec![];
+        let right = vec![snapshot("proposals", &["p"])];
+
+        let diffs = compare(&left, &right);
+
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].table, "proposals");
+        assert_eq!(diffs[0].left_rows, 0);
+        assert_eq!(diffs[0].only_right, vec!["p".to_string()]);
+    }
+
+    #[test]
+    fn test_compare_caps_reported_rows() {
+        let rows: Vec<String> = (0..MAX_REPORTED_ROWS + 5)
+            .map(|i| format!("row_{:02}", i))
+            .collect();
+        let left = vec![TableSnapshot::new("values", rows)];
+
+        let diffs = compare(&left, &[]);
+
+        assert_eq!(diffs[0].only_left.len(), MAX_REPORTED_ROWS);
+        assert_eq!(diffs[0].differing_rows, MAX_REPORTED_ROWS + 5);
+    }
+
+    #[tokio::test]
+    async fn test_recorder_skips_empty_blocks() {
+        let recorder = ReplayRecorder::new();
+        let empty = StagedBlock {
+            output: vec![],
+            ..staged_block(4)
+        };
+
+        for block in [staged_block(3), empty, staged_block(5)] {
+            let block_data = block.to_block_scoped_data();
+            let recorded = recorder
+                .preprocess_block_scoped_data(&block_data)
+                .await
+                .unwrap();
+            recorder
+                .process_block_scoped_data(&block_data, recorded)
+                .await
+                .unwrap();
+        }
+
+        let blocks: Vec<u64> = recorder
+            .blocks()
+            .iter()
+            .map(|block| block.block_number)
+            .collect();
+        assert_eq!(blocks, vec![3, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_runs_blocks_through_handlers() {
+        let storage = Arc::new(ShadowStorage::new());
+        let blocks = vec![staged_block(3), staged_block(5)];
+
+        replay(
+            &blocks,
+            &storage,
+            &Arc::new(EmptyCache),
+            AuthorizationMode::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(storage.changes().editors_added, 2);
+    }
+}
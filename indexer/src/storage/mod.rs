@@ -1,17 +1,20 @@
 use async_trait::async_trait;
-use sqlx::Postgres;
+use indexer_utils::space_kind::SpaceKind;
 use uuid::Uuid;
 
 pub mod postgres;
+pub mod shadow;
 
 use thiserror::Error;
 
 use crate::models::{
     entities::EntityItem,
+    governance_settings::GovernanceSettingsItem,
     membership::{EditorItem, MemberItem},
-    properties::PropertyItem,
+    properties::{PropertyItem, PropertyLifecycle},
+    proposals::{ProposalItem, ProposalStatusItem, ProposalVoteItem},
     relations::{SetRelationItem, UnsetRelationItem, UpdateRelationItem},
-    spaces::SpaceItem,
+    spaces::{SpaceItem, SpaceLineageItem, SpaceMetadataItem},
     subspaces::SubspaceItem,
     values::ValueOp,
 };
@@ -24,82 +27,140 @@ pub enum StorageError {
 
 #[async_trait]
 pub trait StorageBackend: Send + Sync {
-    fn get_pool(&self) -> &sqlx::Pool<Postgres>;
+    /// Unit of work that writes are staged in until it's committed. Dropping
+    /// a transaction without committing discards its writes.
+    type Transaction: Send;
+
+    async fn begin(&self) -> Result<Self::Transaction, StorageError>;
+    async fn commit(&self, tx: Self::Transaction) -> Result<(), StorageError>;
     async fn insert_entities(
         &self,
         entities: &Vec<EntityItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn insert_values(
         &self,
         properties: &Vec<ValueOp>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn delete_values(
         &self,
         value_ids: &Vec<Uuid>,
         space_id: &Uuid,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn insert_relations(
         &self,
         relations: &Vec<SetRelationItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn update_relations(
         &self,
         relations: &Vec<UpdateRelationItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn unset_relation_fields(
         &self,
         relations: &Vec<UnsetRelationItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn delete_relations(
         &self,
         relation_ids: &Vec<Uuid>,
         space_id: &Uuid,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn insert_properties(
         &self,
         properties: &Vec<PropertyItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    async fn update_property_lifecycles(
+        &self,
+        lifecycles: &Vec<PropertyLifecycle>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn insert_spaces(
         &self,
         spaces: &Vec<SpaceItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    async fn update_space_metadata(
+        &self,
+        metadata: &Vec<SpaceMetadataItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    /// Links successor spaces to their predecessors and marks governed
+    /// successors with the `Successor` kind.
+    async fn insert_space_lineage(
+        &self,
+        lineage: &Vec<SpaceLineageItem>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn insert_members(
         &self,
         members: &Vec<MemberItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn remove_members(
         &self,
         members: &Vec<MemberItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn insert_editors(
         &self,
         editors: &Vec<EditorItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn remove_editors(
         &self,
         editors: &Vec<EditorItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
+    async fn get_space_editors(
+        &self,
+        space_id: &Uuid,
+        tx: &mut Self::Transaction,
+    ) -> Result<Vec<String>, StorageError>;
+    /// Kind of an indexed space, or `None` if the space isn't indexed.
+    async fn get_space_kind(&self, space_id: &Uuid) -> Result<Option<SpaceKind>, StorageError>;
     async fn insert_subspaces(
         &self,
         subspaces: &Vec<SubspaceItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
     async fn remove_subspaces(
         &self,
         subspaces: &Vec<SubspaceItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    async fn insert_proposals(
+        &self,
+        proposals: &Vec<ProposalItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    async fn update_proposal_statuses(
+        &self,
+        statuses: &Vec<ProposalStatusItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    /// Stores each voter's latest vote on a proposal, replacing votes cast
+    /// earlier in the chain.
+    async fn insert_proposal_votes(
+        &self,
+        votes: &Vec<ProposalVoteItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    /// Marks proposals that are still proposed once `block_timestamp` is
+    /// past their end time as expired.
+    async fn expire_proposals(
+        &self,
+        block_timestamp: i64,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError>;
+    async fn insert_governance_settings(
+        &self,
+        settings: &Vec<GovernanceSettingsItem>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError>;
 }
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use indexer_utils::space_kind::SpaceKind;
 
 use sqlx::{postgres::PgPoolOptions, Postgres, QueryBuilder, Row};
 use tracing::error;
@@ -8,11 +9,13 @@ use crate::models::{
     entities::EntityItem,
     membership::{EditorItem, MemberItem},
     properties::{
-        DataType, PropertyItem, DATA_TYPE_BOOLEAN, DATA_TYPE_NUMBER, DATA_TYPE_POINT,
-        DATA_TYPE_RELATION, DATA_TYPE_STRING, DATA_TYPE_TIME,
+        DataType, PropertyItem, PropertyLifecycle, DATA_TYPE_BOOLEAN, DATA_TYPE_NUMBER,
+        DATA_TYPE_POINT, DATA_TYPE_RELATION, DATA_TYPE_STRING, DATA_TYPE_TIME,
     },
     relations::{SetRelationItem, UnsetRelationItem, UpdateRelationItem},
-    spaces::{SpaceItem, SpaceType},
+    proposals::{ProposalItem, ProposalStatusItem, ProposalVoteItem},
+    governance_settings::GovernanceSettingsItem,
+    spaces::{legacy_space_type, SpaceItem, SpaceLineageItem, SpaceMetadataItem},
     subspaces::SubspaceItem,
     values::{ValueChangeType, ValueOp},
 };
@@ -208,6 +211,24 @@ impl PostgresStorage {
         Ok(properties)
     }
 
+    /// Lifecycles of properties that are deprecated or have a replacement.
+    pub async fn get_property_lifecycles(&self) -> Result<Vec<PropertyLifecycle>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT id, deprecated, replaced_by FROM properties WHERE deprecated OR replaced_by IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PropertyLifecycle {
+                id: row.get("id"),
+                deprecated: row.get("deprecated"),
+                replaced_by: row.get("replaced_by"),
+            })
+            .collect())
+    }
+
     pub async fn get_member(
         &self,
         address: &str,
@@ -275,14 +296,20 @@ impl PostgresStorage {
 
 #[async_trait]
 impl StorageBackend for PostgresStorage {
-    fn get_pool(&self) -> &sqlx::Pool<Postgres> {
-        &self.pool
+    type Transaction = sqlx::Transaction<'static, Postgres>;
+
+    async fn begin(&self) -> Result<Self::Transaction, StorageError> {
+        Ok(self.pool.begin().await?)
+    }
+
+    async fn commit(&self, tx: Self::Transaction) -> Result<(), StorageError> {
+        Ok(tx.commit().await?)
     }
 
     async fn insert_entities(
         &self,
         entities: &Vec<EntityItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         let ids: Vec<Uuid> = entities.iter().map(|x| x.id).collect();
         let created_ats: Vec<String> = entities.iter().map(|x| x.created_at.clone()).collect();
@@ -316,7 +343,7 @@ impl StorageBackend for PostgresStorage {
     async fn insert_values(
         &self,
         values: &Vec<ValueOp>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if values.is_empty() {
             return Ok(());
@@ -402,7 +429,7 @@ impl StorageBackend for PostgresStorage {
         &self,
         value_ids: &Vec<Uuid>,
         space_id: &Uuid,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if value_ids.is_empty() {
             return Ok(());
@@ -426,7 +453,7 @@ impl StorageBackend for PostgresStorage {
     async fn insert_relations(
         &self,
         relations: &Vec<SetRelationItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if relations.is_empty() {
             return Ok(());
@@ -493,7 +520,7 @@ impl StorageBackend for PostgresStorage {
     async fn update_relations(
         &self,
         relations: &Vec<UpdateRelationItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if relations.is_empty() {
             return Ok(());
@@ -530,7 +557,7 @@ impl StorageBackend for PostgresStorage {
     async fn unset_relation_fields(
         &self,
         relations: &Vec<UnsetRelationItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if relations.is_empty() {
             return Ok(());
@@ -580,7 +607,7 @@ impl StorageBackend for PostgresStorage {
         &self,
         relation_ids: &Vec<Uuid>,
         space_id: &Uuid,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if relation_ids.is_empty() {
             return Ok(());
@@ -613,7 +640,7 @@ impl StorageBackend for PostgresStorage {
     async fn insert_properties(
         &self,
         properties: &Vec<PropertyItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if properties.is_empty() {
             return Ok(());
@@ -649,10 +676,46 @@ impl StorageBackend for PostgresStorage {
         Ok(())
     }
 
+    async fn update_property_lifecycles(
+        &self,
+        lifecycles: &Vec<PropertyLifecycle>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        if lifecycles.is_empty() {
+            return Ok(());
+        }
+
+        let mut ids = Vec::with_capacity(lifecycles.len());
+        let mut deprecated = Vec::with_capacity(lifecycles.len());
+        let mut replaced_by = Vec::with_capacity(lifecycles.len());
+
+        for lifecycle in lifecycles {
+            ids.push(lifecycle.id);
+            deprecated.push(lifecycle.deprecated);
+            replaced_by.push(lifecycle.replaced_by);
+        }
+
+        let query = r#"
+                UPDATE properties AS p
+                SET deprecated = t.deprecated, replaced_by = t.replaced_by
+                FROM UNNEST($1::uuid[], $2::boolean[], $3::uuid[]) AS t(id, deprecated, replaced_by)
+                WHERE p.id = t.id
+            "#;
+
+        sqlx::query(query)
+            .bind(&ids)
+            .bind(&deprecated)
+            .bind(&replaced_by)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
     async fn insert_spaces(
         &self,
         spaces: &Vec<SpaceItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if spaces.is_empty() {
             return Ok(());
@@ -660,6 +723,7 @@ impl StorageBackend for PostgresStorage {
 
         let mut ids: Vec<Uuid> = Vec::new();
         let mut types: Vec<String> = Vec::new();
+        let mut kinds: Vec<String> = Vec::new();
         let mut dao_addresses: Vec<String> = Vec::new();
         let mut space_addresses: Vec<String> = Vec::new();
         let mut main_voting_addresses: Vec<Option<String>> = Vec::new();
@@ -668,10 +732,8 @@ impl StorageBackend for PostgresStorage {
 
         for space in spaces {
             ids.push(space.id);
-            types.push(match space.space_type {
-                SpaceType::Personal => "Personal".to_string(),
-                SpaceType::Public => "Public".to_string(),
-            });
+            types.push(legacy_space_type(space.space_kind).to_string());
+            kinds.push(space.space_kind.as_str().to_string());
             dao_addresses.push(space.dao_address.clone());
             space_addresses.push(space.space_address.clone());
             main_voting_addresses.push(space.voting_address.clone());
@@ -681,14 +743,15 @@ impl StorageBackend for PostgresStorage {
 
         sqlx::query!(
             r#"
-            INSERT INTO spaces (id, type, dao_address, space_address, main_voting_address, membership_address, personal_address)
-            SELECT id, type::"spaceTypes", dao_address, space_address, main_voting_address, membership_address, personal_address
-            FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::text[], $5::text[], $6::text[], $7::text[])
-            AS t(id, type, dao_address, space_address, main_voting_address, membership_address, personal_address)
+            INSERT INTO spaces (id, type, space_kind, dao_address, space_address, main_voting_address, membership_address, personal_address)
+            SELECT id, type::"spaceTypes", space_kind::"spaceKinds", dao_address, space_address, main_voting_address, membership_address, personal_address
+            FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::text[], $5::text[], $6::text[], $7::text[], $8::text[])
+            AS t(id, type, space_kind, dao_address, space_address, main_voting_address, membership_address, personal_address)
             ON CONFLICT (id) DO NOTHING
             "#,
             &ids,
             &types,
+            &kinds,
             &dao_addresses,
             &space_addresses,
             &main_voting_addresses as &[Option<String>],
@@ -701,10 +764,104 @@ impl StorageBackend for PostgresStorage {
         Ok(())
     }
 
+    async fn update_space_metadata(
+        &self,
+        metadata: &Vec<SpaceMetadataItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        if metadata.is_empty() {
+            return Ok(());
+        }
+
+        let mut ids: Vec<Uuid> = Vec::new();
+        let mut metadata_uris: Vec<String> = Vec::new();
+        let mut names: Vec<Option<String>> = Vec::new();
+        let mut descriptions: Vec<Option<String>> = Vec::new();
+
+        for item in metadata {
+            ids.push(item.id);
+            metadata_uris.push(item.metadata_uri.clone());
+            names.push(item.name.clone());
+            descriptions.push(item.description.clone());
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE spaces
+            SET metadata_uri = t.metadata_uri, name = t.name, description = t.description
+            FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::text[])
+            AS t(id, metadata_uri, name, description)
+            WHERE spaces.id = t.id
+            "#,
+        )
+        .bind(&ids)
+        .bind(&metadata_uris)
+        .bind(&names)
+        .bind(&descriptions)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn insert_space_lineage(
+        &self,
+        lineage: &Vec<SpaceLineageItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        if lineage.is_empty() {
+            return Ok(());
+        }
+
+        let mut space_ids: Vec<Uuid> = Vec::new();
+        let mut predecessor_addresses: Vec<String> = Vec::new();
+        let mut forked_at_blocks: Vec<String> = Vec::new();
+        let mut forked_in_txs: Vec<String> = Vec::new();
+
+        for item in lineage {
+            space_ids.push(item.space_id);
+            predecessor_addresses.push(item.predecessor_space_address.clone());
+            forked_at_blocks.push(item.forked_at_block.clone());
+            forked_in_txs.push(item.forked_in_tx.clone());
+        }
+
+        // Predecessors that aren't indexed, like legacy spaces, keep their
+        // address without a space id
+        sqlx::query(
+            r#"
+            INSERT INTO space_lineage (space_id, predecessor_space_id, predecessor_space_address, forked_at_block, forked_in_tx)
+            SELECT t.space_id, spaces.id, t.predecessor_space_address, t.forked_at_block, t.forked_in_tx
+            FROM UNNEST($1::uuid[], $2::text[], $3::text[], $4::text[])
+            AS t(space_id, predecessor_space_address, forked_at_block, forked_in_tx)
+            LEFT JOIN spaces ON spaces.space_address = t.predecessor_space_address
+            ON CONFLICT (space_id) DO NOTHING
+            "#,
+        )
+        .bind(&space_ids)
+        .bind(&predecessor_addresses)
+        .bind(&forked_at_blocks)
+        .bind(&forked_in_txs)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE spaces
+            SET space_kind = 'Successor'
+            WHERE id = ANY($1) AND space_kind = 'Dao'
+            "#,
+        )
+        .bind(&space_ids)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
     async fn insert_members(
         &self,
         members: &Vec<MemberItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if members.is_empty() {
             return Ok(());
@@ -738,7 +895,7 @@ impl StorageBackend for PostgresStorage {
     async fn remove_members(
         &self,
         members: &Vec<MemberItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if members.is_empty() {
             return Ok(());
@@ -773,7 +930,7 @@ impl StorageBackend for PostgresStorage {
     async fn insert_editors(
         &self,
         editors: &Vec<EditorItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if editors.is_empty() {
             return Ok(());
@@ -807,7 +964,7 @@ impl StorageBackend for PostgresStorage {
     async fn remove_editors(
         &self,
         editors: &Vec<EditorItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if editors.is_empty() {
             return Ok(());
@@ -839,10 +996,32 @@ impl StorageBackend for PostgresStorage {
         Ok(())
     }
 
+    async fn get_space_editors(
+        &self,
+        space_id: &Uuid,
+        tx: &mut Self::Transaction,
+    ) -> Result<Vec<String>, StorageError> {
+        let rows = sqlx::query("SELECT address FROM editors WHERE space_id = $1")
+            .bind(space_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| row.get("address")).collect())
+    }
+
+    async fn get_space_kind(&self, space_id: &Uuid) -> Result<Option<SpaceKind>, StorageError> {
+        let row = sqlx::query("SELECT space_kind::text AS space_kind FROM spaces WHERE id = $1")
+            .bind(space_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.and_then(|row| row.get::<String, _>("space_kind").parse().ok()))
+    }
+
     async fn insert_subspaces(
         &self,
         subspaces: &Vec<SubspaceItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if subspaces.is_empty() {
             return Ok(());
@@ -876,7 +1055,7 @@ impl StorageBackend for PostgresStorage {
     async fn remove_subspaces(
         &self,
         subspaces: &Vec<SubspaceItem>,
-        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        tx: &mut Self::Transaction,
     ) -> Result<(), StorageError> {
         if subspaces.is_empty() {
             return Ok(());
@@ -907,6 +1086,220 @@ impl StorageBackend for PostgresStorage {
 
         Ok(())
     }
+
+    async fn insert_proposals(
+        &self,
+        proposals: &Vec<ProposalItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        if proposals.is_empty() {
+            return Ok(());
+        }
+
+        let mut plugin_addresses: Vec<String> = Vec::new();
+        let mut proposal_ids: Vec<String> = Vec::new();
+        let mut creators: Vec<String> = Vec::new();
+        let mut start_times: Vec<i64> = Vec::new();
+        let mut end_times: Vec<i64> = Vec::new();
+        let mut metadata_uris: Vec<String> = Vec::new();
+        let mut created_at_blocks: Vec<String> = Vec::new();
+
+        for proposal in proposals {
+            plugin_addresses.push(proposal.plugin_address.clone());
+            proposal_ids.push(proposal.proposal_id.clone());
+            creators.push(proposal.creator.clone());
+            start_times.push(proposal.start_time);
+            end_times.push(proposal.end_time);
+            metadata_uris.push(proposal.metadata_uri.clone());
+            created_at_blocks.push(proposal.created_at_block.clone());
+        }
+
+        // A replayed block re-emits the same proposals, which are left untouched
+        sqlx::query(
+            r#"
+            INSERT INTO proposals (plugin_address, proposal_id, creator, start_time, end_time, metadata_uri, created_at_block)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::bigint[], $5::bigint[], $6::text[], $7::text[])
+            ON CONFLICT (plugin_address, proposal_id) DO NOTHING
+            "#,
+        )
+        .bind(&plugin_addresses)
+        .bind(&proposal_ids)
+        .bind(&creators)
+        .bind(&start_times)
+        .bind(&end_times)
+        .bind(&metadata_uris)
+        .bind(&created_at_blocks)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn update_proposal_statuses(
+        &self,
+        statuses: &Vec<ProposalStatusItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        if statuses.is_empty() {
+            return Ok(());
+        }
+
+        let mut plugin_addresses: Vec<String> = Vec::new();
+        let mut proposal_ids: Vec<String> = Vec::new();
+        let mut status_values: Vec<String> = Vec::new();
+
+        for status in statuses {
+            plugin_addresses.push(status.plugin_address.clone());
+            proposal_ids.push(status.proposal_id.clone());
+            status_values.push(status.status.as_str().to_string());
+        }
+
+        // Executed and canceled are final. Expired isn't, since a proposal
+        // that passed can still be executed after its end time
+        sqlx::query(
+            r#"
+            UPDATE proposals
+            SET status = t.status::"proposalStatus"
+            FROM UNNEST($1::text[], $2::text[], $3::text[]) AS t(plugin_address, proposal_id, status)
+            WHERE proposals.plugin_address = t.plugin_address
+            AND proposals.proposal_id = t.proposal_id
+            AND proposals.status IN ('Proposed', 'Expired')
+            "#,
+        )
+        .bind(&plugin_addresses)
+        .bind(&proposal_ids)
+        .bind(&status_values)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn insert_proposal_votes(
+        &self,
+        votes: &Vec<ProposalVoteItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        if votes.is_empty() {
+            return Ok(());
+        }
+
+        let mut plugin_addresses: Vec<String> = Vec::new();
+        let mut proposal_ids: Vec<String> = Vec::new();
+        let mut voters: Vec<String> = Vec::new();
+        let mut vote_options: Vec<i32> = Vec::new();
+        let mut voting_powers: Vec<String> = Vec::new();
+        let mut block_numbers: Vec<i64> = Vec::new();
+        let mut log_indexes: Vec<i64> = Vec::new();
+        let mut tx_hashes: Vec<String> = Vec::new();
+
+        for vote in votes {
+            plugin_addresses.push(vote.plugin_address.clone());
+            proposal_ids.push(vote.proposal_id.clone());
+            voters.push(vote.voter.clone());
+            vote_options.push(vote.vote_option);
+            voting_powers.push(vote.voting_power.clone());
+            block_numbers.push(vote.block_number);
+            log_indexes.push(vote.log_index);
+            tx_hashes.push(vote.tx_hash.clone());
+        }
+
+        // A replacing vote overwrites the voter's earlier one. A replayed block
+        // re-emits the same votes, so only votes at or after the stored one's
+        // position in the chain are written
+        sqlx::query(
+            r#"
+            INSERT INTO proposal_votes (plugin_address, proposal_id, voter, vote_option, voting_power, block_number, log_index, tx_hash)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::integer[], $5::text[]::numeric[], $6::bigint[], $7::bigint[], $8::text[])
+            ON CONFLICT (plugin_address, proposal_id, voter) DO UPDATE SET
+                vote_option = EXCLUDED.vote_option,
+                voting_power = EXCLUDED.voting_power,
+                block_number = EXCLUDED.block_number,
+                log_index = EXCLUDED.log_index,
+                tx_hash = EXCLUDED.tx_hash
+            WHERE (proposal_votes.block_number, proposal_votes.log_index) <= (EXCLUDED.block_number, EXCLUDED.log_index)
+            "#,
+        )
+        .bind(&plugin_addresses)
+        .bind(&proposal_ids)
+        .bind(&voters)
+        .bind(&vote_options)
+        .bind(&voting_powers)
+        .bind(&block_numbers)
+        .bind(&log_indexes)
+        .bind(&tx_hashes)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn expire_proposals(
+        &self,
+        block_timestamp: i64,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            r#"
+            UPDATE proposals
+            SET status = 'Expired'
+            WHERE status = 'Proposed' AND end_time <= $1
+            "#,
+        )
+        .bind(block_timestamp)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn insert_governance_settings(
+        &self,
+        settings: &Vec<GovernanceSettingsItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        if settings.is_empty() {
+            return Ok(());
+        }
+
+        let mut plugin_addresses: Vec<String> = Vec::new();
+        let mut block_numbers: Vec<i64> = Vec::new();
+        let mut log_indexes: Vec<i64> = Vec::new();
+        let mut tx_hashes: Vec<String> = Vec::new();
+        let mut voting_modes: Vec<Option<i32>> = Vec::new();
+        let mut support_thresholds: Vec<Option<i64>> = Vec::new();
+        let mut durations: Vec<i64> = Vec::new();
+
+        for update in settings {
+            plugin_addresses.push(update.plugin_address.clone());
+            block_numbers.push(update.block_number);
+            log_indexes.push(update.log_index);
+            tx_hashes.push(update.tx_hash.clone());
+            voting_modes.push(update.voting_mode);
+            support_thresholds.push(update.support_threshold);
+            durations.push(update.duration);
+        }
+
+        // A replayed block re-emits the same updates at the same log positions
+        sqlx::query(
+            r#"
+            INSERT INTO governance_settings (plugin_address, block_number, log_index, tx_hash, voting_mode, support_threshold, duration)
+            SELECT * FROM UNNEST($1::text[], $2::bigint[], $3::bigint[], $4::text[], $5::integer[], $6::bigint[], $7::bigint[])
+            ON CONFLICT (plugin_address, block_number, log_index) DO NOTHING
+            "#,
+        )
+        .bind(&plugin_addresses)
+        .bind(&block_numbers)
+        .bind(&log_indexes)
+        .bind(&tx_hashes)
+        .bind(&voting_modes)
+        .bind(&support_thresholds)
+        .bind(&durations)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
 }
 
 fn string_to_data_type(s: &str) -> Option<DataType> {
@@ -0,0 +1,490 @@
+//! In-memory storage used for dry runs.
+//!
+//! [`ShadowStorage`] implements [`StorageBackend`] without a database. Writes
+//! are staged in a [`ShadowTransaction`] and only counted once the handler
+//! commits, so rolled back work (e.g. rejected edits) doesn't show up in the
+//! report. Editor membership and space kinds are tracked so edit authorization
+//! behaves the same as it would against Postgres for spaces whose editors were
+//! added within the dry run range.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use indexer_utils::space_kind::SpaceKind;
+use uuid::Uuid;
+
+use crate::models::{
+    entities::EntityItem,
+    governance_settings::GovernanceSettingsItem,
+    membership::{EditorItem, MemberItem},
+    properties::{PropertyItem, PropertyLifecycle},
+    proposals::{ProposalItem, ProposalStatusItem, ProposalVoteItem},
+    relations::{SetRelationItem, UnsetRelationItem, UpdateRelationItem},
+    spaces::{SpaceItem, SpaceLineageItem, SpaceMetadataItem},
+    subspaces::SubspaceItem,
+    values::ValueOp,
+};
+
+use super::{StorageBackend, StorageError};
+
+/// Number of rows each kind of write would have touched.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShadowChanges {
+    pub entities: usize,
+    pub values_set: usize,
+    pub values_deleted: usize,
+    pub relations_created: usize,
+    pub relations_updated: usize,
+    pub relations_unset: usize,
+    pub relations_deleted: usize,
+    pub properties: usize,
+    pub property_lifecycles: usize,
+    pub spaces: usize,
+    pub space_metadata: usize,
+    pub space_lineage: usize,
+    pub members_added: usize,
+    pub members_removed: usize,
+    pub editors_added: usize,
+    pub editors_removed: usize,
+    pub subspaces_added: usize,
+    pub subspaces_removed: usize,
+    pub proposals: usize,
+    pub proposal_statuses: usize,
+    pub proposal_votes: usize,
+    pub governance_settings: usize,
+}
+
+impl ShadowChanges {
+    pub fn merge(&mut self, other: &ShadowChanges) {
+        self.entities += other.entities;
+        self.values_set += other.values_set;
+        self.values_deleted += other.values_deleted;
+        self.relations_created += other.relations_created;
+        self.relations_updated += other.relations_updated;
+        self.relations_unset += other.relations_unset;
+        self.relations_deleted += other.relations_deleted;
+        self.properties += other.properties;
+        self.property_lifecycles += other.property_lifecycles;
+        self.spaces += other.spaces;
+        self.space_metadata += other.space_metadata;
+        self.space_lineage += other.space_lineage;
+        self.members_added += other.members_added;
+        self.members_removed += other.members_removed;
+        self.editors_added += other.editors_added;
+        self.editors_removed += other.editors_removed;
+        self.subspaces_added += other.subspaces_added;
+        self.subspaces_removed += other.subspaces_removed;
+        self.proposals += other.proposals;
+        self.proposal_statuses += other.proposal_statuses;
+        self.proposal_votes += other.proposal_votes;
+        self.governance_settings += other.governance_settings;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == ShadowChanges::default()
+    }
+}
+
+/// Writes staged by a handler until it commits.
+#[derive(Debug, Default)]
+pub struct ShadowTransaction {
+    changes: ShadowChanges,
+    added_editors: Vec<EditorItem>,
+    removed_editors: Vec<EditorItem>,
+    created_spaces: Vec<(Uuid, SpaceKind)>,
+}
+
+#[derive(Debug, Default)]
+struct ShadowState {
+    changes: ShadowChanges,
+    committed_transactions: usize,
+    editors: HashMap<Uuid, HashSet<String>>,
+    space_kinds: HashMap<Uuid, SpaceKind>,
+}
+
+/// [`StorageBackend`] that records would-be writes in memory.
+#[derive(Debug, Default)]
+pub struct ShadowStorage {
+    state: Mutex<ShadowState>,
+}
+
+impl ShadowStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Totals of all committed writes so far.
+    pub fn changes(&self) -> ShadowChanges {
+        self.state.lock().unwrap().changes.clone()
+    }
+
+    pub fn committed_transactions(&self) -> usize {
+        self.state.lock().unwrap().committed_transactions
+    }
+}
+
+#[async_trait]
+impl StorageBackend for ShadowStorage {
+    type Transaction = ShadowTransaction;
+
+    async fn begin(&self) -> Result<Self::Transaction, StorageError> {
+        Ok(ShadowTransaction::default())
+    }
+
+    async fn commit(&self, tx: Self::Transaction) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+
+        state.changes.merge(&tx.changes);
+        state.committed_transactions += 1;
+
+        for editor in tx.added_editors {
+            state
+                .editors
+                .entry(editor.space_id)
+                .or_default()
+                .insert(editor.address);
+        }
+
+        for editor in tx.removed_editors {
+            if let Some(editors) = state.editors.get_mut(&editor.space_id) {
+                editors.remove(&editor.address);
+            }
+        }
+
+        for (space_id, kind) in tx.created_spaces {
+            state.space_kinds.entry(space_id).or_insert(kind);
+        }
+
+        Ok(())
+    }
+
+    async fn insert_entities(
+        &self,
+        entities: &Vec<EntityItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.entities += entities.len();
+        Ok(())
+    }
+
+    async fn insert_values(
+        &self,
+        properties: &Vec<ValueOp>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.values_set += properties.len();
+        Ok(())
+    }
+
+    async fn delete_values(
+        &self,
+        value_ids: &Vec<Uuid>,
+        _space_id: &Uuid,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.values_deleted += value_ids.len();
+        Ok(())
+    }
+
+    async fn insert_relations(
+        &self,
+        relations: &Vec<SetRelationItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.relations_created += relations.len();
+        Ok(())
+    }
+
+    async fn update_relations(
+        &self,
+        relations: &Vec<UpdateRelationItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.relations_updated += relations.len();
+        Ok(())
+    }
+
+    async fn unset_relation_fields(
+        &self,
+        relations: &Vec<UnsetRelationItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.relations_unset += relations.len();
+        Ok(())
+    }
+
+    async fn delete_relations(
+        &self,
+        relation_ids: &Vec<Uuid>,
+        _space_id: &Uuid,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.relations_deleted += relation_ids.len();
+        Ok(())
+    }
+
+    async fn insert_properties(
+        &self,
+        properties: &Vec<PropertyItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.properties += properties.len();
+        Ok(())
+    }
+
+    async fn update_property_lifecycles(
+        &self,
+        lifecycles: &Vec<PropertyLifecycle>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.property_lifecycles += lifecycles.len();
+        Ok(())
+    }
+
+    async fn insert_spaces(
+        &self,
+        spaces: &Vec<SpaceItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.spaces += spaces.len();
+        tx.created_spaces
+            .extend(spaces.iter().map(|space| (space.id, space.space_kind)));
+        Ok(())
+    }
+
+    async fn update_space_metadata(
+        &self,
+        metadata: &Vec<SpaceMetadataItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.space_metadata += metadata.len();
+        Ok(())
+    }
+
+    async fn insert_space_lineage(
+        &self,
+        lineage: &Vec<SpaceLineageItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.space_lineage += lineage.len();
+        Ok(())
+    }
+
+    async fn insert_members(
+        &self,
+        members: &Vec<MemberItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.members_added += members.len();
+        Ok(())
+    }
+
+    async fn remove_members(
+        &self,
+        members: &Vec<MemberItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.members_removed += members.len();
+        Ok(())
+    }
+
+    async fn insert_editors(
+        &self,
+        editors: &Vec<EditorItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.editors_added += editors.len();
+        tx.added_editors.extend(editors.iter().cloned());
+        Ok(())
+    }
+
+    async fn remove_editors(
+        &self,
+        editors: &Vec<EditorItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.editors_removed += editors.len();
+        tx.removed_editors.extend(editors.iter().cloned());
+        Ok(())
+    }
+
+    async fn get_space_editors(
+        &self,
+        space_id: &Uuid,
+        _tx: &mut Self::Transaction,
+    ) -> Result<Vec<String>, StorageError> {
+        let state = self.state.lock().unwrap();
+
+        Ok(state
+            .editors
+            .get(space_id)
+            .map(|editors| editors.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn get_space_kind(&self, space_id: &Uuid) -> Result<Option<SpaceKind>, StorageError> {
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .space_kinds
+            .get(space_id)
+            .copied())
+    }
+
+    async fn insert_subspaces(
+        &self,
+        subspaces: &Vec<SubspaceItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.subspaces_added += subspaces.len();
+        Ok(())
+    }
+
+    async fn remove_subspaces(
+        &self,
+        subspaces: &Vec<SubspaceItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.subspaces_removed += subspaces.len();
+        Ok(())
+    }
+
+    async fn insert_proposals(
+        &self,
+        proposals: &Vec<ProposalItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.proposals += proposals.len();
+        Ok(())
+    }
+
+    async fn update_proposal_statuses(
+        &self,
+        statuses: &Vec<ProposalStatusItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.proposal_statuses += statuses.len();
+        Ok(())
+    }
+
+    async fn insert_proposal_votes(
+        &self,
+        votes: &Vec<ProposalVoteItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.proposal_votes += votes.len();
+        Ok(())
+    }
+
+    async fn expire_proposals(
+        &self,
+        _block_timestamp: i64,
+        _tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        // Shadow storage doesn't track proposals, so there is nothing to expire
+        Ok(())
+    }
+
+    async fn insert_governance_settings(
+        &self,
+        settings: &Vec<GovernanceSettingsItem>,
+        tx: &mut Self::Transaction,
+    ) -> Result<(), StorageError> {
+        tx.changes.governance_settings += settings.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(address: &str, space_id: Uuid) -> EditorItem {
+        EditorItem {
+            address: address.to_string(),
+            space_id,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_uncommitted_writes_are_discarded() {
+        let storage = ShadowStorage::new();
+        let space_id = Uuid::new_v4();
+
+        let mut tx = storage.begin().await.unwrap();
+        storage
+            .insert_editors(&vec![editor("0xabc", space_id)], &mut tx)
+            .await
+            .unwrap();
+        drop(tx);
+
+        assert!(storage.changes().is_empty());
+        assert!(storage
+            .get_space_editors(&space_id, &mut storage.begin().await.unwrap())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_committed_editors_are_tracked() {
+        let storage = ShadowStorage::new();
+        let space_id = Uuid::new_v4();
+
+        let mut tx = storage.begin().await.unwrap();
+        storage
+            .insert_editors(
+                &vec![editor("0xabc", space_id), editor("0xdef", space_id)],
+                &mut tx,
+            )
+            .await
+            .unwrap();
+        storage.commit(tx).await.unwrap();
+
+        let mut tx = storage.begin().await.unwrap();
+        storage
+            .remove_editors(&vec![editor("0xdef", space_id)], &mut tx)
+            .await
+            .unwrap();
+        storage.commit(tx).await.unwrap();
+
+        assert_eq!(
+            storage
+                .get_space_editors(&space_id, &mut storage.begin().await.unwrap())
+                .await
+                .unwrap(),
+            vec!["0xabc".to_string()]
+        );
+        assert_eq!(storage.changes().editors_added, 2);
+        assert_eq!(storage.changes().editors_removed, 1);
+        assert_eq!(storage.committed_transactions(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_committed_space_kinds_are_tracked() {
+        let storage = ShadowStorage::new();
+        let space_id = Uuid::new_v4();
+
+        let space = SpaceItem {
+            id: space_id,
+            space_kind: SpaceKind::Dao,
+            dao_address: "0xdao".to_string(),
+            space_address: "0xspace".to_string(),
+            voting_address: Some("0xvoting".to_string()),
+            membership_address: Some("0xmembership".to_string()),
+            personal_address: None,
+        };
+
+        let mut tx = storage.begin().await.unwrap();
+        storage.insert_spaces(&vec![space], &mut tx).await.unwrap();
+        assert_eq!(storage.get_space_kind(&space_id).await.unwrap(), None);
+
+        storage.commit(tx).await.unwrap();
+        assert_eq!(
+            storage.get_space_kind(&space_id).await.unwrap(),
+            Some(SpaceKind::Dao)
+        );
+    }
+}
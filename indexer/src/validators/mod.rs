@@ -9,6 +9,7 @@ pub mod validate_checkbox;
 pub mod validate_time;
 pub mod validate_point;
 pub mod validate_datatype;
+pub mod validate_edit_authors;
 
 pub use error::ValidationError;
 pub use validate_decimal::validate_two_decimal_places;
@@ -18,4 +19,5 @@ pub use validate_number::{validate_number, validate_number_comprehensive, valida
 pub use validate_checkbox::{validate_checkbox, validate_checkbox_comprehensive, validate_checkbox_string};
 pub use validate_time::{validate_time, validate_time_comprehensive, validate_time_string, validate_unix_timestamp};
 pub use validate_point::{validate_point, validate_point_comprehensive, validate_point_string, Point};
-pub use validate_datatype::{validate_by_datatype, validate_string_by_datatype, ValidatedValue};
\ No newline at end of file
+pub use validate_datatype::{validate_by_datatype, validate_string_by_datatype, ValidatedValue};
+pub use validate_edit_authors::{AuthorCheck, AuthorizationMode, EditAuthorValidator, EditorChanges};
@@ -0,0 +1,380 @@
+//! Membership-gated validation of edit authors.
+//!
+//! Edits are published on-chain by a space plugin, but the authors listed in
+//! the edit payload are not checked against the space's editor set by the
+//! contracts. This module checks each edit's authors against the indexed
+//! editor state as of the block the edit was published in.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use indexer_utils::{checksum_address, id::derive_space_id, network_ids::GEO};
+use uuid::Uuid;
+use wire::pb::grc20::Edit;
+
+use crate::{AddedMember, CreatedSpace, RemovedMember};
+
+/// What to do with an edit whose authors are not editors of the space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AuthorizationMode {
+    /// Log and count the violation, but still apply the edit.
+    #[default]
+    Flag,
+
+    /// Log and count the violation, and skip applying the edit.
+    Reject,
+}
+
+impl AuthorizationMode {
+    /// Parses the mode from a configuration value. Anything other than
+    /// "reject" (case-insensitive) falls back to flagging.
+    pub fn from_config_value(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "reject" => AuthorizationMode::Reject,
+            _ => AuthorizationMode::Flag,
+        }
+    }
+}
+
+/// Result of checking an edit's authors against the editor set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthorCheck {
+    /// Every author is an editor of the space.
+    Authorized,
+
+    /// At least one author is not an editor of the space, or the edit has
+    /// no decodable authors at all.
+    Unauthorized { authors: Vec<String> },
+}
+
+/// Counters for authorship checks. These are process-wide totals and are
+/// reported through tracing alongside each violation.
+#[derive(Debug, Default)]
+pub struct AuthorizationMetrics {
+    checked: AtomicU64,
+    unauthorized: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl AuthorizationMetrics {
+    pub fn checked(&self) -> u64 {
+        self.checked.load(Ordering::Relaxed)
+    }
+
+    pub fn unauthorized(&self) -> u64 {
+        self.unauthorized.load(Ordering::Relaxed)
+    }
+
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+}
+
+/// Editor additions and removals emitted in the block being processed.
+///
+/// Membership changes and the initial editors of created spaces are written
+/// concurrently with edits, so the stored editor set only reflects previous
+/// blocks. Applying these changes on top of it gives the editor set as of
+/// the current block.
+#[derive(Clone, Debug, Default)]
+pub struct EditorChanges {
+    pub added: Vec<AddedMember>,
+    pub removed: Vec<RemovedMember>,
+}
+
+impl EditorChanges {
+    /// Spaces are created with their initial editors before any other
+    /// change in the block, so those come first in the additions.
+    pub fn new(spaces: &[CreatedSpace], added: &[AddedMember], removed: &[RemovedMember]) -> Self {
+        let initial_editors = spaces.iter().flat_map(|space| {
+            space.initial_editors().iter().map(|editor| AddedMember {
+                dao_address: space.dao_address().to_string(),
+                editor_address: editor.clone(),
+                context: space.context().clone(),
+            })
+        });
+
+        Self {
+            added: initial_editors.chain(added.iter().cloned()).collect(),
+            removed: removed.to_vec(),
+        }
+    }
+
+    /// Applies the block's changes for `space_id` on top of the stored
+    /// editor addresses.
+    pub fn apply(&self, space_id: &Uuid, stored_editors: Vec<String>) -> HashSet<String> {
+        let mut editors: HashSet<String> = stored_editors.into_iter().collect();
+
+        for added in &self.added {
+            let added_space_id = derive_space_id(GEO, &checksum_address(added.dao_address.clone()));
+
+            if &added_space_id == space_id {
+                editors.insert(checksum_address(added.editor_address.clone()));
+            }
+        }
+
+        for removed in &self.removed {
+            let removed_space_id =
+                derive_space_id(GEO, &checksum_address(removed.dao_address.clone()));
+
+            if &removed_space_id == space_id {
+                editors.remove(&checksum_address(removed.editor_address.clone()));
+            }
+        }
+
+        editors
+    }
+}
+
+/// Validates edit authors against a space's editor set and records
+/// violations.
+#[derive(Debug, Default)]
+pub struct EditAuthorValidator {
+    mode: AuthorizationMode,
+    metrics: AuthorizationMetrics,
+}
+
+impl EditAuthorValidator {
+    pub fn new(mode: AuthorizationMode) -> Self {
+        Self {
+            mode,
+            metrics: AuthorizationMetrics::default(),
+        }
+    }
+
+    pub fn mode(&self) -> AuthorizationMode {
+        self.mode
+    }
+
+    pub fn metrics(&self) -> &AuthorizationMetrics {
+        &self.metrics
+    }
+
+    /// Checks the edit's authors against `editors` and updates the metrics.
+    ///
+    /// An edit with no decodable authors is treated as unauthorized since
+    /// there is nobody to attribute it to.
+    pub fn check(&self, edit: &Edit, editors: &HashSet<String>) -> AuthorCheck {
+        self.metrics.checked.fetch_add(1, Ordering::Relaxed);
+
+        let mut unauthorized = Vec::new();
+        let mut decoded_count = 0;
+
+        for author in &edit.authors {
+            match decode_author(author) {
+                Some(address) => {
+                    decoded_count += 1;
+
+                    if !editors.contains(&address) {
+                        unauthorized.push(address);
+                    }
+                }
+                None => unauthorized.push(format!("0x{}", hex_string(author))),
+            }
+        }
+
+        if unauthorized.is_empty() && decoded_count > 0 {
+            return AuthorCheck::Authorized;
+        }
+
+        self.metrics.unauthorized.fetch_add(1, Ordering::Relaxed);
+
+        AuthorCheck::Unauthorized {
+            authors: unauthorized,
+        }
+    }
+
+    /// Returns whether an unauthorized edit should be skipped, counting the
+    /// rejection if so.
+    pub fn should_reject(&self) -> bool {
+        match self.mode {
+            AuthorizationMode::Flag => false,
+            AuthorizationMode::Reject => {
+                self.metrics.rejected.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+        }
+    }
+}
+
+/// Decodes an author from an edit into a checksummed address.
+///
+/// Authors are either the raw 20 address bytes or the UTF-8 encoded hex
+/// address. Returns None if the bytes are neither.
+pub fn decode_author(bytes: &[u8]) -> Option<String> {
+    if bytes.len() == 20 {
+        return Some(checksum_address(hex_string(bytes)));
+    }
+
+    let text = std::str::from_utf8(bytes).ok()?;
+    let hex = text.strip_prefix("0x").unwrap_or(text);
+
+    if hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(checksum_address(hex))
+    } else {
+        None
+    }
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PersonalSpace;
+
+    const DAO: &str = "0x1234567890123456789012345678901234567890";
+    const EDITOR: &str = "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd";
+    const OUTSIDER: &str = "0x9999999999999999999999999999999999999999";
+
+    fn edit_with_authors(authors: Vec<Vec<u8>>) -> Edit {
+        Edit {
+            id: vec![],
+            name: "Edit".to_string(),
+            ops: vec![],
+            authors,
+            language: None,
+        }
+    }
+
+    fn space_id() -> Uuid {
+        derive_space_id(GEO, &checksum_address(DAO))
+    }
+
+    #[test]
+    fn test_decode_author_formats() {
+        let expected = checksum_address(EDITOR);
+
+        assert_eq!(decode_author(EDITOR.as_bytes()), Some(expected.clone()));
+        assert_eq!(
+            decode_author(&EDITOR.as_bytes()[2..]),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            decode_author(&[0xab; 20]),
+            Some(checksum_address(format!("0x{}", "ab".repeat(20))))
+        );
+        assert_eq!(decode_author(b"not an address"), None);
+    }
+
+    #[test]
+    fn test_check_authorized_author() {
+        let validator = EditAuthorValidator::new(AuthorizationMode::Flag);
+        let editors = HashSet::from([checksum_address(EDITOR)]);
+        let edit = edit_with_authors(vec![EDITOR.as_bytes().to_vec()]);
+
+        assert_eq!(validator.check(&edit, &editors), AuthorCheck::Authorized);
+        assert_eq!(validator.metrics().checked(), 1);
+        assert_eq!(validator.metrics().unauthorized(), 0);
+    }
+
+    #[test]
+    fn test_check_unauthorized_author() {
+        let validator = EditAuthorValidator::new(AuthorizationMode::Reject);
+        let editors = HashSet::from([checksum_address(EDITOR)]);
+        let edit = edit_with_authors(vec![
+            EDITOR.as_bytes().to_vec(),
+            OUTSIDER.as_bytes().to_vec(),
+        ]);
+
+        assert_eq!(
+            validator.check(&edit, &editors),
+            AuthorCheck::Unauthorized {
+                authors: vec![checksum_address(OUTSIDER)]
+            }
+        );
+        assert!(validator.should_reject());
+        assert_eq!(validator.metrics().unauthorized(), 1);
+        assert_eq!(validator.metrics().rejected(), 1);
+    }
+
+    #[test]
+    fn test_check_edit_without_authors_is_unauthorized() {
+        let validator = EditAuthorValidator::new(AuthorizationMode::Flag);
+        let editors = HashSet::from([checksum_address(EDITOR)]);
+        let edit = edit_with_authors(vec![]);
+
+        assert_eq!(
+            validator.check(&edit, &editors),
+            AuthorCheck::Unauthorized { authors: vec![] }
+        );
+        assert!(!validator.should_reject());
+        assert_eq!(validator.metrics().rejected(), 0);
+    }
+
+    #[test]
+    fn test_editor_changes_apply_block_deltas() {
+        let changes = EditorChanges::new(
+            &[],
+            &[AddedMember {
+                dao_address: DAO.to_string(),
+                editor_address: EDITOR.to_string(),
+                context: Default::default(),
+            }],
+            &[RemovedMember {
+                dao_address: DAO.to_string(),
+                editor_address: OUTSIDER.to_string(),
+                context: Default::default(),
+            }],
+        );
+
+        let editors = changes.apply(&space_id(), vec![checksum_address(OUTSIDER)]);
+
+        assert_eq!(editors, HashSet::from([checksum_address(EDITOR)]));
+    }
+
+    #[test]
+    fn test_editor_changes_ignore_other_spaces() {
+        let changes = EditorChanges::new(
+            &[],
+            &[AddedMember {
+                dao_address: OUTSIDER.to_string(),
+                editor_address: EDITOR.to_string(),
+                context: Default::default(),
+            }],
+            &[],
+        );
+
+        assert!(changes.apply(&space_id(), vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_editor_changes_include_initial_editors_of_created_spaces() {
+        let spaces = vec![CreatedSpace::Personal(PersonalSpace {
+            dao_address: DAO.to_string(),
+            space_address: OUTSIDER.to_string(),
+            personal_plugin: OUTSIDER.to_string(),
+            initial_editors: vec![EDITOR.to_string()],
+            initial_members: vec![],
+            context: Default::default(),
+        })];
+        let changes = EditorChanges::new(&spaces, &[], &[]);
+
+        assert_eq!(
+            changes.apply(&space_id(), vec![]),
+            HashSet::from([checksum_address(EDITOR)])
+        );
+    }
+
+    #[test]
+    fn test_authorization_mode_from_config_value() {
+        assert_eq!(
+            AuthorizationMode::from_config_value("reject"),
+            AuthorizationMode::Reject
+        );
+        assert_eq!(
+            AuthorizationMode::from_config_value(" REJECT "),
+            AuthorizationMode::Reject
+        );
+        assert_eq!(
+            AuthorizationMode::from_config_value("flag"),
+            AuthorizationMode::Flag
+        );
+        assert_eq!(
+            AuthorizationMode::from_config_value(""),
+            AuthorizationMode::Flag
+        );
+    }
+}
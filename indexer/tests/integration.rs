@@ -19,8 +19,9 @@ use indexer::{
     models::properties::DataType,
     storage::{postgres::PostgresStorage, StorageError},
     test_utils::TestStorage,
-    AddedMember, AddedSubspace, CreatedSpace, KgData, PersonalSpace, PublicSpace, RemovedMember,
-    RemovedSubspace,
+    validators::{AuthorizationMode, EditAuthorValidator},
+    AddedMember, AddedSubspace, CastVote, CreatedSpace, EventContext, KgData, PersonalSpace,
+    PublicSpace, RemovedMember, RemovedSubspace,
 };
 use indexer_utils::{checksum_address, id::derive_space_id, network_ids::GEO};
 use serial_test::serial;
@@ -30,6 +31,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 struct TestIndexer {
     storage: Arc<PostgresStorage>,
     properties_cache: Arc<PropertiesCache>,
+    author_validator: Arc<EditAuthorValidator>,
 }
 
 impl TestIndexer {
@@ -37,12 +39,20 @@ impl TestIndexer {
         TestIndexer {
             storage,
             properties_cache,
+            author_validator: Arc::new(EditAuthorValidator::default()),
         }
     }
 
     pub async fn run(&self, blocks: &Vec<KgData>) -> Result<(), IndexingError> {
         for block in blocks {
-            root_handler::run(block, &block.block, &self.storage, &self.properties_cache).await?;
+            root_handler::run(
+                block,
+                &block.block,
+                &self.storage,
+                &self.properties_cache,
+                &self.author_validator,
+            )
+            .await?;
         }
 
         Ok(())
@@ -138,6 +148,7 @@ async fn main() -> Result<(), IndexingError> {
         cursor: String::from("5"),
         block_number: 1,
         timestamp: String::from("5"),
+        ..Default::default()
     };
 
     let properties_cache = Arc::new(PropertiesCache::new());
@@ -154,6 +165,12 @@ async fn main() -> Result<(), IndexingError> {
             removed_members: vec![],
             added_subspaces: vec![],
             removed_subspaces: vec![],
+            proposals: vec![],
+            proposal_status_changes: vec![],
+            votes: vec![],
+            governance_settings: vec![],
+            dao_metadata: vec![],
+            successor_spaces: vec![],
         }])
         .await?;
 
@@ -720,6 +737,7 @@ async fn test_property_no_overwrite() -> Result<(), IndexingError> {
         cursor: String::from("6"),
         block_number: 2,
         timestamp: String::from("6"),
+        ..Default::default()
     };
 
     let properties_cache = Arc::new(PropertiesCache::new());
@@ -737,6 +755,12 @@ async fn test_property_no_overwrite() -> Result<(), IndexingError> {
             removed_members: vec![],
             added_subspaces: vec![],
             removed_subspaces: vec![],
+            proposals: vec![],
+            proposal_status_changes: vec![],
+            votes: vec![],
+            governance_settings: vec![],
+            dao_metadata: vec![],
+            successor_spaces: vec![],
         }])
         .await?;
 
@@ -765,6 +789,12 @@ async fn test_property_no_overwrite() -> Result<(), IndexingError> {
             removed_members: vec![],
             added_subspaces: vec![],
             removed_subspaces: vec![],
+            proposals: vec![],
+            proposal_status_changes: vec![],
+            votes: vec![],
+            governance_settings: vec![],
+            dao_metadata: vec![],
+            successor_spaces: vec![],
         }])
         .await?;
 
@@ -817,6 +847,7 @@ async fn test_property_squashing() -> Result<(), IndexingError> {
         cursor: String::from("7"),
         block_number: 3,
         timestamp: String::from("7"),
+        ..Default::default()
     };
 
     let properties_cache = Arc::new(PropertiesCache::new());
@@ -834,6 +865,12 @@ async fn test_property_squashing() -> Result<(), IndexingError> {
             removed_members: vec![],
             added_subspaces: vec![],
             removed_subspaces: vec![],
+            proposals: vec![],
+            proposal_status_changes: vec![],
+            votes: vec![],
+            governance_settings: vec![],
+            dao_metadata: vec![],
+            successor_spaces: vec![],
         }])
         .await?;
 
@@ -977,6 +1014,9 @@ fn make_personal_space(dao_address: &str) -> CreatedSpace {
         dao_address: dao_address.to_string(),
         space_address: format!("{}_space", dao_address),
         personal_plugin: format!("{}_personal_plugin", dao_address),
+        initial_editors: vec![],
+        initial_members: vec![],
+        context: Default::default(),
     })
 }
 
@@ -986,6 +1026,9 @@ fn make_public_space(dao_address: &str) -> CreatedSpace {
         space_address: format!("{}_space", dao_address),
         membership_plugin: format!("{}_membership_plugin", dao_address),
         governance_plugin: format!("{}_governance_plugin", dao_address),
+        initial_editors: vec![],
+        initial_members: vec![],
+        context: Default::default(),
     })
 }
 
@@ -999,6 +1042,7 @@ fn make_kg_data_with_spaces(
             cursor: block_number.to_string(),
             block_number,
             timestamp: "1234567890".to_string(),
+            ..Default::default()
         },
         edits,
         spaces,
@@ -1008,6 +1052,12 @@ fn make_kg_data_with_spaces(
         removed_members: vec![],
         added_subspaces: vec![],
         removed_subspaces: vec![],
+        proposals: vec![],
+        proposal_status_changes: vec![],
+        votes: vec![],
+        governance_settings: vec![],
+        dao_metadata: vec![],
+        successor_spaces: vec![],
     }
 }
 
@@ -1297,6 +1347,7 @@ fn make_added_member(dao_address: &str, editor_address: &str) -> AddedMember {
     AddedMember {
         dao_address: dao_address.to_string(),
         editor_address: editor_address.to_string(),
+        context: Default::default(),
     }
 }
 
@@ -1304,6 +1355,7 @@ fn make_removed_member(dao_address: &str, editor_address: &str) -> RemovedMember
     RemovedMember {
         dao_address: dao_address.to_string(),
         editor_address: editor_address.to_string(),
+        context: Default::default(),
     }
 }
 
@@ -1319,6 +1371,7 @@ fn make_kg_data_with_membership(
             cursor: block_number.to_string(),
             block_number,
             timestamp: "1234567890".to_string(),
+            ..Default::default()
         },
         edits: vec![],
         spaces: vec![],
@@ -1328,6 +1381,12 @@ fn make_kg_data_with_membership(
         removed_editors,
         added_subspaces: vec![],
         removed_subspaces: vec![],
+        proposals: vec![],
+        proposal_status_changes: vec![],
+        votes: vec![],
+        governance_settings: vec![],
+        dao_metadata: vec![],
+        successor_spaces: vec![],
     }
 }
 
@@ -1731,10 +1790,97 @@ async fn test_space_indexing_with_edits() -> Result<(), IndexingError> {
     Ok(())
 }
 
+#[tokio::test]
+#[serial]
+async fn test_edit_from_initial_editor_of_space_created_in_block() -> Result<(), IndexingError> {
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set");
+    let storage = Arc::new(PostgresStorage::new(&database_url).await?);
+    let test_storage = TestStorage::new(storage.clone());
+    let indexer = TestIndexer {
+        storage: storage.clone(),
+        properties_cache: Arc::new(PropertiesCache::new()),
+        author_validator: Arc::new(EditAuthorValidator::new(AuthorizationMode::Reject)),
+    };
+
+    let dao_address = generate_unique_address("initial_editor_test_dao");
+    let editor_address = generate_unique_address("initial_editor_test_editor");
+    let outsider_address = generate_unique_address("initial_editor_test_outsider");
+    let space_id = derive_space_id(GEO, &checksum_address(dao_address.clone()));
+
+    let space = CreatedSpace::Personal(PersonalSpace {
+        dao_address: dao_address.clone(),
+        space_address: format!("{}_space", dao_address),
+        personal_plugin: format!("{}_personal_plugin", dao_address),
+        initial_editors: vec![editor_address.clone()],
+        initial_members: vec![],
+        context: Default::default(),
+    });
+
+    let make_item = |author: &str, entity_id: Uuid| {
+        let property_id = Uuid::new_v4().to_string();
+        PreprocessedEdit {
+            edit: Some(Edit {
+                id: Uuid::new_v4().as_bytes().to_vec(),
+                name: "Initial Editor Edit".to_string(),
+                ops: vec![
+                    make_property_op(&property_id, PbDataType::String),
+                    make_entity_op(
+                        TestEntityOpType::UPDATE,
+                        &entity_id.to_string(),
+                        vec![TestValue {
+                            property_id,
+                            value: Some("value".to_string()),
+                        }],
+                    ),
+                ],
+                authors: vec![author.as_bytes().to_vec()],
+                language: None,
+            }),
+            is_errored: false,
+            space_id,
+            cid: "".to_string(),
+        }
+    };
+
+    // The space's editors are written concurrently with the edits, so the
+    // initial editor is only known from the block itself
+    let editor_entity = Uuid::new_v4();
+    let outsider_entity = Uuid::new_v4();
+    let kg_data = make_kg_data_with_spaces(
+        7,
+        vec![
+            make_item(&editor_address, editor_entity),
+            make_item(&outsider_address, outsider_entity),
+        ],
+        vec![space],
+    );
+
+    indexer.run(&vec![kg_data]).await?;
+
+    assert!(
+        test_storage
+            .get_entity_by_id(&editor_entity)
+            .await?
+            .is_some(),
+        "Edit from the initial editor should be applied"
+    );
+    assert!(
+        test_storage
+            .get_entity_by_id(&outsider_entity)
+            .await?
+            .is_none(),
+        "Edit from a non-editor should be rejected"
+    );
+
+    Ok(())
+}
+
 fn make_added_subspace(dao_address: &str, subspace_address: &str) -> AddedSubspace {
     AddedSubspace {
         dao_address: dao_address.to_string(),
         subspace_address: subspace_address.to_string(),
+        context: Default::default(),
     }
 }
 
@@ -1742,6 +1888,7 @@ fn make_removed_subspace(dao_address: &str, subspace_address: &str) -> RemovedSu
     RemovedSubspace {
         dao_address: dao_address.to_string(),
         subspace_address: subspace_address.to_string(),
+        context: Default::default(),
     }
 }
 
@@ -1755,6 +1902,7 @@ fn make_kg_data_with_subspaces(
             cursor: block_number.to_string(),
             block_number,
             timestamp: "1234567890".to_string(),
+            ..Default::default()
         },
         edits: vec![],
         spaces: vec![],
@@ -1764,6 +1912,12 @@ fn make_kg_data_with_subspaces(
         removed_editors: vec![],
         added_subspaces,
         removed_subspaces,
+        proposals: vec![],
+        proposal_status_changes: vec![],
+        votes: vec![],
+        governance_settings: vec![],
+        dao_metadata: vec![],
+        successor_spaces: vec![],
     }
 }
 
@@ -1990,6 +2144,7 @@ async fn test_subspace_indexing_with_other_operations() -> Result<(), IndexingEr
             cursor: "1".to_string(),
             block_number: 1,
             timestamp: "1234567890".to_string(),
+            ..Default::default()
         },
         edits: vec![],
         spaces,
@@ -1999,6 +2154,12 @@ async fn test_subspace_indexing_with_other_operations() -> Result<(), IndexingEr
         removed_editors: vec![],
         added_subspaces,
         removed_subspaces: vec![],
+        proposals: vec![],
+        proposal_status_changes: vec![],
+        votes: vec![],
+        governance_settings: vec![],
+        dao_metadata: vec![],
+        successor_spaces: vec![],
     };
     let blocks = vec![kg_data];
 
@@ -2008,6 +2169,81 @@ async fn test_subspace_indexing_with_other_operations() -> Result<(), IndexingEr
     Ok(())
 }
 
+fn make_cast_vote(
+    plugin_address: &str,
+    voter: &str,
+    vote_option: u64,
+    voting_power: &str,
+) -> CastVote {
+    CastVote {
+        plugin_address: plugin_address.to_string(),
+        proposal_id: "1".to_string(),
+        voter: voter.to_string(),
+        vote_option,
+        voting_power: voting_power.to_string(),
+        context: EventContext {
+            tx_hash: "0xabc".to_string(),
+            log_index: 0,
+            ..Default::default()
+        },
+    }
+}
+
+fn make_kg_data_with_votes(block_number: u64, votes: Vec<CastVote>) -> KgData {
+    KgData {
+        votes,
+        ..make_kg_data_with_spaces(block_number, vec![], vec![])
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_proposal_vote_indexing() -> Result<(), IndexingError> {
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL not set");
+    let postgres_storage = Arc::new(PostgresStorage::new(&database_url).await?);
+    let test_storage = TestStorage::new(postgres_storage.clone());
+    let properties_cache = Arc::new(PropertiesCache::new());
+    let indexer = TestIndexer::new(postgres_storage, properties_cache);
+
+    test_storage.clear_table("proposal_votes").await?;
+
+    let plugin_address = generate_unique_address("vote_test_plugin");
+    let voter = generate_unique_address("vote_test_voter");
+    // Larger than any integer type, as voting power is a uint256
+    let voting_power =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+    let first_vote = make_kg_data_with_votes(
+        1,
+        vec![make_cast_vote(&plugin_address, &voter, 2, voting_power)],
+    );
+    let replacing_vote = make_kg_data_with_votes(
+        2,
+        vec![make_cast_vote(&plugin_address, &voter, 3, voting_power)],
+    );
+
+    // Replaying the first block after the replacing vote keeps the latter
+    indexer
+        .run(&vec![first_vote.clone(), replacing_vote, first_vote])
+        .await?;
+
+    let rows: Vec<(String, i32, String, i64)> = sqlx::query_as(
+        "SELECT voter, vote_option, voting_power::text, block_number FROM proposal_votes WHERE plugin_address = $1 AND proposal_id = '1'",
+    )
+    .bind(checksum_address(plugin_address))
+    .fetch_all(test_storage.get_pool())
+    .await
+    .map_err(|e| IndexingError::StorageError(StorageError::Database(e)))?;
+
+    assert_eq!(
+        rows,
+        vec![(checksum_address(voter), 3, voting_power.to_string(), 2)]
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 #[serial]
 async fn test_properties_cache_initialization_from_database() -> Result<(), IndexingError> {
@@ -11,12 +11,14 @@ use indexer::{
     cache::{properties_cache::PropertiesCache, PreprocessedEdit},
     error::IndexingError,
     storage::postgres::PostgresStorage,
+    validators::EditAuthorValidator,
     CreatedSpace, KgData, PersonalSpace, PublicSpace,
 };
 
 struct TestIndexer {
     storage: Arc<PostgresStorage>,
     properties_cache: Arc<PropertiesCache>,
+    author_validator: Arc<EditAuthorValidator>,
 }
 
 impl TestIndexer {
@@ -24,12 +26,20 @@ impl TestIndexer {
         TestIndexer {
             storage,
             properties_cache,
+            author_validator: Arc::new(EditAuthorValidator::default()),
         }
     }
 
     pub async fn run(&self, blocks: &Vec<KgData>) -> Result<(), IndexingError> {
         for block in blocks {
-            root_handler::run(block, &block.block, &self.storage, &self.properties_cache).await?;
+            root_handler::run(
+                block,
+                &block.block,
+                &self.storage,
+                &self.properties_cache,
+                &self.author_validator,
+            )
+            .await?;
         }
 
         Ok(())
@@ -99,12 +109,14 @@ async fn main() -> Result<(), IndexingError> {
         cursor: String::from("5"),
         block_number: 1,
         timestamp: String::from("5"),
+        ..Default::default()
     };
 
     let block_2 = BlockMetadata {
         cursor: String::from("6"),
         block_number: 2,
         timestamp: String::from("6"),
+        ..Default::default()
     };
 
     let root_space = CreatedSpace::Public(PublicSpace {
@@ -112,30 +124,45 @@ async fn main() -> Result<(), IndexingError> {
         space_address: "0xABCDEF1234567890123456789012345678901234".to_string(),
         membership_plugin: "0x1111111111111111111111111111111111111111".to_string(),
         governance_plugin: "0x3333333333333333333333333333333333333333".to_string(),
+        initial_editors: vec![],
+        initial_members: vec![],
+        context: Default::default(),
     });
 
     let crypto_space = CreatedSpace::Personal(PersonalSpace {
         dao_address: "0x0987654321098765432109876543210987654321".to_string(),
         space_address: "0xFEDCBA0987654321098765432109876543210987".to_string(),
         personal_plugin: "0x2222222222222222222222222222222222222222".to_string(),
+        initial_editors: vec![],
+        initial_members: vec![],
+        context: Default::default(),
     });
 
     let crypto_events_space = CreatedSpace::Personal(PersonalSpace {
         dao_address: "0x098765432109876543210987654321098765432f".to_string(),
         space_address: "0xFEDCBA0987654321098765432109876543210987".to_string(),
         personal_plugin: "0x2222222222222222222222222222222222222222".to_string(),
+        initial_editors: vec![],
+        initial_members: vec![],
+        context: Default::default(),
     });
 
     let regions_space = CreatedSpace::Personal(PersonalSpace {
         dao_address: "0x098765432109876543210987654321098765432e".to_string(),
         space_address: "0xFEDCBA0987654321098765432109876543210987".to_string(),
         personal_plugin: "0x2222222222222222222222222222222222222222".to_string(),
+        initial_editors: vec![],
+        initial_members: vec![],
+        context: Default::default(),
     });
 
     let crypto_news_space = CreatedSpace::Personal(PersonalSpace {
         dao_address: "0x098765432109876543210987654321098765432g".to_string(),
         space_address: "0xFEDCBA0987654321098765432109876543210987".to_string(),
         personal_plugin: "0x2222222222222222222222222222222222222222".to_string(),
+        initial_editors: vec![],
+        initial_members: vec![],
+        context: Default::default(),
     });
 
     let indexer = TestIndexer::new(storage.clone(), properties_cache.clone());
@@ -153,6 +180,12 @@ async fn main() -> Result<(), IndexingError> {
                 removed_members: vec![],
                 added_subspaces: vec![],
                 removed_subspaces: vec![],
+                proposals: vec![],
+                proposal_status_changes: vec![],
+                votes: vec![],
+                governance_settings: vec![],
+                dao_metadata: vec![],
+                successor_spaces: vec![],
             },
             KgData {
                 block: block_2,
@@ -168,6 +201,12 @@ async fn main() -> Result<(), IndexingError> {
                 removed_members: vec![],
                 added_subspaces: vec![],
                 removed_subspaces: vec![],
+                proposals: vec![],
+                proposal_status_changes: vec![],
+                votes: vec![],
+                governance_settings: vec![],
+                dao_metadata: vec![],
+                successor_spaces: vec![],
             },
         ])
         .await?;
@@ -1,6 +1,7 @@
 pub mod graph_uri;
 pub mod id;
 pub mod network_ids;
+pub mod space_kind;
 
 use sha3::{Digest, Keccak256};
 
@@ -0,0 +1,90 @@
+//! Classification of spaces by how they are governed.
+//!
+//! Every pipeline that sees space creations (the indexer's `CreatedSpace`,
+//! hermes `HermesCreateSpace` payloads, Atlas topology events) maps them to a
+//! [`SpaceKind`], so code that branches on the type of space does it the same
+//! way everywhere. The indexer persists it in the `space_kind` column of the
+//! `spaces` table.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpaceKind {
+    /// Owned by a single account through the personal space plugin.
+    Personal,
+    /// Governed by its members through the membership and voting plugins.
+    Dao,
+    /// DAO space forked from a predecessor space.
+    Successor,
+}
+
+impl SpaceKind {
+    pub const ALL: [SpaceKind; 3] = [SpaceKind::Personal, SpaceKind::Dao, SpaceKind::Successor];
+
+    /// Name stored in the `space_kind` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpaceKind::Personal => "Personal",
+            SpaceKind::Dao => "Dao",
+            SpaceKind::Successor => "Successor",
+        }
+    }
+
+    /// Whether edits and membership go through proposals voted on by members.
+    pub fn is_governed(&self) -> bool {
+        matches!(self, SpaceKind::Dao | SpaceKind::Successor)
+    }
+}
+
+impl fmt::Display for SpaceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownSpaceKind(pub String);
+
+impl fmt::Display for UnknownSpaceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown space kind: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownSpaceKind {}
+
+impl FromStr for SpaceKind {
+    type Err = UnknownSpaceKind;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        SpaceKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| UnknownSpaceKind(value.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_space_kind_round_trips_through_str() {
+        for kind in SpaceKind::ALL {
+            assert_eq!(kind.as_str().parse::<SpaceKind>(), Ok(kind));
+        }
+
+        assert_eq!(
+            "Public".parse::<SpaceKind>(),
+            Err(UnknownSpaceKind("Public".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_only_personal_spaces_are_ungoverned() {
+        assert!(!SpaceKind::Personal.is_governed());
+        assert!(SpaceKind::Dao.is_governed());
+        assert!(SpaceKind::Successor.is_governed());
+    }
+}
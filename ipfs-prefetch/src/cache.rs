@@ -0,0 +1,2137 @@
+//! IPFS cache storage implementation.
+//!
+//! Stores resolved IPFS content so that downstream consumers
+//! (like the edits transformer) don't need to block on network I/O.
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! use ipfs_prefetch::cache::CacheSource;
+//!
+//! // Development: use in-memory cache
+//! let cache = CacheSource::mock().into_cache().await?;
+//!
+//! // Production: use PostgreSQL
+//! let cache = CacheSource::live("postgres://...").into_cache().await?;
+//!
+//! // Deployments without PostgreSQL: use Redis or an S3 bucket
+//! let cache = CacheSource::redis("redis://...").into_cache().await?;
+//! let cache = CacheSource::s3("ipfs-cache-bucket").into_cache().await?;
+//!
+//! // Consumers: read an item, waiting briefly if the cache is just behind
+//! let item = cache.wait_for("ipfs://Qm...", Duration::from_secs(2)).await?;
+//! ```
+//!
+//! ## Eviction
+//!
+//! Reads are tracked in memory and flushed to the storage by
+//! [`Cache::flush_reads`], so items can be evicted least recently read first
+//! without turning every read into a write. See [`crate::compaction`] for the
+//! job that flushes reads and evicts items.
+
+mod content;
+mod object_store;
+mod redis;
+
+pub use self::object_store::ObjectStoreStorage;
+pub use self::redis::RedisStorage;
+
+use self::content::Content;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use wire::pb::grc20::Edit;
+
+use crate::decoder::PrefetchEvent;
+
+/// How often [`Cache::wait_for`] re-reads the storage while waiting.
+///
+/// Writes through the same [`Cache`] wake waiters immediately; polling picks
+/// up items written by another process sharing the storage.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Serialization error: {0}")]
+    SerializeError(#[from] serde_json::Error),
+
+    #[error("Redis error: {0}")]
+    Redis(#[from] ::redis::RedisError),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] ::object_store::Error),
+
+    #[error("Compression error: {0}")]
+    Compression(std::io::Error),
+}
+
+/// A cached IPFS content item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheItem {
+    /// The IPFS URI (e.g., "ipfs://Qm...")
+    pub uri: String,
+    /// Decoded edit content, if successfully fetched and decoded
+    pub json: Option<Edit>,
+    /// Block timestamp when this was cached
+    pub block: String,
+    /// Space ID (16 bytes, hex-encoded)
+    pub space_id: String,
+    /// Whether fetching/decoding failed
+    pub is_errored: bool,
+    /// Number of the block the edit was published in, if known. Items
+    /// cached before blocks were recorded have none.
+    #[serde(default)]
+    pub block_number: Option<u64>,
+    /// Whether the block was undone by a reorg after the item was cached,
+    /// so its edit may never have been published on the canonical chain.
+    /// Cleared when a canonical block publishes the same URI again.
+    #[serde(default)]
+    pub orphaned: bool,
+}
+
+/// A block whose fetches were started but not all written, with everything
+/// needed to resume them after a restart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournaledBlock {
+    pub block: u64,
+    /// Cursor of the block, persisted once it and every earlier block are written
+    pub cursor: String,
+    /// Block timestamp in seconds, if the stream provided one
+    pub block_seconds: Option<i64>,
+    /// The edits whose content is fetched for the block
+    pub events: Vec<PrefetchEvent>,
+}
+
+/// An errored cache item that is due for another fetch attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryCandidate {
+    /// The IPFS URI (e.g., "ipfs://Qm...")
+    pub uri: String,
+    /// Number of retries already attempted
+    pub attempts: u32,
+}
+
+/// Number of items in a cache and the total size of their content.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub items: u64,
+    /// Size of the cached content in bytes, as measured by the backend.
+    pub bytes: u64,
+}
+
+/// Configuration for the cache storage backend.
+///
+/// Use this to explicitly choose between mock (in-memory) and live (PostgreSQL) storage,
+/// following the same pattern as `StreamSource` and `IpfsSource`.
+#[derive(Debug, Clone)]
+pub enum CacheSource {
+    /// Use in-memory cache for testing/development.
+    Mock,
+
+    /// Use PostgreSQL storage.
+    Live {
+        /// PostgreSQL connection URL
+        database_url: String,
+    },
+
+    /// Use Redis storage.
+    Redis {
+        /// Redis connection URL (e.g., "redis://127.0.0.1:6379")
+        url: String,
+    },
+
+    /// Use an S3 bucket, with credentials and region read from the `AWS_*`
+    /// environment variables.
+    S3 {
+        /// Bucket name
+        bucket: String,
+    },
+}
+
+impl CacheSource {
+    /// Create a mock (in-memory) cache source.
+    pub fn mock() -> Self {
+        Self::Mock
+    }
+
+    /// Create a live cache source with the given PostgreSQL URL.
+    pub fn live(database_url: impl Into<String>) -> Self {
+        Self::Live {
+            database_url: database_url.into(),
+        }
+    }
+
+    /// Create a Redis cache source with the given connection URL.
+    pub fn redis(url: impl Into<String>) -> Self {
+        Self::Redis { url: url.into() }
+    }
+
+    /// Create an S3 cache source storing items in the given bucket.
+    pub fn s3(bucket: impl Into<String>) -> Self {
+        Self::S3 {
+            bucket: bucket.into(),
+        }
+    }
+
+    /// Create the cache with the appropriate storage backend.
+    pub async fn into_cache(self) -> Result<Cache, CacheError> {
+        match self {
+            Self::Mock => Ok(Cache::mock()),
+            Self::Live { database_url } => {
+                let storage = PostgresStorage::new(&database_url).await?;
+                Ok(Cache::postgres(storage))
+            }
+            Self::Redis { url } => {
+                let storage = RedisStorage::new(&url).await?;
+                Ok(Cache::new(storage))
+            }
+            Self::S3 { bucket } => {
+                let storage = ObjectStoreStorage::s3(&bucket)?;
+                Ok(Cache::new(storage))
+            }
+        }
+    }
+}
+
+/// Trait for cache storage backends.
+#[async_trait::async_trait]
+pub trait CacheStorage: Send + Sync {
+    /// Insert a cache item, skipping if URI already exists.
+    async fn insert(&self, item: &CacheItem) -> Result<(), CacheError>;
+
+    /// Insert several cache items, skipping URIs that already exist. When a
+    /// URI appears more than once in `items`, the first occurrence wins.
+    ///
+    /// The default implementation calls [`CacheStorage::insert`] per item.
+    async fn insert_many(&self, items: &[CacheItem]) -> Result<(), CacheError> {
+        for item in items {
+            self.insert(item).await?;
+        }
+        Ok(())
+    }
+
+    /// Get a cache item by URI.
+    async fn get(&self, uri: &str) -> Result<Option<CacheItem>, CacheError>;
+
+    /// Get the cached items among `uris`, keyed by URI. Missing URIs are
+    /// left out.
+    ///
+    /// The default implementation calls [`CacheStorage::get`] per URI.
+    async fn get_batch(&self, uris: &[String]) -> Result<HashMap<String, CacheItem>, CacheError> {
+        let mut items = HashMap::with_capacity(uris.len());
+        for uri in uris {
+            if let Some(item) = self.get(uri).await? {
+                items.insert(uri.clone(), item);
+            }
+        }
+        Ok(items)
+    }
+
+    /// List errored items with fewer than `max_attempts` retries whose next
+    /// retry is due at `now` (unix seconds), oldest schedule first.
+    async fn errored_due(
+        &self,
+        now: i64,
+        max_attempts: u32,
+        limit: usize,
+    ) -> Result<Vec<RetryCandidate>, CacheError>;
+
+    /// Replace the content of an errored item in place and clear its error.
+    async fn resolve(&self, uri: &str, json: &Edit) -> Result<(), CacheError>;
+
+    /// Record a failed retry and when the next one is due (unix seconds).
+    async fn schedule_retry(
+        &self,
+        uri: &str,
+        attempts: u32,
+        next_retry_at: i64,
+    ) -> Result<(), CacheError>;
+
+    /// Load the cursor for a given indexer ID.
+    async fn load_cursor(&self, id: &str) -> Result<Option<String>, CacheError>;
+
+    /// Persist the cursor for a given indexer ID.
+    async fn persist_cursor(&self, id: &str, cursor: &str, block: u64) -> Result<(), CacheError>;
+
+    /// Record that every item of `block` is cached while an earlier block of
+    /// indexer `id` is still pending, so a restart doesn't fetch it again.
+    /// Backends without a place for it keep the default, and such blocks are
+    /// fetched again after a restart.
+    async fn mark_block_fetched(&self, _id: &str, _block: u64) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    /// Blocks of indexer `id` recorded by [`CacheStorage::mark_block_fetched`]
+    /// and not yet forgotten.
+    async fn fetched_blocks(&self, _id: &str) -> Result<BTreeSet<u64>, CacheError> {
+        Ok(BTreeSet::new())
+    }
+
+    /// Forget the fetched blocks of indexer `id` up to and including
+    /// `block`, once its cursor has moved past them.
+    async fn forget_fetched_blocks(&self, _id: &str, _block: u64) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    /// Record the fetches of `block` before they start, so a restart resumes
+    /// them instead of waiting for the stream to deliver the block again.
+    /// Backends without a place for it keep the default, and such blocks are
+    /// processed again from the cursor after a restart.
+    async fn journal_block(&self, _id: &str, _block: &JournaledBlock) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    /// Blocks of indexer `id` recorded by [`CacheStorage::journal_block`] and
+    /// not yet forgotten, oldest first.
+    async fn journaled_blocks(&self, _id: &str) -> Result<Vec<JournaledBlock>, CacheError> {
+        Ok(Vec::new())
+    }
+
+    /// Forget the journaled blocks of indexer `id` up to and including
+    /// `block`, once they're written and the cursor is about to move past them.
+    async fn forget_journaled_blocks(&self, _id: &str, _block: u64) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    /// Undo a reorg for indexer `id`: mark the items of `block` and later
+    /// blocks as orphaned and forget its fetched and journaled blocks from
+    /// `block` on, returning the number of items orphaned. Backends that
+    /// don't record block numbers keep the default, and their items stay as
+    /// they were cached.
+    async fn invalidate_from(&self, _id: &str, _block: u64) -> Result<u64, CacheError> {
+        Ok(0)
+    }
+
+    /// Whether this backend implements the eviction methods below.
+    ///
+    /// Backends with native expiry keep the defaults and are bounded through
+    /// their own configuration instead (`maxmemory` with an LRU policy for
+    /// Redis, lifecycle rules for S3 buckets).
+    fn supports_eviction(&self) -> bool {
+        false
+    }
+
+    /// Record when items were last read (unix seconds), for LRU eviction.
+    /// Items are treated as read when they are inserted.
+    async fn record_reads(&self, _reads: &[(String, i64)]) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    /// Up to `limit` successfully fetched items of `space_id` whose block
+    /// timestamp is at least `from_block`, ordered by block timestamp then
+    /// URI. Returns `None` if the backend can't query items by space.
+    async fn space_items(
+        &self,
+        _space_id: &str,
+        _from_block: i64,
+        _limit: usize,
+    ) -> Result<Option<Vec<CacheItem>>, CacheError> {
+        Ok(None)
+    }
+
+    /// Number of cached items and their total size, or `None` if the
+    /// backend can't count them.
+    async fn stats(&self) -> Result<Option<CacheStats>, CacheError> {
+        Ok(None)
+    }
+
+    /// Evict items whose block timestamp is before `block_timestamp` (unix
+    /// seconds), returning the number of items evicted.
+    async fn evict_before(&self, _block_timestamp: i64) -> Result<u64, CacheError> {
+        Ok(0)
+    }
+
+    /// Evict the least recently read items until the cached content takes
+    /// at most `max_bytes`, returning the number of items evicted.
+    async fn evict_lru(&self, _max_bytes: u64) -> Result<u64, CacheError> {
+        Ok(0)
+    }
+
+    /// Move up to `limit` items stored in a previous format to the current
+    /// one, returning the number of items moved. Backends with a single
+    /// format keep the default.
+    async fn migrate_legacy_content(&self, _limit: usize) -> Result<u64, CacheError> {
+        Ok(0)
+    }
+}
+
+// =============================================================================
+// Key-Value Entries
+// =============================================================================
+
+/// A cache item as stored by the key-value backends (Redis, object stores),
+/// which key items by CID and keep the retry schedule alongside the content.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredEntry {
+    uri: String,
+    json: Option<Edit>,
+    block: String,
+    space_id: String,
+    is_errored: bool,
+    #[serde(default)]
+    block_number: Option<u64>,
+    #[serde(default)]
+    orphaned: bool,
+    #[serde(default)]
+    retry_attempts: u32,
+    #[serde(default)]
+    next_retry_at: Option<i64>,
+}
+
+impl StoredEntry {
+    fn new(item: &CacheItem) -> Self {
+        Self {
+            uri: item.uri.clone(),
+            json: item.json.clone(),
+            block: item.block.clone(),
+            space_id: item.space_id.clone(),
+            is_errored: item.is_errored,
+            block_number: item.block_number,
+            orphaned: item.orphaned,
+            retry_attempts: 0,
+            next_retry_at: None,
+        }
+    }
+
+    fn into_item(self) -> CacheItem {
+        CacheItem {
+            uri: self.uri,
+            json: self.json,
+            block: self.block,
+            space_id: self.space_id,
+            is_errored: self.is_errored,
+            block_number: self.block_number,
+            orphaned: self.orphaned,
+        }
+    }
+
+    fn is_due(&self, now: i64, max_attempts: u32) -> bool {
+        self.is_errored
+            && self.retry_attempts < max_attempts
+            && self.next_retry_at.is_none_or(|at| at <= now)
+    }
+
+    fn retry_candidate(&self) -> RetryCandidate {
+        RetryCandidate {
+            uri: self.uri.clone(),
+            attempts: self.retry_attempts,
+        }
+    }
+
+    fn resolve(&mut self, json: &Edit) {
+        self.json = Some(json.clone());
+        self.is_errored = false;
+        self.next_retry_at = None;
+    }
+
+    fn schedule_retry(&mut self, attempts: u32, next_retry_at: i64) {
+        self.retry_attempts = attempts;
+        self.next_retry_at = Some(next_retry_at);
+    }
+}
+
+/// Current unix time in seconds.
+pub(crate) fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Block timestamp of an item as unix seconds, if it has one.
+fn block_timestamp(block: &str) -> Option<i64> {
+    block.parse().ok()
+}
+
+/// Key of an item in the key-value backends: its CID, without `ipfs://`.
+fn cid_key(uri: &str) -> &str {
+    uri.split_once("://").map(|(_, cid)| cid).unwrap_or(uri)
+}
+
+// =============================================================================
+// Mock (In-Memory) Storage
+// =============================================================================
+
+/// In-memory storage backend for testing/development.
+pub struct MockStorage {
+    items: RwLock<HashMap<String, StoredItem>>,
+    cursors: RwLock<HashMap<String, (String, u64)>>,
+    fetched_blocks: RwLock<HashMap<String, BTreeSet<u64>>>,
+    journal: RwLock<HashMap<String, BTreeMap<u64, JournaledBlock>>>,
+}
+
+/// Internal representation of a stored cache item.
+struct StoredItem {
+    json: Option<Edit>,
+    block: String,
+    space_id: String,
+    is_errored: bool,
+    block_number: Option<u64>,
+    orphaned: bool,
+    retry_attempts: u32,
+    next_retry_at: Option<i64>,
+    last_read: i64,
+}
+
+impl StoredItem {
+    fn size(&self) -> u64 {
+        serde_json::to_vec(&self.json).map_or(0, |json| json.len() as u64)
+    }
+
+    fn to_item(&self, uri: &str) -> CacheItem {
+        CacheItem {
+            uri: uri.to_string(),
+            json: self.json.clone(),
+            block: self.block.clone(),
+            space_id: self.space_id.clone(),
+            is_errored: self.is_errored,
+            block_number: self.block_number,
+            orphaned: self.orphaned,
+        }
+    }
+}
+
+impl MockStorage {
+    pub fn new() -> Self {
+        Self {
+            items: RwLock::new(HashMap::new()),
+            cursors: RwLock::new(HashMap::new()),
+            fetched_blocks: RwLock::new(HashMap::new()),
+            journal: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for MockStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheStorage for MockStorage {
+    async fn insert(&self, item: &CacheItem) -> Result<(), CacheError> {
+        let mut items = self.items.write().unwrap();
+        match items.get_mut(&item.uri) {
+            // Orphaned items are claimed by the block publishing them again
+            Some(stored) if stored.orphaned => {
+                stored.block = item.block.clone();
+                stored.block_number = item.block_number;
+                stored.orphaned = false;
+            }
+            // Otherwise existing items are kept (matches PostgreSQL ON CONFLICT)
+            Some(_) => {}
+            None => {
+                items.insert(
+                    item.uri.clone(),
+                    StoredItem {
+                        json: item.json.clone(),
+                        block: item.block.clone(),
+                        space_id: item.space_id.clone(),
+                        is_errored: item.is_errored,
+                        block_number: item.block_number,
+                        orphaned: item.orphaned,
+                        retry_attempts: 0,
+                        next_retry_at: None,
+                        last_read: unix_now(),
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn get(&self, uri: &str) -> Result<Option<CacheItem>, CacheError> {
+        let items = self.items.read().unwrap();
+        Ok(items.get(uri).map(|stored| stored.to_item(uri)))
+    }
+
+    async fn space_items(
+        &self,
+        space_id: &str,
+        from_block: i64,
+        limit: usize,
+    ) -> Result<Option<Vec<CacheItem>>, CacheError> {
+        let items = self.items.read().unwrap();
+
+        let mut matching: Vec<(i64, CacheItem)> = items
+            .iter()
+            .filter(|(_, stored)| stored.space_id == space_id && !stored.is_errored)
+            .filter_map(|(uri, stored)| {
+                let block = block_timestamp(&stored.block).filter(|at| *at >= from_block)?;
+                Some((block, stored.to_item(uri)))
+            })
+            .collect();
+        matching.sort_by(|(a, a_item), (b, b_item)| (a, &a_item.uri).cmp(&(b, &b_item.uri)));
+
+        Ok(Some(
+            matching
+                .into_iter()
+                .take(limit)
+                .map(|(_, item)| item)
+                .collect(),
+        ))
+    }
+
+    async fn errored_due(
+        &self,
+        now: i64,
+        max_attempts: u32,
+        limit: usize,
+    ) -> Result<Vec<RetryCandidate>, CacheError> {
+        let items = self.items.read().unwrap();
+
+        let mut due: Vec<_> = items
+            .iter()
+            .filter(|(_, stored)| {
+                stored.is_errored
+                    && stored.retry_attempts < max_attempts
+                    && stored.next_retry_at.is_none_or(|at| at <= now)
+            })
+            .collect();
+        due.sort_by_key(|(uri, stored)| (stored.next_retry_at, uri.as_str()));
+
+        Ok(due
+            .into_iter()
+            .take(limit)
+            .map(|(uri, stored)| RetryCandidate {
+                uri: uri.clone(),
+                attempts: stored.retry_attempts,
+            })
+            .collect())
+    }
+
+    async fn resolve(&self, uri: &str, json: &Edit) -> Result<(), CacheError> {
+        let mut items = self.items.write().unwrap();
+        if let Some(stored) = items.get_mut(uri) {
+            stored.json = Some(json.clone());
+            stored.is_errored = false;
+            stored.next_retry_at = None;
+        }
+        Ok(())
+    }
+
+    async fn schedule_retry(
+        &self,
+        uri: &str,
+        attempts: u32,
+        next_retry_at: i64,
+    ) -> Result<(), CacheError> {
+        let mut items = self.items.write().unwrap();
+        if let Some(stored) = items.get_mut(uri) {
+            stored.retry_attempts = attempts;
+            stored.next_retry_at = Some(next_retry_at);
+        }
+        Ok(())
+    }
+
+    async fn load_cursor(&self, id: &str) -> Result<Option<String>, CacheError> {
+        let cursors = self.cursors.read().unwrap();
+        Ok(cursors.get(id).map(|(cursor, _)| cursor.clone()))
+    }
+
+    async fn persist_cursor(&self, id: &str, cursor: &str, block: u64) -> Result<(), CacheError> {
+        let mut cursors = self.cursors.write().unwrap();
+        cursors.insert(id.to_string(), (cursor.to_string(), block));
+        Ok(())
+    }
+
+    async fn mark_block_fetched(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        let mut fetched = self.fetched_blocks.write().unwrap();
+        fetched.entry(id.to_string()).or_default().insert(block);
+        Ok(())
+    }
+
+    async fn fetched_blocks(&self, id: &str) -> Result<BTreeSet<u64>, CacheError> {
+        let fetched = self.fetched_blocks.read().unwrap();
+        Ok(fetched.get(id).cloned().unwrap_or_default())
+    }
+
+    async fn forget_fetched_blocks(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        let mut fetched = self.fetched_blocks.write().unwrap();
+        if let Some(blocks) = fetched.get_mut(id) {
+            blocks.retain(|fetched| *fetched > block);
+        }
+        Ok(())
+    }
+
+    async fn journal_block(&self, id: &str, block: &JournaledBlock) -> Result<(), CacheError> {
+        let mut journal = self.journal.write().unwrap();
+        journal
+            .entry(id.to_string())
+            .or_default()
+            .insert(block.block, block.clone());
+        Ok(())
+    }
+
+    async fn journaled_blocks(&self, id: &str) -> Result<Vec<JournaledBlock>, CacheError> {
+        let journal = self.journal.read().unwrap();
+        Ok(journal
+            .get(id)
+            .map(|blocks| blocks.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn forget_journaled_blocks(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        let mut journal = self.journal.write().unwrap();
+        if let Some(blocks) = journal.get_mut(id) {
+            blocks.retain(|journaled, _| *journaled > block);
+        }
+        Ok(())
+    }
+
+    async fn invalidate_from(&self, id: &str, block: u64) -> Result<u64, CacheError> {
+        if let Some(blocks) = self.fetched_blocks.write().unwrap().get_mut(id) {
+            blocks.retain(|fetched| *fetched < block);
+        }
+        if let Some(blocks) = self.journal.write().unwrap().get_mut(id) {
+            blocks.retain(|journaled, _| *journaled < block);
+        }
+
+        let mut items = self.items.write().unwrap();
+        let mut orphaned = 0;
+        for stored in items.values_mut() {
+            if !stored.orphaned && stored.block_number.is_some_and(|number| number >= block) {
+                stored.orphaned = true;
+                orphaned += 1;
+            }
+        }
+        Ok(orphaned)
+    }
+
+    fn supports_eviction(&self) -> bool {
+        true
+    }
+
+    async fn record_reads(&self, reads: &[(String, i64)]) -> Result<(), CacheError> {
+        let mut items = self.items.write().unwrap();
+        for (uri, read_at) in reads {
+            if let Some(stored) = items.get_mut(uri) {
+                stored.last_read = stored.last_read.max(*read_at);
+            }
+        }
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<Option<CacheStats>, CacheError> {
+        let items = self.items.read().unwrap();
+        Ok(Some(CacheStats {
+            items: items.len() as u64,
+            bytes: items.values().map(StoredItem::size).sum(),
+        }))
+    }
+
+    async fn evict_before(&self, before: i64) -> Result<u64, CacheError> {
+        let mut items = self.items.write().unwrap();
+        let count = items.len();
+        items.retain(|_, stored| block_timestamp(&stored.block).is_none_or(|at| at >= before));
+        Ok((count - items.len()) as u64)
+    }
+
+    async fn evict_lru(&self, max_bytes: u64) -> Result<u64, CacheError> {
+        let mut items = self.items.write().unwrap();
+
+        // Keep the most recently read items that fit, like the PostgreSQL
+        // running total over `last_read DESC`
+        let mut by_recency: Vec<_> = items
+            .iter()
+            .map(|(uri, stored)| (stored.last_read, uri.clone(), stored.size()))
+            .collect();
+        by_recency.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        let mut total = 0;
+        let mut evicted = 0;
+        for (_, uri, size) in by_recency {
+            total += size;
+            if total > max_bytes {
+                items.remove(&uri);
+                evicted += 1;
+            }
+        }
+
+        Ok(evicted)
+    }
+}
+
+// =============================================================================
+// PostgreSQL Storage
+// =============================================================================
+
+use sqlx::{postgres::PgPoolOptions, Postgres, Transaction};
+
+/// Columns selected for an item, with its content joined in.
+const ITEM_COLUMNS: &str = "c.uri, c.json, cc.data, c.block, c.space_id, c.is_errored, \
+     c.block_number, c.orphaned FROM ipfs_cache c LEFT JOIN ipfs_cache_content cc ON cc.hash = c.content_hash";
+
+/// Conflict clause of item inserts: existing items are kept, except orphaned
+/// ones, which take the block of the insert and are no longer orphaned.
+const CLAIM_ORPHANED: &str = "ON CONFLICT (uri) DO UPDATE SET \
+     block = EXCLUDED.block, block_number = EXCLUDED.block_number, orphaned = false \
+     WHERE ipfs_cache.orphaned";
+
+/// An `ipfs_cache` row with its content.
+#[derive(sqlx::FromRow)]
+struct ItemRow {
+    uri: String,
+    /// Inline JSON of rows written before content was deduplicated.
+    json: Option<serde_json::Value>,
+    /// Compressed content referenced by `content_hash`.
+    data: Option<Vec<u8>>,
+    block: String,
+    space_id: String,
+    is_errored: bool,
+    block_number: Option<i64>,
+    orphaned: bool,
+}
+
+impl ItemRow {
+    fn into_item(self) -> Result<CacheItem, CacheError> {
+        let json = match (self.data, self.json) {
+            (Some(data), _) => Some(content::decode(&data)?),
+            (None, Some(json)) => serde_json::from_value(json)?,
+            (None, None) => None,
+        };
+
+        Ok(CacheItem {
+            uri: self.uri,
+            json,
+            block: self.block,
+            space_id: self.space_id,
+            is_errored: self.is_errored,
+            block_number: self.block_number.map(|number| number as u64),
+            orphaned: self.orphaned,
+        })
+    }
+}
+
+/// PostgreSQL storage backend for the IPFS cache.
+///
+/// Decoded edits are stored once per distinct content in
+/// `ipfs_cache_content`, compressed and reference counted, and items point
+/// at them through `content_hash`. Rows written before that keep their
+/// inline `json` until [`CacheStorage::migrate_legacy_content`] moves it.
+pub struct PostgresStorage {
+    connection: sqlx::Pool<Postgres>,
+}
+
+impl PostgresStorage {
+    /// Create a new storage instance connected to the database.
+    pub async fn new(database_url: &str) -> Result<Self, CacheError> {
+        let connection = PgPoolOptions::new()
+            .max_connections(20)
+            .connect(database_url)
+            .await?;
+
+        Ok(PostgresStorage { connection })
+    }
+
+    /// Store `content`, or take another reference to it if it's already stored.
+    async fn acquire_content(
+        tx: &mut Transaction<'_, Postgres>,
+        content: &Content,
+    ) -> Result<(), CacheError> {
+        sqlx::query(
+            "INSERT INTO ipfs_cache_content (hash, data, ref_count) VALUES ($1, $2, 1) \
+             ON CONFLICT (hash) DO UPDATE SET ref_count = ipfs_cache_content.ref_count + 1",
+        )
+        .bind(&content.hash)
+        .bind(&content.data)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop one reference per hash in `hashes`, deleting content that is no
+    /// longer referenced.
+    async fn release_content(
+        tx: &mut Transaction<'_, Postgres>,
+        hashes: &[String],
+    ) -> Result<(), CacheError> {
+        if hashes.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "UPDATE ipfs_cache_content cc SET ref_count = cc.ref_count - t.refs \
+             FROM (SELECT hash, count(*)::int AS refs FROM UNNEST($1::text[]) AS hash GROUP BY hash) t \
+             WHERE cc.hash = t.hash",
+        )
+        .bind(hashes)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query("DELETE FROM ipfs_cache_content WHERE hash = ANY($1) AND ref_count <= 0")
+            .bind(hashes)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store every content in `contents` with the given number of new
+    /// references, adding to the references of content already stored.
+    async fn acquire_contents(
+        tx: &mut Transaction<'_, Postgres>,
+        contents: HashMap<String, (Vec<u8>, i32)>,
+    ) -> Result<(), CacheError> {
+        if contents.is_empty() {
+            return Ok(());
+        }
+
+        let mut hashes = Vec::with_capacity(contents.len());
+        let mut data = Vec::with_capacity(contents.len());
+        let mut refs = Vec::with_capacity(contents.len());
+        for (hash, (content, count)) in contents {
+            hashes.push(hash);
+            data.push(content);
+            refs.push(count);
+        }
+
+        sqlx::query(
+            "INSERT INTO ipfs_cache_content (hash, data, ref_count) \
+             SELECT * FROM UNNEST($1::text[], $2::bytea[], $3::int[]) \
+             ON CONFLICT (hash) DO UPDATE SET ref_count = ipfs_cache_content.ref_count + EXCLUDED.ref_count",
+        )
+        .bind(&hashes)
+        .bind(&data)
+        .bind(&refs)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Delete the items matching `condition`, releasing their content.
+    async fn delete_where(&self, condition: &str, bind: i64) -> Result<u64, CacheError> {
+        let mut tx = self.connection.begin().await?;
+
+        let deleted: Vec<(Option<String>,)> = sqlx::query_as(&format!(
+            "DELETE FROM ipfs_cache WHERE {condition} RETURNING content_hash"
+        ))
+        .bind(bind)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let hashes: Vec<String> = deleted.iter().filter_map(|(hash,)| hash.clone()).collect();
+        Self::release_content(&mut tx, &hashes).await?;
+        tx.commit().await?;
+
+        Ok(deleted.len() as u64)
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheStorage for PostgresStorage {
+    async fn insert(&self, item: &CacheItem) -> Result<(), CacheError> {
+        let content = item.json.as_ref().map(content::encode).transpose()?;
+        let mut tx = self.connection.begin().await?;
+
+        // Orphaned items are claimed by the block publishing them again, and
+        // `xmax = 0` tells rows inserted apart from claimed ones
+        let inserted = sqlx::query_scalar::<_, bool>(&format!(
+            "INSERT INTO ipfs_cache \
+             (uri, content_hash, block, space_id, is_errored, block_number, orphaned, last_read) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, EXTRACT(EPOCH FROM now())::bigint) \
+             {CLAIM_ORPHANED} RETURNING (xmax = 0)"
+        ))
+        .bind(&item.uri)
+        .bind(content.as_ref().map(|content| &content.hash))
+        .bind(&item.block)
+        .bind(&item.space_id)
+        .bind(item.is_errored)
+        .bind(item.block_number.map(|number| number as i64))
+        .bind(item.orphaned)
+        .fetch_optional(&mut *tx)
+        .await?
+        .unwrap_or(false);
+
+        // Existing items keep their content, so only new ones take a reference
+        if let (true, Some(content)) = (inserted, &content) {
+            Self::acquire_content(&mut tx, content).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn insert_many(&self, items: &[CacheItem]) -> Result<(), CacheError> {
+        // A URI repeated within one statement would conflict with itself
+        let mut seen = HashSet::with_capacity(items.len());
+        let items: Vec<&CacheItem> = items
+            .iter()
+            .filter(|item| seen.insert(item.uri.as_str()))
+            .collect();
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let mut contents = HashMap::with_capacity(items.len());
+        let mut uris = Vec::with_capacity(items.len());
+        let mut hashes = Vec::with_capacity(items.len());
+        let mut blocks = Vec::with_capacity(items.len());
+        let mut space_ids = Vec::with_capacity(items.len());
+        let mut errored = Vec::with_capacity(items.len());
+        let mut block_numbers = Vec::with_capacity(items.len());
+        let mut orphaned = Vec::with_capacity(items.len());
+        for item in &items {
+            let content = item.json.as_ref().map(content::encode).transpose()?;
+            uris.push(item.uri.clone());
+            hashes.push(content.as_ref().map(|content| content.hash.clone()));
+            blocks.push(item.block.clone());
+            space_ids.push(item.space_id.clone());
+            errored.push(item.is_errored);
+            block_numbers.push(item.block_number.map(|number| number as i64));
+            orphaned.push(item.orphaned);
+            if let Some(content) = content {
+                contents.insert(item.uri.clone(), content);
+            }
+        }
+
+        let mut tx = self.connection.begin().await?;
+
+        let written: Vec<(String, bool)> = sqlx::query_as(&format!(
+            "INSERT INTO ipfs_cache \
+             (uri, content_hash, block, space_id, is_errored, block_number, orphaned, last_read) \
+             SELECT t.*, EXTRACT(EPOCH FROM now())::bigint \
+             FROM UNNEST($1::text[], $2::text[], $3::text[], $4::text[], $5::bool[], $6::bigint[], $7::bool[]) AS t \
+             {CLAIM_ORPHANED} RETURNING uri, (xmax = 0)"
+        ))
+        .bind(&uris)
+        .bind(&hashes)
+        .bind(&blocks)
+        .bind(&space_ids)
+        .bind(&errored)
+        .bind(&block_numbers)
+        .bind(&orphaned)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // Existing items keep their content, so only new ones take a reference
+        let mut references: HashMap<String, (Vec<u8>, i32)> = HashMap::new();
+        for (uri, _) in written.into_iter().filter(|(_, inserted)| *inserted) {
+            if let Some(content) = contents.remove(&uri) {
+                references
+                    .entry(content.hash)
+                    .or_insert((content.data, 0))
+                    .1 += 1;
+            }
+        }
+        Self::acquire_contents(&mut tx, references).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get(&self, uri: &str) -> Result<Option<CacheItem>, CacheError> {
+        let row: Option<ItemRow> =
+            sqlx::query_as(&format!("SELECT {ITEM_COLUMNS} WHERE c.uri = $1"))
+                .bind(uri)
+                .fetch_optional(&self.connection)
+                .await?;
+
+        row.map(ItemRow::into_item).transpose()
+    }
+
+    async fn get_batch(&self, uris: &[String]) -> Result<HashMap<String, CacheItem>, CacheError> {
+        let rows: Vec<ItemRow> =
+            sqlx::query_as(&format!("SELECT {ITEM_COLUMNS} WHERE c.uri = ANY($1)"))
+                .bind(uris)
+                .fetch_all(&self.connection)
+                .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let item = row.into_item()?;
+                Ok((item.uri.clone(), item))
+            })
+            .collect()
+    }
+
+    async fn space_items(
+        &self,
+        space_id: &str,
+        from_block: i64,
+        limit: usize,
+    ) -> Result<Option<Vec<CacheItem>>, CacheError> {
+        // Items without a numeric block timestamp are left out
+        let rows: Vec<ItemRow> = sqlx::query_as(&format!(
+            "SELECT {ITEM_COLUMNS} \
+             WHERE c.space_id = $1 AND NOT c.is_errored AND c.block ~ '^[0-9]+$' \
+             AND c.block::bigint >= $2 \
+             ORDER BY c.block::bigint, c.uri \
+             LIMIT $3"
+        ))
+        .bind(space_id)
+        .bind(from_block)
+        .bind(limit as i64)
+        .fetch_all(&self.connection)
+        .await?;
+
+        rows.into_iter()
+            .map(ItemRow::into_item)
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+
+    async fn errored_due(
+        &self,
+        now: i64,
+        max_attempts: u32,
+        limit: usize,
+    ) -> Result<Vec<RetryCandidate>, CacheError> {
+        let rows: Vec<(String, i32)> = sqlx::query_as(
+            "SELECT uri, retry_attempts FROM ipfs_cache \
+             WHERE is_errored AND retry_attempts < $1 \
+             AND (next_retry_at IS NULL OR next_retry_at <= $2) \
+             ORDER BY next_retry_at NULLS FIRST, uri \
+             LIMIT $3",
+        )
+        .bind(max_attempts as i32)
+        .bind(now)
+        .bind(limit as i64)
+        .fetch_all(&self.connection)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(uri, attempts)| RetryCandidate {
+                uri,
+                attempts: attempts.max(0) as u32,
+            })
+            .collect())
+    }
+
+    async fn resolve(&self, uri: &str, json: &Edit) -> Result<(), CacheError> {
+        let content = content::encode(json)?;
+        let mut tx = self.connection.begin().await?;
+
+        let previous: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT content_hash FROM ipfs_cache WHERE uri = $1 FOR UPDATE")
+                .bind(uri)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let Some((previous_hash,)) = previous else {
+            return Ok(());
+        };
+
+        sqlx::query(
+            "UPDATE ipfs_cache SET content_hash = $2, json = NULL, is_errored = false, \
+             next_retry_at = NULL WHERE uri = $1",
+        )
+        .bind(uri)
+        .bind(&content.hash)
+        .execute(&mut *tx)
+        .await?;
+
+        Self::acquire_content(&mut tx, &content).await?;
+        if let Some(previous_hash) = previous_hash {
+            Self::release_content(&mut tx, &[previous_hash]).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn schedule_retry(
+        &self,
+        uri: &str,
+        attempts: u32,
+        next_retry_at: i64,
+    ) -> Result<(), CacheError> {
+        sqlx::query("UPDATE ipfs_cache SET retry_attempts = $2, next_retry_at = $3 WHERE uri = $1")
+            .bind(uri)
+            .bind(attempts as i32)
+            .bind(next_retry_at)
+            .execute(&self.connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn load_cursor(&self, id: &str) -> Result<Option<String>, CacheError> {
+        let result = sqlx::query_scalar::<_, String>("SELECT cursor FROM meta WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.connection)
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn persist_cursor(&self, id: &str, cursor: &str, block: u64) -> Result<(), CacheError> {
+        sqlx::query(
+            "INSERT INTO meta (id, cursor, block_number) VALUES ($1, $2, $3) \
+             ON CONFLICT (id) DO UPDATE SET cursor = $2, block_number = $3",
+        )
+        .bind(id)
+        .bind(cursor)
+        .bind(block.to_string())
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn mark_block_fetched(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        sqlx::query(
+            "INSERT INTO block_fetch_status (id, block_number) VALUES ($1, $2) \
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(id)
+        .bind(block as i64)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn fetched_blocks(&self, id: &str) -> Result<BTreeSet<u64>, CacheError> {
+        let blocks = sqlx::query_scalar::<_, i64>(
+            "SELECT block_number FROM block_fetch_status WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_all(&self.connection)
+        .await?;
+
+        Ok(blocks.into_iter().map(|block| block as u64).collect())
+    }
+
+    async fn forget_fetched_blocks(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        sqlx::query("DELETE FROM block_fetch_status WHERE id = $1 AND block_number <= $2")
+            .bind(id)
+            .bind(block as i64)
+            .execute(&self.connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn journal_block(&self, id: &str, block: &JournaledBlock) -> Result<(), CacheError> {
+        sqlx::query(
+            "INSERT INTO block_fetch_journal (id, block_number, cursor, block_seconds, events) \
+             VALUES ($1, $2, $3, $4, $5) \
+             ON CONFLICT (id, block_number) DO UPDATE SET \
+             cursor = EXCLUDED.cursor, block_seconds = EXCLUDED.block_seconds, events = EXCLUDED.events",
+        )
+        .bind(id)
+        .bind(block.block as i64)
+        .bind(&block.cursor)
+        .bind(block.block_seconds)
+        .bind(sqlx::types::Json(&block.events))
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn journaled_blocks(&self, id: &str) -> Result<Vec<JournaledBlock>, CacheError> {
+        let rows = sqlx::query_as::<
+            _,
+            (
+                i64,
+                String,
+                Option<i64>,
+                sqlx::types::Json<Vec<PrefetchEvent>>,
+            ),
+        >(
+            "SELECT block_number, cursor, block_seconds, events FROM block_fetch_journal \
+             WHERE id = $1 ORDER BY block_number",
+        )
+        .bind(id)
+        .fetch_all(&self.connection)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(block, cursor, block_seconds, events)| JournaledBlock {
+                block: block as u64,
+                cursor,
+                block_seconds,
+                events: events.0,
+            })
+            .collect())
+    }
+
+    async fn forget_journaled_blocks(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        sqlx::query("DELETE FROM block_fetch_journal WHERE id = $1 AND block_number <= $2")
+            .bind(id)
+            .bind(block as i64)
+            .execute(&self.connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn invalidate_from(&self, id: &str, block: u64) -> Result<u64, CacheError> {
+        let mut tx = self.connection.begin().await?;
+
+        let orphaned = sqlx::query(
+            "UPDATE ipfs_cache SET orphaned = true WHERE block_number >= $1 AND NOT orphaned",
+        )
+        .bind(block as i64)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query("DELETE FROM block_fetch_status WHERE id = $1 AND block_number >= $2")
+            .bind(id)
+            .bind(block as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM block_fetch_journal WHERE id = $1 AND block_number >= $2")
+            .bind(id)
+            .bind(block as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(orphaned)
+    }
+
+    fn supports_eviction(&self) -> bool {
+        true
+    }
+
+    async fn record_reads(&self, reads: &[(String, i64)]) -> Result<(), CacheError> {
+        if reads.is_empty() {
+            return Ok(());
+        }
+
+        let (uris, read_at): (Vec<&str>, Vec<i64>) = reads
+            .iter()
+            .map(|(uri, read_at)| (uri.as_str(), *read_at))
+            .unzip();
+
+        sqlx::query(
+            "UPDATE ipfs_cache AS c SET last_read = GREATEST(c.last_read, t.read_at) \
+             FROM UNNEST($1::text[], $2::bigint[]) AS t(uri, read_at) \
+             WHERE c.uri = t.uri",
+        )
+        .bind(&uris)
+        .bind(&read_at)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<Option<CacheStats>, CacheError> {
+        // Shared content is counted once, plus the inline JSON of legacy rows
+        let (items, bytes): (i64, i64) = sqlx::query_as(
+            "SELECT \
+                 (SELECT count(*) FROM ipfs_cache), \
+                 (SELECT COALESCE(sum(octet_length(data)), 0) FROM ipfs_cache_content)::bigint \
+                 + (SELECT COALESCE(sum(pg_column_size(json)), 0) FROM ipfs_cache)::bigint",
+        )
+        .fetch_one(&self.connection)
+        .await?;
+
+        Ok(Some(CacheStats {
+            items: items.max(0) as u64,
+            bytes: bytes.max(0) as u64,
+        }))
+    }
+
+    async fn evict_before(&self, block_timestamp: i64) -> Result<u64, CacheError> {
+        // Items without a numeric block timestamp are never expired
+        self.delete_where(
+            "CASE WHEN block ~ '^[0-9]+$' THEN block::bigint < $1 ELSE false END",
+            block_timestamp,
+        )
+        .await
+    }
+
+    async fn evict_lru(&self, max_bytes: u64) -> Result<u64, CacheError> {
+        // Keep the most recently read items while their running total fits.
+        // Each item counts the full size of its content, so shared content
+        // is counted once per item and the cache may end up below the limit.
+        self.delete_where(
+            "uri IN ( \
+                 SELECT uri FROM ( \
+                     SELECT c.uri, sum(COALESCE(octet_length(cc.data), pg_column_size(c.json), 0)) \
+                         OVER (ORDER BY c.last_read DESC NULLS LAST, c.uri) AS running_bytes \
+                     FROM ipfs_cache c \
+                     LEFT JOIN ipfs_cache_content cc ON cc.hash = c.content_hash \
+                 ) ranked \
+                 WHERE running_bytes > $1 \
+             )",
+            max_bytes.min(i64::MAX as u64) as i64,
+        )
+        .await
+    }
+
+    async fn migrate_legacy_content(&self, limit: usize) -> Result<u64, CacheError> {
+        let rows: Vec<(String, serde_json::Value)> = sqlx::query_as(
+            "SELECT uri, json FROM ipfs_cache \
+             WHERE json IS NOT NULL AND content_hash IS NULL \
+             LIMIT $1",
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.connection)
+        .await?;
+
+        let mut tx = self.connection.begin().await?;
+        let mut migrated = 0;
+
+        for (uri, json) in &rows {
+            let edit: Option<Edit> = serde_json::from_value(json.clone())?;
+            let content = edit.as_ref().map(content::encode).transpose()?;
+
+            // Only move rows that weren't changed since they were read
+            let moved = sqlx::query(
+                "UPDATE ipfs_cache SET content_hash = $2, json = NULL \
+                 WHERE uri = $1 AND content_hash IS NULL AND json IS NOT NULL",
+            )
+            .bind(uri)
+            .bind(content.as_ref().map(|content| &content.hash))
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+                > 0;
+
+            if !moved {
+                continue;
+            }
+
+            if let Some(content) = &content {
+                Self::acquire_content(&mut tx, content).await?;
+            }
+            migrated += 1;
+        }
+
+        tx.commit().await?;
+        Ok(migrated)
+    }
+}
+
+// =============================================================================
+// Cache (High-Level Interface)
+// =============================================================================
+
+/// High-level cache interface wrapping a storage backend.
+///
+/// All methods take `&self`, so a single cache can be shared through an
+/// `Arc` between the sink writing to it and consumers reading from it.
+pub struct Cache {
+    storage: Box<dyn CacheStorage>,
+    /// Signalled after every write, to wake [`Cache::wait_for`] callers.
+    written: Notify,
+    /// URI -> last read (unix seconds) since the last [`Cache::flush_reads`].
+    reads: Mutex<HashMap<String, i64>>,
+}
+
+impl Cache {
+    /// Create a cache with the given storage backend.
+    pub fn new(storage: impl CacheStorage + 'static) -> Self {
+        Cache {
+            storage: Box::new(storage),
+            written: Notify::new(),
+            reads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a cache with in-memory storage (for testing).
+    pub fn mock() -> Self {
+        Self::new(MockStorage::new())
+    }
+
+    /// Create a cache with PostgreSQL storage.
+    pub fn postgres(storage: PostgresStorage) -> Self {
+        Self::new(storage)
+    }
+
+    /// Store an item in the cache. If the URI already exists, this is a no-op.
+    pub async fn put(&self, item: &CacheItem) -> Result<(), CacheError> {
+        self.storage.insert(item).await?;
+        self.written.notify_waiters();
+        Ok(())
+    }
+
+    /// Store several items in one write. URIs that already exist are left
+    /// as they are, and a URI repeated in `items` keeps its first occurrence.
+    pub async fn put_many(&self, items: &[CacheItem]) -> Result<(), CacheError> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        self.storage.insert_many(items).await?;
+        self.written.notify_waiters();
+        Ok(())
+    }
+
+    /// Get an item from the cache by URI.
+    pub async fn get_by_uri(&self, uri: &str) -> Result<Option<CacheItem>, CacheError> {
+        let item = self.storage.get(uri).await?;
+        if item.is_some() {
+            self.record_read(uri);
+        }
+        Ok(item)
+    }
+
+    /// Get the cached items among `uris`, keyed by URI. Missing URIs are
+    /// left out of the map.
+    pub async fn get_batch(
+        &self,
+        uris: &[String],
+    ) -> Result<HashMap<String, CacheItem>, CacheError> {
+        let items = self.storage.get_batch(uris).await?;
+        for uri in items.keys() {
+            self.record_read(uri);
+        }
+        Ok(items)
+    }
+
+    /// Up to `limit` successfully fetched items of `space_id` whose block
+    /// timestamp is at least `from_block`, ordered by block timestamp then
+    /// URI. Returns `None` if the storage backend can't query items by space.
+    pub async fn space_items(
+        &self,
+        space_id: &str,
+        from_block: i64,
+        limit: usize,
+    ) -> Result<Option<Vec<CacheItem>>, CacheError> {
+        let items = self
+            .storage
+            .space_items(space_id, from_block, limit)
+            .await?;
+        for item in items.iter().flatten() {
+            self.record_read(&item.uri);
+        }
+        Ok(items)
+    }
+
+    /// Get an item by URI, waiting up to `timeout` for it to be cached.
+    ///
+    /// Consumers running just behind the sink can race it to an edit; this
+    /// resolves the race without them retrying on a miss. Returns `None` if
+    /// the item still isn't cached when the timeout elapses. Errored items
+    /// are returned as soon as they are cached, like any other item.
+    pub async fn wait_for(
+        &self,
+        uri: &str,
+        timeout: Duration,
+    ) -> Result<Option<CacheItem>, CacheError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            // Register for the next write before reading, so a write landing
+            // between the read and the wait isn't missed
+            let written = self.written.notified();
+            tokio::pin!(written);
+            written.as_mut().enable();
+
+            if let Some(item) = self.storage.get(uri).await? {
+                self.record_read(uri);
+                return Ok(Some(item));
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+
+            let wait = (deadline - now).min(WAIT_POLL_INTERVAL);
+            let _ = tokio::time::timeout(wait, written).await;
+        }
+    }
+
+    fn record_read(&self, uri: &str) {
+        self.reads
+            .lock()
+            .unwrap()
+            .insert(uri.to_string(), unix_now());
+    }
+
+    /// Write the reads recorded since the last flush to the storage.
+    pub async fn flush_reads(&self) -> Result<(), CacheError> {
+        let reads: Vec<_> = std::mem::take(&mut *self.reads.lock().unwrap())
+            .into_iter()
+            .collect();
+        self.storage.record_reads(&reads).await
+    }
+
+    /// Whether the storage backend supports eviction. See
+    /// [`CacheStorage::supports_eviction`].
+    pub fn supports_eviction(&self) -> bool {
+        self.storage.supports_eviction()
+    }
+
+    /// Number of cached items and their total size, if the backend counts them.
+    pub async fn stats(&self) -> Result<Option<CacheStats>, CacheError> {
+        self.storage.stats().await
+    }
+
+    /// Evict items with a block timestamp before `block_timestamp`.
+    pub async fn evict_before(&self, block_timestamp: i64) -> Result<u64, CacheError> {
+        self.storage.evict_before(block_timestamp).await
+    }
+
+    /// Evict the least recently read items down to `max_bytes` of content.
+    pub async fn evict_lru(&self, max_bytes: u64) -> Result<u64, CacheError> {
+        self.storage.evict_lru(max_bytes).await
+    }
+
+    /// Move up to `limit` items to the current storage format. See
+    /// [`CacheStorage::migrate_legacy_content`].
+    pub async fn migrate_legacy_content(&self, limit: usize) -> Result<u64, CacheError> {
+        self.storage.migrate_legacy_content(limit).await
+    }
+
+    /// List errored items due for a retry. See [`CacheStorage::errored_due`].
+    pub async fn errored_due(
+        &self,
+        now: i64,
+        max_attempts: u32,
+        limit: usize,
+    ) -> Result<Vec<RetryCandidate>, CacheError> {
+        self.storage.errored_due(now, max_attempts, limit).await
+    }
+
+    /// Replace the content of an errored item in place and clear its error.
+    pub async fn resolve(&self, uri: &str, json: &Edit) -> Result<(), CacheError> {
+        self.storage.resolve(uri, json).await?;
+        self.written.notify_waiters();
+        Ok(())
+    }
+
+    /// Record a failed retry and when the next one is due (unix seconds).
+    pub async fn schedule_retry(
+        &self,
+        uri: &str,
+        attempts: u32,
+        next_retry_at: i64,
+    ) -> Result<(), CacheError> {
+        self.storage
+            .schedule_retry(uri, attempts, next_retry_at)
+            .await
+    }
+
+    /// Load the cursor for a given indexer ID.
+    pub async fn load_cursor(&self, id: &str) -> Result<Option<String>, CacheError> {
+        self.storage.load_cursor(id).await
+    }
+
+    /// Persist the cursor for a given indexer ID.
+    pub async fn persist_cursor(
+        &self,
+        id: &str,
+        cursor: &str,
+        block: u64,
+    ) -> Result<(), CacheError> {
+        self.storage.persist_cursor(id, cursor, block).await
+    }
+
+    /// Record that a block is cached while an earlier one is still pending.
+    /// See [`CacheStorage::mark_block_fetched`].
+    pub async fn mark_block_fetched(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        self.storage.mark_block_fetched(id, block).await
+    }
+
+    /// Blocks recorded as cached ahead of the cursor of an indexer ID.
+    pub async fn fetched_blocks(&self, id: &str) -> Result<BTreeSet<u64>, CacheError> {
+        self.storage.fetched_blocks(id).await
+    }
+
+    /// Forget the fetched blocks of an indexer ID up to and including `block`.
+    pub async fn forget_fetched_blocks(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        self.storage.forget_fetched_blocks(id, block).await
+    }
+
+    /// Record the fetches of a block before they start.
+    /// See [`CacheStorage::journal_block`].
+    pub async fn journal_block(&self, id: &str, block: &JournaledBlock) -> Result<(), CacheError> {
+        self.storage.journal_block(id, block).await
+    }
+
+    /// Blocks journaled by an indexer ID whose fetches may not be written.
+    pub async fn journaled_blocks(&self, id: &str) -> Result<Vec<JournaledBlock>, CacheError> {
+        self.storage.journaled_blocks(id).await
+    }
+
+    /// Forget the journaled blocks of an indexer ID up to and including `block`.
+    pub async fn forget_journaled_blocks(&self, id: &str, block: u64) -> Result<(), CacheError> {
+        self.storage.forget_journaled_blocks(id, block).await
+    }
+
+    /// Orphan the items of `block` and later blocks after a reorg.
+    pub async fn invalidate_from(&self, id: &str, block: u64) -> Result<u64, CacheError> {
+        self.storage.invalidate_from(id, block).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn test_edit(name: &str) -> Edit {
+        Edit {
+            id: vec![0x01, 0x02],
+            name: name.to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_put_and_get() {
+        let cache = Cache::mock();
+
+        let item = CacheItem {
+            uri: "ipfs://QmTest123".to_string(),
+            json: Some(test_edit("Test Edit")),
+            block: "12345".to_string(),
+            space_id: "abc123".to_string(),
+            is_errored: false,
+            block_number: None,
+            orphaned: false,
+        };
+
+        cache.put(&item).await.unwrap();
+
+        let retrieved = cache.get_by_uri("ipfs://QmTest123").await.unwrap();
+        assert!(retrieved.is_some());
+
+        let retrieved = retrieved.unwrap();
+        assert_eq!(retrieved.uri, "ipfs://QmTest123");
+        assert_eq!(retrieved.json.unwrap().name, "Test Edit");
+        assert_eq!(retrieved.block, "12345");
+        assert_eq!(retrieved.space_id, "abc123");
+        assert!(!retrieved.is_errored);
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_put_duplicate_is_noop() {
+        let cache = Cache::mock();
+
+        let item1 = CacheItem {
+            uri: "ipfs://QmTest123".to_string(),
+            json: Some(test_edit("First")),
+            block: "100".to_string(),
+            space_id: "abc".to_string(),
+            is_errored: false,
+            block_number: None,
+            orphaned: false,
+        };
+
+        let item2 = CacheItem {
+            uri: "ipfs://QmTest123".to_string(),
+            json: Some(test_edit("Second")),
+            block: "200".to_string(),
+            space_id: "def".to_string(),
+            is_errored: false,
+            block_number: None,
+            orphaned: false,
+        };
+
+        cache.put(&item1).await.unwrap();
+        cache.put(&item2).await.unwrap();
+
+        // Should still have the first item
+        let retrieved = cache.get_by_uri("ipfs://QmTest123").await.unwrap().unwrap();
+        assert_eq!(retrieved.json.unwrap().name, "First");
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_put_many() {
+        let cache = Cache::mock();
+        let item = |uri: &str, name: &str| CacheItem {
+            uri: uri.to_string(),
+            json: Some(test_edit(name)),
+            block: "100".to_string(),
+            space_id: "abc".to_string(),
+            is_errored: false,
+            block_number: None,
+            orphaned: false,
+        };
+
+        cache
+            .put(&item("ipfs://QmExisting", "Existing"))
+            .await
+            .unwrap();
+        cache
+            .put_many(&[
+                item("ipfs://QmExisting", "Replaced"),
+                item("ipfs://QmFirst", "First"),
+                item("ipfs://QmFirst", "Repeated"),
+                item("ipfs://QmSecond", "Second"),
+            ])
+            .await
+            .unwrap();
+
+        let name = |item: Option<CacheItem>| item.unwrap().json.unwrap().name;
+        assert_eq!(
+            name(cache.get_by_uri("ipfs://QmExisting").await.unwrap()),
+            "Existing"
+        );
+        assert_eq!(
+            name(cache.get_by_uri("ipfs://QmFirst").await.unwrap()),
+            "First"
+        );
+        assert_eq!(
+            name(cache.get_by_uri("ipfs://QmSecond").await.unwrap()),
+            "Second"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_get_nonexistent() {
+        let cache = Cache::mock();
+
+        let result = cache.get_by_uri("ipfs://QmNotFound").await.unwrap();
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_get_batch() {
+        let cache = Cache::mock();
+
+        for uri in ["ipfs://QmA", "ipfs://QmB"] {
+            cache
+                .put(&CacheItem {
+                    uri: uri.to_string(),
+                    json: Some(test_edit(uri)),
+                    block: "100".to_string(),
+                    space_id: "abc".to_string(),
+                    is_errored: false,
+                    block_number: None,
+                    orphaned: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let uris = ["ipfs://QmA", "ipfs://QmMissing", "ipfs://QmB"].map(String::from);
+        let items = cache.get_batch(&uris).await.unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items["ipfs://QmA"].json.as_ref().unwrap().name,
+            "ipfs://QmA"
+        );
+        assert!(!items.contains_key("ipfs://QmMissing"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_mock_cache_wait_for() {
+        let cache = Arc::new(Cache::mock());
+
+        // Times out when the item never arrives
+        let result = cache
+            .wait_for("ipfs://QmLate", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        // Wakes up once the item is written
+        let writer = cache.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            writer
+                .put(&CacheItem {
+                    uri: "ipfs://QmLate".to_string(),
+                    json: Some(test_edit("Late")),
+                    block: "100".to_string(),
+                    space_id: "abc".to_string(),
+                    is_errored: false,
+                    block_number: None,
+                    orphaned: false,
+                })
+                .await
+                .unwrap();
+        });
+
+        let item = cache
+            .wait_for("ipfs://QmLate", Duration::from_secs(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(item.json.unwrap().name, "Late");
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_errored_due_and_resolve() {
+        let cache = Cache::mock();
+
+        for (uri, is_errored) in [("ipfs://QmOk", false), ("ipfs://QmBad", true)] {
+            cache
+                .put(&CacheItem {
+                    uri: uri.to_string(),
+                    json: None,
+                    block: "100".to_string(),
+                    space_id: "abc".to_string(),
+                    is_errored,
+                    block_number: None,
+                    orphaned: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        let due = cache.errored_due(1_000, 3, 10).await.unwrap();
+        assert_eq!(
+            due,
+            vec![RetryCandidate {
+                uri: "ipfs://QmBad".to_string(),
+                attempts: 0,
+            }]
+        );
+
+        // Not due again until the scheduled time
+        cache
+            .schedule_retry("ipfs://QmBad", 1, 2_000)
+            .await
+            .unwrap();
+        assert!(cache.errored_due(1_999, 3, 10).await.unwrap().is_empty());
+        assert_eq!(cache.errored_due(2_000, 3, 10).await.unwrap().len(), 1);
+
+        // Exhausted once the max attempts are reached
+        assert!(cache.errored_due(2_000, 1, 10).await.unwrap().is_empty());
+
+        cache
+            .resolve("ipfs://QmBad", &test_edit("Recovered"))
+            .await
+            .unwrap();
+
+        let resolved = cache.get_by_uri("ipfs://QmBad").await.unwrap().unwrap();
+        assert!(!resolved.is_errored);
+        assert_eq!(resolved.json.unwrap().name, "Recovered");
+        assert!(cache.errored_due(2_000, 3, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mock_storage_evict_lru_keeps_recently_read_items() {
+        let storage = MockStorage::new();
+        for uri in ["ipfs://QmA", "ipfs://QmB", "ipfs://QmC"] {
+            storage
+                .insert(&CacheItem {
+                    uri: uri.to_string(),
+                    json: Some(test_edit("Same size")),
+                    block: "100".to_string(),
+                    space_id: "abc".to_string(),
+                    is_errored: false,
+                    block_number: None,
+                    orphaned: false,
+                })
+                .await
+                .unwrap();
+        }
+        let item_bytes = storage.stats().await.unwrap().unwrap().bytes / 3;
+
+        let now = unix_now();
+        storage
+            .record_reads(&[
+                ("ipfs://QmA".to_string(), now + 10),
+                ("ipfs://QmC".to_string(), now + 20),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(storage.evict_lru(item_bytes * 2).await.unwrap(), 1);
+        assert!(storage.get("ipfs://QmB").await.unwrap().is_none());
+
+        assert_eq!(storage.evict_lru(item_bytes).await.unwrap(), 1);
+        assert!(storage.get("ipfs://QmA").await.unwrap().is_none());
+        assert!(storage.get("ipfs://QmC").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_mock_storage_evict_before_skips_items_without_timestamp() {
+        let storage = MockStorage::new();
+        for (uri, block) in [
+            ("ipfs://QmOld", "100"),
+            ("ipfs://QmNew", "300"),
+            ("ipfs://QmNone", ""),
+        ] {
+            storage
+                .insert(&CacheItem {
+                    uri: uri.to_string(),
+                    json: None,
+                    block: block.to_string(),
+                    space_id: "abc".to_string(),
+                    is_errored: true,
+                    block_number: None,
+                    orphaned: false,
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(storage.evict_before(200).await.unwrap(), 1);
+        assert!(storage.get("ipfs://QmOld").await.unwrap().is_none());
+        assert_eq!(storage.stats().await.unwrap().unwrap().items, 2);
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_cursor_persistence() {
+        let cache = Cache::mock();
+
+        // Initially no cursor
+        let cursor = cache.load_cursor("test_indexer").await.unwrap();
+        assert!(cursor.is_none());
+
+        // Persist a cursor
+        cache
+            .persist_cursor("test_indexer", "cursor_abc", 100)
+            .await
+            .unwrap();
+
+        // Should be able to load it
+        let cursor = cache.load_cursor("test_indexer").await.unwrap();
+        assert_eq!(cursor, Some("cursor_abc".to_string()));
+
+        // Update the cursor
+        cache
+            .persist_cursor("test_indexer", "cursor_def", 200)
+            .await
+            .unwrap();
+
+        let cursor = cache.load_cursor("test_indexer").await.unwrap();
+        assert_eq!(cursor, Some("cursor_def".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_fetched_blocks() {
+        let cache = Cache::mock();
+
+        for block in [101, 102, 104] {
+            cache
+                .mark_block_fetched("test_indexer", block)
+                .await
+                .unwrap();
+        }
+        cache
+            .mark_block_fetched("other_indexer", 101)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.fetched_blocks("test_indexer").await.unwrap(),
+            BTreeSet::from([101, 102, 104])
+        );
+
+        // Blocks up to the cursor are forgotten, other indexers are untouched
+        cache
+            .forget_fetched_blocks("test_indexer", 102)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.fetched_blocks("test_indexer").await.unwrap(),
+            BTreeSet::from([104])
+        );
+        assert_eq!(
+            cache.fetched_blocks("other_indexer").await.unwrap(),
+            BTreeSet::from([101])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_journaled_blocks() {
+        let cache = Cache::mock();
+        let journaled = |block: u64| JournaledBlock {
+            block,
+            cursor: format!("cursor_{}", block),
+            block_seconds: Some(1_700_000_000),
+            events: vec![PrefetchEvent {
+                uri: format!("ipfs://Qm{}", block),
+                space_id: "abc".to_string(),
+                dao_address: None,
+            }],
+        };
+
+        for block in [102, 101, 104] {
+            cache
+                .journal_block("test_indexer", &journaled(block))
+                .await
+                .unwrap();
+        }
+        cache
+            .journal_block("other_indexer", &journaled(101))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.journaled_blocks("test_indexer").await.unwrap(),
+            vec![journaled(101), journaled(102), journaled(104)]
+        );
+
+        // Blocks up to the cursor are forgotten, other indexers are untouched
+        cache
+            .forget_journaled_blocks("test_indexer", 102)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.journaled_blocks("test_indexer").await.unwrap(),
+            vec![journaled(104)]
+        );
+        assert_eq!(
+            cache.journaled_blocks("other_indexer").await.unwrap(),
+            vec![journaled(101)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_cache_invalidate_from_orphans_later_blocks() {
+        let cache = Cache::mock();
+        let item = |uri: &str, block_number: Option<u64>| CacheItem {
+            uri: uri.to_string(),
+            json: Some(test_edit(uri)),
+            block: "100".to_string(),
+            space_id: "abc".to_string(),
+            is_errored: false,
+            block_number,
+            orphaned: false,
+        };
+
+        cache
+            .put_many(&[
+                item("ipfs://QmKept", Some(9)),
+                item("ipfs://QmUndone", Some(10)),
+                item("ipfs://QmUnknown", None),
+            ])
+            .await
+            .unwrap();
+        for block in [9, 10] {
+            cache
+                .mark_block_fetched("test_indexer", block)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(cache.invalidate_from("test_indexer", 10).await.unwrap(), 1);
+
+        let orphaned = |uri: &str| {
+            let cache = &cache;
+            let uri = uri.to_string();
+            async move { cache.get_by_uri(&uri).await.unwrap().unwrap().orphaned }
+        };
+        assert!(!orphaned("ipfs://QmKept").await);
+        assert!(orphaned("ipfs://QmUndone").await);
+        assert!(!orphaned("ipfs://QmUnknown").await);
+        assert_eq!(
+            cache.fetched_blocks("test_indexer").await.unwrap(),
+            BTreeSet::from([9])
+        );
+
+        // The canonical block publishing the edit again claims the item
+        cache.put(&item("ipfs://QmUndone", Some(11))).await.unwrap();
+
+        let claimed = cache.get_by_uri("ipfs://QmUndone").await.unwrap().unwrap();
+        assert!(!claimed.orphaned);
+        assert_eq!(claimed.block_number, Some(11));
+    }
+}
@@ -0,0 +1,75 @@
+//! Content-addressed encoding of decoded edits.
+//!
+//! Edits are stored once per distinct content: the JSON encoding of an edit
+//! is hashed with SHA-256 and compressed with zstd. Items reference the
+//! content by its hash, so identical payloads published to several spaces
+//! share one copy.
+
+use sha2::{Digest, Sha256};
+use wire::pb::grc20::Edit;
+
+use super::CacheError;
+
+/// zstd level used for stored content. Edits are written once and read
+/// many times, so a higher level than the default pays off.
+const COMPRESSION_LEVEL: i32 = 9;
+
+/// An encoded edit, ready to be stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Content {
+    /// Hex-encoded SHA-256 of the uncompressed JSON.
+    pub hash: String,
+    /// zstd-compressed JSON.
+    pub data: Vec<u8>,
+}
+
+/// Encode an edit as compressed JSON, keyed by the hash of the JSON.
+pub(crate) fn encode(edit: &Edit) -> Result<Content, CacheError> {
+    let json = serde_json::to_vec(edit)?;
+    let hash = hex::encode(Sha256::digest(&json));
+    let data =
+        zstd::encode_all(json.as_slice(), COMPRESSION_LEVEL).map_err(CacheError::Compression)?;
+
+    Ok(Content { hash, data })
+}
+
+/// Decode an edit stored by [`encode`].
+pub(crate) fn decode(data: &[u8]) -> Result<Edit, CacheError> {
+    let json = zstd::decode_all(data).map_err(CacheError::Compression)?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_edit(name: &str) -> Edit {
+        Edit {
+            id: vec![0x01, 0x02],
+            name: name.to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_content_round_trips() {
+        let edit = test_edit(&"Repeated payload ".repeat(100));
+        let content = encode(&edit).unwrap();
+
+        assert_eq!(decode(&content.data).unwrap(), edit);
+        assert!(content.data.len() < serde_json::to_vec(&edit).unwrap().len());
+    }
+
+    #[test]
+    fn test_content_hash_identifies_payload() {
+        let first = encode(&test_edit("Same")).unwrap();
+        let second = encode(&test_edit("Same")).unwrap();
+        let other = encode(&test_edit("Other")).unwrap();
+
+        assert_eq!(first.hash, second.hash);
+        assert_ne!(first.hash, other.hash);
+        assert_eq!(first.hash.len(), 64);
+    }
+}
@@ -0,0 +1,302 @@
+//! Object store (S3) storage backend.
+//!
+//! Items are stored as JSON objects at `ipfs_cache/items/<cid>.json`. Errored
+//! items also get an empty marker object at `ipfs_cache/errored/<cid>` so
+//! retries only list errored items. Cursors are stored at
+//! `ipfs_cache/cursors/<id>.json`.
+//!
+//! Object stores have no conditional insert shared by every provider, so
+//! [`CacheStorage::insert`] checks for an existing object first. The cache
+//! has a single writer, which keeps this race-free in practice.
+
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::path::Path;
+use object_store::{ObjectStore, ObjectStoreExt, PutPayload};
+use serde::{Deserialize, Serialize};
+use wire::pb::grc20::Edit;
+
+use super::{cid_key, CacheError, CacheItem, CacheStorage, RetryCandidate, StoredEntry};
+
+/// Prefix of every object written by the cache.
+const PATH_PREFIX: &str = "ipfs_cache";
+
+#[derive(Serialize, Deserialize)]
+struct StoredCursor {
+    cursor: String,
+    block_number: u64,
+}
+
+/// Object store storage backend for the IPFS cache.
+pub struct ObjectStoreStorage {
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreStorage {
+    /// Create a new storage instance on top of any object store.
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        ObjectStoreStorage { store }
+    }
+
+    /// Create a new storage instance for an S3 bucket.
+    ///
+    /// Credentials and region are read from the standard `AWS_*` environment
+    /// variables.
+    pub fn s3(bucket: &str) -> Result<Self, CacheError> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()?;
+
+        Ok(Self::new(Arc::new(store)))
+    }
+
+    fn item_path(cid: &str) -> Path {
+        Path::from(format!("{PATH_PREFIX}/items/{cid}.json"))
+    }
+
+    fn errored_prefix() -> Path {
+        Path::from(format!("{PATH_PREFIX}/errored"))
+    }
+
+    fn errored_path(cid: &str) -> Path {
+        Path::from(format!("{PATH_PREFIX}/errored/{cid}"))
+    }
+
+    fn cursor_path(id: &str) -> Path {
+        Path::from(format!("{PATH_PREFIX}/cursors/{id}.json"))
+    }
+
+    async fn read<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &Path,
+    ) -> Result<Option<T>, CacheError> {
+        match self.store.get(path).await {
+            Ok(result) => {
+                let bytes = result.bytes().await?;
+                Ok(Some(serde_json::from_slice(&bytes)?))
+            }
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write<T: Serialize>(&self, path: &Path, value: &T) -> Result<(), CacheError> {
+        let bytes = serde_json::to_vec(value)?;
+        self.store.put(path, PutPayload::from(bytes)).await?;
+        Ok(())
+    }
+
+    async fn remove(&self, path: &Path) -> Result<(), CacheError> {
+        match self.store.delete(path).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheStorage for ObjectStoreStorage {
+    async fn insert(&self, item: &CacheItem) -> Result<(), CacheError> {
+        let cid = cid_key(&item.uri);
+        let path = Self::item_path(cid);
+
+        // Only insert if not exists (matches PostgreSQL ON CONFLICT DO NOTHING)
+        match self.store.head(&path).await {
+            Ok(_) => return Ok(()),
+            Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        self.write(&path, &StoredEntry::new(item)).await?;
+
+        if item.is_errored {
+            self.store
+                .put(&Self::errored_path(cid), PutPayload::default())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, uri: &str) -> Result<Option<CacheItem>, CacheError> {
+        let entry: Option<StoredEntry> = self.read(&Self::item_path(cid_key(uri))).await?;
+        Ok(entry.map(StoredEntry::into_item))
+    }
+
+    async fn errored_due(
+        &self,
+        now: i64,
+        max_attempts: u32,
+        limit: usize,
+    ) -> Result<Vec<RetryCandidate>, CacheError> {
+        let markers: Vec<_> = self
+            .store
+            .list(Some(&Self::errored_prefix()))
+            .try_collect()
+            .await?;
+
+        let mut due = Vec::new();
+        for marker in markers {
+            let Some(cid) = marker.location.filename() else {
+                continue;
+            };
+
+            let entry: Option<StoredEntry> = self.read(&Self::item_path(cid)).await?;
+            if let Some(entry) = entry.filter(|entry| entry.is_due(now, max_attempts)) {
+                due.push(entry);
+            }
+        }
+
+        // Match the PostgreSQL ordering: oldest schedule first
+        due.sort_by(|a, b| {
+            a.next_retry_at
+                .cmp(&b.next_retry_at)
+                .then_with(|| a.uri.cmp(&b.uri))
+        });
+
+        Ok(due
+            .iter()
+            .take(limit)
+            .map(StoredEntry::retry_candidate)
+            .collect())
+    }
+
+    async fn resolve(&self, uri: &str, json: &Edit) -> Result<(), CacheError> {
+        let cid = cid_key(uri);
+        let path = Self::item_path(cid);
+
+        let entry: Option<StoredEntry> = self.read(&path).await?;
+        if let Some(mut entry) = entry {
+            entry.resolve(json);
+            self.write(&path, &entry).await?;
+            self.remove(&Self::errored_path(cid)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn schedule_retry(
+        &self,
+        uri: &str,
+        attempts: u32,
+        next_retry_at: i64,
+    ) -> Result<(), CacheError> {
+        let path = Self::item_path(cid_key(uri));
+
+        let entry: Option<StoredEntry> = self.read(&path).await?;
+        if let Some(mut entry) = entry {
+            entry.schedule_retry(attempts, next_retry_at);
+            self.write(&path, &entry).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn load_cursor(&self, id: &str) -> Result<Option<String>, CacheError> {
+        let cursor: Option<StoredCursor> = self.read(&Self::cursor_path(id)).await?;
+        Ok(cursor.map(|cursor| cursor.cursor))
+    }
+
+    async fn persist_cursor(&self, id: &str, cursor: &str, block: u64) -> Result<(), CacheError> {
+        let cursor = StoredCursor {
+            cursor: cursor.to_string(),
+            block_number: block,
+        };
+        self.write(&Self::cursor_path(id), &cursor).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    fn storage() -> ObjectStoreStorage {
+        ObjectStoreStorage::new(Arc::new(InMemory::new()))
+    }
+
+    fn item(uri: &str, is_errored: bool) -> CacheItem {
+        CacheItem {
+            uri: uri.to_string(),
+            json: None,
+            block: "100".to_string(),
+            space_id: "abc".to_string(),
+            is_errored,
+            block_number: None,
+            orphaned: false,
+        }
+    }
+
+    fn test_edit(name: &str) -> Edit {
+        Edit {
+            id: vec![0x01],
+            name: name.to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_object_store_insert_is_noop_when_present() {
+        let storage = storage();
+        storage.insert(&item("ipfs://QmTest", true)).await.unwrap();
+        storage.insert(&item("ipfs://QmTest", false)).await.unwrap();
+
+        let stored = storage.get("ipfs://QmTest").await.unwrap().unwrap();
+        assert!(stored.is_errored);
+        assert!(storage.get("ipfs://QmMissing").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_object_store_errored_due_and_resolve() {
+        let storage = storage();
+        storage.insert(&item("ipfs://QmA", true)).await.unwrap();
+        storage.insert(&item("ipfs://QmB", true)).await.unwrap();
+        storage.insert(&item("ipfs://QmOk", false)).await.unwrap();
+
+        storage.schedule_retry("ipfs://QmA", 1, 500).await.unwrap();
+        let due = storage.errored_due(100, 3, 10).await.unwrap();
+        assert_eq!(
+            due,
+            vec![RetryCandidate {
+                uri: "ipfs://QmB".to_string(),
+                attempts: 0,
+            }]
+        );
+
+        storage
+            .resolve("ipfs://QmB", &test_edit("Resolved"))
+            .await
+            .unwrap();
+        let due = storage.errored_due(500, 3, 10).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].uri, "ipfs://QmA");
+
+        let resolved = storage.get("ipfs://QmB").await.unwrap().unwrap();
+        assert!(!resolved.is_errored);
+        assert_eq!(resolved.json.unwrap().name, "Resolved");
+    }
+
+    #[tokio::test]
+    async fn test_object_store_cursor_persistence() {
+        let storage = storage();
+        assert!(storage.load_cursor("sink").await.unwrap().is_none());
+
+        storage
+            .persist_cursor("sink", "cursor_1", 100)
+            .await
+            .unwrap();
+        storage
+            .persist_cursor("sink", "cursor_2", 200)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            storage.load_cursor("sink").await.unwrap(),
+            Some("cursor_2".to_string())
+        );
+    }
+}
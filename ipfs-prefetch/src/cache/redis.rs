@@ -0,0 +1,187 @@
+//! Redis storage backend.
+//!
+//! Items are stored as JSON under `ipfs_cache:item:<cid>`. Errored items are
+//! also indexed in the `ipfs_cache:errored` sorted set, scored by the unix
+//! time of their next retry, so retries don't scan every item. Cursors are
+//! hashes under `ipfs_cache:cursor:<id>`.
+
+use redis::aio::ConnectionManager;
+use redis::AsyncTypedCommands;
+use wire::pb::grc20::Edit;
+
+use super::{cid_key, CacheError, CacheItem, CacheStorage, RetryCandidate, StoredEntry};
+
+/// Prefix of every key written by the cache.
+const KEY_PREFIX: &str = "ipfs_cache";
+
+/// Number of errored CIDs read per round trip when looking for due retries.
+const ERRORED_PAGE_SIZE: isize = 500;
+
+/// Redis storage backend for the IPFS cache.
+pub struct RedisStorage {
+    connection: ConnectionManager,
+}
+
+impl RedisStorage {
+    /// Create a new storage instance connected to Redis.
+    pub async fn new(url: &str) -> Result<Self, CacheError> {
+        let client = redis::Client::open(url)?;
+        let connection = client.get_connection_manager().await?;
+
+        Ok(RedisStorage { connection })
+    }
+
+    fn item_key(cid: &str) -> String {
+        format!("{KEY_PREFIX}:item:{cid}")
+    }
+
+    fn errored_key() -> String {
+        format!("{KEY_PREFIX}:errored")
+    }
+
+    fn cursor_key(id: &str) -> String {
+        format!("{KEY_PREFIX}:cursor:{id}")
+    }
+
+    async fn load(&self, cid: &str) -> Result<Option<StoredEntry>, CacheError> {
+        let value = self.connection.clone().get(Self::item_key(cid)).await?;
+
+        value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(CacheError::from)
+    }
+
+    async fn store(&self, cid: &str, entry: &StoredEntry) -> Result<(), CacheError> {
+        let value = serde_json::to_string(entry)?;
+        self.connection
+            .clone()
+            .set(Self::item_key(cid), value)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheStorage for RedisStorage {
+    async fn insert(&self, item: &CacheItem) -> Result<(), CacheError> {
+        let cid = cid_key(&item.uri);
+        let value = serde_json::to_string(&StoredEntry::new(item))?;
+        let mut connection = self.connection.clone();
+
+        // Only insert if not exists (matches PostgreSQL ON CONFLICT DO NOTHING)
+        let inserted = connection.set_nx(Self::item_key(cid), value).await?;
+
+        if inserted && item.is_errored {
+            connection.zadd(Self::errored_key(), cid, 0).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, uri: &str) -> Result<Option<CacheItem>, CacheError> {
+        Ok(self.load(cid_key(uri)).await?.map(StoredEntry::into_item))
+    }
+
+    async fn errored_due(
+        &self,
+        now: i64,
+        max_attempts: u32,
+        limit: usize,
+    ) -> Result<Vec<RetryCandidate>, CacheError> {
+        let mut connection = self.connection.clone();
+        let mut due = Vec::new();
+        let mut offset = 0;
+
+        // Items that ran out of attempts stay in the set, so keep paging
+        // until enough due items are found
+        while due.len() < limit {
+            let cids = connection
+                .zrangebyscore_limit(Self::errored_key(), "-inf", now, offset, ERRORED_PAGE_SIZE)
+                .await?;
+
+            for cid in &cids {
+                match self.load(cid).await? {
+                    Some(entry) if entry.is_due(now, max_attempts) => {
+                        due.push(entry.retry_candidate());
+                    }
+                    Some(_) => {}
+                    None => {
+                        connection.zrem(Self::errored_key(), cid).await?;
+                    }
+                }
+
+                if due.len() == limit {
+                    break;
+                }
+            }
+
+            if (cids.len() as isize) < ERRORED_PAGE_SIZE {
+                break;
+            }
+            offset += ERRORED_PAGE_SIZE;
+        }
+
+        Ok(due)
+    }
+
+    async fn resolve(&self, uri: &str, json: &Edit) -> Result<(), CacheError> {
+        let cid = cid_key(uri);
+
+        if let Some(mut entry) = self.load(cid).await? {
+            entry.resolve(json);
+            self.store(cid, &entry).await?;
+            self.connection
+                .clone()
+                .zrem(Self::errored_key(), cid)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn schedule_retry(
+        &self,
+        uri: &str,
+        attempts: u32,
+        next_retry_at: i64,
+    ) -> Result<(), CacheError> {
+        let cid = cid_key(uri);
+
+        if let Some(mut entry) = self.load(cid).await? {
+            entry.schedule_retry(attempts, next_retry_at);
+            self.store(cid, &entry).await?;
+            self.connection
+                .clone()
+                .zadd(Self::errored_key(), cid, next_retry_at)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn load_cursor(&self, id: &str) -> Result<Option<String>, CacheError> {
+        let cursor = self
+            .connection
+            .clone()
+            .hget(Self::cursor_key(id), "cursor")
+            .await?;
+
+        Ok(cursor)
+    }
+
+    async fn persist_cursor(&self, id: &str, cursor: &str, block: u64) -> Result<(), CacheError> {
+        self.connection
+            .clone()
+            .hset_multiple(
+                Self::cursor_key(id),
+                &[
+                    ("cursor", cursor.to_string()),
+                    ("block_number", block.to_string()),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+//! Eviction of old and rarely read cache items.
+//!
+//! Without eviction the cache grows with every edit ever published. The
+//! [`CompactionWorker`] periodically bounds it with up to two policies:
+//!
+//! - **TTL**: items whose block is older than [`CompactionConfig::ttl`] are
+//!   evicted, since consumers running that far behind are rebuilding anyway.
+//! - **Max size**: once the cached content exceeds
+//!   [`CompactionConfig::max_bytes`], the least recently read items are
+//!   evicted until it fits.
+//!
+//! Both are off by default. Each sweep also flushes the reads recorded by
+//! the [`Cache`], moves a batch of items stored in a previous format to the
+//! current one (see [`Cache::migrate_legacy_content`]) and records the item
+//! count and size in [`CacheMetrics`].
+//!
+//! Evicted items are not fetched again, so a consumer reading an evicted
+//! item gets a miss. The TTL should comfortably exceed how far consumers
+//! lag behind the chain.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let prefetcher = Prefetcher::new(decoder, CURSOR_ID, cache, IpfsSource::live(&gateway_url));
+//! tokio::spawn(prefetcher.compaction_worker(CompactionConfig::from_env()).run());
+//! ```
+
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::cache::{unix_now, Cache, CacheError, CacheStats};
+use crate::metrics::CacheMetrics;
+
+/// Environment variable for the seconds between compaction sweeps.
+const INTERVAL_ENV: &str = "IPFS_CACHE_COMPACTION_INTERVAL_SECS";
+
+/// Environment variable for the maximum block age of cached items, in seconds.
+const TTL_ENV: &str = "IPFS_CACHE_TTL_SECS";
+
+/// Environment variable for the maximum total size of cached content, in bytes.
+const MAX_BYTES_ENV: &str = "IPFS_CACHE_MAX_BYTES";
+
+/// Eviction policies applied by [`CompactionWorker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionConfig {
+    /// Time between sweeps.
+    pub interval: Duration,
+
+    /// Maximum age of an item's block. `None` keeps items regardless of age.
+    pub ttl: Option<Duration>,
+
+    /// Maximum total size of the cached content. `None` leaves the size
+    /// unbounded.
+    pub max_bytes: Option<u64>,
+
+    /// Maximum number of items moved to the current storage format per sweep.
+    pub migration_batch_size: usize,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(600),
+            ttl: None,
+            max_bytes: None,
+            migration_batch_size: 1_000,
+        }
+    }
+}
+
+impl CompactionConfig {
+    /// Read the compaction configuration from the environment.
+    ///
+    /// - `IPFS_CACHE_COMPACTION_INTERVAL_SECS`: seconds between sweeps (default 600)
+    /// - `IPFS_CACHE_TTL_SECS`: maximum block age of items (default: no TTL)
+    /// - `IPFS_CACHE_MAX_BYTES`: maximum size of cached content (default: unbounded)
+    ///
+    /// Missing, zero or unparseable values fall back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let positive = |name: &str| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .filter(|value| *value > 0)
+        };
+
+        Self {
+            interval: positive(INTERVAL_ENV)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.interval),
+            ttl: positive(TTL_ENV).map(Duration::from_secs),
+            max_bytes: positive(MAX_BYTES_ENV),
+            ..defaults
+        }
+    }
+
+    /// Whether any eviction policy is configured.
+    pub fn evicts(&self) -> bool {
+        self.ttl.is_some() || self.max_bytes.is_some()
+    }
+}
+
+/// Outcome of one compaction sweep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionSummary {
+    /// Items evicted for being older than the TTL.
+    pub expired: u64,
+    /// Items evicted to bring the cache under its maximum size.
+    pub evicted: u64,
+    /// Items moved to the current storage format.
+    pub migrated: u64,
+    /// Item count and size after the sweep, if the backend counts them.
+    pub stats: Option<CacheStats>,
+}
+
+/// Periodically evicts cache items and records the cache size.
+pub struct CompactionWorker {
+    cache: Arc<Cache>,
+    metrics: Arc<CacheMetrics>,
+    config: CompactionConfig,
+}
+
+impl CompactionWorker {
+    pub fn new(cache: Arc<Cache>, metrics: Arc<CacheMetrics>, config: CompactionConfig) -> Self {
+        Self {
+            cache,
+            metrics,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &CompactionConfig {
+        &self.config
+    }
+
+    /// Sweep every [`CompactionConfig::interval`], forever.
+    ///
+    /// Storage errors are logged and the sweep is tried again next interval.
+    pub async fn run(self) {
+        if self.config.evicts() && !self.cache.supports_eviction() {
+            tracing::warn!(
+                "IPFS cache eviction is configured but the storage backend doesn't support it, \
+                 bound the backend through its own expiry settings instead"
+            );
+        }
+
+        let mut interval = tokio::time::interval(self.config.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.run_once(unix_now()).await {
+                Ok(summary) if summary.expired > 0 || summary.evicted > 0 => {
+                    tracing::info!(
+                        expired = summary.expired,
+                        evicted = summary.evicted,
+                        migrated = summary.migrated,
+                        items = summary.stats.map(|stats| stats.items),
+                        bytes = summary.stats.map(|stats| stats.bytes),
+                        "Evicted IPFS cache items"
+                    );
+                }
+                Ok(summary) if summary.migrated > 0 => {
+                    tracing::info!(
+                        migrated = summary.migrated,
+                        "Migrated IPFS cache items to deduplicated content"
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!(error = %e, "Failed to compact IPFS cache"),
+            }
+        }
+    }
+
+    /// Flush recorded reads, migrate a batch of legacy items, apply the
+    /// eviction policies at `now` (unix seconds) and record the resulting
+    /// cache size.
+    pub async fn run_once(&self, now: i64) -> Result<CompactionSummary, CacheError> {
+        // Reads must land before LRU eviction, or recently read items would
+        // be ranked by their insertion time
+        self.cache.flush_reads().await?;
+
+        let mut summary = CompactionSummary {
+            migrated: self
+                .cache
+                .migrate_legacy_content(self.config.migration_batch_size)
+                .await?,
+            ..CompactionSummary::default()
+        };
+
+        if let Some(ttl) = self.config.ttl {
+            let cutoff = now.saturating_sub(ttl.as_secs() as i64);
+            summary.expired = self.cache.evict_before(cutoff).await?;
+        }
+
+        if let Some(max_bytes) = self.config.max_bytes {
+            summary.evicted = self.cache.evict_lru(max_bytes).await?;
+        }
+
+        summary.stats = self.cache.stats().await?;
+        self.metrics
+            .record_compaction(summary.stats, summary.expired, summary.evicted);
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheItem;
+    use wire::pb::grc20::Edit;
+
+    fn item(uri: &str, block: i64, name_len: usize) -> CacheItem {
+        CacheItem {
+            uri: uri.to_string(),
+            json: Some(Edit {
+                id: vec![0x01],
+                name: "x".repeat(name_len),
+                ops: vec![],
+                authors: vec![],
+                language: None,
+            }),
+            block: block.to_string(),
+            space_id: "abc".to_string(),
+            is_errored: false,
+            block_number: None,
+            orphaned: false,
+        }
+    }
+
+    fn worker(cache: Arc<Cache>, ttl: Option<u64>, max_bytes: Option<u64>) -> CompactionWorker {
+        let config = CompactionConfig {
+            ttl: ttl.map(Duration::from_secs),
+            max_bytes,
+            ..CompactionConfig::default()
+        };
+        CompactionWorker::new(cache, Arc::new(CacheMetrics::default()), config)
+    }
+
+    #[tokio::test]
+    async fn ttl_evicts_items_from_old_blocks() {
+        let cache = Arc::new(Cache::mock());
+        cache.put(&item("ipfs://QmOld", 1_000, 10)).await.unwrap();
+        cache.put(&item("ipfs://QmNew", 5_000, 10)).await.unwrap();
+
+        let summary = worker(cache.clone(), Some(3_600), None)
+            .run_once(5_000)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.expired, 1);
+        assert_eq!(summary.stats.unwrap().items, 1);
+        assert!(cache.get_by_uri("ipfs://QmOld").await.unwrap().is_none());
+        assert!(cache.get_by_uri("ipfs://QmNew").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn max_size_bounds_cached_content() {
+        let cache = Arc::new(Cache::mock());
+        for uri in ["ipfs://QmA", "ipfs://QmB", "ipfs://QmC"] {
+            cache.put(&item(uri, 1_000, 100)).await.unwrap();
+        }
+        let item_bytes = cache.stats().await.unwrap().unwrap().bytes / 3;
+
+        let summary = worker(cache, None, Some(item_bytes * 2))
+            .run_once(5_000)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.evicted, 1);
+        assert_eq!(
+            summary.stats,
+            Some(CacheStats {
+                items: 2,
+                bytes: item_bytes * 2,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn compaction_records_metrics() {
+        let cache = Arc::new(Cache::mock());
+        cache.put(&item("ipfs://QmOld", 1_000, 10)).await.unwrap();
+
+        let worker = worker(cache, Some(60), None);
+        worker.run_once(5_000).await.unwrap();
+
+        let storage = worker.metrics.storage();
+        assert_eq!(storage.stats, Some(CacheStats::default()));
+        assert_eq!(storage.expired, 1);
+        assert_eq!(storage.evicted, 0);
+    }
+}
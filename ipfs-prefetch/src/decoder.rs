@@ -0,0 +1,43 @@
+//! Decoding of substream blocks into the IPFS content to pre-fetch.
+//!
+//! Each substream encodes published edits differently (`GeoOutput` for
+//! geo_substream, `EditsPublishedList` for hermes-substream). A service
+//! plugs its format into the [`Prefetcher`](crate::Prefetcher) by
+//! implementing [`EventDecoder`].
+
+use serde::{Deserialize, Serialize};
+use stream::pb::sf::substreams::rpc::v2::BlockScopedData;
+
+use crate::PrefetchError;
+
+/// An edit whose IPFS content should be cached.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrefetchEvent {
+    /// The IPFS URI of the edit's content (e.g., "ipfs://Qm...")
+    pub uri: String,
+    /// The space the edit was published to, as stored on the cache item
+    pub space_id: String,
+    /// The DAO the edit was published through, if the substream reports it.
+    /// Only used by [`PrefetchFilter`](crate::PrefetchFilter).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dao_address: Option<String>,
+}
+
+/// Extracts the edits to pre-fetch from a block.
+pub trait EventDecoder: Send + Sync {
+    /// Decode the edits published in `data`. Edits the service never caches
+    /// can be left out here; configurable lists belong in a
+    /// [`PrefetchFilter`](crate::PrefetchFilter).
+    fn decode(&self, data: &BlockScopedData) -> Result<Vec<PrefetchEvent>, PrefetchError>;
+}
+
+/// The module output of a block, or an error if the block has none.
+pub fn map_output(data: &BlockScopedData) -> Result<&[u8], PrefetchError> {
+    data.output
+        .as_ref()
+        .and_then(|output| output.map_output.as_ref())
+        .map(|output| output.value.as_slice())
+        .ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "Missing map output").into()
+        })
+}
@@ -0,0 +1,356 @@
+//! Prometheus exporter for a [`Prefetcher`](crate::Prefetcher).
+//!
+//! [`render`] writes a [`PrefetchStatus`] and the [`CacheMetrics`] behind it
+//! in the Prometheus text format:
+//!
+//! - `ipfs_prefetch_pending_blocks`, `ipfs_prefetch_pending_fetches`: the
+//!   backlog of blocks whose cursor isn't persisted yet.
+//! - `ipfs_prefetch_in_flight_fetches`, `ipfs_prefetch_queued_fetches`,
+//!   `ipfs_prefetch_max_in_flight`: the queue in front of the fetch permits.
+//!   Queued fetches piling up while every permit is in use means the
+//!   gateways can't keep up with the stream.
+//! - `ipfs_prefetch_head_block`, `ipfs_prefetch_cursor_block`,
+//!   `ipfs_prefetch_cursor_lag_blocks`: how far the persisted cursor trails
+//!   the stream.
+//! - `ipfs_prefetch_fetches_total` and `ipfs_prefetch_fetch_duration_seconds`
+//!   per gateway: fetch results and the latency distribution recorded by the
+//!   [`ipfs::MeteredFetcher`].
+//! - `ipfs_prefetch_skipped_blocks_total`: redelivered blocks skipped.
+//!
+//! With the `exporter` feature, [`router`] serves them on `GET /metrics`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! tokio::spawn(exporter::serve(prefetcher.status_handle(), "0.0.0.0:9090".parse()?));
+//! ```
+
+use std::fmt::{Display, Write};
+
+use ipfs::LATENCY_BUCKETS_MS;
+
+use crate::metrics::CacheMetrics;
+use crate::status::PrefetchStatus;
+
+/// Content type of the Prometheus text format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Render `status` and `metrics` in the Prometheus text format.
+pub fn render(status: &PrefetchStatus, metrics: &CacheMetrics) -> String {
+    let mut out = Exposition::default();
+
+    out.gauge(
+        "ipfs_prefetch_pending_blocks",
+        "Blocks whose cursor isn't persisted yet.",
+        status.pending_blocks,
+    );
+    out.gauge(
+        "ipfs_prefetch_pending_fetches",
+        "Fetches still running or waiting for a permit, across all blocks.",
+        status.pending_fetches,
+    );
+    out.gauge(
+        "ipfs_prefetch_in_flight_fetches",
+        "Fetches holding a fetch permit.",
+        status.in_flight_fetches,
+    );
+    out.gauge(
+        "ipfs_prefetch_queued_fetches",
+        "Fetches waiting for a fetch permit.",
+        status.queued_fetches(),
+    );
+    out.gauge(
+        "ipfs_prefetch_max_in_flight",
+        "Fetch permits, the most fetches running at once.",
+        status.max_in_flight,
+    );
+    if let Some(head_block) = status.head_block {
+        out.gauge(
+            "ipfs_prefetch_head_block",
+            "Last block delivered by the stream.",
+            head_block,
+        );
+    }
+    if let Some(cursor_block) = status.cursor_block {
+        out.gauge(
+            "ipfs_prefetch_cursor_block",
+            "Block the cursor was last persisted at.",
+            cursor_block,
+        );
+    }
+    out.gauge(
+        "ipfs_prefetch_cursor_lag_blocks",
+        "Blocks between the oldest pending block and the head.",
+        status.cursor_lag_blocks,
+    );
+    out.counter(
+        "ipfs_prefetch_skipped_blocks_total",
+        "Redelivered blocks skipped since startup.",
+        metrics.skipped_blocks(),
+    );
+
+    let gateways = metrics.gateways();
+
+    out.header(
+        "ipfs_prefetch_fetches_total",
+        "counter",
+        "Fetch attempts by gateway and result.",
+    );
+    for health in &gateways {
+        let gateway = health.gateway.as_str();
+        out.sample(
+            "ipfs_prefetch_fetches_total",
+            &[("gateway", gateway), ("result", "success")],
+            health.successes,
+        );
+
+        let mut errors: Vec<_> = health.errors.iter().collect();
+        errors.sort();
+        for (class, count) in errors {
+            out.sample(
+                "ipfs_prefetch_fetches_total",
+                &[("gateway", gateway), ("result", &class.to_string())],
+                count,
+            );
+        }
+    }
+
+    out.header(
+        "ipfs_prefetch_fetch_duration_seconds",
+        "histogram",
+        "Fetch latency by gateway.",
+    );
+    for health in &gateways {
+        let gateway = health.gateway.as_str();
+        let latency = &health.latency;
+        let mut cumulative = 0;
+
+        for (bound_ms, count) in LATENCY_BUCKETS_MS.iter().zip(latency.buckets) {
+            cumulative += count;
+            out.sample(
+                "ipfs_prefetch_fetch_duration_seconds_bucket",
+                &[
+                    ("gateway", gateway),
+                    ("le", &seconds(*bound_ms).to_string()),
+                ],
+                cumulative,
+            );
+        }
+        out.sample(
+            "ipfs_prefetch_fetch_duration_seconds_bucket",
+            &[("gateway", gateway), ("le", "+Inf")],
+            latency.count,
+        );
+        out.sample(
+            "ipfs_prefetch_fetch_duration_seconds_sum",
+            &[("gateway", gateway)],
+            seconds(latency.sum_ms),
+        );
+        out.sample(
+            "ipfs_prefetch_fetch_duration_seconds_count",
+            &[("gateway", gateway)],
+            latency.count,
+        );
+    }
+
+    out.0
+}
+
+fn seconds(ms: u64) -> f64 {
+    ms as f64 / 1_000.0
+}
+
+/// Metric families in the Prometheus text format.
+#[derive(Default)]
+struct Exposition(String);
+
+impl Exposition {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        // Writing to a String can't fail
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(label, value)| format!("{label}=\"{}\"", escape(value)))
+                .collect();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: impl Display) {
+        self.header(name, "gauge", help);
+        self.sample(name, &[], value);
+    }
+
+    fn counter(&mut self, name: &str, help: &str, value: impl Display) {
+        self.header(name, "counter", help);
+        self.sample(name, &[], value);
+    }
+}
+
+/// Escape a label value: backslashes, double quotes and line feeds.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+#[cfg(feature = "exporter")]
+pub use http::{router, serve};
+
+#[cfg(feature = "exporter")]
+mod http {
+    use std::net::SocketAddr;
+
+    use axum::extract::State;
+    use axum::http::header;
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use axum::Router;
+
+    use super::CONTENT_TYPE;
+    use crate::StatusHandle;
+
+    /// Build the `/metrics` route over a prefetcher's progress.
+    pub fn router(status: StatusHandle) -> Router {
+        Router::new()
+            .route("/metrics", get(get_metrics))
+            .with_state(status)
+    }
+
+    /// Serve `/metrics` on `addr` until the process exits.
+    pub async fn serve(status: StatusHandle, addr: SocketAddr) -> std::io::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tracing::info!(addr = %addr, "Serving IPFS prefetch metrics");
+        axum::serve(listener, router(status)).await
+    }
+
+    async fn get_metrics(State(status): State<StatusHandle>) -> impl IntoResponse {
+        (
+            [(header::CONTENT_TYPE, CONTENT_TYPE)],
+            status.prometheus().await,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::PendingBlockStatus;
+
+    fn status() -> PrefetchStatus {
+        let blocks = vec![PendingBlockStatus {
+            block: 100,
+            remaining: 3,
+            written: false,
+        }];
+
+        PrefetchStatus::new(blocks, Some(105), Some(99), &CacheMetrics::default())
+            .with_permits(2, 0)
+    }
+
+    #[test]
+    fn render_exports_backlog_and_cursor_lag() {
+        let output = render(&status(), &CacheMetrics::default());
+
+        assert!(output.contains("# TYPE ipfs_prefetch_pending_blocks gauge\n"));
+        assert!(output.contains("\nipfs_prefetch_pending_blocks 1\n"));
+        assert!(output.contains("\nipfs_prefetch_pending_fetches 3\n"));
+        assert!(output.contains("\nipfs_prefetch_in_flight_fetches 2\n"));
+        assert!(output.contains("\nipfs_prefetch_queued_fetches 1\n"));
+        assert!(output.contains("\nipfs_prefetch_max_in_flight 2\n"));
+        assert!(output.contains("\nipfs_prefetch_head_block 105\n"));
+        assert!(output.contains("\nipfs_prefetch_cursor_block 99\n"));
+        assert!(output.contains("\nipfs_prefetch_cursor_lag_blocks 5\n"));
+        // No gateway was fetched from yet, so the families have no samples
+        assert!(output.contains("# TYPE ipfs_prefetch_fetch_duration_seconds histogram\n"));
+        assert!(!output.contains("ipfs_prefetch_fetch_duration_seconds_count"));
+    }
+
+    #[test]
+    fn render_omits_unknown_blocks() {
+        let status = PrefetchStatus::new(vec![], None, None, &CacheMetrics::default());
+
+        let output = render(&status, &CacheMetrics::default());
+
+        assert!(!output.contains("ipfs_prefetch_head_block"));
+        assert!(!output.contains("ipfs_prefetch_cursor_block"));
+        assert!(output.contains("\nipfs_prefetch_cursor_lag_blocks 0\n"));
+    }
+
+    #[test]
+    fn samples_escape_label_values() {
+        let mut out = Exposition::default();
+
+        out.sample("metric", &[("gateway", "a\"b\\c\nd")], 1);
+
+        assert_eq!(out.0, "metric{gateway=\"a\\\"b\\\\c\\nd\"} 1\n");
+    }
+
+    #[test]
+    fn seconds_are_formatted_without_trailing_zeros() {
+        assert_eq!(seconds(50).to_string(), "0.05");
+        assert_eq!(seconds(1_000).to_string(), "1");
+        assert_eq!(seconds(2_500).to_string(), "2.5");
+    }
+
+    #[cfg(feature = "exporter")]
+    #[tokio::test]
+    async fn get_metrics_serves_the_text_format() {
+        use std::collections::HashMap;
+
+        use axum::body::{to_bytes, Body};
+        use axum::http::{header, Request, StatusCode};
+        use ipfs::IpfsSource;
+        use stream::pb::sf::substreams::rpc::v2::BlockScopedData;
+        use stream::pb::sf::substreams::v1::Clock;
+        use tower::ServiceExt;
+
+        use crate::cache::Cache;
+        use crate::decoder::{EventDecoder, PrefetchEvent};
+        use crate::{PrefetchError, Prefetcher};
+
+        struct NoEvents;
+
+        impl EventDecoder for NoEvents {
+            fn decode(&self, _data: &BlockScopedData) -> Result<Vec<PrefetchEvent>, PrefetchError> {
+                Ok(vec![])
+            }
+        }
+
+        let prefetcher = Prefetcher::new(
+            NoEvents,
+            "test",
+            Cache::mock(),
+            IpfsSource::mock(HashMap::new()),
+        );
+        let block = BlockScopedData {
+            clock: Some(Clock {
+                id: "block_100".to_string(),
+                number: 100,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        prefetcher.process_block(&block).await.unwrap();
+
+        let response = router(prefetcher.status_handle())
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("\nipfs_prefetch_head_block 100\n"));
+        assert!(body.contains("\nipfs_prefetch_pending_blocks 0\n"));
+    }
+}
@@ -0,0 +1,292 @@
+//! Allow and deny lists deciding which edits are pre-fetched.
+//!
+//! Some spaces only publish spam, and fetching their content costs gateway
+//! requests and cache space for nothing. A [`PrefetchFilter`] drops the
+//! edits of denied spaces and DAOs after a block is decoded, before any of
+//! its fetches is scheduled, and counts what it dropped in
+//! [`CacheMetrics`](crate::metrics::CacheMetrics).
+//!
+//! Each list matches either the edit's space ID, as stored on the cache
+//! item, or the address of the DAO it was published through. Deny lists win
+//! over allow lists. A non-empty allow list only lets through edits matching
+//! it, so a DAO allow list drops every edit whose decoder doesn't know its
+//! DAO address (e.g. hermes-substream edits). IDs and addresses are matched
+//! case-insensitively.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let prefetcher = Prefetcher::new(decoder, CURSOR_ID, cache, IpfsSource::live(&gateway_url))
+//!     .with_filter(PrefetchFilter::from_env()?);
+//! ```
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::{env, fs, io};
+
+use serde::Deserialize;
+
+use crate::decoder::PrefetchEvent;
+
+/// Environment variable for a JSON file with the lists, see
+/// [`PrefetchFilter::from_file`].
+const FILE_ENV: &str = "IPFS_FILTER_FILE";
+
+/// Environment variable for comma-separated space IDs to fetch exclusively.
+const ALLOWED_SPACES_ENV: &str = "IPFS_ALLOWED_SPACES";
+
+/// Environment variable for comma-separated space IDs to skip.
+const BLOCKED_SPACES_ENV: &str = "IPFS_BLOCKED_SPACES";
+
+/// Environment variable for comma-separated DAO addresses to fetch exclusively.
+const ALLOWED_DAOS_ENV: &str = "IPFS_ALLOWED_DAOS";
+
+/// Environment variable for comma-separated DAO addresses to skip.
+const BLOCKED_DAOS_ENV: &str = "IPFS_BLOCKED_DAOS";
+
+/// Why an edit was left out by a [`PrefetchFilter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// The edit's space is on the deny list.
+    BlockedSpace,
+    /// The edit's DAO is on the deny list.
+    BlockedDao,
+    /// An allow list is set and the edit doesn't match it.
+    NotAllowed,
+}
+
+/// Allow and deny lists by space ID and DAO address.
+///
+/// The default filter lets every edit through.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct PrefetchFilter {
+    /// Space IDs to fetch exclusively. Empty allows every space.
+    pub allowed_spaces: HashSet<String>,
+    /// Space IDs whose edits are skipped.
+    pub blocked_spaces: HashSet<String>,
+    /// DAO addresses to fetch exclusively. Empty allows every DAO.
+    pub allowed_daos: HashSet<String>,
+    /// DAO addresses whose edits are skipped.
+    pub blocked_daos: HashSet<String>,
+}
+
+impl PrefetchFilter {
+    /// Read the lists from the environment.
+    ///
+    /// - `IPFS_FILTER_FILE`: JSON file with the lists, see [`Self::from_file`]
+    /// - `IPFS_ALLOWED_SPACES` / `IPFS_BLOCKED_SPACES`: comma-separated space IDs
+    /// - `IPFS_ALLOWED_DAOS` / `IPFS_BLOCKED_DAOS`: comma-separated DAO addresses
+    ///
+    /// Lists from the environment are added to the ones from the file. Fails
+    /// if the file can't be read or parsed, rather than fetching everything.
+    pub fn from_env() -> io::Result<Self> {
+        let mut filter = match env::var(FILE_ENV) {
+            Ok(path) if !path.is_empty() => Self::from_file(path)?,
+            _ => Self::default(),
+        };
+
+        let list = |name: &str| env::var(name).unwrap_or_default();
+        filter
+            .allowed_spaces
+            .extend(split(&list(ALLOWED_SPACES_ENV)));
+        filter
+            .blocked_spaces
+            .extend(split(&list(BLOCKED_SPACES_ENV)));
+        filter.allowed_daos.extend(split(&list(ALLOWED_DAOS_ENV)));
+        filter.blocked_daos.extend(split(&list(BLOCKED_DAOS_ENV)));
+
+        Ok(filter.normalized())
+    }
+
+    /// Read the lists from a JSON file. Every list is optional:
+    ///
+    /// ```json
+    /// {
+    ///   "allowed_spaces": [],
+    ///   "blocked_spaces": ["25be7f0b..."],
+    ///   "allowed_daos": [],
+    ///   "blocked_daos": ["0x2223..."]
+    /// }
+    /// ```
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let filter: Self = serde_json::from_slice(&fs::read(path)?)?;
+        Ok(filter.normalized())
+    }
+
+    /// Add DAO addresses to the deny list, e.g. a service's built-in
+    /// blocklist.
+    pub fn block_daos<S: AsRef<str>>(mut self, addresses: impl IntoIterator<Item = S>) -> Self {
+        self.blocked_daos.extend(
+            addresses
+                .into_iter()
+                .map(|address| address.as_ref().to_lowercase()),
+        );
+        self
+    }
+
+    /// Add space IDs to the deny list.
+    pub fn block_spaces<S: AsRef<str>>(mut self, space_ids: impl IntoIterator<Item = S>) -> Self {
+        self.blocked_spaces.extend(
+            space_ids
+                .into_iter()
+                .map(|space_id| space_id.as_ref().to_lowercase()),
+        );
+        self
+    }
+
+    /// Whether the filter lets every edit through.
+    pub fn is_empty(&self) -> bool {
+        self.allowed_spaces.is_empty()
+            && self.blocked_spaces.is_empty()
+            && self.allowed_daos.is_empty()
+            && self.blocked_daos.is_empty()
+    }
+
+    /// Why `event` should be skipped, or `None` if it should be fetched.
+    pub fn check(&self, event: &PrefetchEvent) -> Option<FilterReason> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let space_id = event.space_id.to_lowercase();
+        let dao_address = event.dao_address.as_deref().map(str::to_lowercase);
+
+        if self.blocked_spaces.contains(&space_id) {
+            return Some(FilterReason::BlockedSpace);
+        }
+        if dao_address
+            .as_ref()
+            .is_some_and(|address| self.blocked_daos.contains(address))
+        {
+            return Some(FilterReason::BlockedDao);
+        }
+
+        let space_allowed =
+            self.allowed_spaces.is_empty() || self.allowed_spaces.contains(&space_id);
+        let dao_allowed = self.allowed_daos.is_empty()
+            || dao_address
+                .as_ref()
+                .is_some_and(|address| self.allowed_daos.contains(address));
+
+        if space_allowed && dao_allowed {
+            None
+        } else {
+            Some(FilterReason::NotAllowed)
+        }
+    }
+
+    fn normalized(self) -> Self {
+        let lower = |set: HashSet<String>| set.into_iter().map(|id| id.to_lowercase()).collect();
+
+        Self {
+            allowed_spaces: lower(self.allowed_spaces),
+            blocked_spaces: lower(self.blocked_spaces),
+            allowed_daos: lower(self.allowed_daos),
+            blocked_daos: lower(self.blocked_daos),
+        }
+    }
+}
+
+/// Non-empty, trimmed entries of a comma-separated list.
+fn split(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(space_id: &str, dao_address: Option<&str>) -> PrefetchEvent {
+        PrefetchEvent {
+            uri: "ipfs://QmTest".to_string(),
+            space_id: space_id.to_string(),
+            dao_address: dao_address.map(str::to_string),
+        }
+    }
+
+    fn set(entries: &[&str]) -> HashSet<String> {
+        entries.iter().map(|entry| entry.to_string()).collect()
+    }
+
+    #[test]
+    fn default_filter_allows_everything() {
+        let filter = PrefetchFilter::default();
+
+        assert!(filter.is_empty());
+        assert_eq!(filter.check(&event("space", None)), None);
+    }
+
+    #[test]
+    fn deny_lists_match_case_insensitively() {
+        let filter = PrefetchFilter::default()
+            .block_spaces(["ABCD"])
+            .block_daos(["0xDEAD"]);
+
+        assert_eq!(
+            filter.check(&event("abcd", None)),
+            Some(FilterReason::BlockedSpace)
+        );
+        assert_eq!(
+            filter.check(&event("other", Some("0xdead"))),
+            Some(FilterReason::BlockedDao)
+        );
+        assert_eq!(filter.check(&event("other", Some("0xbeef"))), None);
+    }
+
+    #[test]
+    fn allow_lists_only_let_matching_edits_through() {
+        let filter = PrefetchFilter {
+            allowed_spaces: set(&["good"]),
+            blocked_spaces: set(&["good"]),
+            ..Default::default()
+        };
+
+        // Deny lists win over allow lists
+        assert_eq!(
+            filter.check(&event("good", None)),
+            Some(FilterReason::BlockedSpace)
+        );
+        assert_eq!(
+            filter.check(&event("other", None)),
+            Some(FilterReason::NotAllowed)
+        );
+
+        let filter = PrefetchFilter {
+            allowed_daos: set(&["0xbeef"]),
+            ..Default::default()
+        };
+
+        assert_eq!(filter.check(&event("space", Some("0xBEEF"))), None);
+        // Edits without a DAO address can't match a DAO allow list
+        assert_eq!(
+            filter.check(&event("space", None)),
+            Some(FilterReason::NotAllowed)
+        );
+    }
+
+    #[test]
+    fn filter_file_lists_are_optional() {
+        let path = env::temp_dir().join(format!("ipfs-filter-{}.json", std::process::id()));
+        fs::write(&path, r#"{ "blocked_daos": ["0xDEAD"] }"#).unwrap();
+
+        let filter = PrefetchFilter::from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(filter.blocked_daos, set(&["0xdead"]));
+        assert!(filter.allowed_spaces.is_empty());
+        assert!(
+            PrefetchFilter::from_file(env::temp_dir().join("missing-ipfs-filter.json")).is_err()
+        );
+    }
+
+    #[test]
+    fn split_skips_empty_entries() {
+        let entries: Vec<String> = split(" a, ,b,").collect();
+
+        assert_eq!(entries, vec!["a", "b"]);
+    }
+}
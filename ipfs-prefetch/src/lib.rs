@@ -0,0 +1,74 @@
+//! IPFS Prefetch
+//!
+//! Shared pre-fetching of IPFS content for substream services, so consumers
+//! like the edits transformer don't block on network I/O.
+//!
+//! A [`Prefetcher`]:
+//! 1. Decodes the edits published in each block with a pluggable
+//!    [`EventDecoder`] (e.g. `GeoOutput` or `EditsPublishedList`)
+//! 2. Drops the edits of denied spaces and DAOs (see [`filter`])
+//! 3. For each remaining edit, fetches the IPFS content by CID
+//! 4. Stores resolved content in the cache, one write per block
+//! 5. Persists the cursor once every block up to it is cached
+//!
+//! Alongside it, [`retry`] re-fetches content that failed to fetch,
+//! [`compaction`] evicts old or rarely read content, [`status`] reports the
+//! prefetcher's backlog, [`exporter`] renders it for Prometheus, and the
+//! `sidecar` module (behind the `sidecar` feature) serves cache reads and the
+//! status over HTTP.
+//!
+//! Services stay thin: they implement [`EventDecoder`] for their substream
+//! output and forward the blocks of their data source to
+//! [`Prefetcher::process_block`].
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! use ipfs_prefetch::{cache::CacheSource, EventDecoder, PrefetchEvent, Prefetcher};
+//!
+//! struct MyDecoder;
+//!
+//! impl EventDecoder for MyDecoder {
+//!     fn decode(&self, data: &BlockScopedData) -> Result<Vec<PrefetchEvent>, PrefetchError> {
+//!         // Decode the block output...
+//!         Ok(events)
+//!     }
+//! }
+//!
+//! let cache = CacheSource::live(&database_url).into_cache().await?;
+//! let prefetcher = Prefetcher::new(MyDecoder, "my_service", cache, IpfsSource::live(&gateway_url));
+//!
+//! // In the service's sink
+//! prefetcher.process_block(&block).await?;
+//! ```
+
+pub mod cache;
+pub mod compaction;
+pub mod decoder;
+pub mod exporter;
+pub mod filter;
+pub mod metrics;
+mod prefetcher;
+pub mod retry;
+#[cfg(feature = "sidecar")]
+pub mod sidecar;
+pub mod status;
+
+pub use decoder::{EventDecoder, PrefetchEvent};
+pub use filter::PrefetchFilter;
+pub use prefetcher::{Prefetcher, StatusHandle};
+
+use cache::CacheError;
+
+/// Error type for pre-fetching blocks.
+#[derive(Debug, thiserror::Error)]
+pub enum PrefetchError {
+    #[error("Cache error: {0}")]
+    Cache(#[from] CacheError),
+
+    #[error("Protobuf decode error: {0}")]
+    Decode(#[from] prost::DecodeError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
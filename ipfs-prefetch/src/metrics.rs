@@ -0,0 +1,339 @@
+//! Cache metrics: per-gateway fetch results and content availability lag.
+//!
+//! Fetch results per gateway (successes, not found, timeouts, decode
+//! failures) are recorded by an [`ipfs::MeteredFetcher`] into
+//! [`CacheMetrics::fetch`]. On top of that, the sink records how long after
+//! an edit was published on chain its content was cached, which tells us how
+//! far content availability lags chain events.
+//!
+//! Edits skipped by the prefetcher's [`crate::filter::PrefetchFilter`] are
+//! counted per [`FilterReason`].
+//!
+//! The [`crate::compaction::CompactionWorker`] records the number of cached
+//! items, their total size and how many were evicted.
+//!
+//! Metrics are exported as structured log lines by
+//! [`CacheMetrics::report_if_due`].
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ipfs::{ErrorClass, FetchMetrics, GatewayHealth};
+
+use crate::cache::CacheStats;
+use crate::filter::FilterReason;
+
+/// Upper bounds of the content lag histogram buckets, in seconds.
+/// Lags longer than the last bound land in an overflow bucket.
+pub const CONTENT_LAG_BUCKETS_SECS: [u64; 8] = [
+    12,
+    60,
+    300,
+    900,
+    3_600,
+    6 * 3_600,
+    24 * 3_600,
+    7 * 24 * 3_600,
+];
+
+/// How often metrics are logged by [`CacheMetrics::report_if_due`].
+pub const DEFAULT_REPORT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Histogram of time from an edit's block timestamp to its content being
+/// cached, using [`CONTENT_LAG_BUCKETS_SECS`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentLagHistogram {
+    /// Count per bucket, with the overflow bucket last.
+    pub buckets: [u64; CONTENT_LAG_BUCKETS_SECS.len() + 1],
+    pub count: u64,
+    pub sum_secs: u64,
+    pub max_secs: u64,
+}
+
+impl ContentLagHistogram {
+    fn record(&mut self, lag_secs: u64) {
+        let bucket = CONTENT_LAG_BUCKETS_SECS
+            .iter()
+            .position(|bound| lag_secs <= *bound)
+            .unwrap_or(CONTENT_LAG_BUCKETS_SECS.len());
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_secs += lag_secs;
+        self.max_secs = self.max_secs.max(lag_secs);
+    }
+
+    /// Mean lag in seconds, or 0.0 before any edit was cached.
+    pub fn mean_secs(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum_secs as f64 / self.count as f64
+        }
+    }
+
+    /// Upper bound of the bucket containing the given quantile (0.0..=1.0),
+    /// or `None` if there are no samples or it falls in the overflow bucket.
+    pub fn quantile_upper_bound_secs(&self, quantile: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let target = (quantile.clamp(0.0, 1.0) * self.count as f64)
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return CONTENT_LAG_BUCKETS_SECS.get(bucket).copied();
+            }
+        }
+
+        None
+    }
+}
+
+/// Cache size and eviction totals, recorded by each compaction sweep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageMetrics {
+    /// Item count and size after the last sweep, if the backend counts them.
+    pub stats: Option<CacheStats>,
+    /// Items evicted for being older than the TTL since startup.
+    pub expired: u64,
+    /// Items evicted to bound the cache size since startup.
+    pub evicted: u64,
+}
+
+/// Edits skipped by the prefetch filter since startup, per
+/// [`FilterReason`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FilterMetrics {
+    pub blocked_spaces: u64,
+    pub blocked_daos: u64,
+    pub not_allowed: u64,
+}
+
+impl FilterMetrics {
+    /// Edits skipped for any reason.
+    pub fn total(&self) -> u64 {
+        self.blocked_spaces + self.blocked_daos + self.not_allowed
+    }
+}
+
+/// Metrics shared by the sink and its fetch tasks.
+#[derive(Debug)]
+pub struct CacheMetrics {
+    fetch: Arc<FetchMetrics>,
+    content_lag: Mutex<ContentLagHistogram>,
+    storage: Mutex<StorageMetrics>,
+    skipped_blocks: AtomicU64,
+    filtered: Mutex<FilterMetrics>,
+    report_interval: Duration,
+    last_report: Mutex<Instant>,
+}
+
+impl Default for CacheMetrics {
+    fn default() -> Self {
+        Self::new(DEFAULT_REPORT_INTERVAL)
+    }
+}
+
+impl CacheMetrics {
+    pub fn new(report_interval: Duration) -> Self {
+        Self {
+            fetch: Arc::new(FetchMetrics::new()),
+            content_lag: Mutex::new(ContentLagHistogram::default()),
+            storage: Mutex::new(StorageMetrics::default()),
+            skipped_blocks: AtomicU64::new(0),
+            filtered: Mutex::new(FilterMetrics::default()),
+            report_interval,
+            last_report: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Registry the IPFS fetcher records per-gateway results into.
+    pub fn fetch(&self) -> Arc<FetchMetrics> {
+        self.fetch.clone()
+    }
+
+    /// Record that an edit published at `block_timestamp` (unix seconds) was
+    /// just cached.
+    pub fn record_cached(&self, block_timestamp: u64) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        self.record_lag(now.saturating_sub(block_timestamp));
+    }
+
+    fn record_lag(&self, lag_secs: u64) {
+        self.content_lag.lock().unwrap().record(lag_secs);
+    }
+
+    /// Snapshot of the content lag histogram.
+    pub fn content_lag(&self) -> ContentLagHistogram {
+        self.content_lag.lock().unwrap().clone()
+    }
+
+    /// Record the outcome of a compaction sweep.
+    pub fn record_compaction(&self, stats: Option<CacheStats>, expired: u64, evicted: u64) {
+        let mut storage = self.storage.lock().unwrap();
+        storage.stats = stats;
+        storage.expired += expired;
+        storage.evicted += evicted;
+    }
+
+    /// Snapshot of the cache size and eviction totals.
+    pub fn storage(&self) -> StorageMetrics {
+        *self.storage.lock().unwrap()
+    }
+
+    /// Record a block the stream delivered again and the sink skipped.
+    pub fn record_skipped_block(&self) {
+        self.skipped_blocks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of redelivered blocks skipped since startup.
+    pub fn skipped_blocks(&self) -> u64 {
+        self.skipped_blocks.load(Ordering::Relaxed)
+    }
+
+    /// Record an edit skipped by the prefetch filter.
+    pub fn record_filtered(&self, reason: FilterReason) {
+        let mut filtered = self.filtered.lock().unwrap();
+        match reason {
+            FilterReason::BlockedSpace => filtered.blocked_spaces += 1,
+            FilterReason::BlockedDao => filtered.blocked_daos += 1,
+            FilterReason::NotAllowed => filtered.not_allowed += 1,
+        }
+    }
+
+    /// Snapshot of the edits skipped by the prefetch filter.
+    pub fn filtered(&self) -> FilterMetrics {
+        *self.filtered.lock().unwrap()
+    }
+
+    /// Snapshot of every gateway's fetch metrics.
+    pub fn gateways(&self) -> Vec<GatewayHealth> {
+        self.fetch.health()
+    }
+
+    /// Log all metrics if the report interval has elapsed since the last
+    /// report. Returns whether they were logged.
+    pub fn report_if_due(&self) -> bool {
+        {
+            let mut last_report = self.last_report.lock().unwrap();
+            if last_report.elapsed() < self.report_interval {
+                return false;
+            }
+            *last_report = Instant::now();
+        }
+
+        self.report();
+        true
+    }
+
+    /// Log one line per gateway, one for the content lag, one for the cache
+    /// size and one for skipped blocks and filtered edits.
+    pub fn report(&self) {
+        for health in self.gateways() {
+            let errors = |class| health.errors.get(&class).copied().unwrap_or(0);
+
+            tracing::info!(
+                gateway = %health.gateway,
+                success = health.successes,
+                not_found = errors(ErrorClass::NotFound),
+                timeout = errors(ErrorClass::Timeout),
+                decode = errors(ErrorClass::Decode),
+                network = errors(ErrorClass::Network),
+                success_rate = health.success_rate(),
+                "IPFS gateway metrics"
+            );
+        }
+
+        let lag = self.content_lag();
+        tracing::info!(
+            cached = lag.count,
+            mean_secs = lag.mean_secs(),
+            p50_secs = ?lag.quantile_upper_bound_secs(0.5),
+            p95_secs = ?lag.quantile_upper_bound_secs(0.95),
+            max_secs = lag.max_secs,
+            "IPFS content lag"
+        );
+
+        let storage = self.storage();
+        tracing::info!(
+            items = storage.stats.map(|stats| stats.items),
+            bytes = storage.stats.map(|stats| stats.bytes),
+            expired = storage.expired,
+            evicted = storage.evicted,
+            "IPFS cache size"
+        );
+
+        let filtered = self.filtered();
+        tracing::info!(
+            skipped_blocks = self.skipped_blocks(),
+            filtered_edits = filtered.total(),
+            blocked_spaces = filtered.blocked_spaces,
+            blocked_daos = filtered.blocked_daos,
+            not_allowed = filtered.not_allowed,
+            "IPFS cache stream"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_lag_histogram_buckets() {
+        let metrics = CacheMetrics::default();
+
+        metrics.record_lag(5);
+        metrics.record_lag(45);
+        metrics.record_lag(120);
+        metrics.record_lag(30 * 24 * 3_600);
+
+        let lag = metrics.content_lag();
+        assert_eq!(lag.count, 4);
+        assert_eq!(lag.buckets[0], 1);
+        assert_eq!(lag.buckets[1], 1);
+        assert_eq!(lag.buckets[2], 1);
+        assert_eq!(lag.buckets[CONTENT_LAG_BUCKETS_SECS.len()], 1);
+        assert_eq!(lag.max_secs, 30 * 24 * 3_600);
+
+        assert_eq!(lag.quantile_upper_bound_secs(0.5), Some(60));
+        assert_eq!(lag.quantile_upper_bound_secs(1.0), None);
+    }
+
+    #[test]
+    fn filtered_edits_are_counted_per_reason() {
+        let metrics = CacheMetrics::default();
+
+        metrics.record_filtered(FilterReason::BlockedDao);
+        metrics.record_filtered(FilterReason::BlockedDao);
+        metrics.record_filtered(FilterReason::NotAllowed);
+
+        let filtered = metrics.filtered();
+        assert_eq!(filtered.blocked_spaces, 0);
+        assert_eq!(filtered.blocked_daos, 2);
+        assert_eq!(filtered.not_allowed, 1);
+        assert_eq!(filtered.total(), 3);
+    }
+
+    #[test]
+    fn record_cached_in_the_future_counts_as_zero_lag() {
+        let metrics = CacheMetrics::default();
+
+        metrics.record_cached(u64::MAX);
+
+        let lag = metrics.content_lag();
+        assert_eq!(lag.count, 1);
+        assert_eq!(lag.sum_secs, 0);
+    }
+}
@@ -0,0 +1,1500 @@
+//! Fetching and caching the IPFS content of decoded blocks.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use ipfs::{IpfsFetcher, IpfsSource, ThrottleConfig, ThrottledFetcher};
+use stream::pb::sf::substreams::rpc::v2::BlockScopedData;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task;
+
+use crate::cache::{Cache, CacheItem, JournaledBlock};
+use crate::compaction::{CompactionConfig, CompactionWorker};
+use crate::decoder::{EventDecoder, PrefetchEvent};
+use crate::exporter;
+use crate::filter::PrefetchFilter;
+use crate::metrics::CacheMetrics;
+use crate::retry::{RetryConfig, RetryWorker};
+use crate::status::{PendingBlockStatus, PrefetchStatus};
+use crate::PrefetchError;
+
+/// Tracks pending fetches per block for cursor management.
+///
+/// Fetched items are buffered until every fetch of their block is done, so
+/// each block is written to the cache in one call. A written block stays
+/// tracked until every earlier block is written too, and the cursor then
+/// advances to the last block of that run. This ensures correctness while
+/// maintaining parallelism across blocks.
+///
+/// Blocks resumed from the journal after a restart are fetched before the
+/// stream delivers them again, but the cursor doesn't move past them until it
+/// does, so it never gets ahead of the stream.
+///
+/// A reorg drops the blocks after the last valid one, see
+/// [`PendingFetches::undo`]. Fetches still running for them are told apart
+/// from the fetches of the canonical blocks replacing them by the epoch
+/// they were registered in.
+#[derive(Default)]
+struct PendingFetches {
+    /// Map of block number -> pending block
+    blocks: BTreeMap<u64, PendingBlock>,
+    /// Blocks written ahead of the cursor before a restart, which are skipped
+    /// when the stream delivers them again.
+    fetched: BTreeSet<u64>,
+    /// Last block delivered by the stream.
+    head: Option<u64>,
+    /// Last block the cursor advanced to.
+    cursor_block: Option<u64>,
+    /// Number of reorgs undone so far.
+    epoch: u64,
+}
+
+/// A block whose cursor isn't persisted yet.
+struct PendingBlock {
+    cursor: String,
+    /// Fetches still running.
+    remaining: usize,
+    /// Items fetched so far.
+    items: Vec<CacheItem>,
+    /// Whether the block's items are written to the cache.
+    written: bool,
+    /// Whether the stream delivered the block since the last restart.
+    delivered: bool,
+    /// Epoch the block was registered in.
+    epoch: u64,
+}
+
+impl PendingFetches {
+    /// Register pending fetches for a block.
+    fn add_block(&mut self, block: u64, cursor: String, count: usize) {
+        if count > 0 {
+            self.blocks.insert(
+                block,
+                PendingBlock {
+                    cursor,
+                    remaining: count,
+                    items: Vec::with_capacity(count),
+                    written: false,
+                    delivered: true,
+                    epoch: self.epoch,
+                },
+            );
+        }
+    }
+
+    /// Register a block journaled before a restart, whose fetches are resumed
+    /// ahead of the stream. Returns whether the block was registered, i.e. it
+    /// has fetches and isn't already tracked.
+    fn add_resumed(&mut self, block: u64, cursor: String, count: usize) -> bool {
+        if count == 0 || self.blocks.contains_key(&block) {
+            return false;
+        }
+
+        self.blocks.insert(
+            block,
+            PendingBlock {
+                cursor,
+                remaining: count,
+                items: Vec::with_capacity(count),
+                written: false,
+                delivered: false,
+                epoch: self.epoch,
+            },
+        );
+        true
+    }
+
+    /// Record that the stream delivered a resumed block again, if it is one.
+    /// See [`PendingFetches::written`] for the return value.
+    fn redeliver(&mut self, block: u64) -> Option<Option<(u64, String)>> {
+        let pending = self
+            .blocks
+            .get_mut(&block)
+            .filter(|pending| !pending.delivered)?;
+        pending.delivered = true;
+
+        Some(self.advance())
+    }
+
+    /// Register a block that was already written before a restart, if it is
+    /// one. See [`PendingFetches::written`] for the return value.
+    fn add_fetched(&mut self, block: u64, cursor: String) -> Option<Option<(u64, String)>> {
+        if !self.fetched.remove(&block) {
+            return None;
+        }
+
+        self.blocks.insert(
+            block,
+            PendingBlock {
+                cursor,
+                remaining: 0,
+                items: Vec::new(),
+                written: true,
+                delivered: true,
+                epoch: self.epoch,
+            },
+        );
+        Some(self.advance())
+    }
+
+    /// Mark one fetch as complete for a block registered in `epoch`,
+    /// buffering its item.
+    ///
+    /// Returns the block's items once all its fetches are done. The block
+    /// stays pending until [`PendingFetches::written`] is called.
+    fn complete_one(&mut self, block: u64, epoch: u64, item: CacheItem) -> Option<Vec<CacheItem>> {
+        let pending = self
+            .blocks
+            .get_mut(&block)
+            .filter(|pending| pending.epoch == epoch)?;
+        if pending.remaining == 0 {
+            return None;
+        }
+
+        pending.items.push(item);
+        pending.remaining -= 1;
+
+        (pending.remaining == 0).then(|| std::mem::take(&mut pending.items))
+    }
+
+    /// Mark a completed block as written to the cache.
+    ///
+    /// Returns `Some((block, cursor))` of the last block in the run of
+    /// written blocks starting at the minimum (safe to persist), or `None`
+    /// if an earlier block is still pending.
+    fn written(&mut self, block: u64) -> Option<(u64, String)> {
+        self.blocks.get_mut(&block)?.written = true;
+        self.advance()
+    }
+
+    /// Remove the written and delivered blocks at the front, returning the
+    /// last one.
+    fn advance(&mut self) -> Option<(u64, String)> {
+        let mut last = None;
+        while let Some(entry) = self.blocks.first_entry() {
+            if !entry.get().written || !entry.get().delivered {
+                break;
+            }
+            let block = *entry.key();
+            last = Some((block, entry.remove().cursor));
+        }
+
+        if let Some((block, _)) = &last {
+            self.fetched.retain(|fetched| fetched > block);
+            self.cursor_block = Some(*block);
+        }
+        last
+    }
+
+    /// Whether `block` is still tracked as registered in `epoch`, i.e. it
+    /// wasn't undone by a reorg since.
+    fn is_current(&self, block: u64, epoch: u64) -> bool {
+        self.blocks
+            .get(&block)
+            .is_some_and(|pending| pending.epoch == epoch)
+    }
+
+    /// Drop every block after `last_valid`, undone by a reorg, and start a
+    /// new epoch so their running fetches are ignored.
+    ///
+    /// Returns the number of blocks dropped, and whether the cursor had
+    /// advanced past `last_valid` and must be moved back to it.
+    fn undo(&mut self, last_valid: u64) -> (usize, bool) {
+        let count = self.blocks.len();
+        self.blocks.retain(|block, _| *block <= last_valid);
+        self.fetched.retain(|block| *block <= last_valid);
+        self.head = self.head.map(|head| head.min(last_valid));
+        self.epoch += 1;
+
+        let rewind = self.cursor_block.is_some_and(|block| block > last_valid);
+        if rewind {
+            self.cursor_block = Some(last_valid);
+        }
+        (count - self.blocks.len(), rewind)
+    }
+
+    /// Record a block delivered by the stream.
+    fn observe(&mut self, block: u64) {
+        self.head = Some(self.head.map_or(block, |head| head.max(block)));
+    }
+
+    /// Snapshot of the pending blocks, oldest first, and of the fetch
+    /// permits in use.
+    fn status(&self, metrics: &CacheMetrics, permits: &FetchPermits) -> PrefetchStatus {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|(block, pending)| PendingBlockStatus {
+                block: *block,
+                remaining: pending.remaining,
+                written: pending.written,
+            })
+            .collect();
+
+        PrefetchStatus::new(blocks, self.head, self.cursor_block, metrics)
+            .with_permits(permits.max, permits.semaphore.available_permits())
+    }
+}
+
+/// Bounds how many fetch tasks run at once.
+#[derive(Clone)]
+struct FetchPermits {
+    semaphore: Arc<Semaphore>,
+    max: usize,
+}
+
+impl FetchPermits {
+    fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max)),
+            max,
+        }
+    }
+}
+
+/// Reads the progress of a [`Prefetcher`] from another task, e.g. the
+/// sidecar's `/status` route.
+#[derive(Clone)]
+pub struct StatusHandle {
+    pending: Arc<Mutex<PendingFetches>>,
+    metrics: Arc<CacheMetrics>,
+    permits: FetchPermits,
+}
+
+impl StatusHandle {
+    /// Snapshot of the prefetcher's progress.
+    pub async fn status(&self) -> PrefetchStatus {
+        self.pending
+            .lock()
+            .await
+            .status(&self.metrics, &self.permits)
+    }
+
+    /// The prefetcher's progress and metrics in the Prometheus text format,
+    /// see [`exporter::render`].
+    pub async fn prometheus(&self) -> String {
+        exporter::render(&self.status().await, &self.metrics)
+    }
+}
+
+/// Pre-fetches the IPFS content of the edits decoded from each block.
+///
+/// The stream side is left to the service: it feeds blocks from its
+/// substream to [`Prefetcher::process_block`] and resumes from
+/// [`Prefetcher::load_cursor`]. Cursors are persisted by the prefetcher
+/// itself, once every block up to them is cached, so the service's own
+/// cursor persistence should be a no-op.
+pub struct Prefetcher<D> {
+    decoder: D,
+    filter: PrefetchFilter,
+    /// ID the cursor is persisted under in the cache.
+    cursor_id: Arc<str>,
+    cache: Arc<Cache>,
+    ipfs: Arc<dyn IpfsFetcher>,
+    permits: FetchPermits,
+    pending: Arc<Mutex<PendingFetches>>,
+    /// Last valid block and cursor of an undo signal not yet applied.
+    undo: std::sync::Mutex<Option<(u64, String)>>,
+    metrics: Arc<CacheMetrics>,
+}
+
+impl<D: EventDecoder> Prefetcher<D> {
+    /// Create a prefetcher decoding blocks with `decoder` and persisting its
+    /// cursor under `cursor_id`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Development: use mock IPFS data
+    /// let mut edits = HashMap::new();
+    /// edits.insert("QmTestCid".to_string(), test_edit);
+    /// let prefetcher = Prefetcher::new(decoder, "ipfs_indexer", cache, IpfsSource::mock(edits));
+    ///
+    /// // Production: use live IPFS gateway
+    /// let prefetcher = Prefetcher::new(
+    ///     decoder,
+    ///     "ipfs_indexer",
+    ///     cache,
+    ///     IpfsSource::live("https://ipfs.io/ipfs/"),
+    /// );
+    /// ```
+    pub fn new(
+        decoder: D,
+        cursor_id: impl Into<Arc<str>>,
+        cache: Cache,
+        ipfs_source: IpfsSource,
+    ) -> Self {
+        Self::with_throttle(
+            decoder,
+            cursor_id,
+            cache,
+            ipfs_source,
+            ThrottleConfig::default(),
+        )
+    }
+
+    /// Create a prefetcher that throttles IPFS requests.
+    ///
+    /// The same `max_in_flight` bound is used to limit how many fetch tasks
+    /// the prefetcher spawns, so a slow gateway applies backpressure to the
+    /// stream. Every fetch attempt is recorded in the prefetcher's
+    /// [`CacheMetrics`].
+    pub fn with_throttle(
+        decoder: D,
+        cursor_id: impl Into<Arc<str>>,
+        cache: Cache,
+        ipfs_source: IpfsSource,
+        config: ThrottleConfig,
+    ) -> Self {
+        let metrics = Arc::new(CacheMetrics::default());
+        let metered = ipfs_source.into_metered_fetcher(metrics.fetch());
+
+        Self {
+            decoder,
+            filter: PrefetchFilter::default(),
+            cursor_id: cursor_id.into(),
+            cache: Arc::new(cache),
+            ipfs: Arc::new(ThrottledFetcher::new(Box::new(metered), config)),
+            permits: FetchPermits::new(config.max_in_flight),
+            pending: Arc::new(Mutex::new(PendingFetches::default())),
+            undo: std::sync::Mutex::new(None),
+            metrics,
+        }
+    }
+
+    /// Skip the edits denied by `filter` before their fetches are scheduled.
+    /// Skipped edits are counted in the prefetcher's [`CacheMetrics`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let prefetcher = Prefetcher::new(decoder, "ipfs_indexer", cache, ipfs_source)
+    ///     .with_filter(PrefetchFilter::from_env()?);
+    /// ```
+    pub fn with_filter(mut self, filter: PrefetchFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Metrics for this prefetcher's fetches and content lag.
+    pub fn metrics(&self) -> Arc<CacheMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Snapshot of this prefetcher's progress, see [`PrefetchStatus`].
+    pub async fn status(&self) -> PrefetchStatus {
+        self.pending
+            .lock()
+            .await
+            .status(&self.metrics, &self.permits)
+    }
+
+    /// A handle reading this prefetcher's progress, for serving it in the
+    /// same process.
+    pub fn status_handle(&self) -> StatusHandle {
+        StatusHandle {
+            pending: self.pending.clone(),
+            metrics: self.metrics.clone(),
+            permits: self.permits.clone(),
+        }
+    }
+
+    /// The cache this prefetcher writes to, for serving reads in the same
+    /// process.
+    pub fn cache(&self) -> Arc<Cache> {
+        self.cache.clone()
+    }
+
+    /// Create a [`RetryWorker`] that re-fetches this prefetcher's errored
+    /// items through the same cache and IPFS fetcher.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// tokio::spawn(prefetcher.retry_worker(RetryConfig::from_env()).run());
+    /// ```
+    pub fn retry_worker(&self, config: RetryConfig) -> RetryWorker {
+        RetryWorker::new(self.cache.clone(), self.ipfs.clone(), config)
+    }
+
+    /// Create a [`CompactionWorker`] that evicts items from this
+    /// prefetcher's cache and records its size in the prefetcher's metrics.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// tokio::spawn(prefetcher.compaction_worker(CompactionConfig::from_env()).run());
+    /// ```
+    pub fn compaction_worker(&self, config: CompactionConfig) -> CompactionWorker {
+        CompactionWorker::new(self.cache.clone(), self.metrics.clone(), config)
+    }
+
+    /// Load the cursor to resume the stream from.
+    ///
+    /// Also loads the blocks cached ahead of the cursor before a restart, so
+    /// they're skipped instead of fetched again when the stream delivers
+    /// them, and resumes the fetches of the blocks journaled but not yet
+    /// cached. Call it before the first [`Prefetcher::process_block`].
+    pub async fn load_cursor(&self) -> Result<Option<String>, PrefetchError> {
+        let fetched = self.cache.fetched_blocks(&self.cursor_id).await?;
+        if !fetched.is_empty() {
+            tracing::info!(
+                blocks = fetched.len(),
+                "Skipping blocks cached ahead of the cursor"
+            );
+        }
+
+        let mut resumed = self.cache.journaled_blocks(&self.cursor_id).await?;
+        let epoch = {
+            let mut pending = self.pending.lock().await;
+            // Blocks cached behind a pending one are skipped instead
+            resumed.retain(|journaled| {
+                !fetched.contains(&journaled.block)
+                    && pending.add_resumed(
+                        journaled.block,
+                        journaled.cursor.clone(),
+                        journaled.events.len(),
+                    )
+            });
+            pending.fetched = fetched;
+            pending.epoch
+        };
+
+        if !resumed.is_empty() {
+            tracing::info!(
+                blocks = resumed.len(),
+                fetches = resumed
+                    .iter()
+                    .map(|block| block.events.len())
+                    .sum::<usize>(),
+                "Resuming fetches journaled before restart"
+            );
+        }
+        for journaled in resumed {
+            self.spawn_fetches(
+                journaled.block,
+                epoch,
+                journaled.block_seconds,
+                journaled.events,
+            )
+            .await;
+        }
+
+        Ok(self.cache.load_cursor(&self.cursor_id).await?)
+    }
+
+    /// Record a block the stream delivered again and that was skipped. The
+    /// stream already logs the skipped block, so it's only counted here.
+    pub fn record_skipped_block(&self) {
+        self.metrics.record_skipped_block();
+    }
+
+    /// Record an undo signal: the blocks after `last_valid_block` were
+    /// reorged away and will be replaced by the canonical ones.
+    ///
+    /// Sinks call it from their synchronous undo handler, and it is applied
+    /// before the next [`Prefetcher::process_block`]: pending fetches of the
+    /// undone blocks are dropped, their cached items are marked orphaned (see
+    /// [`Cache::invalidate_from`]) and the cursor is moved back if it had
+    /// advanced past `last_valid_block`.
+    pub fn record_undo(&self, last_valid_block: u64, last_valid_cursor: String) {
+        tracing::warn!(block = last_valid_block, "Reorg, undoing later blocks");
+
+        let mut undo = self.undo.lock().unwrap();
+        // Undoing to the earliest block covers every undo signal since
+        if undo
+            .as_ref()
+            .is_none_or(|(block, _)| last_valid_block <= *block)
+        {
+            *undo = Some((last_valid_block, last_valid_cursor));
+        }
+    }
+
+    /// Apply the undo signal recorded by [`Prefetcher::record_undo`], if any.
+    async fn apply_undo(&self) -> Result<(), PrefetchError> {
+        let Some((last_valid, cursor)) = self.undo.lock().unwrap().take() else {
+            return Ok(());
+        };
+
+        let (dropped, rewind) = self.pending.lock().await.undo(last_valid);
+        let orphaned = self
+            .cache
+            .invalidate_from(&self.cursor_id, last_valid + 1)
+            .await?;
+        if rewind {
+            self.cache
+                .persist_cursor(&self.cursor_id, &cursor, last_valid)
+                .await?;
+        }
+
+        tracing::info!(
+            block = last_valid,
+            pending_blocks = dropped,
+            orphaned,
+            "Undid blocks after reorg"
+        );
+        Ok(())
+    }
+
+    /// Decode the edits of a block and spawn a fetch for each.
+    ///
+    /// Returns once every fetch is spawned; the block's items are written and
+    /// its cursor persisted when the last of them completes.
+    pub async fn process_block(&self, data: &BlockScopedData) -> Result<(), PrefetchError> {
+        self.apply_undo().await?;
+
+        let mut events = self.decoder.decode(data)?;
+
+        // Get block metadata
+        let block_number = data.clock.as_ref().map(|c| c.number).unwrap_or(0);
+
+        // Drop denied edits before anything is scheduled for them
+        events.retain(|event| match self.filter.check(event) {
+            Some(reason) => {
+                tracing::debug!(
+                    block = block_number,
+                    uri = %event.uri,
+                    space_id = %event.space_id,
+                    ?reason,
+                    "Skipping filtered edit"
+                );
+                self.metrics.record_filtered(reason);
+                false
+            }
+            None => true,
+        });
+        let cursor = data.cursor.clone();
+
+        let block_seconds = data
+            .clock
+            .as_ref()
+            .and_then(|c| c.timestamp.as_ref())
+            .map(|t| t.seconds);
+
+        let edit_count = events.len();
+        let mut pending = self.pending.lock().await;
+        pending.observe(block_number);
+
+        if edit_count > 0 {
+            // Already cached, or resumed from the journal, before a restart.
+            // Only the cursor is left to advance.
+            let skipped = pending
+                .redeliver(block_number)
+                .or_else(|| pending.add_fetched(block_number, cursor.clone()));
+            if let Some(advanced) = skipped {
+                drop(pending);
+                tracing::debug!(
+                    block = block_number,
+                    "Block already cached or resumed, skipping"
+                );
+                if advanced.is_some() {
+                    persist_cursor(&self.cache, &self.cursor_id, block_number, advanced).await;
+                }
+                return Ok(());
+            }
+
+            tracing::info!(block = block_number, edits = edit_count, "Processing edits");
+
+            // Register all pending fetches for this block upfront
+            pending.add_block(block_number, cursor.clone(), edit_count);
+        }
+        let epoch = pending.epoch;
+        drop(pending);
+
+        if edit_count > 0 {
+            // Journal the fetches before any of them starts, so a restart
+            // resumes them if the process dies before they're written
+            let journaled = JournaledBlock {
+                block: block_number,
+                cursor,
+                block_seconds,
+                events: events.clone(),
+            };
+            if let Err(e) = self.cache.journal_block(&self.cursor_id, &journaled).await {
+                tracing::error!(block = block_number, error = %e, "Failed to journal block");
+            }
+        }
+
+        self.spawn_fetches(block_number, epoch, block_seconds, events)
+            .await;
+
+        if self.metrics.report_if_due() {
+            self.status().await.log();
+        }
+
+        Ok(())
+    }
+
+    /// Spawn the fetches of the events of a block registered in `epoch`.
+    ///
+    /// The block's URIs go out in a single [`IpfsFetcher::get_many`] call, so
+    /// a CID published more than once in the block is only fetched once. The
+    /// task holds a permit per event, up to every permit, so blocks with many
+    /// edits still apply backpressure to the stream.
+    async fn spawn_fetches(
+        &self,
+        block_number: u64,
+        epoch: u64,
+        block_seconds: Option<i64>,
+        events: Vec<PrefetchEvent>,
+    ) {
+        if events.is_empty() {
+            return;
+        }
+
+        let permit = self
+            .permits
+            .semaphore
+            .clone()
+            .acquire_many_owned(events.len().min(self.permits.max) as u32)
+            .await
+            .unwrap();
+        let cache = self.cache.clone();
+        let ipfs = self.ipfs.clone();
+        let pending = self.pending.clone();
+        let metrics = self.metrics.clone();
+        let cursor_id = self.cursor_id.clone();
+        let block_num = block_number;
+
+        task::spawn(async move {
+            let fetched = fetch_events(events, &ipfs, block_seconds, block_num).await;
+
+            // Buffer the items until every fetch of their block is done
+            let items = {
+                let mut pending = pending.lock().await;
+                fetched
+                    .into_iter()
+                    .filter_map(|item| pending.complete_one(block_num, epoch, item))
+                    .last()
+            };
+
+            if let Some(items) = items {
+                write_block(
+                    &cache,
+                    &pending,
+                    &metrics,
+                    &cursor_id,
+                    block_num,
+                    epoch,
+                    block_seconds,
+                    items,
+                )
+                .await;
+            }
+
+            drop(permit);
+        });
+    }
+}
+
+/// Write the items of a fully fetched block in one call, then persist the
+/// cursor if no earlier block is still pending.
+///
+/// Blocks undone by a reorg since their fetches started aren't written. A
+/// reorg undoing the block while it's being written leaves its items
+/// unmarked, until the canonical chain publishes them again or they're
+/// evicted.
+#[allow(clippy::too_many_arguments)]
+async fn write_block(
+    cache: &Cache,
+    pending: &Mutex<PendingFetches>,
+    metrics: &CacheMetrics,
+    cursor_id: &str,
+    block_number: u64,
+    epoch: u64,
+    block_seconds: Option<i64>,
+    items: Vec<CacheItem>,
+) {
+    if !pending.lock().await.is_current(block_number, epoch) {
+        tracing::debug!(block = block_number, "Block undone, not caching its items");
+        return;
+    }
+
+    match cache.put_many(&items).await {
+        Ok(()) => {
+            // Track how far content availability lags the edits being published
+            if let Some(seconds) = block_seconds {
+                for _ in items.iter().filter(|item| !item.is_errored) {
+                    metrics.record_cached(seconds.max(0) as u64);
+                }
+            }
+        }
+        Err(e) => {
+            tracing::error!(block = block_number, error = %e, "Failed to cache block items")
+        }
+    }
+
+    let cursor_to_persist = {
+        let mut pending = pending.lock().await;
+        if !pending.is_current(block_number, epoch) {
+            return;
+        }
+        pending.written(block_number)
+    };
+    persist_cursor(cache, cursor_id, block_number, cursor_to_persist).await;
+}
+
+/// Persist the cursor a written block advanced to, or record the block as
+/// cached if an earlier block is still pending so a restart skips it.
+async fn persist_cursor(
+    cache: &Cache,
+    cursor_id: &str,
+    block_number: u64,
+    cursor_to_persist: Option<(u64, String)>,
+) {
+    let Some((persist_block, persist_cursor)) = cursor_to_persist else {
+        if let Err(e) = cache.mark_block_fetched(cursor_id, block_number).await {
+            tracing::error!(block = block_number, error = %e, "Failed to record cached block");
+        }
+        return;
+    };
+
+    tracing::debug!(
+        block = persist_block,
+        "Blocks fully cached, persisting cursor"
+    );
+
+    // Forget the journal first: if the process dies before the cursor is
+    // persisted, the blocks are delivered again and processed as new
+    if let Err(e) = cache
+        .forget_journaled_blocks(cursor_id, persist_block)
+        .await
+    {
+        tracing::error!(error = %e, "Failed to forget journaled blocks");
+    }
+
+    if let Err(e) = cache
+        .persist_cursor(cursor_id, &persist_cursor, persist_block)
+        .await
+    {
+        tracing::error!(error = %e, "Failed to persist cursor");
+        return;
+    }
+
+    // Blocks up to the cursor are no longer delivered after a restart
+    if let Err(e) = cache.forget_fetched_blocks(cursor_id, persist_block).await {
+        tracing::error!(error = %e, "Failed to forget cached blocks");
+    }
+}
+
+/// Fetch the IPFS content of a block's edit events into cache items, in the
+/// order of `events`.
+async fn fetch_events(
+    events: Vec<PrefetchEvent>,
+    ipfs: &Arc<dyn IpfsFetcher>,
+    block_seconds: Option<i64>,
+    block_number: u64,
+) -> Vec<CacheItem> {
+    let block_timestamp = block_seconds.map(|s| s.to_string()).unwrap_or_default();
+
+    for event in &events {
+        tracing::debug!(
+            uri = %event.uri,
+            space_id = %event.space_id,
+            block = block_number,
+            "Fetching IPFS content"
+        );
+    }
+
+    // Fetch and decode the IPFS content of the whole block at once
+    let uris: Vec<String> = events.iter().map(|event| event.uri.clone()).collect();
+    let results = ipfs.get_many(&uris).await;
+
+    events
+        .into_iter()
+        .zip(results)
+        .map(|(PrefetchEvent { uri, space_id, .. }, result)| match result {
+            Ok(decoded_edit) => {
+                tracing::info!(
+                    uri = %uri,
+                    block = block_number,
+                    "Fetched IPFS content"
+                );
+                CacheItem {
+                    uri,
+                    json: Some(decoded_edit),
+                    block: block_timestamp.clone(),
+                    space_id,
+                    is_errored: false,
+                    block_number: Some(block_number),
+                    orphaned: false,
+                }
+            }
+            Err(error) => {
+                tracing::warn!(
+                    uri = %uri,
+                    block = block_number,
+                    error = %error,
+                    "Failed to fetch/decode IPFS content"
+                );
+                // Still cache an errored entry so consumers know the event exists
+                // but the content is invalid
+                CacheItem {
+                    uri,
+                    json: None,
+                    block: block_timestamp.clone(),
+                    space_id,
+                    is_errored: true,
+                    block_number: Some(block_number),
+                    orphaned: false,
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(uri: &str) -> CacheItem {
+        CacheItem {
+            uri: uri.to_string(),
+            json: None,
+            block: String::new(),
+            space_id: String::new(),
+            is_errored: true,
+            block_number: None,
+            orphaned: false,
+        }
+    }
+
+    /// Complete one fetch of `block`, writing the block if it's done.
+    fn complete(pending: &mut PendingFetches, block: u64) -> Option<(u64, String)> {
+        pending.complete_one(block, 0, item("ipfs://QmTest"))?;
+        pending.written(block)
+    }
+
+    #[test]
+    fn pending_fetches_buffer_items_until_block_completes() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 2);
+
+        assert_eq!(pending.complete_one(100, 0, item("ipfs://QmA")), None);
+        assert_eq!(
+            pending.complete_one(100, 0, item("ipfs://QmB")),
+            Some(vec![item("ipfs://QmA"), item("ipfs://QmB")])
+        );
+
+        // The cursor is only released once the items are written
+        assert!(pending.blocks.contains_key(&100));
+        assert_eq!(pending.written(100), Some((100, "cursor_100".to_string())));
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_single_block_single_edit() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 1);
+
+        // Completing the only fetch should return the cursor
+        let result = complete(&mut pending, 100);
+        assert_eq!(result, Some((100, "cursor_100".to_string())));
+
+        // No more pending
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_single_block_multiple_edits() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 3);
+
+        // First two completions should not persist
+        assert_eq!(complete(&mut pending, 100), None);
+        assert_eq!(complete(&mut pending, 100), None);
+
+        // Third completion should persist
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((100, "cursor_100".to_string()))
+        );
+
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_multiple_blocks_complete_in_order() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 2);
+        pending.add_block(101, "cursor_101".to_string(), 1);
+
+        // Complete block 100 first
+        assert_eq!(complete(&mut pending, 100), None); // 1 remaining
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((100, "cursor_100".to_string()))
+        );
+
+        // Now complete block 101
+        assert_eq!(
+            complete(&mut pending, 101),
+            Some((101, "cursor_101".to_string()))
+        );
+
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_multiple_blocks_complete_out_of_order() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 2);
+        pending.add_block(101, "cursor_101".to_string(), 1);
+
+        // Complete block 101 first - should NOT persist (100 still pending)
+        assert_eq!(complete(&mut pending, 101), None);
+
+        // Block 101 is kept until block 100 is written
+        assert!(pending.blocks[&101].written);
+
+        // Complete block 100 - the cursor advances past block 101 too
+        assert_eq!(complete(&mut pending, 100), None); // 1 remaining
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((101, "cursor_101".to_string()))
+        );
+
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_three_blocks_middle_completes_first() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 1);
+        pending.add_block(101, "cursor_101".to_string(), 1);
+        pending.add_block(102, "cursor_102".to_string(), 1);
+
+        // Complete middle block - should NOT persist
+        assert_eq!(complete(&mut pending, 101), None);
+
+        // Complete last block - should NOT persist (100 still pending)
+        assert_eq!(complete(&mut pending, 102), None);
+
+        // Complete first block - should persist the last written block
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((102, "cursor_102".to_string()))
+        );
+
+        // 101 and 102 were removed with it, nothing left
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_empty_block_not_added() {
+        let mut pending = PendingFetches::default();
+
+        // Adding a block with 0 edits should not add it
+        pending.add_block(100, "cursor_100".to_string(), 0);
+
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_complete_unknown_block() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 1);
+
+        // Completing an unknown block should return None
+        assert_eq!(complete(&mut pending, 999), None);
+
+        // Original block still pending
+        assert_eq!(pending.blocks.len(), 1);
+    }
+
+    #[test]
+    fn pending_fetches_later_blocks_complete_then_first() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 1);
+        pending.add_block(101, "cursor_101".to_string(), 1);
+        pending.add_block(102, "cursor_102".to_string(), 1);
+
+        // Complete 102 first - no persist (100 still pending)
+        assert_eq!(complete(&mut pending, 102), None);
+        assert!(pending.blocks[&102].written);
+
+        // Complete 101 - no persist (100 still pending)
+        assert_eq!(complete(&mut pending, 101), None);
+        assert!(pending.blocks[&101].written);
+
+        // Complete 100 - persist cursor 102 (the gap is closed)
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((102, "cursor_102".to_string()))
+        );
+
+        // All blocks removed
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_gap_keeps_later_blocks_pending() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 1);
+        pending.add_block(101, "cursor_101".to_string(), 1);
+        pending.add_block(102, "cursor_102".to_string(), 1);
+
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((100, "cursor_100".to_string()))
+        );
+        assert_eq!(complete(&mut pending, 102), None);
+
+        // The cursor stops before block 101 until it is written
+        assert_eq!(
+            complete(&mut pending, 101),
+            Some((102, "cursor_102".to_string()))
+        );
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_skip_blocks_fetched_before_restart() {
+        let mut pending = PendingFetches {
+            fetched: BTreeSet::from([101, 102]),
+            ..Default::default()
+        };
+
+        pending.add_block(100, "cursor_100".to_string(), 1);
+
+        // Unknown blocks are fetched as usual
+        assert_eq!(pending.add_fetched(103, "cursor_103".to_string()), None);
+
+        // Fetched blocks wait for block 100
+        assert_eq!(
+            pending.add_fetched(101, "cursor_101".to_string()),
+            Some(None)
+        );
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((101, "cursor_101".to_string()))
+        );
+
+        // Once nothing is pending before them, they advance the cursor directly
+        assert_eq!(
+            pending.add_fetched(102, "cursor_102".to_string()),
+            Some(Some((102, "cursor_102".to_string())))
+        );
+        assert!(pending.blocks.is_empty());
+        assert!(pending.fetched.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_resumed_blocks_wait_for_redelivery() {
+        let mut pending = PendingFetches::default();
+
+        assert!(pending.add_resumed(100, "cursor_100".to_string(), 1));
+        assert!(pending.add_resumed(101, "cursor_101".to_string(), 1));
+
+        // Already tracked or without fetches
+        assert!(!pending.add_resumed(100, "cursor_100".to_string(), 1));
+        assert!(!pending.add_resumed(102, "cursor_102".to_string(), 0));
+
+        // Written ahead of the stream, the cursor stays put
+        assert_eq!(complete(&mut pending, 100), None);
+        assert_eq!(pending.redeliver(101), Some(None));
+
+        // Blocks the stream delivers for the first time aren't resumed
+        assert_eq!(pending.redeliver(103), None);
+
+        assert_eq!(
+            pending.redeliver(100),
+            Some(Some((100, "cursor_100".to_string())))
+        );
+        assert_eq!(
+            complete(&mut pending, 101),
+            Some((101, "cursor_101".to_string()))
+        );
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_interleaved_completions() {
+        let mut pending = PendingFetches::default();
+
+        pending.add_block(100, "cursor_100".to_string(), 3);
+        pending.add_block(101, "cursor_101".to_string(), 2);
+
+        // Interleaved completions
+        assert_eq!(complete(&mut pending, 100), None); // 100: 2 remaining
+        assert_eq!(complete(&mut pending, 101), None); // 101: 1 remaining
+        assert_eq!(complete(&mut pending, 100), None); // 100: 1 remaining
+        assert_eq!(complete(&mut pending, 101), None); // 101: 0 remaining, but 100 still pending
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((101, "cursor_101".to_string()))
+        ); // 100: 0 remaining, 101 already written
+
+        assert!(pending.blocks.is_empty());
+    }
+
+    #[test]
+    fn pending_fetches_undo_drops_later_blocks() {
+        let mut pending = PendingFetches {
+            fetched: BTreeSet::from([103]),
+            ..Default::default()
+        };
+
+        pending.add_block(100, "cursor_100".to_string(), 1);
+        pending.add_block(101, "cursor_101".to_string(), 1);
+        pending.add_block(102, "cursor_102".to_string(), 1);
+        assert_eq!(
+            complete(&mut pending, 100),
+            Some((100, "cursor_100".to_string()))
+        );
+
+        // Undoing to a block behind the cursor moves it back
+        assert_eq!(pending.undo(99), (2, true));
+        assert_eq!(pending.cursor_block, Some(99));
+        assert!(pending.blocks.is_empty());
+        assert!(pending.fetched.is_empty());
+
+        // Fetches started before the reorg don't complete the canonical block
+        pending.add_block(101, "cursor_101b".to_string(), 1);
+        assert_eq!(pending.complete_one(101, 0, item("ipfs://QmFork")), None);
+        assert!(!pending.is_current(101, 0));
+        assert_eq!(
+            pending.complete_one(101, 1, item("ipfs://QmCanonical")),
+            Some(vec![item("ipfs://QmCanonical")])
+        );
+        assert_eq!(pending.undo(101), (0, false));
+    }
+
+    struct FixedDecoder(Vec<PrefetchEvent>);
+
+    impl EventDecoder for FixedDecoder {
+        fn decode(&self, _data: &BlockScopedData) -> Result<Vec<PrefetchEvent>, PrefetchError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn event(uri: &str) -> PrefetchEvent {
+        PrefetchEvent {
+            uri: uri.to_string(),
+            space_id: "abc".to_string(),
+            dao_address: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn prefetcher_caches_decoded_events_and_persists_cursor() {
+        use std::collections::HashMap;
+        use std::time::Duration;
+        use stream::pb::sf::substreams::v1::Clock;
+        use wire::pb::grc20::Edit;
+
+        let mut edits = HashMap::new();
+        edits.insert(
+            "QmCached".to_string(),
+            Edit {
+                id: vec![0x01],
+                name: "Cached".to_string(),
+                ops: vec![],
+                authors: vec![],
+                language: None,
+            },
+        );
+        let decoder = FixedDecoder(vec![event("QmCached"), event("QmMissing")]);
+        let prefetcher = Prefetcher::new(decoder, "test", Cache::mock(), IpfsSource::mock(edits));
+
+        let block = BlockScopedData {
+            cursor: "cursor_100".to_string(),
+            clock: Some(Clock {
+                id: "block_100".to_string(),
+                number: 100,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        prefetcher.process_block(&block).await.unwrap();
+
+        let cache = prefetcher.cache();
+        let timeout = Duration::from_secs(5);
+        let cached = cache.wait_for("QmCached", timeout).await.unwrap().unwrap();
+        assert_eq!(cached.json.unwrap().name, "Cached");
+        assert_eq!(cached.space_id, "abc");
+        let missing = cache.wait_for("QmMissing", timeout).await.unwrap().unwrap();
+        assert!(missing.is_errored);
+
+        // The cursor is persisted right after the block's items are written
+        for _ in 0..100 {
+            if prefetcher.load_cursor().await.unwrap().is_some() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(
+            prefetcher.load_cursor().await.unwrap(),
+            Some("cursor_100".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn prefetcher_skips_filtered_edits() {
+        use std::collections::HashMap;
+        use std::time::Duration;
+        use stream::pb::sf::substreams::v1::Clock;
+
+        let spam = PrefetchEvent {
+            dao_address: Some("0xDEAD".to_string()),
+            ..event("QmSpam")
+        };
+        let decoder = FixedDecoder(vec![event("QmKept"), spam]);
+        let prefetcher = Prefetcher::new(
+            decoder,
+            "test",
+            Cache::mock(),
+            IpfsSource::mock(HashMap::new()),
+        )
+        .with_filter(PrefetchFilter::default().block_daos(["0xdead"]));
+
+        let block = BlockScopedData {
+            cursor: "cursor_100".to_string(),
+            clock: Some(Clock {
+                id: "block_100".to_string(),
+                number: 100,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        prefetcher.process_block(&block).await.unwrap();
+
+        // Both edits would be written together, so the spam edit is missing
+        // once the kept one is cached
+        let cache = prefetcher.cache();
+        let kept = cache.wait_for("QmKept", Duration::from_secs(5)).await;
+        assert!(kept.unwrap().is_some());
+        assert_eq!(cache.get_by_uri("QmSpam").await.unwrap(), None);
+        assert_eq!(prefetcher.metrics().filtered().blocked_daos, 1);
+    }
+
+    #[tokio::test]
+    async fn prefetcher_skips_blocks_cached_before_restart() {
+        use std::collections::HashMap;
+        use stream::pb::sf::substreams::v1::Clock;
+
+        let cache = Cache::mock();
+        cache.mark_block_fetched("test", 100).await.unwrap();
+
+        let decoder = FixedDecoder(vec![event("QmMissing")]);
+        let prefetcher = Prefetcher::new(decoder, "test", cache, IpfsSource::mock(HashMap::new()));
+        assert_eq!(prefetcher.load_cursor().await.unwrap(), None);
+
+        let block = BlockScopedData {
+            cursor: "cursor_100".to_string(),
+            clock: Some(Clock {
+                id: "block_100".to_string(),
+                number: 100,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        prefetcher.process_block(&block).await.unwrap();
+
+        // Nothing is fetched again, the cursor moves past the block at once
+        let cache = prefetcher.cache();
+        assert!(cache.get_by_uri("QmMissing").await.unwrap().is_none());
+        assert_eq!(
+            prefetcher.load_cursor().await.unwrap(),
+            Some("cursor_100".to_string())
+        );
+        assert!(cache.fetched_blocks("test").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn prefetcher_resumes_journaled_fetches_after_restart() {
+        use std::collections::HashMap;
+        use std::time::Duration;
+        use stream::pb::sf::substreams::v1::Clock;
+        use wire::pb::grc20::Edit;
+
+        let cache = Cache::mock();
+        cache
+            .journal_block(
+                "test",
+                &JournaledBlock {
+                    block: 100,
+                    cursor: "cursor_100".to_string(),
+                    block_seconds: None,
+                    events: vec![event("QmJournaled")],
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut edits = HashMap::new();
+        edits.insert(
+            "QmJournaled".to_string(),
+            Edit {
+                id: vec![0x01],
+                name: "Journaled".to_string(),
+                ops: vec![],
+                authors: vec![],
+                language: None,
+            },
+        );
+        let decoder = FixedDecoder(vec![event("QmRedelivered")]);
+        let prefetcher = Prefetcher::new(decoder, "test", cache, IpfsSource::mock(edits));
+        assert_eq!(prefetcher.load_cursor().await.unwrap(), None);
+
+        // The journaled fetch runs before the stream delivers its block
+        let cache = prefetcher.cache();
+        let cached = cache
+            .wait_for("QmJournaled", Duration::from_secs(5))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.json.unwrap().name, "Journaled");
+        assert_eq!(cache.load_cursor("test").await.unwrap(), None);
+
+        let block = BlockScopedData {
+            cursor: "cursor_100".to_string(),
+            clock: Some(Clock {
+                id: "block_100".to_string(),
+                number: 100,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        prefetcher.process_block(&block).await.unwrap();
+
+        // Once delivered again, the block isn't decoded and fetched again
+        // and the cursor moves past it
+        for _ in 0..100 {
+            if cache.load_cursor("test").await.unwrap().is_some() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert!(cache.get_by_uri("QmRedelivered").await.unwrap().is_none());
+        assert_eq!(
+            cache.load_cursor("test").await.unwrap(),
+            Some("cursor_100".to_string())
+        );
+        assert!(cache.journaled_blocks("test").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn prefetcher_journals_blocks_until_cursor_passes() {
+        use std::collections::HashMap;
+        use stream::pb::sf::substreams::v1::Clock;
+
+        let cache = Cache::mock();
+        // Block 100 is still journaled from before a restart
+        cache
+            .journal_block(
+                "test",
+                &JournaledBlock {
+                    block: 100,
+                    cursor: "cursor_100".to_string(),
+                    block_seconds: None,
+                    events: vec![event("QmSlow")],
+                },
+            )
+            .await
+            .unwrap();
+
+        let decoder = FixedDecoder(vec![event("QmMissing")]);
+        let prefetcher = Prefetcher::new(decoder, "test", cache, IpfsSource::mock(HashMap::new()));
+        prefetcher.load_cursor().await.unwrap();
+
+        let block = BlockScopedData {
+            cursor: "cursor_101".to_string(),
+            clock: Some(Clock {
+                id: "block_101".to_string(),
+                number: 101,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        prefetcher.process_block(&block).await.unwrap();
+
+        let cache = prefetcher.cache();
+        let journaled: Vec<u64> = cache
+            .journaled_blocks("test")
+            .await
+            .unwrap()
+            .iter()
+            .map(|journaled| journaled.block)
+            .collect();
+        assert_eq!(journaled, vec![100, 101]);
+
+        // Block 100 is never delivered again in this run, so the cursor and
+        // the journal stay behind it
+        for _ in 0..100 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(cache.load_cursor("test").await.unwrap(), None);
+        assert_eq!(cache.journaled_blocks("test").await.unwrap().len(), 2);
+    }
+
+    /// Decodes one edit per block, whose URI is the block's ID.
+    struct BlockIdDecoder;
+
+    impl EventDecoder for BlockIdDecoder {
+        fn decode(&self, data: &BlockScopedData) -> Result<Vec<PrefetchEvent>, PrefetchError> {
+            Ok(data.clock.iter().map(|clock| event(&clock.id)).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn prefetcher_orphans_items_of_undone_blocks() {
+        use std::collections::HashMap;
+        use std::time::Duration;
+        use stream::pb::sf::substreams::v1::Clock;
+
+        let prefetcher = Prefetcher::new(
+            BlockIdDecoder,
+            "test",
+            Cache::mock(),
+            IpfsSource::mock(HashMap::new()),
+        );
+        let block = |id: &str, number: u64| BlockScopedData {
+            cursor: format!("cursor_{}", id),
+            clock: Some(Clock {
+                id: id.to_string(),
+                number,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        let cache = prefetcher.cache();
+        let timeout = Duration::from_secs(5);
+
+        prefetcher.process_block(&block("fork", 101)).await.unwrap();
+        let fork = cache.wait_for("fork", timeout).await.unwrap().unwrap();
+        assert_eq!(fork.block_number, Some(101));
+
+        // The canonical block replacing the undone one is fetched as usual
+        prefetcher.record_undo(100, "cursor_100".to_string());
+        prefetcher
+            .process_block(&block("canonical", 101))
+            .await
+            .unwrap();
+
+        let canonical = cache.wait_for("canonical", timeout).await.unwrap().unwrap();
+        assert!(!canonical.orphaned);
+        assert!(cache.get_by_uri("fork").await.unwrap().unwrap().orphaned);
+
+        for _ in 0..100 {
+            if cache.load_cursor("test").await.unwrap() == Some("cursor_canonical".to_string()) {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(
+            cache.load_cursor("test").await.unwrap(),
+            Some("cursor_canonical".to_string())
+        );
+    }
+}
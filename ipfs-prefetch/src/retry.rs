@@ -0,0 +1,307 @@
+//! Retries for cache items whose IPFS fetch failed.
+//!
+//! Errored CIDs are cached with `is_errored: true` so consumers know the
+//! event exists, but content is often only temporarily unavailable. The
+//! [`RetryWorker`] periodically re-fetches errored items, backing off
+//! exponentially between attempts per item, and updates the cache row in
+//! place once a fetch succeeds. Items are given up on after
+//! [`RetryConfig::max_attempts`] retries.
+//!
+//! The attempt count and next retry time are stored on the cache row, so the
+//! schedule survives restarts.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let prefetcher = Prefetcher::new(decoder, CURSOR_ID, cache, IpfsSource::live(&gateway_url));
+//! let retries = tokio::spawn(prefetcher.retry_worker(RetryConfig::from_env()).run());
+//! ```
+
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ipfs::IpfsFetcher;
+
+use crate::cache::{unix_now, Cache, CacheError, RetryCandidate};
+
+/// Environment variable for the seconds between retry sweeps.
+const INTERVAL_ENV: &str = "IPFS_RETRY_INTERVAL_SECS";
+
+/// Environment variable for the maximum number of retries per item.
+const MAX_ATTEMPTS_ENV: &str = "IPFS_RETRY_MAX_ATTEMPTS";
+
+/// Schedule applied by [`RetryWorker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Time between sweeps over the errored items.
+    pub interval: Duration,
+
+    /// Delay after the first failed retry. Doubles after every further
+    /// failure, up to `max_delay`.
+    pub base_delay: Duration,
+
+    /// Upper bound on the delay between two retries of the same item.
+    pub max_delay: Duration,
+
+    /// Number of retries after which an item is left errored for good.
+    pub max_attempts: u32,
+
+    /// Maximum number of items retried per sweep.
+    pub batch_size: usize,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(60),
+            base_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(6 * 3_600),
+            max_attempts: 10,
+            batch_size: 100,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Read the retry configuration from the environment.
+    ///
+    /// - `IPFS_RETRY_INTERVAL_SECS`: seconds between sweeps (default 60)
+    /// - `IPFS_RETRY_MAX_ATTEMPTS`: maximum retries per item (default 10)
+    ///
+    /// Missing or unparseable values fall back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let interval = env::var(INTERVAL_ENV)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|value| *value > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(defaults.interval);
+
+        let max_attempts = env::var(MAX_ATTEMPTS_ENV)
+            .ok()
+            .and_then(|value| value.parse::<u32>().ok())
+            .unwrap_or(defaults.max_attempts);
+
+        Self {
+            interval,
+            max_attempts,
+            ..defaults
+        }
+    }
+
+    /// Delay before the next retry of an item that has failed `attempts`
+    /// retries so far.
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempts.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+/// Outcome of one sweep over the errored items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetrySummary {
+    /// Items whose content was fetched and written to the cache.
+    pub resolved: usize,
+    /// Items that failed again and were rescheduled.
+    pub rescheduled: usize,
+    /// Items that failed their last allowed retry.
+    pub exhausted: usize,
+}
+
+/// Periodically re-fetches errored cache items.
+pub struct RetryWorker {
+    cache: Arc<Cache>,
+    ipfs: Arc<dyn IpfsFetcher>,
+    config: RetryConfig,
+}
+
+impl RetryWorker {
+    pub fn new(cache: Arc<Cache>, ipfs: Arc<dyn IpfsFetcher>, config: RetryConfig) -> Self {
+        Self {
+            cache,
+            ipfs,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
+    /// Sweep the errored items every [`RetryConfig::interval`], forever.
+    ///
+    /// Storage errors are logged and the sweep is tried again next interval.
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.config.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.run_once(unix_now()).await {
+                Ok(summary) if summary != RetrySummary::default() => {
+                    tracing::info!(
+                        resolved = summary.resolved,
+                        rescheduled = summary.rescheduled,
+                        exhausted = summary.exhausted,
+                        "Retried errored IPFS content"
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!(error = %e, "Failed to retry errored IPFS content"),
+            }
+        }
+    }
+
+    /// Retry every errored item due at `now` (unix seconds), up to
+    /// [`RetryConfig::batch_size`].
+    pub async fn run_once(&self, now: i64) -> Result<RetrySummary, CacheError> {
+        let due = self
+            .cache
+            .errored_due(now, self.config.max_attempts, self.config.batch_size)
+            .await?;
+
+        let mut summary = RetrySummary::default();
+
+        for candidate in due {
+            self.retry(candidate, now, &mut summary).await?;
+        }
+
+        Ok(summary)
+    }
+
+    async fn retry(
+        &self,
+        candidate: RetryCandidate,
+        now: i64,
+        summary: &mut RetrySummary,
+    ) -> Result<(), CacheError> {
+        let RetryCandidate { uri, attempts } = candidate;
+
+        match self.ipfs.get(&uri).await {
+            Ok(edit) => {
+                tracing::info!(uri = %uri, attempts = attempts + 1, "Resolved errored IPFS content");
+                self.cache.resolve(&uri, &edit).await?;
+                summary.resolved += 1;
+            }
+            Err(error) => {
+                let attempts = attempts + 1;
+                let next_retry_at = now + self.config.backoff(attempts).as_secs() as i64;
+
+                if attempts >= self.config.max_attempts {
+                    tracing::warn!(uri = %uri, attempts, error = %error, "Giving up on IPFS content");
+                    summary.exhausted += 1;
+                } else {
+                    tracing::debug!(uri = %uri, attempts, error = %error, "Retry of IPFS content failed");
+                    summary.rescheduled += 1;
+                }
+
+                self.cache
+                    .schedule_retry(&uri, attempts, next_retry_at)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheItem;
+    use ipfs::MockIpfsClient;
+    use wire::pb::grc20::Edit;
+
+    fn test_edit(name: &str) -> Edit {
+        Edit {
+            id: vec![0x01],
+            name: name.to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    async fn errored_cache(uri: &str) -> Arc<Cache> {
+        let cache = Cache::mock();
+        cache
+            .put(&CacheItem {
+                uri: uri.to_string(),
+                json: None,
+                block: "100".to_string(),
+                space_id: "abc".to_string(),
+                is_errored: true,
+                block_number: None,
+                orphaned: false,
+            })
+            .await
+            .unwrap();
+        Arc::new(cache)
+    }
+
+    fn config(max_attempts: u32) -> RetryConfig {
+        RetryConfig {
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(35),
+            max_attempts,
+            ..RetryConfig::default()
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max_delay() {
+        let config = config(10);
+
+        assert_eq!(config.backoff(1), Duration::from_secs(10));
+        assert_eq!(config.backoff(2), Duration::from_secs(20));
+        assert_eq!(config.backoff(3), Duration::from_secs(35));
+        assert_eq!(config.backoff(64), Duration::from_secs(35));
+    }
+
+    #[tokio::test]
+    async fn resolved_item_is_updated_in_place() {
+        let cache = errored_cache("ipfs://QmLate").await;
+        let ipfs = MockIpfsClient::new();
+        ipfs.register_edit("QmLate", test_edit("Late"));
+
+        let worker = RetryWorker::new(cache.clone(), Arc::new(ipfs), config(3));
+        let summary = worker.run_once(1_000).await.unwrap();
+
+        assert_eq!(summary.resolved, 1);
+        let item = cache.get_by_uri("ipfs://QmLate").await.unwrap().unwrap();
+        assert!(!item.is_errored);
+        assert_eq!(item.json.unwrap().name, "Late");
+    }
+
+    #[tokio::test]
+    async fn failed_item_backs_off_until_exhausted() {
+        let cache = errored_cache("ipfs://QmGone").await;
+        let worker = RetryWorker::new(cache, Arc::new(MockIpfsClient::new()), config(2));
+
+        let first = worker.run_once(1_000).await.unwrap();
+        assert_eq!(first.rescheduled, 1);
+
+        // Not due again until the backoff has elapsed
+        assert_eq!(
+            worker.run_once(1_009).await.unwrap(),
+            RetrySummary::default()
+        );
+
+        let second = worker.run_once(1_010).await.unwrap();
+        assert_eq!(second.exhausted, 1);
+
+        // Never retried after the last attempt
+        assert_eq!(
+            worker.run_once(1_000_000).await.unwrap(),
+            RetrySummary::default()
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! HTTP sidecar serving cache reads, enabled by the `sidecar` feature.
+//!
+//! Lets the edits transformer read cached content over HTTP instead of
+//! querying the cache storage directly:
+//!
+//! - `GET /items/{cid}?wait_ms=N`: the cached item for `ipfs://{cid}`, or
+//!   `404` if it isn't cached. With `wait_ms`, waits up to that long for the
+//!   item to be cached first (capped at [`MAX_WAIT`]), see [`Cache::wait_for`].
+//! - `POST /items` with `{"uris": [...]}`: the cached items among `uris`, as
+//!   `{"items": {uri: item}}`. Missing URIs are left out.
+//!
+//! - `GET /edit/{cid}`: the decoded edit for `ipfs://{cid}`, `404` if it
+//!   isn't cached or `422` if its content couldn't be fetched or decoded.
+//! - `GET /space/{space_id}/edits?from_block=N&limit=M`: the decoded edits
+//!   of a space whose block timestamp is at least `from_block`, as
+//!   `{"edits": [{uri, block, edit}]}`, oldest first. `limit` defaults to
+//!   [`DEFAULT_SPACE_EDITS`] and is capped at [`MAX_SPACE_EDITS`]. Answers
+//!   `501` when the storage backend can't query items by space.
+//! - `GET /status`: the prefetcher's [`PrefetchStatus`] as JSON, when served
+//!   with [`serve_with_status`].
+//! - `GET /metrics`: the same status and the fetch metrics in the Prometheus
+//!   text format, see [`exporter`](crate::exporter).
+//!
+//! The `/items` routes return JSON [`CacheItem`]s; the `/edit` and `/space`
+//! routes return only decoded edits, for consumers that don't need the
+//! cache metadata.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let prefetcher = Prefetcher::new(decoder, CURSOR_ID, cache, IpfsSource::live(&gateway_url));
+//! tokio::spawn(sidecar::serve_with_status(
+//!     prefetcher.cache(),
+//!     prefetcher.status_handle(),
+//!     "0.0.0.0:8080".parse()?,
+//! ));
+//! ```
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use wire::pb::grc20::Edit;
+
+use crate::cache::{Cache, CacheError, CacheItem};
+use crate::exporter;
+use crate::status::PrefetchStatus;
+use crate::StatusHandle;
+
+/// Longest a single request may wait for an item to be cached.
+pub const MAX_WAIT: Duration = Duration::from_secs(30);
+
+/// Edits returned by `GET /space/{space_id}/edits` without a `limit`.
+pub const DEFAULT_SPACE_EDITS: usize = 100;
+
+/// Most edits returned by a single `GET /space/{space_id}/edits`.
+pub const MAX_SPACE_EDITS: usize = 1_000;
+
+#[derive(Debug, Deserialize)]
+struct WaitParams {
+    wait_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpaceEditsParams {
+    from_block: Option<i64>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchRequest {
+    pub uris: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BatchResponse {
+    pub items: HashMap<String, CacheItem>,
+}
+
+/// A decoded edit of a space, with the item it was cached as.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpaceEdit {
+    pub uri: String,
+    pub block: String,
+    pub edit: Edit,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpaceEditsResponse {
+    pub edits: Vec<SpaceEdit>,
+}
+
+/// Build the sidecar routes over `cache`.
+pub fn router(cache: Arc<Cache>) -> Router {
+    Router::new()
+        .route("/items/:cid", get(get_item))
+        .route("/items", post(get_batch))
+        .route("/edit/:cid", get(get_edit))
+        .route("/space/:space_id/edits", get(get_space_edits))
+        .with_state(cache)
+}
+
+/// Build the `/status` and `/metrics` routes over a prefetcher's progress.
+pub fn status_router(status: StatusHandle) -> Router {
+    Router::new()
+        .route("/status", get(get_status))
+        .with_state(status.clone())
+        .merge(exporter::router(status))
+}
+
+/// Serve the sidecar on `addr` until the process exits.
+pub async fn serve(cache: Arc<Cache>, addr: SocketAddr) -> std::io::Result<()> {
+    serve_router(router(cache), addr).await
+}
+
+/// Serve the sidecar and the prefetcher's `/status` and `/metrics` on `addr`
+/// until the process exits.
+pub async fn serve_with_status(
+    cache: Arc<Cache>,
+    status: StatusHandle,
+    addr: SocketAddr,
+) -> std::io::Result<()> {
+    serve_router(router(cache).merge(status_router(status)), addr).await
+}
+
+async fn serve_router(app: Router, addr: SocketAddr) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!(addr = %addr, "Serving IPFS cache reads");
+    axum::serve(listener, app).await
+}
+
+async fn get_status(State(status): State<StatusHandle>) -> Json<PrefetchStatus> {
+    Json(status.status().await)
+}
+
+async fn get_item(
+    State(cache): State<Arc<Cache>>,
+    Path(cid): Path<String>,
+    Query(params): Query<WaitParams>,
+) -> Result<Response, SidecarError> {
+    let uri = format!("ipfs://{cid}");
+
+    let item = match params.wait_ms {
+        Some(wait_ms) => {
+            let timeout = Duration::from_millis(wait_ms).min(MAX_WAIT);
+            cache.wait_for(&uri, timeout).await?
+        }
+        None => cache.get_by_uri(&uri).await?,
+    };
+
+    Ok(match item {
+        Some(item) => Json(item).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+async fn get_batch(
+    State(cache): State<Arc<Cache>>,
+    Json(request): Json<BatchRequest>,
+) -> Result<Json<BatchResponse>, SidecarError> {
+    let items = cache.get_batch(&request.uris).await?;
+    Ok(Json(BatchResponse { items }))
+}
+
+async fn get_edit(
+    State(cache): State<Arc<Cache>>,
+    Path(cid): Path<String>,
+) -> Result<Response, SidecarError> {
+    let item = cache.get_by_uri(&format!("ipfs://{cid}")).await?;
+
+    Ok(match item {
+        Some(CacheItem {
+            json: Some(edit), ..
+        }) => Json(edit).into_response(),
+        Some(_) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "IPFS content could not be fetched or decoded",
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+async fn get_space_edits(
+    State(cache): State<Arc<Cache>>,
+    Path(space_id): Path<String>,
+    Query(params): Query<SpaceEditsParams>,
+) -> Result<Response, SidecarError> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SPACE_EDITS)
+        .min(MAX_SPACE_EDITS);
+    let items = cache
+        .space_items(&space_id, params.from_block.unwrap_or(0), limit)
+        .await?;
+
+    let Some(items) = items else {
+        return Ok((
+            StatusCode::NOT_IMPLEMENTED,
+            "The cache storage backend can't list edits by space",
+        )
+            .into_response());
+    };
+
+    let edits = items
+        .into_iter()
+        .filter_map(|item| {
+            Some(SpaceEdit {
+                edit: item.json?,
+                uri: item.uri,
+                block: item.block,
+            })
+        })
+        .collect();
+
+    Ok(Json(SpaceEditsResponse { edits }).into_response())
+}
+
+/// A cache error, reported to the client as a `500`.
+struct SidecarError(CacheError);
+
+impl From<CacheError> for SidecarError {
+    fn from(error: CacheError) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for SidecarError {
+    fn into_response(self) -> Response {
+        tracing::error!(error = %self.0, "Failed to read IPFS cache");
+        (StatusCode::INTERNAL_SERVER_ERROR, self.0.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn cache_with(uri: &str) -> Arc<Cache> {
+        let cache = Cache::mock();
+        cache
+            .put(&CacheItem {
+                uri: uri.to_string(),
+                json: None,
+                block: "100".to_string(),
+                space_id: "abc".to_string(),
+                is_errored: true,
+                block_number: None,
+                orphaned: false,
+            })
+            .await
+            .unwrap();
+        Arc::new(cache)
+    }
+
+    fn edit_item(uri: &str, space_id: &str, block: &str) -> CacheItem {
+        CacheItem {
+            uri: uri.to_string(),
+            json: Some(Edit {
+                id: vec![0x01],
+                name: uri.to_string(),
+                ops: vec![],
+                authors: vec![],
+                language: None,
+            }),
+            block: block.to_string(),
+            space_id: space_id.to_string(),
+            is_errored: false,
+            block_number: None,
+            orphaned: false,
+        }
+    }
+
+    async fn body<T: serde::de::DeserializeOwned>(response: Response) -> T {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_item_returns_cached_item_or_404() {
+        let app = router(cache_with("ipfs://QmCached").await);
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/items/QmCached").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let item: CacheItem = body(response).await;
+        assert_eq!(item.uri, "ipfs://QmCached");
+
+        let response = app
+            .oneshot(
+                Request::get("/items/QmMissing?wait_ms=10")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn get_batch_leaves_out_missing_uris() {
+        let app = router(cache_with("ipfs://QmCached").await);
+        let request = BatchRequest {
+            uris: vec![
+                "ipfs://QmCached".to_string(),
+                "ipfs://QmMissing".to_string(),
+            ],
+        };
+
+        let response = app
+            .oneshot(
+                Request::post("/items")
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&request).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let batch: BatchResponse = body(response).await;
+        assert_eq!(batch.items.len(), 1);
+        assert!(batch.items.contains_key("ipfs://QmCached"));
+    }
+
+    #[tokio::test]
+    async fn get_edit_returns_decoded_edit() {
+        let cache = cache_with("ipfs://QmErrored").await;
+        cache
+            .put(&edit_item("ipfs://QmCached", "abc", "100"))
+            .await
+            .unwrap();
+        let app = router(cache);
+
+        let get = |path: &str| Request::get(path).body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(get("/edit/QmCached")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let edit: Edit = body(response).await;
+        assert_eq!(edit.name, "ipfs://QmCached");
+
+        let response = app.clone().oneshot(get("/edit/QmErrored")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = app.oneshot(get("/edit/QmMissing")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn get_space_edits_filters_by_space_and_block() {
+        let cache = Arc::new(Cache::mock());
+        cache
+            .put_many(&[
+                edit_item("ipfs://QmOld", "abc", "100"),
+                edit_item("ipfs://QmNewer", "abc", "300"),
+                edit_item("ipfs://QmNew", "abc", "200"),
+                edit_item("ipfs://QmOther", "def", "200"),
+            ])
+            .await
+            .unwrap();
+        let app = router(cache);
+
+        let response = app
+            .oneshot(
+                Request::get("/space/abc/edits?from_block=150&limit=10")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let response: SpaceEditsResponse = body(response).await;
+        let uris: Vec<_> = response
+            .edits
+            .iter()
+            .map(|edit| edit.uri.as_str())
+            .collect();
+        assert_eq!(uris, ["ipfs://QmNew", "ipfs://QmNewer"]);
+        assert_eq!(response.edits[0].block, "200");
+        assert_eq!(response.edits[0].edit.name, "ipfs://QmNew");
+    }
+
+    #[tokio::test]
+    async fn get_status_reports_prefetcher_progress() {
+        use crate::decoder::{EventDecoder, PrefetchEvent};
+        use crate::{PrefetchError, Prefetcher};
+        use ipfs::IpfsSource;
+        use stream::pb::sf::substreams::rpc::v2::BlockScopedData;
+        use stream::pb::sf::substreams::v1::Clock;
+
+        struct NoEvents;
+
+        impl EventDecoder for NoEvents {
+            fn decode(&self, _data: &BlockScopedData) -> Result<Vec<PrefetchEvent>, PrefetchError> {
+                Ok(vec![])
+            }
+        }
+
+        let prefetcher = Prefetcher::new(
+            NoEvents,
+            "test",
+            Cache::mock(),
+            IpfsSource::mock(HashMap::new()),
+        );
+        let block = BlockScopedData {
+            clock: Some(Clock {
+                id: "block_100".to_string(),
+                number: 100,
+                timestamp: None,
+            }),
+            ..Default::default()
+        };
+        prefetcher.process_block(&block).await.unwrap();
+
+        let response = status_router(prefetcher.status_handle())
+            .oneshot(Request::get("/status").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let status: PrefetchStatus = body(response).await;
+        assert_eq!(status.head_block, Some(100));
+        assert_eq!(status.pending_blocks, 0);
+        assert_eq!(status.cursor_lag_blocks, 0);
+    }
+}
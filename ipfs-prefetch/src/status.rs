@@ -0,0 +1,197 @@
+//! Progress and backlog of a [`Prefetcher`](crate::Prefetcher).
+//!
+//! A [`PrefetchStatus`] tells whether the prefetcher keeps ahead of the
+//! stream: how many fetches are still pending and in which blocks, how many
+//! of them hold one of the fetch permits, how far the persisted cursor trails
+//! the last block delivered, and how often fetches fail. It is logged with
+//! the metrics by
+//! [`Prefetcher::process_block`](crate::Prefetcher::process_block), served
+//! on `GET /status` by the `sidecar` module and exported to Prometheus by
+//! the [`exporter`](crate::exporter) module.
+
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::CacheMetrics;
+
+/// Most pending blocks listed in a [`PrefetchStatus`], oldest first.
+pub const MAX_REPORTED_BLOCKS: usize = 20;
+
+/// A block whose cursor isn't persisted yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingBlockStatus {
+    pub block: u64,
+    /// Fetches still running.
+    pub remaining: usize,
+    /// Whether the block's items are written, waiting for an earlier block.
+    pub written: bool,
+}
+
+/// Snapshot of a prefetcher's progress.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrefetchStatus {
+    /// Last block delivered by the stream since startup.
+    pub head_block: Option<u64>,
+    /// Block the cursor was last persisted at since startup.
+    pub cursor_block: Option<u64>,
+    /// Blocks between the oldest incomplete block and the head, or 0 when
+    /// every delivered block is cached.
+    pub cursor_lag_blocks: u64,
+    /// Oldest block with fetches still running.
+    pub oldest_incomplete_block: Option<u64>,
+    /// Blocks whose cursor isn't persisted yet.
+    pub pending_blocks: usize,
+    /// Fetches still running, across all blocks.
+    pub pending_fetches: usize,
+    /// Fetches holding a permit, i.e. being fetched rather than waiting.
+    #[serde(default)]
+    pub in_flight_fetches: usize,
+    /// Fetch permits, the most fetches running at once.
+    #[serde(default)]
+    pub max_in_flight: usize,
+    /// Up to [`MAX_REPORTED_BLOCKS`] pending blocks, oldest first.
+    pub blocks: Vec<PendingBlockStatus>,
+    /// Fetch attempts since startup, across all gateways.
+    pub fetches: u64,
+    /// Fraction of fetch attempts that failed, or 0.0 before any fetch.
+    pub failure_rate: f64,
+}
+
+impl PrefetchStatus {
+    /// Build a status from the pending blocks (oldest first) and the head
+    /// and cursor blocks, with the failure rate from `metrics`.
+    pub(crate) fn new(
+        blocks: Vec<PendingBlockStatus>,
+        head_block: Option<u64>,
+        cursor_block: Option<u64>,
+        metrics: &CacheMetrics,
+    ) -> Self {
+        let oldest_incomplete_block = blocks
+            .iter()
+            .find(|block| !block.written)
+            .map(|block| block.block);
+        let cursor_lag_blocks = match (blocks.first(), head_block) {
+            (Some(oldest), Some(head)) => head.saturating_sub(oldest.block),
+            _ => 0,
+        };
+
+        let (fetches, failures) = metrics
+            .gateways()
+            .iter()
+            .fold((0, 0), |(fetches, failures), health| {
+                (fetches + health.requests(), failures + health.failures)
+            });
+        let failure_rate = if fetches == 0 {
+            0.0
+        } else {
+            failures as f64 / fetches as f64
+        };
+
+        Self {
+            head_block,
+            cursor_block,
+            cursor_lag_blocks,
+            oldest_incomplete_block,
+            pending_blocks: blocks.len(),
+            pending_fetches: blocks.iter().map(|block| block.remaining).sum(),
+            in_flight_fetches: 0,
+            max_in_flight: 0,
+            blocks: blocks.into_iter().take(MAX_REPORTED_BLOCKS).collect(),
+            fetches,
+            failure_rate,
+        }
+    }
+
+    /// Set the fetch permits in use out of `max_in_flight`.
+    pub(crate) fn with_permits(mut self, max_in_flight: usize, available: usize) -> Self {
+        self.max_in_flight = max_in_flight;
+        self.in_flight_fetches = max_in_flight.saturating_sub(available);
+        self
+    }
+
+    /// Fetches waiting for a permit, or 0 if every pending fetch holds one.
+    pub fn queued_fetches(&self) -> usize {
+        self.pending_fetches.saturating_sub(self.in_flight_fetches)
+    }
+
+    /// Log the status as one line, without the per-block list.
+    pub fn log(&self) {
+        tracing::info!(
+            head_block = self.head_block,
+            cursor_block = self.cursor_block,
+            cursor_lag_blocks = self.cursor_lag_blocks,
+            oldest_incomplete_block = self.oldest_incomplete_block,
+            pending_blocks = self.pending_blocks,
+            pending_fetches = self.pending_fetches,
+            in_flight_fetches = self.in_flight_fetches,
+            max_in_flight = self.max_in_flight,
+            fetches = self.fetches,
+            failure_rate = self.failure_rate,
+            "IPFS prefetch progress"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(block: u64, remaining: usize, written: bool) -> PendingBlockStatus {
+        PendingBlockStatus {
+            block,
+            remaining,
+            written,
+        }
+    }
+
+    #[test]
+    fn status_reports_backlog_from_oldest_pending_block() {
+        let blocks = vec![
+            block(100, 2, false),
+            block(101, 0, true),
+            block(102, 1, false),
+        ];
+
+        let status = PrefetchStatus::new(blocks, Some(110), Some(99), &CacheMetrics::default());
+
+        assert_eq!(status.oldest_incomplete_block, Some(100));
+        assert_eq!(status.cursor_lag_blocks, 10);
+        assert_eq!(status.pending_blocks, 3);
+        assert_eq!(status.pending_fetches, 3);
+        assert_eq!(status.fetches, 0);
+        assert_eq!(status.failure_rate, 0.0);
+    }
+
+    #[test]
+    fn status_without_pending_blocks_has_no_lag() {
+        let status = PrefetchStatus::new(vec![], Some(110), Some(90), &CacheMetrics::default());
+
+        assert_eq!(status.oldest_incomplete_block, None);
+        assert_eq!(status.cursor_lag_blocks, 0);
+        assert_eq!(status.pending_fetches, 0);
+    }
+
+    #[test]
+    fn status_splits_pending_fetches_by_permit() {
+        let blocks = vec![block(100, 5, false), block(101, 3, false)];
+
+        let status = PrefetchStatus::new(blocks, Some(101), None, &CacheMetrics::default())
+            .with_permits(4, 1);
+
+        assert_eq!(status.max_in_flight, 4);
+        assert_eq!(status.in_flight_fetches, 3);
+        assert_eq!(status.queued_fetches(), 5);
+    }
+
+    #[test]
+    fn status_lists_at_most_max_reported_blocks() {
+        let blocks = (0..MAX_REPORTED_BLOCKS as u64 + 5)
+            .map(|n| block(n, 1, false))
+            .collect();
+
+        let status = PrefetchStatus::new(blocks, Some(100), None, &CacheMetrics::default());
+
+        assert_eq!(status.pending_blocks, MAX_REPORTED_BLOCKS + 5);
+        assert_eq!(status.blocks.len(), MAX_REPORTED_BLOCKS);
+        assert_eq!(status.blocks[0].block, 0);
+    }
+}
@@ -0,0 +1,313 @@
+//! In-memory LRU layer for IPFS fetches.
+//!
+//! Re-processing a block (e.g. after a restart) fetches the same CIDs again.
+//! IPFS content is immutable, so [`CachedFetcher`] keeps recently fetched
+//! content bytes and decoded edits in memory and only goes to the inner
+//! fetcher on a miss. Both caches are bounded by the total size of what they
+//! hold rather than by entry count, since edits vary widely in size.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ipfs::{CachedFetcher, CacheConfig, IpfsSource};
+//!
+//! let fetcher = CachedFetcher::new(
+//!     IpfsSource::live(&gateway_url).into_fetcher(),
+//!     CacheConfig::default(),
+//! );
+//! let edit = fetcher.get("ipfs://QmYwAPJzv5CZsnA...").await?;
+//! println!("hit rate: {}", fetcher.metrics().hit_rate());
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use lru::LruCache;
+use prost::Message;
+use wire::pb::grc20::Edit;
+
+use crate::{normalize_cid, IpfsFetcher, Result};
+
+/// Default size budget for each cache: 64 MiB.
+pub const DEFAULT_CACHE_MAX_BYTES: usize = 64 * 1024 * 1024;
+
+/// Size limits for [`CachedFetcher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Maximum total size of cached raw content, in bytes.
+    pub max_content_bytes: usize,
+
+    /// Maximum total encoded size of cached decoded edits, in bytes.
+    pub max_edit_bytes: usize,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_content_bytes: DEFAULT_CACHE_MAX_BYTES,
+            max_edit_bytes: DEFAULT_CACHE_MAX_BYTES,
+        }
+    }
+}
+
+/// Hit/miss counters for [`CachedFetcher`].
+#[derive(Debug, Default)]
+pub struct CacheMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl CacheMetrics {
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Fraction of lookups served from memory, or 0.0 before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let hits = self.hits();
+        let total = hits + self.misses();
+
+        if total == 0 {
+            0.0
+        } else {
+            hits as f64 / total as f64
+        }
+    }
+}
+
+/// LRU bounded by the total size of its values.
+struct SizedLru<V> {
+    entries: LruCache<String, (V, usize)>,
+    size_bytes: usize,
+    max_bytes: usize,
+}
+
+impl<V: Clone> SizedLru<V> {
+    fn new(max_bytes: usize) -> Self {
+        Self {
+            entries: LruCache::unbounded(),
+            size_bytes: 0,
+            max_bytes,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<V> {
+        self.entries.get(key).map(|(value, _)| value.clone())
+    }
+
+    /// Insert a value, evicting least recently used entries until the cache
+    /// fits its budget. Returns the number of evicted entries. Values larger
+    /// than the whole budget are not cached.
+    fn insert(&mut self, key: String, value: V, size: usize) -> u64 {
+        if size > self.max_bytes {
+            return 0;
+        }
+
+        if let Some((_, old_size)) = self.entries.put(key, (value, size)) {
+            self.size_bytes -= old_size;
+        }
+        self.size_bytes += size;
+
+        let mut evicted = 0;
+
+        while self.size_bytes > self.max_bytes {
+            match self.entries.pop_lru() {
+                Some((_, (_, size))) => {
+                    self.size_bytes -= size;
+                    evicted += 1;
+                }
+                None => break,
+            }
+        }
+
+        evicted
+    }
+}
+
+/// [`IpfsFetcher`] decorator that caches content and decoded edits in memory.
+pub struct CachedFetcher {
+    inner: Box<dyn IpfsFetcher>,
+    content: Mutex<SizedLru<Vec<u8>>>,
+    edits: Mutex<SizedLru<Edit>>,
+    metrics: CacheMetrics,
+}
+
+impl CachedFetcher {
+    pub fn new(inner: Box<dyn IpfsFetcher>, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            content: Mutex::new(SizedLru::new(config.max_content_bytes)),
+            edits: Mutex::new(SizedLru::new(config.max_edit_bytes)),
+            metrics: CacheMetrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &CacheMetrics {
+        &self.metrics
+    }
+
+    /// Total size of cached raw content, in bytes.
+    pub fn content_size_bytes(&self) -> usize {
+        self.content.lock().unwrap().size_bytes
+    }
+
+    /// Total encoded size of cached decoded edits, in bytes.
+    pub fn edit_size_bytes(&self) -> usize {
+        self.edits.lock().unwrap().size_bytes
+    }
+
+    fn record_lookup<V>(&self, cached: &Option<V>) {
+        if cached.is_some() {
+            self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.metrics.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn record_evictions(&self, evicted: u64) {
+        if evicted > 0 {
+            self.metrics.evictions.fetch_add(evicted, Ordering::Relaxed);
+        }
+    }
+}
+
+#[async_trait]
+impl IpfsFetcher for CachedFetcher {
+    async fn get(&self, uri: &str) -> Result<Edit> {
+        let cid = normalize_cid(uri);
+
+        let cached = self.edits.lock().unwrap().get(&cid);
+        self.record_lookup(&cached);
+
+        if let Some(edit) = cached {
+            return Ok(edit);
+        }
+
+        // Failed fetches are not cached so transient errors can be retried.
+        let edit = self.inner.get(uri).await?;
+        let size = edit.encoded_len();
+        let evicted = self.edits.lock().unwrap().insert(cid, edit.clone(), size);
+        self.record_evictions(evicted);
+
+        Ok(edit)
+    }
+
+    async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+        let key = normalize_cid(cid);
+
+        let cached = self.content.lock().unwrap().get(&key);
+        self.record_lookup(&cached);
+
+        if let Some(bytes) = cached {
+            return Ok(bytes);
+        }
+
+        let bytes = self.inner.get_bytes(cid).await?;
+        let size = bytes.len();
+        let evicted = self
+            .content
+            .lock()
+            .unwrap()
+            .insert(key, bytes.clone(), size);
+        self.record_evictions(evicted);
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::MockIpfsClient;
+
+    fn test_edit(name: &str) -> Edit {
+        Edit {
+            id: vec![0x01, 0x02, 0x03],
+            name: name.to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    fn mock_fetcher(names: &[&str]) -> Box<dyn IpfsFetcher> {
+        let edits: HashMap<String, Edit> = names
+            .iter()
+            .map(|name| (format!("Qm{}", name), test_edit(name)))
+            .collect();
+
+        Box::new(MockIpfsClient::with_edits(edits))
+    }
+
+    #[tokio::test]
+    async fn test_repeated_get_hits_cache() {
+        let fetcher = CachedFetcher::new(mock_fetcher(&["One"]), CacheConfig::default());
+
+        let first = fetcher.get("ipfs://QmOne").await.unwrap();
+        let second = fetcher.get("QmOne").await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(fetcher.metrics().misses(), 1);
+        assert_eq!(fetcher.metrics().hits(), 1);
+        assert_eq!(fetcher.metrics().hit_rate(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_not_cached() {
+        let fetcher = CachedFetcher::new(mock_fetcher(&[]), CacheConfig::default());
+
+        assert!(fetcher.get("ipfs://QmMissing").await.is_err());
+        assert!(fetcher.get("ipfs://QmMissing").await.is_err());
+        assert_eq!(fetcher.metrics().misses(), 2);
+        assert_eq!(fetcher.edit_size_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_evicts_least_recently_used_over_budget() {
+        let edit_size = test_edit("One").encoded_len();
+        let config = CacheConfig {
+            max_content_bytes: DEFAULT_CACHE_MAX_BYTES,
+            max_edit_bytes: edit_size * 2,
+        };
+        let fetcher = CachedFetcher::new(mock_fetcher(&["One", "Two", "Six"]), config);
+
+        fetcher.get("QmOne").await.unwrap();
+        fetcher.get("QmTwo").await.unwrap();
+        // Touch One so Two becomes the least recently used entry
+        fetcher.get("QmOne").await.unwrap();
+        fetcher.get("QmSix").await.unwrap();
+
+        assert_eq!(fetcher.metrics().evictions(), 1);
+        assert!(fetcher.edit_size_bytes() <= edit_size * 2);
+
+        // One is still cached, Two was evicted
+        fetcher.get("QmOne").await.unwrap();
+        fetcher.get("QmTwo").await.unwrap();
+        assert_eq!(fetcher.metrics().hits(), 2);
+        assert_eq!(fetcher.metrics().misses(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_get_bytes_is_cached_separately() {
+        let fetcher = CachedFetcher::new(mock_fetcher(&["One"]), CacheConfig::default());
+
+        let bytes = fetcher.get_bytes("QmOne").await.unwrap();
+        fetcher.get_bytes("ipfs://QmOne").await.unwrap();
+
+        assert_eq!(fetcher.content_size_bytes(), bytes.len());
+        assert_eq!(fetcher.edit_size_bytes(), 0);
+        assert_eq!(fetcher.metrics().hits(), 1);
+    }
+}
@@ -0,0 +1,717 @@
+//! Offline access to content exported as CAR archives.
+//!
+//! Bulk backfills receive content as CAR (Content Addressable aRchive) files
+//! exported from the pinning service instead of fetching it CID by CID.
+//! [`CarImporter`] reads a CARv1 or CARv2 file into memory, verifies each
+//! block against its CID and serves the content through [`IpfsFetcher`], so
+//! anything that takes a fetcher (e.g. the hermes-ipfs-cache sink via
+//! [`IpfsSource::car`](crate::IpfsSource::car)) can run without a gateway.
+//!
+//! Raw blocks are served as-is. UnixFS files (dag-pb) are reassembled from
+//! their leaves, which must all be present in the archive. CIDs are looked up
+//! in the form they appear in the archive: base58btc for CIDv0 (`Qm...`) and
+//! base32 for CIDv1 (`bafy...`, `bafk...`).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ipfs::{CarImporter, IpfsFetcher};
+//!
+//! let importer = CarImporter::open("backfill.car")?;
+//! for root in importer.roots() {
+//!     let edit = importer.get(root).await?;
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use wire::{deserialize::deserialize, pb::grc20::Edit};
+
+use crate::{normalize_cid, IpfsError, IpfsFetcher, Result};
+
+/// Multicodec of raw blocks.
+const CODEC_RAW: u64 = 0x55;
+
+/// Multicodec of dag-pb blocks.
+const CODEC_DAG_PB: u64 = 0x70;
+
+/// Multihash code of sha2-256.
+const MULTIHASH_SHA2_256: u64 = 0x12;
+
+/// CBOR tag for CIDs in DAG-CBOR.
+const CBOR_TAG_CID: u64 = 42;
+
+/// First bytes of a CARv2 file: the CBOR header `{"version": 2}`.
+const CARV2_PRAGMA: [u8; 11] = [
+    0x0a, 0xa1, 0x67, 0x76, 0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x02,
+];
+
+/// Content of a CAR archive, keyed by CID.
+///
+/// Cloning is cheap: clones share the loaded blocks.
+#[derive(Clone)]
+pub struct CarImporter {
+    roots: Vec<String>,
+    blocks: Arc<HashMap<String, Block>>,
+}
+
+struct Block {
+    codec: u64,
+    data: Vec<u8>,
+}
+
+impl fmt::Debug for CarImporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CarImporter")
+            .field("roots", &self.roots)
+            .field("blocks", &self.blocks.len())
+            .finish()
+    }
+}
+
+impl CarImporter {
+    /// Read a CAR file from disk.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Read a CAR archive from memory.
+    ///
+    /// Fails if the archive is malformed or any block doesn't match its CID.
+    /// Blocks hashed with functions other than sha2-256 are not verified.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.starts_with(&CARV2_PRAGMA) {
+            return Self::from_v2(bytes);
+        }
+
+        let mut reader = Reader::new(bytes);
+        let header = reader.read_section()?;
+        let roots = parse_header(header)?;
+
+        let mut blocks = HashMap::new();
+
+        while !reader.is_empty() {
+            let section = reader.read_section()?;
+            let (cid, data) = Cid::read(section)?;
+            cid.verify(data)?;
+
+            blocks.insert(
+                cid.to_string(),
+                Block {
+                    codec: cid.codec,
+                    data: data.to_vec(),
+                },
+            );
+        }
+
+        Ok(Self {
+            roots,
+            blocks: Arc::new(blocks),
+        })
+    }
+
+    /// A CARv2 file wraps a CARv1 payload, located by a fixed-size header
+    /// after the pragma. The trailing index is not needed since every block
+    /// is loaded.
+    fn from_v2(bytes: &[u8]) -> Result<Self> {
+        let header = bytes
+            .get(CARV2_PRAGMA.len()..CARV2_PRAGMA.len() + 40)
+            .ok_or_else(|| car_error("truncated CARv2 header"))?;
+
+        let le_u64 = |offset: usize| {
+            u64::from_le_bytes(header[offset..offset + 8].try_into().unwrap()) as usize
+        };
+        let data_offset = le_u64(16);
+        let data_size = le_u64(24);
+
+        let payload = data_offset
+            .checked_add(data_size)
+            .and_then(|end| bytes.get(data_offset..end))
+            .ok_or_else(|| car_error("CARv2 payload out of bounds"))?;
+
+        Self::from_bytes(payload)
+    }
+
+    /// Root CIDs declared in the archive header.
+    pub fn roots(&self) -> &[String] {
+        &self.roots
+    }
+
+    /// CIDs of every block in the archive.
+    pub fn cids(&self) -> impl Iterator<Item = &str> {
+        self.blocks.keys().map(String::as_str)
+    }
+
+    /// Raw bytes of a block, without UnixFS decoding.
+    pub fn block(&self, cid: &str) -> Option<&[u8]> {
+        self.blocks
+            .get(&normalize_cid(cid))
+            .map(|block| block.data.as_slice())
+    }
+
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Content addressed by a CID, reassembling UnixFS files.
+    fn content(&self, cid: &str) -> Result<Vec<u8>> {
+        let block = self
+            .blocks
+            .get(cid)
+            .ok_or_else(|| IpfsError::NotFound(cid.to_string()))?;
+
+        match block.codec {
+            CODEC_RAW => Ok(block.data.clone()),
+            CODEC_DAG_PB => {
+                let mut content = Vec::new();
+                self.append_unixfs(&block.data, &mut content)?;
+                Ok(content)
+            }
+            codec => Err(IpfsError::CidError(format!(
+                "unsupported codec 0x{:x} for {}",
+                codec, cid
+            ))),
+        }
+    }
+
+    /// Append the file data of a dag-pb node and its children, in order.
+    fn append_unixfs(&self, node: &[u8], content: &mut Vec<u8>) -> Result<()> {
+        use prost::Message;
+
+        let node = unixfs::PbNode::decode(node)?;
+
+        if let Some(data) = node.data {
+            let data = unixfs::Data::decode(data.as_slice())?;
+
+            if data.r#type != unixfs::TYPE_RAW && data.r#type != unixfs::TYPE_FILE {
+                return Err(car_error(format!(
+                    "UnixFS node of type {} is not a file",
+                    data.r#type
+                )));
+            }
+
+            content.extend_from_slice(data.data.as_deref().unwrap_or_default());
+        }
+
+        for link in node.links {
+            let hash = link
+                .hash
+                .ok_or_else(|| car_error("UnixFS link without a hash"))?;
+            let (cid, _) = Cid::read(&hash)?;
+            let cid = cid.to_string();
+
+            let child = self
+                .blocks
+                .get(&cid)
+                .ok_or_else(|| IpfsError::NotFound(cid.clone()))?;
+
+            match child.codec {
+                CODEC_RAW => content.extend_from_slice(&child.data),
+                _ => self.append_unixfs(&child.data, content)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl IpfsFetcher for CarImporter {
+    async fn get(&self, uri: &str) -> Result<Edit> {
+        let bytes = self.get_bytes(uri).await?;
+        Ok(deserialize(&bytes)?)
+    }
+
+    async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+        self.content(&normalize_cid(cid))
+    }
+}
+
+fn car_error(message: impl Into<String>) -> IpfsError {
+    IpfsError::CarError(message.into())
+}
+
+/// Reads varint-prefixed sections.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn read_varint(&mut self) -> Result<u64> {
+        let (value, read) = read_varint(&self.bytes[self.pos..])?;
+        self.pos += read;
+        Ok(value)
+    }
+
+    fn read_section(&mut self) -> Result<&'a [u8]> {
+        let len = self.read_varint()? as usize;
+        let section = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or_else(|| car_error("truncated section"))?;
+        self.pos += len;
+        Ok(section)
+    }
+}
+
+/// Decode an unsigned LEB128 varint, returning the value and bytes read.
+fn read_varint(bytes: &[u8]) -> Result<(u64, usize)> {
+    let mut value = 0u64;
+
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    Err(car_error("invalid varint"))
+}
+
+/// A parsed CID, keeping its binary form.
+struct Cid<'a> {
+    version: u64,
+    codec: u64,
+    hash_code: u64,
+    digest: &'a [u8],
+    bytes: &'a [u8],
+}
+
+impl<'a> Cid<'a> {
+    /// Read a binary CID from the start of `bytes`, returning it and the rest.
+    fn read(bytes: &'a [u8]) -> Result<(Self, &'a [u8])> {
+        // CIDv0 is a bare sha2-256 multihash of a dag-pb block
+        if bytes.len() >= 34 && bytes[0] == 0x12 && bytes[1] == 0x20 {
+            let cid = Cid {
+                version: 0,
+                codec: CODEC_DAG_PB,
+                hash_code: MULTIHASH_SHA2_256,
+                digest: &bytes[2..34],
+                bytes: &bytes[..34],
+            };
+            return Ok((cid, &bytes[34..]));
+        }
+
+        let mut reader = Reader::new(bytes);
+        let version = reader.read_varint()?;
+        if version != 1 {
+            return Err(IpfsError::CidError(format!(
+                "unsupported CID version {}",
+                version
+            )));
+        }
+
+        let codec = reader.read_varint()?;
+        let hash_code = reader.read_varint()?;
+        let digest = reader.read_section()?;
+
+        let cid = Cid {
+            version,
+            codec,
+            hash_code,
+            digest,
+            bytes: &bytes[..reader.pos],
+        };
+        Ok((cid, &bytes[reader.pos..]))
+    }
+
+    fn verify(&self, data: &[u8]) -> Result<()> {
+        if self.hash_code == MULTIHASH_SHA2_256 && Sha256::digest(data).as_slice() != self.digest {
+            return Err(car_error(format!("block {} doesn't match its CID", self)));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Cid<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.version == 0 {
+            write!(f, "{}", base58btc(self.bytes))
+        } else {
+            write!(f, "b{}", base32_lower(self.bytes))
+        }
+    }
+}
+
+fn base58btc(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    let mut digits: Vec<u8> = Vec::new();
+
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+
+    std::iter::repeat_n(b'1', zeros)
+        .chain(digits.iter().rev().map(|digit| ALPHABET[*digit as usize]))
+        .map(char::from)
+        .collect()
+}
+
+/// RFC 4648 base32, lowercase and unpadded, as used by multibase `b`.
+fn base32_lower(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+    let mut output = String::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        output.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    output
+}
+
+/// Parse the DAG-CBOR header `{"version": 1, "roots": [CID, ...]}`.
+fn parse_header(bytes: &[u8]) -> Result<Vec<String>> {
+    let mut cbor = Cbor::new(bytes);
+    let mut version = None;
+    let mut roots = Vec::new();
+
+    let entries = cbor.read_header(5)?;
+    for _ in 0..entries {
+        let key_len = cbor.read_header(3)?;
+        let key = cbor.read_bytes(key_len)?;
+
+        match key {
+            b"version" => version = Some(cbor.read_header(0)?),
+            b"roots" => {
+                for _ in 0..cbor.read_header(4)? {
+                    if cbor.read_header(6)? != CBOR_TAG_CID {
+                        return Err(car_error("root is not a CID"));
+                    }
+                    let len = cbor.read_header(2)?;
+                    // DAG-CBOR prefixes binary CIDs with the identity multibase
+                    let cid_bytes = cbor.read_bytes(len)?;
+                    let (cid, _) = Cid::read(cid_bytes.get(1..).unwrap_or_default())?;
+                    roots.push(cid.to_string());
+                }
+            }
+            _ => return Err(car_error("unexpected header field")),
+        }
+    }
+
+    match version {
+        Some(1) => Ok(roots),
+        Some(version) => Err(car_error(format!("unsupported CAR version {}", version))),
+        None => Err(car_error("missing CAR version")),
+    }
+}
+
+/// Just enough CBOR to read a CAR header.
+struct Cbor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cbor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn read_byte(&mut self) -> Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| car_error("truncated header"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn read_bytes(&mut self, len: u64) -> Result<&'a [u8]> {
+        let bytes = self
+            .pos
+            .checked_add(len as usize)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or_else(|| car_error("truncated header"))?;
+        self.pos += len as usize;
+        Ok(bytes)
+    }
+
+    /// Read an item header of the expected major type, returning its
+    /// argument (value, length or tag).
+    fn read_header(&mut self, major: u8) -> Result<u64> {
+        let initial = self.read_byte()?;
+
+        if initial >> 5 != major {
+            return Err(car_error("unexpected CBOR type in header"));
+        }
+
+        match initial & 0x1f {
+            info @ 0..=23 => Ok(info as u64),
+            info @ 24..=27 => {
+                let len = 1 << (info - 24);
+                let bytes = self.read_bytes(len)?;
+                Ok(bytes.iter().fold(0, |acc, byte| (acc << 8) | *byte as u64))
+            }
+            _ => Err(car_error("unsupported CBOR length in header")),
+        }
+    }
+}
+
+/// The parts of the dag-pb and UnixFS schemas needed to read files.
+mod unixfs {
+    pub const TYPE_RAW: i32 = 0;
+    pub const TYPE_FILE: i32 = 2;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PbLink {
+        #[prost(bytes = "vec", optional, tag = "1")]
+        pub hash: Option<Vec<u8>>,
+        #[prost(string, optional, tag = "2")]
+        pub name: Option<String>,
+        #[prost(uint64, optional, tag = "3")]
+        pub tsize: Option<u64>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct PbNode {
+        #[prost(message, repeated, tag = "2")]
+        pub links: Vec<PbLink>,
+        #[prost(bytes = "vec", optional, tag = "1")]
+        pub data: Option<Vec<u8>>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Data {
+        #[prost(int32, tag = "1")]
+        pub r#type: i32,
+        #[prost(bytes = "vec", optional, tag = "2")]
+        pub data: Option<Vec<u8>>,
+        #[prost(uint64, optional, tag = "3")]
+        pub filesize: Option<u64>,
+        #[prost(uint64, repeated, packed = "false", tag = "4")]
+        pub blocksizes: Vec<u64>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                return bytes;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+
+    fn raw_cid(data: &[u8]) -> Vec<u8> {
+        let mut cid = vec![0x01, CODEC_RAW as u8, 0x12, 0x20];
+        cid.extend_from_slice(&Sha256::digest(data));
+        cid
+    }
+
+    fn v0_cid(data: &[u8]) -> Vec<u8> {
+        let mut cid = vec![0x12, 0x20];
+        cid.extend_from_slice(&Sha256::digest(data));
+        cid
+    }
+
+    fn cid_string(cid: &[u8]) -> String {
+        Cid::read(cid).unwrap().0.to_string()
+    }
+
+    fn car_v1(roots: &[Vec<u8>], blocks: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+        let mut header = vec![0xa2, 0x65];
+        header.extend_from_slice(b"roots");
+        header.push(0x80 + roots.len() as u8);
+        for root in roots {
+            header.extend_from_slice(&[0xd8, 0x2a, 0x58, root.len() as u8 + 1, 0x00]);
+            header.extend_from_slice(root);
+        }
+        header.push(0x67);
+        header.extend_from_slice(b"version");
+        header.push(0x01);
+
+        let mut car = varint(header.len() as u64);
+        car.extend_from_slice(&header);
+        for (cid, data) in blocks {
+            car.extend_from_slice(&varint((cid.len() + data.len()) as u64));
+            car.extend_from_slice(cid);
+            car.extend_from_slice(data);
+        }
+        car
+    }
+
+    fn unixfs_node(data: Option<&[u8]>, links: Vec<Vec<u8>>) -> Vec<u8> {
+        let data = unixfs::Data {
+            r#type: unixfs::TYPE_FILE,
+            data: data.map(<[u8]>::to_vec),
+            filesize: data.map(|data| data.len() as u64),
+            blocksizes: vec![],
+        };
+
+        unixfs::PbNode {
+            links: links
+                .into_iter()
+                .map(|hash| unixfs::PbLink {
+                    hash: Some(hash),
+                    name: Some(String::new()),
+                    tsize: None,
+                })
+                .collect(),
+            data: Some(data.encode_to_vec()),
+        }
+        .encode_to_vec()
+    }
+
+    fn test_edit() -> Edit {
+        Edit {
+            id: vec![0x01],
+            name: "Backfilled edit".to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_cid_strings_match_ipfs() {
+        assert_eq!(
+            cid_string(&raw_cid(b"hello world")),
+            "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e"
+        );
+        assert_eq!(
+            cid_string(&v0_cid(&unixfs_node(Some(b"hello world\n"), vec![]))),
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_block_is_served_by_cid() {
+        let edit = test_edit().encode_to_vec();
+        let cid = raw_cid(&edit);
+        let car = car_v1(std::slice::from_ref(&cid), &[(cid.clone(), edit)]);
+
+        let importer = CarImporter::from_bytes(&car).unwrap();
+        let root = format!("ipfs://{}", importer.roots()[0]);
+
+        assert_eq!(importer.len(), 1);
+        assert_eq!(importer.get(&root).await.unwrap(), test_edit());
+    }
+
+    #[tokio::test]
+    async fn test_unixfs_file_is_reassembled_from_leaves() {
+        let first = b"hello ".to_vec();
+        let second = b"world".to_vec();
+        let root = unixfs_node(None, vec![raw_cid(&first), raw_cid(&second)]);
+
+        let car = car_v1(
+            &[v0_cid(&root)],
+            &[
+                (v0_cid(&root), root.clone()),
+                (raw_cid(&first), first),
+                (raw_cid(&second), second),
+            ],
+        );
+
+        let importer = CarImporter::from_bytes(&car).unwrap();
+        let root_cid = importer.roots()[0].clone();
+
+        assert!(root_cid.starts_with("Qm"));
+        assert_eq!(importer.get_bytes(&root_cid).await.unwrap(), b"hello world");
+        assert_eq!(importer.block(&root_cid), Some(root.as_slice()));
+    }
+
+    #[tokio::test]
+    async fn test_missing_cid_is_not_found() {
+        let importer = CarImporter::from_bytes(&car_v1(&[], &[])).unwrap();
+
+        assert!(importer.is_empty());
+        assert!(matches!(
+            importer.get_bytes("QmMissing").await,
+            Err(IpfsError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_block_not_matching_cid_is_rejected() {
+        let car = car_v1(&[], &[(raw_cid(b"expected"), b"tampered".to_vec())]);
+
+        assert!(matches!(
+            CarImporter::from_bytes(&car),
+            Err(IpfsError::CarError(_))
+        ));
+    }
+
+    #[test]
+    fn test_truncated_car_is_rejected() {
+        let data = b"content".to_vec();
+        let mut car = car_v1(&[], &[(raw_cid(&data), data)]);
+        car.truncate(car.len() - 3);
+
+        assert!(matches!(
+            CarImporter::from_bytes(&car),
+            Err(IpfsError::CarError(_))
+        ));
+    }
+
+    #[test]
+    fn test_carv2_payload_is_read() {
+        let data = b"content".to_vec();
+        let v1 = car_v1(&[raw_cid(&data)], &[(raw_cid(&data), data)]);
+
+        let data_offset = (CARV2_PRAGMA.len() + 40) as u64;
+        let mut car = CARV2_PRAGMA.to_vec();
+        car.extend_from_slice(&[0; 16]);
+        car.extend_from_slice(&data_offset.to_le_bytes());
+        car.extend_from_slice(&(v1.len() as u64).to_le_bytes());
+        car.extend_from_slice(&0u64.to_le_bytes());
+        car.extend_from_slice(&v1);
+
+        let importer = CarImporter::from_bytes(&car).unwrap();
+
+        assert_eq!(importer.len(), 1);
+        assert_eq!(importer.roots().len(), 1);
+    }
+}
@@ -4,6 +4,12 @@
 //! - [`IpfsSource`] config enum for choosing between mock and live IPFS clients
 //! - [`IpfsFetcher`] trait for abstracting IPFS access
 //! - [`IpfsClient`] production client that fetches from an IPFS gateway
+//! - [`IpfsNodeClient`] production client that fetches from a Kubo node's RPC API
+//! - [`CachedFetcher`] decorator that keeps recently fetched content in an in-memory LRU
+//! - [`ThrottledFetcher`] decorator that rate limits and bounds concurrency of any fetcher
+//! - [`MeteredFetcher`] decorator that records latency, bytes and errors per gateway
+//! - [`NegativeCachedFetcher`] decorator that short-circuits CIDs known to fail
+//! - [`CarImporter`] offline client serving the blocks of a CAR archive
 //! - [`MockIpfsClient`] mock client for testing with pre-configured CID → Edit mappings
 //!
 //! ## Usage with IpfsSource (Recommended)
@@ -20,20 +26,49 @@
 //! // Production: use live gateway
 //! let fetcher = IpfsSource::live("https://ipfs.io/ipfs/").into_fetcher();
 //!
+//! // Production: use our own Kubo node, pinning fetched CIDs
+//! let fetcher = IpfsSource::node("http://127.0.0.1:5001", true).into_fetcher();
+//!
+//! // Offline backfill: serve content from a CAR archive
+//! let fetcher = IpfsSource::car(CarImporter::open("backfill.car")?).into_fetcher();
+//!
 //! // Use the fetcher
 //! let edit = fetcher.get("ipfs://QmTestCid1").await?;
+//!
+//! // Fetch other content types with IpfsFetcherExt
+//! let metadata: ProposalMetadata = fetcher.get_json("ipfs://QmTestCid2").await?;
 //! ```
 
+mod cached;
+mod car;
+mod metrics;
 mod mock;
+mod negative;
+mod node;
+mod throttle;
 
+pub use cached::{CacheConfig, CacheMetrics, CachedFetcher, DEFAULT_CACHE_MAX_BYTES};
+pub use car::CarImporter;
+pub use metrics::{
+    ErrorClass, FetchMetrics, GatewayHealth, LatencyHistogram, MeteredFetcher, LATENCY_BUCKETS_MS,
+};
 pub use mock::MockIpfsClient;
+pub use negative::{NegativeCache, NegativeCacheConfig, NegativeCachedFetcher, NegativeEntry};
+pub use node::IpfsNodeClient;
+pub use throttle::{ThrottleConfig, ThrottledFetcher, DEFAULT_MAX_IN_FLIGHT};
 
 use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use prost::Message;
 use reqwest::Client as ReqwestClient;
+use serde::de::DeserializeOwned;
 use wire::{
-    deserialize::{deserialize, DeserializeError},
+    deserialize::{deserialize, DeserializeError, EditDecoder},
     pb::grc20::Edit,
 };
 
@@ -45,8 +80,12 @@ pub enum IpfsError {
     Io(#[from] std::io::Error),
     #[error("prost error: {0}")]
     Prost(#[from] prost::DecodeError),
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
     #[error("cid error: {0}")]
     CidError(String),
+    #[error("car error: {0}")]
+    CarError(String),
     #[error("deserialize error: {0}")]
     DeserializeError(#[from] DeserializeError),
     #[error("not found: {0}")]
@@ -55,10 +94,33 @@ pub enum IpfsError {
     NetworkError(String),
     #[error("timeout")]
     Timeout,
+    #[error("known bad cid {0}: cached {1} failure")]
+    KnownBad(String, ErrorClass),
+}
+
+impl IpfsError {
+    /// Copy of the error for callers sharing a single fetch.
+    ///
+    /// Wrapped library errors can't be cloned, so they are carried over as
+    /// their message.
+    fn duplicate(&self) -> IpfsError {
+        match self {
+            IpfsError::CidError(message) => IpfsError::CidError(message.clone()),
+            IpfsError::CarError(message) => IpfsError::CarError(message.clone()),
+            IpfsError::NotFound(message) => IpfsError::NotFound(message.clone()),
+            IpfsError::NetworkError(message) => IpfsError::NetworkError(message.clone()),
+            IpfsError::Timeout => IpfsError::Timeout,
+            IpfsError::KnownBad(cid, class) => IpfsError::KnownBad(cid.clone(), *class),
+            error => IpfsError::NetworkError(error.to_string()),
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, IpfsError>;
 
+/// Chunks of an IPFS payload in the order they arrive.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
 /// Trait for fetching content from IPFS.
 ///
 /// This trait abstracts the IPFS client to enable dependency injection
@@ -83,6 +145,120 @@ pub trait IpfsFetcher: Send + Sync {
 
     /// Fetch raw bytes from IPFS by CID.
     async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>>;
+
+    /// Stream raw bytes from IPFS by CID without buffering the whole body.
+    ///
+    /// The default implementation fetches the full body with `get_bytes` and
+    /// yields it as a single chunk. Network-backed clients override it.
+    async fn get_stream(&self, cid: &str) -> Result<ByteStream> {
+        let bytes = Bytes::from(self.get_bytes(cid).await?);
+        Ok(Box::pin(stream::once(async move { Ok(bytes) })))
+    }
+
+    /// Fetch and decode a GRC-20 Edit, decoding it as chunks arrive.
+    ///
+    /// Prefer this over `get` for large edits, since the raw body is never
+    /// held in memory alongside the decoded edit.
+    async fn get_streamed(&self, uri: &str) -> Result<Edit> {
+        let stream = self.get_stream(uri).await?;
+        decode_edit_stream(stream).await
+    }
+
+    /// Fetch and decode many GRC-20 Edits at once.
+    ///
+    /// URIs pointing to the same CID are only fetched once, and at most
+    /// [`DEFAULT_MAX_IN_FLIGHT`] fetches run concurrently. Results are
+    /// returned in the same order as `uris`.
+    async fn get_many(&self, uris: &[String]) -> Vec<Result<Edit>> {
+        let mut unique: Vec<&str> = Vec::new();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let positions: Vec<usize> = uris
+            .iter()
+            .map(|uri| {
+                *indices.entry(normalize_cid(uri)).or_insert_with(|| {
+                    unique.push(uri);
+                    unique.len() - 1
+                })
+            })
+            .collect();
+
+        let mut remaining = vec![0usize; unique.len()];
+        for &index in &positions {
+            remaining[index] += 1;
+        }
+
+        let fetches: Vec<_> = unique.into_iter().map(|uri| self.get(uri)).collect();
+        let mut fetched: Vec<Option<Result<Edit>>> = stream::iter(fetches)
+            .buffered(DEFAULT_MAX_IN_FLIGHT)
+            .map(Some)
+            .collect()
+            .await;
+
+        // The last duplicate takes the fetched result, earlier ones get a copy
+        positions
+            .into_iter()
+            .map(|index| {
+                remaining[index] -= 1;
+                if remaining[index] == 0 {
+                    fetched[index].take().unwrap()
+                } else {
+                    match fetched[index].as_ref().unwrap() {
+                        Ok(edit) => Ok(edit.clone()),
+                        Err(error) => Err(error.duplicate()),
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// Typed fetching of non-Edit content from IPFS.
+///
+/// Implemented for every [`IpfsFetcher`], including boxed and decorated ones,
+/// so governance metadata or proposal descriptions go through the same
+/// caching, throttling and fallback as edits. The URI may be `ipfs://CID` or
+/// a raw CID.
+///
+/// # Example
+///
+/// ```ignore
+/// use ipfs::IpfsFetcherExt;
+///
+/// let metadata: ProposalMetadata = fetcher.get_json("ipfs://QmProposal").await?;
+/// let space: SpaceMetadata = fetcher.get_decoded("QmSpaceMetadata").await?;
+/// ```
+#[async_trait]
+pub trait IpfsFetcherExt: IpfsFetcher {
+    /// Fetch and decode a protobuf message from IPFS by URI.
+    async fn get_decoded<T>(&self, uri: &str) -> Result<T>
+    where
+        T: Message + Default,
+    {
+        let bytes = self.get_bytes(&normalize_cid(uri)).await?;
+        Ok(T::decode(bytes.as_slice())?)
+    }
+
+    /// Fetch and deserialize a JSON document from IPFS by URI.
+    async fn get_json<T>(&self, uri: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let bytes = self.get_bytes(&normalize_cid(uri)).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+impl<F: IpfsFetcher + ?Sized> IpfsFetcherExt for F {}
+
+/// Decode a GRC-20 Edit from a stream of chunks of its protobuf encoding.
+pub async fn decode_edit_stream(mut stream: ByteStream) -> Result<Edit> {
+    let mut decoder = EditDecoder::new();
+
+    while let Some(chunk) = stream.next().await {
+        decoder.push(&chunk?)?;
+    }
+
+    Ok(decoder.finish()?)
 }
 
 /// Production IPFS client that fetches from a gateway.
@@ -132,6 +308,12 @@ impl IpfsFetcher for IpfsClient {
         let bytes = res.bytes().await?;
         Ok(bytes.to_vec())
     }
+
+    async fn get_stream(&self, uri: &str) -> Result<ByteStream> {
+        let url = format!("{}{}", self.url, normalize_cid(uri));
+        let res = self.client.get(&url).send().await?;
+        Ok(Box::pin(res.bytes_stream().map_err(IpfsError::from)))
+    }
 }
 
 /// Configuration for the IPFS data source.
@@ -165,6 +347,17 @@ pub enum IpfsSource {
         /// The IPFS gateway URL (e.g., "https://ipfs.io/ipfs/")
         gateway_url: String,
     },
+
+    /// Connect to a Kubo node's RPC API.
+    Node {
+        /// The node's RPC API URL (e.g., "http://127.0.0.1:5001")
+        api_url: String,
+        /// Whether to pin fetched CIDs on the node.
+        pin: bool,
+    },
+
+    /// Serve content from a CAR archive loaded in memory.
+    Car(CarImporter),
 }
 
 impl IpfsSource {
@@ -195,6 +388,31 @@ impl IpfsSource {
         }
     }
 
+    /// Create an IPFS source backed by a Kubo node's RPC API.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let source = IpfsSource::node("http://127.0.0.1:5001", true);
+    /// ```
+    pub fn node(api_url: impl Into<String>, pin: bool) -> Self {
+        Self::Node {
+            api_url: api_url.into(),
+            pin,
+        }
+    }
+
+    /// Create an IPFS source serving the blocks of a CAR archive.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let source = IpfsSource::car(CarImporter::open("backfill.car")?);
+    /// ```
+    pub fn car(importer: CarImporter) -> Self {
+        Self::Car(importer)
+    }
+
     /// Create the appropriate IpfsFetcher implementation.
     ///
     /// Returns a boxed trait object that can be used to fetch IPFS content.
@@ -202,6 +420,54 @@ impl IpfsSource {
         match self {
             Self::Mock(edits) => Box::new(MockIpfsClient::with_edits(edits)),
             Self::Live { gateway_url } => Box::new(IpfsClient::new(&gateway_url)),
+            Self::Node { api_url, pin } => Box::new(IpfsNodeClient::new(&api_url, pin)),
+            Self::Car(importer) => Box::new(importer),
         }
     }
+
+    /// Create the IpfsFetcher implementation wrapped in a [`ThrottledFetcher`].
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let fetcher = IpfsSource::live("https://ipfs.io/ipfs/")
+    ///     .into_throttled_fetcher(ThrottleConfig::from_env());
+    /// ```
+    pub fn into_throttled_fetcher(self, config: ThrottleConfig) -> ThrottledFetcher {
+        ThrottledFetcher::new(self.into_fetcher(), config)
+    }
+
+    /// Name of the gateway this source fetches from, used to label metrics.
+    pub fn gateway(&self) -> &str {
+        match self {
+            Self::Mock(_) => "mock",
+            Self::Live { gateway_url } => gateway_url,
+            Self::Node { api_url, .. } => api_url,
+            Self::Car(_) => "car",
+        }
+    }
+
+    /// Create the IpfsFetcher implementation wrapped in a [`MeteredFetcher`]
+    /// that records into `metrics` under this source's gateway.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let metrics = Arc::new(FetchMetrics::new());
+    /// let fetcher = IpfsSource::live("https://ipfs.io/ipfs/")
+    ///     .into_metered_fetcher(metrics.clone());
+    /// ```
+    pub fn into_metered_fetcher(self, metrics: Arc<FetchMetrics>) -> MeteredFetcher {
+        let gateway = self.gateway().to_string();
+        MeteredFetcher::new(self.into_fetcher(), gateway, metrics)
+    }
+}
+
+/// Normalize a CID by removing the `ipfs://` prefix if present.
+pub(crate) fn normalize_cid(uri: &str) -> String {
+    if let Some((_, cid)) = uri.split_once("://") {
+        cid.to_string()
+    } else {
+        uri.to_string()
+    }
 }
@@ -0,0 +1,390 @@
+//! Fetch metrics and per-gateway health tracking.
+//!
+//! A failing gateway shows up as a growing cache backlog long after it
+//! started failing. [`MeteredFetcher`] wraps any [`IpfsFetcher`] and records
+//! latency, bytes downloaded and errors by class into a [`FetchMetrics`]
+//! registry keyed by gateway. The registry can be shared between several
+//! fetchers, and [`FetchMetrics::health`] returns a snapshot per gateway that
+//! can be logged or alerted on.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use ipfs::{FetchMetrics, IpfsSource};
+//!
+//! let metrics = Arc::new(FetchMetrics::new());
+//! let fetcher = IpfsSource::live(&gateway_url).into_metered_fetcher(metrics.clone());
+//!
+//! for health in metrics.health() {
+//!     if health.success_rate() < 0.9 {
+//!         tracing::warn!(gateway = %health.gateway, "IPFS gateway is failing");
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use wire::pb::grc20::Edit;
+
+use crate::{ByteStream, IpfsError, IpfsFetcher, Result};
+
+/// Upper bounds of the fetch latency histogram buckets, in milliseconds.
+/// Fetches slower than the last bound land in an overflow bucket.
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+
+/// Coarse classification of fetch failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ErrorClass {
+    /// The CID couldn't be resolved.
+    NotFound,
+    /// The request timed out.
+    Timeout,
+    /// The gateway was unreachable or returned an error.
+    Network,
+    /// The content was fetched but couldn't be decoded.
+    Decode,
+    /// The CID itself was invalid.
+    InvalidCid,
+}
+
+impl ErrorClass {
+    pub fn of(error: &IpfsError) -> Self {
+        match error {
+            IpfsError::NotFound(_) => ErrorClass::NotFound,
+            IpfsError::Timeout => ErrorClass::Timeout,
+            IpfsError::Reqwest(error) if error.is_timeout() => ErrorClass::Timeout,
+            IpfsError::Reqwest(_) | IpfsError::Io(_) | IpfsError::NetworkError(_) => {
+                ErrorClass::Network
+            }
+            IpfsError::Prost(_)
+            | IpfsError::Json(_)
+            | IpfsError::DeserializeError(_)
+            | IpfsError::CarError(_) => ErrorClass::Decode,
+            IpfsError::CidError(_) => ErrorClass::InvalidCid,
+            IpfsError::KnownBad(_, class) => *class,
+        }
+    }
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ErrorClass::NotFound => "not_found",
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Network => "network",
+            ErrorClass::Decode => "decode",
+            ErrorClass::InvalidCid => "invalid_cid",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Fetch latency histogram using [`LATENCY_BUCKETS_MS`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Count per bucket, with the overflow bucket last.
+    pub buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    pub count: u64,
+    pub sum_ms: u64,
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        let ms = latency.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += ms;
+    }
+
+    /// Mean latency in milliseconds, or 0.0 before any fetch.
+    pub fn mean_ms(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.sum_ms as f64 / self.count as f64
+        }
+    }
+
+    /// Upper bound of the bucket containing the given quantile (0.0..=1.0),
+    /// or `None` if there are no samples or it falls in the overflow bucket.
+    pub fn quantile_upper_bound_ms(&self, quantile: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let target = (quantile.clamp(0.0, 1.0) * self.count as f64)
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return LATENCY_BUCKETS_MS.get(bucket).copied();
+            }
+        }
+
+        None
+    }
+}
+
+/// Snapshot of a single gateway's fetch metrics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GatewayHealth {
+    pub gateway: String,
+    pub successes: u64,
+    pub failures: u64,
+    pub errors: HashMap<ErrorClass, u64>,
+    pub bytes_downloaded: u64,
+    pub latency: LatencyHistogram,
+    /// Failures since the last success.
+    pub consecutive_failures: u64,
+    pub last_error: Option<String>,
+}
+
+impl GatewayHealth {
+    pub fn requests(&self) -> u64 {
+        self.successes + self.failures
+    }
+
+    /// Fraction of successful fetches, or 1.0 before any fetch.
+    ///
+    /// Not-found results count as failures here, since a gateway that can't
+    /// resolve content is as useless to us as one that is down.
+    pub fn success_rate(&self) -> f64 {
+        let requests = self.requests();
+
+        if requests == 0 {
+            1.0
+        } else {
+            self.successes as f64 / requests as f64
+        }
+    }
+}
+
+/// Registry of fetch metrics keyed by gateway.
+#[derive(Debug, Default)]
+pub struct FetchMetrics {
+    gateways: Mutex<HashMap<String, GatewayHealth>>,
+}
+
+impl FetchMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of every gateway that has been fetched from, sorted by name.
+    pub fn health(&self) -> Vec<GatewayHealth> {
+        let mut health: Vec<GatewayHealth> =
+            self.gateways.lock().unwrap().values().cloned().collect();
+        health.sort_by(|a, b| a.gateway.cmp(&b.gateway));
+        health
+    }
+
+    /// Snapshot of a single gateway, if it has been fetched from.
+    pub fn gateway_health(&self, gateway: &str) -> Option<GatewayHealth> {
+        self.gateways.lock().unwrap().get(gateway).cloned()
+    }
+
+    fn record_success(&self, gateway: &str, latency: Duration, bytes: u64) {
+        self.update(gateway, |health| {
+            health.successes += 1;
+            health.bytes_downloaded += bytes;
+            health.consecutive_failures = 0;
+            health.latency.record(latency);
+        });
+    }
+
+    fn record_failure(&self, gateway: &str, latency: Duration, error: &IpfsError) {
+        self.update(gateway, |health| {
+            health.failures += 1;
+            *health.errors.entry(ErrorClass::of(error)).or_default() += 1;
+            health.consecutive_failures += 1;
+            health.last_error = Some(error.to_string());
+            health.latency.record(latency);
+        });
+    }
+
+    fn record_bytes(&self, gateway: &str, bytes: u64) {
+        self.update(gateway, |health| health.bytes_downloaded += bytes);
+    }
+
+    fn update(&self, gateway: &str, f: impl FnOnce(&mut GatewayHealth)) {
+        let mut gateways = self.gateways.lock().unwrap();
+        let health = gateways
+            .entry(gateway.to_string())
+            .or_insert_with(|| GatewayHealth {
+                gateway: gateway.to_string(),
+                ..Default::default()
+            });
+        f(health);
+    }
+}
+
+/// [`IpfsFetcher`] decorator that records fetch metrics for one gateway.
+pub struct MeteredFetcher {
+    inner: Box<dyn IpfsFetcher>,
+    gateway: String,
+    metrics: Arc<FetchMetrics>,
+}
+
+impl MeteredFetcher {
+    pub fn new(
+        inner: Box<dyn IpfsFetcher>,
+        gateway: impl Into<String>,
+        metrics: Arc<FetchMetrics>,
+    ) -> Self {
+        Self {
+            inner,
+            gateway: gateway.into(),
+            metrics,
+        }
+    }
+
+    pub fn metrics(&self) -> &Arc<FetchMetrics> {
+        &self.metrics
+    }
+
+    /// Snapshot of this fetcher's gateway.
+    pub fn health(&self) -> GatewayHealth {
+        self.metrics
+            .gateway_health(&self.gateway)
+            .unwrap_or_else(|| GatewayHealth {
+                gateway: self.gateway.clone(),
+                ..Default::default()
+            })
+    }
+
+    fn record<T>(&self, started: Instant, result: &Result<T>, bytes: impl Fn(&T) -> u64) {
+        match result {
+            Ok(value) => {
+                self.metrics
+                    .record_success(&self.gateway, started.elapsed(), bytes(value))
+            }
+            Err(error) => self
+                .metrics
+                .record_failure(&self.gateway, started.elapsed(), error),
+        }
+    }
+}
+
+#[async_trait]
+impl IpfsFetcher for MeteredFetcher {
+    /// Bytes downloaded are approximated by the edit's encoded size, since
+    /// the inner fetcher doesn't expose the raw body.
+    async fn get(&self, uri: &str) -> Result<Edit> {
+        let started = Instant::now();
+        let result = self.inner.get(uri).await;
+        self.record(started, &result, |edit| edit.encoded_len() as u64);
+        result
+    }
+
+    async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+        let started = Instant::now();
+        let result = self.inner.get_bytes(cid).await;
+        self.record(started, &result, |bytes| bytes.len() as u64);
+        result
+    }
+
+    /// Latency covers opening the stream. Bytes are counted as chunks arrive.
+    async fn get_stream(&self, cid: &str) -> Result<ByteStream> {
+        let started = Instant::now();
+        let result = self.inner.get_stream(cid).await;
+        self.record(started, &result, |_| 0);
+
+        let metrics = self.metrics.clone();
+        let gateway = self.gateway.clone();
+
+        Ok(Box::pin(result?.inspect_ok(move |chunk| {
+            metrics.record_bytes(&gateway, chunk.len() as u64)
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::MockIpfsClient;
+
+    fn metered(metrics: &Arc<FetchMetrics>) -> MeteredFetcher {
+        let edit = Edit {
+            id: vec![0x01],
+            name: "Edit".to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        };
+        let client = MockIpfsClient::with_edits(HashMap::from([("QmOne".to_string(), edit)]));
+
+        MeteredFetcher::new(Box::new(client), "mock", metrics.clone())
+    }
+
+    #[tokio::test]
+    async fn test_records_successes_and_failures() {
+        let metrics = Arc::new(FetchMetrics::new());
+        let fetcher = metered(&metrics);
+
+        let bytes = fetcher.get_bytes("QmOne").await.unwrap();
+        fetcher.get("ipfs://QmMissing").await.unwrap_err();
+        fetcher.get("ipfs://QmMissing").await.unwrap_err();
+
+        let health = fetcher.health();
+        assert_eq!(health.successes, 1);
+        assert_eq!(health.failures, 2);
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(health.errors.get(&ErrorClass::NotFound), Some(&2));
+        assert_eq!(health.bytes_downloaded, bytes.len() as u64);
+        assert_eq!(health.latency.count, 3);
+        assert!((health.success_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+
+        fetcher.get("QmOne").await.unwrap();
+        assert_eq!(fetcher.health().consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_shared_registry_tracks_gateways_separately() {
+        let metrics = Arc::new(FetchMetrics::new());
+        let first = metered(&metrics);
+        let second = MeteredFetcher::new(Box::new(MockIpfsClient::new()), "empty", metrics.clone());
+
+        first.get("QmOne").await.unwrap();
+        second.get("QmOne").await.unwrap_err();
+
+        let health = metrics.health();
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].gateway, "empty");
+        assert_eq!(health[0].success_rate(), 0.0);
+        assert_eq!(health[1].gateway, "mock");
+        assert_eq!(health[1].success_rate(), 1.0);
+    }
+
+    #[test]
+    fn test_latency_histogram_buckets() {
+        let mut histogram = LatencyHistogram::default();
+
+        histogram.record(Duration::from_millis(10));
+        histogram.record(Duration::from_millis(300));
+        histogram.record(Duration::from_millis(20_000));
+
+        assert_eq!(histogram.buckets[0], 1);
+        assert_eq!(histogram.buckets[3], 1);
+        assert_eq!(histogram.buckets[LATENCY_BUCKETS_MS.len()], 1);
+        assert_eq!(histogram.quantile_upper_bound_ms(0.5), Some(500));
+        assert_eq!(histogram.quantile_upper_bound_ms(1.0), None);
+    }
+}
@@ -31,7 +31,7 @@ use async_trait::async_trait;
 use prost::Message;
 use wire::pb::grc20::Edit;
 
-use crate::{IpfsError, IpfsFetcher, Result};
+use crate::{normalize_cid, IpfsError, IpfsFetcher, Result};
 
 /// Mock IPFS client that returns pre-configured edit data.
 ///
@@ -76,6 +76,14 @@ impl MockIpfsClient {
         self.edits.write().unwrap().insert(normalized_cid, bytes);
     }
 
+    /// Register raw content to be returned for a given CID.
+    ///
+    /// Use this for non-Edit content fetched with [`IpfsFetcherExt`](crate::IpfsFetcherExt).
+    pub fn register_bytes(&self, cid: &str, bytes: Vec<u8>) {
+        let normalized_cid = normalize_cid(cid);
+        self.edits.write().unwrap().insert(normalized_cid, bytes);
+    }
+
     /// Check if a CID is registered in the mock.
     pub fn has_cid(&self, cid: &str) -> bool {
         let normalized_cid = normalize_cid(cid);
@@ -118,18 +126,10 @@ impl IpfsFetcher for MockIpfsClient {
     }
 }
 
-/// Normalize a CID by removing the `ipfs://` prefix if present.
-fn normalize_cid(uri: &str) -> String {
-    if let Some((_, cid)) = uri.split_once("://") {
-        cid.to_string()
-    } else {
-        uri.to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::IpfsFetcherExt;
 
     fn test_edit(name: &str) -> Edit {
         Edit {
@@ -202,4 +202,102 @@ mod tests {
         let edit = client.get("ipfs://QmTestCid").await.unwrap();
         assert_eq!(edit.name, "Test");
     }
+
+    #[tokio::test]
+    async fn test_mock_client_get_streamed() {
+        let client = MockIpfsClient::new();
+        client.register_edit("QmTestCid", test_edit("Streamed"));
+
+        let edit = client.get_streamed("ipfs://QmTestCid").await.unwrap();
+        assert_eq!(edit, client.get("ipfs://QmTestCid").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_decode_edit_stream_across_chunks() {
+        let bytes = test_edit("Chunked").encode_to_vec();
+        let chunks: Vec<Result<bytes::Bytes>> = bytes
+            .chunks(3)
+            .map(|chunk| Ok(bytes::Bytes::copy_from_slice(chunk)))
+            .collect();
+
+        let edit = crate::decode_edit_stream(Box::pin(futures::stream::iter(chunks)))
+            .await
+            .unwrap();
+        assert_eq!(edit, test_edit("Chunked"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_client_get_decoded() {
+        let client = MockIpfsClient::new();
+        client.register_edit("QmTestCid", test_edit("Decoded"));
+
+        let edit: Edit = client.get_decoded("ipfs://QmTestCid").await.unwrap();
+        assert_eq!(edit, test_edit("Decoded"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_client_get_json() {
+        let client = MockIpfsClient::new();
+        client.register_bytes(
+            "ipfs://QmProposal",
+            br#"{"title":"Add editor","description":"Adds a new editor"}"#.to_vec(),
+        );
+
+        let metadata: serde_json::Value = client.get_json("QmProposal").await.unwrap();
+        assert_eq!(metadata["title"], "Add editor");
+
+        client.register_bytes("QmInvalid", b"not json".to_vec());
+        let result = client.get_json::<serde_json::Value>("QmInvalid").await;
+        assert!(matches!(result, Err(IpfsError::Json(_))));
+    }
+
+    /// Wraps the mock and counts how many fetches reach it.
+    struct CountingClient {
+        inner: MockIpfsClient,
+        fetches: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl IpfsFetcher for CountingClient {
+        async fn get(&self, uri: &str) -> Result<Edit> {
+            self.fetches
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.inner.get(uri).await
+        }
+
+        async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+            self.inner.get_bytes(cid).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_many_dedupes_and_preserves_order() {
+        let client = CountingClient {
+            inner: MockIpfsClient::new(),
+            fetches: std::sync::atomic::AtomicUsize::new(0),
+        };
+        client.inner.register_edit("QmCid1", test_edit("Edit 1"));
+        client.inner.register_edit("QmCid2", test_edit("Edit 2"));
+
+        let uris: Vec<String> = [
+            "ipfs://QmCid2",
+            "ipfs://QmUnknown",
+            "QmCid1",
+            "ipfs://QmCid2",
+            "QmUnknown",
+        ]
+        .iter()
+        .map(|uri| uri.to_string())
+        .collect();
+
+        let results = client.get_many(&uris).await;
+
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_ref().unwrap().name, "Edit 2");
+        assert!(matches!(results[1], Err(IpfsError::NotFound(_))));
+        assert_eq!(results[2].as_ref().unwrap().name, "Edit 1");
+        assert_eq!(results[3].as_ref().unwrap().name, "Edit 2");
+        assert!(matches!(results[4], Err(IpfsError::NotFound(_))));
+        assert_eq!(client.fetches.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
 }
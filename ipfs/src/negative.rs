@@ -0,0 +1,417 @@
+//! Negative-result cache for CIDs that keep failing.
+//!
+//! Some CIDs are simply unresolvable or point to content that isn't a valid
+//! edit, and without this they are retried on every re-processing of their
+//! block. [`NegativeCachedFetcher`] remembers failures whose [`ErrorClass`]
+//! is configured with a TTL and short-circuits further fetches of that CID
+//! with [`IpfsError::KnownBad`] until the entry expires. Transient failures
+//! such as timeouts are never cached by default.
+//!
+//! The [`NegativeCache`] can be backed by a JSON file so known-bad CIDs
+//! survive restarts. Entries can be purged when content later becomes
+//! available.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use ipfs::{IpfsSource, NegativeCache, NegativeCacheConfig, NegativeCachedFetcher};
+//!
+//! let cache = Arc::new(NegativeCache::open("ipfs-negative-cache.json")?);
+//! let fetcher = NegativeCachedFetcher::new(
+//!     IpfsSource::live(&gateway_url).into_fetcher(),
+//!     cache.clone(),
+//!     NegativeCacheConfig::default(),
+//! );
+//!
+//! // Content was pinned after all
+//! cache.purge("QmYwAPJzv5CZsnA...")?;
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use wire::pb::grc20::Edit;
+
+use crate::{normalize_cid, ErrorClass, IpfsError, IpfsFetcher, Result};
+
+/// How long failures of each class are remembered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCacheConfig {
+    /// TTL per error class. Classes without an entry are never cached.
+    pub ttls: HashMap<ErrorClass, Duration>,
+}
+
+impl Default for NegativeCacheConfig {
+    /// Caches unresolvable CIDs for a day, and invalid CIDs or undecodable
+    /// content (which can't change, since content is immutable) for 30 days.
+    fn default() -> Self {
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        Self {
+            ttls: HashMap::from([
+                (ErrorClass::NotFound, day),
+                (ErrorClass::InvalidCid, day * 30),
+                (ErrorClass::Decode, day * 30),
+            ]),
+        }
+    }
+}
+
+impl NegativeCacheConfig {
+    /// Set the TTL for an error class.
+    pub fn with_ttl(mut self, class: ErrorClass, ttl: Duration) -> Self {
+        self.ttls.insert(class, ttl);
+        self
+    }
+
+    /// Stop caching failures of an error class.
+    pub fn without(mut self, class: ErrorClass) -> Self {
+        self.ttls.remove(&class);
+        self
+    }
+}
+
+/// A remembered failure for a CID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NegativeEntry {
+    pub class: ErrorClass,
+    /// Message of the error that was cached.
+    pub message: String,
+    /// Unix timestamp in seconds after which the entry is ignored.
+    pub expires_at: u64,
+}
+
+/// CID → failure store, optionally persisted to a JSON file.
+#[derive(Debug, Default)]
+pub struct NegativeCache {
+    entries: Mutex<HashMap<String, NegativeEntry>>,
+    path: Option<PathBuf>,
+}
+
+impl NegativeCache {
+    /// Create a cache that only lives in memory.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open a cache persisted at `path`, loading existing entries if the
+    /// file exists. Expired entries are dropped on load.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut entries: HashMap<String, NegativeEntry> = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        let now = unix_now();
+        entries.retain(|_, entry| entry.expires_at > now);
+
+        Ok(Self {
+            entries: Mutex::new(entries),
+            path: Some(path),
+        })
+    }
+
+    /// Returns the unexpired entry for a CID.
+    pub fn get(&self, cid: &str) -> Option<NegativeEntry> {
+        self.get_at(&normalize_cid(cid), unix_now())
+    }
+
+    /// Remember a failure for a CID.
+    pub fn insert(
+        &self,
+        cid: &str,
+        class: ErrorClass,
+        message: String,
+        ttl: Duration,
+    ) -> Result<()> {
+        let entry = NegativeEntry {
+            class,
+            message,
+            expires_at: unix_now().saturating_add(ttl.as_secs()),
+        };
+
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(normalize_cid(cid), entry);
+        self.persist()
+    }
+
+    /// Forget a CID, e.g. once its content has become available. Returns
+    /// whether an entry was removed.
+    pub fn purge(&self, cid: &str) -> Result<bool> {
+        let removed = self
+            .entries
+            .lock()
+            .unwrap()
+            .remove(&normalize_cid(cid))
+            .is_some();
+
+        if removed {
+            self.persist()?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Forget every CID cached with the given error class. Returns the
+    /// number of removed entries.
+    pub fn purge_class(&self, class: ErrorClass) -> Result<usize> {
+        let removed = {
+            let mut entries = self.entries.lock().unwrap();
+            let before = entries.len();
+            entries.retain(|_, entry| entry.class != class);
+            before - entries.len()
+        };
+
+        if removed > 0 {
+            self.persist()?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Drop expired entries. Returns the number of removed entries.
+    pub fn purge_expired(&self) -> Result<usize> {
+        let now = unix_now();
+        let removed = {
+            let mut entries = self.entries.lock().unwrap();
+            let before = entries.len();
+            entries.retain(|_, entry| entry.expires_at > now);
+            before - entries.len()
+        };
+
+        if removed > 0 {
+            self.persist()?;
+        }
+
+        Ok(removed)
+    }
+
+    /// Number of entries, including expired ones not yet purged.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get_at(&self, cid: &str, now: u64) -> Option<NegativeEntry> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(cid)
+            .filter(|entry| entry.expires_at > now)
+            .cloned()
+    }
+
+    /// Write all entries to the backing file, if any. The file is replaced
+    /// atomically so a crash can't leave it truncated, and the lock is held
+    /// while writing so concurrent writers don't interleave.
+    fn persist(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let entries = self.entries.lock().unwrap();
+        let json = serde_json::to_vec(&*entries)?;
+        let tmp_path = path.with_extension("tmp");
+
+        fs::write(&tmp_path, json)?;
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+}
+
+/// [`IpfsFetcher`] decorator that short-circuits CIDs known to fail.
+pub struct NegativeCachedFetcher {
+    inner: Box<dyn IpfsFetcher>,
+    cache: Arc<NegativeCache>,
+    config: NegativeCacheConfig,
+}
+
+impl NegativeCachedFetcher {
+    pub fn new(
+        inner: Box<dyn IpfsFetcher>,
+        cache: Arc<NegativeCache>,
+        config: NegativeCacheConfig,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            config,
+        }
+    }
+
+    pub fn cache(&self) -> &Arc<NegativeCache> {
+        &self.cache
+    }
+
+    fn check(&self, uri: &str) -> Result<()> {
+        match self.cache.get(uri) {
+            Some(entry) => Err(IpfsError::KnownBad(normalize_cid(uri), entry.class)),
+            None => Ok(()),
+        }
+    }
+
+    /// Remember the failure if its class is configured with a TTL.
+    ///
+    /// Failing to persist the cache doesn't fail the fetch, the entry is
+    /// still kept in memory.
+    fn record<T>(&self, uri: &str, result: &Result<T>) {
+        let Err(error) = result else {
+            return;
+        };
+
+        let class = ErrorClass::of(error);
+
+        if let Some(ttl) = self.config.ttls.get(&class) {
+            let _ = self.cache.insert(uri, class, error.to_string(), *ttl);
+        }
+    }
+}
+
+#[async_trait]
+impl IpfsFetcher for NegativeCachedFetcher {
+    async fn get(&self, uri: &str) -> Result<Edit> {
+        self.check(uri)?;
+        let result = self.inner.get(uri).await;
+        self.record(uri, &result);
+        result
+    }
+
+    async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+        self.check(cid)?;
+        let result = self.inner.get_bytes(cid).await;
+        self.record(cid, &result);
+        result
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockIpfsClient;
+
+    fn test_edit() -> Edit {
+        Edit {
+            id: vec![0x01],
+            name: "Edit".to_string(),
+            ops: vec![],
+            authors: vec![],
+            language: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_found_is_short_circuited_until_purged() {
+        let mock = Arc::new(MockIpfsClient::new());
+        let cache = Arc::new(NegativeCache::in_memory());
+        let fetcher = NegativeCachedFetcher::new(
+            Box::new(SharedMock(mock.clone())),
+            cache.clone(),
+            NegativeCacheConfig::default(),
+        );
+
+        let first = fetcher.get("ipfs://QmLate").await;
+        assert!(matches!(first, Err(IpfsError::NotFound(_))));
+
+        // Content appears, but the cached failure still short-circuits
+        mock.register_edit("QmLate", test_edit());
+        let second = fetcher.get("QmLate").await;
+        assert!(matches!(
+            second,
+            Err(IpfsError::KnownBad(_, ErrorClass::NotFound))
+        ));
+
+        assert!(cache.purge("ipfs://QmLate").unwrap());
+        assert_eq!(fetcher.get("QmLate").await.unwrap(), test_edit());
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_classes_are_not_cached() {
+        let cache = Arc::new(NegativeCache::in_memory());
+        let fetcher = NegativeCachedFetcher::new(
+            Box::new(MockIpfsClient::new()),
+            cache.clone(),
+            NegativeCacheConfig::default().without(ErrorClass::NotFound),
+        );
+
+        fetcher.get("QmMissing").await.unwrap_err();
+
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_expired_entries_are_ignored() {
+        let cache = NegativeCache::in_memory();
+        cache
+            .insert(
+                "QmExpiring",
+                ErrorClass::NotFound,
+                "not found".to_string(),
+                Duration::from_secs(60),
+            )
+            .unwrap();
+
+        assert!(cache.get("QmExpiring").is_some());
+        assert!(cache.get_at("QmExpiring", unix_now() + 61).is_none());
+    }
+
+    #[test]
+    fn test_cache_is_persisted() {
+        let path =
+            std::env::temp_dir().join(format!("ipfs-negative-cache-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let cache = NegativeCache::open(&path).unwrap();
+        cache
+            .insert(
+                "ipfs://QmBad",
+                ErrorClass::Decode,
+                "invalid wire type".to_string(),
+                Duration::from_secs(3600),
+            )
+            .unwrap();
+
+        let reopened = NegativeCache::open(&path).unwrap();
+        assert_eq!(reopened.get("QmBad").unwrap().class, ErrorClass::Decode);
+
+        assert_eq!(reopened.purge_class(ErrorClass::Decode).unwrap(), 1);
+        assert!(NegativeCache::open(&path).unwrap().is_empty());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    /// Lets a test register content after the mock is boxed into a fetcher.
+    struct SharedMock(Arc<MockIpfsClient>);
+
+    #[async_trait]
+    impl IpfsFetcher for SharedMock {
+        async fn get(&self, uri: &str) -> Result<Edit> {
+            self.0.get(uri).await
+        }
+
+        async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+            self.0.get_bytes(cid).await
+        }
+    }
+}
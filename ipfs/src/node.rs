@@ -0,0 +1,139 @@
+//! IPFS client that talks to a Kubo node over its RPC API.
+//!
+//! Unlike [`IpfsClient`](crate::IpfsClient), which fetches through a public
+//! HTTP gateway, this client calls `/api/v0/cat` on our own node. It can
+//! optionally pin every CID it fetches so the node keeps the content around
+//! through garbage collection.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ipfs::{IpfsFetcher, IpfsNodeClient};
+//!
+//! let client = IpfsNodeClient::new("http://127.0.0.1:5001", true);
+//! let edit = client.get("ipfs://QmYwAPJzv5CZsnA...").await?;
+//! ```
+
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use reqwest::{Client as ReqwestClient, Response};
+use wire::{deserialize::deserialize, pb::grc20::Edit};
+
+use crate::{normalize_cid, ByteStream, IpfsError, IpfsFetcher, Result};
+
+/// IPFS client backed by a Kubo node's RPC API.
+pub struct IpfsNodeClient {
+    api_url: String,
+    pin: bool,
+    client: ReqwestClient,
+}
+
+impl IpfsNodeClient {
+    /// Create a client for the node at `api_url` (e.g. "http://127.0.0.1:5001").
+    ///
+    /// When `pin` is true every successfully fetched CID is pinned on the node.
+    pub fn new(api_url: &str, pin: bool) -> Self {
+        IpfsNodeClient {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            pin,
+            client: ReqwestClient::new(),
+        }
+    }
+
+    /// Pin a CID on the node.
+    pub async fn pin(&self, cid: &str) -> Result<()> {
+        self.rpc("pin/add", cid).await?;
+        Ok(())
+    }
+
+    fn rpc_url(&self, command: &str) -> String {
+        format!("{}/api/v0/{}", self.api_url, command)
+    }
+
+    /// Call an RPC command that takes a single CID argument.
+    ///
+    /// Kubo only accepts POST on the RPC API and reports errors such as
+    /// unresolvable CIDs as a non-2xx status with a JSON body.
+    async fn rpc(&self, command: &str, cid: &str) -> Result<Vec<u8>> {
+        let res = self.rpc_response(command, cid).await?;
+        Ok(res.bytes().await?.to_vec())
+    }
+
+    /// Call an RPC command and return the response once it's known to have
+    /// succeeded, leaving the body unread.
+    async fn rpc_response(&self, command: &str, cid: &str) -> Result<Response> {
+        let res = self
+            .client
+            .post(self.rpc_url(command))
+            .query(&[("arg", cid)])
+            .send()
+            .await?;
+
+        let status = res.status();
+
+        if !status.is_success() {
+            let bytes = res.bytes().await?;
+            let message = String::from_utf8_lossy(&bytes);
+
+            if message.contains("not found") {
+                return Err(IpfsError::NotFound(cid.to_string()));
+            }
+
+            return Err(IpfsError::NetworkError(format!(
+                "{} {} failed with {}: {}",
+                command, cid, status, message
+            )));
+        }
+
+        Ok(res)
+    }
+}
+
+#[async_trait]
+impl IpfsFetcher for IpfsNodeClient {
+    async fn get(&self, uri: &str) -> Result<Edit> {
+        let bytes = self.get_bytes(uri).await?;
+
+        let data = deserialize(&bytes)?;
+        Ok(data)
+    }
+
+    async fn get_bytes(&self, uri: &str) -> Result<Vec<u8>> {
+        let cid = normalize_cid(uri);
+        let bytes = self.rpc("cat", &cid).await?;
+
+        if self.pin {
+            self.pin(&cid).await?;
+        }
+
+        Ok(bytes)
+    }
+
+    async fn get_stream(&self, uri: &str) -> Result<ByteStream> {
+        let cid = normalize_cid(uri);
+
+        // Pin up front since we can't tell when the caller finishes reading
+        if self.pin {
+            self.pin(&cid).await?;
+        }
+
+        let res = self.rpc_response("cat", &cid).await?;
+        Ok(Box::pin(res.bytes_stream().map_err(IpfsError::from)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rpc_url_strips_trailing_slash() {
+        let client = IpfsNodeClient::new("http://127.0.0.1:5001/", false);
+
+        assert_eq!(client.rpc_url("cat"), "http://127.0.0.1:5001/api/v0/cat");
+        assert_eq!(
+            client.rpc_url("pin/add"),
+            "http://127.0.0.1:5001/api/v0/pin/add"
+        );
+    }
+}
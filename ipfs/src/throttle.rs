@@ -0,0 +1,253 @@
+//! Client-side rate limiting and concurrency control for IPFS fetches.
+//!
+//! Public gateways throttle aggressive clients, which we hit during
+//! backfills. [`ThrottledFetcher`] wraps any [`IpfsFetcher`] and bounds both
+//! the number of requests in flight and the rate at which new requests start.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use ipfs::{IpfsSource, ThrottleConfig, ThrottledFetcher};
+//!
+//! let config = ThrottleConfig::from_env();
+//! let fetcher = ThrottledFetcher::new(IpfsSource::live(&gateway_url).into_fetcher(), config);
+//! let edit = fetcher.get("ipfs://QmYwAPJzv5CZsnA...").await?;
+//! ```
+
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::Instant;
+use wire::pb::grc20::Edit;
+
+use crate::{ByteStream, IpfsFetcher, Result};
+
+/// Default maximum number of concurrent IPFS requests.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 20;
+
+/// Environment variable for the maximum number of concurrent requests.
+const MAX_IN_FLIGHT_ENV: &str = "IPFS_MAX_IN_FLIGHT";
+
+/// Environment variable for the maximum number of requests started per second.
+const REQUESTS_PER_SECOND_ENV: &str = "IPFS_REQUESTS_PER_SECOND";
+
+/// Limits applied by [`ThrottledFetcher`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrottleConfig {
+    /// Maximum number of requests started per second. `None` disables rate
+    /// limiting and only bounds concurrency.
+    pub requests_per_second: Option<f64>,
+
+    /// Maximum number of requests in flight at once.
+    pub max_in_flight: usize,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: None,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+        }
+    }
+}
+
+impl ThrottleConfig {
+    /// Read the throttle configuration from the environment.
+    ///
+    /// - `IPFS_MAX_IN_FLIGHT`: maximum concurrent requests (default 20)
+    /// - `IPFS_REQUESTS_PER_SECOND`: maximum request rate (default unlimited)
+    ///
+    /// Missing or unparseable values fall back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let max_in_flight = env::var(MAX_IN_FLIGHT_ENV)
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(defaults.max_in_flight);
+
+        let requests_per_second = env::var(REQUESTS_PER_SECOND_ENV)
+            .ok()
+            .and_then(|value| value.parse::<f64>().ok())
+            .filter(|value| *value > 0.0);
+
+        Self {
+            requests_per_second,
+            max_in_flight,
+        }
+    }
+
+    /// Minimum spacing between the start of two requests.
+    fn interval(&self) -> Option<Duration> {
+        self.requests_per_second
+            .filter(|rps| *rps > 0.0)
+            .map(|rps| Duration::from_secs_f64(1.0 / rps))
+    }
+}
+
+/// [`IpfsFetcher`] decorator that bounds request concurrency and rate.
+pub struct ThrottledFetcher {
+    inner: Box<dyn IpfsFetcher>,
+    config: ThrottleConfig,
+    in_flight: Semaphore,
+    /// Earliest instant the next request is allowed to start.
+    next_slot: Mutex<Instant>,
+}
+
+impl ThrottledFetcher {
+    pub fn new(inner: Box<dyn IpfsFetcher>, config: ThrottleConfig) -> Self {
+        Self {
+            inner,
+            config,
+            in_flight: Semaphore::new(config.max_in_flight.max(1)),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn config(&self) -> &ThrottleConfig {
+        &self.config
+    }
+
+    /// Wait until the rate limit allows another request to start.
+    ///
+    /// Slots are handed out in order under the lock, and the wait happens
+    /// outside of it so waiting callers don't serialize on the mutex.
+    async fn wait_for_slot(&self) {
+        let Some(interval) = self.config.interval() else {
+            return;
+        };
+
+        let start_at = {
+            let mut next_slot = self.next_slot.lock().await;
+            let start_at = (*next_slot).max(Instant::now());
+            *next_slot = start_at + interval;
+            start_at
+        };
+
+        tokio::time::sleep_until(start_at).await;
+    }
+}
+
+#[async_trait]
+impl IpfsFetcher for ThrottledFetcher {
+    async fn get(&self, uri: &str) -> Result<Edit> {
+        let _permit = self.in_flight.acquire().await.unwrap();
+        self.wait_for_slot().await;
+        self.inner.get(uri).await
+    }
+
+    async fn get_bytes(&self, cid: &str) -> Result<Vec<u8>> {
+        let _permit = self.in_flight.acquire().await.unwrap();
+        self.wait_for_slot().await;
+        self.inner.get_bytes(cid).await
+    }
+
+    /// The permit only covers starting the request, not reading the body.
+    async fn get_stream(&self, cid: &str) -> Result<ByteStream> {
+        let _permit = self.in_flight.acquire().await.unwrap();
+        self.wait_for_slot().await;
+        self.inner.get_stream(cid).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    /// Fetcher that records the peak number of concurrent requests.
+    struct CountingFetcher {
+        current: AtomicUsize,
+        peak: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl IpfsFetcher for CountingFetcher {
+        async fn get(&self, _uri: &str) -> Result<Edit> {
+            unimplemented!()
+        }
+
+        async fn get_bytes(&self, _cid: &str) -> Result<Vec<u8>> {
+            let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight_is_respected() {
+        let peak = Arc::new(AtomicUsize::new(0));
+        let inner = CountingFetcher {
+            current: AtomicUsize::new(0),
+            peak: peak.clone(),
+        };
+        let config = ThrottleConfig {
+            requests_per_second: None,
+            max_in_flight: 2,
+        };
+        let fetcher = Arc::new(ThrottledFetcher::new(Box::new(inner), config));
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let fetcher = fetcher.clone();
+                tokio::spawn(async move { fetcher.get_bytes(&format!("Qm{}", i)).await })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_requests_per_second_spaces_requests() {
+        let mut edits = HashMap::new();
+        edits.insert(
+            "QmTestCid".to_string(),
+            Edit {
+                id: vec![0x01],
+                name: "Test".to_string(),
+                ops: vec![],
+                authors: vec![],
+                language: None,
+            },
+        );
+        let config = ThrottleConfig {
+            requests_per_second: Some(10.0),
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+        };
+        let fetcher =
+            ThrottledFetcher::new(Box::new(crate::MockIpfsClient::with_edits(edits)), config);
+
+        let started = Instant::now();
+        for _ in 0..5 {
+            fetcher.get("ipfs://QmTestCid").await.unwrap();
+        }
+
+        // The first request starts immediately, the next four wait 100ms each.
+        assert!(started.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_interval_disabled_without_rate() {
+        assert_eq!(ThrottleConfig::default().interval(), None);
+        assert_eq!(
+            ThrottleConfig {
+                requests_per_second: Some(4.0),
+                max_in_flight: 1,
+            }
+            .interval(),
+            Some(Duration::from_millis(250))
+        );
+    }
+}
@@ -123,6 +123,17 @@ impl MockSubstream {
         block
     }
 
+    /// Advance to the given block number without emitting blocks.
+    ///
+    /// The timestamp advances by the same ~12 second block time as if the
+    /// skipped blocks had been generated. Going backwards is a no-op.
+    pub fn skip_to_block(&mut self, block_number: u64) {
+        if block_number > self.current_block {
+            self.current_timestamp += (block_number - self.current_block) * 12;
+            self.current_block = block_number;
+        }
+    }
+
     /// Create metadata for the current block state.
     pub fn current_metadata(&self) -> BlockMetadata {
         BlockMetadata {
@@ -63,6 +63,21 @@
 //! ]);
 //! ```
 //!
+//! ## Scripted Scenarios
+//!
+//! Use [`Scenario`] to script events at chosen blocks, optionally after the
+//! test topology:
+//!
+//! ```rust
+//! use mock_substream::Scenario;
+//! use mock_substream::test_topology::*;
+//!
+//! let blocks = Scenario::after_test_topology()
+//!     .at_block(2_000_000)
+//!     .extend_verified(ROOT_SPACE_ID, SPACE_X)
+//!     .build();
+//! ```
+//!
 //! ## Random Generation (requires `random` feature)
 //!
 //! ```rust,ignore
@@ -84,6 +99,7 @@
 
 pub mod events;
 pub mod generator;
+pub mod scenario;
 pub mod test_topology;
 
 // Re-export main types at crate root for convenience
@@ -106,3 +122,4 @@ pub use events::{
 };
 
 pub use generator::{MockConfig, MockSubstream};
+pub use scenario::Scenario;
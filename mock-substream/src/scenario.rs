@@ -0,0 +1,306 @@
+//! Builder for scripting custom mock substream scenarios.
+//!
+//! [`test_topology`](crate::test_topology) is a single fixed script. Use a
+//! [`Scenario`] when a test needs its own sequence of events at chosen blocks,
+//! optionally appended after the standard topology.
+//!
+//! Events are added to the current block until it is closed with
+//! [`Scenario::next_block`] or [`Scenario::at_block`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use mock_substream::scenario::Scenario;
+//! use mock_substream::test_topology::*;
+//! use mock_substream::{Op, UpdateEntity, Value};
+//!
+//! let blocks = Scenario::after_test_topology()
+//!     .create_personal_space(SPACE_S, TOPIC_S, USER_1)
+//!     .extend_verified(ROOT_SPACE_ID, SPACE_S)
+//!     .at_block(1_000_100)
+//!     .publish_edit(
+//!         EDIT_ROOT_1,
+//!         SPACE_S,
+//!         USER_1,
+//!         "Add person",
+//!         vec![Op::UpdateEntity(UpdateEntity {
+//!             id: ENTITY_PERSON_1,
+//!             values: vec![Value {
+//!                 property: PROPERTY_NAME,
+//!                 value: "Alice".to_string(),
+//!             }],
+//!         })],
+//!     )
+//!     .build();
+//!
+//! assert_eq!(blocks.last().unwrap().number, 1_000_100);
+//! ```
+
+use crate::events::*;
+use crate::generator::{MockConfig, MockSubstream};
+use crate::test_topology;
+
+/// Scripted sequence of mock blocks.
+#[derive(Debug)]
+pub struct Scenario {
+    mock: MockSubstream,
+    blocks: Vec<MockBlock>,
+    pending: Vec<MockEvent>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    /// Start an empty scenario at the default deterministic start block.
+    pub fn new() -> Self {
+        Self::with_config(MockConfig::deterministic())
+    }
+
+    /// Start an empty scenario using the given generator configuration.
+    pub fn with_config(config: MockConfig) -> Self {
+        Self {
+            mock: MockSubstream::new(config),
+            blocks: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Start a scenario after the given blocks, which are kept at the start
+    /// of the output. New events land in the block following the last one.
+    pub fn after(blocks: Vec<MockBlock>) -> Self {
+        let mut scenario = Self::new();
+
+        if let Some(last) = blocks.last() {
+            scenario.mock = MockSubstream::new(MockConfig {
+                start_block: last.number + 1,
+                start_timestamp: last.timestamp + 12,
+                ..MockConfig::deterministic()
+            });
+        }
+
+        scenario.blocks = blocks;
+        scenario
+    }
+
+    /// Start a scenario after the deterministic [`test_topology`].
+    pub fn after_test_topology() -> Self {
+        Self::after(test_topology::generate())
+    }
+
+    /// Close the current block, even if it has no events.
+    pub fn next_block(mut self) -> Self {
+        let events = std::mem::take(&mut self.pending);
+        let block = self.mock.block_with_events(events);
+        self.blocks.push(block);
+        self
+    }
+
+    /// Close the current block if it has events and move to `block_number`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_number` is before the current block.
+    pub fn at_block(mut self, block_number: u64) -> Self {
+        if !self.pending.is_empty() {
+            self = self.next_block();
+        }
+
+        assert!(
+            block_number >= self.mock.current_block_number(),
+            "cannot move back to block {} from block {}",
+            block_number,
+            self.mock.current_block_number()
+        );
+
+        self.mock.skip_to_block(block_number);
+        self
+    }
+
+    /// Add a space creation event to the current block.
+    pub fn create_space(
+        mut self,
+        space_id: SpaceId,
+        topic_id: TopicId,
+        space_type: SpaceType,
+    ) -> Self {
+        let event = self.mock.create_space(space_id, topic_id, space_type);
+        self.pending.push(MockEvent::SpaceCreated(event));
+        self
+    }
+
+    /// Add a personal space creation event to the current block.
+    pub fn create_personal_space(
+        self,
+        space_id: SpaceId,
+        topic_id: TopicId,
+        owner: Address,
+    ) -> Self {
+        self.create_space(space_id, topic_id, SpaceType::Personal { owner })
+    }
+
+    /// Add a DAO space creation event to the current block.
+    pub fn create_dao_space(
+        self,
+        space_id: SpaceId,
+        topic_id: TopicId,
+        initial_editors: Vec<SpaceId>,
+        initial_members: Vec<SpaceId>,
+    ) -> Self {
+        self.create_space(
+            space_id,
+            topic_id,
+            SpaceType::Dao {
+                initial_editors,
+                initial_members,
+            },
+        )
+    }
+
+    /// Add a trust extension event to the current block.
+    pub fn extend_trust(mut self, source: SpaceId, extension: TrustExtension) -> Self {
+        let event = self.mock.extend_trust(source, extension);
+        self.pending.push(MockEvent::TrustExtended(event));
+        self
+    }
+
+    /// Add a verified trust extension to the current block.
+    pub fn extend_verified(self, source: SpaceId, target: SpaceId) -> Self {
+        self.extend_trust(
+            source,
+            TrustExtension::Verified {
+                target_space_id: target,
+            },
+        )
+    }
+
+    /// Add a related trust extension to the current block.
+    pub fn extend_related(self, source: SpaceId, target: SpaceId) -> Self {
+        self.extend_trust(
+            source,
+            TrustExtension::Related {
+                target_space_id: target,
+            },
+        )
+    }
+
+    /// Add a subtopic trust extension to the current block.
+    pub fn extend_subtopic(self, source: SpaceId, target_topic: TopicId) -> Self {
+        self.extend_trust(
+            source,
+            TrustExtension::Subtopic {
+                target_topic_id: target_topic,
+            },
+        )
+    }
+
+    /// Add an edit with a single author to the current block.
+    pub fn publish_edit(
+        self,
+        edit_id: EditId,
+        space_id: SpaceId,
+        author: Address,
+        name: impl Into<String>,
+        ops: Vec<Op>,
+    ) -> Self {
+        self.publish_edit_by(edit_id, space_id, vec![author], name, ops)
+    }
+
+    /// Add an edit with any number of authors to the current block.
+    pub fn publish_edit_by(
+        mut self,
+        edit_id: EditId,
+        space_id: SpaceId,
+        authors: Vec<Address>,
+        name: impl Into<String>,
+        ops: Vec<Op>,
+    ) -> Self {
+        let event = self
+            .mock
+            .publish_edit(edit_id, space_id, authors, name.into(), ops);
+        self.pending.push(MockEvent::EditPublished(event));
+        self
+    }
+
+    /// Close the current block if it has events and return all blocks.
+    pub fn build(self) -> Vec<MockBlock> {
+        let scenario = if self.pending.is_empty() {
+            self
+        } else {
+            self.next_block()
+        };
+        scenario.blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPACE_1: SpaceId = make_id(0x01);
+    const SPACE_2: SpaceId = make_id(0x02);
+    const TOPIC_1: TopicId = make_id(0x81);
+    const TOPIC_2: TopicId = make_id(0x82);
+    const OWNER: Address = make_address(0xAA);
+
+    #[test]
+    fn test_events_share_current_block() {
+        let blocks = Scenario::new()
+            .create_personal_space(SPACE_1, TOPIC_1, OWNER)
+            .create_personal_space(SPACE_2, TOPIC_2, OWNER)
+            .next_block()
+            .extend_verified(SPACE_1, SPACE_2)
+            .build();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].events.len(), 2);
+        assert_eq!(blocks[1].number, blocks[0].number + 1);
+
+        match &blocks[1].events[0] {
+            MockEvent::TrustExtended(trust) => {
+                assert_eq!(trust.meta.block_number, blocks[1].number)
+            }
+            other => panic!("Expected trust extension, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_at_block_skips_ahead() {
+        let blocks = Scenario::new()
+            .create_personal_space(SPACE_1, TOPIC_1, OWNER)
+            .at_block(1_000_050)
+            .publish_edit(make_id(0xE1), SPACE_1, OWNER, "Edit", vec![])
+            .build();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].number, 1_000_050);
+        assert_eq!(blocks[1].timestamp - blocks[0].timestamp, 50 * 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot move back")]
+    fn test_at_block_rejects_going_back() {
+        Scenario::new().next_block().at_block(1);
+    }
+
+    #[test]
+    fn test_after_test_topology_appends_blocks() {
+        let topology = test_topology::generate();
+        let last = topology.last().unwrap().number;
+
+        let blocks = Scenario::after_test_topology()
+            .create_personal_space(SPACE_1, TOPIC_1, OWNER)
+            .build();
+
+        assert_eq!(blocks.len(), topology.len() + 1);
+        assert_eq!(blocks.last().unwrap().number, last + 1);
+    }
+
+    #[test]
+    fn test_build_without_pending_events_adds_no_block() {
+        assert!(Scenario::new().build().is_empty());
+    }
+}
@@ -1,5 +1,26 @@
 //! Configuration types for the SearchIndexService.
 
+use std::time::Duration;
+
+/// How the SearchIndexService deletes entity documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeleteMode {
+    /// Remove documents from the index. Recovering them requires a reindex.
+    ///
+    /// Deletes carrying the time of their edit replace the document with a tombstone
+    /// instead, removed after `tombstone_retention` by
+    /// `SearchIndexService::purge_expired`.
+    #[default]
+    Hard,
+    /// Flag documents as deleted so they are excluded from search, and keep them
+    /// for `retention` so they can be restored. Documents deleted longer ago are
+    /// removed by `SearchIndexService::purge_expired`.
+    Soft {
+        /// How long soft-deleted documents are kept before they can be purged.
+        retention: Duration,
+    },
+}
+
 /// Configuration for the SearchIndexService.
 ///
 /// This struct allows customization of service behavior, particularly around batch
@@ -12,12 +33,25 @@ pub struct SearchIndexServiceConfig {
     /// Set to `None` to disable the limit (not recommended for production).
     /// Defaults to 1000 if not specified.
     pub max_batch_size: Option<usize>,
+
+    /// How documents are deleted. Defaults to [`DeleteMode::Hard`].
+    pub delete_mode: DeleteMode,
+
+    /// How long tombstones of hard-deleted documents are kept before they can be
+    /// purged. Defaults to 24 hours.
+    ///
+    /// Must exceed how late an update can arrive after the delete of its entity, e.g.
+    /// because they were consumed from different partitions. Soft-deleted documents
+    /// act as tombstones for their whole retention window.
+    pub tombstone_retention: Duration,
 }
 
 impl Default for SearchIndexServiceConfig {
     fn default() -> Self {
         Self {
             max_batch_size: Some(1000),
+            delete_mode: DeleteMode::Hard,
+            tombstone_retention: Duration::from_secs(24 * 60 * 60),
         }
     }
 }
@@ -36,6 +70,7 @@ impl SearchIndexServiceConfig {
     pub fn unlimited() -> Self {
         Self {
             max_batch_size: None,
+            ..Self::default()
         }
     }
 
@@ -51,6 +86,36 @@ impl SearchIndexServiceConfig {
     pub fn with_max_batch_size(max_batch_size: usize) -> Self {
         Self {
             max_batch_size: Some(max_batch_size),
+            ..Self::default()
         }
     }
+
+    /// Soft-delete documents instead of removing them, keeping them restorable
+    /// for `retention`.
+    ///
+    /// # Arguments
+    ///
+    /// * `retention` - How long soft-deleted documents are kept before they can be purged
+    ///
+    /// # Returns
+    ///
+    /// This config with `delete_mode` set to [`DeleteMode::Soft`].
+    pub fn with_soft_delete(mut self, retention: Duration) -> Self {
+        self.delete_mode = DeleteMode::Soft { retention };
+        self
+    }
+
+    /// Keep tombstones of hard-deleted documents for `tombstone_retention`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tombstone_retention` - How long tombstones are kept before they can be purged
+    ///
+    /// # Returns
+    ///
+    /// This config with `tombstone_retention` set.
+    pub fn with_tombstone_retention(mut self, tombstone_retention: Duration) -> Self {
+        self.tombstone_retention = tombstone_retention;
+        self
+    }
 }
@@ -0,0 +1,639 @@
+//! Periodic reconciliation of the search index against the source of truth.
+
+use std::time::Duration;
+
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::consistency::entity_source::{EntitySource, SourceEntity};
+use crate::errors::SearchIndexError;
+use crate::interfaces::SearchIndexProvider;
+use crate::types::{
+    GetEntityRequest, IndexedEntityDocument, UnsetEntityPropertiesRequest, UpdateEntityRequest,
+};
+
+/// Configuration for the ConsistencyChecker.
+#[derive(Debug, Clone)]
+pub struct ConsistencyCheckerConfig {
+    /// Number of entities sampled per check. Defaults to 500.
+    pub sample_size: usize,
+    /// Time between checks when running periodically. Defaults to 15 minutes.
+    pub interval: Duration,
+    /// Whether divergent documents are rewritten from the source. Defaults to `false`,
+    /// which only reports divergence.
+    pub repair: bool,
+}
+
+impl Default for ConsistencyCheckerConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 500,
+            interval: Duration::from_secs(15 * 60),
+            repair: false,
+        }
+    }
+}
+
+/// A field whose indexed value differs from the source of truth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    /// Name of the document field.
+    pub field: &'static str,
+    /// Value in the source of truth.
+    pub expected: Option<String>,
+    /// Value in the search index.
+    pub actual: Option<String>,
+}
+
+/// How a search document diverges from the source of truth.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DivergenceKind {
+    /// The entity has no document in the search index.
+    MissingDocument,
+    /// The document exists but some fields differ.
+    FieldMismatch(Vec<FieldDiff>),
+}
+
+/// A search document that diverges from the source of truth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// The entity's unique identifier.
+    pub entity_id: Uuid,
+    /// The space this entity belongs to.
+    pub space_id: Uuid,
+    /// What differs.
+    pub kind: DivergenceKind,
+    /// Whether the document was repaired during this check.
+    pub repaired: bool,
+}
+
+/// Outcome of a single consistency check.
+#[derive(Debug, Clone, Default)]
+pub struct ConsistencyReport {
+    /// Number of entities sampled from the source.
+    pub sampled: usize,
+    /// Documents that diverge from the source.
+    pub divergences: Vec<Divergence>,
+    /// Documents that couldn't be fetched or repaired, with the error.
+    pub failures: Vec<(Uuid, Uuid, SearchIndexError)>,
+}
+
+impl ConsistencyReport {
+    /// Whether every sampled document matched the source.
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty() && self.failures.is_empty()
+    }
+
+    /// Number of documents with no search document.
+    pub fn missing(&self) -> usize {
+        self.divergences
+            .iter()
+            .filter(|divergence| divergence.kind == DivergenceKind::MissingDocument)
+            .count()
+    }
+
+    /// Number of divergent documents that were repaired.
+    pub fn repaired(&self) -> usize {
+        self.divergences
+            .iter()
+            .filter(|divergence| divergence.repaired)
+            .count()
+    }
+
+    /// Fraction of sampled entities whose documents diverge, between 0 and 1.
+    pub fn divergence_rate(&self) -> f64 {
+        if self.sampled == 0 {
+            return 0.0;
+        }
+        self.divergences.len() as f64 / self.sampled as f64
+    }
+}
+
+/// Compares sampled entities from the source of truth against the search index.
+///
+/// # Example
+///
+/// ```ignore
+/// let checker = ConsistencyChecker::new(
+///     Box::new(PostgresEntitySource::new(pool)),
+///     Box::new(provider),
+///     ConsistencyCheckerConfig {
+///         repair: true,
+///         ..Default::default()
+///     },
+/// );
+///
+/// // Check every 15 minutes, forever
+/// checker.run().await;
+/// ```
+pub struct ConsistencyChecker {
+    source: Box<dyn EntitySource>,
+    provider: Box<dyn SearchIndexProvider>,
+    config: ConsistencyCheckerConfig,
+}
+
+impl ConsistencyChecker {
+    /// Create a new checker.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source of truth to sample entities from
+    /// * `provider` - The search index to check
+    /// * `config` - Sample size, interval and repair behavior
+    pub fn new(
+        source: Box<dyn EntitySource>,
+        provider: Box<dyn SearchIndexProvider>,
+        config: ConsistencyCheckerConfig,
+    ) -> Self {
+        Self {
+            source,
+            provider,
+            config,
+        }
+    }
+
+    /// Run a check every `config.interval`, logging each report.
+    ///
+    /// Errors are logged and don't stop the loop. This function never returns.
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.config.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.check_once().await {
+                Ok(report) if report.is_consistent() => {
+                    info!(sampled = report.sampled, "Search index is consistent");
+                }
+                Ok(report) => {
+                    warn!(
+                        sampled = report.sampled,
+                        divergent = report.divergences.len(),
+                        missing = report.missing(),
+                        repaired = report.repaired(),
+                        failures = report.failures.len(),
+                        "Search index diverges from source"
+                    );
+                }
+                Err(e) => {
+                    error!(error = %e, "Consistency check failed");
+                }
+            }
+        }
+    }
+
+    /// Sample entities once and compare them against the search index.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ConsistencyReport)` - The check results, including per-document failures
+    /// * `Err(SearchIndexError)` - If the source couldn't be sampled
+    pub async fn check_once(&self) -> Result<ConsistencyReport, SearchIndexError> {
+        let entities = self.source.sample_entities(self.config.sample_size).await?;
+
+        let mut report = ConsistencyReport {
+            sampled: entities.len(),
+            ..Default::default()
+        };
+
+        for entity in entities {
+            let request = GetEntityRequest {
+                entity_id: entity.entity_id.to_string(),
+                space_id: entity.space_id.to_string(),
+            };
+
+            let kind = match self.provider.get_document(&request).await {
+                // A soft-deleted document is hidden from search like a missing one
+                Ok(None) => DivergenceKind::MissingDocument,
+                Ok(Some(document)) if document.deleted => DivergenceKind::MissingDocument,
+                Ok(Some(document)) => {
+                    let diffs = diff_fields(&entity, &document);
+                    if diffs.is_empty() {
+                        continue;
+                    }
+                    DivergenceKind::FieldMismatch(diffs)
+                }
+                Err(e) => {
+                    report.failures.push((entity.entity_id, entity.space_id, e));
+                    continue;
+                }
+            };
+
+            warn!(
+                entity_id = %entity.entity_id,
+                space_id = %entity.space_id,
+                divergence = ?kind,
+                "Search document diverges from source"
+            );
+
+            let mut repaired = false;
+            if self.config.repair {
+                match self.repair(&entity, &kind).await {
+                    Ok(()) => repaired = true,
+                    Err(e) => report.failures.push((entity.entity_id, entity.space_id, e)),
+                }
+            }
+
+            report.divergences.push(Divergence {
+                entity_id: entity.entity_id,
+                space_id: entity.space_id,
+                kind,
+                repaired,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Rewrite the document's fields from the source, removing fields the source
+    /// doesn't have.
+    async fn repair(
+        &self,
+        entity: &SourceEntity,
+        kind: &DivergenceKind,
+    ) -> Result<(), SearchIndexError> {
+        let entity_id = entity.entity_id.to_string();
+        let space_id = entity.space_id.to_string();
+
+        self.provider
+            .update_document(&UpdateEntityRequest {
+                entity_id: entity_id.clone(),
+                space_id: space_id.clone(),
+                name: entity.name.clone(),
+                description: entity.description.clone(),
+                avatar: None,
+                cover: None,
+                entity_global_score: None,
+                space_score: None,
+                entity_space_score: None,
+                edited_at: None,
+                edit_version: None,
+                name_embedding: None,
+                description_embedding: None,
+                language: None,
+                score: None,
+                trust_rank: None,
+            })
+            .await?;
+
+        let stale_fields: Vec<String> = match kind {
+            DivergenceKind::MissingDocument => vec![],
+            DivergenceKind::FieldMismatch(diffs) => diffs
+                .iter()
+                .filter(|diff| diff.expected.is_none())
+                .map(|diff| diff.field.to_string())
+                .collect(),
+        };
+
+        if !stale_fields.is_empty() {
+            self.provider
+                .unset_document_properties(&UnsetEntityPropertiesRequest {
+                    entity_id,
+                    space_id,
+                    property_keys: stale_fields,
+                    edited_at: None,
+                })
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare the fields mirrored from the source of truth.
+fn diff_fields(entity: &SourceEntity, document: &IndexedEntityDocument) -> Vec<FieldDiff> {
+    [
+        ("name", &entity.name, &document.name),
+        ("description", &entity.description, &document.description),
+    ]
+    .into_iter()
+    .filter(|(_, expected, actual)| expected != actual)
+    .map(|(field, expected, actual)| FieldDiff {
+        field,
+        expected: expected.clone(),
+        actual: actual.clone(),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        BatchOperationSummary, DeleteEntityRequest, RestoreEntitiesRequest, SearchRequest,
+        SearchResult, SemanticSearchRequest, VoteCountUpdate,
+    };
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    struct MockSource {
+        entities: Vec<SourceEntity>,
+    }
+
+    #[async_trait]
+    impl EntitySource for MockSource {
+        async fn sample_entities(
+            &self,
+            limit: usize,
+        ) -> Result<Vec<SourceEntity>, SearchIndexError> {
+            Ok(self.entities.iter().take(limit).cloned().collect())
+        }
+    }
+
+    type Documents = Arc<Mutex<HashMap<(String, String), IndexedEntityDocument>>>;
+
+    /// In-memory provider that applies updates and unsets to stored documents.
+    struct InMemoryProvider {
+        documents: Documents,
+    }
+
+    #[async_trait]
+    impl SearchIndexProvider for InMemoryProvider {
+        async fn update_document(
+            &self,
+            request: &UpdateEntityRequest,
+        ) -> Result<(), SearchIndexError> {
+            let mut documents = self.documents.lock().unwrap();
+            let document = documents
+                .entry((request.entity_id.clone(), request.space_id.clone()))
+                .or_default();
+            if request.name.is_some() {
+                document.name = request.name.clone();
+            }
+            if request.description.is_some() {
+                document.description = request.description.clone();
+            }
+            document.deleted = false;
+            document.deleted_at = None;
+            Ok(())
+        }
+
+        async fn delete_document(
+            &self,
+            _request: &DeleteEntityRequest,
+        ) -> Result<(), SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn soft_delete_document(
+            &self,
+            _request: &DeleteEntityRequest,
+        ) -> Result<(), SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn get_document(
+            &self,
+            request: &GetEntityRequest,
+        ) -> Result<Option<IndexedEntityDocument>, SearchIndexError> {
+            let documents = self.documents.lock().unwrap();
+            Ok(documents
+                .get(&(request.entity_id.clone(), request.space_id.clone()))
+                .cloned())
+        }
+
+        async fn bulk_update_documents(
+            &self,
+            _requests: &[UpdateEntityRequest],
+        ) -> Result<BatchOperationSummary, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn bulk_delete_documents(
+            &self,
+            _requests: &[DeleteEntityRequest],
+        ) -> Result<BatchOperationSummary, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn bulk_soft_delete_documents(
+            &self,
+            _requests: &[DeleteEntityRequest],
+        ) -> Result<BatchOperationSummary, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn restore_documents(
+            &self,
+            _request: &RestoreEntitiesRequest,
+        ) -> Result<u64, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn purge_deleted_documents(
+            &self,
+            _deleted_before: DateTime<Utc>,
+        ) -> Result<u64, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn unset_document_properties(
+            &self,
+            request: &UnsetEntityPropertiesRequest,
+        ) -> Result<(), SearchIndexError> {
+            let mut documents = self.documents.lock().unwrap();
+            let document = documents
+                .get_mut(&(request.entity_id.clone(), request.space_id.clone()))
+                .ok_or_else(|| {
+                    SearchIndexError::document_not_found(&request.entity_id, &request.space_id)
+                })?;
+            for key in &request.property_keys {
+                match key.as_str() {
+                    "name" => document.name = None,
+                    "description" => document.description = None,
+                    _ => {}
+                }
+            }
+            Ok(())
+        }
+
+        async fn semantic_search(
+            &self,
+            _request: &SemanticSearchRequest,
+        ) -> Result<Vec<SearchResult>, SearchIndexError> {
+            Ok(Vec::new())
+        }
+
+        async fn search(
+            &self,
+            _request: &SearchRequest,
+        ) -> Result<Vec<SearchResult>, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn delete_space_documents(&self, _space_id: &str) -> Result<u64, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn soft_delete_space_documents(
+            &self,
+            _space_id: &str,
+            _deleted_at: DateTime<Utc>,
+        ) -> Result<u64, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn bulk_update_vote_counts(
+            &self,
+            _updates: &[VoteCountUpdate],
+        ) -> Result<BatchOperationSummary, SearchIndexError> {
+            unimplemented!()
+        }
+
+        async fn set_space_trust_rank(
+            &self,
+            _space_id: &str,
+            _trust_rank: f64,
+        ) -> Result<u64, SearchIndexError> {
+            unimplemented!()
+        }
+    }
+
+    fn source_entity(name: Option<&str>, description: Option<&str>) -> SourceEntity {
+        SourceEntity {
+            entity_id: Uuid::new_v4(),
+            space_id: Uuid::new_v4(),
+            name: name.map(str::to_string),
+            description: description.map(str::to_string),
+        }
+    }
+
+    fn indexed(name: Option<&str>, description: Option<&str>) -> IndexedEntityDocument {
+        IndexedEntityDocument {
+            name: name.map(str::to_string),
+            description: description.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn key(entity: &SourceEntity) -> (String, String) {
+        (entity.entity_id.to_string(), entity.space_id.to_string())
+    }
+
+    fn checker(
+        entities: Vec<SourceEntity>,
+        documents: Documents,
+        repair: bool,
+    ) -> ConsistencyChecker {
+        ConsistencyChecker::new(
+            Box::new(MockSource { entities }),
+            Box::new(InMemoryProvider { documents }),
+            ConsistencyCheckerConfig {
+                repair,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_missing_and_mismatched_documents() {
+        let consistent = source_entity(Some("Alice"), None);
+        let missing = source_entity(Some("Bob"), None);
+        let mismatched = source_entity(Some("Carol"), Some("Engineer"));
+
+        let documents = Documents::default();
+        {
+            let mut docs = documents.lock().unwrap();
+            docs.insert(key(&consistent), indexed(Some("Alice"), None));
+            docs.insert(
+                key(&mismatched),
+                indexed(Some("Caroline"), Some("Engineer")),
+            );
+        }
+
+        let report = checker(
+            vec![consistent, missing.clone(), mismatched.clone()],
+            documents,
+            false,
+        )
+        .check_once()
+        .await
+        .unwrap();
+
+        assert_eq!(report.sampled, 3);
+        assert_eq!(report.divergences.len(), 2);
+        assert_eq!(report.missing(), 1);
+        assert_eq!(report.repaired(), 0);
+        assert!(!report.is_consistent());
+
+        assert_eq!(report.divergences[0].entity_id, missing.entity_id);
+        assert_eq!(
+            report.divergences[1].kind,
+            DivergenceKind::FieldMismatch(vec![FieldDiff {
+                field: "name",
+                expected: Some("Carol".to_string()),
+                actual: Some("Caroline".to_string()),
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repair_rewrites_divergent_documents() {
+        let missing = source_entity(Some("Bob"), Some("Builder"));
+        let stale = source_entity(Some("Carol"), None);
+
+        let documents = Documents::default();
+        documents
+            .lock()
+            .unwrap()
+            .insert(key(&stale), indexed(Some("Caroline"), Some("Old bio")));
+
+        let checker = checker(
+            vec![missing.clone(), stale.clone()],
+            documents.clone(),
+            true,
+        );
+        let report = checker.check_once().await.unwrap();
+
+        assert_eq!(report.divergences.len(), 2);
+        assert_eq!(report.repaired(), 2);
+        assert!(report.failures.is_empty());
+
+        {
+            let docs = documents.lock().unwrap();
+            assert_eq!(docs[&key(&missing)], indexed(Some("Bob"), Some("Builder")));
+            assert_eq!(docs[&key(&stale)], indexed(Some("Carol"), None));
+        }
+
+        assert!(checker.check_once().await.unwrap().is_consistent());
+    }
+
+    #[tokio::test]
+    async fn test_soft_deleted_document_of_existing_entity_is_missing() {
+        let entity = source_entity(Some("Alice"), None);
+
+        let documents = Documents::default();
+        documents.lock().unwrap().insert(
+            key(&entity),
+            IndexedEntityDocument {
+                deleted: true,
+                deleted_at: Some(Utc::now()),
+                ..indexed(Some("Alice"), None)
+            },
+        );
+
+        let checker = checker(vec![entity.clone()], documents.clone(), true);
+        let report = checker.check_once().await.unwrap();
+
+        assert_eq!(report.missing(), 1);
+        assert_eq!(report.repaired(), 1);
+        assert_eq!(
+            documents.lock().unwrap()[&key(&entity)],
+            indexed(Some("Alice"), None)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_empty_sample_is_consistent() {
+        let report = checker(vec![], Documents::default(), false)
+            .check_once()
+            .await
+            .unwrap();
+
+        assert_eq!(report.sampled, 0);
+        assert_eq!(report.divergence_rate(), 0.0);
+        assert!(report.is_consistent());
+    }
+}
@@ -0,0 +1,106 @@
+//! Sources of truth for entity fields that are mirrored into the search index.
+
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use uuid::{uuid, Uuid};
+
+use crate::errors::SearchIndexError;
+
+/// Property ID of the GRC-20 `Name` property.
+pub const NAME_PROPERTY_ID: Uuid = uuid!("a126ca53-0c8e-48d5-b888-82c734c38935");
+
+/// Property ID of the GRC-20 `Description` property.
+pub const DESCRIPTION_PROPERTY_ID: Uuid = uuid!("9b1f76ff-9711-404c-861e-59dc3fa7d037");
+
+/// The indexed fields of an entity in a space, as stored in the source of truth.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceEntity {
+    /// The entity's unique identifier.
+    pub entity_id: Uuid,
+    /// The space this entity belongs to.
+    pub space_id: Uuid,
+    /// The entity's display name.
+    pub name: Option<String>,
+    /// Optional description text.
+    pub description: Option<String>,
+}
+
+/// Provides samples of entities from the source of truth for consistency checks.
+#[async_trait]
+pub trait EntitySource: Send + Sync {
+    /// Return up to `limit` randomly sampled entities.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of entities to return
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SourceEntity>)` - The sampled entities
+    /// * `Err(SearchIndexError)` - If the source can't be queried
+    async fn sample_entities(&self, limit: usize) -> Result<Vec<SourceEntity>, SearchIndexError>;
+}
+
+/// Samples entities from the knowledge graph Postgres store.
+///
+/// Only entities with a name or description in a space are sampled, since those are
+/// the entities the search indexer writes documents for.
+pub struct PostgresEntitySource {
+    pool: PgPool,
+    name_property_id: Uuid,
+    description_property_id: Uuid,
+}
+
+impl PostgresEntitySource {
+    /// Create a source reading the GRC-20 name and description properties.
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            name_property_id: NAME_PROPERTY_ID,
+            description_property_id: DESCRIPTION_PROPERTY_ID,
+        }
+    }
+
+    /// Use different property IDs for the name and description fields.
+    pub fn with_property_ids(mut self, name: Uuid, description: Uuid) -> Self {
+        self.name_property_id = name;
+        self.description_property_id = description;
+        self
+    }
+}
+
+#[async_trait]
+impl EntitySource for PostgresEntitySource {
+    async fn sample_entities(&self, limit: usize) -> Result<Vec<SourceEntity>, SearchIndexError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                entity_id,
+                space_id,
+                MAX(string) FILTER (WHERE property_id = $1) AS name,
+                MAX(string) FILTER (WHERE property_id = $2) AS description
+            FROM values
+            WHERE property_id IN ($1, $2)
+            GROUP BY entity_id, space_id
+            ORDER BY random()
+            LIMIT $3
+            "#,
+        )
+        .bind(self.name_property_id)
+        .bind(self.description_property_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| SearchIndexError::connection(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SourceEntity {
+                entity_id: row.get("entity_id"),
+                space_id: row.get("space_id"),
+                name: row.get("name"),
+                description: row.get("description"),
+            })
+            .collect())
+    }
+}
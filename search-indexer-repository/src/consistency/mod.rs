@@ -0,0 +1,18 @@
+//! Consistency checking between the knowledge graph and the search index.
+//!
+//! The search index is derived from the knowledge graph Postgres store, which is the
+//! source of truth. This module samples entities from an `EntitySource`, fetches the
+//! corresponding search documents, and reports (and optionally repairs) documents that
+//! are missing or have diverged. This catches silent processor or loader bugs that
+//! would otherwise leave stale results in search.
+
+mod checker;
+mod entity_source;
+
+pub use checker::{
+    ConsistencyChecker, ConsistencyCheckerConfig, ConsistencyReport, Divergence, DivergenceKind,
+    FieldDiff,
+};
+pub use entity_source::{
+    EntitySource, PostgresEntitySource, SourceEntity, DESCRIPTION_PROPERTY_ID, NAME_PROPERTY_ID,
+};
@@ -0,0 +1,223 @@
+//! HTTP embedding provider implementation.
+//!
+//! This module provides an implementation of `EmbeddingProvider` for services exposing
+//! the OpenAI embeddings API, e.g. text-embeddings-inference serving a local ONNX model.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::{debug, error};
+
+use crate::errors::SearchIndexError;
+use crate::interfaces::EmbeddingProvider;
+
+/// An embedding of an embeddings response.
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Body of an embeddings response.
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+/// Embedding provider calling an OpenAI-compatible `/embeddings` endpoint.
+///
+/// # Example
+///
+/// ```ignore
+/// use search_indexer_repository::embedding::HttpEmbeddingProvider;
+///
+/// let embedder = HttpEmbeddingProvider::new(
+///     "http://localhost:8080/v1/embeddings",
+///     Some("sentence-transformers/all-MiniLM-L6-v2".to_string()),
+///     384,
+/// )?;
+/// let service = SearchIndexService::new(provider).with_embedding_provider(Box::new(embedder));
+/// ```
+pub struct HttpEmbeddingProvider {
+    client: reqwest::Client,
+    url: String,
+    model: Option<String>,
+    dimension: usize,
+    api_key: Option<String>,
+}
+
+impl HttpEmbeddingProvider {
+    /// Create a new HTTP embedding provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the embeddings endpoint (e.g., "http://localhost:8080/v1/embeddings")
+    /// * `model` - The model to request, if the service serves several
+    /// * `dimension` - The dimension of the embeddings of the model
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HttpEmbeddingProvider)` - A new provider instance
+    /// * `Err(SearchIndexError)` - If the URL is invalid or the client can't be built
+    pub fn new(
+        url: &str,
+        model: Option<String>,
+        dimension: usize,
+    ) -> Result<Self, SearchIndexError> {
+        url::Url::parse(url).map_err(|e| SearchIndexError::connection(e.to_string()))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| SearchIndexError::connection(e.to_string()))?;
+
+        Ok(Self {
+            client,
+            url: url.to_string(),
+            model,
+            dimension,
+            api_key: None,
+        })
+    }
+
+    /// Send `api_key` as a bearer token with each request.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Build the body of an embeddings request.
+    fn request_body(&self, texts: &[String]) -> Value {
+        let mut body = json!({ "input": texts });
+        if let Some(ref model) = self.model {
+            body["model"] = json!(model);
+        }
+        body
+    }
+
+    /// Read the embeddings of a response in the order of the texts of the request.
+    fn response_embeddings(
+        response: EmbeddingsResponse,
+        texts: usize,
+    ) -> Result<Vec<Vec<f32>>, SearchIndexError> {
+        let mut embeddings = vec![None; texts];
+        for data in response.data {
+            let slot = embeddings.get_mut(data.index).ok_or_else(|| {
+                SearchIndexError::embedding(format!(
+                    "Got an embedding for text {} of {}",
+                    data.index, texts
+                ))
+            })?;
+            *slot = Some(data.embedding);
+        }
+
+        embeddings
+            .into_iter()
+            .enumerate()
+            .map(|(index, embedding)| {
+                embedding.ok_or_else(|| {
+                    SearchIndexError::embedding(format!("Got no embedding for text {}", index))
+                })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HttpEmbeddingProvider {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    /// Compute the embeddings of texts in a single request.
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, SearchIndexError> {
+        let mut request = self.client.post(&self.url).json(&self.request_body(texts));
+        if let Some(ref api_key) = self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::embedding(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Embeddings request failed");
+            return Err(SearchIndexError::embedding(format!(
+                "Embeddings request failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: EmbeddingsResponse = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let embeddings = Self::response_embeddings(body, texts.len())?;
+
+        debug!(texts = texts.len(), "Texts embedded");
+        Ok(embeddings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body() {
+        let texts = vec!["Entity".to_string()];
+
+        let provider =
+            HttpEmbeddingProvider::new("http://localhost:8080/v1/embeddings", None, 2).unwrap();
+        assert_eq!(
+            provider.request_body(&texts),
+            json!({ "input": ["Entity"] })
+        );
+
+        let provider = HttpEmbeddingProvider::new(
+            "http://localhost:8080/v1/embeddings",
+            Some("model".to_string()),
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            provider.request_body(&texts),
+            json!({ "input": ["Entity"], "model": "model" })
+        );
+    }
+
+    #[test]
+    fn test_response_embeddings() {
+        let response: EmbeddingsResponse = serde_json::from_value(json!({
+            "data": [
+                { "index": 1, "embedding": [0.5, 0.5] },
+                { "index": 0, "embedding": [0.25, 0.75] }
+            ]
+        }))
+        .unwrap();
+
+        // Ordered by index
+        assert_eq!(
+            HttpEmbeddingProvider::response_embeddings(response, 2).unwrap(),
+            vec![vec![0.25, 0.75], vec![0.5, 0.5]]
+        );
+
+        let missing: EmbeddingsResponse = serde_json::from_value(json!({
+            "data": [{ "index": 0, "embedding": [0.25, 0.75] }]
+        }))
+        .unwrap();
+        assert!(matches!(
+            HttpEmbeddingProvider::response_embeddings(missing, 2),
+            Err(SearchIndexError::EmbeddingError(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_url() {
+        assert!(HttpEmbeddingProvider::new("not a url", None, 2).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+//! Embedding providers computing the embeddings used by semantic search.
+//!
+//! This module provides `HttpEmbeddingProvider`, an implementation of `EmbeddingProvider`
+//! calling an embedding service over HTTP.
+
+mod http_provider;
+
+pub use http_provider::HttpEmbeddingProvider;
@@ -52,6 +52,18 @@ pub enum SearchIndexError {
     #[error("Document not found: {0}")]
     DocumentNotFound(String),
 
+    /// A reindex step failed, e.g. the alias points to another index than expected.
+    #[error("Reindex error: {0}")]
+    ReindexError(String),
+
+    /// Failed to compute embeddings, or the embeddings don't match the expected dimension.
+    #[error("Embedding error: {0}")]
+    EmbeddingError(String),
+
+    /// A search query failed.
+    #[error("Search error: {0}")]
+    SearchError(String),
+
     /// Batch size exceeds configured maximum.
     #[error("Batch size {provided} exceeds maximum {max}")]
     BatchSizeExceeded { provided: usize, max: usize },
@@ -117,6 +129,21 @@ impl SearchIndexError {
         Self::BulkIndexError(msg.into())
     }
 
+    /// Create a reindex error.
+    pub fn reindex(msg: impl Into<String>) -> Self {
+        Self::ReindexError(msg.into())
+    }
+
+    /// Create an embedding error.
+    pub fn embedding(msg: impl Into<String>) -> Self {
+        Self::EmbeddingError(msg.into())
+    }
+
+    /// Create a search error.
+    pub fn search(msg: impl Into<String>) -> Self {
+        Self::SearchError(msg.into())
+    }
+
     /// Create a batch size exceeded error.
     pub fn batch_size_exceeded(provided: usize, max: usize) -> Self {
         Self::BatchSizeExceeded { provided, max }
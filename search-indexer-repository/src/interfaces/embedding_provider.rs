@@ -0,0 +1,32 @@
+//! Embedding provider trait definition.
+//!
+//! This module defines the abstract interface for computing the embeddings stored with
+//! entity documents for semantic search, e.g. with a local ONNX model or an HTTP service.
+
+use async_trait::async_trait;
+
+use crate::errors::SearchIndexError;
+
+/// Computes embeddings of texts.
+///
+/// Implementations are injected into `SearchIndexService`, which embeds the name and
+/// description of updated documents and the text of semantic search queries. All the
+/// embeddings of a provider have the same dimension, which must be the one of the
+/// indexed embeddings.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Get the dimension of the embeddings.
+    fn dimension(&self) -> usize;
+
+    /// Compute the embeddings of texts.
+    ///
+    /// # Arguments
+    ///
+    /// * `texts` - The texts to embed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Vec<f32>>)` - An embedding per text, in the order of `texts`
+    /// * `Err(SearchIndexError)` - If the embeddings can't be computed
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, SearchIndexError>;
+}
@@ -1,8 +1,14 @@
 //! Interface definitions for the search index provider.
 //!
 //! This module defines the abstract `SearchIndexProvider` trait that allows
-//! for dependency injection and swappable search backend implementations.
+//! for dependency injection and swappable search backend implementations, and
+//! the `ReindexProvider` trait managing the versioned indices behind the alias, and the
+//! `EmbeddingProvider` trait computing the embeddings used by semantic search.
 
+mod embedding_provider;
+mod reindex_provider;
 mod search_index_provider;
 
+pub use embedding_provider::EmbeddingProvider;
+pub use reindex_provider::ReindexProvider;
 pub use search_index_provider::SearchIndexProvider;
@@ -0,0 +1,88 @@
+//! Reindex provider trait definition.
+//!
+//! This module defines the abstract interface for managing the versioned indices the
+//! search alias points to, used by `ReindexService` to rebuild the index without downtime.
+
+use async_trait::async_trait;
+
+use crate::errors::SearchIndexError;
+
+/// Manages the versioned indices behind the search index alias.
+///
+/// Documents are read and written through an alias, which points to a single versioned
+/// index (e.g. `entities_v0`). Changing the mappings takes a new version: the index is
+/// created, filled from the current one, then the alias is moved to it.
+#[async_trait]
+pub trait ReindexProvider: Send + Sync {
+    /// Get the version of the index the alias points to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(u32))` - The version of the aliased index
+    /// * `Ok(None)` - If the alias doesn't exist yet
+    /// * `Err(SearchIndexError)` - If the alias points to several indices or the lookup fails
+    async fn aliased_version(&self) -> Result<Option<u32>, SearchIndexError>;
+
+    /// Create an empty index for a version, with the settings and mappings of that version.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the index to create
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the index was created
+    /// * `Err(SearchIndexError)` - If the index exists or the creation fails
+    async fn create_index(&self, version: u32) -> Result<(), SearchIndexError>;
+
+    /// Copy the documents of an index into another, skipping documents the destination
+    /// already has.
+    ///
+    /// Documents written to the destination while the copy runs are newer than the ones
+    /// copied, so they are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_version` - The version of the index to copy from
+    /// * `to_version` - The version of the index to copy to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of copied documents
+    /// * `Err(SearchIndexError)` - If the copy fails
+    async fn copy_documents(
+        &self,
+        from_version: u32,
+        to_version: u32,
+    ) -> Result<u64, SearchIndexError>;
+
+    /// Move the alias from an index to another in a single atomic operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_version` - The version the alias points to, `None` if it doesn't exist yet
+    /// * `to_version` - The version the alias is moved to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the alias points to `to_version`
+    /// * `Err(SearchIndexError)` - If the alias doesn't point to `from_version`, leaving it
+    ///   unchanged, or the operation fails
+    async fn switch_alias(
+        &self,
+        from_version: Option<u32>,
+        to_version: u32,
+    ) -> Result<(), SearchIndexError>;
+
+    /// Delete the index of a version.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the index to delete
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the index was deleted (or didn't exist)
+    /// * `Err(SearchIndexError)` - If the deletion fails
+    async fn delete_index(&self, version: u32) -> Result<(), SearchIndexError>;
+}
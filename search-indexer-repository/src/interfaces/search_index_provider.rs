@@ -4,10 +4,13 @@
 //! allowing for different backend implementations (OpenSearch, Elasticsearch, etc.).
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use crate::errors::SearchIndexError;
 use crate::types::{
-    BatchOperationSummary, DeleteEntityRequest, UnsetEntityPropertiesRequest, UpdateEntityRequest,
+    BatchOperationSummary, DeleteEntityRequest, GetEntityRequest, IndexedEntityDocument,
+    RestoreEntitiesRequest, SearchRequest, SearchResult, SemanticSearchRequest,
+    UnsetEntityPropertiesRequest, UpdateEntityRequest, VoteCountUpdate,
 };
 
 /// Abstracts the underlying search index implementation (OpenSearch, Elasticsearch, etc.).
@@ -31,7 +34,10 @@ pub trait SearchIndexProvider: Send + Sync {
     /// This function performs an upsert operation: if the document exists, only fields that are
     /// `Some` in the request will be updated; if the document doesn't exist, it will be created
     /// with the provided fields. Fields that are `None` in the request will be left unchanged
-    /// (for existing documents) or omitted (for new documents).
+    /// (for existing documents) or omitted (for new documents). Updating a soft-deleted document
+    /// restores it, the same way an update after a hard delete recreates it, unless the update's
+    /// `edited_at` is older than the deletion. An update whose `edit_version` isn't above the
+    /// version of the document is dropped, so redelivered edits are applied once.
     ///
     /// # Arguments
     ///
@@ -45,7 +51,8 @@ pub trait SearchIndexProvider: Send + Sync {
 
     /// Delete a document from the search index.
     ///
-    /// If the document doesn't exist, the operation is considered successful.
+    /// If the document doesn't exist, the operation is considered successful. With `edited_at`,
+    /// the document is replaced by a tombstone instead, which drops updates from older edits.
     ///
     /// # Arguments
     ///
@@ -57,6 +64,39 @@ pub trait SearchIndexProvider: Send + Sync {
     /// * `Err(SearchIndexError)` - If the deletion fails
     async fn delete_document(&self, request: &DeleteEntityRequest) -> Result<(), SearchIndexError>;
 
+    /// Flag a document as deleted so it is excluded from search, keeping it restorable.
+    ///
+    /// If the document doesn't exist, a tombstone is created in its place.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The delete request containing entity_id and space_id
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the document was flagged, or its tombstone created
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn soft_delete_document(
+        &self,
+        request: &DeleteEntityRequest,
+    ) -> Result<(), SearchIndexError>;
+
+    /// Fetch the stored fields of a document from the search index.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The get request containing entity_id and space_id
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(IndexedEntityDocument))` - If the document exists
+    /// * `Ok(None)` - If the document doesn't exist
+    /// * `Err(SearchIndexError)` - If the lookup fails
+    async fn get_document(
+        &self,
+        request: &GetEntityRequest,
+    ) -> Result<Option<IndexedEntityDocument>, SearchIndexError>;
+
     /// Update multiple documents in bulk and return a summary of successful and failed operations.
     ///
     /// Processes each update request individually and collects results. Returns a summary
@@ -78,7 +118,8 @@ pub trait SearchIndexProvider: Send + Sync {
     /// Delete multiple documents in bulk and return a summary of successful and failed operations.
     ///
     /// Processes each delete request individually and collects results. Documents that don't
-    /// exist are considered successful deletions.
+    /// exist are considered successful deletions, or replaced by tombstones for requests with
+    /// `edited_at`.
     ///
     /// # Arguments
     ///
@@ -93,6 +134,54 @@ pub trait SearchIndexProvider: Send + Sync {
         requests: &[DeleteEntityRequest],
     ) -> Result<BatchOperationSummary, SearchIndexError>;
 
+    /// Soft-delete multiple documents and return a summary of successful and failed operations.
+    ///
+    /// Processes each delete request individually and collects results. Documents that don't
+    /// exist are replaced by tombstones.
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - Slice of delete requests
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BatchOperationSummary)` - Contains aggregate statistics and individual results
+    /// * `Err(SearchIndexError)` - If the bulk operation fails entirely
+    async fn bulk_soft_delete_documents(
+        &self,
+        requests: &[DeleteEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError>;
+
+    /// Clear the deleted flag of every soft-deleted document matching the request's filters.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The restore request with the entity, space and deletion time filters
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of restored documents
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn restore_documents(
+        &self,
+        request: &RestoreEntitiesRequest,
+    ) -> Result<u64, SearchIndexError>;
+
+    /// Remove soft-deleted documents deleted before `deleted_before` from the index.
+    ///
+    /// # Arguments
+    ///
+    /// * `deleted_before` - Documents soft-deleted before this time are removed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of removed documents
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn purge_deleted_documents(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError>;
+
     /// Unset (remove) specific properties from a document.
     ///
     /// This function removes the specified property keys from a document. If a property
@@ -110,4 +199,104 @@ pub trait SearchIndexProvider: Send + Sync {
         &self,
         request: &UnsetEntityPropertiesRequest,
     ) -> Result<(), SearchIndexError>;
+
+    /// Find the documents nearest to a query embedding.
+    ///
+    /// Documents are scored on the similarity of the query to the embeddings of their name
+    /// and description. Documents without embeddings, deleted documents and tombstones are
+    /// never returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The semantic search request containing the query vector, k, and an
+    ///   optional space filter
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SearchResult>)` - Up to `k` documents, the closest first
+    /// * `Err(SearchIndexError)` - If the search fails
+    async fn semantic_search(
+        &self,
+        request: &SemanticSearchRequest,
+    ) -> Result<Vec<SearchResult>, SearchIndexError>;
+
+    /// Find the documents whose name or description match a text query.
+    ///
+    /// Deleted documents and tombstones are never returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The search request containing the query, an optional space filter,
+    ///   and the maximum number of documents
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SearchResult>)` - Up to `limit` documents, the most relevant first
+    /// * `Err(SearchIndexError)` - If the search fails
+    async fn search(&self, request: &SearchRequest) -> Result<Vec<SearchResult>, SearchIndexError>;
+
+    /// Delete every document of a space, e.g. when the space is removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - The space whose documents to delete
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of deleted documents
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn delete_space_documents(&self, space_id: &str) -> Result<u64, SearchIndexError>;
+
+    /// Flag every document of a space as deleted at `deleted_at`, keeping them
+    /// restorable like documents deleted one by one.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - The space whose documents to flag
+    /// * `deleted_at` - The deletion time to record on the documents
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of flagged documents
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn soft_delete_space_documents(
+        &self,
+        space_id: &str,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError>;
+
+    /// Set the vote score of documents from the new vote totals of their entity.
+    ///
+    /// Only existing documents are updated: totals for a document that doesn't exist are
+    /// successful no-ops, and deleted documents stay deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `updates` - Slice of vote totals
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BatchOperationSummary)` - Contains aggregate statistics and individual results
+    /// * `Err(SearchIndexError)` - If the bulk operation fails entirely
+    async fn bulk_update_vote_counts(
+        &self,
+        updates: &[VoteCountUpdate],
+    ) -> Result<BatchOperationSummary, SearchIndexError>;
+
+    /// Set the trust rank of every document of a space.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - The space whose documents to update
+    /// * `trust_rank` - The trust rank of the space
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of updated documents
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn set_space_trust_rank(
+        &self,
+        space_id: &str,
+        trust_rank: f64,
+    ) -> Result<u64, SearchIndexError>;
 }
@@ -0,0 +1,169 @@
+//! Languages of entity names and descriptions.
+//!
+//! Names and descriptions are indexed once per configured [`Language`], each with the
+//! analyzer of that language, so stemming and stop words match the language of the query.
+//! The language of an edit comes from the `language` field of `HermesEdit`, decoded with
+//! [`edit_language`], and is otherwise detected from the script of the text with
+//! [`Language::detect`].
+
+/// A language with a dedicated analyzer in the search index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    French,
+    German,
+    Spanish,
+    Portuguese,
+    Italian,
+    Dutch,
+    Russian,
+    Arabic,
+    Chinese,
+    Japanese,
+    Korean,
+}
+
+impl Language {
+    /// Every supported language, the default of `IndexConfig::languages`.
+    pub const ALL: [Language; 12] = [
+        Language::English,
+        Language::French,
+        Language::German,
+        Language::Spanish,
+        Language::Portuguese,
+        Language::Italian,
+        Language::Dutch,
+        Language::Russian,
+        Language::Arabic,
+        Language::Chinese,
+        Language::Japanese,
+        Language::Korean,
+    ];
+
+    /// The ISO 639-1 code of the language, recorded in the `language` field of documents.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Spanish => "es",
+            Language::Portuguese => "pt",
+            Language::Italian => "it",
+            Language::Dutch => "nl",
+            Language::Russian => "ru",
+            Language::Arabic => "ar",
+            Language::Chinese => "zh",
+            Language::Japanese => "ja",
+            Language::Korean => "ko",
+        }
+    }
+
+    /// The analyzer of the language, which also names the subfields it indexes.
+    ///
+    /// Chinese, Japanese and Korean share the `cjk` analyzer, which indexes bigrams as
+    /// their words aren't separated by spaces.
+    pub fn analyzer(self) -> &'static str {
+        match self {
+            Language::English => "english",
+            Language::French => "french",
+            Language::German => "german",
+            Language::Spanish => "spanish",
+            Language::Portuguese => "portuguese",
+            Language::Italian => "italian",
+            Language::Dutch => "dutch",
+            Language::Russian => "russian",
+            Language::Arabic => "arabic",
+            Language::Chinese | Language::Japanese | Language::Korean => "cjk",
+        }
+    }
+
+    /// Parse a language tag, e.g. `en`, `pt-BR` or `zh_Hans`.
+    ///
+    /// Only the primary language subtag is considered, case-insensitively.
+    ///
+    /// # Returns
+    ///
+    /// The language of the tag, or `None` if it isn't supported.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code() == primary)
+    }
+
+    /// Detect the language of a text from its script.
+    ///
+    /// Only scripts used by a single supported language are recognized: Latin-script
+    /// languages can't be told apart this way and need the language of their edit.
+    ///
+    /// # Returns
+    ///
+    /// The language of the first character of a recognized script, Han characters only
+    /// counting as Chinese in texts without kana, or `None`.
+    pub fn detect(text: &str) -> Option<Self> {
+        let mut han = false;
+        for c in text.chars() {
+            match c {
+                // Kana is specific to Japanese, which also uses Han characters
+                '\u{3040}'..='\u{30FF}' => return Some(Language::Japanese),
+                '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => return Some(Language::Korean),
+                '\u{0400}'..='\u{04FF}' => return Some(Language::Russian),
+                '\u{0600}'..='\u{06FF}' => return Some(Language::Arabic),
+                '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => han = true,
+                _ => {}
+            }
+        }
+        han.then_some(Language::Chinese)
+    }
+}
+
+/// Decode the `language` field of a `HermesEdit`, a UTF-8 language tag.
+///
+/// # Returns
+///
+/// The normalized code of the language, or `None` if the edit has no language, or one
+/// that isn't valid UTF-8 or isn't supported.
+pub fn edit_language(language: Option<&[u8]>) -> Option<String> {
+    let tag = std::str::from_utf8(language?).ok()?;
+    Language::from_tag(tag).map(|language| language.code().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_tag() {
+        assert_eq!(Language::from_tag("en"), Some(Language::English));
+        assert_eq!(Language::from_tag("pt-BR"), Some(Language::Portuguese));
+        assert_eq!(Language::from_tag("ZH_Hans"), Some(Language::Chinese));
+        assert_eq!(Language::from_tag("xx"), None);
+        assert_eq!(Language::from_tag(""), None);
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Language::detect("Москва"), Some(Language::Russian));
+        assert_eq!(Language::detect("東京タワー"), Some(Language::Japanese));
+        assert_eq!(Language::detect("北京"), Some(Language::Chinese));
+        assert_eq!(Language::detect("서울"), Some(Language::Korean));
+        assert_eq!(Language::detect("القاهرة"), Some(Language::Arabic));
+        // Latin script is shared by too many languages
+        assert_eq!(Language::detect("Paris"), None);
+    }
+
+    #[test]
+    fn test_edit_language() {
+        assert_eq!(edit_language(Some(b"fr-CA")), Some("fr".to_string()));
+        assert_eq!(edit_language(Some(&[0xff, 0xfe])), None);
+        assert_eq!(edit_language(None), None);
+    }
+
+    #[test]
+    fn test_analyzers() {
+        for language in Language::ALL {
+            assert_eq!(Language::from_tag(language.code()), Some(language));
+        }
+        assert_eq!(Language::Japanese.analyzer(), "cjk");
+    }
+}
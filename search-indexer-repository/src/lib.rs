@@ -2,23 +2,43 @@
 //!
 //! This crate provides traits and implementations for interacting with the
 //! search index. It includes definitions for errors, interfaces, and a
-//! concrete implementation for OpenSearch.
+//! concrete implementation for OpenSearch, behind the `search` feature
+//! (enabled by default), and an HTTP embedding provider for semantic search,
+//! behind the `embeddings-http` feature.
 
 pub mod config;
+pub mod consistency;
+#[cfg(feature = "embeddings-http")]
+pub mod embedding;
 pub mod errors;
 pub mod interfaces;
+pub mod language;
+pub mod offsets;
+#[cfg(feature = "search")]
 pub mod opensearch;
+pub mod ranking;
+pub mod reindex;
 pub mod service;
 pub mod types;
 pub mod utils;
 
-pub use config::SearchIndexServiceConfig;
+pub use config::{DeleteMode, SearchIndexServiceConfig};
+pub use consistency::{
+    ConsistencyChecker, ConsistencyCheckerConfig, ConsistencyReport, EntitySource,
+    PostgresEntitySource,
+};
 pub use errors::SearchIndexError;
-pub use interfaces::SearchIndexProvider;
+pub use interfaces::{EmbeddingProvider, ReindexProvider, SearchIndexProvider};
+pub use language::Language;
+pub use offsets::OffsetTracker;
+#[cfg(feature = "search")]
 pub use opensearch::OpenSearchProvider;
+pub use ranking::TrustRankTracker;
+pub use reindex::{DualWriteProvider, ReindexReport, ReindexService};
 pub use service::SearchIndexService;
 pub use types::{
-    BatchOperationResult, BatchOperationSummary, DeleteEntityRequest, UnsetEntityPropertiesRequest,
-    UpdateEntityRequest,
+    BatchOperationResult, BatchOperationSummary, DeleteEntityRequest, GetEntityRequest,
+    IndexedEntityDocument, RelevanceBoost, RestoreEntitiesRequest, SearchRequest, SearchResult,
+    SemanticSearchRequest, UnsetEntityPropertiesRequest, UpdateEntityRequest, VoteCountUpdate,
 };
 pub use utils::parse_entity_and_space_ids;
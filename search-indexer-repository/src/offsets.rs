@@ -0,0 +1,290 @@
+//! Tracking of consumed offsets until their edits are indexed.
+//!
+//! A consumer indexing a partitioned stream must only commit an offset once every edit
+//! before it was indexed: committing earlier loses the edits in flight if the process
+//! crashes, and committing later redelivers edits that were already indexed.
+//! [`OffsetTracker`] records the offsets consumed per partition, acknowledges them from the
+//! results of the batch they were indexed in, and gives the positions safe to commit.
+//!
+//! Redeliveries after a crash are absorbed by setting `edit_version` on update requests,
+//! e.g. to the offset of their message, which drops edits already applied.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::errors::SearchIndexError;
+use crate::types::BatchOperationSummary;
+
+/// Offsets of a partition consumed and not yet indexed.
+#[derive(Debug, Default)]
+struct PartitionOffsets {
+    /// Offsets consumed and not yet acknowledged.
+    pending: BTreeSet<i64>,
+    /// The offset after the highest one consumed.
+    next: Option<i64>,
+    /// The last position committed.
+    committed: Option<i64>,
+}
+
+impl PartitionOffsets {
+    /// The position to commit: the lowest pending offset, or the offset after the
+    /// highest consumed one if none is pending.
+    fn position(&self) -> Option<i64> {
+        self.pending.first().copied().or(self.next)
+    }
+}
+
+/// Tracks consumed offsets per partition and the positions safe to commit.
+///
+/// Positions follow the Kafka convention: committing `n` means every offset below `n`
+/// was processed, and consumption resumes at `n`.
+///
+/// # Example
+///
+/// ```
+/// use search_indexer_repository::OffsetTracker;
+/// use search_indexer_repository::types::{BatchOperationResult, BatchOperationSummary};
+///
+/// let mut tracker = OffsetTracker::new();
+/// tracker.track(0, 10);
+/// tracker.track(0, 11);
+///
+/// // Results of the bulk request the two messages were indexed in
+/// let result = |success| BatchOperationResult {
+///     entity_id: String::new(),
+///     space_id: String::new(),
+///     success,
+///     error: None,
+/// };
+/// let summary = BatchOperationSummary {
+///     total: 2,
+///     succeeded: 1,
+///     failed: 1,
+///     results: vec![result(true), result(false)],
+/// };
+/// tracker.ack_summary(&[(0, 10), (0, 11)], &summary).unwrap();
+///
+/// // Offset 11 failed, so only offset 10 is committed
+/// assert_eq!(tracker.positions_to_commit(), vec![(0, 11)]);
+/// ```
+#[derive(Debug, Default)]
+pub struct OffsetTracker {
+    partitions: HashMap<i32, PartitionOffsets>,
+}
+
+impl OffsetTracker {
+    /// Create an OffsetTracker with no partition.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a consumed offset, to be acknowledged once its edit is indexed.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The partition the message was consumed from
+    /// * `offset` - The offset of the message
+    pub fn track(&mut self, partition: i32, offset: i64) {
+        let offsets = self.partitions.entry(partition).or_default();
+        offsets.pending.insert(offset);
+        offsets.next = Some(offsets.next.map_or(offset + 1, |next| next.max(offset + 1)));
+    }
+
+    /// Acknowledge an offset whose edit was indexed, or doesn't need to be.
+    ///
+    /// # Arguments
+    ///
+    /// * `partition` - The partition the message was consumed from
+    /// * `offset` - The offset of the message
+    pub fn ack(&mut self, partition: i32, offset: i64) {
+        if let Some(offsets) = self.partitions.get_mut(&partition) {
+            offsets.pending.remove(&offset);
+        }
+    }
+
+    /// Acknowledge the offsets of the requests of a batch that succeeded.
+    ///
+    /// Offsets of failed requests stay pending and hold back the positions of their
+    /// partitions until they're retried and acknowledged, or the consumer seeks back to
+    /// them.
+    ///
+    /// # Arguments
+    ///
+    /// * `offsets` - The partition and offset of each request of the batch, in order
+    /// * `summary` - The summary returned for the batch
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of offsets left pending by failed requests
+    /// * `Err(SearchIndexError::ValidationError)` - If `offsets` and the results of the
+    ///   summary don't have the same length, in which case nothing is acknowledged
+    pub fn ack_summary(
+        &mut self,
+        offsets: &[(i32, i64)],
+        summary: &BatchOperationSummary,
+    ) -> Result<usize, SearchIndexError> {
+        if offsets.len() != summary.results.len() {
+            return Err(SearchIndexError::validation(format!(
+                "Got {} offsets for {} results",
+                offsets.len(),
+                summary.results.len()
+            )));
+        }
+
+        let mut failed = 0;
+        for ((partition, offset), result) in offsets.iter().zip(&summary.results) {
+            if result.success {
+                self.ack(*partition, *offset);
+            } else {
+                failed += 1;
+            }
+        }
+        Ok(failed)
+    }
+
+    /// Get the positions that advanced since they were last committed.
+    ///
+    /// # Returns
+    ///
+    /// The partition and position to commit for each partition that advanced, ordered by
+    /// partition.
+    pub fn positions_to_commit(&self) -> Vec<(i32, i64)> {
+        let positions: BTreeMap<i32, i64> = self
+            .partitions
+            .iter()
+            .filter_map(|(partition, offsets)| {
+                let position = offsets.position()?;
+                (offsets.committed < Some(position)).then_some((*partition, position))
+            })
+            .collect();
+        positions.into_iter().collect()
+    }
+
+    /// Record positions the consumer committed, e.g. the ones of
+    /// [`Self::positions_to_commit`] once the commit succeeded.
+    pub fn mark_committed(&mut self, positions: &[(i32, i64)]) {
+        for (partition, position) in positions {
+            if let Some(offsets) = self.partitions.get_mut(partition) {
+                offsets.committed = offsets.committed.max(Some(*position));
+            }
+        }
+    }
+
+    /// Forget a partition, e.g. when it's revoked from the consumer. Its pending offsets
+    /// will be redelivered to the consumer it's assigned to.
+    pub fn revoke(&mut self, partition: i32) {
+        self.partitions.remove(&partition);
+    }
+
+    /// Number of offsets consumed and not yet acknowledged, across partitions.
+    pub fn pending(&self) -> usize {
+        self.partitions
+            .values()
+            .map(|offsets| offsets.pending.len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BatchOperationResult;
+
+    fn summary(successes: &[bool]) -> BatchOperationSummary {
+        let succeeded = successes.iter().filter(|success| **success).count();
+        BatchOperationSummary {
+            total: successes.len(),
+            succeeded,
+            failed: successes.len() - succeeded,
+            results: successes
+                .iter()
+                .map(|success| BatchOperationResult {
+                    entity_id: String::new(),
+                    space_id: String::new(),
+                    success: *success,
+                    error: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_positions_wait_for_pending_offsets() {
+        let mut tracker = OffsetTracker::new();
+        tracker.track(0, 5);
+        tracker.track(0, 6);
+        tracker.track(0, 7);
+
+        // Nothing indexed yet: resume from the first consumed offset
+        assert_eq!(tracker.positions_to_commit(), vec![(0, 5)]);
+
+        // A later offset acknowledged first doesn't move the position
+        tracker.ack(0, 7);
+        assert_eq!(tracker.positions_to_commit(), vec![(0, 5)]);
+
+        tracker.ack(0, 5);
+        assert_eq!(tracker.positions_to_commit(), vec![(0, 6)]);
+
+        tracker.ack(0, 6);
+        assert_eq!(tracker.positions_to_commit(), vec![(0, 8)]);
+        assert_eq!(tracker.pending(), 0);
+    }
+
+    #[test]
+    fn test_ack_summary() {
+        let mut tracker = OffsetTracker::new();
+        for offset in 0..3 {
+            tracker.track(1, offset);
+        }
+        tracker.track(2, 0);
+
+        let failed = tracker
+            .ack_summary(
+                &[(1, 0), (1, 1), (1, 2), (2, 0)],
+                &summary(&[true, false, true, true]),
+            )
+            .unwrap();
+
+        assert_eq!(failed, 1);
+        assert_eq!(tracker.positions_to_commit(), vec![(1, 1), (2, 1)]);
+        assert_eq!(tracker.pending(), 1);
+    }
+
+    #[test]
+    fn test_ack_summary_length_mismatch() {
+        let mut tracker = OffsetTracker::new();
+        tracker.track(0, 0);
+
+        assert!(matches!(
+            tracker.ack_summary(&[(0, 0)], &summary(&[])),
+            Err(SearchIndexError::ValidationError(_))
+        ));
+        assert_eq!(tracker.pending(), 1);
+    }
+
+    #[test]
+    fn test_mark_committed() {
+        let mut tracker = OffsetTracker::new();
+        tracker.track(0, 0);
+        tracker.ack(0, 0);
+
+        let positions = tracker.positions_to_commit();
+        tracker.mark_committed(&positions);
+        assert!(tracker.positions_to_commit().is_empty());
+
+        tracker.track(0, 1);
+        tracker.ack(0, 1);
+        assert_eq!(tracker.positions_to_commit(), vec![(0, 2)]);
+    }
+
+    #[test]
+    fn test_revoke() {
+        let mut tracker = OffsetTracker::new();
+        tracker.track(0, 0);
+        tracker.track(1, 0);
+
+        tracker.revoke(0);
+
+        assert_eq!(tracker.positions_to_commit(), vec![(1, 0)]);
+        assert_eq!(tracker.pending(), 1);
+    }
+}
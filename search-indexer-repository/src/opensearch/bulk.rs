@@ -0,0 +1,847 @@
+//! Adaptive `_bulk` requests for the OpenSearch provider.
+//!
+//! Bulk operations are packed into requests of a target size in bytes rather than a
+//! fixed number of documents, since documents vary widely in size. Requests are sent by
+//! a capped number of concurrent workers. When the cluster rejects work with 429s, the
+//! rejected documents are retried with exponential backoff and the target size shrinks,
+//! then grows back as requests are accepted.
+//!
+//! [`BulkMetrics`] records bulk latencies, rejections and retries, and renders them in
+//! the Prometheus text format with [`BulkMetrics::render`].
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt};
+use opensearch::http::request::JsonBody;
+use opensearch::BulkParts;
+use serde_json::Value;
+use tracing::{debug, warn};
+
+use crate::errors::SearchIndexError;
+use crate::opensearch::OpenSearchProvider;
+use crate::types::{BatchOperationResult, BatchOperationSummary};
+
+/// Upper bounds of the bulk latency histogram buckets, in milliseconds.
+/// Latencies longer than the last bound land in an overflow bucket.
+pub const BULK_LATENCY_BUCKETS_MS: [u64; 10] =
+    [10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+
+/// Configuration of the bulk requests sent by `OpenSearchProvider`.
+#[derive(Debug, Clone)]
+pub struct BulkConfig {
+    /// Size of the bulk request bodies aimed for, in bytes. Defaults to 5 MiB.
+    pub target_bytes: usize,
+    /// Size the target shrinks down to at most under pressure. Defaults to 64 KiB.
+    pub min_bytes: usize,
+    /// Maximum number of bulk requests in flight. Defaults to 4.
+    pub max_concurrency: usize,
+    /// How many times rejected documents are retried before failing. Defaults to 5.
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled on each retry. Defaults to 100ms.
+    pub initial_backoff: Duration,
+    /// Longest backoff between retries. Defaults to 10s.
+    pub max_backoff: Duration,
+}
+
+impl Default for BulkConfig {
+    fn default() -> Self {
+        Self {
+            target_bytes: 5 * 1024 * 1024,
+            min_bytes: 64 * 1024,
+            max_concurrency: 4,
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl BulkConfig {
+    /// Backoff before a retry, starting from 1 for the first retry.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Histogram of bulk request latencies, using [`BULK_LATENCY_BUCKETS_MS`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkLatencyHistogram {
+    /// Count per bucket, with the overflow bucket last.
+    pub buckets: [u64; BULK_LATENCY_BUCKETS_MS.len() + 1],
+    pub count: u64,
+    pub sum_ms: u64,
+}
+
+impl BulkLatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        let ms = latency.as_millis() as u64;
+        let bucket = BULK_LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms <= *bound)
+            .unwrap_or(BULK_LATENCY_BUCKETS_MS.len());
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += ms;
+    }
+}
+
+/// Metrics of the bulk requests sent by `OpenSearchProvider`.
+#[derive(Debug, Default)]
+pub struct BulkMetrics {
+    requests: AtomicU64,
+    rejected_requests: AtomicU64,
+    documents: AtomicU64,
+    rejected_documents: AtomicU64,
+    failed_documents: AtomicU64,
+    retries: AtomicU64,
+    target_bytes: AtomicU64,
+    latency: Mutex<BulkLatencyHistogram>,
+}
+
+impl BulkMetrics {
+    /// Record a bulk request of `documents` operations and how long it took.
+    fn record_request(&self, documents: usize, latency: Duration) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.documents
+            .fetch_add(documents as u64, Ordering::Relaxed);
+        self.latency.lock().unwrap().record(latency);
+    }
+
+    /// Record a bulk request rejected as a whole with a 429.
+    fn record_rejected_request(&self) {
+        self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record operations rejected with a 429, to be retried or failed.
+    fn record_rejected_documents(&self, documents: usize) {
+        self.rejected_documents
+            .fetch_add(documents as u64, Ordering::Relaxed);
+    }
+
+    /// Record operations that failed for good.
+    fn record_failed_documents(&self, documents: usize) {
+        self.failed_documents
+            .fetch_add(documents as u64, Ordering::Relaxed);
+    }
+
+    /// Record operations sent again after being rejected.
+    fn record_retries(&self, documents: usize) {
+        self.retries.fetch_add(documents as u64, Ordering::Relaxed);
+    }
+
+    /// Record the current target size of bulk requests.
+    fn record_target_bytes(&self, bytes: usize) {
+        self.target_bytes.store(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Bulk requests sent, retries included.
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Bulk requests rejected as a whole with a 429.
+    pub fn rejected_requests(&self) -> u64 {
+        self.rejected_requests.load(Ordering::Relaxed)
+    }
+
+    /// Operations sent in bulk requests, retries included.
+    pub fn documents(&self) -> u64 {
+        self.documents.load(Ordering::Relaxed)
+    }
+
+    /// Operations rejected with a 429, by their bulk request or on their own.
+    pub fn rejected_documents(&self) -> u64 {
+        self.rejected_documents.load(Ordering::Relaxed)
+    }
+
+    /// Operations that failed for good, rejected ones past the retry limit included.
+    pub fn failed_documents(&self) -> u64 {
+        self.failed_documents.load(Ordering::Relaxed)
+    }
+
+    /// Operations sent again after being rejected.
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Share of the sent operations rejected with a 429, or 0.0 before any was sent.
+    pub fn rejection_rate(&self) -> f64 {
+        match self.documents() {
+            0 => 0.0,
+            documents => self.rejected_documents() as f64 / documents as f64,
+        }
+    }
+
+    /// Current target size of bulk requests, in bytes.
+    pub fn target_bytes(&self) -> u64 {
+        self.target_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Snapshot of the bulk latency histogram.
+    pub fn latency(&self) -> BulkLatencyHistogram {
+        self.latency.lock().unwrap().clone()
+    }
+
+    /// Render the metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let counters = [
+            (
+                "search_indexer_bulk_requests_total",
+                "Bulk requests sent, retries included.",
+                self.requests(),
+            ),
+            (
+                "search_indexer_bulk_rejected_requests_total",
+                "Bulk requests rejected as a whole with a 429.",
+                self.rejected_requests(),
+            ),
+            (
+                "search_indexer_bulk_documents_total",
+                "Operations sent in bulk requests, retries included.",
+                self.documents(),
+            ),
+            (
+                "search_indexer_bulk_rejected_documents_total",
+                "Operations rejected with a 429.",
+                self.rejected_documents(),
+            ),
+            (
+                "search_indexer_bulk_failed_documents_total",
+                "Operations that failed for good.",
+                self.failed_documents(),
+            ),
+            (
+                "search_indexer_bulk_retries_total",
+                "Operations sent again after being rejected.",
+                self.retries(),
+            ),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(
+                out,
+                "# HELP {name} {help}\n# TYPE {name} counter\n{name} {value}"
+            );
+        }
+
+        let name = "search_indexer_bulk_target_bytes";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Current target size of bulk requests.\n# TYPE {name} gauge\n{name} {}",
+            self.target_bytes()
+        );
+
+        let name = "search_indexer_bulk_duration_seconds";
+        let latency = self.latency();
+        let _ = writeln!(
+            out,
+            "# HELP {name} Duration of bulk requests.\n# TYPE {name} histogram"
+        );
+        let mut cumulative = 0;
+        for (bound, count) in BULK_LATENCY_BUCKETS_MS.iter().zip(latency.buckets) {
+            cumulative += count;
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{le=\"{}\"}} {cumulative}",
+                *bound as f64 / 1000.0
+            );
+        }
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", latency.count);
+        let _ = writeln!(out, "{name}_sum {}", latency.sum_ms as f64 / 1000.0);
+        let _ = writeln!(out, "{name}_count {}", latency.count);
+
+        out
+    }
+}
+
+/// Target size of bulk requests, halved when the cluster rejects work and grown back
+/// by an eighth of the configured target when it accepts it.
+#[derive(Debug)]
+pub(super) struct AdaptiveTarget {
+    bytes: AtomicUsize,
+}
+
+impl AdaptiveTarget {
+    pub(super) fn new(config: &BulkConfig) -> Self {
+        Self {
+            bytes: AtomicUsize::new(config.target_bytes),
+        }
+    }
+
+    fn current(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    fn on_rejected(&self, config: &BulkConfig) -> usize {
+        let shrunk = (self.current() / 2).max(config.min_bytes.min(config.target_bytes));
+        self.bytes.store(shrunk, Ordering::Relaxed);
+        shrunk
+    }
+
+    fn on_accepted(&self, config: &BulkConfig) -> usize {
+        let grown = (self.current() + config.target_bytes / 8).min(config.target_bytes);
+        self.bytes.store(grown, Ordering::Relaxed);
+        grown
+    }
+}
+
+/// A single operation of a bulk request: its action line and optional source line.
+#[derive(Debug, Clone)]
+pub(super) struct BulkOperation {
+    /// Position of the request in its batch, to report results in order.
+    position: usize,
+    entity_id: String,
+    space_id: String,
+    doc_id: String,
+    lines: Vec<Value>,
+    bytes: usize,
+    /// Whether a 404 counts as success, e.g. deleting a missing document.
+    not_found_ok: bool,
+}
+
+impl BulkOperation {
+    pub(super) fn new(
+        entity_id: &str,
+        space_id: &str,
+        doc_id: String,
+        lines: Vec<Value>,
+        not_found_ok: bool,
+    ) -> Self {
+        // NDJSON: every line ends with a newline
+        let bytes = lines.iter().map(|line| line.to_string().len() + 1).sum();
+        Self {
+            position: 0,
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+            doc_id,
+            lines,
+            bytes,
+            not_found_ok,
+        }
+    }
+
+    fn result(&self, error: Option<SearchIndexError>) -> (usize, BatchOperationResult) {
+        (
+            self.position,
+            BatchOperationResult {
+                entity_id: self.entity_id.clone(),
+                space_id: self.space_id.clone(),
+                success: error.is_none(),
+                error,
+            },
+        )
+    }
+}
+
+/// An operation prepared for a bulk request: sent, or already settled without a
+/// request (e.g. invalid IDs, or an update without fields).
+pub(super) type PreparedOperation = Result<BulkOperation, BatchOperationResult>;
+
+/// Outcome of a bulk item.
+#[derive(Debug, Clone, PartialEq)]
+enum ItemStatus {
+    Succeeded,
+    Rejected,
+    Failed(String),
+}
+
+/// Pack operations into requests of at most `target_bytes`.
+///
+/// Operations on the same document stay in the same request and in order, so concurrent
+/// requests can't reorder them. A group larger than the target gets a request of its own.
+fn chunk_operations(
+    operations: Vec<BulkOperation>,
+    target_bytes: usize,
+) -> Vec<Vec<BulkOperation>> {
+    let mut groups: Vec<Vec<BulkOperation>> = Vec::new();
+    let mut group_of: HashMap<String, usize> = HashMap::new();
+    for operation in operations {
+        match group_of.get(&operation.doc_id) {
+            Some(&group) => groups[group].push(operation),
+            None => {
+                group_of.insert(operation.doc_id.clone(), groups.len());
+                groups.push(vec![operation]);
+            }
+        }
+    }
+
+    let mut chunks: Vec<Vec<BulkOperation>> = Vec::new();
+    let mut chunk_bytes = 0;
+    for group in groups {
+        let group_bytes: usize = group.iter().map(|operation| operation.bytes).sum();
+        if chunks.is_empty() || chunk_bytes + group_bytes > target_bytes {
+            chunks.push(Vec::new());
+            chunk_bytes = 0;
+        }
+        chunk_bytes += group_bytes;
+        chunks.last_mut().unwrap().extend(group);
+    }
+    chunks.retain(|chunk| !chunk.is_empty());
+    chunks
+}
+
+/// Read the status of each item of a bulk response, in request order.
+fn parse_items(
+    body: &Value,
+    operations: &[BulkOperation],
+) -> Result<Vec<ItemStatus>, SearchIndexError> {
+    let items = body
+        .get("items")
+        .and_then(Value::as_array)
+        .ok_or_else(|| SearchIndexError::parse("Bulk response is missing 'items'"))?;
+    if items.len() != operations.len() {
+        return Err(SearchIndexError::parse(format!(
+            "Bulk response has {} items for {} operations",
+            items.len(),
+            operations.len()
+        )));
+    }
+
+    items
+        .iter()
+        .zip(operations)
+        .map(|(item, operation)| {
+            // Each item has a single key, the action of the operation
+            let result = item
+                .as_object()
+                .and_then(|item| item.values().next())
+                .ok_or_else(|| SearchIndexError::parse("Bulk item has no action"))?;
+            let status = result
+                .get("status")
+                .and_then(Value::as_u64)
+                .ok_or_else(|| SearchIndexError::parse("Bulk item is missing 'status'"))?;
+
+            Ok(match status {
+                200..=299 => ItemStatus::Succeeded,
+                404 if operation.not_found_ok => ItemStatus::Succeeded,
+                429 => ItemStatus::Rejected,
+                _ => ItemStatus::Failed(
+                    result
+                        .get("error")
+                        .map(Value::to_string)
+                        .unwrap_or_else(|| format!("status {}", status)),
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Split the operations of a bulk request into settled results and operations to retry.
+///
+/// Rejected operations are retried along with the later operations on their document,
+/// even the ones that succeeded, so the document ends up in the state of its last
+/// operation.
+fn settle(
+    operations: Vec<BulkOperation>,
+    statuses: Vec<ItemStatus>,
+) -> (Vec<(usize, BatchOperationResult)>, Vec<BulkOperation>) {
+    let mut settled = Vec::new();
+    let mut retry = Vec::new();
+    let mut rejected_docs: Vec<String> = Vec::new();
+
+    for (operation, status) in operations.into_iter().zip(statuses) {
+        if status == ItemStatus::Rejected || rejected_docs.contains(&operation.doc_id) {
+            if !rejected_docs.contains(&operation.doc_id) {
+                rejected_docs.push(operation.doc_id.clone());
+            }
+            retry.push(operation);
+            continue;
+        }
+        match status {
+            ItemStatus::Failed(error) => {
+                settled.push(operation.result(Some(SearchIndexError::bulk_index(error))))
+            }
+            _ => settled.push(operation.result(None)),
+        }
+    }
+
+    (settled, retry)
+}
+
+impl OpenSearchProvider {
+    /// Run prepared operations in adaptive, concurrent bulk requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `prepared` - The operations of a batch, in the order of its requests
+    ///
+    /// # Returns
+    ///
+    /// A summary with a result per operation, in the order of `prepared`.
+    pub(super) async fn execute_bulk(
+        &self,
+        prepared: Vec<PreparedOperation>,
+    ) -> BatchOperationSummary {
+        let total = prepared.len();
+        let mut results: Vec<(usize, BatchOperationResult)> = Vec::with_capacity(total);
+        let mut operations = Vec::new();
+        for (position, operation) in prepared.into_iter().enumerate() {
+            match operation {
+                Ok(mut operation) => {
+                    operation.position = position;
+                    operations.push(operation);
+                }
+                Err(result) => results.push((position, result)),
+            }
+        }
+
+        let target_bytes = self.bulk_target.current();
+        self.bulk_metrics.record_target_bytes(target_bytes);
+        let chunks = chunk_operations(operations, target_bytes);
+        let chunk_results: Vec<Vec<(usize, BatchOperationResult)>> = stream::iter(chunks)
+            .map(|chunk| self.execute_chunk(chunk))
+            .buffer_unordered(self.bulk_config.max_concurrency.max(1))
+            .collect()
+            .await;
+        results.extend(chunk_results.into_iter().flatten());
+        results.sort_by_key(|(position, _)| *position);
+
+        let results: Vec<BatchOperationResult> =
+            results.into_iter().map(|(_, result)| result).collect();
+        let succeeded = results.iter().filter(|result| result.success).count();
+        BatchOperationSummary {
+            total,
+            succeeded,
+            failed: total - succeeded,
+            results,
+        }
+    }
+
+    /// Send a bulk request, retrying the operations rejected with 429s with backoff.
+    async fn execute_chunk(
+        &self,
+        mut pending: Vec<BulkOperation>,
+    ) -> Vec<(usize, BatchOperationResult)> {
+        let config = &self.bulk_config;
+        let mut results = Vec::with_capacity(pending.len());
+        let mut attempt = 0;
+
+        loop {
+            let started = Instant::now();
+            let response = self.send_bulk(&pending).await;
+            self.bulk_metrics
+                .record_request(pending.len(), started.elapsed());
+
+            match response {
+                Err(e) => {
+                    self.bulk_metrics.record_failed_documents(pending.len());
+                    results.extend(
+                        pending
+                            .iter()
+                            .map(|operation| operation.result(Some(e.clone()))),
+                    );
+                    return results;
+                }
+                Ok(None) => {
+                    self.bulk_metrics.record_rejected_request();
+                    self.bulk_metrics.record_rejected_documents(pending.len());
+                }
+                Ok(Some(body)) => {
+                    let statuses = match parse_items(&body, &pending) {
+                        Ok(statuses) => statuses,
+                        Err(e) => {
+                            self.bulk_metrics.record_failed_documents(pending.len());
+                            results.extend(
+                                pending
+                                    .iter()
+                                    .map(|operation| operation.result(Some(e.clone()))),
+                            );
+                            return results;
+                        }
+                    };
+                    let rejected = statuses
+                        .iter()
+                        .filter(|status| **status == ItemStatus::Rejected)
+                        .count();
+                    let failed = statuses
+                        .iter()
+                        .filter(|status| matches!(status, ItemStatus::Failed(_)))
+                        .count();
+                    self.bulk_metrics.record_failed_documents(failed);
+
+                    let (settled, retry) = settle(pending, statuses);
+                    results.extend(settled);
+                    pending = retry;
+                    if pending.is_empty() {
+                        let target = self.bulk_target.on_accepted(config);
+                        self.bulk_metrics.record_target_bytes(target);
+                        return results;
+                    }
+                    self.bulk_metrics.record_rejected_documents(rejected);
+                }
+            }
+
+            let target = self.bulk_target.on_rejected(config);
+            self.bulk_metrics.record_target_bytes(target);
+            attempt += 1;
+            if attempt > config.max_retries {
+                warn!(
+                    documents = pending.len(),
+                    attempts = attempt,
+                    "Bulk operations rejected past the retry limit"
+                );
+                self.bulk_metrics.record_failed_documents(pending.len());
+                let error = SearchIndexError::bulk_index(format!(
+                    "Rejected by the cluster after {} retries",
+                    config.max_retries
+                ));
+                results.extend(
+                    pending
+                        .iter()
+                        .map(|operation| operation.result(Some(error.clone()))),
+                );
+                return results;
+            }
+
+            let backoff = config.backoff(attempt);
+            debug!(
+                documents = pending.len(),
+                attempt,
+                target_bytes = target,
+                ?backoff,
+                "Retrying rejected bulk operations"
+            );
+            self.bulk_metrics.record_retries(pending.len());
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Send a bulk request.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Value))` - The response body, with a status per item
+    /// * `Ok(None)` - If the whole request was rejected with a 429
+    /// * `Err(SearchIndexError)` - If the request failed
+    async fn send_bulk(
+        &self,
+        operations: &[BulkOperation],
+    ) -> Result<Option<Value>, SearchIndexError> {
+        let body: Vec<JsonBody<Value>> = operations
+            .iter()
+            .flat_map(|operation| operation.lines.iter().cloned().map(JsonBody::new))
+            .collect();
+
+        let response = self
+            .client
+            .bulk(BulkParts::Index(&self.index_config.alias))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::bulk_index(e.to_string()))?;
+
+        let status = response.status_code();
+        if status.as_u16() == 429 {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            return Err(SearchIndexError::bulk_index(format!(
+                "Bulk request failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| SearchIndexError::parse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn operation(doc_id: &str, bytes: usize) -> BulkOperation {
+        let mut operation = BulkOperation::new(
+            doc_id,
+            doc_id,
+            doc_id.to_string(),
+            vec![json!({ "delete": { "_id": doc_id } })],
+            true,
+        );
+        operation.bytes = bytes;
+        operation
+    }
+
+    fn doc_ids(chunk: &[BulkOperation]) -> Vec<&str> {
+        chunk
+            .iter()
+            .map(|operation| operation.doc_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_operation_bytes() {
+        let operation = BulkOperation::new(
+            "e",
+            "s",
+            "e_s".to_string(),
+            vec![json!({ "update": { "_id": "e_s" } }), json!({ "doc": {} })],
+            false,
+        );
+
+        // Both lines and their newlines
+        assert_eq!(
+            operation.bytes,
+            r#"{"update":{"_id":"e_s"}}"#.len() + r#"{"doc":{}}"#.len() + 2
+        );
+    }
+
+    #[test]
+    fn test_chunk_operations_by_bytes() {
+        let chunks = chunk_operations(
+            vec![
+                operation("a", 40),
+                operation("b", 40),
+                operation("c", 40),
+                operation("d", 200),
+            ],
+            100,
+        );
+
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(doc_ids(&chunks[0]), vec!["a", "b"]);
+        assert_eq!(doc_ids(&chunks[1]), vec!["c"]);
+        // Larger than the target on its own
+        assert_eq!(doc_ids(&chunks[2]), vec!["d"]);
+    }
+
+    #[test]
+    fn test_chunk_operations_keeps_documents_together() {
+        let mut first = operation("a", 40);
+        first.position = 0;
+        let mut second = operation("a", 40);
+        second.position = 2;
+
+        let chunks = chunk_operations(vec![first, operation("b", 40), second], 100);
+
+        assert_eq!(doc_ids(&chunks[0]), vec!["a", "a"]);
+        assert_eq!(chunks[0][0].position, 0);
+        assert_eq!(chunks[0][1].position, 2);
+        assert_eq!(doc_ids(&chunks[1]), vec!["b"]);
+    }
+
+    #[test]
+    fn test_parse_items() {
+        let operations = vec![
+            operation("a", 1),
+            operation("b", 1),
+            operation("c", 1),
+            operation("d", 1),
+        ];
+        let body = json!({
+            "errors": true,
+            "items": [
+                { "delete": { "_id": "a", "status": 200 } },
+                { "delete": { "_id": "b", "status": 404 } },
+                { "delete": { "_id": "c", "status": 429 } },
+                { "delete": { "_id": "d", "status": 400, "error": { "type": "mapper_parsing_exception" } } }
+            ]
+        });
+
+        let statuses = parse_items(&body, &operations).unwrap();
+
+        assert_eq!(statuses[0], ItemStatus::Succeeded);
+        // Deleting a missing document succeeds
+        assert_eq!(statuses[1], ItemStatus::Succeeded);
+        assert_eq!(statuses[2], ItemStatus::Rejected);
+        assert!(
+            matches!(&statuses[3], ItemStatus::Failed(error) if error.contains("mapper_parsing_exception"))
+        );
+
+        assert!(parse_items(&json!({ "items": [] }), &operations).is_err());
+    }
+
+    #[test]
+    fn test_settle_retries_later_operations_on_rejected_documents() {
+        let operations = vec![
+            operation("a", 1),
+            operation("b", 1),
+            operation("a", 1),
+            operation("c", 1),
+        ];
+        let statuses = vec![
+            ItemStatus::Rejected,
+            ItemStatus::Succeeded,
+            ItemStatus::Succeeded,
+            ItemStatus::Failed("boom".to_string()),
+        ];
+
+        let (settled, retry) = settle(operations, statuses);
+
+        assert_eq!(doc_ids(&retry), vec!["a", "a"]);
+        assert_eq!(settled.len(), 2);
+        assert!(settled[0].1.success);
+        assert!(!settled[1].1.success);
+    }
+
+    #[test]
+    fn test_adaptive_target() {
+        let config = BulkConfig {
+            target_bytes: 800,
+            min_bytes: 150,
+            ..Default::default()
+        };
+        let target = AdaptiveTarget::new(&config);
+
+        assert_eq!(target.on_rejected(&config), 400);
+        assert_eq!(target.on_rejected(&config), 200);
+        assert_eq!(target.on_rejected(&config), 150);
+        assert_eq!(target.on_accepted(&config), 250);
+        for _ in 0..10 {
+            target.on_accepted(&config);
+        }
+        assert_eq!(target.current(), 800);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let config = BulkConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            ..Default::default()
+        };
+
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(3), Duration::from_millis(400));
+        assert_eq!(config.backoff(4), Duration::from_millis(500));
+        assert_eq!(config.backoff(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_metrics() {
+        let metrics = BulkMetrics::default();
+        assert_eq!(metrics.rejection_rate(), 0.0);
+
+        metrics.record_request(8, Duration::from_millis(30));
+        metrics.record_request(2, Duration::from_millis(20_000));
+        metrics.record_rejected_documents(2);
+
+        assert_eq!(metrics.requests(), 2);
+        assert_eq!(metrics.documents(), 10);
+        assert_eq!(metrics.rejection_rate(), 0.2);
+        let latency = metrics.latency();
+        assert_eq!(latency.buckets[2], 1);
+        assert_eq!(latency.buckets[BULK_LATENCY_BUCKETS_MS.len()], 1);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("search_indexer_bulk_documents_total 10\n"));
+        assert!(rendered.contains("search_indexer_bulk_rejected_documents_total 2\n"));
+        assert!(rendered.contains("search_indexer_bulk_duration_seconds_bucket{le=\"0.05\"} 1\n"));
+        assert!(rendered.contains("search_indexer_bulk_duration_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(rendered.contains("search_indexer_bulk_duration_seconds_count 2\n"));
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! This module defines the index settings and mappings for the entity search index.
 
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
+
+use crate::language::Language;
 
 /// Configuration for the search index.
 #[derive(Debug, Clone)]
@@ -11,6 +13,9 @@ pub struct IndexConfig {
     pub alias: String,
     /// The version number for the index (e.g., 0 for "entities_v0").
     pub version: u32,
+    /// Languages whose analyzers index the name and description, in subfields named by
+    /// [`Language::analyzer`]. Changing them takes a new index version.
+    pub languages: Vec<Language>,
 }
 
 impl IndexConfig {
@@ -24,13 +29,28 @@ impl IndexConfig {
         Self {
             alias: alias.into(),
             version,
+            languages: Language::ALL.to_vec(),
         }
     }
+
+    /// Set the languages whose analyzers index the name and description.
+    ///
+    /// # Arguments
+    ///
+    /// * `languages` - The languages to index, all supported ones by default
+    pub fn with_languages(mut self, languages: Vec<Language>) -> Self {
+        self.languages = languages;
+        self
+    }
 }
 
 /// The base name of the search index (without version).
 pub const INDEX_NAME: &str = "entities";
 
+/// Dimension of the name and description embeddings, the one of the `EmbeddingProvider`
+/// writing them (384 for e.g. all-MiniLM-L6-v2). Changing it takes a new index version.
+pub const EMBEDDING_DIMENSION: usize = 384;
+
 /// Get the versioned index name.
 ///
 /// # Arguments
@@ -49,8 +69,15 @@ pub fn get_versioned_index_name(version: Option<u32>) -> String {
 ///
 /// The configuration includes:
 /// - **search_as_you_type**: Built-in field type for autocomplete on name and description
+/// - **Language subfields**: Name and description analyzed by the analyzer of each of
+///   `languages`, e.g. `name.french`
 /// - **rank_feature**: Score fields optimized for relevance boosting
+/// - **Ranking signals**: Vote `score` and space `trust_rank` floats boosting search results
 /// - **Keyword fields**: For filtering and exact ID lookups
+/// - **knn_vector**: Name and description embeddings of [`EMBEDDING_DIMENSION`] for
+///   semantic search, in HNSW graphs compared with cosine similarity
+/// - **Required routing**: Documents are routed by space ID, so the documents of a space
+///   share a shard and space-filtered operations only touch that shard
 ///
 /// # Sharding Configuration
 ///
@@ -60,13 +87,20 @@ pub fn get_versioned_index_name(version: Option<u32>) -> String {
 /// # Arguments
 ///
 /// * `version` - Optional version number (currently unused, reserved for future version-specific settings)
-pub fn get_index_settings(_version: Option<u32>) -> Value {
+/// * `languages` - The languages to add subfields for
+pub fn get_index_settings(_version: Option<u32>, languages: &[Language]) -> Value {
+    let mut name_fields = language_fields(languages);
+    name_fields.insert("raw".to_string(), json!({ "type": "keyword" }));
     json!({
         "settings": {
             "number_of_shards": 1,
-            "number_of_replicas": 1
+            "number_of_replicas": 1,
+            "index.knn": true
         },
         "mappings": {
+            "_routing": {
+                "required": true
+            },
             "properties": {
                 "entity_id": {
                     "type": "keyword"
@@ -76,14 +110,14 @@ pub fn get_index_settings(_version: Option<u32>) -> Value {
                 },
                 "name": {
                     "type": "search_as_you_type",
-                    "fields": {
-                        "raw": {
-                            "type": "keyword"
-                        }
-                    }
+                    "fields": name_fields
                 },
                 "description": {
-                    "type": "search_as_you_type"
+                    "type": "search_as_you_type",
+                    "fields": language_fields(languages)
+                },
+                "language": {
+                    "type": "keyword"
                 },
                 "avatar": {
                     "type": "keyword",
@@ -104,7 +138,84 @@ pub fn get_index_settings(_version: Option<u32>) -> Value {
                 },
                 "indexed_at": {
                     "type": "date"
-                }
+                },
+                "deleted": {
+                    "type": "boolean"
+                },
+                "deleted_at": {
+                    "type": "date"
+                },
+                "edit_version": {
+                    "type": "long"
+                },
+                "score": {
+                    "type": "float"
+                },
+                "trust_rank": {
+                    "type": "float"
+                },
+                "name_embedding": embedding_mapping(),
+                "description_embedding": embedding_mapping()
+            }
+        }
+    })
+}
+
+/// Get the index template applying the settings and mappings of [`get_index_settings`]
+/// to every versioned index, including ones not created by the repository crate.
+///
+/// # Arguments
+///
+/// * `languages` - The languages to add subfields for
+pub fn get_index_template(languages: &[Language]) -> Value {
+    json!({
+        "index_patterns": [format!("{}_v*", INDEX_NAME)],
+        "template": get_index_settings(None, languages)
+    })
+}
+
+/// Subfields analyzing a text field with the analyzer of each language.
+///
+/// Languages sharing an analyzer share its subfield.
+fn language_fields(languages: &[Language]) -> Map<String, Value> {
+    languages
+        .iter()
+        .map(|language| {
+            (
+                language.analyzer().to_string(),
+                json!({ "type": "text", "analyzer": language.analyzer() }),
+            )
+        })
+        .collect()
+}
+
+/// Mapping of an embedding field.
+///
+/// The Lucene engine applies the filters of knn queries while searching the graph, so
+/// filtered searches still return `k` documents when there are enough matches.
+fn embedding_mapping() -> Value {
+    json!({
+        "type": "knn_vector",
+        "dimension": EMBEDDING_DIMENSION,
+        "method": {
+            "name": "hnsw",
+            "space_type": "cosinesimil",
+            "engine": "lucene"
+        }
+    })
+}
+
+/// Filter clause excluding soft-deleted documents.
+///
+/// Search queries must add this to their `bool.filter` so documents deleted in
+/// [`DeleteMode::Soft`](crate::config::DeleteMode::Soft) stay hidden until they
+/// are restored or purged. Documents indexed before the `deleted` field existed
+/// don't have it and still match.
+pub fn not_deleted_filter() -> Value {
+    json!({
+        "bool": {
+            "must_not": {
+                "term": { "deleted": true }
             }
         }
     })
@@ -116,13 +227,14 @@ mod tests {
 
     #[test]
     fn test_index_settings_structure() {
-        let settings = get_index_settings(None);
+        let settings = get_index_settings(None, &Language::ALL);
 
         // Check settings exist
         assert!(settings["settings"]["number_of_shards"].is_number());
         assert!(settings["settings"]["number_of_replicas"].is_number());
 
         // Check mappings exist
+        assert_eq!(settings["mappings"]["_routing"]["required"], true);
         assert!(settings["mappings"]["properties"]["entity_id"].is_object());
         assert!(settings["mappings"]["properties"]["name"].is_object());
         assert!(settings["mappings"]["properties"]["description"].is_object());
@@ -150,6 +262,36 @@ mod tests {
             settings["mappings"]["properties"]["entity_space_score"]["type"],
             "rank_feature"
         );
+
+        // Check soft-delete fields
+        assert_eq!(
+            settings["mappings"]["properties"]["deleted"]["type"],
+            "boolean"
+        );
+        assert_eq!(
+            settings["mappings"]["properties"]["deleted_at"]["type"],
+            "date"
+        );
+        assert_eq!(
+            settings["mappings"]["properties"]["edit_version"]["type"],
+            "long"
+        );
+
+        // Check ranking signal fields
+        assert_eq!(settings["mappings"]["properties"]["score"]["type"], "float");
+        assert_eq!(
+            settings["mappings"]["properties"]["trust_rank"]["type"],
+            "float"
+        );
+
+        // Check embedding fields
+        assert_eq!(settings["settings"]["index.knn"], true);
+        for field in ["name_embedding", "description_embedding"] {
+            let mapping = &settings["mappings"]["properties"][field];
+            assert_eq!(mapping["type"], "knn_vector");
+            assert_eq!(mapping["dimension"], EMBEDDING_DIMENSION);
+            assert_eq!(mapping["method"]["engine"], "lucene");
+        }
     }
 
     #[test]
@@ -165,4 +307,38 @@ mod tests {
         assert_eq!(get_versioned_index_name(Some(2)), "entities_v2");
         assert_eq!(get_versioned_index_name(Some(42)), "entities_v42");
     }
+
+    #[test]
+    fn test_language_fields() {
+        let settings = get_index_settings(
+            None,
+            &[Language::French, Language::Chinese, Language::Japanese],
+        );
+        let properties = &settings["mappings"]["properties"];
+
+        assert_eq!(
+            properties["name"]["fields"],
+            json!({
+                "raw": { "type": "keyword" },
+                "french": { "type": "text", "analyzer": "french" },
+                "cjk": { "type": "text", "analyzer": "cjk" }
+            })
+        );
+        assert_eq!(
+            properties["description"]["fields"]["french"]["analyzer"],
+            "french"
+        );
+        assert_eq!(properties["language"]["type"], "keyword");
+    }
+
+    #[test]
+    fn test_index_template() {
+        let template = get_index_template(&[Language::English]);
+
+        assert_eq!(template["index_patterns"], json!(["entities_v*"]));
+        assert_eq!(
+            template["template"],
+            get_index_settings(None, &[Language::English])
+        );
+    }
 }
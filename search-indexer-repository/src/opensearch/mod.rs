@@ -3,8 +3,14 @@
 //! This module provides a concrete implementation of `SearchIndexProvider`
 //! using OpenSearch as the backend.
 
+mod bulk;
 mod index_config;
 mod provider;
+mod reindex_provider;
 
-pub use index_config::{get_index_settings, get_versioned_index_name, IndexConfig, INDEX_NAME};
+pub use bulk::{BulkConfig, BulkLatencyHistogram, BulkMetrics, BULK_LATENCY_BUCKETS_MS};
+pub use index_config::{
+    get_index_settings, get_index_template, get_versioned_index_name, not_deleted_filter,
+    IndexConfig, EMBEDDING_DIMENSION, INDEX_NAME,
+};
 pub use provider::OpenSearchProvider;
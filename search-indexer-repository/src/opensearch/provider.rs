@@ -3,24 +3,37 @@
 //! This module provides the concrete implementation of `SearchIndexProvider`
 //! using the OpenSearch Rust crate.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use opensearch::{
     http::transport::{SingleNodeConnectionPool, TransportBuilder},
-    DeleteParts, OpenSearch, UpdateParts,
+    params::Conflicts,
+    DeleteByQueryParts, DeleteParts, GetParts, IndexParts, OpenSearch, SearchParts,
+    UpdateByQueryParts, UpdateParts,
 };
-use serde_json::json;
+use serde_json::{json, Value};
 use tracing::{debug, error, info};
 use url::Url;
 use uuid::Uuid;
 
 use crate::errors::SearchIndexError;
 use crate::interfaces::SearchIndexProvider;
-use crate::opensearch::index_config::IndexConfig;
+use crate::language::Language;
+use crate::opensearch::bulk::{AdaptiveTarget, BulkConfig, BulkMetrics, BulkOperation};
+use crate::opensearch::index_config::{not_deleted_filter, IndexConfig, EMBEDDING_DIMENSION};
 use crate::types::{
-    BatchOperationResult, BatchOperationSummary, DeleteEntityRequest, UnsetEntityPropertiesRequest,
-    UpdateEntityRequest,
+    BatchOperationResult, BatchOperationSummary, DeleteEntityRequest, GetEntityRequest,
+    IndexedEntityDocument, RelevanceBoost, RestoreEntitiesRequest, SearchRequest, SearchResult,
+    SemanticSearchRequest, UnsetEntityPropertiesRequest, UpdateEntityRequest, VoteCountUpdate,
 };
-use crate::utils;
+use crate::{ranking, utils};
+
+/// Painless script applying an update unless the document is a tombstone deleted at or
+/// after the edit the update comes from, or already has an edit of the same or a later
+/// version applied.
+const UPDATE_UNLESS_STALE_SCRIPT: &str = "if ((params.edited_at != null && ctx._source.deleted == true && ctx._source.deleted_at != null && ZonedDateTime.parse(ctx._source.deleted_at).toInstant().toEpochMilli() >= params.edited_at) || (params.edit_version != null && ctx._source.edit_version != null && ctx._source.edit_version >= params.edit_version)) { ctx.op = 'none' } else { ctx._source.putAll(params.doc) }";
 
 /// OpenSearch provider implementation.
 ///
@@ -45,8 +58,11 @@ use crate::utils;
 /// provider.update_document(&request).await?;
 /// ```
 pub struct OpenSearchProvider {
-    client: OpenSearch,
-    index_config: IndexConfig,
+    pub(super) client: OpenSearch,
+    pub(super) index_config: IndexConfig,
+    pub(super) bulk_config: BulkConfig,
+    pub(super) bulk_target: AdaptiveTarget,
+    pub(super) bulk_metrics: Arc<BulkMetrics>,
 }
 
 impl OpenSearchProvider {
@@ -80,16 +96,43 @@ impl OpenSearchProvider {
             "Created OpenSearch provider"
         );
 
+        let bulk_config = BulkConfig::default();
         Ok(Self {
             client,
             index_config,
+            bulk_target: AdaptiveTarget::new(&bulk_config),
+            bulk_config,
+            bulk_metrics: Arc::new(BulkMetrics::default()),
         })
     }
 
+    /// Set the configuration of the bulk requests, replacing the default one.
+    ///
+    /// # Arguments
+    ///
+    /// * `bulk_config` - Target size, concurrency and retries of bulk requests
+    ///
+    /// # Returns
+    ///
+    /// The provider with the new bulk configuration.
+    pub fn with_bulk_config(mut self, bulk_config: BulkConfig) -> Self {
+        self.bulk_target = AdaptiveTarget::new(&bulk_config);
+        self.bulk_config = bulk_config;
+        self
+    }
+
+    /// Get the metrics of the bulk requests sent by this provider.
+    ///
+    /// The returned handle is shared with the provider, so it can be kept to render the
+    /// metrics while the provider is in use.
+    pub fn bulk_metrics(&self) -> Arc<BulkMetrics> {
+        self.bulk_metrics.clone()
+    }
+
     /// Generate a document ID from entity and space IDs.
     ///
     /// Uses format: `{entity_id}_{space_id}` to ensure uniqueness.
-    fn document_id(entity_id: &Uuid, space_id: &Uuid) -> String {
+    pub(super) fn document_id(entity_id: &Uuid, space_id: &Uuid) -> String {
         format!("{}_{}", entity_id, space_id)
     }
 
@@ -164,33 +207,55 @@ impl OpenSearchProvider {
             .collect::<Vec<_>>()
             .join("; "))
     }
-}
 
-#[async_trait]
-impl SearchIndexProvider for OpenSearchProvider {
-    /// Update specific fields of a document, creating it if it doesn't exist (upsert).
+    /// Build the query matching the soft-deleted documents to restore.
     ///
-    /// This function performs an upsert operation: if the document exists, only fields that are
-    /// `Some` in the request will be updated; if the document doesn't exist, it will be created
-    /// with the provided fields. Fields that are `None` in the request will be left unchanged
-    /// (for existing documents) or omitted (for new documents).
+    /// Entity and space filters match the IDs written to the document when it was
+    /// soft-deleted.
     ///
     /// # Arguments
     ///
-    /// * `request` - The update request containing entity_id, space_id, and optional fields
+    /// * `request` - The restore request with the filters to apply
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the document was updated or created successfully
-    /// * `Err(SearchIndexError)` - If the operation fails
-    async fn update_document(&self, request: &UpdateEntityRequest) -> Result<(), SearchIndexError> {
-        // Validate UUIDs
-        let (entity_id, space_id) =
-            utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)?;
+    /// * `Ok(Value)` - The query
+    /// * `Err(SearchIndexError)` - If an ID is not a valid UUID
+    fn restore_query(request: &RestoreEntitiesRequest) -> Result<Value, SearchIndexError> {
+        let mut filters = vec![json!({ "term": { "deleted": true } })];
 
-        let doc_id = Self::document_id(&entity_id, &space_id);
+        if let Some(ref entity_id) = request.entity_id {
+            let entity_id = Uuid::parse_str(entity_id)
+                .map_err(|e| SearchIndexError::validation(format!("Invalid entity_id: {}", e)))?;
+            filters.push(json!({ "term": { "entity_id": entity_id.to_string() } }));
+        }
+        if let Some(ref space_id) = request.space_id {
+            let space_id = Uuid::parse_str(space_id)
+                .map_err(|e| SearchIndexError::validation(format!("Invalid space_id: {}", e)))?;
+            filters.push(json!({ "term": { "space_id": space_id.to_string() } }));
+        }
+        if let Some(deleted_since) = request.deleted_since {
+            filters
+                .push(json!({ "range": { "deleted_at": { "gte": deleted_since.to_rfc3339() } } }));
+        }
 
-        // Build update document with only provided fields
+        Ok(json!({ "bool": { "filter": filters } }))
+    }
+
+    /// Build the query matching documents soft-deleted before `deleted_before`.
+    fn purge_query(deleted_before: DateTime<Utc>) -> Value {
+        json!({
+            "bool": {
+                "filter": [
+                    { "term": { "deleted": true } },
+                    { "range": { "deleted_at": { "lt": deleted_before.to_rfc3339() } } }
+                ]
+            }
+        })
+    }
+
+    /// Collect the fields an update request sets.
+    pub(super) fn update_fields(request: &UpdateEntityRequest) -> serde_json::Map<String, Value> {
         let mut doc = serde_json::Map::new();
         if let Some(ref name) = request.name {
             doc.insert("name".to_string(), json!(name));
@@ -198,6 +263,12 @@ impl SearchIndexProvider for OpenSearchProvider {
         if let Some(ref description) = request.description {
             doc.insert("description".to_string(), json!(description));
         }
+        // The language only describes the name and description, so it's set along them
+        if let Some(ref language) = request.language {
+            if request.name.is_some() || request.description.is_some() {
+                doc.insert("language".to_string(), json!(language));
+            }
+        }
         if let Some(ref avatar) = request.avatar {
             doc.insert("avatar".to_string(), json!(avatar));
         }
@@ -216,21 +287,362 @@ impl SearchIndexProvider for OpenSearchProvider {
         if let Some(entity_space_score) = request.entity_space_score {
             doc.insert("entity_space_score".to_string(), json!(entity_space_score));
         }
+        if let Some(score) = request.score {
+            doc.insert("score".to_string(), json!(score));
+        }
+        if let Some(trust_rank) = request.trust_rank {
+            doc.insert("trust_rank".to_string(), json!(trust_rank));
+        }
+        if let Some(ref name_embedding) = request.name_embedding {
+            doc.insert("name_embedding".to_string(), json!(name_embedding));
+        }
+        if let Some(ref description_embedding) = request.description_embedding {
+            doc.insert(
+                "description_embedding".to_string(),
+                json!(description_embedding),
+            );
+        }
+        doc
+    }
 
+    /// Build the document of an update request, or `None` if it sets no field.
+    ///
+    /// The entity and space IDs are written along with the fields so searches can filter
+    /// on them, and the deleted flag is cleared: an update restores a soft-deleted
+    /// document, like it recreates a hard-deleted one.
+    pub(super) fn update_doc(
+        request: &UpdateEntityRequest,
+        entity_id: &Uuid,
+        space_id: &Uuid,
+    ) -> Option<serde_json::Map<String, Value>> {
+        let mut doc = Self::update_fields(request);
         if doc.is_empty() {
+            return None;
+        }
+
+        doc.insert("entity_id".to_string(), json!(entity_id.to_string()));
+        doc.insert("space_id".to_string(), json!(space_id.to_string()));
+        doc.insert("deleted".to_string(), json!(false));
+        Some(doc)
+    }
+
+    /// Build the body of an upsert of `doc`.
+    ///
+    /// Without `edited_at` and `edit_version` the fields are merged into the document.
+    /// With either, the merge runs as a scripted upsert which skips documents tombstoned
+    /// by a later or concurrent delete, and documents an edit of the same or a later
+    /// version was applied to. The version is stored along with the fields.
+    pub(super) fn update_body(
+        mut doc: serde_json::Map<String, Value>,
+        edited_at: Option<DateTime<Utc>>,
+        edit_version: Option<u64>,
+    ) -> Value {
+        if edited_at.is_none() && edit_version.is_none() {
+            return json!({
+                "doc": doc,
+                "doc_as_upsert": true
+            });
+        }
+
+        if let Some(edit_version) = edit_version {
+            doc.insert("edit_version".to_string(), json!(edit_version));
+        }
+        json!({
+            "scripted_upsert": true,
+            "script": {
+                "source": UPDATE_UNLESS_STALE_SCRIPT,
+                "lang": "painless",
+                "params": {
+                    "doc": doc,
+                    "edited_at": edited_at.map(|edited_at| edited_at.timestamp_millis()),
+                    "edit_version": edit_version
+                }
+            },
+            "upsert": {}
+        })
+    }
+
+    /// Build the fields flagging a document as deleted at `deleted_at`.
+    ///
+    /// The entity and space IDs are included so restores can filter on them, and so
+    /// tombstones of documents that were never created can be told apart.
+    pub(super) fn tombstone(entity_id: &Uuid, space_id: &Uuid, deleted_at: DateTime<Utc>) -> Value {
+        json!({
+            "entity_id": entity_id.to_string(),
+            "space_id": space_id.to_string(),
+            "deleted": true,
+            "deleted_at": deleted_at.to_rfc3339()
+        })
+    }
+
+    /// Parse the space ID of a request into its routing value, the ID in its canonical form.
+    fn space_routing(space_id: Option<&str>) -> Result<Option<String>, SearchIndexError> {
+        space_id.map(Self::space_id_routing).transpose()
+    }
+
+    /// Get the routing of a space, the hyphenated form of its ID.
+    fn space_id_routing(space_id: &str) -> Result<String, SearchIndexError> {
+        Uuid::parse_str(space_id)
+            .map(|space_id| space_id.to_string())
+            .map_err(|e| SearchIndexError::validation(format!("Invalid space_id: {}", e)))
+    }
+
+    /// Build the filter of a search: documents not deleted, in the space if one is given.
+    fn search_filter(space_id: Option<&str>) -> Value {
+        let mut filters = vec![not_deleted_filter()];
+        if let Some(space_id) = space_id {
+            filters.push(json!({ "term": { "space_id": space_id } }));
+        }
+        json!({ "bool": { "filter": filters } })
+    }
+
+    /// Get the language to search a query in: the language of the request, or the one
+    /// detected from the query, if the index has subfields for it.
+    fn query_language(&self, request: &SearchRequest) -> Option<Language> {
+        let language = match request.language.as_deref() {
+            Some(tag) => Language::from_tag(tag),
+            None => Language::detect(&request.query),
+        }?;
+        self.index_config
+            .languages
+            .iter()
+            .any(|indexed| indexed.analyzer() == language.analyzer())
+            .then_some(language)
+    }
+
+    /// Build the body of a text search.
+    ///
+    /// The query matches the words of the name and description, and the prefix of its
+    /// last word, through the subfields of their `search_as_you_type` mappings. With a
+    /// language, it also matches the subfields analyzed in that language, and ranks
+    /// documents in that language higher. With a boost, the ranking signals of documents
+    /// are combined with their relevance.
+    fn text_search_query(
+        request: &SearchRequest,
+        space_id: Option<&str>,
+        language: Option<Language>,
+    ) -> Value {
+        let mut fields = vec![
+            "name^2".to_string(),
+            "name._2gram".to_string(),
+            "name._3gram".to_string(),
+            "description".to_string(),
+            "description._2gram".to_string(),
+            "description._3gram".to_string(),
+        ];
+        let mut should = Vec::new();
+        if let Some(language) = language {
+            fields.push(format!("name.{}^2", language.analyzer()));
+            fields.push(format!("description.{}", language.analyzer()));
+            should.push(json!({ "term": { "language": language.code() } }));
+        }
+
+        let query = json!({
+            "bool": {
+                "must": {
+                    "multi_match": {
+                        "query": request.query,
+                        "type": "bool_prefix",
+                        "fields": fields
+                    }
+                },
+                "should": should,
+                "filter": Self::search_filter(space_id)
+            }
+        });
+
+        json!({
+            "size": request.limit,
+            "query": match request.boost {
+                Some(boost) => Self::boosted_query(query, boost),
+                None => query,
+            },
+            "_source": { "excludes": ["name_embedding", "description_embedding"] }
+        })
+    }
+
+    /// Wrap a query in a `function_score` multiplying its relevance by
+    /// `1 + vote_weight * score + trust_weight * trust_rank`.
+    fn boosted_query(query: Value, boost: RelevanceBoost) -> Value {
+        let signal = |field: &str, weight: f64| {
+            json!({
+                "field_value_factor": {
+                    "field": field,
+                    "factor": weight,
+                    "missing": 0
+                }
+            })
+        };
+
+        json!({
+            "function_score": {
+                "query": query,
+                "functions": [
+                    { "weight": 1 },
+                    signal("score", boost.vote_weight),
+                    signal("trust_rank", boost.trust_weight)
+                ],
+                "score_mode": "sum",
+                "boost_mode": "multiply"
+            }
+        })
+    }
+
+    /// Build the query matching every document of a space.
+    fn space_query(space_id: &str) -> Value {
+        json!({ "term": { "space_id": space_id } })
+    }
+
+    /// Build the body of a semantic search.
+    ///
+    /// Each embedding field gets a knn query for the `k` nearest documents, with the
+    /// filters applied during the graph search rather than after it.
+    fn semantic_search_query(request: &SemanticSearchRequest, space_id: Option<&str>) -> Value {
+        let filter = Self::search_filter(space_id);
+
+        let knn = |field: &str| {
+            json!({
+                "knn": {
+                    field: {
+                        "vector": request.query_vector,
+                        "k": request.k,
+                        "filter": filter
+                    }
+                }
+            })
+        };
+
+        json!({
+            "size": request.k,
+            "query": {
+                "bool": {
+                    "should": [knn("name_embedding"), knn("description_embedding")]
+                }
+            },
+            "_source": { "excludes": ["name_embedding", "description_embedding"] }
+        })
+    }
+
+    /// Read the documents of a search response.
+    fn search_results(body: &Value) -> Result<Vec<SearchResult>, SearchIndexError> {
+        let hits = body
+            .pointer("/hits/hits")
+            .and_then(Value::as_array)
+            .ok_or_else(|| SearchIndexError::parse("Response is missing 'hits.hits'"))?;
+
+        hits.iter()
+            .map(|hit| {
+                let doc_id = hit
+                    .get("_id")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| SearchIndexError::parse("Hit is missing '_id'"))?;
+                // UUIDs have no underscore, so the document ID splits on the first one
+                let (entity_id, space_id) = doc_id.split_once('_').ok_or_else(|| {
+                    SearchIndexError::parse(format!("Invalid document ID '{}'", doc_id))
+                })?;
+                let score = hit.get("_score").and_then(Value::as_f64).unwrap_or(0.0);
+                let document = serde_json::from_value(
+                    hit.get("_source").cloned().unwrap_or_else(|| json!({})),
+                )
+                .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+
+                Ok(SearchResult {
+                    entity_id: entity_id.to_string(),
+                    space_id: space_id.to_string(),
+                    score,
+                    document,
+                })
+            })
+            .collect()
+    }
+
+    /// Build the action line of a bulk operation on a document, routed to the shard of
+    /// its space.
+    pub(super) fn bulk_action(action: &str, doc_id: &str, space_id: &Uuid) -> Value {
+        json!({ action: { "_id": doc_id, "routing": space_id.to_string() } })
+    }
+
+    /// Result of a batch operation settled without being sent.
+    fn succeeded_result(entity_id: &str, space_id: &str) -> BatchOperationResult {
+        BatchOperationResult {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+            success: true,
+            error: None,
+        }
+    }
+
+    /// Result of a batch operation failed before being sent, e.g. on invalid IDs.
+    fn failed_result(
+        entity_id: &str,
+        space_id: &str,
+        error: SearchIndexError,
+    ) -> BatchOperationResult {
+        BatchOperationResult {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+            success: false,
+            error: Some(error),
+        }
+    }
+
+    /// Read a document count (e.g. "updated", "deleted") from a by-query response.
+    pub(super) fn response_count(body: &Value, field: &str) -> Result<u64, SearchIndexError> {
+        body.get(field)
+            .and_then(Value::as_u64)
+            .ok_or_else(|| SearchIndexError::parse(format!("Response is missing '{}'", field)))
+    }
+}
+
+#[async_trait]
+impl SearchIndexProvider for OpenSearchProvider {
+    /// Update specific fields of a document, creating it if it doesn't exist (upsert).
+    ///
+    /// This function performs an upsert operation: if the document exists, only fields that are
+    /// `Some` in the request will be updated; if the document doesn't exist, it will be created
+    /// with the provided fields. Fields that are `None` in the request will be left unchanged
+    /// (for existing documents) or omitted (for new documents).
+    ///
+    /// Updates with `edited_at` are dropped if the document is a tombstone deleted at or
+    /// after that time, which happens when the delete of an entity is consumed before
+    /// one of its earlier edits. Updates with `edit_version` are dropped if the document
+    /// already has an edit of that version or a later one applied, which happens when
+    /// edits are redelivered after a crash.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The update request containing entity_id, space_id, and optional fields
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the document was updated, created, or the update was dropped
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn update_document(&self, request: &UpdateEntityRequest) -> Result<(), SearchIndexError> {
+        // Validate UUIDs
+        let (entity_id, space_id) =
+            utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)?;
+
+        let doc_id = Self::document_id(&entity_id, &space_id);
+        // Documents are routed to the shard of their space
+        let routing = space_id.to_string();
+
+        // Build update document with only provided fields
+        let Some(doc) = Self::update_doc(request, &entity_id, &space_id) else {
             // No fields to update
             return Ok(());
-        }
+        };
 
         // Use upsert to create document if it doesn't exist
         // API reference: https://docs.opensearch.org/latest/api-reference/document-apis/update-document/#using-the-upsert-operation
         let response = self
             .client
             .update(UpdateParts::IndexId(&self.index_config.alias, &doc_id))
-            .body(json!({
-                "doc": doc,
-                "doc_as_upsert": true
-            }))
+            .routing(&routing)
+            .body(Self::update_body(
+                doc,
+                request.edited_at,
+                request.edit_version,
+            ))
             .send()
             .await
             .map_err(|e| SearchIndexError::update(e.to_string()))?;
@@ -254,6 +666,10 @@ impl SearchIndexProvider for OpenSearchProvider {
     /// This function deletes a document identified by entity_id and space_id. If the
     /// document doesn't exist, the operation is considered successful (no error is returned).
     ///
+    /// With `edited_at`, the document is replaced by a tombstone deleted at that time
+    /// instead, created even if the document doesn't exist yet, so an update from an
+    /// older edit arriving later doesn't recreate it.
+    ///
     /// # Arguments
     ///
     /// * `request` - The delete request containing entity_id and space_id
@@ -267,13 +683,27 @@ impl SearchIndexProvider for OpenSearchProvider {
             utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)?;
 
         let doc_id = Self::document_id(&entity_id, &space_id);
+        // Documents are routed to the shard of their space
+        let routing = space_id.to_string();
 
-        let response = self
-            .client
-            .delete(DeleteParts::IndexId(&self.index_config.alias, &doc_id))
-            .send()
-            .await
-            .map_err(|e| SearchIndexError::delete(e.to_string()))?;
+        let response = match request.edited_at {
+            None => {
+                self.client
+                    .delete(DeleteParts::IndexId(&self.index_config.alias, &doc_id))
+                    .routing(&routing)
+                    .send()
+                    .await
+            }
+            Some(edited_at) => {
+                self.client
+                    .index(IndexParts::IndexId(&self.index_config.alias, &doc_id))
+                    .routing(&routing)
+                    .body(Self::tombstone(&entity_id, &space_id, edited_at))
+                    .send()
+                    .await
+            }
+        }
+        .map_err(|e| SearchIndexError::delete(e.to_string()))?;
 
         let status = response.status_code();
 
@@ -287,15 +717,130 @@ impl SearchIndexProvider for OpenSearchProvider {
             )));
         }
 
-        debug!(doc_id = %doc_id, "Document deleted");
+        debug!(doc_id = %doc_id, tombstone = request.edited_at.is_some(), "Document deleted");
+        Ok(())
+    }
+
+    /// Flag a document as deleted so it is excluded from search, keeping it restorable.
+    ///
+    /// The entity and space IDs are written along with the flag so restores can filter on
+    /// them. The document is flagged as deleted at `edited_at`, or now if it isn't set. If
+    /// the document doesn't exist, a tombstone is created in its place, so an update from
+    /// an older edit arriving later doesn't create it.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The delete request containing entity_id and space_id
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the document was flagged, or its tombstone created
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn soft_delete_document(
+        &self,
+        request: &DeleteEntityRequest,
+    ) -> Result<(), SearchIndexError> {
+        let (entity_id, space_id) =
+            utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)?;
+
+        let doc_id = Self::document_id(&entity_id, &space_id);
+        // Documents are routed to the shard of their space
+        let routing = space_id.to_string();
+
+        let response = self
+            .client
+            .update(UpdateParts::IndexId(&self.index_config.alias, &doc_id))
+            .routing(&routing)
+            .body(json!({
+                "doc": Self::tombstone(
+                    &entity_id,
+                    &space_id,
+                    request.edited_at.unwrap_or_else(Utc::now)
+                ),
+                "doc_as_upsert": true
+            }))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::delete(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Soft delete request failed");
+            return Err(SearchIndexError::delete(format!(
+                "Soft delete failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        debug!(doc_id = %doc_id, "Document soft-deleted");
         Ok(())
     }
 
+    /// Fetch the stored fields of a document from the search index.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The get request containing entity_id and space_id
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(IndexedEntityDocument))` - If the document exists
+    /// * `Ok(None)` - If the document doesn't exist
+    /// * `Err(SearchIndexError)` - If the lookup fails
+    async fn get_document(
+        &self,
+        request: &GetEntityRequest,
+    ) -> Result<Option<IndexedEntityDocument>, SearchIndexError> {
+        let (entity_id, space_id) =
+            utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)?;
+
+        let doc_id = Self::document_id(&entity_id, &space_id);
+        // Documents are routed to the shard of their space
+        let routing = space_id.to_string();
+
+        let response = self
+            .client
+            .get(GetParts::IndexId(&self.index_config.alias, &doc_id))
+            .routing(&routing)
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::connection(e.to_string()))?;
+
+        let status = response.status_code();
+
+        if status.as_u16() == 404 {
+            return Ok(None);
+        }
+
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Get request failed");
+            return Err(SearchIndexError::unknown(format!(
+                "Get failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+
+        match body.get("_source") {
+            Some(source) => serde_json::from_value(source.clone())
+                .map(Some)
+                .map_err(|e| SearchIndexError::parse(e.to_string())),
+            None => Ok(None),
+        }
+    }
+
     /// Update multiple documents in bulk and return a summary of successful and failed operations.
     ///
-    /// This function updates multiple documents by calling `update_document` for each request
-    /// and collecting the results. Returns a summary indicating which updates succeeded and
-    /// which failed, along with error details for failed operations.
+    /// The updates are sent as `_bulk` requests of the configured target size, with the
+    /// same bodies as `update_document`. Updates of the same document are kept in the
+    /// same request, in order. Updates rejected by the cluster with a 429 are retried
+    /// with backoff, shrinking the size of later requests.
     ///
     /// # Arguments
     ///
@@ -309,46 +854,45 @@ impl SearchIndexProvider for OpenSearchProvider {
         &self,
         requests: &[UpdateEntityRequest],
     ) -> Result<BatchOperationSummary, SearchIndexError> {
-        let mut results = Vec::new();
-        let mut succeeded = 0;
-        let mut failed = 0;
-
-        for request in requests {
-            match SearchIndexProvider::update_document(self, request).await {
-                Ok(()) => {
-                    succeeded += 1;
-                    results.push(BatchOperationResult {
-                        entity_id: request.entity_id.clone(),
-                        space_id: request.space_id.clone(),
-                        success: true,
-                        error: None,
-                    });
-                }
-                Err(e) => {
-                    failed += 1;
-                    results.push(BatchOperationResult {
-                        entity_id: request.entity_id.clone(),
-                        space_id: request.space_id.clone(),
-                        success: false,
-                        error: Some(e.clone()),
-                    });
-                }
-            }
-        }
+        let operations = requests
+            .iter()
+            .map(|request| {
+                let (entity_id, space_id) =
+                    utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)
+                        .map_err(|e| {
+                            Self::failed_result(&request.entity_id, &request.space_id, e)
+                        })?;
+                let doc_id = Self::document_id(&entity_id, &space_id);
 
-        Ok(BatchOperationSummary {
-            total: requests.len(),
-            succeeded,
-            failed,
-            results,
-        })
+                let Some(doc) = Self::update_doc(request, &entity_id, &space_id) else {
+                    // No fields to update
+                    return Err(Self::succeeded_result(
+                        &request.entity_id,
+                        &request.space_id,
+                    ));
+                };
+
+                Ok(BulkOperation::new(
+                    &request.entity_id,
+                    &request.space_id,
+                    doc_id.clone(),
+                    vec![
+                        Self::bulk_action("update", &doc_id, &space_id),
+                        Self::update_body(doc, request.edited_at, request.edit_version),
+                    ],
+                    false,
+                ))
+            })
+            .collect();
+
+        Ok(self.execute_bulk(operations).await)
     }
 
     /// Delete multiple documents in bulk and return a summary of successful and failed operations.
     ///
-    /// This function deletes multiple documents by calling `delete_document` for each request
-    /// and collecting the results. Returns a summary indicating which deletions succeeded and
-    /// which failed. Note that documents not found are considered successful deletions.
+    /// The deletes are sent as `_bulk` requests like the updates of
+    /// `bulk_update_documents`. Deletes with `edited_at` index tombstones instead, like
+    /// `delete_document`.
     ///
     /// # Arguments
     ///
@@ -366,60 +910,203 @@ impl SearchIndexProvider for OpenSearchProvider {
         &self,
         requests: &[DeleteEntityRequest],
     ) -> Result<BatchOperationSummary, SearchIndexError> {
-        let mut results = Vec::new();
-        let mut succeeded = 0;
-        let mut failed = 0;
-
-        for request in requests {
-            match SearchIndexProvider::delete_document(self, request).await {
-                Ok(()) => {
-                    succeeded += 1;
-                    results.push(BatchOperationResult {
-                        entity_id: request.entity_id.clone(),
-                        space_id: request.space_id.clone(),
-                        success: true,
-                        error: None,
-                    });
-                }
-                Err(e) => {
-                    // Document not found is considered a successful delete
-                    if matches!(e, SearchIndexError::DocumentNotFound(_)) {
-                        succeeded += 1;
-                        results.push(BatchOperationResult {
-                            entity_id: request.entity_id.clone(),
-                            space_id: request.space_id.clone(),
-                            success: true,
-                            error: None,
-                        });
-                    } else {
-                        failed += 1;
-                        results.push(BatchOperationResult {
-                            entity_id: request.entity_id.clone(),
-                            space_id: request.space_id.clone(),
-                            success: false,
-                            error: Some(e.clone()),
-                        });
-                    }
-                }
-            }
-        }
+        let operations = requests
+            .iter()
+            .map(|request| {
+                let (entity_id, space_id) =
+                    utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)
+                        .map_err(|e| {
+                            Self::failed_result(&request.entity_id, &request.space_id, e)
+                        })?;
+                let doc_id = Self::document_id(&entity_id, &space_id);
 
-        Ok(BatchOperationSummary {
-            total: requests.len(),
-            succeeded,
-            failed,
-            results,
-        })
+                let lines = match request.edited_at {
+                    None => vec![Self::bulk_action("delete", &doc_id, &space_id)],
+                    Some(edited_at) => vec![
+                        Self::bulk_action("index", &doc_id, &space_id),
+                        Self::tombstone(&entity_id, &space_id, edited_at),
+                    ],
+                };
+                Ok(BulkOperation::new(
+                    &request.entity_id,
+                    &request.space_id,
+                    doc_id,
+                    lines,
+                    true,
+                ))
+            })
+            .collect();
+
+        Ok(self.execute_bulk(operations).await)
     }
 
-    /// Unset (remove) specific properties from a document.
+    /// Soft-delete multiple documents and return a summary of successful and failed operations.
     ///
-    /// This function removes the specified property keys from a document using a Painless script.
-    /// If a property doesn't exist, it is safely ignored. The document must exist (this is not an upsert).
+    /// The soft deletes are sent as `_bulk` requests like the updates of
+    /// `bulk_update_documents`, with the same bodies as `soft_delete_document`. Documents
+    /// not found are replaced by tombstones.
     ///
     /// # Arguments
     ///
-    /// * `request` - The unset request containing entity_id, space_id, and property keys to remove
+    /// * `requests` - Slice of delete requests, each containing entity_id and space_id
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BatchOperationSummary)` - Contains total count, succeeded count, failed count,
+    ///   and individual results for each request with success status and optional error
+    async fn bulk_soft_delete_documents(
+        &self,
+        requests: &[DeleteEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        let operations = requests
+            .iter()
+            .map(|request| {
+                let (entity_id, space_id) =
+                    utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)
+                        .map_err(|e| {
+                            Self::failed_result(&request.entity_id, &request.space_id, e)
+                        })?;
+                let doc_id = Self::document_id(&entity_id, &space_id);
+
+                Ok(BulkOperation::new(
+                    &request.entity_id,
+                    &request.space_id,
+                    doc_id.clone(),
+                    vec![
+                        Self::bulk_action("update", &doc_id, &space_id),
+                        json!({
+                            "doc": Self::tombstone(
+                                &entity_id,
+                                &space_id,
+                                request.edited_at.unwrap_or_else(Utc::now)
+                            ),
+                            "doc_as_upsert": true
+                        }),
+                    ],
+                    false,
+                ))
+            })
+            .collect();
+
+        Ok(self.execute_bulk(operations).await)
+    }
+
+    /// Clear the deleted flag of every soft-deleted document matching the request's filters.
+    ///
+    /// Uses `_update_by_query`, so a mass deletion is undone in a single request. Version
+    /// conflicts with concurrent updates are skipped, since those updates already restore
+    /// the documents they touch.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The restore request with the entity, space and deletion time filters
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of restored documents
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn restore_documents(
+        &self,
+        request: &RestoreEntitiesRequest,
+    ) -> Result<u64, SearchIndexError> {
+        let query = Self::restore_query(request)?;
+        let routing = Self::space_routing(request.space_id.as_deref())?;
+        let routing: Vec<&str> = routing.iter().map(String::as_str).collect();
+
+        let indices = [self.index_config.alias.as_str()];
+        let mut update_by_query = self
+            .client
+            .update_by_query(UpdateByQueryParts::Index(&indices))
+            .conflicts(Conflicts::Proceed)
+            .refresh(true);
+        if !routing.is_empty() {
+            // Only search the shard of the space
+            update_by_query = update_by_query.routing(&routing);
+        }
+        let response = update_by_query
+            .body(json!({
+                "query": query,
+                "script": {
+                    "source": "ctx._source.deleted = false; ctx._source.remove(\"deleted_at\")",
+                    "lang": "painless"
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::update(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Restore request failed");
+            return Err(SearchIndexError::update(format!(
+                "Restore failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let restored = Self::response_count(&body, "updated")?;
+
+        info!(restored, request = ?request, "Restored soft-deleted documents");
+        Ok(restored)
+    }
+
+    /// Remove soft-deleted documents deleted before `deleted_before` from the index.
+    ///
+    /// # Arguments
+    ///
+    /// * `deleted_before` - Documents soft-deleted before this time are removed
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of removed documents
+    /// * `Err(SearchIndexError)` - If the operation fails
+    async fn purge_deleted_documents(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError> {
+        let response = self
+            .client
+            .delete_by_query(DeleteByQueryParts::Index(&[&self.index_config.alias]))
+            .conflicts(Conflicts::Proceed)
+            .refresh(true)
+            .body(json!({ "query": Self::purge_query(deleted_before) }))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::delete(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Purge request failed");
+            return Err(SearchIndexError::delete(format!(
+                "Purge failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let purged = Self::response_count(&body, "deleted")?;
+
+        info!(purged, deleted_before = %deleted_before, "Purged soft-deleted documents");
+        Ok(purged)
+    }
+
+    /// Unset (remove) specific properties from a document.
+    ///
+    /// This function removes the specified property keys from a document using a Painless script.
+    /// If a property doesn't exist, it is safely ignored. The document must exist (this is not an upsert).
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The unset request containing entity_id, space_id, and property keys to remove
     ///
     /// # Returns
     ///
@@ -434,6 +1121,8 @@ impl SearchIndexProvider for OpenSearchProvider {
             utils::parse_entity_and_space_ids(&request.entity_id, &request.space_id)?;
 
         let doc_id = Self::document_id(&entity_id, &space_id);
+        // Documents are routed to the shard of their space
+        let routing = space_id.to_string();
 
         // Build Painless script to safely remove multiple fields
         // Validation and sanitization of property_keys happens
@@ -444,6 +1133,7 @@ impl SearchIndexProvider for OpenSearchProvider {
         let response = self
             .client
             .update(UpdateParts::IndexId(&self.index_config.alias, &doc_id))
+            .routing(&routing)
             .body(json!({
                 "script": {
                     "source": script_source,
@@ -471,6 +1161,291 @@ impl SearchIndexProvider for OpenSearchProvider {
         );
         Ok(())
     }
+    /// Find the documents nearest to a query embedding with knn queries on the name and
+    /// description embeddings.
+    ///
+    /// Both embeddings are searched for the `k` nearest documents, filtered while
+    /// searching on the space and to exclude deleted documents, and a document's score is
+    /// the sum of its scores on both. Only documents with their IDs written, i.e. updated
+    /// since the IDs are stored, match the space filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The semantic search request containing the query vector, k, and an
+    ///   optional space filter
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SearchResult>)` - Up to `k` documents, the closest first
+    /// * `Err(SearchIndexError::EmbeddingError)` - If the query vector doesn't have
+    ///   [`EMBEDDING_DIMENSION`] dimensions
+    /// * `Err(SearchIndexError)` - If the space ID is invalid or the search fails
+    async fn semantic_search(
+        &self,
+        request: &SemanticSearchRequest,
+    ) -> Result<Vec<SearchResult>, SearchIndexError> {
+        if request.query_vector.len() != EMBEDDING_DIMENSION {
+            return Err(SearchIndexError::embedding(format!(
+                "Query vector has {} dimensions, the index has {}",
+                request.query_vector.len(),
+                EMBEDDING_DIMENSION
+            )));
+        }
+        let routing = Self::space_routing(request.space_id.as_deref())?;
+        let routing: Vec<&str> = routing.iter().map(String::as_str).collect();
+
+        let indices = [self.index_config.alias.as_str()];
+        let mut search = self.client.search(SearchParts::Index(&indices));
+        if !routing.is_empty() {
+            // Only search the shard of the space
+            search = search.routing(&routing);
+        }
+        let response = search
+            .body(Self::semantic_search_query(
+                request,
+                routing.first().copied(),
+            ))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::search(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Semantic search request failed");
+            return Err(SearchIndexError::search(format!(
+                "Semantic search failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let results = Self::search_results(&body)?;
+
+        debug!(
+            k = request.k,
+            results = results.len(),
+            "Semantic search completed"
+        );
+        Ok(results)
+    }
+
+    async fn search(&self, request: &SearchRequest) -> Result<Vec<SearchResult>, SearchIndexError> {
+        let routing = Self::space_routing(request.space_id.as_deref())?;
+        let routing: Vec<&str> = routing.iter().map(String::as_str).collect();
+
+        let indices = [self.index_config.alias.as_str()];
+        let mut search = self.client.search(SearchParts::Index(&indices));
+        if !routing.is_empty() {
+            // Only search the shard of the space
+            search = search.routing(&routing);
+        }
+        let response = search
+            .body(Self::text_search_query(
+                request,
+                routing.first().copied(),
+                self.query_language(request),
+            ))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::search(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Search request failed");
+            return Err(SearchIndexError::search(format!(
+                "Search failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let results = Self::search_results(&body)?;
+
+        debug!(
+            limit = request.limit,
+            results = results.len(),
+            "Search completed"
+        );
+        Ok(results)
+    }
+
+    async fn delete_space_documents(&self, space_id: &str) -> Result<u64, SearchIndexError> {
+        let routing = Self::space_id_routing(space_id)?;
+        let routing = [routing.as_str()];
+
+        let indices = [self.index_config.alias.as_str()];
+        let response = self
+            .client
+            .delete_by_query(DeleteByQueryParts::Index(&indices))
+            .conflicts(Conflicts::Proceed)
+            .refresh(true)
+            .routing(&routing)
+            .body(json!({ "query": Self::space_query(routing[0]) }))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::delete(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Space delete request failed");
+            return Err(SearchIndexError::delete(format!(
+                "Space delete failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let deleted = Self::response_count(&body, "deleted")?;
+
+        info!(deleted, space_id = %space_id, "Deleted space documents");
+        Ok(deleted)
+    }
+
+    async fn soft_delete_space_documents(
+        &self,
+        space_id: &str,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError> {
+        let routing = Self::space_id_routing(space_id)?;
+        let routing = [routing.as_str()];
+
+        let indices = [self.index_config.alias.as_str()];
+        let response = self
+            .client
+            .update_by_query(UpdateByQueryParts::Index(&indices))
+            .conflicts(Conflicts::Proceed)
+            .refresh(true)
+            .routing(&routing)
+            .body(json!({
+                // Documents already deleted keep their deletion time
+                "query": {
+                    "bool": {
+                        "filter": [Self::space_query(routing[0]), not_deleted_filter()]
+                    }
+                },
+                "script": {
+                    "source": "ctx._source.deleted = true; ctx._source.deleted_at = params.deleted_at",
+                    "lang": "painless",
+                    "params": { "deleted_at": deleted_at.to_rfc3339() }
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::delete(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Space soft delete request failed");
+            return Err(SearchIndexError::delete(format!(
+                "Space soft delete failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let deleted = Self::response_count(&body, "updated")?;
+
+        info!(deleted, space_id = %space_id, "Soft-deleted space documents");
+        Ok(deleted)
+    }
+    async fn bulk_update_vote_counts(
+        &self,
+        updates: &[VoteCountUpdate],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        let operations = updates
+            .iter()
+            .map(|update| {
+                let (entity_id, space_id) =
+                    utils::parse_entity_and_space_ids(&update.entity_id, &update.space_id)
+                        .map_err(|e| Self::failed_result(&update.entity_id, &update.space_id, e))?;
+                let doc_id = Self::document_id(&entity_id, &space_id);
+                let score = ranking::vote_score(update.upvotes, update.downvotes);
+
+                // No upsert: votes don't create documents, so missing ones are skipped
+                Ok(BulkOperation::new(
+                    &update.entity_id,
+                    &update.space_id,
+                    doc_id.clone(),
+                    vec![
+                        Self::bulk_action("update", &doc_id, &space_id),
+                        json!({ "doc": { "score": score } }),
+                    ],
+                    true,
+                ))
+            })
+            .collect();
+
+        Ok(self.execute_bulk(operations).await)
+    }
+
+    async fn set_space_trust_rank(
+        &self,
+        space_id: &str,
+        trust_rank: f64,
+    ) -> Result<u64, SearchIndexError> {
+        let routing = Self::space_id_routing(space_id)?;
+        let routing = [routing.as_str()];
+
+        let indices = [self.index_config.alias.as_str()];
+        let response = self
+            .client
+            .update_by_query(UpdateByQueryParts::Index(&indices))
+            .conflicts(Conflicts::Proceed)
+            .refresh(true)
+            .routing(&routing)
+            .body(json!({
+                "query": {
+                    "bool": {
+                        "filter": Self::space_query(routing[0]),
+                        // Documents already at the rank don't need a rewrite
+                        "must_not": { "term": { "trust_rank": trust_rank } }
+                    }
+                },
+                "script": {
+                    "source": "ctx._source.trust_rank = params.trust_rank",
+                    "lang": "painless",
+                    "params": { "trust_rank": trust_rank }
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::update(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Trust rank update request failed");
+            return Err(SearchIndexError::update(format!(
+                "Trust rank update failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let updated = Self::response_count(&body, "updated")?;
+
+        info!(updated, space_id = %space_id, trust_rank, "Updated space trust rank");
+        Ok(updated)
+    }
 }
 
 #[cfg(test)]
@@ -660,4 +1635,352 @@ mod tests {
             SearchIndexError::ValidationError(_)
         ));
     }
+
+    #[test]
+    fn test_restore_query_filters() {
+        let request = RestoreEntitiesRequest {
+            space_id: Some("6ba7b810-9dad-11d1-80b4-00c04fd430c8".to_string()),
+            deleted_since: Some(
+                DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+                    .unwrap()
+                    .into(),
+            ),
+            ..Default::default()
+        };
+
+        let query = OpenSearchProvider::restore_query(&request).unwrap();
+
+        assert_eq!(
+            query,
+            json!({
+                "bool": {
+                    "filter": [
+                        { "term": { "deleted": true } },
+                        { "term": { "space_id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8" } },
+                        { "range": { "deleted_at": { "gte": "2026-01-01T00:00:00+00:00" } } }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_restore_query_invalid_id() {
+        let request = RestoreEntitiesRequest {
+            entity_id: Some("not-a-uuid".to_string()),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            OpenSearchProvider::restore_query(&request).unwrap_err(),
+            SearchIndexError::ValidationError(_)
+        ));
+    }
+
+    #[test]
+    fn test_response_count() {
+        let body = json!({ "updated": 3, "deleted": 0 });
+
+        assert_eq!(
+            OpenSearchProvider::response_count(&body, "updated").unwrap(),
+            3
+        );
+        assert!(OpenSearchProvider::response_count(&body, "total").is_err());
+    }
+
+    #[test]
+    fn test_update_body_without_edit_time() {
+        let mut doc = serde_json::Map::new();
+        doc.insert("name".to_string(), json!("Entity"));
+
+        let body = OpenSearchProvider::update_body(doc, None, None);
+
+        assert_eq!(
+            body,
+            json!({ "doc": { "name": "Entity" }, "doc_as_upsert": true })
+        );
+    }
+
+    #[test]
+    fn test_update_body_with_edit_time() {
+        let mut doc = serde_json::Map::new();
+        doc.insert("name".to_string(), json!("Entity"));
+        let edited_at: DateTime<Utc> = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .into();
+
+        let body = OpenSearchProvider::update_body(doc, Some(edited_at), None);
+
+        // Scripted so the update can be dropped when it's older than a tombstone
+        assert_eq!(body["scripted_upsert"], true);
+        assert_eq!(body["upsert"], json!({}));
+        assert_eq!(body["script"]["source"], UPDATE_UNLESS_STALE_SCRIPT);
+        assert_eq!(body["script"]["params"]["doc"], json!({ "name": "Entity" }));
+        assert_eq!(
+            body["script"]["params"]["edited_at"],
+            edited_at.timestamp_millis()
+        );
+        assert_eq!(body["script"]["params"]["edit_version"], Value::Null);
+    }
+
+    #[test]
+    fn test_update_body_with_edit_version() {
+        let mut doc = serde_json::Map::new();
+        doc.insert("name".to_string(), json!("Entity"));
+
+        let body = OpenSearchProvider::update_body(doc, None, Some(42));
+
+        // Scripted so a redelivered edit is dropped, and the version stored
+        assert_eq!(body["scripted_upsert"], true);
+        assert_eq!(body["script"]["source"], UPDATE_UNLESS_STALE_SCRIPT);
+        assert_eq!(
+            body["script"]["params"]["doc"],
+            json!({ "name": "Entity", "edit_version": 42 })
+        );
+        assert_eq!(body["script"]["params"]["edited_at"], Value::Null);
+        assert_eq!(body["script"]["params"]["edit_version"], 42);
+    }
+
+    #[test]
+    fn test_tombstone() {
+        let entity_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let space_id = Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+        let deleted_at: DateTime<Utc> = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .into();
+
+        let tombstone = OpenSearchProvider::tombstone(&entity_id, &space_id, deleted_at);
+
+        assert_eq!(
+            tombstone,
+            json!({
+                "entity_id": "550e8400-e29b-41d4-a716-446655440000",
+                "space_id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+                "deleted": true,
+                "deleted_at": "2026-01-01T00:00:00+00:00"
+            })
+        );
+        // The update script parses the deletion time back
+        let stored: IndexedEntityDocument = serde_json::from_value(tombstone).unwrap();
+        assert_eq!(stored.deleted_at, Some(deleted_at));
+        assert!(stored.is_empty());
+    }
+
+    #[test]
+    fn test_update_doc() {
+        let entity_id = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let space_id = Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+        let mut request = UpdateEntityRequest {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+            name: None,
+            description: None,
+            avatar: None,
+            cover: None,
+            entity_global_score: None,
+            space_score: None,
+            entity_space_score: None,
+            edited_at: None,
+            edit_version: None,
+            name_embedding: None,
+            description_embedding: None,
+            language: None,
+            score: None,
+            trust_rank: None,
+        };
+
+        // Nothing to update, the language alone doesn't describe any field
+        request.language = Some("en".to_string());
+        assert!(OpenSearchProvider::update_doc(&request, &entity_id, &space_id).is_none());
+
+        request.name = Some("Entity".to_string());
+        request.name_embedding = Some(vec![0.5, 0.25]);
+        let doc = OpenSearchProvider::update_doc(&request, &entity_id, &space_id).unwrap();
+
+        assert_eq!(
+            Value::Object(doc),
+            json!({
+                "name": "Entity",
+                "language": "en",
+                "name_embedding": [0.5, 0.25],
+                "entity_id": "550e8400-e29b-41d4-a716-446655440000",
+                "space_id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+                "deleted": false
+            })
+        );
+    }
+
+    #[test]
+    fn test_semantic_search_query() {
+        let space_id = Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+        let request = SemanticSearchRequest {
+            query_vector: vec![0.5, 0.25],
+            k: 10,
+            space_id: Some(space_id.to_string()),
+        };
+
+        let query =
+            OpenSearchProvider::semantic_search_query(&request, Some(&space_id.to_string()));
+
+        assert_eq!(query["size"], 10);
+        let should = query["query"]["bool"]["should"].as_array().unwrap();
+        assert_eq!(should.len(), 2);
+        let knn = &should[0]["knn"]["name_embedding"];
+        assert_eq!(knn["vector"], json!([0.5, 0.25]));
+        assert_eq!(knn["k"], 10);
+        assert_eq!(
+            knn["filter"]["bool"]["filter"],
+            json!([
+                not_deleted_filter(),
+                { "term": { "space_id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8" } }
+            ])
+        );
+        assert!(should[1]["knn"]["description_embedding"].is_object());
+
+        // Without a space, only deleted documents are filtered out
+        let query = OpenSearchProvider::semantic_search_query(&request, None);
+        assert_eq!(
+            query["query"]["bool"]["should"][0]["knn"]["name_embedding"]["filter"]["bool"]
+                ["filter"],
+            json!([not_deleted_filter()])
+        );
+    }
+
+    #[test]
+    fn test_search_results() {
+        let body = json!({
+            "hits": {
+                "hits": [{
+                    "_id": "550e8400-e29b-41d4-a716-446655440000_6ba7b810-9dad-11d1-80b4-00c04fd430c8",
+                    "_score": 1.5,
+                    "_source": { "name": "Entity", "deleted": false }
+                }]
+            }
+        });
+
+        let results = OpenSearchProvider::search_results(&body).unwrap();
+
+        assert_eq!(
+            results,
+            vec![SearchResult {
+                entity_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+                space_id: "6ba7b810-9dad-11d1-80b4-00c04fd430c8".to_string(),
+                score: 1.5,
+                document: IndexedEntityDocument {
+                    name: Some("Entity".to_string()),
+                    ..Default::default()
+                },
+            }]
+        );
+        assert!(OpenSearchProvider::search_results(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_space_routing() {
+        assert_eq!(OpenSearchProvider::space_routing(None).unwrap(), None);
+        // The routing is the canonical form of the ID, whatever form the request uses
+        assert_eq!(
+            OpenSearchProvider::space_routing(Some("6BA7B8109DAD11D180B400C04FD430C8")).unwrap(),
+            Some("6ba7b810-9dad-11d1-80b4-00c04fd430c8".to_string())
+        );
+        assert!(matches!(
+            OpenSearchProvider::space_routing(Some("invalid")),
+            Err(SearchIndexError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_bulk_action() {
+        let space_id = Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+
+        assert_eq!(
+            OpenSearchProvider::bulk_action("update", "doc", &space_id),
+            json!({
+                "update": {
+                    "_id": "doc",
+                    "routing": "6ba7b810-9dad-11d1-80b4-00c04fd430c8"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_text_search_query() {
+        let request = SearchRequest {
+            query: "alic".to_string(),
+            space_id: None,
+            limit: 20,
+            language: Some("en".to_string()),
+            boost: None,
+        };
+
+        let query = OpenSearchProvider::text_search_query(
+            &request,
+            Some("6ba7b810-9dad-11d1-80b4-00c04fd430c8"),
+            Some(Language::English),
+        );
+
+        assert_eq!(query["size"], 20);
+        let multi_match = &query["query"]["bool"]["must"]["multi_match"];
+        assert_eq!(multi_match["query"], "alic");
+        assert_eq!(multi_match["type"], "bool_prefix");
+        let fields = multi_match["fields"].as_array().unwrap();
+        assert!(fields.contains(&json!("name.english^2")));
+        assert!(fields.contains(&json!("description.english")));
+        assert_eq!(
+            query["query"]["bool"]["should"],
+            json!([{ "term": { "language": "en" } }])
+        );
+        assert_eq!(
+            query["query"]["bool"]["filter"]["bool"]["filter"],
+            json!([
+                not_deleted_filter(),
+                { "term": { "space_id": "6ba7b810-9dad-11d1-80b4-00c04fd430c8" } }
+            ])
+        );
+        assert_eq!(
+            query["_source"]["excludes"],
+            json!(["name_embedding", "description_embedding"])
+        );
+
+        // Without a language, only the default analyzer is searched
+        let query = OpenSearchProvider::text_search_query(&request, None, None);
+        assert_eq!(
+            query["query"]["bool"]["must"]["multi_match"]["fields"]
+                .as_array()
+                .unwrap()
+                .len(),
+            6
+        );
+        assert_eq!(query["query"]["bool"]["should"], json!([]));
+    }
+
+    #[test]
+    fn test_text_search_query_boost() {
+        let request = SearchRequest {
+            query: "alice".to_string(),
+            space_id: None,
+            limit: 10,
+            language: None,
+            boost: Some(RelevanceBoost {
+                vote_weight: 2.0,
+                trust_weight: 3.0,
+            }),
+        };
+
+        let query = OpenSearchProvider::text_search_query(&request, None, None);
+
+        let function_score = &query["query"]["function_score"];
+        assert!(function_score["query"]["bool"]["must"]["multi_match"].is_object());
+        assert_eq!(
+            function_score["functions"],
+            json!([
+                { "weight": 1 },
+                { "field_value_factor": { "field": "score", "factor": 2.0, "missing": 0 } },
+                { "field_value_factor": { "field": "trust_rank", "factor": 3.0, "missing": 0 } }
+            ])
+        );
+        assert_eq!(function_score["score_mode"], "sum");
+        assert_eq!(function_score["boost_mode"], "multiply");
+    }
 }
@@ -0,0 +1,372 @@
+//! OpenSearch implementation of the reindex provider.
+//!
+//! Versioned indices are named with [`get_versioned_index_name`], and the alias is the
+//! one of the provider's `IndexConfig`.
+
+use async_trait::async_trait;
+use opensearch::indices::{
+    IndicesCreateParts, IndicesDeleteParts, IndicesGetAliasParts, IndicesPutIndexTemplateParts,
+};
+use serde_json::{json, Value};
+use tracing::{error, info};
+
+use crate::errors::SearchIndexError;
+use crate::interfaces::ReindexProvider;
+use crate::opensearch::index_config::{
+    get_index_settings, get_index_template, get_versioned_index_name, INDEX_NAME,
+};
+use crate::opensearch::OpenSearchProvider;
+
+/// Routes a copied document with the space ID, the part of its `_id` after the entity ID.
+const COPY_ROUTING_SCRIPT: &str = "ctx._routing = ctx._id.substring(ctx._id.indexOf('_') + 1)";
+
+impl OpenSearchProvider {
+    /// Create or replace the index template of versioned indices with
+    /// [`get_index_template`], for the languages of the provider's `IndexConfig`.
+    ///
+    /// [`ReindexProvider::create_index`] puts the template before creating an index, so
+    /// it always matches the settings of the latest index.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the template was stored
+    /// * `Err(SearchIndexError::IndexCreationError)` - If the request fails
+    pub async fn put_index_template(&self) -> Result<(), SearchIndexError> {
+        let response = self
+            .client
+            .indices()
+            .put_index_template(IndicesPutIndexTemplateParts::Name(INDEX_NAME))
+            .body(get_index_template(&self.index_config.languages))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::index_creation(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Put index template request failed");
+            return Err(SearchIndexError::index_creation(format!(
+                "Put index template failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        info!(template = INDEX_NAME, "Index template stored");
+        Ok(())
+    }
+
+    /// Parse the version of a versioned index name, e.g. 3 for `entities_v3`.
+    fn parse_index_version(index: &str) -> Option<u32> {
+        index
+            .strip_prefix(INDEX_NAME)?
+            .strip_prefix("_v")?
+            .parse()
+            .ok()
+    }
+
+    /// Build the body of a `_reindex` request copying an index into another.
+    ///
+    /// Copies only create documents, so documents dual writes created in the destination
+    /// are kept, and the resulting version conflicts don't abort the copy. Each copy is
+    /// routed to the shard of its space, so reindexing also routes documents indexed
+    /// before routing was introduced.
+    fn copy_body(from_version: u32, to_version: u32) -> Value {
+        json!({
+            "conflicts": "proceed",
+            "source": { "index": get_versioned_index_name(Some(from_version)) },
+            "dest": {
+                "index": get_versioned_index_name(Some(to_version)),
+                "op_type": "create"
+            },
+            "script": {
+                "source": COPY_ROUTING_SCRIPT,
+                "lang": "painless"
+            }
+        })
+    }
+
+    /// Build the `_aliases` actions moving `alias` from an index to another.
+    ///
+    /// The removal must match an existing alias, so the whole request fails if the alias
+    /// was moved since it was read.
+    fn switch_alias_actions(alias: &str, from_version: Option<u32>, to_version: u32) -> Value {
+        let mut actions = Vec::new();
+        if let Some(from_version) = from_version {
+            actions.push(json!({
+                "remove": {
+                    "index": get_versioned_index_name(Some(from_version)),
+                    "alias": alias,
+                    "must_exist": true
+                }
+            }));
+        }
+        actions.push(json!({
+            "add": {
+                "index": get_versioned_index_name(Some(to_version)),
+                "alias": alias
+            }
+        }));
+
+        json!({ "actions": actions })
+    }
+}
+
+#[async_trait]
+impl ReindexProvider for OpenSearchProvider {
+    /// Get the version of the index the alias points to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(u32))` - The version of the aliased index
+    /// * `Ok(None)` - If the alias doesn't exist yet
+    /// * `Err(SearchIndexError)` - If the alias points to several indices, to an index
+    ///   that isn't versioned, or the lookup fails
+    async fn aliased_version(&self) -> Result<Option<u32>, SearchIndexError> {
+        let alias = self.index_config.alias.as_str();
+        let response = self
+            .client
+            .indices()
+            .get_alias(IndicesGetAliasParts::Name(&[alias]))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::connection(e.to_string()))?;
+
+        let status = response.status_code();
+        if status.as_u16() == 404 {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Get alias request failed");
+            return Err(SearchIndexError::reindex(format!(
+                "Get alias failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        let indices: Vec<&String> = body
+            .as_object()
+            .map(|indices| indices.keys().collect())
+            .unwrap_or_default();
+
+        match indices.as_slice() {
+            [] => Ok(None),
+            [index] => Self::parse_index_version(index).map(Some).ok_or_else(|| {
+                SearchIndexError::reindex(format!(
+                    "Alias {} points to unversioned index {}",
+                    alias, index
+                ))
+            }),
+            _ => Err(SearchIndexError::reindex(format!(
+                "Alias {} points to several indices: {:?}",
+                alias, indices
+            ))),
+        }
+    }
+
+    /// Create the index of a version with [`get_index_settings`], after storing the index
+    /// template with the same settings.
+    async fn create_index(&self, version: u32) -> Result<(), SearchIndexError> {
+        self.put_index_template().await?;
+
+        let index = get_versioned_index_name(Some(version));
+        let response = self
+            .client
+            .indices()
+            .create(IndicesCreateParts::Index(&index))
+            .body(get_index_settings(
+                Some(version),
+                &self.index_config.languages,
+            ))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::index_creation(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Create index request failed");
+            return Err(SearchIndexError::index_creation(format!(
+                "Create index {} failed with status {}: {}",
+                index, status, error_body
+            )));
+        }
+
+        info!(index = %index, "Index created");
+        Ok(())
+    }
+
+    /// Copy the documents of an index into another with `_reindex`, waiting for the copy
+    /// to complete.
+    async fn copy_documents(
+        &self,
+        from_version: u32,
+        to_version: u32,
+    ) -> Result<u64, SearchIndexError> {
+        let response = self
+            .client
+            .reindex()
+            .wait_for_completion(true)
+            .refresh(true)
+            .body(Self::copy_body(from_version, to_version))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::reindex(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Reindex request failed");
+            return Err(SearchIndexError::reindex(format!(
+                "Copy failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SearchIndexError::parse(e.to_string()))?;
+        if let Some(failures) = body
+            .get("failures")
+            .and_then(Value::as_array)
+            .filter(|failures| !failures.is_empty())
+        {
+            return Err(SearchIndexError::reindex(format!(
+                "Copy had {} failures: {}",
+                failures.len(),
+                failures[0]
+            )));
+        }
+        let copied = Self::response_count(&body, "created")?;
+
+        info!(from_version, to_version, copied, "Documents copied");
+        Ok(copied)
+    }
+
+    /// Move the alias with a single `_aliases` request, which applies all its actions
+    /// atomically.
+    async fn switch_alias(
+        &self,
+        from_version: Option<u32>,
+        to_version: u32,
+    ) -> Result<(), SearchIndexError> {
+        let response = self
+            .client
+            .indices()
+            .update_aliases()
+            .body(Self::switch_alias_actions(
+                &self.index_config.alias,
+                from_version,
+                to_version,
+            ))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::reindex(e.to_string()))?;
+
+        let status = response.status_code();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Update aliases request failed");
+            return Err(SearchIndexError::reindex(format!(
+                "Switch alias failed with status {}: {}",
+                status, error_body
+            )));
+        }
+
+        info!(
+            alias = %self.index_config.alias,
+            ?from_version,
+            to_version,
+            "Alias switched"
+        );
+        Ok(())
+    }
+
+    /// Delete the index of a version.
+    async fn delete_index(&self, version: u32) -> Result<(), SearchIndexError> {
+        let index = get_versioned_index_name(Some(version));
+        let response = self
+            .client
+            .indices()
+            .delete(IndicesDeleteParts::Index(&[&index]))
+            .send()
+            .await
+            .map_err(|e| SearchIndexError::delete(e.to_string()))?;
+
+        let status = response.status_code();
+
+        // 404 is acceptable - index may not exist
+        if !status.is_success() && status.as_u16() != 404 {
+            let error_body = response.text().await.unwrap_or_default();
+            error!(status = %status, body = %error_body, "Delete index request failed");
+            return Err(SearchIndexError::delete(format!(
+                "Delete index {} failed with status {}: {}",
+                index, status, error_body
+            )));
+        }
+
+        info!(index = %index, "Index deleted");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index_version() {
+        assert_eq!(
+            OpenSearchProvider::parse_index_version("entities_v0"),
+            Some(0)
+        );
+        assert_eq!(
+            OpenSearchProvider::parse_index_version("entities_v42"),
+            Some(42)
+        );
+        assert_eq!(OpenSearchProvider::parse_index_version("entities"), None);
+        assert_eq!(OpenSearchProvider::parse_index_version("other_v1"), None);
+        assert_eq!(OpenSearchProvider::parse_index_version("entities_vx"), None);
+    }
+
+    #[test]
+    fn test_copy_body() {
+        assert_eq!(
+            OpenSearchProvider::copy_body(0, 1),
+            json!({
+                "conflicts": "proceed",
+                "source": { "index": "entities_v0" },
+                "dest": { "index": "entities_v1", "op_type": "create" },
+                "script": { "source": COPY_ROUTING_SCRIPT, "lang": "painless" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_switch_alias_actions() {
+        assert_eq!(
+            OpenSearchProvider::switch_alias_actions("entities", Some(0), 1),
+            json!({
+                "actions": [
+                    { "remove": { "index": "entities_v0", "alias": "entities", "must_exist": true } },
+                    { "add": { "index": "entities_v1", "alias": "entities" } }
+                ]
+            })
+        );
+
+        // Creating the alias only adds it
+        assert_eq!(
+            OpenSearchProvider::switch_alias_actions("entities", None, 0),
+            json!({
+                "actions": [
+                    { "add": { "index": "entities_v0", "alias": "entities" } }
+                ]
+            })
+        );
+    }
+}
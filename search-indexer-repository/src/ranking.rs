@@ -0,0 +1,180 @@
+//! Ranking signals boosting the relevance of search results.
+//!
+//! Two signals are stored on documents and combined with the text relevance by searches
+//! with a [`RelevanceBoost`](crate::types::RelevanceBoost):
+//!
+//! - `score`: the [`vote_score`] of the entity in its space, from the totals of the
+//!   `VotesCountChanged` messages of the actions indexer
+//! - `trust_rank`: the [`trust_rank`] of the space, from its distance to the root of the
+//!   canonical graph of `CanonicalGraphUpdated` messages, tracked by [`TrustRankTracker`]
+
+use std::collections::{BTreeMap, HashMap};
+
+/// z-score of the 95% confidence level of [`vote_score`].
+const VOTE_CONFIDENCE_Z: f64 = 1.96;
+
+/// Score an entity from its votes, between 0 and 1.
+///
+/// The score is the lower bound of the Wilson confidence interval of the share of
+/// upvotes, so an entity with many votes ranks above one with a few votes of the same
+/// share, and an entity without votes scores 0.
+///
+/// # Arguments
+///
+/// * `upvotes` - The total of upvotes, negative totals counting as 0
+/// * `downvotes` - The total of downvotes, negative totals counting as 0
+pub fn vote_score(upvotes: i64, downvotes: i64) -> f64 {
+    let upvotes = upvotes.max(0) as f64;
+    let total = upvotes + downvotes.max(0) as f64;
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    let z2 = VOTE_CONFIDENCE_Z * VOTE_CONFIDENCE_Z;
+    let share = upvotes / total;
+    let center = share + z2 / (2.0 * total);
+    let margin =
+        VOTE_CONFIDENCE_Z * (share * (1.0 - share) / total + z2 / (4.0 * total * total)).sqrt();
+    ((center - margin) / (1.0 + z2 / total)).max(0.0)
+}
+
+/// Rank the trust of a space from its distance to the root of the canonical graph.
+///
+/// # Arguments
+///
+/// * `depth` - The number of edges from the root to the space, or `None` if the space
+///   isn't canonical
+///
+/// # Returns
+///
+/// `1 / (1 + depth)`: 1 for the root, 1/2 for the spaces it trusts, and so on, and 0 for
+/// spaces outside the canonical graph.
+pub fn trust_rank(depth: Option<u32>) -> f64 {
+    depth.map_or(0.0, |depth| 1.0 / (1.0 + f64::from(depth)))
+}
+
+/// Tracks the depth of canonical spaces to find the spaces whose trust rank changed.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use search_indexer_repository::ranking::TrustRankTracker;
+///
+/// let mut tracker = TrustRankTracker::new();
+/// let graph = HashMap::from([("root".to_string(), 0), ("child".to_string(), 1)]);
+/// assert_eq!(
+///     tracker.changes(&graph),
+///     vec![("child".to_string(), 0.5), ("root".to_string(), 1.0)]
+/// );
+/// tracker.set(graph);
+///
+/// // The child left the canonical graph
+/// let graph = HashMap::from([("root".to_string(), 0)]);
+/// assert_eq!(tracker.changes(&graph), vec![("child".to_string(), 0.0)]);
+/// ```
+#[derive(Debug, Default)]
+pub struct TrustRankTracker {
+    depths: HashMap<String, u32>,
+}
+
+impl TrustRankTracker {
+    /// Create a TrustRankTracker with no canonical space.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the spaces whose trust rank differs in a new canonical graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `depths` - The depth of every space of the new canonical graph
+    ///
+    /// # Returns
+    ///
+    /// The space ID and new trust rank of each changed space, ordered by space ID. Spaces
+    /// that left the canonical graph get a trust rank of 0.
+    pub fn changes(&self, depths: &HashMap<String, u32>) -> Vec<(String, f64)> {
+        let mut changes = BTreeMap::new();
+        for (space_id, depth) in depths {
+            if self.depths.get(space_id) != Some(depth) {
+                changes.insert(space_id.clone(), trust_rank(Some(*depth)));
+            }
+        }
+        for space_id in self.depths.keys() {
+            if !depths.contains_key(space_id) {
+                changes.insert(space_id.clone(), trust_rank(None));
+            }
+        }
+        changes.into_iter().collect()
+    }
+
+    /// Record a canonical graph, e.g. once the [`Self::changes`] it brought are indexed.
+    pub fn set(&mut self, depths: HashMap<String, u32>) {
+        self.depths = depths;
+    }
+
+    /// Get the trust rank of a space in the recorded canonical graph.
+    ///
+    /// # Returns
+    ///
+    /// The trust rank of the space, or `None` if it isn't canonical.
+    pub fn trust_rank(&self, space_id: &str) -> Option<f64> {
+        self.depths
+            .get(space_id)
+            .map(|depth| trust_rank(Some(*depth)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vote_score() {
+        assert_eq!(vote_score(0, 0), 0.0);
+        assert_eq!(vote_score(0, 10), 0.0);
+        assert_eq!(vote_score(-5, -1), 0.0);
+
+        // More votes of the same share give more confidence
+        assert!(vote_score(100, 0) > vote_score(10, 0));
+        assert!(vote_score(90, 10) > vote_score(9, 1));
+        // A higher share of upvotes ranks higher
+        assert!(vote_score(90, 10) > vote_score(60, 40));
+        assert!(vote_score(1_000_000, 0) < 1.0);
+    }
+
+    #[test]
+    fn test_trust_rank() {
+        assert_eq!(trust_rank(Some(0)), 1.0);
+        assert_eq!(trust_rank(Some(1)), 0.5);
+        assert_eq!(trust_rank(None), 0.0);
+    }
+
+    #[test]
+    fn test_tracker_changes() {
+        let mut tracker = TrustRankTracker::new();
+        tracker.set(HashMap::from([
+            ("a".to_string(), 0),
+            ("b".to_string(), 1),
+            ("c".to_string(), 2),
+        ]));
+
+        let changes = tracker.changes(&HashMap::from([
+            ("a".to_string(), 0),
+            ("b".to_string(), 3),
+            ("d".to_string(), 1),
+        ]));
+
+        assert_eq!(
+            changes,
+            vec![
+                ("b".to_string(), 0.25),
+                ("c".to_string(), 0.0),
+                ("d".to_string(), 0.5),
+            ]
+        );
+        assert_eq!(tracker.trust_rank("c"), Some(1.0 / 3.0));
+        assert_eq!(tracker.trust_rank("d"), None);
+    }
+}
@@ -0,0 +1,522 @@
+//! Provider mirroring writes to the index being rebuilt.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+
+use crate::errors::SearchIndexError;
+use crate::interfaces::SearchIndexProvider;
+use crate::types::{
+    BatchOperationResult, BatchOperationSummary, DeleteEntityRequest, GetEntityRequest,
+    IndexedEntityDocument, RestoreEntitiesRequest, SearchRequest, SearchResult,
+    SemanticSearchRequest, UnsetEntityPropertiesRequest, UpdateEntityRequest, VoteCountUpdate,
+};
+
+/// A `SearchIndexProvider` writing to the current index and, during a reindex, to the
+/// next one.
+///
+/// Reads always go to the current index. While dual-writing, every write is applied to
+/// the current index first, then the document is mirrored to the next index in full: a
+/// partial update of a document the copy hasn't reached yet would otherwise leave a
+/// partial document the copy skips.
+///
+/// The provider is a cheap handle: clones share the next index, so the clone given to
+/// `SearchIndexService` dual-writes as soon as `ReindexService` starts a reindex.
+#[derive(Clone)]
+pub struct DualWriteProvider {
+    current: Arc<dyn SearchIndexProvider>,
+    next: Arc<RwLock<Option<Arc<dyn SearchIndexProvider>>>>,
+}
+
+impl DualWriteProvider {
+    /// Create a provider writing to `current` only.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The provider of the index behind the alias
+    pub fn new(current: Box<dyn SearchIndexProvider>) -> Self {
+        Self {
+            current: current.into(),
+            next: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Mirror writes to `next` until [`Self::stop_dual_write`] is called.
+    ///
+    /// Waits for in-flight writes, so every write completing afterwards reaches `next`.
+    ///
+    /// # Arguments
+    ///
+    /// * `next` - The provider of the index being rebuilt
+    pub async fn start_dual_write(&self, next: Box<dyn SearchIndexProvider>) {
+        *self.next.write().await = Some(next.into());
+    }
+
+    /// Stop mirroring writes, waiting for in-flight writes.
+    pub async fn stop_dual_write(&self) {
+        *self.next.write().await = None;
+    }
+
+    /// Whether writes are mirrored to a next index.
+    pub async fn is_dual_writing(&self) -> bool {
+        self.next.read().await.is_some()
+    }
+
+    /// Write the document as stored in the current index to the next one.
+    ///
+    /// Removed documents are deleted, and soft-deleted ones are written then flagged
+    /// again, keeping their fields restorable.
+    async fn mirror(
+        &self,
+        next: &dyn SearchIndexProvider,
+        entity_id: &str,
+        space_id: &str,
+    ) -> Result<(), SearchIndexError> {
+        let document = self
+            .current
+            .get_document(&GetEntityRequest {
+                entity_id: entity_id.to_string(),
+                space_id: space_id.to_string(),
+            })
+            .await?;
+
+        let Some(document) = document else {
+            return next
+                .delete_document(&DeleteEntityRequest {
+                    entity_id: entity_id.to_string(),
+                    space_id: space_id.to_string(),
+                    edited_at: None,
+                })
+                .await;
+        };
+
+        next.update_document(&Self::full_update(entity_id, space_id, &document))
+            .await?;
+        if document.deleted {
+            next.soft_delete_document(&DeleteEntityRequest {
+                entity_id: entity_id.to_string(),
+                space_id: space_id.to_string(),
+                edited_at: document.deleted_at,
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Build an update setting every field of a stored document.
+    fn full_update(
+        entity_id: &str,
+        space_id: &str,
+        document: &IndexedEntityDocument,
+    ) -> UpdateEntityRequest {
+        UpdateEntityRequest {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+            name: document.name.clone(),
+            description: document.description.clone(),
+            avatar: document.avatar.clone(),
+            cover: document.cover.clone(),
+            entity_global_score: document.entity_global_score,
+            space_score: document.space_score,
+            entity_space_score: document.entity_space_score,
+            edited_at: None,
+            edit_version: document.edit_version,
+            name_embedding: document.name_embedding.clone(),
+            description_embedding: document.description_embedding.clone(),
+            language: document.language.clone(),
+            score: document.score,
+            trust_rank: document.trust_rank,
+        }
+    }
+
+    /// Summarize the results of single operations run for a batch.
+    fn summarize(
+        results: Vec<(String, String, Result<(), SearchIndexError>)>,
+    ) -> BatchOperationSummary {
+        let results: Vec<BatchOperationResult> = results
+            .into_iter()
+            .map(|(entity_id, space_id, result)| BatchOperationResult {
+                entity_id,
+                space_id,
+                success: result.is_ok(),
+                error: result.err(),
+            })
+            .collect();
+        let succeeded = results.iter().filter(|result| result.success).count();
+
+        BatchOperationSummary {
+            total: results.len(),
+            succeeded,
+            failed: results.len() - succeeded,
+            results,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchIndexProvider for DualWriteProvider {
+    /// Update the document in the current index, then mirror it to the next one.
+    async fn update_document(&self, request: &UpdateEntityRequest) -> Result<(), SearchIndexError> {
+        let next = self.next.read().await;
+        self.current.update_document(request).await?;
+        if let Some(next) = next.as_deref() {
+            self.mirror(next, &request.entity_id, &request.space_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Delete the document from the current index and the next one.
+    async fn delete_document(&self, request: &DeleteEntityRequest) -> Result<(), SearchIndexError> {
+        let next = self.next.read().await;
+        self.current.delete_document(request).await?;
+        if let Some(next) = next.as_deref() {
+            next.delete_document(request).await?;
+        }
+        Ok(())
+    }
+
+    /// Soft-delete the document in the current index and the next one.
+    async fn soft_delete_document(
+        &self,
+        request: &DeleteEntityRequest,
+    ) -> Result<(), SearchIndexError> {
+        let next = self.next.read().await;
+        self.current.soft_delete_document(request).await?;
+        if let Some(next) = next.as_deref() {
+            next.soft_delete_document(request).await?;
+        }
+        Ok(())
+    }
+
+    /// Fetch the document from the current index.
+    async fn get_document(
+        &self,
+        request: &GetEntityRequest,
+    ) -> Result<Option<IndexedEntityDocument>, SearchIndexError> {
+        self.current.get_document(request).await
+    }
+
+    /// Update each document with [`Self::update_document`].
+    async fn bulk_update_documents(
+        &self,
+        requests: &[UpdateEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            let result = SearchIndexProvider::update_document(self, request).await;
+            results.push((request.entity_id.clone(), request.space_id.clone(), result));
+        }
+        Ok(Self::summarize(results))
+    }
+
+    /// Delete each document with [`Self::delete_document`].
+    async fn bulk_delete_documents(
+        &self,
+        requests: &[DeleteEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            let result = SearchIndexProvider::delete_document(self, request).await;
+            results.push((request.entity_id.clone(), request.space_id.clone(), result));
+        }
+        Ok(Self::summarize(results))
+    }
+
+    /// Soft-delete each document with [`Self::soft_delete_document`].
+    async fn bulk_soft_delete_documents(
+        &self,
+        requests: &[DeleteEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            let result = SearchIndexProvider::soft_delete_document(self, request).await;
+            results.push((request.entity_id.clone(), request.space_id.clone(), result));
+        }
+        Ok(Self::summarize(results))
+    }
+
+    /// Restore the documents in the current index and the next one, returning the number
+    /// of documents restored in the current index.
+    async fn restore_documents(
+        &self,
+        request: &RestoreEntitiesRequest,
+    ) -> Result<u64, SearchIndexError> {
+        let next = self.next.read().await;
+        let restored = self.current.restore_documents(request).await?;
+        if let Some(next) = next.as_deref() {
+            next.restore_documents(request).await?;
+        }
+        Ok(restored)
+    }
+
+    /// Purge the documents from the current index and the next one, returning the number
+    /// of documents purged from the current index.
+    async fn purge_deleted_documents(
+        &self,
+        deleted_before: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError> {
+        let next = self.next.read().await;
+        let purged = self.current.purge_deleted_documents(deleted_before).await?;
+        if let Some(next) = next.as_deref() {
+            next.purge_deleted_documents(deleted_before).await?;
+        }
+        Ok(purged)
+    }
+
+    /// Unset the properties in the current index, then mirror the document to the next
+    /// one and unset them there, as mirroring only adds fields.
+    async fn unset_document_properties(
+        &self,
+        request: &UnsetEntityPropertiesRequest,
+    ) -> Result<(), SearchIndexError> {
+        let next = self.next.read().await;
+        self.current.unset_document_properties(request).await?;
+        if let Some(next) = next.as_deref() {
+            self.mirror(next, &request.entity_id, &request.space_id)
+                .await?;
+            next.unset_document_properties(request).await?;
+        }
+        Ok(())
+    }
+
+    async fn semantic_search(
+        &self,
+        request: &SemanticSearchRequest,
+    ) -> Result<Vec<SearchResult>, SearchIndexError> {
+        self.current.semantic_search(request).await
+    }
+
+    async fn search(&self, request: &SearchRequest) -> Result<Vec<SearchResult>, SearchIndexError> {
+        self.current.search(request).await
+    }
+
+    async fn delete_space_documents(&self, space_id: &str) -> Result<u64, SearchIndexError> {
+        let next = self.next.read().await;
+        let deleted = self.current.delete_space_documents(space_id).await?;
+        if let Some(next) = next.as_deref() {
+            next.delete_space_documents(space_id).await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn soft_delete_space_documents(
+        &self,
+        space_id: &str,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError> {
+        let next = self.next.read().await;
+        let deleted = self
+            .current
+            .soft_delete_space_documents(space_id, deleted_at)
+            .await?;
+        if let Some(next) = next.as_deref() {
+            next.soft_delete_space_documents(space_id, deleted_at)
+                .await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn bulk_update_vote_counts(
+        &self,
+        updates: &[VoteCountUpdate],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        let next = self.next.read().await;
+        let summary = self.current.bulk_update_vote_counts(updates).await?;
+        if let Some(next) = next.as_deref() {
+            for result in summary.results.iter().filter(|result| result.success) {
+                self.mirror(next, &result.entity_id, &result.space_id)
+                    .await?;
+            }
+        }
+        Ok(summary)
+    }
+
+    async fn set_space_trust_rank(
+        &self,
+        space_id: &str,
+        trust_rank: f64,
+    ) -> Result<u64, SearchIndexError> {
+        let next = self.next.read().await;
+        let updated = self
+            .current
+            .set_space_trust_rank(space_id, trust_rank)
+            .await?;
+        if let Some(next) = next.as_deref() {
+            next.set_space_trust_rank(space_id, trust_rank).await?;
+        }
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reindex::memory_provider::MemoryProvider;
+    use uuid::Uuid;
+
+    fn get_request(entity_id: &str, space_id: &str) -> GetEntityRequest {
+        GetEntityRequest {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+        }
+    }
+
+    fn update_request(entity_id: &str, space_id: &str) -> UpdateEntityRequest {
+        UpdateEntityRequest {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+            name: None,
+            description: None,
+            avatar: None,
+            cover: None,
+            entity_global_score: None,
+            space_score: None,
+            entity_space_score: None,
+            edited_at: None,
+            edit_version: None,
+            name_embedding: None,
+            description_embedding: None,
+            language: None,
+            score: None,
+            trust_rank: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writes_current_only_without_next() {
+        let current = MemoryProvider::default();
+        let provider = DualWriteProvider::new(Box::new(current.clone()));
+
+        let (entity_id, space_id) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        let mut request = update_request(&entity_id, &space_id);
+        request.name = Some("Entity".to_string());
+        provider.update_document(&request).await.unwrap();
+
+        assert!(!provider.is_dual_writing().await);
+        assert_eq!(current.len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_partial_update_mirrors_full_document() {
+        let current = MemoryProvider::default();
+        let next = MemoryProvider::default();
+        let provider = DualWriteProvider::new(Box::new(current.clone()));
+
+        let (entity_id, space_id) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        let mut request = update_request(&entity_id, &space_id);
+        request.name = Some("Entity".to_string());
+        provider.update_document(&request).await.unwrap();
+
+        provider.start_dual_write(Box::new(next.clone())).await;
+        let mut request = update_request(&entity_id, &space_id);
+        request.description = Some("Description".to_string());
+        provider.update_document(&request).await.unwrap();
+
+        // The next index has the name written before the reindex started
+        let mirrored = next
+            .get_document(&get_request(&entity_id, &space_id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(mirrored.name.as_deref(), Some("Entity"));
+        assert_eq!(mirrored.description.as_deref(), Some("Description"));
+    }
+
+    #[tokio::test]
+    async fn test_vote_counts_are_mirrored() {
+        let current = MemoryProvider::default();
+        let next = MemoryProvider::default();
+        let provider = DualWriteProvider::new(Box::new(current.clone()));
+
+        let (entity_id, space_id) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        let mut request = update_request(&entity_id, &space_id);
+        request.name = Some("Entity".to_string());
+        provider.update_document(&request).await.unwrap();
+
+        provider.start_dual_write(Box::new(next.clone())).await;
+        let vote_count = |entity_id: &str| VoteCountUpdate {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.clone(),
+            upvotes: 10,
+            downvotes: 0,
+        };
+        provider
+            .bulk_update_vote_counts(&[
+                vote_count(&entity_id),
+                vote_count(&Uuid::new_v4().to_string()),
+            ])
+            .await
+            .unwrap();
+
+        // The next index gets the document with its score, and votes create no document
+        let mirrored = next
+            .get_document(&get_request(&entity_id, &space_id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(mirrored.name.as_deref(), Some("Entity"));
+        assert!(mirrored.score.unwrap() > 0.0);
+        assert_eq!(current.len().await, 1);
+        assert_eq!(next.len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unset_and_delete_are_mirrored() {
+        let current = MemoryProvider::default();
+        let next = MemoryProvider::default();
+        let provider = DualWriteProvider::new(Box::new(current.clone()));
+        provider.start_dual_write(Box::new(next.clone())).await;
+
+        let (entity_id, space_id) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+        let mut request = update_request(&entity_id, &space_id);
+        request.name = Some("Entity".to_string());
+        request.description = Some("Description".to_string());
+        provider.update_document(&request).await.unwrap();
+
+        provider
+            .unset_document_properties(&UnsetEntityPropertiesRequest {
+                entity_id: entity_id.clone(),
+                space_id: space_id.clone(),
+                property_keys: vec!["description".to_string()],
+                edited_at: None,
+            })
+            .await
+            .unwrap();
+        let mirrored = next
+            .get_document(&get_request(&entity_id, &space_id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(mirrored.description, None);
+
+        provider
+            .delete_document(&DeleteEntityRequest {
+                entity_id: entity_id.clone(),
+                space_id: space_id.clone(),
+                edited_at: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(next.len().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_stop_dual_write() {
+        let current = MemoryProvider::default();
+        let next = MemoryProvider::default();
+        let provider = DualWriteProvider::new(Box::new(current.clone()));
+        provider.start_dual_write(Box::new(next.clone())).await;
+        provider.stop_dual_write().await;
+
+        let mut request = update_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        request.name = Some("Entity".to_string());
+        let summary = provider.bulk_update_documents(&[request]).await.unwrap();
+
+        assert_eq!(summary.succeeded, 1);
+        assert_eq!(current.len().await, 1);
+        assert_eq!(next.len().await, 0);
+    }
+}
@@ -0,0 +1,264 @@
+//! In-memory search index provider for the reindex tests.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::Mutex;
+
+use crate::errors::SearchIndexError;
+use crate::interfaces::SearchIndexProvider;
+use crate::ranking;
+use crate::types::{
+    BatchOperationResult, BatchOperationSummary, DeleteEntityRequest, GetEntityRequest,
+    IndexedEntityDocument, RestoreEntitiesRequest, SearchRequest, SearchResult,
+    SemanticSearchRequest, UnsetEntityPropertiesRequest, UpdateEntityRequest, VoteCountUpdate,
+};
+
+/// Stores documents in a map keyed by entity and space ID. Clones share the map.
+#[derive(Clone, Default)]
+pub(crate) struct MemoryProvider {
+    documents: Arc<Mutex<HashMap<(String, String), IndexedEntityDocument>>>,
+}
+
+impl MemoryProvider {
+    /// Number of stored documents, tombstones included.
+    pub(crate) async fn len(&self) -> usize {
+        self.documents.lock().await.len()
+    }
+
+    fn summary(ids: Vec<(String, String)>) -> BatchOperationSummary {
+        BatchOperationSummary {
+            total: ids.len(),
+            succeeded: ids.len(),
+            failed: 0,
+            results: ids
+                .into_iter()
+                .map(|(entity_id, space_id)| BatchOperationResult {
+                    entity_id,
+                    space_id,
+                    success: true,
+                    error: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchIndexProvider for MemoryProvider {
+    async fn update_document(&self, request: &UpdateEntityRequest) -> Result<(), SearchIndexError> {
+        let mut documents = self.documents.lock().await;
+        let document = documents
+            .entry((request.entity_id.clone(), request.space_id.clone()))
+            .or_default();
+        if matches!((request.edit_version, document.edit_version), (Some(new), Some(old)) if new <= old)
+        {
+            return Ok(());
+        }
+        document.name = request.name.clone().or(document.name.take());
+        document.description = request.description.clone().or(document.description.take());
+        document.avatar = request.avatar.clone().or(document.avatar.take());
+        document.cover = request.cover.clone().or(document.cover.take());
+        document.name_embedding = request
+            .name_embedding
+            .clone()
+            .or(document.name_embedding.take());
+        document.description_embedding = request
+            .description_embedding
+            .clone()
+            .or(document.description_embedding.take());
+        document.score = request.score.or(document.score);
+        document.trust_rank = request.trust_rank.or(document.trust_rank);
+        document.edit_version = request.edit_version.or(document.edit_version);
+        document.deleted = false;
+        Ok(())
+    }
+
+    async fn delete_document(&self, request: &DeleteEntityRequest) -> Result<(), SearchIndexError> {
+        self.documents
+            .lock()
+            .await
+            .remove(&(request.entity_id.clone(), request.space_id.clone()));
+        Ok(())
+    }
+
+    async fn soft_delete_document(
+        &self,
+        request: &DeleteEntityRequest,
+    ) -> Result<(), SearchIndexError> {
+        let mut documents = self.documents.lock().await;
+        let document = documents
+            .entry((request.entity_id.clone(), request.space_id.clone()))
+            .or_default();
+        document.deleted = true;
+        document.deleted_at = Some(request.edited_at.unwrap_or_else(Utc::now));
+        Ok(())
+    }
+
+    async fn get_document(
+        &self,
+        request: &GetEntityRequest,
+    ) -> Result<Option<IndexedEntityDocument>, SearchIndexError> {
+        Ok(self
+            .documents
+            .lock()
+            .await
+            .get(&(request.entity_id.clone(), request.space_id.clone()))
+            .cloned())
+    }
+
+    async fn bulk_update_documents(
+        &self,
+        requests: &[UpdateEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        for request in requests {
+            self.update_document(request).await?;
+        }
+        Ok(Self::summary(
+            requests
+                .iter()
+                .map(|r| (r.entity_id.clone(), r.space_id.clone()))
+                .collect(),
+        ))
+    }
+
+    async fn bulk_delete_documents(
+        &self,
+        requests: &[DeleteEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        for request in requests {
+            self.delete_document(request).await?;
+        }
+        Ok(Self::summary(
+            requests
+                .iter()
+                .map(|r| (r.entity_id.clone(), r.space_id.clone()))
+                .collect(),
+        ))
+    }
+
+    async fn bulk_soft_delete_documents(
+        &self,
+        requests: &[DeleteEntityRequest],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        for request in requests {
+            self.soft_delete_document(request).await?;
+        }
+        Ok(Self::summary(
+            requests
+                .iter()
+                .map(|r| (r.entity_id.clone(), r.space_id.clone()))
+                .collect(),
+        ))
+    }
+
+    async fn restore_documents(
+        &self,
+        _request: &RestoreEntitiesRequest,
+    ) -> Result<u64, SearchIndexError> {
+        Ok(0)
+    }
+
+    async fn purge_deleted_documents(
+        &self,
+        _deleted_before: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError> {
+        Ok(0)
+    }
+
+    async fn unset_document_properties(
+        &self,
+        request: &UnsetEntityPropertiesRequest,
+    ) -> Result<(), SearchIndexError> {
+        let mut documents = self.documents.lock().await;
+        let document = documents
+            .get_mut(&(request.entity_id.clone(), request.space_id.clone()))
+            .ok_or_else(|| {
+                SearchIndexError::document_not_found(&request.entity_id, &request.space_id)
+            })?;
+        for key in &request.property_keys {
+            match key.as_str() {
+                "name" => document.name = None,
+                "description" => document.description = None,
+                "avatar" => document.avatar = None,
+                "cover" => document.cover = None,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn semantic_search(
+        &self,
+        _request: &SemanticSearchRequest,
+    ) -> Result<Vec<SearchResult>, SearchIndexError> {
+        Ok(Vec::new())
+    }
+
+    async fn search(
+        &self,
+        _request: &SearchRequest,
+    ) -> Result<Vec<SearchResult>, SearchIndexError> {
+        Ok(Vec::new())
+    }
+
+    async fn delete_space_documents(&self, space_id: &str) -> Result<u64, SearchIndexError> {
+        let mut documents = self.documents.lock().await;
+        let before = documents.len();
+        documents.retain(|(_, document_space_id), _| document_space_id != space_id);
+        Ok((before - documents.len()) as u64)
+    }
+
+    async fn soft_delete_space_documents(
+        &self,
+        space_id: &str,
+        deleted_at: DateTime<Utc>,
+    ) -> Result<u64, SearchIndexError> {
+        let mut deleted = 0;
+        for ((_, document_space_id), document) in self.documents.lock().await.iter_mut() {
+            if document_space_id == space_id && !document.deleted {
+                document.deleted = true;
+                document.deleted_at = Some(deleted_at);
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    async fn bulk_update_vote_counts(
+        &self,
+        updates: &[VoteCountUpdate],
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        let mut documents = self.documents.lock().await;
+        for update in updates {
+            if let Some(document) =
+                documents.get_mut(&(update.entity_id.clone(), update.space_id.clone()))
+            {
+                document.score = Some(ranking::vote_score(update.upvotes, update.downvotes));
+            }
+        }
+        Ok(Self::summary(
+            updates
+                .iter()
+                .map(|u| (u.entity_id.clone(), u.space_id.clone()))
+                .collect(),
+        ))
+    }
+
+    async fn set_space_trust_rank(
+        &self,
+        space_id: &str,
+        trust_rank: f64,
+    ) -> Result<u64, SearchIndexError> {
+        let mut updated = 0;
+        for ((_, document_space_id), document) in self.documents.lock().await.iter_mut() {
+            if document_space_id == space_id {
+                document.trust_rank = Some(trust_rank);
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+}
@@ -0,0 +1,14 @@
+//! Zero-downtime reindexing through the search index alias.
+//!
+//! Documents are read and written through an alias pointing to a versioned index. Mapping
+//! changes need a new index version, which `ReindexService` builds while the current one
+//! keeps serving: it creates the new index, has `DualWriteProvider` mirror every write to
+//! it, copies the existing documents over, then atomically moves the alias to it.
+
+mod dual_write;
+#[cfg(test)]
+mod memory_provider;
+mod reindex_service;
+
+pub use dual_write::DualWriteProvider;
+pub use reindex_service::{ReindexReport, ReindexService};
@@ -0,0 +1,367 @@
+//! Orchestration of aliased reindexes.
+
+use tracing::{error, info};
+
+use crate::errors::SearchIndexError;
+use crate::interfaces::{ReindexProvider, SearchIndexProvider};
+use crate::reindex::dual_write::DualWriteProvider;
+
+/// Outcome of a completed reindex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReindexReport {
+    /// The version the alias pointed to, `None` if the alias didn't exist.
+    pub from_version: Option<u32>,
+    /// The version the alias points to now.
+    pub to_version: u32,
+    /// Number of documents copied from the previous index. Documents mirrored by dual
+    /// writes during the copy aren't counted.
+    pub copied: u64,
+}
+
+/// Rebuilds the search index into a new version without downtime.
+///
+/// A reindex runs these steps, the alias serving reads and writes throughout:
+///
+/// 1. Create the index of the new version, with the mappings of that version
+/// 2. Dual-write: the `DualWriteProvider` used by the application mirrors every write
+///    to the new index
+/// 3. Copy the documents of the current index, skipping the ones dual writes created
+/// 4. Move the alias to the new index in a single atomic operation, then stop
+///    dual-writing
+///
+/// A failed reindex stops dual-writing and deletes the new index, leaving the alias
+/// untouched. The previous index is kept so the alias can be moved back, and is removed
+/// with [`Self::delete_version`].
+///
+/// # Example
+///
+/// ```no_run
+/// use search_indexer_repository::{DualWriteProvider, ReindexService, SearchIndexService};
+/// # #[cfg(feature = "search")]
+/// use search_indexer_repository::opensearch::{get_versioned_index_name, IndexConfig, OpenSearchProvider};
+///
+/// # #[cfg(feature = "search")]
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let url = "http://localhost:9200";
+/// let writes = DualWriteProvider::new(Box::new(
+///     OpenSearchProvider::new(url, IndexConfig::new("entities", 0)).await?,
+/// ));
+/// let service = SearchIndexService::new(Box::new(writes.clone()));
+///
+/// let reindex = ReindexService::new(
+///     Box::new(OpenSearchProvider::new(url, IndexConfig::new("entities", 0)).await?),
+///     writes,
+/// );
+/// // Writes to the index being built go to it directly, not through the alias
+/// let next = OpenSearchProvider::new(url, IndexConfig::new(get_versioned_index_name(Some(1)), 1)).await?;
+/// let report = reindex.reindex(1, Box::new(next)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReindexService {
+    provider: Box<dyn ReindexProvider>,
+    writes: DualWriteProvider,
+}
+
+impl ReindexService {
+    /// Create a new ReindexService.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - A boxed implementation of `ReindexProvider` managing the alias
+    /// * `writes` - The provider the application writes documents through
+    ///
+    /// # Returns
+    ///
+    /// A new `ReindexService` instance.
+    pub fn new(provider: Box<dyn ReindexProvider>, writes: DualWriteProvider) -> Self {
+        Self { provider, writes }
+    }
+
+    /// Rebuild the index into `to_version` and move the alias to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `to_version` - The version of the index to build
+    /// * `next` - A provider writing to the index of `to_version` directly
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ReindexReport)` - If the alias points to the new index
+    /// * `Err(SearchIndexError::ValidationError)` - If the alias already points to
+    ///   `to_version`, or a reindex is already dual-writing
+    /// * `Err(SearchIndexError)` - If a step fails, after the new index was deleted
+    pub async fn reindex(
+        &self,
+        to_version: u32,
+        next: Box<dyn SearchIndexProvider>,
+    ) -> Result<ReindexReport, SearchIndexError> {
+        let from_version = self.provider.aliased_version().await?;
+        if from_version == Some(to_version) {
+            return Err(SearchIndexError::validation(format!(
+                "The alias already points to version {}",
+                to_version
+            )));
+        }
+        if self.writes.is_dual_writing().await {
+            return Err(SearchIndexError::validation(
+                "A reindex is already in progress".to_string(),
+            ));
+        }
+
+        self.provider.create_index(to_version).await?;
+        info!(
+            ?from_version,
+            to_version, "Created index, starting dual writes"
+        );
+        self.writes.start_dual_write(next).await;
+
+        match self.copy_and_switch(from_version, to_version).await {
+            Ok(copied) => {
+                self.writes.stop_dual_write().await;
+                let report = ReindexReport {
+                    from_version,
+                    to_version,
+                    copied,
+                };
+                info!(?report, "Reindex completed");
+                Ok(report)
+            }
+            Err(e) => {
+                error!(error = %e, to_version, "Reindex failed, deleting the new index");
+                self.writes.stop_dual_write().await;
+                self.provider.delete_index(to_version).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Copy the documents of the current index and move the alias to the new one.
+    async fn copy_and_switch(
+        &self,
+        from_version: Option<u32>,
+        to_version: u32,
+    ) -> Result<u64, SearchIndexError> {
+        let copied = match from_version {
+            Some(from_version) => {
+                self.provider
+                    .copy_documents(from_version, to_version)
+                    .await?
+            }
+            None => 0,
+        };
+        info!(copied, to_version, "Copied documents, switching the alias");
+
+        self.provider.switch_alias(from_version, to_version).await?;
+        Ok(copied)
+    }
+
+    /// Delete the index of a version the alias no longer points to, e.g. the previous
+    /// version once the new one is verified.
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The version of the index to delete
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the index was deleted (or didn't exist)
+    /// * `Err(SearchIndexError::ValidationError)` - If the alias points to `version`
+    /// * `Err(SearchIndexError)` - If the deletion fails
+    pub async fn delete_version(&self, version: u32) -> Result<(), SearchIndexError> {
+        if self.provider.aliased_version().await? == Some(version) {
+            return Err(SearchIndexError::validation(format!(
+                "The alias points to version {}",
+                version
+            )));
+        }
+
+        self.provider.delete_index(version).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reindex::memory_provider::MemoryProvider;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    /// Mock provider recording the reindex steps
+    #[derive(Clone)]
+    struct MockReindexProvider {
+        aliased_version: Arc<Mutex<Option<u32>>>,
+        steps: Arc<Mutex<Vec<String>>>,
+        writes: DualWriteProvider,
+        fail_copy: bool,
+    }
+
+    impl MockReindexProvider {
+        fn new(aliased_version: Option<u32>, writes: DualWriteProvider) -> Self {
+            Self {
+                aliased_version: Arc::new(Mutex::new(aliased_version)),
+                steps: Arc::new(Mutex::new(Vec::new())),
+                writes,
+                fail_copy: false,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ReindexProvider for MockReindexProvider {
+        async fn aliased_version(&self) -> Result<Option<u32>, SearchIndexError> {
+            Ok(*self.aliased_version.lock().await)
+        }
+
+        async fn create_index(&self, version: u32) -> Result<(), SearchIndexError> {
+            self.steps.lock().await.push(format!("create {}", version));
+            Ok(())
+        }
+
+        async fn copy_documents(
+            &self,
+            from_version: u32,
+            to_version: u32,
+        ) -> Result<u64, SearchIndexError> {
+            // Writes must already be mirrored when the copy starts
+            assert!(self.writes.is_dual_writing().await);
+            self.steps
+                .lock()
+                .await
+                .push(format!("copy {} {}", from_version, to_version));
+            if self.fail_copy {
+                return Err(SearchIndexError::reindex("Mock failure"));
+            }
+            Ok(3)
+        }
+
+        async fn switch_alias(
+            &self,
+            from_version: Option<u32>,
+            to_version: u32,
+        ) -> Result<(), SearchIndexError> {
+            self.steps
+                .lock()
+                .await
+                .push(format!("switch {:?} {}", from_version, to_version));
+            *self.aliased_version.lock().await = Some(to_version);
+            Ok(())
+        }
+
+        async fn delete_index(&self, version: u32) -> Result<(), SearchIndexError> {
+            self.steps.lock().await.push(format!("delete {}", version));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reindex() {
+        let writes = DualWriteProvider::new(Box::new(MemoryProvider::default()));
+        let provider = MockReindexProvider::new(Some(0), writes.clone());
+        let steps = provider.steps.clone();
+        let service = ReindexService::new(Box::new(provider), writes.clone());
+
+        let report = service
+            .reindex(1, Box::new(MemoryProvider::default()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            report,
+            ReindexReport {
+                from_version: Some(0),
+                to_version: 1,
+                copied: 3,
+            }
+        );
+        assert_eq!(
+            *steps.lock().await,
+            vec!["create 1", "copy 0 1", "switch Some(0) 1"]
+        );
+        assert!(!writes.is_dual_writing().await);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_creates_alias() {
+        let writes = DualWriteProvider::new(Box::new(MemoryProvider::default()));
+        let provider = MockReindexProvider::new(None, writes.clone());
+        let steps = provider.steps.clone();
+        let service = ReindexService::new(Box::new(provider), writes);
+
+        let report = service
+            .reindex(0, Box::new(MemoryProvider::default()))
+            .await
+            .unwrap();
+
+        // Nothing to copy the first time
+        assert_eq!(report.copied, 0);
+        assert_eq!(*steps.lock().await, vec!["create 0", "switch None 0"]);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_failure_deletes_new_index() {
+        let writes = DualWriteProvider::new(Box::new(MemoryProvider::default()));
+        let mut provider = MockReindexProvider::new(Some(0), writes.clone());
+        provider.fail_copy = true;
+        let steps = provider.steps.clone();
+        let aliased_version = provider.aliased_version.clone();
+        let service = ReindexService::new(Box::new(provider), writes.clone());
+
+        let result = service
+            .reindex(1, Box::new(MemoryProvider::default()))
+            .await;
+
+        assert!(matches!(result, Err(SearchIndexError::ReindexError(_))));
+        assert_eq!(
+            *steps.lock().await,
+            vec!["create 1", "copy 0 1", "delete 1"]
+        );
+        assert_eq!(*aliased_version.lock().await, Some(0));
+        assert!(!writes.is_dual_writing().await);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_validation() {
+        let writes = DualWriteProvider::new(Box::new(MemoryProvider::default()));
+        let provider = MockReindexProvider::new(Some(1), writes.clone());
+        let steps = provider.steps.clone();
+        let service = ReindexService::new(Box::new(provider), writes.clone());
+
+        // The alias already points to the version
+        assert!(matches!(
+            service
+                .reindex(1, Box::new(MemoryProvider::default()))
+                .await,
+            Err(SearchIndexError::ValidationError(_))
+        ));
+
+        // Another reindex is dual-writing
+        writes
+            .start_dual_write(Box::new(MemoryProvider::default()))
+            .await;
+        assert!(matches!(
+            service
+                .reindex(2, Box::new(MemoryProvider::default()))
+                .await,
+            Err(SearchIndexError::ValidationError(_))
+        ));
+        assert!(steps.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_version() {
+        let writes = DualWriteProvider::new(Box::new(MemoryProvider::default()));
+        let provider = MockReindexProvider::new(Some(1), writes.clone());
+        let steps = provider.steps.clone();
+        let service = ReindexService::new(Box::new(provider), writes);
+
+        assert!(matches!(
+            service.delete_version(1).await,
+            Err(SearchIndexError::ValidationError(_))
+        ));
+        service.delete_version(0).await.unwrap();
+
+        assert_eq!(*steps.lock().await, vec!["delete 0"]);
+    }
+}
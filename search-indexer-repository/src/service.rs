@@ -9,12 +9,19 @@
 //! The `update` function performs an upsert operation: it will create the document if
 //! it doesn't exist, or update it if it does exist.
 
-use crate::config::SearchIndexServiceConfig;
+use crate::config::{DeleteMode, SearchIndexServiceConfig};
 use crate::errors::SearchIndexError;
-use crate::interfaces::SearchIndexProvider;
+use crate::interfaces::{EmbeddingProvider, SearchIndexProvider};
+use crate::language::Language;
+use crate::ranking::TrustRankTracker;
 use crate::types::{
-    BatchOperationSummary, DeleteEntityRequest, UnsetEntityPropertiesRequest, UpdateEntityRequest,
+    BatchOperationSummary, DeleteEntityRequest, GetEntityRequest, RestoreEntitiesRequest,
+    SearchRequest, SearchResult, SemanticSearchRequest, UnsetEntityPropertiesRequest,
+    UpdateEntityRequest, VoteCountUpdate,
 };
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::RwLock;
 use uuid::Uuid;
 
 /// The main service for interacting with the search index.
@@ -34,9 +41,11 @@ use uuid::Uuid;
 ///
 /// ```no_run
 /// use search_indexer_repository::{SearchIndexService, SearchIndexProvider};
+/// # #[cfg(feature = "search")]
 /// use search_indexer_repository::opensearch::{OpenSearchProvider, IndexConfig};
 /// use search_indexer_repository::UpdateEntityRequest;
 ///
+/// # #[cfg(feature = "search")]
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let config = IndexConfig::new("entities", 0);
 /// let provider = Box::new(OpenSearchProvider::new("http://localhost:9200", config).await?);
@@ -52,6 +61,13 @@ use uuid::Uuid;
 ///     entity_global_score: None,
 ///     space_score: None,
 ///     entity_space_score: None,
+///     edited_at: None,
+///     edit_version: None,
+///     name_embedding: None,
+///     description_embedding: None,
+///     language: None,
+///     score: None,
+///     trust_rank: None,
 /// };
 ///
 /// // This will create the document if it doesn't exist, or update it if it does
@@ -62,6 +78,9 @@ use uuid::Uuid;
 pub struct SearchIndexService {
     provider: Box<dyn SearchIndexProvider>,
     config: SearchIndexServiceConfig,
+    embedder: Option<Box<dyn EmbeddingProvider>>,
+    /// Trust ranks of the spaces of the last canonical graph indexed.
+    trust_ranks: RwLock<TrustRankTracker>,
 }
 
 impl SearchIndexService {
@@ -80,6 +99,8 @@ impl SearchIndexService {
         Self {
             provider,
             config: SearchIndexServiceConfig::default(),
+            embedder: None,
+            trust_ranks: RwLock::new(TrustRankTracker::new()),
         }
     }
 
@@ -99,7 +120,118 @@ impl SearchIndexService {
         provider: Box<dyn SearchIndexProvider>,
         config: SearchIndexServiceConfig,
     ) -> Self {
-        Self { provider, config }
+        Self {
+            provider,
+            config,
+            embedder: None,
+            trust_ranks: RwLock::new(TrustRankTracker::new()),
+        }
+    }
+
+    /// Embed the name and description of updated documents for semantic search.
+    ///
+    /// Without an embedding provider, documents are indexed without embeddings and
+    /// [`Self::semantic_search_text`] is unavailable.
+    ///
+    /// # Arguments
+    ///
+    /// * `embedder` - A boxed implementation of `EmbeddingProvider`, with the dimension
+    ///   of the indexed embeddings
+    ///
+    /// # Returns
+    ///
+    /// The service with the embedding provider.
+    pub fn with_embedding_provider(mut self, embedder: Box<dyn EmbeddingProvider>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Normalize the language of a request to the code of a supported language, or detect
+    /// it from the script of the name or description when the request has none.
+    ///
+    /// Unsupported languages are dropped: the text is then only indexed by the default
+    /// analyzer, rather than by the analyzer of a detected language that may be wrong.
+    fn resolve_language(request: &mut UpdateEntityRequest) {
+        if request.name.is_none() && request.description.is_none() {
+            request.language = None;
+            return;
+        }
+        let language = match request.language.as_deref() {
+            Some(tag) => Language::from_tag(tag),
+            None => request
+                .name
+                .iter()
+                .chain(&request.description)
+                .find_map(|text| Language::detect(text)),
+        };
+        request.language = language.map(|language| language.code().to_string());
+    }
+
+    /// Set the trust rank of the request's space, if it's canonical, so documents created
+    /// after the last canonical graph was indexed rank like the rest of their space.
+    fn set_trust_rank(&self, request: &mut UpdateEntityRequest) {
+        if request.trust_rank.is_some() {
+            return;
+        }
+        let Ok(space_id) = Uuid::parse_str(&request.space_id) else {
+            return;
+        };
+        request.trust_rank = self
+            .trust_ranks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .trust_rank(&space_id.to_string());
+    }
+
+    /// Compute the embeddings of the names and descriptions the requests set, in a single
+    /// call to the embedding provider. Embeddings already set on a request are kept.
+    async fn embed(&self, requests: &mut [UpdateEntityRequest]) -> Result<(), SearchIndexError> {
+        let Some(embedder) = self.embedder.as_deref() else {
+            return Ok(());
+        };
+
+        let mut texts = Vec::new();
+        for request in requests.iter() {
+            if request.name_embedding.is_none() {
+                texts.extend(request.name.clone());
+            }
+            if request.description_embedding.is_none() {
+                texts.extend(request.description.clone());
+            }
+        }
+        if texts.is_empty() {
+            return Ok(());
+        }
+
+        let embeddings = embedder.embed(&texts).await?;
+        if embeddings.len() != texts.len() {
+            return Err(SearchIndexError::embedding(format!(
+                "Got {} embeddings for {} texts",
+                embeddings.len(),
+                texts.len()
+            )));
+        }
+        if let Some(embedding) = embeddings
+            .iter()
+            .find(|embedding| embedding.len() != embedder.dimension())
+        {
+            return Err(SearchIndexError::embedding(format!(
+                "Got an embedding of {} dimensions, expected {}",
+                embedding.len(),
+                embedder.dimension()
+            )));
+        }
+
+        let mut embeddings = embeddings.into_iter();
+        for request in requests.iter_mut() {
+            if request.name_embedding.is_none() && request.name.is_some() {
+                request.name_embedding = embeddings.next();
+            }
+            if request.description_embedding.is_none() && request.description.is_some() {
+                request.description_embedding = embeddings.next();
+            }
+        }
+        Ok(())
     }
 
     /// Check if batch size exceeds the configured limit.
@@ -148,11 +280,15 @@ impl SearchIndexService {
     ///
     /// There is no separate `create` function because all grc-20 events are edits (updates).
     /// This function handles both creating new documents and updating existing ones.
-    pub async fn update(&self, request: UpdateEntityRequest) -> Result<(), SearchIndexError> {
+    pub async fn update(&self, mut request: UpdateEntityRequest) -> Result<(), SearchIndexError> {
         // Validate required fields and UUID format
         Self::validate_uuid("entity_id", &request.entity_id)?;
         Self::validate_uuid("space_id", &request.space_id)?;
 
+        Self::resolve_language(&mut request);
+        self.set_trust_rank(&mut request);
+        self.embed(std::slice::from_mut(&mut request)).await?;
+
         // Build partial document update with only provided fields
         // Send update request to provider
         self.provider.update_document(&request).await
@@ -161,7 +297,9 @@ impl SearchIndexService {
     /// Delete an entity document from the search index.
     ///
     /// This function deletes a document identified by entity_id and space_id. If the
-    /// document doesn't exist, the operation is considered successful.
+    /// document doesn't exist, the operation is considered successful. In
+    /// [`DeleteMode::Soft`] the document is only flagged as deleted and can be restored
+    /// with [`Self::restore`].
     ///
     /// # Arguments
     ///
@@ -177,7 +315,87 @@ impl SearchIndexService {
         Self::validate_uuid("entity_id", &request.entity_id)?;
         Self::validate_uuid("space_id", &request.space_id)?;
 
-        self.provider.delete_document(&request).await
+        match self.config.delete_mode {
+            DeleteMode::Hard => self.provider.delete_document(&request).await,
+            DeleteMode::Soft { .. } => self.provider.soft_delete_document(&request).await,
+        }
+    }
+
+    /// Restore soft-deleted entity documents so they show up in search again.
+    ///
+    /// Every set filter must match for a document to be restored. Documents purged after
+    /// the retention window can't be restored and must be reindexed.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - RestoreEntitiesRequest with the entity, space and deletion time filters
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of restored documents
+    /// * `Err(SearchIndexError::ValidationError)` - If no filter is set or UUIDs are invalid
+    /// * `Err(SearchIndexError)` - If the operation fails
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use chrono::{Duration, Utc};
+    /// use search_indexer_repository::{RestoreEntitiesRequest, SearchIndexService};
+    ///
+    /// # async fn example(service: SearchIndexService) -> Result<(), Box<dyn std::error::Error>> {
+    /// // Undo every deletion in a space from the last hour
+    /// let request = RestoreEntitiesRequest {
+    ///     space_id: Some("6ba7b810-9dad-11d1-80b4-00c04fd430c8".to_string()),
+    ///     deleted_since: Some(Utc::now() - Duration::hours(1)),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let restored = service.restore(request).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn restore(&self, request: RestoreEntitiesRequest) -> Result<u64, SearchIndexError> {
+        if request.entity_id.is_none()
+            && request.space_id.is_none()
+            && request.deleted_since.is_none()
+        {
+            return Err(SearchIndexError::validation(
+                "At least one restore filter must be provided".to_string(),
+            ));
+        }
+
+        if let Some(ref entity_id) = request.entity_id {
+            Self::validate_uuid("entity_id", entity_id)?;
+        }
+        if let Some(ref space_id) = request.space_id {
+            Self::validate_uuid("space_id", space_id)?;
+        }
+
+        self.provider.restore_documents(&request).await
+    }
+
+    /// Remove soft-deleted documents whose retention window has passed.
+    ///
+    /// Meant to be called periodically. In [`DeleteMode::Hard`] this removes the tombstones
+    /// older than `tombstone_retention`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of removed documents
+    /// * `Err(SearchIndexError::ValidationError)` - If the retention window is out of range
+    /// * `Err(SearchIndexError)` - If the operation fails
+    pub async fn purge_expired(&self) -> Result<u64, SearchIndexError> {
+        let retention = match self.config.delete_mode {
+            DeleteMode::Hard => self.config.tombstone_retention,
+            DeleteMode::Soft { retention } => retention,
+        };
+
+        let retention = chrono::Duration::from_std(retention)
+            .map_err(|e| SearchIndexError::validation(format!("Invalid retention: {}", e)))?;
+
+        self.provider
+            .purge_deleted_documents(Utc::now() - retention)
+            .await
     }
 
     /// Unset (remove) specific properties from an entity document.
@@ -187,13 +405,17 @@ impl SearchIndexService {
     /// Common property keys include: "name", "description", "avatar", "cover", "entity_global_score",
     /// "space_score", "entity_space_score".
     ///
+    /// An entity whose values were all unset is gone from the knowledge graph, so a document
+    /// left without a name, description, avatar or cover is deleted like [`Self::delete`] does.
+    ///
     /// # Arguments
     ///
     /// * `request` - UnsetEntityPropertiesRequest containing entity_id, space_id, and property keys to remove
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the properties were removed successfully
+    /// * `Ok(())` - If the properties were removed successfully, deleting the document if it was
+    ///   left empty
     /// * `Err(SearchIndexError::ValidationError)` - If UUIDs are invalid or no property keys provided
     /// * `Err(SearchIndexError)` - If the operation fails
     ///
@@ -203,13 +425,12 @@ impl SearchIndexService {
     /// use search_indexer_repository::{SearchIndexService, SearchIndexProvider};
     /// use search_indexer_repository::types::UnsetEntityPropertiesRequest;
     ///
-    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-    /// # let provider = Box::new(search_indexer_repository::opensearch::OpenSearchProvider::new("http://localhost:9200", search_indexer_repository::opensearch::IndexConfig::new("entities", 0)).await?);
-    /// # let service = SearchIndexService::new(provider);
+    /// # async fn example(service: SearchIndexService) -> Result<(), Box<dyn std::error::Error>> {
     /// let request = UnsetEntityPropertiesRequest {
     ///     entity_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
     ///     space_id: "6ba7b810-9dad-11d1-80b4-00c04fd430c8".to_string(),
     ///     property_keys: vec!["description".to_string(), "avatar".to_string()],
+    ///     edited_at: None,
     /// };
     ///
     /// service.unset_properties(request).await?;
@@ -218,7 +439,7 @@ impl SearchIndexService {
     /// ```
     pub async fn unset_properties(
         &self,
-        request: UnsetEntityPropertiesRequest,
+        mut request: UnsetEntityPropertiesRequest,
     ) -> Result<(), SearchIndexError> {
         // Validate required fields and UUID format
         Self::validate_uuid("entity_id", &request.entity_id)?;
@@ -231,7 +452,38 @@ impl SearchIndexService {
             ));
         }
 
-        self.provider.unset_document_properties(&request).await
+        // The embedding of an unset field goes with it
+        for (key, embedding_key) in [
+            ("name", "name_embedding"),
+            ("description", "description_embedding"),
+        ] {
+            if request.property_keys.iter().any(|k| k == key)
+                && !request.property_keys.iter().any(|k| k == embedding_key)
+            {
+                request.property_keys.push(embedding_key.to_string());
+            }
+        }
+
+        self.provider.unset_document_properties(&request).await?;
+
+        let document = self
+            .provider
+            .get_document(&GetEntityRequest {
+                entity_id: request.entity_id.clone(),
+                space_id: request.space_id.clone(),
+            })
+            .await?;
+        match document {
+            Some(document) if document.is_empty() && !document.deleted => {
+                self.delete(DeleteEntityRequest {
+                    entity_id: request.entity_id,
+                    space_id: request.space_id,
+                    edited_at: request.edited_at,
+                })
+                .await
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Update multiple entity documents in bulk and return a summary of successful and failed operations.
@@ -260,7 +512,7 @@ impl SearchIndexService {
     /// There is no separate `batch_create` function because all grc-20 events are edits (updates).
     pub async fn batch_update(
         &self,
-        requests: Vec<UpdateEntityRequest>,
+        mut requests: Vec<UpdateEntityRequest>,
     ) -> Result<BatchOperationSummary, SearchIndexError> {
         if requests.is_empty() {
             return Ok(BatchOperationSummary {
@@ -279,6 +531,12 @@ impl SearchIndexService {
             Self::validate_uuid("space_id", &request.space_id)?;
         }
 
+        for request in requests.iter_mut() {
+            Self::resolve_language(request);
+            self.set_trust_rank(request);
+        }
+        self.embed(&mut requests).await?;
+
         self.provider.bulk_update_documents(&requests).await
     }
 
@@ -304,6 +562,7 @@ impl SearchIndexService {
     ///
     /// The batch size is limited by the configured `max_batch_size` (default: 1000). Individual
     /// deletion failures are reported in the summary rather than causing the entire operation to fail.
+    /// In [`DeleteMode::Soft`] the documents are only flagged as deleted.
     pub async fn batch_delete(
         &self,
         requests: Vec<DeleteEntityRequest>,
@@ -325,23 +584,264 @@ impl SearchIndexService {
             Self::validate_uuid("space_id", &request.space_id)?;
         }
 
-        self.provider.bulk_delete_documents(&requests).await
+        match self.config.delete_mode {
+            DeleteMode::Hard => self.provider.bulk_delete_documents(&requests).await,
+            DeleteMode::Soft { .. } => self.provider.bulk_soft_delete_documents(&requests).await,
+        }
+    }
+
+    /// Delete every entity document of a space, e.g. when the space is removed.
+    ///
+    /// In soft delete mode the documents are flagged as deleted now, and can be restored
+    /// with [`Self::restore`] until they're purged.
+    ///
+    /// # Arguments
+    ///
+    /// * `space_id` - The space whose documents to delete
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of deleted documents
+    /// * `Err(SearchIndexError::ValidationError)` - If the space ID is invalid
+    /// * `Err(SearchIndexError)` - If the operation fails
+    pub async fn delete_space(&self, space_id: &str) -> Result<u64, SearchIndexError> {
+        Self::validate_uuid("space_id", space_id)?;
+
+        match self.config.delete_mode {
+            DeleteMode::Hard => self.provider.delete_space_documents(space_id).await,
+            DeleteMode::Soft { .. } => {
+                self.provider
+                    .soft_delete_space_documents(space_id, Utc::now())
+                    .await
+            }
+        }
+    }
+
+    /// Update the vote score of entity documents from new vote totals, e.g. from the
+    /// `VotesCountChanged` messages of the actions indexer.
+    ///
+    /// Totals of entities without a document are skipped, and deleted documents stay
+    /// deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `updates` - Vec of vote totals
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BatchOperationSummary)` - Summary with success/failure counts and individual results
+    /// * `Err(SearchIndexError::BatchSizeExceeded)` - If batch size exceeds configured maximum
+    /// * `Err(SearchIndexError::ValidationError)` - If any UUID is invalid
+    /// * `Err(SearchIndexError)` - If the bulk operation fails
+    pub async fn update_vote_counts(
+        &self,
+        updates: Vec<VoteCountUpdate>,
+    ) -> Result<BatchOperationSummary, SearchIndexError> {
+        if updates.is_empty() {
+            return Ok(BatchOperationSummary {
+                total: 0,
+                succeeded: 0,
+                failed: 0,
+                results: vec![],
+            });
+        }
+
+        self.validate_batch_size(updates.len())?;
+
+        for update in &updates {
+            Self::validate_uuid("entity_id", &update.entity_id)?;
+            Self::validate_uuid("space_id", &update.space_id)?;
+        }
+
+        self.provider.bulk_update_vote_counts(&updates).await
+    }
+
+    /// Update the trust rank of the documents of every space whose depth in the canonical
+    /// graph changed, e.g. from a `CanonicalGraphUpdated` message of Atlas.
+    ///
+    /// Spaces that left the canonical graph get a trust rank of 0. The graph is recorded
+    /// once every changed space is updated, so a failed update is retried with the next
+    /// graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `depths` - The depth of every space of the canonical graph, 0 for its root
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of updated documents
+    /// * `Err(SearchIndexError::ValidationError)` - If a space ID is invalid
+    /// * `Err(SearchIndexError)` - If an update fails
+    pub async fn update_trust_ranks(
+        &self,
+        depths: HashMap<String, u32>,
+    ) -> Result<u64, SearchIndexError> {
+        let mut canonical = HashMap::with_capacity(depths.len());
+        for (space_id, depth) in depths {
+            Self::validate_uuid("space_id", &space_id)?;
+            // Key spaces by their canonical form, the one documents store
+            if let Ok(space_id) = Uuid::parse_str(&space_id) {
+                canonical.insert(space_id.to_string(), depth);
+            }
+        }
+
+        let changes = self
+            .trust_ranks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .changes(&canonical);
+        let mut updated = 0;
+        for (space_id, trust_rank) in changes {
+            updated += self
+                .provider
+                .set_space_trust_rank(&space_id, trust_rank)
+                .await?;
+        }
+
+        self.trust_ranks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .set(canonical);
+        Ok(updated)
+    }
+
+    /// Find the documents whose name or description match a text query.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - SearchRequest containing the query, an optional space filter, and the
+    ///   maximum number of documents
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SearchResult>)` - Up to `limit` documents, the most relevant first
+    /// * `Err(SearchIndexError::ValidationError)` - If the query is empty, `limit` is 0
+    ///   or exceeds the configured `max_batch_size`, or the space ID is invalid
+    /// * `Err(SearchIndexError)` - If the search fails
+    pub async fn search(
+        &self,
+        request: SearchRequest,
+    ) -> Result<Vec<SearchResult>, SearchIndexError> {
+        if request.query.trim().is_empty() {
+            return Err(SearchIndexError::validation(
+                "query is required".to_string(),
+            ));
+        }
+        if request.limit == 0 {
+            return Err(SearchIndexError::validation(
+                "limit must be at least 1".to_string(),
+            ));
+        }
+        self.validate_batch_size(request.limit)?;
+        if let Some(ref space_id) = request.space_id {
+            Self::validate_uuid("space_id", space_id)?;
+        }
+
+        self.provider.search(&request).await
+    }
+
+    /// Find the documents nearest to a query embedding.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - SemanticSearchRequest containing the query vector, k, and an optional
+    ///   space filter
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SearchResult>)` - Up to `k` documents, the closest first
+    /// * `Err(SearchIndexError::ValidationError)` - If the query vector is empty, `k` is 0
+    ///   or exceeds the configured `max_batch_size`, or the space ID is invalid
+    /// * `Err(SearchIndexError)` - If the search fails
+    pub async fn semantic_search(
+        &self,
+        request: SemanticSearchRequest,
+    ) -> Result<Vec<SearchResult>, SearchIndexError> {
+        if request.query_vector.is_empty() {
+            return Err(SearchIndexError::validation(
+                "query_vector is required".to_string(),
+            ));
+        }
+        if request.k == 0 {
+            return Err(SearchIndexError::validation(
+                "k must be at least 1".to_string(),
+            ));
+        }
+        self.validate_batch_size(request.k)?;
+        if let Some(ref space_id) = request.space_id {
+            Self::validate_uuid("space_id", space_id)?;
+        }
+
+        self.provider.semantic_search(&request).await
+    }
+
+    /// Find the documents nearest to a text query, embedded with the embedding provider.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The text to search for
+    /// * `k` - Maximum number of documents to return
+    /// * `space_id` - Only search documents in this space
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<SearchResult>)` - Up to `k` documents, the closest first
+    /// * `Err(SearchIndexError::EmbeddingError)` - If the service has no embedding
+    ///   provider, or the query can't be embedded
+    /// * `Err(SearchIndexError)` - If the request is invalid or the search fails, as in
+    ///   [`Self::semantic_search`]
+    pub async fn semantic_search_text(
+        &self,
+        query: &str,
+        k: usize,
+        space_id: Option<String>,
+    ) -> Result<Vec<SearchResult>, SearchIndexError> {
+        let embedder = self
+            .embedder
+            .as_deref()
+            .ok_or_else(|| SearchIndexError::embedding("No embedding provider is configured"))?;
+        let query_vector = embedder
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| SearchIndexError::embedding("Got no embedding for the query"))?;
+
+        self.semantic_search(SemanticSearchRequest {
+            query_vector,
+            k,
+            space_id,
+        })
+        .await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::BatchOperationResult;
+    use crate::types::{BatchOperationResult, GetEntityRequest, IndexedEntityDocument};
     use async_trait::async_trait;
+    use chrono::DateTime;
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::sync::Mutex;
     use uuid::Uuid;
 
+    /// Space ID and, for soft deletes, deletion time of a space delete
+    type SpaceDelete = (String, Option<DateTime<Utc>>);
+
     /// Mock provider for testing
     struct MockProvider {
         update_requests: Arc<Mutex<Vec<UpdateEntityRequest>>>,
         delete_requests: Arc<Mutex<Vec<DeleteEntityRequest>>>,
+        soft_delete_requests: Arc<Mutex<Vec<DeleteEntityRequest>>>,
+        purge_requests: Arc<Mutex<Vec<DateTime<Utc>>>>,
+        unset_requests: Arc<Mutex<Vec<UnsetEntityPropertiesRequest>>>,
+        search_requests: Arc<Mutex<Vec<SemanticSearchRequest>>>,
+        text_search_requests: Arc<Mutex<Vec<SearchRequest>>>,
+        space_delete_requests: Arc<Mutex<Vec<SpaceDelete>>>,
+        vote_count_requests: Arc<Mutex<Vec<VoteCountUpdate>>>,
+        trust_rank_requests: Arc<Mutex<Vec<(String, f64)>>>,
+        stored_document: Option<IndexedEntityDocument>,
         should_fail: bool,
     }
 
@@ -350,6 +850,15 @@ mod tests {
             Self {
                 update_requests: Arc::new(Mutex::new(Vec::new())),
                 delete_requests: Arc::new(Mutex::new(Vec::new())),
+                soft_delete_requests: Arc::new(Mutex::new(Vec::new())),
+                purge_requests: Arc::new(Mutex::new(Vec::new())),
+                unset_requests: Arc::new(Mutex::new(Vec::new())),
+                search_requests: Arc::new(Mutex::new(Vec::new())),
+                text_search_requests: Arc::new(Mutex::new(Vec::new())),
+                space_delete_requests: Arc::new(Mutex::new(Vec::new())),
+                vote_count_requests: Arc::new(Mutex::new(Vec::new())),
+                trust_rank_requests: Arc::new(Mutex::new(Vec::new())),
+                stored_document: None,
                 should_fail: false,
             }
         }
@@ -379,6 +888,27 @@ mod tests {
             Ok(())
         }
 
+        async fn soft_delete_document(
+            &self,
+            request: &DeleteEntityRequest,
+        ) -> Result<(), SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::index("Mock failure"));
+            }
+            self.soft_delete_requests.lock().await.push(request.clone());
+            Ok(())
+        }
+
+        async fn get_document(
+            &self,
+            _request: &GetEntityRequest,
+        ) -> Result<Option<IndexedEntityDocument>, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::index("Mock failure"));
+            }
+            Ok(self.stored_document.clone())
+        }
+
         async fn bulk_update_documents(
             &self,
             requests: &[UpdateEntityRequest],
@@ -443,16 +973,187 @@ mod tests {
             })
         }
 
+        async fn bulk_soft_delete_documents(
+            &self,
+            requests: &[DeleteEntityRequest],
+        ) -> Result<BatchOperationSummary, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::bulk_operation("Mock failure"));
+            }
+
+            let mut results = Vec::new();
+            for req in requests {
+                results.push(BatchOperationResult {
+                    entity_id: req.entity_id.clone(),
+                    space_id: req.space_id.clone(),
+                    success: true,
+                    error: None,
+                });
+                self.soft_delete_requests.lock().await.push(req.clone());
+            }
+
+            Ok(BatchOperationSummary {
+                total: requests.len(),
+                succeeded: requests.len(),
+                failed: 0,
+                results,
+            })
+        }
+
+        async fn restore_documents(
+            &self,
+            _request: &RestoreEntitiesRequest,
+        ) -> Result<u64, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::update("Mock failure"));
+            }
+            Ok(1)
+        }
+
+        async fn purge_deleted_documents(
+            &self,
+            deleted_before: DateTime<Utc>,
+        ) -> Result<u64, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::delete("Mock failure"));
+            }
+            self.purge_requests.lock().await.push(deleted_before);
+            Ok(0)
+        }
+
         async fn unset_document_properties(
             &self,
-            _request: &UnsetEntityPropertiesRequest,
+            request: &UnsetEntityPropertiesRequest,
         ) -> Result<(), SearchIndexError> {
             if self.should_fail {
                 return Err(SearchIndexError::index("Mock failure"));
             }
-            // Mock implementation - just succeed without tracking
+            self.unset_requests.lock().await.push(request.clone());
             Ok(())
         }
+
+        async fn semantic_search(
+            &self,
+            request: &SemanticSearchRequest,
+        ) -> Result<Vec<SearchResult>, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::search("Mock failure"));
+            }
+            self.search_requests.lock().await.push(request.clone());
+            Ok(Vec::new())
+        }
+
+        async fn search(
+            &self,
+            request: &SearchRequest,
+        ) -> Result<Vec<SearchResult>, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::search("Mock failure"));
+            }
+            self.text_search_requests.lock().await.push(request.clone());
+            Ok(Vec::new())
+        }
+
+        async fn delete_space_documents(&self, space_id: &str) -> Result<u64, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::delete("Mock failure"));
+            }
+            self.space_delete_requests
+                .lock()
+                .await
+                .push((space_id.to_string(), None));
+            Ok(2)
+        }
+
+        async fn soft_delete_space_documents(
+            &self,
+            space_id: &str,
+            deleted_at: DateTime<Utc>,
+        ) -> Result<u64, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::delete("Mock failure"));
+            }
+            self.space_delete_requests
+                .lock()
+                .await
+                .push((space_id.to_string(), Some(deleted_at)));
+            Ok(2)
+        }
+
+        async fn bulk_update_vote_counts(
+            &self,
+            updates: &[VoteCountUpdate],
+        ) -> Result<BatchOperationSummary, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::bulk_operation("Mock failure"));
+            }
+            self.vote_count_requests
+                .lock()
+                .await
+                .extend_from_slice(updates);
+            Ok(BatchOperationSummary {
+                total: updates.len(),
+                succeeded: updates.len(),
+                failed: 0,
+                results: updates
+                    .iter()
+                    .map(|update| BatchOperationResult {
+                        entity_id: update.entity_id.clone(),
+                        space_id: update.space_id.clone(),
+                        success: true,
+                        error: None,
+                    })
+                    .collect(),
+            })
+        }
+
+        async fn set_space_trust_rank(
+            &self,
+            space_id: &str,
+            trust_rank: f64,
+        ) -> Result<u64, SearchIndexError> {
+            if self.should_fail {
+                return Err(SearchIndexError::update("Mock failure"));
+            }
+            self.trust_rank_requests
+                .lock()
+                .await
+                .push((space_id.to_string(), trust_rank));
+            Ok(1)
+        }
+    }
+
+    /// Mock embedding provider embedding a text as its length, repeated
+    struct MockEmbedder {
+        dimension: usize,
+        texts: Arc<Mutex<Vec<String>>>,
+        wrong_dimension: bool,
+    }
+
+    impl MockEmbedder {
+        fn new(dimension: usize) -> Self {
+            Self {
+                dimension,
+                texts: Arc::new(Mutex::new(Vec::new())),
+                wrong_dimension: false,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for MockEmbedder {
+        fn dimension(&self) -> usize {
+            self.dimension
+        }
+
+        async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, SearchIndexError> {
+            self.texts.lock().await.extend_from_slice(texts);
+            let dimension = self.dimension + usize::from(self.wrong_dimension);
+            Ok(texts
+                .iter()
+                .map(|text| vec![text.len() as f32; dimension])
+                .collect())
+        }
     }
 
     fn create_test_update_request(entity_id: &str, space_id: &str) -> UpdateEntityRequest {
@@ -466,6 +1167,13 @@ mod tests {
             entity_global_score: None,
             space_score: None,
             entity_space_score: None,
+            edited_at: None,
+            edit_version: None,
+            name_embedding: None,
+            description_embedding: None,
+            language: None,
+            score: None,
+            trust_rank: None,
         }
     }
 
@@ -473,6 +1181,16 @@ mod tests {
         DeleteEntityRequest {
             entity_id: entity_id.to_string(),
             space_id: space_id.to_string(),
+            edited_at: None,
+        }
+    }
+
+    fn create_test_unset_request(entity_id: &str, space_id: &str) -> UnsetEntityPropertiesRequest {
+        UnsetEntityPropertiesRequest {
+            entity_id: entity_id.to_string(),
+            space_id: space_id.to_string(),
+            property_keys: vec!["name".to_string()],
+            edited_at: None,
         }
     }
 
@@ -592,6 +1310,13 @@ mod tests {
             entity_global_score: None,
             space_score: None,
             entity_space_score: None,
+            edited_at: None,
+            edit_version: None,
+            name_embedding: None,
+            description_embedding: None,
+            language: None,
+            score: None,
+            trust_rank: None,
         };
         assert!(service.update(request).await.is_err());
 
@@ -606,6 +1331,13 @@ mod tests {
             entity_global_score: None,
             space_score: None,
             entity_space_score: None,
+            edited_at: None,
+            edit_version: None,
+            name_embedding: None,
+            description_embedding: None,
+            language: None,
+            score: None,
+            trust_rank: None,
         };
         assert!(service.update(request).await.is_err());
     }
@@ -619,6 +1351,7 @@ mod tests {
         let request = DeleteEntityRequest {
             entity_id: "".to_string(),
             space_id: Uuid::new_v4().to_string(),
+            edited_at: None,
         };
         assert!(service.delete(request).await.is_err());
 
@@ -626,6 +1359,7 @@ mod tests {
         let request = DeleteEntityRequest {
             entity_id: Uuid::new_v4().to_string(),
             space_id: "".to_string(),
+            edited_at: None,
         };
         assert!(service.delete(request).await.is_err());
     }
@@ -648,6 +1382,13 @@ mod tests {
                 entity_global_score: None,
                 space_score: None,
                 entity_space_score: None,
+                edited_at: None,
+                edit_version: None,
+                name_embedding: None,
+                description_embedding: None,
+                language: None,
+                score: None,
+                trust_rank: None,
             })
             .collect();
 
@@ -658,4 +1399,470 @@ mod tests {
             panic!("Batch size should not be limited with unlimited config");
         }
     }
+
+    #[tokio::test]
+    async fn test_soft_delete_mode_flags_documents() {
+        let provider = MockProvider::new();
+        let deleted = provider.delete_requests.clone();
+        let soft_deleted = provider.soft_delete_requests.clone();
+        let config =
+            SearchIndexServiceConfig::default().with_soft_delete(Duration::from_secs(86400));
+        let service = SearchIndexService::with_config(Box::new(provider), config);
+
+        let entity_id = Uuid::new_v4().to_string();
+        let space_id = Uuid::new_v4().to_string();
+        service
+            .delete(create_test_delete_request(&entity_id, &space_id))
+            .await
+            .unwrap();
+        service
+            .batch_delete(vec![create_test_delete_request(&entity_id, &space_id)])
+            .await
+            .unwrap();
+
+        assert!(deleted.lock().await.is_empty());
+        assert_eq!(soft_deleted.lock().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_restore_validation() {
+        let provider = MockProvider::new();
+        let service = SearchIndexService::new(Box::new(provider));
+
+        // At least one filter is required
+        assert!(service
+            .restore(RestoreEntitiesRequest::default())
+            .await
+            .is_err());
+
+        let request = RestoreEntitiesRequest {
+            space_id: Some("not-a-uuid".to_string()),
+            ..Default::default()
+        };
+        assert!(service.restore(request).await.is_err());
+
+        let request = RestoreEntitiesRequest {
+            space_id: Some(Uuid::new_v4().to_string()),
+            ..Default::default()
+        };
+        assert_eq!(service.restore(request).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unset_properties_deletes_empty_document() {
+        let mut provider = MockProvider::new();
+        provider.stored_document = Some(IndexedEntityDocument {
+            entity_global_score: Some(1.0),
+            ..Default::default()
+        });
+        let deleted = provider.delete_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+
+        let entity_id = Uuid::new_v4().to_string();
+        let space_id = Uuid::new_v4().to_string();
+        let mut request = create_test_unset_request(&entity_id, &space_id);
+        request.edited_at = Some(Utc::now());
+        service.unset_properties(request.clone()).await.unwrap();
+
+        let deleted = deleted.lock().await;
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].entity_id, entity_id);
+        assert_eq!(deleted[0].edited_at, request.edited_at);
+    }
+
+    #[tokio::test]
+    async fn test_unset_properties_keeps_document_with_fields() {
+        let mut provider = MockProvider::new();
+        provider.stored_document = Some(IndexedEntityDocument {
+            description: Some("Still described".to_string()),
+            ..Default::default()
+        });
+        let deleted = provider.delete_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+
+        let request =
+            create_test_unset_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        service.unset_properties(request).await.unwrap();
+
+        assert!(deleted.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unset_properties_ignores_deleted_document() {
+        let mut provider = MockProvider::new();
+        provider.stored_document = Some(IndexedEntityDocument {
+            deleted: true,
+            ..Default::default()
+        });
+        let soft_deleted = provider.soft_delete_requests.clone();
+        let config =
+            SearchIndexServiceConfig::default().with_soft_delete(Duration::from_secs(86400));
+        let service = SearchIndexService::with_config(Box::new(provider), config);
+
+        let request =
+            create_test_unset_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        service.unset_properties(request).await.unwrap();
+
+        // Deleting it again would move its deletion time forward
+        assert!(soft_deleted.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_purge_expired() {
+        let provider = MockProvider::new();
+        let purges = provider.purge_requests.clone();
+        let config =
+            SearchIndexServiceConfig::default().with_tombstone_retention(Duration::from_secs(600));
+        let service = SearchIndexService::with_config(Box::new(provider), config);
+
+        // Only tombstones are purged when deleting hard
+        service.purge_expired().await.unwrap();
+        let age = Utc::now() - purges.lock().await[0];
+        assert!(age >= chrono::Duration::minutes(10));
+        assert!(age < chrono::Duration::minutes(11));
+
+        let provider = MockProvider::new();
+        let purges = provider.purge_requests.clone();
+        let config =
+            SearchIndexServiceConfig::default().with_soft_delete(Duration::from_secs(3600));
+        let service = SearchIndexService::with_config(Box::new(provider), config);
+
+        service.purge_expired().await.unwrap();
+
+        let deleted_before = purges.lock().await[0];
+        let age = Utc::now() - deleted_before;
+        assert!(age >= chrono::Duration::hours(1));
+        assert!(age < chrono::Duration::hours(1) + chrono::Duration::minutes(1));
+    }
+
+    #[tokio::test]
+    async fn test_update_embeds_name_and_description() {
+        let provider = MockProvider::new();
+        let updates = provider.update_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider))
+            .with_embedding_provider(Box::new(MockEmbedder::new(2)));
+
+        let mut request =
+            create_test_update_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        request.description = Some("Desc".to_string());
+        service.update(request).await.unwrap();
+
+        let updates = updates.lock().await;
+        assert_eq!(updates[0].name_embedding, Some(vec![12.0, 12.0]));
+        assert_eq!(updates[0].description_embedding, Some(vec![4.0, 4.0]));
+    }
+
+    #[tokio::test]
+    async fn test_batch_update_embeds_in_one_call() {
+        let provider = MockProvider::new();
+        let updates = provider.update_requests.clone();
+        let embedder = MockEmbedder::new(2);
+        let texts = embedder.texts.clone();
+        let service =
+            SearchIndexService::new(Box::new(provider)).with_embedding_provider(Box::new(embedder));
+
+        let mut scores_only =
+            create_test_update_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        scores_only.name = None;
+        scores_only.space_score = Some(1.0);
+        let mut embedded =
+            create_test_update_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        embedded.name_embedding = Some(vec![0.5, 0.5]);
+        embedded.description = Some("Desc".to_string());
+        service
+            .batch_update(vec![scores_only, embedded])
+            .await
+            .unwrap();
+
+        // Only the description lacked an embedding
+        assert_eq!(*texts.lock().await, vec!["Desc"]);
+        let updates = updates.lock().await;
+        assert_eq!(updates[0].name_embedding, None);
+        assert_eq!(updates[0].description_embedding, None);
+        assert_eq!(updates[1].name_embedding, Some(vec![0.5, 0.5]));
+        assert_eq!(updates[1].description_embedding, Some(vec![4.0, 4.0]));
+    }
+
+    #[tokio::test]
+    async fn test_update_rejects_embedding_dimension_mismatch() {
+        let provider = MockProvider::new();
+        let updates = provider.update_requests.clone();
+        let mut embedder = MockEmbedder::new(2);
+        embedder.wrong_dimension = true;
+        let service =
+            SearchIndexService::new(Box::new(provider)).with_embedding_provider(Box::new(embedder));
+
+        let request =
+            create_test_update_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        let result = service.update(request).await;
+
+        assert!(matches!(result, Err(SearchIndexError::EmbeddingError(_))));
+        assert!(updates.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unset_properties_unsets_embeddings() {
+        let mut provider = MockProvider::new();
+        provider.stored_document = Some(IndexedEntityDocument {
+            description: Some("Desc".to_string()),
+            ..Default::default()
+        });
+        let unsets = provider.unset_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+
+        let mut request =
+            create_test_unset_request(&Uuid::new_v4().to_string(), &Uuid::new_v4().to_string());
+        request.property_keys.push("avatar".to_string());
+        service.unset_properties(request).await.unwrap();
+
+        assert_eq!(
+            unsets.lock().await[0].property_keys,
+            vec!["name", "avatar", "name_embedding"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_validation() {
+        let service = SearchIndexService::new(Box::new(MockProvider::new()));
+        let request = SemanticSearchRequest {
+            query_vector: vec![0.5, 0.5],
+            k: 10,
+            space_id: None,
+        };
+
+        assert!(service.semantic_search(request.clone()).await.is_ok());
+        for invalid in [
+            SemanticSearchRequest {
+                query_vector: vec![],
+                ..request.clone()
+            },
+            SemanticSearchRequest {
+                k: 0,
+                ..request.clone()
+            },
+            SemanticSearchRequest {
+                k: 1001,
+                ..request.clone()
+            },
+            SemanticSearchRequest {
+                space_id: Some("invalid".to_string()),
+                ..request.clone()
+            },
+        ] {
+            assert!(service.semantic_search(invalid).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_text() {
+        let provider = MockProvider::new();
+        let searches = provider.search_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider))
+            .with_embedding_provider(Box::new(MockEmbedder::new(2)));
+
+        let space_id = Uuid::new_v4().to_string();
+        service
+            .semantic_search_text("query", 5, Some(space_id.clone()))
+            .await
+            .unwrap();
+
+        let searches = searches.lock().await;
+        assert_eq!(searches[0].query_vector, vec![5.0, 5.0]);
+        assert_eq!(searches[0].k, 5);
+        assert_eq!(searches[0].space_id, Some(space_id));
+
+        // Text queries need an embedding provider
+        let service = SearchIndexService::new(Box::new(MockProvider::new()));
+        assert!(matches!(
+            service.semantic_search_text("query", 5, None).await,
+            Err(SearchIndexError::EmbeddingError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_search_validation() {
+        let provider = MockProvider::new();
+        let searches = provider.text_search_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+        let request = SearchRequest {
+            query: "alice".to_string(),
+            space_id: Some(Uuid::new_v4().to_string()),
+            limit: 10,
+            language: None,
+            boost: None,
+        };
+
+        assert!(service.search(request.clone()).await.is_ok());
+        for invalid in [
+            SearchRequest {
+                query: " ".to_string(),
+                ..request.clone()
+            },
+            SearchRequest {
+                limit: 0,
+                ..request.clone()
+            },
+            SearchRequest {
+                limit: 1001,
+                ..request.clone()
+            },
+            SearchRequest {
+                space_id: Some("invalid".to_string()),
+                ..request.clone()
+            },
+        ] {
+            assert!(service.search(invalid).await.is_err());
+        }
+        assert_eq!(searches.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_space() {
+        let space_id = Uuid::new_v4().to_string();
+
+        let provider = MockProvider::new();
+        let space_deletes = provider.space_delete_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+        assert_eq!(service.delete_space(&space_id).await.unwrap(), 2);
+        assert!(service.delete_space("invalid").await.is_err());
+        assert_eq!(
+            space_deletes.lock().await.as_slice(),
+            &[(space_id.clone(), None)]
+        );
+
+        // Soft delete mode flags the documents instead
+        let provider = MockProvider::new();
+        let space_deletes = provider.space_delete_requests.clone();
+        let config =
+            SearchIndexServiceConfig::default().with_soft_delete(Duration::from_secs(86400));
+        let service = SearchIndexService::with_config(Box::new(provider), config);
+        service.delete_space(&space_id).await.unwrap();
+        let space_deletes = space_deletes.lock().await;
+        assert_eq!(space_deletes[0].0, space_id);
+        assert!(space_deletes[0].1.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_update_resolves_language() {
+        let provider = MockProvider::new();
+        let updates = provider.update_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+
+        let entity_id = Uuid::new_v4().to_string();
+        let space_id = Uuid::new_v4().to_string();
+        let request = |name: &str, language: Option<&str>| UpdateEntityRequest {
+            name: Some(name.to_string()),
+            language: language.map(str::to_string),
+            ..create_test_update_request(&entity_id, &space_id)
+        };
+        service
+            .batch_update(vec![
+                // The language of the edit is normalized
+                request("Paris", Some("fr-FR")),
+                // And detected from the script of the name without one
+                request("Москва", None),
+                // Latin script alone doesn't tell the language
+                request("Berlin", None),
+                // Unsupported languages are dropped
+                request("Roma", Some("la")),
+            ])
+            .await
+            .unwrap();
+
+        let languages: Vec<Option<String>> = updates
+            .lock()
+            .await
+            .iter()
+            .map(|update| update.language.clone())
+            .collect();
+        assert_eq!(
+            languages,
+            vec![Some("fr".to_string()), Some("ru".to_string()), None, None]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_vote_counts() {
+        let provider = MockProvider::new();
+        let votes = provider.vote_count_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+        let update = VoteCountUpdate {
+            entity_id: Uuid::new_v4().to_string(),
+            space_id: Uuid::new_v4().to_string(),
+            upvotes: 3,
+            downvotes: 1,
+        };
+
+        let summary = service
+            .update_vote_counts(vec![update.clone()])
+            .await
+            .unwrap();
+        assert_eq!(summary.succeeded, 1);
+        assert_eq!(votes.lock().await.len(), 1);
+
+        let invalid = VoteCountUpdate {
+            space_id: "invalid".to_string(),
+            ..update
+        };
+        assert!(service.update_vote_counts(vec![invalid]).await.is_err());
+        assert!(service
+            .update_vote_counts(Vec::new())
+            .await
+            .unwrap()
+            .results
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_update_trust_ranks() {
+        let provider = MockProvider::new();
+        let trust_ranks = provider.trust_rank_requests.clone();
+        let updates = provider.update_requests.clone();
+        let service = SearchIndexService::new(Box::new(provider));
+
+        let root = Uuid::new_v4();
+        let child = Uuid::new_v4();
+        // Space IDs are keyed by their canonical form
+        service
+            .update_trust_ranks(HashMap::from([
+                (root.simple().to_string(), 0),
+                (child.to_string(), 1),
+            ]))
+            .await
+            .unwrap();
+        assert_eq!(trust_ranks.lock().await.len(), 2);
+
+        // Only changed spaces are updated, and spaces that left the graph get 0
+        trust_ranks.lock().await.clear();
+        service
+            .update_trust_ranks(HashMap::from([(root.to_string(), 0)]))
+            .await
+            .unwrap();
+        assert_eq!(
+            trust_ranks.lock().await.as_slice(),
+            &[(child.to_string(), 0.0)]
+        );
+
+        // Updates in canonical spaces get the trust rank of their space
+        let entity_id = Uuid::new_v4().to_string();
+        service
+            .update(create_test_update_request(
+                &entity_id,
+                &root.simple().to_string(),
+            ))
+            .await
+            .unwrap();
+        service
+            .update(create_test_update_request(&entity_id, &child.to_string()))
+            .await
+            .unwrap();
+        let updates = updates.lock().await;
+        assert_eq!(updates[0].trust_rank, Some(1.0));
+        assert_eq!(updates[1].trust_rank, None);
+
+        assert!(service
+            .update_trust_ranks(HashMap::from([("invalid".to_string(), 0)]))
+            .await
+            .is_err());
+    }
 }
@@ -1,5 +1,8 @@
 //! Request and response types for search index operations.
 
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
 use crate::errors::SearchIndexError;
 
 /// Request to update an existing entity document in the search index.
@@ -27,6 +30,29 @@ pub struct UpdateEntityRequest {
     pub space_score: Option<f64>,
     /// Entity-space score.
     pub entity_space_score: Option<f64>,
+    /// When the edit was made. An update older than the document's tombstone is
+    /// dropped, so a create arriving after its delete doesn't bring the entity back.
+    pub edited_at: Option<DateTime<Utc>>,
+    /// Version of the edit, increasing with the edits of the entity, e.g. the offset of
+    /// its message in the entity's partition. An update with a version at or below the
+    /// document's is dropped, so redelivered edits are applied once.
+    pub edit_version: Option<u64>,
+    /// Embedding of `name`, set by `SearchIndexService` when it has an
+    /// `EmbeddingProvider`.
+    pub name_embedding: Option<Vec<f32>>,
+    /// Embedding of `description`, set by `SearchIndexService` when it has an
+    /// `EmbeddingProvider`.
+    pub description_embedding: Option<Vec<f32>>,
+    /// Language of the name and description, as a language tag, e.g. decoded from the
+    /// edit with [`edit_language`](crate::language::edit_language). When unset,
+    /// `SearchIndexService` detects it from the script of the text.
+    pub language: Option<String>,
+    /// Vote score of the entity in the space. Vote totals are usually applied with
+    /// `SearchIndexService::update_vote_counts` instead, which doesn't create documents.
+    pub score: Option<f64>,
+    /// Trust rank of the space, set by `SearchIndexService` for spaces of the canonical
+    /// graph.
+    pub trust_rank: Option<f64>,
 }
 
 /// Request to delete an entity document from the search index.
@@ -39,6 +65,172 @@ pub struct DeleteEntityRequest {
     pub entity_id: String,
     /// The space this entity belongs to.
     pub space_id: String,
+    /// When the edit deleting the entity was made. When set, the document is replaced
+    /// by a tombstone deleted at this time, which drops updates from older edits.
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+/// Request to fetch an entity document from the search index.
+///
+/// This struct identifies the document to fetch using `entity_id` and `space_id`.
+/// Both fields are required and must be valid UUIDs.
+#[derive(Debug, Clone)]
+pub struct GetEntityRequest {
+    /// The entity's unique identifier.
+    pub entity_id: String,
+    /// The space this entity belongs to.
+    pub space_id: String,
+}
+
+/// Fields of an entity document as currently stored in the search index.
+///
+/// Documents are created through partial upserts, so any field may be missing from
+/// the stored document. Missing fields are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct IndexedEntityDocument {
+    /// The entity's display name.
+    pub name: Option<String>,
+    /// Optional description text.
+    pub description: Option<String>,
+    /// Optional avatar image URL.
+    pub avatar: Option<String>,
+    /// Optional cover image URL.
+    pub cover: Option<String>,
+    /// Global entity score.
+    pub entity_global_score: Option<f64>,
+    /// Space score.
+    pub space_score: Option<f64>,
+    /// Entity-space score.
+    pub entity_space_score: Option<f64>,
+    /// Whether the document was soft-deleted and is hidden from search.
+    pub deleted: bool,
+    /// When the document was soft-deleted.
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Version of the last edit applied to the document.
+    pub edit_version: Option<u64>,
+    /// Embedding of the name.
+    pub name_embedding: Option<Vec<f32>>,
+    /// Embedding of the description.
+    pub description_embedding: Option<Vec<f32>>,
+    /// Code of the language of the name and description.
+    pub language: Option<String>,
+    /// Score of the entity from its votes in the space.
+    pub score: Option<f64>,
+    /// Trust rank of the space.
+    pub trust_rank: Option<f64>,
+}
+
+impl IndexedEntityDocument {
+    /// Whether the document has no searchable or displayable field left.
+    ///
+    /// Scores alone don't make an entity worth returning from search, so a document
+    /// left with only scores is empty.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.description.is_none()
+            && self.avatar.is_none()
+            && self.cover.is_none()
+    }
+}
+
+/// Request to restore soft-deleted entity documents.
+///
+/// Every set filter must match for a document to be restored, and at least one
+/// filter is required. IDs must be valid UUIDs. Use `deleted_since` to undo a
+/// mass deletion, e.g. all documents deleted by a bad edit in a space.
+#[derive(Debug, Clone, Default)]
+pub struct RestoreEntitiesRequest {
+    /// Only restore documents of this entity.
+    pub entity_id: Option<String>,
+    /// Only restore documents in this space.
+    pub space_id: Option<String>,
+    /// Only restore documents deleted at or after this time.
+    pub deleted_since: Option<DateTime<Utc>>,
+}
+
+/// Request for the documents whose name or description match a text query.
+///
+/// The query matches words and word prefixes, so it can be used while typing. Deleted
+/// documents and tombstones are excluded.
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    /// The text to search for.
+    pub query: String,
+    /// Only search documents in this space.
+    pub space_id: Option<String>,
+    /// Maximum number of documents to return.
+    pub limit: usize,
+    /// Language of the query, as a language tag. When unset, it's detected from the script
+    /// of the query.
+    pub language: Option<String>,
+    /// Boost of the vote score and trust rank of documents. When unset, documents are
+    /// ranked on text relevance alone.
+    pub boost: Option<RelevanceBoost>,
+}
+
+/// Weights of the ranking signals combined with the text relevance of search results.
+///
+/// The relevance of a document is multiplied by
+/// `1 + vote_weight * score + trust_weight * trust_rank`, both signals being between 0
+/// and 1, and 0 when missing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelevanceBoost {
+    /// Weight of the vote score of the entity.
+    pub vote_weight: f64,
+    /// Weight of the trust rank of the space.
+    pub trust_weight: f64,
+}
+
+impl Default for RelevanceBoost {
+    fn default() -> Self {
+        Self {
+            vote_weight: 1.0,
+            trust_weight: 1.0,
+        }
+    }
+}
+
+/// New vote totals of an entity in a space, e.g. from a `VotesCountChanged` message.
+///
+/// Totals only update documents that exist: votes never create or restore a document.
+#[derive(Debug, Clone)]
+pub struct VoteCountUpdate {
+    /// The voted entity's unique identifier.
+    pub entity_id: String,
+    /// The space the votes were cast in.
+    pub space_id: String,
+    /// Total of upvotes.
+    pub upvotes: i64,
+    /// Total of downvotes.
+    pub downvotes: i64,
+}
+
+/// Request for the documents nearest to a query embedding.
+///
+/// Documents are scored on the similarity of the query to the embeddings of their name
+/// and description. Deleted documents and tombstones are excluded.
+#[derive(Debug, Clone)]
+pub struct SemanticSearchRequest {
+    /// Embedding of the query, with the dimension of the indexed embeddings.
+    pub query_vector: Vec<f32>,
+    /// Maximum number of documents to return.
+    pub k: usize,
+    /// Only search documents in this space.
+    pub space_id: Option<String>,
+}
+
+/// A document returned by a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// The entity's unique identifier.
+    pub entity_id: String,
+    /// The space this entity belongs to.
+    pub space_id: String,
+    /// Similarity of the document to the query, higher is closer.
+    pub score: f64,
+    /// Fields of the document as stored in the search index.
+    pub document: IndexedEntityDocument,
 }
 
 /// Request to unset (remove) specific properties from an entity document.
@@ -54,6 +246,9 @@ pub struct UnsetEntityPropertiesRequest {
     pub space_id: String,
     /// The property keys to remove from the document.
     pub property_keys: Vec<String>,
+    /// When the edit was made. Passed on to the delete when unsetting the properties
+    /// leaves the document empty.
+    pub edited_at: Option<DateTime<Utc>>,
 }
 
 /// Result of a batch operation for a single item.
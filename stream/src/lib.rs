@@ -1,7 +1,9 @@
+pub mod package;
 pub mod pb;
 pub mod sink;
 pub mod substreams;
 pub mod substreams_stream;
 
+pub use package::{PackageError, PackageManager, PackageSource, PackageSpec};
 pub use sink::{PreprocessedSink, Sink, read_package};
 pub mod utils;
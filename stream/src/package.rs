@@ -0,0 +1,322 @@
+//! Resolution and pinning of Substreams packages.
+//!
+//! Sinks used to be pointed at loose `.spkg` files on disk, which made it easy
+//! to deploy with the wrong package. A [`PackageSpec`] names where a package
+//! comes from (the registry, a URL, a file or bytes embedded in the binary)
+//! and optionally pins its SHA-256 hash. [`PackageManager`] resolves specs,
+//! verifies pinned hashes and caches pinned packages on disk by hash so they
+//! are only downloaded once.
+//!
+//! ```ignore
+//! let spec = PackageSpec::registry("geo-substream", "v1.0.3")
+//!     .pinned("4b7f...e1");
+//! let package = PackageManager::from_env().resolve(&spec).await?;
+//! sink.run_package(&endpoint_url, package, MODULE_NAME, START_BLOCK, 0).await?;
+//! ```
+
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use prost::Message;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::pb::sf::substreams::v1::Package;
+
+const DEFAULT_REGISTRY_URL: &str = "https://spkg.io";
+const DEFAULT_CACHE_DIR: &str = ".spkg-cache";
+
+#[derive(Error, Debug)]
+pub enum PackageError {
+    #[error("failed to fetch package {0}: {1}")]
+    Fetch(String, #[source] reqwest::Error),
+
+    #[error("failed to read package {0}: {1}")]
+    Io(String, #[source] std::io::Error),
+
+    #[error("failed to decode package {0}: {1}")]
+    Decode(String, #[source] prost::DecodeError),
+
+    #[error("package {package} has hash {actual}, expected {expected}")]
+    HashMismatch {
+        package: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Where a package's bytes come from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PackageSource {
+    /// A package published to the Substreams registry.
+    Registry { name: String, version: String },
+
+    /// A package served over HTTP(S).
+    Url(String),
+
+    /// A package on the local filesystem.
+    File(PathBuf),
+
+    /// A package compiled into the binary, e.g. with `include_bytes!`.
+    Embedded(&'static [u8]),
+}
+
+impl fmt::Display for PackageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackageSource::Registry { name, version } => write!(f, "{}@{}", name, version),
+            PackageSource::Url(url) => write!(f, "{}", url),
+            PackageSource::File(path) => write!(f, "{}", path.display()),
+            PackageSource::Embedded(bytes) => write!(f, "<embedded {} bytes>", bytes.len()),
+        }
+    }
+}
+
+/// A package to resolve, optionally pinned to a SHA-256 hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackageSpec {
+    pub source: PackageSource,
+    pub sha256: Option<String>,
+}
+
+impl PackageSpec {
+    pub fn registry(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self::from_source(PackageSource::Registry {
+            name: name.into(),
+            version: version.into(),
+        })
+    }
+
+    pub fn url(url: impl Into<String>) -> Self {
+        Self::from_source(PackageSource::Url(url.into()))
+    }
+
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self::from_source(PackageSource::File(path.into()))
+    }
+
+    pub fn embedded(bytes: &'static [u8]) -> Self {
+        Self::from_source(PackageSource::Embedded(bytes))
+    }
+
+    /// Pins the package to a hex-encoded SHA-256 hash. Resolving fails if the
+    /// package bytes don't match.
+    pub fn pinned(mut self, sha256: impl Into<String>) -> Self {
+        self.sha256 = Some(sha256.into().to_lowercase());
+        self
+    }
+
+    fn from_source(source: PackageSource) -> Self {
+        Self {
+            source,
+            sha256: None,
+        }
+    }
+}
+
+/// Resolves [`PackageSpec`]s, verifying and caching pinned packages.
+#[derive(Clone, Debug)]
+pub struct PackageManager {
+    registry_url: String,
+    cache_dir: PathBuf,
+}
+
+impl PackageManager {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            registry_url: DEFAULT_REGISTRY_URL.to_string(),
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    /// Reads the cache directory from `SPKG_CACHE_DIR` and the registry from
+    /// `SPKG_REGISTRY_URL`, falling back to `.spkg-cache` and spkg.io.
+    pub fn from_env() -> Self {
+        let cache_dir = env::var("SPKG_CACHE_DIR").unwrap_or(DEFAULT_CACHE_DIR.to_string());
+        let manager = Self::new(cache_dir);
+
+        match env::var("SPKG_REGISTRY_URL") {
+            Ok(registry_url) => manager.with_registry_url(registry_url),
+            Err(_) => manager,
+        }
+    }
+
+    pub fn with_registry_url(mut self, registry_url: impl Into<String>) -> Self {
+        self.registry_url = registry_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Resolves a package, verifying its hash if the spec is pinned.
+    ///
+    /// Pinned packages are served from the cache when present and written to
+    /// it after a successful fetch. Unpinned packages are never cached since
+    /// their contents can change between resolutions.
+    pub async fn resolve(&self, spec: &PackageSpec) -> Result<Package, PackageError> {
+        let bytes = self.resolve_bytes(spec).await?;
+
+        Package::decode(bytes.as_slice())
+            .map_err(|error| PackageError::Decode(spec.source.to_string(), error))
+    }
+
+    async fn resolve_bytes(&self, spec: &PackageSpec) -> Result<Vec<u8>, PackageError> {
+        if let Some(expected) = &spec.sha256
+            && let Some(bytes) = self.read_cached(expected)
+        {
+            return Ok(bytes);
+        }
+
+        let bytes = self.fetch(&spec.source).await?;
+
+        if let Some(expected) = &spec.sha256 {
+            let actual = sha256_hex(&bytes);
+
+            if &actual != expected {
+                return Err(PackageError::HashMismatch {
+                    package: spec.source.to_string(),
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+
+            self.write_cached(expected, &bytes);
+        }
+
+        Ok(bytes)
+    }
+
+    async fn fetch(&self, source: &PackageSource) -> Result<Vec<u8>, PackageError> {
+        match source {
+            PackageSource::Registry { name, version } => {
+                let url = format!("{}/v1/packages/{}/{}", self.registry_url, name, version);
+                fetch_url(&url).await
+            }
+            PackageSource::Url(url) => fetch_url(url).await,
+            PackageSource::File(path) => {
+                fs::read(path).map_err(|error| PackageError::Io(path.display().to_string(), error))
+            }
+            PackageSource::Embedded(bytes) => Ok(bytes.to_vec()),
+        }
+    }
+
+    fn cache_path(&self, sha256: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.spkg", sha256))
+    }
+
+    /// Returns the cached package for a hash, ignoring entries that were
+    /// corrupted on disk.
+    fn read_cached(&self, sha256: &str) -> Option<Vec<u8>> {
+        let bytes = fs::read(self.cache_path(sha256)).ok()?;
+
+        if sha256_hex(&bytes) == sha256 {
+            Some(bytes)
+        } else {
+            None
+        }
+    }
+
+    /// Caching is best effort: a read-only filesystem shouldn't stop a sink
+    /// from starting.
+    fn write_cached(&self, sha256: &str, bytes: &[u8]) {
+        if let Err(error) = fs::create_dir_all(&self.cache_dir)
+            .and_then(|_| fs::write(self.cache_path(sha256), bytes))
+        {
+            println!("Failed to cache package {}: {}", sha256, error);
+        }
+    }
+}
+
+async fn fetch_url(url: &str) -> Result<Vec<u8>, PackageError> {
+    let response = reqwest::get(url)
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| PackageError::Fetch(url.to_string(), error))?;
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|error| PackageError::Fetch(url.to_string(), error))?;
+
+    Ok(bytes.to_vec())
+}
+
+/// Hex-encoded SHA-256 hash of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::pb::sf::substreams::v1::PackageMetadata;
+
+    fn package_bytes() -> &'static [u8] {
+        let package = Package {
+            package_meta: vec![PackageMetadata {
+                name: "test-substream".to_string(),
+                version: "v0.1.0".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        Box::leak(package.encode_to_vec().into_boxed_slice())
+    }
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("spkg-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_resolve_pinned_package_caches_by_hash() {
+        let bytes = package_bytes();
+        let hash = sha256_hex(bytes);
+        let manager = PackageManager::new(temp_cache_dir("pinned"));
+
+        let package = manager
+            .resolve(&PackageSpec::embedded(bytes).pinned(hash.to_uppercase()))
+            .await
+            .unwrap();
+
+        assert_eq!(package.package_meta[0].name, "test-substream");
+        assert!(manager.cache_path(&hash).exists());
+
+        // A cached pinned package no longer needs its source
+        let missing = PackageSpec::file("/nonexistent/package.spkg").pinned(&hash);
+        assert_eq!(manager.resolve(&missing).await.unwrap(), package);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_hash_mismatch() {
+        let manager = PackageManager::new(temp_cache_dir("mismatch"));
+        let spec = PackageSpec::embedded(package_bytes()).pinned("00".repeat(32));
+
+        let result = manager.resolve(&spec).await;
+
+        assert!(matches!(result, Err(PackageError::HashMismatch { .. })));
+        assert!(!manager.cache_dir().exists());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_unpinned_package_is_not_cached() {
+        let manager = PackageManager::new(temp_cache_dir("unpinned"));
+
+        manager
+            .resolve(&PackageSpec::embedded(package_bytes()))
+            .await
+            .unwrap();
+
+        assert!(!manager.cache_dir().exists());
+    }
+}
@@ -74,6 +74,26 @@ pub trait PreprocessedSink<P: Send>: Send + Sync {
         module_name: &str,
         start_block: i64,
         end_block: u64,
+    ) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async move {
+            println!("Processing block {}", spkg_file);
+
+            let package = read_package(spkg_file).await.unwrap();
+
+            self.run_package(endpoint_url, package, module_name, start_block, end_block)
+                .await
+        }
+    }
+
+    /// Same as [`run`](Self::run), but streams an already resolved package,
+    /// e.g. one pinned through [`PackageManager`](crate::package::PackageManager).
+    fn run_package(
+        &self,
+        endpoint_url: &str,
+        package: Package,
+        module_name: &str,
+        start_block: i64,
+        end_block: u64,
     ) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
         async move {
             let token_env = env::var("SUBSTREAMS_API_TOKEN").unwrap_or("".to_string());
@@ -84,10 +104,6 @@ pub trait PreprocessedSink<P: Send>: Send + Sync {
 
             let cursor: Option<String> = self.load_persisted_cursor().await?;
 
-            println!("Processing block {}", spkg_file);
-
-            let package = read_package(spkg_file).await.unwrap();
-
             let endpoint = Arc::new(SubstreamsEndpoint::new(&endpoint_url, token).await?);
 
             let mut stream = SubstreamsStream::new(
@@ -185,6 +201,26 @@ pub trait Sink<T: Send>: Send + Sync {
         module_name: &str,
         start_block: i64,
         end_block: u64,
+    ) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
+        async move {
+            println!("Processing block {}", spkg_file);
+
+            let package = read_package(spkg_file).await.unwrap();
+
+            self.run_package(endpoint_url, package, module_name, start_block, end_block)
+                .await
+        }
+    }
+
+    /// Same as [`run`](Self::run), but streams an already resolved package,
+    /// e.g. one pinned through [`PackageManager`](crate::package::PackageManager).
+    fn run_package(
+        &self,
+        endpoint_url: &str,
+        package: Package,
+        module_name: &str,
+        start_block: i64,
+        end_block: u64,
     ) -> impl std::future::Future<Output = Result<(), anyhow::Error>> + Send {
         async move {
             let token_env = env::var("SUBSTREAMS_API_TOKEN").unwrap_or("".to_string());
@@ -195,10 +231,6 @@ pub trait Sink<T: Send>: Send + Sync {
 
             let cursor: Option<String> = self.load_persisted_cursor().await?;
 
-            println!("Processing block {}", spkg_file);
-
-            let package = read_package(spkg_file).await.unwrap();
-
             let endpoint = Arc::new(SubstreamsEndpoint::new(&endpoint_url, token).await?);
 
             let mut stream = SubstreamsStream::new(
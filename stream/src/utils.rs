@@ -11,11 +11,32 @@ pub fn output(block_data: &BlockScopedData) -> &prost_types::Any {
         .unwrap()
 }
 
-#[derive(Clone, Debug)]
+/// Metadata of a streamed block, shared by everything derived from it.
+#[derive(Clone, Debug, Default)]
 pub struct BlockMetadata {
     pub cursor: String,
     pub block_number: u64,
     pub timestamp: String,
+    /// Network the block was streamed from, set by the sink with
+    /// [`BlockMetadata::with_chain_id`]. `None` if the sink doesn't know it.
+    pub chain_id: Option<String>,
+    /// Hash of the block (the clock ID).
+    pub block_hash: String,
+    /// Hash of the block's parent, if the stream reports it. Substreams'
+    /// clock doesn't, so it's `None` for streamed blocks.
+    pub parent_hash: Option<String>,
+    /// Whether the block was final when it was delivered, i.e. at or below
+    /// the stream's final block height. Data derived from blocks that
+    /// aren't final may still be undone by a reorg.
+    pub finalized: bool,
+}
+
+impl BlockMetadata {
+    /// Sets the network the block was streamed from.
+    pub fn with_chain_id(mut self, chain_id: impl Into<String>) -> Self {
+        self.chain_id = Some(chain_id.into());
+        self
+    }
 }
 
 pub fn block_metadata(block_data: &BlockScopedData) -> BlockMetadata {
@@ -26,6 +47,10 @@ pub fn block_metadata(block_data: &BlockScopedData) -> BlockMetadata {
         timestamp: timestamp.seconds.to_string(),
         block_number: clock.number,
         cursor: block_data.cursor.clone(),
+        chain_id: None,
+        block_hash: clock.id.clone(),
+        parent_hash: None,
+        finalized: clock.number <= block_data.final_block_height,
     }
 }
 
@@ -57,3 +82,45 @@ pub fn format_drift(block_metadata: &BlockMetadata) -> String {
         format!("{}ms", milliseconds)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::sf::substreams::v1::Clock;
+
+    fn block(number: u64, final_block_height: u64) -> BlockScopedData {
+        BlockScopedData {
+            clock: Some(Clock {
+                id: format!("0xhash{}", number),
+                number,
+                timestamp: Some(prost_types::Timestamp {
+                    seconds: 1_700_000_000,
+                    nanos: 0,
+                }),
+            }),
+            cursor: format!("cursor_{}", number),
+            final_block_height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn block_metadata_reads_hash_and_finality() {
+        let meta = block_metadata(&block(100, 100));
+
+        assert_eq!(meta.block_hash, "0xhash100");
+        assert_eq!(meta.timestamp, "1700000000");
+        assert_eq!(meta.parent_hash, None);
+        assert!(meta.finalized);
+        assert!(!block_metadata(&block(101, 100)).finalized);
+    }
+
+    #[test]
+    fn block_metadata_chain_id_is_set_by_the_sink() {
+        let meta = block_metadata(&block(100, 0));
+        assert_eq!(meta.chain_id, None);
+
+        let meta = meta.with_chain_id("testnet");
+        assert_eq!(meta.chain_id.as_deref(), Some("testnet"));
+    }
+}
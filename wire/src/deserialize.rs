@@ -10,6 +10,15 @@ pub enum DeserializeError {
 
     #[error("Protobuf deserialization error: {0}")]
     ProtobufDeserializeError(#[from] prost::DecodeError),
+
+    #[error("Invalid varint in protobuf stream")]
+    InvalidVarint,
+
+    #[error("Unsupported protobuf wire type: {0}")]
+    UnsupportedWireType(u64),
+
+    #[error("Protobuf stream ended with {0} bytes of an incomplete field")]
+    IncompleteField(usize),
 }
 
 pub fn deserialize(buf: &[u8]) -> Result<Edit, DeserializeError> {
@@ -19,3 +28,165 @@ pub fn deserialize(buf: &[u8]) -> Result<Edit, DeserializeError> {
 pub fn deserialize_from_json(json: serde_json::Value) -> Result<Edit, DeserializeError> {
     Ok(serde_json::from_value::<Edit>(json)?)
 }
+
+/// Incrementally decodes an `Edit` from chunks of its protobuf encoding.
+///
+/// Each top-level field is merged into the edit as soon as it's complete and
+/// its bytes are dropped, so decoding a large edit only buffers the field
+/// currently being received (usually a single op) rather than the full body.
+///
+/// ```ignore
+/// let mut decoder = EditDecoder::new();
+/// while let Some(chunk) = stream.next().await {
+///     decoder.push(&chunk?)?;
+/// }
+/// let edit = decoder.finish()?;
+/// ```
+#[derive(Debug, Default)]
+pub struct EditDecoder {
+    edit: Edit,
+    buf: Vec<u8>,
+}
+
+impl EditDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of bytes received but not yet decoded.
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Feeds the next chunk of the encoding, decoding every field it completes.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), DeserializeError> {
+        self.buf.extend_from_slice(chunk);
+
+        let mut consumed = 0;
+        while let Some(field_len) = complete_field_len(&self.buf[consumed..])? {
+            self.edit.merge(&self.buf[consumed..consumed + field_len])?;
+            consumed += field_len;
+        }
+
+        self.buf.drain(..consumed);
+        Ok(())
+    }
+
+    /// Returns the decoded edit, failing if the stream ended mid-field.
+    pub fn finish(self) -> Result<Edit, DeserializeError> {
+        if !self.buf.is_empty() {
+            return Err(DeserializeError::IncompleteField(self.buf.len()));
+        }
+
+        Ok(self.edit)
+    }
+}
+
+/// Returns the encoded length of the field at the start of `buf`, or None if
+/// `buf` doesn't hold the whole field yet.
+fn complete_field_len(buf: &[u8]) -> Result<Option<usize>, DeserializeError> {
+    let Some((key, key_len)) = read_varint(buf)? else {
+        return Ok(None);
+    };
+
+    let rest = &buf[key_len..];
+    let value_len = match key & 0x7 {
+        // Varint
+        0 => read_varint(rest)?.map(|(_, len)| len),
+        // 64-bit
+        1 => Some(8),
+        // Length-delimited
+        2 => read_varint(rest)?.map(|(len, prefix_len)| prefix_len + len as usize),
+        // 32-bit
+        5 => Some(4),
+        wire_type => return Err(DeserializeError::UnsupportedWireType(wire_type)),
+    };
+
+    Ok(value_len
+        .map(|value_len| key_len + value_len)
+        .filter(|field_len| *field_len <= buf.len()))
+}
+
+/// Reads a varint from the start of `buf`, returning its value and encoded
+/// length, or None if `buf` ends before the varint does.
+fn read_varint(buf: &[u8]) -> Result<Option<(u64, usize)>, DeserializeError> {
+    let mut value = 0u64;
+
+    for (index, byte) in buf.iter().enumerate() {
+        if index >= 10 {
+            return Err(DeserializeError::InvalidVarint);
+        }
+
+        value |= u64::from(byte & 0x7f) << (7 * index);
+
+        if byte & 0x80 == 0 {
+            return Ok(Some((value, index + 1)));
+        }
+    }
+
+    if buf.len() >= 10 {
+        return Err(DeserializeError::InvalidVarint);
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pb::grc20::{Op, op::Payload};
+
+    fn large_edit() -> Edit {
+        Edit {
+            id: vec![0x01, 0x02, 0x03],
+            name: "Large edit".to_string(),
+            ops: (0..200u8)
+                .map(|i| Op {
+                    payload: Some(Payload::DeleteRelation(vec![i; 300])),
+                })
+                .collect(),
+            authors: vec![vec![0xab; 20]],
+            language: Some(vec![0x04]),
+        }
+    }
+
+    fn decode_in_chunks(bytes: &[u8], chunk_size: usize) -> Result<Edit, DeserializeError> {
+        let mut decoder = EditDecoder::new();
+        for chunk in bytes.chunks(chunk_size) {
+            decoder.push(chunk)?;
+        }
+        decoder.finish()
+    }
+
+    #[test]
+    fn test_edit_decoder_matches_deserialize() {
+        let edit = large_edit();
+        let bytes = edit.encode_to_vec();
+
+        for chunk_size in [1, 7, 300, 4096, bytes.len()] {
+            assert_eq!(decode_in_chunks(&bytes, chunk_size).unwrap(), edit);
+        }
+    }
+
+    #[test]
+    fn test_edit_decoder_only_buffers_incomplete_field() {
+        let bytes = large_edit().encode_to_vec();
+        let mut decoder = EditDecoder::new();
+
+        for chunk in bytes.chunks(64) {
+            decoder.push(chunk).unwrap();
+            // Each op is a little over 300 bytes
+            assert!(decoder.buffered_len() < 400);
+        }
+    }
+
+    #[test]
+    fn test_edit_decoder_truncated_stream() {
+        let bytes = large_edit().encode_to_vec();
+
+        assert!(matches!(
+            decode_in_chunks(&bytes[..bytes.len() - 1], 128),
+            Err(DeserializeError::IncompleteField(_))
+        ));
+    }
+}
@@ -1,12 +1,26 @@
 // This file is @generated by prost-build.
+/// Where an event was emitted onchain. Every event carries its context so
+/// sinks can dedupe events and audit them against the chain.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct EventContext {
+    #[prost(string, tag = "1")]
+    pub tx_hash: ::prost::alloc::string::String,
+    /// Index of the log in the block, not in the transaction.
+    #[prost(uint64, tag = "2")]
+    pub log_index: u64,
+    #[prost(uint64, tag = "3")]
+    pub block_number: u64,
+    /// Block timestamp in seconds.
+    #[prost(uint64, tag = "4")]
+    pub block_timestamp: u64,
+}
 /// *
 /// The new DAO-based contracts allow forking of spaces into successor spaces. This is so
 /// users can create new spaces whose data is derived from another space.
 ///
 /// This is immediately useful when migrating from legacy spaces to the new DAO-based spaces,
 /// but it's generally applicable across any space.
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SuccessorSpaceCreated {
     #[prost(string, tag = "1")]
     pub predecessor_space: ::prost::alloc::string::String,
@@ -14,9 +28,10 @@ pub struct SuccessorSpaceCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SuccessorSpacesCreated {
     #[prost(message, repeated, tag = "1")]
     pub spaces: ::prost::alloc::vec::Vec<SuccessorSpaceCreated>,
@@ -28,16 +43,16 @@ pub struct SuccessorSpacesCreated {
 ///
 /// This event maps creation of the Space plugin and associates the Space plugin contract
 /// address with the address of the DAO contract.
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GeoSpaceCreated {
     #[prost(string, tag = "1")]
     pub dao_address: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub space_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GeoSpacesCreated {
     #[prost(message, repeated, tag = "1")]
     pub spaces: ::prost::alloc::vec::Vec<GeoSpaceCreated>,
@@ -53,8 +68,7 @@ pub struct GeoSpacesCreated {
 /// As of January 23, 2024 there are two governance plugins:
 /// 1. Voting plugin – This defines the voting and proposal rules and behaviors for a DAO
 /// 2. Member access plugin – This defines the membership rules and behaviors for a DAO
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GeoGovernancePluginCreated {
     #[prost(string, tag = "1")]
     pub dao_address: ::prost::alloc::string::String,
@@ -62,15 +76,22 @@ pub struct GeoGovernancePluginCreated {
     pub main_voting_address: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub member_access_address: ::prost::alloc::string::String,
-}
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+    #[prost(message, optional, tag = "4")]
+    pub context: ::core::option::Option<EventContext>,
+    /// Editors and members the main voting plugin was installed with, read from
+    /// the events it emitted in the setup transaction. Sinks can create the space
+    /// with its initial editors without relying on the separate editor events.
+    #[prost(string, repeated, tag = "5")]
+    pub initial_editors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "6")]
+    pub initial_members: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GeoGovernancePluginsCreated {
     #[prost(message, repeated, tag = "1")]
     pub plugins: ::prost::alloc::vec::Vec<GeoGovernancePluginCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GeoPersonalSpaceAdminPluginCreated {
     #[prost(string, tag = "1")]
     pub dao_address: ::prost::alloc::string::String,
@@ -78,9 +99,17 @@ pub struct GeoPersonalSpaceAdminPluginCreated {
     pub personal_admin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub initial_editor: ::prost::alloc::string::String,
-}
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+    #[prost(message, optional, tag = "4")]
+    pub context: ::core::option::Option<EventContext>,
+    /// Editors and members the personal admin plugin was installed with, read
+    /// from the events it emitted in the setup transaction. Always includes the
+    /// initial editor.
+    #[prost(string, repeated, tag = "5")]
+    pub initial_editors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "6")]
+    pub initial_members: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GeoPersonalSpaceAdminPluginsCreated {
     #[prost(message, repeated, tag = "1")]
     pub plugins: ::prost::alloc::vec::Vec<GeoPersonalSpaceAdminPluginCreated>,
@@ -101,8 +130,7 @@ pub struct GeoPersonalSpaceAdminPluginsCreated {
 ///    in the space vote on whether to accept the new editor.
 /// 2. They are added as a set of initial editors when first creating the space. This allows
 ///    space deployers to bootstrap a set of editors on space creation.
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct InitialEditorAdded {
     /// The event emits an array of addresses. We only emit multiple addresses
     /// when first creating the governance plugin. After that we only emit one
@@ -113,31 +141,75 @@ pub struct InitialEditorAdded {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct InitialEditorsAdded {
     #[prost(message, repeated, tag = "1")]
     pub editors: ::prost::alloc::vec::Vec<InitialEditorAdded>,
 }
+/// Proposals created by any of our voting or admin plugins. Every plugin
+/// emits the same `ProposalCreated` event, regardless of the proposal's
+/// actions, so this is the generic record of a proposal and its metadata.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ProposalCreated {
+    #[prost(string, tag = "1")]
+    pub proposal_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub creator: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub start_time: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub end_time: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub metadata_uri: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "7")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ProposalsCreated {
+    #[prost(message, repeated, tag = "1")]
+    pub proposals: ::prost::alloc::vec::Vec<ProposalCreated>,
+}
 /// Executed proposals have been approved and executed onchain in a DAO-based
 /// space's main voting plugin. The DAO itself also emits the executed event,
 /// but the ABI/interface is different. We really only care about the one
 /// from our plugins.
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ProposalExecuted {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct ProposalsExecuted {
     #[prost(message, repeated, tag = "1")]
     pub executed_proposals: ::prost::alloc::vec::Vec<ProposalExecuted>,
 }
+/// Canceled proposals were withdrawn before they could be executed. Only the
+/// main voting plugin can cancel proposals.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ProposalCanceled {
+    #[prost(string, tag = "1")]
+    pub proposal_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct ProposalsCanceled {
+    #[prost(message, repeated, tag = "1")]
+    pub canceled_proposals: ::prost::alloc::vec::Vec<ProposalCanceled>,
+    #[prost(message, repeated, tag = "27")]
+    pub ordered_events: ::prost::alloc::vec::Vec<OrderedEvent>,
+}
 /// *
 /// Processed Proposals represent content that has been approved by a DAO
 /// and executed onchain.
@@ -145,8 +217,7 @@ pub struct ProposalsExecuted {
 /// We use the content URI to represent the content that was approved. We
 /// only consume the `proposalId` in the content URI to map the processed
 /// data to an existing proposal onchain and in the sink.
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct EditPublished {
     #[prost(string, tag = "1")]
     pub content_uri: ::prost::alloc::string::String,
@@ -154,9 +225,10 @@ pub struct EditPublished {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "3")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct EditsPublished {
     #[prost(message, repeated, tag = "1")]
     pub edits: ::prost::alloc::vec::Vec<EditPublished>,
@@ -166,8 +238,7 @@ pub struct EditsPublished {
 /// of the DAO-based space. This is useful to "link" Spaces together in a
 /// tree of spaces, allowing us to curate the graph of their knowledge and
 /// permissions.
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SubspaceAdded {
     /// Subspace dao address
     #[prost(string, tag = "1")]
@@ -180,15 +251,15 @@ pub struct SubspaceAdded {
     /// DAO address of the space that added the subspace (parent space)
     #[prost(string, tag = "4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SubspacesAdded {
     #[prost(message, repeated, tag = "1")]
     pub subspaces: ::prost::alloc::vec::Vec<SubspaceAdded>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SubspaceRemoved {
     /// Subspace dao address
     #[prost(string, tag = "1")]
@@ -201,9 +272,10 @@ pub struct SubspaceRemoved {
     /// DAO address of the space that removed the subspace (parent space)
     #[prost(string, tag = "4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct SubspacesRemoved {
     #[prost(message, repeated, tag = "1")]
     pub subspaces: ::prost::alloc::vec::Vec<SubspaceRemoved>,
@@ -214,8 +286,7 @@ pub struct SubspacesRemoved {
 /// Currently we use a simple majority voting model, where a proposal requires 51% of the
 /// available votes in order to pass. Only editors are allowed to vote on proposals, but editors
 /// _and_ members can create them.
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct VoteCast {
     #[prost(string, tag = "1")]
     pub onchain_proposal_id: ::prost::alloc::string::String,
@@ -225,15 +296,20 @@ pub struct VoteCast {
     pub vote_option: u64,
     #[prost(string, tag = "4")]
     pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
+    /// Voting power of the voter at the proposal's snapshot block, as a decimal
+    /// uint256. The event doesn't carry the plugin's total voting power, which
+    /// is the number of editors at the snapshot block.
+    #[prost(string, tag = "6")]
+    pub voting_power: ::prost::alloc::string::String,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct VotesCast {
     #[prost(message, repeated, tag = "1")]
     pub votes: ::prost::alloc::vec::Vec<VoteCast>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct MemberAdded {
     #[prost(string, tag = "1")]
     pub member_address: ::prost::alloc::string::String,
@@ -243,15 +319,15 @@ pub struct MemberAdded {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct MembersAdded {
     #[prost(message, repeated, tag = "1")]
     pub members: ::prost::alloc::vec::Vec<MemberAdded>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct MemberRemoved {
     #[prost(string, tag = "1")]
     pub member_address: ::prost::alloc::string::String,
@@ -261,15 +337,15 @@ pub struct MemberRemoved {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct MembersRemoved {
     #[prost(message, repeated, tag = "1")]
     pub members: ::prost::alloc::vec::Vec<MemberRemoved>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct EditorAdded {
     #[prost(string, tag = "1")]
     pub editor_address: ::prost::alloc::string::String,
@@ -279,15 +355,15 @@ pub struct EditorAdded {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct EditorsAdded {
     #[prost(message, repeated, tag = "1")]
     pub editors: ::prost::alloc::vec::Vec<EditorAdded>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct EditorRemoved {
     #[prost(string, tag = "1")]
     pub editor_address: ::prost::alloc::string::String,
@@ -297,15 +373,15 @@ pub struct EditorRemoved {
     pub change_type: ::prost::alloc::string::String,
     #[prost(string, tag = "4")]
     pub dao_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct EditorsRemoved {
     #[prost(message, repeated, tag = "1")]
     pub editors: ::prost::alloc::vec::Vec<EditorRemoved>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PublishEditProposalCreated {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
@@ -321,15 +397,15 @@ pub struct PublishEditProposalCreated {
     pub dao_address: ::prost::alloc::string::String,
     #[prost(string, tag = "7")]
     pub plugin_address: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "8")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct PublishEditsProposalsCreated {
     #[prost(message, repeated, tag = "1")]
     pub edits: ::prost::alloc::vec::Vec<PublishEditProposalCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct AddMemberProposalCreated {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
@@ -347,15 +423,15 @@ pub struct AddMemberProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "9")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct AddMemberProposalsCreated {
     #[prost(message, repeated, tag = "1")]
     pub proposed_members: ::prost::alloc::vec::Vec<AddMemberProposalCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RemoveMemberProposalCreated {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
@@ -373,15 +449,15 @@ pub struct RemoveMemberProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "9")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RemoveMemberProposalsCreated {
     #[prost(message, repeated, tag = "1")]
     pub proposed_members: ::prost::alloc::vec::Vec<RemoveMemberProposalCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct AddEditorProposalCreated {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
@@ -399,15 +475,15 @@ pub struct AddEditorProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "9")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct AddEditorProposalsCreated {
     #[prost(message, repeated, tag = "1")]
     pub proposed_editors: ::prost::alloc::vec::Vec<AddEditorProposalCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RemoveEditorProposalCreated {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
@@ -425,15 +501,15 @@ pub struct RemoveEditorProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "9")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RemoveEditorProposalsCreated {
     #[prost(message, repeated, tag = "1")]
     pub proposed_editors: ::prost::alloc::vec::Vec<RemoveEditorProposalCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct AddSubspaceProposalCreated {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
@@ -451,15 +527,15 @@ pub struct AddSubspaceProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "9")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct AddSubspaceProposalsCreated {
     #[prost(message, repeated, tag = "1")]
     pub proposed_subspaces: ::prost::alloc::vec::Vec<AddSubspaceProposalCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RemoveSubspaceProposalCreated {
     #[prost(string, tag = "1")]
     pub proposal_id: ::prost::alloc::string::String,
@@ -477,15 +553,146 @@ pub struct RemoveSubspaceProposalCreated {
     pub plugin_address: ::prost::alloc::string::String,
     #[prost(string, tag = "8")]
     pub change_type: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "9")]
+    pub context: ::core::option::Option<EventContext>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct RemoveSubspaceProposalsCreated {
     #[prost(message, repeated, tag = "1")]
     pub proposed_subspaces: ::prost::alloc::vec::Vec<RemoveSubspaceProposalCreated>,
 }
-#[derive(serde::Serialize, serde::Deserialize)]
-#[derive(Clone, PartialEq, ::prost::Message)]
+/// Voting settings of a main voting plugin. Emitted when the plugin is
+/// initialized and whenever an executed proposal changes them, so proposals
+/// can be checked against the settings in effect when they were created.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct VotingSettingsUpdated {
+    #[prost(string, tag = "1")]
+    pub plugin_address: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub voting_mode: u64,
+    /// Share of votes required to pass, in parts per million.
+    #[prost(uint64, tag = "3")]
+    pub support_threshold: u64,
+    /// Voting duration of new proposals in seconds.
+    #[prost(uint64, tag = "4")]
+    pub duration: u64,
+    #[prost(message, optional, tag = "5")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct VotingSettingsUpdates {
+    #[prost(message, repeated, tag = "1")]
+    pub settings: ::prost::alloc::vec::Vec<VotingSettingsUpdated>,
+}
+/// Settings of a member access plugin, which only configure how long
+/// membership proposals stay open.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct MemberAccessSettingsUpdated {
+    #[prost(string, tag = "1")]
+    pub plugin_address: ::prost::alloc::string::String,
+    /// Duration of new membership proposals in seconds.
+    #[prost(uint64, tag = "2")]
+    pub proposal_duration: u64,
+    #[prost(message, optional, tag = "3")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct MemberAccessSettingsUpdates {
+    #[prost(message, repeated, tag = "1")]
+    pub settings: ::prost::alloc::vec::Vec<MemberAccessSettingsUpdated>,
+}
+/// Metadata set on a Geo DAO. Aragon DAOs store the URI of a JSON document
+/// with the DAO's name and description, set on creation and whenever an
+/// executed proposal changes it.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct DaoMetadataSet {
+    #[prost(string, tag = "1")]
+    pub dao_address: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub metadata_uri: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub context: ::core::option::Option<EventContext>,
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct DaoMetadataSets {
+    #[prost(message, repeated, tag = "1")]
+    pub metadata: ::prost::alloc::vec::Vec<DaoMetadataSet>,
+}
+/// One of the block's events, tagged with its position in the block.
+/// GeoOutput groups events by type, which loses the order between events of
+/// different types, like an editor being added and then voting in the same
+/// block. Ordered events repeat every event of the other fields, sorted by
+/// log index.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
+pub struct OrderedEvent {
+    /// Index of the event's log in the block.
+    #[prost(uint64, tag = "1")]
+    pub log_index: u64,
+    #[prost(
+        oneof = "ordered_event::Event",
+        tags = "2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27"
+    )]
+    pub event: ::core::option::Option<ordered_event::Event>,
+}
+/// Nested message and enum types in `OrderedEvent`.
+pub mod ordered_event {
+    #[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "2")]
+        SpaceCreated(super::GeoSpaceCreated),
+        #[prost(message, tag = "3")]
+        GovernancePluginCreated(super::GeoGovernancePluginCreated),
+        #[prost(message, tag = "4")]
+        InitialEditorAdded(super::InitialEditorAdded),
+        #[prost(message, tag = "5")]
+        VoteCast(super::VoteCast),
+        #[prost(message, tag = "6")]
+        EditPublished(super::EditPublished),
+        #[prost(message, tag = "7")]
+        SuccessorSpaceCreated(super::SuccessorSpaceCreated),
+        #[prost(message, tag = "8")]
+        SubspaceAdded(super::SubspaceAdded),
+        #[prost(message, tag = "9")]
+        SubspaceRemoved(super::SubspaceRemoved),
+        #[prost(message, tag = "10")]
+        ProposalExecuted(super::ProposalExecuted),
+        #[prost(message, tag = "11")]
+        MemberAdded(super::MemberAdded),
+        #[prost(message, tag = "12")]
+        EditorAdded(super::EditorAdded),
+        #[prost(message, tag = "13")]
+        PersonalPluginCreated(super::GeoPersonalSpaceAdminPluginCreated),
+        #[prost(message, tag = "14")]
+        MemberRemoved(super::MemberRemoved),
+        #[prost(message, tag = "15")]
+        EditorRemoved(super::EditorRemoved),
+        #[prost(message, tag = "16")]
+        PublishEditProposalCreated(super::PublishEditProposalCreated),
+        #[prost(message, tag = "17")]
+        AddMemberProposalCreated(super::AddMemberProposalCreated),
+        #[prost(message, tag = "18")]
+        RemoveMemberProposalCreated(super::RemoveMemberProposalCreated),
+        #[prost(message, tag = "19")]
+        AddEditorProposalCreated(super::AddEditorProposalCreated),
+        #[prost(message, tag = "20")]
+        RemoveEditorProposalCreated(super::RemoveEditorProposalCreated),
+        #[prost(message, tag = "21")]
+        AddSubspaceProposalCreated(super::AddSubspaceProposalCreated),
+        #[prost(message, tag = "22")]
+        RemoveSubspaceProposalCreated(super::RemoveSubspaceProposalCreated),
+        #[prost(message, tag = "23")]
+        ProposalCreated(super::ProposalCreated),
+        #[prost(message, tag = "24")]
+        VotingSettingsUpdated(super::VotingSettingsUpdated),
+        #[prost(message, tag = "25")]
+        MemberAccessSettingsUpdated(super::MemberAccessSettingsUpdated),
+        #[prost(message, tag = "26")]
+        DaoMetadataSet(super::DaoMetadataSet),
+        #[prost(message, tag = "27")]
+        ProposalCanceled(super::ProposalCanceled),
+    }
+}
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, ::prost::Message)]
 pub struct GeoOutput {
     #[prost(message, repeated, tag = "1")]
     pub spaces_created: ::prost::alloc::vec::Vec<GeoSpaceCreated>,
@@ -510,9 +717,7 @@ pub struct GeoOutput {
     #[prost(message, repeated, tag = "11")]
     pub editors_added: ::prost::alloc::vec::Vec<EditorAdded>,
     #[prost(message, repeated, tag = "12")]
-    pub personal_plugins_created: ::prost::alloc::vec::Vec<
-        GeoPersonalSpaceAdminPluginCreated,
-    >,
+    pub personal_plugins_created: ::prost::alloc::vec::Vec<GeoPersonalSpaceAdminPluginCreated>,
     #[prost(message, repeated, tag = "13")]
     pub members_removed: ::prost::alloc::vec::Vec<MemberRemoved>,
     #[prost(message, repeated, tag = "14")]
@@ -530,7 +735,15 @@ pub struct GeoOutput {
     #[prost(message, repeated, tag = "20")]
     pub proposed_added_subspaces: ::prost::alloc::vec::Vec<AddSubspaceProposalCreated>,
     #[prost(message, repeated, tag = "21")]
-    pub proposed_removed_subspaces: ::prost::alloc::vec::Vec<
-        RemoveSubspaceProposalCreated,
-    >,
+    pub proposed_removed_subspaces: ::prost::alloc::vec::Vec<RemoveSubspaceProposalCreated>,
+    #[prost(message, repeated, tag = "22")]
+    pub proposals_created: ::prost::alloc::vec::Vec<ProposalCreated>,
+    #[prost(message, repeated, tag = "23")]
+    pub voting_settings_updated: ::prost::alloc::vec::Vec<VotingSettingsUpdated>,
+    #[prost(message, repeated, tag = "24")]
+    pub member_access_settings_updated: ::prost::alloc::vec::Vec<MemberAccessSettingsUpdated>,
+    #[prost(message, repeated, tag = "25")]
+    pub dao_metadata_set: ::prost::alloc::vec::Vec<DaoMetadataSet>,
+    #[prost(message, repeated, tag = "26")]
+    pub canceled_proposals: ::prost::alloc::vec::Vec<ProposalCanceled>,
 }